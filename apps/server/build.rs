@@ -1,4 +1,53 @@
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 fn main() {
     // Rebuild when embedded migration files change so sqlx::migrate! stays in sync.
     println!("cargo:rerun-if-changed=migrations");
+
+    // Build metadata for `GET /api/system/info`. Docker builds have no `.git`, so the
+    // commit can be passed in through RUSTZEN_BUILD_COMMIT instead.
+    println!("cargo:rerun-if-env-changed=RUSTZEN_BUILD_COMMIT");
+    watch_git_head();
+    let commit = std::env::var("RUSTZEN_BUILD_COMMIT")
+        .ok()
+        .filter(|commit| !commit.trim().is_empty())
+        .or_else(|| command_output("git", &["rev-parse", "--short", "HEAD"]))
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=RUSTZEN_BUILD_COMMIT={commit}");
+
+    let built_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    println!("cargo:rustc-env=RUSTZEN_BUILD_TIMESTAMP={built_at}");
+
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version =
+        command_output(&rustc, &["--version"]).unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=RUSTZEN_RUSTC_VERSION={rustc_version}");
+}
+
+/// Reruns when HEAD moves: a checkout rewrites `HEAD`, a commit rewrites the branch ref it
+/// points to, and `git pack-refs` moves that ref into `packed-refs`.
+fn watch_git_head() {
+    let mut files = vec!["HEAD".to_string(), "packed-refs".to_string()];
+    files.extend(command_output("git", &["symbolic-ref", "-q", "HEAD"]));
+    for file in files {
+        let Some(path) = command_output("git", &["rev-parse", "--git-path", &file]) else {
+            continue;
+        };
+        // Cargo reruns on every build for a watched path that does not exist.
+        if Path::new(&path).exists() {
+            println!("cargo:rerun-if-changed={path}");
+        }
+    }
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8(output.stdout).ok()?;
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
 }
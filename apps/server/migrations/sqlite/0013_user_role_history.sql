@@ -0,0 +1,16 @@
+-- ============================================================================
+-- Module: User-role assignment history for compliance reviews.
+-- ============================================================================
+
+-- No foreign keys: history must outlive purged users and deleted roles.
+CREATE TABLE IF NOT EXISTS user_role_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    role_id INTEGER NOT NULL,
+    action TEXT NOT NULL CHECK(action IN ('assigned', 'removed')),
+    operator_id INTEGER,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_role_history_user_id
+    ON user_role_history(user_id, created_at);
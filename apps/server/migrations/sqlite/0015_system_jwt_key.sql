@@ -0,0 +1,14 @@
+-- ============================================================================
+-- Module: JWT signing keys created by in-app rotation.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS jwt_signing_keys (
+    kid TEXT PRIMARY KEY,
+    secret TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- Set when a newer key takes over signing; the key keeps verifying until
+    -- tokens it signed have expired.
+    retired_at DATETIME
+);
+
+CREATE INDEX IF NOT EXISTS idx_jwt_signing_keys_created_at ON jwt_signing_keys(created_at);
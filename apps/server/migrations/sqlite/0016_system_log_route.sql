@@ -0,0 +1,10 @@
+-- ============================================================================
+-- Module: Route template, resource and status code on HTTP operation logs.
+-- ============================================================================
+
+ALTER TABLE operation_logs ADD COLUMN route TEXT;
+ALTER TABLE operation_logs ADD COLUMN resource_type TEXT;
+ALTER TABLE operation_logs ADD COLUMN resource_id TEXT;
+ALTER TABLE operation_logs ADD COLUMN status_code INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_operation_logs_route ON operation_logs(route, created_at);
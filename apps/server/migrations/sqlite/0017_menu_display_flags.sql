@@ -0,0 +1,8 @@
+-- ============================================================================
+-- Module: Menu display flags.
+-- `visible` = 0 keeps a page routable but out of the navigation menu;
+-- `keep_alive` = 1 asks the frontend to keep the page mounted when leaving it.
+-- ============================================================================
+
+ALTER TABLE menus ADD COLUMN visible INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE menus ADD COLUMN keep_alive INTEGER NOT NULL DEFAULT 0;
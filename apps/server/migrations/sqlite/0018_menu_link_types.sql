@@ -0,0 +1,105 @@
+-- ============================================================================
+-- Module: External link (menu_type 4) and iframe (menu_type 5) menus.
+-- `link_url` is the page a link opens in a new tab or an iframe embeds.
+--
+-- SQLite cannot widen a CHECK constraint in place, so `menus` is rebuilt.
+-- Dropping it cascades into `role_menus`, which is saved and restored, and the
+-- views that read `menus` are recreated unchanged.
+-- ============================================================================
+
+DROP VIEW IF EXISTS user_permissions;
+DROP VIEW IF EXISTS role_with_menus;
+
+CREATE TABLE role_menus_backup AS SELECT role_id, menu_id, created_at FROM role_menus;
+
+CREATE TABLE menus_rebuild (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    parent_id INTEGER NOT NULL DEFAULT 0,
+    parent_code TEXT,
+    name TEXT NOT NULL,
+    code TEXT NOT NULL,
+    menu_type INTEGER NOT NULL DEFAULT 2 CHECK (menu_type IN (1, 2, 3, 4, 5)),
+    status INTEGER NOT NULL DEFAULT 1 CHECK (status IN (1, 2)),
+    is_system INTEGER NOT NULL DEFAULT 0,
+    is_manual INTEGER NOT NULL DEFAULT 1,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    deleted_at DATETIME,
+    visible INTEGER NOT NULL DEFAULT 1,
+    keep_alive INTEGER NOT NULL DEFAULT 0,
+    link_url TEXT
+);
+
+INSERT INTO menus_rebuild (
+    id, parent_id, parent_code, name, code, menu_type, status, is_system, is_manual,
+    sort_order, created_at, updated_at, deleted_at, visible, keep_alive
+)
+SELECT
+    id, parent_id, parent_code, name, code, menu_type, status, is_system, is_manual,
+    sort_order, created_at, updated_at, deleted_at, visible, keep_alive
+FROM menus;
+
+DROP TABLE menus;
+ALTER TABLE menus_rebuild RENAME TO menus;
+
+INSERT INTO role_menus (role_id, menu_id, created_at)
+SELECT role_id, menu_id, created_at FROM role_menus_backup WHERE true
+ON CONFLICT DO NOTHING;
+DROP TABLE role_menus_backup;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_menus_name ON menus(name) WHERE deleted_at IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_menus_code ON menus(code) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_resources_parent_id ON menus(parent_id) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_resources_sort_order ON menus(sort_order) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_resources_status ON menus(status) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_resources_deleted_at ON menus(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_resources_parent_sort ON menus(parent_id, sort_order) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_resources_menu_type ON menus(menu_type) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_resources_is_system ON menus(is_system) WHERE is_system = 1 AND deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_resources_parent_code ON menus(parent_code) WHERE deleted_at IS NULL;
+
+CREATE VIEW IF NOT EXISTS user_permissions AS
+SELECT DISTINCT
+    u.id AS user_id,
+    u.username,
+    m.code AS menu_code,
+    m.menu_type,
+    r.code AS role_code,
+    m.id AS menu_id,
+    r.id AS role_id
+FROM users u
+INNER JOIN user_roles ur ON u.id = ur.user_id
+INNER JOIN roles r ON ur.role_id = r.id AND r.status = 1 AND r.deleted_at IS NULL
+INNER JOIN role_menus rm ON r.id = rm.role_id
+INNER JOIN menus m ON rm.menu_id = m.id AND m.deleted_at IS NULL
+WHERE u.deleted_at IS NULL
+  AND u.status = 1
+  AND m.code IS NOT NULL;
+
+CREATE VIEW IF NOT EXISTS role_with_menus AS
+SELECT
+    r.id AS id,
+    r.name,
+    r.code,
+    r.description,
+    r.status,
+    r.created_at,
+    r.updated_at,
+    r.deleted_at,
+    r.is_system,
+    COALESCE(
+        (
+            SELECT json_group_array(json_object('label', mo.name, 'value', mo.id))
+            FROM (
+                SELECT m.name, m.id
+                FROM role_menus rm
+                INNER JOIN menus m ON rm.menu_id = m.id AND m.deleted_at IS NULL
+                WHERE rm.role_id = r.id
+                ORDER BY m.id
+            ) mo
+        ),
+        '[]'
+    ) AS menus
+FROM roles r
+WHERE r.deleted_at IS NULL;
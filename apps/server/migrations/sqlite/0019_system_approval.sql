@@ -0,0 +1,26 @@
+-- ============================================================================
+-- Module: Dual-control approvals for destructive actions.
+-- ============================================================================
+
+-- No foreign keys: the record must outlive the users and roles it names, since
+-- purging a user or deleting a role is what most approvals do.
+CREATE TABLE IF NOT EXISTS approvals (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- Dotted action name such as `user.purge`; `payload` holds its JSON parameters.
+    action TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'approved', 'rejected', 'expired', 'failed')),
+    requested_by INTEGER NOT NULL,
+    requested_by_name TEXT NOT NULL,
+    decided_by INTEGER,
+    decided_by_name TEXT,
+    -- Why the approved action failed, when it did.
+    error TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at DATETIME NOT NULL,
+    decided_at DATETIME
+);
+
+CREATE INDEX IF NOT EXISTS idx_approvals_status ON approvals(status, id);
@@ -0,0 +1,61 @@
+-- ============================================================================
+-- Module: Generic approval workflows (definitions, instances, task inbox).
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS workflow_definitions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    code TEXT NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT,
+    -- JSON array of `{"name": ..., "approverRoleId": ...}`, approved in order.
+    steps TEXT NOT NULL DEFAULT '[]',
+    status INTEGER NOT NULL DEFAULT 1 CHECK (status IN (1, 2)),
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    deleted_at DATETIME
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_workflow_definitions_code ON workflow_definitions(code) WHERE deleted_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS workflow_instances (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    definition_id INTEGER NOT NULL,
+    definition_code TEXT NOT NULL,
+    title TEXT NOT NULL,
+    -- Free-form JSON object describing what is being requested.
+    data TEXT NOT NULL DEFAULT '{}',
+    -- Copy of the definition's steps at start, so later edits don't move running flows.
+    steps TEXT NOT NULL,
+    current_step INTEGER NOT NULL DEFAULT 0,
+    status TEXT NOT NULL DEFAULT 'running'
+        CHECK (status IN ('running', 'approved', 'rejected', 'cancelled')),
+    started_by INTEGER NOT NULL,
+    started_by_name TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at DATETIME,
+    FOREIGN KEY (definition_id) REFERENCES workflow_definitions(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_workflow_instances_started_by ON workflow_instances(started_by, id);
+CREATE INDEX IF NOT EXISTS idx_workflow_instances_status ON workflow_instances(status, id);
+
+-- One row per step reached; only the current step of a running instance is pending.
+CREATE TABLE IF NOT EXISTS workflow_tasks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    instance_id INTEGER NOT NULL,
+    step_index INTEGER NOT NULL,
+    step_name TEXT NOT NULL,
+    approver_role_id INTEGER NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'approved', 'rejected', 'cancelled')),
+    decided_by INTEGER,
+    decided_by_name TEXT,
+    comment TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    decided_at DATETIME,
+    FOREIGN KEY (instance_id) REFERENCES workflow_instances(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_workflow_tasks_instance_id ON workflow_tasks(instance_id, id);
+CREATE INDEX IF NOT EXISTS idx_workflow_tasks_pending ON workflow_tasks(approver_role_id, id) WHERE status = 'pending';
@@ -0,0 +1,13 @@
+-- ============================================================================
+-- Module: Feature flags for shipping experimental subsystems dark.
+-- ============================================================================
+
+-- `RUSTZEN_FEATURE_FLAGS` overrides `enabled` per key; unknown keys evaluate as off.
+CREATE TABLE IF NOT EXISTS feature_flags (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    key TEXT NOT NULL UNIQUE,
+    description TEXT,
+    enabled INTEGER NOT NULL DEFAULT 0 CHECK(enabled IN (0, 1)),
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
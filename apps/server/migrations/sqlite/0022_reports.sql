@@ -0,0 +1,23 @@
+-- ============================================================================
+-- Module: Generated reports (dashboard stats and log summaries as XLSX/PDF).
+-- ============================================================================
+
+-- Files live under `<data_dir>/reports/<stored_name>`; `file_name` is the download name.
+CREATE TABLE IF NOT EXISTS reports (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    title TEXT NOT NULL,
+    format TEXT NOT NULL CHECK(format IN ('xlsx', 'pdf')),
+    file_name TEXT NOT NULL,
+    stored_name TEXT NOT NULL UNIQUE,
+    size_bytes INTEGER NOT NULL,
+    period_start DATETIME NOT NULL,
+    period_end DATETIME NOT NULL,
+    trigger_type TEXT NOT NULL CHECK(trigger_type IN ('scheduled', 'manual')),
+    -- Comma-separated addresses the report was mailed to; NULL when mail is not configured.
+    recipients TEXT,
+    emailed_at DATETIME,
+    email_error TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_reports_created_at ON reports(created_at);
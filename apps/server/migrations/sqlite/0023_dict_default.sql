@@ -0,0 +1,10 @@
+-- ============================================================================
+-- Module: Default dictionary entries.
+-- `is_default` = 1 marks the value forms preselect for its type; the partial
+-- unique index allows at most one live default per type.
+-- ============================================================================
+
+ALTER TABLE dicts ADD COLUMN is_default INTEGER NOT NULL DEFAULT 0;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_dicts_default ON dicts(dict_type)
+    WHERE is_default = 1 AND deleted_at IS NULL;
@@ -0,0 +1,99 @@
+-- ============================================================================
+-- Module: User anonymization for privacy erasure requests.
+-- An anonymized user keeps its row (and id) so logs and history still resolve,
+-- but `email` is cleared, which needs the NOT NULL constraint dropped.
+-- `anonymized_at` records when the personal data was scrubbed.
+--
+-- SQLite cannot relax NOT NULL in place, so `users` is rebuilt. Dropping it
+-- cascades into `user_roles`, which is saved and restored, and the views that
+-- read `users` are recreated unchanged.
+-- ============================================================================
+
+DROP VIEW IF EXISTS user_with_roles;
+DROP VIEW IF EXISTS user_permissions;
+
+CREATE TABLE user_roles_backup AS SELECT user_id, role_id, created_at FROM user_roles;
+
+CREATE TABLE users_rebuild (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    username TEXT NOT NULL,
+    email TEXT,
+    password_hash TEXT NOT NULL,
+    real_name TEXT,
+    avatar_url TEXT,
+    status INTEGER NOT NULL DEFAULT 1 CHECK (status IN (1, 2, 3, 4)),
+    is_system INTEGER NOT NULL DEFAULT 0,
+    last_login_at DATETIME,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    deleted_at DATETIME,
+    anonymized_at DATETIME
+);
+
+INSERT INTO users_rebuild (
+    id, username, email, password_hash, real_name, avatar_url, status, is_system,
+    last_login_at, created_at, updated_at, deleted_at
+)
+SELECT
+    id, username, email, password_hash, real_name, avatar_url, status, is_system,
+    last_login_at, created_at, updated_at, deleted_at
+FROM users;
+
+DROP TABLE users;
+ALTER TABLE users_rebuild RENAME TO users;
+
+INSERT INTO user_roles (user_id, role_id, created_at)
+SELECT user_id, role_id, created_at FROM user_roles_backup WHERE true
+ON CONFLICT DO NOTHING;
+DROP TABLE user_roles_backup;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_username ON users(username) WHERE deleted_at IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_email ON users(email) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_users_deleted_at ON users(deleted_at);
+
+CREATE VIEW IF NOT EXISTS user_with_roles AS
+SELECT
+    u.id AS id,
+    u.username,
+    u.email,
+    u.real_name,
+    u.password_hash,
+    u.avatar_url,
+    u.status,
+    u.is_system,
+    u.last_login_at,
+    u.created_at,
+    u.updated_at,
+    COALESCE(
+        (
+            SELECT json_group_array(json_object('label', ro.name, 'value', ro.id))
+            FROM (
+                SELECT r.name, r.id
+                FROM user_roles ur
+                INNER JOIN roles r ON ur.role_id = r.id AND r.deleted_at IS NULL
+                WHERE ur.user_id = u.id
+                ORDER BY r.id
+            ) ro
+        ),
+        '[]'
+    ) AS roles
+FROM users u
+WHERE u.deleted_at IS NULL;
+
+CREATE VIEW IF NOT EXISTS user_permissions AS
+SELECT DISTINCT
+    u.id AS user_id,
+    u.username,
+    m.code AS menu_code,
+    m.menu_type,
+    r.code AS role_code,
+    m.id AS menu_id,
+    r.id AS role_id
+FROM users u
+INNER JOIN user_roles ur ON u.id = ur.user_id
+INNER JOIN roles r ON ur.role_id = r.id AND r.status = 1 AND r.deleted_at IS NULL
+INNER JOIN role_menus rm ON r.id = rm.role_id
+INNER JOIN menus m ON rm.menu_id = m.id AND m.deleted_at IS NULL
+WHERE u.deleted_at IS NULL
+  AND u.status = 1
+  AND m.code IS NOT NULL;
@@ -0,0 +1,28 @@
+-- ============================================================================
+-- Module: Versioned policy documents and the users' acceptance of them.
+-- ============================================================================
+
+-- Each publish adds the next version of its kind; the highest version is current.
+CREATE TABLE IF NOT EXISTS policy_documents (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL CHECK(kind IN ('terms', 'privacy')),
+    version INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    content TEXT NOT NULL,
+    published_by INTEGER,
+    published_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(kind, version)
+);
+
+CREATE TABLE IF NOT EXISTS policy_consents (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    document_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    ip_address TEXT NOT NULL,
+    user_agent TEXT NOT NULL,
+    accepted_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(document_id, user_id),
+    FOREIGN KEY (document_id) REFERENCES policy_documents(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_policy_consents_user_id ON policy_consents(user_id);
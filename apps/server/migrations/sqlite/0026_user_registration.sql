@@ -0,0 +1,20 @@
+-- ============================================================================
+-- Module: Self-registration with email verification.
+-- Self-registered users start as pending (status 3) and wait for an admin,
+-- who only approves them once `email_verified_at` is set.
+-- ============================================================================
+
+ALTER TABLE users ADD COLUMN email_verified_at DATETIME;
+
+-- Only the SHA-256 of each mailed token is stored; a token is single-use.
+CREATE TABLE IF NOT EXISTS email_verification_tokens (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    expires_at DATETIME NOT NULL,
+    used_at DATETIME,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_email_verification_tokens_user_id
+    ON email_verification_tokens(user_id);
@@ -0,0 +1,18 @@
+-- ============================================================================
+-- Module: Confirmed email changes.
+-- A new address is only written to `users.email` once its owner follows the
+-- token mailed to it; until then the request waits here. A newer request for
+-- the same user replaces any unconfirmed one.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS email_change_requests (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    new_email TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    expires_at DATETIME NOT NULL,
+    confirmed_at DATETIME,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_email_change_requests_user_id ON email_change_requests(user_id);
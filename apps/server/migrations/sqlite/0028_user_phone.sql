@@ -0,0 +1,42 @@
+-- ============================================================================
+-- Module: Phone numbers on users, for SMS one-time codes.
+-- Stored in E.164 form (`+8613812345678`); APIs show them masked. A number
+-- belongs to at most one live user. `user_with_roles` is recreated to expose it.
+-- ============================================================================
+
+ALTER TABLE users ADD COLUMN phone TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_phone
+    ON users(phone) WHERE deleted_at IS NULL AND phone IS NOT NULL;
+
+DROP VIEW IF EXISTS user_with_roles;
+
+CREATE VIEW IF NOT EXISTS user_with_roles AS
+SELECT
+    u.id AS id,
+    u.username,
+    u.email,
+    u.phone,
+    u.real_name,
+    u.password_hash,
+    u.avatar_url,
+    u.status,
+    u.is_system,
+    u.last_login_at,
+    u.created_at,
+    u.updated_at,
+    COALESCE(
+        (
+            SELECT json_group_array(json_object('label', ro.name, 'value', ro.id))
+            FROM (
+                SELECT r.name, r.id
+                FROM user_roles ur
+                INNER JOIN roles r ON ur.role_id = r.id AND r.deleted_at IS NULL
+                WHERE ur.user_id = u.id
+                ORDER BY r.id
+            ) ro
+        ),
+        '[]'
+    ) AS roles
+FROM users u
+WHERE u.deleted_at IS NULL;
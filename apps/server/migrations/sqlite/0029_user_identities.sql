@@ -0,0 +1,18 @@
+-- ============================================================================
+-- Module: Enterprise login identities.
+-- Links an account at WeChat Work, DingTalk or Feishu to a local user, so
+-- scanning the provider's QR code signs that user in. A provider account maps
+-- to one user, and a user has at most one account per provider.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS user_identities (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    provider TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    display_name TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_login_at DATETIME,
+    UNIQUE (provider, subject),
+    UNIQUE (user_id, provider)
+);
@@ -0,0 +1,22 @@
+-- ============================================================================
+-- Module: Label translations.
+-- Optional per-locale text for dictionary labels and menu names. Requests are
+-- served the translation for their Accept-Language locale (`en`, `zh-CN`) and
+-- fall back to the base `dicts.label` / `menus.name` when there is none.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS dict_i18n (
+    dict_id INTEGER NOT NULL,
+    locale TEXT NOT NULL,
+    label TEXT NOT NULL,
+    PRIMARY KEY (dict_id, locale),
+    FOREIGN KEY (dict_id) REFERENCES dicts(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS menu_i18n (
+    menu_id INTEGER NOT NULL,
+    locale TEXT NOT NULL,
+    name TEXT NOT NULL,
+    PRIMARY KEY (menu_id, locale),
+    FOREIGN KEY (menu_id) REFERENCES menus(id) ON DELETE CASCADE
+);
@@ -0,0 +1,8 @@
+-- ============================================================================
+-- Module: Per-user timezone preference.
+-- IANA name (`Asia/Shanghai`) used to group dashboard trends and format CSV
+-- exports for the user. NULL falls back to `RUSTZEN_TIMEZONE`. Timestamps are
+-- still stored and returned in UTC.
+-- ============================================================================
+
+ALTER TABLE users ADD COLUMN timezone TEXT;
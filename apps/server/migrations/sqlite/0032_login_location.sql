@@ -0,0 +1,12 @@
+-- ============================================================================
+-- Module: Login locations from the offline GeoIP database.
+-- Country (ISO code) and city on login log rows, and the last login's address
+-- and location on the user, which the unusual-location alert compares against.
+-- ============================================================================
+
+ALTER TABLE operation_logs ADD COLUMN country TEXT;
+ALTER TABLE operation_logs ADD COLUMN city TEXT;
+
+ALTER TABLE users ADD COLUMN last_login_ip TEXT;
+ALTER TABLE users ADD COLUMN last_login_country TEXT;
+ALTER TABLE users ADD COLUMN last_login_city TEXT;
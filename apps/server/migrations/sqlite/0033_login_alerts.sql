@@ -0,0 +1,46 @@
+-- ============================================================================
+-- Module: Suspicious login detection and in-app notifications.
+-- Rules run on every successful login; each can be switched off here. The
+-- address and user agent pairs a user signed in from feed the new-device and
+-- impossible-travel rules. Alerts land in the notifications of the account
+-- owner and of the security admins.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS login_alert_rules (
+    code TEXT PRIMARY KEY,
+    enabled INTEGER NOT NULL DEFAULT 1 CHECK(enabled IN (0, 1)),
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT OR IGNORE INTO login_alert_rules (code) VALUES
+    ('new_device'),
+    ('impossible_travel'),
+    ('failures_then_success');
+
+CREATE TABLE IF NOT EXISTS user_login_sources (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    ip_address TEXT NOT NULL,
+    user_agent TEXT NOT NULL,
+    latitude REAL,
+    longitude REAL,
+    first_seen_at DATETIME NOT NULL,
+    last_seen_at DATETIME NOT NULL,
+    UNIQUE (user_id, ip_address, user_agent)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_login_sources_recent
+    ON user_login_sources(user_id, last_seen_at);
+
+CREATE TABLE IF NOT EXISTS notifications (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    data TEXT,
+    read_at DATETIME,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_user ON notifications(user_id, id);
@@ -0,0 +1,35 @@
+-- ============================================================================
+-- Module: Background export jobs.
+-- The `export-jobs` task writes each pending job to
+-- `<data_dir>/exports/<stored_name>` in chunks, updating `rows_done` as it
+-- goes. Finished files are served through signed links until `expires_at`,
+-- after which the task deletes them and marks the job expired.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS export_jobs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    resource TEXT NOT NULL CHECK(resource IN ('logs', 'users', 'dicts')),
+    -- List filters as a JSON object, in the query parameters of the list endpoint.
+    filters TEXT NOT NULL DEFAULT '{}',
+    user_id INTEGER NOT NULL,
+    username TEXT NOT NULL,
+    ip_address TEXT NOT NULL DEFAULT '',
+    -- Whether personal fields are masked, decided by the requester's capabilities.
+    masked INTEGER NOT NULL DEFAULT 1 CHECK(masked IN (0, 1)),
+    timezone TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK(status IN ('pending', 'running', 'completed', 'failed', 'expired')),
+    total_rows INTEGER,
+    rows_done INTEGER NOT NULL DEFAULT 0,
+    file_name TEXT NOT NULL,
+    stored_name TEXT NOT NULL UNIQUE,
+    size_bytes INTEGER,
+    error_message TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    started_at DATETIME,
+    finished_at DATETIME,
+    expires_at DATETIME
+);
+
+CREATE INDEX IF NOT EXISTS idx_export_jobs_user ON export_jobs(user_id, id);
+CREATE INDEX IF NOT EXISTS idx_export_jobs_status ON export_jobs(status);
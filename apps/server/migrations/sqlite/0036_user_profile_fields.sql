@@ -0,0 +1,55 @@
+-- ============================================================================
+-- Module: Custom user profile fields.
+-- Each deployment defines its own fields (employee ID, region); values live in
+-- `users.profile` as one JSON object keyed by field key and are checked
+-- against these definitions on write. `user_with_roles` is recreated to
+-- expose the profile.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS user_profile_fields (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    key TEXT NOT NULL UNIQUE,
+    label TEXT NOT NULL,
+    field_type TEXT NOT NULL CHECK(field_type IN ('text', 'number', 'boolean', 'date', 'select')),
+    required INTEGER NOT NULL DEFAULT 0 CHECK(required IN (0, 1)),
+    -- JSON array of allowed values for `select` fields.
+    options TEXT NOT NULL DEFAULT '[]',
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE users ADD COLUMN profile TEXT NOT NULL DEFAULT '{}';
+
+DROP VIEW IF EXISTS user_with_roles;
+
+CREATE VIEW IF NOT EXISTS user_with_roles AS
+SELECT
+    u.id AS id,
+    u.username,
+    u.email,
+    u.phone,
+    u.real_name,
+    u.password_hash,
+    u.avatar_url,
+    u.status,
+    u.is_system,
+    u.last_login_at,
+    u.created_at,
+    u.updated_at,
+    u.profile,
+    COALESCE(
+        (
+            SELECT json_group_array(json_object('label', ro.name, 'value', ro.id))
+            FROM (
+                SELECT r.name, r.id
+                FROM user_roles ur
+                INNER JOIN roles r ON ur.role_id = r.id AND r.deleted_at IS NULL
+                WHERE ur.user_id = u.id
+                ORDER BY r.id
+            ) ro
+        ),
+        '[]'
+    ) AS roles
+FROM users u
+WHERE u.deleted_at IS NULL;
@@ -0,0 +1,17 @@
+-- ============================================================================
+-- Module: Named filter presets that users save for list pages.
+-- `query` holds the list endpoint's filters and sort as a JSON object; paging
+-- is left out and supplied when the filter is applied.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS saved_filters (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    resource TEXT NOT NULL CHECK(resource IN ('users', 'logs')),
+    name TEXT NOT NULL,
+    query TEXT NOT NULL DEFAULT '{}',
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (user_id, resource, name),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
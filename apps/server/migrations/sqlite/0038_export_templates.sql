@@ -0,0 +1,24 @@
+-- ============================================================================
+-- Module: Export templates.
+-- A template picks the columns of a resource's CSV export. The resource's
+-- default template applies to exports that name none; without one, every
+-- column is exported. Export jobs keep the columns they were queued with.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS export_templates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    resource TEXT NOT NULL CHECK(resource IN ('logs', 'users', 'dicts')),
+    name TEXT NOT NULL,
+    -- JSON array of export column names, e.g. ["id", "username"].
+    columns TEXT NOT NULL,
+    is_default INTEGER NOT NULL DEFAULT 0 CHECK(is_default IN (0, 1)),
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (resource, name)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_export_templates_default
+    ON export_templates(resource) WHERE is_default = 1;
+
+-- JSON array of the exported columns; NULL exports every column.
+ALTER TABLE export_jobs ADD COLUMN columns TEXT;
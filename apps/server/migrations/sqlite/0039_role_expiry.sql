@@ -0,0 +1,67 @@
+-- ============================================================================
+-- Module: Temporary role assignments.
+-- An assignment with `expires_at` stops granting its role once that time has
+-- passed; the `role-expiry` task then removes it, records the removal in the
+-- role history and notifies the user. `NULL` keeps the assignment for good.
+-- Times are stored in UTC like `CURRENT_TIMESTAMP`, so the views compare
+-- them directly.
+-- ============================================================================
+
+ALTER TABLE user_roles ADD COLUMN expires_at DATETIME;
+
+CREATE INDEX IF NOT EXISTS idx_user_roles_expires_at
+    ON user_roles(expires_at) WHERE expires_at IS NOT NULL;
+
+DROP VIEW IF EXISTS user_with_roles;
+DROP VIEW IF EXISTS user_permissions;
+
+CREATE VIEW IF NOT EXISTS user_with_roles AS
+SELECT
+    u.id AS id,
+    u.username,
+    u.email,
+    u.phone,
+    u.real_name,
+    u.password_hash,
+    u.avatar_url,
+    u.status,
+    u.is_system,
+    u.last_login_at,
+    u.created_at,
+    u.updated_at,
+    u.profile,
+    COALESCE(
+        (
+            SELECT json_group_array(json_object('label', ro.name, 'value', ro.id))
+            FROM (
+                SELECT r.name, r.id
+                FROM user_roles ur
+                INNER JOIN roles r ON ur.role_id = r.id AND r.deleted_at IS NULL
+                WHERE ur.user_id = u.id
+                  AND (ur.expires_at IS NULL OR ur.expires_at > CURRENT_TIMESTAMP)
+                ORDER BY r.id
+            ) ro
+        ),
+        '[]'
+    ) AS roles
+FROM users u
+WHERE u.deleted_at IS NULL;
+
+CREATE VIEW IF NOT EXISTS user_permissions AS
+SELECT DISTINCT
+    u.id AS user_id,
+    u.username,
+    m.code AS menu_code,
+    m.menu_type,
+    r.code AS role_code,
+    m.id AS menu_id,
+    r.id AS role_id
+FROM users u
+INNER JOIN user_roles ur ON u.id = ur.user_id
+    AND (ur.expires_at IS NULL OR ur.expires_at > CURRENT_TIMESTAMP)
+INNER JOIN roles r ON ur.role_id = r.id AND r.status = 1 AND r.deleted_at IS NULL
+INNER JOIN role_menus rm ON r.id = rm.role_id
+INNER JOIN menus m ON rm.menu_id = m.id AND m.deleted_at IS NULL
+WHERE u.deleted_at IS NULL
+  AND u.status = 1
+  AND m.code IS NOT NULL;
@@ -0,0 +1,19 @@
+-- ============================================================================
+-- Module: Network and time restrictions on roles.
+-- A role with a row here only grants its capabilities to API requests from an
+-- allowed address range, on an allowed weekday, within the allowed hours in
+-- RUSTZEN_TIMEZONE. Each part left empty allows everything; roles without a
+-- row are not restricted.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS role_access_restrictions (
+    role_id INTEGER PRIMARY KEY REFERENCES roles(id) ON DELETE CASCADE,
+    -- JSON array of CIDR ranges, e.g. ["10.0.0.0/8"].
+    allowed_cidrs TEXT NOT NULL DEFAULT '[]',
+    -- JSON array of ISO weekdays, 1 = Monday to 7 = Sunday.
+    allowed_days TEXT NOT NULL DEFAULT '[]',
+    -- `HH:MM`; both set or both NULL. An end before the start runs past midnight.
+    start_time TEXT,
+    end_time TEXT,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,7 @@
+-- ============================================================================
+-- Module: Single session per account.
+-- With RUSTZEN_SINGLE_SESSION=true each login stores a new session id here and
+-- carries it in the token's `sid` claim; tokens with another `sid` are refused.
+-- ============================================================================
+
+ALTER TABLE users ADD COLUMN session_id TEXT;
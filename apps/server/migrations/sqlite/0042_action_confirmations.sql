@@ -0,0 +1,20 @@
+-- ============================================================================
+-- Module: Confirmation tokens for irreversible admin actions.
+-- `POST /api/system/confirmations` stores one row per token for the exact
+-- target in `payload`; the purge endpoint deletes it when the token is used,
+-- so each token works once, for its issuer, until `expires_at`.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS action_confirmations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- SHA-256 of the token; the token itself is only sent to the issuer.
+    token_hash TEXT NOT NULL UNIQUE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- `{"action": "user.purge", "params": {...}}`
+    payload TEXT NOT NULL,
+    expires_at DATETIME NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_action_confirmations_expires_at
+    ON action_confirmations(expires_at);
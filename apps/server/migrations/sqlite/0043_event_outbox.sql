@@ -0,0 +1,24 @@
+-- ============================================================================
+-- Module: Domain event outbox.
+-- Each published event gets one row per subscriber (audit log, webhook
+-- dispatcher, login alerts), written in the same transaction as the change it
+-- describes. The relay hands each row to its subscriber and deletes it once
+-- handled; a failed row is retried with backoff and kept as `failed` after the
+-- last attempt. A relay that stops mid-row leaves it pending, so it is picked
+-- up again once its lease in `available_at` runs out.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS event_outbox (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    subscriber TEXT NOT NULL,
+    -- Dotted event name such as `role.deleted`; `payload` holds the event as JSON.
+    event TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    available_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_error TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_outbox_due ON event_outbox(available_at, id) WHERE status = 'pending';
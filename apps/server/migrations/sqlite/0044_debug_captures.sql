@@ -0,0 +1,20 @@
+-- ============================================================================
+-- Module: Opt-in request payload capture for debugging.
+-- While a capture is live, request log rows of the matching user and/or route
+-- template carry the sanitized request body in `operation_logs.data`.
+-- Captures are never extended; they stop at `expires_at` or when deleted.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS debug_captures (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER REFERENCES users(id) ON DELETE CASCADE,
+    -- Route template such as `/api/system/users/{id}`.
+    route TEXT,
+    reason TEXT,
+    expires_at DATETIME NOT NULL,
+    created_by INTEGER NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (user_id IS NOT NULL OR route IS NOT NULL)
+);
+
+CREATE INDEX IF NOT EXISTS idx_debug_captures_expires_at ON debug_captures(expires_at);
@@ -1,4 +1,4 @@
-use crate::common::error::AppError;
+use crate::common::{error::AppError, pagination::Pagination};
 
 use axum::Json;
 use serde::{Deserialize, Serialize};
@@ -10,6 +10,37 @@ pub struct ApiResponse<T> {
     pub data: T,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub total: Option<i64>,
+    #[serde(flatten)]
+    pub page: Option<PageMeta>,
+}
+
+/// Paging fields sent next to `total` so tables don't recompute them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageMeta {
+    pub current: i64,
+    pub page_size: i64,
+    pub total_pages: i64,
+    pub has_next: bool,
+}
+
+impl PageMeta {
+    pub fn new(pagination: Pagination, total: i64) -> Self {
+        let page_size = pagination.page_size();
+        let current = pagination.current();
+        let total_pages = (total.max(0) + page_size - 1) / page_size;
+        Self { current, page_size, total_pages, has_next: current < total_pages }
+    }
+
+    /// Metadata for list endpoints that return every row in one response.
+    pub fn single(total: i64) -> Self {
+        Self {
+            current: 1,
+            page_size: total.max(0),
+            total_pages: i64::from(total > 0),
+            has_next: false,
+        }
+    }
 }
 
 impl<T: Serialize> ApiResponse<T> {
@@ -18,13 +49,17 @@ impl<T: Serialize> ApiResponse<T> {
     }
 
     pub fn new(data: T, total: Option<i64>) -> Self {
-        Self { code: 0, message: "Success".to_string(), data, total }
+        Self { code: 0, message: "Success".to_string(), data, total, page: None }
     }
 }
 
 impl<T: Serialize> ApiResponse<Vec<T>> {
-    pub fn page(data: Vec<T>, total: i64) -> Json<Self> {
-        Json(Self::new(data, Some(total)))
+    pub fn page(data: Vec<T>, total: i64, page: PageMeta) -> Json<Self> {
+        Json(Self::with_page(data, total, page))
+    }
+
+    pub fn with_page(data: Vec<T>, total: i64, page: PageMeta) -> Self {
+        Self { page: Some(page), ..Self::new(data, Some(total)) }
     }
 }
 
@@ -36,15 +71,59 @@ pub struct OptionItem<T> {
     pub value: T,
 }
 
-#[derive(Debug, Deserialize)]
+/// Query accepted by every `/options` endpoint; see `common::query::fetch_options`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct OptionsQuery {
+    /// Case-insensitive keyword matched against the option label.
     pub q: Option<String>,
+    /// Maximum rows, clamped to `1..=OPTIONS_MAX_LIMIT`.
     pub limit: Option<i64>,
+    /// Status to list; enabled rows (`1`) by default, `all` for every status.
+    pub status: Option<String>,
+    /// Comma-separated option values to leave out, e.g. the record being edited.
+    pub exclude_ids: Option<String>,
+    /// Comma-separated option values returned first whatever `q`, `status` and `limit` say,
+    /// so a form can show its current selection.
+    pub include_ids: Option<String>,
 }
 
+/// Dictionary type filter read next to [`OptionsQuery`].
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DictOptionsQuery {
     pub dict_type: Option<String>,
-    pub q: Option<String>,
-    pub limit: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ApiResponse, PageMeta};
+    use crate::common::pagination::{Pagination, PaginationQuery};
+
+    #[test]
+    fn page_meta_reports_position_and_next_page() {
+        let second =
+            Pagination::from_query(PaginationQuery { current: Some(2), page_size: Some(10) });
+        assert_eq!(
+            PageMeta::new(second, 25),
+            PageMeta { current: 2, page_size: 10, total_pages: 3, has_next: true }
+        );
+        let last =
+            Pagination::from_query(PaginationQuery { current: Some(3), page_size: Some(10) });
+        assert!(!PageMeta::new(last, 25).has_next);
+        assert_eq!(PageMeta::new(last, 0).total_pages, 0);
+        assert_eq!(PageMeta::single(0).total_pages, 0);
+    }
+
+    #[test]
+    fn page_meta_is_flattened_into_the_envelope() {
+        let response = ApiResponse::with_page(vec![1, 2], 2, PageMeta::single(2));
+        let json = serde_json::to_value(&response).expect("serialize");
+
+        assert_eq!(json["total"], 2);
+        assert_eq!(json["pageSize"], 2);
+        assert_eq!(json["totalPages"], 1);
+        assert_eq!(json["hasNext"], false);
+        assert!(serde_json::to_value(ApiResponse::new((), None)).unwrap().get("current").is_none());
+    }
 }
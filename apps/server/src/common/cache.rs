@@ -0,0 +1,65 @@
+//! Small in-process TTL cache for read-heavy aggregates.
+
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::RwLock,
+    time::{Duration, Instant},
+};
+
+/// Keyed values that expire `ttl` after they were stored.
+pub struct TtlCache<K, V> {
+    ttl: Duration,
+    entries: RwLock<HashMap<K, (Instant, V)>>,
+}
+
+impl<K: Eq + Hash, V: Clone> TtlCache<K, V> {
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, entries: RwLock::new(HashMap::new()) }
+    }
+
+    /// Returns a clone of the value when it is still fresh.
+    pub fn get(&self, key: &K) -> Option<V> {
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+        entries
+            .get(key)
+            .filter(|(stored_at, _)| stored_at.elapsed() < self.ttl)
+            .map(|(_, value)| value.clone())
+    }
+
+    /// Stores a value and drops any expired entries.
+    pub fn insert(&self, key: K, value: V) {
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|_, (stored_at, _)| stored_at.elapsed() < self.ttl);
+        entries.insert(key, (Instant::now(), value));
+    }
+
+    /// Number of stored entries, including expired ones not yet evicted.
+    pub fn len(&self) -> usize {
+        self.entries.read().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TtlCache;
+    use std::time::Duration;
+
+    #[test]
+    fn entries_expire_after_ttl() {
+        let cache = TtlCache::new(Duration::from_millis(20));
+        cache.insert(7, "week");
+        assert_eq!(cache.get(&7), Some("week"));
+        assert_eq!(cache.get(&30), None);
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(cache.get(&7), None);
+        cache.insert(30, "month");
+        assert_eq!(cache.get(&30), Some("month"));
+        assert_eq!(cache.len(), 1);
+    }
+}
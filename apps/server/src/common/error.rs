@@ -1,8 +1,14 @@
+use crate::common::{
+    i18n,
+    validation::{FieldConflict, FieldError},
+};
+
 use axum::{
     Json,
-    http::StatusCode,
+    http::{StatusCode, header::RETRY_AFTER},
     response::{IntoResponse, Response},
 };
+use rustzen_core::error::CoreError;
 
 /// A unified error type for the business logic layer.
 #[derive(Debug, thiserror::Error)]
@@ -35,6 +41,10 @@ pub enum ServiceError {
     #[error("Menu is system built-in")]
     MenuIsSystem,
 
+    /// A built-in (`is_system`) record was targeted by a delete or a critical-field change.
+    #[error("System {0} is protected")]
+    SystemRecordProtected(String),
+
     /// A database query failed.
     #[error("Database query failed")]
     DatabaseQueryFailed,
@@ -47,6 +57,10 @@ pub enum ServiceError {
     #[error("Invalid username or password")]
     InvalidCredentials,
 
+    /// The source IP is banned from logging in for this many more seconds.
+    #[error("Too many failed logins, retry after {0}s")]
+    TooManyLoginAttempts(u64),
+
     /// The provided JWT was invalid or expired.
     #[error("Invalid or expired token")]
     InvalidToken,
@@ -55,6 +69,10 @@ pub enum ServiceError {
     #[error("Failed to generate token")]
     TokenCreationFailed,
 
+    /// A cookie-authenticated write did not echo a valid CSRF token.
+    #[error("Missing or invalid CSRF token")]
+    InvalidCsrfToken,
+
     /// A username that was provided already exists.
     #[error("Username already exists")]
     UsernameConflict,
@@ -63,6 +81,30 @@ pub enum ServiceError {
     #[error("Email already exists")]
     EmailConflict,
 
+    /// The phone number is already bound to another user.
+    #[error("Phone number already exists")]
+    PhoneConflict,
+
+    /// The login provider account is already linked to another user.
+    #[error("External account is linked to another user")]
+    IdentityConflict,
+
+    /// A provider login succeeded but no user is linked to that account.
+    #[error("No user is linked to this {0} account")]
+    ExternalAccountNotLinked(String),
+
+    /// A soft-deleted record cannot come back until these fields are renamed.
+    #[error("Restoring would clash with live records")]
+    RestoreConflict(Vec<FieldConflict>),
+
+    /// A sensitive action needs a login or re-authentication within this many minutes.
+    #[error("Re-authentication within {0} minutes is required")]
+    ReauthRequired(u64),
+
+    /// An irreversible action was called without a valid confirmation token for it.
+    #[error("A confirmation token for {0} is required")]
+    ConfirmationRequired(&'static str),
+
     /// An operation was attempted that is invalid given the current state.
     #[error("Invalid operation: {0}")]
     InvalidOperation(String),
@@ -86,25 +128,84 @@ pub enum ServiceError {
     /// Failed to create avatar file.
     #[error("Failed to create avatar file")]
     CreateAvatarFileFailed,
+
+    /// A request handler panicked; the panic itself was logged and reported.
+    #[error("Request handler panicked")]
+    HandlerPanicked,
+
+    /// The request body exceeded the route's size limit.
+    #[error("Request body is too large")]
+    PayloadTooLarge,
+
+    /// One or more payload fields failed validation.
+    #[error("Invalid fields: {}", .0.iter().map(|e| e.field.as_str()).collect::<Vec<_>>().join(", "))]
+    InvalidFields(Vec<FieldError>),
+
+    /// Dual control filed the action as this approval instead of running it.
+    #[error("Waiting for approval {0}")]
+    ApprovalPending(i64),
+
+    /// Creating the resource would go past its configured quota.
+    #[error("Quota exceeded for {resource} (limit {limit})")]
+    QuotaExceeded { resource: &'static str, limit: u64 },
+
+    /// `POST /api/auth/register` was called while self-registration is turned off.
+    #[error("Self-registration is disabled")]
+    RegistrationDisabled,
+
+    /// A one-time code was requested again before the resend interval ran out.
+    #[error("Verification code requested too often, retry after {0}s")]
+    OtpRateLimited(u64),
+
+    /// A one-time code was wrong, expired, or used up its attempts.
+    #[error("Invalid or expired verification code")]
+    InvalidVerificationCode,
+
+    /// The feature is not enabled by the installed license.
+    #[error("Feature {0} is not licensed")]
+    FeatureNotLicensed(&'static str),
 }
 
 /// A unified error type for the application layer, which can be converted into an HTTP response.
+///
+/// The optional second field is sent as `Retry-After` seconds, the third as `data`.
 #[derive(Debug)]
-pub struct AppError((StatusCode, i32, String));
+pub struct AppError((StatusCode, i32, String), Option<u64>, Option<serde_json::Value>);
 
+/// Builds an error, swapping in the request locale's text when one exists for `code`.
 fn app_error(status: StatusCode, code: i32, message: impl Into<String>) -> AppError {
-    AppError((status, code, message.into()))
+    let message = match i18n::message(i18n::current_locale(), code) {
+        Some(localized) => localized.to_string(),
+        None => message.into(),
+    };
+    AppError((status, code, message), None, None)
+}
+
+/// Code and message of a `5xx` [`AppError`], kept on the response for error reporting.
+#[derive(Debug, Clone)]
+pub struct ServerErrorInfo {
+    pub code: i32,
+    pub message: String,
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, code, message) = self.0;
+        let info =
+            status.is_server_error().then(|| ServerErrorInfo { code, message: message.clone() });
         let body = Json(serde_json::json!({
             "code": code,
             "message": message,
-            "data": null,
+            "data": self.2,
         }));
-        (status, body).into_response()
+        let mut response = match self.1 {
+            Some(seconds) => (status, [(RETRY_AFTER, seconds.to_string())], body).into_response(),
+            None => (status, body).into_response(),
+        };
+        if let Some(info) = info {
+            response.extensions_mut().insert(info);
+        }
+        response
     }
 }
 
@@ -113,9 +214,11 @@ impl IntoResponse for AppError {
 impl From<ServiceError> for AppError {
     fn from(err: ServiceError) -> Self {
         match err {
-            ServiceError::NotFound(resource) => {
-                app_error(StatusCode::NOT_FOUND, 10001, format!("{} not found.", resource))
-            }
+            ServiceError::NotFound(resource) => app_error(
+                StatusCode::NOT_FOUND,
+                10001,
+                i18n::not_found(i18n::current_locale(), &resource),
+            ),
             ServiceError::InvalidOperation(reason) => {
                 app_error(StatusCode::BAD_REQUEST, 10002, reason)
             }
@@ -153,20 +256,137 @@ impl From<ServiceError> for AppError {
             ServiceError::MenuIsSystem => {
                 app_error(StatusCode::BAD_REQUEST, 10010, "Cannot modify system built-in menu.")
             }
+            ServiceError::SystemRecordProtected(resource) => app_error(
+                StatusCode::BAD_REQUEST,
+                10014,
+                i18n::system_protected(i18n::current_locale(), &resource),
+            ),
+            ServiceError::InvalidFields(fields) => AppError(
+                app_error(StatusCode::BAD_REQUEST, 10015, "Some fields are invalid.").0,
+                None,
+                Some(serde_json::json!(fields)),
+            ),
+            ServiceError::ApprovalPending(approval_id) => AppError(
+                app_error(
+                    StatusCode::ACCEPTED,
+                    10016,
+                    "This action needs approval from a second administrator.",
+                )
+                .0,
+                None,
+                Some(serde_json::json!({ "approvalId": approval_id })),
+            ),
+            ServiceError::QuotaExceeded { resource, limit } => AppError(
+                app_error(StatusCode::FORBIDDEN, 10017, "Usage quota exceeded.").0,
+                None,
+                Some(serde_json::json!({ "resource": resource, "limit": limit })),
+            ),
+            ServiceError::FeatureNotLicensed(feature) => AppError(
+                app_error(
+                    StatusCode::FORBIDDEN,
+                    10018,
+                    "This feature is not included in the current license.",
+                )
+                .0,
+                None,
+                Some(serde_json::json!({ "feature": feature })),
+            ),
+            ServiceError::RegistrationDisabled => {
+                app_error(StatusCode::FORBIDDEN, 10019, "Self-registration is disabled.")
+            }
+            ServiceError::OtpRateLimited(retry_after) => AppError(
+                app_error(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    10020,
+                    "A verification code was sent recently. Please wait before requesting another.",
+                )
+                .0,
+                Some(retry_after),
+                None,
+            ),
+            ServiceError::InvalidVerificationCode => {
+                app_error(StatusCode::BAD_REQUEST, 10021, "Invalid or expired verification code.")
+            }
+            ServiceError::ConfirmationRequired(action) => AppError(
+                app_error(
+                    StatusCode::PRECONDITION_REQUIRED,
+                    10022,
+                    "Confirm this action again before it runs.",
+                )
+                .0,
+                None,
+                Some(serde_json::json!({ "action": action })),
+            ),
+            ServiceError::PayloadTooLarge => {
+                app_error(StatusCode::PAYLOAD_TOO_LARGE, 10013, "Request body is too large.")
+            }
             ServiceError::InvalidCredentials => {
                 app_error(StatusCode::UNAUTHORIZED, 10101, "Invalid username or password.")
             }
+            ServiceError::TooManyLoginAttempts(retry_after) => AppError(
+                app_error(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    10102,
+                    "Too many failed login attempts. Please try again later.",
+                )
+                .0,
+                Some(retry_after),
+                None,
+            ),
             ServiceError::TokenCreationFailed => app_error(
                 StatusCode::INTERNAL_SERVER_ERROR,
                 10103,
                 "Failed to generate login token. Please try again.",
             ),
+            ServiceError::InvalidCsrfToken => app_error(
+                StatusCode::FORBIDDEN,
+                10104,
+                "Missing or invalid CSRF token. Refresh the page and try again.",
+            ),
             ServiceError::UsernameConflict => {
                 app_error(StatusCode::CONFLICT, 10201, "Username already exists.")
             }
             ServiceError::EmailConflict => {
                 app_error(StatusCode::CONFLICT, 10202, "Email already exists.")
             }
+            ServiceError::PhoneConflict => {
+                app_error(StatusCode::CONFLICT, 10203, "Phone number already exists.")
+            }
+            ServiceError::IdentityConflict => app_error(
+                StatusCode::CONFLICT,
+                10204,
+                "This account is already linked to another user.",
+            ),
+            ServiceError::RestoreConflict(conflicts) => AppError(
+                app_error(
+                    StatusCode::CONFLICT,
+                    10205,
+                    "Another record now uses some of these values. Rename them to restore.",
+                )
+                .0,
+                None,
+                Some(serde_json::json!(conflicts)),
+            ),
+            ServiceError::ExternalAccountNotLinked(provider) => AppError(
+                app_error(
+                    StatusCode::UNAUTHORIZED,
+                    10105,
+                    "No user is linked to this account. Sign in with a password and link it first.",
+                )
+                .0,
+                None,
+                Some(serde_json::json!({ "provider": provider })),
+            ),
+            ServiceError::ReauthRequired(max_age_minutes) => AppError(
+                app_error(
+                    StatusCode::FORBIDDEN,
+                    10106,
+                    "Confirm your password or verification code to continue.",
+                )
+                .0,
+                None,
+                Some(serde_json::json!({ "maxAgeMinutes": max_age_minutes })),
+            ),
             ServiceError::DatabaseQueryFailed => app_error(
                 StatusCode::INTERNAL_SERVER_ERROR,
                 20001,
@@ -182,6 +402,11 @@ impl From<ServiceError> for AppError {
                 20003,
                 "Failed to create avatar file. Please try again later.",
             ),
+            ServiceError::HandlerPanicked => app_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                20004,
+                "Unexpected server error. Please try again later.",
+            ),
             ServiceError::InvalidToken => app_error(
                 StatusCode::UNAUTHORIZED,
                 30000,
@@ -191,6 +416,22 @@ impl From<ServiceError> for AppError {
     }
 }
 
+/// Renders the bare responses of the `rustzen_core` auth layer like any other error.
+impl From<CoreError> for AppError {
+    fn from(err: CoreError) -> Self {
+        match err {
+            CoreError::InvalidToken | CoreError::MissingAuthContext => {
+                ServiceError::InvalidToken.into()
+            }
+            CoreError::PermissionDenied => app_error(
+                StatusCode::FORBIDDEN,
+                30001,
+                "You do not have permission to perform this action.",
+            ),
+        }
+    }
+}
+
 /// Allows `sqlx::Error` to be converted into `AppError` for convenience in route handlers.
 /// This should be used sparingly, prefer mapping to `ServiceError` in the service layer.
 impl From<sqlx::Error> for AppError {
@@ -1,16 +1,35 @@
 use crate::common::error::ServiceError;
-use crate::infra::config::CONFIG;
+use crate::features::system::quota::service::QuotaService;
+use crate::infra::{config::AppConfig, session::decode_hex};
 
-use axum::extract::Multipart;
+use axum::{
+    extract::{Multipart, multipart::MultipartError},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
 use std::{fs::File, io::Write};
 use uuid::Uuid;
 
 const USER_AVATAR_MAX_SIZE: usize = 1024 * 1024;
 
+/// Maps a multipart read error, keeping body-limit rejections distinguishable from bad input.
+pub fn map_multipart_error(err: MultipartError, message: &str) -> ServiceError {
+    if err.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        ServiceError::PayloadTooLarge
+    } else {
+        ServiceError::InvalidOperation(message.to_string())
+    }
+}
+
 /// Saves a user avatar and returns its public URL.
-pub async fn save_avatar(multipart: &mut Multipart) -> Result<String, ServiceError> {
-    let avatar_dir = CONFIG.avatars_dir();
-    let avatar_public_prefix = CONFIG.avatars_prefix();
+pub async fn save_avatar(
+    config: &AppConfig,
+    multipart: &mut Multipart,
+) -> Result<String, ServiceError> {
+    let avatar_dir = config.avatars_dir();
+    let avatar_public_prefix = config.avatars_prefix();
 
     tokio::fs::create_dir_all(&avatar_dir)
         .await
@@ -19,7 +38,7 @@ pub async fn save_avatar(multipart: &mut Multipart) -> Result<String, ServiceErr
     let Some(field) = multipart
         .next_field()
         .await
-        .map_err(|_| ServiceError::InvalidOperation("Invalid multipart data".into()))?
+        .map_err(|e| map_multipart_error(e, "Invalid multipart data"))?
     else {
         return Err(ServiceError::InvalidOperation("No file provided".into()));
     };
@@ -43,14 +62,13 @@ pub async fn save_avatar(multipart: &mut Multipart) -> Result<String, ServiceErr
     let file_name = format!("{}.{}", Uuid::new_v4(), extension);
     let file_path = avatar_dir.join(&file_name);
 
-    let data = field
-        .bytes()
-        .await
-        .map_err(|_| ServiceError::InvalidOperation("Failed to read file data".into()))?;
+    let data =
+        field.bytes().await.map_err(|e| map_multipart_error(e, "Failed to read file data"))?;
 
     if data.len() > USER_AVATAR_MAX_SIZE {
         return Err(ServiceError::InvalidOperation("File size must be less than 1MB".into()));
     }
+    QuotaService::ensure_storage_room(config, data.len() as u64).await?;
 
     let mut file = File::create(&file_path).map_err(|_| ServiceError::CreateAvatarFileFailed)?;
     file.write_all(&data).map_err(|_| ServiceError::CreateAvatarFileFailed)?;
@@ -60,3 +78,77 @@ pub async fn save_avatar(multipart: &mut Multipart) -> Result<String, ServiceErr
 
     Ok(avatar_url)
 }
+
+/// File name of an uploaded avatar, or `None` for URLs outside the avatar prefix.
+pub fn avatar_file_name<'a>(config: &AppConfig, avatar_url: &'a str) -> Option<&'a str> {
+    let prefix = format!("{}/", config.avatars_prefix());
+    avatar_url
+        .strip_prefix(&prefix)
+        .filter(|name| !name.is_empty() && !name.contains(['/', '\\']) && *name != "..")
+}
+
+/// Deletes an uploaded avatar by its public URL; URLs outside the avatar prefix are ignored.
+pub async fn remove_avatar(config: &AppConfig, avatar_url: &str) {
+    let Some(file_name) = avatar_file_name(config, avatar_url) else {
+        return;
+    };
+    if let Err(e) = tokio::fs::remove_file(config.avatars_dir().join(file_name)).await
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        tracing::warn!("Failed to remove avatar {}: {}", avatar_url, e);
+    }
+}
+
+/// Deletes an export file by its stored name; a file already gone is not an error.
+pub async fn remove_export_file(config: &AppConfig, stored_name: &str) {
+    if let Err(e) = tokio::fs::remove_file(config.exports_dir().join(stored_name)).await
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        tracing::warn!("Failed to remove export file {}: {}", stored_name, e);
+    }
+}
+
+/// Public path of an export file, served without a token until `expires_at`.
+///
+/// The link carries its expiry and an HMAC over the file name and expiry, so it cannot be
+/// extended or pointed at another file.
+pub fn signed_export_url(
+    config: &AppConfig,
+    stored_name: &str,
+    expires_at: DateTime<Utc>,
+) -> String {
+    let expires = expires_at.timestamp();
+    let signature = download_mac(config, stored_name, expires)
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    format!("/api/files/exports/{}?expires={}&signature={}", stored_name, expires, signature)
+}
+
+/// Whether a link built by [`signed_export_url`] is intact and not yet expired.
+pub fn verify_export_link(
+    config: &AppConfig,
+    stored_name: &str,
+    expires: i64,
+    signature: &str,
+) -> bool {
+    if expires <= Utc::now().timestamp() {
+        return false;
+    }
+    let Some(signature) = decode_hex(signature) else {
+        return false;
+    };
+    download_mac(config, stored_name, expires).verify_slice(&signature).is_ok()
+}
+
+fn download_mac(config: &AppConfig, stored_name: &str, expires: i64) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(config.jwt.jwt_secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(b"export.");
+    mac.update(stored_name.as_bytes());
+    mac.update(b".");
+    mac.update(expires.to_string().as_bytes());
+    mac
+}
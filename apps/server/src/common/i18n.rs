@@ -0,0 +1,220 @@
+//! Localized error messages selected from the request `Accept-Language` header.
+//!
+//! Error codes stay stable; only the `message` text changes. The locale is stored in a
+//! task-local for the duration of each API request, so `AppError` conversions pick it
+//! up without threading it through every service call.
+//!
+//! Dictionary labels and menu names are localized from data instead: their
+//! [`Translation`]s are stored per locale tag and replace the base text when present.
+
+use crate::common::validation::FieldErrors;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Supported response languages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    En,
+    ZhCn,
+}
+
+impl Locale {
+    /// Every supported locale.
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::ZhCn];
+
+    /// Tag stored with translations, e.g. `zh-CN`.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::ZhCn => "zh-CN",
+        }
+    }
+
+    /// The locale with exactly this tag, ignoring case.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|locale| locale.tag().eq_ignore_ascii_case(tag.trim()))
+    }
+
+    /// Picks the highest-weighted supported language, falling back to English.
+    pub fn from_accept_language(header: &str) -> Self {
+        let mut candidates: Vec<(f32, Locale)> = header
+            .split(',')
+            .filter_map(|part| {
+                let mut pieces = part.split(';');
+                let tag = pieces.next()?.trim().to_ascii_lowercase();
+                let weight = pieces
+                    .find_map(|piece| piece.trim().strip_prefix("q="))
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                let locale = match tag.split('-').next()? {
+                    "zh" => Locale::ZhCn,
+                    "en" => Locale::En,
+                    _ => return None,
+                };
+                (weight > 0.0).then_some((weight, locale))
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+        candidates.first().map(|(_, locale)| *locale).unwrap_or_default()
+    }
+}
+
+tokio::task_local! {
+    static LOCALE: Locale;
+}
+
+/// Runs `future` with `locale` as the current request locale.
+pub async fn scope<F: Future>(locale: Locale, future: F) -> F::Output {
+    LOCALE.scope(locale, future).await
+}
+
+/// The current request locale, or English outside a request scope.
+pub fn current_locale() -> Locale {
+    LOCALE.try_with(|locale| *locale).unwrap_or_default()
+}
+
+/// Localized text for fixed-message error codes; `None` keeps the English default.
+pub fn message(locale: Locale, code: i32) -> Option<&'static str> {
+    if locale == Locale::En {
+        return None;
+    }
+    let text = match code {
+        10003 => "密码处理失败，请重试。",
+        10004 => "用户账号已禁用。",
+        10005 => "用户账号待审核。",
+        10006 => "用户账号已锁定。",
+        10007 => "用户状态无效。",
+        10008 => "不能修改管理员用户。",
+        10009 => "不能修改系统内置角色。",
+        10010 => "不能修改系统内置菜单。",
+        10011 => "当前密码不正确。",
+        10012 => "两次输入的新密码不一致。",
+        10013 => "请求体过大。",
+        10015 => "部分字段无效。",
+        10016 => "该操作需要另一位管理员审批。",
+        10017 => "已超出使用配额。",
+        10018 => "当前许可证未包含该功能。",
+        10019 => "未开放自助注册。",
+        10020 => "验证码发送过于频繁，请稍后再试。",
+        10021 => "验证码错误或已过期。",
+        10022 => "请再次确认该操作后再执行。",
+        10101 => "用户名或密码错误。",
+        10102 => "登录失败次数过多，请稍后再试。",
+        10103 => "生成登录令牌失败，请重试。",
+        10104 => "CSRF 令牌缺失或无效，请刷新页面后重试。",
+        10105 => "该账号尚未关联用户，请先使用密码登录并完成关联。",
+        10106 => "请验证密码或验证码后继续。",
+        10201 => "用户名已存在。",
+        10202 => "邮箱已存在。",
+        10203 => "手机号已被绑定。",
+        10204 => "该账号已关联其他用户。",
+        10205 => "已有其他记录使用了这些值，请重命名后再恢复。",
+        20001 => "服务暂时不可用，请稍后重试。",
+        20002 => "创建头像目录失败，请稍后重试。",
+        20003 => "创建头像文件失败，请稍后重试。",
+        20004 => "服务器内部错误，请稍后重试。",
+        30000 => "令牌无效或已过期，请重新登录。",
+        30001 => "没有权限执行该操作。",
+        _ => return None,
+    };
+    Some(text)
+}
+
+/// Text of a label in one locale, e.g. `{"locale": "en", "text": "Active"}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Translation {
+    pub locale: String,
+    pub text: String,
+}
+
+/// Every translation of a label; locales left out lose theirs.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReplaceTranslationsPayload {
+    pub translations: Vec<Translation>,
+}
+
+/// Trims the texts and rewrites locales to their canonical tag, recording unsupported
+/// locales, empty texts and repeated locales.
+pub fn check_translations(translations: &mut [Translation], errors: &mut FieldErrors) {
+    let mut seen = BTreeSet::new();
+    for (index, translation) in translations.iter_mut().enumerate() {
+        let field = |name: &str| format!("translations[{}].{}", index, name);
+        match Locale::from_tag(&translation.locale) {
+            Some(locale) if !seen.insert(locale.tag()) => {
+                errors.push(&field("locale"), "is listed more than once")
+            }
+            Some(locale) => translation.locale = locale.tag().to_string(),
+            None => {
+                let tags: Vec<&str> = Locale::ALL.iter().map(|locale| locale.tag()).collect();
+                errors.push(&field("locale"), format!("must be one of {}", tags.join(", ")));
+            }
+        }
+        translation.text = translation.text.trim().to_string();
+        if translation.text.is_empty() {
+            errors.push(&field("text"), "must not be empty");
+        }
+    }
+}
+
+/// Localized "not found" message for a named resource.
+pub fn not_found(locale: Locale, resource: &str) -> String {
+    match locale {
+        Locale::En => format!("{} not found.", resource),
+        Locale::ZhCn => format!("{}不存在。", resource),
+    }
+}
+
+/// Localized message for a protected built-in record.
+pub fn system_protected(locale: Locale, resource: &str) -> String {
+    match locale {
+        Locale::En => {
+            format!("System {} cannot be deleted or have critical fields changed.", resource)
+        }
+        Locale::ZhCn => format!("系统内置{}不能删除或修改关键字段。", resource),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Locale, Translation, check_translations, current_locale, message, scope};
+    use crate::common::validation::FieldErrors;
+
+    #[test]
+    fn accept_language_prefers_highest_weight() {
+        assert_eq!(Locale::from_accept_language("zh-CN,zh;q=0.9,en;q=0.8"), Locale::ZhCn);
+        assert_eq!(Locale::from_accept_language("en-US,zh-CN;q=0.5"), Locale::En);
+        assert_eq!(Locale::from_accept_language("fr-FR, zh;q=0.7"), Locale::ZhCn);
+        assert_eq!(Locale::from_accept_language("zh;q=0, de"), Locale::En);
+        assert_eq!(Locale::from_accept_language(""), Locale::En);
+    }
+
+    #[tokio::test]
+    async fn locale_is_scoped_to_the_request() {
+        assert_eq!(current_locale(), Locale::En);
+        let inner = scope(Locale::ZhCn, async { current_locale() }).await;
+        assert_eq!(inner, Locale::ZhCn);
+        assert_eq!(message(Locale::ZhCn, 10202), Some("邮箱已存在。"));
+        assert_eq!(message(Locale::En, 10202), None);
+    }
+
+    #[test]
+    fn translations_are_canonicalized_and_checked() {
+        let translation = |locale: &str, text: &str| Translation {
+            locale: locale.to_string(),
+            text: text.to_string(),
+        };
+        let mut translations = vec![translation("ZH-cn", " 启用 "), translation("en", "Active")];
+        let mut errors = FieldErrors::new();
+        check_translations(&mut translations, &mut errors);
+        assert!(errors.into_result().is_ok());
+        assert_eq!(translations[0], translation("zh-CN", "启用"));
+
+        let mut translations =
+            vec![translation("en", "Active"), translation("en", "On"), translation("fr", " ")];
+        let mut errors = FieldErrors::new();
+        check_translations(&mut translations, &mut errors);
+        assert!(errors.into_result().is_err());
+    }
+}
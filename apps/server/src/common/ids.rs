@@ -0,0 +1,81 @@
+//! Typed primary keys for the RBAC tables.
+//!
+//! Each id is a transparent `i64` on the wire and in SQLite, so JSON stays a plain
+//! number and `.bind(id)` works unchanged, while passing a `RoleId` where a `MenuId`
+//! is expected fails to compile.
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, num::ParseIntError, str::FromStr};
+
+macro_rules! typed_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(
+            Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+            sqlx::Type,
+        )]
+        #[serde(transparent)]
+        #[sqlx(transparent)]
+        pub struct $name(pub i64);
+
+        impl $name {
+            pub fn get(self) -> i64 {
+                self.0
+            }
+        }
+
+        impl From<$name> for i64 {
+            fn from(id: $name) -> i64 {
+                id.0
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseIntError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                s.parse().map(Self)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+    };
+}
+
+typed_id!(
+    /// `users.id`
+    UserId
+);
+typed_id!(
+    /// `roles.id`
+    RoleId
+);
+typed_id!(
+    /// `menus.id`
+    MenuId
+);
+
+#[cfg(test)]
+mod tests {
+    use super::{MenuId, RoleId, UserId};
+
+    #[test]
+    fn ids_are_plain_numbers_in_json() {
+        assert_eq!(serde_json::to_string(&vec![RoleId(1), RoleId(2)]).unwrap(), "[1,2]");
+        let ids: Vec<MenuId> = serde_json::from_str("[7]").unwrap();
+        assert_eq!(ids, vec![MenuId(7)]);
+        assert_eq!(UserId(5).to_string(), "5");
+    }
+
+    #[tokio::test]
+    async fn ids_bind_and_decode_as_integers() {
+        let pool = crate::infra::db::memory_pool().await;
+        let id: UserId =
+            sqlx::query_scalar("SELECT ? + 1").bind(UserId(41)).fetch_one(&pool).await.unwrap();
+        assert_eq!(id, UserId(42));
+    }
+}
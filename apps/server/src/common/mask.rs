@@ -0,0 +1,96 @@
+//! Partial masking of personal data in API responses.
+//!
+//! Emails, phone numbers and client IPs are shown in full only to callers holding
+//! `system:privacy:view`; everyone else gets enough to tell records apart, such as
+//! `a***@example.com` or `203.0.*.*`. Responses apply the mask while they are built from
+//! rows, so nothing downstream sees the raw value.
+
+use rustzen_core::{auth::CurrentUser, capability::system_privacy, sms::mask_phone};
+use std::net::IpAddr;
+
+/// How a caller may see personal fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldMask {
+    Reveal,
+    Mask,
+}
+
+impl FieldMask {
+    pub fn for_user(user: &CurrentUser) -> Self {
+        if user.has_capability(system_privacy::VIEW) { Self::Reveal } else { Self::Mask }
+    }
+
+    pub fn email(self, email: Option<String>) -> Option<String> {
+        self.apply(email, mask_email)
+    }
+
+    pub fn phone(self, phone: Option<String>) -> Option<String> {
+        self.apply(phone, mask_phone)
+    }
+
+    pub fn ip(self, ip: String) -> String {
+        match self {
+            Self::Reveal => ip,
+            Self::Mask => mask_ip(&ip),
+        }
+    }
+
+    fn apply(self, value: Option<String>, mask: fn(&str) -> String) -> Option<String> {
+        match self {
+            Self::Reveal => value,
+            Self::Mask => value.as_deref().map(mask),
+        }
+    }
+}
+
+/// Response types that carry personal fields.
+pub trait MaskFields {
+    fn masked(self, mask: FieldMask) -> Self;
+}
+
+/// Keeps the first character of the local part and the whole domain.
+pub fn mask_email(email: &str) -> String {
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            let first = local.chars().next().map(String::from).unwrap_or_default();
+            format!("{}***@{}", first, domain)
+        }
+        None => "***".to_string(),
+    }
+}
+
+/// Keeps the network half of an address: two octets of IPv4, two groups of IPv6.
+pub fn mask_ip(ip: &str) -> String {
+    match ip.trim().parse::<IpAddr>() {
+        Ok(IpAddr::V4(v4)) => {
+            let [a, b, _, _] = v4.octets();
+            format!("{}.{}.*.*", a, b)
+        }
+        Ok(IpAddr::V6(v6)) => {
+            let [a, b, ..] = v6.segments();
+            format!("{:x}:{:x}:*", a, b)
+        }
+        Err(_) if ip.is_empty() => String::new(),
+        Err(_) => "***".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FieldMask, mask_email, mask_ip};
+
+    #[test]
+    fn masks_keep_enough_to_tell_records_apart() {
+        assert_eq!(mask_email("alice@example.com"), "a***@example.com");
+        assert_eq!(mask_email("not-an-email"), "***");
+        assert_eq!(mask_ip("203.0.113.7"), "203.0.*.*");
+        assert_eq!(mask_ip("2001:db8::1"), "2001:db8:*");
+        assert_eq!(mask_ip("unknown"), "***");
+        assert_eq!(mask_ip(""), "");
+
+        let phone = Some("+8613812345678".to_string());
+        assert_eq!(FieldMask::Mask.phone(phone.clone()).as_deref(), Some("+861******5678"));
+        assert_eq!(FieldMask::Reveal.phone(phone.clone()), phone);
+        assert_eq!(FieldMask::Mask.email(None), None);
+    }
+}
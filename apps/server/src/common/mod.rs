@@ -1,5 +1,16 @@
 pub mod api;
+pub mod cache;
 pub mod error;
+pub mod export;
 pub mod files;
+pub mod i18n;
+pub mod ids;
+pub mod mask;
 pub mod pagination;
+pub mod pdf;
 pub mod query;
+pub mod token;
+pub mod tx;
+pub mod user_agent;
+pub mod validation;
+pub mod xlsx;
@@ -1,3 +1,7 @@
+use crate::common::error::ServiceError;
+
+use sqlx::{QueryBuilder, Sqlite};
+
 #[derive(Debug, Clone, Copy, Default)]
 pub struct PaginationQuery {
     pub current: Option<i64>,
@@ -18,4 +22,152 @@ impl Pagination {
 
         Self { offset: offset as u32, limit: size as u32 }
     }
+
+    /// 1-based page number this window starts on.
+    pub fn current(&self) -> i64 {
+        i64::from(self.offset / self.limit.max(1)) + 1
+    }
+
+    pub fn page_size(&self) -> i64 {
+        i64::from(self.limit)
+    }
+}
+
+/// Keyset cursor over a descending `id` column.
+///
+/// `after` is the last id the client has seen; the next page holds the rows with smaller ids,
+/// so deep pages cost the same as the first one instead of scanning past an OFFSET.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub after: i64,
+}
+
+impl Cursor {
+    /// ORDER BY clause that matches the cursor condition.
+    pub const ORDER_BY: &'static str = "id DESC";
+
+    pub fn from_query(after: Option<i64>) -> Result<Option<Self>, ServiceError> {
+        match after {
+            None => Ok(None),
+            Some(after) if after > 0 => Ok(Some(Self { after })),
+            Some(after) => {
+                Err(ServiceError::InvalidOperation(format!("Invalid cursor value: {}", after)))
+            }
+        }
+    }
+
+    /// Append the keyset condition to a `WHERE 1=1 ...` query.
+    pub fn push_condition(self, query_builder: &mut QueryBuilder<Sqlite>) {
+        query_builder.push(" AND id < ").push_bind(self.after);
+    }
+}
+
+/// Sort direction accepted by list endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "asc" | "ascend" => Some(Self::Asc),
+            "desc" | "descend" => Some(Self::Desc),
+            _ => None,
+        }
+    }
+
+    fn as_sql(self) -> &'static str {
+        match self {
+            Self::Asc => "ASC",
+            Self::Desc => "DESC",
+        }
+    }
+}
+
+/// A list sort resolved against a whitelist of sortable columns.
+///
+/// Only `&'static str` column names from the whitelist ever reach the ORDER BY clause,
+/// so request input cannot inject SQL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sort {
+    pub column: &'static str,
+    pub order: SortOrder,
+}
+
+impl Sort {
+    /// Resolve `sortBy` / `sortOrder` against `(field, column)` pairs.
+    ///
+    /// Returns `None` when `sort_by` is missing so the caller keeps its default ordering.
+    /// `sort_order` defaults to descending and accepts `asc`/`desc` or `ascend`/`descend`.
+    pub fn resolve(
+        sort_by: Option<&str>,
+        sort_order: Option<&str>,
+        columns: &[(&str, &'static str)],
+    ) -> Result<Option<Self>, ServiceError> {
+        let order = match sort_order.map(str::trim) {
+            None | Some("") => SortOrder::Desc,
+            Some(raw) => SortOrder::parse(raw).ok_or_else(|| {
+                ServiceError::InvalidOperation(format!("Invalid sort order: {}", raw))
+            })?,
+        };
+
+        let sort_by = match sort_by.map(str::trim) {
+            None | Some("") => return Ok(None),
+            Some(sort_by) => sort_by,
+        };
+
+        columns
+            .iter()
+            .find(|(field, _)| *field == sort_by)
+            .map(|(_, column)| Some(Self { column, order }))
+            .ok_or_else(|| {
+                ServiceError::InvalidOperation(format!("Invalid sort field: {}", sort_by))
+            })
+    }
+
+    /// Render the ORDER BY clause, using `id` as a stable tie-breaker.
+    pub fn to_order_by(self) -> String {
+        let order = self.order.as_sql();
+        format!("{} {}, id {}", self.column, order, order)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cursor, Sort, SortOrder};
+
+    const COLUMNS: &[(&str, &str)] = &[("createdAt", "created_at"), ("username", "username")];
+
+    #[test]
+    fn sort_resolves_whitelisted_fields() {
+        let sort = Sort::resolve(Some("createdAt"), Some("ascend"), COLUMNS).unwrap();
+        assert_eq!(sort, Some(Sort { column: "created_at", order: SortOrder::Asc }));
+        assert_eq!(sort.unwrap().to_order_by(), "created_at ASC, id ASC");
+
+        let sort = Sort::resolve(Some("username"), None, COLUMNS).unwrap();
+        assert_eq!(sort.unwrap().to_order_by(), "username DESC, id DESC");
+    }
+
+    #[test]
+    fn sort_keeps_default_when_field_missing() {
+        assert_eq!(Sort::resolve(None, Some("asc"), COLUMNS).unwrap(), None);
+        assert_eq!(Sort::resolve(Some(" "), None, COLUMNS).unwrap(), None);
+    }
+
+    #[test]
+    fn sort_rejects_unknown_fields_and_orders() {
+        assert!(Sort::resolve(Some("password_hash"), None, COLUMNS).is_err());
+        assert!(Sort::resolve(Some("id; DROP TABLE users"), None, COLUMNS).is_err());
+        assert!(Sort::resolve(Some("username"), Some("sideways"), COLUMNS).is_err());
+    }
+
+    #[test]
+    fn cursor_accepts_positive_ids_only() {
+        assert_eq!(Cursor::from_query(None).unwrap(), None);
+        assert_eq!(Cursor::from_query(Some(42)).unwrap(), Some(Cursor { after: 42 }));
+        assert!(Cursor::from_query(Some(0)).is_err());
+        assert!(Cursor::from_query(Some(-1)).is_err());
+    }
 }
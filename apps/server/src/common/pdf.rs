@@ -0,0 +1,366 @@
+//! Minimal PDF writer for plain-text documents.
+//!
+//! Lines are set on A4 pages in GNU Unifont, a bitmap font covering the Basic Multilingual
+//! Plane, so Chinese names print as written. The glyphs a document uses are embedded as
+//! Type 3 fonts that fill each glyph's pixel runs. Halfwidth glyphs take one column and
+//! fullwidth (CJK) glyphs two, so columns padded with spaces stay aligned while the padded
+//! values are halfwidth. Control characters print as spaces and characters Unifont lacks
+//! as `?`.
+
+use std::collections::{BTreeMap, HashMap};
+
+const PAGE_WIDTH: u32 = 595;
+const PAGE_HEIGHT: u32 = 842;
+const MARGIN: u32 = 50;
+const FONT_SIZE: u32 = 9;
+const TITLE_SIZE: u32 = 14;
+const LEADING: u32 = 12;
+/// Halfwidth glyphs are half an em wide: (595 - 2 * 50) / (0.5 * 9).
+pub const LINE_WIDTH_COLUMNS: usize = 110;
+/// Codes a Type 3 font can hold; printable ASCII keeps its own code in the first font.
+const CODES_PER_FONT: usize = 256;
+const FIRST_FONT_SPARE: usize = 128;
+
+/// Renders `title` and `lines` as a PDF, wrapping lines wider than [`LINE_WIDTH_COLUMNS`].
+pub fn write_text_document(title: &str, lines: &[String]) -> Vec<u8> {
+    let lines: Vec<String> = lines.iter().flat_map(|line| wrap(line)).collect();
+    let fonts = FontSet::new(std::iter::once(title).chain(lines.iter().map(String::as_str)));
+    let per_page = ((PAGE_HEIGHT - 2 * MARGIN) / LEADING) as usize;
+    // The title takes two lines of the first page.
+    let mut pages: Vec<&[String]> = Vec::new();
+    let first = lines.len().min(per_page - 2);
+    pages.push(&lines[..first]);
+    pages.extend(lines[first..].chunks(per_page));
+
+    // 1 catalog, 2 page tree, 3 info, then the fonts, then page + content pairs.
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        Vec::new(),
+        [b"<< /Title ".as_slice(), &text_string(title), b" /Producer (rustzen-admin) >>"].concat(),
+    ];
+    let font_ids = fonts.write(&mut objects);
+    let resources = font_ids
+        .iter()
+        .enumerate()
+        .map(|(index, id)| format!("/U{} {} 0 R", index, id))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut page_ids = Vec::with_capacity(pages.len());
+    for (index, page_lines) in pages.iter().enumerate() {
+        let mut stream =
+            format!("BT\n{} {} Td\n{} TL\n", MARGIN, PAGE_HEIGHT - MARGIN - FONT_SIZE, LEADING)
+                .into_bytes();
+        if index == 0 {
+            stream.extend(fonts.show(title, TITLE_SIZE));
+            stream.extend(b"T* T*\n");
+        }
+        for line in page_lines.iter() {
+            stream.extend(fonts.show(line, FONT_SIZE));
+            stream.extend(b"T*\n");
+        }
+        stream.extend(b"ET");
+        let page_id = objects.len() + 1;
+        page_ids.push(page_id);
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << {} >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                resources,
+                page_id + 1
+            )
+            .into_bytes(),
+        );
+        objects.push(stream_object(&stream));
+    }
+    objects[1] = format!(
+        "<< /Type /Pages /Kids [{}] /Count {} >>",
+        page_ids.iter().map(|id| format!("{} 0 R", id)).collect::<Vec<_>>().join(" "),
+        pages.len()
+    )
+    .into_bytes();
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend(format!("{} 0 obj\n", index + 1).into_bytes());
+        pdf.extend(object);
+        pdf.extend(b"\nendobj\n");
+    }
+    let xref_at = pdf.len();
+    pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+    for offset in offsets {
+        pdf.extend(format!("{:010} 00000 n \n", offset).into_bytes());
+    }
+    pdf.extend(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R /Info 3 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref_at
+        )
+        .into_bytes(),
+    );
+    pdf
+}
+
+/// The Unifont glyphs a document uses, spread over Type 3 fonts of up to 256 codes.
+struct FontSet {
+    /// Font index and code of each printed character.
+    codes: HashMap<char, (usize, u8)>,
+    /// Printed characters of each font, by code.
+    fonts: Vec<BTreeMap<u8, char>>,
+}
+
+impl FontSet {
+    fn new<'a>(texts: impl Iterator<Item = &'a str>) -> Self {
+        let mut set = FontSet { codes: HashMap::new(), fonts: vec![BTreeMap::new()] };
+        let mut spare = 0;
+        for c in texts.flat_map(str::chars).map(printed) {
+            if set.codes.contains_key(&c) {
+                continue;
+            }
+            let (font, code) = if (' '..='~').contains(&c) {
+                (0, c as u8)
+            } else if spare < FIRST_FONT_SPARE {
+                (0, (CODES_PER_FONT - FIRST_FONT_SPARE + spare) as u8)
+            } else {
+                let slot = spare - FIRST_FONT_SPARE;
+                (1 + slot / CODES_PER_FONT, (slot % CODES_PER_FONT) as u8)
+            };
+            if !(' '..='~').contains(&c) {
+                spare += 1;
+            }
+            if set.fonts.len() <= font {
+                set.fonts.push(BTreeMap::new());
+            }
+            set.fonts[font].insert(code, c);
+            set.codes.insert(c, (font, code));
+        }
+        set
+    }
+
+    /// Text operators showing `text` at `size`, switching fonts where its characters do.
+    fn show(&self, text: &str, size: u32) -> Vec<u8> {
+        let mut ops = Vec::new();
+        let mut run: Option<(usize, Vec<u8>)> = None;
+        for c in text.chars().map(printed) {
+            let (font, code) = self.codes[&c];
+            match &mut run {
+                Some((current, codes)) if *current == font => codes.push(code),
+                _ => {
+                    if let Some((font, codes)) = run.replace((font, vec![code])) {
+                        ops.extend(show_run(font, &codes, size));
+                    }
+                }
+            }
+        }
+        if let Some((font, codes)) = run {
+            ops.extend(show_run(font, &codes, size));
+        }
+        ops
+    }
+
+    /// Appends every font with its glyph programs and `ToUnicode` map; returns the font ids.
+    fn write(&self, objects: &mut Vec<Vec<u8>>) -> Vec<usize> {
+        let mut ids = Vec::with_capacity(self.fonts.len());
+        for chars in &self.fonts {
+            let font_id = objects.len() + 1;
+            ids.push(font_id);
+            objects.push(Vec::new());
+            objects.push(stream_object(&to_unicode(chars)));
+            let mut procs = Vec::with_capacity(chars.len());
+            for c in chars.values() {
+                objects.push(stream_object(&glyph_program(*c)));
+                procs.push(format!("/{} {} 0 R", glyph_name(*c), objects.len()));
+            }
+            let (first, last) = match (chars.keys().next(), chars.keys().next_back()) {
+                (Some(first), Some(last)) => (*first, *last),
+                _ => (b' ', b' '),
+            };
+            let widths: Vec<String> = (first..=last)
+                .map(|code| chars.get(&code).map_or(0, |c| glyph_width(*c)).to_string())
+                .collect();
+            let differences: Vec<String> =
+                chars.iter().map(|(code, c)| format!("{} /{}", code, glyph_name(*c))).collect();
+            objects[font_id - 1] = format!(
+                "<< /Type /Font /Subtype /Type3 /FontBBox [0 -2 16 14] /FontMatrix [0.0625 0 0 0.0625 0 0] /CharProcs << {} >> /Encoding << /Type /Encoding /Differences [{}] >> /FirstChar {} /LastChar {} /Widths [{}] /Resources << >> /ToUnicode {} 0 R >>",
+                procs.join(" "),
+                differences.join(" "),
+                first,
+                last,
+                widths.join(" "),
+                font_id + 1
+            )
+            .into_bytes();
+        }
+        ids
+    }
+}
+
+/// The character actually printed for `c`.
+fn printed(c: char) -> char {
+    match c {
+        c if c.is_control() => ' ',
+        c if unifont::get_glyph(c).is_some() => c,
+        _ => '?',
+    }
+}
+
+/// Columns `c` takes: two for fullwidth glyphs, one otherwise.
+fn columns(c: char) -> usize {
+    match unifont::get_glyph(printed(c)) {
+        Some(glyph) if glyph.is_fullwidth() => 2,
+        _ => 1,
+    }
+}
+
+fn glyph_name(c: char) -> String {
+    format!("u{:04X}", c as u32)
+}
+
+fn glyph_width(c: char) -> usize {
+    unifont::get_glyph(c).map_or(8, |glyph| glyph.get_width())
+}
+
+/// Type 3 glyph program filling the glyph's pixel runs, in a 16-unit em with the baseline
+/// two pixels above the bottom row.
+fn glyph_program(c: char) -> Vec<u8> {
+    let width = glyph_width(c);
+    let mut program = format!("{} 0 0 -2 {} 14 d1\n", width, width);
+    let mut filled = false;
+    if let Some(glyph) = unifont::get_glyph(c) {
+        for y in 0..16 {
+            let mut x = 0;
+            while x < width {
+                if !glyph.get_pixel(x, y) {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                while x < width && glyph.get_pixel(x, y) {
+                    x += 1;
+                }
+                program.push_str(&format!("{} {} {} 1 re\n", start, 13 - y as i32, x - start));
+                filled = true;
+            }
+        }
+    }
+    if filled {
+        program.push('f');
+    }
+    program.into_bytes()
+}
+
+/// `ToUnicode` CMap so viewers can search and copy the text.
+fn to_unicode(chars: &BTreeMap<u8, char>) -> Vec<u8> {
+    let mut cmap = String::from(
+        "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n/CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n/CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n1 begincodespacerange\n<00> <FF>\nendcodespacerange\n",
+    );
+    let entries: Vec<(&u8, &char)> = chars.iter().collect();
+    // bfchar sections hold at most 100 entries each.
+    for chunk in entries.chunks(100) {
+        cmap.push_str(&format!("{} beginbfchar\n", chunk.len()));
+        for (code, c) in chunk {
+            cmap.push_str(&format!("<{:02X}> <{:04X}>\n", code, **c as u32));
+        }
+        cmap.push_str("endbfchar\n");
+    }
+    cmap.push_str("endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend");
+    cmap.into_bytes()
+}
+
+fn show_run(font: usize, codes: &[u8], size: u32) -> Vec<u8> {
+    [format!("/U{} {} Tf ", font, size).as_bytes(), &literal(codes), b" Tj\n"].concat()
+}
+
+fn stream_object(stream: &[u8]) -> Vec<u8> {
+    [format!("<< /Length {} >>\nstream\n", stream.len()).as_bytes(), stream, b"\nendstream"]
+        .concat()
+}
+
+fn wrap(line: &str) -> Vec<String> {
+    let mut wrapped = vec![String::new()];
+    let mut width = 0;
+    for c in line.chars() {
+        let columns = columns(c);
+        if width + columns > LINE_WIDTH_COLUMNS {
+            wrapped.push(String::new());
+            width = 0;
+        }
+        wrapped.last_mut().unwrap().push(c);
+        width += columns;
+    }
+    wrapped
+}
+
+/// PDF literal string of font codes; printable ASCII stays readable.
+fn literal(codes: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![b'('];
+    for &code in codes {
+        match code {
+            b'(' | b')' | b'\\' => bytes.extend([b'\\', code]),
+            b' '..=b'~' => bytes.push(code),
+            _ => bytes.extend(format!("\\{:03o}", code).into_bytes()),
+        }
+    }
+    bytes.push(b')');
+    bytes
+}
+
+/// PDF text string: a literal for ASCII, UTF-16BE hex otherwise.
+fn text_string(text: &str) -> Vec<u8> {
+    if text.chars().all(|c| (' '..='~').contains(&c)) {
+        return literal(text.as_bytes());
+    }
+    let hex: String = text.encode_utf16().map(|unit| format!("{:04X}", unit)).collect();
+    format!("<FEFF{}>", hex).into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LINE_WIDTH_COLUMNS, write_text_document};
+
+    #[test]
+    fn xref_offsets_point_at_their_objects_across_pages() {
+        let mut lines: Vec<String> = (0..130).map(|i| format!("row {} (total) \\ ok", i)).collect();
+        lines.push("x".repeat(LINE_WIDTH_COLUMNS + 5));
+        lines.push("Gr\u{fc}\u{df}e \u{4f60}\u{597d}".to_string());
+        let pdf = write_text_document("Weekly report", &lines);
+        let text = String::from_utf8(pdf.clone()).unwrap();
+
+        assert!(pdf.starts_with(b"%PDF-1.4\n") && text.ends_with("%%EOF\n"));
+        assert!(text.contains("/Count 3 "), "131 lines plus a wrapped one fill three pages");
+        assert!(text.contains(r"/U0 9 Tf (row 7 \(total\) \\ ok) Tj"));
+        assert!(text.contains(r"(Gr\200\201e \202\203) Tj"));
+
+        let startxref: usize =
+            text.rsplit("startxref\n").next().unwrap().lines().next().unwrap().parse().unwrap();
+        let size: usize =
+            text.split("/Size ").nth(1).unwrap().split(' ').next().unwrap().parse().unwrap();
+        let entries: Vec<&str> =
+            text[startxref..].lines().skip(3).take_while(|l| l.len() == 19).collect();
+        assert_eq!(entries.len(), size - 1);
+        for (index, entry) in entries.iter().enumerate() {
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(format!("{} 0 obj", index + 1).as_bytes()));
+        }
+    }
+
+    #[test]
+    fn cjk_text_is_embedded_as_fullwidth_glyphs() {
+        // 128 ideographs fit beside ASCII in the first font and 256 in each one after it.
+        let names: String = (0x4e00..0x4e00 + 600).filter_map(char::from_u32).collect();
+        let lines = vec!["\u{7ba1}\u{7406}\u{5458}".to_string(), names];
+        let pdf = String::from_utf8(write_text_document("\u{5468}\u{62a5}", &lines)).unwrap();
+
+        assert!(pdf.contains("/Title <FEFF546862A5>"));
+        assert!(pdf.contains("/u7BA1 ") && pdf.contains("<82> <7BA1>"));
+        assert!(pdf.contains("16 0 0 -2 16 14 d1\n"));
+        assert!(pdf.contains("/U1 9 Tf ") && pdf.contains("/U2 9 Tf "));
+        assert!(!pdf.contains("/U3 "));
+        // Fullwidth glyphs take two columns, so a line of them wraps after 55.
+        let lines = 1 + 600_usize.div_ceil(LINE_WIDTH_COLUMNS / 2);
+        assert_eq!(pdf.matches("T*\n").count(), 1 + lines);
+    }
+}
@@ -1,6 +1,7 @@
-use crate::common::error::ServiceError;
+use crate::common::{api::OptionsQuery, error::ServiceError};
 
 use sqlx::{QueryBuilder, Sqlite, SqlitePool, sqlite::SqliteRow};
+use std::str::FromStr;
 
 /// Apply a case-insensitive LIKE filter when the value is present and non-empty.
 pub fn push_ilike(query_builder: &mut QueryBuilder<Sqlite>, column: &str, value: Option<&str>) {
@@ -17,6 +18,26 @@ pub fn push_ilike(query_builder: &mut QueryBuilder<Sqlite>, column: &str, value:
     }
 }
 
+/// Apply a case-insensitive LIKE filter matching any of `columns`.
+pub fn push_ilike_any(
+    query_builder: &mut QueryBuilder<Sqlite>,
+    columns: &[&str],
+    value: Option<&str>,
+) {
+    let Some(value) = value.map(str::trim).filter(|value| !value.is_empty()) else {
+        return;
+    };
+    let pattern = format!("%{}%", value.to_lowercase());
+    query_builder.push(" AND (");
+    for (index, column) in columns.iter().enumerate() {
+        if index > 0 {
+            query_builder.push(" OR ");
+        }
+        query_builder.push("LOWER(").push(column).push(") LIKE ").push_bind(pattern.clone());
+    }
+    query_builder.push(")");
+}
+
 /// Apply an equality filter when the value is present.
 pub fn push_eq<T>(query_builder: &mut QueryBuilder<Sqlite>, column: &str, value: Option<T>)
 where
@@ -76,7 +97,7 @@ pub async fn fetch_with_filters<T, F>(
     pool: &SqlitePool,
     base_sql: &'static str,
     apply_filters: F,
-    order_by: Option<&'static str>,
+    order_by: Option<&str>,
     limit: Option<i64>,
     offset: Option<i64>,
 ) -> Result<Vec<T>, ServiceError>
@@ -107,3 +128,217 @@ where
 
     Ok(rows)
 }
+
+/// Upper bound for rows returned by any options endpoint.
+pub const OPTIONS_MAX_LIMIT: i64 = 1000;
+
+/// Clamp an options `limit` into `1..=OPTIONS_MAX_LIMIT`, defaulting to the maximum.
+pub fn options_limit(limit: Option<i64>) -> i64 {
+    limit.unwrap_or(OPTIONS_MAX_LIMIT).clamp(1, OPTIONS_MAX_LIMIT)
+}
+
+/// Options request resolved for a repo: id lists parsed into the option value type and the
+/// limit clamped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionsFilter<T> {
+    pub q: Option<String>,
+    pub status: Option<i16>,
+    pub exclude_ids: Vec<T>,
+    pub include_ids: Vec<T>,
+    pub limit: i64,
+}
+
+impl<T: FromStr> OptionsFilter<T> {
+    pub fn from_query(query: OptionsQuery) -> Result<Self, ServiceError> {
+        Ok(Self {
+            q: query.q,
+            status: parse_optional_i16_filter(query.status.as_deref(), "status", Some(1))?,
+            exclude_ids: parse_id_list(query.exclude_ids.as_deref(), "excludeIds")?,
+            include_ids: parse_id_list(query.include_ids.as_deref(), "includeIds")?,
+            limit: options_limit(query.limit),
+        })
+    }
+}
+
+fn parse_id_list<T: FromStr>(
+    value: Option<&str>,
+    field_name: &str,
+) -> Result<Vec<T>, ServiceError> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|raw| !raw.is_empty())
+        .map(|raw| {
+            raw.parse::<T>().map_err(|_| {
+                ServiceError::InvalidOperation(format!("Invalid {} value: {}", field_name, raw))
+            })
+        })
+        .collect()
+}
+
+/// Shape of one options query. `base_sql` ends inside its `WHERE` so the filters can follow
+/// with `AND`; `scope` in [`fetch_options`] may push more of it first.
+pub struct OptionsSql {
+    pub base_sql: &'static str,
+    /// Column holding the option value that `excludeIds` and `includeIds` match.
+    pub value_column: &'static str,
+    pub status_column: &'static str,
+    pub search_columns: &'static [&'static str],
+    pub order_by: &'static str,
+}
+
+/// Fetch dropdown options: filters and the clamped limit are bound, never formatted into SQL.
+///
+/// `scope` holds conditions every row must meet, included ones too. Rows in `include_ids`
+/// come first and skip the search and status filters; rows in `exclude_ids` never appear.
+pub async fn fetch_options<T, V, F>(
+    pool: &SqlitePool,
+    sql: &OptionsSql,
+    filter: &OptionsFilter<V>,
+    scope: F,
+) -> Result<Vec<T>, ServiceError>
+where
+    F: FnOnce(&mut QueryBuilder<Sqlite>),
+    V: for<'q> sqlx::Encode<'q, Sqlite> + sqlx::Type<Sqlite> + Clone + Send + 'static,
+    T: for<'r> sqlx::FromRow<'r, SqliteRow> + Send + Unpin,
+{
+    let mut query_builder = options_query(sql, filter, scope);
+    query_builder
+        .build_query_as::<T>()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| map_db_error("fetching options", e))
+}
+
+fn options_query<V, F>(
+    sql: &OptionsSql,
+    filter: &OptionsFilter<V>,
+    scope: F,
+) -> QueryBuilder<Sqlite>
+where
+    F: FnOnce(&mut QueryBuilder<Sqlite>),
+    V: for<'q> sqlx::Encode<'q, Sqlite> + sqlx::Type<Sqlite> + Clone + Send + 'static,
+{
+    let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new(sql.base_sql);
+    scope(&mut query_builder);
+
+    query_builder.push(" AND ((1=1");
+    push_eq(&mut query_builder, sql.status_column, filter.status);
+    push_ilike_any(&mut query_builder, sql.search_columns, filter.q.as_deref());
+    query_builder.push(")");
+    if !filter.include_ids.is_empty() {
+        query_builder.push(" OR ");
+        push_in(&mut query_builder, sql.value_column, &filter.include_ids);
+    }
+    query_builder.push(")");
+    if !filter.exclude_ids.is_empty() {
+        query_builder.push(" AND NOT ");
+        push_in(&mut query_builder, sql.value_column, &filter.exclude_ids);
+    }
+
+    query_builder.push(" ORDER BY ");
+    if !filter.include_ids.is_empty() {
+        query_builder.push("CASE WHEN ");
+        push_in(&mut query_builder, sql.value_column, &filter.include_ids);
+        query_builder.push(" THEN 0 ELSE 1 END, ");
+    }
+    query_builder.push(sql.order_by).push(" LIMIT ").push_bind(filter.limit);
+    query_builder
+}
+
+fn push_in<V>(query_builder: &mut QueryBuilder<Sqlite>, column: &str, values: &[V])
+where
+    V: for<'q> sqlx::Encode<'q, Sqlite> + sqlx::Type<Sqlite> + Clone + Send + 'static,
+{
+    query_builder.push(column).push(" IN (");
+    let mut separated = query_builder.separated(", ");
+    for value in values {
+        separated.push_bind(value.clone());
+    }
+    separated.push_unseparated(")");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        OPTIONS_MAX_LIMIT, OptionsFilter, OptionsSql, options_limit, options_query, push_ilike_any,
+    };
+    use crate::common::{api::OptionsQuery, error::ServiceError};
+    use sqlx::{QueryBuilder, Sqlite};
+
+    #[test]
+    fn ilike_any_binds_one_pattern_per_column() {
+        let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new("SELECT 1 WHERE 1=1");
+        push_ilike_any(&mut query_builder, &["username", "real_name"], Some(" Ali'ce "));
+        assert_eq!(
+            query_builder.sql(),
+            "SELECT 1 WHERE 1=1 AND (LOWER(username) LIKE ? OR LOWER(real_name) LIKE ?)"
+        );
+
+        let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new("SELECT 1 WHERE 1=1");
+        push_ilike_any(&mut query_builder, &["username"], Some("  "));
+        assert_eq!(query_builder.sql(), "SELECT 1 WHERE 1=1");
+    }
+
+    #[test]
+    fn options_limit_is_clamped() {
+        assert_eq!(options_limit(None), OPTIONS_MAX_LIMIT);
+        assert_eq!(options_limit(Some(20)), 20);
+        assert_eq!(options_limit(Some(0)), 1);
+        assert_eq!(options_limit(Some(-5)), 1);
+        assert_eq!(options_limit(Some(1_000_000)), OPTIONS_MAX_LIMIT);
+    }
+
+    #[test]
+    fn options_filter_parses_status_and_id_lists() {
+        let filter = OptionsFilter::<i64>::from_query(OptionsQuery {
+            exclude_ids: Some("3, 4,".to_string()),
+            ..OptionsQuery::default()
+        })
+        .expect("filter");
+        assert_eq!(
+            (filter.status, filter.exclude_ids, filter.include_ids),
+            (Some(1), vec![3, 4], vec![])
+        );
+
+        let all = OptionsQuery { status: Some("all".to_string()), ..OptionsQuery::default() };
+        assert_eq!(OptionsFilter::<i64>::from_query(all).expect("filter").status, None);
+        let bad = OptionsQuery { include_ids: Some("1,x".to_string()), ..OptionsQuery::default() };
+        assert!(matches!(
+            OptionsFilter::<i64>::from_query(bad),
+            Err(ServiceError::InvalidOperation(_))
+        ));
+    }
+
+    #[test]
+    fn included_options_skip_the_filters_and_sort_first() {
+        let sql = OptionsSql {
+            base_sql: "SELECT id, name FROM roles WHERE deleted_at IS NULL",
+            value_column: "id",
+            status_column: "status",
+            search_columns: &["name"],
+            order_by: "name ASC",
+        };
+        let filter = OptionsFilter {
+            q: Some("adm".to_string()),
+            status: Some(1),
+            exclude_ids: vec![7],
+            include_ids: vec![2, 5],
+            limit: 20,
+        };
+        assert_eq!(
+            options_query(&sql, &filter, |_| {}).sql(),
+            "SELECT id, name FROM roles WHERE deleted_at IS NULL \
+             AND ((1=1 AND status = ? AND (LOWER(name) LIKE ?)) OR id IN (?, ?)) \
+             AND NOT id IN (?) ORDER BY CASE WHEN id IN (?, ?) THEN 0 ELSE 1 END, name ASC LIMIT ?"
+        );
+
+        let filter = OptionsFilter::<i64> { include_ids: vec![], exclude_ids: vec![], ..filter };
+        assert_eq!(
+            options_query(&sql, &filter, |_| {}).sql(),
+            "SELECT id, name FROM roles WHERE deleted_at IS NULL \
+             AND ((1=1 AND status = ? AND (LOWER(name) LIKE ?))) ORDER BY name ASC LIMIT ?"
+        );
+    }
+}
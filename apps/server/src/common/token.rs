@@ -0,0 +1,29 @@
+//! Single-use tokens mailed to users, such as email verification links.
+//!
+//! Only the SHA-256 of a token is stored, so a leaked table cannot be replayed.
+
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// 64 random hex characters.
+pub fn generate() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// Hex SHA-256 of `token`, the form it is stored and looked up in.
+pub fn hash(token: &str) -> String {
+    Sha256::digest(token.as_bytes()).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate, hash};
+
+    #[test]
+    fn tokens_are_random_and_stored_as_sha256_hex() {
+        let token = generate();
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, generate());
+        assert_eq!(hash("abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    }
+}
@@ -0,0 +1,48 @@
+//! Transaction helpers for service flows that span several repository calls.
+//!
+//! Services open a transaction with [`begin`], pass `&mut Tx` into repository
+//! `*_in_tx` functions, and finish with [`commit`]. Dropping the transaction on an
+//! early `?` return rolls everything back.
+
+use crate::common::error::ServiceError;
+
+use sqlx::{Sqlite, SqlitePool, Transaction};
+
+pub type Tx<'c> = Transaction<'c, Sqlite>;
+
+pub async fn begin(pool: &SqlitePool) -> Result<Tx<'static>, ServiceError> {
+    pool.begin().await.map_err(|e| {
+        tracing::error!("Database error starting transaction: {:?}", e);
+        ServiceError::DatabaseQueryFailed
+    })
+}
+
+pub async fn commit(tx: Tx<'_>) -> Result<(), ServiceError> {
+    tx.commit().await.map_err(|e| {
+        tracing::error!("Database error committing transaction: {:?}", e);
+        ServiceError::DatabaseQueryFailed
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{begin, commit};
+
+    #[tokio::test]
+    async fn dropped_transaction_rolls_back() {
+        let pool = crate::infra::db::memory_pool().await;
+        sqlx::query("CREATE TABLE items (id INTEGER PRIMARY KEY)").execute(&pool).await.unwrap();
+
+        let mut tx = begin(&pool).await.unwrap();
+        sqlx::query("INSERT INTO items (id) VALUES (1)").execute(&mut *tx).await.unwrap();
+        drop(tx);
+
+        let mut tx = begin(&pool).await.unwrap();
+        sqlx::query("INSERT INTO items (id) VALUES (2)").execute(&mut *tx).await.unwrap();
+        commit(tx).await.unwrap();
+
+        let ids: Vec<i64> =
+            sqlx::query_scalar("SELECT id FROM items").fetch_all(&pool).await.unwrap();
+        assert_eq!(ids, vec![2]);
+    }
+}
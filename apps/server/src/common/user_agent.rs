@@ -0,0 +1,106 @@
+//! Readable device names for stored user agents.
+//!
+//! Raw user agents are kept as sent; lists show `Chrome on Windows 10` style names and a
+//! coarse device type derived from them on read.
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use woothee::parser::Parser;
+
+static PARSER: Lazy<Parser> = Lazy::new(Parser::new);
+
+/// Value woothee reports for fields it cannot tell.
+const UNKNOWN: &str = "UNKNOWN";
+
+/// What kind of client sent a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DeviceType {
+    Desktop,
+    Mobile,
+    /// Scripts, SDKs, crawlers and anything else that is not a known browser.
+    ApiClient,
+}
+
+/// A user agent as shown to people.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceInfo {
+    /// `Chrome on Windows 10`, or the client's product name such as `curl`.
+    pub name: String,
+    pub device_type: DeviceType,
+}
+
+impl DeviceInfo {
+    pub fn parse(user_agent: &str) -> Self {
+        let user_agent = user_agent.trim();
+        let parsed = PARSER.parse(user_agent).filter(|r| r.name != UNKNOWN);
+        let Some(result) = parsed else {
+            return Self { name: product_name(user_agent), device_type: DeviceType::ApiClient };
+        };
+        let device_type = match result.category {
+            "pc" => DeviceType::Desktop,
+            "smartphone" | "mobilephone" => DeviceType::Mobile,
+            _ => {
+                // woothee lumps libraries together as `HTTP Library`; the product token is
+                // more useful, except for bots that pose as `Mozilla/5.0 (compatible; ...)`.
+                let product = product_name(user_agent);
+                let name = if product == "Mozilla" { result.name.to_string() } else { product };
+                return Self { name, device_type: DeviceType::ApiClient };
+            }
+        };
+        let name = match result.os {
+            "" | UNKNOWN => result.name.to_string(),
+            os => format!("{} on {}", result.name, os),
+        };
+        Self { name, device_type }
+    }
+}
+
+/// First product token of an unrecognised user agent, e.g. `okhttp` for `okhttp/4.12.0`.
+fn product_name(user_agent: &str) -> String {
+    let product = user_agent.split(['/', ' ']).next().unwrap_or_default();
+    if product.is_empty() { "Unknown client".to_string() } else { product.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeviceInfo, DeviceType};
+
+    fn parse(user_agent: &str) -> (String, DeviceType) {
+        let info = DeviceInfo::parse(user_agent);
+        (info.name, info.device_type)
+    }
+
+    #[test]
+    fn browsers_get_readable_names_and_device_types() {
+        let windows_chrome = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+            (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36";
+        let iphone_safari = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_4 like Mac OS X) \
+            AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1";
+        let mac_firefox =
+            "Mozilla/5.0 (Macintosh; Intel Mac OS X 14.4; rv:125.0) Gecko/20100101 Firefox/125.0";
+
+        assert_eq!(
+            parse(windows_chrome),
+            ("Chrome on Windows 10".to_string(), DeviceType::Desktop)
+        );
+        assert_eq!(parse(iphone_safari), ("Safari on iPhone".to_string(), DeviceType::Mobile));
+        assert_eq!(parse(mac_firefox), ("Firefox on Mac OSX".to_string(), DeviceType::Desktop));
+    }
+
+    #[test]
+    fn other_clients_are_api_clients_named_by_product() {
+        assert_eq!(parse("curl/8.5.0"), ("curl".to_string(), DeviceType::ApiClient));
+        assert_eq!(parse("okhttp/4.12.0"), ("okhttp".to_string(), DeviceType::ApiClient));
+        assert_eq!(
+            parse("python-requests/2.31.0"),
+            ("python-requests".to_string(), DeviceType::ApiClient)
+        );
+        assert_eq!(
+            parse("Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)"),
+            ("Googlebot".to_string(), DeviceType::ApiClient)
+        );
+        assert_eq!(parse(""), ("Unknown client".to_string(), DeviceType::ApiClient));
+    }
+}
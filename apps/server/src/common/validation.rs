@@ -0,0 +1,105 @@
+//! Field-level validation of request payloads.
+//!
+//! Services collect every problem in a payload into [`FieldErrors`] and fail once, so the
+//! response (`code` 10015) lists each offending field in `data` instead of stopping at
+//! the first one.
+
+use crate::common::error::ServiceError;
+
+use chrono_tz::Tz;
+use rustzen_core::sms::normalize_phone;
+use serde::Serialize;
+
+/// One invalid field, named as the client sent it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// A unique field whose value a live record already holds, with free values to use instead.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldConflict {
+    pub field: String,
+    pub value: String,
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct FieldErrors(Vec<FieldError>);
+
+impl FieldErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, field: &str, message: impl Into<String>) {
+        self.0.push(FieldError { field: field.to_string(), message: message.into() });
+    }
+
+    /// Records `value` as invalid unless it is one of `allowed`.
+    pub fn check_one_of(&mut self, field: &str, value: i16, allowed: &[i16]) {
+        if !allowed.contains(&value) {
+            let allowed: Vec<String> = allowed.iter().map(i16::to_string).collect();
+            self.push(field, format!("must be one of {}", allowed.join(", ")));
+        }
+    }
+
+    pub fn into_result(self) -> Result<(), ServiceError> {
+        if self.0.is_empty() { Ok(()) } else { Err(ServiceError::InvalidFields(self.0)) }
+    }
+}
+
+/// Loose `local@domain.tld` check shared by the profile and registration forms.
+pub fn is_email(value: &str) -> bool {
+    value.split_once('@').is_some_and(|(local, domain)| {
+        !local.is_empty() && domain.contains('.') && !domain.contains('@')
+    })
+}
+
+/// E.164 form of a phone number from a request, or an error naming `field`.
+pub fn parse_phone(field: &str, value: &str) -> Result<String, ServiceError> {
+    normalize_phone(value).ok_or_else(|| {
+        ServiceError::InvalidFields(vec![FieldError {
+            field: field.to_string(),
+            message: "must be an international number such as +8613812345678".to_string(),
+        }])
+    })
+}
+
+/// IANA timezone from a request, or an error naming `field`.
+pub fn parse_timezone(field: &str, value: &str) -> Result<Tz, ServiceError> {
+    value.trim().parse::<Tz>().map_err(|_| {
+        ServiceError::InvalidFields(vec![FieldError {
+            field: field.to_string(),
+            message: "must be an IANA timezone such as Asia/Shanghai".to_string(),
+        }])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FieldError, FieldErrors};
+    use crate::common::error::ServiceError;
+
+    #[test]
+    fn collects_every_invalid_field() {
+        let mut errors = FieldErrors::new();
+        errors.check_one_of("status", 1, &[1, 2]);
+        assert!(FieldErrors::new().into_result().is_ok());
+
+        errors.check_one_of("status", 9, &[1, 2]);
+        errors.push("menuType", "is required");
+        let Err(ServiceError::InvalidFields(fields)) = errors.into_result() else {
+            panic!("expected field errors");
+        };
+        assert_eq!(
+            fields,
+            vec![
+                FieldError { field: "status".into(), message: "must be one of 1, 2".into() },
+                FieldError { field: "menuType".into(), message: "is required".into() },
+            ]
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! Minimal XLSX writer: one worksheet per [`Sheet`], text and number cells only.
+//!
+//! Enough for reports that open in Excel, LibreOffice and Numbers. There are no styles,
+//! formulas or shared strings; text is stored inline.
+
+use std::io::{Cursor, Write};
+use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
+
+const SHEET_NAME_MAX_LEN: usize = 31;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Text(String),
+    Number(f64),
+}
+
+impl From<&str> for Cell {
+    fn from(value: &str) -> Self {
+        Cell::Text(value.to_string())
+    }
+}
+
+impl From<String> for Cell {
+    fn from(value: String) -> Self {
+        Cell::Text(value)
+    }
+}
+
+impl From<i64> for Cell {
+    fn from(value: i64) -> Self {
+        Cell::Number(value as f64)
+    }
+}
+
+impl From<f64> for Cell {
+    fn from(value: f64) -> Self {
+        Cell::Number(value)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Sheet {
+    pub name: String,
+    pub rows: Vec<Vec<Cell>>,
+}
+
+/// Packs `sheets` into an `.xlsx` file; an empty list still yields one blank sheet.
+pub fn write_workbook(sheets: &[Sheet]) -> Result<Vec<u8>, String> {
+    let blank = [Sheet { name: "Sheet1".to_string(), rows: Vec::new() }];
+    let sheets = if sheets.is_empty() { &blank[..] } else { sheets };
+
+    let mut content_types = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>"#,
+    );
+    let mut workbook = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets>"#,
+    );
+    let mut workbook_rels = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+    );
+    for (index, sheet) in sheets.iter().enumerate() {
+        let n = index + 1;
+        content_types.push_str(&format!(
+            r#"<Override PartName="/xl/worksheets/sheet{n}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#
+        ));
+        workbook.push_str(&format!(
+            r#"<sheet name="{}" sheetId="{n}" r:id="rId{n}"/>"#,
+            escape(&sheet_name(&sheet.name, n))
+        ));
+        workbook_rels.push_str(&format!(
+            r#"<Relationship Id="rId{n}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet{n}.xml"/>"#
+        ));
+    }
+    content_types.push_str("</Types>");
+    workbook.push_str("</sheets></workbook>");
+    workbook_rels.push_str("</Relationships>");
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut add = |name: String, body: &str| -> Result<(), String> {
+        zip.start_file(name, options).map_err(|e| e.to_string())?;
+        zip.write_all(body.as_bytes()).map_err(|e| e.to_string())
+    };
+    add("[Content_Types].xml".to_string(), &content_types)?;
+    add(
+        "_rels/.rels".to_string(),
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#,
+    )?;
+    add("xl/workbook.xml".to_string(), &workbook)?;
+    add("xl/_rels/workbook.xml.rels".to_string(), &workbook_rels)?;
+    for (index, sheet) in sheets.iter().enumerate() {
+        add(format!("xl/worksheets/sheet{}.xml", index + 1), &worksheet(&sheet.rows))?;
+    }
+    zip.finish().map(Cursor::into_inner).map_err(|e| e.to_string())
+}
+
+fn worksheet(rows: &[Vec<Cell>]) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+    );
+    for (row_index, row) in rows.iter().enumerate() {
+        let r = row_index + 1;
+        xml.push_str(&format!(r#"<row r="{r}">"#));
+        for (col_index, cell) in row.iter().enumerate() {
+            let reference = format!("{}{}", column_name(col_index), r);
+            match cell {
+                Cell::Number(value) if value.is_finite() => {
+                    xml.push_str(&format!(r#"<c r="{reference}"><v>{value}</v></c>"#));
+                }
+                Cell::Number(value) => xml.push_str(&inline_text(&reference, &value.to_string())),
+                Cell::Text(text) => xml.push_str(&inline_text(&reference, text)),
+            }
+        }
+        xml.push_str("</row>");
+    }
+    xml.push_str("</sheetData></worksheet>");
+    xml
+}
+
+fn inline_text(reference: &str, text: &str) -> String {
+    format!(
+        r#"<c r="{reference}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+        escape(text)
+    )
+}
+
+/// `0` -> `A`, `25` -> `Z`, `26` -> `AA`.
+fn column_name(mut index: usize) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+/// Excel rejects sheet names over 31 characters or containing `[]:*?/\`.
+fn sheet_name(name: &str, n: usize) -> String {
+    let name: String = name
+        .chars()
+        .filter(|c| !matches!(c, '[' | ']' | ':' | '*' | '?' | '/' | '\\'))
+        .take(SHEET_NAME_MAX_LEN)
+        .collect();
+    if name.trim().is_empty() { format!("Sheet{}", n) } else { name }
+}
+
+/// Escapes XML markup and drops control characters XML 1.0 cannot carry.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c < ' ' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cell, Sheet, column_name, write_workbook};
+
+    use std::io::{Cursor, Read};
+    use zip::ZipArchive;
+
+    fn read_part(bytes: &[u8], name: &str) -> String {
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).expect("zip");
+        let mut part = String::new();
+        archive.by_name(name).expect(name).read_to_string(&mut part).expect("utf-8");
+        part
+    }
+
+    #[test]
+    fn writes_one_worksheet_per_sheet_with_escaped_text() {
+        let sheets = vec![
+            Sheet {
+                name: "Overview".to_string(),
+                rows: vec![
+                    vec!["Users".into(), 42i64.into()],
+                    vec!["Error rate".into(), 0.5.into()],
+                ],
+            },
+            Sheet { name: "Top: routes/errors".to_string(), rows: vec![vec!["a < b & c".into()]] },
+        ];
+        let bytes = write_workbook(&sheets).expect("workbook");
+
+        let workbook = read_part(&bytes, "xl/workbook.xml");
+        assert!(workbook.contains(r#"<sheet name="Overview" sheetId="1" r:id="rId1"/>"#));
+        assert!(workbook.contains(r#"<sheet name="Top routeserrors" sheetId="2""#));
+        let first = read_part(&bytes, "xl/worksheets/sheet1.xml");
+        assert!(first.contains(r#"<c r="B1"><v>42</v></c>"#), "{}", first);
+        assert!(first.contains(r#"<c r="B2"><v>0.5</v></c>"#), "{}", first);
+        let second = read_part(&bytes, "xl/worksheets/sheet2.xml");
+        assert!(second.contains("a &lt; b &amp; c"), "{}", second);
+        assert_eq!(Cell::from("x"), Cell::Text("x".to_string()));
+    }
+
+    #[test]
+    fn column_names_roll_over_after_z() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(701), "ZZ");
+        assert_eq!(column_name(702), "AAA");
+    }
+}
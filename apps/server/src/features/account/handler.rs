@@ -1,30 +1,37 @@
 use super::{
     service::AccountService,
-    types::{ChangeAccountPasswordRequest, UpdateAccountProfileRequest},
+    types::{
+        BindPhoneCodeRequest, BindPhoneRequest, ChangeAccountPasswordRequest, LoginDeviceResp,
+        NotificationQuery, NotificationResp, UnbindPhoneRequest, UpdateAccountProfileRequest,
+        UpdateTimezoneRequest,
+    },
 };
 use crate::{
     common::{
-        api::{ApiResponse, AppResult},
+        api::{ApiResponse, AppResult, PageMeta},
         files::save_avatar,
+        pagination::{Pagination, PaginationQuery},
     },
     features::auth::types::UserInfoResp,
+    infra::config::AppConfig,
 };
 
 use axum::{
-    Json,
-    extract::{Multipart, State},
+    Extension, Json,
+    extract::{Multipart, Path, Query, State},
 };
 use rustzen_core::auth::CurrentUser;
 use sqlx::SqlitePool;
 
 /// Update current-account avatar.
-#[tracing::instrument(name = "update_avatar", skip(current_user, pool))]
+#[tracing::instrument(name = "update_avatar", skip(current_user, config, pool))]
 pub async fn update_avatar(
     current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
     State(pool): State<SqlitePool>,
     mut multipart: Multipart,
 ) -> AppResult<String> {
-    let avatar_url = save_avatar(&mut multipart).await?;
+    let avatar_url = save_avatar(config, &mut multipart).await?;
 
     AccountService::update_avatar(&pool, current_user.user_id, &avatar_url).await?;
 
@@ -32,24 +39,131 @@ pub async fn update_avatar(
 }
 
 /// Update current-account profile.
-#[tracing::instrument(name = "update_profile", skip(current_user, pool, request))]
+#[tracing::instrument(name = "update_profile", skip(current_user, config, pool, request))]
 pub async fn update_profile(
     current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
     State(pool): State<SqlitePool>,
     Json(request): Json<UpdateAccountProfileRequest>,
 ) -> AppResult<UserInfoResp> {
     Ok(ApiResponse::success(
-        AccountService::update_profile(&pool, current_user.user_id, request).await?,
+        AccountService::update_profile(config, &pool, current_user.user_id, request).await?,
     ))
 }
 
 /// Change current-account password.
-#[tracing::instrument(name = "change_password", skip(current_user, pool, request))]
+#[tracing::instrument(name = "change_password", skip(current_user, config, pool, request))]
 pub async fn change_password(
     current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
     State(pool): State<SqlitePool>,
     Json(request): Json<ChangeAccountPasswordRequest>,
 ) -> AppResult<()> {
-    AccountService::change_password(&pool, current_user.user_id, request).await?;
+    AccountService::change_password(config, &pool, current_user.user_id, request).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Text a one-time code to the current account's phone for confirming a critical action.
+#[tracing::instrument(name = "send_verification_code", skip(current_user, config, pool))]
+pub async fn send_verification_code(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    State(pool): State<SqlitePool>,
+) -> AppResult<()> {
+    AccountService::send_verification_code(config, &pool, current_user.user_id).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Text a code to a phone number the current account wants to bind.
+#[tracing::instrument(name = "send_bind_phone_code", skip(current_user, config, pool, request))]
+pub async fn send_bind_phone_code(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    State(pool): State<SqlitePool>,
+    Json(request): Json<BindPhoneCodeRequest>,
+) -> AppResult<()> {
+    AccountService::send_bind_phone_code(config, &pool, current_user.user_id, &request.phone)
+        .await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Bind a phone number to the current account.
+#[tracing::instrument(name = "bind_phone", skip(current_user, config, pool, request))]
+pub async fn bind_phone(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    State(pool): State<SqlitePool>,
+    Json(request): Json<BindPhoneRequest>,
+) -> AppResult<UserInfoResp> {
+    Ok(ApiResponse::success(
+        AccountService::bind_phone(config, &pool, current_user.user_id, request).await?,
+    ))
+}
+
+/// Unbind the current account's phone number.
+#[tracing::instrument(name = "unbind_phone", skip(current_user, config, pool, request))]
+pub async fn unbind_phone(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    State(pool): State<SqlitePool>,
+    Json(request): Json<UnbindPhoneRequest>,
+) -> AppResult<UserInfoResp> {
+    Ok(ApiResponse::success(
+        AccountService::unbind_phone(config, &pool, current_user.user_id, request).await?,
+    ))
+}
+
+/// Set or clear the current account's timezone preference.
+#[tracing::instrument(name = "update_timezone", skip(current_user, pool, request))]
+pub async fn update_timezone(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Json(request): Json<UpdateTimezoneRequest>,
+) -> AppResult<UserInfoResp> {
+    Ok(ApiResponse::success(
+        AccountService::update_timezone(&pool, current_user.user_id, request).await?,
+    ))
+}
+
+/// List current-account notifications, newest first.
+pub async fn list_notifications(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Query(query): Query<NotificationQuery>,
+) -> AppResult<Vec<NotificationResp>> {
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (notifications, total) =
+        AccountService::list_notifications(&pool, current_user.user_id, query).await?;
+    Ok(ApiResponse::page(notifications, total, PageMeta::new(pagination, total)))
+}
+
+/// List the devices the current account signed in from.
+pub async fn list_login_devices(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+) -> AppResult<Vec<LoginDeviceResp>> {
+    Ok(ApiResponse::success(AccountService::list_login_devices(&pool, current_user.user_id).await?))
+}
+
+/// Mark one current-account notification read.
+pub async fn read_notification(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+) -> AppResult<()> {
+    AccountService::read_notification(&pool, current_user.user_id, id).await?;
     Ok(ApiResponse::success(()))
 }
+
+/// Mark every current-account notification read; returns how many were unread.
+pub async fn read_all_notifications(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+) -> AppResult<u64> {
+    Ok(ApiResponse::success(
+        AccountService::read_all_notifications(&pool, current_user.user_id).await?,
+    ))
+}
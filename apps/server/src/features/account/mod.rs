@@ -5,15 +5,36 @@ pub mod types;
 
 use axum::{
     Router,
-    routing::{post, put},
+    extract::DefaultBodyLimit,
+    routing::{get, post, put},
 };
+use rustzen_core::permission::RouterExt;
 use sqlx::SqlitePool;
 
-use handler::{change_password, update_avatar, update_profile};
+use handler::{
+    bind_phone, change_password, list_login_devices, list_notifications, read_all_notifications,
+    read_notification, send_bind_phone_code, send_verification_code, unbind_phone, update_avatar,
+    update_profile, update_timezone,
+};
+
+use crate::{features::oauth::identity_routes, infra::config::AppConfig};
 
-pub fn account_routes() -> Router<SqlitePool> {
+pub fn account_routes(config: &AppConfig) -> Router<SqlitePool> {
     Router::new()
-        .route("/avatar", post(update_avatar))
+        .route(
+            "/avatar",
+            post(update_avatar).layer(DefaultBodyLimit::max(config.server.upload_body_limit)),
+        )
         .route("/profile", put(update_profile))
         .route("/password", put(change_password))
+        .route("/verification-code", post(send_verification_code))
+        .route("/phone", put(bind_phone))
+        .route("/phone/code", post(send_bind_phone_code))
+        .route("/phone/unbind", post(unbind_phone))
+        .route("/timezone", put(update_timezone))
+        .route("/devices", get(list_login_devices))
+        .route("/notifications", get(list_notifications))
+        .route("/notifications/read", post(read_all_notifications))
+        .route("/notifications/{id}/read", post(read_notification))
+        .nest_routes("/identities", identity_routes)
 }
@@ -1,8 +1,11 @@
-use super::types::{PasswordHashRow, UpdateAccountProfileRequest};
+use super::types::{
+    LoginDeviceRow, NotificationResp, PasswordHashRow, UpdateAccountProfileRequest,
+};
 use crate::common::error::ServiceError;
 
 use chrono::Utc;
-use sqlx::SqlitePool;
+use serde_json::Value;
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
 
 /// Current-account db operations.
 pub struct AccountRepository;
@@ -48,22 +51,71 @@ impl AccountRepository {
         })
     }
 
+    pub async fn phone_exists_for_other_user(
+        pool: &SqlitePool,
+        user_id: i64,
+        phone: &str,
+    ) -> Result<bool, ServiceError> {
+        sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM users WHERE phone = ? AND id <> ? AND deleted_at IS NULL)",
+        )
+        .bind(phone)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                "Database error in phone_exists_for_other_user, user_id={}: {:?}",
+                user_id,
+                e
+            );
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
+    /// Binds `phone`, or unbinds with `None`.
+    pub async fn update_phone(
+        pool: &SqlitePool,
+        user_id: i64,
+        phone: Option<&str>,
+    ) -> Result<(), ServiceError> {
+        sqlx::query(
+            "UPDATE users SET phone = ?, updated_at = ? WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(phone)
+        .bind(Utc::now().naive_utc())
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                ServiceError::PhoneConflict
+            }
+            e => {
+                tracing::error!("Database error in update_phone, user_id={}: {:?}", user_id, e);
+                ServiceError::DatabaseQueryFailed
+            }
+        })?;
+        Ok(())
+    }
+
     pub async fn update_profile(
         pool: &SqlitePool,
         user_id: i64,
         request: &UpdateAccountProfileRequest,
     ) -> Result<(), ServiceError> {
-        sqlx::query("UPDATE users SET email = ?, real_name = ?, updated_at = ? WHERE id = ? AND deleted_at IS NULL")
-            .bind(&request.email)
-            .bind(&request.real_name)
-            .bind(Utc::now().naive_utc())
-            .bind(user_id)
-            .execute(pool)
-            .await
-            .map_err(|e| {
-                tracing::error!("Database error in update_profile, user_id={}: {:?}", user_id, e);
-                ServiceError::DatabaseQueryFailed
-            })?;
+        sqlx::query(
+            "UPDATE users SET real_name = ?, updated_at = ? WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(&request.real_name)
+        .bind(Utc::now().naive_utc())
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error in update_profile, user_id={}: {:?}", user_id, e);
+            ServiceError::DatabaseQueryFailed
+        })?;
         Ok(())
     }
 
@@ -87,6 +139,62 @@ impl AccountRepository {
         })
     }
 
+    /// Phone number bound to the user, if any.
+    pub async fn find_phone(
+        pool: &SqlitePool,
+        user_id: i64,
+    ) -> Result<Option<String>, ServiceError> {
+        sqlx::query_scalar::<_, Option<String>>(
+            "SELECT phone FROM users WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .map(Option::flatten)
+        .map_err(|e| {
+            tracing::error!("Database error in find_phone, user_id={}: {:?}", user_id, e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
+    /// Timezone preference of the user, if one is set.
+    pub async fn find_timezone(
+        pool: &SqlitePool,
+        user_id: i64,
+    ) -> Result<Option<String>, ServiceError> {
+        sqlx::query_scalar::<_, Option<String>>(
+            "SELECT timezone FROM users WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .map(Option::flatten)
+        .map_err(|e| {
+            tracing::error!("Database error in find_timezone, user_id={}: {:?}", user_id, e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
+    pub async fn update_timezone(
+        pool: &SqlitePool,
+        user_id: i64,
+        timezone: Option<&str>,
+    ) -> Result<(), ServiceError> {
+        sqlx::query(
+            "UPDATE users SET timezone = ?, updated_at = ? WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(timezone)
+        .bind(Utc::now().naive_utc())
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error in update_timezone, user_id={}: {:?}", user_id, e);
+            ServiceError::DatabaseQueryFailed
+        })?;
+        Ok(())
+    }
+
     pub async fn update_password(
         pool: &SqlitePool,
         user_id: i64,
@@ -104,4 +212,141 @@ impl AccountRepository {
             })?;
         Ok(())
     }
+
+    /// Adds the same notification for each of `user_ids`.
+    pub async fn insert_notifications<'c, E>(
+        executor: E,
+        user_ids: &[i64],
+        kind: &str,
+        title: &str,
+        body: &str,
+        data: Option<&Value>,
+    ) -> Result<(), ServiceError>
+    where
+        E: sqlx::Executor<'c, Database = Sqlite>,
+    {
+        if user_ids.is_empty() {
+            return Ok(());
+        }
+        let now = Utc::now().naive_utc();
+        let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "INSERT INTO notifications (user_id, kind, title, body, data, created_at) ",
+        );
+        query_builder.push_values(user_ids, |mut row, user_id| {
+            row.push_bind(*user_id)
+                .push_bind(kind.to_string())
+                .push_bind(title.to_string())
+                .push_bind(body.to_string())
+                .push_bind(data.cloned())
+                .push_bind(now);
+        });
+        query_builder.build().execute(executor).await.map_err(|e| {
+            tracing::error!("Database error in insert_notifications, kind={}: {:?}", kind, e);
+            ServiceError::DatabaseQueryFailed
+        })?;
+        Ok(())
+    }
+
+    /// Every address and user agent pair the user signed in from, most recent first.
+    pub async fn list_login_devices(
+        pool: &SqlitePool,
+        user_id: i64,
+    ) -> Result<Vec<LoginDeviceRow>, ServiceError> {
+        sqlx::query_as::<_, LoginDeviceRow>(
+            "SELECT ip_address, user_agent, first_seen_at, last_seen_at FROM user_login_sources
+             WHERE user_id = ? ORDER BY last_seen_at DESC, id DESC",
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error in list_login_devices, user_id={}: {:?}", user_id, e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
+    /// One page of the user's notifications, newest first, with the matching total.
+    pub async fn list_notifications(
+        pool: &SqlitePool,
+        user_id: i64,
+        unread_only: bool,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<NotificationResp>, i64), ServiceError> {
+        let map_err = |e| {
+            tracing::error!("Database error in list_notifications, user_id={}: {:?}", user_id, e);
+            ServiceError::DatabaseQueryFailed
+        };
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM notifications
+             WHERE user_id = ? AND (? = 0 OR read_at IS NULL)",
+        )
+        .bind(user_id)
+        .bind(unread_only)
+        .fetch_one(pool)
+        .await
+        .map_err(map_err)?;
+        if total == 0 {
+            return Ok((Vec::new(), 0));
+        }
+        let items = sqlx::query_as::<_, NotificationResp>(
+            "SELECT id, kind, title, body, data, read_at, created_at FROM notifications
+             WHERE user_id = ? AND (? = 0 OR read_at IS NULL)
+             ORDER BY id DESC LIMIT ? OFFSET ?",
+        )
+        .bind(user_id)
+        .bind(unread_only)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .map_err(map_err)?;
+        Ok((items, total))
+    }
+
+    /// Marks the user's notification `id` read, or all of them when `id` is `None`.
+    /// Returns how many were unread.
+    pub async fn mark_notifications_read(
+        pool: &SqlitePool,
+        user_id: i64,
+        id: Option<i64>,
+    ) -> Result<u64, ServiceError> {
+        let result = sqlx::query(
+            "UPDATE notifications SET read_at = ?
+             WHERE user_id = ? AND (? IS NULL OR id = ?) AND read_at IS NULL",
+        )
+        .bind(Utc::now().naive_utc())
+        .bind(user_id)
+        .bind(id)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                "Database error in mark_notifications_read, user_id={}: {:?}",
+                user_id,
+                e
+            );
+            ServiceError::DatabaseQueryFailed
+        })?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn notification_exists(
+        pool: &SqlitePool,
+        user_id: i64,
+        id: i64,
+    ) -> Result<bool, ServiceError> {
+        sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM notifications WHERE id = ? AND user_id = ?)",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error in notification_exists, user_id={}: {:?}", user_id, e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
 }
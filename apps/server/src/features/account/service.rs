@@ -1,15 +1,32 @@
 use super::{
     repo::AccountRepository,
-    types::{ChangeAccountPasswordRequest, UpdateAccountProfileRequest},
+    types::{
+        BindPhoneRequest, ChangeAccountPasswordRequest, LoginDeviceResp, NotificationQuery,
+        NotificationResp, UnbindPhoneRequest, UpdateAccountProfileRequest, UpdateTimezoneRequest,
+    },
 };
 use crate::{
-    common::error::ServiceError,
-    features::auth::{service::AuthService, types::UserInfoResp},
-    infra::password::PasswordUtils,
+    common::{
+        error::ServiceError,
+        pagination::{Pagination, PaginationQuery},
+        user_agent::DeviceInfo,
+        validation::{is_email, parse_phone, parse_timezone},
+    },
+    features::auth::{repo::AuthRepository, service::AuthService, types::UserInfoResp},
+    infra::{
+        config::AppConfig,
+        otp::{self, OtpPurpose},
+        password::PasswordUtils,
+        sms,
+    },
 };
 
+use chrono_tz::Tz;
+use rustzen_core::sms::mask_phone;
 use sqlx::SqlitePool;
 
+const MAX_REAL_NAME_CHARS: usize = 50;
+
 /// Account service for current-user profile operations.
 pub struct AccountService;
 
@@ -25,21 +42,31 @@ impl AccountService {
         Ok(())
     }
 
+    /// Saves the real name at once; a new email waits for confirmation from its owner.
     pub async fn update_profile(
+        config: &AppConfig,
         pool: &SqlitePool,
         user_id: i64,
         request: UpdateAccountProfileRequest,
     ) -> Result<UserInfoResp, ServiceError> {
         tracing::info!("Updating account profile for user_id: {}", user_id);
+        let request = Self::normalize_profile(request)?;
         if AccountRepository::email_exists_for_other_user(pool, user_id, &request.email).await? {
             return Err(ServiceError::EmailConflict);
         }
 
+        let current = AuthRepository::find_user_by_id(pool, user_id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound("User".to_string()))?;
         AccountRepository::update_profile(pool, user_id, &request).await?;
+        if current.email.as_deref() != Some(request.email.as_str()) {
+            AuthService::request_email_change(config, pool, user_id, &request.email).await?;
+        }
         AuthService::get_login_info(pool, user_id).await
     }
 
     pub async fn change_password(
+        config: &AppConfig,
         pool: &SqlitePool,
         user_id: i64,
         request: ChangeAccountPasswordRequest,
@@ -49,18 +76,213 @@ impl AccountService {
             .await?
             .ok_or_else(|| ServiceError::NotFound("User".to_string()))?;
         let password_hash = Self::build_password_hash(
+            config,
             &request.current_password,
             &current.password_hash,
             &request.new_password,
             &request.confirm_password,
         )?;
+        Self::verify_critical_action(config, pool, user_id, request.verification_code.as_deref())
+            .await?;
 
         AccountRepository::update_password(pool, user_id, &password_hash).await?;
         AuthService::logout(user_id);
         Ok(())
     }
 
+    /// Texts a one-time code to the user's bound phone, for confirming a critical action.
+    pub async fn send_verification_code(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        user_id: i64,
+    ) -> Result<(), ServiceError> {
+        let phone = AccountRepository::find_phone(pool, user_id).await?.ok_or_else(|| {
+            ServiceError::InvalidOperation("No phone number is bound".to_string())
+        })?;
+        otp::send_code(config, OtpPurpose::CriticalAction, &user_id.to_string(), &phone).await
+    }
+
+    /// Checks the SMS code for a critical action. Users without a bound phone, and servers
+    /// without SMS, are not asked for one.
+    pub async fn verify_critical_action(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        user_id: i64,
+        code: Option<&str>,
+    ) -> Result<(), ServiceError> {
+        if sms::provider(config).is_none()
+            || AccountRepository::find_phone(pool, user_id).await?.is_none()
+        {
+            return Ok(());
+        }
+        let code = code.ok_or(ServiceError::InvalidVerificationCode)?;
+        otp::verify_code(OtpPurpose::CriticalAction, &user_id.to_string(), code)
+    }
+
+    /// Texts a code to `phone` so the user can prove they own it before binding.
+    pub async fn send_bind_phone_code(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        user_id: i64,
+        phone: &str,
+    ) -> Result<(), ServiceError> {
+        let phone = parse_phone("phone", phone)?;
+        if AccountRepository::phone_exists_for_other_user(pool, user_id, &phone).await? {
+            return Err(ServiceError::PhoneConflict);
+        }
+        otp::send_code(config, OtpPurpose::BindPhone, &Self::bind_subject(user_id, &phone), &phone)
+            .await
+    }
+
+    pub async fn bind_phone(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        user_id: i64,
+        request: BindPhoneRequest,
+    ) -> Result<UserInfoResp, ServiceError> {
+        let phone = parse_phone("phone", &request.phone)?;
+        Self::verify_critical_action(config, pool, user_id, request.verification_code.as_deref())
+            .await?;
+        otp::verify_code(
+            OtpPurpose::BindPhone,
+            &Self::bind_subject(user_id, &phone),
+            &request.code,
+        )?;
+        AccountRepository::update_phone(pool, user_id, Some(&phone)).await?;
+        tracing::info!("Bound phone {} to user_id: {}", mask_phone(&phone), user_id);
+        AuthService::get_login_info(pool, user_id).await
+    }
+
+    pub async fn unbind_phone(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        user_id: i64,
+        request: UnbindPhoneRequest,
+    ) -> Result<UserInfoResp, ServiceError> {
+        if AccountRepository::find_phone(pool, user_id).await?.is_none() {
+            return Err(ServiceError::InvalidOperation("No phone number is bound".to_string()));
+        }
+        Self::verify_critical_action(config, pool, user_id, request.verification_code.as_deref())
+            .await?;
+        AccountRepository::update_phone(pool, user_id, None).await?;
+        tracing::info!("Unbound phone from user_id: {}", user_id);
+        AuthService::get_login_info(pool, user_id).await
+    }
+
+    pub async fn update_timezone(
+        pool: &SqlitePool,
+        user_id: i64,
+        request: UpdateTimezoneRequest,
+    ) -> Result<UserInfoResp, ServiceError> {
+        let timezone = match request.timezone.as_deref().map(str::trim) {
+            None | Some("") => None,
+            Some(value) => Some(parse_timezone("timezone", value)?),
+        };
+        AccountRepository::update_timezone(pool, user_id, timezone.map(|tz| tz.name())).await?;
+        tracing::info!(user_id, ?timezone, "Updated timezone preference");
+        AuthService::get_login_info(pool, user_id).await
+    }
+
+    /// Timezone for the user's exports and dashboard groupings: their preference, else
+    /// `RUSTZEN_TIMEZONE`.
+    pub async fn effective_timezone(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        user_id: i64,
+    ) -> Result<Tz, ServiceError> {
+        let preferred = AccountRepository::find_timezone(pool, user_id).await?;
+        Ok(preferred
+            .and_then(|name| name.parse::<Tz>().ok())
+            .unwrap_or_else(|| config.timezone().expect("RUSTZEN_TIMEZONE is checked at startup")))
+    }
+
+    pub async fn list_notifications(
+        pool: &SqlitePool,
+        user_id: i64,
+        query: NotificationQuery,
+    ) -> Result<(Vec<NotificationResp>, i64), ServiceError> {
+        let pagination = Pagination::from_query(PaginationQuery {
+            current: query.current,
+            page_size: query.page_size,
+        });
+        AccountRepository::list_notifications(
+            pool,
+            user_id,
+            query.unread,
+            i64::from(pagination.offset),
+            i64::from(pagination.limit),
+        )
+        .await
+    }
+
+    /// Devices the user signed in from, with readable names.
+    pub async fn list_login_devices(
+        pool: &SqlitePool,
+        user_id: i64,
+    ) -> Result<Vec<LoginDeviceResp>, ServiceError> {
+        let rows = AccountRepository::list_login_devices(pool, user_id).await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| LoginDeviceResp {
+                device: DeviceInfo::parse(&row.user_agent),
+                ip_address: row.ip_address,
+                user_agent: row.user_agent,
+                first_seen_at: row.first_seen_at,
+                last_seen_at: row.last_seen_at,
+            })
+            .collect())
+    }
+
+    /// Marks one of the user's notifications read; already read ones are left as they are.
+    pub async fn read_notification(
+        pool: &SqlitePool,
+        user_id: i64,
+        id: i64,
+    ) -> Result<(), ServiceError> {
+        if !AccountRepository::notification_exists(pool, user_id, id).await? {
+            return Err(ServiceError::NotFound("Notification".to_string()));
+        }
+        AccountRepository::mark_notifications_read(pool, user_id, Some(id)).await?;
+        Ok(())
+    }
+
+    /// Marks every notification of the user read and returns how many were unread.
+    pub async fn read_all_notifications(
+        pool: &SqlitePool,
+        user_id: i64,
+    ) -> Result<u64, ServiceError> {
+        AccountRepository::mark_notifications_read(pool, user_id, None).await
+    }
+
+    /// Binding codes belong to one user and one number, so a code for one number cannot
+    /// bind another.
+    fn bind_subject(user_id: i64, phone: &str) -> String {
+        format!("{}:{}", user_id, phone)
+    }
+
+    /// Trim profile fields and reject values the admin user form would not accept.
+    pub fn normalize_profile(
+        request: UpdateAccountProfileRequest,
+    ) -> Result<UpdateAccountProfileRequest, ServiceError> {
+        let email = request.email.trim().to_string();
+        if !is_email(&email) {
+            return Err(ServiceError::InvalidOperation("Email address is invalid".to_string()));
+        }
+
+        let real_name =
+            request.real_name.map(|name| name.trim().to_string()).filter(|name| !name.is_empty());
+        if real_name.as_ref().is_some_and(|name| name.chars().count() > MAX_REAL_NAME_CHARS) {
+            return Err(ServiceError::InvalidOperation(format!(
+                "Real name must be at most {} characters",
+                MAX_REAL_NAME_CHARS
+            )));
+        }
+
+        Ok(UpdateAccountProfileRequest { email, real_name })
+    }
+
     pub fn build_password_hash(
+        config: &AppConfig,
         current_password: &str,
         current_hash: &str,
         new_password: &str,
@@ -72,6 +294,6 @@ impl AccountService {
         if new_password != confirm_password {
             return Err(ServiceError::PasswordConfirmationMismatch);
         }
-        PasswordUtils::hash_password(new_password)
+        PasswordUtils::hash_password(config, new_password)
     }
 }
@@ -1,4 +1,8 @@
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::common::user_agent::DeviceInfo;
 
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct PasswordHashRow {
@@ -20,4 +24,86 @@ pub struct ChangeAccountPasswordRequest {
     pub current_password: String,
     pub new_password: String,
     pub confirm_password: String,
+    /// SMS code from `POST /api/account/verification-code`; required once a phone is bound
+    /// and SMS is configured.
+    #[serde(default)]
+    pub verification_code: Option<String>,
+}
+
+/// Request for a code texted to a phone number about to be bound.
+#[derive(Debug, Deserialize)]
+pub struct BindPhoneCodeRequest {
+    pub phone: String,
+}
+
+/// Binds `phone` with the code texted to it. Replacing a bound phone also needs a
+/// `verification_code` sent to the current one.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BindPhoneRequest {
+    pub phone: String,
+    pub code: String,
+    #[serde(default)]
+    pub verification_code: Option<String>,
+}
+
+/// Unbinds the current phone, confirmed with a code sent to it.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnbindPhoneRequest {
+    #[serde(default)]
+    pub verification_code: Option<String>,
+}
+
+/// Sets the timezone used for the caller's dashboard trends and CSV exports; `null` goes
+/// back to the server default.
+#[derive(Debug, Deserialize)]
+pub struct UpdateTimezoneRequest {
+    #[serde(default)]
+    pub timezone: Option<String>,
+}
+
+/// In-app notification of the current account, e.g. a suspicious login alert.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationResp {
+    pub id: i64,
+    /// What raised it, e.g. `login_alert`.
+    pub kind: String,
+    pub title: String,
+    pub body: String,
+    pub data: Option<Value>,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An address and user agent pair the current account signed in from.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct LoginDeviceRow {
+    pub ip_address: String,
+    pub user_agent: String,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+/// Device the current account signed in from, most recent first.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginDeviceResp {
+    pub ip_address: String,
+    pub user_agent: String,
+    pub device: DeviceInfo,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+/// Notification list query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationQuery {
+    pub current: Option<i64>,
+    pub page_size: Option<i64>,
+    /// Only notifications not yet marked read.
+    #[serde(default)]
+    pub unread: bool,
 }
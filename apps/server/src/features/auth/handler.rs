@@ -1,39 +1,122 @@
 use super::{
     service::AuthService,
-    types::{LoginAuditCommand, LoginRequest, LoginResp, UserInfoResp},
+    types::{
+        CapabilityCheckQuery, CapabilityCheckResp, ConfirmEmailRequest, LoginAuditCommand,
+        LoginCredentials, LoginRequest, LoginResp, ReauthRequest, ReauthResp, SmsLoginCodeRequest,
+        SmsLoginRequest, UserInfoResp,
+    },
+};
+use crate::{
+    common::{
+        api::{ApiResponse, AppResult},
+        error::AppError,
+    },
+    features::system::policy::{
+        service::PolicyService,
+        types::{AcceptPolicyRequest, ConsentSource, PendingPolicy},
+    },
+    infra::{
+        config::AppConfig,
+        session::{
+            csrf_cookie, expired_csrf_cookie, expired_session_cookie, issue_csrf_token,
+            session_cookie,
+        },
+        single_session,
+    },
 };
-use crate::common::api::{ApiResponse, AppResult};
 
 use axum::{
-    Json,
-    extract::{ConnectInfo, State},
+    Extension, Json,
+    extract::{ConnectInfo, Query, State},
     http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
 };
-use rustzen_core::auth::CurrentUser;
+use axum_extra::extract::cookie::CookieJar;
+use futures::{Stream, stream};
+use rustzen_core::auth::{AuthClaims, CurrentUser};
 use sqlx::SqlitePool;
-use std::net::SocketAddr;
+use std::{convert::Infallible, net::SocketAddr};
+use tokio::sync::broadcast::error::RecvError;
 
 /// Login with username/password
-#[tracing::instrument(name = "login", skip(pool, addr, headers, request))]
+///
+/// In cookie session mode the token is set as the HttpOnly session cookie instead of
+/// being returned.
+#[tracing::instrument(name = "login", skip(config, pool, addr, headers, request))]
 pub async fn login(
+    Extension(config): Extension<&'static AppConfig>,
     State(pool): State<SqlitePool>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     headers: HeaderMap,
     Json(request): Json<LoginRequest>,
-) -> AppResult<LoginResp> {
+) -> Result<(CookieJar, Json<ApiResponse<LoginResp>>), AppError> {
     let LoginRequest { username, password } = request;
-    let audit_command = LoginAuditCommand {
-        ip_address: addr.ip().to_string(),
-        user_agent: headers
-            .get("user-agent")
-            .and_then(|h| h.to_str().ok())
-            .unwrap_or("Unknown")
-            .to_string(),
-    };
+    login_response(config, &pool, addr, &headers, LoginCredentials::Password { username, password })
+        .await
+}
 
-    Ok(ApiResponse::success(
-        AuthService::login_with_audit(&pool, &username, &password, audit_command).await?,
-    ))
+/// Text a login code to a bound phone number
+pub async fn request_sms_login_code(
+    Extension(config): Extension<&'static AppConfig>,
+    State(pool): State<SqlitePool>,
+    Json(request): Json<SmsLoginCodeRequest>,
+) -> AppResult<()> {
+    AuthService::request_sms_login_code(config, &pool, &request.phone).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Login with a phone number and the texted code
+///
+/// Sessions are issued exactly as for password login.
+#[tracing::instrument(name = "login_sms", skip(config, pool, addr, headers, request))]
+pub async fn login_sms(
+    Extension(config): Extension<&'static AppConfig>,
+    State(pool): State<SqlitePool>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(request): Json<SmsLoginRequest>,
+) -> Result<(CookieJar, Json<ApiResponse<LoginResp>>), AppError> {
+    let SmsLoginRequest { phone, code } = request;
+    login_response(config, &pool, addr, &headers, LoginCredentials::Sms { phone, code }).await
+}
+
+/// Runs an audited login; with cookie sessions on, moves the token into the session
+/// cookie and sets a CSRF cookie issued for it.
+pub async fn login_response(
+    config: &AppConfig,
+    pool: &SqlitePool,
+    addr: SocketAddr,
+    headers: &HeaderMap,
+    credentials: LoginCredentials,
+) -> Result<(CookieJar, Json<ApiResponse<LoginResp>>), AppError> {
+    let audit_command =
+        LoginAuditCommand { ip_address: addr.ip().to_string(), user_agent: user_agent(headers) };
+
+    let mut response =
+        AuthService::login_with_audit(config, pool, credentials, audit_command).await?;
+    let jar = session_cookies(config, &mut response.token);
+    Ok((jar, ApiResponse::success(response)))
+}
+
+/// Apply an email change with the token mailed to the new address
+pub async fn confirm_email(
+    State(pool): State<SqlitePool>,
+    Json(request): Json<ConfirmEmailRequest>,
+) -> AppResult<()> {
+    AuthService::confirm_email(&pool, request).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Issue a CSRF token for the caller's session cookie, both in the body and as the
+/// readable CSRF cookie
+pub async fn get_csrf_token(
+    Extension(config): Extension<&'static AppConfig>,
+    jar: CookieJar,
+) -> (CookieJar, Json<ApiResponse<String>>) {
+    let session =
+        jar.get(&config.auth.session_cookie_name).map(|cookie| cookie.value()).unwrap_or("");
+    let token = issue_csrf_token(config, session);
+    (CookieJar::new().add(csrf_cookie(config, token.clone())), ApiResponse::success(token))
 }
 
 /// Get current user info with roles and menus
@@ -45,9 +128,109 @@ pub async fn get_login_info(
     Ok(ApiResponse::success(AuthService::get_login_info(&pool, current_user.user_id).await?))
 }
 
+/// Accept the current versions of the listed policies; returns the ones still pending
+#[tracing::instrument(name = "accept_policies", skip(current_user, pool, addr, headers, request))]
+pub async fn accept_policies(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(request): Json<AcceptPolicyRequest>,
+) -> AppResult<Vec<PendingPolicy>> {
+    let source =
+        ConsentSource { ip_address: addr.ip().to_string(), user_agent: user_agent(&headers) };
+    Ok(ApiResponse::success(
+        PolicyService::accept(&pool, current_user.user_id, request, source).await?,
+    ))
+}
+
+/// Whether the current user holds `perm`, and which grant provides it
+#[tracing::instrument(name = "check_my_capability", skip(current_user, pool))]
+pub async fn check_my_capability(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Query(query): Query<CapabilityCheckQuery>,
+) -> AppResult<CapabilityCheckResp> {
+    let check = AuthService::check_capability(&pool, current_user.user_id, &query.perm).await?;
+    Ok(ApiResponse::success(check))
+}
+
 /// Logout and clear cache
-#[tracing::instrument(name = "logout", skip(current_user))]
-pub async fn logout(current_user: CurrentUser) -> AppResult<()> {
+#[tracing::instrument(name = "logout", skip(current_user, config))]
+pub async fn logout(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+) -> Result<(CookieJar, Json<ApiResponse<()>>), AppError> {
     AuthService::logout(current_user.user_id);
-    Ok(ApiResponse::success(()))
+    let mut jar = CookieJar::new();
+    if config.auth.session_cookie {
+        jar = jar.add(expired_session_cookie(config)).add(expired_csrf_cookie(config));
+    }
+    Ok((jar, ApiResponse::success(())))
+}
+
+/// Confirm the password or an SMS code again before a sensitive action
+///
+/// In cookie session mode the fresh token replaces the session cookie instead of being
+/// returned.
+#[tracing::instrument(name = "reauthenticate", skip(config, pool, claims, request))]
+pub async fn reauthenticate(
+    Extension(config): Extension<&'static AppConfig>,
+    State(pool): State<SqlitePool>,
+    Extension(claims): Extension<AuthClaims>,
+    Json(request): Json<ReauthRequest>,
+) -> Result<(CookieJar, Json<ApiResponse<ReauthResp>>), AppError> {
+    let mut response = AuthService::reauthenticate(config, &pool, &claims, request).await?;
+    let jar = session_cookies(config, &mut response.token);
+    Ok((jar, ApiResponse::success(response)))
+}
+
+/// Stream events about the caller's login session until the client disconnects.
+///
+/// With `RUSTZEN_SINGLE_SESSION=true`, a `logged_in_elsewhere` event is sent once a newer
+/// login on this instance replaces this session, and the stream then ends.
+pub async fn stream_session_events(
+    Extension(claims): Extension<AuthClaims>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let events = stream::unfold(Some(single_session::subscribe()), move |receiver| {
+        let claims = claims.clone();
+        async move {
+            let mut receiver = receiver?;
+            loop {
+                match receiver.recv().await {
+                    Ok(replaced)
+                        if replaced.user_id == claims.user_id
+                            && claims.sid.as_deref() == Some(replaced.session_id.as_str()) =>
+                    {
+                        let event = Event::default()
+                            .event("logged_in_elsewhere")
+                            .json_data(&replaced)
+                            .unwrap_or_default();
+                        return Some((Ok(event), None));
+                    }
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        }
+    });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+/// In cookie session mode, takes `token` out of the response body and sets it as the
+/// session cookie, with a CSRF cookie issued for it.
+fn session_cookies(config: &AppConfig, token: &mut Option<String>) -> CookieJar {
+    let jar = CookieJar::new();
+    if !config.auth.session_cookie {
+        return jar;
+    }
+    let Some(token) = token.take() else {
+        return jar;
+    };
+    let csrf = issue_csrf_token(config, &token);
+    jar.add(session_cookie(config, token)).add(csrf_cookie(config, csrf))
+}
+
+fn user_agent(headers: &HeaderMap) -> String {
+    headers.get("user-agent").and_then(|h| h.to_str().ok()).unwrap_or("Unknown").to_string()
 }
@@ -9,12 +9,31 @@ use axum::{
 };
 use sqlx::SqlitePool;
 
-use handler::{get_login_info, login, logout};
+use crate::features::{
+    oauth::public_oauth_routes, system::registration::public_registration_routes,
+};
+use handler::{
+    accept_policies, check_my_capability, confirm_email, get_csrf_token, get_login_info, login,
+    login_sms, logout, reauthenticate, request_sms_login_code, stream_session_events,
+};
 
 pub fn public_auth_routes() -> Router<SqlitePool> {
-    Router::new().route("/login", post(login))
+    Router::new()
+        .route("/login", post(login))
+        .route("/login/sms", post(login_sms))
+        .route("/login/sms/code", post(request_sms_login_code))
+        .route("/csrf", get(get_csrf_token))
+        .route("/confirm-email", post(confirm_email))
+        .merge(public_registration_routes())
+        .merge(public_oauth_routes())
 }
 
 pub fn protected_auth_routes() -> Router<SqlitePool> {
-    Router::new().route("/me", get(get_login_info)).route("/logout", get(logout))
+    Router::new()
+        .route("/me", get(get_login_info))
+        .route("/me/can", get(check_my_capability))
+        .route("/me/consent", post(accept_policies))
+        .route("/logout", get(logout))
+        .route("/reauth", post(reauthenticate))
+        .route("/session/events", get(stream_session_events))
 }
@@ -1,7 +1,16 @@
-use super::types::{AuthUserRow, LoginCredentialsRow};
-use crate::common::error::ServiceError;
+use super::types::{AuthMenuInfo, AuthUserRow, ConfirmedEmailChange, LoginCredentialsRow};
+use crate::{
+    common::{
+        error::ServiceError,
+        ids::RoleId,
+        tx::{self, Tx},
+    },
+    features::system::role::types::RoleAccessRow,
+    infra::geoip::GeoLocation,
+};
 
-use chrono::Utc;
+use chrono::{NaiveDateTime, Utc};
+use rustzen_core::capability::SYSTEM_WILDCARD;
 use sqlx::SqlitePool;
 
 /// Auth db operations.
@@ -14,7 +23,7 @@ impl AuthRepository {
         username: &str,
     ) -> Result<Option<LoginCredentialsRow>, ServiceError> {
         sqlx::query_as::<_, LoginCredentialsRow>(
-            "SELECT id, password_hash, status FROM users WHERE username = ? AND deleted_at IS NULL",
+            "SELECT id, username, password_hash, status FROM users WHERE username = ? AND deleted_at IS NULL",
         )
         .bind(username)
         .fetch_optional(pool)
@@ -29,13 +38,30 @@ impl AuthRepository {
         })
     }
 
+    /// Login credentials of the live user bound to `phone`, an E.164 number.
+    pub async fn get_login_credentials_by_phone(
+        pool: &SqlitePool,
+        phone: &str,
+    ) -> Result<Option<LoginCredentialsRow>, ServiceError> {
+        sqlx::query_as::<_, LoginCredentialsRow>(
+            "SELECT id, username, password_hash, status FROM users WHERE phone = ? AND deleted_at IS NULL",
+        )
+        .bind(phone)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error in get_login_credentials_by_phone: {:?}", e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
     /// Find user by ID for auth/session data.
     pub async fn find_user_by_id(
         pool: &SqlitePool,
         id: i64,
     ) -> Result<Option<AuthUserRow>, ServiceError> {
         sqlx::query_as::<_, AuthUserRow>(
-            "SELECT id, username, real_name, email, avatar_url, is_system FROM users WHERE id = ? AND deleted_at IS NULL AND status = 1",
+            "SELECT id, username, real_name, email, phone, avatar_url, is_system, timezone FROM users WHERE id = ? AND deleted_at IS NULL AND status = 1",
         )
         .bind(id)
         .fetch_optional(pool)
@@ -46,6 +72,81 @@ impl AuthRepository {
         })
     }
 
+    /// Stores the latest login's address, and its location when one was found, and returns
+    /// the country stored before.
+    pub async fn swap_login_location_in_tx(
+        tx: &mut Tx<'_>,
+        id: i64,
+        ip_address: &str,
+        location: &GeoLocation,
+    ) -> Result<Option<String>, ServiceError> {
+        let map_err = |e| {
+            tracing::error!("Database error in swap_login_location_in_tx, user_id={}: {:?}", id, e);
+            ServiceError::DatabaseQueryFailed
+        };
+        let previous: Option<Option<String>> =
+            sqlx::query_scalar("SELECT last_login_country FROM users WHERE id = ?")
+                .bind(id)
+                .fetch_optional(&mut **tx)
+                .await
+                .map_err(map_err)?;
+        sqlx::query(
+            "UPDATE users
+             SET last_login_ip = ?,
+                 last_login_country = COALESCE(?, last_login_country),
+                 last_login_city = CASE WHEN ? IS NULL THEN last_login_city ELSE ? END
+             WHERE id = ?",
+        )
+        .bind(ip_address)
+        .bind(location.country.as_deref())
+        .bind(location.country.as_deref())
+        .bind(location.city.as_deref())
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(map_err)?;
+        Ok(previous.flatten())
+    }
+
+    /// Stores the user's new login session and returns the one it replaces.
+    pub async fn swap_session(
+        pool: &SqlitePool,
+        id: i64,
+        session_id: &str,
+    ) -> Result<Option<String>, ServiceError> {
+        let map_err = |e| {
+            tracing::error!("Database error in swap_session, user_id={}: {:?}", id, e);
+            ServiceError::DatabaseQueryFailed
+        };
+        let previous: Option<Option<String>> =
+            sqlx::query_scalar("SELECT session_id FROM users WHERE id = ?")
+                .bind(id)
+                .fetch_optional(pool)
+                .await
+                .map_err(map_err)?;
+        sqlx::query("UPDATE users SET session_id = ? WHERE id = ?")
+            .bind(session_id)
+            .bind(id)
+            .execute(pool)
+            .await
+            .map_err(map_err)?;
+        Ok(previous.flatten())
+    }
+
+    /// The user's active login session, if one was started.
+    pub async fn find_session(pool: &SqlitePool, id: i64) -> Result<Option<String>, ServiceError> {
+        let session: Option<Option<String>> =
+            sqlx::query_scalar("SELECT session_id FROM users WHERE id = ?")
+                .bind(id)
+                .fetch_optional(pool)
+                .await
+                .map_err(|e| {
+                    tracing::error!("Database error in find_session, user_id={}: {:?}", id, e);
+                    ServiceError::DatabaseQueryFailed
+                })?;
+        Ok(session.flatten())
+    }
+
     /// Update last login timestamp
     pub async fn update_last_login(pool: &SqlitePool, id: i64) -> Result<(), ServiceError> {
         sqlx::query("UPDATE users SET last_login_at = ?, updated_at = ? WHERE id = ?")
@@ -60,6 +161,28 @@ impl AuthRepository {
         Ok(())
     }
 
+    /// Replace a stored hash with an upgraded one for the same password.
+    ///
+    /// Matches on the old hash so a password change that raced the login wins.
+    pub async fn upgrade_password_hash(
+        pool: &SqlitePool,
+        id: i64,
+        old_hash: &str,
+        new_hash: &str,
+    ) -> Result<(), ServiceError> {
+        sqlx::query("UPDATE users SET password_hash = ? WHERE id = ? AND password_hash = ?")
+            .bind(new_hash)
+            .bind(id)
+            .bind(old_hash)
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("Database error in upgrade_password_hash, user_id={}: {:?}", id, e);
+                ServiceError::DatabaseQueryFailed
+            })?;
+        Ok(())
+    }
+
     /// Get all permission keys for a user by user ID.
     /// Returns a list of permission strings (e.g., "system:user:list").
     pub async fn get_user_permissions(
@@ -79,4 +202,189 @@ impl AuthRepository {
                 ServiceError::DatabaseQueryFailed
             })
     }
+
+    /// Every capability of a user with the role granting it; a code held through two
+    /// roles appears twice.
+    pub async fn get_user_grants(
+        pool: &SqlitePool,
+        user_id: i64,
+    ) -> Result<Vec<(String, RoleId)>, ServiceError> {
+        sqlx::query_as("SELECT menu_code, role_id FROM user_permissions WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("Database error in get_user_grants, user_id={}: {:?}", user_id, e);
+                ServiceError::DatabaseQueryFailed
+            })
+    }
+
+    /// Network and time restrictions of the user's restricted roles.
+    pub async fn get_user_role_access(
+        pool: &SqlitePool,
+        user_id: i64,
+    ) -> Result<Vec<RoleAccessRow>, ServiceError> {
+        sqlx::query_as::<_, RoleAccessRow>(
+            "SELECT ra.role_id, ra.allowed_cidrs, ra.allowed_days, ra.start_time, ra.end_time,
+                    ra.updated_at
+             FROM role_access_restrictions ra
+             JOIN user_roles ur ON ur.role_id = ra.role_id
+             WHERE ur.user_id = ?",
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error in get_user_role_access, user_id={}: {:?}", user_id, e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
+    /// Enabled directory, page, link and iframe menus a user can open; all of them for wildcard holders.
+    /// Names are in `locale` where translated.
+    pub async fn get_user_menus(
+        pool: &SqlitePool,
+        user_id: i64,
+        locale: &str,
+    ) -> Result<Vec<AuthMenuInfo>, ServiceError> {
+        sqlx::query_as::<_, AuthMenuInfo>(
+            "SELECT m.code, COALESCE(t.name, m.name) AS name, m.menu_type, m.link_url, m.visible,
+                    m.keep_alive
+             FROM menus m
+             LEFT JOIN menu_i18n t ON t.menu_id = m.id AND t.locale = ?
+             WHERE m.deleted_at IS NULL
+               AND m.status = 1
+               AND m.menu_type IN (1, 2, 4, 5)
+               AND EXISTS (
+                   SELECT 1 FROM user_permissions p
+                   WHERE p.user_id = ? AND (p.menu_code = m.code OR p.menu_code = ?)
+               )
+             ORDER BY m.sort_order ASC, m.id ASC",
+        )
+        .bind(locale)
+        .bind(user_id)
+        .bind(SYSTEM_WILDCARD)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error in get_user_menus, user_id={}: {:?}", user_id, e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
+    /// Stores a pending email change, replacing the user's unconfirmed ones.
+    pub async fn replace_email_change(
+        pool: &SqlitePool,
+        user_id: i64,
+        new_email: &str,
+        token_hash: &str,
+        expires_at: NaiveDateTime,
+    ) -> Result<(), ServiceError> {
+        let mut tx = tx::begin(pool).await?;
+        let db_error = |e| {
+            tracing::error!("Database error in replace_email_change, user_id={}: {:?}", user_id, e);
+            ServiceError::DatabaseQueryFailed
+        };
+        sqlx::query("DELETE FROM email_change_requests WHERE user_id = ? AND confirmed_at IS NULL")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        sqlx::query(
+            "INSERT INTO email_change_requests (user_id, new_email, token_hash, expires_at, created_at)
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(user_id)
+        .bind(new_email)
+        .bind(token_hash)
+        .bind(expires_at)
+        .bind(Utc::now().naive_utc())
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        tx::commit(tx).await
+    }
+
+    /// New email of the user's unexpired, unconfirmed change request.
+    pub async fn find_pending_email(
+        pool: &SqlitePool,
+        user_id: i64,
+        now: NaiveDateTime,
+    ) -> Result<Option<String>, ServiceError> {
+        sqlx::query_scalar(
+            "SELECT new_email FROM email_change_requests
+             WHERE user_id = ? AND confirmed_at IS NULL AND expires_at > ?
+             ORDER BY id DESC LIMIT 1",
+        )
+        .bind(user_id)
+        .bind(now)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error in find_pending_email, user_id={}: {:?}", user_id, e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
+    /// Uses up an unexpired change token and writes its address to the user; `None` when
+    /// the token is unknown, used or expired, or the user is gone.
+    ///
+    /// Fails with a conflict when another account took the address in the meantime.
+    pub async fn confirm_email_change(
+        pool: &SqlitePool,
+        token_hash: &str,
+        now: NaiveDateTime,
+    ) -> Result<Option<ConfirmedEmailChange>, ServiceError> {
+        let db_error = |e| {
+            tracing::error!("Database error in confirm_email_change: {:?}", e);
+            ServiceError::DatabaseQueryFailed
+        };
+        let mut tx = tx::begin(pool).await?;
+        let request = sqlx::query_as::<_, (i64, String)>(
+            "UPDATE email_change_requests SET confirmed_at = ?
+             WHERE token_hash = ? AND confirmed_at IS NULL AND expires_at > ?
+             RETURNING user_id, new_email",
+        )
+        .bind(now)
+        .bind(token_hash)
+        .bind(now)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        let Some((user_id, new_email)) = request else {
+            return Ok(None);
+        };
+        let user = sqlx::query_as::<_, (String, Option<String>)>(
+            "SELECT username, email FROM users
+             WHERE id = ? AND deleted_at IS NULL AND anonymized_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        let Some((username, old_email)) = user else {
+            return Ok(None);
+        };
+        sqlx::query(
+            "UPDATE users SET email = ?, email_verified_at = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(&new_email)
+        .bind(now)
+        .bind(now)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                tracing::warn!(
+                    "Unique email conflict in confirm_email_change, user_id={}",
+                    user_id
+                );
+                ServiceError::EmailConflict
+            }
+            e => db_error(e),
+        })?;
+        tx::commit(tx).await?;
+        Ok(Some(ConfirmedEmailChange { user_id, username, old_email, new_email }))
+    }
 }
@@ -1,63 +1,181 @@
 use super::{
     repo::AuthRepository,
     types::{
-        AuthUserRow, LoginAuditCommand, LoginCredentialsRow, LoginResp, UserInfoResp, UserStatus,
+        AuthUserRow, CapabilityCheckResp, ConfirmEmailRequest, LoginAuditCommand, LoginCredentials,
+        LoginCredentialsRow, LoginResp, ReauthRequest, ReauthResp, UserInfoResp, UserStatus,
     },
 };
 use crate::{
-    common::error::ServiceError,
-    features::manage::log::{service::LogService, types::LogWriteCommand},
-    infra::{auth_runtime::jwt_codec, password::PasswordUtils, permission::PermissionService},
+    common::{
+        error::ServiceError,
+        i18n, token,
+        tx::{self, Tx},
+        validation::{FieldErrors, parse_phone},
+    },
+    features::{
+        account::repo::AccountRepository, oauth::service::OAuthService,
+        system::policy::service::PolicyService,
+    },
+    infra::{
+        auth_runtime::jwt_codec,
+        config::AppConfig,
+        events,
+        geoip::{self, GeoLocation},
+        login_throttle::login_throttle,
+        mail,
+        otp::{self, OtpPurpose},
+        outbox::Recorded,
+        password::PasswordUtils,
+        permission::{PermissionService, RestrictedGrant},
+        single_session,
+    },
+};
+use rustzen_core::{
+    auth::{AuthClaims, CurrentUser},
+    capability::{SYSTEM_WILDCARD, is_registered_capability_code},
+    events::DomainEvent,
+    sms::{mask_phone, normalize_phone},
 };
 
+use chrono::{Duration, Utc};
 use sqlx::SqlitePool;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::time::Instant;
 
+/// How long an email-change confirmation link stays usable.
+const EMAIL_CHANGE_TTL_HOURS: i64 = 24;
+
 /// Auth service for login and current-user session operations.
 pub struct AuthService;
 
 impl AuthService {
     pub async fn login_with_audit(
+        config: &AppConfig,
         pool: &SqlitePool,
-        username: &str,
-        password: &str,
+        credentials: LoginCredentials,
         audit_command: LoginAuditCommand,
     ) -> Result<LoginResp, ServiceError> {
         let start_time = Instant::now();
+        let throttle = login_throttle(config);
+
+        if let Some(remaining) = throttle.retry_after(&audit_command.ip_address, start_time) {
+            tracing::warn!("Login refused for throttled ip={}", audit_command.ip_address);
+            return Err(ServiceError::TooManyLoginAttempts(remaining.as_secs_f64().ceil() as u64));
+        }
 
-        match Self::login(pool, username, password).await {
-            Ok(response) => {
-                Self::record_login_operation(
-                    pool,
-                    response.user_info.id,
-                    username,
-                    "SUCCESS",
-                    "User login successful",
-                    start_time,
-                    &audit_command,
-                )
-                .await;
-                Ok(response)
+        let result = match &credentials {
+            LoginCredentials::Password { username, password } => {
+                Self::login(config, pool, username, password).await
             }
-            Err(err) => {
-                let description = err.to_string();
-                Self::record_login_operation(
-                    pool,
-                    0,
-                    username,
-                    "FAIL",
-                    &description,
-                    start_time,
-                    &audit_command,
-                )
-                .await;
-                Err(err)
+            LoginCredentials::Sms { phone, code } => {
+                Self::login_with_sms(config, pool, phone, code).await
             }
+            LoginCredentials::OAuth { state, code, state_cookie } => {
+                Self::login_with_oauth(config, pool, state, code, state_cookie.as_deref()).await
+            }
+        };
+        let LoginAuditCommand { ip_address, user_agent } = audit_command;
+        let ban = match &result {
+            Ok(_) => {
+                throttle.record_success(&ip_address, &credentials.subject());
+                None
+            }
+            Err(ServiceError::InvalidCredentials | ServiceError::InvalidVerificationCode) => {
+                throttle.record_failure(&ip_address, &credentials.subject(), Instant::now())
+            }
+            Err(_) => None,
+        };
+        let duration_ms = start_time.elapsed().as_millis() as i32;
+        let client_ip = ip_address.clone();
+        let event = match &result {
+            Ok(response) => DomainEvent::LoginSucceeded {
+                user_id: response.user_info.id,
+                username: response.user_info.username.clone(),
+                ip_address,
+                user_agent,
+                duration_ms,
+            },
+            Err(err) => DomainEvent::LoginFailed {
+                username: credentials.subject(),
+                reason: err.to_string(),
+                ip_address,
+                user_agent,
+                duration_ms,
+            },
+        };
+        let mut tx = tx::begin(pool).await?;
+        let mut recorded = vec![events::record(&mut tx, &event).await?];
+        if let Ok(response) = &result {
+            let location = geoip::lookup(&client_ip).unwrap_or_default();
+            let user = &response.user_info;
+            recorded.extend(
+                Self::record_login_location(&mut tx, user.id, &user.username, &client_ip, location)
+                    .await?,
+            );
+        }
+        if let Some(ban) = ban {
+            tracing::warn!(
+                "Banning ip={} for {:?} after {} failed logins",
+                client_ip,
+                ban.duration,
+                ban.failures
+            );
+            let event = DomainEvent::LoginIpBanned {
+                ip_address: client_ip,
+                failures: ban.failures,
+                ban_secs: ban.duration.as_secs(),
+            };
+            recorded.push(events::record(&mut tx, &event).await?);
         }
+        tx::commit(tx).await?;
+        for recorded in recorded {
+            events::deliver(pool, recorded).await;
+        }
+        result
+    }
+
+    /// Stores the login's address and location on the user, and records
+    /// `LoginLocationChanged` in `tx` when the country differs from the previous login's.
+    ///
+    /// A failed store is only logged; it never fails the login.
+    pub async fn record_login_location(
+        tx: &mut Tx<'_>,
+        user_id: i64,
+        username: &str,
+        ip_address: &str,
+        location: GeoLocation,
+    ) -> Result<Option<Recorded>, ServiceError> {
+        let previous_country =
+            match AuthRepository::swap_login_location_in_tx(tx, user_id, ip_address, &location)
+                .await
+            {
+                Ok(previous_country) => previous_country,
+                Err(e) => {
+                    tracing::warn!(user_id, "Failed to record login location: {:?}", e);
+                    return Ok(None);
+                }
+            };
+        let (Some(previous_country), Some(country)) = (previous_country, location.country) else {
+            return Ok(None);
+        };
+        if previous_country == country {
+            return Ok(None);
+        }
+        tracing::warn!(user_id, %country, %previous_country, "Login from an unusual location");
+        let event = DomainEvent::LoginLocationChanged {
+            user_id,
+            username: username.to_string(),
+            ip_address: ip_address.to_string(),
+            country,
+            city: location.city,
+            previous_country,
+        };
+        events::record(tx, &event).await.map(Some)
     }
 
     /// Login with username/password
     pub async fn login(
+        config: &AppConfig,
         pool: &SqlitePool,
         username: &str,
         password: &str,
@@ -65,7 +183,7 @@ impl AuthService {
         let start = std::time::Instant::now();
         tracing::info!("Login attempt received for username: {}", username);
 
-        let user = Self::verify_login(pool, username, password).await.map_err(|error| {
+        let user = Self::verify_login(config, pool, username, password).await.map_err(|error| {
             tracing::warn!("Login verification failed for username={}: {:?}", username, error);
             error
         })?;
@@ -76,10 +194,88 @@ impl AuthService {
             user.id
         );
 
-        let token = jwt_codec().encode(user.id, username).map_err(|e| {
-            tracing::error!("Failed to generate token for user_id={}: {:?}", user.id, e);
-            ServiceError::TokenCreationFailed
-        })?;
+        let response = Self::start_session(config, pool, &user).await?;
+
+        let total_time = start.elapsed();
+        tracing::info!(
+            "Login successful for username={}, user_id={}, total_time={:?}",
+            username,
+            user.id,
+            total_time
+        );
+
+        Ok(response)
+    }
+
+    /// Texts a login code to `phone` when a live user has it bound.
+    ///
+    /// Unknown numbers get the same empty success, so the endpoint does not reveal which
+    /// numbers are registered.
+    pub async fn request_sms_login_code(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        phone: &str,
+    ) -> Result<(), ServiceError> {
+        let phone = parse_phone("phone", phone)?;
+        let Some(user) = AuthRepository::get_login_credentials_by_phone(pool, &phone).await? else {
+            tracing::info!("SMS login code requested for an unbound number");
+            return Ok(());
+        };
+        UserStatus::try_from(user.status)?.check_status()?;
+        otp::send_code(config, OtpPurpose::Login, &phone, &phone).await
+    }
+
+    /// Login with a phone number and the code texted to it.
+    pub async fn login_with_sms(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        phone: &str,
+        code: &str,
+    ) -> Result<LoginResp, ServiceError> {
+        let phone = normalize_phone(phone).ok_or(ServiceError::InvalidVerificationCode)?;
+        otp::verify_code(OtpPurpose::Login, &phone, code)?;
+        let user = AuthRepository::get_login_credentials_by_phone(pool, &phone)
+            .await?
+            .ok_or(ServiceError::InvalidCredentials)?;
+        UserStatus::try_from(user.status)?.check_status()?;
+
+        let response = Self::start_session(config, pool, &user).await?;
+        tracing::info!("SMS login successful for user_id={}", user.id);
+        Ok(response)
+    }
+
+    /// Login with the `code` and `state` a provider redirect hands back.
+    pub async fn login_with_oauth(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        state: &str,
+        code: &str,
+        state_cookie: Option<&str>,
+    ) -> Result<LoginResp, ServiceError> {
+        let user = OAuthService::resolve_login(config, pool, state, code, state_cookie).await?;
+        UserStatus::try_from(user.status)?.check_status()?;
+
+        let response = Self::start_session(config, pool, &user).await?;
+        tracing::info!("Provider login successful for user_id={}", user.id);
+        Ok(response)
+    }
+
+    /// Issues the token for a verified user and loads what the client needs after login.
+    async fn start_session(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        user: &LoginCredentialsRow,
+    ) -> Result<LoginResp, ServiceError> {
+        let session_id = match config.auth.single_session {
+            true => Some(single_session::start(pool, user.id).await?),
+            false => None,
+        };
+        let token = jwt_codec(config)
+            .encode_session(user.id, &user.username, session_id.as_deref())
+            .map_err(|e| {
+                tracing::error!("Failed to generate token for user_id={}: {:?}", user.id, e);
+                ServiceError::TokenCreationFailed
+            })?;
 
         tracing::debug!("JWT token generated successfully for user_id={}", user.id);
 
@@ -110,15 +306,7 @@ impl AuthService {
 
         let user_info = Self::get_login_info(pool, user.id).await?;
 
-        let total_time = start.elapsed();
-        tracing::info!(
-            "Login successful for username={}, user_id={}, total_time={:?}",
-            username,
-            user.id,
-            total_time
-        );
-
-        Ok(LoginResp { token, user_info })
+        Ok(LoginResp { token: Some(token), user_info })
     }
 
     /// Get detailed user info with roles, menus, and permissions
@@ -131,13 +319,17 @@ impl AuthService {
         let user = AuthRepository::find_user_by_id(pool, user_id)
             .await?
             .ok_or_else(|| ServiceError::NotFound("User".to_string()))?;
-        let AuthUserRow { id, username, real_name, email, avatar_url, is_system } = user;
+        let AuthUserRow { id, username, real_name, email, phone, avatar_url, is_system, timezone } =
+            user;
 
         tracing::debug!("User basic info retrieved for user_id={}, username={}", user_id, username);
 
-        let permissions = Self::load_permissions(pool, user_id).await?;
-
-        PermissionService::cache_user_permissions(user_id, &permissions);
+        let permissions = Self::load_and_cache_permissions(pool, user_id).await?;
+        let menus =
+            AuthRepository::get_user_menus(pool, user_id, i18n::current_locale().tag()).await?;
+        let pending_policies = PolicyService::pending_for_user(pool, user_id).await?;
+        let pending_email =
+            AuthRepository::find_pending_email(pool, user_id, Utc::now().naive_utc()).await?;
 
         tracing::info!(
             "User info retrieved successfully for user_id={}, username={}",
@@ -145,44 +337,191 @@ impl AuthService {
             username
         );
 
-        Ok(UserInfoResp { id, username, real_name, email, avatar_url, is_system, permissions })
-    }
-
-    pub fn logout(user_id: i64) {
-        PermissionService::clear_user_cache(user_id);
+        Ok(UserInfoResp {
+            id,
+            username,
+            real_name,
+            email,
+            phone: phone.as_deref().map(mask_phone),
+            avatar_url,
+            is_system,
+            timezone,
+            permissions,
+            menus,
+            pending_policies,
+            pending_email,
+        })
     }
 
-    async fn record_login_operation(
+    /// Starts an email change: `new_email` gets a confirmation token, and the stored email
+    /// stays as it is until the token is used.
+    ///
+    /// A failed mail is only logged; the caller can simply request the change again.
+    pub async fn request_email_change(
+        config: &AppConfig,
         pool: &SqlitePool,
         user_id: i64,
-        username: &str,
-        status: &str,
-        description: &str,
-        start_time: Instant,
-        audit_command: &LoginAuditCommand,
-    ) {
-        if let Err(e) = LogService::record_operation(
+        new_email: &str,
+    ) -> Result<(), ServiceError> {
+        let token = token::generate();
+        let expires_at = Utc::now().naive_utc() + Duration::hours(EMAIL_CHANGE_TTL_HOURS);
+        AuthRepository::replace_email_change(
             pool,
-            LogWriteCommand {
-                user_id,
-                username: username.to_string(),
-                action: "AUTH_LOGIN".to_string(),
-                description: description.to_string(),
-                data: Some(serde_json::json!({})),
-                status: status.to_string(),
-                duration_ms: start_time.elapsed().as_millis() as i32,
-                ip_address: audit_command.ip_address.clone(),
-                user_agent: audit_command.user_agent.clone(),
-            },
+            user_id,
+            new_email,
+            &token::hash(&token),
+            expires_at,
         )
-        .await
+        .await?;
+        tracing::info!(user_id, "Requested email change");
+
+        let action = match config.auth.email_confirm_url.as_deref() {
+            Some(url) => format!("open this link to confirm it:\r\n\r\n{}?token={}", url, token),
+            None => format!("enter this code to confirm it:\r\n\r\n{}", token),
+        };
+        let body = format!(
+            "This address was entered as the new email of your account. Please {}\r\n\r\n\
+             It expires in {} hours. If you did not ask for this, ignore this message.\r\n",
+            action, EMAIL_CHANGE_TTL_HOURS
+        );
+        if let Err(err) = mail::send_notice(new_email, "Confirm your new email address", body).await
         {
-            tracing::error!("Failed to log login operation: {:?}", e);
+            tracing::error!(user_id, "Email change confirmation failed: {}", err);
         }
+        Ok(())
+    }
+
+    /// Applies the email change behind a mailed token and tells the old address about it.
+    pub async fn confirm_email(
+        pool: &SqlitePool,
+        request: ConfirmEmailRequest,
+    ) -> Result<(), ServiceError> {
+        let token = request.token.trim();
+        let change = if token.is_empty() {
+            None
+        } else {
+            AuthRepository::confirm_email_change(pool, &token::hash(token), Utc::now().naive_utc())
+                .await?
+        };
+        let Some(change) = change else {
+            return Err(ServiceError::InvalidOperation(
+                "Confirmation link is invalid or has expired".to_string(),
+            ));
+        };
+        tracing::info!(user_id = change.user_id, "Confirmed email change");
+
+        if let Some(old_email) = change.old_email.as_deref().filter(|old| *old != change.new_email)
+        {
+            let body = format!(
+                "The email of account {} was changed to {}.\r\n\r\nIf you did not make this \
+                 change, contact an administrator.\r\n",
+                change.username, change.new_email
+            );
+            if let Err(err) =
+                mail::send_notice(old_email, "Your email address was changed", body).await
+            {
+                tracing::error!(user_id = change.user_id, "Email change notice failed: {}", err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks `perm` against the user's grants as stored now, bypassing the session cache,
+    /// so role or menu changes show up before the cached set is refreshed.
+    pub async fn check_capability(
+        pool: &SqlitePool,
+        user_id: i64,
+        perm: &str,
+    ) -> Result<CapabilityCheckResp, ServiceError> {
+        let perm = perm.trim();
+        let mut errors = FieldErrors::new();
+        if perm.is_empty() {
+            errors.push("perm", "is required");
+        }
+        errors.into_result()?;
+
+        let user = AuthRepository::find_user_by_id(pool, user_id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound("User".to_string()))?;
+        let permissions = Self::load_permissions(pool, user_id).await?;
+        let is_super = permissions.iter().any(|code| code == SYSTEM_WILDCARD);
+        let granted_by = CurrentUser::new(user.id, user.username, permissions, is_super)
+            .granting_capability(perm);
+
+        Ok(CapabilityCheckResp {
+            user_id,
+            perm: perm.to_string(),
+            allowed: granted_by.is_some(),
+            granted_by,
+            declared: is_registered_capability_code(perm),
+        })
+    }
+
+    /// Fails with [`ServiceError::ReauthRequired`] unless the token holder logged in or
+    /// re-authenticated within `RUSTZEN_STEP_UP_MINUTES`. Call it first in handlers of
+    /// sensitive actions.
+    pub fn require_recent_auth(
+        config: &AppConfig,
+        claims: &AuthClaims,
+    ) -> Result<(), ServiceError> {
+        let max_age_minutes = config.auth.step_up_minutes;
+        if max_age_minutes == 0 {
+            return Ok(());
+        }
+        let age = Utc::now().timestamp() - claims.authenticated_at() as i64;
+        if age > Duration::minutes(max_age_minutes as i64).num_seconds() {
+            tracing::info!(user_id = claims.user_id, age, "Sensitive action needs step-up auth");
+            return Err(ServiceError::ReauthRequired(max_age_minutes));
+        }
+        Ok(())
+    }
+
+    /// Checks the password or a critical-action SMS code again and issues a token with a
+    /// fresh `auth_time` for the same session.
+    pub async fn reauthenticate(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        claims: &AuthClaims,
+        request: ReauthRequest,
+    ) -> Result<ReauthResp, ServiceError> {
+        let user_id = claims.user_id;
+        match (request.password.as_deref(), request.verification_code.as_deref()) {
+            (Some(password), _) => {
+                let current = AccountRepository::find_password_hash_by_id(pool, user_id)
+                    .await?
+                    .ok_or_else(|| ServiceError::NotFound("User".to_string()))?;
+                if !PasswordUtils::verify_password(password, &current.password_hash) {
+                    tracing::warn!(user_id, "Step-up authentication failed");
+                    return Err(ServiceError::InvalidCurrentPassword);
+                }
+            }
+            (None, Some(code)) => {
+                otp::verify_code(OtpPurpose::CriticalAction, &user_id.to_string(), code)?
+            }
+            (None, None) => {
+                let mut errors = FieldErrors::new();
+                errors.push("password", "or verificationCode is required");
+                errors.into_result()?;
+            }
+        }
+
+        let token = jwt_codec(config)
+            .encode_session(user_id, &claims.username, claims.sid.as_deref())
+            .map_err(|e| {
+                tracing::error!("Failed to generate token for user_id={}: {:?}", user_id, e);
+                ServiceError::TokenCreationFailed
+            })?;
+        tracing::info!(user_id, "Step-up authentication succeeded");
+        Ok(ReauthResp { token: Some(token) })
+    }
+
+    pub fn logout(user_id: i64) {
+        PermissionService::clear_user_cache(user_id);
     }
 
     /// Verify login credentials
     pub async fn verify_login(
+        config: &AppConfig,
         pool: &SqlitePool,
         username: &str,
         password: &str,
@@ -211,6 +550,9 @@ impl AuthService {
             );
             return Err(ServiceError::InvalidCredentials);
         }
+        if PasswordUtils::needs_rehash(config, &user.password_hash) {
+            Self::upgrade_password_hash(config, pool, &user, password).await;
+        }
 
         tracing::info!(
             "Login verification successful for username={}, user_id={}",
@@ -220,6 +562,30 @@ impl AuthService {
         Ok(user)
     }
 
+    /// Rehash a just-verified password under the configured algorithm.
+    ///
+    /// Best effort: a failure is logged and the login still succeeds on the old hash.
+    async fn upgrade_password_hash(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        user: &LoginCredentialsRow,
+        password: &str,
+    ) {
+        let result = match PasswordUtils::hash_password(config, password) {
+            Ok(new_hash) => {
+                AuthRepository::upgrade_password_hash(pool, user.id, &user.password_hash, &new_hash)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => tracing::info!("Upgraded password hash for user_id={}", user.id),
+            Err(e) => {
+                tracing::warn!("Failed to upgrade password hash for user_id={}: {:?}", user.id, e)
+            }
+        }
+    }
+
     /// Cache user permissions
     pub async fn cache_user_permissions(
         pool: &SqlitePool,
@@ -227,8 +593,7 @@ impl AuthService {
     ) -> Result<(), ServiceError> {
         tracing::debug!("Starting to cache user permissions for user_id: {}", user_id);
 
-        let permissions = Self::load_permissions(pool, user_id).await?;
-        PermissionService::cache_user_permissions(user_id, &permissions);
+        let permissions = Self::load_and_cache_permissions(pool, user_id).await?;
         tracing::info!(
             "Successfully refreshed {} permissions cache for user_id={}",
             permissions.len(),
@@ -237,6 +602,43 @@ impl AuthService {
         Ok(())
     }
 
+    /// Loads and caches a user's capabilities, keeping those of restricted roles apart.
+    /// Returns every capability the user holds, whatever the request. A role whose stored
+    /// restrictions cannot be read grants nothing.
+    async fn load_and_cache_permissions(
+        pool: &SqlitePool,
+        user_id: i64,
+    ) -> Result<Vec<String>, ServiceError> {
+        let mut windows = HashMap::new();
+        for row in AuthRepository::get_user_role_access(pool, user_id).await? {
+            let window = row.window().inspect_err(|e| tracing::error!("{}", e)).ok();
+            windows.insert(row.role_id, window);
+        }
+
+        let mut all = BTreeSet::new();
+        let mut open = Vec::new();
+        let mut restricted = HashMap::new();
+        for (code, role_id) in AuthRepository::get_user_grants(pool, user_id).await? {
+            match windows.get(&role_id) {
+                None => open.push(code.clone()),
+                Some(Some(window)) => {
+                    restricted
+                        .entry(role_id)
+                        .or_insert_with(|| RestrictedGrant {
+                            window: window.clone(),
+                            permissions: HashSet::new(),
+                        })
+                        .permissions
+                        .insert(code.clone());
+                }
+                Some(None) => continue,
+            }
+            all.insert(code);
+        }
+        PermissionService::cache_user_grants(user_id, &open, restricted.into_values().collect());
+        Ok(all.into_iter().collect())
+    }
+
     async fn load_permissions(
         pool: &SqlitePool,
         user_id: i64,
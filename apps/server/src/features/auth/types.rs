@@ -1,11 +1,13 @@
+use rustzen_core::sms::mask_phone;
 use serde::{Deserialize, Serialize};
 
-use crate::common::error::ServiceError;
+use crate::{common::error::ServiceError, features::system::policy::types::PendingPolicy};
 
 /// Minimal user info for authentication (login).
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct LoginCredentialsRow {
     pub id: i64,
+    pub username: String,
     pub password_hash: String,
     pub status: i16,
 }
@@ -17,8 +19,19 @@ pub struct AuthUserRow {
     pub username: String,
     pub real_name: Option<String>,
     pub email: Option<String>,
+    pub phone: Option<String>,
     pub avatar_url: Option<String>,
     pub is_system: bool,
+    pub timezone: Option<String>,
+}
+
+/// Email change applied by a confirmation token.
+#[derive(Debug, Clone)]
+pub struct ConfirmedEmailChange {
+    pub user_id: i64,
+    pub username: String,
+    pub old_email: Option<String>,
+    pub new_email: String,
 }
 
 /// User status enum for authentication and account control.
@@ -30,6 +43,11 @@ pub enum UserStatus {
     Locked = 4,
 }
 
+impl UserStatus {
+    /// Every stored status code, in declaration order.
+    pub const CODES: [i16; 4] = [1, 2, 3, 4];
+}
+
 impl TryFrom<i16> for UserStatus {
     type Error = ServiceError;
 
@@ -53,12 +71,83 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+/// Request for a login code texted to a bound phone.
+#[derive(Deserialize)]
+pub struct SmsLoginCodeRequest {
+    pub phone: String,
+}
+
+/// Login with a phone number and the code texted to it.
+#[derive(Deserialize)]
+pub struct SmsLoginRequest {
+    pub phone: String,
+    pub code: String,
+}
+
+/// What a login attempt presents.
+#[derive(Debug, Clone)]
+pub enum LoginCredentials {
+    Password {
+        username: String,
+        password: String,
+    },
+    Sms {
+        phone: String,
+        code: String,
+    },
+    /// `code` and `state` from a login provider redirect, with the state cookie set for
+    /// the browser that started it.
+    OAuth {
+        state: String,
+        code: String,
+        state_cookie: Option<String>,
+    },
+}
+
+impl LoginCredentials {
+    /// Who the attempt claims to be, for audit logs; phone numbers are masked.
+    pub fn subject(&self) -> String {
+        match self {
+            LoginCredentials::Password { username, .. } => username.clone(),
+            LoginCredentials::Sms { phone, .. } => mask_phone(phone),
+            // The provider account is only known after the code is exchanged.
+            LoginCredentials::OAuth { .. } => "oauth".to_string(),
+        }
+    }
+}
+
+/// Proof of identity for step-up authentication: the password, or the code texted by
+/// `POST /api/account/verification-code`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReauthRequest {
+    pub password: Option<String>,
+    pub verification_code: Option<String>,
+}
+
+/// Fresh token after step-up authentication; it keeps the current session.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReauthResp {
+    /// Left out in cookie session mode, where the session cookie carries it instead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+/// Token from the email-change confirmation mail.
+#[derive(Deserialize)]
+pub struct ConfirmEmailRequest {
+    pub token: String,
+}
+
 /// Response payload for successful user login.
 #[derive(Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LoginResp {
-    /// JWT token for authenticating subsequent requests
-    pub token: String,
+    /// JWT token for authenticating subsequent requests; left out in cookie session mode,
+    /// where the session cookie carries it instead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
     /// User information
     pub user_info: UserInfoResp,
 }
@@ -75,12 +164,57 @@ pub struct UserInfoResp {
     pub real_name: Option<String>,
     /// Email of the user
     pub email: Option<String>,
+    /// Bound phone number, masked
+    pub phone: Option<String>,
     /// Avatar URL of the user
     pub avatar_url: Option<String>,
     /// Whether the user is a system user
     pub is_system: bool,
+    /// Preferred IANA timezone; `None` uses the server default
+    pub timezone: Option<String>,
     /// List of permission codes the user has access to
     pub permissions: Vec<String>,
+    /// Directory, page, link and iframe menus the user can open, with their display flags
+    pub menus: Vec<AuthMenuInfo>,
+    /// Current policy versions the user has not accepted; empty once all are accepted
+    pub pending_policies: Vec<PendingPolicy>,
+    /// New email waiting for confirmation from its owner
+    pub pending_email: Option<String>,
+}
+
+/// Display data of one menu in the login info.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthMenuInfo {
+    pub code: String,
+    pub name: String,
+    /// 1 directory, 2 page, 4 external link, 5 iframe
+    pub menu_type: i16,
+    /// Target of link (4) and iframe (5) menus
+    pub link_url: Option<String>,
+    /// `false` keeps the page routable but out of the navigation menu
+    pub visible: bool,
+    /// Keep the page mounted after navigating away
+    pub keep_alive: bool,
+}
+
+/// Query of the capability check endpoints.
+#[derive(Debug, Deserialize)]
+pub struct CapabilityCheckQuery {
+    pub perm: String,
+}
+
+/// Whether a user holds a capability, judged from their current role grants.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapabilityCheckResp {
+    pub user_id: i64,
+    pub perm: String,
+    pub allowed: bool,
+    /// Held code that grants `perm`: the code itself, a prefix wildcard, or `*`.
+    pub granted_by: Option<String>,
+    /// `perm` is listed in the capability registry; `false` usually means a typo.
+    pub declared: bool,
 }
 
 /// Service command for recording login audit metadata.
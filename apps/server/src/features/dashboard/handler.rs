@@ -1,27 +1,100 @@
 use super::{
     service::DashboardService,
-    types::{StatsResp, SystemMetricsDataResp, UserTrendsResp},
+    types::{
+        DashboardQuery, DashboardStreamQuery, EndpointsQuery, EndpointsResp, HostMetricsQuery,
+        HostMetricsResp, StatsResp, SystemMetricsDataResp, TopQuery, TopResp, UserTrendsResp,
+    },
 };
 use crate::common::api::{ApiResponse, AppResult};
+use crate::common::error::AppError;
+use crate::features::account::service::AccountService;
+use crate::infra::config::AppConfig;
+use crate::infra::db::DbExecutor;
 use crate::infra::system_info::{SystemInfo, SystemUtils};
-use axum::extract::State;
+use axum::Extension;
+use axum::extract::{Query, State};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures::{Stream, stream};
+use rustzen_core::auth::CurrentUser;
+use std::convert::Infallible;
+use tokio::time::{MissedTickBehavior, interval};
 
-use sqlx::SqlitePool;
 use tracing::instrument;
 
-#[instrument(skip(pool))]
-pub async fn get_stats(State(pool): State<SqlitePool>) -> AppResult<StatsResp> {
-    Ok(ApiResponse::success(DashboardService::get_stats(&pool).await?))
+#[instrument(skip(config, db))]
+pub async fn get_stats(
+    Extension(config): Extension<&'static AppConfig>,
+    State(db): State<DbExecutor>,
+) -> AppResult<StatsResp> {
+    Ok(ApiResponse::success(DashboardService::get_stats(config, db.read()).await?))
 }
 
 pub async fn get_health() -> AppResult<SystemInfo> {
     Ok(ApiResponse::success(SystemUtils::get_system_info()))
 }
 
-pub async fn get_metrics(State(pool): State<SqlitePool>) -> AppResult<SystemMetricsDataResp> {
-    Ok(ApiResponse::success(DashboardService::get_metrics(&pool).await?))
+pub async fn get_metrics(
+    Extension(config): Extension<&'static AppConfig>,
+    State(db): State<DbExecutor>,
+    Query(query): Query<DashboardQuery>,
+) -> AppResult<SystemMetricsDataResp> {
+    Ok(ApiResponse::success(DashboardService::get_metrics(config, &db, query).await?))
 }
 
-pub async fn get_trends(State(pool): State<SqlitePool>) -> AppResult<UserTrendsResp> {
-    Ok(ApiResponse::success(DashboardService::get_trends(&pool).await?))
+/// Slowest and most error-prone routes of this instance over the last hour.
+pub async fn get_endpoints(Query(query): Query<EndpointsQuery>) -> AppResult<EndpointsResp> {
+    Ok(ApiResponse::success(DashboardService::endpoints(query)))
+}
+
+/// CPU, memory, disk and load history of this instance for charting.
+pub async fn get_host_metrics(Query(query): Query<HostMetricsQuery>) -> AppResult<HostMetricsResp> {
+    Ok(ApiResponse::success(DashboardService::host_metrics(query)?))
+}
+
+pub async fn get_trends(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    State(db): State<DbExecutor>,
+    Query(query): Query<DashboardQuery>,
+) -> AppResult<UserTrendsResp> {
+    let tz = AccountService::effective_timezone(config, db.read(), current_user.user_id).await?;
+    Ok(ApiResponse::success(DashboardService::get_trends(config, db.read(), query, tz).await?))
+}
+
+pub async fn get_top(
+    Extension(config): Extension<&'static AppConfig>,
+    State(db): State<DbExecutor>,
+    Query(query): Query<TopQuery>,
+) -> AppResult<TopResp> {
+    Ok(ApiResponse::success(DashboardService::get_top(config, db.read(), query).await?))
+}
+
+/// Push dashboard snapshots as server-sent events until the client disconnects.
+///
+/// A `snapshot` event with stats, health and metrics is sent right away and then every
+/// `interval` seconds; when one cannot be built, an `error` event carries the message and
+/// the stream carries on.
+pub async fn stream_dashboard(
+    Extension(config): Extension<&'static AppConfig>,
+    State(db): State<DbExecutor>,
+    Query(query): Query<DashboardStreamQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let (period, query) = DashboardService::resolve_stream(query)?;
+    let mut ticker = interval(period);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let events = stream::unfold(ticker, move |mut ticker| {
+        let (db, query) = (db.clone(), query.clone());
+        async move {
+            ticker.tick().await;
+            let event = match DashboardService::snapshot(config, &db, query).await {
+                Ok(snapshot) => {
+                    Event::default().event("snapshot").json_data(&snapshot).unwrap_or_default()
+                }
+                Err(err) => Event::default().event("error").data(err.to_string()),
+            };
+            Some((Ok(event), ticker))
+        }
+    });
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
 }
@@ -10,7 +10,10 @@ use rustzen_core::{
 };
 use sqlx::SqlitePool;
 
-use handler::{get_health, get_metrics, get_stats, get_trends};
+use handler::{
+    get_endpoints, get_health, get_host_metrics, get_metrics, get_stats, get_top, get_trends,
+    stream_dashboard,
+};
 
 pub fn dashboard_routes() -> Router<SqlitePool> {
     Router::new()
@@ -25,9 +28,25 @@ pub fn dashboard_routes() -> Router<SqlitePool> {
             get(get_metrics),
             PermissionsCheck::Require(dashboard::VIEW),
         )
+        .route_with_permission(
+            "/endpoints",
+            get(get_endpoints),
+            PermissionsCheck::Require(dashboard::VIEW),
+        )
+        .route_with_permission(
+            "/system-metrics",
+            get(get_host_metrics),
+            PermissionsCheck::Require(dashboard::VIEW),
+        )
         .route_with_permission(
             "/trends",
             get(get_trends),
             PermissionsCheck::Require(dashboard::VIEW),
         )
+        .route_with_permission("/top", get(get_top), PermissionsCheck::Require(dashboard::VIEW))
+        .route_with_permission(
+            "/stream",
+            get(stream_dashboard),
+            PermissionsCheck::Require(dashboard::VIEW),
+        )
 }
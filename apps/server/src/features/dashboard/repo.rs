@@ -1,6 +1,11 @@
-use super::types::{StatsResp, SystemMetricsDataResp, TrendResp, UserTrendsResp};
+use super::types::{
+    DashboardWindow, StatsResp, SystemMetricsDataResp, TopItem, TopResp, TrendResp, UserTrendsResp,
+};
 use crate::common::error::ServiceError;
+use chrono::{DateTime, NaiveDate, Timelike, Utc};
+use chrono_tz::Tz;
 use sqlx::SqlitePool;
+use std::collections::HashSet;
 
 pub struct DashboardRepository;
 
@@ -85,7 +90,11 @@ impl DashboardRepository {
         Ok(stats)
     }
 
-    pub async fn get_metrics(pool: &SqlitePool) -> Result<SystemMetricsDataResp, ServiceError> {
+    pub async fn get_metrics(
+        pool: &SqlitePool,
+        window: &DashboardWindow,
+    ) -> Result<SystemMetricsDataResp, ServiceError> {
+        let since = window.since_modifier();
         // 并行获取系统指标
         let (
             total_requests,
@@ -94,20 +103,23 @@ impl DashboardRepository {
         ) = tokio::join!(
             // 获取总请求数（从日志表统计）
             sqlx::query_scalar::<_, i64>(
-                "SELECT COUNT(*) FROM operation_logs WHERE created_at > datetime('now', '-7 day')"
+                "SELECT COUNT(*) FROM operation_logs WHERE created_at > datetime('now', ?)"
             )
+            .bind(&since)
             .fetch_one(pool),
 
             // 获取错误请求数（状态为 FAILED 或 ERROR）
             sqlx::query_scalar::<_, i64>(
-                "SELECT COUNT(*) FROM operation_logs WHERE status IN ('FAILED', 'ERROR') AND created_at > datetime('now', '-7 day')"
+                "SELECT COUNT(*) FROM operation_logs WHERE status IN ('FAILED', 'ERROR') AND created_at > datetime('now', ?)"
             )
+            .bind(&since)
             .fetch_one(pool),
 
             // 获取平均响应时间（毫秒）
             sqlx::query_scalar::<_, f64>(
-                "SELECT COALESCE(AVG(CAST(duration_ms AS REAL)), 0) FROM operation_logs WHERE created_at > datetime('now', '-7 day') AND duration_ms IS NOT NULL"
+                "SELECT COALESCE(AVG(CAST(duration_ms AS REAL)), 0) FROM operation_logs WHERE created_at > datetime('now', ?) AND duration_ms IS NOT NULL"
             )
+            .bind(&since)
             .fetch_one(pool)
         );
 
@@ -141,18 +153,24 @@ impl DashboardRepository {
             avg_response_time: avg_response_time_ms,
             error_rate,
             total_requests,
+            slow_requests: 0,
+            slow_queries: 0,
+            db_pool: Default::default(),
         };
 
         Ok(metrics)
     }
 
-    pub async fn get_trends(pool: &SqlitePool) -> Result<UserTrendsResp, ServiceError> {
+    pub async fn get_trends(
+        pool: &SqlitePool,
+        window: &DashboardWindow,
+    ) -> Result<UserTrendsResp, ServiceError> {
         // 并行获取趋势数据
         let (daily_logins, hourly_active) = tokio::join!(
-            // 获取最近30天的登录趋势
-            Self::get_daily_login_trends(pool),
+            // 获取窗口内的登录趋势
+            Self::get_daily_login_trends(pool, window),
             // 获取24小时活跃用户分布
-            Self::get_hourly_active_users(pool)
+            Self::get_hourly_active_users(pool, window)
         );
 
         let daily_logins = daily_logins?;
@@ -161,21 +179,26 @@ impl DashboardRepository {
         Ok(UserTrendsResp { daily_logins, hourly_active })
     }
 
-    /// 获取每日登录趋势（最近30天）
-    async fn get_daily_login_trends(pool: &SqlitePool) -> Result<Vec<TrendResp>, ServiceError> {
-        let daily_logins = sqlx::query_as(
+    /// 获取每日登录趋势（按窗口天数，按时区的自然日分组）
+    async fn get_daily_login_trends(
+        pool: &SqlitePool,
+        window: &DashboardWindow,
+    ) -> Result<Vec<TrendResp>, ServiceError> {
+        let days = window.day_starts(Utc::now());
+        let since = days.first().map_or(0, |(_, start)| start.timestamp());
+        let buckets: Vec<(i64, i64)> = sqlx::query_as(
             r#"
             SELECT
-                strftime('%Y-%m-%d', created_at) as date,
+                CAST(strftime('%s', created_at) AS INTEGER) / 900 as bucket,
                 COUNT(*) as count
             FROM operation_logs
             WHERE action = 'AUTH_LOGIN'
                 AND status = 'SUCCESS'
-                AND created_at > datetime('now', '-30 day')
-            GROUP BY DATE(created_at)
-            ORDER BY date
+                AND CAST(strftime('%s', created_at) AS INTEGER) >= ?
+            GROUP BY bucket
             "#,
         )
+        .bind(since)
         .fetch_all(pool)
         .await
         .map_err(|e| {
@@ -183,27 +206,22 @@ impl DashboardRepository {
             ServiceError::DatabaseQueryFailed
         })?;
 
-        Ok(daily_logins)
+        Ok(daily_counts(&buckets, &days))
     }
 
-    /// 获取24小时活跃用户分布
-    async fn get_hourly_active_users(pool: &SqlitePool) -> Result<Vec<TrendResp>, ServiceError> {
-        let hourly_active: Vec<TrendResp> = sqlx::query_as(
+    /// 获取24小时活跃用户分布（按时区的小时）
+    async fn get_hourly_active_users(
+        pool: &SqlitePool,
+        window: &DashboardWindow,
+    ) -> Result<Vec<TrendResp>, ServiceError> {
+        let activity: Vec<(i64, i64)> = sqlx::query_as(
             r#"
-            WITH RECURSIVE hour_series AS (
-                SELECT 0 as hour
-                UNION ALL
-                SELECT hour + 1 FROM hour_series WHERE hour < 23
-            )
-            SELECT
-                CAST(hs.hour AS TEXT) as date,
-                COALESCE(COUNT(DISTINCT ol.user_id), 0) as count
-            FROM hour_series hs
-            LEFT JOIN operation_logs ol ON CAST(strftime('%H', ol.created_at) AS INTEGER) = hs.hour
-                AND ol.created_at > datetime('now', '-24 hour')
-                AND ol.user_id IS NOT NULL
-            GROUP BY hs.hour
-            ORDER BY hs.hour
+            SELECT DISTINCT
+                CAST(strftime('%s', created_at) AS INTEGER) / 900 as bucket,
+                user_id
+            FROM operation_logs
+            WHERE created_at > datetime('now', '-24 hour')
+                AND user_id IS NOT NULL
             "#,
         )
         .fetch_all(pool)
@@ -213,6 +231,233 @@ impl DashboardRepository {
             ServiceError::DatabaseQueryFailed
         })?;
 
-        Ok(hourly_active)
+        Ok(hourly_active_users(&activity, window.tz))
+    }
+
+    pub async fn get_top(
+        pool: &SqlitePool,
+        window: &DashboardWindow,
+        limit: i64,
+    ) -> Result<TopResp, ServiceError> {
+        let since = window.since_modifier();
+        let (top_actions, top_users, top_error_endpoints) = tokio::join!(
+            // 最常见的操作
+            sqlx::query_as::<_, TopItem>(
+                "SELECT action AS name, COUNT(*) AS count
+                 FROM operation_logs
+                 WHERE created_at > datetime('now', ?)
+                 GROUP BY action
+                 ORDER BY count DESC, name ASC
+                 LIMIT ?",
+            )
+            .bind(&since)
+            .bind(limit)
+            .fetch_all(pool),
+            // 最活跃的用户
+            sqlx::query_as::<_, TopItem>(
+                "SELECT username AS name, COUNT(*) AS count
+                 FROM operation_logs
+                 WHERE created_at > datetime('now', ?) AND username IS NOT NULL AND username <> ''
+                 GROUP BY username
+                 ORDER BY count DESC, name ASC
+                 LIMIT ?",
+            )
+            .bind(&since)
+            .bind(limit)
+            .fetch_all(pool),
+            // 最常出错的接口：description 形如 "GET /api/path?query - 500"
+            sqlx::query_as::<_, TopItem>(
+                "WITH failed AS (
+                     SELECT substr(description, 1, instr(description, ' - ') - 1) AS request
+                     FROM operation_logs
+                     WHERE created_at > datetime('now', ?)
+                       AND status IN ('FAILED', 'ERROR')
+                       AND instr(description, ' - ') > 0
+                 )
+                 SELECT
+                     CASE WHEN instr(request, '?') > 0
+                          THEN substr(request, 1, instr(request, '?') - 1)
+                          ELSE request END AS name,
+                     COUNT(*) AS count
+                 FROM failed
+                 GROUP BY name
+                 ORDER BY count DESC, name ASC
+                 LIMIT ?",
+            )
+            .bind(&since)
+            .bind(limit)
+            .fetch_all(pool)
+        );
+
+        let top_actions = top_actions.map_err(|e| {
+            tracing::error!("Database error getting top actions: {:?}", e);
+            ServiceError::DatabaseQueryFailed
+        })?;
+        let top_users = top_users.map_err(|e| {
+            tracing::error!("Database error getting top users: {:?}", e);
+            ServiceError::DatabaseQueryFailed
+        })?;
+        let top_error_endpoints = top_error_endpoints.map_err(|e| {
+            tracing::error!("Database error getting top error endpoints: {:?}", e);
+            ServiceError::DatabaseQueryFailed
+        })?;
+
+        Ok(TopResp { top_actions, top_users, top_error_endpoints })
+    }
+}
+
+/// Trend queries count per 15 minutes of UTC time. Every UTC offset in use is a multiple
+/// of 15 minutes, so each bucket falls in a single local hour and day, and the offset is
+/// looked up per bucket or per day so windows spanning a DST change still group correctly.
+const TREND_BUCKET_SECS: i64 = 900;
+
+fn local_bucket_start(bucket: i64, tz: Tz) -> Option<DateTime<Tz>> {
+    DateTime::from_timestamp(bucket * TREND_BUCKET_SECS, 0).map(|at| at.with_timezone(&tz))
+}
+
+/// Sums `(bucket, count)` rows into the local days of
+/// [`DashboardWindow::day_starts`], zero for days without rows.
+fn daily_counts(buckets: &[(i64, i64)], days: &[(NaiveDate, DateTime<Utc>)]) -> Vec<TrendResp> {
+    let mut counts = vec![0; days.len()];
+    for &(bucket, count) in buckets {
+        let at = bucket * TREND_BUCKET_SECS;
+        let day = days.partition_point(|(_, start)| start.timestamp() <= at);
+        if let Some(slot) = day.checked_sub(1) {
+            counts[slot] += count;
+        }
+    }
+    days.iter()
+        .zip(counts)
+        .map(|((day, _), count)| TrendResp {
+            date: Some(day.format("%Y-%m-%d").to_string()),
+            count: Some(count),
+        })
+        .collect()
+}
+
+/// Distinct users per local hour `0..=23` from `(bucket, user_id)` rows.
+fn hourly_active_users(activity: &[(i64, i64)], tz: Tz) -> Vec<TrendResp> {
+    let mut hours: [HashSet<i64>; 24] = Default::default();
+    for &(bucket, user_id) in activity {
+        if let Some(at) = local_bucket_start(bucket, tz) {
+            hours[at.hour() as usize].insert(user_id);
+        }
+    }
+    hours
+        .iter()
+        .enumerate()
+        .map(|(hour, users)| TrendResp {
+            date: Some(hour.to_string()),
+            count: Some(users.len() as i64),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DashboardRepository, TREND_BUCKET_SECS, daily_counts, hourly_active_users};
+    use crate::features::dashboard::types::DashboardWindow;
+    use chrono::{DateTime, NaiveDate, Utc};
+    use chrono_tz::Tz;
+
+    fn bucket(rfc3339: &str) -> i64 {
+        DateTime::parse_from_rfc3339(rfc3339).expect("timestamp").timestamp() / TREND_BUCKET_SECS
+    }
+
+    fn at(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339).expect("timestamp").with_timezone(&Utc)
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).expect("date")
+    }
+
+    #[test]
+    fn daily_counts_follow_the_offset_of_each_day() {
+        // Berlin moves from +01:00 to +02:00 on 2024-03-31.
+        let window = DashboardWindow { days: 7, tz: Tz::Europe__Berlin };
+        let days = window.day_starts(at("2024-04-01T10:00:00Z"));
+        assert_eq!(days.len(), 7);
+        assert_eq!(days[5], (date(2024, 3, 31), at("2024-03-30T23:00:00Z")));
+        assert_eq!(days[6], (date(2024, 4, 1), at("2024-03-31T22:00:00Z")));
+
+        let buckets = [
+            (bucket("2024-03-20T12:00:00Z"), 9),
+            (bucket("2024-03-30T22:30:00Z"), 2),
+            (bucket("2024-03-31T21:30:00Z"), 1),
+            (bucket("2024-03-31T22:30:00Z"), 4),
+        ];
+        let counts: Vec<_> = daily_counts(&buckets, &days)
+            .into_iter()
+            .map(|trend| (trend.date.unwrap(), trend.count.unwrap()))
+            .collect();
+        assert_eq!(counts[0], ("2024-03-26".to_string(), 0));
+        assert_eq!(
+            counts[4..],
+            [
+                ("2024-03-30".to_string(), 2),
+                ("2024-03-31".to_string(), 1),
+                ("2024-04-01".to_string(), 4)
+            ]
+        );
+    }
+
+    #[test]
+    fn days_start_at_the_first_local_time_when_dst_skips_midnight() {
+        // Santiago went from -04:00 to -03:00 at midnight on 2022-09-11.
+        let window = DashboardWindow { days: 2, tz: Tz::America__Santiago };
+        assert_eq!(
+            window.day_starts(at("2022-09-11T12:00:00Z")),
+            [
+                (date(2022, 9, 10), at("2022-09-10T04:00:00Z")),
+                (date(2022, 9, 11), at("2022-09-11T04:00:00Z"))
+            ]
+        );
+    }
+
+    #[test]
+    fn hourly_active_users_count_each_user_once_per_local_hour() {
+        let activity = [
+            (bucket("2024-05-01T00:05:00Z"), 1),
+            (bucket("2024-05-01T00:50:00Z"), 1),
+            (bucket("2024-05-01T00:20:00Z"), 2),
+            (bucket("2024-05-01T13:00:00Z"), 1),
+        ];
+        let hours = hourly_active_users(&activity, Tz::Asia__Shanghai);
+        assert_eq!(hours.len(), 24);
+        assert_eq!((hours[8].date.as_deref(), hours[8].count), (Some("8"), Some(2)));
+        assert_eq!(hours[21].count, Some(1));
+        assert_eq!(hours[0].count, Some(0));
+    }
+
+    #[tokio::test]
+    async fn top_groups_error_endpoints_without_query_strings() {
+        let pool = crate::infra::db::test_pool().await;
+        for (username, action, description, status) in [
+            ("alice", "HTTP_GET", "GET /api/system/users?current=1 - 500", "ERROR"),
+            ("alice", "HTTP_GET", "GET /api/system/users?current=2 - 500", "ERROR"),
+            ("bob", "HTTP_POST", "POST /api/system/roles - 200", "SUCCESS"),
+        ] {
+            sqlx::query(
+                "INSERT INTO operation_logs (username, action, description, status, created_at)
+                 VALUES (?, ?, ?, ?, datetime('now'))",
+            )
+            .bind(username)
+            .bind(action)
+            .bind(description)
+            .bind(status)
+            .execute(&pool)
+            .await
+            .expect("insert log");
+        }
+
+        let window = DashboardWindow { days: 7, tz: Tz::UTC };
+        let top = DashboardRepository::get_top(&pool, &window, 10).await.expect("top");
+
+        assert_eq!((top.top_actions[0].name.as_str(), top.top_actions[0].count), ("HTTP_GET", 2));
+        assert_eq!((top.top_users[0].name.as_str(), top.top_users[0].count), ("alice", 2));
+        assert_eq!(top.top_error_endpoints.len(), 1);
+        assert_eq!(top.top_error_endpoints[0].name, "GET /api/system/users");
+        assert_eq!(top.top_error_endpoints[0].count, 2);
     }
 }
@@ -1,24 +1,273 @@
-use crate::common::error::ServiceError;
+use crate::{
+    common::{cache::TtlCache, error::ServiceError},
+    infra::{
+        config::AppConfig,
+        db::DbExecutor,
+        host_metrics::{HOST_METRICS, SAMPLE_INTERVAL},
+        route_metrics::{ROUTE_METRICS, RouteLatency, WINDOW_MINUTES},
+        slow_log::SLOW_LOG,
+        system_info::SystemUtils,
+    },
+};
 
 use super::{
     repo::DashboardRepository,
-    types::{StatsResp, SystemMetricsDataResp, UserTrendsResp},
+    types::{
+        DashboardQuery, DashboardSnapshotResp, DashboardStreamQuery, DashboardWindow,
+        EndpointsQuery, EndpointsResp, HostMetricsQuery, HostMetricsResp, StatsResp,
+        SystemMetricsDataResp, TopQuery, TopResp, UserTrendsResp,
+    },
 };
 
+use chrono::Utc;
+use chrono_tz::Tz;
+use once_cell::sync::OnceCell;
 use sqlx::SqlitePool;
+use std::{hash::Hash, time::Duration};
+
+const ALLOWED_WINDOW_DAYS: [i64; 3] = [7, 30, 90];
+const DEFAULT_METRICS_DAYS: i64 = 7;
+const DEFAULT_TRENDS_DAYS: i64 = 30;
+const DEFAULT_TOP_LIMIT: i64 = 10;
+const MAX_TOP_LIMIT: i64 = 50;
+const MAX_HOST_HISTORY_MINUTES: i64 = 60;
+const DEFAULT_STREAM_INTERVAL_SECS: u64 = 15;
+const STREAM_INTERVAL_SECS: std::ops::RangeInclusive<u64> = 5..=300;
+
+static STATS_CACHE: OnceCell<TtlCache<(), StatsResp>> = OnceCell::new();
+static METRICS_CACHE: OnceCell<TtlCache<DashboardWindow, SystemMetricsDataResp>> = OnceCell::new();
+static TRENDS_CACHE: OnceCell<TtlCache<DashboardWindow, UserTrendsResp>> = OnceCell::new();
+static TOP_CACHE: OnceCell<TtlCache<(DashboardWindow, i64), TopResp>> = OnceCell::new();
+
+/// Aggregates are cached for `RUSTZEN_DASHBOARD_CACHE_TTL_SECS` so dashboard refreshes
+/// don't rescan `operation_logs`.
+fn cache<K: Eq + Hash, V: Clone>(
+    cell: &'static OnceCell<TtlCache<K, V>>,
+    config: &AppConfig,
+) -> &'static TtlCache<K, V> {
+    cell.get_or_init(|| TtlCache::new(Duration::from_secs(config.cache.dashboard_cache_ttl_secs)))
+}
 
 pub struct DashboardService;
 
 impl DashboardService {
-    pub async fn get_stats(pool: &SqlitePool) -> Result<StatsResp, ServiceError> {
-        DashboardRepository::get_stats(pool).await
+    pub async fn get_stats(
+        config: &AppConfig,
+        pool: &SqlitePool,
+    ) -> Result<StatsResp, ServiceError> {
+        let cache = cache(&STATS_CACHE, config);
+        if let Some(stats) = cache.get(&()) {
+            return Ok(stats);
+        }
+        let stats = DashboardRepository::get_stats(pool).await?;
+        cache.insert((), stats.clone());
+        Ok(stats)
+    }
+
+    pub async fn get_metrics(
+        config: &AppConfig,
+        db: &DbExecutor,
+        query: DashboardQuery,
+    ) -> Result<SystemMetricsDataResp, ServiceError> {
+        let window = Self::resolve_window(&query, DEFAULT_METRICS_DAYS, Tz::UTC)?;
+        let cache = cache(&METRICS_CACHE, config);
+        let mut metrics = match cache.get(&window) {
+            Some(metrics) => metrics,
+            None => {
+                let metrics = DashboardRepository::get_metrics(db.read(), &window).await?;
+                cache.insert(window, metrics.clone());
+                metrics
+            }
+        };
+        // Live counters, not part of the cached window aggregate.
+        let slow = SLOW_LOG.snapshot();
+        metrics.slow_requests = slow.slow_requests_total;
+        metrics.slow_queries = slow.slow_queries_total;
+        metrics.db_pool = db.health();
+        Ok(metrics)
+    }
+
+    /// Login and activity trends grouped by the days and hours of `query.timezone`, or of
+    /// `default_tz` when the query names none.
+    pub async fn get_trends(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        query: DashboardQuery,
+        default_tz: Tz,
+    ) -> Result<UserTrendsResp, ServiceError> {
+        let window = Self::resolve_window(&query, DEFAULT_TRENDS_DAYS, default_tz)?;
+        let cache = cache(&TRENDS_CACHE, config);
+        if let Some(trends) = cache.get(&window) {
+            return Ok(trends);
+        }
+        let trends = DashboardRepository::get_trends(pool, &window).await?;
+        cache.insert(window, trends.clone());
+        Ok(trends)
+    }
+
+    /// Most frequent actions, most active users and most failing endpoints in the window.
+    pub async fn get_top(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        query: TopQuery,
+    ) -> Result<TopResp, ServiceError> {
+        let window = Self::resolve_window(
+            &DashboardQuery { days: query.days, timezone: None },
+            DEFAULT_METRICS_DAYS,
+            Tz::UTC,
+        )?;
+        let limit = query.limit.unwrap_or(DEFAULT_TOP_LIMIT).clamp(1, MAX_TOP_LIMIT);
+        let cache = cache(&TOP_CACHE, config);
+        if let Some(top) = cache.get(&(window, limit)) {
+            return Ok(top);
+        }
+        let top = DashboardRepository::get_top(pool, &window, limit).await?;
+        cache.insert((window, limit), top.clone());
+        Ok(top)
+    }
+
+    /// The slowest and most failing routes of this instance over the last hour, from the
+    /// in-memory histograms rather than the operation log.
+    pub fn endpoints(query: EndpointsQuery) -> EndpointsResp {
+        let limit = query.limit.unwrap_or(DEFAULT_TOP_LIMIT).clamp(1, MAX_TOP_LIMIT) as usize;
+        let routes = ROUTE_METRICS.summary();
+        let mut slowest = routes.clone();
+        slowest.sort_by(|a, b| b.p95_ms.cmp(&a.p95_ms).then_with(|| b.avg_ms.total_cmp(&a.avg_ms)));
+        slowest.truncate(limit);
+        let mut most_errors: Vec<RouteLatency> =
+            routes.into_iter().filter(|route| route.errors > 0).collect();
+        most_errors.sort_by(|a, b| {
+            b.error_rate.total_cmp(&a.error_rate).then_with(|| b.errors.cmp(&a.errors))
+        });
+        most_errors.truncate(limit);
+        EndpointsResp { window_minutes: WINDOW_MINUTES, slowest, most_errors }
+    }
+
+    /// CPU, memory, disk and load samples of this instance over the last `minutes`.
+    pub fn host_metrics(query: HostMetricsQuery) -> Result<HostMetricsResp, ServiceError> {
+        let minutes = query.minutes.unwrap_or(MAX_HOST_HISTORY_MINUTES);
+        if !(1..=MAX_HOST_HISTORY_MINUTES).contains(&minutes) {
+            return Err(ServiceError::InvalidOperation(format!(
+                "Host metrics history must be 1 to 60 minutes, got {}",
+                minutes
+            )));
+        }
+        let since = Utc::now() - chrono::Duration::minutes(minutes);
+        Ok(HostMetricsResp {
+            interval_secs: SAMPLE_INTERVAL.as_secs(),
+            current: HOST_METRICS.latest(),
+            history: HOST_METRICS.history(Some(since)),
+        })
+    }
+
+    /// Stats, host health and metrics in one payload for the live stream. Each part comes
+    /// from its cache, so streaming clients share the aggregates the endpoints compute.
+    pub async fn snapshot(
+        config: &AppConfig,
+        db: &DbExecutor,
+        query: DashboardQuery,
+    ) -> Result<DashboardSnapshotResp, ServiceError> {
+        Ok(DashboardSnapshotResp {
+            stats: Self::get_stats(config, db.read()).await?,
+            health: SystemUtils::get_system_info(),
+            metrics: Self::get_metrics(config, db, query).await?,
+        })
+    }
+
+    /// Checks a stream query up front, returning the snapshot interval and metrics query.
+    pub fn resolve_stream(
+        query: DashboardStreamQuery,
+    ) -> Result<(Duration, DashboardQuery), ServiceError> {
+        let interval = query.interval.unwrap_or(DEFAULT_STREAM_INTERVAL_SECS);
+        if !STREAM_INTERVAL_SECS.contains(&interval) {
+            return Err(ServiceError::InvalidOperation(format!(
+                "Stream interval must be 5 to 300 seconds, got {}",
+                interval
+            )));
+        }
+        let query = DashboardQuery { days: query.days, timezone: None };
+        Self::resolve_window(&query, DEFAULT_METRICS_DAYS, Tz::UTC)?;
+        Ok((Duration::from_secs(interval), query))
+    }
+
+    /// Entries across all dashboard caches, for the system info panel.
+    pub fn cached_entry_count() -> usize {
+        STATS_CACHE.get().map_or(0, TtlCache::len)
+            + METRICS_CACHE.get().map_or(0, TtlCache::len)
+            + TRENDS_CACHE.get().map_or(0, TtlCache::len)
+            + TOP_CACHE.get().map_or(0, TtlCache::len)
+    }
+
+    /// Validates the requested window and timezone; `default_tz` applies when the query
+    /// names no timezone.
+    pub fn resolve_window(
+        query: &DashboardQuery,
+        default_days: i64,
+        default_tz: Tz,
+    ) -> Result<DashboardWindow, ServiceError> {
+        let days = query.days.unwrap_or(default_days);
+        if !ALLOWED_WINDOW_DAYS.contains(&days) {
+            return Err(ServiceError::InvalidOperation(format!(
+                "Dashboard window must be one of 7, 30 or 90 days, got {}",
+                days
+            )));
+        }
+
+        let tz = match query.timezone.as_deref() {
+            Some(timezone) => timezone.parse::<Tz>().map_err(|_| {
+                ServiceError::InvalidOperation(format!("Unknown timezone: {}", timezone))
+            })?,
+            None => default_tz,
+        };
+
+        Ok(DashboardWindow { days, tz })
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DashboardService;
+    use crate::features::dashboard::types::{DashboardQuery, DashboardStreamQuery};
+    use chrono_tz::Tz;
+    use std::time::Duration;
+
+    #[test]
+    fn window_accepts_known_days_and_timezones() {
+        let query = DashboardQuery { days: Some(90), timezone: Some("UTC".to_string()) };
+        let window = DashboardService::resolve_window(&query, 30, Tz::Asia__Tokyo).expect("window");
+        assert_eq!((window.days, window.tz), (90, Tz::UTC));
+        assert_eq!(window.since_modifier(), "-90 day");
 
-    pub async fn get_metrics(pool: &SqlitePool) -> Result<SystemMetricsDataResp, ServiceError> {
-        DashboardRepository::get_metrics(pool).await
+        let query = DashboardQuery { days: None, timezone: Some("Asia/Shanghai".to_string()) };
+        let window = DashboardService::resolve_window(&query, 30, Tz::UTC).expect("window");
+        assert_eq!((window.days, window.tz), (30, Tz::Asia__Shanghai));
+
+        let query = DashboardQuery { days: None, timezone: None };
+        let window = DashboardService::resolve_window(&query, 30, Tz::Asia__Tokyo).expect("window");
+        assert_eq!(window.tz, Tz::Asia__Tokyo);
+
+        let query = DashboardQuery { days: Some(14), timezone: None };
+        assert!(DashboardService::resolve_window(&query, 30, Tz::UTC).is_err());
+        let query = DashboardQuery { days: None, timezone: Some("Mars/Olympus".to_string()) };
+        assert!(DashboardService::resolve_window(&query, 30, Tz::UTC).is_err());
     }
 
-    pub async fn get_trends(pool: &SqlitePool) -> Result<UserTrendsResp, ServiceError> {
-        DashboardRepository::get_trends(pool).await
+    #[test]
+    fn stream_interval_defaults_to_fifteen_seconds_within_bounds() {
+        let (interval, query) =
+            DashboardService::resolve_stream(DashboardStreamQuery::default()).expect("stream");
+        assert_eq!(interval, Duration::from_secs(15));
+        assert_eq!(query.days, None);
+
+        let query = DashboardStreamQuery { interval: Some(5), days: Some(30) };
+        let (interval, query) = DashboardService::resolve_stream(query).expect("stream");
+        assert_eq!((interval, query.days), (Duration::from_secs(5), Some(30)));
+
+        for interval in [0, 4, 301] {
+            let query = DashboardStreamQuery { interval: Some(interval), days: None };
+            assert!(DashboardService::resolve_stream(query).is_err(), "{}", interval);
+        }
+        let query = DashboardStreamQuery { interval: None, days: Some(14) };
+        assert!(DashboardService::resolve_stream(query).is_err());
     }
 }
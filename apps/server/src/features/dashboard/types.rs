@@ -1,6 +1,13 @@
+use crate::infra::{
+    db::DbPoolHealth, host_metrics::HostSample, route_metrics::RouteLatency,
+    system_info::SystemInfo,
+};
+
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StatsResp {
     pub total_users: i64,
@@ -10,24 +17,160 @@ pub struct StatsResp {
     pub pending_users: i64,
 }
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SystemMetricsDataResp {
     pub avg_response_time: i64,
     pub error_rate: f64,
     pub total_requests: i64,
+    /// Slow requests and SQL statements since this process started.
+    pub slow_requests: u64,
+    pub slow_queries: u64,
+    /// Live database pool usage.
+    pub db_pool: DbPoolHealth,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, sqlx::FromRow)]
 #[serde(rename_all = "camelCase")]
 pub struct TrendResp {
     pub date: Option<String>,
     pub count: Option<i64>,
 }
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UserTrendsResp {
     pub daily_logins: Vec<TrendResp>,
     pub hourly_active: Vec<TrendResp>,
 }
+
+/// Time window query shared by the aggregate dashboard endpoints.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardQuery {
+    /// Window length in days: 7, 30, or 90.
+    pub days: Option<i64>,
+    /// IANA timezone used to bucket trend dates; defaults to the caller's preference,
+    /// then `RUSTZEN_TIMEZONE`.
+    pub timezone: Option<String>,
+}
+
+/// Query for the top-N endpoint.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopQuery {
+    /// Window length in days: 7, 30, or 90.
+    pub days: Option<i64>,
+    /// Rows per list, 1 to 50.
+    pub limit: Option<i64>,
+}
+
+/// Query for the endpoint latency ranking.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointsQuery {
+    /// Rows per list, 1 to 50.
+    pub limit: Option<i64>,
+}
+
+/// Slowest and most error-prone routes this instance served in the last hour.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointsResp {
+    pub window_minutes: i64,
+    /// By p95 latency, then average.
+    pub slowest: Vec<RouteLatency>,
+    /// Routes with errors, by error rate, then error count.
+    pub most_errors: Vec<RouteLatency>,
+}
+
+/// Query for the host metrics history.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostMetricsQuery {
+    /// History length in minutes, 1 to 60; defaults to 60.
+    pub minutes: Option<i64>,
+}
+
+/// Host resource usage sampled by this instance.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostMetricsResp {
+    /// Seconds between samples.
+    pub interval_secs: u64,
+    /// Latest sample; `None` until the first one is taken after startup.
+    pub current: Option<HostSample>,
+    /// Samples in the requested window, oldest first.
+    pub history: Vec<HostSample>,
+}
+
+/// Query for the live dashboard stream.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardStreamQuery {
+    /// Seconds between snapshots, 5 to 300; defaults to 15.
+    pub interval: Option<u64>,
+    /// Metrics window in days: 7, 30, or 90.
+    pub days: Option<i64>,
+}
+
+/// One `snapshot` event of the live dashboard stream.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardSnapshotResp {
+    pub stats: StatsResp,
+    pub health: SystemInfo,
+    pub metrics: SystemMetricsDataResp,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TopItem {
+    pub name: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopResp {
+    pub top_actions: Vec<TopItem>,
+    pub top_users: Vec<TopItem>,
+    pub top_error_endpoints: Vec<TopItem>,
+}
+
+/// Resolved aggregate window; also the cache key for windowed results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DashboardWindow {
+    pub days: i64,
+    /// Timezone whose calendar days and hours group the trends.
+    pub tz: Tz,
+}
+
+impl DashboardWindow {
+    /// SQLite `datetime('now', ?)` modifier for the window start.
+    pub fn since_modifier(&self) -> String {
+        format!("-{} day", self.days)
+    }
+
+    /// The window's local calendar days up to the one holding `now`, oldest first, each
+    /// with the UTC instant it starts. Every day is converted on its own date, so days on
+    /// either side of a DST change get their own offset.
+    pub fn day_starts(&self, now: DateTime<Utc>) -> Vec<(NaiveDate, DateTime<Utc>)> {
+        let today = now.with_timezone(&self.tz).date_naive();
+        (0..self.days)
+            .rev()
+            .map(|ago| today - Duration::days(ago))
+            .map(|day| (day, local_day_start(self.tz, day)))
+            .collect()
+    }
+}
+
+/// UTC instant `day` starts in `tz`. Where a DST change skips midnight, the day starts at
+/// the first local quarter hour that exists.
+fn local_day_start(tz: Tz, day: NaiveDate) -> DateTime<Utc> {
+    let midnight = day.and_hms_opt(0, 0, 0).expect("midnight");
+    (0..96)
+        .map(|quarter| midnight + Duration::minutes(15 * quarter))
+        .find_map(|local| tz.from_local_datetime(&local).earliest())
+        .map_or_else(|| midnight.and_utc(), |start| start.with_timezone(&Utc))
+}
@@ -12,15 +12,16 @@ use zip::ZipArchive;
 
 use crate::{
     common::{
-        api::ApiResponse,
+        api::{ApiResponse, PageMeta},
         error::ServiceError,
+        files::map_multipart_error,
         pagination::{Pagination, PaginationQuery},
     },
     features::manage::deploy::types::{
         DeployComponent, DeployVersionRequest, DeploymentItem, DeploymentPayload,
         ExpireVersionRequest, ListDeploymentsQuery,
     },
-    infra::config::CONFIG,
+    infra::config::AppConfig,
 };
 
 use super::repo::DeployRepository;
@@ -35,12 +36,14 @@ const SERVER_MARKER_PREFIX: &[u8] = b"RUSTZEN_ADMIN_MARKER\ncomponent=server\n";
 #[derive(Clone)]
 pub struct DeployService {
     repo: Arc<DeployRepository>,
+    runtime_root: PathBuf,
 }
 
 impl DeployService {
-    pub fn new(pool: sqlx::SqlitePool) -> Self {
+    pub fn new(config: &AppConfig, pool: sqlx::SqlitePool) -> Self {
         Self {
             repo: Arc::new(DeployRepository::new(pool)),
+            runtime_root: resolve_runtime_path(&config.runtime_root_dir()),
         }
     }
 
@@ -56,11 +59,9 @@ impl DeployService {
             current: query.current,
             page_size: query.page_size,
         });
-        let (items, total) = self
-            .repo
-            .list(&query, pagination.offset.into(), pagination.limit.into())
-            .await?;
-        Ok(ApiResponse::new(items, Some(total)))
+        let (items, total) =
+            self.repo.list(&query, pagination.offset.into(), pagination.limit.into()).await?;
+        Ok(ApiResponse::with_page(items, total, PageMeta::new(pagination, total)))
     }
 
     pub async fn upload(&self, mut multipart: Multipart) -> Result<DeploymentItem, ServiceError> {
@@ -73,7 +74,7 @@ impl DeployService {
         while let Some(field) = multipart
             .next_field()
             .await
-            .map_err(|_| ServiceError::InvalidOperation("Invalid multipart data".to_string()))?
+            .map_err(|e| map_multipart_error(e, "Invalid multipart data"))?
         {
             let Some(name) = field.name().map(str::to_string) else {
                 continue;
@@ -81,11 +82,9 @@ impl DeployService {
 
             match name.as_str() {
                 "component" => {
-                    component = Some(parse_component(
-                        field.text().await.map_err(|_| {
-                            ServiceError::InvalidOperation("Invalid component field".to_string())
-                        })?,
-                    )?);
+                    component = Some(parse_component(field.text().await.map_err(|_| {
+                        ServiceError::InvalidOperation("Invalid component field".to_string())
+                    })?)?);
                 }
                 "version" => {
                     version = Some(validate_version(field.text().await.map_err(|_| {
@@ -101,17 +100,18 @@ impl DeployService {
                     notes = normalize_optional(field.text().await.unwrap_or_default());
                 }
                 "file" => {
-                    let data = field.bytes().await.map_err(|_| {
-                        ServiceError::InvalidOperation("Failed to read uploaded file".to_string())
-                    })?;
+                    let data = field
+                        .bytes()
+                        .await
+                        .map_err(|e| map_multipart_error(e, "Failed to read uploaded file"))?;
                     file_data = Some(data.to_vec());
                 }
                 _ => {}
             }
         }
 
-        let component =
-            component.ok_or_else(|| ServiceError::InvalidOperation("component is required".into()))?;
+        let component = component
+            .ok_or_else(|| ServiceError::InvalidOperation("component is required".into()))?;
         let version =
             version.ok_or_else(|| ServiceError::InvalidOperation("version is required".into()))?;
         let file_data =
@@ -142,7 +142,8 @@ impl DeployService {
         }
 
         let file_hash = sha256_hex(&file_data);
-        let file_path = save_version_file(&component, &version, &arch, &file_data).await?;
+        let file_path =
+            save_version_file(&self.runtime_root, &component, &version, &arch, &file_data).await?;
 
         self.repo
             .insert(&DeploymentPayload {
@@ -237,15 +238,12 @@ impl DeployService {
         version: &DeploymentItem,
         request: &DeployVersionRequest,
     ) -> Result<(), ServiceError> {
-        let target_bin = runtime_root_dir().join("bin").join(SERVER_BINARY_NAME);
+        let target_bin = self.runtime_root.join("bin").join(SERVER_BINARY_NAME);
         fs::create_dir_all(parent_dir(&target_bin)?).map_err(|err| {
             ServiceError::InvalidOperation(format!("Failed to create bin directory: {err}"))
         })?;
 
-        if let Some(current) = self
-            .repo
-            .find_current(&version.component, &version.arch)
-            .await?
+        if let Some(current) = self.repo.find_current(&version.component, &version.arch).await?
             && current.id == version.id
             && fs::read_link(&target_bin)
                 .map(|target| target == Path::new(&version.file_path))
@@ -256,9 +254,10 @@ impl DeployService {
 
         prepare_server_restart().await?;
 
-        let old_target = swap_symlink(&target_bin, Path::new(&version.file_path)).map_err(|err| {
-            ServiceError::InvalidOperation(format!("Failed to switch server binary: {err}"))
-        })?;
+        let old_target =
+            swap_symlink(&target_bin, Path::new(&version.file_path)).map_err(|err| {
+                ServiceError::InvalidOperation(format!("Failed to switch server binary: {err}"))
+            })?;
 
         if let Err(err) = restart_server().await {
             if let Err(restore_err) = restore_symlink(&target_bin, old_target.as_deref()) {
@@ -297,7 +296,7 @@ impl DeployService {
             return Ok(());
         }
 
-        let web_root = runtime_root_dir().join("web");
+        let web_root = self.runtime_root.join("web");
         let tmp_dir = web_root.join(format!(".deploy-{}", version.id));
         if tmp_dir.exists() {
             fs::remove_dir_all(&tmp_dir).map_err(|err| {
@@ -335,7 +334,9 @@ impl DeployService {
         remove_path_if_exists(&prev_dist)?;
         if dist_dir.exists() {
             fs::rename(&dist_dir, &prev_dist).map_err(|err| {
-                ServiceError::InvalidOperation(format!("Failed to archive previous web dist: {err}"))
+                ServiceError::InvalidOperation(format!(
+                    "Failed to archive previous web dist: {err}"
+                ))
             })?;
         }
         fs::rename(&new_dist, &dist_dir).map_err(|err| {
@@ -354,7 +355,10 @@ impl DeployService {
             .await
         {
             if let Err(restore_err) = restore_web_dist(&dist_dir, &prev_dist) {
-                tracing::error!("Failed to rollback web dist after database error: {}", restore_err);
+                tracing::error!(
+                    "Failed to rollback web dist after database error: {}",
+                    restore_err
+                );
             }
             return Err(err);
         }
@@ -365,12 +369,13 @@ impl DeployService {
 }
 
 async fn save_version_file(
+    root: &Path,
     component: &DeployComponent,
     version: &str,
     arch: &str,
     file_data: &[u8],
 ) -> Result<PathBuf, ServiceError> {
-    let path = version_file_path(component, version, arch)?;
+    let path = version_file_path(root, component, version, arch)?;
     if path.exists() {
         return Err(ServiceError::InvalidOperation(format!(
             "Deploy file already exists: {}",
@@ -392,15 +397,15 @@ async fn save_version_file(
 }
 
 fn version_file_path(
+    root: &Path,
     component: &DeployComponent,
     version: &str,
     arch: &str,
 ) -> Result<PathBuf, ServiceError> {
-    let root = runtime_root_dir();
     match component {
-        DeployComponent::Server => Ok(root
-            .join("versions")
-            .join(format!("server-{version}-{arch}"))),
+        DeployComponent::Server => {
+            Ok(root.join("versions").join(format!("server-{version}-{arch}")))
+        }
         DeployComponent::Web => Ok(root.join("web").join(format!("web-{version}.zip"))),
     }
 }
@@ -451,13 +456,8 @@ fn validate_server_file(file_data: &[u8], expected_arch: &str) -> Result<(), Ser
             "server file must be an executable binary".to_string(),
         ));
     }
-    if !file_data
-        .windows(SERVER_MARKER_PREFIX.len())
-        .any(|window| window == SERVER_MARKER_PREFIX)
-    {
-        return Err(ServiceError::InvalidOperation(
-            "server file marker check failed".to_string(),
-        ));
+    if !file_data.windows(SERVER_MARKER_PREFIX.len()).any(|window| window == SERVER_MARKER_PREFIX) {
+        return Err(ServiceError::InvalidOperation("server file marker check failed".to_string()));
     }
     if let Some(detected_arch) = detect_binary_arch(file_data)?
         && detected_arch != expected_arch
@@ -512,9 +512,7 @@ fn validate_web_file(file_data: &[u8]) -> Result<(), ServiceError> {
         if name == "dist/index.html" {
             has_index = true;
         }
-        if name.starts_with("dist/assets/")
-            && (name.ends_with(".js") || name.ends_with(".css"))
-        {
+        if name.starts_with("dist/assets/") && (name.ends_with(".js") || name.ends_with(".css")) {
             has_asset = true;
         }
         if name == WEB_MARKER_FILE {
@@ -564,9 +562,7 @@ fn validate_web_marker<R: Read>(reader: &mut R) -> Result<(), ServiceError> {
         .map_err(|_| ServiceError::InvalidOperation("web marker is not valid JSON".to_string()))?;
 
     if marker.get("component").and_then(|value| value.as_str()) != Some("web") {
-        return Err(ServiceError::InvalidOperation(
-            "web marker component must be web".to_string(),
-        ));
+        return Err(ServiceError::InvalidOperation("web marker component must be web".to_string()));
     }
     if marker.get("build_id").and_then(|value| value.as_str()) != Some("manual") {
         return Err(ServiceError::InvalidOperation(
@@ -618,9 +614,7 @@ fn parse_component(value: String) -> Result<DeployComponent, ServiceError> {
     match value.trim() {
         "server" => Ok(DeployComponent::Server),
         "web" => Ok(DeployComponent::Web),
-        _ => Err(ServiceError::InvalidOperation(
-            "component must be server or web".to_string(),
-        )),
+        _ => Err(ServiceError::InvalidOperation("component must be server or web".to_string())),
     }
 }
 
@@ -631,10 +625,7 @@ fn validate_version(value: String) -> Result<String, ServiceError> {
             "version must be at most 64 characters".to_string(),
         ));
     }
-    if value
-        .chars()
-        .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '.' | '_' | '-'))
-    {
+    if value.chars().all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '.' | '_' | '-')) {
         Ok(value)
     } else {
         Err(ServiceError::InvalidOperation(
@@ -647,9 +638,7 @@ fn normalize_arch(value: &str) -> Result<String, ServiceError> {
     match value.trim().to_ascii_lowercase().as_str() {
         "x86_64" | "amd64" => Ok("x86_64".to_string()),
         "aarch64" | "arm64" => Ok("aarch64".to_string()),
-        _ => Err(ServiceError::InvalidOperation(
-            "arch must be x86_64 or aarch64".to_string(),
-        )),
+        _ => Err(ServiceError::InvalidOperation("arch must be x86_64 or aarch64".to_string())),
     }
 }
 
@@ -710,11 +699,7 @@ fn detect_macho_arch(file_data: &[u8]) -> Result<Option<String>, ServiceError> {
 }
 
 fn swap_symlink(target_link: &Path, new_target: &Path) -> std::io::Result<Option<PathBuf>> {
-    let old_target = if target_link.exists() {
-        fs::read_link(target_link).ok()
-    } else {
-        None
-    };
+    let old_target = if target_link.exists() { fs::read_link(target_link).ok() } else { None };
     if target_link.exists() && old_target.is_none() {
         fs::remove_file(target_link)?;
     }
@@ -722,10 +707,7 @@ fn swap_symlink(target_link: &Path, new_target: &Path) -> std::io::Result<Option
         .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err.to_string()))?;
     let tmp_link = parent.join(format!(
         ".{}.tmp",
-        target_link
-            .file_name()
-            .and_then(|name| name.to_str())
-            .unwrap_or("rustzen-admin")
+        target_link.file_name().and_then(|name| name.to_str()).unwrap_or("rustzen-admin")
     ));
     if tmp_link.exists() {
         let _ = fs::remove_file(&tmp_link);
@@ -747,10 +729,7 @@ fn restore_symlink(target_link: &Path, old_target: Option<&Path>) -> std::io::Re
         .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "missing parent"))?;
     let tmp_link = parent.join(format!(
         ".{}.tmp",
-        target_link
-            .file_name()
-            .and_then(|name| name.to_str())
-            .unwrap_or("rustzen-admin")
+        target_link.file_name().and_then(|name| name.to_str()).unwrap_or("rustzen-admin")
     ));
     if tmp_link.exists() {
         let _ = fs::remove_file(&tmp_link);
@@ -842,17 +821,11 @@ fn set_executable(path: &Path) -> Result<(), ServiceError> {
     Ok(())
 }
 
-fn runtime_root_dir() -> PathBuf {
-    resolve_runtime_path(&CONFIG.runtime_root_dir())
-}
-
 fn resolve_runtime_path(path: &Path) -> PathBuf {
     if path.is_absolute() {
         path.to_path_buf()
     } else {
-        std::env::current_dir()
-            .map(|cwd| cwd.join(path))
-            .unwrap_or_else(|_| path.to_path_buf())
+        std::env::current_dir().map(|cwd| cwd.join(path)).unwrap_or_else(|_| path.to_path_buf())
     }
 }
 
@@ -869,12 +842,8 @@ fn remove_path_if_exists(path: &Path) -> Result<(), ServiceError> {
     let metadata = fs::symlink_metadata(path).map_err(|err| {
         ServiceError::InvalidOperation(format!("Failed to read path metadata: {err}"))
     })?;
-    if metadata.is_dir() {
-        fs::remove_dir_all(path)
-    } else {
-        fs::remove_file(path)
-    }
-    .map_err(|err| ServiceError::InvalidOperation(format!("Failed to remove path: {err}")))
+    if metadata.is_dir() { fs::remove_dir_all(path) } else { fs::remove_file(path) }
+        .map_err(|err| ServiceError::InvalidOperation(format!("Failed to remove path: {err}")))
 }
 
 fn is_zip(bytes: &[u8]) -> bool {
@@ -1,24 +1,76 @@
 use super::{
     service::DictService,
     types::{
-        CreateDictRequest, DictItemResp, DictQuery, UpdateDictPayload, UpdateDictStatusPayload,
+        CreateDictRequest, DictItemResp, DictQuery, ReorderDictPayload, UpdateDictPayload,
+        UpdateDictStatusPayload,
     },
 };
-use crate::common::api::{ApiResponse, AppResult, DictOptionsQuery, OptionItem};
+use crate::{
+    common::{
+        api::{ApiResponse, AppResult, DictOptionsQuery, OptionItem, OptionsQuery, PageMeta},
+        error::ServiceError,
+        export::Exporter,
+        i18n::{ReplaceTranslationsPayload, Translation},
+        pagination::{Pagination, PaginationQuery},
+    },
+    features::{
+        account::service::AccountService,
+        system::{
+            export_job::types::ExportResource,
+            export_template::{service::ExportTemplateService, types::ExportTemplateParam},
+        },
+    },
+    infra::{config::AppConfig, db::DbExecutor},
+};
 
 use axum::{
-    Json,
-    extract::{Path, Query, State},
+    Extension, Json,
+    extract::{ConnectInfo, Path, Query, RawQuery, State},
+    http::StatusCode,
+    response::Response,
 };
+use rustzen_core::auth::CurrentUser;
 use sqlx::SqlitePool;
+use std::net::SocketAddr;
 
 /// Retrieves a complete list of dictionary items with optional filtering.
 pub async fn list_dicts(
-    State(pool): State<SqlitePool>,
+    State(db): State<DbExecutor>,
     Query(query): Query<DictQuery>,
 ) -> AppResult<Vec<DictItemResp>> {
-    let (dict_list, total) = DictService::list_dicts(&pool, query).await?;
-    Ok(ApiResponse::page(dict_list, total))
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (dict_list, total) = DictService::list_dicts(db.read(), query).await?;
+    Ok(ApiResponse::page(dict_list, total, PageMeta::new(pagination, total)))
+}
+
+/// Exports the dictionary items matching the list filters as CSV.
+pub async fn export_dicts(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    State(db): State<DbExecutor>,
+    RawQuery(filters): RawQuery,
+    Query(query): Query<DictQuery>,
+    Query(param): Query<ExportTemplateParam>,
+) -> Result<Response, (StatusCode, String)> {
+    let (tz, export) = async {
+        let tz =
+            AccountService::effective_timezone(config, db.read(), current_user.user_id).await?;
+        let columns =
+            ExportTemplateService::columns_for(db.read(), ExportResource::Dicts, param.template)
+                .await?;
+        let export =
+            DictService::export_dicts_csv(db.read(), query, tz, columns.as_deref()).await?;
+        Ok::<_, ServiceError>((tz, export))
+    }
+    .await
+    .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    let exporter = Exporter::new(&current_user, addr.ip().to_string(), filters);
+    exporter.csv_response(config, db.write(), "dicts", "dict", tz, export).await
 }
 
 /// Creates a new dictionary item.
@@ -55,11 +107,12 @@ pub async fn update_dict_status(
 
 /// Retrieves dictionary options for dropdown/select components.
 pub async fn get_dict_options(
-    State(pool): State<SqlitePool>,
-    Query(query): Query<DictOptionsQuery>,
+    State(db): State<DbExecutor>,
+    Query(filter): Query<DictOptionsQuery>,
+    Query(query): Query<OptionsQuery>,
 ) -> AppResult<Vec<OptionItem<String>>> {
     Ok(ApiResponse::success(
-        DictService::get_dict_options(&pool, query.dict_type, query.q, query.limit).await?,
+        DictService::get_dict_options(db.read(), filter.dict_type, query).await?,
     ))
 }
 
@@ -70,3 +123,30 @@ pub async fn get_dict_by_type(
 ) -> AppResult<Vec<OptionItem<String>>> {
     Ok(ApiResponse::success(DictService::get_dict_by_type(&pool, &dict_type).await?))
 }
+
+/// Sets the display order of every item of a type at once.
+pub async fn reorder_dicts(
+    State(pool): State<SqlitePool>,
+    Path(dict_type): Path<String>,
+    Json(payload): Json<ReorderDictPayload>,
+) -> AppResult<()> {
+    DictService::reorder_dicts(&pool, &dict_type, payload).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Lists the per-locale labels of a dictionary item.
+pub async fn get_dict_translations(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+) -> AppResult<Vec<Translation>> {
+    Ok(ApiResponse::success(DictService::get_translations(&pool, id).await?))
+}
+
+/// Replaces the per-locale labels of a dictionary item.
+pub async fn replace_dict_translations(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+    Json(payload): Json<ReplaceTranslationsPayload>,
+) -> AppResult<Vec<Translation>> {
+    Ok(ApiResponse::success(DictService::replace_translations(&pool, id, payload).await?))
+}
@@ -3,12 +3,14 @@ pub mod repo;
 pub mod service;
 pub mod types;
 
+use crate::features::system::purge::handler::{get_dict_purge_report, purge_dict};
 use axum::{
     Router,
     routing::{delete, get, patch, post, put},
 };
 use handler::{
-    create_dict, delete_dict, get_dict_by_type, get_dict_options, list_dicts, update_dict,
+    create_dict, delete_dict, export_dicts, get_dict_by_type, get_dict_options,
+    get_dict_translations, list_dicts, reorder_dicts, replace_dict_translations, update_dict,
     update_dict_status,
 };
 use rustzen_core::{
@@ -26,6 +28,11 @@ pub fn dict_routes() -> Router<SqlitePool> {
             PermissionsCheck::Require(manage_dict::CREATE),
         )
         // More specific routes first to avoid ambiguous matching with `/{id}`.
+        .route_with_permission(
+            "/export",
+            get(export_dicts),
+            PermissionsCheck::Require(manage_dict::EXPORT),
+        )
         .route_with_permission(
             "/options",
             get(get_dict_options),
@@ -36,11 +43,36 @@ pub fn dict_routes() -> Router<SqlitePool> {
             get(get_dict_by_type),
             PermissionsCheck::Require(manage_dict::OPTIONS),
         )
+        .route_with_permission(
+            "/type/{type}/reorder",
+            put(reorder_dicts),
+            PermissionsCheck::Require(manage_dict::UPDATE),
+        )
         .route_with_permission(
             "/{id}/status",
             patch(update_dict_status),
             PermissionsCheck::Require(manage_dict::UPDATE),
         )
+        .route_with_permission(
+            "/{id}/translations",
+            get(get_dict_translations),
+            PermissionsCheck::Require(manage_dict::LIST),
+        )
+        .route_with_permission(
+            "/{id}/translations",
+            put(replace_dict_translations),
+            PermissionsCheck::Require(manage_dict::UPDATE),
+        )
+        .route_with_permission(
+            "/{id}/purge",
+            get(get_dict_purge_report),
+            PermissionsCheck::Require(manage_dict::PURGE),
+        )
+        .route_with_permission(
+            "/{id}/purge",
+            delete(purge_dict),
+            PermissionsCheck::Require(manage_dict::PURGE),
+        )
         .route_with_permission(
             "/{id}",
             put(update_dict),
@@ -1,13 +1,29 @@
 use crate::common::{
     api::OptionItem,
     error::ServiceError,
-    query::{count_with_filters, fetch_with_filters, push_eq, push_ilike},
+    i18n::Translation,
+    pagination::Sort,
+    query::{
+        OptionsFilter, OptionsSql, count_with_filters, fetch_options, fetch_with_filters, push_eq,
+        push_ilike,
+    },
 };
 
 use chrono::Utc;
-use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection, SqlitePool};
 
-use super::types::{DictItemResp, DictListQuery, UpdateDictPayload};
+use super::types::{CreateDictRequest, DictItemResp, DictListQuery, UpdateDictPayload};
+
+/// Options carry the dictionary value, so `excludeIds` and `includeIds` list values. The
+/// scope binds the locale and closes the join before the `WHERE`.
+const DICT_OPTIONS: OptionsSql = OptionsSql {
+    base_sql: "SELECT COALESCE(t.label, d.label) AS label, d.value FROM dicts d
+               LEFT JOIN dict_i18n t ON t.dict_id = d.id AND t.locale = ",
+    value_column: "d.value",
+    status_column: "d.status",
+    search_columns: &["COALESCE(t.label, d.label)"],
+    order_by: "d.sort_order ASC, label ASC",
+};
 
 pub struct DictRepository;
 
@@ -15,6 +31,17 @@ const DEFAULT_DICT_STATUS: i16 = 1;
 const DEFAULT_DICT_SORT_ORDER: i32 = 1;
 
 impl DictRepository {
+    /// Sortable list fields mapped to `dicts` columns.
+    pub const SORT_COLUMNS: &[(&str, &str)] = &[
+        ("dictType", "dict_type"),
+        ("label", "label"),
+        ("value", "value"),
+        ("status", "status"),
+        ("sortOrder", "sort_order"),
+        ("createdAt", "created_at"),
+        ("updatedAt", "updated_at"),
+    ];
+
     /// Formats the query for the dictionary items
     fn format_query(query: &DictListQuery, query_builder: &mut QueryBuilder<Sqlite>) {
         push_ilike(query_builder, "dict_type", query.dict_type.as_deref());
@@ -41,13 +68,14 @@ impl DictRepository {
         if total == 0 {
             return Ok((Vec::new(), total));
         }
+        let order_by = query.sort.map(Sort::to_order_by);
         let dicts = fetch_with_filters(
             pool,
-            "SELECT id, dict_type, label, value, status, COALESCE(description, '') AS description, sort_order, updated_at FROM dicts WHERE 1=1 AND deleted_at IS NULL",
+            "SELECT id, dict_type, label, value, status, COALESCE(description, '') AS description, sort_order, is_default, updated_at FROM dicts WHERE 1=1 AND deleted_at IS NULL",
             |query_builder| {
                 Self::format_query(&query, query_builder);
             },
-            Some("dict_type ASC, sort_order ASC, id ASC"),
+            Some(order_by.as_deref().unwrap_or("dict_type ASC, sort_order ASC, id ASC")),
             Some(limit),
             Some(offset),
         )
@@ -57,115 +85,276 @@ impl DictRepository {
         Ok((dicts, total))
     }
 
+    /// Enabled `(label, value)` pairs, labels in `locale` where translated.
     pub async fn list_dict_options(
         pool: &SqlitePool,
         dict_type: Option<&str>,
-        search_query: Option<&str>,
-        limit: Option<i64>,
+        filter: &OptionsFilter<String>,
+        locale: &str,
     ) -> Result<Vec<(String, String)>, ServiceError> {
         tracing::debug!(
-            "Querying dictionary options with type: {:?}, search: {:?}, limit: {:?}",
+            "Querying dictionary options with type: {:?}, filter: {:?}",
             dict_type,
-            search_query,
-            limit
+            filter
         );
 
-        let results = fetch_with_filters(
-            pool,
-            "SELECT label, value FROM dicts WHERE deleted_at IS NULL AND status = 1",
-            |query_builder| {
-                if let Some(dtype) = dict_type {
-                    let dtype = dtype.trim();
-                    if !dtype.is_empty() {
-                        query_builder.push(" AND dict_type = ").push_bind(dtype.to_string());
-                    }
+        let results = fetch_options(pool, &DICT_OPTIONS, filter, |query_builder| {
+            query_builder.push_bind(locale.to_string()).push(" WHERE d.deleted_at IS NULL");
+            if let Some(dtype) = dict_type {
+                let dtype = dtype.trim();
+                if !dtype.is_empty() {
+                    query_builder.push(" AND d.dict_type = ").push_bind(dtype.to_string());
                 }
-                push_ilike(query_builder, "label", search_query);
-            },
-            Some("sort_order ASC, label ASC"),
-            limit,
-            None,
-        )
+            }
+        })
         .await?;
 
         tracing::debug!("Found {} dictionary options", results.len());
         Ok(results)
     }
 
-    /// Retrieves dictionary items by type
+    /// Enabled items of a type, labels in `locale` where translated.
     pub async fn list_dicts_by_type(
         pool: &SqlitePool,
         dict_type: &str,
+        locale: &str,
     ) -> Result<Vec<OptionItem<String>>, ServiceError> {
         tracing::debug!("Querying dictionary items with type: {}", dict_type);
 
-        let dicts = fetch_with_filters(
-            pool,
-            "SELECT label, value FROM dicts WHERE deleted_at IS NULL AND status = 1",
-            |query_builder| {
-                query_builder.push(" AND dict_type = ").push_bind(dict_type.to_string());
-            },
-            Some("sort_order ASC, label ASC"),
-            None,
-            None,
+        sqlx::query_as::<_, OptionItem<String>>(
+            "SELECT COALESCE(t.label, d.label) AS label, d.value FROM dicts d
+             LEFT JOIN dict_i18n t ON t.dict_id = d.id AND t.locale = ?
+             WHERE d.deleted_at IS NULL AND d.status = 1 AND d.dict_type = ?
+             ORDER BY d.sort_order ASC, label ASC",
         )
-        .await?;
+        .bind(locale)
+        .bind(dict_type)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error listing dictionary type '{}': {:?}", dict_type, e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
 
-        Ok(dicts)
+    /// Translations of an item's label, or `None` when the item does not exist.
+    pub async fn list_translations(
+        pool: &SqlitePool,
+        id: i64,
+    ) -> Result<Option<Vec<Translation>>, ServiceError> {
+        let map_err = |e: sqlx::Error| {
+            tracing::error!(
+                "Database error listing translations of dictionary item {}: {:?}",
+                id,
+                e
+            );
+            ServiceError::DatabaseQueryFailed
+        };
+        let exists = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM dicts WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .map_err(map_err)?;
+        if exists == 0 {
+            return Ok(None);
+        }
+        let translations = sqlx::query_as::<_, Translation>(
+            "SELECT locale, label AS text FROM dict_i18n WHERE dict_id = ? ORDER BY locale",
+        )
+        .bind(id)
+        .fetch_all(pool)
+        .await
+        .map_err(map_err)?;
+        Ok(Some(translations))
     }
 
-    /// Creates a new dictionary item
-    pub async fn create(
+    /// Replaces every translation of an item's label in one transaction. Returns `false`
+    /// when the item does not exist.
+    pub async fn replace_translations(
+        pool: &SqlitePool,
+        id: i64,
+        translations: &[Translation],
+    ) -> Result<bool, ServiceError> {
+        let map_err = |e: sqlx::Error| {
+            tracing::error!(
+                "Database error replacing translations of dictionary item {}: {:?}",
+                id,
+                e
+            );
+            ServiceError::DatabaseQueryFailed
+        };
+        let mut tx = pool.begin().await.map_err(map_err)?;
+        let touched =
+            sqlx::query("UPDATE dicts SET updated_at = ? WHERE id = ? AND deleted_at IS NULL")
+                .bind(Utc::now().naive_utc())
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .map_err(map_err)?;
+        if touched.rows_affected() == 0 {
+            return Ok(false);
+        }
+        sqlx::query("DELETE FROM dict_i18n WHERE dict_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(map_err)?;
+        for translation in translations {
+            sqlx::query("INSERT INTO dict_i18n (dict_id, locale, label) VALUES (?, ?, ?)")
+                .bind(id)
+                .bind(&translation.locale)
+                .bind(&translation.text)
+                .execute(&mut *tx)
+                .await
+                .map_err(map_err)?;
+        }
+        tx.commit().await.map_err(map_err)?;
+        tracing::info!("Replaced {} translations of dictionary item {}", translations.len(), id);
+        Ok(true)
+    }
+
+    /// IDs of every item of a type, enabled or not.
+    pub async fn list_ids_by_type(
         pool: &SqlitePool,
         dict_type: &str,
-        label: &str,
-        value: &str,
-        status: Option<i16>,
-        description: Option<&str>,
-        sort_order: Option<i32>,
+    ) -> Result<Vec<i64>, ServiceError> {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT id FROM dicts WHERE dict_type = ? AND deleted_at IS NULL ORDER BY id",
+        )
+        .bind(dict_type)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error listing dictionary type '{}' ids: {:?}", dict_type, e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
+    /// Sets `sort_order` to each item's 1-based position in `ids`, in one transaction.
+    /// Returns `false`, changing nothing, when an item left the type in the meantime.
+    pub async fn reorder(
+        pool: &SqlitePool,
+        dict_type: &str,
+        ids: &[i64],
+    ) -> Result<bool, ServiceError> {
+        let map_err = |e: sqlx::Error| {
+            tracing::error!("Database error reordering dictionary type '{}': {:?}", dict_type, e);
+            ServiceError::DatabaseQueryFailed
+        };
+        let now = Utc::now().naive_utc();
+        let mut tx = pool.begin().await.map_err(map_err)?;
+        for (position, id) in (1..).zip(ids) {
+            let result = sqlx::query(
+                "UPDATE dicts SET sort_order = ?, updated_at = ?
+                 WHERE id = ? AND dict_type = ? AND deleted_at IS NULL",
+            )
+            .bind(position)
+            .bind(now)
+            .bind(id)
+            .bind(dict_type)
+            .execute(&mut *tx)
+            .await
+            .map_err(map_err)?;
+            if result.rows_affected() == 0 {
+                return Ok(false);
+            }
+        }
+        tx.commit().await.map_err(map_err)?;
+        tracing::info!("Reordered {} items of dictionary type '{}'", ids.len(), dict_type);
+        Ok(true)
+    }
+
+    /// Clears the default flag of every item of `dict_type` except `keep_id`.
+    async fn clear_default(
+        conn: &mut SqliteConnection,
+        dict_type: &str,
+        keep_id: Option<i64>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE dicts SET is_default = 0, updated_at = ?
+             WHERE dict_type = ? AND is_default = 1 AND deleted_at IS NULL AND id IS NOT ?",
+        )
+        .bind(Utc::now().naive_utc())
+        .bind(dict_type)
+        .bind(keep_id)
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Creates a new dictionary item; a new default replaces the type's previous one in the
+    /// same transaction.
+    pub async fn create(
+        pool: &SqlitePool,
+        request: &CreateDictRequest,
     ) -> Result<i64, ServiceError> {
+        let CreateDictRequest { dict_type, label, value, status, description, sort_order, .. } =
+            request;
         tracing::debug!("Creating new dictionary item with type: {}, label: {}", dict_type, label);
+        let is_default = request.is_default.unwrap_or(false);
         let now = Utc::now().naive_utc();
+        let map_err = |e: sqlx::Error| {
+            tracing::error!(
+                "Database error creating dictionary item type '{}', label '{}': {:?}",
+                dict_type,
+                label,
+                e
+            );
+            ServiceError::DatabaseQueryFailed
+        };
 
+        let mut tx = pool.begin().await.map_err(map_err)?;
+        if is_default {
+            Self::clear_default(&mut tx, dict_type, None).await.map_err(map_err)?;
+        }
         let dict = sqlx::query_scalar::<_, i64>(
-            "INSERT INTO dicts (dict_type, label, value, status, description, sort_order, created_at, updated_at)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "INSERT INTO dicts (dict_type, label, value, status, description, sort_order, is_default, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
              RETURNING id",
         )
         .bind(dict_type)
         .bind(label)
         .bind(value)
         .bind(status.unwrap_or(DEFAULT_DICT_STATUS))
-        .bind(description)
+        .bind(description.as_deref())
         .bind(sort_order.unwrap_or(DEFAULT_DICT_SORT_ORDER))
+        .bind(is_default)
         .bind(now)
         .bind(now)
-        .fetch_one(pool)
+        .fetch_one(&mut *tx)
         .await
-        .map_err(|e| {
-            tracing::error!(
-                "Database error creating dictionary item type '{}', label '{}': {:?}",
-                dict_type,
-                label,
-                e
-            );
-            ServiceError::DatabaseQueryFailed
-        })?;
+        .map_err(map_err)?;
+        tx.commit().await.map_err(map_err)?;
 
         Ok(dict)
     }
 
-    /// Updates an existing dictionary item
+    /// Updates an existing dictionary item. Setting the default replaces the type's
+    /// previous one in the same transaction; an item moved to another type without an
+    /// explicit flag stops being a default.
     pub async fn update(
         pool: &SqlitePool,
         id: i64,
         request: &UpdateDictPayload,
     ) -> Result<i64, ServiceError> {
         tracing::debug!("Updating dictionary item with id: {}", id);
+        let map_err = |e: sqlx::Error| {
+            tracing::error!("Database error updating dictionary item {}: {:?}", id, e);
+            ServiceError::DatabaseQueryFailed
+        };
 
+        let mut tx = pool.begin().await.map_err(map_err)?;
+        if request.is_default == Some(true) {
+            Self::clear_default(&mut tx, &request.dict_type, Some(id)).await.map_err(map_err)?;
+        }
+        // The CASE sees the row's old `dict_type`.
         let dict_id = sqlx::query_scalar::<_, i64>(
             "UPDATE dicts
-             SET dict_type = ?, label = ?, value = ?, status = ?, description = ?, sort_order = ?, updated_at = ?
+             SET dict_type = ?, label = ?, value = ?, status = ?, description = ?, sort_order = ?,
+                 is_default = COALESCE(?, CASE WHEN dict_type = ? THEN is_default ELSE 0 END),
+                 updated_at = ?
              WHERE id = ? AND deleted_at IS NULL
              RETURNING id",
         )
@@ -175,14 +364,14 @@ impl DictRepository {
         .bind(request.status.unwrap_or(DEFAULT_DICT_STATUS))
         .bind(request.description.as_deref())
         .bind(request.sort_order.unwrap_or(DEFAULT_DICT_SORT_ORDER))
+        .bind(request.is_default)
+        .bind(&request.dict_type)
         .bind(Utc::now().naive_utc())
         .bind(id)
-        .fetch_optional(pool)
+        .fetch_optional(&mut *tx)
         .await
-        .map_err(|e| {
-            tracing::error!("Database error updating dictionary item {}: {:?}", id, e);
-            ServiceError::DatabaseQueryFailed
-        })?;
+        .map_err(map_err)?;
+        tx.commit().await.map_err(map_err)?;
 
         if let Some(dict_id) = dict_id {
             Ok(dict_id)
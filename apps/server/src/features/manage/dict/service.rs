@@ -1,15 +1,39 @@
 use super::{
     repo::DictRepository,
-    types::{CreateDictRequest, DictItemResp, DictListQuery, DictQuery, UpdateDictPayload},
+    types::{
+        CreateDictRequest, DICT_STATUS, DictEnum, DictItemResp, DictListQuery, DictQuery,
+        ReorderDictPayload, UpdateDictPayload,
+    },
 };
 use crate::common::{
-    api::OptionItem,
+    api::{OptionItem, OptionsQuery},
     error::ServiceError,
-    pagination::{Pagination, PaginationQuery},
-    query::parse_optional_i16_filter,
+    export::{CSV_TIME_FORMAT, CsvExport, ExportChunk},
+    i18n::{self, Locale, ReplaceTranslationsPayload, Translation, check_translations},
+    pagination::{Pagination, PaginationQuery, Sort},
+    query::{OptionsFilter, parse_optional_i16_filter},
+    validation::FieldErrors,
 };
 
+use chrono_tz::Tz;
 use sqlx::SqlitePool;
+use std::collections::BTreeSet;
+
+/// Items fetched per query when exporting.
+const EXPORT_BATCH_SIZE: i64 = 1000;
+
+/// Header of dictionary CSV exports.
+pub const DICT_EXPORT_COLUMNS: [&str; 9] = [
+    "id",
+    "dict_type",
+    "label",
+    "value",
+    "status",
+    "description",
+    "sort_order",
+    "is_default",
+    "updated_at",
+];
 
 pub struct DictService;
 
@@ -21,18 +45,71 @@ impl DictService {
     ) -> Result<(Vec<DictItemResp>, i64), ServiceError> {
         tracing::info!("Starting to retrieve dictionary list with query: {:?}", query);
 
-        let DictQuery { current, page_size, dict_type, label, value, status } = query;
-        let pagination = Pagination::from_query(PaginationQuery { current, page_size });
+        let (pagination, repo_query) = Self::dict_list_query(query)?;
         let limit = i64::from(pagination.limit);
         let offset = i64::from(pagination.offset);
-        let status = parse_optional_i16_filter(status.as_deref(), "dict status", None)?;
-        let repo_query = DictListQuery { dict_type, label, value, status };
-
         let (dicts, total) = DictRepository::list_dicts(pool, offset, limit, repo_query).await?;
 
         Ok((dicts, total))
     }
 
+    /// Every item matching the list filters as CSV, sorted like the list, with `updated_at`
+    /// written in `tz`, keeping only `columns` when given. Paging fields are ignored.
+    pub async fn export_dicts_csv(
+        pool: &SqlitePool,
+        query: DictQuery,
+        tz: Tz,
+        columns: Option<&[String]>,
+    ) -> Result<CsvExport, ServiceError> {
+        let mut export = CsvExport::with_columns(&DICT_EXPORT_COLUMNS, columns);
+        let mut position = 0;
+        while let Some(next) =
+            Self::export_dicts_chunk(pool, &query, position, tz, &mut export).await?.next
+        {
+            position = next;
+        }
+        Ok(export)
+    }
+
+    /// Writes the batch of matching items starting at offset `position` to `export`.
+    pub async fn export_dicts_chunk(
+        pool: &SqlitePool,
+        query: &DictQuery,
+        position: i64,
+        tz: Tz,
+        export: &mut CsvExport,
+    ) -> Result<ExportChunk, ServiceError> {
+        let (_, repo_query) = Self::dict_list_query(query.clone())?;
+        let (dicts, total) =
+            DictRepository::list_dicts(pool, position, EXPORT_BATCH_SIZE, repo_query).await?;
+        for dict in dicts {
+            export.push_row([
+                dict.id.to_string(),
+                dict.dict_type,
+                dict.label,
+                dict.value,
+                dict.status.to_string(),
+                dict.description,
+                dict.sort_order.to_string(),
+                dict.is_default.to_string(),
+                dict.updated_at.with_timezone(&tz).format(CSV_TIME_FORMAT).to_string(),
+            ]);
+        }
+        let next = position + EXPORT_BATCH_SIZE;
+        Ok(ExportChunk { total, next: (next < total).then_some(next) })
+    }
+
+    /// Splits list parameters into the page and the repository filters.
+    pub fn dict_list_query(query: DictQuery) -> Result<(Pagination, DictListQuery), ServiceError> {
+        let DictQuery { current, page_size, dict_type, label, value, status, sort_by, sort_order } =
+            query;
+        let pagination = Pagination::from_query(PaginationQuery { current, page_size });
+        let status = parse_optional_i16_filter(status.as_deref(), "dict status", None)?;
+        let sort =
+            Sort::resolve(sort_by.as_deref(), sort_order.as_deref(), DictRepository::SORT_COLUMNS)?;
+        Ok((pagination, DictListQuery { dict_type, label, value, status, sort }))
+    }
+
     /// Creates a new dictionary item with validation
     pub async fn create_dict(
         pool: &SqlitePool,
@@ -43,16 +120,12 @@ impl DictService {
             request.dict_type,
             request.label
         );
-        DictRepository::create(
-            pool,
-            &request.dict_type,
-            &request.label,
-            &request.value,
-            request.status,
-            request.description.as_deref(),
-            request.sort_order,
-        )
-        .await
+        let mut errors = FieldErrors::new();
+        if let Some(status) = request.status {
+            Self::check_enum_value(pool, DICT_STATUS, "status", status, &mut errors).await?;
+        }
+        errors.into_result()?;
+        DictRepository::create(pool, &request).await
     }
 
     /// Updates an existing dictionary item with validation
@@ -62,6 +135,11 @@ impl DictService {
         request: UpdateDictPayload,
     ) -> Result<i64, ServiceError> {
         tracing::info!("Updating dictionary item: {}", id);
+        let mut errors = FieldErrors::new();
+        if let Some(status) = request.status {
+            Self::check_enum_value(pool, DICT_STATUS, "status", status, &mut errors).await?;
+        }
+        errors.into_result()?;
         DictRepository::update(pool, id, &request).await
     }
 
@@ -75,18 +153,17 @@ impl DictService {
         }
     }
 
-    /// Retrieves dictionary options for dropdown selections
+    /// Retrieves dictionary options for dropdown selections, labelled in the request locale
     pub async fn get_dict_options(
         pool: &SqlitePool,
         dict_type: Option<String>,
-        search_query: Option<String>,
-        limit: Option<i64>,
+        query: OptionsQuery,
     ) -> Result<Vec<OptionItem<String>>, ServiceError> {
         Ok(DictRepository::list_dict_options(
             pool,
             dict_type.as_deref(),
-            search_query.as_deref(),
-            limit,
+            &OptionsFilter::from_query(query)?,
+            i18n::current_locale().tag(),
         )
         .await?
         .into_iter()
@@ -94,12 +171,64 @@ impl DictService {
         .collect())
     }
 
-    /// Retrieves dictionary items by type
+    /// Retrieves dictionary items by type, labelled in the request locale
     pub async fn get_dict_by_type(
         pool: &SqlitePool,
         dict_type: &str,
     ) -> Result<Vec<OptionItem<String>>, ServiceError> {
-        DictRepository::list_dicts_by_type(pool, dict_type).await
+        DictRepository::list_dicts_by_type(pool, dict_type, i18n::current_locale().tag()).await
+    }
+
+    /// Lists the translations of an item's label
+    pub async fn get_translations(
+        pool: &SqlitePool,
+        id: i64,
+    ) -> Result<Vec<Translation>, ServiceError> {
+        DictRepository::list_translations(pool, id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound("Dictionary item".to_string()))
+    }
+
+    /// Replaces the translations of an item's label
+    pub async fn replace_translations(
+        pool: &SqlitePool,
+        id: i64,
+        payload: ReplaceTranslationsPayload,
+    ) -> Result<Vec<Translation>, ServiceError> {
+        tracing::info!("Replacing translations of dictionary item {}", id);
+        let mut translations = payload.translations;
+        let mut errors = FieldErrors::new();
+        check_translations(&mut translations, &mut errors);
+        errors.into_result()?;
+
+        if !DictRepository::replace_translations(pool, id, &translations).await? {
+            return Err(ServiceError::NotFound("Dictionary item".to_string()));
+        }
+        Self::get_translations(pool, id).await
+    }
+
+    /// Rewrites the display order of a whole dictionary type.
+    pub async fn reorder_dicts(
+        pool: &SqlitePool,
+        dict_type: &str,
+        payload: ReorderDictPayload,
+    ) -> Result<(), ServiceError> {
+        tracing::info!("Reordering dictionary type '{}'", dict_type);
+        let existing = DictRepository::list_ids_by_type(pool, dict_type).await?;
+        if existing.is_empty() {
+            return Err(ServiceError::NotFound("Dictionary type".to_string()));
+        }
+        let mut errors = FieldErrors::new();
+        check_complete_order(&payload.ids, &existing, &mut errors);
+        errors.into_result()?;
+
+        if DictRepository::reorder(pool, dict_type, &payload.ids).await? {
+            Ok(())
+        } else {
+            Err(ServiceError::InvalidOperation(
+                "Dictionary items changed while reordering; reload and try again".to_string(),
+            ))
+        }
     }
 
     /// Updates the status of a dictionary item
@@ -110,11 +239,9 @@ impl DictService {
     ) -> Result<(), ServiceError> {
         tracing::info!("Updating dictionary item {} status to: {}", id, status);
 
-        if ![1, 2].contains(&status) {
-            return Err(ServiceError::InvalidOperation(
-                "Status must be 1 (active) or 2 (inactive)".to_string(),
-            ));
-        }
+        let mut errors = FieldErrors::new();
+        Self::check_enum_value(pool, DICT_STATUS, "status", status, &mut errors).await?;
+        errors.into_result()?;
 
         if DictRepository::update_status(pool, id, status).await? {
             Ok(())
@@ -122,4 +249,55 @@ impl DictService {
             Err(ServiceError::NotFound("Dictionary item".to_string()))
         }
     }
+
+    /// Records `value` as invalid unless an enabled entry of the enum's dictionary type
+    /// lists it. Until the type has entries, every code the column stores is accepted.
+    pub async fn check_enum_value(
+        pool: &SqlitePool,
+        dict_enum: DictEnum,
+        field: &str,
+        value: i16,
+        errors: &mut FieldErrors,
+    ) -> Result<(), ServiceError> {
+        let entries =
+            DictRepository::list_dicts_by_type(pool, dict_enum.dict_type, Locale::En.tag()).await?;
+        let listed: Vec<i16> = entries
+            .iter()
+            .filter_map(|entry| entry.value.trim().parse().ok())
+            .filter(|code| dict_enum.stored.contains(code))
+            .collect();
+        let allowed = if listed.is_empty() { dict_enum.stored } else { &listed };
+        errors.check_one_of(field, value, allowed);
+        Ok(())
+    }
+}
+
+/// Records an error unless `ids` lists each of `existing` exactly once.
+fn check_complete_order(ids: &[i64], existing: &[i64], errors: &mut FieldErrors) {
+    let listed: BTreeSet<i64> = ids.iter().copied().collect();
+    if listed.len() != ids.len() {
+        errors.push("ids", "must not contain duplicates");
+    } else if listed != existing.iter().copied().collect() {
+        errors.push("ids", "must list every item of the dictionary type exactly once");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_complete_order;
+    use crate::common::validation::FieldErrors;
+
+    fn problems(ids: &[i64]) -> bool {
+        let mut errors = FieldErrors::new();
+        check_complete_order(ids, &[4, 7, 9], &mut errors);
+        errors.into_result().is_err()
+    }
+
+    #[test]
+    fn order_must_be_a_permutation_of_the_type() {
+        assert!(!problems(&[9, 4, 7]));
+        assert!(problems(&[9, 4, 4, 7]));
+        assert!(problems(&[9, 4]));
+        assert!(problems(&[9, 4, 7, 12]));
+    }
 }
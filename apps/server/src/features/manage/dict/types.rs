@@ -1,6 +1,19 @@
-use chrono::NaiveDateTime;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::common::pagination::Sort;
+
+/// A status-like column whose codes are labelled by a dictionary type.
+#[derive(Debug, Clone, Copy)]
+pub struct DictEnum {
+    pub dict_type: &'static str,
+    /// Codes the column's `CHECK` constraint accepts.
+    pub stored: &'static [i16],
+}
+
+pub const MENU_STATUS: DictEnum = DictEnum { dict_type: "menu_status", stored: &[1, 2] };
+pub const DICT_STATUS: DictEnum = DictEnum { dict_type: "dict_status", stored: &[1, 2] };
+
 /// Create dictionary item request parameters
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -17,6 +30,8 @@ pub struct CreateDictRequest {
     pub description: Option<String>,
     /// The sort order of the item.
     pub sort_order: Option<i32>,
+    /// Make this the type's default item, replacing the previous default.
+    pub is_default: Option<bool>,
 }
 
 /// Update dictionary item request parameters
@@ -29,6 +44,9 @@ pub struct UpdateDictPayload {
     pub status: Option<i16>,
     pub description: Option<String>,
     pub sort_order: Option<i32>,
+    /// `true` makes this the type's default item, replacing the previous default; `false`
+    /// clears it. Omitted keeps the current flag unless the item moves to another type.
+    pub is_default: Option<bool>,
 }
 
 /// Updates the status of a dictionary item.
@@ -37,6 +55,13 @@ pub struct UpdateDictStatusPayload {
     pub status: i16,
 }
 
+/// New display order for one dictionary type.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReorderDictPayload {
+    /// Item IDs in display order; every item of the type must appear exactly once.
+    pub ids: Vec<i64>,
+}
+
 /// Dictionary item for list display
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 #[serde(rename_all = "camelCase")]
@@ -54,8 +79,10 @@ pub struct DictItemResp {
     pub description: String,
     /// The sort order of the item.
     pub sort_order: i32,
+    /// Whether this is the type's default item; at most one per type.
+    pub is_default: bool,
     /// The last update time.
-    pub updated_at: NaiveDateTime,
+    pub updated_at: DateTime<Utc>,
 }
 
 /// Dictionary query parameters
@@ -74,6 +101,10 @@ pub struct DictQuery {
     pub value: Option<String>,
     /// Filter by status.
     pub status: Option<String>,
+    /// Sort field (camelCase). Defaults to the list's natural order.
+    pub sort_by: Option<String>,
+    /// Sort direction: "asc" or "desc". Defaults to "desc".
+    pub sort_order: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -82,4 +113,5 @@ pub struct DictListQuery {
     pub label: Option<String>,
     pub value: Option<String>,
     pub status: Option<i16>,
+    pub sort: Option<Sort>,
 }
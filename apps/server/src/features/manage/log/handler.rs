@@ -1,49 +1,120 @@
 use super::{
     service::LogService,
-    types::{LogItemResp, LogQuery},
+    types::{
+        LogItemResp, LogPurgeQuery, LogPurgeResp, LogQuery, LogRouteStatsQuery, LogRouteStatsResp,
+        SlowLogResp,
+    },
+};
+use crate::{
+    common::{
+        api::{ApiResponse, AppResult, PageMeta},
+        error::ServiceError,
+        export::Exporter,
+        mask::FieldMask,
+        pagination::{Pagination, PaginationQuery},
+    },
+    features::{
+        account::service::AccountService,
+        system::{
+            approval::{service::ApprovalService, types::ApprovalAction},
+            confirmation::{service::ConfirmationService, types::ConfirmationTarget},
+            export_job::types::ExportResource,
+            export_template::{service::ExportTemplateService, types::ExportTemplateParam},
+        },
+    },
+    infra::{config::AppConfig, db::DbExecutor},
 };
-use crate::common::api::{ApiResponse, AppResult};
 
 use axum::{
-    extract::{Query, State},
-    http::{HeaderMap, HeaderValue, StatusCode, header},
-    response::{IntoResponse, Response},
+    Extension,
+    extract::{ConnectInfo, Query, RawQuery, State},
+    http::{HeaderMap, StatusCode},
+    response::Response,
 };
-use chrono::Utc;
+use rustzen_core::auth::CurrentUser;
 use sqlx::SqlitePool;
+use std::net::SocketAddr;
 
 /// Handles the request to get a paginated list of logs
 pub async fn list_logs(
-    State(pool): State<SqlitePool>,
+    current_user: CurrentUser,
+    State(db): State<DbExecutor>,
     Query(query): Query<LogQuery>,
 ) -> AppResult<Vec<LogItemResp>> {
-    let (logs, total) = LogService::list_logs(&pool, query).await?;
-    Ok(ApiResponse::page(logs, total))
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let cursor_mode = query.after.is_some();
+    let (logs, total) =
+        LogService::list_logs(db.read(), query, FieldMask::for_user(&current_user)).await?;
+    let mut page = PageMeta::new(pagination, total);
+    if cursor_mode {
+        // `current` is ignored with a cursor; a full page means more rows may follow.
+        page.has_next = logs.len() as i64 == page.page_size;
+    }
+    Ok(ApiResponse::page(logs, total, page))
 }
 
-pub async fn export_logs(
+/// Delete logs older than `olderThanDays`, with a confirmation token for that range and
+/// after approval under dual control
+pub async fn purge_logs(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    headers: HeaderMap,
     State(pool): State<SqlitePool>,
-    Query(query): Query<LogQuery>,
-) -> Result<Response, (StatusCode, String)> {
-    let content = LogService::export_logs_csv(&pool, query)
-        .await
-        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
-
-    let filename = format!("log_{}.csv", get_timestamp());
-    let disposition = format!("attachment; filename={}", filename);
+    Query(query): Query<LogPurgeQuery>,
+) -> AppResult<LogPurgeResp> {
+    let before = LogService::purge_cutoff(&query)?;
+    let older_than_days = query.older_than_days.unwrap_or_default();
+    ConfirmationService::consume(
+        &pool,
+        &current_user,
+        &headers,
+        &ConfirmationTarget::LogPurge { older_than_days },
+    )
+    .await?;
+    ApprovalService::require(config, &pool, &current_user, ApprovalAction::LogPurge { before })
+        .await?;
+    Ok(ApiResponse::success(LogService::purge_logs(&pool, before).await?))
+}
 
-    let mut headers = HeaderMap::new();
-    let content_disposition = HeaderValue::from_str(&disposition).map_err(|_| {
-        (StatusCode::INTERNAL_SERVER_ERROR, "invalid content disposition".to_string())
-    })?;
-    headers.insert(header::CONTENT_DISPOSITION, content_disposition);
-    headers.insert(header::CONTENT_LENGTH, HeaderValue::from(content.len()));
-    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+/// Request count, errors and latency per route template, for spotting hot or failing endpoints.
+pub async fn route_stats(
+    State(db): State<DbExecutor>,
+    Query(query): Query<LogRouteStatsQuery>,
+) -> AppResult<Vec<LogRouteStatsResp>> {
+    Ok(ApiResponse::success(LogService::route_stats(db.read(), query).await?))
+}
 
-    Ok((headers, content).into_response())
+/// Requests and SQL statements that crossed the slow thresholds, kept in memory per process.
+pub async fn slow_logs(Extension(config): Extension<&'static AppConfig>) -> AppResult<SlowLogResp> {
+    Ok(ApiResponse::success(LogService::slow_logs(config)))
 }
 
-/// get timestamp
-fn get_timestamp() -> String {
-    Utc::now().timestamp_millis().to_string()
+pub async fn export_logs(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    State(db): State<DbExecutor>,
+    RawQuery(filters): RawQuery,
+    Query(query): Query<LogQuery>,
+    Query(param): Query<ExportTemplateParam>,
+) -> Result<Response, (StatusCode, String)> {
+    let mask = FieldMask::for_user(&current_user);
+    let (tz, export) = async {
+        let tz =
+            AccountService::effective_timezone(config, db.read(), current_user.user_id).await?;
+        let columns =
+            ExportTemplateService::columns_for(db.read(), ExportResource::Logs, param.template)
+                .await?;
+        let export =
+            LogService::export_logs_csv(db.read(), query, tz, mask, columns.as_deref()).await?;
+        Ok::<_, ServiceError>((tz, export))
+    }
+    .await
+    .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    let exporter = Exporter::new(&current_user, addr.ip().to_string(), filters);
+    exporter.csv_response(config, db.write(), "logs", "log", tz, export).await
 }
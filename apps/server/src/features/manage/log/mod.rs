@@ -3,8 +3,11 @@ pub mod repo;
 pub mod service;
 pub mod types;
 
-use axum::{Router, routing::get};
-use handler::{export_logs, list_logs};
+use axum::{
+    Router,
+    routing::{delete, get},
+};
+use handler::{export_logs, list_logs, purge_logs, route_stats, slow_logs};
 use rustzen_core::{
     capability::manage_log,
     permission::{PermissionsCheck, RouterExt},
@@ -14,9 +17,20 @@ use sqlx::SqlitePool;
 pub fn log_routes() -> Router<SqlitePool> {
     Router::new()
         .route_with_permission("/", get(list_logs), PermissionsCheck::Require(manage_log::LIST))
+        .route_with_permission(
+            "/",
+            delete(purge_logs),
+            PermissionsCheck::Require(manage_log::PURGE),
+        )
         .route_with_permission(
             "/export",
             get(export_logs),
             PermissionsCheck::Require(manage_log::EXPORT),
         )
+        .route_with_permission(
+            "/routes",
+            get(route_stats),
+            PermissionsCheck::Require(manage_log::LIST),
+        )
+        .route_with_permission("/slow", get(slow_logs), PermissionsCheck::Require(manage_log::LIST))
 }
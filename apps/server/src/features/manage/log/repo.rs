@@ -1,16 +1,29 @@
 use crate::common::{
     error::ServiceError,
+    pagination::Cursor,
     query::{count_with_filters, fetch_with_filters, push_ilike},
 };
 
+use chrono::NaiveDateTime;
 use sqlx::{QueryBuilder, Sqlite, SqlitePool};
 
-use super::types::{LogItemResp, LogListQuery, LogWriteCommand};
+use super::types::{LogItemResp, LogListQuery, LogRouteStatsResp, LogWriteCommand};
 
 /// Log data access layer
 pub struct LogRepository;
 
 impl LogRepository {
+    /// Sortable list fields mapped to `operation_logs` columns.
+    pub const SORT_COLUMNS: &[(&str, &str)] = &[
+        ("username", "username"),
+        ("action", "action"),
+        ("status", "status"),
+        ("durationMs", "duration_ms"),
+        ("ipAddress", "ip_address"),
+        ("statusCode", "status_code"),
+        ("createdAt", "created_at"),
+    ];
+
     fn format_query(query: &LogListQuery, query_builder: &mut QueryBuilder<Sqlite>) {
         if let Some(search_term) = query.search.as_deref() {
             let search_term = search_term.trim();
@@ -29,6 +42,10 @@ impl LogRepository {
         push_ilike(query_builder, "action", query.action.as_deref());
         push_ilike(query_builder, "description", query.description.as_deref());
         push_ilike(query_builder, "ip_address", query.ip_address.as_deref());
+        if let Some(route) = query.route.as_deref().map(str::trim).filter(|route| !route.is_empty())
+        {
+            query_builder.push(" AND route = ").push_bind(route.to_string());
+        }
     }
 
     /// Find logs with pagination and filters
@@ -50,22 +67,16 @@ impl LogRepository {
         if total == 0 {
             return Ok((Vec::new(), total));
         }
-        let logs = fetch_with_filters(
-            pool,
-            "SELECT id, user_id, username, action, description, data, status, duration_ms, ip_address, user_agent, created_at FROM operation_logs WHERE 1=1",
-            |query_builder| {
-                Self::format_query(&query, query_builder);
-            },
-            Some("created_at DESC"),
-            Some(limit),
-            Some(offset),
-        )
-        .await?;
+        let logs = Self::fetch_page(pool, &query, limit, offset).await?;
 
         Ok((logs, total))
     }
 
     /// Creates a new log entry with full details (for business operations)
+    ///
+    /// A plain `INSERT`: SQLite has no stored functions, so the `log_operation` function of
+    /// the old PostgreSQL schema has no counterpart. Callers on login and request paths log
+    /// a failure here and carry on.
     pub async fn insert_log_entry(
         pool: &SqlitePool,
         command: &LogWriteCommand,
@@ -74,9 +85,10 @@ impl LogRepository {
 
         let log_id = sqlx::query_scalar::<_, i64>(
             "INSERT INTO operation_logs (
-                user_id, username, action, description, data, status, duration_ms, ip_address, user_agent, created_at
+                user_id, username, action, description, data, status, duration_ms, ip_address, user_agent,
+                route, resource_type, resource_id, status_code, country, city, created_at
             ) VALUES (
-                ?, ?, ?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP
+                ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP
             ) RETURNING id",
         )
         .bind(command.user_id)
@@ -88,6 +100,12 @@ impl LogRepository {
         .bind(command.duration_ms)
         .bind(command.ip_address.as_str())
         .bind(command.user_agent.as_str())
+        .bind(command.route.as_deref())
+        .bind(command.resource_type.as_deref())
+        .bind(command.resource_id.as_deref())
+        .bind(command.status_code)
+        .bind(command.country.as_deref())
+        .bind(command.city.as_deref())
         .fetch_one(pool)
         .await
         .map_err(|e| {
@@ -98,19 +116,111 @@ impl LogRepository {
         Ok(log_id)
     }
 
-    pub async fn list_logs_for_export(
+    /// Inserts a batch of records from the background writer in one statement.
+    pub async fn insert_log_entries(
         pool: &SqlitePool,
-        query: LogListQuery,
+        commands: &[LogWriteCommand],
+    ) -> Result<(), ServiceError> {
+        if commands.is_empty() {
+            return Ok(());
+        }
+        let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "INSERT INTO operation_logs (
+                user_id, username, action, description, data, status, duration_ms, ip_address, user_agent,
+                route, resource_type, resource_id, status_code, country, city, created_at
+            ) ",
+        );
+        query_builder.push_values(commands, |mut row, command| {
+            row.push_bind(command.user_id)
+                .push_bind(command.username.clone())
+                .push_bind(command.action.clone())
+                .push_bind(command.description.clone())
+                .push_bind(command.data.clone())
+                .push_bind(command.status.clone())
+                .push_bind(command.duration_ms)
+                .push_bind(command.ip_address.clone())
+                .push_bind(command.user_agent.clone())
+                .push_bind(command.route.clone())
+                .push_bind(command.resource_type.clone())
+                .push_bind(command.resource_id.clone())
+                .push_bind(command.status_code)
+                .push_bind(command.country.clone())
+                .push_bind(command.city.clone())
+                .push("CURRENT_TIMESTAMP");
+        });
+        query_builder.build().execute(pool).await.map_err(|e| {
+            tracing::error!("Database error creating log batch: {:?}", e);
+            ServiceError::DatabaseQueryFailed
+        })?;
+        Ok(())
+    }
+
+    /// Aggregates HTTP request logs since `since` by method and route template, busiest first.
+    pub async fn route_stats(
+        pool: &SqlitePool,
+        since: NaiveDateTime,
+    ) -> Result<Vec<LogRouteStatsResp>, ServiceError> {
+        sqlx::query_as::<_, LogRouteStatsResp>(
+            "SELECT SUBSTR(action, 6) AS method, route,
+                    COUNT(*) AS requests,
+                    SUM(CASE WHEN status_code >= 400 THEN 1 ELSE 0 END) AS errors,
+                    AVG(duration_ms) AS avg_duration_ms,
+                    MAX(duration_ms) AS max_duration_ms
+             FROM operation_logs
+             WHERE route IS NOT NULL AND created_at >= ?
+             GROUP BY action, route
+             ORDER BY requests DESC, route
+             LIMIT 200",
+        )
+        .bind(since)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error aggregating route stats: {:?}", e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
+    /// Deletes logs created before `before`, returning how many rows went.
+    pub async fn delete_before(
+        pool: &SqlitePool,
+        before: NaiveDateTime,
+    ) -> Result<u64, ServiceError> {
+        let result = sqlx::query("DELETE FROM operation_logs WHERE created_at < ?")
+            .bind(before)
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("Database error purging logs: {:?}", e);
+                ServiceError::DatabaseQueryFailed
+            })?;
+        Ok(result.rows_affected())
+    }
+
+    /// Fetch a page by offset, or by keyset when the query carries a cursor.
+    async fn fetch_page(
+        pool: &SqlitePool,
+        query: &LogListQuery,
+        limit: i64,
+        offset: i64,
     ) -> Result<Vec<LogItemResp>, ServiceError> {
+        let order_by = match (query.cursor, query.sort) {
+            (Some(_), _) => Cursor::ORDER_BY.to_string(),
+            (None, Some(sort)) => sort.to_order_by(),
+            (None, None) => "created_at DESC".to_string(),
+        };
         fetch_with_filters(
             pool,
-            "SELECT id, user_id, username, action, description, data, status, duration_ms, ip_address, user_agent, created_at FROM operation_logs WHERE 1=1",
+            "SELECT id, user_id, username, action, description, data, status, duration_ms, ip_address, user_agent, route, resource_type, resource_id, status_code, country, city, created_at FROM operation_logs WHERE 1=1",
             |query_builder| {
-                Self::format_query(&query, query_builder);
+                Self::format_query(query, query_builder);
+                if let Some(cursor) = query.cursor {
+                    cursor.push_condition(query_builder);
+                }
             },
-            Some("created_at DESC"),
-            None,
-            None,
+            Some(&order_by),
+            Some(limit),
+            query.cursor.is_none().then_some(offset),
         )
         .await
     }
@@ -1,14 +1,55 @@
 use super::{
     repo::LogRepository,
-    types::{LogItemResp, LogListQuery, LogQuery, LogWriteCommand},
+    types::{
+        LogItemResp, LogListQuery, LogPurgeQuery, LogPurgeResp, LogQuery, LogRouteStatsQuery,
+        LogRouteStatsResp, LogWriteCommand, SlowLogResp,
+    },
 };
-use crate::common::{
-    error::ServiceError,
-    pagination::{Pagination, PaginationQuery},
+use crate::{
+    common::{
+        error::ServiceError,
+        export::{CSV_TIME_FORMAT, CsvExport, ExportChunk},
+        mask::{FieldMask, MaskFields},
+        pagination::{Cursor, Pagination, PaginationQuery, Sort},
+        user_agent::DeviceInfo,
+        validation::FieldErrors,
+    },
+    infra::{
+        config::AppConfig,
+        geoip::{self, GeoLocation},
+        log_writer::LOG_WRITER,
+        slow_log::SLOW_LOG,
+    },
 };
 
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use rustzen_core::events::{DomainEvent, EventSubscriber};
 use sqlx::SqlitePool;
 
+/// Rows fetched per round trip when exporting logs.
+const EXPORT_BATCH_SIZE: i64 = 1000;
+
+/// Header of log CSV exports.
+pub const LOG_EXPORT_COLUMNS: [&str; 12] = [
+    "ID",
+    "user_id",
+    "username",
+    "action",
+    "description",
+    "status",
+    "duration_ms",
+    "ip_address",
+    "user_agent",
+    "created_at",
+    "route",
+    "status_code",
+];
+
+const DEFAULT_ROUTE_STATS_HOURS: i64 = 24;
+const MAX_ROUTE_STATS_HOURS: i64 = 30 * 24;
+
 /// A service for log-related operations
 pub struct LogService;
 
@@ -17,6 +58,7 @@ impl LogService {
     pub async fn list_logs(
         pool: &SqlitePool,
         query: LogQuery,
+        mask: FieldMask,
     ) -> Result<(Vec<LogItemResp>, i64), ServiceError> {
         let LogQuery {
             current,
@@ -26,99 +68,317 @@ impl LogService {
             action,
             description,
             ip_address,
+            route,
+            sort_by,
+            sort_order,
+            after,
         } = query;
         let pagination = Pagination::from_query(PaginationQuery { current, page_size });
         let limit = i64::from(pagination.limit);
         let offset = i64::from(pagination.offset);
-        let repo_query = LogListQuery {
-            search,
-            username,
-            action,
-            description,
-            ip_address,
-        };
+        let sort =
+            Sort::resolve(sort_by.as_deref(), sort_order.as_deref(), LogRepository::SORT_COLUMNS)?;
+        let cursor = Cursor::from_query(after)?;
+        if cursor.is_some() && sort.is_some() {
+            return Err(ServiceError::InvalidOperation(
+                "Cursor pagination cannot be combined with sortBy".to_string(),
+            ));
+        }
+        let repo_query =
+            LogListQuery { search, username, action, description, ip_address, route, sort, cursor };
+
+        let (logs, total) = LogRepository::list_logs(pool, offset, limit, repo_query).await?;
+        let logs = logs
+            .into_iter()
+            .map(|log| {
+                let device = Some(DeviceInfo::parse(&log.user_agent));
+                LogItemResp { device, ..log }.masked(mask)
+            })
+            .collect();
+        Ok((logs, total))
+    }
+
+    /// Request statistics per method and route template over the last `hours` (1..=720).
+    pub async fn route_stats(
+        pool: &SqlitePool,
+        query: LogRouteStatsQuery,
+    ) -> Result<Vec<LogRouteStatsResp>, ServiceError> {
+        let hours = query.hours.unwrap_or(DEFAULT_ROUTE_STATS_HOURS);
+        if !(1..=MAX_ROUTE_STATS_HOURS).contains(&hours) {
+            return Err(ServiceError::InvalidOperation(format!(
+                "hours must be between 1 and {}",
+                MAX_ROUTE_STATS_HOURS
+            )));
+        }
+        let since = Utc::now().naive_utc() - Duration::hours(hours);
+        LogRepository::route_stats(pool, since).await
+    }
 
-        LogRepository::list_logs(pool, offset, limit, repo_query).await
+    /// Cutoff for a purge request, fixed now so an approval later deletes the same range.
+    pub fn purge_cutoff(query: &LogPurgeQuery) -> Result<NaiveDateTime, ServiceError> {
+        let mut errors = FieldErrors::new();
+        match query.older_than_days {
+            None => errors.push("olderThanDays", "is required"),
+            Some(days) if days < 1 => errors.push("olderThanDays", "must be at least 1"),
+            Some(_) => {}
+        }
+        errors.into_result()?;
+        Ok(Utc::now().naive_utc() - Duration::days(query.older_than_days.unwrap_or_default()))
     }
 
-    /// Stores a structured log record.
-    pub async fn record_operation(
+    pub async fn purge_logs(
         pool: &SqlitePool,
-        command: LogWriteCommand,
-    ) -> Result<i64, ServiceError> {
-        LogRepository::insert_log_entry(pool, &command).await
+        before: NaiveDateTime,
+    ) -> Result<LogPurgeResp, ServiceError> {
+        let deleted = LogRepository::delete_before(pool, before).await?;
+        tracing::info!(deleted, %before, "Purged operation logs");
+        Ok(LogPurgeResp { deleted })
+    }
+
+    /// Slow requests and statements recorded since startup, with the active thresholds.
+    pub fn slow_logs(config: &AppConfig) -> SlowLogResp {
+        let snapshot = SLOW_LOG.snapshot();
+        SlowLogResp {
+            request_threshold_ms: config.log.slow_request_ms,
+            query_threshold_ms: config.log.slow_query_ms,
+            slow_requests_total: snapshot.slow_requests_total,
+            slow_queries_total: snapshot.slow_queries_total,
+            requests: snapshot.requests,
+            queries: snapshot.queries,
+        }
+    }
+
+    /// Hands a record to the background writer, or writes it inline when none is running.
+    ///
+    /// Never fails: a record that cannot be stored is logged and dropped.
+    pub async fn log_operation(pool: &SqlitePool, command: LogWriteCommand) {
+        let Some(command) = LOG_WRITER.submit(command) else {
+            return;
+        };
+        if let Err(e) = LogRepository::insert_log_entry(pool, &command).await {
+            tracing::error!(action = %command.action, "Failed to write operation log: {:?}", e);
+        }
     }
 
+    /// Exports matching logs as CSV, with `created_at` written in `tz` and its offset and IPs
+    /// masked per `mask`, keeping only `columns` when given.
     pub async fn export_logs_csv(
         pool: &SqlitePool,
         query: LogQuery,
-    ) -> Result<String, ServiceError> {
-        let LogQuery {
-            search,
-            username,
-            action,
-            description,
-            ip_address,
-            ..
-        } = query;
+        tz: Tz,
+        mask: FieldMask,
+        columns: Option<&[String]>,
+    ) -> Result<CsvExport, ServiceError> {
+        Self::check_export_query(&query)?;
+        let mut export = CsvExport::with_columns(&LOG_EXPORT_COLUMNS, columns);
+        let mut position = 0;
+        while let Some(next) =
+            Self::export_logs_chunk(pool, &query, position, tz, mask, &mut export).await?.next
+        {
+            position = next;
+        }
+        Ok(export)
+    }
+
+    /// Rejects list parameters an export cannot honour: exports walk the logs newest first
+    /// by id, so `sortBy`, `sortOrder` and the `after` cursor are refused rather than ignored.
+    pub fn check_export_query(query: &LogQuery) -> Result<(), ServiceError> {
+        let mut errors = FieldErrors::new();
+        let unsupported = [
+            ("sortBy", query.sort_by.is_some()),
+            ("sortOrder", query.sort_order.is_some()),
+            ("after", query.after.is_some()),
+        ];
+        for (field, _) in unsupported.into_iter().filter(|(_, set)| *set) {
+            errors.push(field, "is not supported for exports, which are always newest first");
+        }
+        errors.into_result()
+    }
+
+    /// Writes the next batch of matching logs to `export`, newest first.
+    ///
+    /// `position` is the last log id written, or 0 to start. Batches walk the table by keyset
+    /// so large exports never use deep OFFSETs.
+    pub async fn export_logs_chunk(
+        pool: &SqlitePool,
+        query: &LogQuery,
+        position: i64,
+        tz: Tz,
+        mask: FieldMask,
+        export: &mut CsvExport,
+    ) -> Result<ExportChunk, ServiceError> {
+        let LogQuery { search, username, action, description, ip_address, route, .. } =
+            query.clone();
+        let after = if position > 0 { position } else { i64::MAX };
         let repo_query = LogListQuery {
             search,
             username,
             action,
             description,
             ip_address,
+            route,
+            sort: None,
+            cursor: Some(Cursor { after }),
         };
-        Self::create_csv_chunk(LogRepository::list_logs_for_export(pool, repo_query).await?, true)
+        let (batch, total) =
+            LogRepository::list_logs(pool, 0, EXPORT_BATCH_SIZE, repo_query).await?;
+        let is_last = batch.len() < EXPORT_BATCH_SIZE as usize;
+        let last_id = batch.last().map(|log| log.id);
+        for log in batch {
+            let log = log.masked(mask);
+            export.push_row([
+                log.id.to_string(),
+                log.user_id.to_string(),
+                log.username,
+                log.action,
+                log.description.unwrap_or_default(),
+                log.status,
+                log.duration_ms.to_string(),
+                log.ip_address,
+                log.user_agent,
+                log.created_at.with_timezone(&tz).format(CSV_TIME_FORMAT).to_string(),
+                log.route.unwrap_or_default(),
+                log.status_code.map(|code| code.to_string()).unwrap_or_default(),
+            ]);
+        }
+        Ok(ExportChunk { total, next: last_id.filter(|_| !is_last) })
     }
+}
 
-    /// Create CSV chunk for a batch of logs
-    fn create_csv_chunk(
-        logs: Vec<LogItemResp>,
-        include_header: bool,
-    ) -> Result<String, ServiceError> {
-        let mut csv_content = String::new();
+/// Audit subscriber: writes login attempts, IP bans, unusual login locations, suspicious
+/// logins and data exports to the operation log, with the client's GeoIP location when known.
+///
+/// Other admin writes are already logged per request by the log middleware.
+#[async_trait]
+impl EventSubscriber<SqlitePool> for LogService {
+    fn name(&self) -> &'static str {
+        "audit-log"
+    }
 
-        if include_header {
-            csv_content
-                .push_str("ID,user_id,username,action,description,status,duration_ms,ip_address,user_agent,created_at\n");
+    async fn handle(&self, pool: &SqlitePool, event: &DomainEvent) -> Result<(), String> {
+        let mut command = match event {
+            DomainEvent::LoginSucceeded {
+                user_id,
+                username,
+                ip_address,
+                user_agent,
+                duration_ms,
+            } => login_log_command(
+                *user_id,
+                username,
+                "SUCCESS",
+                "User login successful",
+                ip_address,
+                user_agent,
+                *duration_ms,
+            ),
+            DomainEvent::LoginFailed { username, reason, ip_address, user_agent, duration_ms } => {
+                login_log_command(0, username, "FAIL", reason, ip_address, user_agent, *duration_ms)
+            }
+            DomainEvent::LoginIpBanned { ip_address, failures, ban_secs } => LogWriteCommand {
+                user_id: 0,
+                username: String::new(),
+                action: "AUTH_IP_BAN".to_string(),
+                description: format!(
+                    "Login blocked for {} after {} failed attempts",
+                    ip_address, failures
+                ),
+                data: Some(serde_json::json!({ "failures": failures, "banSecs": ban_secs })),
+                status: "FAIL".to_string(),
+                duration_ms: 0,
+                ip_address: ip_address.clone(),
+                ..Default::default()
+            },
+            DomainEvent::LoginLocationChanged {
+                user_id,
+                username,
+                ip_address,
+                country,
+                city,
+                previous_country,
+            } => LogWriteCommand {
+                user_id: *user_id,
+                username: username.clone(),
+                action: "AUTH_UNUSUAL_LOCATION".to_string(),
+                description: format!(
+                    "Login from {}, previous login was from {}",
+                    country, previous_country
+                ),
+                data: Some(serde_json::json!({ "previousCountry": previous_country })),
+                status: "WARN".to_string(),
+                duration_ms: 0,
+                ip_address: ip_address.clone(),
+                country: Some(country.clone()),
+                city: city.clone(),
+                ..Default::default()
+            },
+            DomainEvent::SuspiciousLogin { user_id, username, rule, ip_address, detail } => {
+                LogWriteCommand {
+                    user_id: *user_id,
+                    username: username.clone(),
+                    action: "AUTH_SUSPICIOUS_LOGIN".to_string(),
+                    description: detail.clone(),
+                    data: Some(serde_json::json!({ "rule": rule })),
+                    status: "WARN".to_string(),
+                    duration_ms: 0,
+                    ip_address: ip_address.clone(),
+                    ..Default::default()
+                }
+            }
+            DomainEvent::DataExported {
+                user_id,
+                username,
+                resource,
+                filters,
+                rows,
+                ip_address,
+            } => LogWriteCommand {
+                user_id: *user_id,
+                username: username.clone(),
+                action: "DATA_EXPORT".to_string(),
+                description: format!("Exported {} {}", rows, resource),
+                data: Some(serde_json::json!({
+                    "resource": resource,
+                    "filters": filters,
+                    "rows": rows,
+                })),
+                status: "SUCCESS".to_string(),
+                duration_ms: 0,
+                ip_address: ip_address.clone(),
+                ..Default::default()
+            },
+            _ => return Ok(()),
+        };
+        if command.country.is_none() {
+            let GeoLocation { country, city, .. } =
+                geoip::lookup(&command.ip_address).unwrap_or_default();
+            command.country = country;
+            command.city = city;
         }
-
-        csv_content.push_str(
-            &logs
-                .into_iter()
-                .map(|log| {
-                    format!(
-                        "{},{},{},{},{},{},{},{},{},{}\n",
-                        log.id,
-                        log.user_id,
-                        Self::escape_csv_field(&log.username),
-                        Self::escape_csv_field(&log.action),
-                        Self::escape_csv_field(log.description.as_deref().unwrap_or("")),
-                        Self::escape_csv_field(&log.status),
-                        log.duration_ms,
-                        Self::escape_csv_field(&log.ip_address.to_string()),
-                        Self::escape_csv_field(&log.user_agent),
-                        log.created_at.format("%Y-%m-%d %H:%M:%S")
-                    )
-                })
-                .collect::<String>(),
-        );
-
-        Ok(csv_content)
+        Self::log_operation(pool, command).await;
+        Ok(())
     }
+}
 
-    /// Escape CSV field to handle commas, quotes, and newlines
-    fn escape_csv_field(field: &str) -> String {
-        if field.contains(',')
-            || field.contains('"')
-            || field.contains('\n')
-            || field.contains('\r')
-        {
-            // Escape quotes by doubling them and wrap in quotes
-            let escaped = field.replace('"', "\"\"");
-            format!("\"{}\"", escaped)
-        } else {
-            field.to_string()
-        }
+fn login_log_command(
+    user_id: i64,
+    username: &str,
+    status: &str,
+    description: &str,
+    ip_address: &str,
+    user_agent: &str,
+    duration_ms: i32,
+) -> LogWriteCommand {
+    LogWriteCommand {
+        user_id,
+        username: username.to_string(),
+        action: "AUTH_LOGIN".to_string(),
+        description: description.to_string(),
+        data: Some(serde_json::json!({})),
+        status: status.to_string(),
+        duration_ms,
+        ip_address: ip_address.to_string(),
+        user_agent: user_agent.to_string(),
+        ..Default::default()
     }
 }
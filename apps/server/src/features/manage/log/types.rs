@@ -1,7 +1,16 @@
-use chrono::NaiveDateTime;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::{
+    common::{
+        mask::{FieldMask, MaskFields},
+        pagination::{Cursor, Sort},
+        user_agent::DeviceInfo,
+    },
+    infra::slow_log::{SlowQuery, SlowRequest},
+};
+
 /// Log item for list display
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 #[serde(rename_all = "camelCase")]
@@ -14,9 +23,28 @@ pub struct LogItemResp {
     pub data: Option<Value>,
     pub status: String,
     pub duration_ms: i32,
+    /// Masked unless the caller holds `system:privacy:view`.
     pub ip_address: String,
     pub user_agent: String,
-    pub created_at: NaiveDateTime,
+    /// Readable name and type of `user_agent`; filled in for the log list.
+    #[sqlx(skip)]
+    #[serde(default, skip_deserializing)]
+    pub device: Option<DeviceInfo>,
+    /// Matched route template such as `/api/system/users/{id}`; only on HTTP request logs.
+    pub route: Option<String>,
+    pub resource_type: Option<String>,
+    pub resource_id: Option<String>,
+    pub status_code: Option<i32>,
+    /// Client country (ISO code) and city from the GeoIP database; only on login logs.
+    pub country: Option<String>,
+    pub city: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl MaskFields for LogItemResp {
+    fn masked(self, mask: FieldMask) -> Self {
+        Self { ip_address: mask.ip(self.ip_address), ..self }
+    }
 }
 
 /// Log query parameters
@@ -30,6 +58,12 @@ pub struct LogQuery {
     pub action: Option<String>,
     pub description: Option<String>,
     pub ip_address: Option<String>,
+    /// Exact route template.
+    pub route: Option<String>,
+    pub sort_by: Option<String>,
+    pub sort_order: Option<String>,
+    /// Keyset cursor: return logs with an id below this value instead of using `current`.
+    pub after: Option<i64>,
 }
 
 #[derive(Debug, Clone)]
@@ -39,10 +73,61 @@ pub struct LogListQuery {
     pub action: Option<String>,
     pub description: Option<String>,
     pub ip_address: Option<String>,
+    pub route: Option<String>,
+    pub sort: Option<Sort>,
+    pub cursor: Option<Cursor>,
+}
+
+/// Per-route request statistics query.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogRouteStatsQuery {
+    /// Look-back window in hours; defaults to 24.
+    pub hours: Option<i64>,
+}
+
+/// Log purge query.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogPurgeQuery {
+    /// Logs older than this many days are deleted; at least 1.
+    pub older_than_days: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogPurgeResp {
+    pub deleted: u64,
+}
+
+/// Request count, error count and latency of one method and route template.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct LogRouteStatsResp {
+    pub method: String,
+    pub route: String,
+    pub requests: i64,
+    pub errors: i64,
+    pub avg_duration_ms: f64,
+    pub max_duration_ms: i64,
+}
+
+/// Recent slow requests and SQL statements of this process, newest first.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowLogResp {
+    /// `0` when slow request detection is off.
+    pub request_threshold_ms: u64,
+    /// `0` when slow query detection is off.
+    pub query_threshold_ms: u64,
+    pub slow_requests_total: u64,
+    pub slow_queries_total: u64,
+    pub requests: Vec<SlowRequest>,
+    pub queries: Vec<SlowQuery>,
 }
 
 /// Log write command used by the service and repository layers.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct LogWriteCommand {
     pub user_id: i64,
     pub username: String,
@@ -53,4 +138,10 @@ pub struct LogWriteCommand {
     pub duration_ms: i32,
     pub ip_address: String,
     pub user_agent: String,
+    pub route: Option<String>,
+    pub resource_type: Option<String>,
+    pub resource_id: Option<String>,
+    pub status_code: Option<i32>,
+    pub country: Option<String>,
+    pub city: Option<String>,
 }
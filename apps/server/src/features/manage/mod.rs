@@ -4,6 +4,7 @@ pub mod log;
 pub mod task;
 
 use axum::Router;
+use rustzen_core::permission::RouterExt;
 use sqlx::SqlitePool;
 
 use deploy::deploy_routes;
@@ -13,8 +14,8 @@ use task::task_routes;
 
 pub fn manage_routes() -> Router<SqlitePool> {
     Router::new()
-        .nest("/dicts", dict_routes())
-        .nest("/logs", log_routes())
-        .nest("/tasks", task_routes())
-        .nest("/deploy", deploy_routes())
+        .nest_routes("/dicts", dict_routes)
+        .nest_routes("/logs", log_routes)
+        .nest_routes("/tasks", task_routes)
+        .nest_routes("/deploy", deploy_routes)
 }
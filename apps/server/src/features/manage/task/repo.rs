@@ -29,11 +29,37 @@ pub struct InsertTaskRunInput<'a> {
     pub error_message: Option<&'a str>,
 }
 
+pub struct FinishTaskRunInput<'a> {
+    pub run_id: i64,
+    pub task_key: &'a str,
+    pub trigger_type: TaskTriggerType,
+    pub status: TaskRunStatus,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub error_message: Option<&'a str>,
+}
+
+/// Latest run written to the task's summary columns.
+struct TaskSummary<'a> {
+    task_key: &'a str,
+    run_id: i64,
+    trigger_type: &'a str,
+    status: &'a str,
+    started_at: DateTime<Utc>,
+    finished_at: Option<DateTime<Utc>>,
+    error_message: Option<&'a str>,
+}
+
 impl TaskRepository {
     pub fn new(pool: SqlitePool) -> Self {
         Self { pool }
     }
 
+    /// Pool for executors that call into other features.
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+
     pub async fn fail_stale_running_task_runs(
         &self,
         finished_at: DateTime<Utc>,
@@ -69,15 +95,17 @@ impl TaskRepository {
             .await
             .map_err(map_db_error)?;
 
-            update_task_summary_raw(
+            update_task_summary(
                 &mut tx,
-                &task_key,
-                run_id,
-                &trigger_type,
-                "failed",
-                started_at,
-                Some(finished_at),
-                Some("Task process stopped before completion"),
+                TaskSummary {
+                    task_key: &task_key,
+                    run_id,
+                    trigger_type: &trigger_type,
+                    status: "failed",
+                    started_at,
+                    finished_at: Some(finished_at),
+                    error_message: Some("Task process stopped before completion"),
+                },
             )
             .await?;
         }
@@ -247,13 +275,15 @@ impl TaskRepository {
 
         update_task_summary(
             &mut tx,
-            input.task_key,
-            row.id,
-            input.trigger_type,
-            input.status,
-            input.started_at,
-            input.finished_at,
-            input.error_message,
+            TaskSummary {
+                task_key: input.task_key,
+                run_id: row.id,
+                trigger_type: trigger_type_to_str(input.trigger_type),
+                status: task_status_to_str(input.status),
+                started_at: input.started_at,
+                finished_at: input.finished_at,
+                error_message: input.error_message,
+            },
         )
         .await?;
 
@@ -293,13 +323,7 @@ impl TaskRepository {
 
     pub async fn finish_task_run(
         &self,
-        run_id: i64,
-        task_key: &str,
-        trigger_type: TaskTriggerType,
-        status: TaskRunStatus,
-        started_at: DateTime<Utc>,
-        finished_at: DateTime<Utc>,
-        error_message: Option<&str>,
+        input: FinishTaskRunInput<'_>,
     ) -> Result<TaskRunItem, ServiceError> {
         let mut tx = self.pool.begin().await.map_err(map_db_error)?;
         let row: TaskRunRow = sqlx::query_as(
@@ -311,24 +335,26 @@ impl TaskRepository {
                       finished_at, error_message, created_at, updated_at
             "#,
         )
-        .bind(task_status_to_str(status))
-        .bind(finished_at)
-        .bind(error_message)
-        .bind(finished_at)
-        .bind(run_id)
+        .bind(task_status_to_str(input.status))
+        .bind(input.finished_at)
+        .bind(input.error_message)
+        .bind(input.finished_at)
+        .bind(input.run_id)
         .fetch_one(&mut *tx)
         .await
         .map_err(map_db_error)?;
 
         update_task_summary(
             &mut tx,
-            task_key,
-            run_id,
-            &trigger_type,
-            status,
-            started_at,
-            Some(finished_at),
-            error_message,
+            TaskSummary {
+                task_key: input.task_key,
+                run_id: input.run_id,
+                trigger_type: trigger_type_to_str(&input.trigger_type),
+                status: task_status_to_str(input.status),
+                started_at: input.started_at,
+                finished_at: Some(input.finished_at),
+                error_message: input.error_message,
+            },
         )
         .await?;
 
@@ -359,37 +385,17 @@ impl TaskRepository {
 
 async fn update_task_summary(
     tx: &mut sqlx::Transaction<'_, Sqlite>,
-    task_key: &str,
-    run_id: i64,
-    trigger_type: &TaskTriggerType,
-    status: TaskRunStatus,
-    started_at: DateTime<Utc>,
-    finished_at: Option<DateTime<Utc>>,
-    error_message: Option<&str>,
+    summary: TaskSummary<'_>,
 ) -> Result<(), ServiceError> {
-    update_task_summary_raw(
-        tx,
+    let TaskSummary {
         task_key,
         run_id,
-        trigger_type_to_str(trigger_type),
-        task_status_to_str(status),
+        trigger_type,
+        status,
         started_at,
         finished_at,
         error_message,
-    )
-    .await
-}
-
-async fn update_task_summary_raw(
-    tx: &mut sqlx::Transaction<'_, Sqlite>,
-    task_key: &str,
-    run_id: i64,
-    trigger_type: &str,
-    status: &str,
-    started_at: DateTime<Utc>,
-    finished_at: Option<DateTime<Utc>>,
-    error_message: Option<&str>,
-) -> Result<(), ServiceError> {
+    } = summary;
     let running = if status == "running" { 1 } else { 0 };
     sqlx::query(
         r#"
@@ -6,13 +6,25 @@ use tokio::sync::{Mutex, RwLock};
 use tokio_cron_scheduler::{Job, JobScheduler};
 
 use crate::{
-    common::{api::ApiResponse, error::ServiceError, pagination::{Pagination, PaginationQuery}},
-    infra::config::CONFIG,
+    common::{
+        api::{ApiResponse, PageMeta},
+        error::ServiceError,
+        pagination::{Pagination, PaginationQuery},
+    },
+    features::system::{
+        export_job::service::{EXPORT_JOBS_TASK_KEY, ExportJobService},
+        report::{service::ReportService, types::ReportTrigger},
+        user::service::{ROLE_EXPIRY_TASK_KEY, UserService},
+    },
+    infra::config::AppConfig,
 };
 
 use super::{
-    repo::{InsertTaskRunInput, SyncTaskInput, TaskRepository},
-    types::{TaskExecutionContext, TaskExecutor, TaskItem, TaskRunItem, TaskRunQuery, TaskRunStatus, TaskTriggerType},
+    repo::{FinishTaskRunInput, InsertTaskRunInput, SyncTaskInput, TaskRepository},
+    types::{
+        TaskExecutionContext, TaskExecutor, TaskItem, TaskRunItem, TaskRunQuery, TaskRunStatus,
+        TaskTriggerType,
+    },
 };
 
 #[derive(Clone)]
@@ -20,6 +32,7 @@ pub struct TaskService {
     repo: Arc<TaskRepository>,
     catalog: Arc<RwLock<Option<TaskCatalog>>>,
     scheduler: Arc<RwLock<Option<JobScheduler>>>,
+    config: &'static AppConfig,
     timezone: Tz,
 }
 
@@ -50,9 +63,12 @@ struct TaskSpec {
 enum TaskKind {
     CleanupOperationLogs,
     CleanupTaskRuns,
+    WeeklyReport,
+    ExportJobs,
+    RoleExpiry,
 }
 
-const TASK_SPECS: [TaskSpec; 2] = [
+const TASK_SPECS: [TaskSpec; 5] = [
     TaskSpec {
         task_key: "cleanup-operation-logs-retention",
         name: "Cleanup Operation Logs",
@@ -67,23 +83,45 @@ const TASK_SPECS: [TaskSpec; 2] = [
         expression: "0 30 1 * * * *",
         kind: TaskKind::CleanupTaskRuns,
     },
+    TaskSpec {
+        task_key: "weekly-report",
+        name: "Weekly Report",
+        description: "Render last week's dashboard stats and request logs as XLSX/PDF and email them.",
+        expression: "0 0 7 * * Mon *",
+        kind: TaskKind::WeeklyReport,
+    },
+    TaskSpec {
+        task_key: EXPORT_JOBS_TASK_KEY,
+        name: "Export Jobs",
+        description: "Write queued background exports to CSV files and delete expired ones.",
+        expression: "0 */5 * * * * *",
+        kind: TaskKind::ExportJobs,
+    },
+    TaskSpec {
+        task_key: ROLE_EXPIRY_TASK_KEY,
+        name: "Role Expiry",
+        description: "Remove expired temporary role assignments and notify their users.",
+        expression: "0 */5 * * * * *",
+        kind: TaskKind::RoleExpiry,
+    },
 ];
 
 impl TaskService {
-    pub fn new(pool: sqlx::SqlitePool) -> Result<Self, ServiceError> {
-        let timezone = CONFIG.timezone.parse::<Tz>().map_err(|_| {
-            ServiceError::InvalidOperation(format!("Invalid RUSTZEN_TIMEZONE: {}", CONFIG.timezone))
+    pub fn new(config: &'static AppConfig, pool: sqlx::SqlitePool) -> Result<Self, ServiceError> {
+        let timezone = config.timezone().map_err(|timezone| {
+            ServiceError::InvalidOperation(format!("Invalid RUSTZEN_TIMEZONE: {}", timezone))
         })?;
         Ok(Self {
             repo: Arc::new(TaskRepository::new(pool)),
             catalog: Arc::new(RwLock::new(None)),
             scheduler: Arc::new(RwLock::new(None)),
+            config,
             timezone,
         })
     }
 
     pub async fn bootstrap(&self) -> Result<(), ServiceError> {
-        let catalog = TaskCatalog::new(self.repo.clone());
+        let catalog = TaskCatalog::new(self.config, self.repo.clone());
         let mut scheduler = JobScheduler::new().await.map_err(|err| {
             ServiceError::InvalidOperation(format!("Failed to create task scheduler: {err}"))
         })?;
@@ -164,7 +202,7 @@ impl TaskService {
             .repo
             .list_task_runs(task_key, pagination.offset.into(), pagination.limit.into())
             .await?;
-        Ok(ApiResponse::new(items, Some(total)))
+        Ok(ApiResponse::with_page(items, total, PageMeta::new(pagination, total)))
     }
 
     pub async fn run_task(&self, task_key: &str) -> Result<TaskRunItem, ServiceError> {
@@ -177,8 +215,7 @@ impl TaskService {
         next_run_at: Option<DateTime<Utc>>,
     ) -> Result<TaskRunItem, ServiceError> {
         self.repo.update_task_next_run_at(task_key, next_run_at).await?;
-        self.start_task_by_key(task_key, TaskTriggerType::Scheduled, Some(Utc::now()))
-            .await
+        self.start_task_by_key(task_key, TaskTriggerType::Scheduled, Some(Utc::now())).await
     }
 
     async fn next_run_at_for_expression(
@@ -189,7 +226,9 @@ impl TaskService {
             ServiceError::InvalidOperation(format!("Failed to create task scheduler: {err}"))
         })?;
         let job = Job::new_async_tz(expression, self.timezone, |_uuid, _lock| Box::pin(async {}))
-            .map_err(|err| ServiceError::InvalidOperation(format!("Invalid cron expression: {err}")))?;
+            .map_err(|err| {
+            ServiceError::InvalidOperation(format!("Invalid cron expression: {err}"))
+        })?;
         let job_id = scheduler.add(job).await.map_err(|err| {
             ServiceError::InvalidOperation(format!("Failed to register scheduled task: {err}"))
         })?;
@@ -209,7 +248,9 @@ impl TaskService {
             .read()
             .await
             .as_ref()
-            .ok_or_else(|| ServiceError::InvalidOperation("Task scheduler is not initialized".to_string()))?
+            .ok_or_else(|| {
+                ServiceError::InvalidOperation("Task scheduler is not initialized".to_string())
+            })?
             .get(task_key)
             .ok_or_else(|| ServiceError::NotFound(format!("Task {task_key}")))?;
 
@@ -281,15 +322,15 @@ impl TaskService {
             };
 
             if let Err(err) = repo
-                .finish_task_run(
+                .finish_task_run(FinishTaskRunInput {
                     run_id,
-                    &task_key,
+                    task_key: &task_key,
                     trigger_type,
                     status,
                     started_at,
                     finished_at,
-                    message.as_deref(),
-                )
+                    error_message: message.as_deref(),
+                })
                 .await
             {
                 tracing::error!("Failed to finish task run {}: {}", run_id, err);
@@ -303,7 +344,7 @@ impl TaskService {
 }
 
 impl TaskCatalog {
-    fn new(repo: Arc<TaskRepository>) -> Self {
+    fn new(config: &'static AppConfig, repo: Arc<TaskRepository>) -> Self {
         let tasks = TASK_SPECS
             .iter()
             .map(|spec| ScheduledTask {
@@ -311,7 +352,7 @@ impl TaskCatalog {
                 name: spec.name,
                 description: spec.description,
                 expression: spec.expression,
-                executor: spec.kind.executor(repo.clone()),
+                executor: spec.kind.executor(config, repo.clone()),
                 run_lock: Arc::new(Mutex::new(())),
             })
             .collect();
@@ -319,23 +360,30 @@ impl TaskCatalog {
     }
 
     fn get(&self, task_key: &str) -> Option<ScheduledTask> {
-        self.tasks
-            .iter()
-            .find(|task| task.task_key == task_key)
-            .cloned()
+        self.tasks.iter().find(|task| task.task_key == task_key).cloned()
     }
 }
 
 impl TaskKind {
-    fn executor(&self, repo: Arc<TaskRepository>) -> Arc<dyn TaskExecutor> {
+    fn executor(
+        &self,
+        config: &'static AppConfig,
+        repo: Arc<TaskRepository>,
+    ) -> Arc<dyn TaskExecutor> {
         match self {
-            TaskKind::CleanupOperationLogs => Arc::new(CleanupOperationLogsExecutor { repo }),
-            TaskKind::CleanupTaskRuns => Arc::new(CleanupTaskRunsExecutor { repo }),
+            TaskKind::CleanupOperationLogs => {
+                Arc::new(CleanupOperationLogsExecutor { config, repo })
+            }
+            TaskKind::CleanupTaskRuns => Arc::new(CleanupTaskRunsExecutor { config, repo }),
+            TaskKind::WeeklyReport => Arc::new(WeeklyReportExecutor { config, repo }),
+            TaskKind::ExportJobs => Arc::new(ExportJobsExecutor { config, repo }),
+            TaskKind::RoleExpiry => Arc::new(RoleExpiryExecutor { repo }),
         }
     }
 }
 
 struct CleanupOperationLogsExecutor {
+    config: &'static AppConfig,
     repo: Arc<TaskRepository>,
 }
 
@@ -349,16 +397,15 @@ impl TaskExecutor for CleanupOperationLogsExecutor {
             scheduled_for = ?ctx.scheduled_for,
             "Cleaning operation logs"
         );
-        let deleted = self
-            .repo
-            .cleanup_old_operation_logs(CONFIG.log_retention_days as i64)
-            .await?;
+        let deleted =
+            self.repo.cleanup_old_operation_logs(self.config.log.log_retention_days as i64).await?;
         tracing::info!(deleted, "Operation log cleanup completed");
         Ok(())
     }
 }
 
 struct CleanupTaskRunsExecutor {
+    config: &'static AppConfig,
     repo: Arc<TaskRepository>,
 }
 
@@ -372,11 +419,75 @@ impl TaskExecutor for CleanupTaskRunsExecutor {
             scheduled_for = ?ctx.scheduled_for,
             "Cleaning task runs"
         );
-        let deleted = self
-            .repo
-            .cleanup_old_task_runs(CONFIG.task_run_retention_days)
-            .await?;
+        let deleted =
+            self.repo.cleanup_old_task_runs(self.config.ops.task_run_retention_days).await?;
         tracing::info!(deleted, "Task run cleanup completed");
         Ok(())
     }
 }
+
+struct WeeklyReportExecutor {
+    config: &'static AppConfig,
+    repo: Arc<TaskRepository>,
+}
+
+#[async_trait::async_trait]
+impl TaskExecutor for WeeklyReportExecutor {
+    async fn execute(&self, ctx: TaskExecutionContext) -> Result<(), ServiceError> {
+        tracing::info!(
+            task_key = %ctx.task_key,
+            task_name = %ctx.task_name,
+            trigger_type = ?ctx.trigger_type,
+            scheduled_for = ?ctx.scheduled_for,
+            "Generating weekly report"
+        );
+        let trigger = match ctx.trigger_type {
+            TaskTriggerType::Scheduled => ReportTrigger::Scheduled,
+            TaskTriggerType::Manual => ReportTrigger::Manual,
+        };
+        ReportService::generate(self.config, self.repo.pool(), trigger).await?;
+        Ok(())
+    }
+}
+
+struct ExportJobsExecutor {
+    config: &'static AppConfig,
+    repo: Arc<TaskRepository>,
+}
+
+#[async_trait::async_trait]
+impl TaskExecutor for ExportJobsExecutor {
+    async fn execute(&self, ctx: TaskExecutionContext) -> Result<(), ServiceError> {
+        tracing::info!(
+            task_key = %ctx.task_key,
+            task_name = %ctx.task_name,
+            trigger_type = ?ctx.trigger_type,
+            scheduled_for = ?ctx.scheduled_for,
+            "Processing export jobs"
+        );
+        let expired = ExportJobService::expire_files(self.config, self.repo.pool()).await?;
+        let processed = ExportJobService::process_pending(self.config, self.repo.pool()).await?;
+        tracing::info!(processed, expired, "Export jobs processed");
+        Ok(())
+    }
+}
+
+struct RoleExpiryExecutor {
+    repo: Arc<TaskRepository>,
+}
+
+#[async_trait::async_trait]
+impl TaskExecutor for RoleExpiryExecutor {
+    async fn execute(&self, ctx: TaskExecutionContext) -> Result<(), ServiceError> {
+        tracing::info!(
+            task_key = %ctx.task_key,
+            task_name = %ctx.task_name,
+            trigger_type = ?ctx.trigger_type,
+            scheduled_for = ?ctx.scheduled_for,
+            "Removing expired role assignments"
+        );
+        let removed = UserService::expire_roles(self.repo.pool()).await?;
+        tracing::info!(removed, "Role expiry completed");
+        Ok(())
+    }
+}
@@ -2,18 +2,22 @@ pub mod account;
 pub mod auth;
 pub mod dashboard;
 pub mod manage;
+pub mod oauth;
 pub mod system;
+pub mod workflow;
 
 #[cfg(test)]
 mod tests {
-    use super::account::service::AccountService;
-    use crate::infra::password::PasswordUtils;
+    use super::account::{service::AccountService, types::UpdateAccountProfileRequest};
+    use crate::infra::{config::test_config, password::PasswordUtils};
 
     #[test]
     fn account_password_change_requires_current_password_and_confirmation() {
-        let current_hash = PasswordUtils::hash_password("current-password").expect("hash");
+        let config = test_config();
+        let current_hash = PasswordUtils::hash_password(config, "current-password").expect("hash");
 
         let new_hash = AccountService::build_password_hash(
+            config,
             "current-password",
             &current_hash,
             "new-password",
@@ -25,6 +29,7 @@ mod tests {
         assert!(!PasswordUtils::verify_password("current-password", &new_hash));
         assert!(
             AccountService::build_password_hash(
+                config,
                 "wrong-password",
                 &current_hash,
                 "new-password",
@@ -34,6 +39,7 @@ mod tests {
         );
         assert!(
             AccountService::build_password_hash(
+                config,
                 "current-password",
                 &current_hash,
                 "new-password",
@@ -42,4 +48,25 @@ mod tests {
             .is_err()
         );
     }
+
+    #[test]
+    fn account_profile_is_trimmed_and_validated() {
+        let profile = AccountService::normalize_profile(UpdateAccountProfileRequest {
+            email: "  me@example.com ".to_string(),
+            real_name: Some("   ".to_string()),
+        })
+        .expect("valid profile");
+        assert_eq!(profile.email, "me@example.com");
+        assert_eq!(profile.real_name, None);
+
+        for email in ["", "me", "@example.com", "me@localhost", "me@a@b.com"] {
+            let request = UpdateAccountProfileRequest { email: email.to_string(), real_name: None };
+            assert!(AccountService::normalize_profile(request).is_err(), "{email}");
+        }
+        let request = UpdateAccountProfileRequest {
+            email: "me@example.com".to_string(),
+            real_name: Some("x".repeat(51)),
+        };
+        assert!(AccountService::normalize_profile(request).is_err());
+    }
 }
@@ -0,0 +1,113 @@
+use super::{
+    service::OAuthService,
+    types::{LinkedIdentityResp, OAuthAuthorizeResp, OAuthCallbackRequest},
+};
+use crate::{
+    common::{
+        api::{ApiResponse, AppResult},
+        error::AppError,
+    },
+    features::auth::{
+        handler::login_response,
+        types::{LoginCredentials, LoginResp},
+    },
+    infra::{
+        config::AppConfig,
+        oauth::{OAuthIntent, STATE_COOKIE, state_cookie},
+    },
+};
+
+use axum::{
+    Extension, Json,
+    extract::{ConnectInfo, Path, State},
+    http::HeaderMap,
+};
+use axum_extra::extract::cookie::CookieJar;
+use rustzen_core::auth::CurrentUser;
+use sqlx::SqlitePool;
+use std::net::SocketAddr;
+
+/// List the login providers that are switched on
+pub async fn list_providers(
+    Extension(config): Extension<&'static AppConfig>,
+) -> AppResult<Vec<&'static str>> {
+    Ok(ApiResponse::success(OAuthService::providers(config)))
+}
+
+/// Start a scan-to-login round trip
+///
+/// Sets the state cookie the callback must come back with.
+pub async fn authorize_login(
+    Extension(config): Extension<&'static AppConfig>,
+    Path(provider): Path<String>,
+) -> Result<(CookieJar, Json<ApiResponse<OAuthAuthorizeResp>>), AppError> {
+    authorize_response(config, &provider, OAuthIntent::Login)
+}
+
+/// Finish a scan-to-login round trip with the provider's `code` and `state`
+///
+/// Sessions are issued exactly as for password login.
+#[tracing::instrument(name = "oauth_login", skip(config, pool, addr, headers, request))]
+pub async fn oauth_login(
+    Extension(config): Extension<&'static AppConfig>,
+    State(pool): State<SqlitePool>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    jar: CookieJar,
+    Json(request): Json<OAuthCallbackRequest>,
+) -> Result<(CookieJar, Json<ApiResponse<LoginResp>>), AppError> {
+    let OAuthCallbackRequest { state, code } = request;
+    let state_cookie = jar.get(STATE_COOKIE).map(|cookie| cookie.value().to_string());
+    let credentials = LoginCredentials::OAuth { state, code, state_cookie };
+    login_response(config, &pool, addr, &headers, credentials).await
+}
+
+/// List the provider accounts linked to the current user
+pub async fn list_identities(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+) -> AppResult<Vec<LinkedIdentityResp>> {
+    Ok(ApiResponse::success(OAuthService::list(&pool, current_user.user_id).await?))
+}
+
+/// Start a round trip that links a provider account to the current user
+pub async fn authorize_link(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    Path(provider): Path<String>,
+) -> Result<(CookieJar, Json<ApiResponse<OAuthAuthorizeResp>>), AppError> {
+    authorize_response(config, &provider, OAuthIntent::Link(current_user.user_id))
+}
+
+/// Link the provider account from the redirect's `code` and `state`
+pub async fn link_identity(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    State(pool): State<SqlitePool>,
+    jar: CookieJar,
+    Json(request): Json<OAuthCallbackRequest>,
+) -> AppResult<Vec<LinkedIdentityResp>> {
+    let state_cookie = jar.get(STATE_COOKIE).map(|cookie| cookie.value());
+    let identities =
+        OAuthService::link(config, &pool, current_user.user_id, request, state_cookie).await?;
+    Ok(ApiResponse::success(identities))
+}
+
+/// Unlink the current user's account at a provider
+pub async fn unlink_identity(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Path(provider): Path<String>,
+) -> AppResult<Vec<LinkedIdentityResp>> {
+    Ok(ApiResponse::success(OAuthService::unlink(&pool, current_user.user_id, &provider).await?))
+}
+
+fn authorize_response(
+    config: &AppConfig,
+    provider: &str,
+    intent: OAuthIntent,
+) -> Result<(CookieJar, Json<ApiResponse<OAuthAuthorizeResp>>), AppError> {
+    let response = OAuthService::authorize(config, provider, intent)?;
+    let jar = CookieJar::new().add(state_cookie(config, &response.state));
+    Ok((jar, ApiResponse::success(response)))
+}
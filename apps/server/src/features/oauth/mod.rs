@@ -0,0 +1,33 @@
+//! Scan-to-login through WeChat Work, DingTalk and Feishu, and linking those accounts to
+//! local users. The connectors themselves live in `infra::oauth`.
+
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use handler::{
+    authorize_link, authorize_login, link_identity, list_identities, list_providers, oauth_login,
+    unlink_identity,
+};
+use sqlx::SqlitePool;
+
+/// Unauthenticated routes merged under `/api/auth`.
+pub fn public_oauth_routes() -> Router<SqlitePool> {
+    Router::new()
+        .route("/oauth/providers", get(list_providers))
+        .route("/oauth/{provider}/authorize", get(authorize_login))
+        .route("/oauth/callback", post(oauth_login))
+}
+
+/// Current-user routes nested under `/api/account/identities`.
+pub fn identity_routes() -> Router<SqlitePool> {
+    Router::new()
+        .route("/", get(list_identities).post(link_identity))
+        .route("/{provider}/authorize", get(authorize_link))
+        .route("/{provider}/unbind", post(unlink_identity))
+}
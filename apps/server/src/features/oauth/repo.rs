@@ -0,0 +1,110 @@
+use super::types::LinkedIdentityResp;
+use crate::{common::error::ServiceError, features::auth::types::LoginCredentialsRow};
+
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+fn db_error(context: &str, e: sqlx::Error) -> ServiceError {
+    tracing::error!("Database error {}: {:?}", context, e);
+    ServiceError::DatabaseQueryFailed
+}
+
+/// Links between login provider accounts and local users.
+pub struct OAuthRepository;
+
+impl OAuthRepository {
+    /// Login credentials of the live user linked to `subject` at `provider`.
+    pub async fn find_linked_user(
+        pool: &SqlitePool,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Option<LoginCredentialsRow>, ServiceError> {
+        sqlx::query_as::<_, LoginCredentialsRow>(
+            "SELECT u.id, u.username, u.password_hash, u.status
+             FROM user_identities i JOIN users u ON u.id = i.user_id
+             WHERE i.provider = ? AND i.subject = ? AND u.deleted_at IS NULL",
+        )
+        .bind(provider)
+        .bind(subject)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_error("finding linked user", e))
+    }
+
+    /// Links `subject` to `user_id`, replacing the user's earlier account at `provider`.
+    pub async fn link(
+        pool: &SqlitePool,
+        user_id: i64,
+        provider: &str,
+        subject: &str,
+        display_name: Option<&str>,
+    ) -> Result<(), ServiceError> {
+        sqlx::query(
+            "INSERT INTO user_identities (user_id, provider, subject, display_name, created_at)
+             VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT (user_id, provider) DO UPDATE
+             SET subject = excluded.subject, display_name = excluded.display_name,
+                 created_at = excluded.created_at, last_login_at = NULL",
+        )
+        .bind(user_id)
+        .bind(provider)
+        .bind(subject)
+        .bind(display_name)
+        .bind(Utc::now().naive_utc())
+        .execute(pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                ServiceError::IdentityConflict
+            }
+            e => db_error("linking identity", e),
+        })?;
+        Ok(())
+    }
+
+    pub async fn touch_login(
+        pool: &SqlitePool,
+        provider: &str,
+        subject: &str,
+    ) -> Result<(), ServiceError> {
+        sqlx::query(
+            "UPDATE user_identities SET last_login_at = ? WHERE provider = ? AND subject = ?",
+        )
+        .bind(Utc::now().naive_utc())
+        .bind(provider)
+        .bind(subject)
+        .execute(pool)
+        .await
+        .map_err(|e| db_error("recording identity login", e))?;
+        Ok(())
+    }
+
+    pub async fn list_for_user(
+        pool: &SqlitePool,
+        user_id: i64,
+    ) -> Result<Vec<LinkedIdentityResp>, ServiceError> {
+        sqlx::query_as::<_, LinkedIdentityResp>(
+            "SELECT provider, subject, display_name, created_at, last_login_at
+             FROM user_identities WHERE user_id = ? ORDER BY provider",
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("listing identities", e))
+    }
+
+    /// Returns `false` when the user had no account linked at `provider`.
+    pub async fn unlink(
+        pool: &SqlitePool,
+        user_id: i64,
+        provider: &str,
+    ) -> Result<bool, ServiceError> {
+        let result = sqlx::query("DELETE FROM user_identities WHERE user_id = ? AND provider = ?")
+            .bind(user_id)
+            .bind(provider)
+            .execute(pool)
+            .await
+            .map_err(|e| db_error("unlinking identity", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+}
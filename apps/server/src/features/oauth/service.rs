@@ -0,0 +1,212 @@
+use super::{
+    repo::OAuthRepository,
+    types::{LinkedIdentityResp, OAuthAuthorizeResp, OAuthCallbackRequest},
+};
+use crate::{
+    common::{error::ServiceError, token},
+    features::{
+        auth::{
+            repo::AuthRepository,
+            types::{LoginCredentialsRow, UserStatus},
+        },
+        system::user::{repo::UserRepository, service::UserService, types::CreateUserRequest},
+    },
+    infra::{
+        config::AppConfig,
+        oauth::{self, OAuthIntent, STATES},
+    },
+};
+
+use rustzen_core::oauth::ExternalIdentity;
+use sqlx::SqlitePool;
+use std::time::Instant;
+
+pub struct OAuthService;
+
+impl OAuthService {
+    /// Providers whose connector is switched on.
+    pub fn providers(config: &AppConfig) -> Vec<&'static str> {
+        oauth::connectors(config).iter().map(|c| c.provider()).collect()
+    }
+
+    /// Starts a provider round trip and returns the QR page to open.
+    pub fn authorize(
+        config: &AppConfig,
+        provider: &str,
+        intent: OAuthIntent,
+    ) -> Result<OAuthAuthorizeResp, ServiceError> {
+        let connector = oauth::connector(config, provider)
+            .ok_or_else(|| ServiceError::NotFound("Login provider".to_string()))?;
+        let redirect_uri = config.oauth.oauth_redirect_url.as_deref().unwrap_or_default();
+        let state = STATES.issue(connector.provider(), intent, Instant::now());
+        Ok(OAuthAuthorizeResp {
+            provider: connector.provider().to_string(),
+            url: connector.authorize_url(redirect_uri, &state),
+            state,
+        })
+    }
+
+    /// Resolves a login redirect to the linked user, creating one first when the provider
+    /// is set to auto-provision.
+    pub async fn resolve_login(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        state: &str,
+        code: &str,
+        state_cookie: Option<&str>,
+    ) -> Result<LoginCredentialsRow, ServiceError> {
+        let (provider, identity) =
+            Self::exchange(config, state, code, OAuthIntent::Login, state_cookie).await?;
+        let user =
+            match OAuthRepository::find_linked_user(pool, provider, &identity.subject).await? {
+                Some(user) => user,
+                None if config.oauth_auto_provision_list().iter().any(|p| p == provider) => {
+                    Self::provision(config, pool, provider, &identity).await?
+                }
+                None => return Err(ServiceError::ExternalAccountNotLinked(provider.to_string())),
+            };
+        OAuthRepository::touch_login(pool, provider, &identity.subject).await?;
+        Ok(user)
+    }
+
+    /// Links the provider account from a redirect started by `user_id`.
+    pub async fn link(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        user_id: i64,
+        request: OAuthCallbackRequest,
+        state_cookie: Option<&str>,
+    ) -> Result<Vec<LinkedIdentityResp>, ServiceError> {
+        let intent = OAuthIntent::Link(user_id);
+        let (provider, identity) =
+            Self::exchange(config, &request.state, &request.code, intent, state_cookie).await?;
+        OAuthRepository::link(pool, user_id, provider, &identity.subject, identity.name.as_deref())
+            .await?;
+        tracing::info!(user_id, provider, "Linked login provider account");
+        OAuthRepository::list_for_user(pool, user_id).await
+    }
+
+    pub async fn list(
+        pool: &SqlitePool,
+        user_id: i64,
+    ) -> Result<Vec<LinkedIdentityResp>, ServiceError> {
+        OAuthRepository::list_for_user(pool, user_id).await
+    }
+
+    pub async fn unlink(
+        pool: &SqlitePool,
+        user_id: i64,
+        provider: &str,
+    ) -> Result<Vec<LinkedIdentityResp>, ServiceError> {
+        if !OAuthRepository::unlink(pool, user_id, provider).await? {
+            return Err(ServiceError::NotFound(format!("Linked {} account", provider)));
+        }
+        tracing::info!(user_id, provider, "Unlinked login provider account");
+        OAuthRepository::list_for_user(pool, user_id).await
+    }
+
+    /// Consumes the state, which must have been issued for `intent` to the browser holding
+    /// `state_cookie`, and swaps the code for the provider account.
+    async fn exchange(
+        config: &AppConfig,
+        state: &str,
+        code: &str,
+        intent: OAuthIntent,
+        state_cookie: Option<&str>,
+    ) -> Result<(&'static str, ExternalIdentity), ServiceError> {
+        let invalid = || {
+            ServiceError::InvalidOperation("Login request is invalid or has expired".to_string())
+        };
+        let state = state.trim();
+        if !oauth::state_cookie_matches(state_cookie, state) {
+            tracing::warn!("Provider callback without the state cookie of its round trip");
+            return Err(invalid());
+        }
+        let (provider, issued_for) = STATES.take(state, Instant::now()).ok_or_else(invalid)?;
+        if issued_for != intent {
+            return Err(invalid());
+        }
+        let connector = oauth::connector(config, provider).ok_or_else(invalid)?;
+        let identity = connector.exchange_code(code.trim()).await.map_err(|e| {
+            tracing::warn!(provider, "Provider login failed: {}", e);
+            ServiceError::InvalidOperation(format!("{} login failed", provider))
+        })?;
+        Ok((provider, identity))
+    }
+
+    /// Creates an active user for a first-time provider login. The account gets an unusable
+    /// random password, so it signs in through the provider until someone sets one.
+    async fn provision(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        provider: &str,
+        identity: &ExternalIdentity,
+    ) -> Result<LoginCredentialsRow, ServiceError> {
+        let Some(email) = identity.email.clone() else {
+            return Err(ServiceError::InvalidOperation(format!(
+                "The {} account shares no email; ask an administrator to link it",
+                provider
+            )));
+        };
+        let role_code = config.oauth.oauth_provision_role.trim();
+        let role_ids = if role_code.is_empty() {
+            Vec::new()
+        } else {
+            let role_id =
+                UserRepository::find_role_id_by_code(pool, role_code).await?.ok_or_else(|| {
+                    tracing::error!(role_code, "Provisioning role does not exist");
+                    ServiceError::NotFound("Provisioning role".to_string())
+                })?;
+            vec![role_id]
+        };
+        let username = provisioned_username(provider, &identity.subject);
+        let user_id = UserService::create_user(
+            config,
+            pool,
+            None,
+            CreateUserRequest {
+                username: username.clone(),
+                email,
+                password: token::generate(),
+                real_name: identity.name.clone(),
+                status: Some(UserStatus::Normal as i16),
+                role_ids,
+                profile: None,
+            },
+        )
+        .await?;
+        OAuthRepository::link(
+            pool,
+            user_id.get(),
+            provider,
+            &identity.subject,
+            identity.name.as_deref(),
+        )
+        .await?;
+        tracing::info!(user_id = user_id.get(), provider, "Provisioned user from provider login");
+
+        AuthRepository::get_login_credentials(pool, &username)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound("User".to_string()))
+    }
+}
+
+/// `<provider>_<subject>` with characters outside `[A-Za-z0-9_.-]` replaced by `_`.
+fn provisioned_username(provider: &str, subject: &str) -> String {
+    let subject: String = subject
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-') { c } else { '_' })
+        .collect();
+    format!("{}_{}", provider, subject)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::provisioned_username;
+
+    #[test]
+    fn provisioned_usernames_keep_only_safe_characters() {
+        assert_eq!(provisioned_username("wecom", "ZhangSan"), "wecom_ZhangSan");
+        assert_eq!(provisioned_username("dingtalk", "ab+c/d=="), "dingtalk_ab_c_d__");
+    }
+}
@@ -0,0 +1,30 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Provider page to open for scan-to-login or linking
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthAuthorizeResp {
+    pub provider: String,
+    /// Provider QR page; it redirects to `RUSTZEN_OAUTH_REDIRECT_URL` with `code` and `state`
+    pub url: String,
+    pub state: String,
+}
+
+/// `code` and `state` from the provider redirect
+#[derive(Debug, Clone, Deserialize)]
+pub struct OAuthCallbackRequest {
+    pub state: String,
+    pub code: String,
+}
+
+/// Provider account linked to the current user
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkedIdentityResp {
+    pub provider: String,
+    pub subject: String,
+    pub display_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_login_at: Option<DateTime<Utc>>,
+}
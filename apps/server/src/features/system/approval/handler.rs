@@ -0,0 +1,50 @@
+use super::{
+    service::ApprovalService,
+    types::{ApprovalItemResp, ApprovalQuery},
+};
+use crate::{
+    common::{
+        api::{ApiResponse, AppResult, PageMeta},
+        pagination::{Pagination, PaginationQuery},
+    },
+    infra::config::AppConfig,
+};
+
+use axum::{
+    Extension,
+    extract::{Path, Query, State},
+};
+use rustzen_core::auth::CurrentUser;
+use sqlx::SqlitePool;
+
+/// Get paginated approval requests, newest first
+pub async fn list_approvals(
+    State(pool): State<SqlitePool>,
+    Query(query): Query<ApprovalQuery>,
+) -> AppResult<Vec<ApprovalItemResp>> {
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (approvals, total) = ApprovalService::list_approvals(&pool, query).await?;
+    Ok(ApiResponse::page(approvals, total, PageMeta::new(pagination, total)))
+}
+
+/// Approve a pending request and run its action
+pub async fn approve_approval(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+) -> AppResult<ApprovalItemResp> {
+    Ok(ApiResponse::success(ApprovalService::approve(config, &pool, &current_user, id).await?))
+}
+
+/// Reject a pending request
+pub async fn reject_approval(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+) -> AppResult<ApprovalItemResp> {
+    Ok(ApiResponse::success(ApprovalService::reject(&pool, &current_user, id).await?))
+}
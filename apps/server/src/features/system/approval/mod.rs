@@ -0,0 +1,34 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use handler::{approve_approval, list_approvals, reject_approval};
+use rustzen_core::{
+    capability::system_approval,
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+pub fn approval_routes() -> Router<SqlitePool> {
+    Router::new()
+        .route_with_permission(
+            "/",
+            get(list_approvals),
+            PermissionsCheck::Require(system_approval::LIST),
+        )
+        .route_with_permission(
+            "/{id}/approve",
+            post(approve_approval),
+            PermissionsCheck::Require(system_approval::APPROVE),
+        )
+        .route_with_permission(
+            "/{id}/reject",
+            post(reject_approval),
+            PermissionsCheck::Require(system_approval::APPROVE),
+        )
+}
@@ -0,0 +1,154 @@
+use super::types::{ApprovalCommand, ApprovalListQuery, ApprovalRow, ApprovalStatus};
+use crate::common::{
+    error::ServiceError,
+    query::{count_with_filters, fetch_with_filters},
+};
+
+use chrono::{NaiveDateTime, Utc};
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+
+pub struct ApprovalRepository;
+
+fn db_error(context: &str, e: sqlx::Error) -> ServiceError {
+    tracing::error!("Database error {}: {:?}", context, e);
+    ServiceError::DatabaseQueryFailed
+}
+
+impl ApprovalRepository {
+    fn format_query(query: &ApprovalListQuery, query_builder: &mut QueryBuilder<Sqlite>) {
+        if let Some(status) = query.status {
+            query_builder.push(" AND status = ").push_bind(status);
+        }
+        if let Some(action) = query.action.as_deref().map(str::trim).filter(|a| !a.is_empty()) {
+            query_builder.push(" AND action = ").push_bind(action.to_string());
+        }
+    }
+
+    pub async fn list_approvals(
+        pool: &SqlitePool,
+        offset: i64,
+        limit: i64,
+        query: ApprovalListQuery,
+    ) -> Result<(Vec<ApprovalRow>, i64), ServiceError> {
+        let total = count_with_filters(pool, "SELECT COUNT(*) FROM approvals WHERE 1=1", |qb| {
+            Self::format_query(&query, qb)
+        })
+        .await?;
+        if total == 0 {
+            return Ok((Vec::new(), total));
+        }
+        let approvals = fetch_with_filters(
+            pool,
+            "SELECT id, action, payload, summary, status, requested_by, requested_by_name, decided_by,
+                    decided_by_name, error, created_at, expires_at, decided_at
+             FROM approvals WHERE 1=1",
+            |qb| Self::format_query(&query, qb),
+            Some("id DESC"),
+            Some(limit),
+            Some(offset),
+        )
+        .await?;
+        Ok((approvals, total))
+    }
+
+    pub async fn find_by_id(
+        pool: &SqlitePool,
+        id: i64,
+    ) -> Result<Option<ApprovalRow>, ServiceError> {
+        sqlx::query_as::<_, ApprovalRow>(
+            "SELECT id, action, payload, summary, status, requested_by, requested_by_name, decided_by,
+                    decided_by_name, error, created_at, expires_at, decided_at
+             FROM approvals WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_error("finding approval", e))
+    }
+
+    /// Id of an unexpired pending approval for the same action and parameters.
+    pub async fn find_pending(
+        pool: &SqlitePool,
+        payload: &str,
+        now: NaiveDateTime,
+    ) -> Result<Option<i64>, ServiceError> {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT id FROM approvals WHERE payload = ? AND status = 'pending' AND expires_at > ?
+             ORDER BY id LIMIT 1",
+        )
+        .bind(payload)
+        .bind(now)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_error("finding pending approval", e))
+    }
+
+    pub async fn insert(
+        pool: &SqlitePool,
+        command: &ApprovalCommand,
+        payload: &str,
+    ) -> Result<i64, ServiceError> {
+        sqlx::query_scalar::<_, i64>(
+            "INSERT INTO approvals (action, payload, summary, requested_by, requested_by_name, created_at, expires_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)
+             RETURNING id",
+        )
+        .bind(command.action.name())
+        .bind(payload)
+        .bind(command.action.summary())
+        .bind(command.requested_by)
+        .bind(&command.requested_by_name)
+        .bind(Utc::now().naive_utc())
+        .bind(command.expires_at)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_error("inserting approval", e))
+    }
+
+    /// Moves a pending approval to `status`; `false` when another decision got there first.
+    pub async fn decide(
+        pool: &SqlitePool,
+        id: i64,
+        status: ApprovalStatus,
+        decided_by: Option<(i64, &str)>,
+    ) -> Result<bool, ServiceError> {
+        let result = sqlx::query(
+            "UPDATE approvals SET status = ?, decided_by = ?, decided_by_name = ?, decided_at = ?
+             WHERE id = ? AND status = 'pending'",
+        )
+        .bind(status.as_str())
+        .bind(decided_by.map(|(id, _)| id))
+        .bind(decided_by.map(|(_, name)| name))
+        .bind(Utc::now().naive_utc())
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| db_error("deciding approval", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Marks pending requests past `expires_at` as expired.
+    pub async fn expire_stale(pool: &SqlitePool, now: NaiveDateTime) -> Result<u64, ServiceError> {
+        let result = sqlx::query(
+            "UPDATE approvals SET status = 'expired', decided_at = ?
+             WHERE status = 'pending' AND expires_at <= ?",
+        )
+        .bind(now)
+        .bind(now)
+        .execute(pool)
+        .await
+        .map_err(|e| db_error("expiring approvals", e))?;
+        Ok(result.rows_affected())
+    }
+
+    /// Records that an approved action returned an error.
+    pub async fn mark_failed(pool: &SqlitePool, id: i64, error: &str) -> Result<(), ServiceError> {
+        sqlx::query("UPDATE approvals SET status = 'failed', error = ? WHERE id = ?")
+            .bind(error)
+            .bind(id)
+            .execute(pool)
+            .await
+            .map_err(|e| db_error("marking approval failed", e))?;
+        Ok(())
+    }
+}
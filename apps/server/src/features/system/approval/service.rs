@@ -0,0 +1,236 @@
+use super::{
+    repo::ApprovalRepository,
+    types::{
+        ApprovalAction, ApprovalCommand, ApprovalItemResp, ApprovalListQuery, ApprovalQuery,
+        ApprovalRow, ApprovalStatus,
+    },
+};
+use crate::{
+    common::{
+        error::ServiceError,
+        ids::UserId,
+        pagination::{Pagination, PaginationQuery},
+    },
+    features::{
+        manage::log::service::LogService,
+        system::{
+            purge::{service::PurgeService, types::PurgeTarget},
+            role::service::RoleService,
+            user::service::UserService,
+        },
+    },
+    infra::config::AppConfig,
+};
+
+use chrono::{Duration, Utc};
+use rustzen_core::auth::CurrentUser;
+use sqlx::SqlitePool;
+
+/// Hours a request stays open before it can no longer be approved.
+const APPROVAL_TTL_HOURS: i64 = 24;
+
+pub struct ApprovalService;
+
+impl ApprovalService {
+    /// Gate for destructive handlers.
+    ///
+    /// Returns `Ok` when dual control is off, so the caller runs the action itself.
+    /// Otherwise files a pending approval (or reuses an open one for the same action)
+    /// and fails with [`ServiceError::ApprovalPending`].
+    pub async fn require(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        action: ApprovalAction,
+    ) -> Result<(), ServiceError> {
+        if !config.auth.dual_control {
+            return Ok(());
+        }
+        // Serializing a plain enum of ids and timestamps cannot fail.
+        let payload = serde_json::to_string(&action).unwrap_or_default();
+        let now = Utc::now().naive_utc();
+        if let Some(id) = ApprovalRepository::find_pending(pool, &payload, now).await? {
+            return Err(ServiceError::ApprovalPending(id));
+        }
+        let command = ApprovalCommand {
+            action,
+            requested_by: current_user.user_id,
+            requested_by_name: current_user.username.clone(),
+            expires_at: now + Duration::hours(APPROVAL_TTL_HOURS),
+        };
+        let id = ApprovalRepository::insert(pool, &command, &payload).await?;
+        tracing::info!(id, action = command.action.name(), "Filed approval request");
+        Err(ServiceError::ApprovalPending(id))
+    }
+
+    pub async fn list_approvals(
+        pool: &SqlitePool,
+        query: ApprovalQuery,
+    ) -> Result<(Vec<ApprovalItemResp>, i64), ServiceError> {
+        let ApprovalQuery { current, page_size, status, action } = query;
+        let status = match status.as_deref().map(str::trim) {
+            None | Some("") | Some("all") => None,
+            Some(raw) => Some(
+                ApprovalStatus::parse(raw)
+                    .ok_or_else(|| {
+                        ServiceError::InvalidOperation(format!(
+                            "Invalid approval status value: {}",
+                            raw
+                        ))
+                    })?
+                    .as_str(),
+            ),
+        };
+        ApprovalRepository::expire_stale(pool, Utc::now().naive_utc()).await?;
+        let pagination = Pagination::from_query(PaginationQuery { current, page_size });
+        let (rows, total) = ApprovalRepository::list_approvals(
+            pool,
+            i64::from(pagination.offset),
+            i64::from(pagination.limit),
+            ApprovalListQuery { status, action },
+        )
+        .await?;
+        Ok((rows.into_iter().map(ApprovalItemResp::from).collect(), total))
+    }
+
+    /// Approves and executes a pending request.
+    ///
+    /// The approver must be someone other than the requester and must hold the
+    /// capability the action needs. An action error is stored on the row as `failed`.
+    pub async fn approve(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        id: i64,
+    ) -> Result<ApprovalItemResp, ServiceError> {
+        let (row, action) = Self::find_pending(pool, id).await?;
+        if row.requested_by == current_user.user_id {
+            return Err(ServiceError::InvalidOperation(
+                "This request requires a second administrator to approve".to_string(),
+            ));
+        }
+        if !current_user.has_capability(action.capability()) {
+            return Err(ServiceError::InvalidOperation(format!(
+                "Approving this request requires the {} permission",
+                action.capability()
+            )));
+        }
+        let approver = (current_user.user_id, current_user.username.as_str());
+        if !ApprovalRepository::decide(pool, id, ApprovalStatus::Approved, Some(approver)).await? {
+            return Err(Self::already_decided(id));
+        }
+
+        tracing::info!(id, action = action.name(), approver = %current_user.username, "Executing approved action");
+        if let Err(err) = Self::execute(config, pool, row.requested_by, action).await {
+            tracing::warn!(id, error = %err, "Approved action failed");
+            ApprovalRepository::mark_failed(pool, id, &err.to_string()).await?;
+        }
+        Self::get(pool, id).await
+    }
+
+    /// Rejects a pending request; the requester may withdraw their own.
+    pub async fn reject(
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        id: i64,
+    ) -> Result<ApprovalItemResp, ServiceError> {
+        Self::find_pending(pool, id).await?;
+        let decider = (current_user.user_id, current_user.username.as_str());
+        if !ApprovalRepository::decide(pool, id, ApprovalStatus::Rejected, Some(decider)).await? {
+            return Err(Self::already_decided(id));
+        }
+        Self::get(pool, id).await
+    }
+
+    async fn execute(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        requested_by: i64,
+        action: ApprovalAction,
+    ) -> Result<(), ServiceError> {
+        match action {
+            ApprovalAction::UserPurge { user_id } => {
+                PurgeService::purge(config, pool, PurgeTarget::User(user_id)).await.map(|_| ())
+            }
+            ApprovalAction::UserAnonymize { user_id } => {
+                UserService::anonymize_user(config, pool, user_id, UserId(requested_by)).await
+            }
+            ApprovalAction::RoleDelete { role_id } => {
+                RoleService::delete_role(pool, role_id, UserId(requested_by)).await
+            }
+            ApprovalAction::LogPurge { before } => {
+                LogService::purge_logs(pool, before).await.map(|_| ())
+            }
+        }
+    }
+
+    /// Loads a request that can still be decided, expiring it first when its time is up.
+    async fn find_pending(
+        pool: &SqlitePool,
+        id: i64,
+    ) -> Result<(ApprovalRow, ApprovalAction), ServiceError> {
+        let row = ApprovalRepository::find_by_id(pool, id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound(format!("Approval id: {}", id)))?;
+        if row.status != ApprovalStatus::Pending.as_str() {
+            return Err(Self::already_decided(id));
+        }
+        if row.expires_at <= Utc::now() {
+            ApprovalRepository::decide(pool, id, ApprovalStatus::Expired, None).await?;
+            return Err(ServiceError::InvalidOperation(format!(
+                "Approval {} has expired; submit the action again",
+                id
+            )));
+        }
+        let action = serde_json::from_str::<ApprovalAction>(&row.payload).map_err(|e| {
+            tracing::error!("Unreadable approval payload {}: {:?}", id, e);
+            ServiceError::DatabaseQueryFailed
+        })?;
+        Ok((row, action))
+    }
+
+    async fn get(pool: &SqlitePool, id: i64) -> Result<ApprovalItemResp, ServiceError> {
+        ApprovalRepository::find_by_id(pool, id)
+            .await?
+            .map(ApprovalItemResp::from)
+            .ok_or_else(|| ServiceError::NotFound(format!("Approval id: {}", id)))
+    }
+
+    fn already_decided(id: i64) -> ServiceError {
+        ServiceError::InvalidOperation(format!("Approval {} is no longer pending", id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ApprovalAction;
+    use crate::common::ids::{RoleId, UserId};
+
+    use chrono::NaiveDate;
+    use rustzen_core::capability::{manage_log, system_role};
+
+    #[test]
+    fn actions_round_trip_through_the_stored_payload() {
+        let before = NaiveDate::from_ymd_opt(2026, 1, 2).unwrap().and_hms_opt(3, 4, 5).unwrap();
+        let actions = [
+            ApprovalAction::UserPurge { user_id: UserId(7) },
+            ApprovalAction::UserAnonymize { user_id: UserId(8) },
+            ApprovalAction::RoleDelete { role_id: RoleId(3) },
+            ApprovalAction::LogPurge { before },
+        ];
+        for action in actions {
+            let payload = serde_json::to_string(&action).unwrap();
+            assert!(payload.starts_with(&format!("{{\"action\":\"{}\"", action.name())));
+            assert_eq!(serde_json::from_str::<ApprovalAction>(&payload).unwrap(), action);
+        }
+
+        let payload =
+            serde_json::to_value(ApprovalAction::UserPurge { user_id: UserId(7) }).unwrap();
+        assert_eq!(payload["params"]["userId"], 7);
+        assert_eq!(
+            ApprovalAction::RoleDelete { role_id: RoleId(3) }.capability(),
+            system_role::DELETE
+        );
+        assert_eq!(ApprovalAction::LogPurge { before }.capability(), manage_log::PURGE);
+    }
+}
@@ -0,0 +1,181 @@
+use crate::common::ids::{RoleId, UserId};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rustzen_core::capability::{manage_log, system_role, system_user};
+use serde::{Deserialize, Serialize};
+
+/// Destructive action that waits for a second administrator under dual control.
+///
+/// Stored in `approvals.payload` as `{"action": "user.purge", "params": {...}}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", content = "params", rename_all_fields = "camelCase")]
+pub enum ApprovalAction {
+    /// Permanently remove a soft-deleted user.
+    #[serde(rename = "user.purge")]
+    UserPurge { user_id: UserId },
+    /// Scrub a user's personal data; it cannot be restored afterwards.
+    #[serde(rename = "user.anonymize")]
+    UserAnonymize { user_id: UserId },
+    #[serde(rename = "role.delete")]
+    RoleDelete { role_id: RoleId },
+    /// Delete operation logs created before `before`, fixed when the request was filed.
+    #[serde(rename = "log.purge")]
+    LogPurge { before: NaiveDateTime },
+}
+
+impl ApprovalAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ApprovalAction::UserPurge { .. } => "user.purge",
+            ApprovalAction::UserAnonymize { .. } => "user.anonymize",
+            ApprovalAction::RoleDelete { .. } => "role.delete",
+            ApprovalAction::LogPurge { .. } => "log.purge",
+        }
+    }
+
+    /// Capability the route checks for the requester; the approver must hold it too.
+    pub fn capability(&self) -> &'static str {
+        match self {
+            ApprovalAction::UserPurge { .. } => system_user::PURGE,
+            ApprovalAction::UserAnonymize { .. } => system_user::ANONYMIZE,
+            ApprovalAction::RoleDelete { .. } => system_role::DELETE,
+            ApprovalAction::LogPurge { .. } => manage_log::PURGE,
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        match self {
+            ApprovalAction::UserPurge { user_id } => format!("Purge deleted user #{}", user_id),
+            ApprovalAction::UserAnonymize { user_id } => format!("Anonymize user #{}", user_id),
+            ApprovalAction::RoleDelete { role_id } => format!("Delete role #{}", role_id),
+            ApprovalAction::LogPurge { before } => {
+                format!("Purge operation logs before {}", before.format("%Y-%m-%d %H:%M:%S"))
+            }
+        }
+    }
+}
+
+/// Approval lifecycle stored in `approvals.status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalStatus {
+    Pending,
+    /// Approved and executed.
+    Approved,
+    Rejected,
+    /// Left pending past `expires_at`.
+    Expired,
+    /// Approved, but the action returned an error.
+    Failed,
+}
+
+impl ApprovalStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ApprovalStatus::Pending => "pending",
+            ApprovalStatus::Approved => "approved",
+            ApprovalStatus::Rejected => "rejected",
+            ApprovalStatus::Expired => "expired",
+            ApprovalStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(ApprovalStatus::Pending),
+            "approved" => Some(ApprovalStatus::Approved),
+            "rejected" => Some(ApprovalStatus::Rejected),
+            "expired" => Some(ApprovalStatus::Expired),
+            "failed" => Some(ApprovalStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// Approval row as read from the database.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ApprovalRow {
+    pub id: i64,
+    pub action: String,
+    pub payload: String,
+    pub summary: String,
+    pub status: String,
+    pub requested_by: i64,
+    pub requested_by_name: String,
+    pub decided_by: Option<i64>,
+    pub decided_by_name: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub decided_at: Option<DateTime<Utc>>,
+}
+
+/// Approval for list display.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalItemResp {
+    pub id: i64,
+    pub action: String,
+    /// Action parameters, such as `{"userId": 12}`.
+    pub params: serde_json::Value,
+    pub summary: String,
+    pub status: String,
+    pub requested_by: i64,
+    pub requested_by_name: String,
+    pub decided_by: Option<i64>,
+    pub decided_by_name: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub decided_at: Option<DateTime<Utc>>,
+}
+
+impl From<ApprovalRow> for ApprovalItemResp {
+    fn from(row: ApprovalRow) -> Self {
+        let params = serde_json::from_str::<serde_json::Value>(&row.payload)
+            .ok()
+            .and_then(|mut payload| payload.get_mut("params").map(serde_json::Value::take))
+            .unwrap_or(serde_json::Value::Null);
+        Self {
+            id: row.id,
+            action: row.action,
+            params,
+            summary: row.summary,
+            status: row.status,
+            requested_by: row.requested_by,
+            requested_by_name: row.requested_by_name,
+            decided_by: row.decided_by,
+            decided_by_name: row.decided_by_name,
+            error: row.error,
+            created_at: row.created_at,
+            expires_at: row.expires_at,
+            decided_at: row.decided_at,
+        }
+    }
+}
+
+/// Approval query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalQuery {
+    pub current: Option<i64>,
+    pub page_size: Option<i64>,
+    /// `pending`, `approved`, `rejected`, `expired`, `failed`, or `all`.
+    pub status: Option<String>,
+    pub action: Option<String>,
+}
+
+/// Filters for the approval list.
+#[derive(Debug, Clone)]
+pub struct ApprovalListQuery {
+    pub status: Option<&'static str>,
+    pub action: Option<String>,
+}
+
+/// Pending approval filed for an action.
+#[derive(Debug, Clone)]
+pub struct ApprovalCommand {
+    pub action: ApprovalAction,
+    pub requested_by: i64,
+    pub requested_by_name: String,
+    pub expires_at: NaiveDateTime,
+}
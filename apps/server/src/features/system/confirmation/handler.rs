@@ -0,0 +1,18 @@
+use super::{
+    service::ConfirmationService,
+    types::{ConfirmationResp, ConfirmationTarget},
+};
+use crate::common::api::{ApiResponse, AppResult};
+
+use axum::{Json, extract::State};
+use rustzen_core::auth::CurrentUser;
+use sqlx::SqlitePool;
+
+/// Issue a two-minute, single-use token for one purge
+pub async fn create_confirmation(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Json(target): Json<ConfirmationTarget>,
+) -> AppResult<ConfirmationResp> {
+    Ok(ApiResponse::success(ConfirmationService::issue(&pool, &current_user, target).await?))
+}
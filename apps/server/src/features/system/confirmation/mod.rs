@@ -0,0 +1,27 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{Router, routing::post};
+use handler::create_confirmation;
+use rustzen_core::{
+    capability::{manage_dict, manage_log, system_menu, system_role, system_user},
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+/// Any purge permission opens the route; issuing checks the one for the target.
+pub fn confirmation_routes() -> Router<SqlitePool> {
+    Router::new().route_with_permission(
+        "/",
+        post(create_confirmation),
+        PermissionsCheck::Any(vec![
+            system_user::PURGE,
+            system_role::PURGE,
+            system_menu::PURGE,
+            manage_dict::PURGE,
+            manage_log::PURGE,
+        ]),
+    )
+}
@@ -0,0 +1,65 @@
+use crate::common::error::ServiceError;
+
+use chrono::NaiveDateTime;
+use sqlx::SqlitePool;
+
+pub struct ConfirmationRepository;
+
+fn db_error(context: &str, e: sqlx::Error) -> ServiceError {
+    tracing::error!("Database error {}: {:?}", context, e);
+    ServiceError::DatabaseQueryFailed
+}
+
+impl ConfirmationRepository {
+    /// Stores a token, dropping expired ones on the way.
+    pub async fn insert(
+        pool: &SqlitePool,
+        token_hash: &str,
+        user_id: i64,
+        payload: &str,
+        expires_at: NaiveDateTime,
+        now: NaiveDateTime,
+    ) -> Result<(), ServiceError> {
+        let mut tx = pool.begin().await.map_err(|e| db_error("starting confirmation", e))?;
+        sqlx::query("DELETE FROM action_confirmations WHERE expires_at <= ?")
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| db_error("deleting expired confirmations", e))?;
+        sqlx::query(
+            "INSERT INTO action_confirmations (token_hash, user_id, payload, expires_at)
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(token_hash)
+        .bind(user_id)
+        .bind(payload)
+        .bind(expires_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_error("inserting confirmation", e))?;
+        tx.commit().await.map_err(|e| db_error("committing confirmation", e))
+    }
+
+    /// Deletes the token if it belongs to `user_id`, names `payload` and is unexpired.
+    /// Returns whether it did, so a token is only ever accepted once.
+    pub async fn consume(
+        pool: &SqlitePool,
+        token_hash: &str,
+        user_id: i64,
+        payload: &str,
+        now: NaiveDateTime,
+    ) -> Result<bool, ServiceError> {
+        let result = sqlx::query(
+            "DELETE FROM action_confirmations
+             WHERE token_hash = ? AND user_id = ? AND payload = ? AND expires_at > ?",
+        )
+        .bind(token_hash)
+        .bind(user_id)
+        .bind(payload)
+        .bind(now)
+        .execute(pool)
+        .await
+        .map_err(|e| db_error("consuming confirmation", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,113 @@
+use super::{
+    repo::ConfirmationRepository,
+    types::{ConfirmationResp, ConfirmationTarget},
+};
+use crate::common::{error::ServiceError, token, validation::FieldErrors};
+
+use axum::http::HeaderMap;
+use chrono::{Duration, Utc};
+use rustzen_core::auth::CurrentUser;
+use sqlx::SqlitePool;
+
+/// Header the irreversible endpoints read the confirmation token from.
+pub const CONFIRMATION_HEADER: &str = "x-confirmation-token";
+
+/// Seconds a confirmation token stays usable.
+const CONFIRMATION_TTL_SECONDS: i64 = 120;
+
+pub struct ConfirmationService;
+
+impl ConfirmationService {
+    /// Issues a single-use token for `target` to the caller, who must be allowed to run it.
+    pub async fn issue(
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        target: ConfirmationTarget,
+    ) -> Result<ConfirmationResp, ServiceError> {
+        if !current_user.has_capability(target.capability()) {
+            return Err(ServiceError::InvalidOperation(format!(
+                "Confirming {} requires the {} permission",
+                target.name(),
+                target.capability()
+            )));
+        }
+        let mut errors = FieldErrors::new();
+        if let ConfirmationTarget::LogPurge { older_than_days } = target
+            && older_than_days < 1
+        {
+            errors.push("params.olderThanDays", "must be at least 1");
+        }
+        errors.into_result()?;
+
+        let value = token::generate();
+        let now = Utc::now();
+        let expires_at = now + Duration::seconds(CONFIRMATION_TTL_SECONDS);
+        ConfirmationRepository::insert(
+            pool,
+            &token::hash(&value),
+            current_user.user_id,
+            &payload(&target),
+            expires_at.naive_utc(),
+            now.naive_utc(),
+        )
+        .await?;
+        Ok(ConfirmationResp {
+            token: value,
+            action: target.name(),
+            summary: target.summary(),
+            expires_at,
+        })
+    }
+
+    /// Gate for irreversible handlers: uses up the caller's token for exactly `target`.
+    ///
+    /// Fails with [`ServiceError::ConfirmationRequired`] when the token is missing, expired,
+    /// already used, or was issued to someone else or for another target.
+    pub async fn consume(
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        headers: &HeaderMap,
+        target: &ConfirmationTarget,
+    ) -> Result<(), ServiceError> {
+        let Some(value) = headers.get(CONFIRMATION_HEADER).and_then(|value| value.to_str().ok())
+        else {
+            return Err(ServiceError::ConfirmationRequired(target.name()));
+        };
+        let consumed = ConfirmationRepository::consume(
+            pool,
+            &token::hash(value.trim()),
+            current_user.user_id,
+            &payload(target),
+            Utc::now().naive_utc(),
+        )
+        .await?;
+        if !consumed {
+            return Err(ServiceError::ConfirmationRequired(target.name()));
+        }
+        tracing::info!(action = target.name(), user = %current_user.username, "Confirmation token used");
+        Ok(())
+    }
+}
+
+fn payload(target: &ConfirmationTarget) -> String {
+    // Serializing a plain enum of ids and numbers cannot fail.
+    serde_json::to_string(target).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::payload;
+    use crate::{common::ids::UserId, features::system::confirmation::types::ConfirmationTarget};
+
+    #[test]
+    fn targets_are_stored_in_one_canonical_form() {
+        let target: ConfirmationTarget =
+            serde_json::from_str(r#"{"params": {"userId": 7}, "action": "user.purge"}"#).unwrap();
+        assert_eq!(target, ConfirmationTarget::UserPurge { user_id: UserId(7) });
+        assert_eq!(payload(&target), r#"{"action":"user.purge","params":{"userId":7}}"#);
+        assert_ne!(
+            payload(&ConfirmationTarget::LogPurge { older_than_days: 30 }),
+            payload(&ConfirmationTarget::LogPurge { older_than_days: 7 })
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use crate::common::ids::{MenuId, RoleId, UserId};
+
+use chrono::{DateTime, Utc};
+use rustzen_core::capability::{manage_dict, manage_log, system_menu, system_role, system_user};
+use serde::{Deserialize, Serialize};
+
+/// Irreversible action a confirmation token is issued for, with its exact target.
+///
+/// Sent and stored as `{"action": "user.purge", "params": {...}}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", content = "params", rename_all_fields = "camelCase")]
+pub enum ConfirmationTarget {
+    /// `DELETE /api/system/users/{userId}/purge`
+    #[serde(rename = "user.purge")]
+    UserPurge { user_id: UserId },
+    /// `DELETE /api/system/roles/{roleId}/purge`
+    #[serde(rename = "role.purge")]
+    RolePurge { role_id: RoleId },
+    /// `DELETE /api/system/menus/{menuId}/purge`
+    #[serde(rename = "menu.purge")]
+    MenuPurge { menu_id: MenuId },
+    /// `DELETE /api/manage/dicts/{dictId}/purge`
+    #[serde(rename = "dict.purge")]
+    DictPurge { dict_id: i64 },
+    /// `DELETE /api/manage/logs?olderThanDays=...`
+    #[serde(rename = "log.purge")]
+    LogPurge { older_than_days: i64 },
+}
+
+impl ConfirmationTarget {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConfirmationTarget::UserPurge { .. } => "user.purge",
+            ConfirmationTarget::RolePurge { .. } => "role.purge",
+            ConfirmationTarget::MenuPurge { .. } => "menu.purge",
+            ConfirmationTarget::DictPurge { .. } => "dict.purge",
+            ConfirmationTarget::LogPurge { .. } => "log.purge",
+        }
+    }
+
+    /// Capability of the endpoint the token is for; the issuer must hold it.
+    pub fn capability(&self) -> &'static str {
+        match self {
+            ConfirmationTarget::UserPurge { .. } => system_user::PURGE,
+            ConfirmationTarget::RolePurge { .. } => system_role::PURGE,
+            ConfirmationTarget::MenuPurge { .. } => system_menu::PURGE,
+            ConfirmationTarget::DictPurge { .. } => manage_dict::PURGE,
+            ConfirmationTarget::LogPurge { .. } => manage_log::PURGE,
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        match self {
+            ConfirmationTarget::UserPurge { user_id } => {
+                format!("Permanently remove deleted user #{}", user_id)
+            }
+            ConfirmationTarget::RolePurge { role_id } => {
+                format!("Permanently remove deleted role #{}", role_id)
+            }
+            ConfirmationTarget::MenuPurge { menu_id } => {
+                format!("Permanently remove disabled menu #{}", menu_id)
+            }
+            ConfirmationTarget::DictPurge { dict_id } => {
+                format!("Permanently remove deleted dictionary item #{}", dict_id)
+            }
+            ConfirmationTarget::LogPurge { older_than_days } => {
+                format!("Delete operation logs older than {} days", older_than_days)
+            }
+        }
+    }
+}
+
+/// Token issued for one action; send it back in the `X-Confirmation-Token` header.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmationResp {
+    pub token: String,
+    pub action: &'static str,
+    pub summary: String,
+    pub expires_at: DateTime<Utc>,
+}
@@ -0,0 +1,51 @@
+use super::{
+    service::DebugCaptureService,
+    types::{CreateDebugCaptureRequest, DebugCaptureItemResp, DebugCaptureQuery},
+};
+use crate::{
+    common::{
+        api::{ApiResponse, AppResult, PageMeta},
+        pagination::{Pagination, PaginationQuery},
+    },
+    infra::db::DbExecutor,
+};
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use rustzen_core::auth::CurrentUser;
+use sqlx::SqlitePool;
+
+/// Get paginated debug capture list
+pub async fn list_debug_captures(
+    State(db): State<DbExecutor>,
+    Query(query): Query<DebugCaptureQuery>,
+) -> AppResult<Vec<DebugCaptureItemResp>> {
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (captures, total) = DebugCaptureService::list_captures(db.read(), query).await?;
+    Ok(ApiResponse::page(captures, total, PageMeta::new(pagination, total)))
+}
+
+/// Start capturing request bodies of a user or route for a limited time
+pub async fn create_debug_capture(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Json(request): Json<CreateDebugCaptureRequest>,
+) -> AppResult<i64> {
+    Ok(ApiResponse::success(
+        DebugCaptureService::create_capture(&pool, current_user.user_id, request).await?,
+    ))
+}
+
+/// End a debug capture early
+pub async fn delete_debug_capture(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+) -> AppResult<()> {
+    DebugCaptureService::delete_capture(&pool, id).await?;
+    Ok(ApiResponse::success(()))
+}
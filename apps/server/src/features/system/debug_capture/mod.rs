@@ -0,0 +1,34 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{
+    Router,
+    routing::{delete, get, post},
+};
+use handler::{create_debug_capture, delete_debug_capture, list_debug_captures};
+use rustzen_core::{
+    capability::system_debug_capture,
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+pub fn debug_capture_routes() -> Router<SqlitePool> {
+    Router::new()
+        .route_with_permission(
+            "/",
+            get(list_debug_captures),
+            PermissionsCheck::Require(system_debug_capture::LIST),
+        )
+        .route_with_permission(
+            "/",
+            post(create_debug_capture),
+            PermissionsCheck::Require(system_debug_capture::CREATE),
+        )
+        .route_with_permission(
+            "/{id}",
+            delete(delete_debug_capture),
+            PermissionsCheck::Require(system_debug_capture::DELETE),
+        )
+}
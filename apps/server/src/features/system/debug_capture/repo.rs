@@ -0,0 +1,104 @@
+use super::types::{ActiveCapture, DebugCaptureRow};
+use crate::common::error::ServiceError;
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+
+pub struct DebugCaptureRepository;
+
+fn db_error(context: &str, e: sqlx::Error) -> ServiceError {
+    tracing::error!("Database error {}: {:?}", context, e);
+    ServiceError::DatabaseQueryFailed
+}
+
+impl DebugCaptureRepository {
+    /// Captures newest first; with `active_only`, only those that have not expired.
+    pub async fn list_captures(
+        pool: &SqlitePool,
+        offset: i64,
+        limit: i64,
+        active_only: bool,
+    ) -> Result<(Vec<DebugCaptureRow>, i64), ServiceError> {
+        let live_after = active_only.then(|| Utc::now().naive_utc());
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM debug_captures WHERE (?1 IS NULL OR expires_at > ?1)",
+        )
+        .bind(live_after)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_error("counting debug captures", e))?;
+        if total == 0 {
+            return Ok((Vec::new(), total));
+        }
+        let captures = sqlx::query_as::<_, DebugCaptureRow>(
+            "SELECT c.id, c.user_id, u.username, c.route, c.reason, c.expires_at,
+                    c.created_by, c.created_at
+             FROM debug_captures c
+             LEFT JOIN users u ON u.id = c.user_id
+             WHERE (?1 IS NULL OR c.expires_at > ?1)
+             ORDER BY c.id DESC
+             LIMIT ?2 OFFSET ?3",
+        )
+        .bind(live_after)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("listing debug captures", e))?;
+        Ok((captures, total))
+    }
+
+    /// Captures that have not expired, for the in-memory snapshot.
+    pub async fn list_active(pool: &SqlitePool) -> Result<Vec<ActiveCapture>, ServiceError> {
+        sqlx::query_as::<_, ActiveCapture>(
+            "SELECT id, user_id, route, expires_at FROM debug_captures WHERE expires_at > ?",
+        )
+        .bind(Utc::now().naive_utc())
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("loading active debug captures", e))
+    }
+
+    pub async fn live_user_exists(pool: &SqlitePool, user_id: i64) -> Result<bool, ServiceError> {
+        sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM users WHERE id = ? AND deleted_at IS NULL)",
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_error("checking debug capture user", e))
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        user_id: Option<i64>,
+        route: Option<&str>,
+        reason: Option<&str>,
+        expires_at: DateTime<Utc>,
+        created_by: i64,
+    ) -> Result<i64, ServiceError> {
+        sqlx::query_scalar(
+            "INSERT INTO debug_captures (user_id, route, reason, expires_at, created_by, created_at)
+             VALUES (?, ?, ?, ?, ?, ?)
+             RETURNING id",
+        )
+        .bind(user_id)
+        .bind(route)
+        .bind(reason)
+        .bind(expires_at.naive_utc())
+        .bind(created_by)
+        .bind(Utc::now().naive_utc())
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_error("creating debug capture", e))
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: i64) -> Result<bool, ServiceError> {
+        let result = sqlx::query("DELETE FROM debug_captures WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await
+            .map_err(|e| db_error("deleting debug capture", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+}
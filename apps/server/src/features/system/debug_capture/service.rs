@@ -0,0 +1,333 @@
+use super::{
+    repo::DebugCaptureRepository,
+    types::{ActiveCapture, CreateDebugCaptureRequest, DebugCaptureItemResp, DebugCaptureQuery},
+};
+use crate::common::{
+    error::ServiceError,
+    pagination::{Pagination, PaginationQuery},
+    validation::FieldErrors,
+};
+
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use once_cell::sync::Lazy;
+use serde_json::{Value, json};
+use sqlx::SqlitePool;
+use std::{sync::RwLock, time::Duration};
+
+const DEFAULT_MINUTES: i64 = 30;
+const MAX_MINUTES: i64 = 24 * 60;
+const ROUTE_MAX_LEN: usize = 200;
+const REASON_MAX_LEN: usize = 200;
+const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+/// Captured bodies longer than this are cut off before they reach the operation log.
+const MAX_CAPTURED_BYTES: usize = 8 * 1024;
+const REDACTED: &str = "[REDACTED]";
+/// Lowercased key fragments, without `_` and `-`, whose values never reach the log.
+const SENSITIVE_KEY_PARTS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "otp",
+    "authorization",
+    "apikey",
+    "privatekey",
+    "credential",
+    "verificationcode",
+];
+/// Keys too short to match inside longer names (`roleCode`, `statement`): the SMS and OAuth
+/// login codes and the OAuth state.
+const SENSITIVE_KEYS: &[&str] = &["code", "state"];
+
+/// Captures that have not expired, as of the last reload.
+static ACTIVE: Lazy<RwLock<Vec<ActiveCapture>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Capture checks for the log middleware.
+///
+/// Like feature flags, checks read an in-process snapshot that reloads after every write on
+/// this instance and every 30 seconds. Expiry is checked on each request, so a capture stops
+/// on time even between reloads.
+pub struct DebugCaptures;
+
+impl DebugCaptures {
+    /// The capture a request by `user_id` to the route template `route` falls under, if any.
+    pub fn matching(user_id: Option<i64>, route: Option<&str>) -> Option<i64> {
+        let active = ACTIVE.read().unwrap_or_else(|e| e.into_inner());
+        if active.is_empty() {
+            return None;
+        }
+        find_match(&active, user_id, route, Utc::now())
+    }
+
+    pub async fn reload(pool: &SqlitePool) -> Result<(), ServiceError> {
+        let active = DebugCaptureRepository::list_active(pool).await?;
+        *ACTIVE.write().unwrap_or_else(|e| e.into_inner()) = active;
+        Ok(())
+    }
+
+    /// Loads the active captures, then keeps reloading them in the background.
+    pub async fn start(pool: SqlitePool) -> Result<(), ServiceError> {
+        Self::reload(&pool).await?;
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(REFRESH_INTERVAL).await;
+                if let Err(e) = Self::reload(&pool).await {
+                    tracing::error!("Debug capture reload failed: {:?}", e);
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+/// A capture matches when every target it sets matches; the newest wins.
+fn find_match(
+    captures: &[ActiveCapture],
+    user_id: Option<i64>,
+    route: Option<&str>,
+    now: DateTime<Utc>,
+) -> Option<i64> {
+    captures
+        .iter()
+        .filter(|capture| capture.expires_at > now)
+        .filter(|capture| capture.user_id.is_none_or(|id| Some(id) == user_id))
+        .filter(|capture| capture.route.as_deref().is_none_or(|r| Some(r) == route))
+        .map(|capture| capture.id)
+        .max()
+}
+
+/// The `data` stored with a captured request: its body with sensitive values redacted.
+///
+/// JSON bodies are redacted by key at any depth; other content types are noted but not
+/// stored.
+pub fn capture_payload(capture_id: i64, content_type: Option<&str>, body: &[u8]) -> Value {
+    let request_body = if body.is_empty() {
+        Value::Null
+    } else if is_capturable(content_type) {
+        match serde_json::from_slice::<Value>(body) {
+            Ok(mut value) => {
+                redact(&mut value);
+                value
+            }
+            Err(_) => Value::String("[unparseable JSON]".to_string()),
+        }
+    } else {
+        Value::String(format!("[{} bytes not captured]", body.len()))
+    };
+    let (request_body, truncated) = truncate(request_body);
+    json!({
+        "debugCapture": {
+            "captureId": capture_id,
+            "contentType": content_type,
+            "requestBody": request_body,
+            "truncated": truncated,
+        }
+    })
+}
+
+/// Whether the middleware should buffer a body of this content type for capture.
+pub fn is_capturable(content_type: Option<&str>) -> bool {
+    let media_type = content_type
+        .and_then(|value| value.split(';').next())
+        .map(|value| value.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    media_type == "application/json" || media_type.ends_with("+json")
+}
+
+fn is_sensitive_key(key: &str) -> bool {
+    let normalized: String =
+        key.chars().filter(|c| *c != '_' && *c != '-').collect::<String>().to_ascii_lowercase();
+    SENSITIVE_KEYS.contains(&normalized.as_str())
+        || SENSITIVE_KEY_PARTS.iter().any(|part| normalized.contains(part))
+}
+
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields.iter_mut() {
+                if is_sensitive_key(key) {
+                    *field = Value::String(REDACTED.to_string());
+                } else {
+                    redact(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+/// Bodies over `MAX_CAPTURED_BYTES` are kept as a cut-off JSON string.
+fn truncate(body: Value) -> (Value, bool) {
+    let serialized = body.to_string();
+    if serialized.len() <= MAX_CAPTURED_BYTES {
+        return (body, false);
+    }
+    let mut end = MAX_CAPTURED_BYTES;
+    while !serialized.is_char_boundary(end) {
+        end -= 1;
+    }
+    (Value::String(serialized[..end].to_string()), true)
+}
+
+pub struct DebugCaptureService;
+
+impl DebugCaptureService {
+    pub async fn list_captures(
+        pool: &SqlitePool,
+        query: DebugCaptureQuery,
+    ) -> Result<(Vec<DebugCaptureItemResp>, i64), ServiceError> {
+        let DebugCaptureQuery { current, page_size, active } = query;
+        let pagination = Pagination::from_query(PaginationQuery { current, page_size });
+        let (rows, total) = DebugCaptureRepository::list_captures(
+            pool,
+            i64::from(pagination.offset),
+            i64::from(pagination.limit),
+            active.unwrap_or(false),
+        )
+        .await?;
+        Ok((rows.into_iter().map(DebugCaptureItemResp::from).collect(), total))
+    }
+
+    pub async fn create_capture(
+        pool: &SqlitePool,
+        created_by: i64,
+        request: CreateDebugCaptureRequest,
+    ) -> Result<i64, ServiceError> {
+        let route = normalize(request.route);
+        let reason = normalize(request.reason);
+        let minutes = request.minutes.unwrap_or(DEFAULT_MINUTES);
+        let mut errors = FieldErrors::new();
+        if request.user_id.is_none() && route.is_none() {
+            errors.push("userId", "a user or a route is required");
+        }
+        if let Some(route) = route.as_deref() {
+            if !route.starts_with("/api/") {
+                errors.push("route", "must be a route template starting with /api/");
+            } else if route.chars().count() > ROUTE_MAX_LEN {
+                errors.push("route", format!("must be at most {} characters", ROUTE_MAX_LEN));
+            }
+        }
+        if !(1..=MAX_MINUTES).contains(&minutes) {
+            errors.push("minutes", format!("must be between 1 and {}", MAX_MINUTES));
+        }
+        if reason.as_deref().is_some_and(|r| r.chars().count() > REASON_MAX_LEN) {
+            errors.push("reason", format!("must be at most {} characters", REASON_MAX_LEN));
+        }
+        errors.into_result()?;
+        if let Some(user_id) = request.user_id
+            && !DebugCaptureRepository::live_user_exists(pool, user_id).await?
+        {
+            return Err(ServiceError::NotFound("User".to_string()));
+        }
+        let expires_at = Utc::now() + ChronoDuration::minutes(minutes);
+        tracing::warn!(
+            user_id = request.user_id,
+            route = route.as_deref().unwrap_or_default(),
+            created_by,
+            "Starting debug capture of request bodies for {} minutes",
+            minutes
+        );
+        let id = DebugCaptureRepository::create(
+            pool,
+            request.user_id,
+            route.as_deref(),
+            reason.as_deref(),
+            expires_at,
+            created_by,
+        )
+        .await?;
+        DebugCaptures::reload(pool).await?;
+        Ok(id)
+    }
+
+    /// Ends a capture now; expired captures can be deleted too.
+    pub async fn delete_capture(pool: &SqlitePool, id: i64) -> Result<(), ServiceError> {
+        tracing::info!("Deleting debug capture {}", id);
+        if !DebugCaptureRepository::delete(pool, id).await? {
+            return Err(ServiceError::NotFound("Debug capture".to_string()));
+        }
+        DebugCaptures::reload(pool).await
+    }
+}
+
+fn normalize(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ActiveCapture, capture_payload, find_match, is_capturable};
+
+    use chrono::{Duration, Utc};
+    use serde_json::json;
+
+    fn capture(id: i64, user_id: Option<i64>, route: Option<&str>, minutes: i64) -> ActiveCapture {
+        ActiveCapture {
+            id,
+            user_id,
+            route: route.map(str::to_string),
+            expires_at: Utc::now() + Duration::minutes(minutes),
+        }
+    }
+
+    #[test]
+    fn captures_match_every_target_they_set_until_they_expire() {
+        let now = Utc::now();
+        let captures = [
+            capture(1, Some(7), None, 30),
+            capture(2, None, Some("/api/system/users"), 30),
+            capture(3, Some(8), Some("/api/system/roles"), 30),
+            capture(4, Some(9), None, -1),
+        ];
+        assert_eq!(find_match(&captures, Some(7), Some("/api/system/menus"), now), Some(1));
+        assert_eq!(find_match(&captures, None, Some("/api/system/users"), now), Some(2));
+        assert_eq!(find_match(&captures, Some(7), Some("/api/system/users"), now), Some(2));
+        assert_eq!(find_match(&captures, Some(8), Some("/api/system/roles"), now), Some(3));
+        assert_eq!(find_match(&captures, Some(8), Some("/api/system/menus"), now), None);
+        assert_eq!(find_match(&captures, Some(9), None, now), None);
+    }
+
+    #[test]
+    fn captured_bodies_hide_secrets_at_any_depth() {
+        let body = json!({
+            "username": "alice",
+            "password": "hunter2",
+            "profile": { "apiKey": "k", "items": [{ "refresh_token": "t", "note": "ok" }] },
+            "OTP-Code": "123456",
+            "login": { "phone": "13800000000", "code": "424242", "state": "s", "roleCode": "ops" },
+            "verification_code": "9999",
+        });
+        let data = capture_payload(5, Some("application/json"), body.to_string().as_bytes());
+        assert_eq!(
+            data["debugCapture"]["requestBody"],
+            json!({
+                "username": "alice",
+                "password": "[REDACTED]",
+                "profile": { "apiKey": "[REDACTED]", "items": [{ "refresh_token": "[REDACTED]", "note": "ok" }] },
+                "OTP-Code": "[REDACTED]",
+                "login": {
+                    "phone": "13800000000",
+                    "code": "[REDACTED]",
+                    "state": "[REDACTED]",
+                    "roleCode": "ops",
+                },
+                "verification_code": "[REDACTED]",
+            })
+        );
+        assert_eq!(data["debugCapture"]["captureId"], 5);
+    }
+
+    #[test]
+    fn large_and_opaque_bodies_are_not_stored_whole() {
+        let large = json!({ "note": "x".repeat(20_000) }).to_string();
+        let data = capture_payload(1, Some("application/json"), large.as_bytes());
+        assert_eq!(data["debugCapture"]["truncated"], true);
+        assert!(data["debugCapture"]["requestBody"].as_str().unwrap().len() <= 8 * 1024);
+
+        assert!(is_capturable(Some("application/merge-patch+json; charset=utf-8")));
+        assert!(!is_capturable(Some("multipart/form-data; boundary=x")));
+        let data = capture_payload(1, Some("application/octet-stream"), b"\x00\x01");
+        assert_eq!(data["debugCapture"]["requestBody"], "[2 bytes not captured]");
+    }
+}
@@ -0,0 +1,78 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Debug capture row joined with the captured user's name.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DebugCaptureRow {
+    pub id: i64,
+    pub user_id: Option<i64>,
+    pub username: Option<String>,
+    pub route: Option<String>,
+    pub reason: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub created_by: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Debug capture for list display.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugCaptureItemResp {
+    pub id: i64,
+    pub user_id: Option<i64>,
+    pub username: Option<String>,
+    pub route: Option<String>,
+    pub reason: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    /// Still capturing; false once `expires_at` has passed.
+    pub active: bool,
+    pub created_by: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<DebugCaptureRow> for DebugCaptureItemResp {
+    fn from(row: DebugCaptureRow) -> Self {
+        Self {
+            active: row.expires_at > Utc::now(),
+            id: row.id,
+            user_id: row.user_id,
+            username: row.username,
+            route: row.route,
+            reason: row.reason,
+            expires_at: row.expires_at,
+            created_by: row.created_by,
+            created_at: row.created_at,
+        }
+    }
+}
+
+/// Start capturing request bodies of a user, a route template, or a user on one route.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateDebugCaptureRequest {
+    pub user_id: Option<i64>,
+    /// Route template as shown in the operation log, e.g. `/api/system/users/{id}`.
+    pub route: Option<String>,
+    /// How long to capture; defaults to 30 and is at most 1440 (one day).
+    pub minutes: Option<i64>,
+    pub reason: Option<String>,
+}
+
+/// Debug capture query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugCaptureQuery {
+    pub current: Option<i64>,
+    pub page_size: Option<i64>,
+    /// Only captures that have not expired.
+    pub active: Option<bool>,
+}
+
+/// What a live capture matches, as held in memory for the log middleware.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct ActiveCapture {
+    pub id: i64,
+    pub user_id: Option<i64>,
+    pub route: Option<String>,
+    pub expires_at: DateTime<Utc>,
+}
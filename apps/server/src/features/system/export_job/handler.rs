@@ -0,0 +1,100 @@
+use super::{
+    service::{EXPORT_JOBS_TASK_KEY, ExportJobService},
+    types::{
+        CreateExportJobRequest, ExportDownloadQuery, ExportJobQuery, ExportJobResp, ExportLinkResp,
+    },
+};
+use crate::{
+    common::{
+        api::{ApiResponse, AppResult, PageMeta},
+        error::AppError,
+        pagination::{Pagination, PaginationQuery},
+    },
+    features::manage::task::service::TaskService,
+    infra::config::AppConfig,
+};
+
+use axum::{
+    Extension, Json,
+    extract::{ConnectInfo, Path, Query, State},
+    http::{HeaderMap, HeaderValue, header},
+    response::{IntoResponse, Response},
+};
+use rustzen_core::auth::CurrentUser;
+use sqlx::SqlitePool;
+use std::{net::SocketAddr, sync::Arc};
+
+/// Queue an export and start the export task right away
+pub async fn create_export(
+    current_user: CurrentUser,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Extension(config): Extension<&'static AppConfig>,
+    Extension(task_service): Extension<Arc<TaskService>>,
+    State(pool): State<SqlitePool>,
+    Json(request): Json<CreateExportJobRequest>,
+) -> AppResult<ExportJobResp> {
+    let job =
+        ExportJobService::create(config, &pool, &current_user, addr.ip().to_string(), request)
+            .await?;
+    // A run already in progress picks the job up before it finishes; so does the next tick.
+    if let Err(err) = task_service.run_task(EXPORT_JOBS_TASK_KEY).await {
+        tracing::debug!(id = job.id, "Export task not started now: {}", err);
+    }
+    Ok(ApiResponse::success(job))
+}
+
+/// Get the caller's export jobs, newest first
+pub async fn list_exports(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Query(query): Query<ExportJobQuery>,
+) -> AppResult<Vec<ExportJobResp>> {
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (jobs, total) = ExportJobService::list(&pool, &current_user, query).await?;
+    Ok(ApiResponse::page(jobs, total, PageMeta::new(pagination, total)))
+}
+
+/// Get one export job with its progress
+pub async fn get_export(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+) -> AppResult<ExportJobResp> {
+    Ok(ApiResponse::success(ExportJobService::get(&pool, &current_user, id).await?))
+}
+
+/// Get a short-lived signed download link for a finished export
+pub async fn get_export_link(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+) -> AppResult<ExportLinkResp> {
+    Ok(ApiResponse::success(ExportJobService::link(config, &pool, &current_user, id).await?))
+}
+
+/// Download an export file through a signed link, without a token
+pub async fn download_export(
+    Extension(config): Extension<&'static AppConfig>,
+    State(pool): State<SqlitePool>,
+    Path(name): Path<String>,
+    Query(query): Query<ExportDownloadQuery>,
+) -> Result<Response, AppError> {
+    let (job, data) = ExportJobService::download(config, &pool, &name, query).await?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("text/csv; charset=utf-8"));
+    // File names are generated ASCII, so this cannot fail in practice.
+    if let Ok(disposition) =
+        HeaderValue::from_str(&format!("attachment; filename={}", job.file_name))
+    {
+        headers.insert(header::CONTENT_DISPOSITION, disposition);
+    }
+    headers.insert(header::CONTENT_LENGTH, HeaderValue::from(data.len()));
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+
+    Ok((headers, data).into_response())
+}
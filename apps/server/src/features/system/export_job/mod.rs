@@ -0,0 +1,33 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use handler::{create_export, download_export, get_export, get_export_link, list_exports};
+use rustzen_core::{
+    capability::{manage_dict, manage_log, system_user},
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+/// Any export capability opens the queue; creating a job checks the one for its resource.
+fn exporter() -> PermissionsCheck {
+    PermissionsCheck::Any(vec![manage_log::EXPORT, system_user::EXPORT, manage_dict::EXPORT])
+}
+
+pub fn export_job_routes() -> Router<SqlitePool> {
+    Router::new()
+        .route_with_permission("/", post(create_export), exporter())
+        .route_with_permission("/", get(list_exports), exporter())
+        .route_with_permission("/{id}", get(get_export), exporter())
+        .route_with_permission("/{id}/link", get(get_export_link), exporter())
+}
+
+/// Signed export downloads, listed in `PUBLIC_ROUTES`; the link is the credential.
+pub fn public_export_routes() -> Router<SqlitePool> {
+    Router::new().route("/{name}", get(download_export))
+}
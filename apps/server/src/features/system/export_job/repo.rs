@@ -0,0 +1,186 @@
+use super::types::{ExportJobRow, ExportJobStatus, NewExportJob};
+use crate::common::{error::ServiceError, tx::Tx};
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+
+pub struct ExportJobRepository;
+
+fn db_error(context: &str, e: sqlx::Error) -> ServiceError {
+    tracing::error!("Database error {}: {:?}", context, e);
+    ServiceError::DatabaseQueryFailed
+}
+
+impl ExportJobRepository {
+    pub async fn insert(pool: &SqlitePool, job: &NewExportJob) -> Result<i64, ServiceError> {
+        sqlx::query_scalar::<_, i64>(
+            "INSERT INTO export_jobs (resource, filters, columns, user_id, username, ip_address,
+                                      masked, timezone, file_name, stored_name, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+             RETURNING id",
+        )
+        .bind(job.resource.as_str())
+        .bind(&job.filters)
+        .bind(job.columns.as_deref())
+        .bind(job.user_id)
+        .bind(&job.username)
+        .bind(&job.ip_address)
+        .bind(job.masked)
+        .bind(&job.timezone)
+        .bind(&job.file_name)
+        .bind(&job.stored_name)
+        .bind(Utc::now().naive_utc())
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_error("inserting export job", e))
+    }
+
+    /// Jobs requested by `user_id`, newest first.
+    pub async fn list_for_user(
+        pool: &SqlitePool,
+        user_id: i64,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<ExportJobRow>, i64), ServiceError> {
+        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM export_jobs WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| db_error("counting export jobs", e))?;
+        if total == 0 {
+            return Ok((Vec::new(), total));
+        }
+        let jobs = sqlx::query_as::<_, ExportJobRow>(
+            "SELECT id, resource, filters, user_id, username, ip_address, masked, timezone,
+                    status, total_rows, rows_done, file_name, stored_name, size_bytes,
+                    error_message, created_at, started_at, finished_at, expires_at, columns
+             FROM export_jobs WHERE user_id = ? ORDER BY id DESC LIMIT ? OFFSET ?",
+        )
+        .bind(user_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("listing export jobs", e))?;
+        Ok((jobs, total))
+    }
+
+    pub async fn find_by_id(
+        pool: &SqlitePool,
+        id: i64,
+    ) -> Result<Option<ExportJobRow>, ServiceError> {
+        sqlx::query_as::<_, ExportJobRow>(
+            "SELECT id, resource, filters, user_id, username, ip_address, masked, timezone,
+                    status, total_rows, rows_done, file_name, stored_name, size_bytes,
+                    error_message, created_at, started_at, finished_at, expires_at, columns
+             FROM export_jobs WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_error("finding export job", e))
+    }
+
+    pub async fn find_by_stored_name(
+        pool: &SqlitePool,
+        stored_name: &str,
+    ) -> Result<Option<ExportJobRow>, ServiceError> {
+        sqlx::query_as::<_, ExportJobRow>(
+            "SELECT id, resource, filters, user_id, username, ip_address, masked, timezone,
+                    status, total_rows, rows_done, file_name, stored_name, size_bytes,
+                    error_message, created_at, started_at, finished_at, expires_at, columns
+             FROM export_jobs WHERE stored_name = ?",
+        )
+        .bind(stored_name)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_error("finding export job by file", e))
+    }
+
+    /// Marks the oldest unfinished job running and returns it.
+    ///
+    /// Jobs left `running` by an interrupted run are claimed again and start over.
+    pub async fn claim_next(pool: &SqlitePool) -> Result<Option<ExportJobRow>, ServiceError> {
+        sqlx::query_as::<_, ExportJobRow>(
+            "UPDATE export_jobs
+             SET status = 'running', started_at = ?, rows_done = 0, total_rows = NULL
+             WHERE id = (
+                 SELECT id FROM export_jobs WHERE status IN ('pending', 'running')
+                 ORDER BY id LIMIT 1
+             )
+             RETURNING id, resource, filters, user_id, username, ip_address, masked, timezone,
+                       status, total_rows, rows_done, file_name, stored_name, size_bytes,
+                       error_message, created_at, started_at, finished_at, expires_at, columns",
+        )
+        .bind(Utc::now().naive_utc())
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_error("claiming export job", e))
+    }
+
+    pub async fn record_progress(
+        pool: &SqlitePool,
+        id: i64,
+        rows_done: i64,
+        total_rows: i64,
+    ) -> Result<(), ServiceError> {
+        sqlx::query("UPDATE export_jobs SET rows_done = ?, total_rows = ? WHERE id = ?")
+            .bind(rows_done)
+            .bind(total_rows)
+            .bind(id)
+            .execute(pool)
+            .await
+            .map_err(|e| db_error("recording export progress", e))?;
+        Ok(())
+    }
+
+    pub async fn complete_in_tx(
+        tx: &mut Tx<'_>,
+        id: i64,
+        size_bytes: i64,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), ServiceError> {
+        sqlx::query(
+            "UPDATE export_jobs SET status = ?, size_bytes = ?, finished_at = ?, expires_at = ?
+             WHERE id = ?",
+        )
+        .bind(ExportJobStatus::Completed.as_str())
+        .bind(size_bytes)
+        .bind(Utc::now().naive_utc())
+        .bind(expires_at.naive_utc())
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| db_error("completing export job", e))?;
+        Ok(())
+    }
+
+    pub async fn fail(pool: &SqlitePool, id: i64, error: &str) -> Result<(), ServiceError> {
+        sqlx::query(
+            "UPDATE export_jobs SET status = ?, error_message = ?, finished_at = ? WHERE id = ?",
+        )
+        .bind(ExportJobStatus::Failed.as_str())
+        .bind(error)
+        .bind(Utc::now().naive_utc())
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| db_error("failing export job", e))?;
+        Ok(())
+    }
+
+    /// Marks completed jobs past `expires_at` expired and returns their stored file names.
+    pub async fn expire_finished(pool: &SqlitePool) -> Result<Vec<String>, ServiceError> {
+        sqlx::query_scalar::<_, String>(
+            "UPDATE export_jobs SET status = ?
+             WHERE status = ? AND expires_at <= ?
+             RETURNING stored_name",
+        )
+        .bind(ExportJobStatus::Expired.as_str())
+        .bind(ExportJobStatus::Completed.as_str())
+        .bind(Utc::now().naive_utc())
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("expiring export jobs", e))
+    }
+}
@@ -0,0 +1,311 @@
+use super::{
+    repo::ExportJobRepository,
+    types::{
+        CreateExportJobRequest, ExportDownloadQuery, ExportJobQuery, ExportJobResp, ExportJobRow,
+        ExportJobStatus, ExportLinkResp, ExportQuery, ExportResource, NewExportJob,
+    },
+};
+use crate::{
+    common::{
+        error::ServiceError,
+        export::{CsvExport, ExportChunk, Exporter},
+        files::{remove_export_file, signed_export_url, verify_export_link},
+        mask::FieldMask,
+        pagination::{Pagination, PaginationQuery},
+        tx,
+        validation::FieldError,
+    },
+    features::{
+        account::service::AccountService,
+        manage::{dict::service::DictService, log::service::LogService},
+        system::{export_template::service::ExportTemplateService, user::service::UserService},
+    },
+    infra::{config::AppConfig, events},
+};
+
+use chrono::{Duration, Utc};
+use chrono_tz::Tz;
+use rustzen_core::auth::CurrentUser;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tokio::io::AsyncWriteExt;
+
+/// Scheduled task that works through the export queue.
+pub const EXPORT_JOBS_TASK_KEY: &str = "export-jobs";
+/// How long a finished file stays downloadable before the task deletes it.
+const FILE_RETENTION_HOURS: i64 = 24;
+/// Lifetime of one download link; ask for a new link to download again later.
+const LINK_TTL_MINUTES: i64 = 15;
+
+pub struct ExportJobService;
+
+impl ExportJobService {
+    /// Queues an export of `resource` with the filters of its list endpoint.
+    ///
+    /// The job runs with the requester's export capability, privacy masking and timezone as
+    /// they are now, and with the columns of `templateId` or the resource's default template;
+    /// the `export-jobs` task writes the file.
+    pub async fn create(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        ip_address: String,
+        request: CreateExportJobRequest,
+    ) -> Result<ExportJobResp, ServiceError> {
+        let CreateExportJobRequest { resource, filters, template_id } = request;
+        let resource = ExportResource::parse(resource.trim())
+            .ok_or_else(|| field_error("resource", "must be logs, users or dicts".to_string()))?;
+        if !current_user.has_capability(resource.capability()) {
+            return Err(ServiceError::InvalidOperation(format!(
+                "Exporting {} requires the {} permission",
+                resource.as_str(),
+                resource.capability()
+            )));
+        }
+        let filters = Value::Object(filters);
+        parse_query(resource, filters.clone())?;
+        let columns = ExportTemplateService::columns_for(pool, resource, template_id).await?;
+
+        let tz = AccountService::effective_timezone(config, pool, current_user.user_id).await?;
+        let job = NewExportJob {
+            resource,
+            filters: filters.to_string(),
+            columns: columns.map(|columns| Value::from(columns).to_string()),
+            user_id: current_user.user_id,
+            username: current_user.username.clone(),
+            ip_address,
+            masked: FieldMask::for_user(current_user) == FieldMask::Mask,
+            timezone: tz.name().to_string(),
+            file_name: format!("{}_{}.csv", resource.file_prefix(), Utc::now().timestamp_millis()),
+            stored_name: format!("{}.csv", uuid::Uuid::new_v4()),
+        };
+        let id = ExportJobRepository::insert(pool, &job).await?;
+        tracing::info!(id, resource = resource.as_str(), user_id = job.user_id, "Export queued");
+        Self::get(pool, current_user, id).await
+    }
+
+    /// The caller's own jobs, newest first.
+    pub async fn list(
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        query: ExportJobQuery,
+    ) -> Result<(Vec<ExportJobResp>, i64), ServiceError> {
+        let ExportJobQuery { current, page_size } = query;
+        let pagination = Pagination::from_query(PaginationQuery { current, page_size });
+        let (rows, total) = ExportJobRepository::list_for_user(
+            pool,
+            current_user.user_id,
+            pagination.offset.into(),
+            pagination.limit.into(),
+        )
+        .await?;
+        Ok((rows.into_iter().map(ExportJobResp::from).collect(), total))
+    }
+
+    pub async fn get(
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        id: i64,
+    ) -> Result<ExportJobResp, ServiceError> {
+        Ok(Self::find_own(pool, current_user, id).await?.into())
+    }
+
+    /// A signed link to the finished file, valid for 15 minutes or until the file expires.
+    pub async fn link(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        id: i64,
+    ) -> Result<ExportLinkResp, ServiceError> {
+        let job = Self::find_own(pool, current_user, id).await?;
+        let now = Utc::now();
+        let file_expires_at = job
+            .expires_at
+            .filter(|at| job.status == ExportJobStatus::Completed.as_str() && *at > now)
+            .ok_or_else(|| {
+                ServiceError::InvalidOperation(format!(
+                    "Export {} has no file to download (status {})",
+                    id, job.status
+                ))
+            })?;
+        let expires_at = file_expires_at.min(now + Duration::minutes(LINK_TTL_MINUTES));
+        Ok(ExportLinkResp {
+            url: signed_export_url(config, &job.stored_name, expires_at),
+            expires_at,
+        })
+    }
+
+    /// Job row and file contents behind a signed link.
+    pub async fn download(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        stored_name: &str,
+        query: ExportDownloadQuery,
+    ) -> Result<(ExportJobRow, Vec<u8>), ServiceError> {
+        if !verify_export_link(config, stored_name, query.expires, &query.signature) {
+            return Err(ServiceError::InvalidOperation(
+                "Download link is invalid or has expired".to_string(),
+            ));
+        }
+        let job = ExportJobRepository::find_by_stored_name(pool, stored_name)
+            .await?
+            .filter(|job| job.status == ExportJobStatus::Completed.as_str())
+            .ok_or_else(|| ServiceError::NotFound("Export file".to_string()))?;
+        let path = config.exports_dir().join(&job.stored_name);
+        let data = tokio::fs::read(&path).await.map_err(|err| {
+            tracing::warn!(id = job.id, path = %path.display(), "Export file unreadable: {}", err);
+            ServiceError::NotFound(format!("Export file {}", job.id))
+        })?;
+        Ok((job, data))
+    }
+
+    /// Writes every queued job to its file, oldest first, and returns how many were taken.
+    ///
+    /// Jobs queued while this runs are picked up before it returns. A job that fails is
+    /// marked failed with the error and its partial file removed; the others carry on.
+    pub async fn process_pending(
+        config: &AppConfig,
+        pool: &SqlitePool,
+    ) -> Result<usize, ServiceError> {
+        let mut processed = 0;
+        while let Some(job) = ExportJobRepository::claim_next(pool).await? {
+            processed += 1;
+            let (id, stored_name) = (job.id, job.stored_name.clone());
+            if let Err(err) = Self::process(config, pool, job).await {
+                tracing::error!(id, "Export job failed: {}", err);
+                remove_export_file(config, &stored_name).await;
+                ExportJobRepository::fail(pool, id, &err.to_string()).await?;
+            }
+        }
+        Ok(processed)
+    }
+
+    /// Deletes the files of jobs past their expiry and returns how many there were.
+    pub async fn expire_files(
+        config: &AppConfig,
+        pool: &SqlitePool,
+    ) -> Result<usize, ServiceError> {
+        let stored_names = ExportJobRepository::expire_finished(pool).await?;
+        for stored_name in &stored_names {
+            remove_export_file(config, stored_name).await;
+        }
+        Ok(stored_names.len())
+    }
+
+    async fn process(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        job: ExportJobRow,
+    ) -> Result<(), ServiceError> {
+        let resource = ExportResource::parse(&job.resource).ok_or_else(|| {
+            ServiceError::InvalidOperation(format!("Unknown export resource {}", job.resource))
+        })?;
+        let filters = serde_json::from_str(&job.filters).unwrap_or(Value::Null);
+        let query = parse_query(resource, filters)?;
+        let tz = job.timezone.parse::<Tz>().unwrap_or(Tz::UTC);
+        let mask = if job.masked { FieldMask::Mask } else { FieldMask::Reveal };
+
+        let dir = config.exports_dir();
+        tokio::fs::create_dir_all(&dir).await.map_err(file_error)?;
+        let mut file =
+            tokio::fs::File::create(dir.join(&job.stored_name)).await.map_err(file_error)?;
+        let mut export = CsvExport::with_columns(resource.columns(), job.column_names().as_deref());
+        if config.ops.export_watermark {
+            export.watermark(&job.username, Utc::now().with_timezone(&tz));
+        }
+        let mut position = 0;
+        let mut rows_done = 0;
+        loop {
+            let chunk = write_chunk(pool, &query, position, tz, mask, &mut export).await?;
+            rows_done += export.rows();
+            let content = export.take_content();
+            file.write_all(content.as_bytes()).await.map_err(file_error)?;
+            ExportJobRepository::record_progress(pool, job.id, rows_done as i64, chunk.total)
+                .await?;
+            match chunk.next {
+                Some(next) => position = next,
+                None => break,
+            }
+        }
+        file.flush().await.map_err(file_error)?;
+        let size_bytes = file.metadata().await.map_err(file_error)?.len() as i64;
+
+        let expires_at = Utc::now() + Duration::hours(FILE_RETENTION_HOURS);
+        let exporter = Exporter {
+            user_id: job.user_id,
+            username: job.username,
+            ip_address: job.ip_address,
+            filters: Some(job.filters),
+        };
+        let mut tx = tx::begin(pool).await?;
+        ExportJobRepository::complete_in_tx(&mut tx, job.id, size_bytes, expires_at).await?;
+        let recorded =
+            events::record(&mut tx, &exporter.event(resource.as_str(), rows_done)).await?;
+        tx::commit(tx).await?;
+        tracing::info!(id = job.id, rows_done, size_bytes, "Export job completed");
+        events::deliver(pool, recorded).await;
+        Ok(())
+    }
+
+    async fn find_own(
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        id: i64,
+    ) -> Result<ExportJobRow, ServiceError> {
+        ExportJobRepository::find_by_id(pool, id)
+            .await?
+            .filter(|job| job.user_id == current_user.user_id)
+            .ok_or_else(|| ServiceError::NotFound(format!("Export job {id}")))
+    }
+}
+
+/// Reads `filters` as the list query of `resource`, rejecting values the list would reject.
+fn parse_query(resource: ExportResource, filters: Value) -> Result<ExportQuery, ServiceError> {
+    let query = match resource {
+        ExportResource::Logs => serde_json::from_value(filters).map(ExportQuery::Logs),
+        ExportResource::Users => serde_json::from_value(filters).map(ExportQuery::Users),
+        ExportResource::Dicts => serde_json::from_value(filters).map(ExportQuery::Dicts),
+    }
+    .map_err(|err| field_error("filters", err.to_string()))?;
+    // The list's own checks (status values, sort fields), so bad filters fail the request
+    // rather than the job.
+    match &query {
+        ExportQuery::Logs(query) => LogService::check_export_query(query)?,
+        ExportQuery::Users(query) => {
+            UserService::user_list_query(query.clone())?;
+        }
+        ExportQuery::Dicts(query) => {
+            DictService::dict_list_query(query.clone())?;
+        }
+    }
+    Ok(query)
+}
+
+async fn write_chunk(
+    pool: &SqlitePool,
+    query: &ExportQuery,
+    position: i64,
+    tz: Tz,
+    mask: FieldMask,
+    export: &mut CsvExport,
+) -> Result<ExportChunk, ServiceError> {
+    match query {
+        ExportQuery::Logs(query) => {
+            LogService::export_logs_chunk(pool, query, position, tz, mask, export).await
+        }
+        ExportQuery::Users(query) => {
+            UserService::export_users_chunk(pool, query, position, mask, tz, export).await
+        }
+        ExportQuery::Dicts(query) => {
+            DictService::export_dicts_chunk(pool, query, position, tz, export).await
+        }
+    }
+}
+
+fn field_error(field: &str, message: String) -> ServiceError {
+    ServiceError::InvalidFields(vec![FieldError { field: field.to_string(), message }])
+}
+
+fn file_error(err: std::io::Error) -> ServiceError {
+    ServiceError::InvalidOperation(format!("Failed to write export file: {err}"))
+}
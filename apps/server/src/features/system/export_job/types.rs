@@ -0,0 +1,225 @@
+use crate::features::{
+    manage::{
+        dict::{service::DICT_EXPORT_COLUMNS, types::DictQuery},
+        log::{service::LOG_EXPORT_COLUMNS, types::LogQuery},
+    },
+    system::user::{service::USER_EXPORT_COLUMNS, types::UserQuery},
+};
+
+use chrono::{DateTime, Utc};
+use rustzen_core::capability::{manage_dict, manage_log, system_user};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// What an export job exports, stored in `export_jobs.resource`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportResource {
+    Logs,
+    Users,
+    Dicts,
+}
+
+impl ExportResource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExportResource::Logs => "logs",
+            ExportResource::Users => "users",
+            ExportResource::Dicts => "dicts",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "logs" => Some(ExportResource::Logs),
+            "users" => Some(ExportResource::Users),
+            "dicts" => Some(ExportResource::Dicts),
+            _ => None,
+        }
+    }
+
+    /// Capability the synchronous export of the same list requires.
+    pub fn capability(self) -> &'static str {
+        match self {
+            ExportResource::Logs => manage_log::EXPORT,
+            ExportResource::Users => system_user::EXPORT,
+            ExportResource::Dicts => manage_dict::EXPORT,
+        }
+    }
+
+    /// Download name prefix, matching the synchronous export.
+    pub fn file_prefix(self) -> &'static str {
+        match self {
+            ExportResource::Logs => "log",
+            ExportResource::Users => "user",
+            ExportResource::Dicts => "dict",
+        }
+    }
+
+    pub fn columns(self) -> &'static [&'static str] {
+        match self {
+            ExportResource::Logs => &LOG_EXPORT_COLUMNS,
+            ExportResource::Users => &USER_EXPORT_COLUMNS,
+            ExportResource::Dicts => &DICT_EXPORT_COLUMNS,
+        }
+    }
+}
+
+/// Job state stored in `export_jobs.status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportJobStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+    Expired,
+}
+
+impl ExportJobStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExportJobStatus::Pending => "pending",
+            ExportJobStatus::Running => "running",
+            ExportJobStatus::Completed => "completed",
+            ExportJobStatus::Failed => "failed",
+            ExportJobStatus::Expired => "expired",
+        }
+    }
+}
+
+/// List filters of a job, parsed as the list endpoint of its resource would.
+#[derive(Debug, Clone)]
+pub enum ExportQuery {
+    Logs(LogQuery),
+    Users(UserQuery),
+    Dicts(DictQuery),
+}
+
+/// Export job row as read from the database.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ExportJobRow {
+    pub id: i64,
+    pub resource: String,
+    pub filters: String,
+    pub user_id: i64,
+    pub username: String,
+    pub ip_address: String,
+    pub masked: bool,
+    pub timezone: String,
+    pub status: String,
+    pub total_rows: Option<i64>,
+    pub rows_done: i64,
+    pub file_name: String,
+    pub stored_name: String,
+    pub size_bytes: Option<i64>,
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// JSON array of the exported columns; `None` exports them all.
+    pub columns: Option<String>,
+}
+
+impl ExportJobRow {
+    /// Columns the file holds; `None` for every column.
+    pub fn column_names(&self) -> Option<Vec<String>> {
+        self.columns.as_deref().and_then(|columns| serde_json::from_str(columns).ok())
+    }
+}
+
+/// Export job with its progress.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportJobResp {
+    pub id: i64,
+    pub resource: String,
+    pub filters: Value,
+    /// Exported columns, from the template the job was queued with; `None` for all.
+    pub columns: Option<Vec<String>>,
+    /// `pending`, `running`, `completed`, `failed` or `expired`.
+    pub status: String,
+    /// Rows matching the filters; known once the job has started.
+    pub total_rows: Option<i64>,
+    pub rows_done: i64,
+    pub file_name: String,
+    pub size_bytes: Option<i64>,
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// When the file is deleted; download links never outlive it.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl From<ExportJobRow> for ExportJobResp {
+    fn from(row: ExportJobRow) -> Self {
+        Self {
+            columns: row.column_names(),
+            id: row.id,
+            resource: row.resource,
+            filters: serde_json::from_str(&row.filters).unwrap_or_default(),
+            status: row.status,
+            total_rows: row.total_rows,
+            rows_done: row.rows_done,
+            file_name: row.file_name,
+            size_bytes: row.size_bytes,
+            error_message: row.error_message,
+            created_at: row.created_at,
+            started_at: row.started_at,
+            finished_at: row.finished_at,
+            expires_at: row.expires_at,
+        }
+    }
+}
+
+/// Request to export a list in the background.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateExportJobRequest {
+    /// `logs`, `users` or `dicts`.
+    pub resource: String,
+    /// Filters and sort of the resource's list endpoint, e.g. `{ "status": "1" }`.
+    #[serde(default)]
+    pub filters: Map<String, Value>,
+    /// Export template picking the columns; the resource's default template when omitted.
+    pub template_id: Option<i64>,
+}
+
+/// Export job list query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportJobQuery {
+    pub current: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+/// Time-limited download link of a finished export.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportLinkResp {
+    pub url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Query of a signed download link.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportDownloadQuery {
+    pub expires: i64,
+    pub signature: String,
+}
+
+/// Job to record in `export_jobs`.
+#[derive(Debug, Clone)]
+pub struct NewExportJob {
+    pub resource: ExportResource,
+    pub filters: String,
+    /// JSON array of the columns to export; `None` for all.
+    pub columns: Option<String>,
+    pub user_id: i64,
+    pub username: String,
+    pub ip_address: String,
+    pub masked: bool,
+    pub timezone: String,
+    pub file_name: String,
+    pub stored_name: String,
+}
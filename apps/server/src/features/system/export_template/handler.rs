@@ -0,0 +1,57 @@
+use super::{
+    service::ExportTemplateService,
+    types::{
+        CreateExportTemplateRequest, ExportColumnsResp, ExportTemplateQuery, ExportTemplateResp,
+        UpdateExportTemplatePayload,
+    },
+};
+use crate::{
+    common::api::{ApiResponse, AppResult},
+    infra::db::DbExecutor,
+};
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use sqlx::SqlitePool;
+
+/// Get export templates, optionally of one resource
+pub async fn list_export_templates(
+    State(db): State<DbExecutor>,
+    Query(query): Query<ExportTemplateQuery>,
+) -> AppResult<Vec<ExportTemplateResp>> {
+    Ok(ApiResponse::success(ExportTemplateService::list(db.read(), query).await?))
+}
+
+/// Get the columns each exportable resource offers
+pub async fn list_export_columns() -> AppResult<Vec<ExportColumnsResp>> {
+    Ok(ApiResponse::success(ExportTemplateService::columns()))
+}
+
+/// Create an export template
+pub async fn create_export_template(
+    State(pool): State<SqlitePool>,
+    Json(request): Json<CreateExportTemplateRequest>,
+) -> AppResult<i64> {
+    Ok(ApiResponse::success(ExportTemplateService::create(&pool, request).await?))
+}
+
+/// Update an export template
+pub async fn update_export_template(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+    Json(request): Json<UpdateExportTemplatePayload>,
+) -> AppResult<()> {
+    ExportTemplateService::update(&pool, id, request).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Delete an export template
+pub async fn delete_export_template(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+) -> AppResult<()> {
+    ExportTemplateService::delete(&pool, id).await?;
+    Ok(ApiResponse::success(()))
+}
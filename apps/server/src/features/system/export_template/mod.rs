@@ -0,0 +1,49 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{
+    Router,
+    routing::{delete, get, post, put},
+};
+use handler::{
+    create_export_template, delete_export_template, list_export_columns, list_export_templates,
+    update_export_template,
+};
+use rustzen_core::{
+    capability::{manage_dict, manage_log, system_export_template, system_user},
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+/// Template managers and anyone who can export may read templates to pick one.
+fn reader() -> PermissionsCheck {
+    PermissionsCheck::Any(vec![
+        system_export_template::LIST,
+        manage_log::EXPORT,
+        system_user::EXPORT,
+        manage_dict::EXPORT,
+    ])
+}
+
+pub fn export_template_routes() -> Router<SqlitePool> {
+    Router::new()
+        .route_with_permission("/", get(list_export_templates), reader())
+        .route_with_permission("/columns", get(list_export_columns), reader())
+        .route_with_permission(
+            "/",
+            post(create_export_template),
+            PermissionsCheck::Require(system_export_template::CREATE),
+        )
+        .route_with_permission(
+            "/{id}",
+            put(update_export_template),
+            PermissionsCheck::Require(system_export_template::UPDATE),
+        )
+        .route_with_permission(
+            "/{id}",
+            delete(delete_export_template),
+            PermissionsCheck::Require(system_export_template::DELETE),
+        )
+}
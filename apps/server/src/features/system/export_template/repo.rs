@@ -0,0 +1,156 @@
+use super::types::ExportTemplateRow;
+use crate::common::{error::ServiceError, tx};
+
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+pub struct ExportTemplateRepository;
+
+fn db_error(context: &str, e: sqlx::Error) -> ServiceError {
+    tracing::error!("Database error {}: {:?}", context, e);
+    ServiceError::DatabaseQueryFailed
+}
+
+impl ExportTemplateRepository {
+    pub async fn list(
+        pool: &SqlitePool,
+        resource: Option<&str>,
+    ) -> Result<Vec<ExportTemplateRow>, ServiceError> {
+        sqlx::query_as::<_, ExportTemplateRow>(
+            "SELECT id, resource, name, columns, is_default, created_at, updated_at
+             FROM export_templates
+             WHERE ? IS NULL OR resource = ?
+             ORDER BY resource, name",
+        )
+        .bind(resource)
+        .bind(resource)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("listing export templates", e))
+    }
+
+    pub async fn find_by_id(
+        pool: &SqlitePool,
+        id: i64,
+    ) -> Result<Option<ExportTemplateRow>, ServiceError> {
+        sqlx::query_as::<_, ExportTemplateRow>(
+            "SELECT id, resource, name, columns, is_default, created_at, updated_at
+             FROM export_templates WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_error("finding export template", e))
+    }
+
+    pub async fn find_default(
+        pool: &SqlitePool,
+        resource: &str,
+    ) -> Result<Option<ExportTemplateRow>, ServiceError> {
+        sqlx::query_as::<_, ExportTemplateRow>(
+            "SELECT id, resource, name, columns, is_default, created_at, updated_at
+             FROM export_templates WHERE resource = ? AND is_default = 1",
+        )
+        .bind(resource)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_error("finding default export template", e))
+    }
+
+    /// Whether another template than `except_id` of `resource` already uses `name`.
+    pub async fn name_exists(
+        pool: &SqlitePool,
+        resource: &str,
+        name: &str,
+        except_id: Option<i64>,
+    ) -> Result<bool, ServiceError> {
+        sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM export_templates
+                           WHERE resource = ? AND name = ? AND id IS NOT ?)",
+        )
+        .bind(resource)
+        .bind(name)
+        .bind(except_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_error("checking export template name", e))
+    }
+
+    /// Inserts a template; a new default replaces the resource's previous one.
+    pub async fn create(
+        pool: &SqlitePool,
+        resource: &str,
+        name: &str,
+        columns: &str,
+        is_default: bool,
+    ) -> Result<i64, ServiceError> {
+        let mut tx = tx::begin(pool).await?;
+        if is_default {
+            Self::clear_default(&mut tx, resource).await?;
+        }
+        let now = Utc::now().naive_utc();
+        let id = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO export_templates (resource, name, columns, is_default, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?)
+             RETURNING id",
+        )
+        .bind(resource)
+        .bind(name)
+        .bind(columns)
+        .bind(is_default)
+        .bind(now)
+        .bind(now)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| db_error("creating export template", e))?;
+        tx::commit(tx).await?;
+        Ok(id)
+    }
+
+    /// Updates a template of `resource`; a new default replaces the previous one.
+    pub async fn update(
+        pool: &SqlitePool,
+        id: i64,
+        resource: &str,
+        name: &str,
+        columns: &str,
+        is_default: bool,
+    ) -> Result<bool, ServiceError> {
+        let mut tx = tx::begin(pool).await?;
+        if is_default {
+            Self::clear_default(&mut tx, resource).await?;
+        }
+        let result = sqlx::query(
+            "UPDATE export_templates SET name = ?, columns = ?, is_default = ?, updated_at = ?
+             WHERE id = ?",
+        )
+        .bind(name)
+        .bind(columns)
+        .bind(is_default)
+        .bind(Utc::now().naive_utc())
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_error("updating export template", e))?;
+        tx::commit(tx).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: i64) -> Result<bool, ServiceError> {
+        let result = sqlx::query("DELETE FROM export_templates WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await
+            .map_err(|e| db_error("deleting export template", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn clear_default(tx: &mut tx::Tx<'_>, resource: &str) -> Result<(), ServiceError> {
+        sqlx::query("UPDATE export_templates SET is_default = 0 WHERE resource = ?")
+            .bind(resource)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| db_error("clearing default export template", e))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,219 @@
+use super::{
+    repo::ExportTemplateRepository,
+    types::{
+        CreateExportTemplateRequest, ExportColumnsResp, ExportTemplateQuery, ExportTemplateResp,
+        UpdateExportTemplatePayload,
+    },
+};
+use crate::{
+    common::{
+        error::ServiceError,
+        validation::{FieldError, FieldErrors},
+    },
+    features::system::export_job::types::ExportResource,
+};
+
+use sqlx::SqlitePool;
+
+const NAME_MAX_LEN: usize = 64;
+const RESOURCES: [ExportResource; 3] =
+    [ExportResource::Logs, ExportResource::Users, ExportResource::Dicts];
+
+pub struct ExportTemplateService;
+
+impl ExportTemplateService {
+    pub async fn list(
+        pool: &SqlitePool,
+        query: ExportTemplateQuery,
+    ) -> Result<Vec<ExportTemplateResp>, ServiceError> {
+        let resource = query.resource.as_deref().map(|r| parse_resource(r.trim())).transpose()?;
+        let rows =
+            ExportTemplateRepository::list(pool, resource.map(ExportResource::as_str)).await?;
+        Ok(rows.into_iter().map(ExportTemplateResp::from).collect())
+    }
+
+    /// Every exportable resource with the columns its templates can pick from.
+    pub fn columns() -> Vec<ExportColumnsResp> {
+        RESOURCES
+            .into_iter()
+            .map(|resource| ExportColumnsResp {
+                resource: resource.as_str(),
+                columns: resource.columns(),
+            })
+            .collect()
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        request: CreateExportTemplateRequest,
+    ) -> Result<i64, ServiceError> {
+        let resource = parse_resource(request.resource.trim())?;
+        let (name, columns) = check_template(resource, &request.name, &request.columns)?;
+        if ExportTemplateRepository::name_exists(pool, resource.as_str(), &name, None).await? {
+            return Err(ServiceError::InvalidOperation(format!(
+                "Export template '{}' already exists for {}",
+                name,
+                resource.as_str()
+            )));
+        }
+        tracing::info!("Creating {} export template '{}'", resource.as_str(), name);
+        ExportTemplateRepository::create(
+            pool,
+            resource.as_str(),
+            &name,
+            &columns_json(&columns),
+            request.is_default,
+        )
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: i64,
+        request: UpdateExportTemplatePayload,
+    ) -> Result<(), ServiceError> {
+        let existing = ExportTemplateRepository::find_by_id(pool, id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound("Export template".to_string()))?;
+        let resource = stored_resource(&existing.resource)?;
+        let (name, columns) = check_template(resource, &request.name, &request.columns)?;
+        if ExportTemplateRepository::name_exists(pool, resource.as_str(), &name, Some(id)).await? {
+            return Err(ServiceError::InvalidOperation(format!(
+                "Export template '{}' already exists for {}",
+                name,
+                resource.as_str()
+            )));
+        }
+        tracing::info!("Updating export template {} ('{}')", id, name);
+        let updated = ExportTemplateRepository::update(
+            pool,
+            id,
+            resource.as_str(),
+            &name,
+            &columns_json(&columns),
+            request.is_default,
+        )
+        .await?;
+        if !updated {
+            return Err(ServiceError::NotFound("Export template".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Deletes a template; queued export jobs keep the columns they were created with.
+    pub async fn delete(pool: &SqlitePool, id: i64) -> Result<(), ServiceError> {
+        tracing::info!("Deleting export template {}", id);
+        if !ExportTemplateRepository::delete(pool, id).await? {
+            return Err(ServiceError::NotFound("Export template".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Columns an export of `resource` writes: those of template `template_id`, else of the
+    /// resource's default template, else `None` for every column.
+    pub async fn columns_for(
+        pool: &SqlitePool,
+        resource: ExportResource,
+        template_id: Option<i64>,
+    ) -> Result<Option<Vec<String>>, ServiceError> {
+        let template = match template_id {
+            Some(id) => {
+                let template = ExportTemplateRepository::find_by_id(pool, id)
+                    .await?
+                    .ok_or_else(|| ServiceError::NotFound(format!("Export template {id}")))?;
+                if template.resource != resource.as_str() {
+                    return Err(ServiceError::InvalidOperation(format!(
+                        "Export template {} is for {}, not {}",
+                        id,
+                        template.resource,
+                        resource.as_str()
+                    )));
+                }
+                Some(template)
+            }
+            None => ExportTemplateRepository::find_default(pool, resource.as_str()).await?,
+        };
+        Ok(template.map(|template| template.column_names()))
+    }
+}
+
+fn parse_resource(value: &str) -> Result<ExportResource, ServiceError> {
+    ExportResource::parse(value).ok_or_else(|| {
+        ServiceError::InvalidFields(vec![FieldError {
+            field: "resource".to_string(),
+            message: "must be logs, users or dicts".to_string(),
+        }])
+    })
+}
+
+fn stored_resource(value: &str) -> Result<ExportResource, ServiceError> {
+    ExportResource::parse(value).ok_or_else(|| {
+        tracing::error!("Unknown export template resource {}", value);
+        ServiceError::DatabaseQueryFailed
+    })
+}
+
+/// Trimmed name and the picked columns in the order of the full export.
+fn check_template(
+    resource: ExportResource,
+    name: &str,
+    columns: &[String],
+) -> Result<(String, Vec<String>), ServiceError> {
+    let name = name.trim().to_string();
+    let mut errors = FieldErrors::new();
+    if name.is_empty() || name.chars().count() > NAME_MAX_LEN {
+        errors.push("name", format!("must be 1-{} characters", NAME_MAX_LEN));
+    }
+    let available = resource.columns();
+    let unknown: Vec<&str> =
+        columns.iter().map(|c| c.trim()).filter(|c| !available.contains(c)).collect();
+    if !unknown.is_empty() {
+        errors.push("columns", format!("unknown columns: {}", unknown.join(", ")));
+    } else if columns.is_empty() {
+        errors.push("columns", "must pick at least one column");
+    }
+    errors.into_result()?;
+    let picked = available
+        .iter()
+        .filter(|available| columns.iter().any(|c| c.trim() == **available))
+        .map(|c| c.to_string())
+        .collect();
+    Ok((name, picked))
+}
+
+fn columns_json(columns: &[String]) -> String {
+    serde_json::to_string(columns).unwrap_or_else(|_| "[]".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExportResource, check_template};
+    use crate::common::error::ServiceError;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn templates_keep_known_columns_in_export_order() {
+        let (name, columns) = check_template(
+            ExportResource::Users,
+            " Contacts ",
+            &strings(&["email", " username", "id", "email"]),
+        )
+        .unwrap();
+        assert_eq!(name, "Contacts");
+        assert_eq!(columns, ["id", "username", "email"]);
+    }
+
+    #[test]
+    fn templates_reject_unknown_or_missing_columns() {
+        let err = check_template(ExportResource::Users, "Bad", &strings(&["id", "password_hash"]))
+            .unwrap_err();
+        let ServiceError::InvalidFields(fields) = err else { panic!("{:?}", err) };
+        assert_eq!(fields[0].field, "columns");
+        assert_eq!(fields[0].message, "unknown columns: password_hash");
+        assert!(check_template(ExportResource::Logs, "Empty", &[]).is_err());
+        assert!(check_template(ExportResource::Logs, " ", &strings(&["id"])).is_err());
+    }
+}
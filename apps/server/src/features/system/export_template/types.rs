@@ -0,0 +1,95 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Export template row as read from the database.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ExportTemplateRow {
+    pub id: i64,
+    pub resource: String,
+    pub name: String,
+    /// JSON array of column names.
+    pub columns: String,
+    pub is_default: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ExportTemplateRow {
+    pub fn column_names(&self) -> Vec<String> {
+        serde_json::from_str(&self.columns).unwrap_or_default()
+    }
+}
+
+/// Export template
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportTemplateResp {
+    pub id: i64,
+    /// `logs`, `users` or `dicts`.
+    pub resource: String,
+    pub name: String,
+    /// Exported columns, in the order of the full export.
+    pub columns: Vec<String>,
+    /// Applied to exports of the resource that name no template.
+    pub is_default: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<ExportTemplateRow> for ExportTemplateResp {
+    fn from(row: ExportTemplateRow) -> Self {
+        Self {
+            columns: row.column_names(),
+            id: row.id,
+            resource: row.resource,
+            name: row.name,
+            is_default: row.is_default,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+/// Columns an export of one resource can hold.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportColumnsResp {
+    pub resource: &'static str,
+    pub columns: &'static [&'static str],
+}
+
+/// Create export template request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateExportTemplateRequest {
+    /// `logs`, `users` or `dicts`.
+    pub resource: String,
+    pub name: String,
+    pub columns: Vec<String>,
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+/// Update export template request; the resource is fixed.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateExportTemplatePayload {
+    pub name: String,
+    pub columns: Vec<String>,
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+/// Export template list query parameters
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportTemplateQuery {
+    /// Only the templates of this resource.
+    pub resource: Option<String>,
+}
+
+/// Template parameter of the CSV export endpoints, next to the list filters.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportTemplateParam {
+    /// Export template id; the resource's default template when omitted.
+    pub template: Option<i64>,
+}
@@ -0,0 +1,59 @@
+use super::{
+    service::FeatureFlagService,
+    types::{
+        CreateFeatureFlagRequest, FeatureFlagItemResp, FeatureFlagQuery, UpdateFeatureFlagPayload,
+    },
+};
+use crate::{
+    common::{
+        api::{ApiResponse, AppResult, PageMeta},
+        pagination::{Pagination, PaginationQuery},
+    },
+    infra::db::DbExecutor,
+};
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use sqlx::SqlitePool;
+
+/// Get paginated feature flag list
+pub async fn list_feature_flags(
+    State(db): State<DbExecutor>,
+    Query(query): Query<FeatureFlagQuery>,
+) -> AppResult<Vec<FeatureFlagItemResp>> {
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (flags, total) = FeatureFlagService::list_flags(db.read(), query).await?;
+    Ok(ApiResponse::page(flags, total, PageMeta::new(pagination, total)))
+}
+
+/// Create a feature flag
+pub async fn create_feature_flag(
+    State(pool): State<SqlitePool>,
+    Json(request): Json<CreateFeatureFlagRequest>,
+) -> AppResult<i64> {
+    Ok(ApiResponse::success(FeatureFlagService::create_flag(&pool, request).await?))
+}
+
+/// Update a feature flag
+pub async fn update_feature_flag(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+    Json(request): Json<UpdateFeatureFlagPayload>,
+) -> AppResult<()> {
+    FeatureFlagService::update_flag(&pool, id, request).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Delete a feature flag
+pub async fn delete_feature_flag(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+) -> AppResult<()> {
+    FeatureFlagService::delete_flag(&pool, id).await?;
+    Ok(ApiResponse::success(()))
+}
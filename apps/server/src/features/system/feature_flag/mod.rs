@@ -0,0 +1,39 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{
+    Router,
+    routing::{delete, get, post, put},
+};
+use handler::{create_feature_flag, delete_feature_flag, list_feature_flags, update_feature_flag};
+use rustzen_core::{
+    capability::system_flag,
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+pub fn feature_flag_routes() -> Router<SqlitePool> {
+    Router::new()
+        .route_with_permission(
+            "/",
+            get(list_feature_flags),
+            PermissionsCheck::Require(system_flag::LIST),
+        )
+        .route_with_permission(
+            "/",
+            post(create_feature_flag),
+            PermissionsCheck::Require(system_flag::CREATE),
+        )
+        .route_with_permission(
+            "/{id}",
+            put(update_feature_flag),
+            PermissionsCheck::Require(system_flag::UPDATE),
+        )
+        .route_with_permission(
+            "/{id}",
+            delete(delete_feature_flag),
+            PermissionsCheck::Require(system_flag::DELETE),
+        )
+}
@@ -0,0 +1,109 @@
+use super::types::FeatureFlagRow;
+use crate::common::{
+    error::ServiceError,
+    query::{count_with_filters, fetch_with_filters, push_ilike},
+};
+
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+pub struct FeatureFlagRepository;
+
+fn db_error(context: &str, e: sqlx::Error) -> ServiceError {
+    tracing::error!("Database error {}: {:?}", context, e);
+    ServiceError::DatabaseQueryFailed
+}
+
+impl FeatureFlagRepository {
+    pub async fn list_flags(
+        pool: &SqlitePool,
+        offset: i64,
+        limit: i64,
+        key: Option<&str>,
+    ) -> Result<(Vec<FeatureFlagRow>, i64), ServiceError> {
+        let total =
+            count_with_filters(pool, "SELECT COUNT(*) FROM feature_flags WHERE 1=1", |qb| {
+                push_ilike(qb, "key", key)
+            })
+            .await?;
+        if total == 0 {
+            return Ok((Vec::new(), total));
+        }
+        let flags = fetch_with_filters(
+            pool,
+            "SELECT id, key, description, enabled, created_at, updated_at FROM feature_flags WHERE 1=1",
+            |qb| push_ilike(qb, "key", key),
+            Some("key ASC"),
+            Some(limit),
+            Some(offset),
+        )
+        .await?;
+        Ok((flags, total))
+    }
+
+    /// Every stored `(key, enabled)` pair.
+    pub async fn list_states(pool: &SqlitePool) -> Result<Vec<(String, bool)>, ServiceError> {
+        sqlx::query_as::<_, (String, bool)>("SELECT key, enabled FROM feature_flags")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| db_error("loading feature flags", e))
+    }
+
+    pub async fn key_exists(pool: &SqlitePool, key: &str) -> Result<bool, ServiceError> {
+        sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM feature_flags WHERE key = ?)")
+            .bind(key)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| db_error("checking feature flag key", e))
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        key: &str,
+        description: Option<&str>,
+        enabled: bool,
+    ) -> Result<i64, ServiceError> {
+        let now = Utc::now().naive_utc();
+        sqlx::query_scalar::<_, i64>(
+            "INSERT INTO feature_flags (key, description, enabled, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?)
+             RETURNING id",
+        )
+        .bind(key)
+        .bind(description)
+        .bind(enabled)
+        .bind(now)
+        .bind(now)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_error("creating feature flag", e))
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: i64,
+        description: Option<&str>,
+        enabled: bool,
+    ) -> Result<bool, ServiceError> {
+        let result = sqlx::query(
+            "UPDATE feature_flags SET description = ?, enabled = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(description)
+        .bind(enabled)
+        .bind(Utc::now().naive_utc())
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| db_error("updating feature flag", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: i64) -> Result<bool, ServiceError> {
+        let result = sqlx::query("DELETE FROM feature_flags WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await
+            .map_err(|e| db_error("deleting feature flag", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,217 @@
+use super::{
+    repo::FeatureFlagRepository,
+    types::{
+        CreateFeatureFlagRequest, FeatureFlagItemResp, FeatureFlagQuery, UpdateFeatureFlagPayload,
+    },
+};
+use crate::{
+    common::{
+        error::ServiceError,
+        pagination::{Pagination, PaginationQuery},
+        validation::FieldErrors,
+    },
+    infra::config::AppConfig,
+};
+
+use once_cell::sync::Lazy;
+use sqlx::SqlitePool;
+use std::{collections::HashMap, sync::RwLock, time::Duration};
+
+const FLAG_KEY_MAX_LEN: usize = 64;
+const FLAG_DESCRIPTION_MAX_LEN: usize = 200;
+const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Stored flag values as of the last reload.
+static STORED: Lazy<RwLock<HashMap<String, bool>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// `RUSTZEN_FEATURE_FLAGS`, already validated when the config loaded; set by
+/// [`FeatureFlags::start`].
+static OVERRIDES: Lazy<RwLock<HashMap<String, bool>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Flag checks for service code, e.g. `FeatureFlags::is_enabled("new_dashboard")`.
+///
+/// Checks read an in-process snapshot, so they cost no query. The snapshot reloads after
+/// every write on this instance and every 30 seconds, which is how other instances see
+/// a change.
+pub struct FeatureFlags;
+
+impl FeatureFlags {
+    /// `RUSTZEN_FEATURE_FLAGS` wins, then the stored value; unknown keys are off.
+    pub fn is_enabled(key: &str) -> bool {
+        let overrides = OVERRIDES.read().unwrap_or_else(|e| e.into_inner());
+        let stored = STORED.read().unwrap_or_else(|e| e.into_inner());
+        evaluate(&overrides, &stored, key)
+    }
+
+    pub async fn reload(pool: &SqlitePool) -> Result<(), ServiceError> {
+        let states = FeatureFlagRepository::list_states(pool).await?;
+        *STORED.write().unwrap_or_else(|e| e.into_inner()) = states.into_iter().collect();
+        Ok(())
+    }
+
+    /// Loads the flags, then keeps reloading them in the background.
+    pub async fn start(config: &AppConfig, pool: SqlitePool) -> Result<(), ServiceError> {
+        let overrides: HashMap<_, _> =
+            config.feature_flag_overrides().unwrap_or_default().into_iter().collect();
+        tracing::info!(overrides = overrides.len(), "Loaded feature flags");
+        *OVERRIDES.write().unwrap_or_else(|e| e.into_inner()) = overrides;
+        Self::reload(&pool).await?;
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(REFRESH_INTERVAL).await;
+                if let Err(e) = Self::reload(&pool).await {
+                    tracing::error!("Feature flag reload failed: {:?}", e);
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+fn evaluate(overrides: &HashMap<String, bool>, stored: &HashMap<String, bool>, key: &str) -> bool {
+    overrides.get(key).or_else(|| stored.get(key)).copied().unwrap_or(false)
+}
+
+pub struct FeatureFlagService;
+
+impl FeatureFlagService {
+    pub async fn list_flags(
+        pool: &SqlitePool,
+        query: FeatureFlagQuery,
+    ) -> Result<(Vec<FeatureFlagItemResp>, i64), ServiceError> {
+        let FeatureFlagQuery { current, page_size, key } = query;
+        let pagination = Pagination::from_query(PaginationQuery { current, page_size });
+        let (rows, total) = FeatureFlagRepository::list_flags(
+            pool,
+            i64::from(pagination.offset),
+            i64::from(pagination.limit),
+            key.as_deref(),
+        )
+        .await?;
+        let overrides = OVERRIDES.read().unwrap_or_else(|e| e.into_inner());
+        let flags = rows
+            .into_iter()
+            .map(|row| {
+                let env_override = overrides.get(&row.key).copied();
+                FeatureFlagItemResp {
+                    effective: env_override.unwrap_or(row.enabled),
+                    env_override,
+                    id: row.id,
+                    key: row.key,
+                    description: row.description,
+                    enabled: row.enabled,
+                    created_at: row.created_at,
+                    updated_at: row.updated_at,
+                }
+            })
+            .collect();
+        Ok((flags, total))
+    }
+
+    pub async fn create_flag(
+        pool: &SqlitePool,
+        request: CreateFeatureFlagRequest,
+    ) -> Result<i64, ServiceError> {
+        let key = request.key.trim().to_string();
+        let description = normalize_description(request.description);
+        let mut errors = FieldErrors::new();
+        if let Err(message) = validate_key(&key) {
+            errors.push("key", message);
+        }
+        check_description(&mut errors, description.as_deref());
+        errors.into_result()?;
+        if FeatureFlagRepository::key_exists(pool, &key).await? {
+            return Err(ServiceError::InvalidOperation(format!(
+                "Feature flag '{}' already exists",
+                key
+            )));
+        }
+        tracing::info!("Creating feature flag '{}' (enabled: {})", key, request.enabled);
+        let id = FeatureFlagRepository::create(pool, &key, description.as_deref(), request.enabled)
+            .await?;
+        FeatureFlags::reload(pool).await?;
+        Ok(id)
+    }
+
+    pub async fn update_flag(
+        pool: &SqlitePool,
+        id: i64,
+        request: UpdateFeatureFlagPayload,
+    ) -> Result<(), ServiceError> {
+        let description = normalize_description(request.description);
+        let mut errors = FieldErrors::new();
+        check_description(&mut errors, description.as_deref());
+        errors.into_result()?;
+        tracing::info!("Updating feature flag {} (enabled: {})", id, request.enabled);
+        if !FeatureFlagRepository::update(pool, id, description.as_deref(), request.enabled).await?
+        {
+            return Err(ServiceError::NotFound("Feature flag".to_string()));
+        }
+        FeatureFlags::reload(pool).await
+    }
+
+    pub async fn delete_flag(pool: &SqlitePool, id: i64) -> Result<(), ServiceError> {
+        tracing::info!("Deleting feature flag {}", id);
+        if !FeatureFlagRepository::delete(pool, id).await? {
+            return Err(ServiceError::NotFound("Feature flag".to_string()));
+        }
+        FeatureFlags::reload(pool).await
+    }
+}
+
+/// Keys are lowercase snake case, optionally dotted (`reports.pdf_export`).
+fn validate_key(key: &str) -> Result<(), String> {
+    if key.is_empty() || key.len() > FLAG_KEY_MAX_LEN {
+        return Err(format!("must be 1-{} characters", FLAG_KEY_MAX_LEN));
+    }
+    let valid = key.starts_with(|c: char| c.is_ascii_lowercase())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '.');
+    if valid {
+        Ok(())
+    } else {
+        Err("must start with a letter and use only a-z, 0-9, '_' and '.'".to_string())
+    }
+}
+
+fn normalize_description(description: Option<String>) -> Option<String> {
+    description.map(|d| d.trim().to_string()).filter(|d| !d.is_empty())
+}
+
+fn check_description(errors: &mut FieldErrors, description: Option<&str>) {
+    if description.is_some_and(|d| d.chars().count() > FLAG_DESCRIPTION_MAX_LEN) {
+        errors.push(
+            "description",
+            format!("must be at most {} characters", FLAG_DESCRIPTION_MAX_LEN),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{evaluate, validate_key};
+
+    use std::collections::HashMap;
+
+    #[test]
+    fn env_overrides_win_and_unknown_flags_are_off() {
+        let overrides = HashMap::from([("new_dashboard".to_string(), false)]);
+        let stored = HashMap::from([
+            ("new_dashboard".to_string(), true),
+            ("reports.pdf_export".to_string(), true),
+        ]);
+        assert!(!evaluate(&overrides, &stored, "new_dashboard"));
+        assert!(evaluate(&overrides, &stored, "reports.pdf_export"));
+        assert!(!evaluate(&overrides, &stored, "never_created"));
+    }
+
+    #[test]
+    fn keys_are_lowercase_identifiers() {
+        assert!(validate_key("new_dashboard").is_ok());
+        assert!(validate_key("reports.pdf_export").is_ok());
+        assert!(validate_key("").is_err());
+        assert!(validate_key("New-Dashboard").is_err());
+        assert!(validate_key("9lives").is_err());
+    }
+}
@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Feature flag row as read from the database.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct FeatureFlagRow {
+    pub id: i64,
+    pub key: String,
+    pub description: Option<String>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Feature flag for list display.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureFlagItemResp {
+    pub id: i64,
+    pub key: String,
+    pub description: Option<String>,
+    /// Stored value.
+    pub enabled: bool,
+    /// Value forced by `RUSTZEN_FEATURE_FLAGS`, if any.
+    pub env_override: Option<bool>,
+    /// What `FeatureFlags::is_enabled` returns.
+    pub effective: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create feature flag request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateFeatureFlagRequest {
+    pub key: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// Update feature flag request; the key is fixed once code checks it.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateFeatureFlagPayload {
+    pub description: Option<String>,
+    pub enabled: bool,
+}
+
+/// Feature flag query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureFlagQuery {
+    pub current: Option<i64>,
+    pub page_size: Option<i64>,
+    pub key: Option<String>,
+}
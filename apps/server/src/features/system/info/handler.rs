@@ -0,0 +1,18 @@
+use super::{
+    service::SystemInfoService,
+    types::{DbStatsResp, SystemInfoResp},
+};
+use crate::common::api::{ApiResponse, AppResult};
+
+use axum::extract::State;
+use sqlx::SqlitePool;
+
+/// Build, runtime and database overview for operators.
+pub async fn get_system_info(State(pool): State<SqlitePool>) -> AppResult<SystemInfoResp> {
+    Ok(ApiResponse::success(SystemInfoService::get_info(&pool).await?))
+}
+
+/// Size, rows and unused space of each table, for deciding when to purge or vacuum.
+pub async fn get_db_stats(State(pool): State<SqlitePool>) -> AppResult<DbStatsResp> {
+    Ok(ApiResponse::success(SystemInfoService::db_stats(&pool).await?))
+}
@@ -0,0 +1,28 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{Router, routing::get};
+use handler::{get_db_stats, get_system_info};
+use rustzen_core::{
+    capability::system_info,
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+pub fn info_routes() -> Router<SqlitePool> {
+    Router::new().route_with_permission(
+        "/",
+        get(get_system_info),
+        PermissionsCheck::Require(system_info::VIEW),
+    )
+}
+
+pub fn db_routes() -> Router<SqlitePool> {
+    Router::new().route_with_permission(
+        "/stats",
+        get(get_db_stats),
+        PermissionsCheck::Require(system_info::VIEW),
+    )
+}
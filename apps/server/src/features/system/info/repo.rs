@@ -0,0 +1,59 @@
+use super::types::DbTableStatsResp;
+use crate::common::error::ServiceError;
+
+use sqlx::SqlitePool;
+
+pub struct SystemInfoRepository;
+
+impl SystemInfoRepository {
+    /// SQLite page count and page size for the main database.
+    pub async fn database_pages(pool: &SqlitePool) -> Result<(i64, i64), ServiceError> {
+        sqlx::query_as::<_, (i64, i64)>(
+            "SELECT page_count, page_size FROM pragma_page_count(), pragma_page_size()",
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error reading SQLite page stats: {:?}", e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
+    /// Free list length and `auto_vacuum` mode (0 none, 1 full, 2 incremental).
+    pub async fn free_pages(pool: &SqlitePool) -> Result<(i64, i64), ServiceError> {
+        sqlx::query_as::<_, (i64, i64)>(
+            "SELECT freelist_count, auto_vacuum FROM pragma_freelist_count(), pragma_auto_vacuum()",
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error reading SQLite free list: {:?}", e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
+    /// Storage per application table from the `dbstat` virtual table, largest first.
+    ///
+    /// This reads every page of the database, so it takes about as long as a full scan.
+    pub async fn table_stats(pool: &SqlitePool) -> Result<Vec<DbTableStatsResp>, ServiceError> {
+        sqlx::query_as::<_, DbTableStatsResp>(
+            "SELECT m.tbl_name AS name,
+                    SUM(CASE WHEN m.type = 'table' AND d.pagetype = 'leaf' THEN d.ncell ELSE 0 END)
+                        AS row_count,
+                    SUM(CASE WHEN m.type = 'table' THEN d.pgsize ELSE 0 END) AS table_bytes,
+                    SUM(CASE WHEN m.type = 'index' THEN d.pgsize ELSE 0 END) AS index_bytes,
+                    SUM(d.unused) AS unused_bytes
+             FROM dbstat d
+             JOIN sqlite_schema m ON m.name = d.name
+             WHERE substr(m.tbl_name, 1, 7) <> 'sqlite_' AND substr(m.tbl_name, 1, 6) <> '_sqlx_'
+             GROUP BY m.tbl_name
+             ORDER BY table_bytes + index_bytes DESC, m.tbl_name",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error reading SQLite table stats: {:?}", e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+}
@@ -0,0 +1,128 @@
+use super::{
+    repo::SystemInfoRepository,
+    types::{BuildInfoResp, CacheStatsResp, DatabaseStatsResp, DbStatsResp, SystemInfoResp},
+};
+use crate::{
+    common::error::ServiceError,
+    features::dashboard::service::DashboardService,
+    infra::{permission::PermissionService, system_info::SystemUtils},
+};
+
+use chrono::DateTime;
+use sqlx::SqlitePool;
+
+pub struct SystemInfoService;
+
+impl SystemInfoService {
+    pub async fn get_info(pool: &SqlitePool) -> Result<SystemInfoResp, ServiceError> {
+        let (page_count, page_size) = SystemInfoRepository::database_pages(pool).await?;
+
+        Ok(SystemInfoResp {
+            build: Self::build_info(),
+            uptime_secs: SystemUtils::uptime().as_secs(),
+            database: DatabaseStatsResp {
+                pool_size: pool.size(),
+                pool_idle: pool.num_idle(),
+                max_connections: pool.options().get_max_connections(),
+                page_count,
+                page_size,
+                size_bytes: page_count * page_size,
+            },
+            caches: CacheStatsResp {
+                permission_users: PermissionService::cached_user_count(),
+                dashboard_entries: DashboardService::cached_entry_count(),
+            },
+        })
+    }
+
+    /// Per-table rows, sizes and unused space plus the database free list, so operators can
+    /// see when `operation_logs` needs a purge or the file a `VACUUM`.
+    pub async fn db_stats(pool: &SqlitePool) -> Result<DbStatsResp, ServiceError> {
+        let (page_count, page_size) = SystemInfoRepository::database_pages(pool).await?;
+        let (free_pages, auto_vacuum) = SystemInfoRepository::free_pages(pool).await?;
+        let mut tables = SystemInfoRepository::table_stats(pool).await?;
+        for table in &mut tables {
+            table.unused_ratio = ratio(table.unused_bytes, table.table_bytes + table.index_bytes);
+        }
+        Ok(DbStatsResp {
+            page_size,
+            page_count,
+            size_bytes: page_count * page_size,
+            free_pages,
+            free_ratio: ratio(free_pages, page_count),
+            auto_vacuum: match auto_vacuum {
+                1 => "full",
+                2 => "incremental",
+                _ => "none",
+            },
+            tables,
+        })
+    }
+
+    /// Values stamped in by `build.rs`.
+    pub fn build_info() -> BuildInfoResp {
+        let built_at = env!("RUSTZEN_BUILD_TIMESTAMP")
+            .parse::<i64>()
+            .ok()
+            .filter(|secs| *secs > 0)
+            .and_then(|secs| DateTime::from_timestamp(secs, 0))
+            .map(|time| time.to_rfc3339());
+
+        BuildInfoResp {
+            version: env!("CARGO_PKG_VERSION"),
+            commit: env!("RUSTZEN_BUILD_COMMIT"),
+            built_at,
+            rust_version: env!("RUSTZEN_RUSTC_VERSION"),
+        }
+    }
+}
+
+fn ratio(part: i64, whole: i64) -> f64 {
+    if whole > 0 { part as f64 / whole as f64 } else { 0.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SystemInfoService;
+
+    #[tokio::test]
+    async fn info_reports_build_and_database_stats() {
+        let pool = crate::infra::db::memory_pool().await;
+
+        let info = SystemInfoService::get_info(&pool).await.expect("system info");
+
+        assert_eq!(info.build.version, env!("CARGO_PKG_VERSION"));
+        assert!(!info.build.commit.is_empty());
+        assert!(info.build.built_at.is_some());
+        assert!(info.database.page_size > 0);
+        assert_eq!(info.database.max_connections, 1);
+    }
+
+    #[tokio::test]
+    async fn db_stats_report_rows_sizes_and_free_pages_per_table() {
+        let pool = crate::infra::db::memory_pool().await;
+        for sql in [
+            "CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT NOT NULL UNIQUE)",
+            "CREATE TABLE empty_notes (id INTEGER PRIMARY KEY)",
+            "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 2000)
+             INSERT INTO notes (body) SELECT printf('note %05d %s', i, hex(randomblob(64))) FROM n",
+            "DELETE FROM notes WHERE id > 500",
+        ] {
+            sqlx::query(sql).execute(&pool).await.expect(sql);
+        }
+
+        let stats = SystemInfoService::db_stats(&pool).await.expect("db stats");
+
+        assert_eq!(stats.size_bytes, stats.page_count * stats.page_size);
+        assert_eq!(stats.auto_vacuum, "none");
+        assert!(stats.free_pages > 0, "{:?}", stats);
+        assert!(stats.free_ratio > 0.0 && stats.free_ratio < 1.0);
+        let names: Vec<&str> = stats.tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["notes", "empty_notes"]);
+        let notes = &stats.tables[0];
+        assert_eq!(notes.row_count, 500);
+        assert!(notes.index_bytes > 0 && notes.table_bytes > 0, "{:?}", notes);
+        assert!(notes.unused_ratio >= 0.0 && notes.unused_ratio < 1.0);
+        assert_eq!(stats.tables[1].row_count, 0);
+    }
+}
@@ -0,0 +1,73 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildInfoResp {
+    pub version: &'static str,
+    pub commit: &'static str,
+    /// RFC 3339 build time, or `None` when the build did not record one.
+    pub built_at: Option<String>,
+    pub rust_version: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseStatsResp {
+    pub pool_size: u32,
+    pub pool_idle: usize,
+    pub max_connections: u32,
+    pub page_count: i64,
+    pub page_size: i64,
+    pub size_bytes: i64,
+}
+
+/// Storage of one table and its indexes.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct DbTableStatsResp {
+    pub name: String,
+    pub row_count: i64,
+    pub table_bytes: i64,
+    pub index_bytes: i64,
+    /// Bytes inside the table's and indexes' pages that hold no data, left by deletes and
+    /// page splits; `VACUUM` reclaims them.
+    pub unused_bytes: i64,
+    /// `unusedBytes` over table plus index bytes, 0 to 1.
+    #[sqlx(skip)]
+    pub unused_ratio: f64,
+}
+
+/// Database file usage and per-table storage.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbStatsResp {
+    pub page_size: i64,
+    pub page_count: i64,
+    pub size_bytes: i64,
+    /// Pages emptied by deletes that the file keeps until a `VACUUM`.
+    pub free_pages: i64,
+    /// `freePages` over `pageCount`, 0 to 1.
+    pub free_ratio: f64,
+    /// `none`, `full` or `incremental`.
+    pub auto_vacuum: &'static str,
+    /// Largest first.
+    pub tables: Vec<DbTableStatsResp>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStatsResp {
+    /// Users with cached capability sets.
+    pub permission_users: usize,
+    /// Cached dashboard aggregate results.
+    pub dashboard_entries: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemInfoResp {
+    pub build: BuildInfoResp,
+    pub uptime_secs: u64,
+    pub database: DatabaseStatsResp,
+    pub caches: CacheStatsResp,
+}
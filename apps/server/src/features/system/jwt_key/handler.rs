@@ -0,0 +1,24 @@
+use super::{service::JwtKeyService, types::JwtKeyResp};
+use crate::{
+    common::api::{ApiResponse, AppResult},
+    infra::config::AppConfig,
+};
+
+use axum::{Extension, extract::State};
+use sqlx::SqlitePool;
+
+/// List the keys that currently sign or verify access tokens
+pub async fn list_jwt_keys(
+    Extension(config): Extension<&'static AppConfig>,
+    State(pool): State<SqlitePool>,
+) -> AppResult<Vec<JwtKeyResp>> {
+    Ok(ApiResponse::success(JwtKeyService::list_keys(config, &pool).await?))
+}
+
+/// Switch signing to a fresh key; returns its `kid`
+pub async fn rotate_jwt_key(
+    Extension(config): Extension<&'static AppConfig>,
+    State(pool): State<SqlitePool>,
+) -> AppResult<String> {
+    Ok(ApiResponse::success(JwtKeyService::rotate(config, &pool).await?))
+}
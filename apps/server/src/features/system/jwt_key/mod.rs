@@ -0,0 +1,25 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use handler::{list_jwt_keys, rotate_jwt_key};
+use rustzen_core::{
+    capability::system_jwt,
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+pub fn jwt_key_routes() -> Router<SqlitePool> {
+    Router::new()
+        .route_with_permission("/", get(list_jwt_keys), PermissionsCheck::Require(system_jwt::LIST))
+        .route_with_permission(
+            "/rotate",
+            post(rotate_jwt_key),
+            PermissionsCheck::Require(system_jwt::ROTATE),
+        )
+}
@@ -0,0 +1,47 @@
+use super::types::JwtSigningKeyRow;
+use crate::common::error::ServiceError;
+
+use chrono::NaiveDateTime;
+use sqlx::SqlitePool;
+
+pub struct JwtKeyRepository;
+
+fn db_error(context: &str, e: sqlx::Error) -> ServiceError {
+    tracing::error!("Database error {}: {:?}", context, e);
+    ServiceError::DatabaseQueryFailed
+}
+
+impl JwtKeyRepository {
+    /// Every rotated key, newest first.
+    pub async fn list_keys(pool: &SqlitePool) -> Result<Vec<JwtSigningKeyRow>, ServiceError> {
+        sqlx::query_as::<_, JwtSigningKeyRow>(
+            "SELECT kid, secret, created_at, retired_at FROM jwt_signing_keys ORDER BY created_at DESC, rowid DESC",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("listing jwt signing keys", e))
+    }
+
+    /// Retires the signing key and stores `kid` as the new one, atomically.
+    pub async fn insert_current(
+        pool: &SqlitePool,
+        kid: &str,
+        secret: &str,
+        now: NaiveDateTime,
+    ) -> Result<(), ServiceError> {
+        let mut tx = pool.begin().await.map_err(|e| db_error("starting key rotation", e))?;
+        sqlx::query("UPDATE jwt_signing_keys SET retired_at = ? WHERE retired_at IS NULL")
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| db_error("retiring jwt signing key", e))?;
+        sqlx::query("INSERT INTO jwt_signing_keys (kid, secret, created_at) VALUES (?, ?, ?)")
+            .bind(kid)
+            .bind(secret)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| db_error("inserting jwt signing key", e))?;
+        tx.commit().await.map_err(|e| db_error("committing key rotation", e))
+    }
+}
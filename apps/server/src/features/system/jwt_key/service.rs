@@ -0,0 +1,237 @@
+//! JWT signing key rotation.
+//!
+//! The live keyring is assembled from `RUSTZEN_JWT_*` settings plus keys created by
+//! [`JwtKeyService::rotate`]. A rotated key signs until the next rotation and then keeps
+//! verifying for one token lifetime, so sessions move to the new key as they re-login
+//! instead of all at once. That deadline is checked when a token is decoded, not only
+//! when the ring is built. Each instance reloads the ring after it rotates and every
+//! 30 seconds, which is how other instances pick up a rotation.
+
+use super::{
+    repo::JwtKeyRepository,
+    types::{JwtKeyResp, JwtKeySource, JwtSigningKeyRow},
+};
+use crate::{
+    common::error::ServiceError,
+    infra::{
+        auth_runtime::{hmac_kid, jwt_codec, rsa_kid},
+        config::AppConfig,
+    },
+};
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use rustzen_core::auth::{JwtKey, JwtKeyring};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+const REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Keys defined by configuration rather than by rotation.
+struct ConfigKeys {
+    rsa: Option<JwtKey>,
+    secret: String,
+    previous_secrets: Vec<String>,
+}
+
+impl ConfigKeys {
+    fn load(config: &AppConfig) -> Result<Self, ServiceError> {
+        let rsa = match config.jwt_rsa_key_paths() {
+            Some((private_path, public_path)) => {
+                let read = |path: &std::path::Path| {
+                    std::fs::read(path).map_err(|e| {
+                        ServiceError::InvalidOperation(format!(
+                            "Cannot read JWT RSA key {}: {}",
+                            path.display(),
+                            e
+                        ))
+                    })
+                };
+                let private_pem = read(&private_path)?;
+                let public_pem = read(&public_path)?;
+                let key = JwtKey::rsa_pem(rsa_kid(&public_pem), Some(&private_pem), &public_pem)
+                    .map_err(|e| {
+                        ServiceError::InvalidOperation(format!("Invalid JWT RSA key: {}", e))
+                    })?;
+                Some(key)
+            }
+            None => None,
+        };
+        Ok(Self {
+            rsa,
+            secret: config.jwt.jwt_secret.clone(),
+            previous_secrets: config.jwt_previous_secret_list(),
+        })
+    }
+}
+
+pub struct JwtKeyService;
+
+impl JwtKeyService {
+    /// Rebuilds the shared codec's keyring from configuration and stored keys.
+    pub async fn reload(config: &AppConfig, pool: &SqlitePool) -> Result<(), ServiceError> {
+        let entries = Self::entries(config, pool).await?;
+        let mut keys = entries.into_iter().map(|(key, _)| key);
+        let current = keys.next().expect("keyring always has a configured key");
+        let keyring = keys.fold(JwtKeyring::new(current), JwtKeyring::with_previous);
+        let codec = jwt_codec(config);
+        if codec.keyring().current().kid() != keyring.current().kid() {
+            tracing::info!(
+                current = keyring.current().kid(),
+                keys = keyring.keys().count(),
+                "Loaded JWT keyring"
+            );
+        }
+        codec.set_keyring(keyring);
+        Ok(())
+    }
+
+    /// Loads the keyring, then keeps reloading it in the background.
+    pub async fn start(config: &'static AppConfig, pool: SqlitePool) -> Result<(), ServiceError> {
+        Self::reload(config, &pool).await?;
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(REFRESH_INTERVAL).await;
+                if let Err(e) = Self::reload(config, &pool).await {
+                    tracing::error!("JWT keyring reload failed: {:?}", e);
+                }
+            }
+        });
+        Ok(())
+    }
+
+    pub async fn list_keys(
+        config: &AppConfig,
+        pool: &SqlitePool,
+    ) -> Result<Vec<JwtKeyResp>, ServiceError> {
+        Ok(Self::entries(config, pool).await?.into_iter().map(|(_, resp)| resp).collect())
+    }
+
+    /// Creates a new HMAC signing key, retires the current one and reloads the keyring.
+    pub async fn rotate(config: &AppConfig, pool: &SqlitePool) -> Result<String, ServiceError> {
+        if config.jwt_rsa_key_paths().is_some() {
+            return Err(ServiceError::InvalidOperation(
+                "Tokens are signed with the configured RS256 key; rotate it by replacing the key files"
+                    .to_string(),
+            ));
+        }
+        let secret = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        let kid = hmac_kid(&secret);
+        JwtKeyRepository::insert_current(pool, &kid, &secret, Utc::now().naive_utc()).await?;
+        tracing::warn!(kid = %kid, "Rotated JWT signing key");
+        Self::reload(config, pool).await?;
+        Ok(kid)
+    }
+
+    async fn entries(
+        config: &AppConfig,
+        pool: &SqlitePool,
+    ) -> Result<Vec<(JwtKey, JwtKeyResp)>, ServiceError> {
+        let keys = ConfigKeys::load(config)?;
+        let rows = JwtKeyRepository::list_keys(pool).await?;
+        Ok(assemble(
+            keys,
+            &rows,
+            Utc::now().naive_utc(),
+            Duration::seconds(config.jwt.jwt_expiration),
+        ))
+    }
+}
+
+/// Orders the keyring, current key first, drops keys whose tokens have all expired and
+/// stops the remaining retired keys verifying once theirs do.
+///
+/// The configured RS256 key always signs when present. Otherwise the newest rotated key
+/// signs, and `RUSTZEN_JWT_SECRET` counts as retired from the first rotation on.
+fn assemble(
+    config: ConfigKeys,
+    rows: &[JwtSigningKeyRow],
+    now: NaiveDateTime,
+    token_lifetime: Duration,
+) -> Vec<(JwtKey, JwtKeyResp)> {
+    let verifies = |retired_at: Option<NaiveDateTime>| {
+        retired_at.is_none_or(|retired_at| retired_at + token_lifetime > now)
+    };
+    let retire = |key: JwtKey, retired_at: Option<NaiveDateTime>| match retired_at {
+        Some(retired_at) => key.verifying_until((retired_at + token_lifetime).and_utc()),
+        None => key,
+    };
+    let mut entries = Vec::new();
+    let mut push = |key: JwtKey, source, created_at, retired_at| {
+        let resp = JwtKeyResp {
+            kid: key.kid().to_string(),
+            algorithm: format!("{:?}", key.algorithm()),
+            source,
+            current: entries.is_empty(),
+            created_at,
+            retired_at,
+        };
+        entries.push((key, resp));
+    };
+
+    if let Some(rsa) = config.rsa {
+        push(rsa, JwtKeySource::Config, None, None);
+    }
+    for row in rows.iter().filter(|row| verifies(row.retired_at)) {
+        let key = retire(JwtKey::hmac(&row.kid, row.secret.as_bytes()), row.retired_at);
+        let retired_at = row.retired_at.map(|at| at.and_utc());
+        push(key, JwtKeySource::Rotated, Some(row.created_at.and_utc()), retired_at);
+    }
+    let secret_retired_at = rows.iter().map(|row| row.created_at).min();
+    if verifies(secret_retired_at) {
+        let key = JwtKey::hmac(hmac_kid(&config.secret), config.secret.as_bytes());
+        let key = retire(key, secret_retired_at);
+        push(key, JwtKeySource::Config, None, secret_retired_at.map(|at| at.and_utc()));
+    }
+    for secret in &config.previous_secrets {
+        push(JwtKey::hmac(hmac_kid(secret), secret.as_bytes()), JwtKeySource::Config, None, None);
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConfigKeys, JwtSigningKeyRow, assemble};
+    use crate::infra::auth_runtime::hmac_kid;
+
+    use chrono::{Duration, NaiveDate, NaiveDateTime};
+
+    fn at(hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 1, 1).unwrap().and_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    fn row(kid: &str, created: u32, retired: Option<u32>) -> JwtSigningKeyRow {
+        JwtSigningKeyRow {
+            kid: kid.to_string(),
+            secret: format!("{}-secret", kid),
+            created_at: at(created),
+            retired_at: retired.map(at),
+        }
+    }
+
+    fn config() -> ConfigKeys {
+        ConfigKeys { rsa: None, secret: "env-secret".to_string(), previous_secrets: Vec::new() }
+    }
+
+    fn kids(entries: &[(rustzen_core::auth::JwtKey, super::JwtKeyResp)]) -> Vec<String> {
+        entries.iter().map(|(key, _)| key.kid().to_string()).collect()
+    }
+
+    #[test]
+    fn newest_rotated_key_signs_and_retired_keys_verify_for_one_token_lifetime() {
+        let env_kid = hmac_kid("env-secret");
+        assert_eq!(
+            kids(&assemble(config(), &[], at(1), Duration::hours(2))),
+            vec![env_kid.clone()]
+        );
+
+        let rows = [row("k2", 3, None), row("k1", 1, Some(3))];
+        let entries = assemble(config(), &rows, at(4), Duration::hours(4));
+        assert_eq!(kids(&entries), vec!["k2".to_string(), "k1".to_string(), env_kid]);
+        assert!(entries[0].1.current && !entries[1].1.current);
+
+        let entries = assemble(config(), &rows, at(6), Duration::hours(4));
+        assert_eq!(kids(&entries), vec!["k2".to_string(), "k1".to_string()]);
+        let entries = assemble(config(), &rows, at(8), Duration::hours(4));
+        assert_eq!(kids(&entries), vec!["k2".to_string()]);
+    }
+}
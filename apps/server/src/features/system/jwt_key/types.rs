@@ -0,0 +1,34 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Serialize;
+
+/// Rotated signing key as stored; `secret` never leaves the server.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct JwtSigningKeyRow {
+    pub kid: String,
+    pub secret: String,
+    pub created_at: NaiveDateTime,
+    pub retired_at: Option<NaiveDateTime>,
+}
+
+/// Where a keyring entry is defined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JwtKeySource {
+    /// `RUSTZEN_JWT_*` environment settings.
+    Config,
+    /// Created by the rotate endpoint.
+    Rotated,
+}
+
+/// One key of the live keyring.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JwtKeyResp {
+    pub kid: String,
+    pub algorithm: String,
+    pub source: JwtKeySource,
+    /// Signs new tokens; every other key only verifies.
+    pub current: bool,
+    pub created_at: Option<DateTime<Utc>>,
+    pub retired_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,7 @@
+use super::{service::LicenseService, types::LicenseResp};
+use crate::common::api::{ApiResponse, AppResult};
+
+/// Installed license, edition and licensed features.
+pub async fn get_license() -> AppResult<LicenseResp> {
+    Ok(ApiResponse::success(LicenseService::current()))
+}
@@ -0,0 +1,19 @@
+pub mod handler;
+pub mod service;
+pub mod types;
+
+use axum::{Router, routing::get};
+use handler::get_license;
+use rustzen_core::{
+    capability::system_license,
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+pub fn license_routes() -> Router<SqlitePool> {
+    Router::new().route_with_permission(
+        "/",
+        get(get_license),
+        PermissionsCheck::Require(system_license::VIEW),
+    )
+}
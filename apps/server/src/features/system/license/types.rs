@@ -0,0 +1,77 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Feature that only a license can switch on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LicensedFeature {
+    MultiTenant,
+    Ldap,
+}
+
+impl LicensedFeature {
+    pub const ALL: [LicensedFeature; 2] = [LicensedFeature::MultiTenant, LicensedFeature::Ldap];
+
+    /// Code listed in the license `features` claim.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LicensedFeature::MultiTenant => "multi_tenant",
+            LicensedFeature::Ldap => "ldap",
+        }
+    }
+}
+
+/// Claims of a license file: a JWT signed with the vendor's Ed25519 key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LicenseClaims {
+    /// Licensee name.
+    pub sub: String,
+    pub edition: String,
+    #[serde(default)]
+    pub features: Vec<String>,
+    #[serde(default)]
+    pub iat: Option<i64>,
+    /// Unix seconds; a license without it never expires.
+    #[serde(default)]
+    pub exp: Option<i64>,
+}
+
+/// License file as read at startup.
+#[derive(Debug, Clone)]
+pub enum LicenseState {
+    /// No license configured.
+    Missing,
+    Loaded(LicenseClaims),
+    /// Unreadable file, wrong key or bad signature.
+    Invalid(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LicenseStatus {
+    /// No license configured.
+    Community,
+    Valid,
+    Expired,
+    Invalid,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseFeatureResp {
+    pub code: &'static str,
+    pub enabled: bool,
+}
+
+/// Installed license as shown to operators; `features` lists every licensable feature.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseResp {
+    pub status: LicenseStatus,
+    pub edition: String,
+    pub licensee: Option<String>,
+    pub issued_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub features: Vec<LicenseFeatureResp>,
+    /// Why the license file was rejected.
+    pub error: Option<String>,
+}
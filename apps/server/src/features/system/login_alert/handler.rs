@@ -0,0 +1,30 @@
+use super::{
+    service::LoginAlertService,
+    types::{LoginAlertRuleResp, UpdateLoginAlertRuleRequest},
+};
+use crate::{
+    common::api::{ApiResponse, AppResult},
+    infra::db::DbExecutor,
+};
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use sqlx::SqlitePool;
+
+/// List the suspicious login rules and whether each is on
+pub async fn list_login_alert_rules(
+    State(db): State<DbExecutor>,
+) -> AppResult<Vec<LoginAlertRuleResp>> {
+    Ok(ApiResponse::success(LoginAlertService::list_rules(db.read()).await?))
+}
+
+/// Switch a suspicious login rule on or off
+pub async fn update_login_alert_rule(
+    State(pool): State<SqlitePool>,
+    Path(code): Path<String>,
+    Json(request): Json<UpdateLoginAlertRuleRequest>,
+) -> AppResult<Vec<LoginAlertRuleResp>> {
+    Ok(ApiResponse::success(LoginAlertService::update_rule(&pool, &code, request).await?))
+}
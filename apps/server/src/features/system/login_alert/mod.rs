@@ -0,0 +1,29 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{
+    Router,
+    routing::{get, put},
+};
+use handler::{list_login_alert_rules, update_login_alert_rule};
+use rustzen_core::{
+    capability::system_login_alert,
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+pub fn login_alert_routes() -> Router<SqlitePool> {
+    Router::new()
+        .route_with_permission(
+            "/rules",
+            get(list_login_alert_rules),
+            PermissionsCheck::Require(system_login_alert::LIST),
+        )
+        .route_with_permission(
+            "/rules/{code}",
+            put(update_login_alert_rule),
+            PermissionsCheck::Require(system_login_alert::UPDATE),
+        )
+}
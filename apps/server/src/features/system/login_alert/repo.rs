@@ -0,0 +1,120 @@
+use super::types::{AlertRecipientRow, LoginAlertRuleRow, LoginSourceRow};
+use crate::{common::error::ServiceError, infra::geoip::GeoLocation};
+
+use chrono::{NaiveDateTime, Utc};
+use rustzen_core::capability::{SYSTEM_WILDCARD, system_login_alert};
+use sqlx::SqlitePool;
+
+pub struct LoginAlertRepository;
+
+fn db_error(context: &str, e: sqlx::Error) -> ServiceError {
+    tracing::error!("Database error {}: {:?}", context, e);
+    ServiceError::DatabaseQueryFailed
+}
+
+impl LoginAlertRepository {
+    pub async fn list_rules(pool: &SqlitePool) -> Result<Vec<LoginAlertRuleRow>, ServiceError> {
+        sqlx::query_as::<_, LoginAlertRuleRow>(
+            "SELECT code, enabled, updated_at FROM login_alert_rules ORDER BY rowid",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("listing login alert rules", e))
+    }
+
+    pub async fn set_enabled(
+        pool: &SqlitePool,
+        code: &str,
+        enabled: bool,
+    ) -> Result<bool, ServiceError> {
+        let result =
+            sqlx::query("UPDATE login_alert_rules SET enabled = ?, updated_at = ? WHERE code = ?")
+                .bind(enabled)
+                .bind(Utc::now().naive_utc())
+                .bind(code)
+                .execute(pool)
+                .await
+                .map_err(|e| db_error("updating login alert rule", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The user's most recently used login source.
+    pub async fn latest_source(
+        pool: &SqlitePool,
+        user_id: i64,
+    ) -> Result<Option<LoginSourceRow>, ServiceError> {
+        sqlx::query_as::<_, LoginSourceRow>(
+            "SELECT latitude, longitude, last_seen_at FROM user_login_sources
+             WHERE user_id = ?
+             ORDER BY last_seen_at DESC, id DESC
+             LIMIT 1",
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_error("loading latest login source", e))
+    }
+
+    /// Stores a login from `ip_address` with `user_agent` and returns whether the user had
+    /// signed in from that pair before.
+    pub async fn record_source(
+        pool: &SqlitePool,
+        user_id: i64,
+        ip_address: &str,
+        user_agent: &str,
+        location: &GeoLocation,
+        at: NaiveDateTime,
+    ) -> Result<bool, ServiceError> {
+        let known: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM user_login_sources
+                           WHERE user_id = ? AND ip_address = ? AND user_agent = ?)",
+        )
+        .bind(user_id)
+        .bind(ip_address)
+        .bind(user_agent)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_error("checking login source", e))?;
+        sqlx::query(
+            "INSERT INTO user_login_sources (
+                 user_id, ip_address, user_agent, latitude, longitude, first_seen_at, last_seen_at
+             ) VALUES (?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT (user_id, ip_address, user_agent) DO UPDATE SET
+                 latitude = COALESCE(excluded.latitude, latitude),
+                 longitude = COALESCE(excluded.longitude, longitude),
+                 last_seen_at = excluded.last_seen_at",
+        )
+        .bind(user_id)
+        .bind(ip_address)
+        .bind(user_agent)
+        .bind(location.latitude)
+        .bind(location.longitude)
+        .bind(at)
+        .bind(at)
+        .execute(pool)
+        .await
+        .map_err(|e| db_error("recording login source", e))?;
+        Ok(known)
+    }
+
+    /// The user plus every active user who may list the login alert rules.
+    pub async fn alert_recipients(
+        pool: &SqlitePool,
+        user_id: i64,
+    ) -> Result<Vec<AlertRecipientRow>, ServiceError> {
+        sqlx::query_as::<_, AlertRecipientRow>(
+            "SELECT id, email FROM users
+             WHERE deleted_at IS NULL AND status = 1
+               AND (id = ? OR id IN (
+                   SELECT user_id FROM user_permissions WHERE menu_code IN (?, ?)
+               ))
+             ORDER BY id",
+        )
+        .bind(user_id)
+        .bind(system_login_alert::LIST)
+        .bind(SYSTEM_WILDCARD)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("loading login alert recipients", e))
+    }
+}
@@ -0,0 +1,338 @@
+use super::{
+    repo::LoginAlertRepository,
+    types::{LoginAlertRule, LoginAlertRuleResp, LoginSourceRow, UpdateLoginAlertRuleRequest},
+};
+use crate::{
+    common::{error::ServiceError, tx},
+    features::account::repo::AccountRepository,
+    infra::{events, geoip, mail},
+};
+
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use once_cell::sync::Lazy;
+use rustzen_core::events::{DomainEvent, EventSubscriber};
+use sqlx::SqlitePool;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Failed attempts on one account within `FAILURE_WINDOW` that make its next success suspect.
+const FAILURE_THRESHOLD: usize = 5;
+const FAILURE_WINDOW: Duration = Duration::from_secs(15 * 60);
+/// Accounts tracked at once; older failures are pruned past this.
+const MAX_TRACKED_ACCOUNTS: usize = 10_000;
+/// Logins closer than this are never impossible travel; GeoIP is city-level at best.
+const MIN_TRAVEL_KM: f64 = 500.0;
+/// Faster than an airliner, door to door.
+const MAX_TRAVEL_KMH: f64 = 1000.0;
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+const NOTIFICATION_KIND: &str = "login_alert";
+
+static FAILED_LOGINS: Lazy<FailedLogins> = Lazy::new(FailedLogins::default);
+
+/// Recent failed attempts per account name, in memory and per instance.
+#[derive(Default)]
+struct FailedLogins {
+    attempts: Mutex<HashMap<String, VecDeque<Instant>>>,
+}
+
+impl FailedLogins {
+    fn record(&self, username: &str, now: Instant) {
+        let mut attempts = self.attempts.lock().unwrap_or_else(|e| e.into_inner());
+        if attempts.len() >= MAX_TRACKED_ACCOUNTS {
+            attempts.retain(|_, times| {
+                times.back().is_some_and(|last| now.duration_since(*last) < FAILURE_WINDOW)
+            });
+        }
+        let times = attempts.entry(username.to_lowercase()).or_default();
+        while times.front().is_some_and(|first| now.duration_since(*first) >= FAILURE_WINDOW) {
+            times.pop_front();
+        }
+        times.push_back(now);
+    }
+
+    /// Failures within the window before `now`, clearing the account's count.
+    fn take(&self, username: &str, now: Instant) -> usize {
+        let mut attempts = self.attempts.lock().unwrap_or_else(|e| e.into_inner());
+        attempts
+            .remove(&username.to_lowercase())
+            .map(|times| {
+                times.iter().filter(|at| now.duration_since(**at) < FAILURE_WINDOW).count()
+            })
+            .unwrap_or(0)
+    }
+}
+
+/// What the rules look at for one successful login.
+#[derive(Debug, Default)]
+struct LoginFacts {
+    /// The user had signed in before, from any source.
+    returning: bool,
+    /// The user had signed in from this address and user agent before.
+    known_source: bool,
+    recent_failures: usize,
+    /// Distance in km and elapsed hours since the previous located login.
+    travel: Option<(f64, f64)>,
+}
+
+/// Rules that fire for `facts`, each with a one-line explanation.
+fn triggered(facts: &LoginFacts, enabled: &[LoginAlertRule]) -> Vec<(LoginAlertRule, String)> {
+    enabled
+        .iter()
+        .filter_map(|rule| {
+            let detail = match rule {
+                LoginAlertRule::NewDevice if facts.returning && !facts.known_source => {
+                    "Sign-in from a device or address not seen on this account before".to_string()
+                }
+                LoginAlertRule::ImpossibleTravel => {
+                    let (km, hours) = facts.travel?;
+                    if km < MIN_TRAVEL_KM || km / hours.max(1.0 / 3600.0) <= MAX_TRAVEL_KMH {
+                        return None;
+                    }
+                    format!("Sign-in {:.0} km from the previous one {:.1} hours earlier", km, hours)
+                }
+                LoginAlertRule::FailuresThenSuccess
+                    if facts.recent_failures >= FAILURE_THRESHOLD =>
+                {
+                    format!(
+                        "Sign-in after {} failed attempts in the last {} minutes",
+                        facts.recent_failures,
+                        FAILURE_WINDOW.as_secs() / 60
+                    )
+                }
+                _ => return None,
+            };
+            Some((*rule, detail))
+        })
+        .collect()
+}
+
+/// Great-circle distance in km.
+fn distance_km(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (lat1, lon1) = (from.0.to_radians(), from.1.to_radians());
+    let (lat2, lon2) = (to.0.to_radians(), to.1.to_radians());
+    let a = ((lat2 - lat1) / 2.0).sin().powi(2)
+        + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+/// Distance and hours from the previous login's source to `to` at `at`, when both are located.
+fn travel_since(
+    previous: Option<&LoginSourceRow>,
+    to: Option<(f64, f64)>,
+    at: NaiveDateTime,
+) -> Option<(f64, f64)> {
+    let previous = previous?;
+    let from = (previous.latitude?, previous.longitude?);
+    let hours = (at - previous.last_seen_at).num_seconds().max(0) as f64 / 3600.0;
+    Some((distance_km(from, to?), hours))
+}
+
+/// Suspicious login detection: settings for the rules, and the subscriber that runs them.
+pub struct LoginAlertService;
+
+impl LoginAlertService {
+    pub async fn list_rules(pool: &SqlitePool) -> Result<Vec<LoginAlertRuleResp>, ServiceError> {
+        let rows = LoginAlertRepository::list_rules(pool).await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let rule = LoginAlertRule::parse(&row.code)?;
+                Some(LoginAlertRuleResp {
+                    code: row.code,
+                    description: rule.description().to_string(),
+                    enabled: row.enabled,
+                    updated_at: row.updated_at,
+                })
+            })
+            .collect())
+    }
+
+    pub async fn update_rule(
+        pool: &SqlitePool,
+        code: &str,
+        request: UpdateLoginAlertRuleRequest,
+    ) -> Result<Vec<LoginAlertRuleResp>, ServiceError> {
+        let not_found = || ServiceError::NotFound("Login alert rule".to_string());
+        let rule = LoginAlertRule::parse(code).ok_or_else(not_found)?;
+        if !LoginAlertRepository::set_enabled(pool, rule.code(), request.enabled).await? {
+            return Err(not_found());
+        }
+        tracing::info!(rule = rule.code(), enabled = request.enabled, "Updated login alert rule");
+        Self::list_rules(pool).await
+    }
+
+    async fn enabled_rules(pool: &SqlitePool) -> Result<Vec<LoginAlertRule>, ServiceError> {
+        let rows = LoginAlertRepository::list_rules(pool).await?;
+        Ok(rows
+            .into_iter()
+            .filter(|row| row.enabled)
+            .filter_map(|row| LoginAlertRule::parse(&row.code))
+            .collect())
+    }
+
+    /// Records the login's source and raises an alert for every rule it trips.
+    async fn check_login(
+        pool: &SqlitePool,
+        user_id: i64,
+        username: &str,
+        ip_address: &str,
+        user_agent: &str,
+    ) -> Result<(), ServiceError> {
+        let recent_failures = FAILED_LOGINS.take(username, Instant::now());
+        let now = Utc::now().naive_utc();
+        let location = geoip::lookup(ip_address).unwrap_or_default();
+        let previous = LoginAlertRepository::latest_source(pool, user_id).await?;
+        let known_source = LoginAlertRepository::record_source(
+            pool, user_id, ip_address, user_agent, &location, now,
+        )
+        .await?;
+        let facts = LoginFacts {
+            returning: previous.is_some(),
+            known_source,
+            recent_failures,
+            travel: travel_since(previous.as_ref(), location.latitude.zip(location.longitude), now),
+        };
+        let enabled = Self::enabled_rules(pool).await?;
+        for (rule, detail) in triggered(&facts, &enabled) {
+            Self::raise(pool, user_id, username, ip_address, rule, detail).await?;
+        }
+        Ok(())
+    }
+
+    /// Notifies the account owner and the security admins in-app and by email, and
+    /// records `SuspiciousLogin` for the audit log and webhooks with the notifications.
+    async fn raise(
+        pool: &SqlitePool,
+        user_id: i64,
+        username: &str,
+        ip_address: &str,
+        rule: LoginAlertRule,
+        detail: String,
+    ) -> Result<(), ServiceError> {
+        tracing::warn!(user_id, rule = rule.code(), ip_address, "Suspicious login: {}", detail);
+        let recipients = LoginAlertRepository::alert_recipients(pool, user_id).await?;
+        let title = format!("Suspicious sign-in to {}", username);
+        let body =
+            format!("{} from {}. If this was not you, change the password.", detail, ip_address);
+        let data = serde_json::json!({
+            "rule": rule.code(),
+            "userId": user_id,
+            "ipAddress": ip_address,
+        });
+        let recipient_ids: Vec<i64> = recipients.iter().map(|r| r.id).collect();
+        let event = DomainEvent::SuspiciousLogin {
+            user_id,
+            username: username.to_string(),
+            rule: rule.code().to_string(),
+            ip_address: ip_address.to_string(),
+            detail,
+        };
+        let mut tx = tx::begin(pool).await?;
+        AccountRepository::insert_notifications(
+            &mut *tx,
+            &recipient_ids,
+            NOTIFICATION_KIND,
+            &title,
+            &body,
+            Some(&data),
+        )
+        .await?;
+        let recorded = events::record(&mut tx, &event).await?;
+        tx::commit(tx).await?;
+        events::deliver(pool, recorded).await;
+
+        if mail::notices_enabled() {
+            let emails: Vec<String> = recipients.into_iter().filter_map(|r| r.email).collect();
+            let (subject, text) = (title, body);
+            tokio::spawn(async move {
+                for email in emails {
+                    if let Err(e) = mail::send_notice(&email, &subject, text.clone()).await {
+                        tracing::warn!("Failed to mail login alert: {}", e);
+                    }
+                }
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Counts failed logins and checks successful ones against the enabled rules.
+#[async_trait]
+impl EventSubscriber<SqlitePool> for LoginAlertService {
+    fn name(&self) -> &'static str {
+        "login-alerts"
+    }
+
+    async fn handle(&self, pool: &SqlitePool, event: &DomainEvent) -> Result<(), String> {
+        match event {
+            DomainEvent::LoginFailed { username, .. } => {
+                FAILED_LOGINS.record(username, Instant::now());
+            }
+            DomainEvent::LoginSucceeded { user_id, username, ip_address, user_agent, .. } => {
+                Self::check_login(pool, *user_id, username, ip_address, user_agent)
+                    .await
+                    .map_err(|e| format!("login alert check failed: {:?}", e))?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FAILURE_WINDOW, FailedLogins, LoginAlertRule, LoginFacts, distance_km, triggered};
+    use std::time::{Duration, Instant};
+
+    fn fired(facts: &LoginFacts) -> Vec<LoginAlertRule> {
+        triggered(facts, LoginAlertRule::ALL).into_iter().map(|(rule, _)| rule).collect()
+    }
+
+    #[test]
+    fn rules_fire_on_new_sources_fast_travel_and_failure_runs() {
+        let usual = LoginFacts { returning: true, known_source: true, ..LoginFacts::default() };
+        assert!(fired(&usual).is_empty());
+        assert!(fired(&LoginFacts::default()).is_empty(), "first logins are not new devices");
+
+        let new_device = LoginFacts { known_source: false, ..usual };
+        assert_eq!(fired(&new_device), [LoginAlertRule::NewDevice]);
+
+        let flight = LoginFacts { travel: Some((9_000.0, 12.0)), ..LoginFacts::default() };
+        assert!(fired(&flight).is_empty());
+        let teleport = LoginFacts { travel: Some((9_000.0, 1.0)), ..LoginFacts::default() };
+        assert_eq!(fired(&teleport), [LoginAlertRule::ImpossibleTravel]);
+        let nearby = LoginFacts { travel: Some((300.0, 0.0)), ..LoginFacts::default() };
+        assert!(fired(&nearby).is_empty());
+
+        let brute_force = LoginFacts { recent_failures: 5, ..LoginFacts::default() };
+        assert_eq!(fired(&brute_force), [LoginAlertRule::FailuresThenSuccess]);
+        assert!(triggered(&brute_force, &[LoginAlertRule::NewDevice]).is_empty());
+    }
+
+    #[test]
+    fn distances_follow_the_great_circle() {
+        let beijing = (39.9042, 116.4074);
+        let new_york = (40.7128, -74.0060);
+        let km = distance_km(beijing, new_york);
+        assert!((10_950.0..11_050.0).contains(&km), "{}", km);
+        assert_eq!(distance_km(beijing, beijing), 0.0);
+    }
+
+    #[test]
+    fn failure_counts_expire_and_reset_on_success() {
+        let failures = FailedLogins::default();
+        let start = Instant::now();
+        failures.record("Mallory", start);
+        failures.record("mallory", start + Duration::from_secs(60));
+        failures.record("mallory", start + FAILURE_WINDOW + Duration::from_secs(30));
+
+        assert_eq!(failures.take("MALLORY", start + FAILURE_WINDOW + Duration::from_secs(31)), 2);
+        assert_eq!(failures.take("mallory", start + FAILURE_WINDOW + Duration::from_secs(32)), 0);
+    }
+}
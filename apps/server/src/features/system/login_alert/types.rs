@@ -0,0 +1,83 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A check run on every successful login; each can be switched off in settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginAlertRule {
+    /// Login from an address and user agent pair the user never signed in from.
+    NewDevice,
+    /// Login too far from the previous one to have travelled in between.
+    ImpossibleTravel,
+    /// Login right after a run of failed attempts on the same account.
+    FailuresThenSuccess,
+}
+
+impl LoginAlertRule {
+    pub const ALL: &[LoginAlertRule] = &[
+        LoginAlertRule::NewDevice,
+        LoginAlertRule::ImpossibleTravel,
+        LoginAlertRule::FailuresThenSuccess,
+    ];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            LoginAlertRule::NewDevice => "new_device",
+            LoginAlertRule::ImpossibleTravel => "impossible_travel",
+            LoginAlertRule::FailuresThenSuccess => "failures_then_success",
+        }
+    }
+
+    pub fn parse(code: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|rule| rule.code() == code)
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            LoginAlertRule::NewDevice => "Sign-in from a new device or address",
+            LoginAlertRule::ImpossibleTravel => {
+                "Sign-in too far from the previous one to have travelled"
+            }
+            LoginAlertRule::FailuresThenSuccess => "Sign-in right after repeated failed attempts",
+        }
+    }
+}
+
+/// Rule row as read from the database.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct LoginAlertRuleRow {
+    pub code: String,
+    pub enabled: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Rule for the settings page.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginAlertRuleResp {
+    pub code: String,
+    pub description: String,
+    pub enabled: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Update login alert rule request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateLoginAlertRuleRequest {
+    pub enabled: bool,
+}
+
+/// Where and when a user last signed in from a known source.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct LoginSourceRow {
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub last_seen_at: NaiveDateTime,
+}
+
+/// Someone who receives a user's login alerts.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AlertRecipientRow {
+    pub id: i64,
+    pub email: Option<String>,
+}
@@ -1,8 +1,18 @@
 use super::{
     service::MenuService,
-    types::{CreateMenuRequest, MenuItemResp, MenuOptionResp, MenuQuery, UpdateMenuPayload},
+    types::{
+        CreateMenuRequest, MenuChildResp, MenuChildrenQuery, MenuItemResp, MenuOptionResp,
+        MenuQuery, UpdateMenuPayload,
+    },
+};
+use crate::{
+    common::{
+        api::{ApiResponse, AppResult, OptionsQuery, PageMeta},
+        i18n::{ReplaceTranslationsPayload, Translation},
+        ids::{MenuId, UserId},
+    },
+    infra::db::DbExecutor,
 };
-use crate::common::api::{ApiResponse, AppResult, OptionsQuery};
 
 use axum::{
     Json,
@@ -15,11 +25,20 @@ use sqlx::SqlitePool;
 /// Query params: title, status
 /// Need show all menu, not pagination
 pub async fn list_menus(
-    State(pool): State<SqlitePool>,
+    State(db): State<DbExecutor>,
     Query(params): Query<MenuQuery>,
 ) -> AppResult<Vec<MenuItemResp>> {
-    let (menu_list, total) = MenuService::list_menus(&pool, params).await?;
-    Ok(ApiResponse::page(menu_list, total))
+    let (menu_list, total) = MenuService::list_menus(db.read(), params).await?;
+    Ok(ApiResponse::page(menu_list, total, PageMeta::single(total)))
+}
+
+/// List the direct children of one menu, flagged with `hasChildren`
+/// Query params: parentId (0 or omitted for the top level)
+pub async fn list_menu_children(
+    State(db): State<DbExecutor>,
+    Query(query): Query<MenuChildrenQuery>,
+) -> AppResult<Vec<MenuChildResp>> {
+    Ok(ApiResponse::success(MenuService::list_children(db.read(), query).await?))
 }
 
 /// Create new menu
@@ -27,7 +46,7 @@ pub async fn list_menus(
 pub async fn create_menu(
     State(pool): State<SqlitePool>,
     Json(request): Json<CreateMenuRequest>,
-) -> AppResult<i64> {
+) -> AppResult<MenuId> {
     Ok(ApiResponse::success(MenuService::create_menu(&pool, request).await?))
 }
 
@@ -36,11 +55,11 @@ pub async fn create_menu(
 pub async fn update_menu(
     current_user: CurrentUser,
     State(pool): State<SqlitePool>,
-    Path(id): Path<i64>,
+    Path(id): Path<MenuId>,
     Json(request): Json<UpdateMenuPayload>,
-) -> AppResult<i64> {
+) -> AppResult<MenuId> {
     Ok(ApiResponse::success(
-        MenuService::update_menu(&pool, id, current_user.user_id, request).await?,
+        MenuService::update_menu(&pool, id, UserId(current_user.user_id), request).await?,
     ))
 }
 
@@ -48,16 +67,43 @@ pub async fn update_menu(
 pub async fn delete_menu(
     current_user: CurrentUser,
     State(pool): State<SqlitePool>,
-    Path(id): Path<i64>,
+    Path(id): Path<MenuId>,
 ) -> AppResult<()> {
-    MenuService::delete_menu(&pool, id, current_user.user_id).await?;
+    MenuService::delete_menu(&pool, id, UserId(current_user.user_id)).await?;
     Ok(ApiResponse::success(()))
 }
 
 /// Get menu options for dropdowns
 pub async fn get_menu_options(
-    State(pool): State<SqlitePool>,
+    State(db): State<DbExecutor>,
     Query(query): Query<OptionsQuery>,
 ) -> AppResult<Vec<MenuOptionResp>> {
-    Ok(ApiResponse::success(MenuService::get_menu_options(&pool, query).await?))
+    Ok(ApiResponse::success(MenuService::get_menu_options(db.read(), query).await?))
+}
+
+/// Get the per-locale names of a menu
+pub async fn get_menu_translations(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<MenuId>,
+) -> AppResult<Vec<Translation>> {
+    Ok(ApiResponse::success(MenuService::get_translations(&pool, id).await?))
+}
+
+/// Replace the per-locale names of a menu
+/// Body: translations [{ locale, text }]
+pub async fn replace_menu_translations(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<MenuId>,
+    Json(payload): Json<ReplaceTranslationsPayload>,
+) -> AppResult<Vec<Translation>> {
+    Ok(ApiResponse::success(
+        MenuService::replace_translations(
+            &pool,
+            id,
+            UserId(current_user.user_id),
+            payload.translations,
+        )
+        .await?,
+    ))
 }
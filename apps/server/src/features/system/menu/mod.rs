@@ -3,11 +3,15 @@ pub mod repo;
 pub mod service;
 pub mod types;
 
+use crate::features::system::purge::handler::{get_menu_purge_report, purge_menu};
 use axum::{
     Router,
     routing::{delete, get, post, put},
 };
-use handler::{create_menu, delete_menu, get_menu_options, list_menus, update_menu};
+use handler::{
+    create_menu, delete_menu, get_menu_options, get_menu_translations, list_menu_children,
+    list_menus, replace_menu_translations, update_menu,
+};
 use rustzen_core::{
     capability::system_menu,
     permission::{PermissionsCheck, RouterExt},
@@ -17,6 +21,11 @@ use sqlx::SqlitePool;
 pub fn menu_routes() -> Router<SqlitePool> {
     Router::new()
         .route_with_permission("/", get(list_menus), PermissionsCheck::Require(system_menu::LIST))
+        .route_with_permission(
+            "/children",
+            get(list_menu_children),
+            PermissionsCheck::Require(system_menu::LIST),
+        )
         .route_with_permission(
             "/",
             post(create_menu),
@@ -32,6 +41,26 @@ pub fn menu_routes() -> Router<SqlitePool> {
             delete(delete_menu),
             PermissionsCheck::Require(system_menu::DELETE),
         )
+        .route_with_permission(
+            "/{id}/purge",
+            get(get_menu_purge_report),
+            PermissionsCheck::Require(system_menu::PURGE),
+        )
+        .route_with_permission(
+            "/{id}/purge",
+            delete(purge_menu),
+            PermissionsCheck::Require(system_menu::PURGE),
+        )
+        .route_with_permission(
+            "/{id}/translations",
+            get(get_menu_translations),
+            PermissionsCheck::Require(system_menu::LIST),
+        )
+        .route_with_permission(
+            "/{id}/translations",
+            put(replace_menu_translations),
+            PermissionsCheck::Require(system_menu::UPDATE),
+        )
         .route_with_permission(
             "/options",
             get(get_menu_options),
@@ -1,12 +1,24 @@
 use crate::common::{
     error::ServiceError,
-    query::{fetch_with_filters, push_eq, push_ilike},
+    i18n::Translation,
+    ids::MenuId,
+    query::{OptionsFilter, OptionsSql, fetch_options, fetch_with_filters, push_eq, push_ilike},
 };
 
 use chrono::Utc;
 use sqlx::{QueryBuilder, Sqlite, SqlitePool};
 
-use super::types::{MenuListQuery, MenuRow, UpdateMenuPayload};
+use super::types::{
+    CreateMenuRequest, MenuChildRow, MenuGuardRow, MenuListQuery, MenuRow, UpdateMenuPayload,
+};
+
+const MENU_OPTIONS: OptionsSql = OptionsSql {
+    base_sql: "SELECT id, name, code FROM menus WHERE deleted_at IS NULL",
+    value_column: "id",
+    status_column: "status",
+    search_columns: &["name"],
+    order_by: "sort_order ASC, name ASC",
+};
 
 /// Menu data access layer
 pub struct MenuRepository;
@@ -25,7 +37,7 @@ impl MenuRepository {
     ) -> Result<Vec<MenuRow>, ServiceError> {
         fetch_with_filters(
             pool,
-            "SELECT id, parent_id, parent_code, name, code, menu_type, status, is_system, is_manual, sort_order, created_at, updated_at FROM menus WHERE 1=1 AND deleted_at IS NULL",
+            "SELECT id, parent_id, parent_code, name, code, menu_type, status, visible, keep_alive, link_url, is_system, is_manual, sort_order, created_at, updated_at FROM menus WHERE 1=1 AND deleted_at IS NULL",
             |query_builder| {
                 Self::format_query(&query, query_builder);
             },
@@ -36,28 +48,47 @@ impl MenuRepository {
         .await
     }
 
+    /// Direct children of `parent_id`, each flagged with whether it has children itself.
+    pub async fn list_children(
+        pool: &SqlitePool,
+        parent_id: MenuId,
+    ) -> Result<Vec<MenuChildRow>, ServiceError> {
+        sqlx::query_as::<_, MenuChildRow>(
+            "SELECT m.id, m.parent_id, m.parent_code, m.name, m.code, m.menu_type, m.status, m.visible, m.keep_alive, m.link_url, m.is_system, m.is_manual, m.sort_order, m.created_at, m.updated_at,
+                    EXISTS (SELECT 1 FROM menus c WHERE c.parent_id = m.id AND c.deleted_at IS NULL) AS has_children
+             FROM menus m
+             WHERE m.parent_id = ? AND m.deleted_at IS NULL
+             ORDER BY m.sort_order ASC, m.id ASC",
+        )
+        .bind(parent_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error listing children of menu {}: {:?}", parent_id, e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
     /// Creates a new menu
     pub async fn create(
         pool: &SqlitePool,
-        parent_id: i64,
-        name: &str,
-        code: &str,
-        menu_type: i16,
-        sort_order: i16,
-        status: i16,
-    ) -> Result<i64, ServiceError> {
+        request: &CreateMenuRequest,
+    ) -> Result<MenuId, ServiceError> {
         let now = Utc::now().naive_utc();
-        let menu_id = sqlx::query_scalar::<_, i64>(
-            "INSERT INTO menus (parent_id, name, code, menu_type, sort_order, status, is_manual, created_at, updated_at)
-             VALUES (?, ?, ?, ?, ?, ?, TRUE, ?, ?)
+        let menu_id = sqlx::query_scalar::<_, MenuId>(
+            "INSERT INTO menus (parent_id, name, code, menu_type, sort_order, status, visible, keep_alive, link_url, is_manual, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, TRUE, ?, ?)
              RETURNING id",
         )
-        .bind(parent_id)
-        .bind(name)
-        .bind(code)
-        .bind(menu_type)
-        .bind(sort_order)
-        .bind(status)
+        .bind(request.parent_id)
+        .bind(&request.name)
+        .bind(&request.code)
+        .bind(request.menu_type)
+        .bind(request.sort_order)
+        .bind(request.status)
+        .bind(request.visible.unwrap_or(true))
+        .bind(request.keep_alive.unwrap_or(false))
+        .bind(&request.link_url)
         .bind(now)
         .bind(now)
         .fetch_one(pool)
@@ -73,29 +104,34 @@ impl MenuRepository {
     /// Updates an existing menu
     pub async fn update(
         pool: &SqlitePool,
-        id: i64,
+        id: MenuId,
         request: &UpdateMenuPayload,
-    ) -> Result<i64, ServiceError> {
-        let menu_id = sqlx::query_scalar::<_, i64>(
-                "UPDATE menus
-                 SET parent_id = ?, name = ?, code = ?, menu_type = ?, sort_order = ?, status = ?, is_manual = TRUE, updated_at = ?
+    ) -> Result<MenuId, ServiceError> {
+        let menu_id = sqlx::query_scalar::<_, MenuId>(
+            "UPDATE menus
+                 SET parent_id = ?, name = ?, code = ?, menu_type = ?, sort_order = ?, status = ?,
+                     visible = COALESCE(?, visible), keep_alive = COALESCE(?, keep_alive),
+                     link_url = ?, is_manual = TRUE, updated_at = ?
                  WHERE id = ? AND deleted_at IS NULL
                  RETURNING id",
-            )
-            .bind(request.parent_id)
-            .bind(&request.name)
-            .bind(&request.code)
-            .bind(request.menu_type)
-            .bind(request.sort_order)
-            .bind(request.status)
-            .bind(Utc::now().naive_utc())
-            .bind(id)
-            .fetch_optional(pool)
-            .await
-            .map_err(|e| {
-                tracing::error!("Database error updating menu: {:?}", e);
-                ServiceError::DatabaseQueryFailed
-            })?;
+        )
+        .bind(request.parent_id)
+        .bind(&request.name)
+        .bind(&request.code)
+        .bind(request.menu_type)
+        .bind(request.sort_order)
+        .bind(request.status)
+        .bind(request.visible)
+        .bind(request.keep_alive)
+        .bind(&request.link_url)
+        .bind(Utc::now().naive_utc())
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error updating menu: {:?}", e);
+            ServiceError::DatabaseQueryFailed
+        })?;
 
         if let Some(menu_id) = menu_id {
             Ok(menu_id)
@@ -104,10 +140,14 @@ impl MenuRepository {
         }
     }
 
-    /// Returns whether the menu is a system built-in menu.
-    pub async fn is_system_menu(pool: &SqlitePool, id: i64) -> Result<Option<bool>, ServiceError> {
-        sqlx::query_scalar::<_, bool>(
-            "SELECT is_system FROM menus WHERE id = ? AND deleted_at IS NULL",
+    /// Returns the system flag and critical fields of a live menu.
+    pub async fn find_guard_fields(
+        pool: &SqlitePool,
+        id: MenuId,
+    ) -> Result<Option<MenuGuardRow>, ServiceError> {
+        sqlx::query_as::<_, MenuGuardRow>(
+            "SELECT is_system, parent_id, code, menu_type, status
+             FROM menus WHERE id = ? AND deleted_at IS NULL",
         )
         .bind(id)
         .fetch_optional(pool)
@@ -119,7 +159,7 @@ impl MenuRepository {
     }
 
     /// Disable a menu.
-    pub async fn disable(pool: &SqlitePool, id: i64) -> Result<bool, ServiceError> {
+    pub async fn disable(pool: &SqlitePool, id: MenuId) -> Result<bool, ServiceError> {
         let result = sqlx::query(
             "UPDATE menus SET status = 2, updated_at = ? WHERE id = ? AND is_system = false AND deleted_at IS NULL"
         )
@@ -138,19 +178,59 @@ impl MenuRepository {
     /// Retrieves menu list for Options API
     pub async fn list_menu_options(
         pool: &SqlitePool,
-        search_query: Option<&str>,
-        limit: Option<i64>,
-    ) -> Result<Vec<(i64, String, String)>, ServiceError> {
-        fetch_with_filters(
-            pool,
-            "SELECT id, name, code FROM menus WHERE status = 1 AND deleted_at IS NULL",
-            |query_builder| {
-                push_ilike(query_builder, "name", search_query);
-            },
-            Some("sort_order ASC, name ASC"),
-            limit,
-            None,
+        filter: &OptionsFilter<MenuId>,
+    ) -> Result<Vec<(MenuId, String, String)>, ServiceError> {
+        fetch_options(pool, &MENU_OPTIONS, filter, |_| {}).await
+    }
+
+    /// Translations of a menu's name.
+    pub async fn list_translations(
+        pool: &SqlitePool,
+        id: MenuId,
+    ) -> Result<Vec<Translation>, ServiceError> {
+        sqlx::query_as::<_, Translation>(
+            "SELECT locale, name AS text FROM menu_i18n WHERE menu_id = ? ORDER BY locale",
         )
+        .bind(id)
+        .fetch_all(pool)
         .await
+        .map_err(|e| {
+            tracing::error!("Database error listing translations of menu {}: {:?}", id, e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
+    /// Replaces every translation of a menu's name in one transaction.
+    pub async fn replace_translations(
+        pool: &SqlitePool,
+        id: MenuId,
+        translations: &[Translation],
+    ) -> Result<(), ServiceError> {
+        let map_err = |e: sqlx::Error| {
+            tracing::error!("Database error replacing translations of menu {}: {:?}", id, e);
+            ServiceError::DatabaseQueryFailed
+        };
+        let mut tx = pool.begin().await.map_err(map_err)?;
+        sqlx::query("DELETE FROM menu_i18n WHERE menu_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(map_err)?;
+        for translation in translations {
+            sqlx::query("INSERT INTO menu_i18n (menu_id, locale, name) VALUES (?, ?, ?)")
+                .bind(id)
+                .bind(&translation.locale)
+                .bind(&translation.text)
+                .execute(&mut *tx)
+                .await
+                .map_err(map_err)?;
+        }
+        sqlx::query("UPDATE menus SET updated_at = ? WHERE id = ?")
+            .bind(Utc::now().naive_utc())
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(map_err)?;
+        tx.commit().await.map_err(map_err)
     }
 }
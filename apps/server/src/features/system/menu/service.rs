@@ -1,14 +1,24 @@
 use super::{
     repo::MenuRepository,
     types::{
-        CreateMenuRequest, MenuItemResp, MenuListQuery, MenuOptionResp, MenuQuery,
+        CreateMenuRequest, MENU_TYPE_IFRAME, MENU_TYPE_LINK, MENU_TYPES, MenuChildResp,
+        MenuChildrenQuery, MenuGuardRow, MenuItemResp, MenuListQuery, MenuOptionResp, MenuQuery,
         UpdateMenuPayload,
     },
 };
-use crate::common::{api::OptionsQuery, error::ServiceError, query::parse_optional_i16_filter};
+use crate::common::{
+    api::OptionsQuery,
+    error::ServiceError,
+    i18n::{Translation, check_translations},
+    ids::{MenuId, UserId},
+    query::{OptionsFilter, parse_optional_i16_filter},
+    validation::FieldErrors,
+};
+use crate::features::manage::dict::{service::DictService, types::MENU_STATUS};
 use crate::infra::permission::PermissionService;
 use rustzen_core::capability::SYSTEM_WILDCARD;
 
+use axum::http::Uri;
 use sqlx::SqlitePool;
 
 pub struct MenuService;
@@ -31,44 +41,59 @@ impl MenuService {
         Ok((menu_responses, count))
     }
 
+    /// One level of the menu tree, for clients that expand nodes on demand
+    pub async fn list_children(
+        pool: &SqlitePool,
+        query: MenuChildrenQuery,
+    ) -> Result<Vec<MenuChildResp>, ServiceError> {
+        let parent_id = query.parent_id.unwrap_or(MenuId(0));
+        let children = MenuRepository::list_children(pool, parent_id).await?;
+        Ok(children.into_iter().map(MenuChildResp::from).collect())
+    }
+
     /// Create new menu with validation
     pub async fn create_menu(
         pool: &SqlitePool,
-        request: CreateMenuRequest,
-    ) -> Result<i64, ServiceError> {
+        mut request: CreateMenuRequest,
+    ) -> Result<MenuId, ServiceError> {
         tracing::info!("Attempting to create menu with name: {}", request.name);
-        MenuRepository::create(
-            pool,
-            request.parent_id,
-            &request.name,
-            &request.code,
-            request.menu_type,
-            request.sort_order,
-            request.status,
-        )
-        .await
+        let mut errors = FieldErrors::new();
+        DictService::check_enum_value(pool, MENU_STATUS, "status", request.status, &mut errors)
+            .await?;
+        request.link_url = check_link_fields(request.menu_type, request.link_url, &mut errors);
+        errors.into_result()?;
+        MenuRepository::create(pool, &request).await
     }
 
     /// Update existing menu with validation
     pub async fn update_menu(
         pool: &SqlitePool,
-        id: i64,
-        current_user_id: i64,
-        request: UpdateMenuPayload,
-    ) -> Result<i64, ServiceError> {
+        id: MenuId,
+        current_user_id: UserId,
+        mut request: UpdateMenuPayload,
+    ) -> Result<MenuId, ServiceError> {
         tracing::info!("Attempting to update menu: {}", id);
-        Self::ensure_menu_is_mutable(pool, id, current_user_id).await?;
+        let menu = Self::ensure_menu_is_mutable(pool, id, current_user_id).await?;
+        ensure_system_menu_fields_unchanged(&menu, &request)?;
+        let mut errors = FieldErrors::new();
+        DictService::check_enum_value(pool, MENU_STATUS, "status", request.status, &mut errors)
+            .await?;
+        request.link_url = check_link_fields(request.menu_type, request.link_url, &mut errors);
+        errors.into_result()?;
         MenuRepository::update(pool, id, &request).await
     }
 
     /// Delete menu with child validation
     pub async fn delete_menu(
         pool: &SqlitePool,
-        id: i64,
-        current_user_id: i64,
+        id: MenuId,
+        current_user_id: UserId,
     ) -> Result<(), ServiceError> {
         tracing::info!("Attempting to disable menu: {}", id);
-        Self::ensure_menu_is_mutable(pool, id, current_user_id).await?;
+        let menu = Self::ensure_menu_is_mutable(pool, id, current_user_id).await?;
+        if menu.is_system {
+            return Err(ServiceError::SystemRecordProtected("menu".to_string()));
+        }
 
         if MenuRepository::disable(pool, id).await? {
             Ok(())
@@ -77,22 +102,49 @@ impl MenuService {
         }
     }
 
+    /// Lists the translations of a menu's name
+    pub async fn get_translations(
+        pool: &SqlitePool,
+        id: MenuId,
+    ) -> Result<Vec<Translation>, ServiceError> {
+        MenuRepository::find_guard_fields(pool, id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound(format!("Menu id: {}", id)))?;
+        MenuRepository::list_translations(pool, id).await
+    }
+
+    /// Replaces the translations of a menu's name; built-in menus need the same rights as a
+    /// rename
+    pub async fn replace_translations(
+        pool: &SqlitePool,
+        id: MenuId,
+        current_user_id: UserId,
+        mut translations: Vec<Translation>,
+    ) -> Result<Vec<Translation>, ServiceError> {
+        tracing::info!("Replacing translations of menu {}", id);
+        Self::ensure_menu_is_mutable(pool, id, current_user_id).await?;
+        let mut errors = FieldErrors::new();
+        check_translations(&mut translations, &mut errors);
+        errors.into_result()?;
+
+        MenuRepository::replace_translations(pool, id, &translations).await?;
+        MenuRepository::list_translations(pool, id).await
+    }
+
     async fn ensure_menu_is_mutable(
         pool: &SqlitePool,
-        id: i64,
-        current_user_id: i64,
-    ) -> Result<(), ServiceError> {
-        match MenuRepository::is_system_menu(pool, id).await? {
-            Some(true) => {
-                if PermissionService::has_permission(current_user_id, SYSTEM_WILDCARD).await? {
-                    Ok(())
-                } else {
-                    Err(ServiceError::MenuIsSystem)
-                }
-            }
-            Some(false) => Ok(()),
-            None => Err(ServiceError::NotFound(format!("Menu id: {}", id))),
+        id: MenuId,
+        current_user_id: UserId,
+    ) -> Result<MenuGuardRow, ServiceError> {
+        let menu = MenuRepository::find_guard_fields(pool, id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound(format!("Menu id: {}", id)))?;
+        if menu.is_system
+            && !PermissionService::has_permission(current_user_id.get(), SYSTEM_WILDCARD).await?
+        {
+            return Err(ServiceError::MenuIsSystem);
         }
+        Ok(menu)
     }
 
     /// Get menu options for dropdowns
@@ -101,10 +153,122 @@ impl MenuService {
         query: OptionsQuery,
     ) -> Result<Vec<MenuOptionResp>, ServiceError> {
         tracing::info!("Fetching menu options: {:?}", query);
-        Ok(MenuRepository::list_menu_options(pool, query.q.as_deref(), query.limit)
+        Ok(MenuRepository::list_menu_options(pool, &OptionsFilter::from_query(query)?)
             .await?
             .into_iter()
             .map(|(id, name, code)| MenuOptionResp { label: name, value: id, code })
             .collect())
     }
 }
+
+/// Built-in menus may be renamed or reordered, but their code, parent, type, and
+/// status are what permission sync and the frontend rely on.
+fn ensure_system_menu_fields_unchanged(
+    menu: &MenuGuardRow,
+    request: &UpdateMenuPayload,
+) -> Result<(), ServiceError> {
+    if menu.is_system
+        && (menu.code != request.code
+            || menu.parent_id != request.parent_id
+            || menu.menu_type != request.menu_type
+            || menu.status != request.status)
+    {
+        return Err(ServiceError::SystemRecordProtected("menu".to_string()));
+    }
+    Ok(())
+}
+
+/// Checks `menu_type` and returns the `link_url` to store: an http(s) URL for link and
+/// iframe menus, `None` for every other type.
+fn check_link_fields(
+    menu_type: i16,
+    link_url: Option<String>,
+    errors: &mut FieldErrors,
+) -> Option<String> {
+    errors.check_one_of("menuType", menu_type, &MENU_TYPES);
+    if !matches!(menu_type, MENU_TYPE_LINK | MENU_TYPE_IFRAME) {
+        return None;
+    }
+    let link_url = link_url.map(|url| url.trim().to_string()).filter(|url| !url.is_empty());
+    match link_url.as_deref().map(|url| url.parse::<Uri>()) {
+        None => errors.push("linkUrl", "is required for link and iframe menus"),
+        Some(Ok(uri))
+            if matches!(uri.scheme_str(), Some("http" | "https"))
+                && uri.host().is_some_and(|host| !host.is_empty()) => {}
+        Some(_) => errors.push("linkUrl", "must be an http or https URL"),
+    }
+    link_url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_link_fields, ensure_system_menu_fields_unchanged};
+    use crate::common::validation::FieldErrors;
+    use crate::common::{error::ServiceError, ids::MenuId};
+    use crate::features::system::menu::types::{MenuGuardRow, UpdateMenuPayload};
+
+    fn guard(is_system: bool) -> MenuGuardRow {
+        MenuGuardRow {
+            is_system,
+            parent_id: MenuId(0),
+            code: "system:user:list".to_string(),
+            menu_type: 2,
+            status: 1,
+        }
+    }
+
+    fn payload(code: &str, status: i16) -> UpdateMenuPayload {
+        UpdateMenuPayload {
+            parent_id: MenuId(0),
+            name: "Users".to_string(),
+            code: code.to_string(),
+            menu_type: 2,
+            sort_order: 5,
+            status,
+            visible: Some(false),
+            keep_alive: Some(true),
+            link_url: None,
+        }
+    }
+
+    #[test]
+    fn link_menus_need_an_http_url_and_other_types_drop_it() {
+        let mut errors = FieldErrors::new();
+        let grafana = Some(" https://grafana.example.com/d/api ".to_string());
+        assert_eq!(
+            check_link_fields(5, grafana.clone(), &mut errors).as_deref(),
+            Some("https://grafana.example.com/d/api")
+        );
+        assert_eq!(check_link_fields(2, grafana, &mut errors), None);
+        assert!(errors.into_result().is_ok());
+
+        let mut errors = FieldErrors::new();
+        check_link_fields(4, Some("  ".to_string()), &mut errors);
+        check_link_fields(4, Some("javascript:alert(1)".to_string()), &mut errors);
+        check_link_fields(6, None, &mut errors);
+        let Err(ServiceError::InvalidFields(fields)) = errors.into_result() else {
+            panic!("expected field errors");
+        };
+        let fields: Vec<_> = fields.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["linkUrl", "linkUrl", "menuType"]);
+    }
+
+    #[test]
+    fn system_menus_only_accept_cosmetic_changes() {
+        assert!(
+            ensure_system_menu_fields_unchanged(&guard(true), &payload("system:user:list", 1))
+                .is_ok()
+        );
+        assert!(matches!(
+            ensure_system_menu_fields_unchanged(&guard(true), &payload("system:user:all", 1)),
+            Err(ServiceError::SystemRecordProtected(_))
+        ));
+        assert!(matches!(
+            ensure_system_menu_fields_unchanged(&guard(true), &payload("system:user:list", 2)),
+            Err(ServiceError::SystemRecordProtected(_))
+        ));
+        assert!(
+            ensure_system_menu_fields_unchanged(&guard(false), &payload("custom:code", 2)).is_ok()
+        );
+    }
+}
@@ -1,52 +1,94 @@
-use chrono::NaiveDateTime;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::common::ids::MenuId;
+
+/// Opens `link_url` in a new browser tab.
+pub const MENU_TYPE_LINK: i16 = 4;
+/// Embeds `link_url` in an iframe inside the layout.
+pub const MENU_TYPE_IFRAME: i16 = 5;
+/// Directory, page, button, external link and iframe.
+pub const MENU_TYPES: [i16; 5] = [1, 2, 3, MENU_TYPE_LINK, MENU_TYPE_IFRAME];
+
 /// Menu row from the database.
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct MenuRow {
-    pub id: i64,
-    pub parent_id: i64,
+    pub id: MenuId,
+    pub parent_id: MenuId,
     pub parent_code: Option<String>,
     pub name: String,
     pub code: String,
     pub menu_type: i16,
     pub status: i16,
+    pub visible: bool,
+    pub keep_alive: bool,
+    pub link_url: Option<String>,
     pub is_system: bool,
     pub is_manual: bool,
     pub sort_order: i32,
-    pub created_at: NaiveDateTime,
-    pub updated_at: NaiveDateTime,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Menu row with whether it has live children, for the lazily loaded tree.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct MenuChildRow {
+    #[sqlx(flatten)]
+    pub menu: MenuRow,
+    pub has_children: bool,
+}
+
+/// Fields checked before a menu is changed; built-in menus keep these fixed.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct MenuGuardRow {
+    pub is_system: bool,
+    pub parent_id: MenuId,
+    pub code: String,
+    pub menu_type: i16,
+    pub status: i16,
 }
 
 /// Create menu request parameters
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateMenuRequest {
-    pub parent_id: i64,
+    pub parent_id: MenuId,
     pub name: String,
     pub code: String,
     pub menu_type: i16,
     pub sort_order: i16,
     pub status: i16,
+    /// Shown in the navigation menu; defaults to `true`.
+    pub visible: Option<bool>,
+    /// Keep the page mounted after navigating away; defaults to `false`.
+    pub keep_alive: Option<bool>,
+    /// Target of link and iframe menus; required for those types, dropped for others.
+    pub link_url: Option<String>,
 }
 
 /// Update menu request parameters
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateMenuPayload {
-    pub parent_id: i64,
+    pub parent_id: MenuId,
     pub name: String,
     pub code: String,
     pub menu_type: i16,
     pub sort_order: i16,
     pub status: i16,
+    /// Omitted keeps the current value.
+    pub visible: Option<bool>,
+    /// Omitted keeps the current value.
+    pub keep_alive: Option<bool>,
+    /// Target of link and iframe menus; required for those types, dropped for others.
+    pub link_url: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MenuOptionResp {
     pub label: String,
-    pub value: i64,
+    pub value: MenuId,
     pub code: String,
 }
 
@@ -54,19 +96,46 @@ pub struct MenuOptionResp {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MenuItemResp {
-    pub id: i64,
-    pub parent_id: i64,
+    pub id: MenuId,
+    pub parent_id: MenuId,
     pub name: String,
     pub code: String,
     pub menu_type: i16,
     pub status: i16,
+    pub visible: bool,
+    pub keep_alive: bool,
+    pub link_url: Option<String>,
     pub is_system: bool,
     pub sort_order: i32,
-    pub created_at: NaiveDateTime,
-    pub updated_at: NaiveDateTime,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
     pub children: Option<Vec<MenuItemResp>>,
 }
 
+/// One level of the menu tree; expand a node by requesting its own children.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MenuChildResp {
+    #[serde(flatten)]
+    pub menu: MenuItemResp,
+    pub has_children: bool,
+}
+
+impl From<MenuChildRow> for MenuChildResp {
+    fn from(row: MenuChildRow) -> Self {
+        Self { menu: row.menu.into(), has_children: row.has_children }
+    }
+}
+
+/// Query for one level of the menu tree.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MenuChildrenQuery {
+    /// Parent whose direct children are listed; `0` (the default) lists the top level.
+    #[serde(default, alias = "parent_id")]
+    pub parent_id: Option<MenuId>,
+}
+
 /// Menu query parameters
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -98,6 +167,9 @@ impl From<MenuRow> for MenuItemResp {
             is_system: entity.is_system,
             sort_order: entity.sort_order,
             status: entity.status,
+            visible: entity.visible,
+            keep_alive: entity.keep_alive,
+            link_url: entity.link_url,
             created_at: entity.created_at,
             updated_at: entity.updated_at,
             children: None,
@@ -1,17 +1,95 @@
+pub mod approval;
+pub mod confirmation;
+pub mod debug_capture;
+pub mod export_job;
+pub mod export_template;
+pub mod feature_flag;
+pub mod info;
+pub mod jwt_key;
+pub mod license;
+pub mod login_alert;
 pub mod menu;
+pub mod permission;
+pub mod policy;
+pub mod profile_field;
+pub mod purge;
+pub mod quota;
+pub mod rbac;
+pub mod recycle_bin;
+pub mod registration;
+pub mod report;
 pub mod role;
+pub mod saved_filter;
+pub mod seed;
+pub mod server_log;
+pub mod tag;
 pub mod user;
+pub mod webhook;
 
 use axum::Router;
+use rustzen_core::{
+    capability::system_privacy,
+    permission::{RouterExt, register_permission_codes},
+};
 use sqlx::SqlitePool;
 
+use approval::approval_routes;
+use confirmation::confirmation_routes;
+use debug_capture::debug_capture_routes;
+use export_job::export_job_routes;
+use export_template::export_template_routes;
+use feature_flag::feature_flag_routes;
+use info::{db_routes, info_routes};
+use jwt_key::jwt_key_routes;
+use license::license_routes;
+use login_alert::login_alert_routes;
 use menu::menu_routes;
+use permission::permission_routes;
+use policy::policy_routes;
+use profile_field::profile_field_routes;
+use quota::usage_routes;
+use rbac::rbac_routes;
+use recycle_bin::recycle_bin_routes;
+use registration::registration_routes;
+use report::report_routes;
 use role::role_routes;
+use saved_filter::saved_filter_routes;
+use seed::seed_routes;
+use server_log::server_log_routes;
+use tag::tag_routes;
 use user::user_routes;
+use webhook::webhook_routes;
 
 pub fn system_routes() -> Router<SqlitePool> {
+    // Checked by handlers to decide masking rather than by a route; registered so it gets a
+    // menu row and can be granted.
+    register_permission_codes([system_privacy::VIEW]);
     Router::new()
-        .nest("/users", user_routes())
-        .nest("/menus", menu_routes())
-        .nest("/roles", role_routes())
+        .nest_routes("/users", user_routes)
+        .nest_routes("/menus", menu_routes)
+        .nest_routes("/permissions", permission_routes)
+        .nest_routes("/roles", role_routes)
+        .nest_routes("/rbac", rbac_routes)
+        .nest_routes("/seed", seed_routes)
+        .nest_routes("/info", info_routes)
+        .nest_routes("/db", db_routes)
+        .nest_routes("/webhooks", webhook_routes)
+        .nest_routes("/jwt-keys", jwt_key_routes)
+        .nest_routes("/approvals", approval_routes)
+        .nest_routes("/recycle-bin", recycle_bin_routes)
+        .nest_routes("/confirmations", confirmation_routes)
+        .nest_routes("/usage", usage_routes)
+        .nest_routes("/license", license_routes)
+        .nest_routes("/feature-flags", feature_flag_routes)
+        .nest_routes("/tags", tag_routes)
+        .nest_routes("/profile-fields", profile_field_routes)
+        .nest_routes("/policies", policy_routes)
+        .nest_routes("/registrations", registration_routes)
+        .nest_routes("/reports", report_routes)
+        .nest_routes("/exports", export_job_routes)
+        .nest_routes("/export-templates", export_template_routes)
+        .nest_routes("/filters", saved_filter_routes)
+        .nest_routes("/logs", server_log_routes)
+        .nest_routes("/login-alerts", login_alert_routes)
+        .nest_routes("/debug-captures", debug_capture_routes)
 }
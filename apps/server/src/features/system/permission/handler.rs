@@ -0,0 +1,12 @@
+use super::{service::PermissionRegistryService, types::PermissionRegistryResp};
+use crate::common::api::{ApiResponse, AppResult};
+
+use axum::extract::State;
+use sqlx::SqlitePool;
+
+/// Declared, route-required and stored capability codes, with any mismatch between them.
+pub async fn get_permission_registry(
+    State(pool): State<SqlitePool>,
+) -> AppResult<PermissionRegistryResp> {
+    Ok(ApiResponse::success(PermissionRegistryService::registry(&pool).await?))
+}
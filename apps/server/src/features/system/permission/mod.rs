@@ -0,0 +1,20 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{Router, routing::get};
+use handler::get_permission_registry;
+use rustzen_core::{
+    capability::system_menu,
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+pub fn permission_routes() -> Router<SqlitePool> {
+    Router::new().route_with_permission(
+        "/registry",
+        get(get_permission_registry),
+        PermissionsCheck::Require(system_menu::LIST),
+    )
+}
@@ -0,0 +1,28 @@
+use super::types::CapabilityMenuRow;
+use crate::common::error::ServiceError;
+
+use sqlx::SqlitePool;
+
+pub struct PermissionRegistryRepository;
+
+impl PermissionRegistryRepository {
+    /// Live page and button menus; directory and wildcard codes are only grouping.
+    pub async fn list_capability_menus(
+        pool: &SqlitePool,
+    ) -> Result<Vec<CapabilityMenuRow>, ServiceError> {
+        sqlx::query_as::<_, CapabilityMenuRow>(
+            "SELECT code, name, status, is_manual
+             FROM menus
+             WHERE deleted_at IS NULL
+               AND menu_type IN (2, 3)
+               AND code NOT LIKE '%*'
+             ORDER BY code",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error listing capability menus: {:?}", e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+}
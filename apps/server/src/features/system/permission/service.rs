@@ -0,0 +1,123 @@
+use super::{
+    repo::PermissionRegistryRepository,
+    types::{CapabilityMenuRow, PermissionRegistryEntry, PermissionRegistryResp, RegistryIssue},
+};
+use crate::{common::error::ServiceError, infra::permission::PermissionService};
+
+use rustzen_core::capability::REGISTRY;
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, BTreeSet};
+
+const MENU_STATUS_ENABLED: i16 = 1;
+
+pub struct PermissionRegistryService;
+
+impl PermissionRegistryService {
+    pub async fn registry(pool: &SqlitePool) -> Result<PermissionRegistryResp, ServiceError> {
+        let menus = PermissionRegistryRepository::list_capability_menus(pool).await?;
+        let entries = build_entries(REGISTRY, &PermissionService::route_codes(), menus);
+        let issue_count = entries.iter().filter(|entry| entry.issue.is_some()).count();
+        Ok(PermissionRegistryResp { entries, issue_count })
+    }
+
+    /// Logs one warning per mismatched code; run after the startup permission sync.
+    pub async fn warn_on_issues(pool: &SqlitePool) -> Result<(), ServiceError> {
+        let registry = Self::registry(pool).await?;
+        for entry in &registry.entries {
+            if let Some(issue) = entry.issue {
+                tracing::warn!(code = %entry.code, ?issue, "Capability {}", issue.describe());
+            }
+        }
+        if registry.issue_count == 0 {
+            tracing::info!(count = registry.entries.len(), "Capability registry is consistent");
+        }
+        Ok(())
+    }
+}
+
+/// Joins declared, routed and stored codes. Manual menus only count as the row of a
+/// declared or routed code; on their own they are custom entries, not orphans.
+fn build_entries(
+    declared: &[&str],
+    routed: &BTreeSet<String>,
+    menus: Vec<CapabilityMenuRow>,
+) -> Vec<PermissionRegistryEntry> {
+    let declared: BTreeSet<&str> = declared.iter().copied().collect();
+    let menus: BTreeMap<String, CapabilityMenuRow> =
+        menus.into_iter().map(|menu| (menu.code.clone(), menu)).collect();
+
+    let mut codes: BTreeSet<&str> = declared.clone();
+    codes.extend(routed.iter().map(String::as_str));
+    codes.extend(menus.values().filter(|menu| !menu.is_manual).map(|menu| menu.code.as_str()));
+
+    codes
+        .into_iter()
+        .map(|code| {
+            let menu = menus.get(code);
+            let is_declared = declared.contains(code);
+            let is_routed = routed.contains(code);
+            let issue = if is_routed && !is_declared {
+                Some(RegistryIssue::Undeclared)
+            } else if is_routed && menu.is_none_or(|menu| menu.status != MENU_STATUS_ENABLED) {
+                Some(RegistryIssue::MissingMenu)
+            } else if !is_routed && is_declared {
+                Some(RegistryIssue::Unrouted)
+            } else if !is_routed {
+                Some(RegistryIssue::Stale)
+            } else {
+                None
+            };
+            PermissionRegistryEntry {
+                code: code.to_string(),
+                name: menu.map(|menu| menu.name.clone()),
+                declared: is_declared,
+                routed: is_routed,
+                menu_status: menu.map(|menu| menu.status),
+                issue,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_entries;
+    use crate::features::system::permission::types::{CapabilityMenuRow, RegistryIssue};
+
+    use std::collections::BTreeSet;
+
+    fn menu(code: &str, status: i16, is_manual: bool) -> CapabilityMenuRow {
+        CapabilityMenuRow { code: code.to_string(), name: code.to_string(), status, is_manual }
+    }
+
+    #[test]
+    fn flags_typos_ungrantable_unused_and_stale_codes() {
+        let declared = ["system:user:list", "system:user:create", "system:user:export"];
+        let routed: BTreeSet<String> =
+            ["system:user:list", "system:user:create", "system:usr:delete"]
+                .into_iter()
+                .map(String::from)
+                .collect();
+        let menus = vec![
+            menu("system:user:list", 1, false),
+            menu("system:user:create", 2, true),
+            menu("system:usr:delete", 1, false),
+            menu("system:user:import", 1, false),
+            menu("reports:monthly", 1, true),
+        ];
+
+        let entries = build_entries(&declared, &routed, menus);
+        let issues: Vec<_> = entries.iter().map(|e| (e.code.as_str(), e.issue)).collect();
+        assert_eq!(
+            issues,
+            vec![
+                ("system:user:create", Some(RegistryIssue::MissingMenu)),
+                ("system:user:export", Some(RegistryIssue::Unrouted)),
+                ("system:user:import", Some(RegistryIssue::Stale)),
+                ("system:user:list", None),
+                ("system:usr:delete", Some(RegistryIssue::Undeclared)),
+            ]
+        );
+        assert_eq!(entries[1].menu_status, None);
+    }
+}
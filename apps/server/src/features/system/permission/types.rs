@@ -0,0 +1,57 @@
+use serde::Serialize;
+
+/// Why a capability code needs attention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RegistryIssue {
+    /// A route requires a code missing from `capability::REGISTRY`, usually a typo.
+    Undeclared,
+    /// A route requires a code without an enabled menu row, so no role can grant it.
+    MissingMenu,
+    /// A declared code that no route requires.
+    Unrouted,
+    /// A synced menu row whose code is neither declared nor required by a route.
+    Stale,
+}
+
+impl RegistryIssue {
+    pub fn describe(self) -> &'static str {
+        match self {
+            Self::Undeclared => "route capability is not declared in the registry",
+            Self::MissingMenu => "route capability has no enabled menu and cannot be granted",
+            Self::Unrouted => "declared capability is not required by any route",
+            Self::Stale => "synced menu capability is neither declared nor routed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionRegistryEntry {
+    pub code: String,
+    /// Menu name, when a live menu row holds the code.
+    pub name: Option<String>,
+    /// Listed in `capability::REGISTRY`.
+    pub declared: bool,
+    /// Required by at least one registered route.
+    pub routed: bool,
+    /// Status of the menu row; `None` without a live row.
+    pub menu_status: Option<i16>,
+    pub issue: Option<RegistryIssue>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionRegistryResp {
+    pub entries: Vec<PermissionRegistryEntry>,
+    pub issue_count: usize,
+}
+
+/// Live page or button menu row, as read for the registry.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct CapabilityMenuRow {
+    pub code: String,
+    pub name: String,
+    pub status: i16,
+    pub is_manual: bool,
+}
@@ -0,0 +1,61 @@
+use super::{
+    service::PolicyService,
+    types::{
+        PolicyConsentQuery, PolicyConsentResp, PolicyDocumentResp, PolicyQuery,
+        PublishPolicyRequest,
+    },
+};
+use crate::{
+    common::{
+        api::{ApiResponse, AppResult, PageMeta},
+        mask::FieldMask,
+        pagination::{Pagination, PaginationQuery},
+    },
+    infra::db::DbExecutor,
+};
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use rustzen_core::auth::CurrentUser;
+use sqlx::SqlitePool;
+
+/// Get paginated policy documents, newest first
+pub async fn list_policies(
+    State(db): State<DbExecutor>,
+    Query(query): Query<PolicyQuery>,
+) -> AppResult<Vec<PolicyDocumentResp>> {
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (documents, total) = PolicyService::list_documents(db.read(), query).await?;
+    Ok(ApiResponse::page(documents, total, PageMeta::new(pagination, total)))
+}
+
+/// Publish a new policy version
+pub async fn publish_policy(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Json(request): Json<PublishPolicyRequest>,
+) -> AppResult<i64> {
+    Ok(ApiResponse::success(PolicyService::publish(&pool, request, current_user.user_id).await?))
+}
+
+/// Who accepted a policy version, and when and from where
+pub async fn list_policy_consents(
+    current_user: CurrentUser,
+    State(db): State<DbExecutor>,
+    Path(id): Path<i64>,
+    Query(query): Query<PolicyConsentQuery>,
+) -> AppResult<Vec<PolicyConsentResp>> {
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (consents, total) =
+        PolicyService::list_consents(db.read(), id, query, FieldMask::for_user(&current_user))
+            .await?;
+    Ok(ApiResponse::page(consents, total, PageMeta::new(pagination, total)))
+}
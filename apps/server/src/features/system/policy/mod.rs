@@ -0,0 +1,34 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use handler::{list_policies, list_policy_consents, publish_policy};
+use rustzen_core::{
+    capability::system_policy,
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+pub fn policy_routes() -> Router<SqlitePool> {
+    Router::new()
+        .route_with_permission(
+            "/",
+            get(list_policies),
+            PermissionsCheck::Require(system_policy::LIST),
+        )
+        .route_with_permission(
+            "/",
+            post(publish_policy),
+            PermissionsCheck::Require(system_policy::PUBLISH),
+        )
+        .route_with_permission(
+            "/{id}/consents",
+            get(list_policy_consents),
+            PermissionsCheck::Require(system_policy::LIST),
+        )
+}
@@ -0,0 +1,184 @@
+use super::types::{ConsentSource, PendingPolicy, PolicyConsentResp, PolicyDocumentRow};
+use crate::common::{
+    error::ServiceError,
+    query::{count_with_filters, fetch_with_filters, push_eq},
+};
+
+use chrono::Utc;
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+
+pub struct PolicyRepository;
+
+fn db_error(context: &str, e: sqlx::Error) -> ServiceError {
+    tracing::error!("Database error {}: {:?}", context, e);
+    ServiceError::DatabaseQueryFailed
+}
+
+/// Matches documents that are the newest version of their kind.
+const IS_CURRENT: &str =
+    "d.version = (SELECT MAX(version) FROM policy_documents latest WHERE latest.kind = d.kind)";
+
+impl PolicyRepository {
+    pub async fn list_documents(
+        pool: &SqlitePool,
+        offset: i64,
+        limit: i64,
+        kind: Option<&str>,
+    ) -> Result<(Vec<PolicyDocumentRow>, i64), ServiceError> {
+        let total =
+            count_with_filters(pool, "SELECT COUNT(*) FROM policy_documents d WHERE 1=1", |qb| {
+                push_eq(qb, "d.kind", kind)
+            })
+            .await?;
+        if total == 0 {
+            return Ok((Vec::new(), total));
+        }
+        let documents = fetch_with_filters(
+            pool,
+            "SELECT d.id, d.kind, d.version, d.title, d.content, d.published_by,
+                    u.username AS published_by_name, d.published_at,
+                    (SELECT COUNT(*) FROM policy_consents c WHERE c.document_id = d.id)
+                        AS consent_count
+             FROM policy_documents d
+             LEFT JOIN users u ON u.id = d.published_by
+             WHERE 1=1",
+            |qb| push_eq(qb, "d.kind", kind),
+            Some("d.id DESC"),
+            Some(limit),
+            Some(offset),
+        )
+        .await?;
+        Ok((documents, total))
+    }
+
+    /// Ids of the newest version of each kind.
+    pub async fn current_ids(pool: &SqlitePool) -> Result<Vec<i64>, ServiceError> {
+        let mut query_builder: QueryBuilder<Sqlite> =
+            QueryBuilder::new("SELECT d.id FROM policy_documents d WHERE ");
+        query_builder.push(IS_CURRENT);
+        query_builder
+            .build_query_scalar::<i64>()
+            .fetch_all(pool)
+            .await
+            .map_err(|e| db_error("loading current policy versions", e))
+    }
+
+    /// Inserts the next version of `kind`; returns its id and version.
+    pub async fn publish(
+        pool: &SqlitePool,
+        kind: &str,
+        title: &str,
+        content: &str,
+        published_by: i64,
+    ) -> Result<(i64, i64), ServiceError> {
+        sqlx::query_as::<_, (i64, i64)>(
+            "INSERT INTO policy_documents (kind, version, title, content, published_by, published_at)
+             VALUES (
+                 ?,
+                 (SELECT COALESCE(MAX(version), 0) + 1 FROM policy_documents WHERE kind = ?),
+                 ?, ?, ?, ?
+             )
+             RETURNING id, version",
+        )
+        .bind(kind)
+        .bind(kind)
+        .bind(title)
+        .bind(content)
+        .bind(published_by)
+        .bind(Utc::now().naive_utc())
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_error("publishing policy document", e))
+    }
+
+    pub async fn exists(pool: &SqlitePool, id: i64) -> Result<bool, ServiceError> {
+        sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM policy_documents WHERE id = ?)")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| db_error("checking policy document", e))
+    }
+
+    /// Current versions `user_id` has not accepted, by kind.
+    pub async fn list_pending(
+        pool: &SqlitePool,
+        user_id: i64,
+    ) -> Result<Vec<PendingPolicy>, ServiceError> {
+        let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "SELECT d.id, d.kind, d.version, d.title FROM policy_documents d WHERE ",
+        );
+        query_builder
+            .push(IS_CURRENT)
+            .push(
+                " AND NOT EXISTS (
+                    SELECT 1 FROM policy_consents c WHERE c.document_id = d.id AND c.user_id = ",
+            )
+            .push_bind(user_id)
+            .push(") ORDER BY d.kind");
+        query_builder
+            .build_query_as::<PendingPolicy>()
+            .fetch_all(pool)
+            .await
+            .map_err(|e| db_error("loading pending policies", e))
+    }
+
+    /// Records acceptance of `document_ids`; documents accepted before keep their first record.
+    pub async fn record_consents(
+        pool: &SqlitePool,
+        user_id: i64,
+        document_ids: &[i64],
+        source: &ConsentSource,
+    ) -> Result<u64, ServiceError> {
+        if document_ids.is_empty() {
+            return Ok(0);
+        }
+        let now = Utc::now().naive_utc();
+        let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "INSERT INTO policy_consents (document_id, user_id, ip_address, user_agent, accepted_at) ",
+        );
+        query_builder.push_values(document_ids, |mut builder, document_id| {
+            builder
+                .push_bind(*document_id)
+                .push_bind(user_id)
+                .push_bind(&source.ip_address)
+                .push_bind(&source.user_agent)
+                .push_bind(now);
+        });
+        query_builder.push(" ON CONFLICT(document_id, user_id) DO NOTHING");
+        let result = query_builder
+            .build()
+            .execute(pool)
+            .await
+            .map_err(|e| db_error("recording policy consent", e))?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn list_consents(
+        pool: &SqlitePool,
+        document_id: i64,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<PolicyConsentResp>, i64), ServiceError> {
+        let total =
+            count_with_filters(pool, "SELECT COUNT(*) FROM policy_consents c WHERE 1=1", |qb| {
+                push_eq(qb, "c.document_id", Some(document_id))
+            })
+            .await?;
+        if total == 0 {
+            return Ok((Vec::new(), total));
+        }
+        let consents = fetch_with_filters(
+            pool,
+            "SELECT c.id, c.user_id, u.username, c.ip_address, c.user_agent, c.accepted_at
+             FROM policy_consents c
+             LEFT JOIN users u ON u.id = c.user_id
+             WHERE 1=1",
+            |qb| push_eq(qb, "c.document_id", Some(document_id)),
+            Some("c.id DESC"),
+            Some(limit),
+            Some(offset),
+        )
+        .await?;
+        Ok((consents, total))
+    }
+}
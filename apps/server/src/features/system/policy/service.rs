@@ -0,0 +1,176 @@
+use super::{
+    repo::PolicyRepository,
+    types::{
+        AcceptPolicyRequest, ConsentSource, PendingPolicy, PolicyConsentQuery, PolicyConsentResp,
+        PolicyDocumentResp, PolicyKind, PolicyQuery, PublishPolicyRequest,
+    },
+};
+use crate::common::{
+    error::ServiceError,
+    mask::{FieldMask, MaskFields},
+    pagination::{Pagination, PaginationQuery},
+    validation::FieldErrors,
+};
+
+use sqlx::SqlitePool;
+
+const TITLE_MAX_LEN: usize = 200;
+const KIND_MESSAGE: &str = "must be one of terms, privacy";
+
+pub struct PolicyService;
+
+impl PolicyService {
+    pub async fn list_documents(
+        pool: &SqlitePool,
+        query: PolicyQuery,
+    ) -> Result<(Vec<PolicyDocumentResp>, i64), ServiceError> {
+        let PolicyQuery { current, page_size, kind } = query;
+        let mut errors = FieldErrors::new();
+        let kind = parse_kind(&mut errors, kind.as_deref());
+        errors.into_result()?;
+
+        let pagination = Pagination::from_query(PaginationQuery { current, page_size });
+        let (rows, total) = PolicyRepository::list_documents(
+            pool,
+            i64::from(pagination.offset),
+            i64::from(pagination.limit),
+            kind.map(PolicyKind::as_str),
+        )
+        .await?;
+        let current_ids = PolicyRepository::current_ids(pool).await?;
+        let documents = rows
+            .into_iter()
+            .map(|row| PolicyDocumentResp {
+                current: current_ids.contains(&row.id),
+                id: row.id,
+                kind: row.kind,
+                version: row.version,
+                title: row.title,
+                content: row.content,
+                published_by: row.published_by,
+                published_by_name: row.published_by_name,
+                published_at: row.published_at,
+                consent_count: row.consent_count,
+            })
+            .collect();
+        Ok((documents, total))
+    }
+
+    /// Publishes the next version of a policy; users must accept it from then on.
+    pub async fn publish(
+        pool: &SqlitePool,
+        request: PublishPolicyRequest,
+        published_by: i64,
+    ) -> Result<i64, ServiceError> {
+        let title = request.title.trim();
+        let content = request.content.trim();
+        let kind = PolicyKind::parse(request.kind.trim());
+        let mut errors = FieldErrors::new();
+        if kind.is_none() {
+            errors.push("kind", KIND_MESSAGE);
+        }
+        if title.is_empty() || title.chars().count() > TITLE_MAX_LEN {
+            errors.push("title", format!("must be 1-{} characters", TITLE_MAX_LEN));
+        }
+        if content.is_empty() {
+            errors.push("content", "is required");
+        }
+        errors.into_result()?;
+        let Some(kind) = kind else {
+            return Err(ServiceError::InvalidOperation("Policy kind is required".to_string()));
+        };
+
+        let (id, version) =
+            PolicyRepository::publish(pool, kind.as_str(), title, content, published_by).await?;
+        tracing::info!(id, version, kind = kind.as_str(), "Published policy document");
+        Ok(id)
+    }
+
+    /// Current policy versions the user still has to accept; empty once all are accepted.
+    pub async fn pending_for_user(
+        pool: &SqlitePool,
+        user_id: i64,
+    ) -> Result<Vec<PendingPolicy>, ServiceError> {
+        PolicyRepository::list_pending(pool, user_id).await
+    }
+
+    /// Records the user's acceptance of current policy versions and returns what is left.
+    ///
+    /// Only current versions can be accepted, so a client that showed an outdated text
+    /// has to reload it first.
+    pub async fn accept(
+        pool: &SqlitePool,
+        user_id: i64,
+        request: AcceptPolicyRequest,
+        source: ConsentSource,
+    ) -> Result<Vec<PendingPolicy>, ServiceError> {
+        let mut document_ids = request.document_ids;
+        document_ids.sort_unstable();
+        document_ids.dedup();
+        let current_ids = PolicyRepository::current_ids(pool).await?;
+        let mut errors = FieldErrors::new();
+        if document_ids.is_empty() {
+            errors.push("documentIds", "is required");
+        } else if document_ids.iter().any(|id| !current_ids.contains(id)) {
+            errors.push("documentIds", "must only list current policy versions");
+        }
+        errors.into_result()?;
+
+        let recorded =
+            PolicyRepository::record_consents(pool, user_id, &document_ids, &source).await?;
+        tracing::info!(user_id, recorded, ip = %source.ip_address, "Recorded policy consent");
+        Self::pending_for_user(pool, user_id).await
+    }
+
+    pub async fn list_consents(
+        pool: &SqlitePool,
+        document_id: i64,
+        query: PolicyConsentQuery,
+        mask: FieldMask,
+    ) -> Result<(Vec<PolicyConsentResp>, i64), ServiceError> {
+        if !PolicyRepository::exists(pool, document_id).await? {
+            return Err(ServiceError::NotFound(format!("Policy document id: {}", document_id)));
+        }
+        let pagination = Pagination::from_query(PaginationQuery {
+            current: query.current,
+            page_size: query.page_size,
+        });
+        let (consents, total) = PolicyRepository::list_consents(
+            pool,
+            document_id,
+            i64::from(pagination.offset),
+            i64::from(pagination.limit),
+        )
+        .await?;
+        Ok((consents.into_iter().map(|consent| consent.masked(mask)).collect(), total))
+    }
+}
+
+/// Parses an optional kind filter, recording an error for unknown values.
+fn parse_kind(errors: &mut FieldErrors, kind: Option<&str>) -> Option<PolicyKind> {
+    let raw = kind.map(str::trim).filter(|kind| !kind.is_empty())?;
+    let kind = PolicyKind::parse(raw);
+    if kind.is_none() {
+        errors.push("kind", KIND_MESSAGE);
+    }
+    kind
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_kind;
+    use crate::{common::validation::FieldErrors, features::system::policy::types::PolicyKind};
+
+    #[test]
+    fn kind_filters_are_optional_but_must_be_known() {
+        let mut errors = FieldErrors::new();
+        assert_eq!(parse_kind(&mut errors, Some(" privacy ")), Some(PolicyKind::Privacy));
+        assert_eq!(parse_kind(&mut errors, Some("")), None);
+        assert_eq!(parse_kind(&mut errors, None), None);
+        assert!(errors.into_result().is_ok());
+
+        let mut errors = FieldErrors::new();
+        assert_eq!(parse_kind(&mut errors, Some("cookies")), None);
+        assert!(errors.into_result().is_err());
+    }
+}
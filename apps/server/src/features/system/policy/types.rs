@@ -0,0 +1,131 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::common::mask::{FieldMask, MaskFields};
+
+/// Kind of policy a document versions, stored in `policy_documents.kind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyKind {
+    /// Terms of service.
+    Terms,
+    /// Privacy policy.
+    Privacy,
+}
+
+impl PolicyKind {
+    pub const ALL: [PolicyKind; 2] = [PolicyKind::Terms, PolicyKind::Privacy];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PolicyKind::Terms => "terms",
+            PolicyKind::Privacy => "privacy",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+}
+
+/// Policy document row with the publisher's name.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PolicyDocumentRow {
+    pub id: i64,
+    pub kind: String,
+    pub version: i64,
+    pub title: String,
+    pub content: String,
+    pub published_by: Option<i64>,
+    pub published_by_name: Option<String>,
+    pub published_at: DateTime<Utc>,
+    pub consent_count: i64,
+}
+
+/// Policy document for list display
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyDocumentResp {
+    pub id: i64,
+    /// `terms` or `privacy`.
+    pub kind: String,
+    pub version: i64,
+    pub title: String,
+    pub content: String,
+    pub published_by: Option<i64>,
+    pub published_by_name: Option<String>,
+    pub published_at: DateTime<Utc>,
+    /// Whether this is the newest version of its kind, the one users must accept.
+    pub current: bool,
+    pub consent_count: i64,
+}
+
+/// Current policy version a user has not accepted yet.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingPolicy {
+    pub id: i64,
+    pub kind: String,
+    pub version: i64,
+    pub title: String,
+}
+
+/// Publish policy request; the version is assigned by the server.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishPolicyRequest {
+    pub kind: String,
+    pub title: String,
+    pub content: String,
+}
+
+/// Policy document query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyQuery {
+    pub current: Option<i64>,
+    pub page_size: Option<i64>,
+    /// `terms` or `privacy`; all kinds when omitted.
+    pub kind: Option<String>,
+}
+
+/// Acceptance of one policy document, joined with the user's name.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyConsentResp {
+    pub id: i64,
+    pub user_id: i64,
+    /// `None` once the user has been purged.
+    pub username: Option<String>,
+    pub ip_address: String,
+    pub user_agent: String,
+    pub accepted_at: DateTime<Utc>,
+}
+
+impl MaskFields for PolicyConsentResp {
+    fn masked(self, mask: FieldMask) -> Self {
+        Self { ip_address: mask.ip(self.ip_address), ..self }
+    }
+}
+
+/// Consent list query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyConsentQuery {
+    pub current: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+/// Policy acceptance request from the current user
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptPolicyRequest {
+    /// Ids of the current versions being accepted, as listed in `pendingPolicies`.
+    pub document_ids: Vec<i64>,
+}
+
+/// Where an acceptance came from, kept as evidence with the consent.
+#[derive(Debug, Clone)]
+pub struct ConsentSource {
+    pub ip_address: String,
+    pub user_agent: String,
+}
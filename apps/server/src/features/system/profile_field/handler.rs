@@ -0,0 +1,46 @@
+use super::{
+    service::ProfileFieldService,
+    types::{CreateProfileFieldRequest, ProfileFieldResp, UpdateProfileFieldPayload},
+};
+use crate::{
+    common::api::{ApiResponse, AppResult},
+    infra::db::DbExecutor,
+};
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use sqlx::SqlitePool;
+
+/// Get every profile field definition in form order
+pub async fn list_profile_fields(State(db): State<DbExecutor>) -> AppResult<Vec<ProfileFieldResp>> {
+    Ok(ApiResponse::success(ProfileFieldService::list_fields(db.read()).await?))
+}
+
+/// Create a profile field
+pub async fn create_profile_field(
+    State(pool): State<SqlitePool>,
+    Json(request): Json<CreateProfileFieldRequest>,
+) -> AppResult<i64> {
+    Ok(ApiResponse::success(ProfileFieldService::create_field(&pool, request).await?))
+}
+
+/// Update the label, options and order of a profile field
+pub async fn update_profile_field(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+    Json(request): Json<UpdateProfileFieldPayload>,
+) -> AppResult<()> {
+    ProfileFieldService::update_field(&pool, id, request).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Delete a profile field and its stored values
+pub async fn delete_profile_field(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+) -> AppResult<()> {
+    ProfileFieldService::delete_field(&pool, id).await?;
+    Ok(ApiResponse::success(()))
+}
@@ -0,0 +1,46 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{
+    Router,
+    routing::{delete, get, post, put},
+};
+use handler::{
+    create_profile_field, delete_profile_field, list_profile_fields, update_profile_field,
+};
+use rustzen_core::{
+    capability::{system_profile_field, system_user},
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+/// Profile field definitions; the user forms read them to render the profile inputs.
+pub fn profile_field_routes() -> Router<SqlitePool> {
+    Router::new()
+        .route_with_permission(
+            "/",
+            get(list_profile_fields),
+            PermissionsCheck::Any(vec![
+                system_profile_field::LIST,
+                system_user::CREATE,
+                system_user::UPDATE,
+            ]),
+        )
+        .route_with_permission(
+            "/",
+            post(create_profile_field),
+            PermissionsCheck::Require(system_profile_field::CREATE),
+        )
+        .route_with_permission(
+            "/{id}",
+            put(update_profile_field),
+            PermissionsCheck::Require(system_profile_field::UPDATE),
+        )
+        .route_with_permission(
+            "/{id}",
+            delete(delete_profile_field),
+            PermissionsCheck::Require(system_profile_field::DELETE),
+        )
+}
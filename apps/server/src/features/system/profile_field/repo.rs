@@ -0,0 +1,136 @@
+use super::types::ProfileFieldRow;
+use crate::common::{error::ServiceError, tx};
+
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+pub struct ProfileFieldRepository;
+
+fn db_error(context: &str, e: sqlx::Error) -> ServiceError {
+    tracing::error!("Database error {}: {:?}", context, e);
+    ServiceError::DatabaseQueryFailed
+}
+
+impl ProfileFieldRepository {
+    /// Every definition in form order.
+    pub async fn list_fields(pool: &SqlitePool) -> Result<Vec<ProfileFieldRow>, ServiceError> {
+        sqlx::query_as::<_, ProfileFieldRow>(
+            "SELECT id, key, label, field_type, required, options, sort_order, created_at,
+                    updated_at
+             FROM user_profile_fields ORDER BY sort_order, key",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("listing profile fields", e))
+    }
+
+    pub async fn find_by_id(
+        pool: &SqlitePool,
+        id: i64,
+    ) -> Result<Option<ProfileFieldRow>, ServiceError> {
+        sqlx::query_as::<_, ProfileFieldRow>(
+            "SELECT id, key, label, field_type, required, options, sort_order, created_at,
+                    updated_at
+             FROM user_profile_fields WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_error("finding profile field", e))
+    }
+
+    pub async fn key_exists(pool: &SqlitePool, key: &str) -> Result<bool, ServiceError> {
+        sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM user_profile_fields WHERE key = ?)",
+        )
+        .bind(key)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_error("checking profile field key", e))
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        key: &str,
+        label: &str,
+        field_type: &str,
+        required: bool,
+        options: &str,
+        sort_order: i32,
+    ) -> Result<i64, ServiceError> {
+        let now = Utc::now().naive_utc();
+        sqlx::query_scalar::<_, i64>(
+            "INSERT INTO user_profile_fields
+                (key, label, field_type, required, options, sort_order, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+             RETURNING id",
+        )
+        .bind(key)
+        .bind(label)
+        .bind(field_type)
+        .bind(required)
+        .bind(options)
+        .bind(sort_order)
+        .bind(now)
+        .bind(now)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_error("creating profile field", e))
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: i64,
+        label: &str,
+        required: bool,
+        options: &str,
+        sort_order: i32,
+    ) -> Result<bool, ServiceError> {
+        let result = sqlx::query(
+            "UPDATE user_profile_fields
+             SET label = ?, required = ?, options = ?, sort_order = ?, updated_at = ?
+             WHERE id = ?",
+        )
+        .bind(label)
+        .bind(required)
+        .bind(options)
+        .bind(sort_order)
+        .bind(Utc::now().naive_utc())
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| db_error("updating profile field", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Deletes a definition and drops its value from every user's profile.
+    pub async fn delete(pool: &SqlitePool, id: i64) -> Result<bool, ServiceError> {
+        let mut tx = tx::begin(pool).await?;
+        let key = sqlx::query_scalar::<_, String>(
+            "DELETE FROM user_profile_fields WHERE id = ? RETURNING key",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| db_error("deleting profile field", e))?;
+        let Some(key) = key else {
+            return Ok(false);
+        };
+        sqlx::query(
+            "UPDATE users SET profile = json_remove(profile, ?)
+             WHERE json_type(profile, ?) IS NOT NULL",
+        )
+        .bind(json_path(&key))
+        .bind(json_path(&key))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_error("clearing profile field values", e))?;
+        tx::commit(tx).await?;
+        Ok(true)
+    }
+}
+
+/// JSON path of a top-level profile key; keys are validated to need no quoting.
+fn json_path(key: &str) -> String {
+    format!("$.{key}")
+}
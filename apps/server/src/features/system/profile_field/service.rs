@@ -0,0 +1,362 @@
+use super::{
+    repo::ProfileFieldRepository,
+    types::{
+        CreateProfileFieldRequest, ProfileField, ProfileFieldResp, ProfileFieldType,
+        UpdateProfileFieldPayload,
+    },
+};
+use crate::common::{error::ServiceError, validation::FieldErrors};
+
+use chrono::NaiveDate;
+use serde_json::{Map, Value};
+use sqlx::SqlitePool;
+use std::collections::BTreeSet;
+
+const KEY_MAX_LEN: usize = 32;
+const LABEL_MAX_LEN: usize = 64;
+const TEXT_MAX_LEN: usize = 200;
+const MAX_OPTIONS: usize = 50;
+
+pub struct ProfileFieldService;
+
+impl ProfileFieldService {
+    pub async fn list_fields(pool: &SqlitePool) -> Result<Vec<ProfileFieldResp>, ServiceError> {
+        let rows = ProfileFieldRepository::list_fields(pool).await?;
+        Ok(rows.into_iter().map(ProfileFieldResp::from).collect())
+    }
+
+    /// Definitions user profiles are validated against.
+    pub async fn definitions(pool: &SqlitePool) -> Result<Vec<ProfileField>, ServiceError> {
+        ProfileFieldRepository::list_fields(pool)
+            .await?
+            .iter()
+            .map(|row| {
+                ProfileField::try_from(row).map_err(|e| {
+                    tracing::error!("{}", e);
+                    ServiceError::DatabaseQueryFailed
+                })
+            })
+            .collect()
+    }
+
+    pub async fn create_field(
+        pool: &SqlitePool,
+        request: CreateProfileFieldRequest,
+    ) -> Result<i64, ServiceError> {
+        let key = request.key.trim().to_string();
+        let label = request.label.trim().to_string();
+        let field_type = ProfileFieldType::parse(request.field_type.trim());
+        let mut errors = FieldErrors::new();
+        if let Err(message) = validate_key(&key) {
+            errors.push("key", message);
+        }
+        check_label(&mut errors, &label);
+        let options = match field_type {
+            Some(field_type) => check_options(&mut errors, field_type, request.options),
+            None => {
+                errors.push("fieldType", "must be one of text, number, boolean, date, select");
+                Vec::new()
+            }
+        };
+        errors.into_result()?;
+        let field_type = field_type.expect("checked above");
+        if ProfileFieldRepository::key_exists(pool, &key).await? {
+            return Err(ServiceError::InvalidOperation(format!(
+                "Profile field '{}' already exists",
+                key
+            )));
+        }
+        tracing::info!("Creating {} profile field '{}'", field_type.as_str(), key);
+        ProfileFieldRepository::create(
+            pool,
+            &key,
+            &label,
+            field_type.as_str(),
+            request.required,
+            &options_json(&options),
+            request.sort_order,
+        )
+        .await
+    }
+
+    pub async fn update_field(
+        pool: &SqlitePool,
+        id: i64,
+        request: UpdateProfileFieldPayload,
+    ) -> Result<(), ServiceError> {
+        let existing = ProfileFieldRepository::find_by_id(pool, id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound("Profile field".to_string()))?;
+        let field_type = ProfileFieldType::parse(&existing.field_type).ok_or_else(|| {
+            tracing::error!("Unknown type {} of profile field {}", existing.field_type, id);
+            ServiceError::DatabaseQueryFailed
+        })?;
+        let label = request.label.trim().to_string();
+        let mut errors = FieldErrors::new();
+        check_label(&mut errors, &label);
+        let options = check_options(&mut errors, field_type, request.options);
+        errors.into_result()?;
+        tracing::info!("Updating profile field {} ('{}')", id, existing.key);
+        let updated = ProfileFieldRepository::update(
+            pool,
+            id,
+            &label,
+            request.required,
+            &options_json(&options),
+            request.sort_order,
+        )
+        .await?;
+        if !updated {
+            return Err(ServiceError::NotFound("Profile field".to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn delete_field(pool: &SqlitePool, id: i64) -> Result<(), ServiceError> {
+        tracing::info!("Deleting profile field {}", id);
+        if !ProfileFieldRepository::delete(pool, id).await? {
+            return Err(ServiceError::NotFound("Profile field".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Checks a profile sent by a client against `fields` and returns the value to store.
+///
+/// Unknown keys and values of the wrong type are rejected, `null` clears a value, text is
+/// trimmed and required fields must be present. Problems are recorded as `profile.<key>`.
+pub fn check_profile(
+    errors: &mut FieldErrors,
+    fields: &[ProfileField],
+    profile: Map<String, Value>,
+) -> Map<String, Value> {
+    let mut stored = Map::new();
+    for (key, value) in profile {
+        let name = format!("profile.{key}");
+        let Some(field) = fields.iter().find(|f| f.key == key) else {
+            errors.push(&name, "is not a profile field");
+            continue;
+        };
+        if value.is_null() {
+            continue;
+        }
+        match check_value(field, value) {
+            Ok(Some(value)) => {
+                stored.insert(key, value);
+            }
+            Ok(None) => {}
+            Err(message) => errors.push(&name, message),
+        }
+    }
+    for field in fields.iter().filter(|f| f.required) {
+        if !stored.contains_key(&field.key) {
+            errors.push(&format!("profile.{}", field.key), "is required");
+        }
+    }
+    stored
+}
+
+/// Parses a `key:value,key:value` list filter; values compare as text.
+pub fn parse_profile_filter(filter: Option<&str>) -> Result<Vec<(String, String)>, ServiceError> {
+    let mut errors = FieldErrors::new();
+    let mut pairs = Vec::new();
+    for pair in filter.unwrap_or_default().split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        match pair.split_once(':') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                pairs.push((key.trim().to_string(), value.trim().to_string()));
+            }
+            _ => errors.push("profile", format!("'{}' must be written as key:value", pair)),
+        }
+    }
+    errors.into_result()?;
+    Ok(pairs)
+}
+
+/// Normalized value, `None` for blank text.
+fn check_value(field: &ProfileField, value: Value) -> Result<Option<Value>, String> {
+    match field.field_type {
+        ProfileFieldType::Text => {
+            let Value::String(text) = value else {
+                return Err("must be a string".to_string());
+            };
+            let text = text.trim();
+            if text.chars().count() > TEXT_MAX_LEN {
+                return Err(format!("must be at most {} characters", TEXT_MAX_LEN));
+            }
+            Ok((!text.is_empty()).then(|| Value::String(text.to_string())))
+        }
+        ProfileFieldType::Number => match value {
+            Value::Number(_) => Ok(Some(value)),
+            _ => Err("must be a number".to_string()),
+        },
+        ProfileFieldType::Boolean => match value {
+            Value::Bool(_) => Ok(Some(value)),
+            _ => Err("must be true or false".to_string()),
+        },
+        ProfileFieldType::Date => match &value {
+            Value::String(date) if NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok() => {
+                Ok(Some(value))
+            }
+            _ => Err("must be a date such as 2026-01-31".to_string()),
+        },
+        ProfileFieldType::Select => match &value {
+            Value::String(option) if field.options.contains(option) => Ok(Some(value)),
+            _ => Err(format!("must be one of {}", field.options.join(", "))),
+        },
+    }
+}
+
+/// Keys are snake case (`employee_id`) so they double as JSON paths.
+fn validate_key(key: &str) -> Result<(), String> {
+    if key.is_empty() || key.len() > KEY_MAX_LEN {
+        return Err(format!("must be 1-{} characters", KEY_MAX_LEN));
+    }
+    let valid = key.starts_with(|c: char| c.is_ascii_lowercase())
+        && key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err("must start with a letter and use only a-z, 0-9 and '_'".to_string())
+    }
+}
+
+fn check_label(errors: &mut FieldErrors, label: &str) {
+    if label.is_empty() || label.chars().count() > LABEL_MAX_LEN {
+        errors.push("label", format!("must be 1-{} characters", LABEL_MAX_LEN));
+    }
+}
+
+/// Trimmed, deduplicated options; only `select` fields take any.
+fn check_options(
+    errors: &mut FieldErrors,
+    field_type: ProfileFieldType,
+    options: Vec<String>,
+) -> Vec<String> {
+    let mut seen = BTreeSet::new();
+    let options: Vec<String> = options
+        .into_iter()
+        .map(|o| o.trim().to_string())
+        .filter(|o| !o.is_empty() && seen.insert(o.clone()))
+        .collect();
+    if field_type == ProfileFieldType::Select {
+        if options.is_empty() || options.len() > MAX_OPTIONS {
+            errors.push("options", format!("must hold 1-{} values", MAX_OPTIONS));
+        }
+    } else if !options.is_empty() {
+        errors.push("options", "only apply to select fields");
+    }
+    options
+}
+
+fn options_json(options: &[String]) -> String {
+    serde_json::to_string(options).unwrap_or_else(|_| "[]".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ProfileField, ProfileFieldType, check_profile, parse_profile_filter, validate_key,
+    };
+    use crate::common::validation::FieldErrors;
+
+    use serde_json::{Map, Value, json};
+
+    fn fields() -> Vec<ProfileField> {
+        let field = |key: &str, field_type, required| ProfileField {
+            key: key.to_string(),
+            field_type,
+            required,
+            options: Vec::new(),
+        };
+        vec![
+            field("employee_id", ProfileFieldType::Text, true),
+            field("level", ProfileFieldType::Number, false),
+            field("remote", ProfileFieldType::Boolean, false),
+            field("joined_on", ProfileFieldType::Date, false),
+            ProfileField {
+                options: vec!["north".to_string(), "south".to_string()],
+                ..field("region", ProfileFieldType::Select, false)
+            },
+        ]
+    }
+
+    /// Stored profile and the names of the fields reported invalid.
+    fn check(profile: Value) -> (Map<String, Value>, Vec<String>) {
+        let mut errors = FieldErrors::new();
+        let stored = check_profile(&mut errors, &fields(), profile.as_object().cloned().unwrap());
+        let invalid = match errors.into_result() {
+            Ok(()) => Vec::new(),
+            Err(crate::common::error::ServiceError::InvalidFields(errors)) => {
+                errors.into_iter().map(|e| e.field).collect()
+            }
+            Err(other) => panic!("expected field errors, got {:?}", other),
+        };
+        (stored, invalid)
+    }
+
+    #[test]
+    fn accepts_typed_values_and_drops_nulls() {
+        let (stored, invalid) = check(json!({
+            "employee_id": "  E-1001 ",
+            "level": null,
+            "remote": true,
+            "joined_on": "2026-01-31",
+            "region": "north",
+        }));
+        assert!(invalid.is_empty(), "{:?}", invalid);
+        assert_eq!(stored["employee_id"], "E-1001");
+        assert!(!stored.contains_key("level"));
+        assert_eq!(stored["remote"], true);
+        assert_eq!(stored["region"], "north");
+    }
+
+    #[test]
+    fn rejects_wrong_types_and_missing_required_fields() {
+        let (_, invalid) = check(json!({
+            "level": "3",
+            "remote": "yes",
+            "joined_on": "31/01/2026",
+            "region": "east",
+            "team": "core",
+        }));
+        assert_eq!(
+            invalid,
+            [
+                "profile.joined_on",
+                "profile.level",
+                "profile.region",
+                "profile.remote",
+                "profile.team",
+                "profile.employee_id",
+            ]
+        );
+        let (_, invalid) = check(json!({ "employee_id": " " }));
+        assert_eq!(invalid, ["profile.employee_id"]);
+    }
+
+    #[test]
+    fn keys_are_snake_case() {
+        assert!(validate_key("employee_id").is_ok());
+        assert!(validate_key("level2").is_ok());
+        assert!(validate_key("").is_err());
+        assert!(validate_key("Employee").is_err());
+        assert!(validate_key("cost-center").is_err());
+        assert!(validate_key("2fa").is_err());
+        assert!(validate_key(&"a".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn list_filters_are_key_value_pairs() {
+        let pairs = parse_profile_filter(Some(" region:north, remote:true ,")).unwrap();
+        assert_eq!(
+            pairs,
+            [
+                ("region".to_string(), "north".to_string()),
+                ("remote".to_string(), "true".to_string())
+            ]
+        );
+        assert!(parse_profile_filter(None).unwrap().is_empty());
+        assert!(parse_profile_filter(Some("region")).is_err());
+        assert!(parse_profile_filter(Some(":north")).is_err());
+    }
+}
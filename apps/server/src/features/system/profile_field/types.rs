@@ -0,0 +1,135 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Value type of a profile field, stored in `user_profile_fields.field_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileFieldType {
+    /// Up to 200 characters.
+    Text,
+    Number,
+    Boolean,
+    /// `YYYY-MM-DD`.
+    Date,
+    /// One of the field's `options`.
+    Select,
+}
+
+impl ProfileFieldType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProfileFieldType::Text => "text",
+            ProfileFieldType::Number => "number",
+            ProfileFieldType::Boolean => "boolean",
+            ProfileFieldType::Date => "date",
+            ProfileFieldType::Select => "select",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "text" => Some(ProfileFieldType::Text),
+            "number" => Some(ProfileFieldType::Number),
+            "boolean" => Some(ProfileFieldType::Boolean),
+            "date" => Some(ProfileFieldType::Date),
+            "select" => Some(ProfileFieldType::Select),
+            _ => None,
+        }
+    }
+}
+
+/// Profile field definition row as read from the database.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ProfileFieldRow {
+    pub id: i64,
+    pub key: String,
+    pub label: String,
+    pub field_type: String,
+    pub required: bool,
+    /// JSON array of strings.
+    pub options: String,
+    pub sort_order: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Definition a profile value is checked against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileField {
+    pub key: String,
+    pub field_type: ProfileFieldType,
+    pub required: bool,
+    pub options: Vec<String>,
+}
+
+impl TryFrom<&ProfileFieldRow> for ProfileField {
+    type Error = String;
+
+    fn try_from(row: &ProfileFieldRow) -> Result<Self, Self::Error> {
+        let field_type = ProfileFieldType::parse(&row.field_type)
+            .ok_or_else(|| format!("unknown profile field type {}", row.field_type))?;
+        let options = serde_json::from_str(&row.options)
+            .map_err(|e| format!("invalid options of profile field {}: {}", row.key, e))?;
+        Ok(Self { key: row.key.clone(), field_type, required: row.required, options })
+    }
+}
+
+/// Profile field definition
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileFieldResp {
+    pub id: i64,
+    /// Key of the value in a user's `profile`.
+    pub key: String,
+    pub label: String,
+    /// `text`, `number`, `boolean`, `date` or `select`.
+    pub field_type: String,
+    pub required: bool,
+    /// Allowed values of a `select` field.
+    pub options: Vec<String>,
+    pub sort_order: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<ProfileFieldRow> for ProfileFieldResp {
+    fn from(row: ProfileFieldRow) -> Self {
+        Self {
+            options: serde_json::from_str(&row.options).unwrap_or_default(),
+            id: row.id,
+            key: row.key,
+            label: row.label,
+            field_type: row.field_type,
+            required: row.required,
+            sort_order: row.sort_order,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+/// Create profile field request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateProfileFieldRequest {
+    pub key: String,
+    pub label: String,
+    pub field_type: String,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub sort_order: i32,
+}
+
+/// Update profile field request; the key and type are fixed once values are stored.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateProfileFieldPayload {
+    pub label: String,
+    pub required: bool,
+    #[serde(default)]
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub sort_order: i32,
+}
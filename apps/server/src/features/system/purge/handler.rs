@@ -0,0 +1,135 @@
+use super::{
+    service::PurgeService,
+    types::{PurgeReport, PurgeTarget},
+};
+use crate::{
+    common::{
+        api::{ApiResponse, AppResult},
+        ids::{MenuId, RoleId, UserId},
+    },
+    features::{
+        auth::service::AuthService,
+        system::{
+            approval::{service::ApprovalService, types::ApprovalAction},
+            confirmation::{service::ConfirmationService, types::ConfirmationTarget},
+        },
+    },
+    infra::{config::AppConfig, db::DbExecutor},
+};
+
+use axum::{
+    Extension,
+    extract::{Path, State},
+    http::HeaderMap,
+};
+use rustzen_core::auth::{AuthClaims, CurrentUser};
+use sqlx::SqlitePool;
+use tracing::instrument;
+
+/// Dry run of purging a deleted user: the rows and files that would go or stay
+pub async fn get_user_purge_report(
+    Extension(config): Extension<&'static AppConfig>,
+    State(db): State<DbExecutor>,
+    Path(id): Path<UserId>,
+) -> AppResult<PurgeReport> {
+    Ok(ApiResponse::success(PurgeService::report(config, db.read(), PurgeTarget::User(id)).await?))
+}
+
+/// Permanently remove a soft-deleted user with its account rows and files, with a
+/// confirmation token for that user and after approval under dual control
+#[instrument(skip(current_user, config, claims, headers, pool, id))]
+pub async fn purge_user(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    Extension(claims): Extension<AuthClaims>,
+    headers: HeaderMap,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<UserId>,
+) -> AppResult<PurgeReport> {
+    AuthService::require_recent_auth(config, &claims)?;
+    let target = ConfirmationTarget::UserPurge { user_id: id };
+    ConfirmationService::consume(&pool, &current_user, &headers, &target).await?;
+    ApprovalService::require(
+        config,
+        &pool,
+        &current_user,
+        ApprovalAction::UserPurge { user_id: id },
+    )
+    .await?;
+    Ok(ApiResponse::success(PurgeService::purge(config, &pool, PurgeTarget::User(id)).await?))
+}
+
+/// Dry run of purging a deleted role
+pub async fn get_role_purge_report(
+    Extension(config): Extension<&'static AppConfig>,
+    State(db): State<DbExecutor>,
+    Path(id): Path<RoleId>,
+) -> AppResult<PurgeReport> {
+    Ok(ApiResponse::success(PurgeService::report(config, db.read(), PurgeTarget::Role(id)).await?))
+}
+
+/// Permanently remove a soft-deleted role with its assignments and grants
+#[instrument(skip(current_user, config, claims, headers, pool, id))]
+pub async fn purge_role(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    Extension(claims): Extension<AuthClaims>,
+    headers: HeaderMap,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<RoleId>,
+) -> AppResult<PurgeReport> {
+    AuthService::require_recent_auth(config, &claims)?;
+    let target = ConfirmationTarget::RolePurge { role_id: id };
+    ConfirmationService::consume(&pool, &current_user, &headers, &target).await?;
+    Ok(ApiResponse::success(PurgeService::purge(config, &pool, PurgeTarget::Role(id)).await?))
+}
+
+/// Dry run of purging a disabled menu
+pub async fn get_menu_purge_report(
+    Extension(config): Extension<&'static AppConfig>,
+    State(db): State<DbExecutor>,
+    Path(id): Path<MenuId>,
+) -> AppResult<PurgeReport> {
+    Ok(ApiResponse::success(PurgeService::report(config, db.read(), PurgeTarget::Menu(id)).await?))
+}
+
+/// Permanently remove a disabled custom menu with its grants and translations
+#[instrument(skip(current_user, config, claims, headers, pool, id))]
+pub async fn purge_menu(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    Extension(claims): Extension<AuthClaims>,
+    headers: HeaderMap,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<MenuId>,
+) -> AppResult<PurgeReport> {
+    AuthService::require_recent_auth(config, &claims)?;
+    let target = ConfirmationTarget::MenuPurge { menu_id: id };
+    ConfirmationService::consume(&pool, &current_user, &headers, &target).await?;
+    Ok(ApiResponse::success(PurgeService::purge(config, &pool, PurgeTarget::Menu(id)).await?))
+}
+
+/// Dry run of purging a deleted dictionary item
+pub async fn get_dict_purge_report(
+    Extension(config): Extension<&'static AppConfig>,
+    State(db): State<DbExecutor>,
+    Path(id): Path<i64>,
+) -> AppResult<PurgeReport> {
+    Ok(ApiResponse::success(PurgeService::report(config, db.read(), PurgeTarget::Dict(id)).await?))
+}
+
+/// Permanently remove a soft-deleted dictionary item with its translations
+#[instrument(skip(current_user, config, claims, headers, pool, id))]
+pub async fn purge_dict(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    Extension(claims): Extension<AuthClaims>,
+    headers: HeaderMap,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+) -> AppResult<PurgeReport> {
+    AuthService::require_recent_auth(config, &claims)?;
+    let target = ConfirmationTarget::DictPurge { dict_id: id };
+    ConfirmationService::consume(&pool, &current_user, &headers, &target).await?;
+    Ok(ApiResponse::success(PurgeService::purge(config, &pool, PurgeTarget::Dict(id)).await?))
+}
@@ -0,0 +1,4 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
@@ -0,0 +1,121 @@
+use super::types::{CascadeRef, PurgeTarget};
+use crate::common::{error::ServiceError, ids::UserId, tx::Tx};
+
+use sqlx::{QueryBuilder, Sqlite};
+
+pub struct PurgeRepository;
+
+fn db_error(context: &str, e: sqlx::Error) -> ServiceError {
+    tracing::error!("Database error {}: {:?}", context, e);
+    ServiceError::DatabaseQueryFailed
+}
+
+// Table and column names below come from `PurgeTarget` and its `CascadeRef`s, never from
+// the request.
+impl PurgeRepository {
+    /// Name of the record when it can be purged.
+    pub async fn find_purgeable(
+        tx: &mut Tx<'_>,
+        target: PurgeTarget,
+    ) -> Result<Option<String>, ServiceError> {
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT ");
+        query
+            .push(target.name_column())
+            .push(" FROM ")
+            .push(target.table())
+            .push(" WHERE id = ")
+            .push_bind(target.id())
+            .push(" AND ")
+            .push(target.purgeable());
+        query
+            .build_query_scalar::<String>()
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(|e| db_error("finding purgeable record", e))
+    }
+
+    pub async fn count_references(
+        tx: &mut Tx<'_>,
+        target: PurgeTarget,
+        reference: &CascadeRef,
+    ) -> Result<i64, ServiceError> {
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM ");
+        push_reference_filter(&mut query, target, reference);
+        query
+            .build_query_scalar::<i64>()
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(|e| db_error(&format!("counting {} rows", reference.table), e))
+    }
+
+    pub async fn delete_references(
+        tx: &mut Tx<'_>,
+        target: PurgeTarget,
+        reference: &CascadeRef,
+    ) -> Result<(), ServiceError> {
+        let mut query = QueryBuilder::<Sqlite>::new("DELETE FROM ");
+        push_reference_filter(&mut query, target, reference);
+        query
+            .build()
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| db_error(&format!("purging {} rows", reference.table), e))?;
+        Ok(())
+    }
+
+    /// Deletes the record itself; false when it is no longer purgeable.
+    pub async fn delete_target(tx: &mut Tx<'_>, target: PurgeTarget) -> Result<bool, ServiceError> {
+        let mut query = QueryBuilder::<Sqlite>::new("DELETE FROM ");
+        query
+            .push(target.table())
+            .push(" WHERE id = ")
+            .push_bind(target.id())
+            .push(" AND ")
+            .push(target.purgeable());
+        let result = query
+            .build()
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| db_error(&format!("purging {} {}", target.kind(), target.id()), e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The user's avatar URL and the stored names of their finished export files.
+    pub async fn user_files(
+        tx: &mut Tx<'_>,
+        id: UserId,
+    ) -> Result<(Option<String>, Vec<String>), ServiceError> {
+        let avatar_url: Option<String> =
+            sqlx::query_scalar("SELECT avatar_url FROM users WHERE id = ?")
+                .bind(id)
+                .fetch_optional(&mut **tx)
+                .await
+                .map_err(|e| db_error("reading user avatar", e))?
+                .flatten();
+        let stored_names = sqlx::query_scalar(
+            "SELECT stored_name FROM export_jobs
+             WHERE user_id = ? AND status = 'completed' ORDER BY id",
+        )
+        .bind(id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| db_error("listing user export files", e))?;
+        Ok((avatar_url, stored_names))
+    }
+}
+
+fn push_reference_filter(
+    query: &mut QueryBuilder<Sqlite>,
+    target: PurgeTarget,
+    reference: &CascadeRef,
+) {
+    query
+        .push(reference.table)
+        .push(" WHERE ")
+        .push(reference.column)
+        .push(" = ")
+        .push_bind(target.id());
+    if let Some(only) = reference.only {
+        query.push(" AND ").push(only);
+    }
+}
@@ -0,0 +1,139 @@
+use super::{
+    repo::PurgeRepository,
+    types::{CascadeAction, CascadeItem, PurgeFile, PurgeReport, PurgeTarget},
+};
+use crate::{
+    common::{
+        error::ServiceError,
+        files::{avatar_file_name, remove_avatar, remove_export_file},
+        tx::{self, Tx},
+    },
+    infra::config::AppConfig,
+};
+
+use sqlx::SqlitePool;
+
+/// Hard deletes of users, roles, menus and dictionary items.
+///
+/// Each target lists the columns elsewhere that hold its id. [`report`](Self::report) counts
+/// them as a dry run; [`purge`](Self::purge) counts again and deletes the `delete` rows and
+/// the record in one transaction, refusing while any `block` rows remain. Stored files go
+/// after the commit, so a failed purge leaves them in place.
+pub struct PurgeService;
+
+impl PurgeService {
+    /// What purging `target` would remove and keep, without changing anything.
+    pub async fn report(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        target: PurgeTarget,
+    ) -> Result<PurgeReport, ServiceError> {
+        let mut tx = tx::begin(pool).await?;
+        let (report, _) = Self::build_report(config, &mut tx, target).await?;
+        Ok(report)
+    }
+
+    /// Deletes `target` with its `delete` references and files, and returns what went.
+    pub async fn purge(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        target: PurgeTarget,
+    ) -> Result<PurgeReport, ServiceError> {
+        tracing::debug!("Purging {} ID: {}", target.kind(), target.id());
+        let mut tx = tx::begin(pool).await?;
+        let (report, files) = Self::build_report(config, &mut tx, target).await?;
+        if let Some(blocker) = report
+            .references
+            .iter()
+            .find(|item| item.action == CascadeAction::Block && item.rows > 0)
+        {
+            return Err(ServiceError::InvalidOperation(format!(
+                "Cannot purge {} {} while it has {} {}",
+                target.kind(),
+                target.id(),
+                blocker.rows,
+                blocker.label
+            )));
+        }
+
+        for reference in target.references() {
+            if reference.action == CascadeAction::Delete {
+                PurgeRepository::delete_references(&mut tx, target, reference).await?;
+            }
+        }
+        if !PurgeRepository::delete_target(&mut tx, target).await? {
+            return Err(ServiceError::NotFound(target.not_found()));
+        }
+        tx::commit(tx).await?;
+
+        if let Some(avatar_url) = &files.avatar_url {
+            remove_avatar(config, avatar_url).await;
+        }
+        for stored_name in &files.exports {
+            remove_export_file(config, stored_name).await;
+        }
+        tracing::info!(kind = target.kind(), id = target.id(), name = %report.name, "Purged record");
+        Ok(report)
+    }
+
+    async fn build_report(
+        config: &AppConfig,
+        tx: &mut Tx<'_>,
+        target: PurgeTarget,
+    ) -> Result<(PurgeReport, StoredFiles), ServiceError> {
+        let name = PurgeRepository::find_purgeable(tx, target)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound(target.not_found()))?;
+
+        let mut references = Vec::new();
+        for reference in target.references() {
+            references.push(CascadeItem {
+                table: reference.table,
+                column: reference.column,
+                label: reference.label,
+                action: reference.action,
+                rows: PurgeRepository::count_references(tx, target, reference).await?,
+            });
+        }
+        let can_purge =
+            !references.iter().any(|item| item.action == CascadeAction::Block && item.rows > 0);
+
+        let files = match target {
+            PurgeTarget::User(id) => {
+                let (avatar_url, exports) = PurgeRepository::user_files(tx, id).await?;
+                StoredFiles {
+                    avatar_url: avatar_url.filter(|url| avatar_file_name(config, url).is_some()),
+                    exports,
+                }
+            }
+            _ => StoredFiles::default(),
+        };
+        let mut report_files = Vec::new();
+        if let Some(file_name) =
+            files.avatar_url.as_deref().and_then(|url| avatar_file_name(config, url))
+        {
+            report_files.push(PurgeFile { kind: "avatar", name: file_name.to_string() });
+        }
+        report_files.extend(
+            files.exports.iter().map(|name| PurgeFile { kind: "export", name: name.clone() }),
+        );
+
+        let report = PurgeReport {
+            kind: target.kind(),
+            id: target.id(),
+            name,
+            references,
+            files: report_files,
+            can_purge,
+        };
+        Ok((report, files))
+    }
+}
+
+/// Files removed once a user's purge commits.
+#[derive(Default)]
+struct StoredFiles {
+    /// Only set for avatars uploaded here.
+    avatar_url: Option<String>,
+    exports: Vec<String>,
+}
@@ -0,0 +1,186 @@
+use crate::common::ids::{MenuId, RoleId, UserId};
+
+use serde::Serialize;
+
+/// Record removed for good by a purge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PurgeTarget {
+    User(UserId),
+    Role(RoleId),
+    Menu(MenuId),
+    /// `dicts.id`
+    Dict(i64),
+}
+
+impl PurgeTarget {
+    pub fn kind(self) -> &'static str {
+        match self {
+            PurgeTarget::User(_) => "user",
+            PurgeTarget::Role(_) => "role",
+            PurgeTarget::Menu(_) => "menu",
+            PurgeTarget::Dict(_) => "dict",
+        }
+    }
+
+    pub fn id(self) -> i64 {
+        match self {
+            PurgeTarget::User(id) => id.get(),
+            PurgeTarget::Role(id) => id.get(),
+            PurgeTarget::Menu(id) => id.get(),
+            PurgeTarget::Dict(id) => id,
+        }
+    }
+
+    pub fn table(self) -> &'static str {
+        match self {
+            PurgeTarget::User(_) => "users",
+            PurgeTarget::Role(_) => "roles",
+            PurgeTarget::Menu(_) => "menus",
+            PurgeTarget::Dict(_) => "dicts",
+        }
+    }
+
+    /// Column shown as the record's name in the report.
+    pub fn name_column(self) -> &'static str {
+        match self {
+            PurgeTarget::User(_) => "username",
+            PurgeTarget::Role(_) | PurgeTarget::Menu(_) => "name",
+            PurgeTarget::Dict(_) => "label",
+        }
+    }
+
+    /// Which rows may be purged: those already deleted, or for menus, whose delete only
+    /// disables them, disabled custom ones.
+    pub fn purgeable(self) -> &'static str {
+        match self {
+            PurgeTarget::Menu(_) => "is_system = false AND (status = 2 OR deleted_at IS NOT NULL)",
+            _ => "deleted_at IS NOT NULL",
+        }
+    }
+
+    /// What "not purgeable" is reported as.
+    pub fn not_found(self) -> String {
+        match self {
+            PurgeTarget::Menu(id) => format!("Disabled menu id: {}", id),
+            target => format!("Deleted {} id: {}", target.kind(), target.id()),
+        }
+    }
+
+    /// Rows elsewhere that hold this record's id.
+    pub fn references(self) -> &'static [CascadeRef] {
+        match self {
+            PurgeTarget::User(_) => USER_REFERENCES,
+            PurgeTarget::Role(_) => ROLE_REFERENCES,
+            PurgeTarget::Menu(_) => MENU_REFERENCES,
+            PurgeTarget::Dict(_) => DICT_REFERENCES,
+        }
+    }
+}
+
+/// What a purge does with rows that point at the purged record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CascadeAction {
+    /// Deleted in the same transaction.
+    Delete,
+    /// Left in place; audit records outlive the record they name.
+    Keep,
+    /// Stops the purge until they are dealt with.
+    Block,
+}
+
+/// A column holding the purged record's id, with what happens to the matching rows.
+#[derive(Debug, Clone, Copy)]
+pub struct CascadeRef {
+    pub table: &'static str,
+    pub column: &'static str,
+    /// Extra condition on the rows that count, e.g. only pending tasks.
+    pub only: Option<&'static str>,
+    pub label: &'static str,
+    pub action: CascadeAction,
+}
+
+const fn cascade(
+    table: &'static str,
+    column: &'static str,
+    label: &'static str,
+    action: CascadeAction,
+) -> CascadeRef {
+    CascadeRef { table, column, only: None, label, action }
+}
+
+const USER_REFERENCES: &[CascadeRef] = &[
+    cascade("user_roles", "user_id", "role assignments", CascadeAction::Delete),
+    cascade("user_tags", "user_id", "tags", CascadeAction::Delete),
+    cascade("user_identities", "user_id", "linked sign-in identities", CascadeAction::Delete),
+    cascade("user_login_sources", "user_id", "known login sources", CascadeAction::Delete),
+    cascade("email_verification_tokens", "user_id", "email tokens", CascadeAction::Delete),
+    cascade("email_change_requests", "user_id", "email changes", CascadeAction::Delete),
+    cascade("notifications", "user_id", "notifications", CascadeAction::Delete),
+    cascade("saved_filters", "user_id", "saved filters", CascadeAction::Delete),
+    cascade("export_jobs", "user_id", "export jobs", CascadeAction::Delete),
+    cascade("action_confirmations", "user_id", "confirmation tokens", CascadeAction::Delete),
+    cascade("debug_captures", "user_id", "debug captures", CascadeAction::Delete),
+    cascade("policy_consents", "user_id", "policy consents", CascadeAction::Keep),
+    cascade("user_role_history", "user_id", "role history", CascadeAction::Keep),
+    cascade("operation_logs", "user_id", "operation logs", CascadeAction::Keep),
+    cascade("approvals", "requested_by", "approval requests", CascadeAction::Keep),
+    cascade("workflow_instances", "started_by", "workflow instances", CascadeAction::Keep),
+];
+
+const ROLE_REFERENCES: &[CascadeRef] = &[
+    cascade("user_roles", "role_id", "role assignments", CascadeAction::Delete),
+    cascade("role_menus", "role_id", "menu grants", CascadeAction::Delete),
+    cascade("role_tags", "role_id", "tags", CascadeAction::Delete),
+    cascade("role_access_restrictions", "role_id", "access restrictions", CascadeAction::Delete),
+    cascade("user_role_history", "role_id", "role history", CascadeAction::Keep),
+    CascadeRef {
+        table: "workflow_tasks",
+        column: "approver_role_id",
+        only: Some("status = 'pending'"),
+        label: "pending workflow tasks",
+        action: CascadeAction::Block,
+    },
+];
+
+const MENU_REFERENCES: &[CascadeRef] = &[
+    cascade("role_menus", "menu_id", "role grants", CascadeAction::Delete),
+    cascade("menu_i18n", "menu_id", "translations", CascadeAction::Delete),
+    cascade("menus", "parent_id", "child menus", CascadeAction::Block),
+];
+
+const DICT_REFERENCES: &[CascadeRef] =
+    &[cascade("dict_i18n", "dict_id", "translations", CascadeAction::Delete)];
+
+/// Rows in one table that hold the purged record's id.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CascadeItem {
+    pub table: &'static str,
+    pub column: &'static str,
+    pub label: &'static str,
+    pub action: CascadeAction,
+    pub rows: i64,
+}
+
+/// A stored file removed with the record.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeFile {
+    /// `avatar` or `export`.
+    pub kind: &'static str,
+    pub name: String,
+}
+
+/// What purging a record removes and keeps; the dry run and the purge answer the same.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeReport {
+    pub kind: &'static str,
+    pub id: i64,
+    pub name: String,
+    pub references: Vec<CascadeItem>,
+    pub files: Vec<PurgeFile>,
+    /// False while any `block` reference has rows.
+    pub can_purge: bool,
+}
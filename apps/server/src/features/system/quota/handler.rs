@@ -0,0 +1,16 @@
+use super::{service::QuotaService, types::UsageResp};
+use crate::{
+    common::api::{ApiResponse, AppResult},
+    infra::config::AppConfig,
+};
+
+use axum::{Extension, extract::State};
+use sqlx::SqlitePool;
+
+/// Users, roles and upload storage against their configured quotas.
+pub async fn get_usage(
+    Extension(config): Extension<&'static AppConfig>,
+    State(pool): State<SqlitePool>,
+) -> AppResult<UsageResp> {
+    Ok(ApiResponse::success(QuotaService::usage(config, &pool).await?))
+}
@@ -0,0 +1,19 @@
+pub mod handler;
+pub mod service;
+pub mod types;
+
+use axum::{Router, routing::get};
+use handler::get_usage;
+use rustzen_core::{
+    capability::system_usage,
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+pub fn usage_routes() -> Router<SqlitePool> {
+    Router::new().route_with_permission(
+        "/",
+        get(get_usage),
+        PermissionsCheck::Require(system_usage::VIEW),
+    )
+}
@@ -0,0 +1,121 @@
+use super::types::{QuotaResource, QuotaUsageResp, UsageResp};
+use crate::{
+    common::error::ServiceError,
+    features::system::{role::repo::RoleRepository, user::repo::UserRepository},
+    infra::config::AppConfig,
+};
+
+use sqlx::SqlitePool;
+use std::path::Path;
+
+pub struct QuotaService;
+
+impl QuotaService {
+    /// Checks there is room for one more `resource`; `count` only runs when a limit is set.
+    ///
+    /// The count and the insert are not atomic, so concurrent creates can overshoot by the
+    /// number of requests in flight.
+    pub async fn ensure_slot(
+        config: &AppConfig,
+        resource: QuotaResource,
+        count: impl Future<Output = Result<i64, ServiceError>>,
+    ) -> Result<(), ServiceError> {
+        Self::ensure_slots(config, resource, count, 1).await
+    }
+
+    /// Checks there is room for `additional` more `resource`, e.g. for a bulk import.
+    pub async fn ensure_slots(
+        config: &AppConfig,
+        resource: QuotaResource,
+        count: impl Future<Output = Result<i64, ServiceError>>,
+        additional: u64,
+    ) -> Result<(), ServiceError> {
+        let limit = resource.limit(config);
+        if limit.is_none() || additional == 0 {
+            return Ok(());
+        }
+        check(resource, limit, count.await? as u64, additional)
+    }
+
+    /// Checks that `additional` bytes fit in the storage quota.
+    pub async fn ensure_storage_room(
+        config: &AppConfig,
+        additional: u64,
+    ) -> Result<(), ServiceError> {
+        let limit = QuotaResource::Storage.limit(config);
+        if limit.is_none() {
+            return Ok(());
+        }
+        check(QuotaResource::Storage, limit, storage_used(config).await, additional)
+    }
+
+    pub async fn usage(config: &AppConfig, pool: &SqlitePool) -> Result<UsageResp, ServiceError> {
+        let users = UserRepository::count_users(pool).await?;
+        let roles = RoleRepository::count_roles(pool).await?;
+        Ok(UsageResp {
+            users: usage_of(config, QuotaResource::Users, users as u64),
+            roles: usage_of(config, QuotaResource::Roles, roles as u64),
+            storage_bytes: usage_of(config, QuotaResource::Storage, storage_used(config).await),
+        })
+    }
+}
+
+fn usage_of(config: &AppConfig, resource: QuotaResource, used: u64) -> QuotaUsageResp {
+    QuotaUsageResp { used, limit: resource.limit(config) }
+}
+
+/// Fails with `QuotaExceeded` when `additional` more on top of `used` would pass `limit`.
+fn check(
+    resource: QuotaResource,
+    limit: Option<u64>,
+    used: u64,
+    additional: u64,
+) -> Result<(), ServiceError> {
+    match limit {
+        Some(limit) if used.saturating_add(additional) > limit => {
+            tracing::warn!(resource = resource.as_str(), used, limit, "Quota exceeded");
+            Err(ServiceError::QuotaExceeded { resource: resource.as_str(), limit })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Total size of uploaded files, avatars included.
+async fn storage_used(config: &AppConfig) -> u64 {
+    let dirs = [config.uploads_dir(), config.avatars_dir()];
+    tokio::task::spawn_blocking(move || dirs.iter().map(|dir| dir_size(dir)).sum())
+        .await
+        .unwrap_or_default()
+}
+
+/// Recursive file size under `dir`; unreadable entries count as empty.
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check;
+    use crate::{common::error::ServiceError, features::system::quota::types::QuotaResource};
+
+    #[test]
+    fn allows_up_to_the_limit_and_rejects_past_it() {
+        assert!(check(QuotaResource::Users, None, 1_000, 1).is_ok());
+        assert!(check(QuotaResource::Users, Some(3), 2, 1).is_ok());
+        assert!(matches!(
+            check(QuotaResource::Users, Some(3), 3, 1),
+            Err(ServiceError::QuotaExceeded { resource: "users", limit: 3 })
+        ));
+        assert!(check(QuotaResource::Storage, Some(10), 4, 7).is_err());
+    }
+}
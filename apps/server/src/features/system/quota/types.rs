@@ -0,0 +1,50 @@
+use crate::infra::config::AppConfig;
+
+use serde::Serialize;
+
+/// Resource with a configurable ceiling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaResource {
+    Users,
+    Roles,
+    /// Bytes held by uploaded files.
+    Storage,
+}
+
+impl QuotaResource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            QuotaResource::Users => "users",
+            QuotaResource::Roles => "roles",
+            QuotaResource::Storage => "storage",
+        }
+    }
+
+    /// Configured limit, or `None` when the resource is unlimited.
+    pub fn limit(self, config: &AppConfig) -> Option<u64> {
+        let limit = match self {
+            QuotaResource::Users => config.quota.max_users,
+            QuotaResource::Roles => config.quota.max_roles,
+            QuotaResource::Storage => config.quota.max_storage_bytes,
+        };
+        (limit > 0).then_some(limit)
+    }
+}
+
+/// Current use of one resource against its limit.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaUsageResp {
+    pub used: u64,
+    /// `None` when the resource is unlimited.
+    pub limit: Option<u64>,
+}
+
+/// Usage snapshot for billing and capacity checks.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageResp {
+    pub users: QuotaUsageResp,
+    pub roles: QuotaUsageResp,
+    pub storage_bytes: QuotaUsageResp,
+}
@@ -0,0 +1,23 @@
+use super::{
+    service::RbacService,
+    types::{RbacDiffResp, RbacSnapshot},
+};
+use crate::{
+    common::api::{ApiResponse, AppResult},
+    infra::db::DbExecutor,
+};
+
+use axum::{Json, extract::State};
+
+/// Every live menu and role with its grants, by code
+pub async fn get_rbac_snapshot(State(db): State<DbExecutor>) -> AppResult<RbacSnapshot> {
+    Ok(ApiResponse::success(RbacService::snapshot(db.read()).await?))
+}
+
+/// Compare a snapshot from another environment with this one
+pub async fn diff_rbac(
+    State(db): State<DbExecutor>,
+    Json(snapshot): Json<RbacSnapshot>,
+) -> AppResult<RbacDiffResp> {
+    Ok(ApiResponse::success(RbacService::diff(db.read(), snapshot).await?))
+}
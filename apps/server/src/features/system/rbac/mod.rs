@@ -0,0 +1,29 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use handler::{diff_rbac, get_rbac_snapshot};
+use rustzen_core::{
+    capability::system_rbac,
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+pub fn rbac_routes() -> Router<SqlitePool> {
+    Router::new()
+        .route_with_permission(
+            "/snapshot",
+            get(get_rbac_snapshot),
+            PermissionsCheck::Require(system_rbac::SNAPSHOT),
+        )
+        .route_with_permission(
+            "/diff",
+            post(diff_rbac),
+            PermissionsCheck::Require(system_rbac::DIFF),
+        )
+}
@@ -0,0 +1,23 @@
+use super::types::MenuSnapshotItem;
+use crate::common::{error::ServiceError, tx::Tx};
+
+pub struct RbacRepository;
+
+impl RbacRepository {
+    /// Every live menu with its parent's code, ordered by code.
+    pub async fn list_menus_in_tx(tx: &mut Tx<'_>) -> Result<Vec<MenuSnapshotItem>, ServiceError> {
+        sqlx::query_as::<_, MenuSnapshotItem>(
+            "SELECT m.code, m.name, p.code AS parent_code, m.menu_type, m.status
+             FROM menus m
+             LEFT JOIN menus p ON p.id = m.parent_id AND p.deleted_at IS NULL
+             WHERE m.deleted_at IS NULL
+             ORDER BY m.code",
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error listing menu snapshot: {:?}", e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+}
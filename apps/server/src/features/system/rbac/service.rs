@@ -0,0 +1,246 @@
+use super::{
+    repo::RbacRepository,
+    types::{
+        MenuChange, MenuDiff, MenuSnapshotItem, RBAC_SNAPSHOT_VERSION, RbacDiffResp, RbacSnapshot,
+        RoleChange, RoleDiff,
+    },
+};
+use crate::{
+    common::{error::ServiceError, tx, validation::FieldErrors},
+    features::system::role::{repo::RoleRepository, types::RoleExportItem},
+};
+
+use chrono::Utc;
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+
+/// Snapshots of the menus and role grants of an environment, and the differences
+/// between one taken elsewhere and this database.
+pub struct RbacService;
+
+impl RbacService {
+    /// Every live menu and role, built-in ones included, with grants by menu code.
+    pub async fn snapshot(pool: &SqlitePool) -> Result<RbacSnapshot, ServiceError> {
+        let mut tx = tx::begin(pool).await?;
+        let menus = RbacRepository::list_menus_in_tx(&mut tx).await?;
+        let roles = RoleRepository::list_snapshots_in_tx(&mut tx)
+            .await?
+            .into_iter()
+            .map(RoleExportItem::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(RbacSnapshot {
+            version: RBAC_SNAPSHOT_VERSION,
+            exported_at: Some(Utc::now()),
+            menus: Some(menus),
+            roles,
+        })
+    }
+
+    /// What differs between `snapshot` and this database, seen from the snapshot: `missing`
+    /// is in the snapshot only, `extra` is here only.
+    pub async fn diff(
+        pool: &SqlitePool,
+        snapshot: RbacSnapshot,
+    ) -> Result<RbacDiffResp, ServiceError> {
+        check_snapshot(&snapshot)?;
+        let live = Self::snapshot(pool).await?;
+        Ok(diff_snapshots(snapshot, live))
+    }
+}
+
+fn check_snapshot(snapshot: &RbacSnapshot) -> Result<(), ServiceError> {
+    let mut errors = FieldErrors::new();
+    if snapshot.version != RBAC_SNAPSHOT_VERSION {
+        errors.push("version", format!("must be {}", RBAC_SNAPSHOT_VERSION));
+    }
+    let menu_codes = snapshot.menus.iter().flatten().map(|menu| menu.code.as_str());
+    for (index, code) in duplicate_positions(menu_codes) {
+        errors.push(
+            &format!("menus[{}].code", index),
+            format!("'{}' is listed more than once", code),
+        );
+    }
+    for (index, code) in duplicate_positions(snapshot.roles.iter().map(|role| role.code.as_str())) {
+        errors.push(
+            &format!("roles[{}].code", index),
+            format!("'{}' is listed more than once", code),
+        );
+    }
+    errors.into_result()
+}
+
+/// Index and value of every item that repeats an earlier one.
+fn duplicate_positions<'a>(codes: impl Iterator<Item = &'a str>) -> Vec<(usize, &'a str)> {
+    let mut seen = Vec::new();
+    let mut duplicates = Vec::new();
+    for (index, code) in codes.enumerate() {
+        if seen.contains(&code) {
+            duplicates.push((index, code));
+        } else {
+            seen.push(code);
+        }
+    }
+    duplicates
+}
+
+fn diff_snapshots(snapshot: RbacSnapshot, live: RbacSnapshot) -> RbacDiffResp {
+    let menus_compared = snapshot.menus.is_some();
+    let menus = match snapshot.menus {
+        Some(menus) => diff_menus(menus, live.menus.unwrap_or_default()),
+        None => MenuDiff::default(),
+    };
+    let roles = diff_roles(snapshot.roles, live.roles);
+    let in_sync = [menus.missing.len(), menus.extra.len(), menus.changed.len()]
+        .into_iter()
+        .chain([roles.missing.len(), roles.extra.len(), roles.changed.len()])
+        .all(|count| count == 0);
+    RbacDiffResp { in_sync, menus_compared, menus, roles }
+}
+
+fn diff_menus(snapshot: Vec<MenuSnapshotItem>, live: Vec<MenuSnapshotItem>) -> MenuDiff {
+    let mut live: BTreeMap<String, MenuSnapshotItem> =
+        live.into_iter().map(|menu| (menu.code.clone(), menu)).collect();
+    let mut diff = MenuDiff::default();
+    for menu in snapshot {
+        let Some(current) = live.remove(&menu.code) else {
+            diff.missing.push(menu);
+            continue;
+        };
+        let mut changed_fields = Vec::new();
+        if current.name != menu.name {
+            changed_fields.push("name");
+        }
+        if current.parent_code != menu.parent_code {
+            changed_fields.push("parentCode");
+        }
+        if current.menu_type != menu.menu_type {
+            changed_fields.push("menuType");
+        }
+        if current.status != menu.status {
+            changed_fields.push("status");
+        }
+        if !changed_fields.is_empty() {
+            diff.changed.push(MenuChange { code: menu.code, changed_fields });
+        }
+    }
+    diff.extra = live.into_keys().collect();
+    diff
+}
+
+fn diff_roles(snapshot: Vec<RoleExportItem>, live: Vec<RoleExportItem>) -> RoleDiff {
+    let mut live: BTreeMap<String, RoleExportItem> =
+        live.into_iter().map(|role| (role.code.clone(), role)).collect();
+    let mut diff = RoleDiff::default();
+    for role in snapshot {
+        let Some(current) = live.remove(&role.code) else {
+            diff.missing.push(role.code);
+            continue;
+        };
+        let mut changed_fields = Vec::new();
+        if current.name != role.name {
+            changed_fields.push("name");
+        }
+        if current.description.as_deref().unwrap_or_default()
+            != role.description.as_deref().unwrap_or_default()
+        {
+            changed_fields.push("description");
+        }
+        if current.status != role.status {
+            changed_fields.push("status");
+        }
+        let mut missing_menus: Vec<String> = role
+            .menu_codes
+            .iter()
+            .filter(|code| !current.menu_codes.contains(code))
+            .cloned()
+            .collect();
+        missing_menus.sort_unstable();
+        missing_menus.dedup();
+        let mut extra_menus: Vec<String> = current
+            .menu_codes
+            .iter()
+            .filter(|code| !role.menu_codes.contains(code))
+            .cloned()
+            .collect();
+        extra_menus.sort_unstable();
+        if !changed_fields.is_empty() || !missing_menus.is_empty() || !extra_menus.is_empty() {
+            diff.changed.push(RoleChange {
+                code: role.code,
+                changed_fields,
+                missing_menus,
+                extra_menus,
+            });
+        }
+    }
+    diff.extra = live.into_keys().collect();
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn menu(code: &str, parent_code: Option<&str>, status: i16) -> MenuSnapshotItem {
+        MenuSnapshotItem {
+            code: code.to_string(),
+            name: code.to_string(),
+            parent_code: parent_code.map(str::to_string),
+            menu_type: 2,
+            status,
+        }
+    }
+
+    fn role(code: &str, menu_codes: &[&str]) -> RoleExportItem {
+        RoleExportItem {
+            code: code.to_string(),
+            name: code.to_string(),
+            description: None,
+            status: 1,
+            menu_codes: menu_codes.iter().map(|code| code.to_string()).collect(),
+        }
+    }
+
+    fn snapshot(menus: Option<Vec<MenuSnapshotItem>>, roles: Vec<RoleExportItem>) -> RbacSnapshot {
+        RbacSnapshot { version: RBAC_SNAPSHOT_VERSION, exported_at: None, menus, roles }
+    }
+
+    #[test]
+    fn snapshots_report_missing_extra_and_changed_entries() {
+        let live = || {
+            snapshot(
+                Some(vec![menu("reports", None, 1), menu("daily", Some("reports"), 1)]),
+                vec![role("ops", &["daily", "reports"]), role("support", &[])],
+            )
+        };
+        assert!(diff_snapshots(live(), live()).in_sync);
+
+        let staging = snapshot(
+            Some(vec![menu("reports", None, 2), menu("weekly", Some("reports"), 1)]),
+            vec![role("ops", &["reports", "weekly"]), role("auditor", &["reports"])],
+        );
+        let diff = diff_snapshots(staging, live());
+        assert!(!diff.in_sync);
+        assert_eq!(diff.menus.missing, vec![menu("weekly", Some("reports"), 1)]);
+        assert_eq!(diff.menus.extra, vec!["daily"]);
+        assert_eq!(diff.menus.changed[0].changed_fields, vec!["status"]);
+        assert_eq!(
+            (diff.roles.missing, diff.roles.extra),
+            (vec!["auditor".to_string()], vec!["support".to_string()])
+        );
+        assert_eq!(diff.roles.changed[0].missing_menus, vec!["weekly"]);
+        assert_eq!(diff.roles.changed[0].extra_menus, vec!["daily"]);
+
+        // A role export has no menus, so only roles are compared.
+        let diff = diff_snapshots(snapshot(None, live().roles), live());
+        assert!(diff.in_sync && !diff.menus_compared);
+    }
+
+    #[test]
+    fn snapshots_with_repeated_codes_or_another_version_are_refused() {
+        let repeated = snapshot(None, vec![role("ops", &[]), role("ops", &[])]);
+        assert!(matches!(check_snapshot(&repeated), Err(ServiceError::InvalidFields(_))));
+        let newer =
+            RbacSnapshot { version: RBAC_SNAPSHOT_VERSION + 1, ..snapshot(None, Vec::new()) };
+        assert!(matches!(check_snapshot(&newer), Err(ServiceError::InvalidFields(_))));
+    }
+}
@@ -0,0 +1,87 @@
+use crate::features::system::role::types::{ROLE_EXPORT_VERSION, RoleExportItem};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Version written into RBAC snapshots. It follows the role export, so a role export is
+/// accepted as a snapshot without menus.
+pub const RBAC_SNAPSHOT_VERSION: u32 = ROLE_EXPORT_VERSION;
+
+/// Menus and roles of one environment, keyed by code so ids never have to match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RbacSnapshot {
+    pub version: u32,
+    #[serde(default)]
+    pub exported_at: Option<DateTime<Utc>>,
+    /// `None` when the snapshot is a role export; menus are then not compared.
+    #[serde(default)]
+    pub menus: Option<Vec<MenuSnapshotItem>>,
+    #[serde(default)]
+    pub roles: Vec<RoleExportItem>,
+}
+
+/// One live menu, with its parent by code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct MenuSnapshotItem {
+    pub code: String,
+    pub name: String,
+    #[serde(default)]
+    pub parent_code: Option<String>,
+    pub menu_type: i16,
+    pub status: i16,
+}
+
+/// A menu present on both sides whose fields differ.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MenuChange {
+    pub code: String,
+    /// `name`, `parentCode`, `menuType` or `status`.
+    pub changed_fields: Vec<&'static str>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MenuDiff {
+    /// In the snapshot but not here.
+    pub missing: Vec<MenuSnapshotItem>,
+    /// Here but not in the snapshot.
+    pub extra: Vec<String>,
+    pub changed: Vec<MenuChange>,
+}
+
+/// A role present on both sides whose fields or grants differ.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleChange {
+    pub code: String,
+    /// `name`, `description` or `status`.
+    pub changed_fields: Vec<&'static str>,
+    /// Granted in the snapshot but not here.
+    pub missing_menus: Vec<String>,
+    /// Granted here but not in the snapshot.
+    pub extra_menus: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleDiff {
+    /// Role codes in the snapshot but not here.
+    pub missing: Vec<String>,
+    /// Role codes here but not in the snapshot.
+    pub extra: Vec<String>,
+    pub changed: Vec<RoleChange>,
+}
+
+/// Differences between a snapshot and the live database.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RbacDiffResp {
+    pub in_sync: bool,
+    /// False for snapshots without menus; `menus` is then empty.
+    pub menus_compared: bool,
+    pub menus: MenuDiff,
+    pub roles: RoleDiff,
+}
@@ -0,0 +1,28 @@
+use super::{
+    service::RecycleBinService,
+    types::{RecycleBinQuery, TrashItemResp},
+};
+use crate::{
+    common::{
+        api::{ApiResponse, AppResult, PageMeta},
+        pagination::{Pagination, PaginationQuery},
+    },
+    infra::db::DbExecutor,
+};
+
+use axum::extract::{Query, State};
+use rustzen_core::auth::CurrentUser;
+
+/// Get deleted users or roles, with the fields that would block each restore
+pub async fn list_trash(
+    current_user: CurrentUser,
+    State(db): State<DbExecutor>,
+    Query(query): Query<RecycleBinQuery>,
+) -> AppResult<Vec<TrashItemResp>> {
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (items, total) = RecycleBinService::list(db.read(), &current_user, query).await?;
+    Ok(ApiResponse::page(items, total, PageMeta::new(pagination, total)))
+}
@@ -0,0 +1,21 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{Router, routing::get};
+use handler::list_trash;
+use rustzen_core::{
+    capability::{system_role, system_user},
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+/// Either restore permission opens the list; each kind checks its own.
+pub fn recycle_bin_routes() -> Router<SqlitePool> {
+    Router::new().route_with_permission(
+        "/",
+        get(list_trash),
+        PermissionsCheck::Any(vec![system_user::RESTORE, system_role::RESTORE]),
+    )
+}
@@ -0,0 +1,108 @@
+use super::types::{TrashedRoleRow, TrashedUserRow, UniqueField};
+use crate::common::{
+    error::ServiceError,
+    ids::{RoleId, UserId},
+};
+
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+
+pub struct RecycleBinRepository;
+
+fn db_error(context: &str, e: sqlx::Error) -> ServiceError {
+    tracing::error!("Database error {}: {:?}", context, e);
+    ServiceError::DatabaseQueryFailed
+}
+
+impl RecycleBinRepository {
+    /// Soft-deleted users, most recently deleted first.
+    pub async fn list_deleted_users(
+        pool: &SqlitePool,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<TrashedUserRow>, i64), ServiceError> {
+        let total = sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE deleted_at IS NOT NULL")
+            .fetch_one(pool)
+            .await
+            .map_err(|e| db_error("counting deleted users", e))?;
+        let rows = sqlx::query_as::<_, TrashedUserRow>(
+            "SELECT id, username, email, phone, deleted_at FROM users
+             WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC, id DESC LIMIT ? OFFSET ?",
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("listing deleted users", e))?;
+        Ok((rows, total))
+    }
+
+    /// Soft-deleted roles, most recently deleted first.
+    pub async fn list_deleted_roles(
+        pool: &SqlitePool,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<TrashedRoleRow>, i64), ServiceError> {
+        let total = sqlx::query_scalar("SELECT COUNT(*) FROM roles WHERE deleted_at IS NOT NULL")
+            .fetch_one(pool)
+            .await
+            .map_err(|e| db_error("counting deleted roles", e))?;
+        let rows = sqlx::query_as::<_, TrashedRoleRow>(
+            "SELECT id, name, code, deleted_at FROM roles
+             WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC, id DESC LIMIT ? OFFSET ?",
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("listing deleted roles", e))?;
+        Ok((rows, total))
+    }
+
+    pub async fn find_deleted_user(
+        pool: &SqlitePool,
+        id: UserId,
+    ) -> Result<Option<TrashedUserRow>, ServiceError> {
+        sqlx::query_as::<_, TrashedUserRow>(
+            "SELECT id, username, email, phone, deleted_at FROM users
+             WHERE id = ? AND deleted_at IS NOT NULL",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_error("finding deleted user", e))
+    }
+
+    pub async fn find_deleted_role(
+        pool: &SqlitePool,
+        id: RoleId,
+    ) -> Result<Option<TrashedRoleRow>, ServiceError> {
+        sqlx::query_as::<_, TrashedRoleRow>(
+            "SELECT id, name, code, deleted_at FROM roles WHERE id = ? AND deleted_at IS NOT NULL",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_error("finding deleted role", e))
+    }
+
+    /// Whether a live record holds `value` in `field`, or with `include_trash` any record.
+    pub async fn value_in_use(
+        pool: &SqlitePool,
+        field: UniqueField,
+        value: &str,
+        include_trash: bool,
+    ) -> Result<bool, ServiceError> {
+        // Table and column names come from `UniqueField`, never from the request.
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT EXISTS(SELECT 1 FROM ");
+        query.push(field.table()).push(" WHERE ").push(field.column()).push(" = ").push_bind(value);
+        if !include_trash {
+            query.push(" AND deleted_at IS NULL");
+        }
+        query
+            .push(")")
+            .build_query_scalar::<bool>()
+            .fetch_one(pool)
+            .await
+            .map_err(|e| db_error("checking unique value", e))
+    }
+}
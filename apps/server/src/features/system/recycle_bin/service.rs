@@ -0,0 +1,276 @@
+use super::{
+    repo::RecycleBinRepository,
+    types::{
+        RecycleBinQuery, RestoreRoleRequest, RestoreUserRequest, TrashItemResp, TrashKind,
+        UniqueField,
+    },
+};
+use crate::{
+    common::{
+        error::ServiceError,
+        ids::{RoleId, UserId},
+        mask::{FieldMask, MaskFields},
+        pagination::{Pagination, PaginationQuery},
+        validation::{FieldConflict, FieldErrors, is_email},
+    },
+    features::system::{
+        quota::{service::QuotaService, types::QuotaResource},
+        role::repo::RoleRepository,
+        user::repo::UserRepository,
+    },
+    infra::config::AppConfig,
+};
+
+use rustzen_core::auth::CurrentUser;
+use sqlx::SqlitePool;
+
+/// Free values offered for each conflicting field.
+const SUGGESTION_COUNT: usize = 3;
+/// Numbered variants tried before giving up on more suggestions.
+const MAX_NUMBERED_CANDIDATE: usize = 20;
+
+/// Soft-deleted users and roles, and bringing them back when their names were reused.
+///
+/// Unique indexes only cover live rows, so a name freed by a delete can be taken again.
+/// Restores check every unique field against live records first and, instead of failing
+/// on the index, answer with [`ServiceError::RestoreConflict`] listing free alternatives
+/// that no record, live or deleted, uses. The caller restores again with those renames.
+pub struct RecycleBinService;
+
+impl RecycleBinService {
+    /// One page of deleted users or roles, each with what would block its restore.
+    pub async fn list(
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        query: RecycleBinQuery,
+    ) -> Result<(Vec<TrashItemResp>, i64), ServiceError> {
+        let RecycleBinQuery { current, page_size, kind } = query;
+        let Some(kind) = kind.as_deref().map(str::trim).and_then(TrashKind::parse) else {
+            let mut errors = FieldErrors::new();
+            errors.push("kind", "must be users or roles");
+            return errors.into_result().map(|_| (Vec::new(), 0));
+        };
+        if !current_user.has_capability(kind.capability()) {
+            return Err(ServiceError::InvalidOperation(format!(
+                "Viewing deleted {} requires the {} permission",
+                kind.as_str(),
+                kind.capability()
+            )));
+        }
+        let pagination = Pagination::from_query(PaginationQuery { current, page_size });
+        let (offset, limit) = (i64::from(pagination.offset), i64::from(pagination.limit));
+        let mask = FieldMask::for_user(current_user);
+
+        let mut items = Vec::new();
+        let total = match kind {
+            TrashKind::Users => {
+                let (rows, total) =
+                    RecycleBinRepository::list_deleted_users(pool, offset, limit).await?;
+                for row in rows {
+                    let conflicts =
+                        user_conflicts(pool, &row.username, &row.email, row.phone.as_deref())
+                            .await?;
+                    items.push(TrashItemResp {
+                        kind: kind.as_str(),
+                        id: row.id.get(),
+                        name: row.username,
+                        deleted_at: row.deleted_at,
+                        conflicts: conflicts.into_iter().map(|c| c.masked(mask)).collect(),
+                    });
+                }
+                total
+            }
+            TrashKind::Roles => {
+                let (rows, total) =
+                    RecycleBinRepository::list_deleted_roles(pool, offset, limit).await?;
+                for row in rows {
+                    let conflicts = role_conflicts(pool, &row.name, &row.code).await?;
+                    items.push(TrashItemResp {
+                        kind: kind.as_str(),
+                        id: row.id.get(),
+                        name: row.name,
+                        deleted_at: row.deleted_at,
+                        conflicts,
+                    });
+                }
+                total
+            }
+        };
+        Ok((items, total))
+    }
+
+    /// Restore a soft-deleted user with its original roles, renamed where `request` says.
+    pub async fn restore_user(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        id: UserId,
+        request: RestoreUserRequest,
+    ) -> Result<(), ServiceError> {
+        tracing::debug!("Restoring deleted user ID: {}", id);
+        let row = RecycleBinRepository::find_deleted_user(pool, id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound(format!("Deleted user id: {}", id)))?;
+        let RestoreUserRequest { username, email, clear_phone } = request;
+        let username = username.map(|value| value.trim().to_string()).unwrap_or(row.username);
+        let email = email.map(|value| value.trim().to_string()).unwrap_or(row.email);
+        let phone = if clear_phone { None } else { row.phone };
+        let mut errors = FieldErrors::new();
+        if username.is_empty() {
+            errors.push("username", "must not be empty");
+        }
+        if !is_email(&email) {
+            errors.push("email", "must be an email address");
+        }
+        errors.into_result()?;
+
+        let conflicts = user_conflicts(pool, &username, &email, phone.as_deref()).await?;
+        if !conflicts.is_empty() {
+            let mask = FieldMask::for_user(current_user);
+            return Err(ServiceError::RestoreConflict(
+                conflicts.into_iter().map(|c| c.masked(mask)).collect(),
+            ));
+        }
+        QuotaService::ensure_slot(config, QuotaResource::Users, UserRepository::count_users(pool))
+            .await?;
+        if !UserRepository::restore_deleted(pool, id, &username, &email, phone.as_deref()).await? {
+            return Err(ServiceError::NotFound(format!("Deleted user id: {}", id)));
+        }
+        tracing::info!(user_id = id.get(), %username, "Restored deleted user");
+        Ok(())
+    }
+
+    /// Restore a soft-deleted role with its menus, renamed where `request` says.
+    pub async fn restore_role(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        id: RoleId,
+        request: RestoreRoleRequest,
+    ) -> Result<(), ServiceError> {
+        tracing::debug!("Restoring deleted role ID: {}", id);
+        let row = RecycleBinRepository::find_deleted_role(pool, id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound(format!("Deleted role id: {}", id)))?;
+        let name = request.name.map(|value| value.trim().to_string()).unwrap_or(row.name);
+        let code = request.code.map(|value| value.trim().to_string()).unwrap_or(row.code);
+        let mut errors = FieldErrors::new();
+        if name.is_empty() {
+            errors.push("name", "must not be empty");
+        }
+        if code.is_empty() {
+            errors.push("code", "must not be empty");
+        }
+        errors.into_result()?;
+
+        let conflicts = role_conflicts(pool, &name, &code).await?;
+        if !conflicts.is_empty() {
+            return Err(ServiceError::RestoreConflict(conflicts));
+        }
+        QuotaService::ensure_slot(config, QuotaResource::Roles, RoleRepository::count_roles(pool))
+            .await?;
+        if !RoleRepository::restore_deleted(pool, id, &name, &code).await? {
+            return Err(ServiceError::NotFound(format!("Deleted role id: {}", id)));
+        }
+        tracing::info!(role_id = id.get(), %code, "Restored deleted role");
+        Ok(())
+    }
+}
+
+async fn user_conflicts(
+    pool: &SqlitePool,
+    username: &str,
+    email: &str,
+    phone: Option<&str>,
+) -> Result<Vec<FieldConflict>, ServiceError> {
+    let mut conflicts = Vec::new();
+    conflicts.extend(conflict(pool, UniqueField::Username, username).await?);
+    conflicts.extend(conflict(pool, UniqueField::Email, email).await?);
+    if let Some(phone) = phone {
+        conflicts.extend(conflict(pool, UniqueField::Phone, phone).await?);
+    }
+    Ok(conflicts)
+}
+
+async fn role_conflicts(
+    pool: &SqlitePool,
+    name: &str,
+    code: &str,
+) -> Result<Vec<FieldConflict>, ServiceError> {
+    let mut conflicts = Vec::new();
+    conflicts.extend(conflict(pool, UniqueField::RoleName, name).await?);
+    conflicts.extend(conflict(pool, UniqueField::RoleCode, code).await?);
+    Ok(conflicts)
+}
+
+/// `None` when no live record holds `value`; otherwise the conflict with up to
+/// [`SUGGESTION_COUNT`] candidates that no record holds, so a later restore of another
+/// deleted record does not clash with the rename either.
+async fn conflict(
+    pool: &SqlitePool,
+    field: UniqueField,
+    value: &str,
+) -> Result<Option<FieldConflict>, ServiceError> {
+    if !RecycleBinRepository::value_in_use(pool, field, value, false).await? {
+        return Ok(None);
+    }
+    let mut suggestions = Vec::new();
+    for candidate in rename_candidates(field, value) {
+        if suggestions.len() == SUGGESTION_COUNT {
+            break;
+        }
+        if !RecycleBinRepository::value_in_use(pool, field, &candidate, true).await? {
+            suggestions.push(candidate);
+        }
+    }
+    Ok(Some(FieldConflict {
+        field: field.name().to_string(),
+        value: value.to_string(),
+        suggestions,
+    }))
+}
+
+/// A `restored` variant of `value` followed by numbered ones. Phone numbers get none;
+/// restore those users with `clearPhone` instead.
+fn rename_candidates(field: UniqueField, value: &str) -> Vec<String> {
+    let numbered = 2..=MAX_NUMBERED_CANDIDATE;
+    match field {
+        UniqueField::Phone => Vec::new(),
+        UniqueField::Email => match value.split_once('@') {
+            Some((local, domain)) => std::iter::once("restored".to_string())
+                .chain(numbered.map(|n| n.to_string()))
+                .map(|tag| format!("{}+{}@{}", local, tag, domain))
+                .collect(),
+            None => Vec::new(),
+        },
+        UniqueField::RoleName => std::iter::once(format!("{} (restored)", value))
+            .chain(numbered.map(|n| format!("{} {}", value, n)))
+            .collect(),
+        UniqueField::Username | UniqueField::RoleCode => {
+            std::iter::once(format!("{}_restored", value))
+                .chain(numbered.map(|n| format!("{}_{}", value, n)))
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rename_candidates;
+    use crate::features::system::recycle_bin::types::UniqueField;
+
+    #[test]
+    fn rename_candidates_keep_the_shape_of_each_field() {
+        let first_two = |field, value| rename_candidates(field, value)[..2].to_vec();
+        assert_eq!(first_two(UniqueField::Username, "alice"), ["alice_restored", "alice_2"]);
+        assert_eq!(
+            first_two(UniqueField::Email, "alice@example.com"),
+            ["alice+restored@example.com", "alice+2@example.com"]
+        );
+        assert_eq!(
+            first_two(UniqueField::RoleName, "Auditor"),
+            ["Auditor (restored)", "Auditor 2"]
+        );
+        assert_eq!(first_two(UniqueField::RoleCode, "auditor"), ["auditor_restored", "auditor_2"]);
+        assert!(rename_candidates(UniqueField::Phone, "+8613812345678").is_empty());
+    }
+}
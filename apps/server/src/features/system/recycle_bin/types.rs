@@ -0,0 +1,160 @@
+use crate::common::{
+    ids::{RoleId, UserId},
+    mask::{FieldMask, MaskFields, mask_email},
+    validation::FieldConflict,
+};
+
+use chrono::{DateTime, Utc};
+use rustzen_core::{
+    capability::{system_role, system_user},
+    sms::mask_phone,
+};
+use serde::{Deserialize, Serialize};
+
+/// Which soft-deleted records a recycle-bin page lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrashKind {
+    Users,
+    Roles,
+}
+
+impl TrashKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "users" => Some(TrashKind::Users),
+            "roles" => Some(TrashKind::Roles),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TrashKind::Users => "users",
+            TrashKind::Roles => "roles",
+        }
+    }
+
+    /// Capability for listing and restoring this kind.
+    pub fn capability(self) -> &'static str {
+        match self {
+            TrashKind::Users => system_user::RESTORE,
+            TrashKind::Roles => system_role::RESTORE,
+        }
+    }
+}
+
+/// Column a live record must hold uniquely, as the restore checks it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UniqueField {
+    Username,
+    Email,
+    Phone,
+    RoleName,
+    RoleCode,
+}
+
+impl UniqueField {
+    /// Field name in restore requests and conflicts.
+    pub fn name(self) -> &'static str {
+        match self {
+            UniqueField::Username => "username",
+            UniqueField::Email => "email",
+            UniqueField::Phone => "phone",
+            UniqueField::RoleName => "name",
+            UniqueField::RoleCode => "code",
+        }
+    }
+
+    pub fn table(self) -> &'static str {
+        match self {
+            UniqueField::Username | UniqueField::Email | UniqueField::Phone => "users",
+            UniqueField::RoleName | UniqueField::RoleCode => "roles",
+        }
+    }
+
+    pub fn column(self) -> &'static str {
+        match self {
+            UniqueField::Username => "username",
+            UniqueField::Email => "email",
+            UniqueField::Phone => "phone",
+            UniqueField::RoleName => "name",
+            UniqueField::RoleCode => "code",
+        }
+    }
+}
+
+/// Recycle-bin query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecycleBinQuery {
+    pub current: Option<i64>,
+    pub page_size: Option<i64>,
+    /// `users` or `roles`.
+    pub kind: Option<String>,
+}
+
+/// Soft-deleted user as the recycle bin reads it.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TrashedUserRow {
+    pub id: UserId,
+    pub username: String,
+    pub email: String,
+    pub phone: Option<String>,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Soft-deleted role as the recycle bin reads it.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TrashedRoleRow {
+    pub id: RoleId,
+    pub name: String,
+    pub code: String,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Soft-deleted record with what would block its restore.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashItemResp {
+    pub kind: &'static str,
+    pub id: i64,
+    /// Username or role name.
+    pub name: String,
+    pub deleted_at: DateTime<Utc>,
+    /// Empty when the record can be restored as it is.
+    pub conflicts: Vec<FieldConflict>,
+}
+
+/// Optional new values for a user's unique fields when its old ones are taken.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreUserRequest {
+    pub username: Option<String>,
+    pub email: Option<String>,
+    /// Restore without the phone number, for when a live user has bound it since.
+    #[serde(default)]
+    pub clear_phone: bool,
+}
+
+/// Optional new values for a role's name and code when its old ones are taken.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreRoleRequest {
+    pub name: Option<String>,
+    pub code: Option<String>,
+}
+
+impl MaskFields for FieldConflict {
+    fn masked(self, mask: FieldMask) -> Self {
+        let apply: fn(&str) -> String = match (mask, self.field.as_str()) {
+            (FieldMask::Mask, "email") => mask_email,
+            (FieldMask::Mask, "phone") => mask_phone,
+            _ => return self,
+        };
+        Self {
+            value: apply(&self.value),
+            suggestions: self.suggestions.iter().map(|value| apply(value)).collect(),
+            ..self
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use super::{
+    service::RegistrationService,
+    types::{PendingRegistrationResp, RegisterRequest, RegistrationQuery, VerifyEmailRequest},
+};
+use crate::{
+    common::{
+        api::{ApiResponse, AppResult, PageMeta},
+        ids::UserId,
+        mask::FieldMask,
+        pagination::{Pagination, PaginationQuery},
+    },
+    infra::{config::AppConfig, db::DbExecutor},
+};
+
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+};
+use rustzen_core::auth::CurrentUser;
+use sqlx::SqlitePool;
+
+/// Self-register a pending account and mail a verification link
+pub async fn register(
+    Extension(config): Extension<&'static AppConfig>,
+    State(pool): State<SqlitePool>,
+    Json(request): Json<RegisterRequest>,
+) -> AppResult<()> {
+    RegistrationService::register(config, &pool, request).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Verify the email of a self-registered account
+pub async fn verify_email(
+    State(pool): State<SqlitePool>,
+    Json(request): Json<VerifyEmailRequest>,
+) -> AppResult<()> {
+    RegistrationService::verify_email(&pool, request).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Get paginated self-registered users waiting for approval, oldest first
+pub async fn list_registrations(
+    current_user: CurrentUser,
+    State(db): State<DbExecutor>,
+    Query(query): Query<RegistrationQuery>,
+) -> AppResult<Vec<PendingRegistrationResp>> {
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (users, total) =
+        RegistrationService::list_pending(db.read(), query, FieldMask::for_user(&current_user))
+            .await?;
+    Ok(ApiResponse::page(users, total, PageMeta::new(pagination, total)))
+}
+
+/// Activate a pending user with a verified email
+pub async fn approve_registration(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+) -> AppResult<()> {
+    RegistrationService::approve(&pool, id, UserId(current_user.user_id)).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Reject a pending user
+pub async fn reject_registration(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+) -> AppResult<()> {
+    RegistrationService::reject(&pool, id, UserId(current_user.user_id)).await?;
+    Ok(ApiResponse::success(()))
+}
@@ -0,0 +1,41 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use handler::{
+    approve_registration, list_registrations, register, reject_registration, verify_email,
+};
+use rustzen_core::{
+    capability::system_registration,
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+/// Unauthenticated routes merged under `/api/auth`.
+pub fn public_registration_routes() -> Router<SqlitePool> {
+    Router::new().route("/register", post(register)).route("/register/verify", post(verify_email))
+}
+
+pub fn registration_routes() -> Router<SqlitePool> {
+    Router::new()
+        .route_with_permission(
+            "/",
+            get(list_registrations),
+            PermissionsCheck::Require(system_registration::LIST),
+        )
+        .route_with_permission(
+            "/{id}/approve",
+            post(approve_registration),
+            PermissionsCheck::Require(system_registration::APPROVE),
+        )
+        .route_with_permission(
+            "/{id}/reject",
+            post(reject_registration),
+            PermissionsCheck::Require(system_registration::APPROVE),
+        )
+}
@@ -0,0 +1,159 @@
+use super::types::PendingRegistrationResp;
+use crate::common::{
+    error::ServiceError,
+    query::{count_with_filters, fetch_with_filters},
+    tx::{self, Tx},
+};
+
+use chrono::{NaiveDateTime, Utc};
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+
+pub struct RegistrationRepository;
+
+fn db_error(context: &str, e: sqlx::Error) -> ServiceError {
+    tracing::error!("Database error {}: {:?}", context, e);
+    ServiceError::DatabaseQueryFailed
+}
+
+impl RegistrationRepository {
+    fn format_query(verified: Option<bool>, query_builder: &mut QueryBuilder<Sqlite>) {
+        match verified {
+            Some(true) => query_builder.push(" AND email_verified_at IS NOT NULL"),
+            Some(false) => query_builder.push(" AND email_verified_at IS NULL"),
+            None => query_builder,
+        };
+    }
+
+    pub async fn list_pending(
+        pool: &SqlitePool,
+        offset: i64,
+        limit: i64,
+        verified: Option<bool>,
+    ) -> Result<(Vec<PendingRegistrationResp>, i64), ServiceError> {
+        let total = count_with_filters(
+            pool,
+            "SELECT COUNT(*) FROM users WHERE status = 3 AND deleted_at IS NULL",
+            |qb| Self::format_query(verified, qb),
+        )
+        .await?;
+        if total == 0 {
+            return Ok((Vec::new(), total));
+        }
+        let users = fetch_with_filters(
+            pool,
+            "SELECT id, username, email, real_name, email_verified_at, created_at
+             FROM users WHERE status = 3 AND deleted_at IS NULL",
+            |qb| Self::format_query(verified, qb),
+            Some("id"),
+            Some(limit),
+            Some(offset),
+        )
+        .await?;
+        Ok((users, total))
+    }
+
+    /// Stores the hash of a verification token mailed to `user_id`.
+    pub async fn insert_token(
+        pool: &SqlitePool,
+        user_id: i64,
+        token_hash: &str,
+        expires_at: NaiveDateTime,
+    ) -> Result<(), ServiceError> {
+        sqlx::query(
+            "INSERT INTO email_verification_tokens (user_id, token_hash, expires_at, created_at)
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(user_id)
+        .bind(token_hash)
+        .bind(expires_at)
+        .bind(Utc::now().naive_utc())
+        .execute(pool)
+        .await
+        .map_err(|e| db_error("inserting verification token", e))?;
+        Ok(())
+    }
+
+    /// Uses up an unexpired token and marks its user's email verified; `None` when the
+    /// token is unknown, used or expired.
+    pub async fn consume_token(
+        pool: &SqlitePool,
+        token_hash: &str,
+        now: NaiveDateTime,
+    ) -> Result<Option<i64>, ServiceError> {
+        let mut tx = tx::begin(pool).await?;
+        let user_id = sqlx::query_scalar::<_, i64>(
+            "UPDATE email_verification_tokens SET used_at = ?
+             WHERE token_hash = ? AND used_at IS NULL AND expires_at > ?
+             RETURNING user_id",
+        )
+        .bind(now)
+        .bind(token_hash)
+        .bind(now)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| db_error("using verification token", e))?;
+        let Some(user_id) = user_id else {
+            return Ok(None);
+        };
+        let verified = sqlx::query(
+            "UPDATE users SET email_verified_at = COALESCE(email_verified_at, ?), updated_at = ?
+             WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(now)
+        .bind(now)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_error("marking email verified", e))?;
+        if verified.rows_affected() == 0 {
+            return Ok(None);
+        }
+        tx::commit(tx).await?;
+        Ok(Some(user_id))
+    }
+
+    /// Username of a pending user and whether its email is verified.
+    pub async fn find_pending(
+        pool: &SqlitePool,
+        id: i64,
+    ) -> Result<Option<(String, bool)>, ServiceError> {
+        sqlx::query_as::<_, (String, bool)>(
+            "SELECT username, email_verified_at IS NOT NULL FROM users
+             WHERE id = ? AND status = 3 AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_error("finding pending registration", e))
+    }
+
+    /// Activates a verified pending user; `false` when it was decided concurrently.
+    pub async fn approve(pool: &SqlitePool, id: i64) -> Result<bool, ServiceError> {
+        let result = sqlx::query(
+            "UPDATE users SET status = 1, updated_at = ?
+             WHERE id = ? AND status = 3 AND deleted_at IS NULL AND email_verified_at IS NOT NULL",
+        )
+        .bind(Utc::now().naive_utc())
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| db_error("approving registration", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Soft-deletes a pending user so the username and email can register again.
+    pub async fn reject_in_tx(tx: &mut Tx<'_>, id: i64) -> Result<bool, ServiceError> {
+        let now = Utc::now().naive_utc();
+        let result = sqlx::query(
+            "UPDATE users SET deleted_at = ?, updated_at = ?
+             WHERE id = ? AND status = 3 AND deleted_at IS NULL",
+        )
+        .bind(now)
+        .bind(now)
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| db_error("rejecting registration", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+}
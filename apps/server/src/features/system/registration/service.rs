@@ -0,0 +1,250 @@
+use super::{
+    repo::RegistrationRepository,
+    types::{PendingRegistrationResp, RegisterRequest, RegistrationQuery, VerifyEmailRequest},
+};
+use crate::{
+    common::{
+        error::ServiceError,
+        ids::UserId,
+        mask::{FieldMask, MaskFields},
+        pagination::{Pagination, PaginationQuery},
+        token, tx,
+        validation::{FieldErrors, is_email},
+    },
+    features::{
+        auth::types::UserStatus,
+        system::user::{repo::UserRepository, service::UserService, types::CreateUserRequest},
+    },
+    infra::{config::AppConfig, events, mail},
+};
+
+use chrono::{Duration, Utc};
+use rustzen_core::events::DomainEvent;
+use sqlx::SqlitePool;
+
+const USERNAME_MIN_LEN: usize = 3;
+const USERNAME_MAX_LEN: usize = 32;
+const PASSWORD_MIN_LEN: usize = 8;
+const REAL_NAME_MAX_LEN: usize = 50;
+/// How long a verification link stays usable.
+const TOKEN_TTL_HOURS: i64 = 24;
+
+pub struct RegistrationService;
+
+impl RegistrationService {
+    /// Creates a pending user with the configured role and mails a verification link.
+    ///
+    /// A failed mail is only logged: the account exists either way, and an administrator
+    /// can still see it in the approval queue.
+    pub async fn register(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        request: RegisterRequest,
+    ) -> Result<(), ServiceError> {
+        if !config.auth.registration_enabled {
+            return Err(ServiceError::RegistrationDisabled);
+        }
+        let request = normalize(request)?;
+        let role_code = config.auth.registration_role.trim();
+        let role_ids = if role_code.is_empty() {
+            Vec::new()
+        } else {
+            let role_id =
+                UserRepository::find_role_id_by_code(pool, role_code).await?.ok_or_else(|| {
+                    tracing::error!(role_code, "Registration role does not exist");
+                    ServiceError::NotFound("Registration role".to_string())
+                })?;
+            vec![role_id]
+        };
+
+        let email = request.email.clone();
+        let user_id = UserService::create_user(
+            config,
+            pool,
+            None,
+            CreateUserRequest {
+                username: request.username,
+                email: request.email,
+                password: request.password,
+                real_name: request.real_name,
+                status: Some(UserStatus::Pending as i16),
+                role_ids,
+                profile: None,
+            },
+        )
+        .await?;
+
+        let token = token::generate();
+        let expires_at = Utc::now().naive_utc() + Duration::hours(TOKEN_TTL_HOURS);
+        RegistrationRepository::insert_token(pool, user_id.get(), &token::hash(&token), expires_at)
+            .await?;
+        tracing::info!(user_id = user_id.get(), "Registered pending user");
+
+        if let Err(err) = send_verification(config, &email, &token).await {
+            tracing::error!(user_id = user_id.get(), "Verification email failed: {}", err);
+        }
+        Ok(())
+    }
+
+    /// Marks the email behind a mailed token as verified; each token works once.
+    pub async fn verify_email(
+        pool: &SqlitePool,
+        request: VerifyEmailRequest,
+    ) -> Result<(), ServiceError> {
+        let token = request.token.trim();
+        let now = Utc::now().naive_utc();
+        let user_id = if token.is_empty() {
+            None
+        } else {
+            RegistrationRepository::consume_token(pool, &token::hash(token), now).await?
+        };
+        let Some(user_id) = user_id else {
+            return Err(ServiceError::InvalidOperation(
+                "Verification link is invalid or has expired".to_string(),
+            ));
+        };
+        tracing::info!(user_id, "Verified registration email");
+        Ok(())
+    }
+
+    pub async fn list_pending(
+        pool: &SqlitePool,
+        query: RegistrationQuery,
+        mask: FieldMask,
+    ) -> Result<(Vec<PendingRegistrationResp>, i64), ServiceError> {
+        let pagination = Pagination::from_query(PaginationQuery {
+            current: query.current,
+            page_size: query.page_size,
+        });
+        let (users, total) = RegistrationRepository::list_pending(
+            pool,
+            i64::from(pagination.offset),
+            i64::from(pagination.limit),
+            query.verified,
+        )
+        .await?;
+        Ok((users.into_iter().map(|user| user.masked(mask)).collect(), total))
+    }
+
+    /// Activates a pending user whose email is verified.
+    pub async fn approve(
+        pool: &SqlitePool,
+        id: i64,
+        operator_id: UserId,
+    ) -> Result<(), ServiceError> {
+        let (_, verified) = Self::find_pending(pool, id).await?;
+        if !verified {
+            return Err(ServiceError::InvalidOperation(
+                "The user has not verified their email yet".to_string(),
+            ));
+        }
+        if !RegistrationRepository::approve(pool, id).await? {
+            return Err(ServiceError::NotFound(format!("Pending registration id: {}", id)));
+        }
+        tracing::info!(id, operator_id = operator_id.get(), "Approved registration");
+        Ok(())
+    }
+
+    /// Turns a registration down by soft-deleting the pending user.
+    pub async fn reject(
+        pool: &SqlitePool,
+        id: i64,
+        operator_id: UserId,
+    ) -> Result<(), ServiceError> {
+        let (username, _) = Self::find_pending(pool, id).await?;
+        let mut tx = tx::begin(pool).await?;
+        if !RegistrationRepository::reject_in_tx(&mut tx, id).await? {
+            return Err(ServiceError::NotFound(format!("Pending registration id: {}", id)));
+        }
+        let event =
+            DomainEvent::UserDeleted { user_id: id, username, operator_id: operator_id.get() };
+        let recorded = events::record(&mut tx, &event).await?;
+        tx::commit(tx).await?;
+        tracing::info!(id, operator_id = operator_id.get(), "Rejected registration");
+        events::deliver(pool, recorded).await;
+        Ok(())
+    }
+
+    async fn find_pending(pool: &SqlitePool, id: i64) -> Result<(String, bool), ServiceError> {
+        RegistrationRepository::find_pending(pool, id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound(format!("Pending registration id: {}", id)))
+    }
+}
+
+/// Trims the form and records every field the registration form would reject.
+fn normalize(request: RegisterRequest) -> Result<RegisterRequest, ServiceError> {
+    let username = request.username.trim().to_string();
+    let email = request.email.trim().to_string();
+    let real_name =
+        request.real_name.map(|name| name.trim().to_string()).filter(|name| !name.is_empty());
+    let mut errors = FieldErrors::new();
+    let username_len = username.chars().count();
+    if !(USERNAME_MIN_LEN..=USERNAME_MAX_LEN).contains(&username_len)
+        || !username.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        errors.push(
+            "username",
+            format!(
+                "must be {}-{} letters, digits, '_', '-' or '.'",
+                USERNAME_MIN_LEN, USERNAME_MAX_LEN
+            ),
+        );
+    }
+    if !is_email(&email) {
+        errors.push("email", "must be a valid email address");
+    }
+    if request.password.chars().count() < PASSWORD_MIN_LEN {
+        errors.push("password", format!("must be at least {} characters", PASSWORD_MIN_LEN));
+    }
+    if real_name.as_ref().is_some_and(|name| name.chars().count() > REAL_NAME_MAX_LEN) {
+        errors.push("realName", format!("must be at most {} characters", REAL_NAME_MAX_LEN));
+    }
+    errors.into_result()?;
+    Ok(RegisterRequest { username, email, password: request.password, real_name })
+}
+
+async fn send_verification(config: &AppConfig, to: &str, token: &str) -> Result<(), String> {
+    let action = match config.auth.registration_verify_url.as_deref() {
+        Some(url) => format!("open this link to verify it:\r\n\r\n{}?token={}", url, token),
+        None => format!("enter this code to verify it:\r\n\r\n{}", token),
+    };
+    let body = format!(
+        "Thanks for registering. Please {}\r\n\r\nIt expires in {} hours. An administrator \
+         will review your account once the address is verified.\r\n",
+        action, TOKEN_TTL_HOURS
+    );
+    mail::send_notice(to, "Verify your email address", body).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize;
+    use crate::{
+        common::error::ServiceError, features::system::registration::types::RegisterRequest,
+    };
+
+    fn request(username: &str, email: &str, password: &str) -> RegisterRequest {
+        RegisterRequest {
+            username: username.to_string(),
+            email: email.to_string(),
+            password: password.to_string(),
+            real_name: Some("  ".to_string()),
+        }
+    }
+
+    #[test]
+    fn registration_forms_are_trimmed_and_checked_field_by_field() {
+        let normalized = normalize(request(" alice ", " alice@example.com ", "s3cret-pw")).unwrap();
+        assert_eq!(normalized.username, "alice");
+        assert_eq!(normalized.email, "alice@example.com");
+        assert_eq!(normalized.real_name, None);
+
+        let Err(ServiceError::InvalidFields(fields)) = normalize(request("a b", "alice", "short"))
+        else {
+            panic!("expected field errors");
+        };
+        let fields: Vec<_> = fields.iter().map(|error| error.field.as_str()).collect();
+        assert_eq!(fields, ["username", "email", "password"]);
+    }
+}
@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::common::mask::{FieldMask, MaskFields};
+
+/// Self-registration request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterRequest {
+    pub username: String,
+    pub email: String,
+    pub password: String,
+    pub real_name: Option<String>,
+}
+
+/// Token from the verification mail
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyEmailRequest {
+    pub token: String,
+}
+
+/// Approval queue query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrationQuery {
+    pub current: Option<i64>,
+    pub page_size: Option<i64>,
+    /// `true` lists only users who verified their email, `false` only those who did not.
+    pub verified: Option<bool>,
+}
+
+/// Self-registered user waiting for approval
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingRegistrationResp {
+    pub id: i64,
+    pub username: String,
+    pub email: Option<String>,
+    pub real_name: Option<String>,
+    /// Unset until the user opens the verification mail; approval needs it.
+    pub email_verified_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl MaskFields for PendingRegistrationResp {
+    fn masked(self, mask: FieldMask) -> Self {
+        Self { email: mask.email(self.email), ..self }
+    }
+}
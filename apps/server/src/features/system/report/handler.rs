@@ -0,0 +1,58 @@
+use super::{
+    service::ReportService,
+    types::{ReportFormat, ReportItemResp, ReportQuery},
+};
+use crate::{
+    common::{
+        api::{ApiResponse, AppResult, PageMeta},
+        error::AppError,
+        pagination::{Pagination, PaginationQuery},
+    },
+    infra::{config::AppConfig, db::DbExecutor},
+};
+
+use axum::{
+    Extension,
+    extract::{Path, Query, State},
+    http::{HeaderMap, HeaderValue, header},
+    response::{IntoResponse, Response},
+};
+use sqlx::SqlitePool;
+
+/// Get paginated list of generated reports, newest first
+pub async fn list_reports(
+    State(db): State<DbExecutor>,
+    Query(query): Query<ReportQuery>,
+) -> AppResult<Vec<ReportItemResp>> {
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (reports, total) = ReportService::list_reports(db.read(), query).await?;
+    Ok(ApiResponse::page(reports, total, PageMeta::new(pagination, total)))
+}
+
+/// Download a generated report file
+pub async fn download_report(
+    Extension(config): Extension<&'static AppConfig>,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+) -> Result<Response, AppError> {
+    let (report, data) = ReportService::download(config, &pool, id).await?;
+    let content_type = ReportFormat::parse(&report.format)
+        .map(ReportFormat::content_type)
+        .unwrap_or("application/octet-stream");
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    // Stored names are generated ASCII, so this cannot fail in practice.
+    if let Ok(disposition) =
+        HeaderValue::from_str(&format!("attachment; filename={}", report.file_name))
+    {
+        headers.insert(header::CONTENT_DISPOSITION, disposition);
+    }
+    headers.insert(header::CONTENT_LENGTH, HeaderValue::from(data.len()));
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+
+    Ok((headers, data).into_response())
+}
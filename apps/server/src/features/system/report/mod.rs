@@ -0,0 +1,26 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{Router, routing::get};
+use handler::{download_report, list_reports};
+use rustzen_core::{
+    capability::system_report,
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+pub fn report_routes() -> Router<SqlitePool> {
+    Router::new()
+        .route_with_permission(
+            "/",
+            get(list_reports),
+            PermissionsCheck::Require(system_report::LIST),
+        )
+        .route_with_permission(
+            "/{id}/download",
+            get(download_report),
+            PermissionsCheck::Require(system_report::DOWNLOAD),
+        )
+}
@@ -0,0 +1,97 @@
+use super::types::{NewReport, ReportRow};
+use crate::common::{
+    error::ServiceError,
+    query::{count_with_filters, fetch_with_filters, push_eq},
+};
+
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+pub struct ReportRepository;
+
+fn db_error(context: &str, e: sqlx::Error) -> ServiceError {
+    tracing::error!("Database error {}: {:?}", context, e);
+    ServiceError::DatabaseQueryFailed
+}
+
+impl ReportRepository {
+    pub async fn list_reports(
+        pool: &SqlitePool,
+        offset: i64,
+        limit: i64,
+        format: Option<&'static str>,
+    ) -> Result<(Vec<ReportRow>, i64), ServiceError> {
+        let total = count_with_filters(pool, "SELECT COUNT(*) FROM reports WHERE 1=1", |qb| {
+            push_eq(qb, "format", format)
+        })
+        .await?;
+        if total == 0 {
+            return Ok((Vec::new(), total));
+        }
+        let reports = fetch_with_filters(
+            pool,
+            "SELECT id, title, format, file_name, stored_name, size_bytes, period_start, period_end,
+                    trigger_type, recipients, emailed_at, email_error, created_at
+             FROM reports WHERE 1=1",
+            |qb| push_eq(qb, "format", format),
+            Some("id DESC"),
+            Some(limit),
+            Some(offset),
+        )
+        .await?;
+        Ok((reports, total))
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: i64) -> Result<Option<ReportRow>, ServiceError> {
+        sqlx::query_as::<_, ReportRow>(
+            "SELECT id, title, format, file_name, stored_name, size_bytes, period_start, period_end,
+                    trigger_type, recipients, emailed_at, email_error, created_at
+             FROM reports WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_error("finding report", e))
+    }
+
+    pub async fn insert(pool: &SqlitePool, report: &NewReport) -> Result<i64, ServiceError> {
+        sqlx::query_scalar::<_, i64>(
+            "INSERT INTO reports (title, format, file_name, stored_name, size_bytes, period_start,
+                                  period_end, trigger_type, recipients, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+             RETURNING id",
+        )
+        .bind(&report.title)
+        .bind(report.format.as_str())
+        .bind(&report.file_name)
+        .bind(&report.stored_name)
+        .bind(report.size_bytes)
+        .bind(report.period_start)
+        .bind(report.period_end)
+        .bind(report.trigger.as_str())
+        .bind(&report.recipients)
+        .bind(Utc::now().naive_utc())
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_error("inserting report", e))
+    }
+
+    /// Records the mail outcome for `ids`: `emailed_at` on success, `email_error` otherwise.
+    pub async fn record_delivery(
+        pool: &SqlitePool,
+        ids: &[i64],
+        error: Option<&str>,
+    ) -> Result<(), ServiceError> {
+        let emailed_at = error.is_none().then(|| Utc::now().naive_utc());
+        for id in ids {
+            sqlx::query("UPDATE reports SET emailed_at = ?, email_error = ? WHERE id = ?")
+                .bind(emailed_at)
+                .bind(error)
+                .bind(id)
+                .execute(pool)
+                .await
+                .map_err(|e| db_error("recording report delivery", e))?;
+        }
+        Ok(())
+    }
+}
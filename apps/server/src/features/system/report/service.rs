@@ -0,0 +1,351 @@
+use super::{
+    repo::ReportRepository,
+    types::{
+        NewReport, ReportData, ReportFormat, ReportItemResp, ReportQuery, ReportRow, ReportTrigger,
+    },
+};
+use crate::{
+    common::{
+        error::ServiceError,
+        pagination::{Pagination, PaginationQuery},
+        pdf,
+        validation::FieldErrors,
+        xlsx::{self, Cell, Sheet},
+    },
+    features::{
+        dashboard::{
+            service::DashboardService,
+            types::{DashboardQuery, TopItem, TopQuery},
+        },
+        manage::log::{service::LogService, types::LogRouteStatsQuery},
+    },
+    infra::{
+        config::AppConfig,
+        db::DbExecutor,
+        mail::{self, Attachment, MailMessage, SmtpServer},
+    },
+};
+
+use chrono::{Duration, Utc};
+use sqlx::SqlitePool;
+
+const REPORT_DAYS: i64 = 7;
+const REPORT_TOP_LIMIT: i64 = 10;
+const REPORT_ROUTE_LIMIT: usize = 50;
+const PDF_ROUTE_WIDTH: usize = 48;
+const MAIL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+pub struct ReportService;
+
+impl ReportService {
+    pub async fn list_reports(
+        pool: &SqlitePool,
+        query: ReportQuery,
+    ) -> Result<(Vec<ReportItemResp>, i64), ServiceError> {
+        let ReportQuery { current, page_size, format } = query;
+        let format = format.as_deref().map(str::trim).filter(|f| !f.is_empty());
+        let parsed = format.and_then(ReportFormat::parse);
+        let mut errors = FieldErrors::new();
+        if format.is_some() && parsed.is_none() {
+            errors.push("format", "must be xlsx or pdf");
+        }
+        errors.into_result()?;
+        let pagination = Pagination::from_query(PaginationQuery { current, page_size });
+        let (rows, total) = ReportRepository::list_reports(
+            pool,
+            pagination.offset.into(),
+            pagination.limit.into(),
+            parsed.map(ReportFormat::as_str),
+        )
+        .await?;
+        Ok((rows.into_iter().map(ReportItemResp::from).collect(), total))
+    }
+
+    /// Report row and file contents; `NotFound` when either is gone.
+    pub async fn download(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        id: i64,
+    ) -> Result<(ReportRow, Vec<u8>), ServiceError> {
+        let report = ReportRepository::find_by_id(pool, id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound(format!("Report {id}")))?;
+        let path = config.reports_dir().join(&report.stored_name);
+        let data = tokio::fs::read(&path).await.map_err(|err| {
+            tracing::warn!(id, path = %path.display(), "Report file unreadable: {}", err);
+            ServiceError::NotFound(format!("Report file {id}"))
+        })?;
+        Ok((report, data))
+    }
+
+    /// Renders the last seven days as one XLSX and one PDF report and stores both.
+    ///
+    /// With `RUSTZEN_REPORT_RECIPIENTS` set the pair is mailed too; a delivery failure is
+    /// recorded on the rows and returned, so the task run shows it.
+    pub async fn generate(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        trigger: ReportTrigger,
+    ) -> Result<Vec<i64>, ServiceError> {
+        let data = collect(config, &DbExecutor::new(pool.clone())).await?;
+        let title = format!(
+            "Weekly report {} to {}",
+            data.period_start.format("%Y-%m-%d"),
+            data.period_end.format("%Y-%m-%d")
+        );
+        let stamp = data.period_end.format("%Y%m%d_%H%M%S");
+        let recipients = config.report_recipient_list();
+        let xlsx = render_xlsx(&data).map_err(|err| {
+            ServiceError::InvalidOperation(format!("Failed to render XLSX report: {err}"))
+        })?;
+        let pdf = render_pdf(&title, &data);
+
+        let dir = config.reports_dir();
+        tokio::fs::create_dir_all(&dir).await.map_err(|err| {
+            ServiceError::InvalidOperation(format!("Failed to create reports directory: {err}"))
+        })?;
+        let mut ids = Vec::with_capacity(2);
+        let mut attachments = Vec::with_capacity(2);
+        for (format, bytes) in [(ReportFormat::Xlsx, xlsx), (ReportFormat::Pdf, pdf)] {
+            let stored_name = format!("{}.{}", uuid::Uuid::new_v4(), format.as_str());
+            tokio::fs::write(dir.join(&stored_name), &bytes).await.map_err(|err| {
+                ServiceError::InvalidOperation(format!("Failed to save report: {err}"))
+            })?;
+            let report = NewReport {
+                title: title.clone(),
+                format,
+                file_name: format!("report_{}.{}", stamp, format.as_str()),
+                stored_name,
+                size_bytes: bytes.len() as i64,
+                period_start: data.period_start,
+                period_end: data.period_end,
+                trigger,
+                recipients: (!recipients.is_empty()).then(|| recipients.join(",")),
+            };
+            ids.push(ReportRepository::insert(pool, &report).await?);
+            attachments.push(Attachment {
+                file_name: report.file_name,
+                content_type: format.content_type(),
+                data: bytes,
+            });
+        }
+        tracing::info!(?ids, trigger = trigger.as_str(), "Generated reports");
+
+        let (Some(server), Some(from)) =
+            (SmtpServer::from_config(config), config.mailer.smtp_from.as_deref())
+        else {
+            return Ok(ids);
+        };
+        if recipients.is_empty() {
+            return Ok(ids);
+        }
+        let message = MailMessage {
+            from: from.to_string(),
+            to: recipients,
+            subject: title.clone(),
+            body: format!("{}.\r\n\r\nThe XLSX and PDF versions are attached.\r\n", title),
+            attachments,
+        };
+        match mail::send(&server, &message, MAIL_TIMEOUT).await {
+            Ok(()) => {
+                ReportRepository::record_delivery(pool, &ids, None).await?;
+                Ok(ids)
+            }
+            Err(err) => {
+                tracing::error!(?ids, "Report email failed: {}", err);
+                ReportRepository::record_delivery(pool, &ids, Some(&err)).await?;
+                Err(ServiceError::InvalidOperation(format!("Report email failed: {err}")))
+            }
+        }
+    }
+}
+
+async fn collect(config: &AppConfig, db: &DbExecutor) -> Result<ReportData, ServiceError> {
+    let period_end = Utc::now().naive_utc();
+    let stats = DashboardService::get_stats(config, db.read()).await?;
+    let metrics = DashboardService::get_metrics(
+        config,
+        db,
+        DashboardQuery { days: Some(REPORT_DAYS), timezone: None },
+    )
+    .await?;
+    let top = DashboardService::get_top(
+        config,
+        db.read(),
+        TopQuery { days: Some(REPORT_DAYS), limit: Some(REPORT_TOP_LIMIT) },
+    )
+    .await?;
+    let mut routes =
+        LogService::route_stats(db.read(), LogRouteStatsQuery { hours: Some(REPORT_DAYS * 24) })
+            .await?;
+    routes.truncate(REPORT_ROUTE_LIMIT);
+    Ok(ReportData {
+        period_start: period_end - Duration::days(REPORT_DAYS),
+        period_end,
+        stats,
+        metrics,
+        top,
+        routes,
+    })
+}
+
+fn summary_rows(data: &ReportData) -> Vec<(&'static str, Cell)> {
+    let datetime = |value: chrono::NaiveDateTime| value.format("%Y-%m-%d %H:%M UTC").to_string();
+    vec![
+        ("Period start", datetime(data.period_start).into()),
+        ("Period end", datetime(data.period_end).into()),
+        ("Total users", data.stats.total_users.into()),
+        ("Active users", data.stats.active_users.into()),
+        ("Pending users", data.stats.pending_users.into()),
+        ("Logins today", data.stats.today_logins.into()),
+        ("System uptime", data.stats.system_uptime.clone().into()),
+        ("Requests", data.metrics.total_requests.into()),
+        ("Avg response (ms)", data.metrics.avg_response_time.into()),
+        ("Error rate (%)", data.metrics.error_rate.into()),
+        ("Slow requests", (data.metrics.slow_requests as i64).into()),
+        ("Slow queries", (data.metrics.slow_queries as i64).into()),
+    ]
+}
+
+fn top_lists(data: &ReportData) -> [(&'static str, &[TopItem]); 3] {
+    [
+        ("Top actions", &data.top.top_actions),
+        ("Most active users", &data.top.top_users),
+        ("Most failing endpoints", &data.top.top_error_endpoints),
+    ]
+}
+
+fn render_xlsx(data: &ReportData) -> Result<Vec<u8>, String> {
+    let mut summary = vec![vec![Cell::from("Metric"), Cell::from("Value")]];
+    summary.extend(summary_rows(data).into_iter().map(|(name, value)| vec![name.into(), value]));
+
+    let mut top = vec![vec![Cell::from("List"), Cell::from("Name"), Cell::from("Count")]];
+    for (list, items) in top_lists(data) {
+        top.extend(
+            items.iter().map(|item| vec![list.into(), item.name.clone().into(), item.count.into()]),
+        );
+    }
+
+    let mut routes = vec![
+        ["Method", "Route", "Requests", "Errors", "Avg (ms)", "Max (ms)"]
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Vec<_>>(),
+    ];
+    routes.extend(data.routes.iter().map(|route| {
+        vec![
+            route.method.clone().into(),
+            route.route.clone().into(),
+            route.requests.into(),
+            route.errors.into(),
+            route.avg_duration_ms.into(),
+            route.max_duration_ms.into(),
+        ]
+    }));
+
+    xlsx::write_workbook(&[
+        Sheet { name: "Summary".to_string(), rows: summary },
+        Sheet { name: "Top".to_string(), rows: top },
+        Sheet { name: "Routes".to_string(), rows: routes },
+    ])
+}
+
+fn render_pdf(title: &str, data: &ReportData) -> Vec<u8> {
+    let mut lines = vec!["Summary".to_string(), String::new()];
+    for (name, value) in summary_rows(data) {
+        let value = match value {
+            Cell::Text(text) => text,
+            Cell::Number(number) => number.to_string(),
+        };
+        lines.push(format!("  {:<20} {}", name, value));
+    }
+    for (list, items) in top_lists(data) {
+        lines.extend([String::new(), list.to_string(), String::new()]);
+        if items.is_empty() {
+            lines.push("  (none)".to_string());
+        }
+        lines.extend(items.iter().map(|item| format!("  {:>8}  {}", item.count, item.name)));
+    }
+    lines.extend([String::new(), "Routes".to_string(), String::new()]);
+    lines.push(format!(
+        "  {:<7} {:<w$} {:>8} {:>6} {:>8}",
+        "Method",
+        "Route",
+        "Requests",
+        "Errors",
+        "Avg ms",
+        w = PDF_ROUTE_WIDTH
+    ));
+    for route in &data.routes {
+        let name: String = route.route.chars().take(PDF_ROUTE_WIDTH).collect();
+        lines.push(format!(
+            "  {:<7} {:<w$} {:>8} {:>6} {:>8.1}",
+            route.method,
+            name,
+            route.requests,
+            route.errors,
+            route.avg_duration_ms,
+            w = PDF_ROUTE_WIDTH
+        ));
+    }
+    pdf::write_text_document(title, &lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_pdf, render_xlsx};
+    use crate::features::{
+        dashboard::types::{StatsResp, SystemMetricsDataResp, TopItem, TopResp},
+        manage::log::types::LogRouteStatsResp,
+        system::report::types::ReportData,
+    };
+
+    use chrono::NaiveDate;
+    use std::io::{Cursor, Read};
+
+    fn data() -> ReportData {
+        let day = NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+        ReportData {
+            period_start: day.and_hms_opt(7, 0, 0).unwrap(),
+            period_end: day.and_hms_opt(7, 0, 0).unwrap() + chrono::Duration::days(7),
+            stats: StatsResp { total_users: 12, ..Default::default() },
+            metrics: SystemMetricsDataResp { error_rate: 2.5, ..Default::default() },
+            top: TopResp {
+                top_users: vec![
+                    TopItem { name: "admin".to_string(), count: 31 },
+                    TopItem { name: "\u{7ba1}\u{7406}\u{5458}".to_string(), count: 5 },
+                ],
+                ..Default::default()
+            },
+            routes: vec![LogRouteStatsResp {
+                method: "GET".to_string(),
+                route: "/api/system/users".to_string(),
+                requests: 40,
+                errors: 2,
+                avg_duration_ms: 12.5,
+                max_duration_ms: 90,
+            }],
+        }
+    }
+
+    #[test]
+    fn both_formats_carry_the_same_figures() {
+        let data = data();
+
+        let xlsx = render_xlsx(&data).unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(xlsx)).unwrap();
+        let mut summary = String::new();
+        archive.by_name("xl/worksheets/sheet1.xml").unwrap().read_to_string(&mut summary).unwrap();
+        assert!(summary.contains("2026-10-19 07:00 UTC"));
+        assert!(summary.contains("<v>12</v>") && summary.contains("<v>2.5</v>"));
+        let mut routes = String::new();
+        archive.by_name("xl/worksheets/sheet3.xml").unwrap().read_to_string(&mut routes).unwrap();
+        assert!(routes.contains("/api/system/users"));
+
+        let pdf = String::from_utf8(render_pdf("Weekly report", &data)).unwrap();
+        assert!(pdf.contains("(  Total users          12) Tj"));
+        assert!(pdf.contains("(        31  admin) Tj"));
+        assert!(pdf.contains(r"(         5  \200\201\202) Tj") && pdf.contains("/u7BA1 "));
+        assert!(pdf.contains("/api/system/users"));
+    }
+}
@@ -0,0 +1,150 @@
+use crate::features::{
+    dashboard::types::{StatsResp, SystemMetricsDataResp, TopResp},
+    manage::log::types::LogRouteStatsResp,
+};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Report file format stored in `reports.format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Xlsx,
+    Pdf,
+}
+
+impl ReportFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReportFormat::Xlsx => "xlsx",
+            ReportFormat::Pdf => "pdf",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "xlsx" => Some(ReportFormat::Xlsx),
+            "pdf" => Some(ReportFormat::Pdf),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ReportFormat::Xlsx => {
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            }
+            ReportFormat::Pdf => "application/pdf",
+        }
+    }
+}
+
+/// What started a report run, stored in `reports.trigger_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportTrigger {
+    Scheduled,
+    Manual,
+}
+
+impl ReportTrigger {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReportTrigger::Scheduled => "scheduled",
+            ReportTrigger::Manual => "manual",
+        }
+    }
+}
+
+/// Report row as read from the database.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ReportRow {
+    pub id: i64,
+    pub title: String,
+    pub format: String,
+    pub file_name: String,
+    pub stored_name: String,
+    pub size_bytes: i64,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub trigger_type: String,
+    pub recipients: Option<String>,
+    pub emailed_at: Option<DateTime<Utc>>,
+    pub email_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Report for list display.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportItemResp {
+    pub id: i64,
+    pub title: String,
+    pub format: String,
+    pub file_name: String,
+    pub size_bytes: i64,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub trigger_type: String,
+    pub recipients: Vec<String>,
+    /// Set once the relay accepted the message.
+    pub emailed_at: Option<DateTime<Utc>>,
+    pub email_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ReportRow> for ReportItemResp {
+    fn from(row: ReportRow) -> Self {
+        Self {
+            id: row.id,
+            title: row.title,
+            format: row.format,
+            file_name: row.file_name,
+            size_bytes: row.size_bytes,
+            period_start: row.period_start,
+            period_end: row.period_end,
+            trigger_type: row.trigger_type,
+            recipients: row
+                .recipients
+                .map(|r| r.split(',').map(str::to_string).collect())
+                .unwrap_or_default(),
+            emailed_at: row.emailed_at,
+            email_error: row.email_error,
+            created_at: row.created_at,
+        }
+    }
+}
+
+/// Report query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportQuery {
+    pub current: Option<i64>,
+    pub page_size: Option<i64>,
+    /// `xlsx` or `pdf`.
+    pub format: Option<String>,
+}
+
+/// Generated file to record in `reports`.
+#[derive(Debug, Clone)]
+pub struct NewReport {
+    pub title: String,
+    pub format: ReportFormat,
+    pub file_name: String,
+    pub stored_name: String,
+    pub size_bytes: i64,
+    pub period_start: NaiveDateTime,
+    pub period_end: NaiveDateTime,
+    pub trigger: ReportTrigger,
+    pub recipients: Option<String>,
+}
+
+/// Figures rendered into every report format.
+#[derive(Debug, Clone)]
+pub struct ReportData {
+    pub period_start: NaiveDateTime,
+    pub period_end: NaiveDateTime,
+    pub stats: StatsResp,
+    pub metrics: SystemMetricsDataResp,
+    pub top: TopResp,
+    pub routes: Vec<LogRouteStatsResp>,
+}
@@ -1,60 +1,189 @@
 use super::{
     service::RoleService,
-    types::{CreateRoleRequest, RoleItemResp, RoleQuery, UpdateRolePayload},
+    types::{
+        CreateRoleRequest, RoleAccessResp, RoleExportDoc, RoleImportQuery, RoleImportResp,
+        RoleItemResp, RoleMemberQuery, RoleMemberResp, RoleMembersChangeResp, RoleQuery,
+        TransferRoleMembersPayload, UpdateRoleAccessPayload, UpdateRoleMembersPayload,
+        UpdateRolePayload,
+    },
+};
+use crate::{
+    common::{
+        api::{ApiResponse, AppResult, OptionItem, OptionsQuery, PageMeta},
+        ids::{RoleId, UserId},
+        pagination::{Pagination, PaginationQuery},
+    },
+    features::{
+        auth::service::AuthService,
+        system::{
+            approval::{service::ApprovalService, types::ApprovalAction},
+            recycle_bin::{service::RecycleBinService, types::RestoreRoleRequest},
+        },
+    },
+    infra::{config::AppConfig, db::DbExecutor},
 };
-use crate::common::api::{ApiResponse, AppResult, OptionItem, OptionsQuery};
 
 use axum::{
-    Json,
+    Extension, Json,
     extract::{Path, Query, State},
 };
-use rustzen_core::auth::CurrentUser;
+use rustzen_core::auth::{AuthClaims, CurrentUser};
 use sqlx::SqlitePool;
 
 /// Get paginated role list with filtering
 pub async fn list_roles(
-    State(pool): State<SqlitePool>,
+    State(db): State<DbExecutor>,
     Query(query): Query<RoleQuery>,
 ) -> AppResult<Vec<RoleItemResp>> {
-    let (role_list, total) = RoleService::list_roles(&pool, query).await?;
-    Ok(ApiResponse::page(role_list, total))
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (role_list, total) = RoleService::list_roles(db.read(), query).await?;
+    Ok(ApiResponse::page(role_list, total, PageMeta::new(pagination, total)))
 }
 
 /// Create new role
 pub async fn create_role(
     current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
     State(pool): State<SqlitePool>,
     Json(request): Json<CreateRoleRequest>,
 ) -> AppResult<()> {
-    RoleService::create_role(&pool, current_user.user_id, request).await?;
+    RoleService::create_role(config, &pool, UserId(current_user.user_id), request).await?;
     Ok(ApiResponse::success(()))
 }
 
 /// Update role information
 pub async fn update_role(
     current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    Extension(claims): Extension<AuthClaims>,
     State(pool): State<SqlitePool>,
-    Path(id): Path<i64>,
+    Path(id): Path<RoleId>,
     Json(request): Json<UpdateRolePayload>,
 ) -> AppResult<()> {
-    RoleService::update_role(&pool, id, current_user.user_id, request).await?;
+    AuthService::require_recent_auth(config, &claims)?;
+    RoleService::update_role(&pool, id, UserId(current_user.user_id), request).await?;
     Ok(ApiResponse::success(()))
 }
 
-/// Delete role with dependency validation
+/// Delete role with dependency validation, after approval under dual control
 pub async fn delete_role(
     current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    Extension(claims): Extension<AuthClaims>,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<RoleId>,
+) -> AppResult<()> {
+    AuthService::require_recent_auth(config, &claims)?;
+    ApprovalService::require(
+        config,
+        &pool,
+        &current_user,
+        ApprovalAction::RoleDelete { role_id: id },
+    )
+    .await?;
+    RoleService::delete_role(&pool, id, UserId(current_user.user_id)).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Restore a soft-deleted role, renaming fields a live role has taken since
+pub async fn restore_role(
+    Extension(config): Extension<&'static AppConfig>,
     State(pool): State<SqlitePool>,
-    Path(id): Path<i64>,
+    Path(id): Path<RoleId>,
+    request: Option<Json<RestoreRoleRequest>>,
 ) -> AppResult<()> {
-    RoleService::delete_role(&pool, id, current_user.user_id).await?;
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    RecycleBinService::restore_role(config, &pool, id, request).await?;
     Ok(ApiResponse::success(()))
 }
 
 /// Get role options for dropdowns
 pub async fn get_role_options(
-    State(pool): State<SqlitePool>,
+    State(db): State<DbExecutor>,
     Query(query): Query<OptionsQuery>,
-) -> AppResult<Vec<OptionItem<i64>>> {
-    Ok(ApiResponse::success(RoleService::get_role_options(&pool, query).await?))
+) -> AppResult<Vec<OptionItem<RoleId>>> {
+    Ok(ApiResponse::success(RoleService::get_role_options(db.read(), query).await?))
+}
+
+/// Get paginated users assigned to a role
+pub async fn list_role_members(
+    State(db): State<DbExecutor>,
+    Path(id): Path<RoleId>,
+    Query(query): Query<RoleMemberQuery>,
+) -> AppResult<Vec<RoleMemberResp>> {
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (members, total) = RoleService::list_role_members(db.read(), id, query).await?;
+    Ok(ApiResponse::page(members, total, PageMeta::new(pagination, total)))
+}
+
+/// Add or remove role members
+pub async fn update_role_members(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<RoleId>,
+    Json(payload): Json<UpdateRoleMembersPayload>,
+) -> AppResult<RoleMembersChangeResp> {
+    Ok(ApiResponse::success(
+        RoleService::update_role_members(&pool, id, UserId(current_user.user_id), payload).await?,
+    ))
+}
+
+/// Move all role members to another role
+pub async fn transfer_role_members(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<RoleId>,
+    Json(payload): Json<TransferRoleMembersPayload>,
+) -> AppResult<RoleMembersChangeResp> {
+    Ok(ApiResponse::success(
+        RoleService::transfer_role_members(&pool, id, UserId(current_user.user_id), payload)
+            .await?,
+    ))
+}
+
+/// Get the network and time restrictions of a role
+pub async fn get_role_access(
+    State(db): State<DbExecutor>,
+    Path(id): Path<RoleId>,
+) -> AppResult<RoleAccessResp> {
+    Ok(ApiResponse::success(RoleService::get_role_access(db.read(), id).await?))
+}
+
+/// Replace the network and time restrictions of a role
+pub async fn update_role_access(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<RoleId>,
+    Json(payload): Json<UpdateRoleAccessPayload>,
+) -> AppResult<RoleAccessResp> {
+    Ok(ApiResponse::success(RoleService::update_role_access(&pool, id, payload).await?))
+}
+
+/// Export every custom role with its menus by code
+pub async fn export_roles(State(db): State<DbExecutor>) -> AppResult<RoleExportDoc> {
+    Ok(ApiResponse::success(RoleService::export_roles(db.read()).await?))
+}
+
+/// Create and update roles by code from an export; `dryRun=true` only reports the changes
+pub async fn import_roles(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    Extension(claims): Extension<AuthClaims>,
+    State(pool): State<SqlitePool>,
+    Query(query): Query<RoleImportQuery>,
+    Json(doc): Json<RoleExportDoc>,
+) -> AppResult<RoleImportResp> {
+    let dry_run = query.dry_run.unwrap_or(false);
+    if !dry_run {
+        AuthService::require_recent_auth(config, &claims)?;
+    }
+    Ok(ApiResponse::success(
+        RoleService::import_roles(config, &pool, UserId(current_user.user_id), doc, dry_run)
+            .await?,
+    ))
 }
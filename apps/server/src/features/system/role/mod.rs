@@ -3,13 +3,21 @@ pub mod repo;
 pub mod service;
 pub mod types;
 
+use crate::features::system::{
+    purge::handler::{get_role_purge_report, purge_role},
+    tag::handler::{get_role_tags, update_role_tags},
+};
 use axum::{
     Router,
     routing::{delete, get, post, put},
 };
-use handler::{create_role, delete_role, get_role_options, list_roles, update_role};
+use handler::{
+    create_role, delete_role, export_roles, get_role_access, get_role_options, import_roles,
+    list_role_members, list_roles, restore_role, transfer_role_members, update_role,
+    update_role_access, update_role_members,
+};
 use rustzen_core::{
-    capability::system_role,
+    capability::{system_role, system_tag},
     permission::{PermissionsCheck, RouterExt},
 };
 use sqlx::SqlitePool;
@@ -22,6 +30,16 @@ pub fn role_routes() -> Router<SqlitePool> {
             post(create_role),
             PermissionsCheck::Require(system_role::CREATE),
         )
+        .route_with_permission(
+            "/export",
+            get(export_roles),
+            PermissionsCheck::Require(system_role::EXPORT),
+        )
+        .route_with_permission(
+            "/import",
+            post(import_roles),
+            PermissionsCheck::Require(system_role::IMPORT),
+        )
         .route_with_permission(
             "/{id}",
             put(update_role),
@@ -32,9 +50,59 @@ pub fn role_routes() -> Router<SqlitePool> {
             delete(delete_role),
             PermissionsCheck::Require(system_role::DELETE),
         )
+        .route_with_permission(
+            "/{id}/restore",
+            put(restore_role),
+            PermissionsCheck::Require(system_role::RESTORE),
+        )
+        .route_with_permission(
+            "/{id}/purge",
+            get(get_role_purge_report),
+            PermissionsCheck::Require(system_role::PURGE),
+        )
+        .route_with_permission(
+            "/{id}/purge",
+            delete(purge_role),
+            PermissionsCheck::Require(system_role::PURGE),
+        )
         .route_with_permission(
             "/options",
             get(get_role_options),
             PermissionsCheck::Require(system_role::OPTIONS),
         )
+        .route_with_permission(
+            "/{id}/users",
+            get(list_role_members),
+            PermissionsCheck::Require(system_role::MEMBERS),
+        )
+        .route_with_permission(
+            "/{id}/users",
+            post(update_role_members),
+            PermissionsCheck::Require(system_role::MEMBERS),
+        )
+        .route_with_permission(
+            "/{id}/users/transfer",
+            post(transfer_role_members),
+            PermissionsCheck::Require(system_role::MEMBERS),
+        )
+        .route_with_permission(
+            "/{id}/tags",
+            get(get_role_tags),
+            PermissionsCheck::Require(system_role::LIST),
+        )
+        .route_with_permission(
+            "/{id}/tags",
+            put(update_role_tags),
+            PermissionsCheck::Require(system_tag::ASSIGN),
+        )
+        .route_with_permission(
+            "/{id}/access",
+            get(get_role_access),
+            PermissionsCheck::Require(system_role::LIST),
+        )
+        .route_with_permission(
+            "/{id}/access",
+            put(update_role_access),
+            PermissionsCheck::Require(system_role::UPDATE),
+        )
 }
@@ -1,23 +1,51 @@
 use crate::common::{
     error::ServiceError,
-    query::{count_with_filters, fetch_with_filters, push_eq, push_ilike},
+    ids::{MenuId, RoleId, UserId},
+    pagination::Sort,
+    query::{
+        OptionsFilter, OptionsSql, count_with_filters, fetch_options, fetch_with_filters, push_eq,
+        push_ilike,
+    },
+    tx::{self, Tx},
 };
 
 use chrono::Utc;
 use sqlx::{QueryBuilder, Sqlite, SqlitePool};
 
-use super::types::{RoleListQuery, RoleWithMenusRow};
+use super::types::{
+    RoleAccessRow, RoleListQuery, RoleMemberRow, RoleSnapshotRow, RoleWithMenusRow,
+};
+
+const ROLE_OPTIONS: OptionsSql = OptionsSql {
+    base_sql: "SELECT id, name FROM roles WHERE deleted_at IS NULL",
+    value_column: "id",
+    status_column: "status",
+    search_columns: &["name"],
+    order_by: "name ASC",
+};
 
 pub struct RoleRepository;
 
 impl RoleRepository {
+    /// Sortable list fields mapped to `role_with_menus` columns.
+    pub const SORT_COLUMNS: &[(&str, &str)] = &[
+        ("name", "name"),
+        ("code", "code"),
+        ("status", "status"),
+        ("createdAt", "created_at"),
+        ("updatedAt", "updated_at"),
+    ];
+
     fn format_query(query: &RoleListQuery, query_builder: &mut QueryBuilder<Sqlite>) {
-        push_ilike(query_builder, "role_name", query.role_name.as_deref());
-        push_ilike(query_builder, "role_code", query.role_code.as_deref());
+        push_ilike(query_builder, "name", query.role_name.as_deref());
+        push_ilike(query_builder, "code", query.role_code.as_deref());
         push_eq(query_builder, "status", query.status);
     }
 
-    /// Queries roles with pagination
+    /// Queries roles with pagination.
+    ///
+    /// Menus come pre-aggregated from the `role_with_menus` view, so a page of roles is
+    /// one count plus one select regardless of how many roles it holds.
     pub async fn list_roles(
         pool: &SqlitePool,
         offset: i64,
@@ -35,13 +63,14 @@ impl RoleRepository {
         if total == 0 {
             return Ok((Vec::new(), total));
         }
+        let order_by = query.sort.map(Sort::to_order_by);
         let roles = fetch_with_filters(
             pool,
             "SELECT id, name, code, description, status, created_at, updated_at, is_system, menus FROM role_with_menus WHERE 1=1",
             |query_builder| {
                 Self::format_query(&query, query_builder);
             },
-            Some("created_at DESC"),
+            Some(order_by.as_deref().unwrap_or("created_at DESC")),
             Some(limit),
             Some(offset),
         )
@@ -57,15 +86,29 @@ impl RoleRepository {
         role_code: &str,
         description: Option<&str>,
         status: i16,
-        menu_ids: &[i64],
-    ) -> Result<i64, ServiceError> {
-        let mut tx = pool.begin().await.map_err(|e| {
-            tracing::error!("Database error starting transaction for role creation: {:?}", e);
-            ServiceError::DatabaseQueryFailed
-        })?;
+        menu_ids: &[MenuId],
+    ) -> Result<RoleId, ServiceError> {
+        let mut tx = tx::begin(pool).await?;
+        let role_id =
+            Self::create_in_tx(&mut tx, role_name, role_code, description, status, menu_ids)
+                .await?;
+        tx::commit(tx).await?;
+
+        Ok(role_id)
+    }
+
+    /// Creates a new role with its menus inside the caller's transaction
+    pub async fn create_in_tx(
+        tx: &mut Tx<'_>,
+        role_name: &str,
+        role_code: &str,
+        description: Option<&str>,
+        status: i16,
+        menu_ids: &[MenuId],
+    ) -> Result<RoleId, ServiceError> {
         let now = Utc::now().naive_utc();
 
-        let role_id = sqlx::query_scalar::<_, i64>(
+        let role_id = sqlx::query_scalar::<_, RoleId>(
             "INSERT INTO roles (name, code, description, status, created_at, updated_at)
              VALUES (?, ?, ?, ?, ?, ?)
              RETURNING id",
@@ -76,39 +119,28 @@ impl RoleRepository {
         .bind(status)
         .bind(now)
         .bind(now)
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut **tx)
         .await
         .map_err(|e| {
             tracing::error!("Database error creating role: {:?}", e);
             ServiceError::DatabaseQueryFailed
         })?;
 
-        Self::insert_role_menus(&mut tx, role_id, menu_ids).await?;
-
-        tx.commit().await.map_err(|e| {
-            tracing::error!("Database error committing role creation transaction: {:?}", e);
-            ServiceError::DatabaseQueryFailed
-        })?;
-
+        Self::insert_role_menus(tx, role_id, menu_ids).await?;
         Ok(role_id)
     }
 
-    /// Updates an existing role
-    pub async fn update(
-        pool: &SqlitePool,
-        id: i64,
+    /// Updates an existing role and replaces its menus inside the caller's transaction
+    pub async fn update_in_tx(
+        tx: &mut Tx<'_>,
+        id: RoleId,
         role_name: &str,
         role_code: &str,
         description: Option<&str>,
         status: i16,
-        menu_ids: &[i64],
-    ) -> Result<i64, ServiceError> {
-        let mut tx = pool.begin().await.map_err(|e| {
-            tracing::error!("Database error starting transaction for role update: {:?}", e);
-            ServiceError::DatabaseQueryFailed
-        })?;
-
-        let id_opt = sqlx::query_scalar::<_, i64>(
+        menu_ids: &[MenuId],
+    ) -> Result<RoleId, ServiceError> {
+        let id_opt = sqlx::query_scalar::<_, RoleId>(
             "UPDATE roles
                  SET name = ?, code = ?, description = ?, status = ?, updated_at = ?
                  WHERE id = ? AND deleted_at IS NULL
@@ -120,34 +152,27 @@ impl RoleRepository {
         .bind(status)
         .bind(Utc::now().naive_utc())
         .bind(id)
-        .fetch_optional(&mut *tx)
+        .fetch_optional(&mut **tx)
         .await
         .map_err(|e| {
             tracing::error!("Database error updating role: {:?}", e);
             ServiceError::DatabaseQueryFailed
         })?;
 
-        if let Some(id) = id_opt {
-            Self::insert_role_menus(&mut tx, id, menu_ids).await?;
-            tx.commit().await.map_err(|e| {
-                tracing::error!("Database error committing role update transaction: {:?}", e);
-                ServiceError::DatabaseQueryFailed
-            })?;
-            Ok(id)
-        } else {
-            Err(ServiceError::NotFound(format!("Role id: {}", id)))
-        }
+        let id = id_opt.ok_or_else(|| ServiceError::NotFound(format!("Role id: {}", id)))?;
+        Self::insert_role_menus(tx, id, menu_ids).await?;
+        Ok(id)
     }
 
-    /// Soft deletes a role
-    pub async fn soft_delete(pool: &SqlitePool, id: i64) -> Result<bool, ServiceError> {
+    /// Soft deletes a role inside the caller's transaction
+    pub async fn soft_delete_in_tx(tx: &mut Tx<'_>, id: RoleId) -> Result<bool, ServiceError> {
         let result = sqlx::query(
             "UPDATE roles SET deleted_at = ?, updated_at = ? WHERE id = ? AND deleted_at IS NULL",
         )
         .bind(Utc::now().naive_utc())
         .bind(Utc::now().naive_utc())
         .bind(id)
-        .execute(pool)
+        .execute(&mut **tx)
         .await
         .map_err(|e| {
             tracing::error!("Database error soft deleting role {}: {:?}", id, e);
@@ -157,9 +182,57 @@ impl RoleRepository {
         Ok(result.rows_affected() > 0)
     }
 
+    /// Restore a soft-deleted role under the given name and code, with its menus.
+    ///
+    /// Like the user restore, this fails when a live role holds the name or code; the
+    /// recycle bin checks first and offers renames.
+    pub async fn restore_deleted(
+        pool: &SqlitePool,
+        id: RoleId,
+        name: &str,
+        code: &str,
+    ) -> Result<bool, ServiceError> {
+        let result = sqlx::query(
+            "UPDATE roles SET name = ?, code = ?, deleted_at = NULL, updated_at = ?
+             WHERE id = ? AND deleted_at IS NOT NULL",
+        )
+        .bind(name)
+        .bind(code)
+        .bind(Utc::now().naive_utc())
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                tracing::warn!("Role {} name or code was taken while restoring", id);
+                ServiceError::InvalidOperation(
+                    "The role name or code was taken while restoring. Please try again."
+                        .to_string(),
+                )
+            }
+            e => {
+                tracing::error!("Database error restoring role {}: {:?}", id, e);
+                ServiceError::DatabaseQueryFailed
+            }
+        })?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Number of roles that are not soft-deleted.
+    pub async fn count_roles(pool: &SqlitePool) -> Result<i64, ServiceError> {
+        sqlx::query_scalar("SELECT COUNT(*) FROM roles WHERE deleted_at IS NULL")
+            .fetch_one(pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("Database error counting roles: {:?}", e);
+                ServiceError::DatabaseQueryFailed
+            })
+    }
+
     pub async fn get_role_identity(
         pool: &SqlitePool,
-        id: i64,
+        id: RoleId,
     ) -> Result<Option<(String, bool)>, ServiceError> {
         sqlx::query_as::<_, (String, bool)>(
             "SELECT code, is_system FROM roles WHERE id = ? AND deleted_at IS NULL",
@@ -173,11 +246,12 @@ impl RoleRepository {
         })
     }
 
-    /// insert role_menus
+    /// Replace role_menus with one multi-row insert; duplicate ids are collapsed first
+    /// so the `UNIQUE(role_id, menu_id)` constraint cannot fail the batch.
     async fn insert_role_menus(
-        tx: &mut sqlx::Transaction<'_, Sqlite>,
-        role_id: i64,
-        menu_ids: &[i64],
+        tx: &mut Tx<'_>,
+        role_id: RoleId,
+        menu_ids: &[MenuId],
     ) -> Result<(), ServiceError> {
         sqlx::query("DELETE FROM role_menus WHERE role_id = ?")
             .bind(role_id)
@@ -187,6 +261,9 @@ impl RoleRepository {
                 tracing::error!("Database error deleting existing role_menus: {:?}", e);
                 ServiceError::DatabaseQueryFailed
             })?;
+        let mut menu_ids = menu_ids.to_vec();
+        menu_ids.sort_unstable();
+        menu_ids.dedup();
         if menu_ids.is_empty() {
             return Ok(());
         }
@@ -207,27 +284,19 @@ impl RoleRepository {
     /// Retrieves role list for Options API
     pub async fn list_role_options(
         pool: &SqlitePool,
-        search_query: Option<&str>,
-        limit: Option<i64>,
-    ) -> Result<Vec<(i64, String)>, ServiceError> {
-        fetch_with_filters(
-            pool,
-            "SELECT id, name FROM roles WHERE status = 1 AND deleted_at IS NULL",
-            |query_builder| {
-                push_ilike(query_builder, "name", search_query);
-            },
-            Some("name ASC"),
-            limit,
-            None,
-        )
-        .await
+        filter: &OptionsFilter<RoleId>,
+    ) -> Result<Vec<(RoleId, String)>, ServiceError> {
+        fetch_options(pool, &ROLE_OPTIONS, filter, |_| {}).await
     }
 
-    pub async fn get_role_user_count(pool: &SqlitePool, role_id: i64) -> Result<i64, ServiceError> {
+    pub async fn get_role_user_count_in_tx(
+        tx: &mut Tx<'_>,
+        role_id: RoleId,
+    ) -> Result<i64, ServiceError> {
         let result =
             sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM user_roles WHERE role_id = ?")
                 .bind(role_id)
-                .fetch_one(pool)
+                .fetch_one(&mut **tx)
                 .await
                 .map_err(|e| {
                     tracing::error!("Database error getting role user count: {:?}", e);
@@ -236,9 +305,353 @@ impl RoleRepository {
         Ok(result)
     }
 
+    /// Lists live users assigned to a role, newest assignment first
+    pub async fn list_role_members(
+        pool: &SqlitePool,
+        role_id: RoleId,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<RoleMemberRow>, i64), ServiceError> {
+        let total = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM user_roles ur
+             JOIN users u ON u.id = ur.user_id
+             WHERE ur.role_id = ? AND u.deleted_at IS NULL",
+        )
+        .bind(role_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error counting members of role {}: {:?}", role_id, e);
+            ServiceError::DatabaseQueryFailed
+        })?;
+
+        let members = sqlx::query_as::<_, RoleMemberRow>(
+            "SELECT u.id, u.username, u.real_name, u.status, ur.created_at AS assigned_at,
+                    ur.expires_at
+             FROM user_roles ur
+             JOIN users u ON u.id = ur.user_id
+             WHERE ur.role_id = ? AND u.deleted_at IS NULL
+             ORDER BY ur.created_at DESC, u.id DESC
+             LIMIT ? OFFSET ?",
+        )
+        .bind(role_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error listing members of role {}: {:?}", role_id, e);
+            ServiceError::DatabaseQueryFailed
+        })?;
+
+        Ok((members, total))
+    }
+
+    /// Returns `(id, is_system)` for the live users among `user_ids`
+    pub async fn find_live_users_in_tx(
+        tx: &mut Tx<'_>,
+        user_ids: &[UserId],
+    ) -> Result<Vec<(UserId, bool)>, ServiceError> {
+        if user_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "SELECT id, is_system FROM users WHERE deleted_at IS NULL AND id IN (",
+        );
+        let mut separated = query_builder.separated(", ");
+        for user_id in user_ids {
+            separated.push_bind(user_id);
+        }
+        separated.push_unseparated(")");
+
+        query_builder.build_query_as().fetch_all(&mut **tx).await.map_err(|e| {
+            tracing::error!("Database error loading users for role membership: {:?}", e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
+    /// Returns `(user_id, is_system)` for every user assigned to a role
+    pub async fn list_member_ids_in_tx(
+        tx: &mut Tx<'_>,
+        role_id: RoleId,
+    ) -> Result<Vec<(UserId, bool)>, ServiceError> {
+        sqlx::query_as::<_, (UserId, bool)>(
+            "SELECT u.id, u.is_system FROM user_roles ur
+             JOIN users u ON u.id = ur.user_id
+             WHERE ur.role_id = ?",
+        )
+        .bind(role_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error listing member ids of role {}: {:?}", role_id, e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
+    /// Assigns users to a role, skipping existing assignments; returns the users added
+    pub async fn add_members_in_tx(
+        tx: &mut Tx<'_>,
+        role_id: RoleId,
+        user_ids: &[UserId],
+    ) -> Result<Vec<UserId>, ServiceError> {
+        if user_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let now = Utc::now().naive_utc();
+        let mut query_builder: QueryBuilder<Sqlite> =
+            QueryBuilder::new("INSERT OR IGNORE INTO user_roles (user_id, role_id, created_at) ");
+        query_builder.push_values(user_ids.iter(), |mut builder, user_id| {
+            builder.push_bind(user_id).push_bind(role_id).push_bind(now);
+        });
+        query_builder.push(" RETURNING user_id");
+
+        query_builder.build_query_scalar().fetch_all(&mut **tx).await.map_err(|e| {
+            tracing::error!("Database error adding members to role {}: {:?}", role_id, e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
+    /// Unassigns users from a role; returns the users removed
+    pub async fn remove_members_in_tx(
+        tx: &mut Tx<'_>,
+        role_id: RoleId,
+        user_ids: &[UserId],
+    ) -> Result<Vec<UserId>, ServiceError> {
+        if user_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut query_builder: QueryBuilder<Sqlite> =
+            QueryBuilder::new("DELETE FROM user_roles WHERE role_id = ");
+        query_builder.push_bind(role_id).push(" AND user_id IN (");
+        let mut separated = query_builder.separated(", ");
+        for user_id in user_ids {
+            separated.push_bind(user_id);
+        }
+        separated.push_unseparated(") RETURNING user_id");
+
+        query_builder.build_query_scalar().fetch_all(&mut **tx).await.map_err(|e| {
+            tracing::error!("Database error removing members from role {}: {:?}", role_id, e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
+    /// Moves every assignment of `from_role_id` onto `to_role_id`.
+    ///
+    /// Users already holding the target role keep a single assignment. Returns the
+    /// rows inserted into the target and the rows removed from the source; both sides
+    /// are written to `user_role_history`.
+    pub async fn transfer_members_in_tx(
+        tx: &mut Tx<'_>,
+        from_role_id: RoleId,
+        to_role_id: RoleId,
+        operator_id: UserId,
+    ) -> Result<(u64, u64), ServiceError> {
+        let now = Utc::now().naive_utc();
+        sqlx::query(
+            "INSERT INTO user_role_history (user_id, role_id, action, operator_id, created_at)
+             SELECT user_id, ?, 'assigned', ?, ? FROM user_roles
+             WHERE role_id = ?
+               AND user_id NOT IN (SELECT user_id FROM user_roles WHERE role_id = ?)",
+        )
+        .bind(to_role_id)
+        .bind(operator_id)
+        .bind(now)
+        .bind(from_role_id)
+        .bind(to_role_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error recording transfer history: {:?}", e);
+            ServiceError::DatabaseQueryFailed
+        })?;
+        sqlx::query(
+            "INSERT INTO user_role_history (user_id, role_id, action, operator_id, created_at)
+             SELECT user_id, role_id, 'removed', ?, ? FROM user_roles WHERE role_id = ?",
+        )
+        .bind(operator_id)
+        .bind(now)
+        .bind(from_role_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error recording transfer history: {:?}", e);
+            ServiceError::DatabaseQueryFailed
+        })?;
+
+        let added = sqlx::query(
+            "INSERT OR IGNORE INTO user_roles (user_id, role_id, created_at, expires_at)
+             SELECT user_id, ?, ?, expires_at FROM user_roles WHERE role_id = ?",
+        )
+        .bind(to_role_id)
+        .bind(now)
+        .bind(from_role_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                "Database error copying members from role {} to {}: {:?}",
+                from_role_id,
+                to_role_id,
+                e
+            );
+            ServiceError::DatabaseQueryFailed
+        })?
+        .rows_affected();
+
+        let removed = sqlx::query("DELETE FROM user_roles WHERE role_id = ?")
+            .bind(from_role_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| {
+                tracing::error!(
+                    "Database error clearing members of role {}: {:?}",
+                    from_role_id,
+                    e
+                );
+                ServiceError::DatabaseQueryFailed
+            })?
+            .rows_affected();
+
+        Ok((added, removed))
+    }
+
+    /// Network and time restrictions of a role, if it has any
+    pub async fn find_access(
+        pool: &SqlitePool,
+        role_id: RoleId,
+    ) -> Result<Option<RoleAccessRow>, ServiceError> {
+        sqlx::query_as::<_, RoleAccessRow>(
+            "SELECT role_id, allowed_cidrs, allowed_days, start_time, end_time, updated_at
+             FROM role_access_restrictions WHERE role_id = ?",
+        )
+        .bind(role_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error fetching access of role {}: {:?}", role_id, e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
+    /// Stores a role's restrictions; `cidrs` and `days` are JSON arrays
+    pub async fn upsert_access(
+        pool: &SqlitePool,
+        role_id: RoleId,
+        cidrs: &str,
+        days: &str,
+        hours: Option<(&str, &str)>,
+    ) -> Result<(), ServiceError> {
+        sqlx::query(
+            "INSERT INTO role_access_restrictions
+                 (role_id, allowed_cidrs, allowed_days, start_time, end_time, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT(role_id) DO UPDATE SET
+                 allowed_cidrs = excluded.allowed_cidrs,
+                 allowed_days = excluded.allowed_days,
+                 start_time = excluded.start_time,
+                 end_time = excluded.end_time,
+                 updated_at = excluded.updated_at",
+        )
+        .bind(role_id)
+        .bind(cidrs)
+        .bind(days)
+        .bind(hours.map(|(start, _)| start))
+        .bind(hours.map(|(_, end)| end))
+        .bind(Utc::now().naive_utc())
+        .execute(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error saving access of role {}: {:?}", role_id, e);
+            ServiceError::DatabaseQueryFailed
+        })?;
+        Ok(())
+    }
+
+    /// Lifts a role's restrictions
+    pub async fn delete_access(pool: &SqlitePool, role_id: RoleId) -> Result<(), ServiceError> {
+        sqlx::query("DELETE FROM role_access_restrictions WHERE role_id = ?")
+            .bind(role_id)
+            .execute(pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("Database error clearing access of role {}: {:?}", role_id, e);
+                ServiceError::DatabaseQueryFailed
+            })?;
+        Ok(())
+    }
+
+    /// Ids of every user assigned to a role
+    pub async fn list_member_user_ids(
+        pool: &SqlitePool,
+        role_id: RoleId,
+    ) -> Result<Vec<UserId>, ServiceError> {
+        sqlx::query_scalar::<_, UserId>("SELECT user_id FROM user_roles WHERE role_id = ?")
+            .bind(role_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("Database error listing user ids of role {}: {:?}", role_id, e);
+                ServiceError::DatabaseQueryFailed
+            })
+    }
+
+    /// Every live role with the codes of its live menus, ordered by code
+    pub async fn list_snapshots_in_tx(
+        tx: &mut Tx<'_>,
+    ) -> Result<Vec<RoleSnapshotRow>, ServiceError> {
+        sqlx::query_as::<_, RoleSnapshotRow>(
+            "SELECT r.id, r.name, r.code, r.description, r.status, r.is_system,
+                    COALESCE(
+                        (SELECT json_group_array(mc.code) FROM (
+                            SELECT m.code FROM role_menus rm
+                            JOIN menus m ON m.id = rm.menu_id AND m.deleted_at IS NULL
+                            WHERE rm.role_id = r.id
+                            ORDER BY m.code
+                        ) mc),
+                        '[]'
+                    ) AS menu_codes
+             FROM roles r
+             WHERE r.deleted_at IS NULL
+             ORDER BY r.code",
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error listing role snapshots: {:?}", e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
+    /// Ids of the live menus among `codes`; codes without a menu are left out
+    pub async fn find_menu_ids_by_codes_in_tx(
+        tx: &mut Tx<'_>,
+        codes: &[String],
+    ) -> Result<Vec<(String, MenuId)>, ServiceError> {
+        if codes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut query_builder: QueryBuilder<Sqlite> =
+            QueryBuilder::new("SELECT code, id FROM menus WHERE deleted_at IS NULL AND code IN (");
+        let mut separated = query_builder.separated(", ");
+        for code in codes {
+            separated.push_bind(code);
+        }
+        separated.push_unseparated(")");
+
+        query_builder.build_query_as().fetch_all(&mut **tx).await.map_err(|e| {
+            tracing::error!("Database error finding menus by codes: {:?}", e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
     pub async fn list_menu_codes_by_ids(
         pool: &SqlitePool,
-        menu_ids: &[i64],
+        menu_ids: &[MenuId],
     ) -> Result<Vec<String>, ServiceError> {
         if menu_ids.is_empty() {
             return Ok(Vec::new());
@@ -258,3 +671,59 @@ impl RoleRepository {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::RoleRepository;
+    use crate::{common::ids::MenuId, features::system::role::types::RoleListQuery};
+
+    #[tokio::test]
+    async fn role_list_returns_aggregated_menus_and_filters_by_name_and_code() {
+        let pool = crate::infra::db::test_pool().await;
+
+        let menu_ids: Vec<MenuId> = sqlx::query_scalar("SELECT id FROM menus ORDER BY id LIMIT 2")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        RoleRepository::create(&pool, "Ops", "ops", None, 1, &menu_ids).await.unwrap();
+        RoleRepository::create(&pool, "Support", "support", None, 1, &menu_ids[..1]).await.unwrap();
+
+        let query = |name: Option<&str>, code: Option<&str>| RoleListQuery {
+            role_name: name.map(str::to_string),
+            role_code: code.map(str::to_string),
+            status: None,
+            sort: None,
+        };
+        let (roles, _) =
+            RoleRepository::list_roles(&pool, 0, 50, query(None, Some("ops"))).await.unwrap();
+        assert_eq!(roles.len(), 1);
+        let menus = roles[0].menus.as_array().expect("menus json array");
+        assert_eq!(menus.len(), menu_ids.len());
+
+        let (roles, total) =
+            RoleRepository::list_roles(&pool, 0, 50, query(Some("supp"), None)).await.unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(roles[0].code, "support");
+        assert_eq!(roles[0].menus.as_array().map(Vec::len), Some(1));
+    }
+
+    #[tokio::test]
+    async fn role_menus_are_batch_inserted_without_duplicates() {
+        let pool = crate::infra::db::test_pool().await;
+
+        let menu_id: MenuId = sqlx::query_scalar("SELECT id FROM menus ORDER BY id LIMIT 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let role_id = RoleRepository::create(&pool, "Ops", "ops", None, 1, &[menu_id, menu_id])
+            .await
+            .unwrap();
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM role_menus WHERE role_id = ?")
+            .bind(role_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+}
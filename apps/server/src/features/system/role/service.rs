@@ -1,16 +1,37 @@
 use super::{
     repo::RoleRepository,
-    types::{CreateRoleRequest, RoleItemResp, RoleListQuery, RoleQuery, UpdateRolePayload},
+    types::{
+        ACCESS_TIME_FORMAT, CreateRoleRequest, ROLE_EXPORT_VERSION, RoleAccessResp, RoleExportDoc,
+        RoleExportItem, RoleImportAction, RoleImportItem, RoleImportResp, RoleItemResp,
+        RoleListQuery, RoleMemberQuery, RoleMemberResp, RoleMembersChangeResp, RoleQuery,
+        TransferRoleMembersPayload, UpdateRoleAccessPayload, UpdateRoleMembersPayload,
+        UpdateRolePayload,
+    },
 };
 use crate::common::{
     api::{OptionItem, OptionsQuery},
     error::ServiceError,
-    pagination::{Pagination, PaginationQuery},
-    query::parse_optional_i16_filter,
+    ids::{MenuId, RoleId, UserId},
+    pagination::{Pagination, PaginationQuery, Sort},
+    query::{OptionsFilter, parse_optional_i16_filter},
+    tx,
+    validation::FieldErrors,
+};
+use crate::features::system::{
+    quota::{service::QuotaService, types::QuotaResource},
+    user::{repo::UserRepository, types::RoleHistoryAction},
+};
+use crate::infra::{
+    access_window::IpRange, config::AppConfig, events, permission::PermissionService,
+};
+use rustzen_core::{
+    capability::{SYSTEM_WILDCARD, is_deploy_capability_code},
+    events::DomainEvent,
 };
-use rustzen_core::capability::{SYSTEM_WILDCARD, is_deploy_capability_code};
 
+use chrono::{NaiveTime, Utc};
 use sqlx::SqlitePool;
+use std::collections::HashMap;
 
 const OWNER_ROLE_CODE: &str = "owner";
 const BUILTIN_ROLE_CODES: &[&str] = &["owner", "admin", "viewer"];
@@ -25,12 +46,15 @@ impl RoleService {
     ) -> Result<(Vec<RoleItemResp>, i64), ServiceError> {
         tracing::info!("Fetching role list with query: {:?}", query);
 
-        let RoleQuery { current, page_size, role_name, role_code, status } = query;
+        let RoleQuery { current, page_size, role_name, role_code, status, sort_by, sort_order } =
+            query;
         let pagination = Pagination::from_query(PaginationQuery { current, page_size });
         let limit = i64::from(pagination.limit);
         let offset = i64::from(pagination.offset);
         let status = parse_optional_i16_filter(status.as_deref(), "role status", None)?;
-        let repo_query = RoleListQuery { role_name, role_code, status };
+        let sort =
+            Sort::resolve(sort_by.as_deref(), sort_order.as_deref(), RoleRepository::SORT_COLUMNS)?;
+        let repo_query = RoleListQuery { role_name, role_code, status, sort };
 
         let (roles, total) = RoleRepository::list_roles(pool, offset, limit, repo_query).await?;
 
@@ -39,15 +63,19 @@ impl RoleService {
 
     /// Create new role with validation
     pub async fn create_role(
+        config: &AppConfig,
         pool: &SqlitePool,
-        _current_user_id: i64,
+        current_user_id: UserId,
         request: CreateRoleRequest,
     ) -> Result<(), ServiceError> {
         tracing::info!("Creating role: {}", request.name);
         ensure_builtin_role_code_is_reserved(&request.code)?;
         Self::ensure_role_menus_are_assignable(pool, &request.menu_ids).await?;
-        RoleRepository::create(
-            pool,
+        QuotaService::ensure_slot(config, QuotaResource::Roles, RoleRepository::count_roles(pool))
+            .await?;
+        let mut tx = tx::begin(pool).await?;
+        let role_id = RoleRepository::create_in_tx(
+            &mut tx,
             &request.name,
             &request.code,
             request.description.as_deref(),
@@ -55,22 +83,32 @@ impl RoleService {
             &request.menu_ids,
         )
         .await?;
+        let event = DomainEvent::RoleCreated {
+            role_id: role_id.get(),
+            name: request.name,
+            code: request.code,
+            operator_id: current_user_id.get(),
+        };
+        let recorded = events::record(&mut tx, &event).await?;
+        tx::commit(tx).await?;
+        events::deliver(pool, recorded).await;
         Ok(())
     }
 
     /// Update existing role with validation
     pub async fn update_role(
         pool: &SqlitePool,
-        id: i64,
-        _current_user_id: i64,
+        id: RoleId,
+        current_user_id: UserId,
         request: UpdateRolePayload,
     ) -> Result<(), ServiceError> {
         tracing::info!("Updating role: {}", id);
         Self::ensure_role_is_mutable(pool, id).await?;
         ensure_builtin_role_code_is_reserved(&request.code)?;
         Self::ensure_role_menus_are_assignable(pool, &request.menu_ids).await?;
-        RoleRepository::update(
-            pool,
+        let mut tx = tx::begin(pool).await?;
+        RoleRepository::update_in_tx(
+            &mut tx,
             id,
             &request.name,
             &request.code,
@@ -79,33 +117,56 @@ impl RoleService {
             &request.menu_ids,
         )
         .await?;
+        let recorded = events::record(
+            &mut tx,
+            &DomainEvent::RoleUpdated {
+                role_id: id.get(),
+                name: request.name,
+                code: request.code,
+                operator_id: current_user_id.get(),
+            },
+        )
+        .await?;
+        tx::commit(tx).await?;
+        events::deliver(pool, recorded).await;
         Ok(())
     }
 
     /// Delete role with user assignment validation
     pub async fn delete_role(
         pool: &SqlitePool,
-        id: i64,
-        _current_user_id: i64,
+        id: RoleId,
+        current_user_id: UserId,
     ) -> Result<(), ServiceError> {
         tracing::info!("Attempting to delete role: {}", id);
-        Self::ensure_role_is_mutable(pool, id).await?;
+        match RoleRepository::get_role_identity(pool, id).await? {
+            Some((code, is_system)) => ensure_role_identity_is_deletable(&code, is_system)?,
+            None => return Err(ServiceError::NotFound(format!("Role id: {}", id))),
+        }
 
-        // Check if role is still assigned to users
-        let user_count = RoleRepository::get_role_user_count(pool, id).await?;
+        // Count and delete in one transaction so an assignment cannot slip in between.
+        let mut tx = tx::begin(pool).await?;
+        let user_count = RoleRepository::get_role_user_count_in_tx(&mut tx, id).await?;
         if user_count > 0 {
             tracing::warn!("Cannot delete role {} - still assigned to {} users", id, user_count);
             return Err(ServiceError::InvalidOperation(format!(
-                "Cannot delete role '{}' - it is still assigned to {} user(s). Please remove all user assignments before deleting the role.",
+                "Cannot delete role '{}' - it is still assigned to {} user(s). Please transfer or remove all user assignments before deleting the role.",
                 id, user_count
             )));
         }
 
         // Perform the deletion
-        let success = RoleRepository::soft_delete(pool, id).await?;
+        let success = RoleRepository::soft_delete_in_tx(&mut tx, id).await?;
 
         if success {
+            let recorded = events::record(
+                &mut tx,
+                &DomainEvent::RoleDeleted { role_id: id.get(), operator_id: current_user_id.get() },
+            )
+            .await?;
+            tx::commit(tx).await?;
             tracing::info!("Successfully deleted role: {}", id);
+            events::deliver(pool, recorded).await;
             Ok(())
         } else {
             tracing::warn!("Role not found during deletion: {}", id);
@@ -113,7 +174,333 @@ impl RoleService {
         }
     }
 
-    async fn ensure_role_is_mutable(pool: &SqlitePool, id: i64) -> Result<(), ServiceError> {
+    /// List live users assigned to a role
+    pub async fn list_role_members(
+        pool: &SqlitePool,
+        id: RoleId,
+        query: RoleMemberQuery,
+    ) -> Result<(Vec<RoleMemberResp>, i64), ServiceError> {
+        tracing::info!("Listing members of role: {}", id);
+        Self::find_role_code(pool, id).await?;
+
+        let pagination = Pagination::from_query(PaginationQuery {
+            current: query.current,
+            page_size: query.page_size,
+        });
+        let (members, total) = RoleRepository::list_role_members(
+            pool,
+            id,
+            i64::from(pagination.offset),
+            i64::from(pagination.limit),
+        )
+        .await?;
+        Ok((members.into_iter().map(RoleMemberResp::from).collect(), total))
+    }
+
+    /// Add and remove role members in one transaction
+    pub async fn update_role_members(
+        pool: &SqlitePool,
+        id: RoleId,
+        current_user_id: UserId,
+        payload: UpdateRoleMembersPayload,
+    ) -> Result<RoleMembersChangeResp, ServiceError> {
+        tracing::info!("Updating members of role: {}", id);
+        ensure_role_membership_is_editable(&Self::find_role_code(pool, id).await?)?;
+
+        let UpdateRoleMembersPayload { mut add_user_ids, mut remove_user_ids } = payload;
+        add_user_ids.sort_unstable();
+        add_user_ids.dedup();
+        remove_user_ids.sort_unstable();
+        remove_user_ids.dedup();
+        if add_user_ids.iter().any(|user_id| remove_user_ids.binary_search(user_id).is_ok()) {
+            return Err(ServiceError::InvalidOperation(
+                "A user cannot be added to and removed from a role at once.".to_string(),
+            ));
+        }
+
+        let mut tx = tx::begin(pool).await?;
+        let added_users = RoleRepository::find_live_users_in_tx(&mut tx, &add_user_ids).await?;
+        if let Some(missing) =
+            add_user_ids.iter().find(|user_id| !added_users.iter().any(|(id, _)| id == *user_id))
+        {
+            return Err(ServiceError::NotFound(format!("User id: {}", missing)));
+        }
+        let removed_users =
+            RoleRepository::find_live_users_in_tx(&mut tx, &remove_user_ids).await?;
+        if added_users.iter().chain(&removed_users).any(|(_, is_system)| *is_system) {
+            return Err(ServiceError::SystemRecordProtected("user".to_string()));
+        }
+
+        let added = RoleRepository::add_members_in_tx(&mut tx, id, &add_user_ids).await?;
+        let removed = RoleRepository::remove_members_in_tx(&mut tx, id, &remove_user_ids).await?;
+        for (user_ids, action) in
+            [(&added, RoleHistoryAction::Assigned), (&removed, RoleHistoryAction::Removed)]
+        {
+            for user_id in user_ids {
+                UserRepository::record_role_history_in_tx(
+                    &mut tx,
+                    *user_id,
+                    &[id],
+                    action,
+                    Some(current_user_id),
+                )
+                .await?;
+            }
+        }
+        let mut recorded = None;
+        if !added.is_empty() {
+            let event = DomainEvent::RoleAssigned {
+                role_id: id.get(),
+                user_ids: added.iter().copied().map(UserId::get).collect(),
+                operator_id: current_user_id.get(),
+            };
+            recorded = Some(events::record(&mut tx, &event).await?);
+        }
+        tx::commit(tx).await?;
+
+        for user_id in added.iter().chain(&removed) {
+            PermissionService::clear_user_cache(user_id.get());
+        }
+        if let Some(recorded) = recorded {
+            events::deliver(pool, recorded).await;
+        }
+        Ok(RoleMembersChangeResp { added: added.len() as u64, removed: removed.len() as u64 })
+    }
+
+    /// Move every member of a role to another role, typically before deleting it
+    pub async fn transfer_role_members(
+        pool: &SqlitePool,
+        id: RoleId,
+        current_user_id: UserId,
+        payload: TransferRoleMembersPayload,
+    ) -> Result<RoleMembersChangeResp, ServiceError> {
+        let target_id = payload.target_role_id;
+        tracing::info!("Transferring members of role {} to role {}", id, target_id);
+        if target_id == id {
+            return Err(ServiceError::InvalidOperation(
+                "Target role must differ from the source role.".to_string(),
+            ));
+        }
+        ensure_role_membership_is_editable(&Self::find_role_code(pool, id).await?)?;
+        ensure_role_membership_is_editable(&Self::find_role_code(pool, target_id).await?)?;
+
+        let mut tx = tx::begin(pool).await?;
+        let members = RoleRepository::list_member_ids_in_tx(&mut tx, id).await?;
+        if members.iter().any(|(_, is_system)| *is_system) {
+            return Err(ServiceError::SystemRecordProtected("user".to_string()));
+        }
+        let (added, removed) =
+            RoleRepository::transfer_members_in_tx(&mut tx, id, target_id, current_user_id).await?;
+        let mut recorded = None;
+        if added > 0 {
+            let event = DomainEvent::RoleAssigned {
+                role_id: target_id.get(),
+                user_ids: members.iter().map(|(user_id, _)| user_id.get()).collect(),
+                operator_id: current_user_id.get(),
+            };
+            recorded = Some(events::record(&mut tx, &event).await?);
+        }
+        tx::commit(tx).await?;
+
+        for (user_id, _) in &members {
+            PermissionService::clear_user_cache(user_id.get());
+        }
+        if let Some(recorded) = recorded {
+            events::deliver(pool, recorded).await;
+        }
+        Ok(RoleMembersChangeResp { added, removed })
+    }
+
+    /// Network and time restrictions of a role; all empty when it has none
+    pub async fn get_role_access(
+        pool: &SqlitePool,
+        id: RoleId,
+    ) -> Result<RoleAccessResp, ServiceError> {
+        Self::find_role_code(pool, id).await?;
+        match RoleRepository::find_access(pool, id).await? {
+            Some(row) => RoleAccessResp::try_from(row),
+            None => Ok(RoleAccessResp::unrestricted(id)),
+        }
+    }
+
+    /// Replace a role's network and time restrictions; its members pick them up on their
+    /// next request
+    pub async fn update_role_access(
+        pool: &SqlitePool,
+        id: RoleId,
+        payload: UpdateRoleAccessPayload,
+    ) -> Result<RoleAccessResp, ServiceError> {
+        tracing::info!("Updating access restrictions of role: {}", id);
+        if Self::find_role_code(pool, id).await? == OWNER_ROLE_CODE {
+            return Err(ServiceError::InvalidOperation(
+                "Owner role cannot be limited to networks or hours.".to_string(),
+            ));
+        }
+        let payload = check_role_access(payload)?;
+        let hours = payload.start_time.as_deref().zip(payload.end_time.as_deref());
+        if payload.allowed_cidrs.is_empty() && payload.allowed_days.is_empty() && hours.is_none() {
+            RoleRepository::delete_access(pool, id).await?;
+        } else {
+            let cidrs = serde_json::to_string(&payload.allowed_cidrs)
+                .map_err(|e| ServiceError::InvalidOperation(e.to_string()))?;
+            let days = serde_json::to_string(&payload.allowed_days)
+                .map_err(|e| ServiceError::InvalidOperation(e.to_string()))?;
+            RoleRepository::upsert_access(pool, id, &cidrs, &days, hours).await?;
+        }
+
+        for user_id in RoleRepository::list_member_user_ids(pool, id).await? {
+            PermissionService::clear_user_cache(user_id.get());
+        }
+        Self::get_role_access(pool, id).await
+    }
+
+    /// Every custom role with its menus by code, for import into another environment
+    pub async fn export_roles(pool: &SqlitePool) -> Result<RoleExportDoc, ServiceError> {
+        tracing::info!("Exporting roles");
+        let mut tx = tx::begin(pool).await?;
+        let roles = RoleRepository::list_snapshots_in_tx(&mut tx)
+            .await?
+            .into_iter()
+            .filter(|role| !role.is_system && !BUILTIN_ROLE_CODES.contains(&role.code.as_str()))
+            .map(RoleExportItem::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(RoleExportDoc { version: ROLE_EXPORT_VERSION, exported_at: Some(Utc::now()), roles })
+    }
+
+    /// Create and update roles by code from an export in one transaction, or with
+    /// `dry_run` only report what would change. Roles missing from the file are left alone.
+    pub async fn import_roles(
+        config: &AppConfig,
+        pool: &SqlitePool,
+        current_user_id: UserId,
+        doc: RoleExportDoc,
+        dry_run: bool,
+    ) -> Result<RoleImportResp, ServiceError> {
+        tracing::info!("Importing {} roles (dry run: {})", doc.roles.len(), dry_run);
+        let roles = check_role_import(doc)?;
+
+        let mut tx = tx::begin(pool).await?;
+        let mut existing = HashMap::new();
+        for row in RoleRepository::list_snapshots_in_tx(&mut tx).await? {
+            let (id, is_system) = (row.id, row.is_system);
+            let role = RoleExportItem::try_from(row)?;
+            existing.insert(role.code.clone(), ExistingRole { id, is_system, role });
+        }
+        let mut menu_codes: Vec<String> =
+            roles.iter().flat_map(|role| role.menu_codes.iter().cloned()).collect();
+        menu_codes.sort_unstable();
+        menu_codes.dedup();
+        let menu_ids: HashMap<String, MenuId> =
+            RoleRepository::find_menu_ids_by_codes_in_tx(&mut tx, &menu_codes)
+                .await?
+                .into_iter()
+                .collect();
+
+        let items: Vec<RoleImportItem> =
+            roles.iter().map(|role| plan_role_import(role, &existing, &menu_ids)).collect();
+        let count = |action| items.iter().filter(|item| item.action == action).count() as u64;
+        let mut resp = RoleImportResp {
+            dry_run,
+            applied: false,
+            created: count(RoleImportAction::Create),
+            updated: count(RoleImportAction::Update),
+            unchanged: count(RoleImportAction::Unchanged),
+            roles: items,
+        };
+        let blocked = resp.roles.iter().filter(|item| !item.problems.is_empty()).count();
+        if dry_run {
+            return Ok(resp);
+        }
+        if blocked > 0 {
+            return Err(ServiceError::InvalidOperation(format!(
+                "{} role(s) cannot be imported. Run a dry run to see why.",
+                blocked
+            )));
+        }
+        let existing_count = existing.len() as i64;
+        QuotaService::ensure_slots(
+            config,
+            QuotaResource::Roles,
+            async { Ok(existing_count) },
+            resp.created,
+        )
+        .await?;
+
+        let mut recorded = Vec::new();
+        let mut updated_ids = Vec::new();
+        for (role, item) in roles.iter().zip(&resp.roles) {
+            let role_menu_ids: Vec<MenuId> =
+                role.menu_codes.iter().filter_map(|code| menu_ids.get(code).copied()).collect();
+            let event = match item.action {
+                RoleImportAction::Unchanged => continue,
+                RoleImportAction::Create => {
+                    let role_id = RoleRepository::create_in_tx(
+                        &mut tx,
+                        &role.name,
+                        &role.code,
+                        role.description.as_deref(),
+                        role.status,
+                        &role_menu_ids,
+                    )
+                    .await?;
+                    DomainEvent::RoleCreated {
+                        role_id: role_id.get(),
+                        name: role.name.clone(),
+                        code: role.code.clone(),
+                        operator_id: current_user_id.get(),
+                    }
+                }
+                RoleImportAction::Update => {
+                    let role_id = existing[&role.code].id;
+                    RoleRepository::update_in_tx(
+                        &mut tx,
+                        role_id,
+                        &role.name,
+                        &role.code,
+                        role.description.as_deref(),
+                        role.status,
+                        &role_menu_ids,
+                    )
+                    .await?;
+                    updated_ids.push(role_id);
+                    DomainEvent::RoleUpdated {
+                        role_id: role_id.get(),
+                        name: role.name.clone(),
+                        code: role.code.clone(),
+                        operator_id: current_user_id.get(),
+                    }
+                }
+            };
+            recorded.push(events::record(&mut tx, &event).await?);
+        }
+        tx::commit(tx).await?;
+        resp.applied = true;
+        tracing::info!(
+            created = resp.created,
+            updated = resp.updated,
+            unchanged = resp.unchanged,
+            "Imported roles"
+        );
+
+        for recorded in recorded {
+            events::deliver(pool, recorded).await;
+        }
+        for role_id in updated_ids {
+            for user_id in RoleRepository::list_member_user_ids(pool, role_id).await? {
+                PermissionService::clear_user_cache(user_id.get());
+            }
+        }
+        Ok(resp)
+    }
+
+    async fn find_role_code(pool: &SqlitePool, id: RoleId) -> Result<String, ServiceError> {
+        RoleRepository::get_role_identity(pool, id)
+            .await?
+            .map(|(code, _)| code)
+            .ok_or_else(|| ServiceError::NotFound(format!("Role id: {}", id)))
+    }
+
+    async fn ensure_role_is_mutable(pool: &SqlitePool, id: RoleId) -> Result<(), ServiceError> {
         match RoleRepository::get_role_identity(pool, id).await? {
             Some((code, is_system)) => ensure_role_identity_is_mutable(&code, is_system),
             None => Err(ServiceError::NotFound(format!("Role id: {}", id))),
@@ -122,7 +509,7 @@ impl RoleService {
 
     async fn ensure_role_menus_are_assignable(
         pool: &SqlitePool,
-        menu_ids: &[i64],
+        menu_ids: &[MenuId],
     ) -> Result<(), ServiceError> {
         let menu_codes = RoleRepository::list_menu_codes_by_ids(pool, menu_ids).await?;
         ensure_menu_codes_assignable(&menu_codes)
@@ -132,9 +519,9 @@ impl RoleService {
     pub async fn get_role_options(
         pool: &SqlitePool,
         query: OptionsQuery,
-    ) -> Result<Vec<OptionItem<i64>>, ServiceError> {
+    ) -> Result<Vec<OptionItem<RoleId>>, ServiceError> {
         tracing::info!("Retrieving role options: {:?}", query);
-        Ok(RoleRepository::list_role_options(pool, query.q.as_deref(), query.limit)
+        Ok(RoleRepository::list_role_options(pool, &OptionsFilter::from_query(query)?)
             .await?
             .into_iter()
             .map(|(id, name)| OptionItem { label: name, value: id })
@@ -142,6 +529,136 @@ impl RoleService {
     }
 }
 
+/// A live role as the import planner sees it.
+struct ExistingRole {
+    id: RoleId,
+    is_system: bool,
+    role: RoleExportItem,
+}
+
+/// Validates an import file and returns its roles with trimmed text and sorted menu codes.
+fn check_role_import(doc: RoleExportDoc) -> Result<Vec<RoleExportItem>, ServiceError> {
+    let mut errors = FieldErrors::new();
+    if doc.version != ROLE_EXPORT_VERSION {
+        errors.push("version", format!("must be {}", ROLE_EXPORT_VERSION));
+    }
+    let mut roles: Vec<RoleExportItem> = Vec::with_capacity(doc.roles.len());
+    for (index, role) in doc.roles.into_iter().enumerate() {
+        let field = |name: &str| format!("roles[{}].{}", index, name);
+        let code = role.code.trim().to_string();
+        let name = role.name.trim().to_string();
+        if code.is_empty() {
+            errors.push(&field("code"), "must not be empty");
+        } else if BUILTIN_ROLE_CODES.contains(&code.as_str()) {
+            errors.push(&field("code"), "is reserved for a built-in role");
+        } else if roles.iter().any(|other| other.code == code) {
+            errors.push(&field("code"), "is listed more than once");
+        }
+        if name.is_empty() {
+            errors.push(&field("name"), "must not be empty");
+        } else if roles.iter().any(|other| other.name == name) {
+            errors.push(&field("name"), "is listed more than once");
+        }
+        errors.check_one_of(&field("status"), role.status, &[1, 2]);
+
+        let mut menu_codes: Vec<String> =
+            role.menu_codes.iter().map(|code| code.trim().to_string()).collect();
+        menu_codes.sort_unstable();
+        menu_codes.dedup();
+        if let Some(code) = menu_codes.iter().find(|code| is_reserved_role_menu_code(code)) {
+            errors.push(
+                &field("menuCodes"),
+                format!("'{}' can only be assigned to the built-in owner role", code),
+            );
+        }
+        roles.push(RoleExportItem {
+            code,
+            name,
+            description: role.description.filter(|text| !text.trim().is_empty()),
+            status: role.status,
+            menu_codes,
+        });
+    }
+    errors.into_result()?;
+    Ok(roles)
+}
+
+/// Compares one imported role with the live role of the same code.
+fn plan_role_import(
+    role: &RoleExportItem,
+    existing: &HashMap<String, ExistingRole>,
+    menu_ids: &HashMap<String, MenuId>,
+) -> RoleImportItem {
+    let mut problems = Vec::new();
+    let unknown: Vec<&str> = role
+        .menu_codes
+        .iter()
+        .filter(|code| !menu_ids.contains_key(*code))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        problems.push(format!("Unknown menu codes: {}", unknown.join(", ")));
+    }
+    if let Some(other) =
+        existing.values().find(|other| other.role.code != role.code && other.role.name == role.name)
+    {
+        problems.push(format!("Name is taken by role '{}'", other.role.code));
+    }
+
+    let Some(current) = existing.get(&role.code) else {
+        return RoleImportItem {
+            code: role.code.clone(),
+            name: role.name.clone(),
+            action: RoleImportAction::Create,
+            changed_fields: Vec::new(),
+            added_menus: role.menu_codes.clone(),
+            removed_menus: Vec::new(),
+            problems,
+        };
+    };
+    if current.is_system {
+        problems.push("Built-in roles cannot be changed by import".to_string());
+    }
+    let mut changed_fields = Vec::new();
+    if current.role.name != role.name {
+        changed_fields.push("name");
+    }
+    if current.role.description != role.description {
+        changed_fields.push("description");
+    }
+    if current.role.status != role.status {
+        changed_fields.push("status");
+    }
+    let added_menus: Vec<String> = role
+        .menu_codes
+        .iter()
+        .filter(|code| !current.role.menu_codes.contains(code))
+        .cloned()
+        .collect();
+    let removed_menus: Vec<String> = current
+        .role
+        .menu_codes
+        .iter()
+        .filter(|code| !role.menu_codes.contains(code))
+        .cloned()
+        .collect();
+    let action = if changed_fields.is_empty() && added_menus.is_empty() && removed_menus.is_empty()
+    {
+        RoleImportAction::Unchanged
+    } else {
+        RoleImportAction::Update
+    };
+    RoleImportItem {
+        code: role.code.clone(),
+        name: role.name.clone(),
+        action,
+        changed_fields,
+        added_menus,
+        removed_menus,
+        problems,
+    }
+}
+
 fn ensure_menu_codes_assignable(menu_codes: &[String]) -> Result<(), ServiceError> {
     if let Some(code) = menu_codes.iter().find(|code| is_reserved_role_menu_code(code)) {
         return Err(ServiceError::InvalidOperation(format!(
@@ -153,6 +670,60 @@ fn ensure_menu_codes_assignable(menu_codes: &[String]) -> Result<(), ServiceErro
     Ok(())
 }
 
+/// Validates restrictions and rewrites them canonically: ranges in CIDR form, days
+/// sorted without repeats, times as `HH:MM`.
+fn check_role_access(
+    payload: UpdateRoleAccessPayload,
+) -> Result<UpdateRoleAccessPayload, ServiceError> {
+    let mut errors = FieldErrors::new();
+    let mut allowed_cidrs = Vec::with_capacity(payload.allowed_cidrs.len());
+    for (index, cidr) in payload.allowed_cidrs.iter().enumerate() {
+        match cidr.parse::<IpRange>() {
+            Ok(range) if !allowed_cidrs.contains(&range.to_string()) => {
+                allowed_cidrs.push(range.to_string())
+            }
+            Ok(_) => errors.push(&format!("allowedCidrs[{}]", index), "is listed more than once"),
+            Err(message) => errors.push(&format!("allowedCidrs[{}]", index), message),
+        }
+    }
+    let mut allowed_days = payload.allowed_days;
+    if allowed_days.iter().any(|day| !(1..=7).contains(day)) {
+        errors.push("allowedDays", "must be 1 (Monday) to 7 (Sunday)");
+    }
+    allowed_days.sort_unstable();
+    allowed_days.dedup();
+
+    let mut parse_time = |field: &str, value: Option<String>| {
+        let value = value.filter(|value| !value.trim().is_empty())?;
+        match NaiveTime::parse_from_str(value.trim(), ACCESS_TIME_FORMAT) {
+            Ok(time) => Some(time),
+            Err(_) => {
+                errors.push(field, "must be a time like 09:00");
+                None
+            }
+        }
+    };
+    let start_time = parse_time("startTime", payload.start_time);
+    let end_time = parse_time("endTime", payload.end_time);
+    match (start_time, end_time) {
+        (Some(start), Some(end)) if start == end => {
+            errors.push("endTime", "must differ from startTime")
+        }
+        (Some(_), None) => errors.push("endTime", "must be set together with startTime"),
+        (None, Some(_)) => errors.push("startTime", "must be set together with endTime"),
+        _ => {}
+    }
+    errors.into_result()?;
+
+    let format = |time: NaiveTime| time.format(ACCESS_TIME_FORMAT).to_string();
+    Ok(UpdateRoleAccessPayload {
+        allowed_cidrs,
+        allowed_days,
+        start_time: start_time.map(format),
+        end_time: end_time.map(format),
+    })
+}
+
 fn ensure_role_identity_is_mutable(role_code: &str, is_system: bool) -> Result<(), ServiceError> {
     if role_code == OWNER_ROLE_CODE {
         return Err(ServiceError::InvalidOperation(
@@ -167,6 +738,25 @@ fn ensure_role_identity_is_mutable(role_code: &str, is_system: bool) -> Result<(
     Ok(())
 }
 
+fn ensure_role_identity_is_deletable(role_code: &str, is_system: bool) -> Result<(), ServiceError> {
+    if is_system || role_code == OWNER_ROLE_CODE {
+        return Err(ServiceError::SystemRecordProtected("role".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Owner membership grants `*`, so it stays with the operator CLI.
+fn ensure_role_membership_is_editable(role_code: &str) -> Result<(), ServiceError> {
+    if role_code == OWNER_ROLE_CODE {
+        return Err(ServiceError::InvalidOperation(
+            "Owner role membership cannot be changed through role management.".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
 fn ensure_builtin_role_code_is_reserved(role_code: &str) -> Result<(), ServiceError> {
     if BUILTIN_ROLE_CODES.contains(&role_code) {
         return Err(ServiceError::InvalidOperation(format!(
@@ -232,5 +822,180 @@ mod tests {
             Err(ServiceError::RoleIsSystem)
         ));
         assert!(ensure_role_identity_is_mutable("ops_viewer", false).is_ok());
+
+        assert!(matches!(
+            ensure_role_identity_is_deletable("admin", true),
+            Err(ServiceError::SystemRecordProtected(_))
+        ));
+        assert!(matches!(
+            ensure_role_identity_is_deletable(OWNER_ROLE_CODE, false),
+            Err(ServiceError::SystemRecordProtected(_))
+        ));
+        assert!(ensure_role_identity_is_deletable("ops_viewer", false).is_ok());
+    }
+
+    #[test]
+    fn role_access_is_validated_and_normalized() {
+        let payload = |cidrs: &[&str], days: Vec<u8>, start: Option<&str>, end: Option<&str>| {
+            UpdateRoleAccessPayload {
+                allowed_cidrs: cidrs.iter().map(|cidr| cidr.to_string()).collect(),
+                allowed_days: days,
+                start_time: start.map(str::to_string),
+                end_time: end.map(str::to_string),
+            }
+        };
+        let access = check_role_access(payload(
+            &["10.0.0.0/8", "10.1.2.3"],
+            vec![5, 1, 5],
+            Some("9:00"),
+            Some("18:30"),
+        ))
+        .expect("valid access");
+        assert_eq!(access.allowed_cidrs, ["10.0.0.0/8", "10.1.2.3/32"]);
+        assert_eq!(access.allowed_days, [1, 5]);
+        assert_eq!(access.start_time.as_deref(), Some("09:00"));
+
+        for invalid in [
+            payload(&["10.0.0.0/40"], Vec::new(), None, None),
+            payload(&["10.0.0.1", "10.0.0.1/32"], Vec::new(), None, None),
+            payload(&[], vec![0], None, None),
+            payload(&[], Vec::new(), Some("09:00"), None),
+            payload(&[], Vec::new(), Some("09:00"), Some("09:00")),
+            payload(&[], Vec::new(), Some("9am"), Some("17:00")),
+        ] {
+            assert!(matches!(check_role_access(invalid), Err(ServiceError::InvalidFields(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn role_members_can_be_edited_and_transferred() {
+        use crate::features::system::user::{repo::UserRepository, types::CreateUserCommand};
+
+        let pool = crate::infra::db::test_pool().await;
+
+        let source = RoleRepository::create(&pool, "Ops", "ops", None, 1, &[]).await.unwrap();
+        let target =
+            RoleRepository::create(&pool, "Support", "support", None, 1, &[]).await.unwrap();
+        let mut user_ids = Vec::new();
+        for name in ["alice", "bob"] {
+            let command = CreateUserCommand {
+                username: name.to_string(),
+                email: format!("{}@example.com", name),
+                password_hash: "hash".to_string(),
+                real_name: None,
+                status: None,
+                role_ids: Vec::new(),
+                profile: None,
+                operator_id: None,
+            };
+            user_ids.push(UserRepository::create_user(&pool, &command).await.unwrap());
+        }
+
+        let change = RoleService::update_role_members(
+            &pool,
+            source,
+            user_ids[0],
+            UpdateRoleMembersPayload {
+                add_user_ids: user_ids.clone(),
+                remove_user_ids: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!((change.added, change.removed), (2, 0));
+
+        let err = RoleService::update_role_members(
+            &pool,
+            source,
+            user_ids[0],
+            UpdateRoleMembersPayload {
+                add_user_ids: vec![UserId(9999)],
+                remove_user_ids: Vec::new(),
+            },
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, ServiceError::NotFound(_)));
+
+        let page = RoleMemberQuery { current: None, page_size: None };
+        let (_, total) = RoleService::list_role_members(&pool, source, page.clone()).await.unwrap();
+        assert_eq!(total, 2);
+
+        let change = RoleService::transfer_role_members(
+            &pool,
+            source,
+            user_ids[0],
+            TransferRoleMembersPayload { target_role_id: target },
+        )
+        .await
+        .unwrap();
+        assert_eq!((change.added, change.removed), (2, 2));
+        let (_, total) = RoleService::list_role_members(&pool, source, page.clone()).await.unwrap();
+        assert_eq!(total, 0);
+        let (members, _) = RoleService::list_role_members(&pool, target, page).await.unwrap();
+        assert_eq!(members.len(), 2);
+
+        RoleService::delete_role(&pool, source, UserId(0)).await.unwrap();
+    }
+
+    #[test]
+    fn role_imports_are_validated_and_compared_by_code() {
+        let role = |code: &str, name: &str, menu_codes: &[&str]| RoleExportItem {
+            code: code.to_string(),
+            name: name.to_string(),
+            description: None,
+            status: 1,
+            menu_codes: menu_codes.iter().map(|code| code.to_string()).collect(),
+        };
+        let doc = |roles| RoleExportDoc { version: ROLE_EXPORT_VERSION, exported_at: None, roles };
+        for invalid in [
+            doc(vec![role("admin", "Admin", &[])]),
+            doc(vec![role("ops", "Ops", &[]), role("ops", "Ops 2", &[])]),
+            doc(vec![role("ops", " ", &[])]),
+            doc(vec![role("ops", "Ops", &["manage:deploy:list"])]),
+            RoleExportDoc { version: 2, exported_at: None, roles: Vec::new() },
+        ] {
+            assert!(matches!(check_role_import(invalid), Err(ServiceError::InvalidFields(_))));
+        }
+        let roles = check_role_import(doc(vec![role(" ops ", "Ops", &["b", "a", "b"])])).unwrap();
+        assert_eq!(
+            (roles[0].code.as_str(), roles[0].menu_codes.clone()),
+            ("ops", vec!["a".into(), "b".into()])
+        );
+
+        let existing = HashMap::from([
+            (
+                "ops".to_string(),
+                ExistingRole {
+                    id: RoleId(1),
+                    is_system: false,
+                    role: role("ops", "Ops", &["a", "c"]),
+                },
+            ),
+            (
+                "support".to_string(),
+                ExistingRole {
+                    id: RoleId(2),
+                    is_system: false,
+                    role: role("support", "Support", &[]),
+                },
+            ),
+        ]);
+        let menu_ids = HashMap::from([("a".to_string(), MenuId(1)), ("b".to_string(), MenuId(2))]);
+
+        let item = plan_role_import(&role("ops", "Ops", &["a", "b"]), &existing, &menu_ids);
+        assert_eq!(item.action, RoleImportAction::Update);
+        assert_eq!(
+            (item.added_menus, item.removed_menus),
+            (vec!["b".to_string()], vec!["c".to_string()])
+        );
+        assert!(item.problems.is_empty());
+
+        let item = plan_role_import(&role("ops", "Ops", &["a", "c"]), &existing, &menu_ids);
+        assert_eq!(item.action, RoleImportAction::Unchanged);
+
+        let item = plan_role_import(&role("audit", "Support", &["x"]), &existing, &menu_ids);
+        assert_eq!(item.action, RoleImportAction::Create);
+        assert_eq!(item.problems.len(), 2, "{:?}", item.problems);
     }
 }
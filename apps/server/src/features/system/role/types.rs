@@ -1,19 +1,27 @@
-use chrono::NaiveDateTime;
+use chrono::{DateTime, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::common::api::OptionItem;
-use crate::common::error::ServiceError;
+use crate::common::{
+    error::ServiceError,
+    ids::{MenuId, RoleId, UserId},
+    pagination::Sort,
+};
+use crate::infra::access_window::{AccessWindow, IpRange};
+
+/// Format of the start and end times of a role's allowed hours.
+pub const ACCESS_TIME_FORMAT: &str = "%H:%M";
 
 /// Role with menus row from the database view.
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct RoleWithMenusRow {
-    pub id: i64,
+    pub id: RoleId,
     pub name: String,
     pub code: String,
     pub description: Option<String>,
     pub status: i16,
-    pub created_at: NaiveDateTime,
-    pub updated_at: NaiveDateTime,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
     pub is_system: Option<bool>,
     pub menus: serde_json::Value,
 }
@@ -25,7 +33,7 @@ pub struct CreateRoleRequest {
     pub name: String,
     pub code: String,
     pub status: i16,
-    pub menu_ids: Vec<i64>,
+    pub menu_ids: Vec<MenuId>,
     pub description: Option<String>,
 }
 
@@ -36,7 +44,7 @@ pub struct UpdateRolePayload {
     pub name: String,
     pub code: String,
     pub status: i16,
-    pub menu_ids: Vec<i64>,
+    pub menu_ids: Vec<MenuId>,
     pub description: Option<String>,
 }
 
@@ -44,14 +52,14 @@ pub struct UpdateRolePayload {
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RoleItemResp {
-    pub id: i64,
+    pub id: RoleId,
     pub name: String,
     pub code: String,
     pub description: Option<String>,
     pub status: i16,
-    pub created_at: NaiveDateTime,
-    pub updated_at: NaiveDateTime,
-    pub menus: Vec<OptionItem<i64>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub menus: Vec<OptionItem<MenuId>>,
 }
 
 /// Role list query parameters
@@ -68,6 +76,10 @@ pub struct RoleQuery {
     pub role_code: Option<String>,
     /// Filter by role status.
     pub status: Option<String>,
+    /// Sort field (camelCase). Defaults to the list's natural order.
+    pub sort_by: Option<String>,
+    /// Sort direction: "asc" or "desc". Defaults to "desc".
+    pub sort_order: Option<String>,
 }
 
 /// Role repository list query.
@@ -76,13 +88,252 @@ pub struct RoleListQuery {
     pub role_name: Option<String>,
     pub role_code: Option<String>,
     pub status: Option<i16>,
+    pub sort: Option<Sort>,
+}
+
+/// Role member row joined from `user_roles` and `users`.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct RoleMemberRow {
+    pub id: UserId,
+    pub username: String,
+    pub real_name: Option<String>,
+    pub status: i16,
+    pub assigned_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Role member item for list display
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleMemberResp {
+    pub id: UserId,
+    pub username: String,
+    pub real_name: Option<String>,
+    pub status: i16,
+    pub assigned_at: DateTime<Utc>,
+    /// When a temporary assignment stops granting the role; past times are awaiting cleanup.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl From<RoleMemberRow> for RoleMemberResp {
+    fn from(row: RoleMemberRow) -> Self {
+        Self {
+            id: row.id,
+            username: row.username,
+            real_name: row.real_name,
+            status: row.status,
+            assigned_at: row.assigned_at,
+            expires_at: row.expires_at,
+        }
+    }
+}
+
+/// Role member list query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleMemberQuery {
+    /// The page number to retrieve. Defaults to 1.
+    pub current: Option<i64>,
+    /// The number of items per page. Defaults to 10.
+    pub page_size: Option<i64>,
+}
+
+/// Add or remove role members
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRoleMembersPayload {
+    #[serde(default)]
+    pub add_user_ids: Vec<UserId>,
+    #[serde(default)]
+    pub remove_user_ids: Vec<UserId>,
+}
+
+/// Move every member of a role to another role
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferRoleMembersPayload {
+    pub target_role_id: RoleId,
+}
+
+/// Membership change counts
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleMembersChangeResp {
+    pub added: u64,
+    pub removed: u64,
+}
+
+/// Version written into role exports; imports of any other version are refused.
+pub const ROLE_EXPORT_VERSION: u32 = 1;
+
+/// A live role with its menu codes as a JSON array, for export and import planning.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct RoleSnapshotRow {
+    pub id: RoleId,
+    pub name: String,
+    pub code: String,
+    pub description: Option<String>,
+    pub status: i16,
+    pub is_system: bool,
+    pub menu_codes: String,
+}
+
+/// Roles of one environment, moved to another by code so ids never have to match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleExportDoc {
+    pub version: u32,
+    #[serde(default)]
+    pub exported_at: Option<DateTime<Utc>>,
+    pub roles: Vec<RoleExportItem>,
+}
+
+/// One exported role; `menu_codes` are the codes of its menus and permissions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleExportItem {
+    pub code: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub status: i16,
+    #[serde(default)]
+    pub menu_codes: Vec<String>,
+}
+
+impl TryFrom<RoleSnapshotRow> for RoleExportItem {
+    type Error = ServiceError;
+
+    fn try_from(row: RoleSnapshotRow) -> Result<Self, Self::Error> {
+        let menu_codes = serde_json::from_str(&row.menu_codes).map_err(|e| {
+            ServiceError::InvalidOperation(format!("Invalid role menu data: {}", e))
+        })?;
+        Ok(Self {
+            code: row.code,
+            name: row.name,
+            description: row.description.filter(|text| !text.trim().is_empty()),
+            status: row.status,
+            menu_codes,
+        })
+    }
+}
+
+/// Role import query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleImportQuery {
+    /// Only report what the import would change. Defaults to false.
+    pub dry_run: Option<bool>,
+}
+
+/// What an import does with one role of the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoleImportAction {
+    Create,
+    Update,
+    Unchanged,
+}
+
+/// Difference between one imported role and the role with its code here.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleImportItem {
+    pub code: String,
+    pub name: String,
+    pub action: RoleImportAction,
+    /// `name`, `description` or `status` when they differ.
+    pub changed_fields: Vec<&'static str>,
+    pub added_menus: Vec<String>,
+    pub removed_menus: Vec<String>,
+    /// Why the role cannot be imported, e.g. menu codes unknown here.
+    pub problems: Vec<String>,
+}
+
+/// Outcome of a role import or its dry run.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleImportResp {
+    pub dry_run: bool,
+    pub applied: bool,
+    pub created: u64,
+    pub updated: u64,
+    pub unchanged: u64,
+    pub roles: Vec<RoleImportItem>,
+}
+
+/// Network and time restrictions row of a role.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct RoleAccessRow {
+    pub role_id: RoleId,
+    /// JSON array of CIDR ranges
+    pub allowed_cidrs: String,
+    /// JSON array of ISO weekdays
+    pub allowed_days: String,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RoleAccessRow {
+    /// The stored restrictions as a window requests are checked against.
+    pub fn window(&self) -> Result<AccessWindow, ServiceError> {
+        let invalid = |e: String| {
+            ServiceError::InvalidOperation(format!(
+                "Invalid access restrictions on role {}: {}",
+                self.role_id, e
+            ))
+        };
+        let cidrs = serde_json::from_str::<Vec<String>>(&self.allowed_cidrs)
+            .map_err(|e| invalid(e.to_string()))?
+            .iter()
+            .map(|cidr| cidr.parse::<IpRange>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(invalid)?;
+        let days = serde_json::from_str::<Vec<u8>>(&self.allowed_days)
+            .map_err(|e| invalid(e.to_string()))?;
+        let parse_time = |time: &str| {
+            NaiveTime::parse_from_str(time, ACCESS_TIME_FORMAT).map_err(|e| invalid(e.to_string()))
+        };
+        let hours = match (&self.start_time, &self.end_time) {
+            (Some(start), Some(end)) => Some((parse_time(start)?, parse_time(end)?)),
+            _ => None,
+        };
+        Ok(AccessWindow { cidrs, days, hours })
+    }
+}
+
+/// Where and when a role's capabilities apply; empty lists and no hours leave it open.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleAccessResp {
+    pub role_id: RoleId,
+    pub allowed_cidrs: Vec<String>,
+    /// ISO weekdays, 1 for Monday to 7 for Sunday
+    pub allowed_days: Vec<u8>,
+    /// `HH:MM` in `RUSTZEN_TIMEZONE`
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Replace a role's network and time restrictions; leaving everything empty lifts them.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRoleAccessPayload {
+    #[serde(default)]
+    pub allowed_cidrs: Vec<String>,
+    #[serde(default)]
+    pub allowed_days: Vec<u8>,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
 }
 
 impl TryFrom<RoleWithMenusRow> for RoleItemResp {
     type Error = ServiceError;
 
     fn try_from(role: RoleWithMenusRow) -> Result<Self, Self::Error> {
-        let menus = serde_json::from_value::<Vec<OptionItem<i64>>>(role.menus).map_err(|e| {
+        let menus = serde_json::from_value::<Vec<OptionItem<MenuId>>>(role.menus).map_err(|e| {
             ServiceError::InvalidOperation(format!("Invalid role menu data: {}", e))
         })?;
 
@@ -98,3 +349,35 @@ impl TryFrom<RoleWithMenusRow> for RoleItemResp {
         })
     }
 }
+
+impl RoleAccessResp {
+    /// A role without restrictions.
+    pub fn unrestricted(role_id: RoleId) -> Self {
+        Self {
+            role_id,
+            allowed_cidrs: Vec::new(),
+            allowed_days: Vec::new(),
+            start_time: None,
+            end_time: None,
+            updated_at: None,
+        }
+    }
+}
+
+impl TryFrom<RoleAccessRow> for RoleAccessResp {
+    type Error = ServiceError;
+
+    fn try_from(row: RoleAccessRow) -> Result<Self, Self::Error> {
+        let invalid = |e: serde_json::Error| {
+            ServiceError::InvalidOperation(format!("Invalid role access data: {}", e))
+        };
+        Ok(Self {
+            role_id: row.role_id,
+            allowed_cidrs: serde_json::from_str(&row.allowed_cidrs).map_err(invalid)?,
+            allowed_days: serde_json::from_str(&row.allowed_days).map_err(invalid)?,
+            start_time: row.start_time,
+            end_time: row.end_time,
+            updated_at: Some(row.updated_at),
+        })
+    }
+}
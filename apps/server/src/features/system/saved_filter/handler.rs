@@ -0,0 +1,61 @@
+use super::{
+    service::SavedFilterService,
+    types::{ApplyFilterQuery, FilteredItem, SaveFilterRequest, SavedFilterQuery, SavedFilterResp},
+};
+use crate::{
+    common::{
+        api::{ApiResponse, AppResult, PageMeta},
+        pagination::{Pagination, PaginationQuery},
+    },
+    infra::db::DbExecutor,
+};
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use rustzen_core::auth::CurrentUser;
+use sqlx::SqlitePool;
+
+/// Get the caller's saved filters
+pub async fn list_filters(
+    current_user: CurrentUser,
+    State(db): State<DbExecutor>,
+    Query(query): Query<SavedFilterQuery>,
+) -> AppResult<Vec<SavedFilterResp>> {
+    Ok(ApiResponse::success(SavedFilterService::list(db.read(), &current_user, query).await?))
+}
+
+/// Save a named filter for a list page
+pub async fn save_filter(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Json(request): Json<SaveFilterRequest>,
+) -> AppResult<SavedFilterResp> {
+    Ok(ApiResponse::success(SavedFilterService::save(&pool, &current_user, request).await?))
+}
+
+/// Get one page of the list a saved filter selects
+pub async fn apply_filter(
+    current_user: CurrentUser,
+    State(db): State<DbExecutor>,
+    Path(id): Path<i64>,
+    Query(query): Query<ApplyFilterQuery>,
+) -> AppResult<Vec<FilteredItem>> {
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (items, total) = SavedFilterService::apply(db.read(), &current_user, id, query).await?;
+    Ok(ApiResponse::page(items, total, PageMeta::new(pagination, total)))
+}
+
+/// Delete one of the caller's saved filters
+pub async fn delete_filter(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+) -> AppResult<()> {
+    SavedFilterService::delete(&pool, &current_user, id).await?;
+    Ok(ApiResponse::success(()))
+}
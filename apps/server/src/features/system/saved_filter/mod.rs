@@ -0,0 +1,28 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{
+    Router,
+    routing::{delete, get, post},
+};
+use handler::{apply_filter, delete_filter, list_filters, save_filter};
+use rustzen_core::{
+    capability::{manage_log, system_user},
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+/// Any filterable list opens the routes; saving and applying check the one for the resource.
+fn filter_user() -> PermissionsCheck {
+    PermissionsCheck::Any(vec![system_user::LIST, manage_log::LIST])
+}
+
+pub fn saved_filter_routes() -> Router<SqlitePool> {
+    Router::new()
+        .route_with_permission("/", get(list_filters), filter_user())
+        .route_with_permission("/", post(save_filter), filter_user())
+        .route_with_permission("/{id}/apply", get(apply_filter), filter_user())
+        .route_with_permission("/{id}", delete(delete_filter), filter_user())
+}
@@ -0,0 +1,104 @@
+use super::types::SavedFilterRow;
+use crate::common::error::ServiceError;
+
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+pub struct SavedFilterRepository;
+
+fn db_error(context: &str, e: sqlx::Error) -> ServiceError {
+    tracing::error!("Database error {}: {:?}", context, e);
+    ServiceError::DatabaseQueryFailed
+}
+
+impl SavedFilterRepository {
+    /// Filters of one user, by resource and then name.
+    pub async fn list_for_user(
+        pool: &SqlitePool,
+        user_id: i64,
+        resource: Option<&str>,
+    ) -> Result<Vec<SavedFilterRow>, ServiceError> {
+        sqlx::query_as::<_, SavedFilterRow>(
+            "SELECT id, user_id, resource, name, query, created_at, updated_at
+             FROM saved_filters
+             WHERE user_id = ? AND (? IS NULL OR resource = ?)
+             ORDER BY resource, name",
+        )
+        .bind(user_id)
+        .bind(resource)
+        .bind(resource)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("listing saved filters", e))
+    }
+
+    pub async fn find_by_id(
+        pool: &SqlitePool,
+        id: i64,
+    ) -> Result<Option<SavedFilterRow>, ServiceError> {
+        sqlx::query_as::<_, SavedFilterRow>(
+            "SELECT id, user_id, resource, name, query, created_at, updated_at
+             FROM saved_filters WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_error("finding saved filter", e))
+    }
+
+    /// How many filters a user keeps for `resource`, not counting one named `except_name`.
+    pub async fn count_for(
+        pool: &SqlitePool,
+        user_id: i64,
+        resource: &str,
+        except_name: &str,
+    ) -> Result<i64, ServiceError> {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM saved_filters WHERE user_id = ? AND resource = ? AND name != ?",
+        )
+        .bind(user_id)
+        .bind(resource)
+        .bind(except_name)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_error("counting saved filters", e))
+    }
+
+    /// Inserts a filter, or replaces the query of the user's filter with the same name.
+    pub async fn upsert(
+        pool: &SqlitePool,
+        user_id: i64,
+        resource: &str,
+        name: &str,
+        query: &str,
+    ) -> Result<i64, ServiceError> {
+        let now = Utc::now().naive_utc();
+        sqlx::query_scalar::<_, i64>(
+            "INSERT INTO saved_filters (user_id, resource, name, query, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT (user_id, resource, name)
+             DO UPDATE SET query = excluded.query, updated_at = excluded.updated_at
+             RETURNING id",
+        )
+        .bind(user_id)
+        .bind(resource)
+        .bind(name)
+        .bind(query)
+        .bind(now)
+        .bind(now)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_error("saving filter", e))
+    }
+
+    /// Deletes one of the user's filters; `false` when the user has no such filter.
+    pub async fn delete(pool: &SqlitePool, user_id: i64, id: i64) -> Result<bool, ServiceError> {
+        let result = sqlx::query("DELETE FROM saved_filters WHERE id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .execute(pool)
+            .await
+            .map_err(|e| db_error("deleting saved filter", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,208 @@
+use super::{
+    repo::SavedFilterRepository,
+    types::{
+        ApplyFilterQuery, FilterQuery, FilterResource, FilteredItem, SaveFilterRequest,
+        SavedFilterQuery, SavedFilterResp,
+    },
+};
+use crate::{
+    common::{
+        error::ServiceError,
+        mask::FieldMask,
+        pagination::Sort,
+        validation::{FieldError, FieldErrors},
+    },
+    features::{
+        manage::log::{repo::LogRepository, service::LogService, types::LogQuery},
+        system::user::{service::UserService, types::UserQuery},
+    },
+};
+
+use rustzen_core::auth::CurrentUser;
+use serde_json::{Map, Value};
+use sqlx::SqlitePool;
+
+const NAME_MAX_LEN: usize = 64;
+/// Filters one user may keep per list page.
+const MAX_FILTERS_PER_RESOURCE: i64 = 50;
+/// Paging parameters of the list endpoints; a filter is applied to whichever page is asked.
+const PAGING_KEYS: [&str; 3] = ["current", "pageSize", "after"];
+
+pub struct SavedFilterService;
+
+impl SavedFilterService {
+    /// The caller's filters, optionally for one list page.
+    pub async fn list(
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        query: SavedFilterQuery,
+    ) -> Result<Vec<SavedFilterResp>, ServiceError> {
+        let resource = query
+            .resource
+            .as_deref()
+            .map(|resource| parse_resource(resource.trim()))
+            .transpose()?;
+        let rows = SavedFilterRepository::list_for_user(
+            pool,
+            current_user.user_id,
+            resource.map(FilterResource::as_str),
+        )
+        .await?;
+        Ok(rows.into_iter().map(SavedFilterResp::from).collect())
+    }
+
+    /// Saves the caller's filter, replacing the query of an existing one with the same name.
+    pub async fn save(
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        request: SaveFilterRequest,
+    ) -> Result<SavedFilterResp, ServiceError> {
+        let SaveFilterRequest { resource, name, mut query } = request;
+        let resource = parse_resource(resource.trim())?;
+        ensure_can_list(current_user, resource)?;
+        let name = name.trim().to_string();
+        let mut errors = FieldErrors::new();
+        if name.is_empty() || name.chars().count() > NAME_MAX_LEN {
+            errors.push("name", format!("must be 1-{} characters", NAME_MAX_LEN));
+        }
+        errors.into_result()?;
+        for key in PAGING_KEYS {
+            query.remove(key);
+        }
+        parse_query(resource, &query)?;
+
+        let saved =
+            SavedFilterRepository::count_for(pool, current_user.user_id, resource.as_str(), &name)
+                .await?;
+        if saved >= MAX_FILTERS_PER_RESOURCE {
+            return Err(ServiceError::InvalidOperation(format!(
+                "At most {} filters can be saved for {}",
+                MAX_FILTERS_PER_RESOURCE,
+                resource.as_str()
+            )));
+        }
+        let id = SavedFilterRepository::upsert(
+            pool,
+            current_user.user_id,
+            resource.as_str(),
+            &name,
+            &Value::Object(query).to_string(),
+        )
+        .await?;
+        tracing::info!(
+            id,
+            resource = resource.as_str(),
+            user_id = current_user.user_id,
+            "Filter saved"
+        );
+        Ok(Self::find_own(pool, current_user, id).await?.1)
+    }
+
+    /// Runs the caller's filter against its list and returns the requested page, shaped and
+    /// masked like the list endpoint.
+    pub async fn apply(
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        id: i64,
+        page: ApplyFilterQuery,
+    ) -> Result<(Vec<FilteredItem>, i64), ServiceError> {
+        let (resource, filter) = Self::find_own(pool, current_user, id).await?;
+        ensure_can_list(current_user, resource)?;
+        let Value::Object(query) = filter.query else {
+            return Err(ServiceError::InvalidOperation(format!("Filter {} has no query", id)));
+        };
+        let ApplyFilterQuery { current, page_size } = page;
+        let mask = FieldMask::for_user(current_user);
+        match parse_query(resource, &query)? {
+            FilterQuery::Users(query) => {
+                let query = UserQuery { current, page_size, ..query };
+                let (users, total) = UserService::list_users(pool, query, mask).await?;
+                Ok((users.into_iter().map(FilteredItem::User).collect(), total))
+            }
+            FilterQuery::Logs(query) => {
+                let query = LogQuery { current, page_size, after: None, ..query };
+                let (logs, total) = LogService::list_logs(pool, query, mask).await?;
+                Ok((logs.into_iter().map(FilteredItem::Log).collect(), total))
+            }
+        }
+    }
+
+    pub async fn delete(
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        id: i64,
+    ) -> Result<(), ServiceError> {
+        if !SavedFilterRepository::delete(pool, current_user.user_id, id).await? {
+            return Err(ServiceError::NotFound(format!("Saved filter {id}")));
+        }
+        Ok(())
+    }
+
+    async fn find_own(
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        id: i64,
+    ) -> Result<(FilterResource, SavedFilterResp), ServiceError> {
+        let row = SavedFilterRepository::find_by_id(pool, id)
+            .await?
+            .filter(|row| row.user_id == current_user.user_id)
+            .ok_or_else(|| ServiceError::NotFound(format!("Saved filter {id}")))?;
+        let resource = FilterResource::parse(&row.resource).ok_or_else(|| {
+            ServiceError::InvalidOperation(format!("Unknown filter resource {}", row.resource))
+        })?;
+        Ok((resource, row.into()))
+    }
+}
+
+fn parse_resource(value: &str) -> Result<FilterResource, ServiceError> {
+    FilterResource::parse(value).ok_or_else(|| field_error("resource", "must be users or logs"))
+}
+
+/// Filters follow the list's permission, so a saved filter cannot outlive the access it
+/// was saved with.
+fn ensure_can_list(
+    current_user: &CurrentUser,
+    resource: FilterResource,
+) -> Result<(), ServiceError> {
+    if current_user.has_capability(resource.capability()) {
+        return Ok(());
+    }
+    Err(ServiceError::InvalidOperation(format!(
+        "Filtering {} requires the {} permission",
+        resource.as_str(),
+        resource.capability()
+    )))
+}
+
+/// Reads `query` as the list query of `resource`, rejecting values the list would reject.
+fn parse_query(
+    resource: FilterResource,
+    query: &Map<String, Value>,
+) -> Result<FilterQuery, ServiceError> {
+    let value = Value::Object(query.clone());
+    let parsed = match resource {
+        FilterResource::Users => serde_json::from_value(value).map(FilterQuery::Users),
+        FilterResource::Logs => serde_json::from_value(value).map(FilterQuery::Logs),
+    }
+    .map_err(|err| field_error("query", &err.to_string()))?;
+    match &parsed {
+        FilterQuery::Users(query) => {
+            UserService::user_list_query(query.clone())?;
+        }
+        FilterQuery::Logs(query) => {
+            Sort::resolve(
+                query.sort_by.as_deref(),
+                query.sort_order.as_deref(),
+                LogRepository::SORT_COLUMNS,
+            )?;
+        }
+    }
+    Ok(parsed)
+}
+
+fn field_error(field: &str, message: &str) -> ServiceError {
+    ServiceError::InvalidFields(vec![FieldError {
+        field: field.to_string(),
+        message: message.to_string(),
+    }])
+}
@@ -0,0 +1,123 @@
+use crate::features::{
+    manage::log::types::{LogItemResp, LogQuery},
+    system::user::types::{UserItemResp, UserQuery},
+};
+
+use chrono::{DateTime, Utc};
+use rustzen_core::capability::{manage_log, system_user};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// List page a filter belongs to, stored in `saved_filters.resource`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterResource {
+    Users,
+    Logs,
+}
+
+impl FilterResource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FilterResource::Users => "users",
+            FilterResource::Logs => "logs",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "users" => Some(FilterResource::Users),
+            "logs" => Some(FilterResource::Logs),
+            _ => None,
+        }
+    }
+
+    /// Capability of the list the filter runs against.
+    pub fn capability(self) -> &'static str {
+        match self {
+            FilterResource::Users => system_user::LIST,
+            FilterResource::Logs => manage_log::LIST,
+        }
+    }
+}
+
+/// Stored query of a filter, read as the list endpoint's own parameters.
+#[derive(Debug, Clone)]
+pub enum FilterQuery {
+    Users(UserQuery),
+    Logs(LogQuery),
+}
+
+/// Saved filter row as read from the database.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SavedFilterRow {
+    pub id: i64,
+    pub user_id: i64,
+    pub resource: String,
+    pub name: String,
+    pub query: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Saved filter
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedFilterResp {
+    pub id: i64,
+    /// `users` or `logs`.
+    pub resource: String,
+    pub name: String,
+    /// Filters and sort in the list endpoint's query parameters, e.g. `{ "status": "1" }`.
+    pub query: Value,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<SavedFilterRow> for SavedFilterResp {
+    fn from(row: SavedFilterRow) -> Self {
+        Self {
+            query: serde_json::from_str(&row.query).unwrap_or_else(|_| Value::Object(Map::new())),
+            id: row.id,
+            resource: row.resource,
+            name: row.name,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+/// Save filter request; saving under an existing name replaces that filter's query.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveFilterRequest {
+    /// `users` or `logs`.
+    pub resource: String,
+    pub name: String,
+    /// Filters and sort of the resource's list endpoint; paging fields are dropped.
+    #[serde(default)]
+    pub query: Map<String, Value>,
+}
+
+/// Saved filter list query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedFilterQuery {
+    /// Only the filters of this list page.
+    pub resource: Option<String>,
+}
+
+/// Page to return when applying a filter.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyFilterQuery {
+    pub current: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+/// One row of an applied filter's list, shaped like the resource's list endpoint.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum FilteredItem {
+    User(UserItemResp),
+    Log(LogItemResp),
+}
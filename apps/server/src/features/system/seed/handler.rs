@@ -0,0 +1,18 @@
+use super::{service::SeedService, types::SeedReport};
+use crate::{
+    common::api::{ApiResponse, AppResult},
+    infra::config::AppConfig,
+};
+
+use axum::{Extension, extract::State};
+use sqlx::SqlitePool;
+
+/// Seed the demo dataset (development only).
+#[tracing::instrument(name = "seed_demo_data", skip(config, pool))]
+pub async fn seed_demo_data(
+    Extension(config): Extension<&'static AppConfig>,
+    State(pool): State<SqlitePool>,
+) -> AppResult<SeedReport> {
+    SeedService::ensure_seed_allowed(config)?;
+    Ok(ApiResponse::success(SeedService::seed_demo(config, &pool).await?))
+}
@@ -0,0 +1,20 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{Router, routing::post};
+use handler::seed_demo_data;
+use rustzen_core::{
+    capability::system_seed,
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+pub fn seed_routes() -> Router<SqlitePool> {
+    Router::new().route_with_permission(
+        "/",
+        post(seed_demo_data),
+        PermissionsCheck::Require(system_seed::RUN),
+    )
+}
@@ -0,0 +1,84 @@
+use crate::common::{error::ServiceError, tx::Tx};
+
+use chrono::Utc;
+
+use super::types::{DemoUserSeed, DictSeed};
+
+pub struct SeedRepository;
+
+impl SeedRepository {
+    /// Insert a demo user bound to a role unless the username is already taken.
+    pub async fn insert_demo_user(
+        tx: &mut Tx<'_>,
+        user: &DemoUserSeed,
+        password_hash: &str,
+    ) -> Result<u64, ServiceError> {
+        let now = Utc::now().naive_utc();
+        let user_id = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO users (username, email, password_hash, real_name, status, is_system, created_at, updated_at)
+             SELECT ?, ?, ?, ?, 1, FALSE, ?, ?
+             WHERE NOT EXISTS (
+                 SELECT 1 FROM users WHERE (username = ? OR email = ?) AND deleted_at IS NULL
+             )
+             RETURNING id",
+        )
+        .bind(user.username)
+        .bind(user.email)
+        .bind(password_hash)
+        .bind(user.real_name)
+        .bind(now)
+        .bind(now)
+        .bind(user.username)
+        .bind(user.email)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error seeding demo user {}: {:?}", user.username, e);
+            ServiceError::DatabaseQueryFailed
+        })?;
+
+        let Some(user_id) = user_id else {
+            return Ok(0);
+        };
+
+        sqlx::query(
+            "INSERT OR IGNORE INTO user_roles (user_id, role_id, created_at)
+             SELECT ?, id, ? FROM roles WHERE code = ? AND deleted_at IS NULL",
+        )
+        .bind(user_id)
+        .bind(now)
+        .bind(user.role_code)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error seeding demo user role {}: {:?}", user.username, e);
+            ServiceError::DatabaseQueryFailed
+        })?;
+
+        Ok(1)
+    }
+
+    /// Insert a dictionary item unless the type/label pair already exists.
+    pub async fn insert_dict(tx: &mut Tx<'_>, dict: &DictSeed) -> Result<u64, ServiceError> {
+        let now = Utc::now().naive_utc();
+        let result = sqlx::query(
+            "INSERT INTO dicts (dict_type, label, value, status, sort_order, created_at, updated_at)
+             VALUES (?, ?, ?, 1, ?, ?, ?)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(dict.dict_type)
+        .bind(dict.label)
+        .bind(dict.value)
+        .bind(dict.sort_order)
+        .bind(now)
+        .bind(now)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error seeding dict {}:{}: {:?}", dict.dict_type, dict.label, e);
+            ServiceError::DatabaseQueryFailed
+        })?;
+
+        Ok(result.rows_affected())
+    }
+}
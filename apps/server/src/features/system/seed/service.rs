@@ -0,0 +1,114 @@
+use super::{
+    repo::SeedRepository,
+    types::{DemoUserSeed, DictSeed, SeedReport},
+};
+use crate::{
+    common::{error::ServiceError, tx},
+    infra::{config::AppConfig, password::PasswordUtils, permission::PermissionService},
+};
+
+use sqlx::SqlitePool;
+
+/// Shared password for every demo account.
+pub const DEMO_PASSWORD: &str = "rustzen-demo";
+
+const DEMO_USERS: &[DemoUserSeed] = &[
+    DemoUserSeed {
+        username: "demo_admin",
+        email: "demo_admin@example.com",
+        real_name: "Demo Admin",
+        role_code: "admin",
+    },
+    DemoUserSeed {
+        username: "demo_viewer",
+        email: "demo_viewer@example.com",
+        real_name: "Demo Viewer",
+        role_code: "viewer",
+    },
+];
+
+const DICT_SEEDS: &[DictSeed] = &[
+    DictSeed { dict_type: "menu_type", label: "Directory", value: "1", sort_order: 1 },
+    DictSeed { dict_type: "menu_type", label: "Menu", value: "2", sort_order: 2 },
+    DictSeed { dict_type: "menu_type", label: "Button", value: "3", sort_order: 3 },
+    DictSeed { dict_type: "menu_status", label: "Visible", value: "1", sort_order: 1 },
+    DictSeed { dict_type: "menu_status", label: "Hidden", value: "2", sort_order: 2 },
+    DictSeed { dict_type: "dict_status", label: "Enabled", value: "1", sort_order: 1 },
+    DictSeed { dict_type: "dict_status", label: "Disabled", value: "2", sort_order: 2 },
+];
+
+/// Idempotent demo dataset for new installs.
+pub struct SeedService;
+
+impl SeedService {
+    /// The HTTP seed endpoint is only available outside production.
+    pub fn ensure_seed_allowed(config: &AppConfig) -> Result<(), ServiceError> {
+        if config.is_production() {
+            return Err(ServiceError::InvalidOperation(
+                "Demo seeding is disabled in production".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Sync built-in roles and menus, then add demo dictionaries and users.
+    ///
+    /// Existing rows are left untouched, so running this repeatedly is safe.
+    pub async fn seed_demo(
+        config: &AppConfig,
+        pool: &SqlitePool,
+    ) -> Result<SeedReport, ServiceError> {
+        PermissionService::sync_permissions(pool).await?;
+
+        let password_hash = PasswordUtils::hash_password(config, DEMO_PASSWORD)?;
+        let mut report = SeedReport::default();
+        let mut tx = tx::begin(pool).await?;
+
+        for dict in DICT_SEEDS {
+            report.dicts_created += SeedRepository::insert_dict(&mut tx, dict).await?;
+        }
+        for user in DEMO_USERS {
+            report.users_created +=
+                SeedRepository::insert_demo_user(&mut tx, user, &password_hash).await?;
+        }
+
+        tx::commit(tx).await?;
+
+        tracing::info!(
+            users_created = report.users_created,
+            dicts_created = report.dicts_created,
+            "Demo seed completed"
+        );
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SeedService;
+    use crate::infra::config::test_config;
+
+    #[tokio::test]
+    async fn seed_demo_is_idempotent() {
+        let pool = crate::infra::db::test_pool().await;
+
+        let first = SeedService::seed_demo(test_config(), &pool).await.expect("first seed");
+        assert_eq!(first.users_created, 2);
+        assert_eq!(first.dicts_created, 7);
+
+        let second = SeedService::seed_demo(test_config(), &pool).await.expect("second seed");
+        assert_eq!(second.users_created, 0);
+        assert_eq!(second.dicts_created, 0);
+
+        let viewer_roles: Vec<String> = sqlx::query_scalar(
+            "SELECT r.code FROM users u
+             INNER JOIN user_roles ur ON ur.user_id = u.id
+             INNER JOIN roles r ON r.id = ur.role_id
+             WHERE u.username = 'demo_viewer'",
+        )
+        .fetch_all(&pool)
+        .await
+        .expect("viewer roles");
+        assert_eq!(viewer_roles, vec!["viewer".to_string()]);
+    }
+}
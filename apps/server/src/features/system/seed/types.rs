@@ -0,0 +1,27 @@
+use serde::Serialize;
+
+/// Demo account created by the seed.
+#[derive(Debug, Clone, Copy)]
+pub struct DemoUserSeed {
+    pub username: &'static str,
+    pub email: &'static str,
+    pub real_name: &'static str,
+    pub role_code: &'static str,
+}
+
+/// Dictionary item created by the seed.
+#[derive(Debug, Clone, Copy)]
+pub struct DictSeed {
+    pub dict_type: &'static str,
+    pub label: &'static str,
+    pub value: &'static str,
+    pub sort_order: i32,
+}
+
+/// Rows inserted by a seed run; zero on re-runs.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedReport {
+    pub users_created: u64,
+    pub dicts_created: u64,
+}
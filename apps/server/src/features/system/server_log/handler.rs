@@ -0,0 +1,52 @@
+use super::types::LogStreamQuery;
+use crate::{
+    common::{error::AppError, validation::FieldErrors},
+    infra::log_stream,
+};
+
+use axum::{
+    extract::Query,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::{Stream, stream};
+use std::convert::Infallible;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::Level;
+
+/// Stream server log lines as server-sent events until the client disconnects.
+///
+/// Each `log` event carries one JSON line; a `lagged` event carries the number of lines
+/// skipped because the client fell behind.
+pub async fn stream_logs(
+    Query(query): Query<LogStreamQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let min_level = match query.level.as_deref().map(str::trim).filter(|l| !l.is_empty()) {
+        None => Level::INFO,
+        Some(level) => {
+            let parsed = level.parse::<Level>().ok();
+            let mut errors = FieldErrors::new();
+            if parsed.is_none() {
+                errors.push("level", "must be trace, debug, info, warn or error");
+            }
+            errors.into_result()?;
+            parsed.unwrap_or(Level::INFO)
+        }
+    };
+
+    let events = stream::unfold(log_stream::subscribe(), move |mut receiver| async move {
+        loop {
+            let event = match receiver.recv().await {
+                Ok(line) if line.at_least(min_level) => {
+                    Event::default().event("log").json_data(&line).unwrap_or_default()
+                }
+                Ok(_) => continue,
+                Err(RecvError::Lagged(skipped)) => {
+                    Event::default().event("lagged").data(skipped.to_string())
+                }
+                Err(RecvError::Closed) => return None,
+            };
+            return Some((Ok(event), receiver));
+        }
+    });
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
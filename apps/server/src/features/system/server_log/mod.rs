@@ -0,0 +1,18 @@
+pub mod handler;
+pub mod types;
+
+use axum::{Router, routing::get};
+use handler::stream_logs;
+use rustzen_core::{
+    capability::system_log,
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+pub fn server_log_routes() -> Router<SqlitePool> {
+    Router::new().route_with_permission(
+        "/stream",
+        get(stream_logs),
+        PermissionsCheck::Require(system_log::STREAM),
+    )
+}
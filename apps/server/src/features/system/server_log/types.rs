@@ -0,0 +1,9 @@
+use serde::Deserialize;
+
+/// Live log stream query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogStreamQuery {
+    /// Least severe level to send: `trace`, `debug`, `info` (default), `warn` or `error`.
+    pub level: Option<String>,
+}
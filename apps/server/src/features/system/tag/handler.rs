@@ -0,0 +1,93 @@
+use super::{
+    service::TagService,
+    types::{
+        AssignTagsPayload, CreateTagRequest, TagItemResp, TagQuery, TagResp, UpdateTagPayload,
+    },
+};
+use crate::{
+    common::{
+        api::{ApiResponse, AppResult, PageMeta},
+        ids::{RoleId, UserId},
+        pagination::{Pagination, PaginationQuery},
+    },
+    infra::db::DbExecutor,
+};
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use sqlx::SqlitePool;
+
+/// Get paginated tag list with usage counts
+pub async fn list_tags(
+    State(db): State<DbExecutor>,
+    Query(query): Query<TagQuery>,
+) -> AppResult<Vec<TagItemResp>> {
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (tags, total) = TagService::list_tags(db.read(), query).await?;
+    Ok(ApiResponse::page(tags, total, PageMeta::new(pagination, total)))
+}
+
+/// Create a tag
+pub async fn create_tag(
+    State(pool): State<SqlitePool>,
+    Json(request): Json<CreateTagRequest>,
+) -> AppResult<i64> {
+    Ok(ApiResponse::success(TagService::create_tag(&pool, request).await?))
+}
+
+/// Update a tag
+pub async fn update_tag(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+    Json(request): Json<UpdateTagPayload>,
+) -> AppResult<()> {
+    TagService::update_tag(&pool, id, request).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Delete a tag and its assignments
+pub async fn delete_tag(State(pool): State<SqlitePool>, Path(id): Path<i64>) -> AppResult<()> {
+    TagService::delete_tag(&pool, id).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Get the tags of a user
+pub async fn get_user_tags(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<UserId>,
+) -> AppResult<Vec<TagResp>> {
+    Ok(ApiResponse::success(TagService::user_tags(&pool, id).await?))
+}
+
+/// Replace the tags of a user
+pub async fn update_user_tags(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<UserId>,
+    Json(payload): Json<AssignTagsPayload>,
+) -> AppResult<()> {
+    TagService::assign_user_tags(&pool, id, payload).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Get the tags of a role
+pub async fn get_role_tags(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<RoleId>,
+) -> AppResult<Vec<TagResp>> {
+    Ok(ApiResponse::success(TagService::role_tags(&pool, id).await?))
+}
+
+/// Replace the tags of a role
+pub async fn update_role_tags(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<RoleId>,
+    Json(payload): Json<AssignTagsPayload>,
+) -> AppResult<()> {
+    TagService::assign_role_tags(&pool, id, payload).await?;
+    Ok(ApiResponse::success(()))
+}
@@ -0,0 +1,32 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{
+    Router,
+    routing::{delete, get, post, put},
+};
+use handler::{create_tag, delete_tag, list_tags, update_tag};
+use rustzen_core::{
+    capability::system_tag,
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+/// Tag CRUD; assigning tags lives on the user and role routes under `system:tag:assign`.
+pub fn tag_routes() -> Router<SqlitePool> {
+    Router::new()
+        .route_with_permission("/", get(list_tags), PermissionsCheck::Require(system_tag::LIST))
+        .route_with_permission("/", post(create_tag), PermissionsCheck::Require(system_tag::CREATE))
+        .route_with_permission(
+            "/{id}",
+            put(update_tag),
+            PermissionsCheck::Require(system_tag::UPDATE),
+        )
+        .route_with_permission(
+            "/{id}",
+            delete(delete_tag),
+            PermissionsCheck::Require(system_tag::DELETE),
+        )
+}
@@ -0,0 +1,314 @@
+use super::types::{TagCondition, TagResp, TagRow};
+use crate::common::{
+    error::ServiceError,
+    ids::{RoleId, UserId},
+    query::{count_with_filters, fetch_with_filters, push_ilike},
+    tx,
+};
+
+use chrono::Utc;
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+
+pub struct TagRepository;
+
+fn db_error(context: &str, e: sqlx::Error) -> ServiceError {
+    tracing::error!("Database error {}: {:?}", context, e);
+    ServiceError::DatabaseQueryFailed
+}
+
+/// Join table and owner table of each taggable kind.
+#[derive(Debug, Clone, Copy)]
+enum Owner {
+    User,
+    Role,
+}
+
+impl Owner {
+    fn label(self) -> &'static str {
+        match self {
+            Owner::User => "user",
+            Owner::Role => "role",
+        }
+    }
+
+    fn exists_sql(self) -> &'static str {
+        match self {
+            Owner::User => "SELECT EXISTS(SELECT 1 FROM users WHERE id = ? AND deleted_at IS NULL)",
+            Owner::Role => "SELECT EXISTS(SELECT 1 FROM roles WHERE id = ? AND deleted_at IS NULL)",
+        }
+    }
+
+    fn list_sql(self) -> &'static str {
+        match self {
+            Owner::User => {
+                "SELECT t.id, t.name, t.color FROM user_tags ut JOIN tags t ON t.id = ut.tag_id
+                 WHERE ut.user_id = ? ORDER BY t.name"
+            }
+            Owner::Role => {
+                "SELECT t.id, t.name, t.color FROM role_tags rt JOIN tags t ON t.id = rt.tag_id
+                 WHERE rt.role_id = ? ORDER BY t.name"
+            }
+        }
+    }
+
+    fn clear_sql(self) -> &'static str {
+        match self {
+            Owner::User => "DELETE FROM user_tags WHERE user_id = ?",
+            Owner::Role => "DELETE FROM role_tags WHERE role_id = ?",
+        }
+    }
+
+    fn insert_sql(self) -> &'static str {
+        match self {
+            Owner::User => "INSERT INTO user_tags (user_id, tag_id, created_at) VALUES (?, ?, ?)",
+            Owner::Role => "INSERT INTO role_tags (role_id, tag_id, created_at) VALUES (?, ?, ?)",
+        }
+    }
+}
+
+impl TagRepository {
+    pub async fn list_tags(
+        pool: &SqlitePool,
+        offset: i64,
+        limit: i64,
+        name: Option<&str>,
+    ) -> Result<(Vec<TagRow>, i64), ServiceError> {
+        let total = count_with_filters(pool, "SELECT COUNT(*) FROM tags WHERE 1=1", |qb| {
+            push_ilike(qb, "name", name)
+        })
+        .await?;
+        if total == 0 {
+            return Ok((Vec::new(), total));
+        }
+        let tags = fetch_with_filters(
+            pool,
+            "SELECT id, name, color, description, created_at, updated_at,
+                    (SELECT COUNT(*) FROM user_tags ut JOIN users u ON u.id = ut.user_id
+                     WHERE ut.tag_id = tags.id AND u.deleted_at IS NULL) AS user_count,
+                    (SELECT COUNT(*) FROM role_tags rt JOIN roles r ON r.id = rt.role_id
+                     WHERE rt.tag_id = tags.id AND r.deleted_at IS NULL) AS role_count
+             FROM tags WHERE 1=1",
+            |qb| push_ilike(qb, "name", name),
+            Some("name ASC"),
+            Some(limit),
+            Some(offset),
+        )
+        .await?;
+        Ok((tags, total))
+    }
+
+    /// Whether another tag than `except_id` already uses `name`.
+    pub async fn name_exists(
+        pool: &SqlitePool,
+        name: &str,
+        except_id: Option<i64>,
+    ) -> Result<bool, ServiceError> {
+        sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM tags WHERE name = ? AND id IS NOT ?)",
+        )
+        .bind(name)
+        .bind(except_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_error("checking tag name", e))
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        name: &str,
+        color: Option<&str>,
+        description: Option<&str>,
+    ) -> Result<i64, ServiceError> {
+        let now = Utc::now().naive_utc();
+        sqlx::query_scalar::<_, i64>(
+            "INSERT INTO tags (name, color, description, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?)
+             RETURNING id",
+        )
+        .bind(name)
+        .bind(color)
+        .bind(description)
+        .bind(now)
+        .bind(now)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_error("creating tag", e))
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: i64,
+        name: &str,
+        color: Option<&str>,
+        description: Option<&str>,
+    ) -> Result<bool, ServiceError> {
+        let result = sqlx::query(
+            "UPDATE tags SET name = ?, color = ?, description = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(name)
+        .bind(color)
+        .bind(description)
+        .bind(Utc::now().naive_utc())
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| db_error("updating tag", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Deletes a tag; its assignments go with it.
+    pub async fn delete(pool: &SqlitePool, id: i64) -> Result<bool, ServiceError> {
+        let result = sqlx::query("DELETE FROM tags WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await
+            .map_err(|e| db_error("deleting tag", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// How many of `ids` name an existing tag.
+    pub async fn count_existing(pool: &SqlitePool, ids: &[i64]) -> Result<i64, ServiceError> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let mut qb = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM tags WHERE id IN (");
+        let mut separated = qb.separated(", ");
+        for id in ids {
+            separated.push_bind(*id);
+        }
+        qb.push(")");
+        qb.build_query_scalar::<i64>()
+            .fetch_one(pool)
+            .await
+            .map_err(|e| db_error("checking tag ids", e))
+    }
+
+    pub async fn list_for_user(
+        pool: &SqlitePool,
+        user_id: UserId,
+    ) -> Result<Option<Vec<TagResp>>, ServiceError> {
+        Self::list_for(pool, Owner::User, user_id.get()).await
+    }
+
+    pub async fn list_for_role(
+        pool: &SqlitePool,
+        role_id: RoleId,
+    ) -> Result<Option<Vec<TagResp>>, ServiceError> {
+        Self::list_for(pool, Owner::Role, role_id.get()).await
+    }
+
+    /// Replaces the tags of a live user; `false` when there is no such user.
+    pub async fn replace_user_tags(
+        pool: &SqlitePool,
+        user_id: UserId,
+        tag_ids: &[i64],
+    ) -> Result<bool, ServiceError> {
+        Self::replace_for(pool, Owner::User, user_id.get(), tag_ids).await
+    }
+
+    /// Replaces the tags of a live role; `false` when there is no such role.
+    pub async fn replace_role_tags(
+        pool: &SqlitePool,
+        role_id: RoleId,
+        tag_ids: &[i64],
+    ) -> Result<bool, ServiceError> {
+        Self::replace_for(pool, Owner::Role, role_id.get(), tag_ids).await
+    }
+
+    /// Ids of live users matching `condition`.
+    pub async fn matching_user_ids(
+        pool: &SqlitePool,
+        condition: &TagCondition,
+    ) -> Result<Vec<UserId>, ServiceError> {
+        let mut qb = QueryBuilder::<Sqlite>::new("SELECT id FROM users WHERE deleted_at IS NULL");
+        Self::push_user_condition(&mut qb, "id", condition);
+        qb.push(" ORDER BY id");
+        qb.build_query_scalar::<UserId>()
+            .fetch_all(pool)
+            .await
+            .map_err(|e| db_error("selecting users by tag", e))
+    }
+
+    /// Restricts a user query to rows whose `id_column` matches `condition`.
+    pub fn push_user_condition(
+        qb: &mut QueryBuilder<Sqlite>,
+        id_column: &str,
+        condition: &TagCondition,
+    ) {
+        let tagged = |qb: &mut QueryBuilder<Sqlite>| {
+            qb.push(" AND ").push(id_column).push(
+                " IN (SELECT ut.user_id FROM user_tags ut JOIN tags t ON t.id = ut.tag_id
+                 WHERE t.name",
+            );
+        };
+        for name in &condition.all {
+            tagged(qb);
+            qb.push(" = ").push_bind(name.clone()).push(")");
+        }
+        if !condition.any.is_empty() {
+            tagged(qb);
+            qb.push(" IN (");
+            let mut separated = qb.separated(", ");
+            for name in &condition.any {
+                separated.push_bind(name.clone());
+            }
+            qb.push("))");
+        }
+    }
+
+    async fn list_for(
+        pool: &SqlitePool,
+        owner: Owner,
+        id: i64,
+    ) -> Result<Option<Vec<TagResp>>, ServiceError> {
+        let exists = sqlx::query_scalar::<_, bool>(owner.exists_sql())
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| db_error(&format!("checking {} {}", owner.label(), id), e))?;
+        if !exists {
+            return Ok(None);
+        }
+        let tags = sqlx::query_as::<_, TagResp>(owner.list_sql())
+            .bind(id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| db_error(&format!("listing tags of {} {}", owner.label(), id), e))?;
+        Ok(Some(tags))
+    }
+
+    async fn replace_for(
+        pool: &SqlitePool,
+        owner: Owner,
+        id: i64,
+        tag_ids: &[i64],
+    ) -> Result<bool, ServiceError> {
+        let context = format!("tagging {} {}", owner.label(), id);
+        let mut tx = tx::begin(pool).await?;
+        let exists = sqlx::query_scalar::<_, bool>(owner.exists_sql())
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| db_error(&context, e))?;
+        if !exists {
+            return Ok(false);
+        }
+        sqlx::query(owner.clear_sql())
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| db_error(&context, e))?;
+        let now = Utc::now().naive_utc();
+        for tag_id in tag_ids {
+            sqlx::query(owner.insert_sql())
+                .bind(id)
+                .bind(tag_id)
+                .bind(now)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| db_error(&context, e))?;
+        }
+        tx::commit(tx).await?;
+        Ok(true)
+    }
+}
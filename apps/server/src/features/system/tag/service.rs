@@ -0,0 +1,242 @@
+use super::{
+    repo::TagRepository,
+    types::{
+        AssignTagsPayload, CreateTagRequest, TagCondition, TagItemResp, TagQuery, TagResp,
+        UpdateTagPayload,
+    },
+};
+use crate::common::{
+    error::ServiceError,
+    ids::{RoleId, UserId},
+    pagination::{Pagination, PaginationQuery},
+    validation::FieldErrors,
+};
+
+use sqlx::SqlitePool;
+use std::collections::BTreeSet;
+
+const TAG_NAME_MAX_LEN: usize = 32;
+const TAG_DESCRIPTION_MAX_LEN: usize = 200;
+/// Tags one user or role may carry.
+const MAX_TAGS_PER_OWNER: usize = 20;
+
+pub struct TagService;
+
+impl TagService {
+    pub async fn list_tags(
+        pool: &SqlitePool,
+        query: TagQuery,
+    ) -> Result<(Vec<TagItemResp>, i64), ServiceError> {
+        let TagQuery { current, page_size, name } = query;
+        let pagination = Pagination::from_query(PaginationQuery { current, page_size });
+        let (rows, total) = TagRepository::list_tags(
+            pool,
+            i64::from(pagination.offset),
+            i64::from(pagination.limit),
+            name.as_deref(),
+        )
+        .await?;
+        Ok((rows.into_iter().map(TagItemResp::from).collect(), total))
+    }
+
+    pub async fn create_tag(
+        pool: &SqlitePool,
+        request: CreateTagRequest,
+    ) -> Result<i64, ServiceError> {
+        let fields = TagFields::parse(request.name, request.color, request.description)?;
+        if TagRepository::name_exists(pool, &fields.name, None).await? {
+            return Err(ServiceError::InvalidOperation(format!(
+                "Tag '{}' already exists",
+                fields.name
+            )));
+        }
+        tracing::info!("Creating tag '{}'", fields.name);
+        TagRepository::create(pool, &fields.name, fields.color.as_deref(), fields.description())
+            .await
+    }
+
+    pub async fn update_tag(
+        pool: &SqlitePool,
+        id: i64,
+        request: UpdateTagPayload,
+    ) -> Result<(), ServiceError> {
+        let fields = TagFields::parse(request.name, request.color, request.description)?;
+        if TagRepository::name_exists(pool, &fields.name, Some(id)).await? {
+            return Err(ServiceError::InvalidOperation(format!(
+                "Tag '{}' already exists",
+                fields.name
+            )));
+        }
+        tracing::info!("Updating tag {} ('{}')", id, fields.name);
+        let updated = TagRepository::update(
+            pool,
+            id,
+            &fields.name,
+            fields.color.as_deref(),
+            fields.description(),
+        )
+        .await?;
+        if !updated {
+            return Err(ServiceError::NotFound("Tag".to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn delete_tag(pool: &SqlitePool, id: i64) -> Result<(), ServiceError> {
+        tracing::info!("Deleting tag {}", id);
+        if !TagRepository::delete(pool, id).await? {
+            return Err(ServiceError::NotFound("Tag".to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn user_tags(pool: &SqlitePool, id: UserId) -> Result<Vec<TagResp>, ServiceError> {
+        TagRepository::list_for_user(pool, id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound("User".to_string()))
+    }
+
+    pub async fn role_tags(pool: &SqlitePool, id: RoleId) -> Result<Vec<TagResp>, ServiceError> {
+        TagRepository::list_for_role(pool, id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound("Role".to_string()))
+    }
+
+    pub async fn assign_user_tags(
+        pool: &SqlitePool,
+        id: UserId,
+        payload: AssignTagsPayload,
+    ) -> Result<(), ServiceError> {
+        let tag_ids = Self::check_tag_ids(pool, payload.tag_ids).await?;
+        tracing::info!("Setting tags of user {} to {:?}", id, tag_ids);
+        if !TagRepository::replace_user_tags(pool, id, &tag_ids).await? {
+            return Err(ServiceError::NotFound("User".to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn assign_role_tags(
+        pool: &SqlitePool,
+        id: RoleId,
+        payload: AssignTagsPayload,
+    ) -> Result<(), ServiceError> {
+        let tag_ids = Self::check_tag_ids(pool, payload.tag_ids).await?;
+        tracing::info!("Setting tags of role {} to {:?}", id, tag_ids);
+        if !TagRepository::replace_role_tags(pool, id, &tag_ids).await? {
+            return Err(ServiceError::NotFound("Role".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Live users carrying the tags `condition` asks for.
+    pub async fn users_matching(
+        pool: &SqlitePool,
+        condition: &TagCondition,
+    ) -> Result<Vec<UserId>, ServiceError> {
+        TagRepository::matching_user_ids(pool, condition).await
+    }
+
+    /// Deduplicates `tag_ids` and checks that each one exists.
+    async fn check_tag_ids(pool: &SqlitePool, tag_ids: Vec<i64>) -> Result<Vec<i64>, ServiceError> {
+        let tag_ids: Vec<i64> = tag_ids.into_iter().collect::<BTreeSet<_>>().into_iter().collect();
+        let mut errors = FieldErrors::new();
+        if tag_ids.len() > MAX_TAGS_PER_OWNER {
+            errors.push("tagIds", format!("must hold at most {} tags", MAX_TAGS_PER_OWNER));
+        } else if TagRepository::count_existing(pool, &tag_ids).await? != tag_ids.len() as i64 {
+            errors.push("tagIds", "contains a tag that does not exist");
+        }
+        errors.into_result()?;
+        Ok(tag_ids)
+    }
+}
+
+/// Validated name, color and description of a tag.
+struct TagFields {
+    name: String,
+    color: Option<String>,
+    description: Option<String>,
+}
+
+impl TagFields {
+    fn parse(
+        name: String,
+        color: Option<String>,
+        description: Option<String>,
+    ) -> Result<Self, ServiceError> {
+        let name = name.trim().to_string();
+        let color = color.map(|c| c.trim().to_lowercase()).filter(|c| !c.is_empty());
+        let description = description.map(|d| d.trim().to_string()).filter(|d| !d.is_empty());
+        let mut errors = FieldErrors::new();
+        if let Err(message) = validate_name(&name) {
+            errors.push("name", message);
+        }
+        if color.as_deref().is_some_and(|c| !is_hex_color(c)) {
+            errors.push("color", "must be a #rrggbb color");
+        }
+        if description.as_ref().is_some_and(|d| d.chars().count() > TAG_DESCRIPTION_MAX_LEN) {
+            errors.push(
+                "description",
+                format!("must be at most {} characters", TAG_DESCRIPTION_MAX_LEN),
+            );
+        }
+        errors.into_result()?;
+        Ok(Self { name, color, description })
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
+
+/// Names are lowercase and kebab or snake case (`contractor`, `pilot-group`).
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > TAG_NAME_MAX_LEN {
+        return Err(format!("must be 1-{} characters", TAG_NAME_MAX_LEN));
+    }
+    let valid = name.starts_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err("must start with a letter or digit and use only a-z, 0-9, '-' and '_'".to_string())
+    }
+}
+
+fn is_hex_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TagCondition, is_hex_color, validate_name};
+
+    #[test]
+    fn names_are_lowercase_labels() {
+        assert!(validate_name("contractor").is_ok());
+        assert!(validate_name("pilot-group").is_ok());
+        assert!(validate_name("2026_hires").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("Pilot Group").is_err());
+        assert!(validate_name("-pilot").is_err());
+        assert!(validate_name(&"a".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn colors_are_six_digit_hex() {
+        assert!(is_hex_color("#1a2b3c"));
+        assert!(!is_hex_color("1a2b3c"));
+        assert!(!is_hex_color("#fff"));
+        assert!(!is_hex_color("#gggggg"));
+    }
+
+    #[test]
+    fn tag_filters_split_on_commas() {
+        let condition = TagCondition::all_of(Some(" contractor, ,pilot-group "));
+        assert_eq!(condition.all, ["contractor", "pilot-group"]);
+        assert!(condition.any.is_empty());
+        assert!(TagCondition::all_of(Some("")).is_empty());
+        assert!(TagCondition::all_of(None).is_empty());
+    }
+}
@@ -0,0 +1,121 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Tag row as read from the database.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TagRow {
+    pub id: i64,
+    pub name: String,
+    pub color: Option<String>,
+    pub description: Option<String>,
+    /// Users carrying the tag, deleted users excluded.
+    pub user_count: i64,
+    /// Roles carrying the tag, deleted roles excluded.
+    pub role_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Tag for list display.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagItemResp {
+    pub id: i64,
+    pub name: String,
+    pub color: Option<String>,
+    pub description: Option<String>,
+    pub user_count: i64,
+    pub role_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<TagRow> for TagItemResp {
+    fn from(row: TagRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            color: row.color,
+            description: row.description,
+            user_count: row.user_count,
+            role_count: row.role_count,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+/// Tag carried by a user or role.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TagResp {
+    pub id: i64,
+    pub name: String,
+    pub color: Option<String>,
+}
+
+/// Create tag request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTagRequest {
+    pub name: String,
+    /// `#rrggbb`.
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Update tag request; renaming keeps the assignments.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateTagPayload {
+    pub name: String,
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Replaces every tag of a user or role.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignTagsPayload {
+    pub tag_ids: Vec<i64>,
+}
+
+/// Tag list query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagQuery {
+    pub current: Option<i64>,
+    pub page_size: Option<i64>,
+    /// Filter by name (case-insensitive search).
+    pub name: Option<String>,
+}
+
+/// Tags a user must carry, by name.
+///
+/// Used by the user list filter; anything that targets users by label, such as
+/// notifications, should select them through the same condition.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagCondition {
+    /// Every one of these tags.
+    pub all: Vec<String>,
+    /// At least one of these tags; empty means no constraint.
+    pub any: Vec<String>,
+}
+
+impl TagCondition {
+    /// Reads a comma-separated list such as `contractor,pilot-group`, which must all match.
+    pub fn all_of(names: Option<&str>) -> Self {
+        let all = names
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .collect();
+        Self { all, any: Vec::new() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.all.is_empty() && self.any.is_empty()
+    }
+}
@@ -1,85 +1,281 @@
 use super::{
     service::UserService,
     types::{
-        CreateUserRequest, UpdateUserPasswordPayload, UpdateUserPayload, UpdateUserStatusPayload,
-        UserItemResp, UserOptionResp, UserOptionsQuery, UserQuery,
+        ActivityItemResp, ActivityQuery, CreateUserRequest, EffectiveAccessResp,
+        RoleAssignmentResp, RoleHistoryQuery, RoleHistoryResp, UpdateRoleExpiryPayload,
+        UpdateUserPasswordPayload, UpdateUserPayload, UpdateUserStatusPayload, UserDataExportResp,
+        UserItemResp, UserOptionResp, UserQuery,
     },
 };
-use crate::common::api::{ApiResponse, AppResult};
+use crate::{
+    common::{
+        api::{ApiResponse, AppResult, OptionItem, OptionsQuery, PageMeta},
+        error::ServiceError,
+        export::Exporter,
+        ids::{RoleId, UserId},
+        mask::FieldMask,
+        pagination::{Pagination, PaginationQuery},
+    },
+    features::{
+        account::service::AccountService,
+        auth::{
+            service::AuthService,
+            types::{CapabilityCheckQuery, CapabilityCheckResp},
+        },
+        system::{
+            approval::{service::ApprovalService, types::ApprovalAction},
+            export_job::types::ExportResource,
+            export_template::{service::ExportTemplateService, types::ExportTemplateParam},
+            recycle_bin::{service::RecycleBinService, types::RestoreUserRequest},
+        },
+    },
+    infra::{config::AppConfig, db::DbExecutor},
+};
 
 use axum::{
-    Json,
-    extract::{Path, Query, State},
+    Extension, Json,
+    extract::{ConnectInfo, Path, Query, RawQuery, State},
+    http::StatusCode,
+    response::Response,
 };
-use rustzen_core::auth::CurrentUser;
+use rustzen_core::auth::{AuthClaims, CurrentUser};
 use sqlx::SqlitePool;
+use std::net::SocketAddr;
 use tracing::instrument;
 
 /// Get user list
-#[instrument(skip(pool, query))]
+#[instrument(skip(current_user, db, query))]
 pub async fn list_users(
-    State(pool): State<SqlitePool>,
+    current_user: CurrentUser,
+    State(db): State<DbExecutor>,
     Query(query): Query<UserQuery>,
 ) -> AppResult<Vec<UserItemResp>> {
-    let (users, total) = UserService::list_users(&pool, query).await?;
-    Ok(ApiResponse::page(users, total))
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (users, total) =
+        UserService::list_users(db.read(), query, FieldMask::for_user(&current_user)).await?;
+    Ok(ApiResponse::page(users, total, PageMeta::new(pagination, total)))
+}
+
+/// Export the user list as CSV, with the list's filters and sort
+#[instrument(skip(current_user, config, addr, db, filters, query, param))]
+pub async fn export_users(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    State(db): State<DbExecutor>,
+    RawQuery(filters): RawQuery,
+    Query(query): Query<UserQuery>,
+    Query(param): Query<ExportTemplateParam>,
+) -> Result<Response, (StatusCode, String)> {
+    let mask = FieldMask::for_user(&current_user);
+    let (tz, export) = async {
+        let tz =
+            AccountService::effective_timezone(config, db.read(), current_user.user_id).await?;
+        let columns =
+            ExportTemplateService::columns_for(db.read(), ExportResource::Users, param.template)
+                .await?;
+        let export =
+            UserService::export_users_csv(db.read(), query, mask, tz, columns.as_deref()).await?;
+        Ok::<_, ServiceError>((tz, export))
+    }
+    .await
+    .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    let exporter = Exporter::new(&current_user, addr.ip().to_string(), filters);
+    exporter.csv_response(config, db.write(), "users", "user", tz, export).await
 }
 
 /// Create user
-#[instrument(skip(pool, dto))]
+#[instrument(skip(config, current_user, pool, dto))]
 pub async fn create_user(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
     State(pool): State<SqlitePool>,
     Json(dto): Json<CreateUserRequest>,
-) -> AppResult<i64> {
-    Ok(ApiResponse::success(UserService::create_user(&pool, dto).await?))
+) -> AppResult<UserId> {
+    Ok(ApiResponse::success(
+        UserService::create_user(config, &pool, Some(UserId(current_user.user_id)), dto).await?,
+    ))
 }
 
 /// Update user
-#[instrument(skip(pool, id, dto))]
+#[instrument(skip(config, pool, id, dto))]
 pub async fn update_user(
     current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
     State(pool): State<SqlitePool>,
-    Path(id): Path<i64>,
+    Path(id): Path<UserId>,
     Json(dto): Json<UpdateUserPayload>,
-) -> AppResult<i64> {
-    Ok(ApiResponse::success(UserService::update_user(&pool, id, current_user.user_id, dto).await?))
+) -> AppResult<UserId> {
+    Ok(ApiResponse::success(
+        UserService::update_user(config, &pool, id, UserId(current_user.user_id), dto).await?,
+    ))
 }
 
 /// Delete user
-#[instrument(skip(pool, id, current_user))]
+#[instrument(skip(config, pool, id, current_user, claims))]
 pub async fn delete_user(
     current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    Extension(claims): Extension<AuthClaims>,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<UserId>,
+) -> AppResult<()> {
+    AuthService::require_recent_auth(config, &claims)?;
+    UserService::delete_user(&pool, id, UserId(current_user.user_id)).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Restore a soft-deleted user, renaming fields a live user has taken since
+#[instrument(skip(config, current_user, pool, id, request))]
+pub async fn restore_user(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
     State(pool): State<SqlitePool>,
-    Path(id): Path<i64>,
+    Path(id): Path<UserId>,
+    request: Option<Json<RestoreUserRequest>>,
 ) -> AppResult<()> {
-    UserService::delete_user(&pool, id, current_user.user_id).await?;
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    RecycleBinService::restore_user(config, &pool, &current_user, id, request).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Get a user's role assignment history
+#[instrument(skip(pool, id, query))]
+pub async fn get_role_history(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<UserId>,
+    Query(query): Query<RoleHistoryQuery>,
+) -> AppResult<Vec<RoleHistoryResp>> {
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (history, total) = UserService::list_role_history(&pool, id, query).await?;
+    Ok(ApiResponse::page(history, total, PageMeta::new(pagination, total)))
+}
+
+/// Get the roles a user holds and when temporary ones end
+#[instrument(skip(pool, id))]
+pub async fn get_user_roles(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<UserId>,
+) -> AppResult<Vec<RoleAssignmentResp>> {
+    Ok(ApiResponse::success(UserService::list_role_assignments(&pool, id).await?))
+}
+
+/// Set or clear when a user's role assignment expires
+#[instrument(skip(current_user, pool, payload))]
+pub async fn update_user_role_expiry(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Path((id, role_id)): Path<(UserId, RoleId)>,
+    Json(payload): Json<UpdateRoleExpiryPayload>,
+) -> AppResult<()> {
+    UserService::update_role_expiry(&pool, id, role_id, UserId(current_user.user_id), payload)
+        .await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// A user's logins, operations and role changes merged into one timeline
+#[instrument(skip(current_user, db, id, query))]
+pub async fn get_user_activity(
+    current_user: CurrentUser,
+    State(db): State<DbExecutor>,
+    Path(id): Path<UserId>,
+    Query(query): Query<ActivityQuery>,
+) -> AppResult<Vec<ActivityItemResp>> {
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let mask = FieldMask::for_user(&current_user);
+    let (activity, total) = UserService::list_activity(db.read(), id, query, mask).await?;
+    Ok(ApiResponse::page(activity, total, PageMeta::new(pagination, total)))
+}
+
+/// Whether an active user holds `perm`, and which grant provides it
+#[instrument(skip(pool, id, query))]
+pub async fn check_user_capability(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<UserId>,
+    Query(query): Query<CapabilityCheckQuery>,
+) -> AppResult<CapabilityCheckResp> {
+    Ok(ApiResponse::success(AuthService::check_capability(&pool, id.0, &query.perm).await?))
+}
+
+/// A user's merged capabilities, the roles granting each, and the menu tree they would see
+#[instrument(skip(pool, id))]
+pub async fn get_effective_access(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<UserId>,
+) -> AppResult<EffectiveAccessResp> {
+    Ok(ApiResponse::success(UserService::effective_access(&pool, id).await?))
+}
+
+/// Export all personal data held about a user as JSON
+#[instrument(skip(current_user, addr, pool, id))]
+pub async fn export_user_data(
+    current_user: CurrentUser,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<UserId>,
+) -> AppResult<UserDataExportResp> {
+    let data = UserService::export_user_data(&pool, id).await?;
+    let filters = Some(format!("id={}", id.0));
+    let exporter = Exporter::new(&current_user, addr.ip().to_string(), filters);
+    exporter.record(&pool, "user-data", 1).await?;
+    Ok(ApiResponse::success(data))
+}
+
+/// Scrub a user's personal data, after approval under dual control
+#[instrument(skip(config, current_user, pool, id))]
+pub async fn anonymize_user(
+    current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<UserId>,
+) -> AppResult<()> {
+    ApprovalService::require(
+        config,
+        &pool,
+        &current_user,
+        ApprovalAction::UserAnonymize { user_id: id },
+    )
+    .await?;
+    UserService::anonymize_user(config, &pool, id, UserId(current_user.user_id)).await?;
     Ok(ApiResponse::success(()))
 }
 
 /// Get user status options
 #[instrument]
-pub async fn get_user_status_options() -> AppResult<Vec<UserOptionResp>> {
+pub async fn get_user_status_options() -> AppResult<Vec<OptionItem<i16>>> {
     Ok(ApiResponse::success(UserService::get_user_status_options()))
 }
 
 /// Get user options
-#[instrument(skip(pool, query))]
+#[instrument(skip(db, query))]
 pub async fn get_user_options(
-    State(pool): State<SqlitePool>,
-    Query(query): Query<UserOptionsQuery>,
+    State(db): State<DbExecutor>,
+    Query(query): Query<OptionsQuery>,
 ) -> AppResult<Vec<UserOptionResp>> {
-    Ok(ApiResponse::success(UserService::get_user_options(&pool, query).await?))
+    Ok(ApiResponse::success(UserService::get_user_options(db.read(), query).await?))
 }
 
-#[instrument(skip(pool, id, dto))]
+#[instrument(skip(config, pool, id, dto))]
 pub async fn update_user_password(
     current_user: CurrentUser,
+    Extension(config): Extension<&'static AppConfig>,
     State(pool): State<SqlitePool>,
-    Path(id): Path<i64>,
+    Path(id): Path<UserId>,
     Json(dto): Json<UpdateUserPasswordPayload>,
 ) -> AppResult<bool> {
     Ok(ApiResponse::success(
-        UserService::update_user_password(&pool, id, current_user.user_id, dto).await?,
+        UserService::update_user_password(config, &pool, id, UserId(current_user.user_id), dto)
+            .await?,
     ))
 }
 
@@ -87,10 +283,10 @@ pub async fn update_user_password(
 pub async fn update_user_status(
     current_user: CurrentUser,
     State(pool): State<SqlitePool>,
-    Path(id): Path<i64>,
+    Path(id): Path<UserId>,
     Json(dto): Json<UpdateUserStatusPayload>,
 ) -> AppResult<bool> {
     Ok(ApiResponse::success(
-        UserService::update_user_status(&pool, id, current_user.user_id, dto).await?,
+        UserService::update_user_status(&pool, id, UserId(current_user.user_id), dto).await?,
     ))
 }
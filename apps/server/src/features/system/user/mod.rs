@@ -3,16 +3,22 @@ pub mod repo;
 pub mod service;
 pub mod types;
 
+use crate::features::system::{
+    purge::handler::{get_user_purge_report, purge_user},
+    tag::handler::{get_user_tags, update_user_tags},
+};
 use axum::{
     Router,
     routing::{delete, get, post, put},
 };
 use handler::{
-    create_user, delete_user, get_user_options, get_user_status_options, list_users, update_user,
-    update_user_password, update_user_status,
+    anonymize_user, check_user_capability, create_user, delete_user, export_user_data,
+    export_users, get_effective_access, get_role_history, get_user_activity, get_user_options,
+    get_user_roles, get_user_status_options, list_users, restore_user, update_user,
+    update_user_password, update_user_role_expiry, update_user_status,
 };
 use rustzen_core::{
-    capability::system_user,
+    capability::{system_tag, system_user},
     permission::{PermissionsCheck, RouterExt},
 };
 use sqlx::SqlitePool;
@@ -25,6 +31,11 @@ pub fn user_routes() -> Router<SqlitePool> {
             post(create_user),
             PermissionsCheck::Require(system_user::CREATE),
         )
+        .route_with_permission(
+            "/export",
+            get(export_users),
+            PermissionsCheck::Require(system_user::EXPORT),
+        )
         .route_with_permission(
             "/{id}",
             put(update_user),
@@ -35,6 +46,71 @@ pub fn user_routes() -> Router<SqlitePool> {
             delete(delete_user),
             PermissionsCheck::Require(system_user::DELETE),
         )
+        .route_with_permission(
+            "/{id}/restore",
+            put(restore_user),
+            PermissionsCheck::Require(system_user::RESTORE),
+        )
+        .route_with_permission(
+            "/{id}/purge",
+            get(get_user_purge_report),
+            PermissionsCheck::Require(system_user::PURGE),
+        )
+        .route_with_permission(
+            "/{id}/purge",
+            delete(purge_user),
+            PermissionsCheck::Require(system_user::PURGE),
+        )
+        .route_with_permission(
+            "/{id}/role-history",
+            get(get_role_history),
+            PermissionsCheck::Require(system_user::ROLE_HISTORY),
+        )
+        .route_with_permission(
+            "/{id}/roles",
+            get(get_user_roles),
+            PermissionsCheck::Require(system_user::LIST),
+        )
+        .route_with_permission(
+            "/{id}/roles/{role_id}/expiry",
+            put(update_user_role_expiry),
+            PermissionsCheck::Require(system_user::UPDATE),
+        )
+        .route_with_permission(
+            "/{id}/activity",
+            get(get_user_activity),
+            PermissionsCheck::Require(system_user::ACTIVITY),
+        )
+        .route_with_permission(
+            "/{id}/export",
+            get(export_user_data),
+            PermissionsCheck::Require(system_user::EXPORT_DATA),
+        )
+        .route_with_permission(
+            "/{id}/anonymize",
+            post(anonymize_user),
+            PermissionsCheck::Require(system_user::ANONYMIZE),
+        )
+        .route_with_permission(
+            "/{id}/effective-access",
+            get(get_effective_access),
+            PermissionsCheck::Require(system_user::LIST),
+        )
+        .route_with_permission(
+            "/{id}/can",
+            get(check_user_capability),
+            PermissionsCheck::Require(system_user::LIST),
+        )
+        .route_with_permission(
+            "/{id}/tags",
+            get(get_user_tags),
+            PermissionsCheck::Require(system_user::LIST),
+        )
+        .route_with_permission(
+            "/{id}/tags",
+            put(update_user_tags),
+            PermissionsCheck::Require(system_tag::ASSIGN),
+        )
         .route_with_permission(
             "/options",
             get(get_user_options),
@@ -1,24 +1,82 @@
 use crate::common::{
+    api::OptionItem,
     error::ServiceError,
-    query::{count_with_filters, fetch_with_filters, push_eq, push_ilike},
+    ids::{RoleId, UserId},
+    pagination::Sort,
+    query::{
+        OptionsFilter, OptionsSql, count_with_filters, fetch_options, fetch_with_filters, push_eq,
+        push_ilike,
+    },
+    tx::{self, Tx},
 };
+use crate::features::{
+    auth::service::AuthService,
+    manage::log::types::LogItemResp,
+    system::{
+        profile_field::{service::ProfileFieldService, types::ProfileField},
+        tag::repo::TagRepository,
+    },
+};
+use crate::infra::{config::AppConfig, events};
 
-use chrono::Utc;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rustzen_core::{capability::SYSTEM_WILDCARD, events::DomainEvent};
 use sqlx::{Error as SqlxError, QueryBuilder, Sqlite, SqlitePool};
 
-use super::types::{CreateUserCommand, UserListQuery, UserWithRolesRow};
+use super::types::{
+    AccessMenuRow, ActivityKind, ActivityListQuery, ActivityRow, CreateUserCommand,
+    EffectiveAccessRows, EmailChangeItemResp, ExpiredRoleRow, PermissionSourceRow,
+    PersonalDataRows, RoleAssignmentResp, RoleHistoryAction, RoleHistoryRow, UserListQuery,
+    UserProfileRow, UserWithRolesRow,
+};
+
+/// Users for dropdowns, labelled with their real name when set.
+const USER_OPTIONS: OptionsSql = OptionsSql {
+    base_sql: "SELECT id, COALESCE(real_name, username) AS label FROM users WHERE deleted_at IS NULL",
+    value_column: "id",
+    status_column: "status",
+    search_columns: &["username", "real_name"],
+    order_by: "label ASC",
+};
 
 /// User db for database operations
 pub struct UserRepository;
 
 const DEFAULT_USER_STATUS: i16 = 1;
+/// Anonymized users stay disabled; they no longer have a password to log in with.
+const ANONYMIZED_USER_STATUS: i16 = 2;
 
 impl UserRepository {
+    /// Sortable list fields mapped to `user_with_roles` columns.
+    pub const SORT_COLUMNS: &[(&str, &str)] = &[
+        ("username", "username"),
+        ("realName", "real_name"),
+        ("email", "email"),
+        ("status", "status"),
+        ("lastLoginAt", "last_login_at"),
+        ("createdAt", "created_at"),
+        ("updatedAt", "updated_at"),
+    ];
+
     fn format_query(query: &UserListQuery, query_builder: &mut QueryBuilder<Sqlite>) {
         push_ilike(query_builder, "username", query.username.as_deref());
         push_ilike(query_builder, "real_name", query.real_name.as_deref());
         push_ilike(query_builder, "email", query.email.as_deref());
         push_eq(query_builder, "status", query.status);
+        TagRepository::push_user_condition(query_builder, "id", &query.tags);
+        for (key, value) in &query.profile {
+            // Booleans compare as `true`/`false` rather than SQLite's 1/0.
+            query_builder
+                .push(" AND EXISTS (SELECT 1 FROM json_each(profile) j WHERE j.key = ")
+                .push_bind(key.clone())
+                .push(
+                    " AND (CASE j.type WHEN 'true' THEN 'true' WHEN 'false' THEN 'false'
+                      ELSE CAST(j.value AS TEXT) END) = ",
+                )
+                .push_bind(value.clone())
+                .push(")");
+        }
     }
 
     /// Find users with pagination and filters
@@ -40,13 +98,14 @@ impl UserRepository {
         if total == 0 {
             return Ok((Vec::new(), total));
         }
+        let order_by = query.sort.map(Sort::to_order_by);
         let users = fetch_with_filters(
             pool,
-            "SELECT id, username, email, password_hash, real_name, avatar_url, is_system, status, last_login_at, created_at, updated_at, roles FROM user_with_roles WHERE 1=1",
+            "SELECT id, username, email, phone, password_hash, real_name, avatar_url, is_system, status, last_login_at, created_at, updated_at, profile, roles FROM user_with_roles WHERE 1=1",
             |query_builder| {
                 Self::format_query(&query, query_builder);
             },
-            Some("created_at DESC"),
+            Some(order_by.as_deref().unwrap_or("created_at DESC")),
             Some(limit),
             Some(offset),
         )
@@ -58,42 +117,18 @@ impl UserRepository {
     /// Find users for dropdown options
     pub async fn list_user_options(
         pool: &SqlitePool,
-        status: Option<i16>,
-        q: Option<&str>,
-        limit: Option<i64>,
-    ) -> Result<Vec<(i64, String)>, ServiceError> {
-        fetch_with_filters(
-            pool,
-            "SELECT id, COALESCE(real_name, username) AS label FROM users WHERE deleted_at IS NULL",
-            |query_builder| {
-                push_eq(query_builder, "status", status);
-                if let Some(search_term) = q {
-                    let search_term = search_term.trim();
-                    if !search_term.is_empty() {
-                        let pattern = format!("%{}%", search_term);
-                        query_builder
-                            .push(" AND (LOWER(username) LIKE ")
-                            .push_bind(pattern.clone())
-                            .push(" OR LOWER(real_name) LIKE ")
-                            .push_bind(pattern)
-                            .push(")");
-                    }
-                }
-            },
-            Some("label ASC"),
-            limit,
-            None,
-        )
-        .await
+        filter: &OptionsFilter<UserId>,
+    ) -> Result<Vec<(UserId, String)>, ServiceError> {
+        fetch_options(pool, &USER_OPTIONS, filter, |_| {}).await
     }
 
     /// Find user by ID (returns None if not found)
     pub async fn find_user_by_id(
         pool: &SqlitePool,
-        id: i64,
+        id: UserId,
     ) -> Result<Option<UserWithRolesRow>, ServiceError> {
         sqlx::query_as::<_, UserWithRolesRow>(
-            "SELECT id, username, email, password_hash, real_name, avatar_url, is_system, status, last_login_at, created_at, updated_at, roles FROM user_with_roles WHERE id = ?",
+            "SELECT id, username, email, phone, password_hash, real_name, avatar_url, is_system, status, last_login_at, created_at, updated_at, profile, roles FROM user_with_roles WHERE id = ?",
         )
         .bind(id)
         .fetch_optional(pool)
@@ -108,16 +143,24 @@ impl UserRepository {
     pub async fn create_user(
         pool: &SqlitePool,
         cmd: &CreateUserCommand,
-    ) -> Result<i64, ServiceError> {
-        let mut tx = pool.begin().await.map_err(|e| {
-            tracing::error!("Database error starting transaction for user creation: {:?}", e);
-            ServiceError::DatabaseQueryFailed
-        })?;
+    ) -> Result<UserId, ServiceError> {
+        let mut tx = tx::begin(pool).await?;
+        let user_id = Self::create_user_in_tx(&mut tx, cmd).await?;
+        tx::commit(tx).await?;
+
+        Ok(user_id)
+    }
+
+    /// Create new user with optional roles inside the caller's transaction
+    pub async fn create_user_in_tx(
+        tx: &mut Tx<'_>,
+        cmd: &CreateUserCommand,
+    ) -> Result<UserId, ServiceError> {
         let now = Utc::now().naive_utc();
 
-        let user_id = sqlx::query_scalar::<_, i64>(
-            "INSERT INTO users (username, email, password_hash, real_name, status, created_at, updated_at)
-             VALUES (?, ?, ?, ?, ?, ?, ?)
+        let user_id = sqlx::query_scalar::<_, UserId>(
+            "INSERT INTO users (username, email, password_hash, real_name, status, profile, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, COALESCE(?, '{}'), ?, ?)
              RETURNING id",
         )
         .bind(&cmd.username)
@@ -125,70 +168,57 @@ impl UserRepository {
         .bind(&cmd.password_hash)
         .bind(cmd.real_name.as_deref())
         .bind(cmd.status.unwrap_or(DEFAULT_USER_STATUS))
+        .bind(cmd.profile.as_deref())
         .bind(now)
         .bind(now)
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut **tx)
         .await
         .map_err(|e| Self::map_user_write_error("creating user", e))?;
 
-        Self::insert_user_roles(&mut tx, user_id, &cmd.role_ids).await?;
-
-        tx.commit().await.map_err(|e| {
-            tracing::error!("Database error committing user creation transaction: {:?}", e);
-            ServiceError::DatabaseQueryFailed
-        })?;
-
+        Self::insert_user_roles(tx, user_id, &cmd.role_ids, cmd.operator_id).await?;
         Ok(user_id)
     }
 
-    /// Update existing user
-    pub async fn update_user(
-        pool: &SqlitePool,
-        id: i64,
-        email: &str,
+    /// Update an existing user and replace its roles inside the caller's transaction
+    ///
+    /// The email is left alone; a new address only applies once its owner confirms it. A
+    /// `None` profile keeps the stored one.
+    pub async fn update_user_in_tx(
+        tx: &mut Tx<'_>,
+        id: UserId,
         real_name: &str,
-        role_ids: &[i64],
-    ) -> Result<i64, ServiceError> {
-        let mut tx = pool.begin().await.map_err(|e| {
-            tracing::error!("Database error starting transaction for user update: {:?}", e);
-            ServiceError::DatabaseQueryFailed
-        })?;
-
-        let user_id = sqlx::query_scalar::<_, i64>(
+        profile: Option<&str>,
+        role_ids: &[RoleId],
+        operator_id: UserId,
+    ) -> Result<UserId, ServiceError> {
+        let user_id = sqlx::query_scalar::<_, UserId>(
             "UPDATE users
-             SET email = ?, real_name = ?, updated_at = ?
+             SET real_name = ?, profile = COALESCE(?, profile), updated_at = ?
              WHERE id = ? AND deleted_at IS NULL
              RETURNING id",
         )
-        .bind(email)
         .bind(real_name)
+        .bind(profile)
         .bind(Utc::now().naive_utc())
         .bind(id)
-        .fetch_optional(&mut *tx)
+        .fetch_optional(&mut **tx)
         .await
         .map_err(|e| Self::map_user_write_error("updating user", e))?;
 
-        if let Some(id) = user_id {
-            Self::insert_user_roles(&mut tx, id, role_ids).await?;
-            tx.commit().await.map_err(|e| {
-                tracing::error!("Database error committing user update transaction: {:?}", e);
-                ServiceError::DatabaseQueryFailed
-            })?;
-            Ok(id)
-        } else {
-            Err(ServiceError::NotFound(format!("User id: {}", id)))
-        }
+        let id = user_id.ok_or_else(|| ServiceError::NotFound(format!("User id: {}", id)))?;
+        Self::insert_user_roles(tx, id, role_ids, Some(operator_id)).await?;
+        Ok(id)
     }
 
-    /// Soft delete user
-    pub async fn soft_delete(pool: &SqlitePool, id: i64) -> Result<bool, ServiceError> {
+    /// Soft delete user inside the caller's transaction
+    pub async fn soft_delete_in_tx(tx: &mut Tx<'_>, id: UserId) -> Result<bool, ServiceError> {
         let result = sqlx::query(
             "UPDATE users SET deleted_at = ?, updated_at = ? WHERE id = ? AND deleted_at IS NULL",
         )
         .bind(Utc::now().naive_utc())
         .bind(Utc::now().naive_utc())
         .bind(id)
-        .execute(pool)
+        .execute(&mut **tx)
         .await
         .map_err(|e| {
             tracing::error!("Database error soft deleting user ID {}: {:?}", id, e);
@@ -198,30 +228,105 @@ impl UserRepository {
         Ok(result.rows_affected() > 0)
     }
 
-    /// Set user roles (replace all existing roles)
+    /// Restore a soft-deleted user under the given username, email and phone.
+    ///
+    /// The unique indexes only cover live rows, so this fails with a conflict when one of
+    /// them is held by a live user; the recycle bin checks first and offers renames.
+    pub async fn restore_deleted(
+        pool: &SqlitePool,
+        id: UserId,
+        username: &str,
+        email: &str,
+        phone: Option<&str>,
+    ) -> Result<bool, ServiceError> {
+        let result = sqlx::query(
+            "UPDATE users SET username = ?, email = ?, phone = ?, deleted_at = NULL, updated_at = ?
+             WHERE id = ? AND deleted_at IS NOT NULL",
+        )
+        .bind(username)
+        .bind(email)
+        .bind(phone)
+        .bind(Utc::now().naive_utc())
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| Self::map_user_write_error("restoring user", e))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Set user roles (replace all existing roles) and record the difference in history.
+    /// Kept roles keep their expiry; an expired role that is set again becomes permanent.
     pub async fn insert_user_roles(
-        tx: &mut sqlx::Transaction<'_, Sqlite>,
-        user_id: i64,
-        role_ids: &[i64],
+        tx: &mut Tx<'_>,
+        user_id: UserId,
+        role_ids: &[RoleId],
+        operator_id: Option<UserId>,
     ) -> Result<(), ServiceError> {
-        sqlx::query("DELETE FROM user_roles WHERE user_id = ?")
-            .bind(user_id)
-            .execute(&mut **tx)
-            .await
-            .map_err(|e| {
-                tracing::error!("Database error deleting existing user_roles: {:?}", e);
+        let now = Utc::now().naive_utc();
+        let current: Vec<RoleId> = sqlx::query_scalar(
+            "SELECT role_id FROM user_roles
+             WHERE user_id = ? AND (expires_at IS NULL OR expires_at > ?)",
+        )
+        .bind(user_id)
+        .bind(now)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error loading user_roles for history: {:?}", e);
+            ServiceError::DatabaseQueryFailed
+        })?;
+        let mut role_ids = role_ids.to_vec();
+        role_ids.sort_unstable();
+        role_ids.dedup();
+        let assigned: Vec<RoleId> =
+            role_ids.iter().copied().filter(|role_id| !current.contains(role_id)).collect();
+        let removed: Vec<RoleId> =
+            current.iter().copied().filter(|role_id| !role_ids.contains(role_id)).collect();
+        Self::record_role_history_in_tx(
+            tx,
+            user_id,
+            &assigned,
+            RoleHistoryAction::Assigned,
+            operator_id,
+        )
+        .await?;
+        Self::record_role_history_in_tx(
+            tx,
+            user_id,
+            &removed,
+            RoleHistoryAction::Removed,
+            operator_id,
+        )
+        .await?;
+
+        if !removed.is_empty() {
+            let mut query_builder: QueryBuilder<Sqlite> =
+                QueryBuilder::new("DELETE FROM user_roles WHERE user_id = ");
+            query_builder.push_bind(user_id).push(" AND role_id IN (");
+            let mut separated = query_builder.separated(", ");
+            for role_id in &removed {
+                separated.push_bind(*role_id);
+            }
+            query_builder.push(")");
+            query_builder.build().execute(&mut **tx).await.map_err(|e| {
+                tracing::error!("Database error deleting removed user_roles: {:?}", e);
                 ServiceError::DatabaseQueryFailed
             })?;
+        }
 
-        if role_ids.is_empty() {
+        if assigned.is_empty() {
             return Ok(());
         }
-        let now = Utc::now().naive_utc();
         let mut query_builder: QueryBuilder<Sqlite> =
             QueryBuilder::new("INSERT INTO user_roles (user_id, role_id, created_at) ");
-        query_builder.push_values(role_ids.iter(), |mut builder, role_id| {
+        query_builder.push_values(assigned.iter(), |mut builder, role_id| {
             builder.push_bind(user_id).push_bind(role_id).push_bind(now);
         });
+        query_builder.push(
+            " ON CONFLICT(user_id, role_id)
+             DO UPDATE SET expires_at = NULL, created_at = excluded.created_at",
+        );
 
         query_builder.build().execute(&mut **tx).await.map_err(|e| {
             tracing::error!("Database error inserting user_roles: {:?}", e);
@@ -230,6 +335,461 @@ impl UserRepository {
         Ok(())
     }
 
+    /// Append one history row per role for `user_id`
+    pub async fn record_role_history_in_tx(
+        tx: &mut Tx<'_>,
+        user_id: UserId,
+        role_ids: &[RoleId],
+        action: RoleHistoryAction,
+        operator_id: Option<UserId>,
+    ) -> Result<(), ServiceError> {
+        if role_ids.is_empty() {
+            return Ok(());
+        }
+        let now = Utc::now().naive_utc();
+        let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "INSERT INTO user_role_history (user_id, role_id, action, operator_id, created_at) ",
+        );
+        query_builder.push_values(role_ids.iter(), |mut builder, role_id| {
+            builder
+                .push_bind(user_id)
+                .push_bind(role_id)
+                .push_bind(action.as_str())
+                .push_bind(operator_id)
+                .push_bind(now);
+        });
+
+        query_builder.build().execute(&mut **tx).await.map_err(|e| {
+            tracing::error!("Database error recording role history for user {}: {:?}", user_id, e);
+            ServiceError::DatabaseQueryFailed
+        })?;
+        Ok(())
+    }
+
+    /// Roles a user holds now, temporary ones with their end.
+    pub async fn list_role_assignments(
+        pool: &SqlitePool,
+        user_id: UserId,
+    ) -> Result<Vec<RoleAssignmentResp>, ServiceError> {
+        sqlx::query_as::<_, RoleAssignmentResp>(
+            "SELECT r.id AS role_id, r.name AS role_name, r.code AS role_code,
+                    ur.created_at AS assigned_at, ur.expires_at
+             FROM user_roles ur
+             INNER JOIN roles r ON r.id = ur.role_id AND r.deleted_at IS NULL
+             WHERE ur.user_id = ? AND (ur.expires_at IS NULL OR ur.expires_at > ?)
+             ORDER BY r.id",
+        )
+        .bind(user_id)
+        .bind(Utc::now().naive_utc())
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error listing roles of user ID {}: {:?}", user_id, e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
+    /// Sets when a current assignment ends; `false` when the user does not hold the role.
+    pub async fn set_role_expiry(
+        pool: &SqlitePool,
+        user_id: UserId,
+        role_id: RoleId,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<bool, ServiceError> {
+        let now = Utc::now().naive_utc();
+        let result = sqlx::query(
+            "UPDATE user_roles SET expires_at = ?
+             WHERE user_id = ? AND role_id = ? AND (expires_at IS NULL OR expires_at > ?)",
+        )
+        .bind(expires_at.map(|at| at.naive_utc()))
+        .bind(user_id)
+        .bind(role_id)
+        .bind(now)
+        .execute(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                "Database error setting expiry of role {} for user ID {}: {:?}",
+                role_id,
+                user_id,
+                e
+            );
+            ServiceError::DatabaseQueryFailed
+        })?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Deletes assignments that expired by `now` and records each as removed by nobody.
+    pub async fn remove_expired_roles(
+        pool: &SqlitePool,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<ExpiredRoleRow>, ServiceError> {
+        let mut tx = tx::begin(pool).await?;
+        let expired = sqlx::query_as::<_, (UserId, RoleId)>(
+            "DELETE FROM user_roles WHERE expires_at <= ? RETURNING user_id, role_id",
+        )
+        .bind(now.naive_utc())
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error removing expired user_roles: {:?}", e);
+            ServiceError::DatabaseQueryFailed
+        })?;
+        let mut rows = Vec::with_capacity(expired.len());
+        for (user_id, role_id) in expired {
+            Self::record_role_history_in_tx(
+                &mut tx,
+                user_id,
+                &[role_id],
+                RoleHistoryAction::Removed,
+                None,
+            )
+            .await?;
+            let role_name = sqlx::query_scalar::<_, String>("SELECT name FROM roles WHERE id = ?")
+                .bind(role_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| {
+                    tracing::error!("Database error loading name of role {}: {:?}", role_id, e);
+                    ServiceError::DatabaseQueryFailed
+                })?;
+            rows.push(ExpiredRoleRow { user_id, role_id, role_name });
+        }
+        tx::commit(tx).await?;
+        Ok(rows)
+    }
+
+    /// Lists a user's role assignment history, newest first
+    pub async fn list_role_history(
+        pool: &SqlitePool,
+        user_id: UserId,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<RoleHistoryRow>, i64), ServiceError> {
+        let total = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM user_role_history WHERE user_id = ?",
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error counting role history for user {}: {:?}", user_id, e);
+            ServiceError::DatabaseQueryFailed
+        })?;
+
+        let rows = sqlx::query_as::<_, RoleHistoryRow>(
+            "SELECT h.id, h.role_id, r.name AS role_name, r.code AS role_code, h.action,
+                    h.operator_id, o.username AS operator_username, h.created_at
+             FROM user_role_history h
+             LEFT JOIN roles r ON r.id = h.role_id
+             LEFT JOIN users o ON o.id = h.operator_id
+             WHERE h.user_id = ?
+             ORDER BY h.created_at DESC, h.id DESC
+             LIMIT ? OFFSET ?",
+        )
+        .bind(user_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error listing role history for user {}: {:?}", user_id, e);
+            ServiceError::DatabaseQueryFailed
+        })?;
+
+        Ok((rows, total))
+    }
+
+    /// Merges a user's operation logs (logins included) and role changes, newest first.
+    pub async fn list_activity(
+        pool: &SqlitePool,
+        user_id: UserId,
+        query: &ActivityListQuery,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<ActivityRow>, i64), ServiceError> {
+        let map_err = |e: SqlxError| {
+            tracing::error!("Database error listing activity for user {}: {:?}", user_id, e);
+            ServiceError::DatabaseQueryFailed
+        };
+        let mut count = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM (");
+        Self::push_activity_sources(&mut count, user_id, query);
+        count.push(")");
+        let total: i64 = count.build_query_scalar().fetch_one(pool).await.map_err(map_err)?;
+        if total == 0 {
+            return Ok((Vec::new(), total));
+        }
+
+        let mut select = QueryBuilder::<Sqlite>::new("SELECT * FROM (");
+        Self::push_activity_sources(&mut select, user_id, query);
+        // Operation logs are stamped to the second and role history to the microsecond, so
+        // compare whole seconds; text order would put any role change after a same-second log.
+        select
+            .push(") ORDER BY datetime(created_at) DESC, kind, source_id DESC LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
+        let rows = select.build_query_as::<ActivityRow>().fetch_all(pool).await.map_err(map_err)?;
+        Ok((rows, total))
+    }
+
+    /// `UNION ALL` of the timeline sources selected by `query.kind`.
+    fn push_activity_sources(
+        qb: &mut QueryBuilder<Sqlite>,
+        user_id: UserId,
+        query: &ActivityListQuery,
+    ) {
+        let include = |kind: ActivityKind| query.kind.is_none_or(|wanted| wanted == kind);
+        let push_range = |qb: &mut QueryBuilder<Sqlite>, column: &str| {
+            if let Some(from) = query.from {
+                qb.push(format!(" AND {} >= ", column)).push_bind(from);
+            }
+            if let Some(to) = query.to {
+                qb.push(format!(" AND {} < ", column)).push_bind(to);
+            }
+        };
+
+        let logs = include(ActivityKind::Login) || include(ActivityKind::Operation);
+        if logs {
+            qb.push(
+                "SELECT CASE WHEN l.action = 'AUTH_LOGIN' THEN 'login' ELSE 'operation' END AS kind,
+                        l.id AS source_id, l.action, l.description, l.status, l.ip_address,
+                        NULL AS role_id, NULL AS role_name, NULL AS operator_username, l.created_at
+                 FROM operation_logs l WHERE l.user_id = ",
+            )
+            .push_bind(user_id);
+            match query.kind {
+                Some(ActivityKind::Login) => qb.push(" AND l.action = 'AUTH_LOGIN'"),
+                Some(ActivityKind::Operation) => qb.push(" AND l.action <> 'AUTH_LOGIN'"),
+                _ => qb,
+            };
+            push_range(qb, "l.created_at");
+        }
+        if include(ActivityKind::RoleChange) {
+            if logs {
+                qb.push(" UNION ALL ");
+            }
+            qb.push(
+                "SELECT 'role_change' AS kind, h.id AS source_id, h.action,
+                        'Role ' || h.action || ': ' || COALESCE(r.name, '#' || h.role_id)
+                            AS description,
+                        NULL AS status, NULL AS ip_address, h.role_id, r.name AS role_name,
+                        o.username AS operator_username, h.created_at
+                 FROM user_role_history h
+                 LEFT JOIN roles r ON r.id = h.role_id
+                 LEFT JOIN users o ON o.id = h.operator_id
+                 WHERE h.user_id = ",
+            )
+            .push_bind(user_id);
+            push_range(qb, "h.created_at");
+        }
+    }
+
+    /// Profile of a user, including soft-deleted accounts
+    pub async fn find_profile(
+        pool: &SqlitePool,
+        id: UserId,
+    ) -> Result<Option<UserProfileRow>, ServiceError> {
+        sqlx::query_as::<_, UserProfileRow>(
+            "SELECT id, username, email, phone, real_name, avatar_url, status, is_system,
+                    last_login_at, created_at, updated_at, deleted_at, anonymized_at, profile
+             FROM users WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error finding profile of user ID {}: {:?}", id, e);
+            ServiceError::DatabaseQueryFailed
+        })
+    }
+
+    /// Roles, full role history and operation logs of a user, oldest first
+    pub async fn list_personal_data(
+        pool: &SqlitePool,
+        id: UserId,
+    ) -> Result<PersonalDataRows, ServiceError> {
+        let db_error = |context: &str, e: SqlxError| {
+            tracing::error!("Database error loading {} of user ID {}: {:?}", context, id, e);
+            ServiceError::DatabaseQueryFailed
+        };
+        let roles = sqlx::query_as::<_, OptionItem<RoleId>>(
+            "SELECT r.name AS label, r.id AS value
+             FROM user_roles ur
+             INNER JOIN roles r ON r.id = ur.role_id AND r.deleted_at IS NULL
+             WHERE ur.user_id = ? AND (ur.expires_at IS NULL OR ur.expires_at > CURRENT_TIMESTAMP)
+             ORDER BY r.id",
+        )
+        .bind(id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("roles", e))?;
+        let role_history = sqlx::query_as::<_, RoleHistoryRow>(
+            "SELECT h.id, h.role_id, r.name AS role_name, r.code AS role_code, h.action,
+                    h.operator_id, o.username AS operator_username, h.created_at
+             FROM user_role_history h
+             LEFT JOIN roles r ON r.id = h.role_id
+             LEFT JOIN users o ON o.id = h.operator_id
+             WHERE h.user_id = ?
+             ORDER BY h.id",
+        )
+        .bind(id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("role history", e))?;
+        let email_changes = sqlx::query_as::<_, EmailChangeItemResp>(
+            "SELECT new_email, created_at, expires_at, confirmed_at
+             FROM email_change_requests WHERE user_id = ?
+             ORDER BY id",
+        )
+        .bind(id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("email changes", e))?;
+        let operation_logs = sqlx::query_as::<_, LogItemResp>(
+            "SELECT id, user_id, username, action, description, data, status, duration_ms,
+                    ip_address, user_agent, route, resource_type, resource_id, status_code,
+                    country, city, created_at
+             FROM operation_logs WHERE user_id = ?
+             ORDER BY id",
+        )
+        .bind(id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("operation logs", e))?;
+        Ok(PersonalDataRows { roles, role_history, email_changes, operation_logs })
+    }
+
+    /// Each capability the user holds with the role granting it, and the enabled menus they
+    /// can open, named in `locale` where translated.
+    pub async fn list_effective_access(
+        pool: &SqlitePool,
+        id: UserId,
+        locale: &str,
+    ) -> Result<EffectiveAccessRows, ServiceError> {
+        let db_error = |context: &str, e: SqlxError| {
+            tracing::error!("Database error loading {} of user ID {}: {:?}", context, id, e);
+            ServiceError::DatabaseQueryFailed
+        };
+        let permissions = sqlx::query_as::<_, PermissionSourceRow>(
+            "SELECT menu_code, role_code FROM user_permissions
+             WHERE user_id = ?
+             ORDER BY menu_code, role_code",
+        )
+        .bind(id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("permission sources", e))?;
+        let menus = sqlx::query_as::<_, AccessMenuRow>(
+            "SELECT m.id, m.parent_id, m.code, COALESCE(t.name, m.name) AS name, m.menu_type,
+                    m.visible
+             FROM menus m
+             LEFT JOIN menu_i18n t ON t.menu_id = m.id AND t.locale = ?
+             WHERE m.deleted_at IS NULL
+               AND m.status = 1
+               AND m.menu_type IN (1, 2, 4, 5)
+               AND EXISTS (
+                   SELECT 1 FROM user_permissions p
+                   WHERE p.user_id = ? AND (p.menu_code = m.code OR p.menu_code = ?)
+               )
+             ORDER BY m.sort_order ASC, m.id ASC",
+        )
+        .bind(locale)
+        .bind(id)
+        .bind(SYSTEM_WILDCARD)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("menus", e))?;
+        Ok(EffectiveAccessRows { permissions, menus })
+    }
+
+    /// Scrub the personal data of a non-system user while keeping its id.
+    ///
+    /// The row is renamed to `username`, loses its email, phone, name, avatar, password,
+    /// linked login accounts, known login sources, notifications, requested email changes
+    /// and verification tokens, and is disabled. Roles are
+    /// removed through the history, and the user's operation logs keep their actions but lose
+    /// the name, IP, location, user agent and request data. Returns `false` when the user is
+    /// missing, a system user, or already anonymized.
+    pub async fn anonymize(
+        pool: &SqlitePool,
+        id: UserId,
+        username: &str,
+        operator_id: UserId,
+    ) -> Result<bool, ServiceError> {
+        let mut tx = tx::begin(pool).await?;
+        let now = Utc::now().naive_utc();
+        let result = sqlx::query(
+            "UPDATE users
+             SET username = ?, email = NULL, phone = NULL, real_name = NULL, avatar_url = NULL,
+                 profile = '{}', password_hash = '', last_login_ip = NULL,
+                 last_login_country = NULL, last_login_city = NULL, status = ?, anonymized_at = ?,
+                 updated_at = ?
+             WHERE id = ? AND is_system = 0 AND anonymized_at IS NULL",
+        )
+        .bind(username)
+        .bind(ANONYMIZED_USER_STATUS)
+        .bind(now)
+        .bind(now)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Self::map_user_write_error("anonymizing user", e))?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+        Self::insert_user_roles(&mut tx, id, &[], Some(operator_id)).await?;
+        sqlx::query("DELETE FROM user_identities WHERE user_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                tracing::error!("Database error unlinking identities of user ID {}: {:?}", id, e);
+                ServiceError::DatabaseQueryFailed
+            })?;
+        for sql in [
+            // Expired assignments that `insert_user_roles` leaves to the expiry task.
+            "DELETE FROM user_roles WHERE user_id = ?",
+            "DELETE FROM user_login_sources WHERE user_id = ?",
+            "DELETE FROM notifications WHERE user_id = ?",
+            "DELETE FROM email_change_requests WHERE user_id = ?",
+            "DELETE FROM email_verification_tokens WHERE user_id = ?",
+        ] {
+            sqlx::query(sql).bind(id).execute(&mut *tx).await.map_err(|e| {
+                tracing::error!("Database error clearing personal data of user ID {}: {:?}", id, e);
+                ServiceError::DatabaseQueryFailed
+            })?;
+        }
+        sqlx::query(
+            "UPDATE operation_logs
+             SET username = ?, ip_address = '', user_agent = '', data = NULL, country = NULL,
+                 city = NULL
+             WHERE user_id = ?",
+        )
+        .bind(username)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error scrubbing logs of user ID {}: {:?}", id, e);
+            ServiceError::DatabaseQueryFailed
+        })?;
+
+        tx::commit(tx).await?;
+        Ok(true)
+    }
+
+    /// Number of users that are not soft-deleted.
+    pub async fn count_users(pool: &SqlitePool) -> Result<i64, ServiceError> {
+        sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE deleted_at IS NULL")
+            .fetch_one(pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("Database error counting users: {:?}", e);
+                ServiceError::DatabaseQueryFailed
+            })
+    }
+
     /// Check if email exists
     pub async fn email_exists(pool: &SqlitePool, email: &str) -> Result<bool, ServiceError> {
         let exists: bool = sqlx::query_scalar(
@@ -262,16 +822,57 @@ impl UserRepository {
         Ok(exists)
     }
 
+    /// Find an active user's ID by username
+    pub async fn find_user_id_by_username(
+        pool: &SqlitePool,
+        username: &str,
+    ) -> Result<Option<UserId>, ServiceError> {
+        sqlx::query_scalar("SELECT id FROM users WHERE username = ? AND deleted_at IS NULL")
+            .bind(username)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("Database error finding user by username '{}': {:?}", username, e);
+                ServiceError::DatabaseQueryFailed
+            })
+    }
+
+    /// Find an active role's ID by code
+    pub async fn find_role_id_by_code(
+        pool: &SqlitePool,
+        code: &str,
+    ) -> Result<Option<RoleId>, ServiceError> {
+        sqlx::query_scalar("SELECT id FROM roles WHERE code = ? AND deleted_at IS NULL")
+            .bind(code)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("Database error finding role by code '{}': {:?}", code, e);
+                ServiceError::DatabaseQueryFailed
+            })
+    }
+
     pub async fn update_user_password(
         pool: &SqlitePool,
-        id: i64,
+        id: UserId,
+        password_hash: &str,
+    ) -> Result<bool, ServiceError> {
+        let mut tx = tx::begin(pool).await?;
+        let updated = Self::update_user_password_in_tx(&mut tx, id, password_hash).await?;
+        tx::commit(tx).await?;
+        Ok(updated)
+    }
+
+    pub async fn update_user_password_in_tx(
+        tx: &mut Tx<'_>,
+        id: UserId,
         password_hash: &str,
     ) -> Result<bool, ServiceError> {
         let result = sqlx::query("UPDATE users SET password_hash = ?, updated_at = ? WHERE id = ?")
             .bind(password_hash)
             .bind(Utc::now().naive_utc())
             .bind(id)
-            .execute(pool)
+            .execute(&mut **tx)
             .await
             .map_err(|e| {
                 tracing::error!("Database error updating user password for ID {}: {:?}", id, e);
@@ -283,14 +884,25 @@ impl UserRepository {
 
     pub async fn update_user_status(
         pool: &SqlitePool,
-        id: i64,
+        id: UserId,
+        status: i16,
+    ) -> Result<bool, ServiceError> {
+        let mut tx = tx::begin(pool).await?;
+        let updated = Self::update_user_status_in_tx(&mut tx, id, status).await?;
+        tx::commit(tx).await?;
+        Ok(updated)
+    }
+
+    pub async fn update_user_status_in_tx(
+        tx: &mut Tx<'_>,
+        id: UserId,
         status: i16,
     ) -> Result<bool, ServiceError> {
         let result = sqlx::query("UPDATE users SET status = ?, updated_at = ? WHERE id = ?")
             .bind(status)
             .bind(Utc::now().naive_utc())
             .bind(id)
-            .execute(pool)
+            .execute(&mut **tx)
             .await
             .map_err(|e| {
                 tracing::error!("Database error updating user status for ID {}: {:?}", id, e);
@@ -301,19 +913,18 @@ impl UserRepository {
     }
 
     fn map_user_write_error(context: &str, err: SqlxError) -> ServiceError {
+        // SQLite reports unique violations as "UNIQUE constraint failed: users.<column>".
         if let SqlxError::Database(db_err) = &err
-            && db_err.code().as_deref() == Some("23505")
+            && db_err.is_unique_violation()
         {
-            match db_err.constraint() {
-                Some("idx_users_username") | Some("users_username_key") => {
-                    tracing::warn!("Unique username conflict while {}", context);
-                    return ServiceError::UsernameConflict;
-                }
-                Some("idx_users_email") | Some("users_email_key") => {
-                    tracing::warn!("Unique email conflict while {}", context);
-                    return ServiceError::EmailConflict;
-                }
-                _ => {}
+            let message = db_err.message();
+            if message.contains("users.username") {
+                tracing::warn!("Unique username conflict while {}", context);
+                return ServiceError::UsernameConflict;
+            }
+            if message.contains("users.email") {
+                tracing::warn!("Unique email conflict while {}", context);
+                return ServiceError::EmailConflict;
             }
         }
 
@@ -321,3 +932,381 @@ impl UserRepository {
         ServiceError::DatabaseQueryFailed
     }
 }
+
+/// Persistence and event hand-off used by `UserService`.
+///
+/// `SqlitePool` is the production store; service tests swap in an in-memory fake so the
+/// guard and validation rules run without a database.
+#[async_trait]
+pub trait UserRepo: Send + Sync {
+    async fn list_users(
+        &self,
+        offset: i64,
+        limit: i64,
+        query: UserListQuery,
+    ) -> Result<(Vec<UserWithRolesRow>, i64), ServiceError>;
+    async fn list_user_options(
+        &self,
+        filter: &OptionsFilter<UserId>,
+    ) -> Result<Vec<(UserId, String)>, ServiceError>;
+    async fn find_user_by_id(&self, id: UserId) -> Result<Option<UserWithRolesRow>, ServiceError>;
+    async fn find_user_id_by_username(
+        &self,
+        username: &str,
+    ) -> Result<Option<UserId>, ServiceError>;
+    async fn find_role_id_by_code(&self, code: &str) -> Result<Option<RoleId>, ServiceError>;
+    async fn username_exists(&self, username: &str) -> Result<bool, ServiceError>;
+    async fn email_exists(&self, email: &str) -> Result<bool, ServiceError>;
+    async fn count_users(&self) -> Result<i64, ServiceError>;
+    /// Inserts the user with its roles and records `UserCreated` in one transaction.
+    async fn create_user(&self, cmd: &CreateUserCommand) -> Result<UserId, ServiceError>;
+    /// Updates profile fields, replaces the role set and records `event` in one transaction.
+    async fn update_user(
+        &self,
+        id: UserId,
+        real_name: &str,
+        profile: Option<&str>,
+        role_ids: &[RoleId],
+        operator_id: UserId,
+        event: &DomainEvent,
+    ) -> Result<UserId, ServiceError>;
+    /// Custom profile field definitions user profiles are checked against.
+    async fn list_profile_fields(&self) -> Result<Vec<ProfileField>, ServiceError>;
+    /// Mails a confirmation token to `new_email`, which replaces the user's email once used.
+    async fn request_email_change(
+        &self,
+        config: &AppConfig,
+        id: UserId,
+        new_email: &str,
+    ) -> Result<(), ServiceError>;
+    /// Soft-deletes the user and records `event` in one transaction; `false` when it
+    /// was already gone, in which case nothing is recorded.
+    async fn soft_delete(&self, id: UserId, event: &DomainEvent) -> Result<bool, ServiceError>;
+    async fn update_user_password(
+        &self,
+        id: UserId,
+        password_hash: &str,
+    ) -> Result<bool, ServiceError>;
+    async fn update_user_status(&self, id: UserId, status: i16) -> Result<bool, ServiceError>;
+    /// Sets a new password and the given status in one transaction.
+    async fn reset_password(
+        &self,
+        id: UserId,
+        password_hash: &str,
+        status: i16,
+    ) -> Result<(), ServiceError>;
+    async fn list_role_history(
+        &self,
+        user_id: UserId,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<RoleHistoryRow>, i64), ServiceError>;
+    async fn list_role_assignments(
+        &self,
+        user_id: UserId,
+    ) -> Result<Vec<RoleAssignmentResp>, ServiceError>;
+    /// `false` when the user does not currently hold the role.
+    async fn set_role_expiry(
+        &self,
+        user_id: UserId,
+        role_id: RoleId,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<bool, ServiceError>;
+    async fn list_activity(
+        &self,
+        user_id: UserId,
+        query: &ActivityListQuery,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<ActivityRow>, i64), ServiceError>;
+    async fn find_profile(&self, id: UserId) -> Result<Option<UserProfileRow>, ServiceError>;
+    async fn list_personal_data(&self, id: UserId) -> Result<PersonalDataRows, ServiceError>;
+    async fn list_effective_access(
+        &self,
+        id: UserId,
+        locale: &str,
+    ) -> Result<EffectiveAccessRows, ServiceError>;
+    async fn anonymize(
+        &self,
+        id: UserId,
+        username: &str,
+        operator_id: UserId,
+    ) -> Result<bool, ServiceError>;
+}
+
+#[async_trait]
+impl UserRepo for SqlitePool {
+    async fn list_users(
+        &self,
+        offset: i64,
+        limit: i64,
+        query: UserListQuery,
+    ) -> Result<(Vec<UserWithRolesRow>, i64), ServiceError> {
+        UserRepository::list_users(self, offset, limit, query).await
+    }
+
+    async fn list_user_options(
+        &self,
+        filter: &OptionsFilter<UserId>,
+    ) -> Result<Vec<(UserId, String)>, ServiceError> {
+        UserRepository::list_user_options(self, filter).await
+    }
+
+    async fn find_user_by_id(&self, id: UserId) -> Result<Option<UserWithRolesRow>, ServiceError> {
+        UserRepository::find_user_by_id(self, id).await
+    }
+
+    async fn find_user_id_by_username(
+        &self,
+        username: &str,
+    ) -> Result<Option<UserId>, ServiceError> {
+        UserRepository::find_user_id_by_username(self, username).await
+    }
+
+    async fn find_role_id_by_code(&self, code: &str) -> Result<Option<RoleId>, ServiceError> {
+        UserRepository::find_role_id_by_code(self, code).await
+    }
+
+    async fn username_exists(&self, username: &str) -> Result<bool, ServiceError> {
+        UserRepository::username_exists(self, username).await
+    }
+
+    async fn email_exists(&self, email: &str) -> Result<bool, ServiceError> {
+        UserRepository::email_exists(self, email).await
+    }
+
+    async fn count_users(&self) -> Result<i64, ServiceError> {
+        UserRepository::count_users(self).await
+    }
+
+    async fn create_user(&self, cmd: &CreateUserCommand) -> Result<UserId, ServiceError> {
+        let mut tx = tx::begin(self).await?;
+        let user_id = UserRepository::create_user_in_tx(&mut tx, cmd).await?;
+        let recorded = events::record(&mut tx, &cmd.created_event(user_id)).await?;
+        tx::commit(tx).await?;
+        events::deliver(self, recorded).await;
+        Ok(user_id)
+    }
+
+    async fn update_user(
+        &self,
+        id: UserId,
+        real_name: &str,
+        profile: Option<&str>,
+        role_ids: &[RoleId],
+        operator_id: UserId,
+        event: &DomainEvent,
+    ) -> Result<UserId, ServiceError> {
+        let mut tx = tx::begin(self).await?;
+        let id = UserRepository::update_user_in_tx(
+            &mut tx,
+            id,
+            real_name,
+            profile,
+            role_ids,
+            operator_id,
+        )
+        .await?;
+        let recorded = events::record(&mut tx, event).await?;
+        tx::commit(tx).await?;
+        events::deliver(self, recorded).await;
+        Ok(id)
+    }
+
+    async fn list_profile_fields(&self) -> Result<Vec<ProfileField>, ServiceError> {
+        ProfileFieldService::definitions(self).await
+    }
+
+    async fn request_email_change(
+        &self,
+        config: &AppConfig,
+        id: UserId,
+        new_email: &str,
+    ) -> Result<(), ServiceError> {
+        AuthService::request_email_change(config, self, id.get(), new_email).await
+    }
+
+    async fn soft_delete(&self, id: UserId, event: &DomainEvent) -> Result<bool, ServiceError> {
+        let mut tx = tx::begin(self).await?;
+        if !UserRepository::soft_delete_in_tx(&mut tx, id).await? {
+            return Ok(false);
+        }
+        let recorded = events::record(&mut tx, event).await?;
+        tx::commit(tx).await?;
+        events::deliver(self, recorded).await;
+        Ok(true)
+    }
+
+    async fn update_user_password(
+        &self,
+        id: UserId,
+        password_hash: &str,
+    ) -> Result<bool, ServiceError> {
+        UserRepository::update_user_password(self, id, password_hash).await
+    }
+
+    async fn update_user_status(&self, id: UserId, status: i16) -> Result<bool, ServiceError> {
+        UserRepository::update_user_status(self, id, status).await
+    }
+
+    async fn reset_password(
+        &self,
+        id: UserId,
+        password_hash: &str,
+        status: i16,
+    ) -> Result<(), ServiceError> {
+        let mut tx = tx::begin(self).await?;
+        UserRepository::update_user_password_in_tx(&mut tx, id, password_hash).await?;
+        UserRepository::update_user_status_in_tx(&mut tx, id, status).await?;
+        tx::commit(tx).await
+    }
+
+    async fn list_role_history(
+        &self,
+        user_id: UserId,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<RoleHistoryRow>, i64), ServiceError> {
+        UserRepository::list_role_history(self, user_id, offset, limit).await
+    }
+
+    async fn list_role_assignments(
+        &self,
+        user_id: UserId,
+    ) -> Result<Vec<RoleAssignmentResp>, ServiceError> {
+        UserRepository::list_role_assignments(self, user_id).await
+    }
+
+    async fn set_role_expiry(
+        &self,
+        user_id: UserId,
+        role_id: RoleId,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<bool, ServiceError> {
+        UserRepository::set_role_expiry(self, user_id, role_id, expires_at).await
+    }
+
+    async fn list_activity(
+        &self,
+        user_id: UserId,
+        query: &ActivityListQuery,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<ActivityRow>, i64), ServiceError> {
+        UserRepository::list_activity(self, user_id, query, offset, limit).await
+    }
+
+    async fn find_profile(&self, id: UserId) -> Result<Option<UserProfileRow>, ServiceError> {
+        UserRepository::find_profile(self, id).await
+    }
+
+    async fn list_personal_data(&self, id: UserId) -> Result<PersonalDataRows, ServiceError> {
+        UserRepository::list_personal_data(self, id).await
+    }
+
+    async fn list_effective_access(
+        &self,
+        id: UserId,
+        locale: &str,
+    ) -> Result<EffectiveAccessRows, ServiceError> {
+        UserRepository::list_effective_access(self, id, locale).await
+    }
+
+    async fn anonymize(
+        &self,
+        id: UserId,
+        username: &str,
+        operator_id: UserId,
+    ) -> Result<bool, ServiceError> {
+        UserRepository::anonymize(self, id, username, operator_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UserRepository;
+    use crate::{
+        common::{error::ServiceError, ids::RoleId, tx},
+        features::system::user::types::CreateUserCommand,
+    };
+
+    fn command(username: &str, email: &str) -> CreateUserCommand {
+        CreateUserCommand {
+            username: username.to_string(),
+            email: email.to_string(),
+            password_hash: "hash".to_string(),
+            real_name: None,
+            status: None,
+            role_ids: Vec::new(),
+            profile: None,
+            operator_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn soft_deleted_username_can_be_reused_then_restored_or_purged() {
+        use crate::{
+            features::system::purge::{service::PurgeService, types::PurgeTarget},
+            infra::config::test_config,
+        };
+
+        let pool = crate::infra::db::test_pool().await;
+
+        let old_id = UserRepository::create_user(&pool, &command("alice", "alice@example.com"))
+            .await
+            .unwrap();
+        let mut tx = tx::begin(&pool).await.unwrap();
+        assert!(UserRepository::soft_delete_in_tx(&mut tx, old_id).await.unwrap());
+        tx::commit(tx).await.unwrap();
+        let new_id = UserRepository::create_user(&pool, &command("alice", "alice2@example.com"))
+            .await
+            .unwrap();
+
+        let err =
+            UserRepository::restore_deleted(&pool, old_id, "alice", "alice@example.com", None)
+                .await
+                .unwrap_err();
+        assert!(matches!(err, ServiceError::UsernameConflict));
+
+        let purged =
+            PurgeService::purge(test_config(), &pool, PurgeTarget::User(old_id)).await.unwrap();
+        assert_eq!(purged.name, "alice");
+        let err =
+            PurgeService::purge(test_config(), &pool, PurgeTarget::User(new_id)).await.unwrap_err();
+        assert!(matches!(err, ServiceError::NotFound(_)));
+        assert!(UserRepository::username_exists(&pool, "alice").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn role_changes_are_recorded_in_history() {
+        use crate::features::system::role::repo::RoleRepository;
+
+        let pool = crate::infra::db::test_pool().await;
+
+        let ops = RoleRepository::create(&pool, "Ops", "ops", None, 1, &[]).await.unwrap();
+        let support =
+            RoleRepository::create(&pool, "Support", "support", None, 1, &[]).await.unwrap();
+        let operator =
+            UserRepository::create_user(&pool, &command("root", "root@example.com")).await.unwrap();
+        let mut cmd = command("alice", "alice@example.com");
+        cmd.role_ids = vec![ops];
+        cmd.operator_id = Some(operator);
+        let user_id = UserRepository::create_user(&pool, &cmd).await.unwrap();
+
+        let mut tx = tx::begin(&pool).await.unwrap();
+        UserRepository::update_user_in_tx(&mut tx, user_id, "", None, &[support], operator)
+            .await
+            .unwrap();
+        tx::commit(tx).await.unwrap();
+
+        let (history, total) =
+            UserRepository::list_role_history(&pool, user_id, 0, 10).await.unwrap();
+        assert_eq!(total, 3);
+        let entries: Vec<(RoleId, &str)> =
+            history.iter().map(|row| (row.role_id, row.action.as_str())).collect();
+        assert!(entries.contains(&(ops, "assigned")));
+        assert!(entries.contains(&(ops, "removed")));
+        assert!(entries.contains(&(support, "assigned")));
+        assert!(history.iter().all(|row| row.operator_username.as_deref() == Some("root")));
+    }
+}
@@ -1,23 +1,73 @@
 use super::{
-    repo::UserRepository,
+    repo::{UserRepo, UserRepository},
     types::{
-        CreateUserCommand, CreateUserRequest, UpdateUserPasswordPayload, UpdateUserPayload,
-        UpdateUserStatusPayload, UserItemResp, UserListQuery, UserOptionResp, UserOptionsQuery,
-        UserQuery,
+        AccessMenuNode, AccessMenuRow, ActivityItemResp, ActivityKind, ActivityListQuery,
+        ActivityQuery, CreateUserCommand, CreateUserRequest, EffectiveAccessResp,
+        EffectiveAccessRows, EffectivePermission, PersonalDataRows, RoleAssignmentResp,
+        RoleHistoryQuery, RoleHistoryResp, UpdateRoleExpiryPayload, UpdateUserPasswordPayload,
+        UpdateUserPayload, UpdateUserStatusPayload, UserDataExportResp, UserItemResp,
+        UserListQuery, UserOptionResp, UserQuery, UserWithRolesRow,
     },
 };
 use crate::{
     common::{
+        api::{OptionItem, OptionsQuery},
         error::ServiceError,
-        pagination::{Pagination, PaginationQuery},
+        export::{CSV_TIME_FORMAT, CsvExport, ExportChunk},
+        files::remove_avatar,
+        i18n,
+        ids::{MenuId, RoleId, UserId},
+        mask::{FieldMask, MaskFields},
+        pagination::{Pagination, PaginationQuery, Sort},
+        query::OptionsFilter,
         query::parse_optional_i16_filter,
+        validation::{FieldErrors, is_email},
     },
+    features::{
+        account::repo::AccountRepository,
+        auth::types::UserStatus,
+        system::{
+            profile_field::service::{check_profile, parse_profile_filter},
+            quota::{service::QuotaService, types::QuotaResource},
+            tag::types::TagCondition,
+        },
+    },
+    infra::config::AppConfig,
     infra::password::PasswordUtils,
     infra::permission::PermissionService,
 };
-use rustzen_core::capability::SYSTEM_WILDCARD;
 
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use rustzen_core::{capability::SYSTEM_WILDCARD, events::DomainEvent};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
 use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Key of the scheduled task that removes expired role assignments.
+pub const ROLE_EXPIRY_TASK_KEY: &str = "role-expiry";
+/// Notification kind sent when a temporary role assignment ends.
+const ROLE_EXPIRED_NOTIFICATION: &str = "role_expired";
+
+const OWNER_ROLE_CODE: &str = "owner";
+const USER_STATUS_NORMAL: i16 = 1;
+/// Users fetched per query when exporting.
+const EXPORT_BATCH_SIZE: i64 = 1000;
+
+/// Header of user CSV exports.
+pub const USER_EXPORT_COLUMNS: [&str; 9] = [
+    "id",
+    "username",
+    "email",
+    "phone",
+    "real_name",
+    "status",
+    "roles",
+    "last_login_at",
+    "created_at",
+];
 
 /// User service for business operations
 pub struct UserService;
@@ -25,36 +75,120 @@ pub struct UserService;
 impl UserService {
     /// Get user list with pagination
     pub async fn list_users(
-        pool: &SqlitePool,
+        repo: &impl UserRepo,
         query: UserQuery,
+        mask: FieldMask,
     ) -> Result<(Vec<UserItemResp>, i64), ServiceError> {
         tracing::info!("Fetching user list with query: {:?}", query);
 
-        let UserQuery { current, page_size, username, status, real_name, email } = query;
-        let pagination = Pagination::from_query(PaginationQuery { current, page_size });
+        let (pagination, repo_query) = Self::user_list_query(query)?;
         let limit = i64::from(pagination.limit);
         let offset = i64::from(pagination.offset);
-        let status = parse_optional_i16_filter(status.as_deref(), "user status", None)?;
-        let repo_query = UserListQuery { username, status, real_name, email };
+        let (users, total) = repo.list_users(offset, limit, repo_query).await?;
 
-        let (users, total) = UserRepository::list_users(pool, offset, limit, repo_query).await?;
+        let users = users
+            .into_iter()
+            .map(|user| UserItemResp::try_from(user).map(|user| user.masked(mask)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((users, total))
+    }
 
-        Ok((users.into_iter().map(UserItemResp::try_from).collect::<Result<Vec<_>, _>>()?, total))
+    /// Every user matching the list filters as CSV, sorted like the list, with times written
+    /// in `tz` and contact details masked per `mask`, keeping only `columns` when given.
+    /// Paging fields are ignored.
+    pub async fn export_users_csv(
+        repo: &impl UserRepo,
+        query: UserQuery,
+        mask: FieldMask,
+        tz: Tz,
+        columns: Option<&[String]>,
+    ) -> Result<CsvExport, ServiceError> {
+        let mut export = CsvExport::with_columns(&USER_EXPORT_COLUMNS, columns);
+        let mut position = 0;
+        while let Some(next) =
+            Self::export_users_chunk(repo, &query, position, mask, tz, &mut export).await?.next
+        {
+            position = next;
+        }
+        Ok(export)
+    }
+
+    /// Writes the batch of matching users starting at offset `position` to `export`.
+    pub async fn export_users_chunk(
+        repo: &impl UserRepo,
+        query: &UserQuery,
+        position: i64,
+        mask: FieldMask,
+        tz: Tz,
+        export: &mut CsvExport,
+    ) -> Result<ExportChunk, ServiceError> {
+        let (_, repo_query) = Self::user_list_query(query.clone())?;
+        let format_time = |at: DateTime<Utc>| at.with_timezone(&tz).format(CSV_TIME_FORMAT);
+        let (users, total) = repo.list_users(position, EXPORT_BATCH_SIZE, repo_query).await?;
+        for user in users {
+            let user = UserItemResp::try_from(user)?.masked(mask);
+            let roles: Vec<String> = user.roles.into_iter().map(|role| role.label).collect();
+            export.push_row([
+                user.id.0.to_string(),
+                user.username,
+                user.email.unwrap_or_default(),
+                user.phone.unwrap_or_default(),
+                user.real_name.unwrap_or_default(),
+                user.status.to_string(),
+                roles.join(";"),
+                user.last_login_at.map(|at| format_time(at).to_string()).unwrap_or_default(),
+                format_time(user.created_at).to_string(),
+            ]);
+        }
+        let next = position + EXPORT_BATCH_SIZE;
+        Ok(ExportChunk { total, next: (next < total).then_some(next) })
+    }
+
+    /// Splits list parameters into the page and the repository filters.
+    pub fn user_list_query(query: UserQuery) -> Result<(Pagination, UserListQuery), ServiceError> {
+        let UserQuery {
+            current,
+            page_size,
+            username,
+            status,
+            real_name,
+            email,
+            tags,
+            profile,
+            sort_by,
+            sort_order,
+        } = query;
+        let pagination = Pagination::from_query(PaginationQuery { current, page_size });
+        let status = parse_optional_i16_filter(status.as_deref(), "user status", None)?;
+        let sort =
+            Sort::resolve(sort_by.as_deref(), sort_order.as_deref(), UserRepository::SORT_COLUMNS)?;
+        let tags = TagCondition::all_of(tags.as_deref());
+        let profile = parse_profile_filter(profile.as_deref())?;
+        Ok((pagination, UserListQuery { username, status, real_name, email, tags, profile, sort }))
     }
 
     /// Create user
     pub async fn create_user(
-        pool: &SqlitePool,
+        config: &AppConfig,
+        repo: &impl UserRepo,
+        operator_id: Option<UserId>,
         dto: CreateUserRequest,
-    ) -> Result<i64, ServiceError> {
+    ) -> Result<UserId, ServiceError> {
         tracing::debug!("Creating user: {}", dto.username);
-        if UserRepository::username_exists(pool, &dto.username).await? {
+        let mut errors = FieldErrors::new();
+        if let Some(status) = dto.status {
+            errors.check_one_of("status", status, &UserStatus::CODES);
+        }
+        let profile = Self::check_profile(repo, &mut errors, dto.profile).await?;
+        errors.into_result()?;
+        if repo.username_exists(&dto.username).await? {
             return Err(ServiceError::UsernameConflict);
         }
-        if UserRepository::email_exists(pool, &dto.email).await? {
+        if repo.email_exists(&dto.email).await? {
             return Err(ServiceError::EmailConflict);
         }
-        let password_hash = PasswordUtils::hash_password(&dto.password)?;
+        QuotaService::ensure_slot(config, QuotaResource::Users, repo.count_users()).await?;
+        let password_hash = PasswordUtils::hash_password(config, &dto.password)?;
         let create_cmd = CreateUserCommand {
             username: dto.username,
             email: dto.email,
@@ -62,56 +196,100 @@ impl UserService {
             real_name: dto.real_name,
             status: dto.status,
             role_ids: dto.role_ids,
+            profile,
+            operator_id,
         };
 
-        let user_id = UserRepository::create_user(pool, &create_cmd).await?;
+        let user_id = repo.create_user(&create_cmd).await?;
 
         Ok(user_id)
     }
 
     /// Update user
     pub async fn update_user(
-        pool: &SqlitePool,
-        id: i64,
-        current_user_id: i64,
+        config: &AppConfig,
+        repo: &impl UserRepo,
+        id: UserId,
+        current_user_id: UserId,
         request: UpdateUserPayload,
-    ) -> Result<i64, ServiceError> {
+    ) -> Result<UserId, ServiceError> {
         tracing::debug!("Updating user ID: {}", id);
-        Self::ensure_user_is_mutable(pool, id, current_user_id).await?;
-        UserRepository::update_user(pool, id, &request.email, &request.real_name, &request.role_ids)
-            .await
+        let user = Self::ensure_user_is_mutable(repo, id, current_user_id).await?;
+        if user.is_system && !same_role_ids(&user, &request.role_ids)? {
+            return Err(ServiceError::SystemRecordProtected("user".to_string()));
+        }
+        let new_email = request.email.trim();
+        let email_changed = user.email.as_deref() != Some(new_email);
+        let mut errors = FieldErrors::new();
+        if email_changed && !is_email(new_email) {
+            errors.push("email", "must be a valid email address");
+        }
+        let profile = Self::check_profile(repo, &mut errors, request.profile).await?;
+        errors.into_result()?;
+        if email_changed && repo.email_exists(new_email).await? {
+            return Err(ServiceError::EmailConflict);
+        }
+        let event = DomainEvent::UserUpdated {
+            user_id: id.get(),
+            username: user.username,
+            role_ids: request.role_ids.iter().copied().map(RoleId::get).collect(),
+            operator_id: current_user_id.get(),
+        };
+        let id = repo
+            .update_user(
+                id,
+                &request.real_name,
+                profile.as_deref(),
+                &request.role_ids,
+                current_user_id,
+                &event,
+            )
+            .await?;
+        if email_changed {
+            repo.request_email_change(config, id, new_email).await?;
+        }
+        Ok(id)
     }
 
     /// Delete user
     pub async fn delete_user(
-        pool: &SqlitePool,
-        id: i64,
-        current_user_id: i64,
+        repo: &impl UserRepo,
+        id: UserId,
+        current_user_id: UserId,
     ) -> Result<(), ServiceError> {
         tracing::debug!("Deleting user ID: {}", id);
-        Self::ensure_user_is_mutable(pool, id, current_user_id).await?;
-        UserRepository::soft_delete(pool, id).await?;
+        let user = Self::ensure_user_is_mutable(repo, id, current_user_id).await?;
+        if user.is_system {
+            return Err(ServiceError::SystemRecordProtected("user".to_string()));
+        }
+        let event = DomainEvent::UserDeleted {
+            user_id: id.get(),
+            username: user.username,
+            operator_id: current_user_id.get(),
+        };
+        repo.soft_delete(id, &event).await?;
 
         Ok(())
     }
 
     /// Get user status options
-    pub fn get_user_status_options() -> Vec<UserOptionResp> {
+    pub fn get_user_status_options() -> Vec<OptionItem<i16>> {
         vec![
-            UserOptionResp { label: "Normal".to_string(), value: 1 },
-            UserOptionResp { label: "Disabled".to_string(), value: 2 },
-            UserOptionResp { label: "Pending".to_string(), value: 3 },
-            UserOptionResp { label: "Locked".to_string(), value: 4 },
+            OptionItem { label: "Normal".to_string(), value: 1 },
+            OptionItem { label: "Disabled".to_string(), value: 2 },
+            OptionItem { label: "Pending".to_string(), value: 3 },
+            OptionItem { label: "Locked".to_string(), value: 4 },
         ]
     }
 
     /// Get user options for dropdowns
     pub async fn get_user_options(
-        pool: &SqlitePool,
-        query: UserOptionsQuery,
+        repo: &impl UserRepo,
+        query: OptionsQuery,
     ) -> Result<Vec<UserOptionResp>, ServiceError> {
         tracing::debug!("Getting user options with query: {:?}", query);
-        Ok(UserRepository::list_user_options(pool, query.status, query.q.as_deref(), query.limit)
+        Ok(repo
+            .list_user_options(&OptionsFilter::from_query(query)?)
             .await?
             .into_iter()
             .map(|(value, label)| UserOptionResp { label, value })
@@ -119,41 +297,944 @@ impl UserService {
     }
 
     pub async fn update_user_password(
-        pool: &SqlitePool,
-        id: i64,
-        current_user_id: i64,
+        config: &AppConfig,
+        repo: &impl UserRepo,
+        id: UserId,
+        current_user_id: UserId,
         dto: UpdateUserPasswordPayload,
     ) -> Result<bool, ServiceError> {
         tracing::debug!("Updating user password for user ID: {}", id);
-        Self::ensure_user_is_mutable(pool, id, current_user_id).await?;
-        let password_hash = PasswordUtils::hash_password(&dto.password)?;
-        UserRepository::update_user_password(pool, id, &password_hash).await
+        Self::ensure_user_is_mutable(repo, id, current_user_id).await?;
+        let password_hash = PasswordUtils::hash_password(config, &dto.password)?;
+        repo.update_user_password(id, &password_hash).await
     }
 
     pub async fn update_user_status(
-        pool: &SqlitePool,
-        id: i64,
-        current_user_id: i64,
+        repo: &impl UserRepo,
+        id: UserId,
+        current_user_id: UserId,
         dto: UpdateUserStatusPayload,
     ) -> Result<bool, ServiceError> {
         tracing::debug!("Updating user status for user ID: {}", id);
-        Self::ensure_user_is_mutable(pool, id, current_user_id).await?;
-        UserRepository::update_user_status(pool, id, dto.status).await
+        let mut errors = FieldErrors::new();
+        errors.check_one_of("status", dto.status, &UserStatus::CODES);
+        errors.into_result()?;
+        let user = Self::ensure_user_is_mutable(repo, id, current_user_id).await?;
+        if user.is_system && dto.status != USER_STATUS_NORMAL {
+            return Err(ServiceError::SystemRecordProtected("user".to_string()));
+        }
+        repo.update_user_status(id, dto.status).await
     }
 
-    async fn ensure_user_is_mutable(
-        pool: &SqlitePool,
-        id: i64,
-        current_user_id: i64,
+    /// Role assignment history of a user, including soft-deleted accounts.
+    pub async fn list_role_history(
+        repo: &impl UserRepo,
+        id: UserId,
+        query: RoleHistoryQuery,
+    ) -> Result<(Vec<RoleHistoryResp>, i64), ServiceError> {
+        tracing::debug!("Listing role history for user ID: {}", id);
+        let pagination = Pagination::from_query(PaginationQuery {
+            current: query.current,
+            page_size: query.page_size,
+        });
+        let (rows, total) = repo
+            .list_role_history(id, i64::from(pagination.offset), i64::from(pagination.limit))
+            .await?;
+        Ok((rows.into_iter().map(RoleHistoryResp::from).collect(), total))
+    }
+
+    /// Roles a user holds now and when temporary ones end.
+    pub async fn list_role_assignments(
+        repo: &impl UserRepo,
+        id: UserId,
+    ) -> Result<Vec<RoleAssignmentResp>, ServiceError> {
+        if repo.find_user_by_id(id).await?.is_none() {
+            return Err(ServiceError::NotFound(format!("User id: {}", id)));
+        }
+        repo.list_role_assignments(id).await
+    }
+
+    /// Makes a role assignment temporary, or permanent again with `expiresAt: null`.
+    ///
+    /// System users keep their roles for good, so they cannot be given an end.
+    pub async fn update_role_expiry(
+        repo: &impl UserRepo,
+        id: UserId,
+        role_id: RoleId,
+        current_user_id: UserId,
+        payload: UpdateRoleExpiryPayload,
     ) -> Result<(), ServiceError> {
-        let user = UserRepository::find_user_by_id(pool, id)
+        tracing::debug!("Setting expiry of role {} for user ID: {}", role_id, id);
+        let mut errors = FieldErrors::new();
+        if payload.expires_at.is_some_and(|at| at <= Utc::now()) {
+            errors.push("expiresAt", "must be in the future");
+        }
+        errors.into_result()?;
+        let user = Self::ensure_user_is_mutable(repo, id, current_user_id).await?;
+        if user.is_system && payload.expires_at.is_some() {
+            return Err(ServiceError::SystemRecordProtected("user".to_string()));
+        }
+        if !repo.set_role_expiry(id, role_id, payload.expires_at).await? {
+            return Err(ServiceError::NotFound(format!("Role {} of user id: {}", role_id, id)));
+        }
+        Ok(())
+    }
+
+    /// Removes expired role assignments, drops the affected users' cached capabilities and
+    /// tells each user which role they lost. Returns how many assignments were removed.
+    pub async fn expire_roles(pool: &SqlitePool) -> Result<usize, ServiceError> {
+        let expired = UserRepository::remove_expired_roles(pool, Utc::now()).await?;
+        for row in &expired {
+            PermissionService::clear_user_cache(row.user_id.get());
+            let role = row.role_name.clone().unwrap_or_else(|| format!("#{}", row.role_id));
+            let body = format!(
+                "Your temporary assignment to the {} role has expired and its access was removed.",
+                role
+            );
+            let data = serde_json::json!({ "roleId": row.role_id });
+            AccountRepository::insert_notifications(
+                pool,
+                &[row.user_id.get()],
+                ROLE_EXPIRED_NOTIFICATION,
+                &format!("Role {} expired", role),
+                &body,
+                Some(&data),
+            )
+            .await?;
+        }
+        Ok(expired.len())
+    }
+
+    /// A user's logins, other operations and role changes as one timeline, newest first.
+    pub async fn list_activity(
+        repo: &impl UserRepo,
+        id: UserId,
+        query: ActivityQuery,
+        mask: FieldMask,
+    ) -> Result<(Vec<ActivityItemResp>, i64), ServiceError> {
+        let ActivityQuery { current, page_size, kind, from, to } = query;
+        let mut errors = FieldErrors::new();
+        let kind = match kind.as_deref().map(str::trim).filter(|kind| !kind.is_empty()) {
+            None => None,
+            Some(raw) => {
+                let kind = ActivityKind::parse(raw);
+                if kind.is_none() {
+                    errors.push("kind", "must be one of login, operation, role_change");
+                }
+                kind
+            }
+        };
+        if let (Some(from), Some(to)) = (from, to)
+            && from > to
+        {
+            errors.push("to", "must not be before from");
+        }
+        errors.into_result()?;
+
+        let pagination = Pagination::from_query(PaginationQuery { current, page_size });
+        let list_query = ActivityListQuery {
+            kind,
+            from: from.and_then(|day| day.and_hms_opt(0, 0, 0)),
+            to: to.and_then(|day| day.succ_opt()).and_then(|day| day.and_hms_opt(0, 0, 0)),
+        };
+        let (rows, total) = repo
+            .list_activity(
+                id,
+                &list_query,
+                i64::from(pagination.offset),
+                i64::from(pagination.limit),
+            )
+            .await?;
+        Ok((rows.into_iter().map(|row| ActivityItemResp::from(row).masked(mask)).collect(), total))
+    }
+
+    /// Everything stored about a user, soft-deleted or not, for a subject access request.
+    pub async fn export_user_data(
+        repo: &impl UserRepo,
+        id: UserId,
+    ) -> Result<UserDataExportResp, ServiceError> {
+        tracing::debug!("Exporting personal data of user ID: {}", id);
+        let profile = repo
+            .find_profile(id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound(format!("User id: {}", id)))?;
+        let PersonalDataRows { roles, role_history, email_changes, operation_logs } =
+            repo.list_personal_data(id).await?;
+        Ok(UserDataExportResp {
+            exported_at: Utc::now(),
+            profile,
+            roles,
+            role_history: role_history.into_iter().map(RoleHistoryResp::from).collect(),
+            email_changes,
+            operation_logs,
+        })
+    }
+
+    /// A user's merged capabilities with the roles granting each, and the menu tree they
+    /// would see, read from the database rather than the session cache.
+    pub async fn effective_access(
+        repo: &impl UserRepo,
+        id: UserId,
+    ) -> Result<EffectiveAccessResp, ServiceError> {
+        let user = repo
+            .find_user_by_id(id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound(format!("User id: {}", id)))?;
+        let EffectiveAccessRows { permissions: sources, menus } =
+            repo.list_effective_access(id, i18n::current_locale().tag()).await?;
+
+        let mut permissions: Vec<EffectivePermission> = Vec::new();
+        for source in sources {
+            match permissions.last_mut() {
+                Some(last) if last.code == source.menu_code => last.roles.push(source.role_code),
+                _ => permissions.push(EffectivePermission {
+                    code: source.menu_code,
+                    roles: vec![source.role_code],
+                }),
+            }
+        }
+        Ok(EffectiveAccessResp {
+            user_id: user.id,
+            username: user.username,
+            active: user.status == USER_STATUS_NORMAL,
+            is_super: permissions.iter().any(|p| p.code == SYSTEM_WILDCARD),
+            permissions,
+            menus: menu_tree(menus),
+        })
+    }
+
+    /// Scrub a user's personal data for an erasure request, keeping its id and records.
+    ///
+    /// Soft-deleted users can be anonymized too. System users and the caller's own
+    /// account are refused, and the change cannot be undone.
+    pub async fn anonymize_user(
+        config: &AppConfig,
+        repo: &impl UserRepo,
+        id: UserId,
+        current_user_id: UserId,
+    ) -> Result<(), ServiceError> {
+        tracing::debug!("Anonymizing user ID: {}", id);
+        let profile = repo
+            .find_profile(id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound(format!("User id: {}", id)))?;
+        if profile.is_system {
+            return Err(ServiceError::SystemRecordProtected("user".to_string()));
+        }
+        if id == current_user_id {
+            return Err(ServiceError::InvalidOperation(
+                "Cannot anonymize your own account".to_string(),
+            ));
+        }
+        if profile.anonymized_at.is_some() {
+            return Err(ServiceError::InvalidOperation(format!(
+                "User {} is already anonymized",
+                id
+            )));
+        }
+        let username = anonymized_username(&profile.username);
+        if !repo.anonymize(id, &username, current_user_id).await? {
+            return Err(ServiceError::InvalidOperation(format!(
+                "User {} is already anonymized",
+                id
+            )));
+        }
+        if let Some(avatar_url) = profile.avatar_url.as_deref() {
+            remove_avatar(config, avatar_url).await;
+        }
+        Ok(())
+    }
+
+    /// Create an owner account from the operator CLI.
+    pub async fn create_owner_user(
+        config: &AppConfig,
+        repo: &impl UserRepo,
+        username: String,
+        email: String,
+        password: String,
+    ) -> Result<UserId, ServiceError> {
+        if password.is_empty() {
+            return Err(ServiceError::InvalidOperation("Password cannot be empty".to_string()));
+        }
+        let owner_role_id = repo
+            .find_role_id_by_code(OWNER_ROLE_CODE)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound("Owner role".to_string()))?;
+
+        Self::create_user(
+            config,
+            repo,
+            None,
+            CreateUserRequest {
+                username,
+                email,
+                password,
+                real_name: None,
+                status: Some(USER_STATUS_NORMAL),
+                role_ids: vec![owner_role_id],
+                profile: None,
+            },
+        )
+        .await
+    }
+
+    /// Reset a password from the operator CLI and re-enable the account.
+    ///
+    /// This skips the system-user guard on purpose: it is the recovery path when nobody can log in.
+    pub async fn reset_password_by_username(
+        config: &AppConfig,
+        repo: &impl UserRepo,
+        username: &str,
+        password: &str,
+    ) -> Result<UserId, ServiceError> {
+        if password.is_empty() {
+            return Err(ServiceError::InvalidOperation("Password cannot be empty".to_string()));
+        }
+        let id = repo
+            .find_user_id_by_username(username)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound(format!("User {}", username)))?;
+        let password_hash = PasswordUtils::hash_password(config, password)?;
+        repo.reset_password(id, &password_hash, USER_STATUS_NORMAL).await?;
+        Ok(id)
+    }
+
+    /// Loads the target user, rejecting system users unless the caller holds the wildcard.
+    ///
+    /// Even wildcard holders cannot delete, disable, or re-role a system user; callers
+    /// check those critical changes against the returned row.
+    async fn ensure_user_is_mutable(
+        repo: &impl UserRepo,
+        id: UserId,
+        current_user_id: UserId,
+    ) -> Result<UserWithRolesRow, ServiceError> {
+        let user = repo
+            .find_user_by_id(id)
             .await?
             .ok_or_else(|| ServiceError::NotFound(format!("User id: {}", id)))?;
         if user.is_system
-            && !PermissionService::has_permission(current_user_id, SYSTEM_WILDCARD).await?
+            && !PermissionService::has_permission(current_user_id.get(), SYSTEM_WILDCARD).await?
         {
             return Err(ServiceError::UserIsAdmin);
         }
-        Ok(())
+        Ok(user)
+    }
+
+    /// Checks a submitted profile and returns the JSON to store; `None` when none was sent.
+    async fn check_profile(
+        repo: &impl UserRepo,
+        errors: &mut FieldErrors,
+        profile: Option<Map<String, Value>>,
+    ) -> Result<Option<String>, ServiceError> {
+        let Some(profile) = profile else {
+            return Ok(None);
+        };
+        let fields = repo.list_profile_fields().await?;
+        let stored = check_profile(errors, &fields, profile);
+        Ok(Some(Value::Object(stored).to_string()))
+    }
+}
+
+/// Replacement username for an anonymized account.
+///
+/// The hash is salted with a random value, so the result cannot be matched back to
+/// the original name by hashing guesses.
+fn anonymized_username(username: &str) -> String {
+    let digest = Sha256::new()
+        .chain_update(Uuid::new_v4().as_bytes())
+        .chain_update(username.as_bytes())
+        .finalize();
+    let hex: String = digest.iter().take(8).map(|byte| format!("{:02x}", byte)).collect();
+    format!("anon_{}", hex)
+}
+
+/// Whether `role_ids` is the same set of roles the user currently holds.
+fn same_role_ids(user: &UserWithRolesRow, role_ids: &[RoleId]) -> Result<bool, ServiceError> {
+    let current = serde_json::from_value::<Vec<OptionItem<RoleId>>>(user.roles.clone())
+        .map_err(|e| ServiceError::InvalidOperation(format!("Invalid user role data: {}", e)))?;
+    let mut current: Vec<RoleId> = current.into_iter().map(|role| role.value).collect();
+    let mut requested = role_ids.to_vec();
+    current.sort_unstable();
+    current.dedup();
+    requested.sort_unstable();
+    requested.dedup();
+    Ok(current == requested)
+}
+
+/// Nests `rows`, kept in their order, under their parents. Rows whose parent is not among
+/// them become roots.
+fn menu_tree(rows: Vec<AccessMenuRow>) -> Vec<AccessMenuNode> {
+    let ids: HashSet<MenuId> = rows.iter().map(|row| row.id).collect();
+    let mut children: HashMap<MenuId, Vec<AccessMenuRow>> = HashMap::new();
+    let mut roots = Vec::new();
+    for row in rows {
+        if ids.contains(&row.parent_id) && row.parent_id != row.id {
+            children.entry(row.parent_id).or_default().push(row);
+        } else {
+            roots.push(row);
+        }
+    }
+    fn build(
+        row: AccessMenuRow,
+        children: &mut HashMap<MenuId, Vec<AccessMenuRow>>,
+    ) -> AccessMenuNode {
+        let nested = children.remove(&row.id).unwrap_or_default();
+        AccessMenuNode {
+            code: row.code,
+            name: row.name,
+            menu_type: row.menu_type,
+            visible: row.visible,
+            children: nested.into_iter().map(|child| build(child, children)).collect(),
+        }
+    }
+    roots.into_iter().map(|row| build(row, &mut children)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{UserService, menu_tree};
+    use crate::{
+        common::{
+            error::ServiceError,
+            ids::{MenuId, RoleId, UserId},
+            query::OptionsFilter,
+        },
+        features::system::profile_field::types::{ProfileField, ProfileFieldType},
+        features::system::user::{
+            repo::UserRepo,
+            types::{
+                AccessMenuNode, AccessMenuRow, ActivityListQuery, ActivityRow, CreateUserCommand,
+                CreateUserRequest, EffectiveAccessRows, PersonalDataRows, RoleAssignmentResp,
+                RoleHistoryRow, UpdateRoleExpiryPayload, UpdateUserPayload,
+                UpdateUserStatusPayload, UserListQuery, UserProfileRow, UserWithRolesRow,
+            },
+        },
+        infra::{
+            config::{AppConfig, test_config},
+            permission::PermissionService,
+        },
+    };
+    use async_trait::async_trait;
+    use chrono::{DateTime, Duration, Utc};
+    use rustzen_core::{capability::SYSTEM_WILDCARD, events::DomainEvent};
+    use std::sync::Mutex;
+
+    /// A `set_role_expiry` call the fake accepted.
+    type RoleExpiry = (UserId, RoleId, Option<DateTime<Utc>>);
+
+    /// In-memory store covering the calls the service makes in these tests.
+    #[derive(Default)]
+    struct FakeUserRepo {
+        users: Mutex<Vec<UserWithRolesRow>>,
+        anonymized: Mutex<Vec<UserId>>,
+        events: Mutex<Vec<DomainEvent>>,
+        email_changes: Mutex<Vec<(UserId, String)>>,
+        role_expiries: Mutex<Vec<RoleExpiry>>,
+        profile_fields: Vec<ProfileField>,
+    }
+
+    fn user_row(id: i64, username: &str, is_system: bool, role_ids: &[i64]) -> UserWithRolesRow {
+        let now = chrono::Utc::now();
+        let roles = role_ids
+            .iter()
+            .map(|id| serde_json::json!({ "label": format!("role-{}", id), "value": id }))
+            .collect();
+        UserWithRolesRow {
+            id: UserId(id),
+            username: username.to_string(),
+            email: Some(format!("{}@example.com", username)),
+            phone: None,
+            password_hash: "hash".to_string(),
+            real_name: None,
+            avatar_url: None,
+            is_system,
+            status: 1,
+            last_login_at: None,
+            created_at: now,
+            updated_at: now,
+            profile: serde_json::json!({}),
+            roles: serde_json::Value::Array(roles),
+        }
+    }
+
+    impl FakeUserRepo {
+        fn with_user(self, id: i64, username: &str, is_system: bool, role_ids: &[i64]) -> Self {
+            self.users.lock().unwrap().push(user_row(id, username, is_system, role_ids));
+            self
+        }
+
+        fn event_names(&self) -> Vec<&'static str> {
+            self.events.lock().unwrap().iter().map(DomainEvent::name).collect()
+        }
+    }
+
+    #[async_trait]
+    impl UserRepo for FakeUserRepo {
+        async fn list_users(
+            &self,
+            _offset: i64,
+            _limit: i64,
+            _query: UserListQuery,
+        ) -> Result<(Vec<UserWithRolesRow>, i64), ServiceError> {
+            let users = self.users.lock().unwrap().clone();
+            let total = users.len() as i64;
+            Ok((users, total))
+        }
+
+        async fn list_user_options(
+            &self,
+            _filter: &OptionsFilter<UserId>,
+        ) -> Result<Vec<(UserId, String)>, ServiceError> {
+            Ok(self.users.lock().unwrap().iter().map(|u| (u.id, u.username.clone())).collect())
+        }
+
+        async fn find_user_by_id(
+            &self,
+            id: UserId,
+        ) -> Result<Option<UserWithRolesRow>, ServiceError> {
+            Ok(self.users.lock().unwrap().iter().find(|u| u.id == id).cloned())
+        }
+
+        async fn find_user_id_by_username(
+            &self,
+            username: &str,
+        ) -> Result<Option<UserId>, ServiceError> {
+            Ok(self.users.lock().unwrap().iter().find(|u| u.username == username).map(|u| u.id))
+        }
+
+        async fn find_role_id_by_code(&self, _code: &str) -> Result<Option<RoleId>, ServiceError> {
+            Ok(Some(RoleId(1)))
+        }
+
+        async fn username_exists(&self, username: &str) -> Result<bool, ServiceError> {
+            Ok(self.users.lock().unwrap().iter().any(|u| u.username == username))
+        }
+
+        async fn email_exists(&self, email: &str) -> Result<bool, ServiceError> {
+            Ok(self.users.lock().unwrap().iter().any(|u| u.email.as_deref() == Some(email)))
+        }
+
+        async fn count_users(&self) -> Result<i64, ServiceError> {
+            Ok(self.users.lock().unwrap().len() as i64)
+        }
+
+        async fn create_user(&self, cmd: &CreateUserCommand) -> Result<UserId, ServiceError> {
+            let mut users = self.users.lock().unwrap();
+            let id = 100 + users.len() as i64;
+            let role_ids: Vec<i64> = cmd.role_ids.iter().map(|id| id.get()).collect();
+            let mut user = user_row(id, &cmd.username, false, &role_ids);
+            if let Some(profile) = &cmd.profile {
+                user.profile = serde_json::from_str(profile).unwrap();
+            }
+            users.push(user);
+            self.events.lock().unwrap().push(cmd.created_event(UserId(id)));
+            Ok(UserId(id))
+        }
+
+        async fn update_user(
+            &self,
+            id: UserId,
+            real_name: &str,
+            profile: Option<&str>,
+            _role_ids: &[RoleId],
+            _operator_id: UserId,
+            event: &DomainEvent,
+        ) -> Result<UserId, ServiceError> {
+            let mut users = self.users.lock().unwrap();
+            let user =
+                users.iter_mut().find(|u| u.id == id).ok_or(ServiceError::DatabaseQueryFailed)?;
+            user.real_name = Some(real_name.to_string());
+            if let Some(profile) = profile {
+                user.profile = serde_json::from_str(profile).unwrap();
+            }
+            self.events.lock().unwrap().push(event.clone());
+            Ok(id)
+        }
+
+        async fn list_profile_fields(&self) -> Result<Vec<ProfileField>, ServiceError> {
+            Ok(self.profile_fields.clone())
+        }
+
+        async fn request_email_change(
+            &self,
+            _config: &AppConfig,
+            id: UserId,
+            new_email: &str,
+        ) -> Result<(), ServiceError> {
+            self.email_changes.lock().unwrap().push((id, new_email.to_string()));
+            Ok(())
+        }
+
+        async fn soft_delete(&self, id: UserId, event: &DomainEvent) -> Result<bool, ServiceError> {
+            let mut users = self.users.lock().unwrap();
+            let before = users.len();
+            users.retain(|u| u.id != id);
+            if users.len() == before {
+                return Ok(false);
+            }
+            self.events.lock().unwrap().push(event.clone());
+            Ok(true)
+        }
+
+        async fn update_user_password(
+            &self,
+            id: UserId,
+            password_hash: &str,
+        ) -> Result<bool, ServiceError> {
+            let mut users = self.users.lock().unwrap();
+            let user = users.iter_mut().find(|u| u.id == id);
+            Ok(user.map(|u| u.password_hash = password_hash.to_string()).is_some())
+        }
+
+        async fn update_user_status(&self, id: UserId, status: i16) -> Result<bool, ServiceError> {
+            let mut users = self.users.lock().unwrap();
+            Ok(users.iter_mut().find(|u| u.id == id).map(|u| u.status = status).is_some())
+        }
+
+        async fn reset_password(
+            &self,
+            id: UserId,
+            password_hash: &str,
+            status: i16,
+        ) -> Result<(), ServiceError> {
+            self.update_user_password(id, password_hash).await?;
+            self.update_user_status(id, status).await?;
+            Ok(())
+        }
+
+        async fn list_role_history(
+            &self,
+            _user_id: UserId,
+            _offset: i64,
+            _limit: i64,
+        ) -> Result<(Vec<RoleHistoryRow>, i64), ServiceError> {
+            Ok((Vec::new(), 0))
+        }
+
+        async fn list_role_assignments(
+            &self,
+            _user_id: UserId,
+        ) -> Result<Vec<RoleAssignmentResp>, ServiceError> {
+            Ok(Vec::new())
+        }
+
+        async fn set_role_expiry(
+            &self,
+            user_id: UserId,
+            role_id: RoleId,
+            expires_at: Option<DateTime<Utc>>,
+        ) -> Result<bool, ServiceError> {
+            let users = self.users.lock().unwrap();
+            let holds = users.iter().any(|u| {
+                u.id == user_id
+                    && u.roles.as_array().is_some_and(|roles| {
+                        roles.iter().any(|role| role["value"] == serde_json::json!(role_id.get()))
+                    })
+            });
+            if holds {
+                self.role_expiries.lock().unwrap().push((user_id, role_id, expires_at));
+            }
+            Ok(holds)
+        }
+
+        async fn list_activity(
+            &self,
+            _user_id: UserId,
+            _query: &ActivityListQuery,
+            _offset: i64,
+            _limit: i64,
+        ) -> Result<(Vec<ActivityRow>, i64), ServiceError> {
+            Ok((Vec::new(), 0))
+        }
+
+        async fn find_profile(&self, id: UserId) -> Result<Option<UserProfileRow>, ServiceError> {
+            let anonymized_at =
+                self.anonymized.lock().unwrap().contains(&id).then(chrono::Utc::now);
+            Ok(self.users.lock().unwrap().iter().find(|u| u.id == id).map(|u| UserProfileRow {
+                id: u.id,
+                username: u.username.clone(),
+                email: u.email.clone(),
+                phone: u.phone.clone(),
+                real_name: u.real_name.clone(),
+                avatar_url: u.avatar_url.clone(),
+                status: u.status,
+                is_system: u.is_system,
+                last_login_at: u.last_login_at,
+                created_at: u.created_at,
+                updated_at: u.updated_at,
+                deleted_at: None,
+                anonymized_at,
+                profile: u.profile.clone(),
+            }))
+        }
+
+        async fn list_personal_data(&self, _id: UserId) -> Result<PersonalDataRows, ServiceError> {
+            Ok(PersonalDataRows::default())
+        }
+
+        async fn list_effective_access(
+            &self,
+            _id: UserId,
+            _locale: &str,
+        ) -> Result<EffectiveAccessRows, ServiceError> {
+            Ok(EffectiveAccessRows::default())
+        }
+
+        async fn anonymize(
+            &self,
+            id: UserId,
+            username: &str,
+            _operator_id: UserId,
+        ) -> Result<bool, ServiceError> {
+            let mut users = self.users.lock().unwrap();
+            let Some(user) = users.iter_mut().find(|u| u.id == id && !u.is_system) else {
+                return Ok(false);
+            };
+            user.username = username.to_string();
+            user.email = None;
+            user.phone = None;
+            user.status = 2;
+            self.anonymized.lock().unwrap().push(id);
+            Ok(true)
+        }
+    }
+
+    fn create_request(username: &str) -> CreateUserRequest {
+        CreateUserRequest {
+            username: username.to_string(),
+            email: format!("{}@example.com", username),
+            password: "secret".to_string(),
+            real_name: None,
+            status: None,
+            role_ids: vec![RoleId(2)],
+            profile: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn create_user_rejects_taken_username_and_publishes_on_success() {
+        let repo = FakeUserRepo::default().with_user(1, "alice", false, &[]);
+
+        let err = UserService::create_user(test_config(), &repo, None, create_request("alice"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServiceError::UsernameConflict));
+        assert!(repo.event_names().is_empty());
+
+        let id =
+            UserService::create_user(test_config(), &repo, Some(UserId(1)), create_request("bob"))
+                .await
+                .unwrap();
+        assert!(repo.users.lock().unwrap().iter().any(|u| u.id == id && u.username == "bob"));
+        assert_eq!(repo.event_names(), vec!["user.created"]);
+    }
+
+    #[tokio::test]
+    async fn create_user_rejects_status_outside_user_status() {
+        let repo = FakeUserRepo::default();
+        let request = CreateUserRequest { status: Some(7), ..create_request("carol") };
+
+        let err = UserService::create_user(test_config(), &repo, None, request).await.unwrap_err();
+        let ServiceError::InvalidFields(fields) = err else { panic!("{:?}", err) };
+        assert_eq!(fields[0].field, "status");
+        assert_eq!(fields[0].message, "must be one of 1, 2, 3, 4");
+        assert!(repo.users.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn create_user_checks_a_sent_profile_against_the_fields() {
+        let repo = FakeUserRepo {
+            profile_fields: vec![ProfileField {
+                key: "employee_id".to_string(),
+                field_type: ProfileFieldType::Text,
+                required: true,
+                options: Vec::new(),
+            }],
+            ..FakeUserRepo::default()
+        };
+        let with_profile = |username: &str, profile: serde_json::Value| CreateUserRequest {
+            profile: profile.as_object().cloned(),
+            ..create_request(username)
+        };
+
+        let err = UserService::create_user(
+            test_config(),
+            &repo,
+            None,
+            with_profile("dave", serde_json::json!({})),
+        )
+        .await
+        .unwrap_err();
+        let ServiceError::InvalidFields(fields) = err else { panic!("{:?}", err) };
+        assert_eq!(fields[0].field, "profile.employee_id");
+
+        let profile = serde_json::json!({ "employee_id": " E-7 " });
+        let id =
+            UserService::create_user(test_config(), &repo, None, with_profile("dave", profile))
+                .await
+                .unwrap();
+        let user = repo.find_user_by_id(id).await.unwrap().unwrap();
+        assert_eq!(user.profile, serde_json::json!({ "employee_id": "E-7" }));
+        // Accounts created without a profile, e.g. from the CLI, start empty.
+        UserService::create_user(test_config(), &repo, None, create_request("erin")).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn system_users_need_the_wildcard_and_keep_critical_fields() {
+        let (plain_admin, wildcard_admin) = (UserId(9_340_001), UserId(9_340_002));
+        PermissionService::cache_user_permissions(plain_admin.get(), &[]);
+        PermissionService::cache_user_permissions(
+            wildcard_admin.get(),
+            &[SYSTEM_WILDCARD.to_string()],
+        );
+        let repo = FakeUserRepo::default().with_user(1, "root", true, &[1]);
+        let root = UserId(1);
+
+        let err = UserService::delete_user(&repo, root, plain_admin).await.unwrap_err();
+        assert!(matches!(err, ServiceError::UserIsAdmin));
+
+        let err = UserService::delete_user(&repo, root, wildcard_admin).await.unwrap_err();
+        assert!(matches!(err, ServiceError::SystemRecordProtected(_)));
+        let err = UserService::update_user_status(
+            &repo,
+            root,
+            wildcard_admin,
+            UpdateUserStatusPayload { status: 2 },
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, ServiceError::SystemRecordProtected(_)));
+
+        let payload = |role_ids: Vec<RoleId>| UpdateUserPayload {
+            email: "root@example.org".to_string(),
+            real_name: String::new(),
+            role_ids,
+            profile: None,
+        };
+        let err = UserService::update_user(
+            test_config(),
+            &repo,
+            root,
+            wildcard_admin,
+            payload(vec![RoleId(2)]),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, ServiceError::SystemRecordProtected(_)));
+        UserService::update_user(
+            test_config(),
+            &repo,
+            root,
+            wildcard_admin,
+            payload(vec![RoleId(1)]),
+        )
+        .await
+        .unwrap();
+        assert_eq!(repo.event_names(), vec!["user.updated"]);
+        let root_row = repo.find_user_by_id(root).await.unwrap().unwrap();
+        assert_eq!(root_row.email.as_deref(), Some("root@example.com"));
+        assert_eq!(*repo.email_changes.lock().unwrap(), [(root, "root@example.org".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn role_expiry_must_be_future_held_and_off_system_users() {
+        let wildcard_admin = UserId(9_340_003);
+        PermissionService::cache_user_permissions(
+            wildcard_admin.get(),
+            &[SYSTEM_WILDCARD.to_string()],
+        );
+        let repo = FakeUserRepo::default().with_user(1, "root", true, &[1]).with_user(
+            2,
+            "contractor",
+            false,
+            &[3],
+        );
+        let in_30_days = Utc::now() + Duration::days(30);
+        let expiry = |expires_at| UpdateRoleExpiryPayload { expires_at };
+        let set = |id, role_id, payload| {
+            UserService::update_role_expiry(
+                &repo,
+                UserId(id),
+                RoleId(role_id),
+                wildcard_admin,
+                payload,
+            )
+        };
+
+        let err = set(2, 3, expiry(Some(Utc::now() - Duration::minutes(1)))).await.unwrap_err();
+        assert!(matches!(err, ServiceError::InvalidFields(_)));
+        let err = set(1, 1, expiry(Some(in_30_days))).await.unwrap_err();
+        assert!(matches!(err, ServiceError::SystemRecordProtected(_)));
+        let err = set(2, 4, expiry(Some(in_30_days))).await.unwrap_err();
+        assert!(matches!(err, ServiceError::NotFound(_)));
+
+        set(2, 3, expiry(Some(in_30_days))).await.unwrap();
+        set(2, 3, expiry(None)).await.unwrap();
+        set(1, 1, expiry(None)).await.unwrap();
+        assert_eq!(
+            *repo.role_expiries.lock().unwrap(),
+            [
+                (UserId(2), RoleId(3), Some(in_30_days)),
+                (UserId(2), RoleId(3), None),
+                (UserId(1), RoleId(1), None)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn anonymize_scrubs_once_and_spares_system_users_and_the_caller() {
+        let repo = FakeUserRepo::default()
+            .with_user(1, "root", true, &[1])
+            .with_user(2, "alice", false, &[2])
+            .with_user(3, "bob", false, &[2]);
+        let (root, alice, bob) = (UserId(1), UserId(2), UserId(3));
+
+        let err = UserService::anonymize_user(test_config(), &repo, root, alice).await.unwrap_err();
+        assert!(matches!(err, ServiceError::SystemRecordProtected(_)));
+        let err =
+            UserService::anonymize_user(test_config(), &repo, alice, alice).await.unwrap_err();
+        assert!(matches!(err, ServiceError::InvalidOperation(_)));
+
+        UserService::anonymize_user(test_config(), &repo, bob, alice).await.unwrap();
+        let profile = repo.find_profile(bob).await.unwrap().unwrap();
+        assert!(profile.username.starts_with("anon_"));
+        assert_eq!(profile.username.len(), "anon_".len() + 16);
+        assert_eq!(profile.email, None);
+
+        let err = UserService::anonymize_user(test_config(), &repo, bob, alice).await.unwrap_err();
+        assert!(matches!(err, ServiceError::InvalidOperation(_)));
+    }
+
+    #[test]
+    fn menu_tree_nests_children_and_lifts_orphans_to_the_top() {
+        let row = |id: i64, parent_id: i64, code: &str| AccessMenuRow {
+            id: MenuId(id),
+            parent_id: MenuId(parent_id),
+            code: code.to_string(),
+            name: code.to_string(),
+            menu_type: 2,
+            visible: true,
+        };
+        let node = |code: &str, children: Vec<AccessMenuNode>| AccessMenuNode {
+            code: code.to_string(),
+            name: code.to_string(),
+            menu_type: 2,
+            visible: true,
+            children,
+        };
+        let rows = vec![
+            row(1, 0, "system"),
+            row(3, 1, "system:role"),
+            row(2, 1, "system:user"),
+            row(4, 9, "manage:log"),
+        ];
+
+        assert_eq!(
+            menu_tree(rows),
+            [
+                node("system", vec![node("system:role", vec![]), node("system:user", vec![])]),
+                node("manage:log", vec![]),
+            ]
+        );
     }
 }
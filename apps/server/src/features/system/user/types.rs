@@ -1,23 +1,35 @@
-use chrono::NaiveDateTime;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use rustzen_core::events::DomainEvent;
 use serde::{Deserialize, Serialize};
 
 use crate::common::api::OptionItem;
-use crate::common::error::ServiceError;
+use crate::common::{
+    error::ServiceError,
+    ids::{MenuId, RoleId, UserId},
+    mask::{FieldMask, MaskFields},
+    pagination::Sort,
+};
+use crate::features::{manage::log::types::LogItemResp, system::tag::types::TagCondition};
 
 /// User with roles row from the database view.
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct UserWithRolesRow {
-    pub id: i64,
+    pub id: UserId,
     pub username: String,
-    pub email: String,
+    /// `None` once the user has been anonymized.
+    pub email: Option<String>,
+    /// E.164 phone number, when one is bound.
+    pub phone: Option<String>,
     pub password_hash: String,
     pub real_name: Option<String>,
     pub avatar_url: Option<String>,
     pub is_system: bool,
     pub status: i16,
-    pub last_login_at: Option<NaiveDateTime>,
-    pub created_at: NaiveDateTime,
-    pub updated_at: NaiveDateTime,
+    pub last_login_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Custom profile values keyed by profile field key.
+    pub profile: serde_json::Value,
     pub roles: serde_json::Value,
 }
 
@@ -33,7 +45,10 @@ pub struct CreateUserRequest {
     pub status: Option<i16>,
     /// A list of role IDs to assign to the user. If empty, will use default role.
     #[serde(default)]
-    pub role_ids: Vec<i64>,
+    pub role_ids: Vec<RoleId>,
+    /// Custom profile values keyed by profile field key.
+    #[serde(default)]
+    pub profile: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
 /// Update user request parameters
@@ -43,7 +58,10 @@ pub struct UpdateUserPayload {
     pub email: String,
     pub real_name: String,
     /// A list of role IDs to assign to the user. If provided, replaces all existing roles.
-    pub role_ids: Vec<i64>,
+    pub role_ids: Vec<RoleId>,
+    /// Replaces the custom profile values when provided.
+    #[serde(default)]
+    pub profile: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -60,20 +78,25 @@ pub struct UpdateUserStatusPayload {
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UserItemResp {
-    pub id: i64,
+    pub id: UserId,
     pub username: String,
-    pub email: String,
+    /// Masked, e.g. `a***@example.com`, unless the caller holds `system:privacy:view`.
+    pub email: Option<String>,
+    /// Masked, e.g. `+861******5678`, unless the caller holds `system:privacy:view`.
+    pub phone: Option<String>,
     pub real_name: Option<String>,
     pub avatar_url: Option<String>,
     pub status: i16,
-    pub last_login_at: Option<NaiveDateTime>,
-    pub roles: Vec<UserOptionResp>,
-    pub created_at: NaiveDateTime,
-    pub updated_at: NaiveDateTime,
+    pub last_login_at: Option<DateTime<Utc>>,
+    pub roles: Vec<OptionItem<RoleId>>,
+    /// Custom profile values keyed by profile field key.
+    pub profile: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
 /// User option
-pub type UserOptionResp = OptionItem<i64>;
+pub type UserOptionResp = OptionItem<UserId>;
 
 /// User list query parameters
 #[derive(Debug, Clone, Deserialize)]
@@ -91,18 +114,15 @@ pub struct UserQuery {
     pub real_name: Option<String>,
     /// Filter by email (case-insensitive search).
     pub email: Option<String>,
-}
-
-/// User options query parameters
-#[derive(Debug, Clone, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct UserOptionsQuery {
-    /// Search keyword
-    pub q: Option<String>,
-    /// Maximum number of results to return
-    pub limit: Option<i64>,
-    /// Filter by user status
-    pub status: Option<i16>,
+    /// Comma-separated tag names the user must all carry, e.g. `contractor,pilot-group`.
+    pub tags: Option<String>,
+    /// Comma-separated `key:value` profile values the user must all have, e.g.
+    /// `region:north,remote:true`.
+    pub profile: Option<String>,
+    /// Sort field (camelCase). Defaults to the list's natural order.
+    pub sort_by: Option<String>,
+    /// Sort direction: "asc" or "desc". Defaults to "desc".
+    pub sort_order: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -111,6 +131,10 @@ pub struct UserListQuery {
     pub status: Option<i16>,
     pub real_name: Option<String>,
     pub email: Option<String>,
+    pub tags: TagCondition,
+    /// Profile key and value pairs compared as text.
+    pub profile: Vec<(String, String)>,
+    pub sort: Option<Sort>,
 }
 
 #[derive(Debug, Clone)]
@@ -120,14 +144,353 @@ pub struct CreateUserCommand {
     pub password_hash: String,
     pub real_name: Option<String>,
     pub status: Option<i16>,
-    pub role_ids: Vec<i64>,
+    pub role_ids: Vec<RoleId>,
+    /// Validated profile JSON; `None` stores an empty profile.
+    pub profile: Option<String>,
+    /// The user making the change; `None` for operator CLI commands.
+    pub operator_id: Option<UserId>,
+}
+
+impl CreateUserCommand {
+    /// The `UserCreated` event for the row this command inserted as `user_id`.
+    pub fn created_event(&self, user_id: UserId) -> DomainEvent {
+        DomainEvent::UserCreated {
+            user_id: user_id.get(),
+            username: self.username.clone(),
+            email: self.email.clone(),
+            operator_id: self.operator_id.map(UserId::get),
+        }
+    }
+}
+
+/// Direction of a user-role history entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoleHistoryAction {
+    Assigned,
+    Removed,
+}
+
+impl RoleHistoryAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Assigned => "assigned",
+            Self::Removed => "removed",
+        }
+    }
+}
+
+/// User-role history row joined with role and operator names.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct RoleHistoryRow {
+    pub id: i64,
+    pub role_id: RoleId,
+    pub role_name: Option<String>,
+    pub role_code: Option<String>,
+    pub action: String,
+    pub operator_id: Option<UserId>,
+    pub operator_username: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// User-role history item for compliance review
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleHistoryResp {
+    pub id: i64,
+    pub role_id: RoleId,
+    pub role_name: Option<String>,
+    pub role_code: Option<String>,
+    pub action: String,
+    pub operator_id: Option<UserId>,
+    pub operator_username: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<RoleHistoryRow> for RoleHistoryResp {
+    fn from(row: RoleHistoryRow) -> Self {
+        Self {
+            id: row.id,
+            role_id: row.role_id,
+            role_name: row.role_name,
+            role_code: row.role_code,
+            action: row.action,
+            operator_id: row.operator_id,
+            operator_username: row.operator_username,
+            created_at: row.created_at,
+        }
+    }
+}
+
+/// A role currently assigned to a user, with when a temporary assignment ends.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleAssignmentResp {
+    pub role_id: RoleId,
+    pub role_name: String,
+    pub role_code: String,
+    pub assigned_at: DateTime<Utc>,
+    /// `None` for a permanent assignment.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Sets or clears the end of a role assignment.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRoleExpiryPayload {
+    /// Must be in the future; `null` makes the assignment permanent.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// An expired assignment removed by the role expiry task.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ExpiredRoleRow {
+    pub user_id: UserId,
+    pub role_id: RoleId,
+    pub role_name: Option<String>,
+}
+
+impl MaskFields for ActivityItemResp {
+    fn masked(self, mask: FieldMask) -> Self {
+        Self { ip_address: self.ip_address.map(|ip| mask.ip(ip)), ..self }
+    }
+}
+
+/// User-role history query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleHistoryQuery {
+    /// The page number to retrieve. Defaults to 1.
+    pub current: Option<i64>,
+    /// The number of items per page. Defaults to 10.
+    pub page_size: Option<i64>,
+}
+
+/// Source of a user activity timeline entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivityKind {
+    /// `AUTH_LOGIN` operation log, successful or not.
+    Login,
+    /// Any other operation log.
+    Operation,
+    /// Role assignment history.
+    RoleChange,
+}
+
+impl ActivityKind {
+    pub const ALL: [ActivityKind; 3] =
+        [ActivityKind::Login, ActivityKind::Operation, ActivityKind::RoleChange];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ActivityKind::Login => "login",
+            ActivityKind::Operation => "operation",
+            ActivityKind::RoleChange => "role_change",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+}
+
+/// Timeline row merged from operation logs and role history.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ActivityRow {
+    pub kind: String,
+    pub source_id: i64,
+    pub action: String,
+    pub description: Option<String>,
+    pub status: Option<String>,
+    pub ip_address: Option<String>,
+    pub role_id: Option<RoleId>,
+    pub role_name: Option<String>,
+    pub operator_username: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// One entry of a user's activity timeline
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityItemResp {
+    /// `login`, `operation` or `role_change`.
+    pub kind: String,
+    /// Id in `operation_logs` or `user_role_history`, depending on `kind`.
+    pub source_id: i64,
+    /// Log action (e.g. `AUTH_LOGIN`), or `assigned` / `removed` for role changes.
+    pub action: String,
+    pub description: Option<String>,
+    /// `SUCCESS` or `FAIL` for logs; `None` for role changes.
+    pub status: Option<String>,
+    /// Masked unless the caller holds `system:privacy:view`.
+    pub ip_address: Option<String>,
+    pub role_id: Option<RoleId>,
+    pub role_name: Option<String>,
+    /// Who changed the role; `None` for logs.
+    pub operator_username: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ActivityRow> for ActivityItemResp {
+    fn from(row: ActivityRow) -> Self {
+        Self {
+            kind: row.kind,
+            source_id: row.source_id,
+            action: row.action,
+            description: row.description,
+            status: row.status,
+            ip_address: row.ip_address,
+            role_id: row.role_id,
+            role_name: row.role_name,
+            operator_username: row.operator_username,
+            created_at: row.created_at,
+        }
+    }
+}
+
+/// User activity query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityQuery {
+    pub current: Option<i64>,
+    pub page_size: Option<i64>,
+    /// `login`, `operation` or `role_change`; all kinds when omitted.
+    pub kind: Option<String>,
+    /// First UTC day to include, `YYYY-MM-DD`.
+    pub from: Option<NaiveDate>,
+    /// Last UTC day to include, `YYYY-MM-DD`.
+    pub to: Option<NaiveDate>,
+}
+
+/// Filters for the activity timeline; bounds are `[from, to)`.
+#[derive(Debug, Clone, Default)]
+pub struct ActivityListQuery {
+    pub kind: Option<ActivityKind>,
+    pub from: Option<NaiveDateTime>,
+    pub to: Option<NaiveDateTime>,
+}
+
+/// Stored profile of a user, soft-deleted or not, without the password hash.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct UserProfileRow {
+    pub id: UserId,
+    pub username: String,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub real_name: Option<String>,
+    pub avatar_url: Option<String>,
+    pub status: i16,
+    pub is_system: bool,
+    pub last_login_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub anonymized_at: Option<DateTime<Utc>>,
+    /// Custom profile values keyed by profile field key.
+    pub profile: serde_json::Value,
+}
+
+/// Records linked to a user beyond the profile, loaded for a personal data export.
+#[derive(Debug, Default)]
+pub struct PersonalDataRows {
+    pub roles: Vec<OptionItem<RoleId>>,
+    pub role_history: Vec<RoleHistoryRow>,
+    pub email_changes: Vec<EmailChangeItemResp>,
+    pub operation_logs: Vec<LogItemResp>,
+}
+
+/// A requested email change, pending or confirmed
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailChangeItemResp {
+    pub new_email: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+}
+
+/// Every piece of personal data held about a user
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserDataExportResp {
+    pub exported_at: DateTime<Utc>,
+    pub profile: UserProfileRow,
+    /// Roles currently assigned.
+    pub roles: Vec<OptionItem<RoleId>>,
+    pub role_history: Vec<RoleHistoryResp>,
+    /// Requested email addresses, including ones never confirmed.
+    pub email_changes: Vec<EmailChangeItemResp>,
+    pub operation_logs: Vec<LogItemResp>,
+}
+
+/// A capability held by a user and the role that grants it; one row per pair.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PermissionSourceRow {
+    pub menu_code: String,
+    pub role_code: String,
+}
+
+/// A menu a user can open, as read for the effective access tree.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AccessMenuRow {
+    pub id: MenuId,
+    pub parent_id: MenuId,
+    pub code: String,
+    pub name: String,
+    pub menu_type: i16,
+    pub visible: bool,
+}
+
+/// Grants and menus behind a user's effective access.
+#[derive(Debug, Default)]
+pub struct EffectiveAccessRows {
+    pub permissions: Vec<PermissionSourceRow>,
+    pub menus: Vec<AccessMenuRow>,
+}
+
+/// A merged capability and the roles that grant it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectivePermission {
+    pub code: String,
+    /// Codes of the enabled roles granting it.
+    pub roles: Vec<String>,
+}
+
+/// One node of the menu tree a user would see.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessMenuNode {
+    pub code: String,
+    pub name: String,
+    /// 1 directory, 2 page, 4 external link, 5 iframe
+    pub menu_type: i16,
+    /// `false` keeps the page routable but out of the sidebar
+    pub visible: bool,
+    pub children: Vec<AccessMenuNode>,
+}
+
+/// What a user can do and see, and which role each capability comes from.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveAccessResp {
+    pub user_id: UserId,
+    pub username: String,
+    /// Only active users hold grants; disabled, locked or deleted ones have none.
+    pub active: bool,
+    /// Holds `*`, so every capability and menu is granted.
+    pub is_super: bool,
+    pub permissions: Vec<EffectivePermission>,
+    /// Enabled directories, pages, links and iframes the user can open. A menu whose parent
+    /// is not granted shows at the top level.
+    pub menus: Vec<AccessMenuNode>,
 }
 
 impl TryFrom<UserWithRolesRow> for UserItemResp {
     type Error = ServiceError;
 
     fn try_from(user: UserWithRolesRow) -> Result<Self, Self::Error> {
-        let roles = serde_json::from_value::<Vec<UserOptionResp>>(user.roles).map_err(|e| {
+        let roles = serde_json::from_value::<Vec<OptionItem<RoleId>>>(user.roles).map_err(|e| {
             ServiceError::InvalidOperation(format!("Invalid user role data: {}", e))
         })?;
 
@@ -135,13 +498,21 @@ impl TryFrom<UserWithRolesRow> for UserItemResp {
             id: user.id,
             username: user.username,
             email: user.email,
+            phone: user.phone,
             real_name: user.real_name,
             avatar_url: user.avatar_url,
             status: user.status,
             last_login_at: user.last_login_at,
             created_at: user.created_at,
             updated_at: user.updated_at,
+            profile: user.profile,
             roles,
         })
     }
 }
+
+impl MaskFields for UserItemResp {
+    fn masked(self, mask: FieldMask) -> Self {
+        Self { email: mask.email(self.email), phone: mask.phone(self.phone), ..self }
+    }
+}
@@ -0,0 +1,85 @@
+use super::{
+    service::WebhookService,
+    types::{
+        CreateWebhookRequest, UpdateWebhookPayload, WebhookDeliveryQuery, WebhookDeliveryResp,
+        WebhookItemResp, WebhookQuery,
+    },
+};
+use crate::{
+    common::{
+        api::{ApiResponse, AppResult, PageMeta},
+        pagination::{Pagination, PaginationQuery},
+    },
+    infra::db::DbExecutor,
+};
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use sqlx::SqlitePool;
+
+/// Get paginated webhook list
+pub async fn list_webhooks(
+    State(db): State<DbExecutor>,
+    Query(query): Query<WebhookQuery>,
+) -> AppResult<Vec<WebhookItemResp>> {
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (webhooks, total) = WebhookService::list_webhooks(db.read(), query).await?;
+    Ok(ApiResponse::page(webhooks, total, PageMeta::new(pagination, total)))
+}
+
+/// Register a webhook
+pub async fn create_webhook(
+    State(pool): State<SqlitePool>,
+    Json(request): Json<CreateWebhookRequest>,
+) -> AppResult<i64> {
+    Ok(ApiResponse::success(WebhookService::create_webhook(&pool, request).await?))
+}
+
+/// Update a webhook
+pub async fn update_webhook(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+    Json(request): Json<UpdateWebhookPayload>,
+) -> AppResult<()> {
+    WebhookService::update_webhook(&pool, id, request).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Delete a webhook
+pub async fn delete_webhook(State(pool): State<SqlitePool>, Path(id): Path<i64>) -> AppResult<()> {
+    WebhookService::delete_webhook(&pool, id).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// List event names a webhook can subscribe to
+pub async fn list_webhook_events() -> AppResult<Vec<&'static str>> {
+    Ok(ApiResponse::success(WebhookService::list_events()))
+}
+
+/// Get one webhook's paginated delivery log
+pub async fn list_webhook_deliveries(
+    State(db): State<DbExecutor>,
+    Path(id): Path<i64>,
+    Query(query): Query<WebhookDeliveryQuery>,
+) -> AppResult<Vec<WebhookDeliveryResp>> {
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (deliveries, total) = WebhookService::list_deliveries(db.read(), id, query).await?;
+    Ok(ApiResponse::page(deliveries, total, PageMeta::new(pagination, total)))
+}
+
+/// Send a finished delivery again
+pub async fn retry_webhook_delivery(
+    State(pool): State<SqlitePool>,
+    Path((id, delivery_id)): Path<(i64, i64)>,
+) -> AppResult<()> {
+    WebhookService::retry_delivery(&pool, id, delivery_id).await?;
+    Ok(ApiResponse::success(()))
+}
@@ -0,0 +1,57 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{
+    Router,
+    routing::{delete, get, post, put},
+};
+use handler::{
+    create_webhook, delete_webhook, list_webhook_deliveries, list_webhook_events, list_webhooks,
+    retry_webhook_delivery, update_webhook,
+};
+use rustzen_core::{
+    capability::system_webhook,
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+pub fn webhook_routes() -> Router<SqlitePool> {
+    Router::new()
+        .route_with_permission(
+            "/",
+            get(list_webhooks),
+            PermissionsCheck::Require(system_webhook::LIST),
+        )
+        .route_with_permission(
+            "/",
+            post(create_webhook),
+            PermissionsCheck::Require(system_webhook::CREATE),
+        )
+        .route_with_permission(
+            "/events",
+            get(list_webhook_events),
+            PermissionsCheck::Require(system_webhook::LIST),
+        )
+        .route_with_permission(
+            "/{id}",
+            put(update_webhook),
+            PermissionsCheck::Require(system_webhook::UPDATE),
+        )
+        .route_with_permission(
+            "/{id}",
+            delete(delete_webhook),
+            PermissionsCheck::Require(system_webhook::DELETE),
+        )
+        .route_with_permission(
+            "/{id}/deliveries",
+            get(list_webhook_deliveries),
+            PermissionsCheck::Require(system_webhook::DELIVERIES),
+        )
+        .route_with_permission(
+            "/{id}/deliveries/{delivery_id}/retry",
+            post(retry_webhook_delivery),
+            PermissionsCheck::Require(system_webhook::UPDATE),
+        )
+}
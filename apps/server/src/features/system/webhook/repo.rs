@@ -0,0 +1,294 @@
+use super::types::{
+    DeliveryListQuery, DeliveryStatus, DueDeliveryRow, WebhookCommand, WebhookDeliveryResp,
+    WebhookListQuery, WebhookRow,
+};
+use crate::common::{
+    error::ServiceError,
+    query::{count_with_filters, fetch_with_filters, push_eq, push_ilike},
+};
+
+use chrono::{NaiveDateTime, Utc};
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+
+pub struct WebhookRepository;
+
+fn db_error(context: &str, e: sqlx::Error) -> ServiceError {
+    tracing::error!("Database error {}: {:?}", context, e);
+    ServiceError::DatabaseQueryFailed
+}
+
+impl WebhookRepository {
+    fn format_query(query: &WebhookListQuery, query_builder: &mut QueryBuilder<Sqlite>) {
+        push_ilike(query_builder, "name", query.name.as_deref());
+        push_eq(query_builder, "status", query.status);
+    }
+
+    fn format_delivery_query(query: &DeliveryListQuery, query_builder: &mut QueryBuilder<Sqlite>) {
+        push_eq(query_builder, "webhook_id", Some(query.webhook_id));
+        push_eq(query_builder, "status", query.status);
+        if let Some(event) = query.event.as_deref().map(str::trim).filter(|e| !e.is_empty()) {
+            query_builder.push(" AND event = ").push_bind(event.to_string());
+        }
+    }
+
+    /// Retrieves webhooks with pagination
+    pub async fn list_webhooks(
+        pool: &SqlitePool,
+        offset: i64,
+        limit: i64,
+        query: WebhookListQuery,
+    ) -> Result<(Vec<WebhookRow>, i64), ServiceError> {
+        let total = count_with_filters(
+            pool,
+            "SELECT COUNT(*) FROM webhooks WHERE deleted_at IS NULL",
+            |query_builder| Self::format_query(&query, query_builder),
+        )
+        .await?;
+        if total == 0 {
+            return Ok((Vec::new(), total));
+        }
+        let webhooks = fetch_with_filters(
+            pool,
+            "SELECT id, name, url, events, status, description, created_at, updated_at FROM webhooks WHERE deleted_at IS NULL",
+            |query_builder| Self::format_query(&query, query_builder),
+            Some("id DESC"),
+            Some(limit),
+            Some(offset),
+        )
+        .await?;
+        Ok((webhooks, total))
+    }
+
+    /// Whether a live webhook other than `exclude_id` already uses `name`.
+    pub async fn name_exists(
+        pool: &SqlitePool,
+        name: &str,
+        exclude_id: Option<i64>,
+    ) -> Result<bool, ServiceError> {
+        sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM webhooks WHERE name = ? AND id != ? AND deleted_at IS NULL)",
+        )
+        .bind(name)
+        .bind(exclude_id.unwrap_or(0))
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_error("checking webhook name", e))
+    }
+
+    pub async fn exists(pool: &SqlitePool, id: i64) -> Result<bool, ServiceError> {
+        sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM webhooks WHERE id = ? AND deleted_at IS NULL)",
+        )
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_error("checking webhook", e))
+    }
+
+    pub async fn create(pool: &SqlitePool, command: &WebhookCommand) -> Result<i64, ServiceError> {
+        let now = Utc::now().naive_utc();
+        let events = serde_json::to_string(&command.events).unwrap_or_else(|_| "[]".to_string());
+        sqlx::query_scalar::<_, i64>(
+            "INSERT INTO webhooks (name, url, secret, events, status, description, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+             RETURNING id",
+        )
+        .bind(&command.name)
+        .bind(&command.url)
+        .bind(command.secret.as_deref().unwrap_or_default())
+        .bind(events)
+        .bind(command.status)
+        .bind(command.description.as_deref())
+        .bind(now)
+        .bind(now)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_error("creating webhook", e))
+    }
+
+    /// Updates a webhook; a `None` secret keeps the stored one.
+    pub async fn update(
+        pool: &SqlitePool,
+        id: i64,
+        command: &WebhookCommand,
+    ) -> Result<bool, ServiceError> {
+        let events = serde_json::to_string(&command.events).unwrap_or_else(|_| "[]".to_string());
+        let result = sqlx::query(
+            "UPDATE webhooks
+             SET name = ?, url = ?, secret = COALESCE(?, secret), events = ?, status = ?,
+                 description = ?, updated_at = ?
+             WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(&command.name)
+        .bind(&command.url)
+        .bind(command.secret.as_deref())
+        .bind(events)
+        .bind(command.status)
+        .bind(command.description.as_deref())
+        .bind(Utc::now().naive_utc())
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| db_error("updating webhook", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn soft_delete(pool: &SqlitePool, id: i64) -> Result<bool, ServiceError> {
+        let now = Utc::now().naive_utc();
+        let result = sqlx::query(
+            "UPDATE webhooks SET deleted_at = ?, updated_at = ? WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(now)
+        .bind(now)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| db_error("deleting webhook", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Enabled webhooks as `(id, events JSON)` for subscription matching.
+    pub async fn list_active_subscriptions(
+        pool: &SqlitePool,
+    ) -> Result<Vec<(i64, String)>, ServiceError> {
+        sqlx::query_as::<_, (i64, String)>(
+            "SELECT id, events FROM webhooks WHERE status = 1 AND deleted_at IS NULL",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("listing webhook subscriptions", e))
+    }
+
+    /// Queues one pending delivery per webhook, due immediately.
+    pub async fn enqueue_deliveries(
+        pool: &SqlitePool,
+        webhook_ids: &[i64],
+        event: &str,
+        payload: &str,
+    ) -> Result<(), ServiceError> {
+        if webhook_ids.is_empty() {
+            return Ok(());
+        }
+        let now = Utc::now().naive_utc();
+        let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "INSERT INTO webhook_deliveries (webhook_id, event, payload, status, next_attempt_at, created_at) ",
+        );
+        query_builder.push_values(webhook_ids, |mut row, webhook_id| {
+            row.push_bind(*webhook_id)
+                .push_bind(event)
+                .push_bind(payload)
+                .push_bind(DeliveryStatus::Pending.as_str())
+                .push_bind(now)
+                .push_bind(now);
+        });
+        query_builder
+            .build()
+            .execute(pool)
+            .await
+            .map_err(|e| db_error("queuing webhook deliveries", e))?;
+        Ok(())
+    }
+
+    /// Retrieves one webhook's delivery log, newest first.
+    pub async fn list_deliveries(
+        pool: &SqlitePool,
+        offset: i64,
+        limit: i64,
+        query: DeliveryListQuery,
+    ) -> Result<(Vec<WebhookDeliveryResp>, i64), ServiceError> {
+        let total = count_with_filters(
+            pool,
+            "SELECT COUNT(*) FROM webhook_deliveries WHERE 1=1",
+            |query_builder| Self::format_delivery_query(&query, query_builder),
+        )
+        .await?;
+        if total == 0 {
+            return Ok((Vec::new(), total));
+        }
+        let deliveries = fetch_with_filters(
+            pool,
+            "SELECT id, webhook_id, event, payload, status, attempts, next_attempt_at, response_status,
+                    last_error, created_at, delivered_at
+             FROM webhook_deliveries WHERE 1=1",
+            |query_builder| Self::format_delivery_query(&query, query_builder),
+            Some("id DESC"),
+            Some(limit),
+            Some(offset),
+        )
+        .await?;
+        Ok((deliveries, total))
+    }
+
+    /// Pending deliveries whose next attempt is due, oldest first.
+    pub async fn list_due_deliveries(
+        pool: &SqlitePool,
+        now: NaiveDateTime,
+        limit: i64,
+    ) -> Result<Vec<DueDeliveryRow>, ServiceError> {
+        sqlx::query_as::<_, DueDeliveryRow>(
+            "SELECT d.id, d.event, d.payload, d.attempts, w.url, w.secret,
+                    (w.status = 1 AND w.deleted_at IS NULL) AS webhook_active
+             FROM webhook_deliveries d
+             JOIN webhooks w ON w.id = d.webhook_id
+             WHERE d.status = 'pending' AND d.next_attempt_at <= ?
+             ORDER BY d.next_attempt_at ASC, d.id ASC
+             LIMIT ?",
+        )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("listing due webhook deliveries", e))
+    }
+
+    /// Records an attempt outcome. `next_attempt_at` is only used while still pending.
+    pub async fn record_attempt(
+        pool: &SqlitePool,
+        id: i64,
+        status: DeliveryStatus,
+        attempts: i64,
+        next_attempt_at: NaiveDateTime,
+        response_status: Option<u16>,
+        last_error: Option<&str>,
+    ) -> Result<(), ServiceError> {
+        let now = Utc::now().naive_utc();
+        let delivered_at = (status == DeliveryStatus::Succeeded).then_some(now);
+        sqlx::query(
+            "UPDATE webhook_deliveries
+             SET status = ?, attempts = ?, next_attempt_at = ?, response_status = ?, last_error = ?,
+                 delivered_at = ?
+             WHERE id = ?",
+        )
+        .bind(status.as_str())
+        .bind(attempts)
+        .bind(next_attempt_at)
+        .bind(response_status.map(i64::from))
+        .bind(last_error)
+        .bind(delivered_at)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| db_error("recording webhook delivery attempt", e))?;
+        Ok(())
+    }
+
+    /// Puts a finished delivery back in the queue with a fresh attempt budget.
+    pub async fn requeue_delivery(
+        pool: &SqlitePool,
+        webhook_id: i64,
+        delivery_id: i64,
+    ) -> Result<bool, ServiceError> {
+        let result = sqlx::query(
+            "UPDATE webhook_deliveries
+             SET status = 'pending', attempts = 0, next_attempt_at = ?, last_error = NULL
+             WHERE id = ? AND webhook_id = ? AND status != 'pending'",
+        )
+        .bind(Utc::now().naive_utc())
+        .bind(delivery_id)
+        .bind(webhook_id)
+        .execute(pool)
+        .await
+        .map_err(|e| db_error("requeuing webhook delivery", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,575 @@
+use super::{
+    repo::WebhookRepository,
+    types::{
+        CreateWebhookRequest, DeliveryListQuery, DeliveryStatus, DueDeliveryRow,
+        UpdateWebhookPayload, WILDCARD_EVENT, WebhookCommand, WebhookDeliveryQuery,
+        WebhookDeliveryResp, WebhookEvent, WebhookItemResp, WebhookListQuery, WebhookQuery,
+    },
+};
+use crate::{
+    common::{
+        error::ServiceError,
+        pagination::{Pagination, PaginationQuery},
+        query::parse_optional_i16_filter,
+    },
+    infra::http_client,
+};
+
+use async_trait::async_trait;
+use chrono::{Duration as ChronoDuration, Utc};
+use hmac::{Hmac, Mac};
+use once_cell::sync::Lazy;
+use rustzen_core::events::{DomainEvent, EventSubscriber};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use sqlx::SqlitePool;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+const WEBHOOK_STATUS_ENABLED: i16 = 1;
+const WEBHOOK_NAME_MAX_LEN: usize = 100;
+const WEBHOOK_SECRET_MIN_LEN: usize = 16;
+/// Attempts per delivery before it is marked failed.
+const MAX_DELIVERY_ATTEMPTS: i64 = 5;
+/// First retry delay; doubles on every further failure.
+const RETRY_BASE_DELAY_SECS: i64 = 30;
+const DELIVERY_BATCH_SIZE: i64 = 20;
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+const WORKER_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Wakes the delivery worker as soon as new deliveries are queued.
+static WORKER_WAKEUP: Lazy<Notify> = Lazy::new(Notify::new);
+
+pub struct WebhookService;
+
+impl WebhookService {
+    pub async fn list_webhooks(
+        pool: &SqlitePool,
+        query: WebhookQuery,
+    ) -> Result<(Vec<WebhookItemResp>, i64), ServiceError> {
+        let WebhookQuery { current, page_size, name, status } = query;
+        let pagination = Pagination::from_query(PaginationQuery { current, page_size });
+        let status = parse_optional_i16_filter(status.as_deref(), "webhook status", None)?;
+        let (rows, total) = WebhookRepository::list_webhooks(
+            pool,
+            i64::from(pagination.offset),
+            i64::from(pagination.limit),
+            WebhookListQuery { name, status },
+        )
+        .await?;
+        let webhooks = rows
+            .into_iter()
+            .map(WebhookItemResp::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| {
+                tracing::error!("Invalid webhook events data: {}", e);
+                ServiceError::DatabaseQueryFailed
+            })?;
+        Ok((webhooks, total))
+    }
+
+    pub async fn create_webhook(
+        pool: &SqlitePool,
+        request: CreateWebhookRequest,
+    ) -> Result<i64, ServiceError> {
+        let command = normalize_webhook(
+            request.name,
+            request.url,
+            Some(request.secret),
+            request.events,
+            request.status,
+            request.description,
+        )?;
+        if command.secret.is_none() {
+            return Err(ServiceError::InvalidOperation("Webhook secret is required".to_string()));
+        }
+        if WebhookRepository::name_exists(pool, &command.name, None).await? {
+            return Err(ServiceError::InvalidOperation(format!(
+                "Webhook name '{}' already exists",
+                command.name
+            )));
+        }
+        tracing::info!("Creating webhook '{}' -> {}", command.name, command.url);
+        WebhookRepository::create(pool, &command).await
+    }
+
+    pub async fn update_webhook(
+        pool: &SqlitePool,
+        id: i64,
+        request: UpdateWebhookPayload,
+    ) -> Result<(), ServiceError> {
+        let command = normalize_webhook(
+            request.name,
+            request.url,
+            request.secret,
+            request.events,
+            request.status,
+            request.description,
+        )?;
+        if WebhookRepository::name_exists(pool, &command.name, Some(id)).await? {
+            return Err(ServiceError::InvalidOperation(format!(
+                "Webhook name '{}' already exists",
+                command.name
+            )));
+        }
+        tracing::info!("Updating webhook {}", id);
+        if WebhookRepository::update(pool, id, &command).await? {
+            Ok(())
+        } else {
+            Err(ServiceError::NotFound("Webhook".to_string()))
+        }
+    }
+
+    pub async fn delete_webhook(pool: &SqlitePool, id: i64) -> Result<(), ServiceError> {
+        tracing::info!("Deleting webhook {}", id);
+        if WebhookRepository::soft_delete(pool, id).await? {
+            Ok(())
+        } else {
+            Err(ServiceError::NotFound("Webhook".to_string()))
+        }
+    }
+
+    pub fn list_events() -> Vec<&'static str> {
+        WebhookEvent::ALL.iter().map(|event| event.as_str()).collect()
+    }
+
+    pub async fn list_deliveries(
+        pool: &SqlitePool,
+        webhook_id: i64,
+        query: WebhookDeliveryQuery,
+    ) -> Result<(Vec<WebhookDeliveryResp>, i64), ServiceError> {
+        if !WebhookRepository::exists(pool, webhook_id).await? {
+            return Err(ServiceError::NotFound("Webhook".to_string()));
+        }
+        let WebhookDeliveryQuery { current, page_size, status, event } = query;
+        let status = match status.as_deref().map(str::trim) {
+            None | Some("") | Some("all") => None,
+            Some(raw) => Some(
+                DeliveryStatus::parse(raw)
+                    .ok_or_else(|| {
+                        ServiceError::InvalidOperation(format!(
+                            "Invalid delivery status value: {}",
+                            raw
+                        ))
+                    })?
+                    .as_str(),
+            ),
+        };
+        let pagination = Pagination::from_query(PaginationQuery { current, page_size });
+        WebhookRepository::list_deliveries(
+            pool,
+            i64::from(pagination.offset),
+            i64::from(pagination.limit),
+            DeliveryListQuery { webhook_id, status, event },
+        )
+        .await
+    }
+
+    /// Re-queues a finished delivery with a fresh attempt budget.
+    pub async fn retry_delivery(
+        pool: &SqlitePool,
+        webhook_id: i64,
+        delivery_id: i64,
+    ) -> Result<(), ServiceError> {
+        if !WebhookRepository::requeue_delivery(pool, webhook_id, delivery_id).await? {
+            return Err(ServiceError::NotFound("Finished webhook delivery".to_string()));
+        }
+        WORKER_WAKEUP.notify_one();
+        Ok(())
+    }
+
+    async fn enqueue(
+        pool: &SqlitePool,
+        event: WebhookEvent,
+        data: Value,
+    ) -> Result<usize, ServiceError> {
+        let webhook_ids: Vec<i64> = WebhookRepository::list_active_subscriptions(pool)
+            .await?
+            .into_iter()
+            .filter(|(_, events)| subscribes_to(events, event))
+            .map(|(id, _)| id)
+            .collect();
+        if webhook_ids.is_empty() {
+            return Ok(0);
+        }
+        let payload = json!({
+            "event": event.as_str(),
+            "occurredAt": Utc::now().to_rfc3339(),
+            "data": data,
+        })
+        .to_string();
+        WebhookRepository::enqueue_deliveries(pool, &webhook_ids, event.as_str(), &payload).await?;
+        WORKER_WAKEUP.notify_one();
+        Ok(webhook_ids.len())
+    }
+
+    /// Starts the background delivery worker for this process.
+    pub fn spawn_worker(pool: SqlitePool) {
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = Self::deliver_due(&pool).await {
+                    tracing::error!("Webhook delivery pass failed: {:?}", e);
+                }
+                tokio::select! {
+                    _ = WORKER_WAKEUP.notified() => {}
+                    _ = tokio::time::sleep(WORKER_POLL_INTERVAL) => {}
+                }
+            }
+        });
+    }
+
+    /// Sends every due delivery, batch by batch, and returns how many were attempted.
+    pub async fn deliver_due(pool: &SqlitePool) -> Result<usize, ServiceError> {
+        let mut attempted = 0;
+        loop {
+            let due = WebhookRepository::list_due_deliveries(
+                pool,
+                Utc::now().naive_utc(),
+                DELIVERY_BATCH_SIZE,
+            )
+            .await?;
+            if due.is_empty() {
+                return Ok(attempted);
+            }
+            let batch_len = due.len();
+            let results = futures::future::join_all(due.into_iter().map(|delivery| async move {
+                let outcome = send_delivery(&delivery).await;
+                record_outcome(pool, &delivery, outcome).await
+            }))
+            .await;
+            for result in results {
+                result?;
+            }
+            attempted += batch_len;
+            if (batch_len as i64) < DELIVERY_BATCH_SIZE {
+                return Ok(attempted);
+            }
+        }
+    }
+}
+
+/// Webhook dispatcher subscriber: turns exposed domain events into queued deliveries.
+#[async_trait]
+impl EventSubscriber<SqlitePool> for WebhookService {
+    fn name(&self) -> &'static str {
+        "webhook-dispatcher"
+    }
+
+    /// Queues `event` for every enabled webhook subscribed to it. A failure goes back to the
+    /// outbox for a retry and never fails the admin action.
+    async fn handle(&self, pool: &SqlitePool, event: &DomainEvent) -> Result<(), String> {
+        if let Some((webhook_event, data)) = WebhookEvent::from_domain(event) {
+            Self::enqueue(pool, webhook_event, data).await.map_err(|e| {
+                format!("failed to queue webhook event {}: {:?}", webhook_event.as_str(), e)
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Result of one HTTP attempt: the response status, or a transport error.
+type AttemptOutcome = Result<u16, String>;
+
+async fn send_delivery(delivery: &DueDeliveryRow) -> AttemptOutcome {
+    if !delivery.webhook_active {
+        return Err("webhook is disabled or deleted".to_string());
+    }
+    let timestamp = Utc::now().timestamp().to_string();
+    let signature = sign_payload(&delivery.secret, &timestamp, &delivery.payload);
+    let headers = [
+        ("x-rustzen-event", delivery.event.clone()),
+        ("x-rustzen-delivery", delivery.id.to_string()),
+        ("x-rustzen-timestamp", timestamp),
+        ("x-rustzen-signature", format!("sha256={}", signature)),
+    ];
+    http_client::post_json(&delivery.url, &headers, delivery.payload.clone(), DELIVERY_TIMEOUT)
+        .await
+}
+
+async fn record_outcome(
+    pool: &SqlitePool,
+    delivery: &DueDeliveryRow,
+    outcome: AttemptOutcome,
+) -> Result<(), ServiceError> {
+    let attempts = delivery.attempts + 1;
+    let (response_status, error) = match outcome {
+        Ok(code) if (200..300).contains(&code) => (Some(code), None),
+        Ok(code) => (Some(code), Some(format!("receiver answered HTTP {}", code))),
+        Err(error) => (None, Some(error)),
+    };
+    let status = match &error {
+        None => DeliveryStatus::Succeeded,
+        Some(_) if !delivery.webhook_active || attempts >= MAX_DELIVERY_ATTEMPTS => {
+            DeliveryStatus::Failed
+        }
+        Some(_) => DeliveryStatus::Pending,
+    };
+    if let Some(error) = &error {
+        tracing::warn!("Webhook delivery {} attempt {} failed: {}", delivery.id, attempts, error);
+    }
+    WebhookRepository::record_attempt(
+        pool,
+        delivery.id,
+        status,
+        attempts,
+        Utc::now().naive_utc() + retry_delay(attempts),
+        response_status,
+        error.as_deref(),
+    )
+    .await
+}
+
+/// Backoff before the next attempt after `attempts` failures: 30s, 1m, 2m, 4m, ...
+fn retry_delay(attempts: i64) -> ChronoDuration {
+    let exponent = u32::try_from(attempts.saturating_sub(1)).unwrap_or(0).min(10);
+    ChronoDuration::seconds(RETRY_BASE_DELAY_SECS * 2_i64.pow(exponent))
+}
+
+/// Hex HMAC-SHA256 of `"{timestamp}.{payload}"`, sent as `x-rustzen-signature: sha256=<hex>`.
+pub fn sign_payload(secret: &str, timestamp: &str, payload: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(payload.as_bytes());
+    mac.finalize().into_bytes().iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn subscribes_to(events_json: &str, event: WebhookEvent) -> bool {
+    serde_json::from_str::<Vec<String>>(events_json)
+        .map(|events| events.iter().any(|e| e == WILDCARD_EVENT || e == event.as_str()))
+        .unwrap_or(false)
+}
+
+fn normalize_webhook(
+    name: String,
+    url: String,
+    secret: Option<String>,
+    events: Vec<String>,
+    status: Option<i16>,
+    description: Option<String>,
+) -> Result<WebhookCommand, ServiceError> {
+    let name = name.trim().to_string();
+    if name.is_empty() || name.chars().count() > WEBHOOK_NAME_MAX_LEN {
+        return Err(ServiceError::InvalidOperation(format!(
+            "Webhook name must be 1-{} characters",
+            WEBHOOK_NAME_MAX_LEN
+        )));
+    }
+    let url = url.trim().to_string();
+    http_client::validate_url(&url)
+        .map_err(|e| ServiceError::InvalidOperation(format!("Webhook URL: {}", e)))?;
+    let secret = secret.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    if secret.as_ref().is_some_and(|s| s.chars().count() < WEBHOOK_SECRET_MIN_LEN) {
+        return Err(ServiceError::InvalidOperation(format!(
+            "Webhook secret must be at least {} characters",
+            WEBHOOK_SECRET_MIN_LEN
+        )));
+    }
+    let events = normalize_events(events)?;
+    let status = status.unwrap_or(WEBHOOK_STATUS_ENABLED);
+    if ![1, 2].contains(&status) {
+        return Err(ServiceError::InvalidOperation(
+            "Status must be 1 (enabled) or 2 (disabled)".to_string(),
+        ));
+    }
+    let description = description.map(|d| d.trim().to_string()).filter(|d| !d.is_empty());
+    Ok(WebhookCommand { name, url, secret, events, status, description })
+}
+
+fn normalize_events(events: Vec<String>) -> Result<Vec<String>, ServiceError> {
+    let mut normalized: Vec<String> = Vec::new();
+    for event in events {
+        let event = event.trim().to_string();
+        let known =
+            event == WILDCARD_EVENT || WebhookEvent::ALL.iter().any(|e| e.as_str() == event);
+        if !known {
+            return Err(ServiceError::InvalidOperation(format!(
+                "Unknown webhook event: {}",
+                event
+            )));
+        }
+        if !normalized.contains(&event) {
+            normalized.push(event);
+        }
+    }
+    if normalized.is_empty() {
+        return Err(ServiceError::InvalidOperation(
+            "Select at least one webhook event".to_string(),
+        ));
+    }
+    if normalized.iter().any(|e| e == WILDCARD_EVENT) {
+        normalized = vec![WILDCARD_EVENT.to_string()];
+    }
+    Ok(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{WebhookService, normalize_events, retry_delay, sign_payload};
+    use crate::features::system::webhook::{
+        repo::WebhookRepository,
+        types::{CreateWebhookRequest, WebhookDeliveryQuery, WebhookEvent},
+    };
+    use crate::infra::{config::test_config, http_client};
+    use serde_json::json;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[test]
+    fn signature_is_hmac_sha256_over_timestamp_and_body() {
+        // Receivers recompute HMAC-SHA256(secret, "1700000000.{}") and compare.
+        assert_eq!(
+            sign_payload("key", "1700000000", "{}"),
+            "9d713ed406bb7076d4123f0dc2c39d2df5c654ed4b0cd56b52c8b4c940bd63ae"
+        );
+    }
+
+    #[test]
+    fn events_are_validated_deduped_and_wildcard_collapses() {
+        let events = normalize_events(vec![
+            " user.created ".to_string(),
+            "user.created".to_string(),
+            "login.failed".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(events, vec!["user.created", "login.failed"]);
+        assert_eq!(
+            normalize_events(vec!["role.updated".to_string(), "*".to_string()]).unwrap(),
+            vec!["*"]
+        );
+        assert!(normalize_events(vec!["user.exploded".to_string()]).is_err());
+        assert!(normalize_events(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn retry_delay_doubles_from_thirty_seconds() {
+        assert_eq!(retry_delay(1).num_seconds(), 30);
+        assert_eq!(retry_delay(2).num_seconds(), 60);
+        assert_eq!(retry_delay(4).num_seconds(), 240);
+    }
+
+    #[tokio::test]
+    async fn dispatched_events_are_signed_delivered_and_logged() {
+        http_client::configure(test_config());
+        let pool = crate::infra::db::test_pool().await;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let receiver = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut raw = Vec::new();
+            let mut buf = [0u8; 1024];
+            loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                assert!(n > 0, "connection closed before the body arrived");
+                raw.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&raw);
+                let Some((head, body)) = text.split_once("\r\n\r\n") else { continue };
+                let length = head
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length: "))
+                    .and_then(|value| value.trim().parse::<usize>().ok())
+                    .unwrap();
+                if body.len() >= length {
+                    break;
+                }
+            }
+            socket.write_all(b"HTTP/1.1 204 No Content\r\n\r\n").await.unwrap();
+            String::from_utf8(raw).unwrap()
+        });
+
+        let webhook_id = WebhookService::create_webhook(
+            &pool,
+            CreateWebhookRequest {
+                name: "crm".to_string(),
+                url: format!("http://{}/hooks", addr),
+                secret: "0123456789abcdef".to_string(),
+                events: vec!["user.created".to_string()],
+                status: None,
+                description: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        // Unsubscribed events are not queued.
+        WebhookService::enqueue(&pool, WebhookEvent::RoleUpdated, json!({ "id": 1 }))
+            .await
+            .unwrap();
+        WebhookService::enqueue(&pool, WebhookEvent::UserCreated, json!({ "id": 7 }))
+            .await
+            .unwrap();
+        assert_eq!(WebhookService::deliver_due(&pool).await.unwrap(), 1);
+
+        let raw = receiver.await.unwrap();
+        assert!(raw.contains("x-rustzen-event: user.created"));
+        let timestamp =
+            raw.lines().find_map(|line| line.strip_prefix("x-rustzen-timestamp: ")).unwrap().trim();
+        let body = raw.split("\r\n\r\n").nth(1).unwrap();
+        let expected = sign_payload("0123456789abcdef", timestamp, body);
+        assert!(raw.contains(&format!("x-rustzen-signature: sha256={}", expected)));
+
+        let query = WebhookDeliveryQuery {
+            current: None,
+            page_size: None,
+            status: Some("succeeded".to_string()),
+            event: None,
+        };
+        let (deliveries, total) =
+            WebhookService::list_deliveries(&pool, webhook_id, query).await.unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(deliveries[0].attempts, 1);
+        assert_eq!(deliveries[0].response_status, Some(204));
+        assert!(deliveries[0].delivered_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn unreachable_receivers_are_retried_then_failed() {
+        http_client::configure(test_config());
+        let pool = crate::infra::db::test_pool().await;
+        // Bind then drop a listener so the port refuses connections.
+        let addr =
+            tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+
+        let webhook_id = WebhookService::create_webhook(
+            &pool,
+            CreateWebhookRequest {
+                name: "down".to_string(),
+                url: format!("http://{}/hooks", addr),
+                secret: "0123456789abcdef".to_string(),
+                events: vec!["*".to_string()],
+                status: None,
+                description: None,
+            },
+        )
+        .await
+        .unwrap();
+        WebhookService::enqueue(&pool, WebhookEvent::LoginFailed, json!({ "username": "x" }))
+            .await
+            .unwrap();
+
+        for attempt in 1..=super::MAX_DELIVERY_ATTEMPTS {
+            assert_eq!(WebhookService::deliver_due(&pool).await.unwrap(), 1, "{attempt}");
+            sqlx::query("UPDATE webhook_deliveries SET next_attempt_at = '2000-01-01 00:00:00'")
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        assert_eq!(WebhookService::deliver_due(&pool).await.unwrap(), 0);
+
+        let query =
+            WebhookDeliveryQuery { current: None, page_size: None, status: None, event: None };
+        let (deliveries, _) =
+            WebhookService::list_deliveries(&pool, webhook_id, query).await.unwrap();
+        assert_eq!(deliveries[0].status, "failed");
+        assert_eq!(deliveries[0].attempts, super::MAX_DELIVERY_ATTEMPTS);
+        assert!(deliveries[0].last_error.is_some());
+
+        WebhookService::retry_delivery(&pool, webhook_id, deliveries[0].id).await.unwrap();
+        assert!(
+            !WebhookRepository::requeue_delivery(&pool, webhook_id, deliveries[0].id)
+                .await
+                .unwrap()
+        );
+    }
+}
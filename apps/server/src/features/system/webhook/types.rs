@@ -0,0 +1,316 @@
+use chrono::{DateTime, Utc};
+use rustzen_core::events::DomainEvent;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+/// Subscribes a webhook to every event.
+pub const WILDCARD_EVENT: &str = "*";
+
+/// Admin-side changes a webhook can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEvent {
+    UserCreated,
+    UserUpdated,
+    UserDeleted,
+    RoleCreated,
+    RoleUpdated,
+    RoleDeleted,
+    RoleAssigned,
+    LoginFailed,
+    LoginUnusualLocation,
+    LoginSuspicious,
+    WorkflowFinished,
+}
+
+impl WebhookEvent {
+    pub const ALL: &[WebhookEvent] = &[
+        WebhookEvent::UserCreated,
+        WebhookEvent::UserUpdated,
+        WebhookEvent::UserDeleted,
+        WebhookEvent::RoleCreated,
+        WebhookEvent::RoleUpdated,
+        WebhookEvent::RoleDeleted,
+        WebhookEvent::RoleAssigned,
+        WebhookEvent::LoginFailed,
+        WebhookEvent::LoginUnusualLocation,
+        WebhookEvent::LoginSuspicious,
+        WebhookEvent::WorkflowFinished,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WebhookEvent::UserCreated => "user.created",
+            WebhookEvent::UserUpdated => "user.updated",
+            WebhookEvent::UserDeleted => "user.deleted",
+            WebhookEvent::RoleCreated => "role.created",
+            WebhookEvent::RoleUpdated => "role.updated",
+            WebhookEvent::RoleDeleted => "role.deleted",
+            WebhookEvent::RoleAssigned => "role.assigned",
+            WebhookEvent::LoginFailed => "login.failed",
+            WebhookEvent::LoginUnusualLocation => "login.unusual_location",
+            WebhookEvent::LoginSuspicious => "login.suspicious",
+            WebhookEvent::WorkflowFinished => "workflow.finished",
+        }
+    }
+}
+
+impl WebhookEvent {
+    /// Maps a domain event to its webhook name and JSON `data`; `None` is not exposed.
+    pub fn from_domain(event: &DomainEvent) -> Option<(Self, Value)> {
+        let mapped = match event {
+            DomainEvent::UserCreated { user_id, username, email, operator_id } => (
+                WebhookEvent::UserCreated,
+                json!({ "id": user_id, "username": username, "email": email, "operatorId": operator_id }),
+            ),
+            DomainEvent::UserUpdated { user_id, username, role_ids, operator_id } => (
+                WebhookEvent::UserUpdated,
+                json!({ "id": user_id, "username": username, "roleIds": role_ids, "operatorId": operator_id }),
+            ),
+            DomainEvent::UserDeleted { user_id, username, operator_id } => (
+                WebhookEvent::UserDeleted,
+                json!({ "id": user_id, "username": username, "operatorId": operator_id }),
+            ),
+            DomainEvent::RoleCreated { role_id, name, code, operator_id } => (
+                WebhookEvent::RoleCreated,
+                json!({ "id": role_id, "name": name, "code": code, "operatorId": operator_id }),
+            ),
+            DomainEvent::RoleUpdated { role_id, name, code, operator_id } => (
+                WebhookEvent::RoleUpdated,
+                json!({ "id": role_id, "name": name, "code": code, "operatorId": operator_id }),
+            ),
+            DomainEvent::RoleDeleted { role_id, operator_id } => {
+                (WebhookEvent::RoleDeleted, json!({ "id": role_id, "operatorId": operator_id }))
+            }
+            DomainEvent::RoleAssigned { role_id, user_ids, operator_id } => (
+                WebhookEvent::RoleAssigned,
+                json!({ "roleId": role_id, "userIds": user_ids, "operatorId": operator_id }),
+            ),
+            DomainEvent::LoginFailed { username, reason, ip_address, .. } => (
+                WebhookEvent::LoginFailed,
+                json!({ "username": username, "reason": reason, "ipAddress": ip_address }),
+            ),
+            DomainEvent::LoginLocationChanged {
+                user_id,
+                username,
+                ip_address,
+                country,
+                city,
+                previous_country,
+            } => (
+                WebhookEvent::LoginUnusualLocation,
+                json!({
+                    "userId": user_id,
+                    "username": username,
+                    "ipAddress": ip_address,
+                    "country": country,
+                    "city": city,
+                    "previousCountry": previous_country,
+                }),
+            ),
+            DomainEvent::SuspiciousLogin { user_id, username, rule, ip_address, detail } => (
+                WebhookEvent::LoginSuspicious,
+                json!({
+                    "userId": user_id,
+                    "username": username,
+                    "rule": rule,
+                    "ipAddress": ip_address,
+                    "detail": detail,
+                }),
+            ),
+            DomainEvent::WorkflowFinished {
+                instance_id,
+                definition_code,
+                status,
+                started_by,
+                operator_id,
+            } => (
+                WebhookEvent::WorkflowFinished,
+                json!({
+                    "id": instance_id,
+                    "definitionCode": definition_code,
+                    "status": status,
+                    "startedBy": started_by,
+                    "operatorId": operator_id,
+                }),
+            ),
+            DomainEvent::LoginSucceeded { .. }
+            | DomainEvent::LoginIpBanned { .. }
+            | DomainEvent::DataExported { .. } => return None,
+        };
+        Some(mapped)
+    }
+}
+
+/// Delivery lifecycle stored in `webhook_deliveries.status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    Pending,
+    Succeeded,
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Succeeded => "succeeded",
+            DeliveryStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(DeliveryStatus::Pending),
+            "succeeded" => Some(DeliveryStatus::Succeeded),
+            "failed" => Some(DeliveryStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// Create webhook request. `secret` signs every delivery and is never returned.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWebhookRequest {
+    pub name: String,
+    /// Receiver endpoint; must be an `http://` URL.
+    pub url: String,
+    pub secret: String,
+    /// Event names such as `user.created`, or `*` for all events.
+    pub events: Vec<String>,
+    pub status: Option<i16>,
+    pub description: Option<String>,
+}
+
+/// Update webhook request. Leave `secret` empty to keep the current one.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWebhookPayload {
+    pub name: String,
+    pub url: String,
+    pub secret: Option<String>,
+    pub events: Vec<String>,
+    pub status: Option<i16>,
+    pub description: Option<String>,
+}
+
+/// Validated webhook fields written by create and update.
+#[derive(Debug, Clone)]
+pub struct WebhookCommand {
+    pub name: String,
+    pub url: String,
+    pub secret: Option<String>,
+    pub events: Vec<String>,
+    pub status: i16,
+    pub description: Option<String>,
+}
+
+/// Webhook row as read from the database.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct WebhookRow {
+    pub id: i64,
+    pub name: String,
+    pub url: String,
+    pub events: String,
+    pub status: i16,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Webhook for list display.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookItemResp {
+    pub id: i64,
+    pub name: String,
+    pub url: String,
+    pub events: Vec<String>,
+    pub status: i16,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<WebhookRow> for WebhookItemResp {
+    type Error = serde_json::Error;
+
+    fn try_from(row: WebhookRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id,
+            name: row.name,
+            url: row.url,
+            events: serde_json::from_str(&row.events)?,
+            status: row.status,
+            description: row.description,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+/// Webhook query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookQuery {
+    pub current: Option<i64>,
+    pub page_size: Option<i64>,
+    pub name: Option<String>,
+    pub status: Option<String>,
+}
+
+/// Filters for the webhook list.
+#[derive(Debug, Clone)]
+pub struct WebhookListQuery {
+    pub name: Option<String>,
+    pub status: Option<i16>,
+}
+
+/// Filters for one webhook's delivery log.
+#[derive(Debug, Clone)]
+pub struct DeliveryListQuery {
+    pub webhook_id: i64,
+    pub status: Option<&'static str>,
+    pub event: Option<String>,
+}
+
+/// Delivery log query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryQuery {
+    pub current: Option<i64>,
+    pub page_size: Option<i64>,
+    /// Filter by `pending`, `succeeded`, or `failed`.
+    pub status: Option<String>,
+    /// Filter by event name.
+    pub event: Option<String>,
+}
+
+/// One delivery attempt record for the delivery log.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryResp {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event: String,
+    pub payload: String,
+    pub status: String,
+    pub attempts: i64,
+    pub next_attempt_at: DateTime<Utc>,
+    pub response_status: Option<i64>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+/// Due delivery joined with its webhook target, as claimed by the worker.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DueDeliveryRow {
+    pub id: i64,
+    pub event: String,
+    pub payload: String,
+    pub attempts: i64,
+    pub url: String,
+    pub secret: String,
+    pub webhook_active: bool,
+}
@@ -0,0 +1,155 @@
+use super::{
+    service::WorkflowService,
+    types::{
+        DecideTaskPayload, DefinitionItemResp, DefinitionPayload, DefinitionQuery, InboxQuery,
+        InboxTaskResp, InstanceDetailResp, InstanceItemResp, InstanceQuery, StartInstanceRequest,
+    },
+};
+use crate::{
+    common::{
+        api::{ApiResponse, AppResult, PageMeta},
+        pagination::{Pagination, PaginationQuery},
+    },
+    infra::db::DbExecutor,
+};
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use rustzen_core::auth::CurrentUser;
+use sqlx::SqlitePool;
+
+/// Get paginated workflow definitions
+pub async fn list_definitions(
+    State(db): State<DbExecutor>,
+    Query(query): Query<DefinitionQuery>,
+) -> AppResult<Vec<DefinitionItemResp>> {
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (definitions, total) = WorkflowService::list_definitions(db.read(), query).await?;
+    Ok(ApiResponse::page(definitions, total, PageMeta::new(pagination, total)))
+}
+
+/// Create a workflow definition
+pub async fn create_definition(
+    State(pool): State<SqlitePool>,
+    Json(payload): Json<DefinitionPayload>,
+) -> AppResult<i64> {
+    Ok(ApiResponse::success(WorkflowService::create_definition(&pool, payload).await?))
+}
+
+/// Update a workflow definition
+pub async fn update_definition(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+    Json(payload): Json<DefinitionPayload>,
+) -> AppResult<()> {
+    WorkflowService::update_definition(&pool, id, payload).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Delete a workflow definition
+pub async fn delete_definition(
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+) -> AppResult<()> {
+    WorkflowService::delete_definition(&pool, id).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Start a workflow instance
+pub async fn start_instance(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Json(request): Json<StartInstanceRequest>,
+) -> AppResult<i64> {
+    Ok(ApiResponse::success(WorkflowService::start_instance(&pool, &current_user, request).await?))
+}
+
+/// Get paginated workflow instances of every user
+pub async fn list_instances(
+    State(db): State<DbExecutor>,
+    Query(query): Query<InstanceQuery>,
+) -> AppResult<Vec<InstanceItemResp>> {
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (instances, total) = WorkflowService::list_instances(db.read(), query, None).await?;
+    Ok(ApiResponse::page(instances, total, PageMeta::new(pagination, total)))
+}
+
+/// Get paginated workflow instances the current user started
+pub async fn list_my_instances(
+    current_user: CurrentUser,
+    State(db): State<DbExecutor>,
+    Query(query): Query<InstanceQuery>,
+) -> AppResult<Vec<InstanceItemResp>> {
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (instances, total) =
+        WorkflowService::list_instances(db.read(), query, Some(current_user.user_id)).await?;
+    Ok(ApiResponse::page(instances, total, PageMeta::new(pagination, total)))
+}
+
+/// Get a workflow instance with its task history
+pub async fn get_instance(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+) -> AppResult<InstanceDetailResp> {
+    Ok(ApiResponse::success(WorkflowService::get_instance(&pool, &current_user, id).await?))
+}
+
+/// Cancel a running workflow instance the current user started
+pub async fn cancel_instance(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+) -> AppResult<()> {
+    WorkflowService::cancel_instance(&pool, &current_user, id).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Get paginated pending tasks assigned to the current user's roles
+pub async fn list_my_tasks(
+    current_user: CurrentUser,
+    State(db): State<DbExecutor>,
+    Query(query): Query<InboxQuery>,
+) -> AppResult<Vec<InboxTaskResp>> {
+    let pagination = Pagination::from_query(PaginationQuery {
+        current: query.current,
+        page_size: query.page_size,
+    });
+    let (tasks, total) = WorkflowService::list_inbox(db.read(), &current_user, query).await?;
+    Ok(ApiResponse::page(tasks, total, PageMeta::new(pagination, total)))
+}
+
+/// Approve a pending workflow task
+pub async fn approve_task(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+    payload: Option<Json<DecideTaskPayload>>,
+) -> AppResult<()> {
+    let payload = payload.map(|Json(payload)| payload).unwrap_or_default();
+    WorkflowService::approve_task(&pool, &current_user, id, payload).await?;
+    Ok(ApiResponse::success(()))
+}
+
+/// Reject a pending workflow task, ending its instance
+pub async fn reject_task(
+    current_user: CurrentUser,
+    State(pool): State<SqlitePool>,
+    Path(id): Path<i64>,
+    payload: Option<Json<DecideTaskPayload>>,
+) -> AppResult<()> {
+    let payload = payload.map(|Json(payload)| payload).unwrap_or_default();
+    WorkflowService::reject_task(&pool, &current_user, id, payload).await?;
+    Ok(ApiResponse::success(()))
+}
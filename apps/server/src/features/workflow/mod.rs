@@ -0,0 +1,62 @@
+pub mod handler;
+pub mod repo;
+pub mod service;
+pub mod types;
+
+use axum::{
+    Router,
+    routing::{delete, get, post, put},
+};
+use handler::{
+    approve_task, cancel_instance, create_definition, delete_definition, get_instance,
+    list_definitions, list_instances, list_my_instances, list_my_tasks, reject_task,
+    start_instance, update_definition,
+};
+use rustzen_core::{
+    capability::{workflow_definition, workflow_instance},
+    permission::{PermissionsCheck, RouterExt},
+};
+use sqlx::SqlitePool;
+
+/// Definitions and the full instance list are permission-gated. The personal routes
+/// only need a session: the service limits them to the caller's own instances and to
+/// tasks of roles the caller belongs to.
+pub fn workflow_routes() -> Router<SqlitePool> {
+    Router::new()
+        .route_with_permission(
+            "/definitions",
+            get(list_definitions),
+            PermissionsCheck::Require(workflow_definition::LIST),
+        )
+        .route_with_permission(
+            "/definitions",
+            post(create_definition),
+            PermissionsCheck::Require(workflow_definition::CREATE),
+        )
+        .route_with_permission(
+            "/definitions/{id}",
+            put(update_definition),
+            PermissionsCheck::Require(workflow_definition::UPDATE),
+        )
+        .route_with_permission(
+            "/definitions/{id}",
+            delete(delete_definition),
+            PermissionsCheck::Require(workflow_definition::DELETE),
+        )
+        .route_with_permission(
+            "/instances",
+            get(list_instances),
+            PermissionsCheck::Require(workflow_instance::LIST),
+        )
+        .route_with_permission(
+            "/instances",
+            post(start_instance),
+            PermissionsCheck::Require(workflow_instance::START),
+        )
+        .route("/instances/mine", get(list_my_instances))
+        .route("/instances/{id}", get(get_instance))
+        .route("/instances/{id}/cancel", post(cancel_instance))
+        .route("/tasks/mine", get(list_my_tasks))
+        .route("/tasks/{id}/approve", post(approve_task))
+        .route("/tasks/{id}/reject", post(reject_task))
+}
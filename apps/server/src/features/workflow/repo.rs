@@ -0,0 +1,454 @@
+use super::types::{
+    DefinitionCommand, DefinitionListQuery, DefinitionRow, InboxTaskResp, InstanceListQuery,
+    InstanceRow, InstanceStatus, TaskResp, TaskStatus, WorkflowStep,
+};
+use crate::common::{
+    error::ServiceError,
+    query::{count_with_filters, fetch_with_filters, push_eq, push_ilike},
+    tx::Tx,
+};
+
+use chrono::Utc;
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+
+pub struct WorkflowRepository;
+
+fn db_error(context: &str, e: sqlx::Error) -> ServiceError {
+    tracing::error!("Database error {}: {:?}", context, e);
+    ServiceError::DatabaseQueryFailed
+}
+
+/// Roles whose members currently act for them: enabled, not deleted and not expired.
+const ACTIVE_MEMBER_ROLES: &str = "SELECT ur.role_id FROM user_roles ur
+     JOIN roles r ON r.id = ur.role_id AND r.deleted_at IS NULL AND r.status = 1
+     WHERE (ur.expires_at IS NULL OR ur.expires_at > CURRENT_TIMESTAMP) AND ur.user_id = ";
+
+impl WorkflowRepository {
+    fn format_definition_query(
+        query: &DefinitionListQuery,
+        query_builder: &mut QueryBuilder<Sqlite>,
+    ) {
+        push_ilike(query_builder, "name", query.name.as_deref());
+        push_eq(query_builder, "status", query.status);
+    }
+
+    fn format_instance_query(query: &InstanceListQuery, query_builder: &mut QueryBuilder<Sqlite>) {
+        push_eq(query_builder, "status", query.status);
+        push_eq(query_builder, "started_by", query.started_by);
+        if let Some(code) =
+            query.definition_code.as_deref().map(str::trim).filter(|c| !c.is_empty())
+        {
+            query_builder.push(" AND definition_code = ").push_bind(code.to_string());
+        }
+    }
+
+    fn format_inbox_query(user_id: i64, query_builder: &mut QueryBuilder<Sqlite>) {
+        query_builder
+            .push(" AND t.approver_role_id IN (")
+            .push(ACTIVE_MEMBER_ROLES)
+            .push_bind(user_id)
+            .push(")");
+    }
+
+    pub async fn list_definitions(
+        pool: &SqlitePool,
+        offset: i64,
+        limit: i64,
+        query: DefinitionListQuery,
+    ) -> Result<(Vec<DefinitionRow>, i64), ServiceError> {
+        let total = count_with_filters(
+            pool,
+            "SELECT COUNT(*) FROM workflow_definitions WHERE deleted_at IS NULL",
+            |query_builder| Self::format_definition_query(&query, query_builder),
+        )
+        .await?;
+        if total == 0 {
+            return Ok((Vec::new(), total));
+        }
+        let definitions = fetch_with_filters(
+            pool,
+            "SELECT id, code, name, description, steps, status, created_at, updated_at
+             FROM workflow_definitions WHERE deleted_at IS NULL",
+            |query_builder| Self::format_definition_query(&query, query_builder),
+            Some("id DESC"),
+            Some(limit),
+            Some(offset),
+        )
+        .await?;
+        Ok((definitions, total))
+    }
+
+    /// Whether a live definition other than `exclude_id` already uses `code`.
+    pub async fn code_exists(
+        pool: &SqlitePool,
+        code: &str,
+        exclude_id: Option<i64>,
+    ) -> Result<bool, ServiceError> {
+        sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM workflow_definitions WHERE code = ? AND id != ? AND deleted_at IS NULL)",
+        )
+        .bind(code)
+        .bind(exclude_id.unwrap_or(0))
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_error("checking workflow code", e))
+    }
+
+    /// Ids among `role_ids` that name a live role.
+    pub async fn existing_role_ids(
+        pool: &SqlitePool,
+        role_ids: &[i64],
+    ) -> Result<Vec<i64>, ServiceError> {
+        if role_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut query_builder: QueryBuilder<Sqlite> =
+            QueryBuilder::new("SELECT id FROM roles WHERE deleted_at IS NULL AND id IN (");
+        let mut separated = query_builder.separated(", ");
+        for id in role_ids {
+            separated.push_bind(*id);
+        }
+        query_builder.push(")");
+        query_builder
+            .build_query_scalar::<i64>()
+            .fetch_all(pool)
+            .await
+            .map_err(|e| db_error("checking workflow approver roles", e))
+    }
+
+    pub async fn find_enabled_definition(
+        pool: &SqlitePool,
+        code: &str,
+    ) -> Result<Option<DefinitionRow>, ServiceError> {
+        sqlx::query_as::<_, DefinitionRow>(
+            "SELECT id, code, name, description, steps, status, created_at, updated_at
+             FROM workflow_definitions WHERE code = ? AND status = 1 AND deleted_at IS NULL",
+        )
+        .bind(code)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_error("finding workflow definition", e))
+    }
+
+    pub async fn create_definition(
+        pool: &SqlitePool,
+        command: &DefinitionCommand,
+    ) -> Result<i64, ServiceError> {
+        let now = Utc::now().naive_utc();
+        sqlx::query_scalar::<_, i64>(
+            "INSERT INTO workflow_definitions (code, name, description, steps, status, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)
+             RETURNING id",
+        )
+        .bind(&command.code)
+        .bind(&command.name)
+        .bind(command.description.as_deref())
+        .bind(steps_json(&command.steps))
+        .bind(command.status)
+        .bind(now)
+        .bind(now)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| db_error("creating workflow definition", e))
+    }
+
+    pub async fn update_definition(
+        pool: &SqlitePool,
+        id: i64,
+        command: &DefinitionCommand,
+    ) -> Result<bool, ServiceError> {
+        let result = sqlx::query(
+            "UPDATE workflow_definitions
+             SET code = ?, name = ?, description = ?, steps = ?, status = ?, updated_at = ?
+             WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(&command.code)
+        .bind(&command.name)
+        .bind(command.description.as_deref())
+        .bind(steps_json(&command.steps))
+        .bind(command.status)
+        .bind(Utc::now().naive_utc())
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| db_error("updating workflow definition", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn soft_delete_definition(pool: &SqlitePool, id: i64) -> Result<bool, ServiceError> {
+        let now = Utc::now().naive_utc();
+        let result = sqlx::query(
+            "UPDATE workflow_definitions SET deleted_at = ?, updated_at = ?
+             WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(now)
+        .bind(now)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| db_error("deleting workflow definition", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn list_instances(
+        pool: &SqlitePool,
+        offset: i64,
+        limit: i64,
+        query: InstanceListQuery,
+    ) -> Result<(Vec<InstanceRow>, i64), ServiceError> {
+        let total =
+            count_with_filters(pool, "SELECT COUNT(*) FROM workflow_instances WHERE 1=1", |qb| {
+                Self::format_instance_query(&query, qb)
+            })
+            .await?;
+        if total == 0 {
+            return Ok((Vec::new(), total));
+        }
+        let instances = fetch_with_filters(
+            pool,
+            "SELECT id, definition_id, definition_code, title, data, steps, current_step, status,
+                    started_by, started_by_name, created_at, updated_at, finished_at
+             FROM workflow_instances WHERE 1=1",
+            |query_builder| Self::format_instance_query(&query, query_builder),
+            Some("id DESC"),
+            Some(limit),
+            Some(offset),
+        )
+        .await?;
+        Ok((instances, total))
+    }
+
+    pub async fn find_instance(
+        pool: &SqlitePool,
+        id: i64,
+    ) -> Result<Option<InstanceRow>, ServiceError> {
+        sqlx::query_as::<_, InstanceRow>(
+            "SELECT id, definition_id, definition_code, title, data, steps, current_step, status,
+                    started_by, started_by_name, created_at, updated_at, finished_at
+             FROM workflow_instances WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_error("finding workflow instance", e))
+    }
+
+    pub async fn find_instance_in_tx(
+        tx: &mut Tx<'_>,
+        id: i64,
+    ) -> Result<Option<InstanceRow>, ServiceError> {
+        sqlx::query_as::<_, InstanceRow>(
+            "SELECT id, definition_id, definition_code, title, data, steps, current_step, status,
+                    started_by, started_by_name, created_at, updated_at, finished_at
+             FROM workflow_instances WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| db_error("finding workflow instance", e))
+    }
+
+    pub async fn insert_instance_in_tx(
+        tx: &mut Tx<'_>,
+        definition: &DefinitionRow,
+        title: &str,
+        data: &str,
+        started_by: (i64, &str),
+    ) -> Result<i64, ServiceError> {
+        let now = Utc::now().naive_utc();
+        sqlx::query_scalar::<_, i64>(
+            "INSERT INTO workflow_instances
+                 (definition_id, definition_code, title, data, steps, started_by, started_by_name, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+             RETURNING id",
+        )
+        .bind(definition.id)
+        .bind(&definition.code)
+        .bind(title)
+        .bind(data)
+        .bind(&definition.steps)
+        .bind(started_by.0)
+        .bind(started_by.1)
+        .bind(now)
+        .bind(now)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| db_error("starting workflow instance", e))
+    }
+
+    /// Opens the pending task for `step` and points the instance at it.
+    pub async fn open_step_in_tx(
+        tx: &mut Tx<'_>,
+        instance_id: i64,
+        step_index: usize,
+        step: &WorkflowStep,
+    ) -> Result<(), ServiceError> {
+        let now = Utc::now().naive_utc();
+        sqlx::query(
+            "INSERT INTO workflow_tasks (instance_id, step_index, step_name, approver_role_id, created_at)
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(instance_id)
+        .bind(step_index as i64)
+        .bind(&step.name)
+        .bind(step.approver_role_id)
+        .bind(now)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| db_error("opening workflow task", e))?;
+        sqlx::query("UPDATE workflow_instances SET current_step = ?, updated_at = ? WHERE id = ?")
+            .bind(step_index as i64)
+            .bind(now)
+            .bind(instance_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| db_error("advancing workflow instance", e))?;
+        Ok(())
+    }
+
+    /// Closes a running instance; `false` when it was no longer running.
+    pub async fn finish_instance_in_tx(
+        tx: &mut Tx<'_>,
+        id: i64,
+        status: InstanceStatus,
+    ) -> Result<bool, ServiceError> {
+        let now = Utc::now().naive_utc();
+        let result = sqlx::query(
+            "UPDATE workflow_instances SET status = ?, updated_at = ?, finished_at = ?
+             WHERE id = ? AND status = 'running'",
+        )
+        .bind(status.as_str())
+        .bind(now)
+        .bind(now)
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| db_error("finishing workflow instance", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn cancel_pending_tasks_in_tx(
+        tx: &mut Tx<'_>,
+        instance_id: i64,
+    ) -> Result<(), ServiceError> {
+        sqlx::query(
+            "UPDATE workflow_tasks SET status = 'cancelled', decided_at = ?
+             WHERE instance_id = ? AND status = 'pending'",
+        )
+        .bind(Utc::now().naive_utc())
+        .bind(instance_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| db_error("cancelling workflow tasks", e))?;
+        Ok(())
+    }
+
+    pub async fn list_tasks(
+        pool: &SqlitePool,
+        instance_id: i64,
+    ) -> Result<Vec<TaskResp>, ServiceError> {
+        sqlx::query_as::<_, TaskResp>(
+            "SELECT id, instance_id, step_index, step_name, approver_role_id, status, decided_by,
+                    decided_by_name, comment, created_at, decided_at
+             FROM workflow_tasks WHERE instance_id = ? ORDER BY id",
+        )
+        .bind(instance_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("listing workflow tasks", e))
+    }
+
+    pub async fn find_task_in_tx(
+        tx: &mut Tx<'_>,
+        id: i64,
+    ) -> Result<Option<TaskResp>, ServiceError> {
+        sqlx::query_as::<_, TaskResp>(
+            "SELECT id, instance_id, step_index, step_name, approver_role_id, status, decided_by,
+                    decided_by_name, comment, created_at, decided_at
+             FROM workflow_tasks WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| db_error("finding workflow task", e))
+    }
+
+    /// Records a decision on a pending task; `false` when it was already decided.
+    pub async fn decide_task_in_tx(
+        tx: &mut Tx<'_>,
+        id: i64,
+        status: TaskStatus,
+        decided_by: (i64, &str),
+        comment: Option<&str>,
+    ) -> Result<bool, ServiceError> {
+        let result = sqlx::query(
+            "UPDATE workflow_tasks
+             SET status = ?, decided_by = ?, decided_by_name = ?, comment = ?, decided_at = ?
+             WHERE id = ? AND status = 'pending'",
+        )
+        .bind(status.as_str())
+        .bind(decided_by.0)
+        .bind(decided_by.1)
+        .bind(comment)
+        .bind(Utc::now().naive_utc())
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| db_error("deciding workflow task", e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Whether `user_id` belongs to `role_id` and the role is enabled.
+    pub async fn is_active_member<'c, E>(
+        executor: E,
+        user_id: i64,
+        role_id: i64,
+    ) -> Result<bool, ServiceError>
+    where
+        E: sqlx::Executor<'c, Database = Sqlite>,
+    {
+        let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new("SELECT EXISTS(");
+        query_builder.push(ACTIVE_MEMBER_ROLES).push_bind(user_id);
+        query_builder.push(" AND ur.role_id = ").push_bind(role_id).push(")");
+        query_builder
+            .build_query_scalar::<bool>()
+            .fetch_one(executor)
+            .await
+            .map_err(|e| db_error("checking workflow approver", e))
+    }
+
+    /// Pending tasks assigned to any active role of `user_id`, oldest first.
+    pub async fn list_inbox(
+        pool: &SqlitePool,
+        user_id: i64,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<InboxTaskResp>, i64), ServiceError> {
+        let total = count_with_filters(
+            pool,
+            "SELECT COUNT(*) FROM workflow_tasks t WHERE t.status = 'pending'",
+            |query_builder| Self::format_inbox_query(user_id, query_builder),
+        )
+        .await?;
+        if total == 0 {
+            return Ok((Vec::new(), total));
+        }
+        let tasks = fetch_with_filters(
+            pool,
+            "SELECT t.id, t.instance_id, t.step_index, t.step_name, t.approver_role_id,
+                    i.definition_code, i.title, i.started_by, i.started_by_name, t.created_at
+             FROM workflow_tasks t JOIN workflow_instances i ON i.id = t.instance_id
+             WHERE t.status = 'pending'",
+            |query_builder| Self::format_inbox_query(user_id, query_builder),
+            Some("t.id"),
+            Some(limit),
+            Some(offset),
+        )
+        .await?;
+        Ok((tasks, total))
+    }
+}
+
+fn steps_json(steps: &[WorkflowStep]) -> String {
+    serde_json::to_string(steps).unwrap_or_else(|_| "[]".to_string())
+}
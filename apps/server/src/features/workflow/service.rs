@@ -0,0 +1,463 @@
+use super::{
+    repo::WorkflowRepository,
+    types::{
+        DecideTaskPayload, DefinitionCommand, DefinitionItemResp, DefinitionListQuery,
+        DefinitionPayload, DefinitionQuery, InboxQuery, InboxTaskResp, InstanceDetailResp,
+        InstanceItemResp, InstanceListQuery, InstanceQuery, InstanceRow, InstanceStatus,
+        StartInstanceRequest, TaskStatus, WorkflowStep,
+    },
+};
+use crate::{
+    common::{
+        error::ServiceError,
+        pagination::{Pagination, PaginationQuery},
+        query::parse_optional_i16_filter,
+        tx,
+        validation::FieldErrors,
+    },
+    infra::events,
+};
+
+use rustzen_core::{auth::CurrentUser, capability::workflow_instance, events::DomainEvent};
+use sqlx::SqlitePool;
+
+const DEFINITION_STATUS_ENABLED: i16 = 1;
+const DEFINITION_STATUSES: &[i16] = &[1, 2];
+const CODE_MAX_LEN: usize = 50;
+const NAME_MAX_LEN: usize = 100;
+const TITLE_MAX_LEN: usize = 200;
+const MAX_STEPS: usize = 10;
+const COMMENT_MAX_LEN: usize = 500;
+
+pub struct WorkflowService;
+
+impl WorkflowService {
+    pub async fn list_definitions(
+        pool: &SqlitePool,
+        query: DefinitionQuery,
+    ) -> Result<(Vec<DefinitionItemResp>, i64), ServiceError> {
+        let DefinitionQuery { current, page_size, name, status } = query;
+        let pagination = Pagination::from_query(PaginationQuery { current, page_size });
+        let status = parse_optional_i16_filter(status.as_deref(), "workflow status", None)?;
+        let (rows, total) = WorkflowRepository::list_definitions(
+            pool,
+            i64::from(pagination.offset),
+            i64::from(pagination.limit),
+            DefinitionListQuery { name, status },
+        )
+        .await?;
+        let definitions = rows
+            .into_iter()
+            .map(DefinitionItemResp::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| {
+                tracing::error!("Invalid workflow steps data: {}", e);
+                ServiceError::DatabaseQueryFailed
+            })?;
+        Ok((definitions, total))
+    }
+
+    pub async fn create_definition(
+        pool: &SqlitePool,
+        payload: DefinitionPayload,
+    ) -> Result<i64, ServiceError> {
+        let command = Self::validate_definition(pool, payload, None).await?;
+        tracing::info!("Creating workflow definition '{}'", command.code);
+        WorkflowRepository::create_definition(pool, &command).await
+    }
+
+    /// Replaces a definition. Running instances keep the steps they started with.
+    pub async fn update_definition(
+        pool: &SqlitePool,
+        id: i64,
+        payload: DefinitionPayload,
+    ) -> Result<(), ServiceError> {
+        let command = Self::validate_definition(pool, payload, Some(id)).await?;
+        tracing::info!("Updating workflow definition {}", id);
+        if WorkflowRepository::update_definition(pool, id, &command).await? {
+            Ok(())
+        } else {
+            Err(ServiceError::NotFound("Workflow definition".to_string()))
+        }
+    }
+
+    pub async fn delete_definition(pool: &SqlitePool, id: i64) -> Result<(), ServiceError> {
+        tracing::info!("Deleting workflow definition {}", id);
+        if WorkflowRepository::soft_delete_definition(pool, id).await? {
+            Ok(())
+        } else {
+            Err(ServiceError::NotFound("Workflow definition".to_string()))
+        }
+    }
+
+    /// Starts an instance of an enabled definition and opens its first step.
+    pub async fn start_instance(
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        request: StartInstanceRequest,
+    ) -> Result<i64, ServiceError> {
+        let code = request.definition_code.trim();
+        let title = request.title.trim();
+        let mut errors = FieldErrors::new();
+        if code.is_empty() {
+            errors.push("definitionCode", "is required");
+        }
+        if title.is_empty() || title.chars().count() > TITLE_MAX_LEN {
+            errors.push("title", format!("must be 1-{} characters", TITLE_MAX_LEN));
+        }
+        let data = request.data.unwrap_or_else(|| serde_json::json!({}));
+        if !data.is_object() {
+            errors.push("data", "must be a JSON object");
+        }
+        errors.into_result()?;
+
+        let definition = WorkflowRepository::find_enabled_definition(pool, code)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound(format!("Enabled workflow '{}'", code)))?;
+        let steps = parse_steps(&definition.steps)?;
+        let first = steps.first().ok_or_else(|| {
+            ServiceError::InvalidOperation(format!("Workflow '{}' has no steps", code))
+        })?;
+
+        let mut tx = tx::begin(pool).await?;
+        let id = WorkflowRepository::insert_instance_in_tx(
+            &mut tx,
+            &definition,
+            title,
+            &data.to_string(),
+            (current_user.user_id, &current_user.username),
+        )
+        .await?;
+        WorkflowRepository::open_step_in_tx(&mut tx, id, 0, first).await?;
+        tx::commit(tx).await?;
+        tracing::info!(id, workflow = code, "Started workflow instance");
+        Ok(id)
+    }
+
+    pub async fn list_instances(
+        pool: &SqlitePool,
+        query: InstanceQuery,
+        started_by: Option<i64>,
+    ) -> Result<(Vec<InstanceItemResp>, i64), ServiceError> {
+        let InstanceQuery { current, page_size, status, definition_code } = query;
+        let status = match status.as_deref().map(str::trim) {
+            None | Some("") | Some("all") => None,
+            Some(raw) => Some(
+                InstanceStatus::parse(raw)
+                    .ok_or_else(|| {
+                        ServiceError::InvalidOperation(format!(
+                            "Invalid workflow instance status value: {}",
+                            raw
+                        ))
+                    })?
+                    .as_str(),
+            ),
+        };
+        let pagination = Pagination::from_query(PaginationQuery { current, page_size });
+        let (rows, total) = WorkflowRepository::list_instances(
+            pool,
+            i64::from(pagination.offset),
+            i64::from(pagination.limit),
+            InstanceListQuery { status, definition_code, started_by },
+        )
+        .await?;
+        let instances =
+            rows.into_iter().map(instance_resp).collect::<Result<Vec<_>, ServiceError>>()?;
+        Ok((instances, total))
+    }
+
+    /// Instance with its task history, visible to the starter, to current approvers of
+    /// any of its steps and to holders of `workflow:instance:list`.
+    pub async fn get_instance(
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        id: i64,
+    ) -> Result<InstanceDetailResp, ServiceError> {
+        let not_found = || ServiceError::NotFound(format!("Workflow instance id: {}", id));
+        let row = WorkflowRepository::find_instance(pool, id).await?.ok_or_else(not_found)?;
+        let tasks = WorkflowRepository::list_tasks(pool, id).await?;
+        let mut visible = row.started_by == current_user.user_id
+            || current_user.has_capability(workflow_instance::LIST);
+        for task in &tasks {
+            if visible {
+                break;
+            }
+            visible = WorkflowRepository::is_active_member(
+                pool,
+                current_user.user_id,
+                task.approver_role_id,
+            )
+            .await?;
+        }
+        if !visible {
+            return Err(not_found());
+        }
+        Ok(InstanceDetailResp { instance: instance_resp(row)?, tasks })
+    }
+
+    /// Withdraws a running instance; only its starter may.
+    pub async fn cancel_instance(
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        id: i64,
+    ) -> Result<(), ServiceError> {
+        let mut tx = tx::begin(pool).await?;
+        let row = WorkflowRepository::find_instance_in_tx(&mut tx, id)
+            .await?
+            .filter(|row| row.started_by == current_user.user_id)
+            .ok_or_else(|| ServiceError::NotFound(format!("Workflow instance id: {}", id)))?;
+        if !WorkflowRepository::finish_instance_in_tx(&mut tx, id, InstanceStatus::Cancelled)
+            .await?
+        {
+            return Err(not_running(id));
+        }
+        WorkflowRepository::cancel_pending_tasks_in_tx(&mut tx, id).await?;
+        let event = finished_event(&row, InstanceStatus::Cancelled, current_user.user_id);
+        let recorded = events::record(&mut tx, &event).await?;
+        tx::commit(tx).await?;
+        events::deliver(pool, recorded).await;
+        Ok(())
+    }
+
+    pub async fn list_inbox(
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        query: InboxQuery,
+    ) -> Result<(Vec<InboxTaskResp>, i64), ServiceError> {
+        let pagination = Pagination::from_query(PaginationQuery {
+            current: query.current,
+            page_size: query.page_size,
+        });
+        WorkflowRepository::list_inbox(
+            pool,
+            current_user.user_id,
+            i64::from(pagination.offset),
+            i64::from(pagination.limit),
+        )
+        .await
+    }
+
+    /// Approves a pending task: opens the next step, or approves the instance after
+    /// the last one.
+    pub async fn approve_task(
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        id: i64,
+        payload: DecideTaskPayload,
+    ) -> Result<(), ServiceError> {
+        Self::decide_task(pool, current_user, id, TaskStatus::Approved, payload).await
+    }
+
+    /// Rejects a pending task, which ends the whole instance as rejected.
+    pub async fn reject_task(
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        id: i64,
+        payload: DecideTaskPayload,
+    ) -> Result<(), ServiceError> {
+        Self::decide_task(pool, current_user, id, TaskStatus::Rejected, payload).await
+    }
+
+    async fn decide_task(
+        pool: &SqlitePool,
+        current_user: &CurrentUser,
+        id: i64,
+        decision: TaskStatus,
+        payload: DecideTaskPayload,
+    ) -> Result<(), ServiceError> {
+        let comment = payload.comment.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+        if comment.as_ref().is_some_and(|c| c.chars().count() > COMMENT_MAX_LEN) {
+            let mut errors = FieldErrors::new();
+            errors.push("comment", format!("must be at most {} characters", COMMENT_MAX_LEN));
+            errors.into_result()?;
+        }
+
+        let mut tx = tx::begin(pool).await?;
+        let not_found = || ServiceError::NotFound(format!("Workflow task id: {}", id));
+        let task = WorkflowRepository::find_task_in_tx(&mut tx, id).await?.ok_or_else(not_found)?;
+        // Tasks of other roles are reported as missing, like the inbox never listing them.
+        let approver = task.approver_role_id;
+        if !WorkflowRepository::is_active_member(&mut *tx, current_user.user_id, approver).await? {
+            return Err(not_found());
+        }
+        let decider = (current_user.user_id, current_user.username.as_str());
+        if !WorkflowRepository::decide_task_in_tx(
+            &mut tx,
+            id,
+            decision,
+            decider,
+            comment.as_deref(),
+        )
+        .await?
+        {
+            return Err(ServiceError::InvalidOperation(format!(
+                "Workflow task {} is no longer pending",
+                id
+            )));
+        }
+
+        let instance = WorkflowRepository::find_instance_in_tx(&mut tx, task.instance_id)
+            .await?
+            .ok_or_else(not_found)?;
+        let steps = parse_steps(&instance.steps)?;
+        let next_index = task.step_index as usize + 1;
+        let finished = match (decision, steps.get(next_index)) {
+            (TaskStatus::Approved, Some(next)) => {
+                WorkflowRepository::open_step_in_tx(&mut tx, instance.id, next_index, next).await?;
+                None
+            }
+            (TaskStatus::Approved, None) => Some(InstanceStatus::Approved),
+            _ => Some(InstanceStatus::Rejected),
+        };
+        let recorded = match finished {
+            Some(status) => {
+                if !WorkflowRepository::finish_instance_in_tx(&mut tx, instance.id, status).await? {
+                    return Err(not_running(instance.id));
+                }
+                let event = finished_event(&instance, status, current_user.user_id);
+                Some(events::record(&mut tx, &event).await?)
+            }
+            None => None,
+        };
+        tx::commit(tx).await?;
+
+        tracing::info!(
+            task = id,
+            instance = instance.id,
+            decision = decision.as_str(),
+            "Decided workflow task"
+        );
+        if let Some(recorded) = recorded {
+            events::deliver(pool, recorded).await;
+        }
+        Ok(())
+    }
+
+    async fn validate_definition(
+        pool: &SqlitePool,
+        payload: DefinitionPayload,
+        id: Option<i64>,
+    ) -> Result<DefinitionCommand, ServiceError> {
+        let command = normalize_definition(payload)?;
+        let role_ids: Vec<i64> = command.steps.iter().map(|step| step.approver_role_id).collect();
+        let existing = WorkflowRepository::existing_role_ids(pool, &role_ids).await?;
+        let mut errors = FieldErrors::new();
+        for (index, step) in command.steps.iter().enumerate() {
+            if !existing.contains(&step.approver_role_id) {
+                errors.push(&format!("steps[{}].approverRoleId", index), "is not a role");
+            }
+        }
+        errors.into_result()?;
+        if WorkflowRepository::code_exists(pool, &command.code, id).await? {
+            return Err(ServiceError::InvalidOperation(format!(
+                "Workflow code '{}' already exists",
+                command.code
+            )));
+        }
+        Ok(command)
+    }
+}
+
+/// Trims and checks a definition payload; approver roles are checked against the database.
+fn normalize_definition(payload: DefinitionPayload) -> Result<DefinitionCommand, ServiceError> {
+    let mut errors = FieldErrors::new();
+    let code = payload.code.trim().to_string();
+    let code_chars_ok =
+        code.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '.');
+    if code.is_empty() || code.len() > CODE_MAX_LEN || !code_chars_ok {
+        errors.push("code", format!("must be 1-{} of a-z, 0-9, '_' or '.'", CODE_MAX_LEN));
+    }
+    let name = payload.name.trim().to_string();
+    if name.is_empty() || name.chars().count() > NAME_MAX_LEN {
+        errors.push("name", format!("must be 1-{} characters", NAME_MAX_LEN));
+    }
+    if payload.steps.is_empty() || payload.steps.len() > MAX_STEPS {
+        errors.push("steps", format!("must have 1-{} steps", MAX_STEPS));
+    }
+    let steps: Vec<WorkflowStep> = payload
+        .steps
+        .into_iter()
+        .map(|step| WorkflowStep { name: step.name.trim().to_string(), ..step })
+        .collect();
+    for (index, step) in steps.iter().enumerate() {
+        if step.name.is_empty() || step.name.chars().count() > NAME_MAX_LEN {
+            errors.push(
+                &format!("steps[{}].name", index),
+                format!("must be 1-{} characters", NAME_MAX_LEN),
+            );
+        }
+    }
+    let status = payload.status.unwrap_or(DEFINITION_STATUS_ENABLED);
+    errors.check_one_of("status", status, DEFINITION_STATUSES);
+    errors.into_result()?;
+    let description = payload.description.map(|d| d.trim().to_string()).filter(|d| !d.is_empty());
+    Ok(DefinitionCommand { code, name, description, steps, status })
+}
+
+fn parse_steps(steps: &str) -> Result<Vec<WorkflowStep>, ServiceError> {
+    serde_json::from_str(steps).map_err(|e| {
+        tracing::error!("Invalid workflow steps data: {}", e);
+        ServiceError::DatabaseQueryFailed
+    })
+}
+
+fn instance_resp(row: InstanceRow) -> Result<InstanceItemResp, ServiceError> {
+    InstanceItemResp::try_from(row).map_err(|e| {
+        tracing::error!("Invalid workflow instance data: {}", e);
+        ServiceError::DatabaseQueryFailed
+    })
+}
+
+fn not_running(id: i64) -> ServiceError {
+    ServiceError::InvalidOperation(format!("Workflow instance {} is no longer running", id))
+}
+
+fn finished_event(instance: &InstanceRow, status: InstanceStatus, operator_id: i64) -> DomainEvent {
+    DomainEvent::WorkflowFinished {
+        instance_id: instance.id,
+        definition_code: instance.definition_code.clone(),
+        status: status.as_str().to_string(),
+        started_by: instance.started_by,
+        operator_id,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_definition;
+    use crate::{
+        common::error::ServiceError,
+        features::workflow::types::{DefinitionPayload, WorkflowStep},
+    };
+
+    fn step(name: &str) -> WorkflowStep {
+        WorkflowStep { name: name.to_string(), approver_role_id: 2 }
+    }
+
+    #[test]
+    fn definitions_are_trimmed_and_every_bad_field_is_reported() {
+        let command = normalize_definition(DefinitionPayload {
+            code: " user.onboarding ".to_string(),
+            name: "User onboarding".to_string(),
+            description: Some("  ".to_string()),
+            steps: vec![step(" Manager "), step("IT")],
+            status: None,
+        })
+        .unwrap();
+        assert_eq!(command.code, "user.onboarding");
+        assert_eq!(command.steps[0].name, "Manager");
+        assert_eq!((command.status, command.description), (1, None));
+
+        let Err(ServiceError::InvalidFields(fields)) = normalize_definition(DefinitionPayload {
+            code: "User Onboarding".to_string(),
+            name: String::new(),
+            description: None,
+            steps: vec![step(""), step("IT")],
+            status: Some(3),
+        }) else {
+            panic!("expected field errors");
+        };
+        let fields: Vec<_> = fields.iter().map(|f| f.field.as_str()).collect();
+        assert_eq!(fields, vec!["code", "name", "steps[0].name", "status"]);
+    }
+}
@@ -0,0 +1,291 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// One approval step; a member of `approver_role_id` decides it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowStep {
+    pub name: String,
+    pub approver_role_id: i64,
+}
+
+/// Instance lifecycle stored in `workflow_instances.status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstanceStatus {
+    Running,
+    /// Every step approved.
+    Approved,
+    /// Rejected at some step.
+    Rejected,
+    /// Withdrawn by the starter while running.
+    Cancelled,
+}
+
+impl InstanceStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            InstanceStatus::Running => "running",
+            InstanceStatus::Approved => "approved",
+            InstanceStatus::Rejected => "rejected",
+            InstanceStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "running" => Some(InstanceStatus::Running),
+            "approved" => Some(InstanceStatus::Approved),
+            "rejected" => Some(InstanceStatus::Rejected),
+            "cancelled" => Some(InstanceStatus::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+/// Task lifecycle stored in `workflow_tasks.status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskStatus {
+    Pending,
+    Approved,
+    Rejected,
+    /// The instance was cancelled before this task was decided.
+    Cancelled,
+}
+
+impl TaskStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TaskStatus::Pending => "pending",
+            TaskStatus::Approved => "approved",
+            TaskStatus::Rejected => "rejected",
+            TaskStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// Workflow definition row as read from the database.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DefinitionRow {
+    pub id: i64,
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub steps: String,
+    pub status: i16,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Workflow definition for list display.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefinitionItemResp {
+    pub id: i64,
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub steps: Vec<WorkflowStep>,
+    pub status: i16,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<DefinitionRow> for DefinitionItemResp {
+    type Error = serde_json::Error;
+
+    fn try_from(row: DefinitionRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id,
+            code: row.code,
+            name: row.name,
+            description: row.description,
+            steps: serde_json::from_str(&row.steps)?,
+            status: row.status,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+/// Definition query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefinitionQuery {
+    pub current: Option<i64>,
+    pub page_size: Option<i64>,
+    pub name: Option<String>,
+    pub status: Option<String>,
+}
+
+/// Filters for the definition list.
+#[derive(Debug, Clone)]
+pub struct DefinitionListQuery {
+    pub name: Option<String>,
+    pub status: Option<i16>,
+}
+
+/// Create or update definition request. `code` is how callers start an instance.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefinitionPayload {
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub steps: Vec<WorkflowStep>,
+    pub status: Option<i16>,
+}
+
+/// Validated definition fields written by create and update.
+#[derive(Debug, Clone)]
+pub struct DefinitionCommand {
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub steps: Vec<WorkflowStep>,
+    pub status: i16,
+}
+
+/// Start instance request.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartInstanceRequest {
+    pub definition_code: String,
+    pub title: String,
+    /// What is being requested, e.g. `{"username": "new.hire"}`; shown to approvers.
+    pub data: Option<serde_json::Value>,
+}
+
+/// Instance row as read from the database.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct InstanceRow {
+    pub id: i64,
+    pub definition_id: i64,
+    pub definition_code: String,
+    pub title: String,
+    pub data: String,
+    pub steps: String,
+    pub current_step: i64,
+    pub status: String,
+    pub started_by: i64,
+    pub started_by_name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Workflow instance for list display.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceItemResp {
+    pub id: i64,
+    pub definition_code: String,
+    pub title: String,
+    pub data: serde_json::Value,
+    pub steps: Vec<WorkflowStep>,
+    /// Index into `steps` of the step being decided, or of the last one reached.
+    pub current_step: i64,
+    pub status: String,
+    pub started_by: i64,
+    pub started_by_name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<InstanceRow> for InstanceItemResp {
+    type Error = serde_json::Error;
+
+    fn try_from(row: InstanceRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id,
+            definition_code: row.definition_code,
+            title: row.title,
+            data: serde_json::from_str(&row.data)?,
+            steps: serde_json::from_str(&row.steps)?,
+            current_step: row.current_step,
+            status: row.status,
+            started_by: row.started_by,
+            started_by_name: row.started_by_name,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            finished_at: row.finished_at,
+        })
+    }
+}
+
+/// Instance with every task reached so far, oldest first.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceDetailResp {
+    #[serde(flatten)]
+    pub instance: InstanceItemResp,
+    pub tasks: Vec<TaskResp>,
+}
+
+/// Instance query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceQuery {
+    pub current: Option<i64>,
+    pub page_size: Option<i64>,
+    /// `running`, `approved`, `rejected`, `cancelled`, or `all`.
+    pub status: Option<String>,
+    pub definition_code: Option<String>,
+}
+
+/// Filters for the instance lists.
+#[derive(Debug, Clone)]
+pub struct InstanceListQuery {
+    pub status: Option<&'static str>,
+    pub definition_code: Option<String>,
+    /// Restricts the list to one starter, for `/instances/mine`.
+    pub started_by: Option<i64>,
+}
+
+/// One step decision, pending or made.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskResp {
+    pub id: i64,
+    pub instance_id: i64,
+    pub step_index: i64,
+    pub step_name: String,
+    pub approver_role_id: i64,
+    pub status: String,
+    pub decided_by: Option<i64>,
+    pub decided_by_name: Option<String>,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub decided_at: Option<DateTime<Utc>>,
+}
+
+/// Pending task joined with its instance, for the inbox.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct InboxTaskResp {
+    pub id: i64,
+    pub instance_id: i64,
+    pub step_index: i64,
+    pub step_name: String,
+    pub approver_role_id: i64,
+    pub definition_code: String,
+    pub title: String,
+    pub started_by: i64,
+    pub started_by_name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Inbox query parameters
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboxQuery {
+    pub current: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+/// Approve or reject request.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecideTaskPayload {
+    pub comment: Option<String>,
+}
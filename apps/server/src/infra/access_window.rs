@@ -0,0 +1,159 @@
+//! Network and time limits on when a role's grants apply.
+//!
+//! A role with an [`AccessWindow`] only grants its capabilities to requests from one of the
+//! allowed CIDR ranges, on an allowed weekday, between the start and end times in
+//! `RUSTZEN_TIMEZONE`. Each part left empty allows everything.
+
+use chrono::{DateTime, Datelike, NaiveTime, TimeZone};
+use std::{fmt, net::IpAddr, str::FromStr};
+
+/// An IPv4 or IPv6 range in CIDR notation, e.g. `10.0.0.0/8`; a bare address is one host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpRange {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl IpRange {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        // IPv4 clients reach dual-stack listeners as IPv4-mapped IPv6 addresses.
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+            IpAddr::V4(_) => ip,
+        };
+        match (self.network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(self.prefix)).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(self.prefix)).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpRange {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let (address, prefix) = value.split_once('/').unwrap_or((value, ""));
+        let network: IpAddr =
+            address.parse().map_err(|_| format!("{} is not an IP address or CIDR range", value))?;
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            "" => max,
+            prefix => prefix
+                .parse::<u8>()
+                .ok()
+                .filter(|prefix| *prefix <= max)
+                .ok_or_else(|| format!("{} has a prefix outside 0..={}", value, max))?,
+        };
+        Ok(Self { network, prefix })
+    }
+}
+
+impl fmt::Display for IpRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix)
+    }
+}
+
+/// Where and when a restricted role's grants apply.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccessWindow {
+    /// Client address ranges; empty allows any address.
+    pub cidrs: Vec<IpRange>,
+    /// ISO weekdays, 1 for Monday to 7 for Sunday; empty allows every day.
+    pub days: Vec<u8>,
+    /// Start and end of the allowed hours. An end before the start runs past midnight,
+    /// and the day check applies to the day the window started.
+    pub hours: Option<(NaiveTime, NaiveTime)>,
+}
+
+impl AccessWindow {
+    /// Whether a request from `client_ip` at `now` may use the role. An unknown address
+    /// only passes when no ranges are set.
+    pub fn allows<Tz: TimeZone>(&self, client_ip: Option<IpAddr>, now: &DateTime<Tz>) -> bool {
+        let network_ok = self.cidrs.is_empty()
+            || client_ip.is_some_and(|ip| self.cidrs.iter().any(|range| range.contains(ip)));
+        network_ok && self.allows_time(now)
+    }
+
+    fn allows_time<Tz: TimeZone>(&self, now: &DateTime<Tz>) -> bool {
+        let time = now.time();
+        let weekday = now.weekday().number_from_monday() as u8;
+        let day_ok = |day: u8| self.days.is_empty() || self.days.contains(&day);
+        match self.hours {
+            None => day_ok(weekday),
+            Some((start, end)) if start <= end => day_ok(weekday) && start <= time && time < end,
+            Some((start, end)) => {
+                let yesterday = if weekday == 1 { 7 } else { weekday - 1 };
+                (time >= start && day_ok(weekday)) || (time < end && day_ok(yesterday))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AccessWindow, IpRange};
+    use chrono::{NaiveTime, TimeZone, Utc};
+    use std::net::IpAddr;
+
+    fn ip(value: &str) -> Option<IpAddr> {
+        Some(value.parse().unwrap())
+    }
+
+    #[test]
+    fn ranges_parse_cidrs_and_hosts_and_match_mapped_addresses() {
+        let office: IpRange = "10.20.0.0/16".parse().unwrap();
+        assert!(office.contains("10.20.5.1".parse().unwrap()));
+        assert!(office.contains("::ffff:10.20.5.1".parse().unwrap()));
+        assert!(!office.contains("10.21.0.1".parse().unwrap()));
+        assert_eq!(office.to_string(), "10.20.0.0/16");
+        let host: IpRange = "2001:db8::1".parse().unwrap();
+        assert_eq!(host.to_string(), "2001:db8::1/128");
+        assert!(!host.contains("2001:db8::2".parse().unwrap()));
+        let anywhere: IpRange = "0.0.0.0/0".parse().unwrap();
+        assert!(anywhere.contains("203.0.113.9".parse().unwrap()));
+        assert!("10.0.0.0/33".parse::<IpRange>().is_err());
+        assert!("office".parse::<IpRange>().is_err());
+    }
+
+    #[test]
+    fn windows_check_network_weekday_and_hours() {
+        let hour = |h| NaiveTime::from_hms_opt(h, 0, 0).unwrap();
+        let work_hours = AccessWindow {
+            cidrs: vec!["10.0.0.0/8".parse().unwrap()],
+            days: vec![1, 2, 3, 4, 5],
+            hours: Some((hour(9), hour(18))),
+        };
+        // 2026-10-16 is a Friday.
+        let friday_10 = Utc.with_ymd_and_hms(2026, 10, 16, 10, 0, 0).unwrap();
+        assert!(work_hours.allows(ip("10.1.2.3"), &friday_10));
+        assert!(!work_hours.allows(ip("192.0.2.1"), &friday_10));
+        assert!(!work_hours.allows(None, &friday_10));
+        assert!(
+            !work_hours
+                .allows(ip("10.1.2.3"), &Utc.with_ymd_and_hms(2026, 10, 16, 18, 0, 0).unwrap())
+        );
+        assert!(
+            !work_hours
+                .allows(ip("10.1.2.3"), &Utc.with_ymd_and_hms(2026, 10, 17, 10, 0, 0).unwrap())
+        );
+
+        let night_shift =
+            AccessWindow { days: vec![7], hours: Some((hour(22), hour(6))), ..Default::default() };
+        let sunday_23 = Utc.with_ymd_and_hms(2026, 10, 18, 23, 0, 0).unwrap();
+        let monday_5 = Utc.with_ymd_and_hms(2026, 10, 19, 5, 0, 0).unwrap();
+        let monday_23 = Utc.with_ymd_and_hms(2026, 10, 19, 23, 0, 0).unwrap();
+        assert!(night_shift.allows(None, &sunday_23));
+        assert!(night_shift.allows(None, &monday_5));
+        assert!(!night_shift.allows(None, &monday_23));
+        assert!(AccessWindow::default().allows(None, &monday_23));
+    }
+}
@@ -4,103 +4,314 @@ use crate::{
         account::account_routes,
         auth::{protected_auth_routes, public_auth_routes},
         dashboard::dashboard_routes,
-        manage::{
-            deploy::service::DeployService, manage_routes, task::service::TaskService,
+        manage::{deploy::service::DeployService, manage_routes, task::service::TaskService},
+        system::{
+            confirmation::service::CONFIRMATION_HEADER, debug_capture::service::DebugCaptures,
+            export_job::public_export_routes, feature_flag::service::FeatureFlags,
+            jwt_key::service::JwtKeyService, license::service::LicenseService, system_routes,
+            webhook::service::WebhookService,
         },
-        system::system_routes,
+        workflow::workflow_routes,
     },
     infra::{
-        auth_runtime::{ServerAuthContextLoader, jwt_codec},
-        config::CONFIG,
-        db::{create_default_pool, run_migrations, test_connection},
+        auth_runtime::{PUBLIC_ROUTES, ServerAuthContextLoader, jwt_codec},
+        config::AppConfig,
+        db::{create_default_pool, init_read_pool, prepare_schema, test_connection},
+        dev_proxy::proxy_to_dev_server,
+        error_report::install_panic_hook,
+        events, geoip,
+        host_metrics::HostMetrics,
+        http_client,
+        log_writer::LOG_WRITER,
+        mail,
         permission::PermissionService,
+        session::CSRF_HEADER,
+        system_info::SystemUtils,
+        tls::load_tls_config,
+    },
+    middleware::{
+        body_limit::payload_too_large_response,
+        csrf::csrf_middleware,
+        error_report::{REQUEST_ID_HEADER, error_report_middleware},
+        locale::locale_middleware,
+        log::log_middleware,
+        request_metrics::request_metrics_middleware,
+        security_headers::security_header_layers,
+        static_cache::static_cache_middleware,
     },
-    middleware::log::log_middleware,
 };
 
 use axum::{
-    Extension,
-    Router,
+    Extension, Router,
+    extract::DefaultBodyLimit,
     http::{
-        HeaderValue, Method,
-        header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE},
+        HeaderName, HeaderValue, Method, Uri,
+        header::{ACCEPT, ACCEPT_LANGUAGE, AUTHORIZATION, CONTENT_TYPE},
     },
     middleware,
     routing::get,
 };
-use rustzen_core::auth::auth_middleware;
+use rustzen_core::{
+    auth::auth_middleware,
+    permission::{RouterExt, take_route_permissions},
+};
 use serde_json::json;
-use std::net::SocketAddr;
+use sqlx::SqlitePool;
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 use tower_http::{
-    cors::CorsLayer,
+    cors::{AllowOrigin, CorsLayer},
     services::{ServeDir, ServeFile},
 };
 
+/// How long TLS connections get to finish after a shutdown signal.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
 #[tracing::instrument(name = "run_server")]
-pub async fn run_server() -> Result<(), Box<dyn std::error::Error>> {
+pub async fn run_server(config: &'static AppConfig) -> Result<(), Box<dyn std::error::Error>> {
+    SystemUtils::mark_started();
+    HostMetrics::spawn_sampler();
     tracing::info!("Initializing database connection pool...");
-    let pool = create_default_pool().await?;
-    run_migrations(&pool).await?;
+    let pool = create_default_pool(config).await?;
+    prepare_schema(config, &pool).await?;
     test_connection(&pool).await?;
-    let task_service = std::sync::Arc::new(TaskService::new(pool.clone())?);
+    init_read_pool(config).await?;
+    let task_service = Arc::new(TaskService::new(config, pool.clone())?);
     task_service.bootstrap().await?;
-    let deploy_service = std::sync::Arc::new(DeployService::new(pool.clone()));
+    let deploy_service = Arc::new(DeployService::new(config, pool.clone()));
+    WebhookService::spawn_worker(pool.clone());
+    events::spawn_relay(pool.clone());
+    LOG_WRITER.spawn(pool.clone());
+    JwtKeyService::start(config, pool.clone()).await?;
+    LicenseService::load(config);
+    install_panic_hook(config);
+    FeatureFlags::start(config, pool.clone()).await?;
+    DebugCaptures::start(pool.clone()).await?;
+
+    let app = build_router(config, pool.clone(), task_service, deploy_service)?
+        .into_make_service_with_connect_info::<SocketAddr>();
+    PermissionService::sync_permissions(&pool).await?;
+    spawn_grpc_server(config, &pool);
+
+    let addr = server_addr(config);
+    let listener = tokio::net::TcpListener::bind(&addr).await?;
+
+    if let Some((cert_path, key_path)) = config.tls_paths() {
+        let tls_config = load_tls_config(cert_path, key_path).await?;
+        let handle = axum_server::Handle::new();
+        let shutdown = handle.clone();
+        tokio::spawn(async move {
+            shutdown_signal().await;
+            shutdown.graceful_shutdown(Some(SHUTDOWN_GRACE));
+        });
+        tracing::info!("Server started successfully, listening on https://{}", addr);
+        axum_server::from_tcp_rustls(listener.into_std()?, tls_config)
+            .handle(handle)
+            .serve(app)
+            .await?;
+    } else {
+        tracing::info!("Server started successfully, listening on http://{}", addr);
+        axum::serve(listener, app).with_graceful_shutdown(shutdown_signal()).await?;
+    }
+    LOG_WRITER.shutdown(&pool).await;
+
+    Ok(())
+}
+
+/// Resolves on Ctrl+C or, on Unix, SIGTERM.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{SignalKind, signal};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+    tracing::info!("Shutdown signal received, finishing in-flight requests");
+}
+
+/// Assembles the HTTP app: public and protected API, uploaded files, and the SPA fallback,
+/// all behind CORS and the security headers.
+///
+/// Route permissions are registered while the routers are built, so run
+/// `PermissionService::sync_permissions` after this.
+pub fn build_router(
+    config: &'static AppConfig,
+    pool: SqlitePool,
+    task_service: Arc<TaskService>,
+    deploy_service: Arc<DeployService>,
+) -> Result<Router, Box<dyn std::error::Error>> {
+    geoip::open(config);
+    mail::configure(config);
+    http_client::configure(config);
 
     let cors = CorsLayer::new()
-        .allow_origin(HeaderValue::from_static("*"))
+        .allow_origin(cors_allow_origin(config)?)
         .allow_methods([Method::GET, Method::POST, Method::PUT, Method::PATCH, Method::DELETE])
-        .allow_headers([CONTENT_TYPE, AUTHORIZATION, ACCEPT]);
+        .allow_headers([
+            CONTENT_TYPE,
+            AUTHORIZATION,
+            ACCEPT,
+            ACCEPT_LANGUAGE,
+            HeaderName::from_static(CSRF_HEADER),
+            HeaderName::from_static(CONFIRMATION_HEADER),
+            REQUEST_ID_HEADER,
+        ])
+        .expose_headers([REQUEST_ID_HEADER]);
 
     let protected_api = Router::new()
-        .nest("/account", account_routes())
-        .nest("/auth", protected_auth_routes())
-        .nest("/dashboard", dashboard_routes())
-        .nest("/manage", manage_routes())
-        .nest("/system", system_routes())
+        .nest_routes("/account", || account_routes(config))
+        .nest_routes("/auth", protected_auth_routes)
+        .nest_routes("/dashboard", dashboard_routes)
+        .nest_routes("/manage", manage_routes)
+        .nest_routes("/system", system_routes)
+        .nest_routes("/workflow", workflow_routes)
         .layer(Extension(task_service))
         .layer(Extension(deploy_service))
-        .route_layer(middleware::from_fn_with_state(pool.clone(), log_middleware))
-        .route_layer(middleware::from_fn_with_state(
-            (jwt_codec(), ServerAuthContextLoader::new(pool.clone())),
-            auth_middleware,
-        ));
+        .layer(Extension(config))
+        .route_layer(middleware::from_fn_with_state(config, error_report_middleware))
+        .route_layer(middleware::from_fn_with_state((pool.clone(), config), log_middleware));
 
-    let public_api = Router::new().nest("/auth", public_auth_routes());
-
-    PermissionService::sync_permissions(&pool).await?;
+    let public_api = Router::new()
+        .nest_routes("/auth", public_auth_routes)
+        .nest_routes("/files/exports", public_export_routes)
+        .layer(Extension(config))
+        .route_layer(middleware::from_fn_with_state(config, error_report_middleware));
+    // Every API route goes through auth; only `PUBLIC_ROUTES` pass without a token.
+    let api = public_api.merge(protected_api).route_layer(middleware::from_fn_with_state(
+        (jwt_codec(config), ServerAuthContextLoader::new(pool.clone(), config)),
+        auth_middleware,
+    ));
+    log_route_map();
+    let slow_threshold =
+        (config.log.slow_request_ms > 0).then(|| Duration::from_millis(config.log.slow_request_ms));
+    let api =
+        api.route_layer(middleware::from_fn_with_state(slow_threshold, request_metrics_middleware));
 
-    let uploads_prefix = CONFIG.files_prefix.clone();
-    let avatars_prefix = CONFIG.avatars_prefix();
+    let uploads_prefix = config.server.files_prefix.clone();
+    let avatars_prefix = config.avatars_prefix();
     let uploads_service =
-        ServeDir::new(CONFIG.uploads_dir()).append_index_html_on_directories(true);
+        ServeDir::new(config.uploads_dir()).append_index_html_on_directories(true);
     let avatars_service =
-        ServeDir::new(CONFIG.avatars_dir()).append_index_html_on_directories(true);
-    let static_dir = CONFIG.web_dist_dir();
-    let index_path = static_dir.join("index.html");
-
-    tracing::info!(?static_dir, "Serving frontend assets from static dir");
+        ServeDir::new(config.avatars_dir()).append_index_html_on_directories(true);
+    let web_service = web_service(config)?;
 
     let app = Router::new()
         .route("/api/summary", get(summary))
-        .nest("/api", public_api.merge(protected_api))
+        .nest(
+            "/api",
+            api.layer(DefaultBodyLimit::max(config.server.request_body_limit))
+                .layer(middleware::map_response(payload_too_large_response))
+                .layer(middleware::from_fn_with_state(config, csrf_middleware))
+                .layer(middleware::from_fn(locale_middleware)),
+        )
         .nest_service(&avatars_prefix, avatars_service)
         .nest_service(&uploads_prefix, uploads_service)
         .layer(cors)
         .with_state(pool)
-        .fallback_service(ServeDir::new(static_dir).not_found_service(ServeFile::new(index_path)))
-        .into_make_service_with_connect_info::<SocketAddr>();
+        .fallback_service(web_service);
+    let app = security_header_layers(config)?.into_iter().fold(app, Router::layer);
 
-    let addr = server_addr();
-    let listener = tokio::net::TcpListener::bind(&addr).await?;
-    tracing::info!("Server started successfully, listening on http://{}", addr);
+    Ok(app)
+}
+
+/// Logs which API routes are public and which capabilities the guarded ones need, for
+/// security review. Routes in neither list only need a signed-in user.
+fn log_route_map() {
+    for pattern in PUBLIC_ROUTES.patterns() {
+        tracing::info!(route = pattern, "Public route");
+    }
+    for route in take_route_permissions() {
+        let route_path = format!("/api{}", route.path);
+        tracing::info!(route = route_path, requires = route.check.description(), "Guarded route");
+    }
+}
 
-    axum::serve(listener, app).await?;
+/// The web UI: built files from `web/dist`, or the Vite dev server behind
+/// `RUSTZEN_WEB_DEV_PROXY`.
+fn web_service(config: &AppConfig) -> Result<Router, Box<dyn std::error::Error>> {
+    if let Some(url) = &config.server.web_dev_proxy {
+        let upstream = url.parse::<Uri>()?;
+        tracing::info!(%upstream, "Proxying frontend requests to the web dev server");
+        return Ok(
+            Router::new().fallback(move |request| proxy_to_dev_server(upstream.clone(), request))
+        );
+    }
 
-    Ok(())
+    let static_dir = config.web_dist_dir();
+    let index_path = static_dir.join("index.html");
+    tracing::info!(?static_dir, "Serving frontend assets from static dir");
+    // Prefer the `.br`/`.gz` files written next to each asset by the web build.
+    let index_service = ServeFile::new(index_path).precompressed_br().precompressed_gzip();
+    Ok(Router::new()
+        .fallback_service(
+            ServeDir::new(static_dir)
+                .precompressed_br()
+                .precompressed_gzip()
+                .fallback(index_service),
+        )
+        .layer(middleware::from_fn(static_cache_middleware)))
+}
+
+fn cors_allow_origin(config: &AppConfig) -> Result<AllowOrigin, Box<dyn std::error::Error>> {
+    let origins = config.cors_origins();
+    if origins.contains(&"*") {
+        return Ok(AllowOrigin::any());
+    }
+
+    let origins = origins.into_iter().map(HeaderValue::from_str).collect::<Result<Vec<_>, _>>()?;
+    Ok(AllowOrigin::list(origins))
+}
+
+/// Starts the gRPC listener next to HTTP when `RUSTZEN_GRPC_PORT` is set.
+#[cfg(feature = "grpc")]
+fn spawn_grpc_server(config: &'static AppConfig, pool: &SqlitePool) {
+    let (Some(port), Some(api_key)) = (config.server.grpc_port, config.server.grpc_api_key.clone())
+    else {
+        return;
+    };
+    let addr = match format!("{}:{}", config.server.app_host, port).parse::<SocketAddr>() {
+        Ok(addr) => addr,
+        Err(e) => {
+            tracing::error!("Invalid gRPC listen address: {}", e);
+            return;
+        }
+    };
+    let pool = pool.clone();
+    tokio::spawn(async move {
+        if let Err(e) = crate::infra::grpc::serve(config, pool, addr, api_key).await {
+            tracing::error!("gRPC server stopped: {}", e);
+        }
+    });
+}
+
+#[cfg(not(feature = "grpc"))]
+fn spawn_grpc_server(config: &AppConfig, _pool: &SqlitePool) {
+    if config.server.grpc_port.is_some() {
+        tracing::warn!("RUSTZEN_GRPC_PORT is set but this build lacks the `grpc` feature");
+    }
 }
 
-fn server_addr() -> String {
-    format!("{}:{}", CONFIG.app_host, CONFIG.app_port)
+fn server_addr(config: &AppConfig) -> String {
+    format!("{}:{}", config.server.app_host, config.server.app_port)
 }
 
 async fn summary() -> AppResult<serde_json::Value> {
@@ -1,33 +1,84 @@
 use crate::{
     common::error::ServiceError,
-    features::auth::{repo::AuthRepository, types::AuthUserRow},
-    infra::{config::CONFIG, permission::PermissionService},
+    features::auth::{repo::AuthRepository, service::AuthService, types::AuthUserRow},
+    infra::{config::AppConfig, permission::PermissionService, single_session},
 };
 
 use async_trait::async_trait;
-use once_cell::sync::Lazy;
+use once_cell::sync::OnceCell;
 use rustzen_core::{
-    auth::{AuthClaims, AuthContextLoader, CurrentUser, JwtCodec},
-    capability::SYSTEM_WILDCARD,
+    auth::{
+        AuthClaims, AuthContextLoader, CurrentUser, JwtCodec, JwtKey, JwtKeyring, PublicRoutes,
+    },
     error::CoreError,
 };
+use sha2::{Digest, Sha256};
 use sqlx::SqlitePool;
+use std::net::IpAddr;
 
-static JWT_CODEC: Lazy<JwtCodec> =
-    Lazy::new(|| JwtCodec::new(CONFIG.jwt_secret.clone(), CONFIG.jwt_expiration));
+static JWT_CODEC: OnceCell<JwtCodec> = OnceCell::new();
 
-pub fn jwt_codec() -> JwtCodec {
-    JWT_CODEC.clone()
+/// The process-wide codec. It starts with `RUSTZEN_JWT_SECRET` alone; `JwtKeyService::start`
+/// installs the full keyring at startup and keeps it current. Clones share the keyring.
+pub fn jwt_codec(config: &AppConfig) -> JwtCodec {
+    JWT_CODEC
+        .get_or_init(|| {
+            let secret = &config.jwt.jwt_secret;
+            let key = JwtKey::hmac(hmac_kid(secret), secret.as_bytes());
+            JwtCodec::with_keyring(JwtKeyring::new(key), config.jwt.jwt_expiration)
+        })
+        .clone()
+}
+
+/// `kid` of an HS256 key, derived from the secret so it is stable across restarts.
+pub fn hmac_kid(secret: &str) -> String {
+    format!("hs-{}", fingerprint(secret.as_bytes()))
+}
+
+/// `kid` of an RS256 key, derived from its public PEM.
+pub fn rsa_kid(public_pem: &[u8]) -> String {
+    format!("rs-{}", fingerprint(public_pem))
+}
+
+fn fingerprint(material: &[u8]) -> String {
+    Sha256::digest(material).iter().take(6).map(|byte| format!("{:02x}", byte)).collect()
 }
 
+/// Every API route served without a token. Auth runs in front of the whole API, so a
+/// public route missing here answers 401 instead of opening up by accident.
+pub const PUBLIC_ROUTES: PublicRoutes = PublicRoutes::new(&[
+    "/api/auth/login",
+    "/api/auth/login/sms",
+    "/api/auth/login/sms/code",
+    "/api/auth/csrf",
+    "/api/auth/confirm-email",
+    "/api/auth/register",
+    "/api/auth/register/verify",
+    "/api/auth/oauth/providers",
+    "/api/auth/oauth/{provider}/authorize",
+    "/api/auth/oauth/callback",
+    "/api/files/exports/{name}",
+]);
+
 #[derive(Debug, Clone)]
 pub struct ServerAuthContextLoader {
     pool: SqlitePool,
+    config: &'static AppConfig,
 }
 
 #[async_trait]
 impl AuthContextLoader for ServerAuthContextLoader {
     async fn load_current_user(&self, claims: &AuthClaims) -> Result<CurrentUser, CoreError> {
+        if self.config.auth.single_session {
+            let active =
+                single_session::is_active(&self.pool, claims.user_id, claims.sid.as_deref())
+                    .await
+                    .map_err(|_| CoreError::MissingAuthContext)?;
+            if !active {
+                tracing::info!(user_id = claims.user_id, "Refused token of a replaced session");
+                return Err(CoreError::InvalidToken);
+            }
+        }
         if let Ok(current_user) =
             PermissionService::load_current_user(claims.user_id, &claims.username)
         {
@@ -39,11 +90,28 @@ impl AuthContextLoader for ServerAuthContextLoader {
             _ => CoreError::MissingAuthContext,
         })
     }
+
+    fn scope_to_request(
+        &self,
+        current_user: CurrentUser,
+        client_ip: Option<IpAddr>,
+    ) -> CurrentUser {
+        PermissionService::scope_to_client(self.config, current_user, client_ip)
+    }
+
+    fn session_cookie_name(&self) -> Option<&str> {
+        let auth = &self.config.auth;
+        auth.session_cookie.then_some(auth.session_cookie_name.as_str())
+    }
+
+    fn public_routes(&self) -> &PublicRoutes {
+        &PUBLIC_ROUTES
+    }
 }
 
 impl ServerAuthContextLoader {
-    pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+    pub fn new(pool: SqlitePool, config: &'static AppConfig) -> Self {
+        Self { pool, config }
     }
 
     async fn load_from_db(
@@ -53,10 +121,7 @@ impl ServerAuthContextLoader {
         let user = AuthRepository::find_user_by_id(pool, claims.user_id).await?;
         let AuthUserRow { id, username, .. } = user.ok_or(ServiceError::InvalidToken)?;
 
-        let permissions = AuthRepository::get_user_permissions(pool, claims.user_id).await?;
-        let is_super = permissions.iter().any(|permission| permission == SYSTEM_WILDCARD);
-        PermissionService::cache_user_permissions(id, &permissions);
-
-        Ok(CurrentUser::new(id, username, permissions, is_super))
+        AuthService::cache_user_permissions(pool, id).await?;
+        PermissionService::load_current_user(id, &username)
     }
 }
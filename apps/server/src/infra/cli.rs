@@ -0,0 +1,218 @@
+//! Operator commands that run against the database without starting the HTTP server.
+
+use crate::{
+    features::system::{
+        seed::service::{DEMO_PASSWORD, SeedService},
+        user::service::UserService,
+    },
+    infra::{
+        config::AppConfig,
+        db::{create_default_pool, prepare_schema, run_migrations},
+        permission::PermissionService,
+    },
+};
+
+use sqlx::SqlitePool;
+use std::io::{self, BufRead, IsTerminal};
+
+pub const USAGE: &str = "Usage:
+  rustzen-admin                         Start the HTTP server
+  rustzen-admin --migrate-only          Apply database migrations and exit
+  rustzen-admin user create-admin --username <name> --email <email> [--password <password>]
+  rustzen-admin user reset-password --username <name> [--password <password>]
+  rustzen-admin db seed [--demo]        Apply migrations and built-in roles; --demo adds demo users and dicts
+
+When --password is omitted it is prompted for without echo, or read from stdin when piped.";
+
+/// A parsed command-line invocation.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Serve,
+    Help,
+    MigrateOnly,
+    CreateAdmin { username: String, email: String, password: Option<String> },
+    ResetPassword { username: String, password: Option<String> },
+    Seed { demo: bool },
+}
+
+impl Command {
+    /// Parse process arguments, excluding the program name.
+    pub fn parse<I>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let args = args.into_iter().collect::<Vec<_>>();
+        let words = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+        match words.as_slice() {
+            [] => Ok(Self::Serve),
+            ["--help" | "-h" | "help"] => Ok(Self::Help),
+            ["--migrate-only"] => Ok(Self::MigrateOnly),
+            ["db", "seed"] => Ok(Self::Seed { demo: false }),
+            ["db", "seed", "--demo"] => Ok(Self::Seed { demo: true }),
+            ["user", "create-admin", flags @ ..] => {
+                let mut flags = Flags::parse(flags, &["--username", "--email", "--password"])?;
+                Ok(Self::CreateAdmin {
+                    username: flags.require("--username")?,
+                    email: flags.require("--email")?,
+                    password: flags.take("--password"),
+                })
+            }
+            ["user", "reset-password", flags @ ..] => {
+                let mut flags = Flags::parse(flags, &["--username", "--password"])?;
+                Ok(Self::ResetPassword {
+                    username: flags.require("--username")?,
+                    password: flags.take("--password"),
+                })
+            }
+            _ => Err(format!("Unknown command: {}", words.join(" "))),
+        }
+    }
+}
+
+struct Flags(Vec<(String, String)>);
+
+impl Flags {
+    fn parse(args: &[&str], allowed: &[&str]) -> Result<Self, String> {
+        let mut values = Vec::new();
+        let mut args = args.iter();
+        while let Some(flag) = args.next() {
+            if !allowed.contains(flag) {
+                return Err(format!("Unknown option: {}", flag));
+            }
+            let value = args.next().ok_or_else(|| format!("Missing value for {}", flag))?;
+            values.push((flag.to_string(), value.to_string()));
+        }
+        Ok(Self(values))
+    }
+
+    fn take(&mut self, flag: &str) -> Option<String> {
+        let index = self.0.iter().position(|(name, _)| name == flag)?;
+        Some(self.0.remove(index).1)
+    }
+
+    fn require(&mut self, flag: &str) -> Result<String, String> {
+        self.take(flag).ok_or_else(|| format!("Missing required option {}", flag))
+    }
+}
+
+/// Run a non-server command to completion.
+///
+/// # Errors
+///
+/// Returns an error for `Serve` and `Help`, which `main` handles itself, or when a
+/// database or account operation fails.
+pub async fn run(
+    config: &'static AppConfig,
+    command: Command,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let pool = create_default_pool(config).await?;
+    let result = execute(config, &pool, command).await;
+    pool.close().await;
+    result
+}
+
+async fn execute(
+    config: &AppConfig,
+    pool: &SqlitePool,
+    command: Command,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        Command::Serve | Command::Help => {
+            return Err(format!("{:?} is not an operator command", command).into());
+        }
+        Command::MigrateOnly => {
+            run_migrations(pool).await?;
+            println!("Database migrations applied.");
+        }
+        Command::Seed { demo: false } => {
+            prepare_schema(config, pool).await?;
+            PermissionService::sync_permissions(pool).await?;
+            println!("Built-in roles and capabilities seeded.");
+        }
+        Command::Seed { demo: true } => {
+            prepare_schema(config, pool).await?;
+            let report = SeedService::seed_demo(config, pool).await?;
+            println!(
+                "Demo data seeded: {} user(s), {} dict item(s). Demo password: {}",
+                report.users_created, report.dicts_created, DEMO_PASSWORD
+            );
+        }
+        Command::CreateAdmin { username, email, password } => {
+            prepare_schema(config, pool).await?;
+            PermissionService::sync_permissions(pool).await?;
+            let password = password_or_prompt(password)?;
+            let user_id =
+                UserService::create_owner_user(config, pool, username, email, password).await?;
+            println!("Created owner account (user id {}).", user_id);
+        }
+        Command::ResetPassword { username, password } => {
+            prepare_schema(config, pool).await?;
+            let password = password_or_prompt(password)?;
+            let user_id =
+                UserService::reset_password_by_username(config, pool, &username, &password).await?;
+            println!(
+                "Password reset and account re-enabled for {} (user id {}).",
+                username, user_id
+            );
+        }
+    }
+    Ok(())
+}
+
+fn password_or_prompt(password: Option<String>) -> io::Result<String> {
+    if let Some(password) = password {
+        return Ok(password);
+    }
+
+    if io::stdin().is_terminal() {
+        return rpassword::prompt_password("Password: ");
+    }
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Command;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        Command::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_server_and_maintenance_modes() {
+        assert_eq!(parse(&[]), Ok(Command::Serve));
+        assert_eq!(parse(&["--migrate-only"]), Ok(Command::MigrateOnly));
+        assert_eq!(parse(&["db", "seed"]), Ok(Command::Seed { demo: false }));
+        assert_eq!(parse(&["db", "seed", "--demo"]), Ok(Command::Seed { demo: true }));
+    }
+
+    #[test]
+    fn parses_user_commands_with_flags_in_any_order() {
+        assert_eq!(
+            parse(&["user", "create-admin", "--email", "ops@example.com", "--username", "ops"]),
+            Ok(Command::CreateAdmin {
+                username: "ops".to_string(),
+                email: "ops@example.com".to_string(),
+                password: None,
+            })
+        );
+        assert_eq!(
+            parse(&["user", "reset-password", "--username", "ops", "--password", "s3cret"]),
+            Ok(Command::ResetPassword {
+                username: "ops".to_string(),
+                password: Some("s3cret".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_commands_and_missing_values() {
+        assert!(parse(&["user", "drop"]).is_err());
+        assert!(parse(&["user", "reset-password"]).is_err());
+        assert!(parse(&["user", "reset-password", "--username"]).is_err());
+        assert!(parse(&["user", "create-admin", "--username", "ops", "--role", "x"]).is_err());
+    }
+}
@@ -1 +1,7 @@
-pub use rustzen_config::CONFIG;
+pub use rustzen_config::{AppConfig, load};
+
+/// The configuration `main` would load, for tests that skip it.
+#[cfg(test)]
+pub fn test_config() -> &'static AppConfig {
+    load().expect("RUSTZEN_* configuration")
+}
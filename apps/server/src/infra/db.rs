@@ -1,13 +1,28 @@
+use axum::extract::FromRef;
+use once_cell::sync::OnceCell;
 use rustzen_storage::{
     migration,
     sqlite::{
         DatabaseConnectionOptions, SqlitePool, connect_sqlite_with_options, database_url_from_path,
     },
 };
-use std::time::Duration;
+use serde::Serialize;
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
 use tracing;
 
-use crate::infra::config::CONFIG;
+use crate::infra::config::AppConfig;
+
+/// Upper bound for the doubling delay between startup connection attempts.
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Replica pool; unset when `RUSTZEN_SQLITE_REPLICA_PATH` is not configured.
+static READ_POOL: OnceCell<SqlitePool> = OnceCell::new();
+
+/// Connection retries taken while creating pools since the process started.
+static CONNECT_RETRIES: AtomicU32 = AtomicU32::new(0);
 
 /// Configuration for the database connection pool.
 ///
@@ -25,22 +40,63 @@ pub struct DatabaseConfig {
     pub connect_timeout: Duration,
     /// The timeout for an idle connection. `None` disables idle reaping.
     pub idle_timeout: Option<Duration>,
+    /// Statements at least this slow are logged as slow. `None` disables the check.
+    pub slow_query_threshold: Option<Duration>,
+    /// How often and how patiently to retry a failed initial connection.
+    pub retry: RetryPolicy,
+    /// Open the database read-only, for a replica.
+    pub read_only: bool,
+}
+
+/// Retries for the initial pool connection, so the server survives a database that
+/// comes up after it (a mounted volume, a replica still restoring).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts after the first one; `0` fails immediately.
+    pub retries: u32,
+    /// Delay before the first retry, doubled for each further one.
+    pub initial_backoff: Duration,
 }
 
-impl Default for DatabaseConfig {
+impl RetryPolicy {
+    /// Delay before retry number `retry` (1-based), capped at [`MAX_RETRY_BACKOFF`].
+    fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_backoff.saturating_mul(factor).min(MAX_RETRY_BACKOFF)
+    }
+}
+
+impl DatabaseConfig {
     /// Creates a database configuration from `RUSTZEN_*` runtime config.
-    fn default() -> Self {
-        let path = CONFIG.sqlite_database_path();
+    pub fn from_config(config: &AppConfig) -> Self {
+        let path = config.sqlite_database_path();
         let url = database_url_from_path(path.as_path());
 
         Self {
             url,
-            max_connections: CONFIG.db_max_conn,
-            min_connections: CONFIG.db_min_conn,
-            connect_timeout: Duration::from_secs(CONFIG.db_conn_timeout),
-            idle_timeout: db_idle_timeout(CONFIG.db_idle_timeout),
+            max_connections: config.db.db_max_conn,
+            min_connections: config.db.db_min_conn,
+            connect_timeout: Duration::from_secs(config.db.db_conn_timeout),
+            idle_timeout: db_idle_timeout(config.db.db_idle_timeout),
+            slow_query_threshold: (config.log.slow_query_ms > 0)
+                .then(|| Duration::from_millis(config.log.slow_query_ms)),
+            retry: RetryPolicy {
+                retries: config.db.db_connect_retries,
+                initial_backoff: Duration::from_millis(config.db.db_retry_backoff_ms),
+            },
+            read_only: false,
         }
     }
+
+    /// Read-only configuration for `RUSTZEN_SQLITE_REPLICA_PATH`, sized like the primary.
+    pub fn replica(config: &AppConfig) -> Option<Self> {
+        let path = config.sqlite_replica_database_path()?;
+        Some(Self {
+            url: database_url_from_path(path.as_path()),
+            read_only: true,
+            ..Self::from_config(config)
+        })
+    }
 }
 
 fn db_idle_timeout(timeout_secs: u64) -> Option<Duration> {
@@ -49,10 +105,13 @@ fn db_idle_timeout(timeout_secs: u64) -> Option<Duration> {
 
 /// Creates a new database connection pool based on the provided configuration.
 ///
+/// A failed connection is retried with exponential backoff per `config.retry`; invalid
+/// URLs and options fail straight away.
+///
 /// # Errors
 ///
-/// Returns a `sqlx::Error` if connecting to the database fails.
-#[tracing::instrument(name = "create_db_pool", skip_all)]
+/// Returns a `sqlx::Error` if connecting to the database fails after the last retry.
+#[tracing::instrument(name = "create_db_pool", skip_all, fields(read_only = config.read_only))]
 pub async fn create_pool(config: DatabaseConfig) -> Result<SqlitePool, sqlx::Error> {
     tracing::info!("Creating database connection pool...");
     tracing::debug!("Connecting to SQLite URL: {}", config.url);
@@ -61,21 +120,136 @@ pub async fn create_pool(config: DatabaseConfig) -> Result<SqlitePool, sqlx::Err
         min_connections: config.min_connections,
         connect_timeout: config.connect_timeout,
         idle_timeout: config.idle_timeout,
+        slow_statement_threshold: config.slow_query_threshold,
+        read_only: config.read_only,
+    };
+    let mut retry = 0;
+    let pool = loop {
+        match connect_sqlite_with_options(&config.url, options.clone()).await {
+            Ok(pool) => break pool,
+            Err(err @ sqlx::Error::Configuration(_)) => return Err(err),
+            Err(err) if retry < config.retry.retries => {
+                retry += 1;
+                CONNECT_RETRIES.fetch_add(1, Ordering::Relaxed);
+                let delay = config.retry.backoff(retry);
+                tracing::warn!(
+                    retry,
+                    retries = config.retry.retries,
+                    delay_ms = delay.as_millis() as u64,
+                    "Database connection failed, retrying: {}",
+                    err
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Err(err) => return Err(err),
+        }
     };
-    let pool = connect_sqlite_with_options(&config.url, options).await?;
     tracing::info!("Database connection pool created successfully.");
     Ok(pool)
 }
 
-/// Creates a new database connection pool using the default configuration.
+/// Creates a new database connection pool for the database in `config`.
 ///
 /// # Errors
 ///
 /// Returns a `sqlx::Error` if connecting to the database fails.
-#[tracing::instrument(name = "create_default_db_pool")]
-pub async fn create_default_pool() -> Result<SqlitePool, sqlx::Error> {
-    let config = DatabaseConfig::default();
-    create_pool(config).await
+#[tracing::instrument(name = "create_default_db_pool", skip_all)]
+pub async fn create_default_pool(config: &AppConfig) -> Result<SqlitePool, sqlx::Error> {
+    create_pool(DatabaseConfig::from_config(config)).await
+}
+
+/// Connects the read replica when one is configured. Call once at startup.
+///
+/// # Errors
+///
+/// Returns a `sqlx::Error` if the configured replica cannot be opened after the last retry.
+pub async fn init_read_pool(config: &AppConfig) -> Result<(), sqlx::Error> {
+    let Some(replica) = DatabaseConfig::replica(config) else {
+        return Ok(());
+    };
+    let pool = create_pool(replica).await?;
+    test_connection(&pool).await?;
+    if READ_POOL.set(pool).is_err() {
+        tracing::warn!("Read replica pool was already initialized");
+    }
+    Ok(())
+}
+
+/// Picks the pool for a repository call: writes and read-your-write lookups on the
+/// primary, lag-tolerant reads (lists, options, dashboard aggregates, exports) on the
+/// replica when one is configured.
+///
+/// Extract it in handlers with `State(db): State<DbExecutor>`; it derives from the
+/// router's `SqlitePool` state.
+#[derive(Debug, Clone)]
+pub struct DbExecutor {
+    primary: SqlitePool,
+}
+
+impl DbExecutor {
+    pub fn new(primary: SqlitePool) -> Self {
+        Self { primary }
+    }
+
+    /// Replica, falling back to the primary. Rows may trail recent writes by the replication lag.
+    pub fn read(&self) -> &SqlitePool {
+        READ_POOL.get().unwrap_or(&self.primary)
+    }
+
+    pub fn write(&self) -> &SqlitePool {
+        &self.primary
+    }
+
+    pub fn health(&self) -> DbPoolHealth {
+        DbPoolHealth {
+            primary: PoolHealth::of(&self.primary),
+            replica: READ_POOL.get().map(PoolHealth::of),
+            connect_retries: CONNECT_RETRIES.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl FromRef<SqlitePool> for DbExecutor {
+    fn from_ref(pool: &SqlitePool) -> Self {
+        Self::new(pool.clone())
+    }
+}
+
+/// Connection usage of one pool.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolHealth {
+    /// Open connections, idle or in use.
+    pub size: u32,
+    pub idle: u32,
+    pub in_use: u32,
+    pub max_connections: u32,
+    pub closed: bool,
+}
+
+impl PoolHealth {
+    fn of(pool: &SqlitePool) -> Self {
+        let size = pool.size();
+        let idle = u32::try_from(pool.num_idle()).unwrap_or(u32::MAX).min(size);
+        Self {
+            size,
+            idle,
+            in_use: size - idle,
+            max_connections: pool.options().get_max_connections(),
+            closed: pool.is_closed(),
+        }
+    }
+}
+
+/// Health of the primary and replica pools.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbPoolHealth {
+    pub primary: PoolHealth,
+    /// `None` without a configured replica.
+    pub replica: Option<PoolHealth>,
+    /// Connection retries taken while starting up.
+    pub connect_retries: u32,
 }
 
 pub use rustzen_storage::sqlite::test_connection;
@@ -93,11 +267,179 @@ pub async fn run_migrations(pool: &SqlitePool) -> Result<(), sqlx::migrate::Migr
     Ok(())
 }
 
+/// Empty in-memory database on a single connection, for unit tests.
+#[cfg(test)]
+pub async fn memory_pool() -> SqlitePool {
+    sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .expect("in-memory sqlite pool")
+}
+
+/// [`memory_pool`] with the embedded migrations applied, for tests that need the schema.
+#[cfg(test)]
+pub async fn test_pool() -> SqlitePool {
+    let pool = memory_pool().await;
+    run_migrations(&pool).await.expect("migrations");
+    pool
+}
+
+/// A view the repos select from, with the columns they read.
+struct RequiredView {
+    name: &'static str,
+    /// Migration holding the view's current `CREATE VIEW`.
+    defined_in: &'static str,
+    columns: &'static [&'static str],
+}
+
+/// Views are created by migrations; SQLite has no stored functions, so operation logs and
+/// the like are written by the repos and need no check.
+const REQUIRED_VIEWS: &[RequiredView] = &[
+    RequiredView {
+        name: "user_with_roles",
+        defined_in: "0036_user_profile_fields.sql",
+        columns: &[
+            "id",
+            "username",
+            "email",
+            "phone",
+            "password_hash",
+            "real_name",
+            "avatar_url",
+            "is_system",
+            "status",
+            "last_login_at",
+            "created_at",
+            "updated_at",
+            "profile",
+            "roles",
+        ],
+    },
+    RequiredView {
+        name: "role_with_menus",
+        defined_in: "0018_menu_link_types.sql",
+        columns: &[
+            "id",
+            "name",
+            "code",
+            "description",
+            "status",
+            "created_at",
+            "updated_at",
+            "deleted_at",
+            "is_system",
+            "menus",
+        ],
+    },
+    RequiredView {
+        name: "user_permissions",
+        defined_in: "0024_user_anonymize.sql",
+        columns: &["user_id", "username", "menu_code"],
+    },
+];
+
+/// Checks that every view the repos read exists and yields the columns they select, so a
+/// database changed outside the migrations fails at startup with the fix instead of
+/// `ColumnNotFound` on the first request.
+///
+/// # Errors
+///
+/// Returns a message naming each missing or outdated view and the migration to recreate
+/// it from.
+pub async fn verify_views(pool: &SqlitePool) -> Result<(), String> {
+    let mut problems = Vec::new();
+    for view in REQUIRED_VIEWS {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'view' AND name = ?)",
+        )
+        .bind(view.name)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("failed to inspect database views: {}", e))?;
+        let problem = if !exists {
+            Some("is missing".to_string())
+        } else {
+            let mut probe = sqlx::QueryBuilder::new("SELECT ");
+            probe.push(view.columns.join(", ")).push(" FROM ").push(view.name).push(" LIMIT 0");
+            probe.build().execute(pool).await.err().map(|e| format!("is out of date ({})", e))
+        };
+        if let Some(problem) = problem {
+            problems.push(format!(
+                "view `{}` {}; recreate it with the `CREATE VIEW` in migrations/sqlite/{}",
+                view.name, problem, view.defined_in
+            ));
+        }
+    }
+    if problems.is_empty() { Ok(()) } else { Err(problems.join("\n")) }
+}
+
+/// Applies migrations when `RUSTZEN_DB_AUTO_MIGRATE` is on; otherwise refuses to start
+/// against a schema that is behind the embedded migrations.
+///
+/// # Errors
+///
+/// Returns an error if migrating fails or migrations are pending with auto-migrate disabled.
+pub async fn prepare_schema(
+    config: &AppConfig,
+    pool: &SqlitePool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if config.db.db_auto_migrate {
+        run_migrations(pool).await?;
+        verify_views(pool).await?;
+        return Ok(());
+    }
+
+    let pending = migration::pending_migrations(pool).await?;
+    if !pending.is_empty() {
+        return Err(format!(
+            "{} database migration(s) pending ({:?}) and RUSTZEN_DB_AUTO_MIGRATE is false; run `rustzen-admin --migrate-only` first",
+            pending.len(),
+            pending
+        )
+        .into());
+    }
+    verify_views(pool).await?;
+    tracing::info!("Automatic migrations disabled; database schema is up to date.");
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
-    use super::db_idle_timeout;
+    use super::{DbExecutor, RetryPolicy, db_idle_timeout};
+    use axum::extract::FromRef;
+    use rustzen_storage::migration::pending_migrations;
     use std::time::Duration;
 
+    #[tokio::test]
+    async fn pending_migrations_clear_after_running() {
+        let pool = super::memory_pool().await;
+
+        assert!(!pending_migrations(&pool).await.expect("pending before").is_empty());
+        super::run_migrations(&pool).await.expect("migrations");
+        assert!(pending_migrations(&pool).await.expect("pending after").is_empty());
+    }
+
+    #[tokio::test]
+    async fn view_check_names_missing_and_outdated_views() {
+        let pool = super::test_pool().await;
+        super::verify_views(&pool).await.expect("views after migrating");
+
+        sqlx::query("DROP VIEW role_with_menus").execute(&pool).await.unwrap();
+        sqlx::query("DROP VIEW user_with_roles").execute(&pool).await.unwrap();
+        sqlx::query("CREATE VIEW user_with_roles AS SELECT id, username FROM users")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let report = super::verify_views(&pool).await.expect_err("broken views");
+        let lines: Vec<_> = report.lines().collect();
+        assert_eq!(lines.len(), 2, "{}", report);
+        assert!(lines[0].starts_with("view `user_with_roles` is out of date"), "{}", report);
+        assert!(lines[0].contains("no such column"), "{}", report);
+        assert!(lines[0].ends_with("migrations/sqlite/0036_user_profile_fields.sql"), "{}", report);
+        assert!(lines[1].starts_with("view `role_with_menus` is missing;"), "{}", report);
+    }
+
     #[test]
     fn db_idle_timeout_disables_reaping_for_zero() {
         assert_eq!(db_idle_timeout(0), None);
@@ -107,4 +449,71 @@ mod tests {
     fn db_idle_timeout_uses_seconds_for_positive_values() {
         assert_eq!(db_idle_timeout(600), Some(Duration::from_secs(600)));
     }
+
+    #[tokio::test]
+    async fn executor_reads_from_the_primary_without_a_replica() {
+        let pool = super::memory_pool().await;
+        let db = DbExecutor::from_ref(&pool);
+
+        sqlx::query("CREATE TABLE t (id INTEGER)").execute(db.write()).await.unwrap();
+        sqlx::query("INSERT INTO t VALUES (1)").execute(db.write()).await.unwrap();
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM t").fetch_one(db.read()).await.unwrap();
+        assert_eq!(count, 1);
+
+        let health = db.health();
+        assert!(health.replica.is_none());
+        assert_eq!(health.primary.max_connections, 1);
+        assert_eq!(health.primary.size, health.primary.idle + health.primary.in_use);
+    }
+
+    #[test]
+    fn retry_backoff_doubles_up_to_the_cap() {
+        let policy = RetryPolicy { retries: 10, initial_backoff: Duration::from_millis(500) };
+        let delays: Vec<_> = (1..=8).map(|retry| policy.backoff(retry).as_millis()).collect();
+        assert_eq!(delays, [500, 1_000, 2_000, 4_000, 8_000, 16_000, 30_000, 30_000]);
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(30));
+    }
+
+    #[tokio::test]
+    async fn create_pool_retries_until_the_database_appears() {
+        let dir = std::env::temp_dir().join(format!("rustzen-db-retry-{}", std::process::id()));
+        let path = dir.join("late.db");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::remove_file(&path).ok();
+        let config = || super::DatabaseConfig {
+            url: format!("sqlite:///{}", path.display()),
+            max_connections: 1,
+            min_connections: 0,
+            connect_timeout: Duration::from_secs(1),
+            idle_timeout: None,
+            slow_query_threshold: None,
+            retry: RetryPolicy { retries: 3, initial_backoff: Duration::from_millis(50) },
+            read_only: true,
+        };
+
+        // A read-only replica that does not exist yet keeps failing without retries...
+        let no_retry = super::DatabaseConfig {
+            retry: RetryPolicy { retries: 0, ..config().retry },
+            ..config()
+        };
+        assert!(super::create_pool(no_retry).await.is_err());
+
+        // ...and connects once it shows up between attempts.
+        let creator = tokio::spawn({
+            let path = path.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(30)).await;
+                let url = format!("sqlite:///{}?mode=rwc", path.display());
+                let pool = sqlx::SqlitePool::connect(&url).await.unwrap();
+                sqlx::query("CREATE TABLE t (id INTEGER)").execute(&pool).await.unwrap();
+                pool.close().await;
+            }
+        });
+        let pool = super::create_pool(config()).await.expect("pool after retry");
+        creator.await.unwrap();
+        let error = sqlx::query("INSERT INTO t VALUES (1)").execute(&pool).await.unwrap_err();
+        assert!(error.to_string().contains("readonly"), "{}", error);
+        std::fs::remove_dir_all(&dir).ok();
+    }
 }
@@ -0,0 +1,93 @@
+//! Development proxy for the web UI.
+//!
+//! With `RUSTZEN_WEB_DEV_PROXY=http://127.0.0.1:9800`, every path the API and file routes
+//! do not handle is forwarded to the Vite dev server, so the backend port serves the live
+//! frontend. Vite's HMR websocket is not proxied; the browser connects to Vite directly.
+
+use crate::infra::http_client::forward;
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{StatusCode, Uri, header},
+    response::{IntoResponse, Response},
+};
+
+pub async fn proxy_to_dev_server(upstream: Uri, request: Request) -> Response {
+    let path = request.uri().path().to_string();
+    match forward(&upstream, request).await {
+        Ok(response) => response.map(Body::new),
+        Err(e) => {
+            tracing::warn!(%upstream, %path, "Web dev server request failed: {}", e);
+            let message = format!("Web dev server at {} is unreachable: {}\n", upstream, e);
+            (
+                StatusCode::BAD_GATEWAY,
+                [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+                message,
+            )
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::proxy_to_dev_server;
+
+    use axum::{
+        Router,
+        body::Body,
+        extract::Request,
+        http::{StatusCode, Uri, header},
+        routing::get,
+    };
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    fn proxy(upstream: Uri) -> Router {
+        Router::new()
+            .fallback(move |request: Request| proxy_to_dev_server(upstream.clone(), request))
+    }
+
+    async fn get_text(app: Router, uri: &str) -> (StatusCode, String) {
+        let request = Request::builder()
+            .uri(uri)
+            .header(header::HOST, "127.0.0.1:9801")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn forwards_path_and_query_to_the_dev_server() {
+        let vite = Router::new().route(
+            "/src/main.tsx",
+            get(|request: Request| async move {
+                let host = request.headers()[header::HOST].to_str().unwrap().to_string();
+                format!("{}?{} via {}", request.uri().path(), request.uri().query().unwrap(), host)
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, vite).await.unwrap() });
+
+        let upstream: Uri = format!("http://{}", addr).parse().unwrap();
+        let (status, body) = get_text(proxy(upstream), "/src/main.tsx?t=1").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, format!("/src/main.tsx?t=1 via {}", addr));
+    }
+
+    #[tokio::test]
+    async fn unreachable_dev_server_is_a_bad_gateway() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let upstream: Uri = format!("http://{}", listener.local_addr().unwrap()).parse().unwrap();
+        drop(listener);
+
+        let (status, body) = get_text(proxy(upstream), "/").await;
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+        assert!(body.starts_with("Web dev server at"), "{}", body);
+    }
+}
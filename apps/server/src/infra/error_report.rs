@@ -0,0 +1,181 @@
+//! Error reporting to Sentry or a Sentry-compatible service such as GlitchTip.
+//!
+//! With `RUSTZEN_SENTRY_DSN` set, `5xx` API responses and every panic are sent as events
+//! tagged with the request ID, user ID and route. Delivery is fire-and-forget through
+//! [`http_client`](super::http_client), so it never slows a response; a failed delivery
+//! is only logged. Without a DSN the same errors are still logged locally.
+
+use crate::infra::{config::AppConfig, http_client};
+
+use chrono::Utc;
+use rustzen_config::SentryEndpoint;
+use serde_json::{Value, json};
+use std::{future::Future, panic::PanicHookInfo, time::Duration};
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+tokio::task_local! {
+    static REQUEST: RequestContext;
+}
+
+/// The request an error happened in.
+#[derive(Debug, Clone, Default)]
+pub struct RequestContext {
+    pub request_id: String,
+    pub method: String,
+    /// Route template such as `/api/system/users/{id}`, when one matched.
+    pub route: Option<String>,
+    pub user_id: Option<i64>,
+}
+
+/// Runs `future` with `context` attached to any panic raised while it is polled.
+pub async fn scope<F: Future>(context: RequestContext, future: F) -> F::Output {
+    REQUEST.scope(context, future).await
+}
+
+/// Logs a server error answered to a request and reports it.
+pub fn capture_error(config: &AppConfig, context: &RequestContext, message: &str) {
+    tracing::error!(
+        request_id = %context.request_id,
+        route = context.route.as_deref().unwrap_or_default(),
+        user_id = context.user_id,
+        "Server error: {}",
+        message
+    );
+    send(config, build_event(config, "error", message, None, Some(context)));
+}
+
+/// Reports panics from any thread, after the default hook printed them.
+///
+/// Panics inside [`scope`] carry its request context. Call once at startup, from inside
+/// the Tokio runtime that should deliver the events.
+pub fn install_panic_hook(config: &'static AppConfig) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        previous(info);
+        let message = panic_message(info);
+        let context = REQUEST.try_with(Clone::clone).ok();
+        tracing::error!(
+            request_id = context.as_ref().map(|c| c.request_id.as_str()).unwrap_or_default(),
+            "Panic: {}",
+            message
+        );
+        send(config, build_event(config, "fatal", &message, Some("panic"), context.as_ref()));
+    }));
+    match endpoint(config) {
+        Some(endpoint) => tracing::info!(url = %endpoint.store_url, "Error reporting enabled"),
+        None => tracing::debug!("Error reporting disabled; RUSTZEN_SENTRY_DSN is not set"),
+    }
+}
+
+fn panic_message(info: &PanicHookInfo<'_>) -> String {
+    let payload = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "panic".to_string());
+    match info.location() {
+        Some(location) => format!("{} at {}:{}", payload, location.file(), location.line()),
+        None => payload,
+    }
+}
+
+/// Already validated when the config loaded.
+fn endpoint(config: &AppConfig) -> Option<SentryEndpoint> {
+    config.sentry_endpoint().ok().flatten()
+}
+
+/// Sentry store API event.
+fn build_event(
+    config: &AppConfig,
+    level: &str,
+    message: &str,
+    exception_type: Option<&str>,
+    context: Option<&RequestContext>,
+) -> Value {
+    let mut event = json!({
+        "event_id": uuid::Uuid::new_v4().simple().to_string(),
+        "timestamp": Utc::now().to_rfc3339(),
+        "platform": "other",
+        "level": level,
+        "logger": "rustzen-admin",
+        "release": concat!("rustzen-admin@", env!("CARGO_PKG_VERSION")),
+        "environment": config.env,
+        "message": { "formatted": message },
+    });
+    if let Some(exception_type) = exception_type {
+        event["exception"] = json!({ "values": [{ "type": exception_type, "value": message }] });
+    }
+    if let Some(context) = context {
+        event["tags"] = json!({ "request_id": context.request_id, "method": context.method });
+        if let Some(route) = &context.route {
+            event["transaction"] = json!(route);
+            event["tags"]["route"] = json!(route);
+        }
+        if let Some(user_id) = context.user_id {
+            event["user"] = json!({ "id": user_id.to_string() });
+        }
+    }
+    event
+}
+
+fn send(config: &AppConfig, event: Value) {
+    let Some(endpoint) = endpoint(config) else {
+        return;
+    };
+    // Panics outside the runtime (or during shutdown) are only logged.
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        return;
+    };
+    let auth = format!(
+        "Sentry sentry_version=7, sentry_client=rustzen-admin/{}, sentry_key={}",
+        env!("CARGO_PKG_VERSION"),
+        endpoint.public_key
+    );
+    runtime.spawn(async move {
+        let headers = [("x-sentry-auth", auth)];
+        match http_client::post_json(&endpoint.store_url, &headers, event.to_string(), SEND_TIMEOUT)
+            .await
+        {
+            Ok(status) if (200..300).contains(&status) => {}
+            Ok(status) => tracing::warn!(status, "Error report rejected"),
+            Err(err) => tracing::warn!("Error report delivery failed: {}", err),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RequestContext, build_event};
+    use crate::infra::config::test_config;
+
+    #[test]
+    fn events_carry_request_id_user_and_route() {
+        let context = RequestContext {
+            request_id: "req-1".to_string(),
+            method: "DELETE".to_string(),
+            route: Some("/api/system/users/{id}".to_string()),
+            user_id: Some(12),
+        };
+        let event = build_event(
+            test_config(),
+            "fatal",
+            "boom at src/x.rs:3",
+            Some("panic"),
+            Some(&context),
+        );
+
+        assert_eq!(event["event_id"].as_str().unwrap().len(), 32);
+        assert_eq!(event["level"], "fatal");
+        assert_eq!(event["message"]["formatted"], "boom at src/x.rs:3");
+        assert_eq!(event["exception"]["values"][0]["type"], "panic");
+        assert_eq!(event["transaction"], "/api/system/users/{id}");
+        assert_eq!(event["tags"]["request_id"], "req-1");
+        assert_eq!(event["tags"]["route"], "/api/system/users/{id}");
+        assert_eq!(event["user"]["id"], "12");
+
+        let bare = build_event(test_config(), "error", "failed", None, None);
+        assert!(bare.get("exception").is_none() && bare.get("tags").is_none());
+    }
+}
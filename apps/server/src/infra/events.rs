@@ -0,0 +1,104 @@
+//! Process-wide domain event bus and its subscriber list.
+//!
+//! Every event goes through the [`outbox`](crate::infra::outbox), so a subscriber that
+//! fails or a server that stops mid-delivery gets it again later. Services that change
+//! state call [`record`] inside the transaction that makes the change and [`deliver`] after
+//! committing; [`publish`] is only for events that accompany no write, such as a
+//! download. To react to a new kind of change, implement
+//! `EventSubscriber<SqlitePool>` and register it here instead of calling into other
+//! features from the service.
+
+use crate::{
+    common::{
+        error::ServiceError,
+        tx::{self, Tx},
+    },
+    features::{
+        manage::log::service::LogService,
+        system::{login_alert::service::LoginAlertService, webhook::service::WebhookService},
+    },
+    infra::outbox::{self, Recorded},
+};
+
+use once_cell::sync::Lazy;
+use rustzen_core::events::{DomainEvent, EventBus};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+static EVENT_BUS: Lazy<EventBus<SqlitePool>> = Lazy::new(|| {
+    EventBus::new()
+        .subscribe(Arc::new(LogService))
+        .subscribe(Arc::new(WebhookService))
+        .subscribe(Arc::new(LoginAlertService))
+});
+
+/// Queues `event` for every subscriber as part of `tx`; pass the result to [`deliver`]
+/// after committing.
+pub async fn record(tx: &mut Tx<'_>, event: &DomainEvent) -> Result<Recorded, ServiceError> {
+    outbox::record(tx, &EVENT_BUS, event).await
+}
+
+/// Hands recorded events to their subscribers now and waits for them to finish. What
+/// fails stays in the outbox for the relay worker.
+pub async fn deliver(pool: &SqlitePool, recorded: Recorded) {
+    if let Err(e) = outbox::deliver(pool, &EVENT_BUS, recorded).await {
+        tracing::error!("Failed to deliver recorded events: {:?}", e);
+    }
+}
+
+/// Records `event` in a transaction of its own and delivers it, for events that accompany
+/// no write. Fails when the outbox cannot be written.
+pub async fn publish(pool: &SqlitePool, event: DomainEvent) -> Result<(), ServiceError> {
+    let mut tx = tx::begin(pool).await?;
+    let recorded = record(&mut tx, &event).await?;
+    tx::commit(tx).await?;
+    deliver(pool, recorded).await;
+    Ok(())
+}
+
+/// Starts the worker that retries events left in the outbox. Call once at startup.
+pub fn spawn_relay(pool: SqlitePool) {
+    outbox::spawn_relay(pool, &EVENT_BUS);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::publish;
+    use rustzen_core::events::DomainEvent;
+
+    #[tokio::test]
+    async fn login_failures_reach_the_audit_log_and_webhook_queue() {
+        let pool = crate::infra::db::test_pool().await;
+        sqlx::query(
+            "INSERT INTO webhooks (name, url, secret, events) VALUES ('siem', 'http://127.0.0.1:9/', '0123456789abcdef', '[\"login.failed\"]')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        publish(
+            &pool,
+            DomainEvent::LoginFailed {
+                username: "mallory".to_string(),
+                reason: "Invalid username or password".to_string(),
+                ip_address: "10.0.0.9".to_string(),
+                user_agent: "curl".to_string(),
+                duration_ms: 3,
+            },
+        )
+        .await
+        .unwrap();
+
+        let (action, status): (String, String) =
+            sqlx::query_as("SELECT action, status FROM operation_logs WHERE username = 'mallory'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!((action.as_str(), status.as_str()), ("AUTH_LOGIN", "FAIL"));
+        let queued: String = sqlx::query_scalar("SELECT event FROM webhook_deliveries")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(queued, "login.failed");
+    }
+}
@@ -0,0 +1,99 @@
+//! Offline IP geolocation from a MaxMind-format database at `RUSTZEN_GEOIP_DB_PATH`.
+//!
+//! The file is read into memory by [`open`] when the router is built; until then lookups
+//! are off. City and Country databases both work; a
+//! Country database simply never yields a city. Private, loopback and other non-public
+//! addresses are not looked up.
+
+use crate::infra::config::AppConfig;
+
+use maxminddb::{Reader, geoip2};
+use once_cell::sync::OnceCell;
+use std::net::IpAddr;
+
+/// The loaded database; `None` when it is not configured or failed to load.
+static GEOIP: OnceCell<Option<Reader<Vec<u8>>>> = OnceCell::new();
+
+/// Loads the database configured in `config`. Later calls keep the first result.
+pub fn open(config: &AppConfig) {
+    GEOIP.get_or_init(|| read(config));
+}
+
+fn read(config: &AppConfig) -> Option<Reader<Vec<u8>>> {
+    let path = config.auth.geoip_db_path.as_deref()?;
+    match Reader::open_readfile(path) {
+        Ok(reader) => {
+            tracing::info!(path, "Loaded GeoIP database");
+            Some(reader)
+        }
+        Err(e) => {
+            tracing::error!(path, "Failed to load GeoIP database, locations are off: {}", e);
+            None
+        }
+    }
+}
+
+/// Where an address is, as far as the database knows.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GeoLocation {
+    /// ISO 3166-1 alpha-2 code, e.g. `CN`.
+    pub country: Option<String>,
+    /// English city name.
+    pub city: Option<String>,
+    /// Approximate coordinates, used to spot impossible travel between logins.
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+/// Looks up `ip`; `None` when lookups are off, the address is not public or not listed.
+pub fn lookup(ip: &str) -> Option<GeoLocation> {
+    let reader = GEOIP.get()?.as_ref()?;
+    let address = public_address(ip)?;
+    let record: geoip2::City = reader.lookup(address).ok()?;
+    let coordinates = record.location.as_ref();
+    let location = GeoLocation {
+        country: record.country.and_then(|c| c.iso_code).map(str::to_string),
+        city: record
+            .city
+            .and_then(|c| c.names)
+            .and_then(|names| names.get("en").map(|name| name.to_string())),
+        latitude: coordinates.and_then(|l| l.latitude),
+        longitude: coordinates.and_then(|l| l.longitude),
+    };
+    (location != GeoLocation::default()).then_some(location)
+}
+
+/// `ip` parsed, unless it is an address no database lists.
+fn public_address(ip: &str) -> Option<IpAddr> {
+    let address: IpAddr = ip.trim().parse().ok()?;
+    let private = match address {
+        IpAddr::V4(v4) => {
+            v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+        }
+        IpAddr::V6(v6) => {
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_unique_local()
+                || v6.is_unicast_link_local()
+        }
+    };
+    (!private).then_some(address)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::public_address;
+
+    #[test]
+    fn only_public_addresses_are_looked_up() {
+        assert!(public_address("8.8.8.8").is_some());
+        assert!(public_address(" 2001:4860:4860::8888 ").is_some());
+        for ip in ["10.0.0.9", "192.168.1.2", "127.0.0.1", "::1", "fd00::1", "fe80::1", "bogus"] {
+            assert!(public_address(ip).is_none(), "{}", ip);
+        }
+    }
+}
@@ -0,0 +1,247 @@
+//! Optional gRPC listener so sibling services can look up users, verify tokens, and
+//! check capabilities without going through the JSON API.
+//!
+//! Compiled with the `grpc` feature and started only when `RUSTZEN_GRPC_PORT` is set.
+//! The contract is `proto/admin.proto`; routing is wired by hand so builds need no `protoc`.
+
+mod proto;
+mod service;
+
+use service::AdminRpc;
+
+use crate::infra::config::AppConfig;
+
+use sqlx::SqlitePool;
+use std::{
+    convert::Infallible,
+    future::Future,
+    net::SocketAddr,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tonic::{
+    Request, Response, Status,
+    body::Body,
+    codegen::{BoxFuture, StdError, http},
+    server::{Grpc, NamedService, UnaryService},
+};
+use tonic_prost::ProstCodec;
+
+/// Metadata key carrying the shared `RUSTZEN_GRPC_API_KEY`.
+const API_KEY_METADATA: &str = "x-rustzen-api-key";
+
+/// Serves `rustzen.admin.v1.AdminService` on `addr` until the process exits.
+pub async fn serve(
+    config: &'static AppConfig,
+    pool: SqlitePool,
+    addr: SocketAddr,
+    api_key: String,
+) -> Result<(), tonic::transport::Error> {
+    tracing::info!("gRPC server listening on {}", addr);
+    tonic::transport::Server::builder()
+        .add_service(AdminServiceServer::new(config, pool, api_key))
+        .serve(addr)
+        .await
+}
+
+#[derive(Clone)]
+struct AdminServiceServer {
+    rpc: Arc<AdminRpc>,
+    api_key: Arc<str>,
+}
+
+impl AdminServiceServer {
+    fn new(config: &'static AppConfig, pool: SqlitePool, api_key: String) -> Self {
+        Self { rpc: Arc::new(AdminRpc::new(config, pool)), api_key: api_key.into() }
+    }
+
+    fn authorize<T>(&self, request: &Request<T>) -> Result<(), Status> {
+        let provided = request
+            .metadata()
+            .get(API_KEY_METADATA)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        if constant_time_eq(provided.as_bytes(), self.api_key.as_bytes()) {
+            Ok(())
+        } else {
+            Err(Status::unauthenticated("missing or invalid API key"))
+        }
+    }
+}
+
+impl NamedService for AdminServiceServer {
+    const NAME: &'static str = "rustzen.admin.v1.AdminService";
+}
+
+/// Adapts an async closure into the unary handler `Grpc::unary` expects.
+struct Unary<F>(F);
+
+impl<Req, Resp, F, Fut> UnaryService<Req> for Unary<F>
+where
+    F: FnMut(Request<Req>) -> Fut,
+    Fut: Future<Output = Result<Response<Resp>, Status>>,
+{
+    type Response = Resp;
+    type Future = Fut;
+
+    fn call(&mut self, request: Request<Req>) -> Self::Future {
+        (self.0)(request)
+    }
+}
+
+/// Routes one unary method; with `peer`, the method also gets the caller's IP address.
+macro_rules! unary_route {
+    ($server:expr, $req:expr, $method:ident $(, $peer:ident)?) => {{
+        let server = $server.clone();
+        Box::pin(async move {
+            let handler = Unary(move |request: Request<_>| {
+                let server = server.clone();
+                async move {
+                    server.authorize(&request)?;
+                    $(let $peer = request.remote_addr().map(|addr| addr.ip());)?
+                    server.rpc.$method(request.into_inner() $(, $peer)?).await.map(Response::new)
+                }
+            });
+            Ok(Grpc::new(ProstCodec::default()).unary(handler, $req).await)
+        })
+    }};
+}
+
+impl<B> tonic::codegen::Service<http::Request<B>> for AdminServiceServer
+where
+    B: tonic::codegen::Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<Body>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        match req.uri().path() {
+            "/rustzen.admin.v1.AdminService/GetUser" => unary_route!(self, req, get_user),
+            "/rustzen.admin.v1.AdminService/VerifyToken" => {
+                unary_route!(self, req, verify_token, peer)
+            }
+            "/rustzen.admin.v1.AdminService/CheckPermission" => {
+                unary_route!(self, req, check_permission, peer)
+            }
+            _ => Box::pin(async move { Ok(Status::unimplemented("unknown method").into_http()) }),
+        }
+    }
+}
+
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len() && left.iter().zip(right).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AdminRpc, AdminServiceServer, constant_time_eq,
+        proto::{CheckPermissionRequest, CheckPermissionResponse},
+    };
+    use crate::infra::config::test_config;
+    use tonic::{Code, Request};
+
+    #[test]
+    fn api_key_must_match_exactly() {
+        assert!(constant_time_eq(b"sibling-key", b"sibling-key"));
+        assert!(!constant_time_eq(b"sibling-key", b"sibling-kez"));
+        assert!(!constant_time_eq(b"sibling", b"sibling-key"));
+    }
+
+    #[tokio::test]
+    async fn requests_without_the_api_key_are_rejected() {
+        let pool = crate::infra::db::memory_pool().await;
+        let server = AdminServiceServer::new(test_config(), pool, "sibling-key".to_string());
+
+        let mut request = Request::new(CheckPermissionRequest::default());
+        let err = server.authorize(&request).unwrap_err();
+        assert_eq!(err.code(), Code::Unauthenticated);
+
+        request.metadata_mut().insert("x-rustzen-api-key", "sibling-key".parse().unwrap());
+        assert!(server.authorize(&request).is_ok());
+    }
+
+    #[tokio::test]
+    async fn check_permission_is_routed_and_encoded() {
+        use prost::Message;
+        use tonic::codegen::{Service, http};
+
+        let pool = crate::infra::db::test_pool().await;
+        let mut server = AdminServiceServer::new(test_config(), pool, "sibling-key".to_string());
+
+        let message =
+            CheckPermissionRequest { user_id: 404, permission: "system:user:list".to_string() };
+        let mut frame = vec![0u8];
+        frame.extend_from_slice(&(message.encoded_len() as u32).to_be_bytes());
+        message.encode(&mut frame).unwrap();
+        let request = http::Request::builder()
+            .method("POST")
+            .uri("/rustzen.admin.v1.AdminService/CheckPermission")
+            .header("content-type", "application/grpc")
+            .header("x-rustzen-api-key", "sibling-key")
+            .body(axum::body::Body::from(frame))
+            .unwrap();
+
+        let response = server.call(request).await.unwrap();
+        assert_eq!(response.status(), http::StatusCode::OK);
+        let body = axum::body::to_bytes(axum::body::Body::new(response.into_body()), usize::MAX)
+            .await
+            .unwrap();
+        let reply = CheckPermissionResponse::decode(&body[5..]).unwrap();
+        assert!(!reply.allowed);
+    }
+
+    #[tokio::test]
+    async fn restricted_roles_are_scoped_to_the_peer_address() {
+        use crate::{
+            common::ids::RoleId,
+            features::system::user::{service::UserService, types::CreateUserRequest},
+            infra::permission::PermissionService,
+        };
+
+        let pool = crate::infra::db::test_pool().await;
+        PermissionService::sync_permissions(&pool).await.unwrap();
+        let viewer: RoleId = sqlx::query_scalar("SELECT id FROM roles WHERE code = 'viewer'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let request = CreateUserRequest {
+            username: "grpc_greta".to_string(),
+            email: "grpc_greta@example.com".to_string(),
+            password: "greta-password".to_string(),
+            real_name: None,
+            status: Some(1),
+            role_ids: vec![viewer],
+            profile: None,
+        };
+        let user_id =
+            UserService::create_user(test_config(), &pool, None, request).await.unwrap().get();
+        // No routes are registered outside the router, so grant the capability by hand.
+        sqlx::query("INSERT INTO menus (name, code, menu_type) VALUES ('List users', ?, 3)")
+            .bind("system:user:list")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for statement in [
+            "INSERT INTO role_menus (role_id, menu_id) SELECT ?, id FROM menus WHERE code = 'system:user:list'",
+            "INSERT INTO role_access_restrictions (role_id, allowed_cidrs) VALUES (?, '[\"10.0.0.0/8\"]')",
+        ] {
+            sqlx::query(statement).bind(viewer).execute(&pool).await.unwrap();
+        }
+
+        let rpc = AdminRpc::new(test_config(), pool);
+        let check = |peer: [u8; 4]| {
+            let request =
+                CheckPermissionRequest { user_id, permission: "system:user:list".to_string() };
+            rpc.check_permission(request, Some(peer.into()))
+        };
+        assert!(check([10, 1, 2, 3]).await.unwrap().allowed);
+        assert!(!check([192, 168, 1, 2]).await.unwrap().allowed);
+    }
+}
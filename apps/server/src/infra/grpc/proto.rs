@@ -0,0 +1,78 @@
+//! Protobuf messages for `proto/admin.proto`, declared with `prost` derives so the
+//! build does not need `protoc`.
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetUserRequest {
+    #[prost(oneof = "get_user_request::Lookup", tags = "1, 2")]
+    pub lookup: Option<get_user_request::Lookup>,
+}
+
+pub mod get_user_request {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Lookup {
+        #[prost(int64, tag = "1")]
+        Id(i64),
+        #[prost(string, tag = "2")]
+        Username(String),
+    }
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Role {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+    #[prost(string, tag = "2")]
+    pub name: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct User {
+    #[prost(int64, tag = "1")]
+    pub id: i64,
+    #[prost(string, tag = "2")]
+    pub username: String,
+    #[prost(string, tag = "3")]
+    pub email: String,
+    #[prost(string, optional, tag = "4")]
+    pub real_name: Option<String>,
+    #[prost(int32, tag = "5")]
+    pub status: i32,
+    #[prost(bool, tag = "6")]
+    pub is_system: bool,
+    #[prost(message, repeated, tag = "7")]
+    pub roles: Vec<Role>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct VerifyTokenRequest {
+    #[prost(string, tag = "1")]
+    pub token: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct VerifyTokenResponse {
+    #[prost(bool, tag = "1")]
+    pub valid: bool,
+    #[prost(int64, tag = "2")]
+    pub user_id: i64,
+    #[prost(string, tag = "3")]
+    pub username: String,
+    #[prost(int64, tag = "4")]
+    pub expires_at: i64,
+    #[prost(string, repeated, tag = "5")]
+    pub permissions: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CheckPermissionRequest {
+    #[prost(int64, tag = "1")]
+    pub user_id: i64,
+    #[prost(string, tag = "2")]
+    pub permission: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CheckPermissionResponse {
+    #[prost(bool, tag = "1")]
+    pub allowed: bool,
+}
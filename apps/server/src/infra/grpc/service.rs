@@ -0,0 +1,131 @@
+use super::proto::{
+    CheckPermissionRequest, CheckPermissionResponse, GetUserRequest, Role, User,
+    VerifyTokenRequest, VerifyTokenResponse, get_user_request::Lookup,
+};
+use crate::{
+    common::{
+        api::OptionItem,
+        error::ServiceError,
+        ids::{RoleId, UserId},
+    },
+    features::{
+        auth::{repo::AuthRepository, service::AuthService},
+        system::user::repo::UserRepository,
+    },
+    infra::{
+        auth_runtime::{ServerAuthContextLoader, jwt_codec},
+        config::AppConfig,
+        permission::PermissionService,
+    },
+};
+
+use rustzen_core::auth::AuthContextLoader;
+use sqlx::SqlitePool;
+use std::net::IpAddr;
+use tonic::Status;
+
+/// RPC bodies for `rustzen.admin.v1.AdminService`, reusing the HTTP repos and auth loader.
+pub struct AdminRpc {
+    config: &'static AppConfig,
+    pool: SqlitePool,
+}
+
+impl AdminRpc {
+    pub fn new(config: &'static AppConfig, pool: SqlitePool) -> Self {
+        Self { config, pool }
+    }
+
+    pub async fn get_user(&self, request: GetUserRequest) -> Result<User, Status> {
+        let id = match request.lookup {
+            Some(Lookup::Id(id)) => UserId(id),
+            Some(Lookup::Username(username)) => {
+                UserRepository::find_user_id_by_username(&self.pool, username.trim())
+                    .await?
+                    .ok_or_else(|| Status::not_found(format!("User {} not found", username)))?
+            }
+            None => return Err(Status::invalid_argument("id or username is required")),
+        };
+        let user = UserRepository::find_user_by_id(&self.pool, id)
+            .await?
+            .ok_or_else(|| Status::not_found(format!("User id {} not found", id)))?;
+        let roles = serde_json::from_value::<Vec<OptionItem<RoleId>>>(user.roles)
+            .map_err(|e| Status::internal(format!("Invalid user role data: {}", e)))?;
+
+        Ok(User {
+            id: user.id.get(),
+            username: user.username,
+            email: user.email.unwrap_or_default(),
+            real_name: user.real_name,
+            status: i32::from(user.status),
+            is_system: user.is_system,
+            roles: roles
+                .into_iter()
+                .map(|role| Role { id: role.value.get(), name: role.label })
+                .collect(),
+        })
+    }
+
+    /// Invalid, expired, or disabled-user tokens answer `valid = false` rather than an error.
+    /// The permissions listed are those the token holds from the `peer` address.
+    pub async fn verify_token(
+        &self,
+        request: VerifyTokenRequest,
+        peer: Option<IpAddr>,
+    ) -> Result<VerifyTokenResponse, Status> {
+        let Ok(claims) = jwt_codec(self.config).decode(request.token.trim()) else {
+            return Ok(VerifyTokenResponse::default());
+        };
+        let loader = ServerAuthContextLoader::new(self.pool.clone(), self.config);
+        let Ok(current_user) = loader.load_current_user(&claims).await else {
+            return Ok(VerifyTokenResponse::default());
+        };
+        let current_user = loader.scope_to_request(current_user, peer);
+
+        let mut permissions: Vec<String> = current_user.permissions.iter().cloned().collect();
+        permissions.sort();
+        Ok(VerifyTokenResponse {
+            valid: true,
+            user_id: claims.user_id,
+            username: claims.username,
+            expires_at: claims.exp as i64,
+            permissions,
+        })
+    }
+
+    /// Always reads from the database so a disabled user is denied immediately. Restricted
+    /// roles are scoped to the `peer` address like HTTP requests are to the client's.
+    pub async fn check_permission(
+        &self,
+        request: CheckPermissionRequest,
+        peer: Option<IpAddr>,
+    ) -> Result<CheckPermissionResponse, Status> {
+        let permission = request.permission.trim();
+        if permission.is_empty() {
+            return Err(Status::invalid_argument("permission is required"));
+        }
+        let Some(user) = AuthRepository::find_user_by_id(&self.pool, request.user_id).await? else {
+            return Ok(CheckPermissionResponse { allowed: false });
+        };
+        AuthService::cache_user_permissions(&self.pool, user.id).await?;
+        let current_user = PermissionService::load_current_user(user.id, &user.username)?;
+        let current_user = PermissionService::scope_to_client(self.config, current_user, peer);
+
+        Ok(CheckPermissionResponse { allowed: current_user.has_capability(permission) })
+    }
+}
+
+impl From<ServiceError> for Status {
+    fn from(err: ServiceError) -> Self {
+        match err {
+            ServiceError::NotFound(resource) => {
+                Status::not_found(format!("{} not found", resource))
+            }
+            ServiceError::InvalidToken => Status::unauthenticated(err.to_string()),
+            ServiceError::InvalidOperation(reason) => Status::invalid_argument(reason),
+            other => {
+                tracing::error!("gRPC call failed: {}", other);
+                Status::internal("Service is temporarily unavailable")
+            }
+        }
+    }
+}
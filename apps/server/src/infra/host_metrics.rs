@@ -0,0 +1,146 @@
+//! Host resource history for the dashboard.
+//!
+//! [`HostMetrics::spawn_sampler`] samples CPU, memory, swap, disk and load average every
+//! 30 seconds and keeps the last hour in memory, so the history is per instance and starts
+//! over on restart. Load average is 0 on Windows, which has none.
+
+use crate::infra::system_info::SystemUtils;
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::{collections::VecDeque, sync::Mutex, time::Duration};
+use sysinfo::{CpuRefreshKind, MemoryRefreshKind, RefreshKind, System};
+
+/// Time between samples.
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(30);
+/// Samples kept: one hour at the sample interval.
+const HISTORY_CAPACITY: usize = 120;
+
+pub static HOST_METRICS: Lazy<HostMetrics> = Lazy::new(|| HostMetrics::new(HISTORY_CAPACITY));
+
+/// Host resource usage at one point in time.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostSample {
+    pub sampled_at: DateTime<Utc>,
+    /// Busy share of all CPUs, 0 to 100.
+    pub cpu_percent: f32,
+    pub memory_total: u64,
+    pub memory_used: u64,
+    pub swap_total: u64,
+    pub swap_used: u64,
+    pub disk_total: u64,
+    pub disk_used: u64,
+    pub load_one: f64,
+    pub load_five: f64,
+    pub load_fifteen: f64,
+}
+
+pub struct HostMetrics {
+    capacity: usize,
+    samples: Mutex<VecDeque<HostSample>>,
+}
+
+impl HostMetrics {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, samples: Mutex::new(VecDeque::with_capacity(capacity)) }
+    }
+
+    pub fn record(&self, sample: HostSample) {
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        if samples.len() == self.capacity {
+            samples.pop_front();
+        }
+        samples.push_back(sample);
+    }
+
+    /// Samples taken since `since`, oldest first.
+    pub fn history(&self, since: Option<DateTime<Utc>>) -> Vec<HostSample> {
+        let samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        samples
+            .iter()
+            .filter(|s| since.is_none_or(|since| s.sampled_at >= since))
+            .cloned()
+            .collect()
+    }
+
+    pub fn latest(&self) -> Option<HostSample> {
+        self.samples.lock().unwrap_or_else(|e| e.into_inner()).back().cloned()
+    }
+
+    /// Samples the host into [`HOST_METRICS`] until the process exits.
+    pub fn spawn_sampler() {
+        tokio::spawn(async move {
+            let mut system = System::new_with_specifics(
+                RefreshKind::nothing()
+                    .with_cpu(CpuRefreshKind::nothing().with_cpu_usage())
+                    .with_memory(MemoryRefreshKind::everything()),
+            );
+            // CPU usage is measured between two refreshes.
+            tokio::time::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL).await;
+            let mut ticker = tokio::time::interval(SAMPLE_INTERVAL);
+            loop {
+                ticker.tick().await;
+                HOST_METRICS.record(sample(&mut system));
+            }
+        });
+    }
+}
+
+fn sample(system: &mut System) -> HostSample {
+    system.refresh_cpu_usage();
+    system.refresh_memory();
+    let (disk_total, disk_used, _) = SystemUtils::get_disk_info();
+    let load = System::load_average();
+    HostSample {
+        sampled_at: Utc::now(),
+        cpu_percent: system.global_cpu_usage(),
+        memory_total: system.total_memory(),
+        memory_used: system.used_memory(),
+        swap_total: system.total_swap(),
+        swap_used: system.used_swap(),
+        disk_total,
+        disk_used,
+        load_one: load.one,
+        load_five: load.five,
+        load_fifteen: load.fifteen,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HostMetrics, HostSample};
+    use chrono::{Duration, Utc};
+
+    fn sample_at(minutes_ago: i64) -> HostSample {
+        HostSample {
+            sampled_at: Utc::now() - Duration::minutes(minutes_ago),
+            cpu_percent: minutes_ago as f32,
+            memory_total: 8,
+            memory_used: 4,
+            swap_total: 0,
+            swap_used: 0,
+            disk_total: 100,
+            disk_used: 50,
+            load_one: 0.5,
+            load_five: 0.4,
+            load_fifteen: 0.3,
+        }
+    }
+
+    #[test]
+    fn keeps_the_most_recent_samples_oldest_first() {
+        let metrics = HostMetrics::new(2);
+        assert!(metrics.latest().is_none());
+        for minutes_ago in [30, 20, 10] {
+            metrics.record(sample_at(minutes_ago));
+        }
+
+        let cpu: Vec<f32> = metrics.history(None).iter().map(|s| s.cpu_percent).collect();
+        assert_eq!(cpu, [20.0, 10.0]);
+        assert_eq!(metrics.latest().map(|s| s.cpu_percent), Some(10.0));
+        let recent = metrics.history(Some(Utc::now() - Duration::minutes(15)));
+        assert_eq!(recent.len(), 1);
+    }
+}
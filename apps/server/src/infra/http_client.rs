@@ -0,0 +1,416 @@
+//! Outbound HTTP/1.1 client used for webhook delivery, SMS gateways, login connectors,
+//! error reports and the web dev proxy.
+//!
+//! `https://` targets are verified against the bundled Mozilla root certificates. Outbound
+//! calls only reach public addresses: a target that is, or resolves to, a loopback, private,
+//! link-local or otherwise internal address is refused unless `RUSTZEN_OUTBOUND_ALLOW_NETWORKS`
+//! lists it, so an admin-entered webhook URL cannot probe the internal network. The dev
+//! proxy is exempt; it only talks to the configured local dev server.
+
+use crate::infra::config::AppConfig;
+
+use axum::{
+    body::{Body, Bytes},
+    http::{HeaderValue, Method, Request, Response, Uri, header},
+};
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use hyper_util::{
+    client::legacy::{
+        Client,
+        connect::{HttpConnector, dns::Name},
+    },
+    rt::TokioExecutor,
+};
+use once_cell::sync::{Lazy, OnceCell};
+use rustzen_config::IpNetwork;
+use std::{
+    error::Error,
+    future::Future,
+    io,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tower_service::Service;
+
+static CLIENT: OnceCell<Outbound> = OnceCell::new();
+
+/// Sets up outbound calls with `RUSTZEN_OUTBOUND_ALLOW_NETWORKS`; the router builder calls
+/// it, and calls made before it fail like a refused target.
+pub fn configure(config: &AppConfig) {
+    CLIENT.get_or_init(|| {
+        let mut allowed = config
+            .outbound_allowed_networks()
+            .expect("RUSTZEN_OUTBOUND_ALLOW_NETWORKS is checked at startup");
+        // Unit tests stand in for webhook receivers and providers on 127.0.0.1.
+        if cfg!(test) {
+            allowed.push(IpNetwork::from(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+        }
+        Outbound::new(allowed)
+    });
+}
+
+fn client() -> Result<&'static Outbound, String> {
+    CLIENT.get().ok_or_else(|| "outbound calls are not configured yet".to_string())
+}
+
+static DEV_PROXY: Lazy<Client<HttpConnector, Body>> =
+    Lazy::new(|| Client::builder(TokioExecutor::new()).build_http());
+
+/// Checks that `url` is an absolute `http://` or `https://` URL whose host may be called.
+///
+/// Host names are checked again against their resolved addresses on every call.
+pub fn validate_url(url: &str) -> Result<Uri, String> {
+    client()?.target(url)
+}
+
+/// POSTs a JSON body and returns the response status code.
+///
+/// Connection, protocol, and timeout failures are returned as a readable message.
+pub async fn post_json(
+    url: &str,
+    headers: &[(&str, String)],
+    body: String,
+    timeout: Duration,
+) -> Result<u16, String> {
+    client()?.post_json(url, headers, body, timeout).await
+}
+
+/// POSTs a form-encoded body and returns the response status code and body text.
+pub async fn post_form(
+    url: &str,
+    headers: &[(&str, String)],
+    body: String,
+    timeout: Duration,
+) -> Result<(u16, String), String> {
+    let body = Some(("application/x-www-form-urlencoded", body));
+    client()?.fetch(Method::POST, url, headers, body, timeout).await
+}
+
+/// Sends a request with an optional JSON body and returns the status code and body text,
+/// for APIs whose answer matters (login connectors).
+pub async fn send_json(
+    method: Method,
+    url: &str,
+    headers: &[(&str, String)],
+    body: Option<String>,
+    timeout: Duration,
+) -> Result<(u16, String), String> {
+    let body = body.map(|body| ("application/json", body));
+    client()?.fetch(method, url, headers, body, timeout).await
+}
+
+/// `key=value` pairs joined by `&`, percent-encoded per RFC 3986, for form bodies and
+/// query strings.
+pub fn form_encode(pairs: &[(&str, &str)]) -> String {
+    pairs
+        .iter()
+        .map(|(key, value)| format!("{}={}", percent_encode(key), percent_encode(value)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+pub fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Sends `request` to the same path and query on `upstream`, streaming both bodies.
+///
+/// `Host` is rewritten to the upstream authority and hop-by-hop headers are dropped.
+pub async fn forward(upstream: &Uri, request: Request<Body>) -> Result<Response<Incoming>, String> {
+    let (mut parts, body) = request.into_parts();
+    let path = parts.uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    let authority = upstream.authority().map(|a| a.as_str()).unwrap_or_default();
+    parts.uri = format!("http://{}{}", authority, path)
+        .parse()
+        .map_err(|e| format!("bad request path: {}", e))?;
+    for name in [header::CONNECTION, header::UPGRADE, header::TE, header::TRAILER] {
+        parts.headers.remove(name);
+    }
+    let authority = HeaderValue::from_str(authority).map_err(|e| format!("bad host: {}", e))?;
+    parts.headers.insert(header::HOST, authority);
+
+    DEV_PROXY
+        .request(Request::from_parts(parts, body))
+        .await
+        .map_err(|e| format!("request failed: {}", describe(&e)))
+}
+
+/// Whether `ip` is a public unicast address that outbound calls may reach by default.
+fn is_public(ip: IpAddr) -> bool {
+    match ip.to_canonical() {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || first == 0
+                // 100.64.0.0/10, carrier-grade NAT
+                || (first == 100 && second & 0xc0 == 64))
+        }
+        IpAddr::V6(ip) => {
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || ip.is_unique_local()
+                || ip.is_unicast_link_local())
+        }
+    }
+}
+
+fn is_allowed(ip: IpAddr, allowed: &[IpNetwork]) -> bool {
+    is_public(ip) || allowed.iter().any(|network| network.contains(ip))
+}
+
+/// Resolves host names and drops addresses outbound calls may not reach.
+#[derive(Clone)]
+struct GuardedResolver {
+    allowed: Arc<[IpNetwork]>,
+}
+
+impl Service<Name> for GuardedResolver {
+    type Response = std::vec::IntoIter<SocketAddr>;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, io::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), io::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, name: Name) -> Self::Future {
+        let allowed = self.allowed.clone();
+        Box::pin(async move {
+            let host = name.as_str();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, 0))
+                .await?
+                .filter(|addr| is_allowed(addr.ip(), &allowed))
+                .collect();
+            if addrs.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    format!(
+                        "{} resolves only to internal addresses; list them in RUSTZEN_OUTBOUND_ALLOW_NETWORKS to allow it",
+                        host
+                    ),
+                ));
+            }
+            Ok(addrs.into_iter())
+        })
+    }
+}
+
+/// The outbound client and the internal networks it may reach.
+struct Outbound {
+    client: Client<HttpsConnector<HttpConnector<GuardedResolver>>, Full<Bytes>>,
+    allowed: Arc<[IpNetwork]>,
+}
+
+impl Outbound {
+    fn new(allowed: Vec<IpNetwork>) -> Self {
+        let allowed: Arc<[IpNetwork]> = allowed.into();
+        let mut http =
+            HttpConnector::new_with_resolver(GuardedResolver { allowed: allowed.clone() });
+        http.enforce_http(false);
+        let connector = HttpsConnectorBuilder::new()
+            .with_provider_and_webpki_roots(Arc::new(rustls::crypto::ring::default_provider()))
+            .expect("ring supports the default TLS versions")
+            .https_or_http()
+            .enable_http1()
+            .wrap_connector(http);
+        Self { client: Client::builder(TokioExecutor::new()).build(connector), allowed }
+    }
+
+    /// Parses `url` and refuses IP literals outside the allowed networks; those never reach
+    /// the resolver.
+    fn target(&self, url: &str) -> Result<Uri, String> {
+        let uri = url.parse::<Uri>().map_err(|e| format!("invalid URL: {}", e))?;
+        if !matches!(uri.scheme_str(), Some("http" | "https")) {
+            return Err("URL must start with http:// or https://".to_string());
+        }
+        let host = uri.host().unwrap_or_default();
+        if host.is_empty() {
+            return Err("URL must include a host".to_string());
+        }
+        if let Ok(ip) = host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>()
+            && !is_allowed(ip, &self.allowed)
+        {
+            return Err(format!(
+                "{} is an internal address; list it in RUSTZEN_OUTBOUND_ALLOW_NETWORKS to allow it",
+                ip
+            ));
+        }
+        Ok(uri)
+    }
+
+    async fn post_json(
+        &self,
+        url: &str,
+        headers: &[(&str, String)],
+        body: String,
+        timeout: Duration,
+    ) -> Result<u16, String> {
+        let body = Some(("application/json", body));
+        let response = tokio::time::timeout(timeout, self.send(Method::POST, url, headers, body))
+            .await
+            .map_err(|_| format!("timed out after {}s", timeout.as_secs()))??;
+        Ok(response.status().as_u16())
+    }
+
+    async fn fetch(
+        &self,
+        method: Method,
+        url: &str,
+        headers: &[(&str, String)],
+        body: Option<(&str, String)>,
+        timeout: Duration,
+    ) -> Result<(u16, String), String> {
+        tokio::time::timeout(timeout, async {
+            let response = self.send(method, url, headers, body).await?;
+            let status = response.status().as_u16();
+            let bytes = response
+                .into_body()
+                .collect()
+                .await
+                .map_err(|e| format!("reading response failed: {}", e))?
+                .to_bytes();
+            Ok((status, String::from_utf8_lossy(&bytes).into_owned()))
+        })
+        .await
+        .map_err(|_| format!("timed out after {}s", timeout.as_secs()))?
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        url: &str,
+        headers: &[(&str, String)],
+        body: Option<(&str, String)>,
+    ) -> Result<Response<Incoming>, String> {
+        let uri = self.target(url)?;
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::USER_AGENT, concat!("rustzen-admin/", env!("CARGO_PKG_VERSION")));
+        let body = match body {
+            Some((content_type, body)) => {
+                request = request.header(header::CONTENT_TYPE, content_type);
+                body
+            }
+            None => String::new(),
+        };
+        for (name, value) in headers {
+            request = request.header(*name, value);
+        }
+        let request = request
+            .body(Full::new(Bytes::from(body)))
+            .map_err(|e| format!("bad request: {}", e))?;
+
+        self.client.request(request).await.map_err(|e| format!("request failed: {}", describe(&e)))
+    }
+}
+
+/// `error` followed by its causes; the client's own message alone is just "client error".
+fn describe(error: &dyn Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(&format!(": {}", cause));
+        source = cause.source();
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Outbound;
+    use rustzen_config::IpNetwork;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[test]
+    fn only_http_and_https_urls_with_a_public_host_are_accepted() {
+        let outbound = Outbound::new(Vec::new());
+
+        assert!(outbound.target("http://hooks.internal:8080/rustzen").is_ok());
+        assert!(outbound.target("https://hooks.example.com").is_ok());
+        assert!(outbound.target("ftp://hooks.example.com").is_err());
+        assert!(outbound.target("/relative/path").is_err());
+        for internal in [
+            "http://127.0.0.1:9801",
+            "http://10.1.2.3",
+            "https://169.254.169.254/latest/meta-data",
+            "http://[::1]:8080",
+            "http://[fd00::1]",
+            "http://[::ffff:192.168.0.1]",
+        ] {
+            assert!(outbound.target(internal).is_err(), "{}", internal);
+        }
+
+        let outbound = Outbound::new(vec!["10.0.0.0/8".parse::<IpNetwork>().unwrap()]);
+        assert!(outbound.target("http://10.1.2.3").is_ok());
+        assert!(outbound.target("http://192.168.0.1").is_err());
+    }
+
+    #[tokio::test]
+    async fn names_resolving_to_internal_addresses_are_refused() {
+        let outbound = Outbound::new(Vec::new());
+
+        let error = outbound
+            .post_json("http://localhost:9/hook", &[], "{}".to_string(), Duration::from_secs(5))
+            .await
+            .unwrap_err();
+
+        assert!(error.contains("RUSTZEN_OUTBOUND_ALLOW_NETWORKS"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn posts_body_and_headers_and_reports_status() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut raw = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !raw.ends_with(b"{\"ok\":true}") {
+                let n = socket.read(&mut buf).await.unwrap();
+                assert!(n > 0, "connection closed before the body arrived");
+                raw.extend_from_slice(&buf[..n]);
+            }
+            socket.write_all(b"HTTP/1.1 202 Accepted\r\ncontent-length: 0\r\n\r\n").await.unwrap();
+            String::from_utf8(raw).unwrap()
+        });
+        let outbound = Outbound::new(vec!["127.0.0.1".parse::<IpNetwork>().unwrap()]);
+
+        let status = outbound
+            .post_json(
+                &format!("http://localhost:{}/hook?x=1", addr.port()),
+                &[("x-test", "yes".to_string())],
+                "{\"ok\":true}".to_string(),
+                Duration::from_secs(5),
+            )
+            .await
+            .unwrap();
+        let raw = server.await.unwrap();
+
+        assert_eq!(status, 202);
+        assert!(raw.starts_with("POST /hook?x=1 HTTP/1.1"));
+        assert!(raw.contains("x-test: yes"));
+        assert!(raw.ends_with("{\"ok\":true}"));
+    }
+}
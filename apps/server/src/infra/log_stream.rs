@@ -0,0 +1,133 @@
+//! Live tail of the server's tracing output for the admin console.
+//!
+//! [`LogStreamLayer`] copies each event that passes the subscriber's filter into a
+//! broadcast channel, and `GET /api/system/logs/stream` forwards it to connected admins.
+//! Nothing is formatted while nobody listens, and a slow client skips lines instead of
+//! holding up logging.
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt};
+use tokio::sync::broadcast;
+use tracing::{
+    Event, Level, Subscriber,
+    field::{Field, Visit},
+};
+use tracing_subscriber::{Layer, layer::Context};
+
+/// Lines a subscriber may fall behind before it starts skipping.
+const CHANNEL_CAPACITY: usize = 1024;
+
+static LOG_STREAM: Lazy<broadcast::Sender<LogLine>> =
+    Lazy::new(|| broadcast::channel(CHANNEL_CAPACITY).0);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogLine {
+    pub time: DateTime<Utc>,
+    /// `TRACE`, `DEBUG`, `INFO`, `WARN` or `ERROR`.
+    pub level: &'static str,
+    pub target: String,
+    pub message: String,
+    /// Structured fields other than `message`, rendered as text.
+    pub fields: BTreeMap<&'static str, String>,
+    #[serde(skip)]
+    severity: Level,
+}
+
+impl LogLine {
+    /// `true` when the line is at `min` or more severe; `WARN` passes for `INFO`.
+    pub fn at_least(&self, min: Level) -> bool {
+        self.severity <= min
+    }
+}
+
+/// New receiver for lines logged from now on.
+pub fn subscribe() -> broadcast::Receiver<LogLine> {
+    LOG_STREAM.subscribe()
+}
+
+/// Publishes events to [`subscribe`]rs.
+///
+/// Only events that pass `RUST_LOG` arrive here, so the stream cannot show more detail
+/// than the log files.
+pub struct LogStreamLayer;
+
+impl<S: Subscriber> Layer<S> for LogStreamLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if LOG_STREAM.receiver_count() == 0 {
+            return;
+        }
+        let metadata = event.metadata();
+        let mut visitor = LineVisitor::default();
+        event.record(&mut visitor);
+        let _ = LOG_STREAM.send(LogLine {
+            time: Utc::now(),
+            level: metadata.level().as_str(),
+            target: metadata.target().to_string(),
+            message: visitor.message,
+            fields: visitor.fields,
+            severity: *metadata.level(),
+        });
+    }
+}
+
+#[derive(Default)]
+struct LineVisitor {
+    message: String,
+    fields: BTreeMap<&'static str, String>,
+}
+
+impl Visit for LineVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "message" => self.message = value.to_string(),
+            name => {
+                self.fields.insert(name, value.to_string());
+            }
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "message" => self.message = format!("{:?}", value),
+            name => {
+                self.fields.insert(name, format!("{:?}", value));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LogStreamLayer, subscribe};
+
+    use tracing::Level;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn events_reach_subscribers_with_their_fields() {
+        let mut receiver = subscribe();
+        let subscriber = tracing_subscriber::registry().with(LogStreamLayer);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(target: "log_stream_probe", user_id = 7, route = "/api/x", "slow {}", "request");
+            tracing::debug!(target: "log_stream_probe", "details");
+        });
+
+        let mut probes = Vec::new();
+        while let Ok(line) = receiver.try_recv() {
+            if line.target == "log_stream_probe" {
+                probes.push(line);
+            }
+        }
+        assert_eq!(probes.len(), 2);
+        let warn = &probes[0];
+        assert_eq!((warn.level, warn.message.as_str()), ("WARN", "slow request"));
+        assert_eq!(warn.fields["user_id"], "7");
+        assert_eq!(warn.fields["route"], "/api/x");
+        assert!(warn.at_least(Level::INFO) && warn.at_least(Level::WARN));
+        assert!(!warn.at_least(Level::ERROR));
+        assert!(!probes[1].at_least(Level::INFO));
+    }
+}
@@ -0,0 +1,187 @@
+//! Background writer for the operation log.
+//!
+//! Request and login paths hand records to [`LOG_WRITER`] instead of inserting them, so
+//! their latency and outcome never depend on `operation_logs`. The queue is bounded: when
+//! the writer falls behind, the oldest records are dropped and counted. The writer waits
+//! for a full batch or `FLUSH_INTERVAL`, whichever comes first, and writes each batch with
+//! one multi-row insert; a failed batch is logged and dropped. [`LogWriter::shutdown`]
+//! writes what is left when the server stops.
+//!
+//! Until [`LogWriter::spawn`] runs (tests, CLI tools) nothing is queued and records are
+//! written inline.
+
+use crate::features::manage::log::{repo::LogRepository, types::LogWriteCommand};
+
+use once_cell::sync::Lazy;
+use sqlx::SqlitePool;
+use std::{
+    collections::VecDeque,
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+use tokio::{sync::Notify, time::Instant};
+
+/// Records held while the writer catches up; beyond this the oldest are dropped.
+const QUEUE_CAPACITY: usize = 10_000;
+/// Records per insert statement; a full batch is written without waiting.
+const BATCH_SIZE: usize = 100;
+/// Longest a record waits in the queue for its batch to fill.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+pub static LOG_WRITER: Lazy<LogWriter> = Lazy::new(|| LogWriter::new(QUEUE_CAPACITY));
+
+struct LogQueue {
+    capacity: usize,
+    records: VecDeque<LogWriteCommand>,
+    dropped: u64,
+}
+
+pub struct LogWriter {
+    queue: Mutex<LogQueue>,
+    wakeup: Notify,
+    running: AtomicBool,
+}
+
+impl LogWriter {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            queue: Mutex::new(LogQueue { capacity, records: VecDeque::new(), dropped: 0 }),
+            wakeup: Notify::new(),
+            running: AtomicBool::new(false),
+        }
+    }
+
+    /// Starts draining the queue into `pool`. Call once at startup.
+    pub fn spawn(&'static self, pool: SqlitePool) {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        tokio::spawn(async move {
+            loop {
+                self.wakeup.notified().await;
+                let deadline = Instant::now() + FLUSH_INTERVAL;
+                while self.queued() < BATCH_SIZE {
+                    if tokio::time::timeout_at(deadline, self.wakeup.notified()).await.is_err() {
+                        break;
+                    }
+                }
+                self.flush(&pool).await;
+            }
+        });
+    }
+
+    /// Stops queueing, so later records are written inline, and writes what is queued.
+    pub async fn shutdown(&self, pool: &SqlitePool) {
+        self.running.store(false, Ordering::SeqCst);
+        let written = self.flush(pool).await;
+        tracing::info!(written, "Flushed queued operation logs");
+    }
+
+    fn queued(&self) -> usize {
+        self.queue.lock().unwrap_or_else(|e| e.into_inner()).records.len()
+    }
+
+    /// Queues `command` for the writer, dropping the oldest record when the queue is full.
+    ///
+    /// Hands the command back when no writer is running, for the caller to write inline.
+    pub fn submit(&self, command: LogWriteCommand) -> Option<LogWriteCommand> {
+        if !self.running.load(Ordering::SeqCst) {
+            return Some(command);
+        }
+        {
+            let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+            if queue.records.len() >= queue.capacity {
+                queue.records.pop_front();
+                queue.dropped += 1;
+            }
+            queue.records.push_back(command);
+        }
+        self.wakeup.notify_one();
+        None
+    }
+
+    /// Writes everything queued so far, batch by batch, and returns how many records were
+    /// stored.
+    pub async fn flush(&self, pool: &SqlitePool) -> usize {
+        let mut written = 0;
+        loop {
+            let (batch, dropped) = {
+                let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+                let len = queue.records.len().min(BATCH_SIZE);
+                let batch: Vec<LogWriteCommand> = queue.records.drain(..len).collect();
+                (batch, std::mem::take(&mut queue.dropped))
+            };
+            if dropped > 0 {
+                tracing::warn!(dropped, "Operation log queue was full; dropped the oldest records");
+            }
+            if batch.is_empty() {
+                return written;
+            }
+            match LogRepository::insert_log_entries(pool, &batch).await {
+                Ok(()) => written += batch.len(),
+                Err(e) => tracing::error!(
+                    records = batch.len(),
+                    "Failed to write operation log batch: {:?}",
+                    e
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LogWriter;
+    use crate::features::manage::log::types::LogWriteCommand;
+    use std::sync::atomic::Ordering;
+
+    fn command(username: &str) -> LogWriteCommand {
+        LogWriteCommand {
+            username: username.to_string(),
+            action: "HTTP_GET".to_string(),
+            status: "SUCCESS".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn full_queues_drop_the_oldest_records_and_flush_in_batches() {
+        let pool = crate::infra::db::test_pool().await;
+        let writer = LogWriter::new(2);
+
+        assert!(writer.submit(command("inline")).is_some());
+        writer.running.store(true, Ordering::SeqCst);
+        for username in ["first", "second", "third"] {
+            assert!(writer.submit(command(username)).is_none());
+        }
+
+        assert_eq!(writer.flush(&pool).await, 2);
+        let usernames: Vec<String> =
+            sqlx::query_scalar("SELECT username FROM operation_logs ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(usernames, ["second", "third"]);
+        assert_eq!(writer.flush(&pool).await, 0);
+    }
+
+    #[tokio::test]
+    async fn shutdown_writes_queued_records_and_stops_queueing() {
+        let pool = crate::infra::db::test_pool().await;
+        let writer = LogWriter::new(10);
+        writer.running.store(true, Ordering::SeqCst);
+        assert!(writer.submit(command("queued")).is_none());
+
+        writer.shutdown(&pool).await;
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM operation_logs")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+        assert!(writer.submit(command("late")).is_some());
+    }
+}
@@ -1,56 +1,165 @@
 use std::{
-    fs,
+    fmt, fs,
     fs::{File, OpenOptions},
     io::{self, Write},
     path::{Path, PathBuf},
     time::Duration,
 };
 
-use chrono::{Local, NaiveDate};
+use chrono::{Local, NaiveDate, SecondsFormat, Utc};
+use serde_json::{Map, Value};
+use tracing::{
+    Event, Subscriber,
+    field::{Field, Visit},
+};
 use tracing_appender::non_blocking::WorkerGuard;
-use tracing_subscriber::{EnvFilter, fmt::writer::MakeWriterExt};
+use tracing_subscriber::{
+    EnvFilter, Layer, Registry,
+    fmt::{FmtContext, FormatEvent, FormatFields, MakeWriter, format::Writer},
+    layer::SubscriberExt,
+    registry::LookupSpan,
+    util::SubscriberInitExt,
+};
+
+use crate::infra::{config::AppConfig, log_stream::LogStreamLayer, slow_log::SlowQueryLayer};
 
-use crate::infra::config::CONFIG;
+/// Level for modules not named in `RUST_LOG` or `RUSTZEN_LOG_LEVELS`.
+const DEFAULT_LOG_LEVEL: &str = "info";
 
 pub struct LoggingGuard {
-    _file_guard: WorkerGuard,
+    _file_guard: Option<WorkerGuard>,
 }
 
-pub fn init_logging() -> Result<LoggingGuard, Box<dyn std::error::Error>> {
-    let log_dir = CONFIG.log_dir();
-    fs::create_dir_all(&log_dir)?;
+pub fn init_logging(
+    config: &'static AppConfig,
+) -> Result<LoggingGuard, Box<dyn std::error::Error>> {
+    let log_dir = config.log_dir();
+    let output = config.log.log_output.trim();
+    let mut outputs: Vec<Box<dyn Layer<Registry> + Send + Sync>> = Vec::new();
+    if output != "file" {
+        outputs.push(output_layer(config, std::io::stdout));
+    }
+    let file_guard = if output != "stdout" {
+        fs::create_dir_all(&log_dir)?;
+        let file_appender = DailyLogWriter::new(&log_dir, &config.log.log_file_prefix)?;
+        let (file_writer, file_guard) = tracing_appender::non_blocking(file_appender);
+        outputs.push(output_layer(config, file_writer));
+        Some(file_guard)
+    } else {
+        None
+    };
 
-    let file_appender = DailyLogWriter::new(&log_dir, &CONFIG.log_file_prefix)?;
-    let (file_writer, file_guard) = tracing_appender::non_blocking(file_appender);
+    let rust_log = std::env::var("RUST_LOG").ok();
+    let env_filter =
+        EnvFilter::try_new(filter_directives(rust_log.as_deref(), &config.log_level_overrides()?))?;
 
-    let env_filter = match std::env::var("RUST_LOG") {
-        Ok(value) if !value.trim().is_empty() => EnvFilter::try_new(value)?,
-        _ => EnvFilter::try_new("info")?,
-    };
-    let writer = std::io::stdout.and(file_writer);
-
-    tracing_subscriber::fmt()
-        .with_env_filter(env_filter)
-        .with_target(false)
-        .compact()
-        .with_ansi(false)
-        .with_writer(writer)
+    tracing_subscriber::registry()
+        .with(outputs)
+        .with(env_filter)
+        .with(SlowQueryLayer)
+        .with(LogStreamLayer)
         .init();
 
-    cleanup_expired_logs()?;
-    spawn_log_cleanup_task();
+    if file_guard.is_some() {
+        cleanup_expired_logs(config)?;
+        spawn_log_cleanup_task(config);
+    }
 
     tracing::info!(
         log_dir = %log_dir.display(),
-        log_file_prefix = %CONFIG.log_file_prefix,
-        log_retention_days = CONFIG.log_retention_days,
+        log_file_prefix = %config.log.log_file_prefix,
+        log_retention_days = config.log.log_retention_days,
+        log_format = %config.log.log_format,
+        log_output = %config.log.log_output,
         "Logging initialized"
     );
 
     Ok(LoggingGuard { _file_guard: file_guard })
 }
 
-fn spawn_log_cleanup_task() {
+/// Formatting layer for one destination, in the configured `RUSTZEN_LOG_FORMAT`.
+fn output_layer<W>(config: &AppConfig, writer: W) -> Box<dyn Layer<Registry> + Send + Sync>
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer().with_ansi(false).with_writer(writer);
+    if config.log.log_format.trim() == "json" {
+        layer.event_format(JsonFormat).boxed()
+    } else {
+        layer.with_target(false).compact().boxed()
+    }
+}
+
+/// `RUST_LOG` (or `info`) followed by the `RUSTZEN_LOG_LEVELS` overrides.
+fn filter_directives(rust_log: Option<&str>, overrides: &[(String, String)]) -> String {
+    let base = rust_log.map(str::trim).filter(|value| !value.is_empty());
+    let mut directives = vec![base.unwrap_or(DEFAULT_LOG_LEVEL).to_string()];
+    directives.extend(overrides.iter().map(|(module, level)| format!("{}={}", module, level)));
+    directives.join(",")
+}
+
+/// One JSON object per line with `timestamp`, `level`, `target`, the event's fields
+/// (including `message`), and `spans` naming the enclosing spans from the outermost.
+struct JsonFormat;
+
+impl<S, N> FormatEvent<S, N> for JsonFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let metadata = event.metadata();
+        let mut object = Map::new();
+        event.record(&mut JsonVisitor(&mut object));
+        // Written after the fields so an event field cannot shadow them.
+        object.insert(
+            "timestamp".to_string(),
+            Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true).into(),
+        );
+        object.insert("level".to_string(), metadata.level().as_str().into());
+        object.insert("target".to_string(), metadata.target().into());
+        if let Some(scope) = ctx.event_scope() {
+            let spans: Vec<Value> = scope.from_root().map(|span| span.name().into()).collect();
+            object.insert("spans".to_string(), spans.into());
+        }
+        writeln!(writer, "{}", Value::Object(object))
+    }
+}
+
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name().to_string(), format!("{:?}", value).into());
+    }
+}
+
+fn spawn_log_cleanup_task(config: &'static AppConfig) {
     tokio::spawn(async move {
         let interval = Duration::from_secs(24 * 60 * 60);
         tracing::debug!("Started log cleanup background task");
@@ -58,7 +167,7 @@ fn spawn_log_cleanup_task() {
             tokio::time::sleep(interval).await;
 
             tracing::debug!("Running expired log cleanup");
-            if let Err(error) = cleanup_expired_logs() {
+            if let Err(error) = cleanup_expired_logs(config) {
                 tracing::error!(%error, "Failed to cleanup expired log files");
             } else {
                 tracing::debug!("Expired log cleanup finished");
@@ -67,8 +176,8 @@ fn spawn_log_cleanup_task() {
     });
 }
 
-fn cleanup_expired_logs() -> Result<(), Box<dyn std::error::Error>> {
-    let log_dir = CONFIG.log_dir();
+fn cleanup_expired_logs(config: &AppConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let log_dir = config.log_dir();
     let today = Local::now().date_naive();
     let mut scanned = 0_u64;
     let mut deleted = 0_u64;
@@ -81,13 +190,13 @@ fn cleanup_expired_logs() -> Result<(), Box<dyn std::error::Error>> {
             continue;
         }
 
-        let Some(file_date) = parse_log_date(&path, &CONFIG.log_file_prefix) else {
+        let Some(file_date) = parse_log_date(&path, &config.log.log_file_prefix) else {
             continue;
         };
 
         scanned += 1;
 
-        if !is_expired_log(file_date, today, CONFIG.log_retention_days) {
+        if !is_expired_log(file_date, today, config.log.log_retention_days) {
             continue;
         }
 
@@ -95,12 +204,12 @@ fn cleanup_expired_logs() -> Result<(), Box<dyn std::error::Error>> {
         deleted += 1;
     }
 
-    let cutoff = today - chrono::Days::new(CONFIG.log_retention_days);
+    let cutoff = today - chrono::Days::new(config.log.log_retention_days);
 
     tracing::info!(
         log_dir = %log_dir.display(),
-        log_file_prefix = %CONFIG.log_file_prefix,
-        retention_days = CONFIG.log_retention_days,
+        log_file_prefix = %config.log.log_file_prefix,
+        retention_days = config.log.log_retention_days,
         cutoff = %cutoff,
         scanned,
         deleted,
@@ -178,9 +287,65 @@ fn open_log_file(
 
 #[cfg(test)]
 mod tests {
-    use super::{is_expired_log, parse_log_date};
+    use super::{JsonFormat, filter_directives, is_expired_log, parse_log_date};
     use chrono::{Days, Local, NaiveDate};
-    use std::path::PathBuf;
+    use std::{
+        io,
+        path::PathBuf,
+        sync::{Arc, Mutex},
+    };
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn json_format_writes_one_object_per_event() {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::registry().with(
+            tracing_subscriber::fmt::layer()
+                .event_format(JsonFormat)
+                .with_writer(move || writer.clone()),
+        );
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = tracing::info_span!("request", id = 3).entered();
+            tracing::warn!(target: "json_probe", user_id = 7, ok = true, level = "spoofed", "slow {}", "call");
+        });
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(output.lines().count(), 1, "{}", output);
+        let line: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(line["level"], "WARN");
+        assert_eq!(line["target"], "json_probe");
+        assert_eq!(line["message"], "slow call");
+        assert_eq!(line["user_id"], 7);
+        assert_eq!(line["ok"], true);
+        assert_eq!(line["spans"], serde_json::json!(["request"]));
+        assert!(line["timestamp"].as_str().unwrap().ends_with('Z'));
+    }
+
+    #[test]
+    fn module_overrides_follow_the_base_filter() {
+        let overrides = vec![("sqlx".to_string(), "warn".to_string())];
+        assert_eq!(filter_directives(None, &overrides), "info,sqlx=warn");
+        assert_eq!(filter_directives(Some(" "), &[]), "info");
+        assert_eq!(
+            filter_directives(Some("debug,hyper=info"), &overrides),
+            "debug,hyper=info,sqlx=warn"
+        );
+    }
 
     #[test]
     fn parse_log_date_returns_date_for_matching_file_name() {
@@ -0,0 +1,229 @@
+//! Failed-login throttling per source IP.
+//!
+//! This is separate from account status: it stops one address from guessing passwords
+//! across many usernames. After `max_failures` failures inside `window` the IP is banned;
+//! every further ban doubles, capped at `max_ban`, until a quiet period of `max_ban` resets
+//! it. A successful login only forgives the failures against its own username, so logging
+//! in to one known account between guesses does not reset the count for the others.
+
+use crate::infra::config::AppConfig;
+
+use once_cell::sync::OnceCell;
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+static LOGIN_THROTTLE: OnceCell<LoginThrottle> = OnceCell::new();
+
+/// The process-wide throttle, with the policy of the `config` it is first asked with.
+pub fn login_throttle(config: &AppConfig) -> &'static LoginThrottle {
+    LOGIN_THROTTLE.get_or_init(|| LoginThrottle::new(ThrottlePolicy::from_config(config)))
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ThrottlePolicy {
+    /// `0` disables throttling.
+    pub max_failures: u32,
+    pub window: Duration,
+    pub base_ban: Duration,
+    pub max_ban: Duration,
+}
+
+impl ThrottlePolicy {
+    fn from_config(config: &AppConfig) -> Self {
+        Self {
+            max_failures: config.auth.login_ip_max_failures,
+            window: Duration::from_secs(config.auth.login_ip_window_secs),
+            base_ban: Duration::from_secs(config.auth.login_ip_ban_secs),
+            max_ban: Duration::from_secs(config.auth.login_ip_max_ban_secs),
+        }
+    }
+
+    /// Length of the ban after `previous_bans` earlier ones.
+    fn ban_for(&self, previous_bans: u32) -> Duration {
+        self.base_ban.saturating_mul(1 << previous_bans.min(16)).min(self.max_ban)
+    }
+}
+
+/// A ban that was just put in place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpBan {
+    pub failures: u32,
+    pub duration: Duration,
+}
+
+#[derive(Debug, Clone)]
+struct IpState {
+    /// Failures inside the current window, per attempted username.
+    failures: HashMap<String, u32>,
+    window_started: Instant,
+    bans: u32,
+    banned_until: Option<Instant>,
+    last_seen: Instant,
+}
+
+pub struct LoginThrottle {
+    policy: ThrottlePolicy,
+    entries: Mutex<HashMap<String, IpState>>,
+}
+
+impl LoginThrottle {
+    pub fn new(policy: ThrottlePolicy) -> Self {
+        Self { policy, entries: Mutex::new(HashMap::new()) }
+    }
+
+    /// Remaining ban time when `ip` may not attempt a login right now.
+    pub fn retry_after(&self, ip: &str, now: Instant) -> Option<Duration> {
+        if self.policy.max_failures == 0 {
+            return None;
+        }
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let banned_until = entries.get(ip)?.banned_until?;
+        banned_until.checked_duration_since(now).filter(|remaining| !remaining.is_zero())
+    }
+
+    /// Counts a failed login for `username` and returns the ban it triggered, if any.
+    pub fn record_failure(&self, ip: &str, username: &str, now: Instant) -> Option<IpBan> {
+        if self.policy.max_failures == 0 {
+            return None;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|_, state| !self.is_stale(state, now));
+        let state = entries.entry(ip.to_string()).or_insert_with(|| IpState {
+            failures: HashMap::new(),
+            window_started: now,
+            bans: 0,
+            banned_until: None,
+            last_seen: now,
+        });
+        if now.duration_since(state.window_started) >= self.policy.window {
+            state.failures.clear();
+            state.window_started = now;
+        }
+        *state.failures.entry(username.to_string()).or_default() += 1;
+        state.last_seen = now;
+        let failures = state.failures.values().sum();
+        if failures < self.policy.max_failures {
+            return None;
+        }
+
+        let ban = IpBan { failures, duration: self.policy.ban_for(state.bans) };
+        state.bans += 1;
+        state.failures.clear();
+        state.window_started = now;
+        state.banned_until = Some(now + ban.duration);
+        Some(ban)
+    }
+
+    /// Forgives the failures against `username` after it logged in; failures against
+    /// other usernames and the ban history stay.
+    pub fn record_success(&self, ip: &str, username: &str) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(state) = entries.get_mut(ip) {
+            state.failures.remove(username);
+        }
+    }
+
+    fn is_stale(&self, state: &IpState, now: Instant) -> bool {
+        let quiet_since =
+            state.banned_until.map_or(state.last_seen, |until| until.max(state.last_seen));
+        now.checked_duration_since(quiet_since)
+            .is_some_and(|quiet| quiet >= self.policy.max_ban.max(self.policy.window))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IpBan, LoginThrottle, ThrottlePolicy};
+    use std::time::{Duration, Instant};
+
+    fn throttle() -> LoginThrottle {
+        LoginThrottle::new(ThrottlePolicy {
+            max_failures: 3,
+            window: Duration::from_secs(60),
+            base_ban: Duration::from_secs(10),
+            max_ban: Duration::from_secs(25),
+        })
+    }
+
+    #[test]
+    fn repeated_failures_ban_the_ip_with_doubling_capped_bans() {
+        let throttle = throttle();
+        let start = Instant::now();
+
+        assert_eq!(throttle.record_failure("10.0.0.1", "alice", start), None);
+        assert_eq!(throttle.record_failure("10.0.0.1", "alice", start), None);
+        assert_eq!(
+            throttle.record_failure("10.0.0.1", "alice", start),
+            Some(IpBan { failures: 3, duration: Duration::from_secs(10) })
+        );
+        assert_eq!(throttle.retry_after("10.0.0.1", start), Some(Duration::from_secs(10)));
+        assert_eq!(throttle.retry_after("10.0.0.2", start), None);
+
+        let later = start + Duration::from_secs(11);
+        assert_eq!(throttle.retry_after("10.0.0.1", later), None);
+        throttle.record_failure("10.0.0.1", "alice", later);
+        throttle.record_failure("10.0.0.1", "alice", later);
+        let ban = throttle.record_failure("10.0.0.1", "alice", later).unwrap();
+        assert_eq!(ban.duration, Duration::from_secs(20));
+
+        let later = later + Duration::from_secs(21);
+        for _ in 0..2 {
+            throttle.record_failure("10.0.0.1", "alice", later);
+        }
+        let ban = throttle.record_failure("10.0.0.1", "alice", later).unwrap();
+        assert_eq!(ban.duration, Duration::from_secs(25));
+    }
+
+    #[test]
+    fn failures_outside_the_window_and_successes_reset_the_count() {
+        let throttle = throttle();
+        let start = Instant::now();
+
+        throttle.record_failure("10.0.0.1", "alice", start);
+        throttle.record_failure("10.0.0.1", "alice", start);
+        let later = start + Duration::from_secs(61);
+        assert_eq!(throttle.record_failure("10.0.0.1", "alice", later), None);
+
+        throttle.record_failure("10.0.0.1", "alice", later);
+        throttle.record_success("10.0.0.1", "alice");
+        assert_eq!(throttle.record_failure("10.0.0.1", "alice", later), None);
+    }
+
+    #[test]
+    fn logins_to_another_account_between_guesses_do_not_reset_the_count() {
+        let throttle = throttle();
+        let start = Instant::now();
+
+        assert_eq!(throttle.record_failure("10.0.0.1", "alice", start), None);
+        throttle.record_success("10.0.0.1", "mallory");
+        assert_eq!(throttle.record_failure("10.0.0.1", "bob", start), None);
+        throttle.record_success("10.0.0.1", "mallory");
+        let ban = throttle.record_failure("10.0.0.1", "carol", start).unwrap();
+        assert_eq!(ban, IpBan { failures: 3, duration: Duration::from_secs(10) });
+
+        let later = start + Duration::from_secs(11);
+        throttle.record_success("10.0.0.1", "mallory");
+        throttle.record_failure("10.0.0.1", "alice", later);
+        throttle.record_failure("10.0.0.1", "bob", later);
+        let ban = throttle.record_failure("10.0.0.1", "carol", later).unwrap();
+        assert_eq!(ban.duration, Duration::from_secs(20));
+    }
+
+    #[test]
+    fn zero_max_failures_disables_throttling() {
+        let throttle = LoginThrottle::new(ThrottlePolicy {
+            max_failures: 0,
+            window: Duration::from_secs(60),
+            base_ban: Duration::from_secs(10),
+            max_ban: Duration::from_secs(10),
+        });
+        let now = Instant::now();
+        for _ in 0..10 {
+            assert_eq!(throttle.record_failure("10.0.0.1", "alice", now), None);
+        }
+        assert_eq!(throttle.retry_after("10.0.0.1", now), None);
+    }
+}
@@ -0,0 +1,490 @@
+//! Minimal SMTP client used for report and account emails.
+//!
+//! Connections are secured per `RUSTZEN_SMTP_TLS`: `starttls` upgrades after the greeting
+//! and fails rather than continue in plaintext, `tls` speaks TLS from the first byte, and
+//! `none` is plain SMTP for a relay on a trusted network. Certificates are verified against
+//! the bundled Mozilla roots. With `RUSTZEN_SMTP_USERNAME` set the client logs in with
+//! `AUTH PLAIN` once the connection is encrypted.
+
+use crate::infra::config::AppConfig;
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use once_cell::sync::{Lazy, OnceCell};
+use rustls::{ClientConfig, RootCertStore, crypto::ring, pki_types::ServerName};
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+use tokio_rustls::{TlsConnector, client::TlsStream};
+
+const BASE64_LINE_LEN: usize = 76;
+
+/// Time allowed for [`send_notice`], which runs inside a request.
+const NOTICE_TIMEOUT: Duration = Duration::from_secs(30);
+
+static TLS: Lazy<TlsConnector> = Lazy::new(|| {
+    let roots = RootCertStore { roots: webpki_roots::TLS_SERVER_ROOTS.to_vec() };
+    let config = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .expect("ring supports the default TLS versions")
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    TlsConnector::from(Arc::new(config))
+});
+
+/// Server and sender for [`send_notice`], set by [`configure`].
+static NOTICES: OnceCell<Option<(SmtpServer, String)>> = OnceCell::new();
+
+#[derive(Debug, Clone)]
+pub struct Attachment {
+    pub file_name: String,
+    pub content_type: &'static str,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MailMessage {
+    pub from: String,
+    pub to: Vec<String>,
+    pub subject: String,
+    pub body: String,
+    pub attachments: Vec<Attachment>,
+}
+
+/// How the connection to the mail server is secured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpTls {
+    StartTls,
+    Tls,
+    None,
+}
+
+/// The mail server and how to talk to it.
+#[derive(Debug, Clone)]
+pub struct SmtpServer {
+    pub host: String,
+    pub port: u16,
+    pub tls: SmtpTls,
+    /// `AUTH PLAIN` username and password.
+    pub credentials: Option<(String, String)>,
+}
+
+impl SmtpServer {
+    /// The server in `RUSTZEN_SMTP_*`; `None` when no host is set.
+    pub fn from_config(config: &AppConfig) -> Option<Self> {
+        let mailer = &config.mailer;
+        let tls = match mailer.smtp_tls.trim() {
+            "tls" => SmtpTls::Tls,
+            "none" => SmtpTls::None,
+            // `starttls`; `Config::validate` rejects anything else.
+            _ => SmtpTls::StartTls,
+        };
+        Some(Self {
+            host: mailer.smtp_host.clone()?,
+            port: mailer.smtp_port,
+            tls,
+            credentials: mailer.smtp_username.clone().zip(mailer.smtp_password.clone()),
+        })
+    }
+}
+
+/// Delivers `message` through `server`.
+///
+/// Connection failures, rejected commands, and timeouts are returned as a readable message.
+pub async fn send(
+    server: &SmtpServer,
+    message: &MailMessage,
+    timeout: Duration,
+) -> Result<(), String> {
+    for address in std::iter::once(&message.from).chain(&message.to) {
+        validate_address(address)?;
+    }
+    if message.to.is_empty() {
+        return Err("no recipients".to_string());
+    }
+    let data = build_message(message)?;
+    tokio::time::timeout(timeout, deliver(server, message, &data))
+        .await
+        .map_err(|_| format!("timed out after {}s", timeout.as_secs()))?
+}
+
+/// Takes the relay and sender for [`send_notice`] from `config`. The router builder calls
+/// this; later calls keep the first setting.
+pub fn configure(config: &AppConfig) {
+    NOTICES.get_or_init(|| SmtpServer::from_config(config).zip(config.mailer.smtp_from.clone()));
+}
+
+/// Whether [`send_notice`] has a relay to send through.
+pub fn notices_enabled() -> bool {
+    NOTICES.get().is_some_and(Option::is_some)
+}
+
+/// Sends a plain-text mail from `RUSTZEN_SMTP_FROM` through the configured server.
+pub async fn send_notice(to: &str, subject: &str, body: String) -> Result<(), String> {
+    let Some((server, from)) = NOTICES.get().and_then(Option::as_ref) else {
+        return Err("SMTP relay is not configured".to_string());
+    };
+    let message = MailMessage {
+        from: from.clone(),
+        to: vec![to.to_string()],
+        subject: subject.to_string(),
+        body,
+        attachments: Vec::new(),
+    };
+    send(server, &message, NOTICE_TIMEOUT).await
+}
+
+async fn deliver(server: &SmtpServer, message: &MailMessage, data: &str) -> Result<(), String> {
+    let stream = TcpStream::connect((server.host.as_str(), server.port))
+        .await
+        .map_err(|e| format!("connect failed: {}", e))?;
+    match server.tls {
+        SmtpTls::None => {
+            let mut stream = BufReader::new(stream);
+            expect(&mut stream, 220, "greeting").await?;
+            transact(&mut stream, server, message, data).await
+        }
+        SmtpTls::Tls => {
+            let mut stream = BufReader::new(tls_connect(&server.host, stream).await?);
+            expect(&mut stream, 220, "greeting").await?;
+            transact(&mut stream, server, message, data).await
+        }
+        SmtpTls::StartTls => {
+            let mut plain = BufReader::new(stream);
+            expect(&mut plain, 220, "greeting").await?;
+            command(&mut plain, "EHLO rustzen-admin", 250).await?;
+            command(&mut plain, "STARTTLS", 220).await?;
+            // Anything already buffered arrived unencrypted and must not be read as a reply.
+            if !plain.buffer().is_empty() {
+                return Err("server sent data before the TLS handshake".to_string());
+            }
+            let mut stream = BufReader::new(tls_connect(&server.host, plain.into_inner()).await?);
+            transact(&mut stream, server, message, data).await
+        }
+    }
+}
+
+async fn tls_connect(host: &str, stream: TcpStream) -> Result<TlsStream<TcpStream>, String> {
+    let name =
+        ServerName::try_from(host.to_string()).map_err(|e| format!("invalid host: {}", e))?;
+    TLS.connect(name, stream).await.map_err(|e| format!("TLS handshake failed: {}", e))
+}
+
+/// Everything after the greeting (and after `STARTTLS`): login, envelope and data.
+async fn transact<S>(
+    stream: &mut BufReader<S>,
+    server: &SmtpServer,
+    message: &MailMessage,
+    data: &str,
+) -> Result<(), String>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    command(stream, "EHLO rustzen-admin", 250).await?;
+    if let Some((username, password)) = &server.credentials {
+        let token = STANDARD.encode(format!("\0{}\0{}", username, password));
+        command(stream, &format!("AUTH PLAIN {}", token), 235).await?;
+    }
+    command(stream, &format!("MAIL FROM:<{}>", message.from), 250).await?;
+    for to in &message.to {
+        command(stream, &format!("RCPT TO:<{}>", to), 250).await?;
+    }
+    command(stream, "DATA", 354).await?;
+    stream
+        .write_all(dot_stuff(data).as_bytes())
+        .await
+        .map_err(|e| format!("write failed: {}", e))?;
+    command(stream, ".", 250).await?;
+    // The message is accepted at this point; a relay that drops the connection early is fine.
+    let _ = command(stream, "QUIT", 221).await;
+    Ok(())
+}
+
+async fn command<S>(stream: &mut BufReader<S>, line: &str, code: u16) -> Result<(), String>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    stream
+        .write_all(format!("{}\r\n", line).as_bytes())
+        .await
+        .map_err(|e| format!("write failed: {}", e))?;
+    let verb = line.split([' ', ':']).next().unwrap_or(line);
+    expect(stream, code, verb).await
+}
+
+/// Reads one (possibly multi-line) reply and checks its status code; 2xx/3xx codes in the
+/// same class as `code` are accepted, so `251 forwarding` passes for `RCPT`.
+async fn expect<R>(read: &mut R, code: u16, step: &str) -> Result<(), String>
+where
+    R: AsyncBufReadExt + Unpin,
+{
+    let mut reply = String::new();
+    loop {
+        let mut line = String::new();
+        let n = read.read_line(&mut line).await.map_err(|e| format!("read failed: {}", e))?;
+        if n == 0 {
+            return Err(format!("connection closed during {}", step));
+        }
+        reply.push_str(line.trim_end());
+        // `250-` continues a multi-line reply, `250 ` ends it.
+        if line.as_bytes().get(3) != Some(&b'-') {
+            break;
+        }
+        reply.push(' ');
+    }
+    let status: u16 = reply.get(..3).and_then(|s| s.parse().ok()).unwrap_or(0);
+    if status / 100 == code / 100 { Ok(()) } else { Err(format!("{} rejected: {}", step, reply)) }
+}
+
+fn validate_address(address: &str) -> Result<(), String> {
+    let valid = address.contains('@')
+        && !address.chars().any(|c| c.is_whitespace() || c.is_control() || c == '<' || c == '>');
+    if valid { Ok(()) } else { Err(format!("invalid address: {:?}", address)) }
+}
+
+/// Builds a `multipart/mixed` message with every part base64-encoded.
+fn build_message(message: &MailMessage) -> Result<String, String> {
+    let boundary = format!("rustzen-{}", uuid::Uuid::new_v4().simple());
+    let mut data = format!(
+        "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nMessage-ID: <{}@rustzen-admin>\r\n\
+         MIME-Version: 1.0\r\nContent-Type: multipart/mixed; boundary=\"{}\"\r\n\r\n",
+        message.from,
+        message.to.join(", "),
+        encode_header(&message.subject),
+        chrono::Utc::now().to_rfc2822(),
+        uuid::Uuid::new_v4().simple(),
+        boundary
+    );
+    data.push_str(&format!(
+        "--{}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: base64\r\n\r\n{}",
+        boundary,
+        base64_lines(message.body.as_bytes())
+    ));
+    for attachment in &message.attachments {
+        if attachment.file_name.chars().any(|c| c.is_control() || c == '"') {
+            return Err(format!("invalid attachment name: {:?}", attachment.file_name));
+        }
+        data.push_str(&format!(
+            "--{boundary}\r\nContent-Type: {}; name=\"{name}\"\r\n\
+             Content-Disposition: attachment; filename=\"{name}\"\r\n\
+             Content-Transfer-Encoding: base64\r\n\r\n{}",
+            attachment.content_type,
+            base64_lines(&attachment.data),
+            name = attachment.file_name,
+        ));
+    }
+    data.push_str(&format!("--{}--\r\n", boundary));
+    Ok(data)
+}
+
+/// RFC 2047 encoded word for non-ASCII headers; line breaks are dropped either way.
+fn encode_header(value: &str) -> String {
+    let value: String = value.chars().filter(|c| !c.is_control()).collect();
+    if value.is_ascii() { value } else { format!("=?UTF-8?B?{}?=", STANDARD.encode(value)) }
+}
+
+fn base64_lines(data: &[u8]) -> String {
+    let encoded = STANDARD.encode(data);
+    let mut lines = String::with_capacity(encoded.len() + encoded.len() / BASE64_LINE_LEN * 2 + 2);
+    for chunk in encoded.as_bytes().chunks(BASE64_LINE_LEN) {
+        lines.push_str(std::str::from_utf8(chunk).unwrap_or_default());
+        lines.push_str("\r\n");
+    }
+    lines
+}
+
+/// Doubles a leading `.` on every line so the relay does not read it as end of data.
+fn dot_stuff(data: &str) -> String {
+    let mut stuffed = String::with_capacity(data.len() + 16);
+    for line in data.split_inclusive("\r\n") {
+        if line.starts_with('.') {
+            stuffed.push('.');
+        }
+        stuffed.push_str(line);
+    }
+    if !stuffed.ends_with("\r\n") {
+        stuffed.push_str("\r\n");
+    }
+    stuffed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Attachment, MailMessage, SmtpServer, SmtpTls, dot_stuff, send};
+
+    use base64::{Engine, engine::general_purpose::STANDARD};
+    use std::time::Duration;
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::TcpListener,
+    };
+
+    /// Fake relay: answers every command with `reply_for` and returns the transcript.
+    async fn fake_relay(
+        reply_for: fn(&str) -> &'static str,
+    ) -> (u16, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let (read, mut write) = socket.into_split();
+            let mut read = BufReader::new(read);
+            write.write_all(b"220 relay ready\r\n").await.unwrap();
+            let mut transcript = String::new();
+            let mut in_data = false;
+            loop {
+                let mut line = String::new();
+                if read.read_line(&mut line).await.unwrap() == 0 {
+                    break;
+                }
+                transcript.push_str(&line);
+                if in_data {
+                    if line == ".\r\n" {
+                        in_data = false;
+                        write.write_all(b"250 queued\r\n").await.unwrap();
+                    }
+                    continue;
+                }
+                let reply = reply_for(line.trim_end());
+                write.write_all(reply.as_bytes()).await.unwrap();
+                in_data = line.starts_with("DATA") && reply.starts_with("354");
+                if line.starts_with("QUIT") || reply.starts_with('5') {
+                    break;
+                }
+            }
+            transcript
+        });
+        (port, server)
+    }
+
+    fn server(port: u16, tls: SmtpTls) -> SmtpServer {
+        SmtpServer { host: "127.0.0.1".to_string(), port, tls, credentials: None }
+    }
+
+    fn message() -> MailMessage {
+        MailMessage {
+            from: "reports@example.com".to_string(),
+            to: vec!["ops@example.com".to_string(), "cfo@example.com".to_string()],
+            subject: "Weekly report".to_string(),
+            body: "See attached.".to_string(),
+            attachments: vec![Attachment {
+                file_name: "report.pdf".to_string(),
+                content_type: "application/pdf",
+                data: b"%PDF-1.4 fake".to_vec(),
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn delivers_each_recipient_and_base64_attachments() {
+        let (port, relay) = fake_relay(|line| match line {
+            l if l.starts_with("EHLO") => "250-relay\r\n250 8BITMIME\r\n",
+            "DATA" => "354 go ahead\r\n",
+            "QUIT" => "221 bye\r\n",
+            _ => "250 ok\r\n",
+        })
+        .await;
+
+        send(&server(port, SmtpTls::None), &message(), Duration::from_secs(5)).await.unwrap();
+        let transcript = relay.await.unwrap();
+
+        assert!(
+            transcript.starts_with("EHLO rustzen-admin\r\nMAIL FROM:<reports@example.com>\r\n")
+        );
+        assert!(transcript.contains("RCPT TO:<ops@example.com>\r\nRCPT TO:<cfo@example.com>\r\n"));
+        assert!(transcript.contains("Content-Disposition: attachment; filename=\"report.pdf\""));
+        assert!(transcript.contains(&STANDARD.encode(b"%PDF-1.4 fake")));
+        assert!(transcript.ends_with(".\r\nQUIT\r\n"));
+    }
+
+    #[tokio::test]
+    async fn rejected_recipient_is_reported() {
+        let (port, relay) = fake_relay(|line| {
+            if line.starts_with("RCPT") { "550 no such user\r\n" } else { "250 ok\r\n" }
+        })
+        .await;
+        let server = server(port, SmtpTls::None);
+
+        let err = send(&server, &message(), Duration::from_secs(5)).await.unwrap_err();
+        relay.await.unwrap();
+        assert_eq!(err, "RCPT rejected: 550 no such user");
+
+        let mut bad = message();
+        bad.to = vec!["ops@example.com>\r\nRCPT TO:<x@evil.test".to_string()];
+        assert!(send(&server, &bad, Duration::from_secs(5)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn logs_in_with_auth_plain_before_the_envelope() {
+        let (port, relay) = fake_relay(|line| match line {
+            l if l.starts_with("AUTH") => "235 authenticated\r\n",
+            "DATA" => "354 go ahead\r\n",
+            "QUIT" => "221 bye\r\n",
+            _ => "250 ok\r\n",
+        })
+        .await;
+        let server = SmtpServer {
+            credentials: Some(("mailer".to_string(), "secret".to_string())),
+            ..server(port, SmtpTls::None)
+        };
+
+        send(&server, &message(), Duration::from_secs(5)).await.unwrap();
+        let transcript = relay.await.unwrap();
+
+        let token = STANDARD.encode("\0mailer\0secret");
+        assert!(
+            transcript
+                .starts_with(&format!("EHLO rustzen-admin\r\nAUTH PLAIN {}\r\nMAIL FROM:", token)),
+            "{}",
+            transcript
+        );
+    }
+
+    #[tokio::test]
+    async fn starttls_never_falls_back_to_plaintext() {
+        let (port, relay) = fake_relay(|line| match line {
+            "STARTTLS" => "502 not implemented\r\n",
+            _ => "250 ok\r\n",
+        })
+        .await;
+
+        let err = send(&server(port, SmtpTls::StartTls), &message(), Duration::from_secs(5))
+            .await
+            .unwrap_err();
+        let transcript = relay.await.unwrap();
+
+        assert_eq!(err, "STARTTLS rejected: 502 not implemented");
+        assert_eq!(transcript, "EHLO rustzen-admin\r\nSTARTTLS\r\n");
+    }
+
+    #[tokio::test]
+    async fn starttls_needs_a_valid_handshake() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let relay = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut socket = BufReader::new(socket);
+            socket.write_all(b"220 relay ready\r\n").await.unwrap();
+            for reply in ["250 ok\r\n", "220 go ahead\r\n"] {
+                socket.read_line(&mut String::new()).await.unwrap();
+                socket.write_all(reply.as_bytes()).await.unwrap();
+            }
+            // Wait for the ClientHello, then answer in plaintext.
+            socket.fill_buf().await.unwrap();
+            socket.write_all(b"250 not tls\r\n").await.unwrap();
+        });
+
+        let err = send(&server(port, SmtpTls::StartTls), &message(), Duration::from_secs(5))
+            .await
+            .unwrap_err();
+        relay.await.unwrap();
+
+        assert!(err.starts_with("TLS handshake failed"), "{}", err);
+    }
+
+    #[test]
+    fn leading_dots_are_doubled() {
+        assert_eq!(dot_stuff("a\r\n.b\r\n..c"), "a\r\n..b\r\n...c\r\n");
+    }
+}
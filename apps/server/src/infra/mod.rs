@@ -1,8 +1,31 @@
+pub mod access_window;
 pub mod app;
 pub mod auth_runtime;
+pub mod cli;
 pub mod config;
 pub mod db;
+pub mod dev_proxy;
+pub mod error_report;
+pub mod events;
+pub mod geoip;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod host_metrics;
+pub mod http_client;
+pub mod log_stream;
+pub mod log_writer;
 pub mod logger;
+pub mod login_throttle;
+pub mod mail;
+pub mod oauth;
+pub mod otp;
+pub mod outbox;
 pub mod password;
 pub mod permission;
+pub mod route_metrics;
+pub mod session;
+pub mod single_session;
+pub mod slow_log;
+pub mod sms;
 pub mod system_info;
+pub mod tls;
@@ -0,0 +1,454 @@
+//! Scan-to-login connectors for WeChat Work, DingTalk and Feishu.
+//!
+//! Each connector is switched on by its client id in `RUSTZEN_OAUTH_*` and calls the
+//! provider's HTTPS API directly.
+//!
+//! A login round trip carries a `state` issued here: it names the provider and what the
+//! code will be used for, is single-use, and expires after [`STATE_TTL`]. The browser that
+//! started the round trip also gets the [`STATE_COOKIE`] holding the state's hash, and the
+//! callback must come with it, so a victim sent someone else's callback URL is not signed
+//! in to that account.
+
+use crate::{
+    common::token,
+    infra::{
+        config::AppConfig,
+        http_client::{self, form_encode},
+    },
+};
+
+use async_trait::async_trait;
+use axum::http::Method;
+use axum_extra::extract::cookie::{Cookie, SameSite};
+use once_cell::sync::{Lazy, OnceCell};
+use rustzen_core::oauth::{ConnectorError, ExternalIdentity, LoginConnector};
+use serde_json::{Value, json};
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+const CALL_TIMEOUT: Duration = Duration::from_secs(10);
+pub const STATE_TTL: Duration = Duration::from_secs(10 * 60);
+pub const STATE_COOKIE: &str = "rustzen_oauth_state";
+
+pub const WECOM_API: &str = "https://qyapi.weixin.qq.com";
+pub const DINGTALK_API: &str = "https://api.dingtalk.com";
+pub const FEISHU_API: &str = "https://open.feishu.cn";
+
+static CONNECTORS: OnceCell<Vec<Box<dyn LoginConnector>>> = OnceCell::new();
+
+pub static STATES: Lazy<StateStore> = Lazy::new(StateStore::default);
+
+/// Connectors switched on in the configuration.
+pub fn connectors(config: &AppConfig) -> &'static [Box<dyn LoginConnector>] {
+    CONNECTORS.get_or_init(|| from_config(config))
+}
+
+/// Uses `connectors` instead of the configured ones; call it before the first login request.
+/// Integration tests use it to stand in for the provider APIs.
+pub fn install_connectors(connectors: Vec<Box<dyn LoginConnector>>) {
+    if CONNECTORS.set(connectors).is_err() {
+        panic!("login connectors are already in use");
+    }
+}
+
+/// The connector for `provider`, when it is switched on.
+pub fn connector(config: &AppConfig, provider: &str) -> Option<&'static dyn LoginConnector> {
+    connectors(config).iter().find(|c| c.provider() == provider).map(|c| c.as_ref())
+}
+
+fn from_config(config: &AppConfig) -> Vec<Box<dyn LoginConnector>> {
+    let mut connectors: Vec<Box<dyn LoginConnector>> = Vec::new();
+    if let (Some(corp_id), Some(agent_id), Some(secret)) = (
+        config.oauth.oauth_wecom_corp_id.clone(),
+        config.oauth.oauth_wecom_agent_id,
+        config.oauth.oauth_wecom_secret.clone(),
+    ) {
+        connectors.push(Box::new(WeComConnector::new(corp_id, agent_id, secret)));
+    }
+    if let (Some(client_id), Some(client_secret)) = (
+        config.oauth.oauth_dingtalk_client_id.clone(),
+        config.oauth.oauth_dingtalk_client_secret.clone(),
+    ) {
+        connectors.push(Box::new(DingTalkConnector::new(client_id, client_secret)));
+    }
+    if let (Some(app_id), Some(app_secret)) =
+        (config.oauth.oauth_feishu_app_id.clone(), config.oauth.oauth_feishu_app_secret.clone())
+    {
+        connectors.push(Box::new(FeishuConnector::new(app_id, app_secret)));
+    }
+    connectors
+}
+
+/// What a returning `code` may be used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OAuthIntent {
+    Login,
+    /// Link the provider account to this user.
+    Link(i64),
+}
+
+#[derive(Debug)]
+struct PendingState {
+    provider: &'static str,
+    intent: OAuthIntent,
+    issued_at: Instant,
+}
+
+#[derive(Default)]
+pub struct StateStore {
+    entries: Mutex<HashMap<String, PendingState>>,
+}
+
+impl StateStore {
+    pub fn issue(&self, provider: &'static str, intent: OAuthIntent, now: Instant) -> String {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|_, pending| now.duration_since(pending.issued_at) < STATE_TTL);
+        let state = token::generate();
+        entries.insert(state.clone(), PendingState { provider, intent, issued_at: now });
+        state
+    }
+
+    /// Consumes `state`, returning its provider and intent while it is still valid.
+    pub fn take(&self, state: &str, now: Instant) -> Option<(&'static str, OAuthIntent)> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let pending = entries.remove(state)?;
+        (now.duration_since(pending.issued_at) < STATE_TTL)
+            .then_some((pending.provider, pending.intent))
+    }
+}
+
+/// HttpOnly cookie tying the round trip for `state` to the browser that started it.
+pub fn state_cookie(config: &AppConfig, state: &str) -> Cookie<'static> {
+    let mut cookie = Cookie::new(STATE_COOKIE, token::hash(state));
+    cookie.set_path("/api");
+    cookie.set_http_only(true);
+    cookie.set_secure(config.session_cookie_is_secure());
+    cookie.set_same_site(SameSite::Lax);
+    cookie.set_max_age(time::Duration::seconds(STATE_TTL.as_secs() as i64));
+    cookie
+}
+
+/// Whether the [`STATE_COOKIE`] value a callback came with was set for `state`.
+pub fn state_cookie_matches(cookie: Option<&str>, state: &str) -> bool {
+    cookie.is_some_and(|cookie| cookie == token::hash(state))
+}
+
+/// App-level access token reused until shortly before it expires.
+#[derive(Default)]
+struct TokenCache(Mutex<Option<(String, Instant)>>);
+
+impl TokenCache {
+    fn get(&self) -> Option<String> {
+        let cached = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        cached.as_ref().filter(|(_, until)| Instant::now() < *until).map(|(token, _)| token.clone())
+    }
+
+    fn put(&self, token: &str, expires_in: u64) {
+        let until = Instant::now() + Duration::from_secs(expires_in.saturating_sub(300));
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = Some((token.to_string(), until));
+    }
+}
+
+/// Calls a provider API and returns its JSON answer; non-2xx answers are rejections.
+async fn call(
+    method: Method,
+    url: &str,
+    headers: &[(&str, String)],
+    body: Option<Value>,
+) -> Result<Value, ConnectorError> {
+    let (status, text) =
+        http_client::send_json(method, url, headers, body.map(|b| b.to_string()), CALL_TIMEOUT)
+            .await
+            .map_err(ConnectorError::Unreachable)?;
+    if !(200..300).contains(&status) {
+        return Err(ConnectorError::Rejected(format!("HTTP {}: {}", status, text)));
+    }
+    serde_json::from_str(&text)
+        .map_err(|e| ConnectorError::Rejected(format!("unreadable answer {:?}: {}", text, e)))
+}
+
+/// `body[key]` as a string; `None` when missing or empty.
+fn text(body: &Value, key: &str) -> Option<String> {
+    body.get(key).and_then(Value::as_str).filter(|value| !value.is_empty()).map(str::to_string)
+}
+
+/// Fails unless the numeric status field `key` is 0, which WeChat Work and Feishu use for
+/// success.
+fn ensure_ok(body: &Value, key: &str) -> Result<(), ConnectorError> {
+    match body.get(key).and_then(Value::as_i64) {
+        Some(0) => Ok(()),
+        _ => Err(ConnectorError::Rejected(body.to_string())),
+    }
+}
+
+/// WeChat Work (企业微信) web login for members of one corp.
+pub struct WeComConnector {
+    /// API base, [`WECOM_API`] unless replaced with [`Self::with_url`].
+    pub url: String,
+    pub corp_id: String,
+    pub agent_id: u64,
+    pub secret: String,
+    token: TokenCache,
+}
+
+impl WeComConnector {
+    pub fn new(corp_id: String, agent_id: u64, secret: String) -> Self {
+        Self { url: WECOM_API.to_string(), corp_id, agent_id, secret, token: TokenCache::default() }
+    }
+
+    pub fn with_url(self, url: &str) -> Self {
+        Self { url: url.trim_end_matches('/').to_string(), ..self }
+    }
+
+    async fn access_token(&self) -> Result<String, ConnectorError> {
+        if let Some(token) = self.token.get() {
+            return Ok(token);
+        }
+        let query = form_encode(&[("corpid", &self.corp_id), ("corpsecret", &self.secret)]);
+        let body =
+            call(Method::GET, &format!("{}/cgi-bin/gettoken?{}", self.url, query), &[], None)
+                .await?;
+        ensure_ok(&body, "errcode")?;
+        let token = text(&body, "access_token")
+            .ok_or_else(|| ConnectorError::Rejected(body.to_string()))?;
+        self.token.put(&token, body["expires_in"].as_u64().unwrap_or(7200));
+        Ok(token)
+    }
+}
+
+#[async_trait]
+impl LoginConnector for WeComConnector {
+    fn provider(&self) -> &'static str {
+        "wecom"
+    }
+
+    fn authorize_url(&self, redirect_uri: &str, state: &str) -> String {
+        format!(
+            "https://login.work.weixin.qq.com/wwlogin/sso/login?{}",
+            form_encode(&[
+                ("login_type", "CorpApp"),
+                ("appid", &self.corp_id),
+                ("agentid", &self.agent_id.to_string()),
+                ("redirect_uri", redirect_uri),
+                ("state", state),
+            ])
+        )
+    }
+
+    async fn exchange_code(&self, code: &str) -> Result<ExternalIdentity, ConnectorError> {
+        let token = self.access_token().await?;
+        let query = form_encode(&[("access_token", &token), ("code", code)]);
+        let body = call(
+            Method::GET,
+            &format!("{}/cgi-bin/auth/getuserinfo?{}", self.url, query),
+            &[],
+            None,
+        )
+        .await?;
+        ensure_ok(&body, "errcode")?;
+        // People outside the corp only get an `openid`; they cannot sign in here.
+        let user_id = text(&body, "userid").ok_or_else(|| {
+            ConnectorError::Rejected("the account is not a member of the corp".to_string())
+        })?;
+
+        let query = form_encode(&[("access_token", &token), ("userid", &user_id)]);
+        let member =
+            call(Method::GET, &format!("{}/cgi-bin/user/get?{}", self.url, query), &[], None)
+                .await?;
+        // Contact details depend on the app's visibility; the login works without them.
+        let member = if ensure_ok(&member, "errcode").is_ok() { member } else { Value::Null };
+        Ok(ExternalIdentity {
+            subject: user_id,
+            name: text(&member, "name"),
+            email: text(&member, "email").or_else(|| text(&member, "biz_mail")),
+            mobile: text(&member, "mobile"),
+        })
+    }
+}
+
+/// DingTalk (钉钉) OAuth 2.0 login.
+pub struct DingTalkConnector {
+    /// API base, [`DINGTALK_API`] unless replaced with [`Self::with_url`].
+    pub url: String,
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+impl DingTalkConnector {
+    pub fn new(client_id: String, client_secret: String) -> Self {
+        Self { url: DINGTALK_API.to_string(), client_id, client_secret }
+    }
+
+    pub fn with_url(self, url: &str) -> Self {
+        Self { url: url.trim_end_matches('/').to_string(), ..self }
+    }
+}
+
+#[async_trait]
+impl LoginConnector for DingTalkConnector {
+    fn provider(&self) -> &'static str {
+        "dingtalk"
+    }
+
+    fn authorize_url(&self, redirect_uri: &str, state: &str) -> String {
+        format!(
+            "https://login.dingtalk.com/oauth2/auth?{}",
+            form_encode(&[
+                ("redirect_uri", redirect_uri),
+                ("response_type", "code"),
+                ("client_id", &self.client_id),
+                ("scope", "openid"),
+                ("state", state),
+                ("prompt", "consent"),
+            ])
+        )
+    }
+
+    async fn exchange_code(&self, code: &str) -> Result<ExternalIdentity, ConnectorError> {
+        let body = call(
+            Method::POST,
+            &format!("{}/v1.0/oauth2/userAccessToken", self.url),
+            &[],
+            Some(json!({
+                "clientId": self.client_id,
+                "clientSecret": self.client_secret,
+                "code": code,
+                "grantType": "authorization_code",
+            })),
+        )
+        .await?;
+        let token =
+            text(&body, "accessToken").ok_or_else(|| ConnectorError::Rejected(body.to_string()))?;
+        let me = call(
+            Method::GET,
+            &format!("{}/v1.0/contact/users/me", self.url),
+            &[("x-acs-dingtalk-access-token", token)],
+            None,
+        )
+        .await?;
+        Ok(ExternalIdentity {
+            subject: text(&me, "unionId")
+                .ok_or_else(|| ConnectorError::Rejected(me.to_string()))?,
+            name: text(&me, "nick"),
+            email: text(&me, "email"),
+            mobile: text(&me, "mobile"),
+        })
+    }
+}
+
+/// Feishu (飞书) web login.
+pub struct FeishuConnector {
+    /// API base, [`FEISHU_API`] unless replaced with [`Self::with_url`].
+    pub url: String,
+    pub app_id: String,
+    pub app_secret: String,
+    token: TokenCache,
+}
+
+impl FeishuConnector {
+    pub fn new(app_id: String, app_secret: String) -> Self {
+        Self { url: FEISHU_API.to_string(), app_id, app_secret, token: TokenCache::default() }
+    }
+
+    pub fn with_url(self, url: &str) -> Self {
+        Self { url: url.trim_end_matches('/').to_string(), ..self }
+    }
+
+    async fn app_access_token(&self) -> Result<String, ConnectorError> {
+        if let Some(token) = self.token.get() {
+            return Ok(token);
+        }
+        let body = call(
+            Method::POST,
+            &format!("{}/open-apis/auth/v3/app_access_token/internal", self.url),
+            &[],
+            Some(json!({ "app_id": self.app_id, "app_secret": self.app_secret })),
+        )
+        .await?;
+        ensure_ok(&body, "code")?;
+        let token = text(&body, "app_access_token")
+            .ok_or_else(|| ConnectorError::Rejected(body.to_string()))?;
+        self.token.put(&token, body["expire"].as_u64().unwrap_or(7200));
+        Ok(token)
+    }
+}
+
+#[async_trait]
+impl LoginConnector for FeishuConnector {
+    fn provider(&self) -> &'static str {
+        "feishu"
+    }
+
+    fn authorize_url(&self, redirect_uri: &str, state: &str) -> String {
+        format!(
+            "https://accounts.feishu.cn/open-apis/authen/v1/authorize?{}",
+            form_encode(&[
+                ("client_id", &self.app_id),
+                ("redirect_uri", redirect_uri),
+                ("state", state),
+            ])
+        )
+    }
+
+    async fn exchange_code(&self, code: &str) -> Result<ExternalIdentity, ConnectorError> {
+        let app_token = self.app_access_token().await?;
+        let body = call(
+            Method::POST,
+            &format!("{}/open-apis/authen/v1/oidc/access_token", self.url),
+            &[("authorization", format!("Bearer {}", app_token))],
+            Some(json!({ "grant_type": "authorization_code", "code": code })),
+        )
+        .await?;
+        ensure_ok(&body, "code")?;
+        let token = text(&body["data"], "access_token")
+            .ok_or_else(|| ConnectorError::Rejected(body.to_string()))?;
+        let body = call(
+            Method::GET,
+            &format!("{}/open-apis/authen/v1/user_info", self.url),
+            &[("authorization", format!("Bearer {}", token))],
+            None,
+        )
+        .await?;
+        ensure_ok(&body, "code")?;
+        let user = &body["data"];
+        Ok(ExternalIdentity {
+            subject: text(user, "open_id")
+                .ok_or_else(|| ConnectorError::Rejected(body.to_string()))?,
+            name: text(user, "name"),
+            email: text(user, "email").or_else(|| text(user, "enterprise_email")),
+            mobile: text(user, "mobile"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DingTalkConnector, OAuthIntent, STATE_TTL, StateStore};
+    use rustzen_core::oauth::LoginConnector;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn states_are_single_use_and_expire() {
+        let store = StateStore::default();
+        let now = Instant::now();
+        let state = store.issue("feishu", OAuthIntent::Link(7), now);
+
+        assert_eq!(store.take(&state, now), Some(("feishu", OAuthIntent::Link(7))));
+        assert_eq!(store.take(&state, now), None);
+        let state = store.issue("feishu", OAuthIntent::Login, now);
+        assert_eq!(store.take(&state, now + STATE_TTL + Duration::from_secs(1)), None);
+    }
+
+    #[test]
+    fn authorize_urls_carry_the_encoded_redirect_and_state() {
+        let connector = DingTalkConnector::new("ding123".to_string(), "secret".to_string());
+        let url = connector.authorize_url("https://admin.example.com/oauth?x=1", "abc");
+
+        assert!(url.starts_with("https://login.dingtalk.com/oauth2/auth?"), "{}", url);
+        assert!(url.contains("redirect_uri=https%3A%2F%2Fadmin.example.com%2Foauth%3Fx%3D1"));
+        assert!(url.contains("client_id=ding123&scope=openid&state=abc"), "{}", url);
+    }
+}
@@ -0,0 +1,181 @@
+//! One-time SMS codes for phone login, phone binding and critical actions.
+//!
+//! Codes live in memory only, hashed, keyed by purpose and subject (a phone number or a
+//! user id). A subject can request a new code once per `resend_interval`; a code is good
+//! for `ttl` and `max_attempts` guesses, and is consumed by the first correct one.
+
+use crate::{
+    common::{error::ServiceError, token},
+    infra::{config::AppConfig, sms},
+};
+
+use once_cell::sync::Lazy;
+use rustzen_core::sms::OtpMessage;
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+pub static OTP: Lazy<OtpStore> = Lazy::new(|| OtpStore::new(OtpPolicy::default()));
+
+/// What a code may be used for; a code issued for one purpose never verifies another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OtpPurpose {
+    Login,
+    BindPhone,
+    CriticalAction,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct OtpPolicy {
+    pub ttl: Duration,
+    pub resend_interval: Duration,
+    pub max_attempts: u32,
+}
+
+impl Default for OtpPolicy {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(5 * 60),
+            resend_interval: Duration::from_secs(60),
+            max_attempts: 5,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct OtpEntry {
+    code_hash: String,
+    issued_at: Instant,
+    attempts: u32,
+}
+
+pub struct OtpStore {
+    policy: OtpPolicy,
+    entries: Mutex<HashMap<(OtpPurpose, String), OtpEntry>>,
+}
+
+impl OtpStore {
+    pub fn new(policy: OtpPolicy) -> Self {
+        Self { policy, entries: Mutex::new(HashMap::new()) }
+    }
+
+    /// Issues a fresh 6-digit code for `subject`, replacing any earlier one.
+    ///
+    /// Returns the wait in seconds when the previous code is younger than the resend interval.
+    pub fn issue(&self, purpose: OtpPurpose, subject: &str, now: Instant) -> Result<String, u64> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|_, entry| now.duration_since(entry.issued_at) < self.policy.ttl);
+        let key = (purpose, subject.to_string());
+        if let Some(entry) = entries.get(&key) {
+            let elapsed = now.duration_since(entry.issued_at);
+            if elapsed < self.policy.resend_interval {
+                return Err((self.policy.resend_interval - elapsed).as_secs().max(1));
+            }
+        }
+        let code = format!("{:06}", uuid::Uuid::new_v4().as_u128() % 1_000_000);
+        entries
+            .insert(key, OtpEntry { code_hash: token::hash(&code), issued_at: now, attempts: 0 });
+        Ok(code)
+    }
+
+    /// Whether `code` is the live code for `subject`; a match consumes it.
+    pub fn verify(&self, purpose: OtpPurpose, subject: &str, code: &str, now: Instant) -> bool {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let key = (purpose, subject.to_string());
+        let Some(entry) = entries.get_mut(&key) else {
+            return false;
+        };
+        if now.duration_since(entry.issued_at) >= self.policy.ttl
+            || entry.attempts >= self.policy.max_attempts
+        {
+            entries.remove(&key);
+            return false;
+        }
+        if entry.code_hash == token::hash(code.trim()) {
+            entries.remove(&key);
+            return true;
+        }
+        entry.attempts += 1;
+        false
+    }
+
+    /// Minutes a code stays valid, as told to the user.
+    pub fn ttl_minutes(&self) -> u32 {
+        (self.policy.ttl.as_secs() / 60) as u32
+    }
+}
+
+/// Issues a code for `subject` and texts it to `phone` through the configured gateway.
+pub async fn send_code(
+    config: &AppConfig,
+    purpose: OtpPurpose,
+    subject: &str,
+    phone: &str,
+) -> Result<(), ServiceError> {
+    let Some(provider) = sms::provider(config) else {
+        return Err(ServiceError::InvalidOperation("SMS is not configured".to_string()));
+    };
+    let code = OTP.issue(purpose, subject, Instant::now()).map_err(ServiceError::OtpRateLimited)?;
+    let message = OtpMessage { code, ttl_minutes: OTP.ttl_minutes() };
+    provider.send_otp(phone, &message).await.map_err(|e| {
+        tracing::error!(
+            provider = provider.name(),
+            ?purpose,
+            "Failed to send one-time code: {}",
+            e
+        );
+        ServiceError::InvalidOperation("Could not send the verification code".to_string())
+    })
+}
+
+/// Checks `code` for `subject` against the shared store.
+pub fn verify_code(purpose: OtpPurpose, subject: &str, code: &str) -> Result<(), ServiceError> {
+    if OTP.verify(purpose, subject, code, Instant::now()) {
+        Ok(())
+    } else {
+        Err(ServiceError::InvalidVerificationCode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OtpPolicy, OtpPurpose, OtpStore};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn codes_are_single_use_and_scoped_to_their_purpose() {
+        let store = OtpStore::new(OtpPolicy::default());
+        let now = Instant::now();
+        let code = store.issue(OtpPurpose::Login, "+8613812345678", now).unwrap();
+
+        assert_eq!(code.len(), 6);
+        assert!(!store.verify(OtpPurpose::BindPhone, "+8613812345678", &code, now));
+        assert!(store.verify(OtpPurpose::Login, "+8613812345678", &code, now));
+        assert!(!store.verify(OtpPurpose::Login, "+8613812345678", &code, now));
+    }
+
+    #[test]
+    fn resend_is_rate_limited_and_codes_expire() {
+        let store = OtpStore::new(OtpPolicy::default());
+        let now = Instant::now();
+        store.issue(OtpPurpose::Login, "1", now).unwrap();
+
+        assert_eq!(store.issue(OtpPurpose::Login, "1", now + Duration::from_secs(15)), Err(45));
+        let code = store.issue(OtpPurpose::Login, "1", now + Duration::from_secs(60)).unwrap();
+        assert!(!store.verify(OtpPurpose::Login, "1", &code, now + Duration::from_secs(400)));
+    }
+
+    #[test]
+    fn too_many_wrong_guesses_burn_the_code() {
+        let store = OtpStore::new(OtpPolicy { max_attempts: 2, ..OtpPolicy::default() });
+        let now = Instant::now();
+        let code = store.issue(OtpPurpose::CriticalAction, "7", now).unwrap();
+        let wrong = if code == "000000" { "000001" } else { "000000" };
+
+        assert!(!store.verify(OtpPurpose::CriticalAction, "7", wrong, now));
+        assert!(!store.verify(OtpPurpose::CriticalAction, "7", wrong, now));
+        assert!(!store.verify(OtpPurpose::CriticalAction, "7", &code, now));
+    }
+}
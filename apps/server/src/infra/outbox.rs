@@ -0,0 +1,278 @@
+//! Transactional outbox between services and event subscribers.
+//!
+//! [`record`] writes one `event_outbox` row per subscriber inside the caller's transaction,
+//! so an event exists exactly when the change it describes committed. [`relay`] claims due
+//! rows, hands each to its subscriber and deletes it once handled. Claiming bumps
+//! `attempts` and pushes `available_at` out by `CLAIM_LEASE_SECS`; a relay that dies mid-row
+//! leaves it to be claimed again, so subscribers can see an event more than once. A failed
+//! delivery waits with doubling backoff and is marked `failed` after `MAX_ATTEMPTS`.
+
+use crate::common::{error::ServiceError, tx::Tx};
+
+use chrono::{Duration as ChronoDuration, NaiveDateTime, Utc};
+use rustzen_core::events::{DomainEvent, EventBus};
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use std::time::Duration;
+
+/// Rows claimed per relay query.
+const RELAY_BATCH_SIZE: i64 = 50;
+/// How long a claimed row is hidden from other relays while its subscriber runs.
+const CLAIM_LEASE_SECS: i64 = 60;
+/// Delay before the first retry; doubled on each further failure.
+const RETRY_BASE_SECS: i64 = 10;
+/// Attempts before a row is kept as `failed` for an operator to look at.
+const MAX_ATTEMPTS: i64 = 8;
+/// How often the background relay looks for rows left behind by inline delivery.
+const RELAY_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Outbox rows written by [`record`]; pass them to [`deliver`] once the transaction commits.
+#[must_use = "deliver the recorded rows after committing, or leave them to the relay worker"]
+#[derive(Debug, Default)]
+pub struct Recorded(Vec<i64>);
+
+#[derive(sqlx::FromRow)]
+struct ClaimedRow {
+    id: i64,
+    subscriber: String,
+    payload: String,
+    attempts: i64,
+}
+
+fn db_error(context: &str, e: sqlx::Error) -> ServiceError {
+    tracing::error!("Database error {}: {:?}", context, e);
+    ServiceError::DatabaseQueryFailed
+}
+
+/// Queues `event` for every subscriber of `bus` as part of `tx`.
+pub async fn record<C: Sync>(
+    tx: &mut Tx<'_>,
+    bus: &EventBus<C>,
+    event: &DomainEvent,
+) -> Result<Recorded, ServiceError> {
+    let payload = serde_json::to_string(event).map_err(|e| {
+        tracing::error!("Failed to serialize event {}: {:?}", event.name(), e);
+        ServiceError::InvalidOperation(format!("Event {} cannot be stored", event.name()))
+    })?;
+    let mut ids = Vec::new();
+    for subscriber in bus.subscriber_names() {
+        let id = sqlx::query_scalar(
+            "INSERT INTO event_outbox (subscriber, event, payload) VALUES (?, ?, ?) RETURNING id",
+        )
+        .bind(subscriber)
+        .bind(event.name())
+        .bind(&payload)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| db_error("recording outbox event", e))?;
+        ids.push(id);
+    }
+    Ok(Recorded(ids))
+}
+
+/// Delivers the rows from [`record`] now instead of on the next relay pass. Rows another
+/// relay has already claimed are skipped.
+pub async fn deliver(
+    pool: &SqlitePool,
+    bus: &EventBus<SqlitePool>,
+    recorded: Recorded,
+) -> Result<(), ServiceError> {
+    if recorded.0.is_empty() {
+        return Ok(());
+    }
+    let now = Utc::now().naive_utc();
+    let mut query = claim_query(now);
+    query.push(" AND id IN (");
+    let mut ids = query.separated(", ");
+    for id in &recorded.0 {
+        ids.push_bind(*id);
+    }
+    ids.push_unseparated(")");
+    finish_claim(&mut query);
+    let rows = query
+        .build_query_as::<ClaimedRow>()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_error("claiming recorded outbox rows", e))?;
+    for row in rows {
+        handle_row(pool, bus, row, now).await?;
+    }
+    Ok(())
+}
+
+/// Delivers every due row, batch by batch, and returns how many were attempted.
+pub async fn relay(pool: &SqlitePool, bus: &EventBus<SqlitePool>) -> Result<usize, ServiceError> {
+    let mut attempted = 0;
+    loop {
+        let now = Utc::now().naive_utc();
+        let mut query = claim_query(now);
+        query.push(" ORDER BY id LIMIT ").push_bind(RELAY_BATCH_SIZE);
+        finish_claim(&mut query);
+        let rows = query
+            .build_query_as::<ClaimedRow>()
+            .fetch_all(pool)
+            .await
+            .map_err(|e| db_error("claiming due outbox rows", e))?;
+        let batch_len = rows.len();
+        for row in rows {
+            handle_row(pool, bus, row, now).await?;
+        }
+        attempted += batch_len;
+        if (batch_len as i64) < RELAY_BATCH_SIZE {
+            return Ok(attempted);
+        }
+    }
+}
+
+/// Runs [`relay`] every `RELAY_POLL_INTERVAL` for rows that were not delivered inline.
+pub fn spawn_relay(pool: SqlitePool, bus: &'static EventBus<SqlitePool>) {
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = relay(&pool, bus).await {
+                tracing::error!("Event outbox relay pass failed: {:?}", e);
+            }
+            tokio::time::sleep(RELAY_POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// Opens the claim statement up to the point where callers narrow down which pending rows.
+fn claim_query(now: NaiveDateTime) -> QueryBuilder<Sqlite> {
+    let mut query = QueryBuilder::<Sqlite>::new(
+        "UPDATE event_outbox SET attempts = attempts + 1, available_at = ",
+    );
+    query
+        .push_bind(now + ChronoDuration::seconds(CLAIM_LEASE_SECS))
+        .push(
+            " WHERE id IN (SELECT id FROM event_outbox WHERE status = 'pending' AND available_at <= ",
+        )
+        .push_bind(now);
+    query
+}
+
+fn finish_claim(query: &mut QueryBuilder<Sqlite>) {
+    query.push(") RETURNING id, subscriber, payload, attempts");
+}
+
+async fn handle_row(
+    pool: &SqlitePool,
+    bus: &EventBus<SqlitePool>,
+    row: ClaimedRow,
+    now: NaiveDateTime,
+) -> Result<(), ServiceError> {
+    let outcome = match serde_json::from_str::<DomainEvent>(&row.payload) {
+        Ok(event) => bus.deliver(pool, &row.subscriber, &event).await,
+        Err(e) => Err(format!("unreadable payload: {}", e)),
+    };
+    match outcome {
+        Ok(()) => {
+            sqlx::query("DELETE FROM event_outbox WHERE id = ?")
+                .bind(row.id)
+                .execute(pool)
+                .await
+                .map_err(|e| db_error("removing delivered outbox row", e))?;
+        }
+        Err(error) if row.attempts >= MAX_ATTEMPTS => {
+            tracing::error!(
+                "Outbox row {} for {} failed after {} attempts: {}",
+                row.id,
+                row.subscriber,
+                row.attempts,
+                error
+            );
+            sqlx::query("UPDATE event_outbox SET status = 'failed', last_error = ? WHERE id = ?")
+                .bind(error)
+                .bind(row.id)
+                .execute(pool)
+                .await
+                .map_err(|e| db_error("marking outbox row failed", e))?;
+        }
+        Err(error) => {
+            tracing::warn!(
+                "Outbox row {} for {} failed (attempt {}): {}",
+                row.id,
+                row.subscriber,
+                row.attempts,
+                error
+            );
+            let delay = RETRY_BASE_SECS << (row.attempts - 1).clamp(0, 16);
+            sqlx::query("UPDATE event_outbox SET available_at = ?, last_error = ? WHERE id = ?")
+                .bind(now + ChronoDuration::seconds(delay))
+                .bind(error)
+                .bind(row.id)
+                .execute(pool)
+                .await
+                .map_err(|e| db_error("rescheduling outbox row", e))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{deliver, record, relay};
+    use async_trait::async_trait;
+    use rustzen_core::events::{DomainEvent, EventBus, EventSubscriber};
+    use sqlx::SqlitePool;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    /// Fails its first call, then counts the events it accepts.
+    #[derive(Default)]
+    struct Flaky {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl EventSubscriber<SqlitePool> for Flaky {
+        fn name(&self) -> &'static str {
+            "flaky"
+        }
+
+        async fn handle(&self, _: &SqlitePool, _: &DomainEvent) -> Result<(), String> {
+            match self.calls.fetch_add(1, Ordering::SeqCst) {
+                0 => Err("endpoint unavailable".to_string()),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn events_survive_failed_delivery_and_vanish_with_a_rollback() {
+        let pool = crate::infra::db::test_pool().await;
+        let flaky = Arc::new(Flaky::default());
+        let bus = EventBus::new().subscribe(flaky.clone());
+        let event = DomainEvent::RoleDeleted { role_id: 7, operator_id: 1 };
+
+        let mut tx = crate::common::tx::begin(&pool).await.unwrap();
+        let _ = record(&mut tx, &bus, &event).await.unwrap();
+        drop(tx);
+        let rows: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM event_outbox").fetch_one(&pool).await.unwrap();
+        assert_eq!(rows, 0);
+
+        let mut tx = crate::common::tx::begin(&pool).await.unwrap();
+        let recorded = record(&mut tx, &bus, &event).await.unwrap();
+        crate::common::tx::commit(tx).await.unwrap();
+        deliver(&pool, &bus, recorded).await.unwrap();
+        let (attempts, last_error): (i64, Option<String>) = sqlx::query_as(
+            "SELECT attempts, last_error FROM event_outbox WHERE status = 'pending'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!((attempts, last_error.as_deref()), (1, Some("endpoint unavailable")));
+
+        // Not due yet, so the relay leaves it alone.
+        assert_eq!(relay(&pool, &bus).await.unwrap(), 0);
+        sqlx::query("UPDATE event_outbox SET available_at = '2000-01-01 00:00:00'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(relay(&pool, &bus).await.unwrap(), 1);
+        let rows: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM event_outbox").fetch_one(&pool).await.unwrap();
+        assert_eq!((rows, flaky.calls.load(Ordering::SeqCst)), (0, 2));
+    }
+}
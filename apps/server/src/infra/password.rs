@@ -1,40 +1,118 @@
-use crate::common::error::ServiceError;
+use crate::{common::error::ServiceError, infra::config::AppConfig};
 
 use argon2::{
-    Argon2,
+    Argon2, Params,
     password_hash::{PasswordHasher, PasswordVerifier, phc::PasswordHash},
 };
 
+/// Supported password hash algorithms.
+///
+/// Argon2id is preferred; bcrypt is accepted for hashes imported from older systems
+/// and can be selected for deployments that must keep producing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordAlgorithm {
+    Argon2id,
+    Bcrypt,
+}
+
+impl PasswordAlgorithm {
+    /// Reads the algorithm family from a stored hash's prefix.
+    ///
+    /// Every Argon2 variant maps to `Argon2id`; [`HashPolicy::needs_rehash`] checks the variant.
+    pub fn detect(hash: &str) -> Option<Self> {
+        if hash.starts_with("$argon2") {
+            Some(Self::Argon2id)
+        } else if ["$2a$", "$2b$", "$2x$", "$2y$"].iter().any(|prefix| hash.starts_with(prefix)) {
+            Some(Self::Bcrypt)
+        } else {
+            None
+        }
+    }
+}
+
+/// Which algorithm and strength new hashes use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HashPolicy {
+    pub algorithm: PasswordAlgorithm,
+    pub bcrypt_cost: u32,
+}
+
+impl HashPolicy {
+    /// Policy for new passwords, from `RUSTZEN_PASSWORD_ALGORITHM` / `RUSTZEN_BCRYPT_COST`.
+    pub fn from_config(config: &AppConfig) -> Self {
+        let algorithm = match config.auth.password_algorithm.trim() {
+            "bcrypt" => PasswordAlgorithm::Bcrypt,
+            _ => PasswordAlgorithm::Argon2id,
+        };
+        Self { algorithm, bcrypt_cost: config.auth.bcrypt_cost }
+    }
+
+    pub fn hash_password(&self, password: &str) -> Result<String, ServiceError> {
+        match self.algorithm {
+            PasswordAlgorithm::Argon2id => Argon2::default()
+                .hash_password(password.as_bytes())
+                .map(|hash| hash.to_string())
+                .map_err(|_| ServiceError::PasswordHashingFailed),
+            PasswordAlgorithm::Bcrypt => bcrypt::hash(password, self.bcrypt_cost)
+                .map_err(|_| ServiceError::PasswordHashingFailed),
+        }
+    }
+
+    /// Whether `hash` uses another algorithm or weaker parameters than this policy.
+    ///
+    /// Unrecognized hashes return `false`: they cannot be verified, so there is
+    /// never a plain-text password to rehash them from.
+    pub fn needs_rehash(&self, hash: &str) -> bool {
+        match PasswordAlgorithm::detect(hash) {
+            None => false,
+            Some(algorithm) if algorithm != self.algorithm => true,
+            Some(PasswordAlgorithm::Argon2id) => {
+                let Ok(parsed) = PasswordHash::new(hash) else {
+                    return false;
+                };
+                let Ok(params) = Params::try_from(&parsed) else {
+                    return true;
+                };
+                let current = Params::default();
+                parsed.algorithm.as_str() != "argon2id"
+                    || params.m_cost() < current.m_cost()
+                    || params.t_cost() < current.t_cost()
+                    || params.p_cost() < current.p_cost()
+            }
+            Some(PasswordAlgorithm::Bcrypt) => hash
+                .get(4..6)
+                .and_then(|cost| cost.parse::<u32>().ok())
+                .is_none_or(|cost| cost < self.bcrypt_cost),
+        }
+    }
+}
+
 /// Password utilities for secure hashing and verification.
 pub struct PasswordUtils;
 
 impl PasswordUtils {
-    /// Hashes a plain-text password using Argon2.
+    /// Hashes a plain-text password with the configured algorithm.
     ///
-    /// This function generates a random salt and uses Argon2 with default parameters
-    /// to create a secure hash of the provided password.
+    /// Argon2id uses default parameters and a random salt; bcrypt uses
+    /// `RUSTZEN_BCRYPT_COST`.
     ///
     /// # Arguments
     ///
+    /// * `config` - Supplies the hashing policy
     /// * `password` - The plain-text password to hash
     ///
     /// # Returns
     ///
     /// * `Ok(String)` - The hashed password as a string
     /// * `Err(ServiceError::PasswordHashingFailed)` - If hashing fails
-    pub fn hash_password(password: &str) -> Result<String, ServiceError> {
-        let argon2 = Argon2::default();
-        let password_hash = argon2
-            .hash_password(password.as_bytes())
-            .map_err(|_| ServiceError::PasswordHashingFailed)?
-            .to_string();
-        Ok(password_hash)
+    pub fn hash_password(config: &AppConfig, password: &str) -> Result<String, ServiceError> {
+        HashPolicy::from_config(config).hash_password(password)
     }
 
     /// Verifies a password against a hash.
     ///
-    /// This function parses the stored hash and verifies if the provided
-    /// plain-text password matches the hash.
+    /// The algorithm is detected from the stored hash, so Argon2 and bcrypt hashes
+    /// both verify regardless of the configured algorithm.
     ///
     /// # Arguments
     ///
@@ -46,24 +124,37 @@ impl PasswordUtils {
     /// * `true` - If the password matches the hash
     /// * `false` - If the password doesn't match or hash parsing fails
     pub fn verify_password(password: &str, hash: &str) -> bool {
-        let parsed_hash = match PasswordHash::new(hash) {
-            Ok(h) => h,
-            Err(_) => return false,
-        };
-        Argon2::default().verify_password(password.as_bytes(), &parsed_hash).is_ok()
+        match PasswordAlgorithm::detect(hash) {
+            Some(PasswordAlgorithm::Argon2id) => {
+                let parsed_hash = match PasswordHash::new(hash) {
+                    Ok(h) => h,
+                    Err(_) => return false,
+                };
+                Argon2::default().verify_password(password.as_bytes(), &parsed_hash).is_ok()
+            }
+            Some(PasswordAlgorithm::Bcrypt) => bcrypt::verify(password, hash).unwrap_or(false),
+            None => false,
+        }
+    }
+
+    /// Whether a verified hash should be replaced under the configured policy.
+    pub fn needs_rehash(config: &AppConfig, hash: &str) -> bool {
+        HashPolicy::from_config(config).needs_rehash(hash)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::infra::config::test_config;
 
     #[test]
     fn test_password_hashing_and_verification() {
         let password = "test_password_123";
 
         // Test hashing
-        let hash = PasswordUtils::hash_password(password).expect("Should hash password");
+        let hash =
+            PasswordUtils::hash_password(test_config(), password).expect("Should hash password");
         assert!(!hash.is_empty());
 
         // Test verification with correct password
@@ -81,8 +172,10 @@ mod tests {
         let password1 = "password1";
         let password2 = "password2";
 
-        let hash1 = PasswordUtils::hash_password(password1).expect("Should hash password1");
-        let hash2 = PasswordUtils::hash_password(password2).expect("Should hash password2");
+        let hash1 =
+            PasswordUtils::hash_password(test_config(), password1).expect("Should hash password1");
+        let hash2 =
+            PasswordUtils::hash_password(test_config(), password2).expect("Should hash password2");
 
         assert_ne!(hash1, hash2);
     }
@@ -92,8 +185,10 @@ mod tests {
         // Due to random salt, same password should produce different hashes
         let password = "same_password";
 
-        let hash1 = PasswordUtils::hash_password(password).expect("Should hash password");
-        let hash2 = PasswordUtils::hash_password(password).expect("Should hash password");
+        let hash1 =
+            PasswordUtils::hash_password(test_config(), password).expect("Should hash password");
+        let hash2 =
+            PasswordUtils::hash_password(test_config(), password).expect("Should hash password");
 
         assert_ne!(hash1, hash2);
 
@@ -101,4 +196,40 @@ mod tests {
         assert!(PasswordUtils::verify_password(password, &hash1));
         assert!(PasswordUtils::verify_password(password, &hash2));
     }
+
+    #[test]
+    fn test_legacy_bcrypt_hashes_verify_and_are_flagged_for_rehash() {
+        let argon2 = HashPolicy { algorithm: PasswordAlgorithm::Argon2id, bcrypt_cost: 12 };
+        let bcrypt = HashPolicy { algorithm: PasswordAlgorithm::Bcrypt, bcrypt_cost: 4 };
+
+        let legacy = bcrypt.hash_password("legacy_password").expect("Should hash password");
+        assert_eq!(PasswordAlgorithm::detect(&legacy), Some(PasswordAlgorithm::Bcrypt));
+        assert!(PasswordUtils::verify_password("legacy_password", &legacy));
+        assert!(!PasswordUtils::verify_password("wrong_password", &legacy));
+
+        assert!(argon2.needs_rehash(&legacy));
+        assert!(!bcrypt.needs_rehash(&legacy));
+        assert!(HashPolicy { bcrypt_cost: 5, ..bcrypt }.needs_rehash(&legacy));
+
+        let current = argon2.hash_password("current_password").expect("Should hash password");
+        assert!(!argon2.needs_rehash(&current));
+        assert!(bcrypt.needs_rehash(&current));
+    }
+
+    #[test]
+    fn test_weaker_argon2_hashes_are_flagged_for_rehash() {
+        let policy = HashPolicy { algorithm: PasswordAlgorithm::Argon2id, bcrypt_cost: 12 };
+        let weak = Argon2::new(
+            argon2::Algorithm::Argon2i,
+            argon2::Version::V0x13,
+            Params::new(8 * 1024, 1, 1, None).unwrap(),
+        )
+        .hash_password(b"weak_password")
+        .unwrap()
+        .to_string();
+
+        assert!(PasswordUtils::verify_password("weak_password", &weak));
+        assert!(policy.needs_rehash(&weak));
+        assert!(!policy.needs_rehash("invalid_hash"));
+    }
 }
@@ -1,15 +1,19 @@
-use crate::common::error::ServiceError;
+use crate::{
+    common::error::ServiceError,
+    features::system::permission::service::PermissionRegistryService,
+    infra::{access_window::AccessWindow, config::AppConfig},
+};
 
 use chrono::{DateTime, Duration, Utc};
 use once_cell::sync::Lazy;
 use rustzen_core::{
     auth::CurrentUser,
-    capability::{SYSTEM_WILDCARD, is_deploy_capability_code},
+    capability::{SYSTEM_WILDCARD, is_deploy_capability_code, system_privacy},
     permission::{PermissionsCheck, take_registered_permission_codes},
 };
-use serde::{Deserialize, Serialize};
 use sqlx::{Sqlite, SqlitePool};
 use std::collections::{BTreeSet, HashMap, HashSet};
+use std::net::IpAddr;
 use std::sync::{Arc, RwLock};
 
 /// Capability cache expiration time (1 hour)
@@ -67,11 +71,20 @@ pub struct MenuSeedRecord {
     pub is_manual: bool,
 }
 
+/// Capabilities of a role that only apply inside its [`AccessWindow`].
+#[derive(Debug, Clone)]
+pub struct RestrictedGrant {
+    pub window: AccessWindow,
+    pub permissions: HashSet<String>,
+}
+
 /// Cached user permissions with expiration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
 pub struct UserPermissionCache {
-    /// User's capability set
+    /// Capabilities granted by roles without network or time limits
     pub permissions: HashSet<String>,
+    /// Capabilities of restricted roles, applied per request
+    pub restricted: Vec<RestrictedGrant>,
     /// Cache creation timestamp
     pub cached_at: DateTime<Utc>,
 }
@@ -79,7 +92,17 @@ pub struct UserPermissionCache {
 impl UserPermissionCache {
     /// Create new capability cache for user runtime checks
     pub fn new(permissions: Vec<String>) -> Self {
-        Self { permissions: permissions.into_iter().collect(), cached_at: Utc::now() }
+        Self::with_restricted(permissions, Vec::new())
+    }
+
+    pub fn with_restricted(permissions: Vec<String>, restricted: Vec<RestrictedGrant>) -> Self {
+        Self { permissions: permissions.into_iter().collect(), restricted, cached_at: Utc::now() }
+    }
+
+    /// Every capability the user holds, whatever the request.
+    fn all_permissions(&self) -> HashSet<String> {
+        let restricted = self.restricted.iter().flat_map(|grant| grant.permissions.iter());
+        self.permissions.iter().chain(restricted).cloned().collect()
     }
 
     /// Check if cached capabilities have expired.
@@ -107,7 +130,7 @@ impl PermissionCacheManager {
 
     /// Store user capabilities in cache.
     pub fn set(&self, user_id: i64, permission_cache: UserPermissionCache) {
-        let permission_count = permission_cache.permissions.len();
+        let permission_count = permission_cache.all_permissions().len();
         if let Ok(mut cache) = self.cache.write() {
             cache.insert(user_id, permission_cache);
             tracing::debug!(
@@ -119,6 +142,15 @@ impl PermissionCacheManager {
         }
     }
 
+    /// Number of users with cached capabilities.
+    pub fn len(&self) -> usize {
+        self.cache.read().map(|cache| cache.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     /// Remove user capability cache.
     pub fn remove(&self, user_id: i64) {
         if let Ok(mut cache) = self.cache.write() {
@@ -131,6 +163,9 @@ impl PermissionCacheManager {
 /// Global capability cache instance
 static PERMISSION_CACHE: Lazy<PermissionCacheManager> = Lazy::new(PermissionCacheManager::new);
 
+/// Every code a route has required since startup; kept for the capability registry.
+static ROUTE_CODES: Lazy<RwLock<BTreeSet<String>>> = Lazy::new(|| RwLock::new(BTreeSet::new()));
+
 /// Permission service with intelligent caching
 pub struct PermissionService;
 
@@ -138,6 +173,10 @@ impl PermissionService {
     /// Synchronize collected route permissions into the menus table.
     pub async fn sync_permissions(pool: &SqlitePool) -> Result<(), ServiceError> {
         let raw_codes = take_registered_permission_codes();
+        ROUTE_CODES
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend(raw_codes.iter().map(|code| code.trim().to_string()));
         let seed_records = build_menu_seed_records(&raw_codes);
 
         if seed_records.is_empty() {
@@ -171,7 +210,12 @@ impl PermissionService {
             ServiceError::DatabaseQueryFailed
         })?;
 
-        Ok(())
+        PermissionRegistryService::warn_on_issues(pool).await
+    }
+
+    /// Codes required by the routes registered so far.
+    pub fn route_codes() -> BTreeSet<String> {
+        ROUTE_CODES.read().unwrap_or_else(|e| e.into_inner()).clone()
     }
 
     /// Check whether a user has a specific capability code.
@@ -193,16 +237,34 @@ impl PermissionService {
 
     /// Cache user capabilities (called during login).
     pub fn cache_user_permissions(user_id: i64, permissions: &[String]) {
-        let permission_cache = UserPermissionCache::new(permissions.to_vec());
+        Self::cache_user_grants(user_id, permissions, Vec::new());
+    }
+
+    /// Cache user capabilities, keeping those of restricted roles apart so each request
+    /// only gets the ones its address and time allow.
+    pub fn cache_user_grants(
+        user_id: i64,
+        permissions: &[String],
+        restricted: Vec<RestrictedGrant>,
+    ) {
+        let restricted_roles = restricted.len();
+        let permission_cache =
+            UserPermissionCache::with_restricted(permissions.to_vec(), restricted);
         PERMISSION_CACHE.set(user_id, permission_cache);
         tracing::info!(
-            "Cached {} permissions for user {} (expires in {}h)",
+            "Cached {} permissions and {} restricted roles for user {} (expires in {}h)",
             permissions.len(),
+            restricted_roles,
             user_id,
             CACHE_EXPIRE_HOURS
         );
     }
 
+    /// Users with a cached capability set, for the system info panel.
+    pub fn cached_user_count() -> usize {
+        PERMISSION_CACHE.len()
+    }
+
     /// Clear user cache (called during logout)
     pub fn clear_user_cache(user_id: i64) {
         PERMISSION_CACHE.remove(user_id);
@@ -224,12 +286,33 @@ impl PermissionService {
             return Err(ServiceError::InvalidToken);
         }
 
-        Ok(CurrentUser::new(
-            user_id,
-            username,
-            cache.permissions.iter().cloned(),
-            cache.permissions.contains(SYSTEM_WILDCARD),
-        ))
+        let permissions = cache.all_permissions();
+        let is_super = permissions.contains(SYSTEM_WILDCARD);
+        Ok(CurrentUser::new(user_id, username, permissions, is_super))
+    }
+
+    /// Drops the capabilities of restricted roles whose window does not cover a request
+    /// from `client_ip` right now, with hours read in `RUSTZEN_TIMEZONE`. Users without
+    /// restricted roles pass through unchanged.
+    pub fn scope_to_client(
+        config: &AppConfig,
+        current_user: CurrentUser,
+        client_ip: Option<IpAddr>,
+    ) -> CurrentUser {
+        let Some(cache) = PERMISSION_CACHE.get(current_user.user_id) else {
+            return current_user;
+        };
+        if cache.restricted.is_empty() {
+            return current_user;
+        }
+        let timezone = config.timezone().expect("RUSTZEN_TIMEZONE is checked at startup");
+        let now = Utc::now().with_timezone(&timezone);
+        let mut permissions = cache.permissions.clone();
+        for grant in cache.restricted.iter().filter(|grant| grant.window.allows(client_ip, &now)) {
+            permissions.extend(grant.permissions.iter().cloned());
+        }
+        let is_super = permissions.contains(SYSTEM_WILDCARD);
+        CurrentUser::new(current_user.user_id, current_user.username, permissions, is_super)
     }
 }
 
@@ -630,7 +713,12 @@ fn builtin_role_allows_code(role_code: &str, code: &str) -> bool {
     match role_code {
         BUILTIN_OWNER_ROLE_CODE => code == SYSTEM_WILDCARD,
         BUILTIN_ADMIN_ROLE_CODE => is_assignable_leaf_capability(code),
-        BUILTIN_VIEWER_ROLE_CODE => is_assignable_leaf_capability(code) && is_view_capability(code),
+        // Viewers read lists with personal data masked; unmasking is granted explicitly.
+        BUILTIN_VIEWER_ROLE_CODE => {
+            is_assignable_leaf_capability(code)
+                && is_view_capability(code)
+                && code != system_privacy::VIEW
+        }
         _ => false,
     }
 }
@@ -851,6 +939,7 @@ mod tests {
             "manage:deploy:list".to_string(),
             "manage:log:export".to_string(),
             "manage:dict:options".to_string(),
+            "system:privacy:view".to_string(),
         ];
 
         let owner_codes = builtin_role_permission_codes(BUILTIN_OWNER_ROLE_CODE, &menu_codes);
@@ -871,18 +960,15 @@ mod tests {
         assert!(!viewer_codes.contains(&"system:user:create".to_string()));
         assert!(!viewer_codes.contains(&"manage:task:run".to_string()));
         assert!(!viewer_codes.contains(&"manage:log:export".to_string()));
+        assert!(admin_codes.contains(&"system:privacy:view".to_string()));
+        assert!(!viewer_codes.contains(&"system:privacy:view".to_string()));
         assert!(!viewer_codes.iter().any(|code| code == "*" || code.ends_with(":*")));
         assert!(!viewer_codes.iter().any(|code| is_deploy_capability_code(code)));
     }
 
     #[tokio::test]
     async fn sync_permissions_persists_builtin_roles_and_default_owner() {
-        let pool = sqlx::sqlite::SqlitePoolOptions::new()
-            .max_connections(1)
-            .connect("sqlite::memory:")
-            .await
-            .expect("in-memory sqlite pool");
-        crate::infra::db::run_migrations(&pool).await.expect("migrations");
+        let pool = crate::infra::db::test_pool().await;
 
         rustzen_core::permission::register_permission_codes([
             "dashboard:view",
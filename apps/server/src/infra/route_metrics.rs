@@ -0,0 +1,197 @@
+//! In-memory latency histograms and error counts per route template.
+//!
+//! `request_metrics_middleware` records every API request that matched a route. Counts are
+//! kept per minute for the last hour, so like the slow log they are per instance and reset
+//! on restart. Responses with a status of 400 or above count as errors, as in the
+//! operation log route stats.
+
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+};
+
+/// Upper bounds of the latency buckets in milliseconds; slower requests land in a final
+/// open-ended bucket.
+pub const LATENCY_BUCKETS_MS: [u64; 10] = [5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000];
+/// Minutes of history kept per route.
+pub const WINDOW_MINUTES: i64 = 60;
+
+pub static ROUTE_METRICS: Lazy<RouteMetrics> = Lazy::new(RouteMetrics::default);
+
+/// One histogram bucket of a route.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyBucket {
+    /// Upper bound in milliseconds; `None` for the open-ended last bucket.
+    pub le_ms: Option<u64>,
+    pub count: u64,
+}
+
+/// Latency and errors of one route over the window.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteLatency {
+    pub method: String,
+    pub route: String,
+    pub requests: u64,
+    pub errors: u64,
+    /// `errors / requests`, 0 to 1.
+    pub error_rate: f64,
+    pub avg_ms: f64,
+    pub max_ms: u64,
+    /// Percentiles as the upper bound of the bucket they fall in, or `maxMs` past the last.
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub p99_ms: u64,
+    pub histogram: Vec<LatencyBucket>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct MinuteCounts {
+    minute: i64,
+    requests: u64,
+    errors: u64,
+    total_ms: u64,
+    max_ms: u64,
+    buckets: [u64; LATENCY_BUCKETS_MS.len() + 1],
+}
+
+#[derive(Default)]
+pub struct RouteMetrics {
+    routes: Mutex<HashMap<(String, String), VecDeque<MinuteCounts>>>,
+}
+
+impl RouteMetrics {
+    pub fn record(&self, method: &str, route: &str, status_code: u16, duration_ms: u64) {
+        self.record_at(current_minute(), method, route, status_code, duration_ms);
+    }
+
+    /// Every route seen in the window, busiest first.
+    pub fn summary(&self) -> Vec<RouteLatency> {
+        self.summary_at(current_minute())
+    }
+
+    fn record_at(&self, minute: i64, method: &str, route: &str, status_code: u16, ms: u64) {
+        let mut routes = self.routes.lock().unwrap_or_else(|e| e.into_inner());
+        let minutes = routes.entry((method.to_string(), route.to_string())).or_default();
+        if minutes.back().is_none_or(|counts| counts.minute != minute) {
+            minutes.push_back(MinuteCounts { minute, ..Default::default() });
+        }
+        while minutes.front().is_some_and(|counts| counts.minute <= minute - WINDOW_MINUTES) {
+            minutes.pop_front();
+        }
+        let counts = minutes.back_mut().expect("current minute");
+        counts.requests += 1;
+        counts.errors += u64::from(status_code >= 400);
+        counts.total_ms += ms;
+        counts.max_ms = counts.max_ms.max(ms);
+        let bucket = LATENCY_BUCKETS_MS.iter().position(|&le| ms <= le);
+        counts.buckets[bucket.unwrap_or(LATENCY_BUCKETS_MS.len())] += 1;
+    }
+
+    fn summary_at(&self, minute: i64) -> Vec<RouteLatency> {
+        let mut routes = self.routes.lock().unwrap_or_else(|e| e.into_inner());
+        // Routes idle for the whole window are dropped here rather than on each record.
+        routes.retain(|_, minutes| {
+            minutes.retain(|counts| counts.minute > minute - WINDOW_MINUTES);
+            !minutes.is_empty()
+        });
+        let mut summary: Vec<RouteLatency> = routes
+            .iter()
+            .map(|((method, route), minutes)| {
+                let mut total = MinuteCounts::default();
+                for counts in minutes {
+                    total.requests += counts.requests;
+                    total.errors += counts.errors;
+                    total.total_ms += counts.total_ms;
+                    total.max_ms = total.max_ms.max(counts.max_ms);
+                    for (sum, count) in total.buckets.iter_mut().zip(counts.buckets) {
+                        *sum += count;
+                    }
+                }
+                latency(method, route, &total)
+            })
+            .collect();
+        summary.sort_by(|a, b| b.requests.cmp(&a.requests).then_with(|| a.route.cmp(&b.route)));
+        summary
+    }
+}
+
+fn current_minute() -> i64 {
+    Utc::now().timestamp().div_euclid(60)
+}
+
+fn latency(method: &str, route: &str, total: &MinuteCounts) -> RouteLatency {
+    let requests = total.requests.max(1) as f64;
+    let percentile = |q: f64| {
+        let rank = (q * total.requests as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (i, count) in total.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return LATENCY_BUCKETS_MS.get(i).copied().unwrap_or(total.max_ms);
+            }
+        }
+        total.max_ms
+    };
+    RouteLatency {
+        method: method.to_string(),
+        route: route.to_string(),
+        requests: total.requests,
+        errors: total.errors,
+        error_rate: total.errors as f64 / requests,
+        avg_ms: total.total_ms as f64 / requests,
+        max_ms: total.max_ms,
+        p50_ms: percentile(0.5),
+        p95_ms: percentile(0.95),
+        p99_ms: percentile(0.99),
+        histogram: total
+            .buckets
+            .iter()
+            .enumerate()
+            .map(|(i, &count)| LatencyBucket { le_ms: LATENCY_BUCKETS_MS.get(i).copied(), count })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RouteMetrics, WINDOW_MINUTES};
+
+    #[test]
+    fn histograms_give_percentiles_and_error_rates_per_route() {
+        let metrics = RouteMetrics::default();
+        for ms in [3, 4, 8, 40, 40, 90, 120, 300, 700, 9000] {
+            metrics.record_at(100, "GET", "/api/system/users", 200, ms);
+        }
+        metrics.record_at(101, "POST", "/api/system/users", 500, 20);
+        metrics.record_at(101, "POST", "/api/system/users", 201, 20);
+
+        let summary = metrics.summary_at(101);
+        assert_eq!(summary.len(), 2);
+        let list = &summary[0];
+        assert_eq!((list.method.as_str(), list.requests, list.errors), ("GET", 10, 0));
+        assert_eq!((list.p50_ms, list.p95_ms, list.p99_ms, list.max_ms), (50, 9000, 9000, 9000));
+        assert_eq!(list.histogram[0].count, 2);
+        assert_eq!(list.histogram.last().map(|b| (b.le_ms, b.count)), Some((None, 1)));
+        let create = &summary[1];
+        assert_eq!((create.requests, create.errors, create.error_rate), (2, 1, 0.5));
+        assert_eq!(create.avg_ms, 20.0);
+    }
+
+    #[test]
+    fn minutes_older_than_the_window_are_dropped() {
+        let metrics = RouteMetrics::default();
+        metrics.record_at(10, "GET", "/api/old", 200, 5);
+        metrics.record_at(10, "GET", "/api/recent", 200, 5);
+        metrics.record_at(10 + WINDOW_MINUTES - 1, "GET", "/api/recent", 404, 5);
+
+        let summary = metrics.summary_at(10 + WINDOW_MINUTES);
+        assert_eq!(summary.len(), 1);
+        assert_eq!((summary[0].route.as_str(), summary[0].requests), ("/api/recent", 1));
+        assert_eq!(summary[0].errors, 1);
+    }
+}
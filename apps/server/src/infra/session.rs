@@ -0,0 +1,120 @@
+//! Cookie session mode.
+//!
+//! With `RUSTZEN_SESSION_COOKIE=true`, login sets the access token as an HttpOnly cookie
+//! instead of returning it, so the web UI never has it in JS. Browsers attach that cookie
+//! to every request for this origin, so writes authenticated by it must also send the
+//! readable CSRF cookie's value in `X-CSRF-Token` (double submit). CSRF tokens are an
+//! HMAC over a nonce and the session token they were issued for, so a token issued to
+//! another session, such as one planted by a sibling subdomain, does not pass.
+
+use crate::infra::config::AppConfig;
+
+use axum_extra::extract::cookie::{Cookie, SameSite};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use time::Duration;
+use uuid::Uuid;
+
+pub const CSRF_COOKIE: &str = "rustzen_csrf";
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+/// HttpOnly cookie carrying the access token for `RUSTZEN_JWT_EXPIRATION`.
+pub fn session_cookie(config: &AppConfig, token: String) -> Cookie<'static> {
+    let mut cookie = base_cookie(config, config.auth.session_cookie_name.clone(), token);
+    cookie.set_http_only(true);
+    cookie.set_max_age(Duration::seconds(config.jwt.jwt_expiration));
+    cookie
+}
+
+/// Overwrites the session cookie with an already expired one.
+pub fn expired_session_cookie(config: &AppConfig) -> Cookie<'static> {
+    let mut cookie = session_cookie(config, String::new());
+    cookie.set_max_age(Duration::ZERO);
+    cookie
+}
+
+/// CSRF cookie; readable by the web UI so it can echo the value in [`CSRF_HEADER`].
+pub fn csrf_cookie(config: &AppConfig, token: String) -> Cookie<'static> {
+    base_cookie(config, CSRF_COOKIE.to_string(), token)
+}
+
+/// Overwrites the CSRF cookie with an already expired one.
+pub fn expired_csrf_cookie(config: &AppConfig) -> Cookie<'static> {
+    let mut cookie = csrf_cookie(config, String::new());
+    cookie.set_max_age(Duration::ZERO);
+    cookie
+}
+
+fn base_cookie(config: &AppConfig, name: String, value: String) -> Cookie<'static> {
+    let mut cookie = Cookie::new(name, value);
+    cookie.set_path("/");
+    cookie.set_secure(config.session_cookie_is_secure());
+    cookie.set_same_site(
+        match config.auth.session_cookie_same_site.trim().to_ascii_lowercase().as_str() {
+            "strict" => SameSite::Strict,
+            "none" => SameSite::None,
+            _ => SameSite::Lax,
+        },
+    );
+    cookie
+}
+
+/// Random nonce plus its HMAC with the session token: `<nonce>.<hex mac>`.
+pub fn issue_csrf_token(config: &AppConfig, session: &str) -> String {
+    let nonce = Uuid::new_v4().simple().to_string();
+    let signature = csrf_mac(config, &nonce, session)
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    format!("{}.{}", nonce, signature)
+}
+
+/// Whether `token` was issued for the `session` token.
+pub fn verify_csrf_token(config: &AppConfig, token: &str, session: &str) -> bool {
+    let Some((nonce, signature)) = token.split_once('.') else {
+        return false;
+    };
+    let Some(signature) = decode_hex(signature) else {
+        return false;
+    };
+    csrf_mac(config, nonce, session).verify_slice(&signature).is_ok()
+}
+
+fn csrf_mac(config: &AppConfig, nonce: &str, session: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(config.jwt.jwt_secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(b"csrf.");
+    mac.update(&Sha256::digest(session.as_bytes()));
+    mac.update(nonce.as_bytes());
+    mac
+}
+
+/// Bytes of a hex string; `None` when it has an odd length or a non-hex digit.
+pub fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if !value.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..value.len()).step_by(2).map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{issue_csrf_token, verify_csrf_token};
+    use crate::infra::config::test_config;
+
+    #[test]
+    fn csrf_tokens_verify_only_with_their_own_signature_and_session() {
+        let config = test_config();
+        let token = issue_csrf_token(config, "session-a");
+        assert!(verify_csrf_token(config, &token, "session-a"));
+        assert!(!verify_csrf_token(config, &token, "session-b"));
+        assert_ne!(token, issue_csrf_token(config, "session-a"));
+
+        let (nonce, _) = token.split_once('.').unwrap();
+        assert!(!verify_csrf_token(config, &format!("{}.{}", nonce, "00".repeat(32)), "session-a"));
+        assert!(!verify_csrf_token(config, nonce, "session-a"));
+        assert!(!verify_csrf_token(config, "planted.zz", "session-a"));
+    }
+}
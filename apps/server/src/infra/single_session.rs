@@ -0,0 +1,65 @@
+//! One active session per account, with `RUSTZEN_SINGLE_SESSION=true`.
+//!
+//! Each login gets a new session id, stored on the user and carried in the token's `sid`
+//! claim. A token whose `sid` is no longer the stored one is refused; the check reads the
+//! database on every request, so all instances refuse it at once. The
+//! `logged_in_elsewhere` event goes out over an in-process channel, so only
+//! `GET /api/auth/session/events` streams on the instance that served the new login get it;
+//! a stream on another instance stays open until its client's next request gets `401`.
+
+use crate::{common::error::ServiceError, features::auth::repo::AuthRepository};
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Replaced sessions a stream may fall behind before it starts skipping.
+const CHANNEL_CAPACITY: usize = 256;
+
+static REPLACED_SESSIONS: Lazy<broadcast::Sender<ReplacedSession>> =
+    Lazy::new(|| broadcast::channel(CHANNEL_CAPACITY).0);
+
+/// A session ended by a newer login of the same account.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplacedSession {
+    #[serde(skip)]
+    pub user_id: i64,
+    #[serde(skip)]
+    pub session_id: String,
+    /// When the newer login happened.
+    pub replaced_at: DateTime<Utc>,
+}
+
+/// Starts a new session for the user and ends the previous one.
+pub async fn start(pool: &SqlitePool, user_id: i64) -> Result<String, ServiceError> {
+    let session_id = Uuid::new_v4().simple().to_string();
+    if let Some(previous) = AuthRepository::swap_session(pool, user_id, &session_id).await? {
+        tracing::info!(user_id, "New login replaced the previous session");
+        // Nobody may be listening; the old token is refused either way.
+        let _ = REPLACED_SESSIONS.send(ReplacedSession {
+            user_id,
+            session_id: previous,
+            replaced_at: Utc::now(),
+        });
+    }
+    Ok(session_id)
+}
+
+/// Whether a token with session `sid` belongs to the user's active session. Tokens without
+/// `sid` stay valid until the user's first login with single sessions on.
+pub async fn is_active(
+    pool: &SqlitePool,
+    user_id: i64,
+    sid: Option<&str>,
+) -> Result<bool, ServiceError> {
+    Ok(AuthRepository::find_session(pool, user_id).await?.as_deref() == sid)
+}
+
+/// New receiver for sessions replaced from now on.
+pub fn subscribe() -> broadcast::Receiver<ReplacedSession> {
+    REPLACED_SESSIONS.subscribe()
+}
@@ -0,0 +1,212 @@
+//! Slow request and slow SQL statement tracking.
+//!
+//! Requests over `RUSTZEN_SLOW_REQUEST_MS` are reported by `request_metrics_middleware`;
+//! statements over `RUSTZEN_SLOW_QUERY_MS` are reported by sqlx as `WARN` events on the
+//! `sqlx::query` target, which [`SlowQueryLayer`] picks up. Both are logged, counted and
+//! kept in a short in-process history, so they reset on restart and are per instance.
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+use tracing::{
+    Event, Level, Subscriber,
+    field::{Field, Visit},
+};
+use tracing_subscriber::{Layer, layer::Context};
+
+/// Most recent slow entries kept per kind.
+const RECENT_CAPACITY: usize = 100;
+
+pub static SLOW_LOG: Lazy<SlowLog> = Lazy::new(|| SlowLog::new(RECENT_CAPACITY));
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowRequest {
+    pub method: String,
+    /// Route template when one matched, otherwise the raw path.
+    pub route: String,
+    pub status_code: u16,
+    pub duration_ms: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowQuery {
+    /// Leading words of the statement, as summarized by sqlx.
+    pub summary: String,
+    pub duration_ms: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Totals since startup plus the most recent entries, newest first.
+#[derive(Debug, Clone)]
+pub struct SlowLogSnapshot {
+    pub slow_requests_total: u64,
+    pub slow_queries_total: u64,
+    pub requests: Vec<SlowRequest>,
+    pub queries: Vec<SlowQuery>,
+}
+
+pub struct SlowLog {
+    capacity: usize,
+    requests_total: AtomicU64,
+    queries_total: AtomicU64,
+    requests: Mutex<VecDeque<SlowRequest>>,
+    queries: Mutex<VecDeque<SlowQuery>>,
+}
+
+impl SlowLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            requests_total: AtomicU64::new(0),
+            queries_total: AtomicU64::new(0),
+            requests: Mutex::new(VecDeque::with_capacity(capacity)),
+            queries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    pub fn record_request(&self, method: &str, route: &str, status_code: u16, duration_ms: u64) {
+        self.requests_total.fetch_add(1, Ordering::Relaxed);
+        let entry = SlowRequest {
+            method: method.to_string(),
+            route: route.to_string(),
+            status_code,
+            duration_ms,
+            created_at: Utc::now(),
+        };
+        push_recent(&self.requests, entry, self.capacity);
+    }
+
+    pub fn record_query(&self, summary: &str, duration_ms: u64) {
+        self.queries_total.fetch_add(1, Ordering::Relaxed);
+        let entry = SlowQuery { summary: summary.to_string(), duration_ms, created_at: Utc::now() };
+        push_recent(&self.queries, entry, self.capacity);
+    }
+
+    pub fn snapshot(&self) -> SlowLogSnapshot {
+        SlowLogSnapshot {
+            slow_requests_total: self.requests_total.load(Ordering::Relaxed),
+            slow_queries_total: self.queries_total.load(Ordering::Relaxed),
+            requests: recent(&self.requests),
+            queries: recent(&self.queries),
+        }
+    }
+}
+
+fn recent<T: Clone>(entries: &Mutex<VecDeque<T>>) -> Vec<T> {
+    entries.lock().unwrap_or_else(|e| e.into_inner()).iter().rev().cloned().collect()
+}
+
+fn push_recent<T>(entries: &Mutex<VecDeque<T>>, entry: T, capacity: usize) {
+    let mut entries = entries.lock().unwrap_or_else(|e| e.into_inner());
+    if entries.len() == capacity {
+        entries.pop_front();
+    }
+    entries.push_back(entry);
+}
+
+/// Records sqlx's slow statement events into [`SLOW_LOG`].
+///
+/// Only events that pass the subscriber's filter arrive here, so `RUST_LOG` must keep
+/// `sqlx::query` at `warn` or lower for slow statements to be counted.
+pub struct SlowQueryLayer;
+
+impl<S: Subscriber> Layer<S> for SlowQueryLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        if metadata.target() != "sqlx::query" || *metadata.level() > Level::WARN {
+            return;
+        }
+        let mut visitor = SlowStatementVisitor::default();
+        event.record(&mut visitor);
+        if let (true, Some(elapsed_secs)) = (visitor.slow, visitor.elapsed_secs) {
+            SLOW_LOG.record_query(&visitor.summary, (elapsed_secs * 1000.0) as u64);
+        }
+    }
+}
+
+#[derive(Default)]
+struct SlowStatementVisitor {
+    summary: String,
+    elapsed_secs: Option<f64>,
+    slow: bool,
+}
+
+impl Visit for SlowStatementVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        if field.name() == "elapsed_secs" {
+            self.elapsed_secs = Some(value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "summary" {
+            self.summary = value.to_string();
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "slow_threshold" => self.slow = true,
+            "summary" => self.summary = format!("{:?}", value).trim_matches('"').to_string(),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SLOW_LOG, SlowLog, SlowQueryLayer};
+
+    use std::time::Duration;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn keeps_totals_and_the_most_recent_entries() {
+        let log = SlowLog::new(2);
+        log.record_request("GET", "/api/system/users", 200, 1500);
+        log.record_request("POST", "/api/system/roles", 500, 2100);
+        log.record_request("GET", "/api/manage/logs", 200, 1200);
+
+        let snapshot = log.snapshot();
+        assert_eq!(snapshot.slow_requests_total, 3);
+        let routes: Vec<_> = snapshot.requests.iter().map(|r| r.route.as_str()).collect();
+        assert_eq!(routes, vec!["/api/manage/logs", "/api/system/roles"]);
+        assert_eq!(snapshot.slow_queries_total, 0);
+    }
+
+    #[test]
+    fn sqlx_slow_statement_events_are_recorded() {
+        let subscriber = tracing_subscriber::registry().with(SlowQueryLayer);
+        tracing::subscriber::with_default(subscriber, || {
+            let summary = "select count(*) from slow_layer_probe";
+            tracing::warn!(
+                target: "sqlx::query",
+                summary,
+                elapsed_secs = 0.25,
+                slow_threshold = ?Duration::from_millis(200),
+                "slow statement: execution time exceeded alert threshold"
+            );
+            tracing::debug!(target: "sqlx::query", summary, elapsed_secs = 0.001);
+        });
+
+        let snapshot = SLOW_LOG.snapshot();
+        let probes: Vec<_> = snapshot
+            .queries
+            .iter()
+            .filter(|query| query.summary == "select count(*) from slow_layer_probe")
+            .collect();
+        assert_eq!(probes.len(), 1);
+        assert_eq!(probes[0].duration_ms, 250);
+    }
+}
@@ -0,0 +1,244 @@
+//! SMS gateways for one-time codes, selected by `RUSTZEN_SMS_PROVIDER`.
+//!
+//! Both gateways are called at their public HTTPS endpoints, [`TWILIO_API`] and
+//! [`ALIYUN_API`].
+
+use crate::infra::{
+    config::AppConfig,
+    http_client::{self, form_encode},
+};
+
+use async_trait::async_trait;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use once_cell::sync::OnceCell;
+use rustzen_core::sms::{OtpMessage, SmsError, SmsProvider};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub const TWILIO_API: &str = "https://api.twilio.com";
+pub const ALIYUN_API: &str = "https://dysmsapi.aliyuncs.com";
+
+static PROVIDER: OnceCell<Option<Box<dyn SmsProvider>>> = OnceCell::new();
+
+/// The configured gateway; `None` when SMS is turned off.
+pub fn provider(config: &AppConfig) -> Option<&'static dyn SmsProvider> {
+    PROVIDER.get_or_init(|| from_config(config)).as_deref()
+}
+
+/// Uses `provider` instead of the configured gateway; call it before the first code is sent.
+/// Integration tests use it to stand in for the gateway API.
+pub fn install_provider(provider: Box<dyn SmsProvider>) {
+    if PROVIDER.set(Some(provider)).is_err() {
+        panic!("the SMS gateway is already in use");
+    }
+}
+
+fn from_config(config: &AppConfig) -> Option<Box<dyn SmsProvider>> {
+    let account = config.sms.sms_account.clone()?;
+    let secret = config.sms.sms_secret.clone()?;
+    let sender = config.sms.sms_sender.clone()?;
+    match config.sms.sms_provider.as_deref()? {
+        "twilio" => Some(Box::new(TwilioProvider {
+            url: TWILIO_API.to_string(),
+            account_sid: account,
+            auth_token: secret,
+            from: sender,
+        })),
+        "aliyun" => Some(Box::new(AliyunProvider {
+            url: ALIYUN_API.to_string(),
+            access_key_id: account,
+            access_key_secret: secret,
+            sign_name: sender,
+            template_code: config.sms.sms_template.clone()?,
+        })),
+        _ => None,
+    }
+}
+
+/// Twilio Programmable Messaging, sending the code as free text.
+pub struct TwilioProvider {
+    /// API base, [`TWILIO_API`] outside tests.
+    pub url: String,
+    pub account_sid: String,
+    pub auth_token: String,
+    pub from: String,
+}
+
+#[async_trait]
+impl SmsProvider for TwilioProvider {
+    fn name(&self) -> &'static str {
+        "twilio"
+    }
+
+    async fn send_otp(&self, phone: &str, message: &OtpMessage) -> Result<(), SmsError> {
+        let url = format!("{}/2010-04-01/Accounts/{}/Messages.json", self.url, self.account_sid);
+        let credentials = STANDARD.encode(format!("{}:{}", self.account_sid, self.auth_token));
+        let body = form_encode(&[("To", phone), ("From", &self.from), ("Body", &message.text())]);
+        let (status, response) = http_client::post_form(
+            &url,
+            &[("authorization", format!("Basic {}", credentials))],
+            body,
+            SEND_TIMEOUT,
+        )
+        .await
+        .map_err(SmsError::Unreachable)?;
+        if (200..300).contains(&status) {
+            Ok(())
+        } else {
+            Err(SmsError::Rejected(format!("HTTP {}: {}", status, response)))
+        }
+    }
+}
+
+/// Aliyun Dysms `SendSms`, filling the template's `${code}` variable.
+pub struct AliyunProvider {
+    /// API base, [`ALIYUN_API`] outside tests; requests are signed for its host.
+    pub url: String,
+    pub access_key_id: String,
+    pub access_key_secret: String,
+    pub sign_name: String,
+    pub template_code: String,
+}
+
+const ALIYUN_ACTION: &str = "SendSms";
+const ALIYUN_VERSION: &str = "2017-05-25";
+
+impl AliyunProvider {
+    /// Headers for a `POST /?{query}` with an empty body, signed with ACS3-HMAC-SHA256.
+    fn signed_headers(
+        &self,
+        host: &str,
+        query: &str,
+        date: DateTime<Utc>,
+        nonce: &str,
+    ) -> Vec<(&'static str, String)> {
+        let mut headers = vec![
+            ("host", host.to_string()),
+            ("x-acs-action", ALIYUN_ACTION.to_string()),
+            ("x-acs-content-sha256", sha256_hex(b"")),
+            ("x-acs-date", date.format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+            ("x-acs-signature-nonce", nonce.to_string()),
+            ("x-acs-version", ALIYUN_VERSION.to_string()),
+        ];
+        let canonical_headers: String =
+            headers.iter().map(|(name, value)| format!("{}:{}\n", name, value)).collect();
+        let signed = headers.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+        let canonical_request =
+            format!("POST\n/\n{}\n{}\n{}\n{}", query, canonical_headers, signed, sha256_hex(b""));
+        let string_to_sign =
+            format!("ACS3-HMAC-SHA256\n{}", sha256_hex(canonical_request.as_bytes()));
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.access_key_secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(string_to_sign.as_bytes());
+        let signature = hex(&mac.finalize().into_bytes());
+
+        headers.push((
+            "authorization",
+            format!(
+                "ACS3-HMAC-SHA256 Credential={},SignedHeaders={},Signature={}",
+                self.access_key_id, signed, signature
+            ),
+        ));
+        // `Host` itself is set by the HTTP client from the URL.
+        headers.retain(|(name, _)| *name != "host");
+        headers
+    }
+}
+
+#[async_trait]
+impl SmsProvider for AliyunProvider {
+    fn name(&self) -> &'static str {
+        "aliyun"
+    }
+
+    async fn send_otp(&self, phone: &str, message: &OtpMessage) -> Result<(), SmsError> {
+        let uri = http_client::validate_url(&self.url).map_err(SmsError::Unreachable)?;
+        let host = uri.authority().map(|a| a.to_string()).unwrap_or_default();
+        // Mainland numbers go without the country code; others keep the full E.164 form.
+        let phone = phone.strip_prefix("+86").unwrap_or(phone);
+        let template_param = serde_json::json!({ "code": message.code }).to_string();
+        // Parameters are already in canonical (sorted) order.
+        let query = form_encode(&[
+            ("PhoneNumbers", phone),
+            ("SignName", &self.sign_name),
+            ("TemplateCode", &self.template_code),
+            ("TemplateParam", &template_param),
+        ]);
+        let nonce = uuid::Uuid::new_v4().simple().to_string();
+        let headers = self.signed_headers(&host, &query, Utc::now(), &nonce);
+
+        let (status, response) = http_client::post_form(
+            &format!("{}/?{}", self.url, query),
+            &headers,
+            String::new(),
+            SEND_TIMEOUT,
+        )
+        .await
+        .map_err(SmsError::Unreachable)?;
+        let code = serde_json::from_str::<serde_json::Value>(&response)
+            .ok()
+            .and_then(|body| body.get("Code")?.as_str().map(str::to_string));
+        if status == 200 && code.as_deref() == Some("OK") {
+            Ok(())
+        } else {
+            Err(SmsError::Rejected(format!("HTTP {}: {}", status, response)))
+        }
+    }
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex(&Sha256::digest(data))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AliyunProvider;
+    use crate::infra::http_client::form_encode;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn form_values_are_percent_encoded() {
+        assert_eq!(
+            form_encode(&[("To", "+8613812345678"), ("Body", "code: 123 456~")]),
+            "To=%2B8613812345678&Body=code%3A%20123%20456~"
+        );
+    }
+
+    #[test]
+    fn aliyun_requests_carry_an_acs3_signature() {
+        let provider = AliyunProvider {
+            url: super::ALIYUN_API.to_string(),
+            access_key_id: "key-id".to_string(),
+            access_key_secret: "key-secret".to_string(),
+            sign_name: "rustzen".to_string(),
+            template_code: "SMS_1".to_string(),
+        };
+        let date = Utc.with_ymd_and_hms(2026, 1, 2, 3, 4, 5).unwrap();
+        let headers = provider.signed_headers("dysmsapi.aliyuncs.com", "A=1", date, "nonce");
+        let header =
+            |name: &str| headers.iter().find(|(n, _)| *n == name).map(|(_, v)| v.as_str()).unwrap();
+
+        assert_eq!(header("x-acs-date"), "2026-01-02T03:04:05Z");
+        assert!(!headers.iter().any(|(name, _)| *name == "host"));
+        let authorization = header("authorization");
+        assert!(authorization.starts_with(
+            "ACS3-HMAC-SHA256 Credential=key-id,SignedHeaders=host;x-acs-action;\
+             x-acs-content-sha256;x-acs-date;x-acs-signature-nonce;x-acs-version,Signature="
+        ));
+        let signature = authorization.rsplit('=').next().unwrap();
+        assert_eq!(signature.len(), 64);
+        // Same inputs, same signature; a different nonce changes it.
+        let again = provider.signed_headers("dysmsapi.aliyuncs.com", "A=1", date, "nonce");
+        assert_eq!(again, headers);
+        let other = provider.signed_headers("dysmsapi.aliyuncs.com", "A=1", date, "other");
+        assert_ne!(other.last(), headers.last());
+    }
+}
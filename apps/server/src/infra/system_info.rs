@@ -20,6 +20,8 @@ const SYSTEM_INFO_CACHE_TTL: Duration = Duration::from_secs(10);
 
 static CACHED_INFO: Lazy<RwLock<Option<CachedSystemInfo>>> = Lazy::new(|| RwLock::new(None));
 
+static PROCESS_STARTED_AT: Lazy<Instant> = Lazy::new(Instant::now);
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SystemInfo {
@@ -44,6 +46,16 @@ struct CachedSystemInfo {
 pub struct SystemUtils;
 
 impl SystemUtils {
+    /// 记录进程启动时间，启动时调用一次
+    pub fn mark_started() {
+        Lazy::force(&PROCESS_STARTED_AT);
+    }
+
+    /// 进程运行时长
+    pub fn uptime() -> Duration {
+        PROCESS_STARTED_AT.elapsed()
+    }
+
     /// 获取系统信息
     pub fn get_system_info() -> SystemInfo {
         if let Some(info) = Self::get_cached_system_info() {
@@ -75,7 +87,7 @@ impl SystemUtils {
     }
 
     /// 获取磁盘信息
-    fn get_disk_info() -> (u64, u64, u64) {
+    pub(crate) fn get_disk_info() -> (u64, u64, u64) {
         let disks = Disks::new_with_refreshed_list();
         let mut seen_devices = HashSet::new();
         let mut total_space = 0;
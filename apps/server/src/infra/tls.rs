@@ -0,0 +1,57 @@
+//! HTTPS termination with rustls.
+//!
+//! With `RUSTZEN_TLS_CERT_PATH` and `RUSTZEN_TLS_KEY_PATH` set, the app port speaks TLS
+//! instead of plain HTTP. On Unix, `SIGHUP` re-reads both files so renewed certificates
+//! apply without a restart; a reload that fails keeps serving the previous certificate.
+
+use axum_server::tls_rustls::RustlsConfig;
+use std::path::PathBuf;
+
+/// Loads the certificate pair and starts the reload-on-SIGHUP watcher.
+pub async fn load_tls_config(
+    cert_path: PathBuf,
+    key_path: PathBuf,
+) -> Result<RustlsConfig, Box<dyn std::error::Error>> {
+    // axum-server builds rustls without a crypto provider; ring is the one compiled in.
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    let config = RustlsConfig::from_pem_file(&cert_path, &key_path).await.map_err(|e| {
+        format!(
+            "Cannot load TLS certificate {} / key {}: {}",
+            cert_path.display(),
+            key_path.display(),
+            e
+        )
+    })?;
+    tracing::info!(cert = %cert_path.display(), "Loaded TLS certificate");
+    spawn_reload_on_hangup(config.clone(), cert_path, key_path);
+    Ok(config)
+}
+
+#[cfg(unix)]
+fn spawn_reload_on_hangup(config: RustlsConfig, cert_path: PathBuf, key_path: PathBuf) {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut hangups = match signal(SignalKind::hangup()) {
+        Ok(hangups) => hangups,
+        Err(e) => {
+            tracing::warn!("Cannot listen for SIGHUP, TLS certificates will not reload: {}", e);
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        while hangups.recv().await.is_some() {
+            match config.reload_from_pem_file(&cert_path, &key_path).await {
+                Ok(()) => tracing::info!(cert = %cert_path.display(), "Reloaded TLS certificate"),
+                Err(e) => tracing::error!(
+                    cert = %cert_path.display(),
+                    "TLS certificate reload failed, keeping the previous one: {}",
+                    e
+                ),
+            }
+        }
+    });
+}
+
+#[cfg(not(unix))]
+fn spawn_reload_on_hangup(_config: RustlsConfig, _cert_path: PathBuf, _key_path: PathBuf) {}
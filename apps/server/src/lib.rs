@@ -0,0 +1,6 @@
+//! Server library: the binary in `main.rs` and the integration tests in `tests/` share it.
+
+pub mod common;
+pub mod features;
+pub mod infra;
+pub mod middleware;
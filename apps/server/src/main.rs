@@ -1,11 +1,7 @@
-mod common;
-mod features;
-mod infra;
-mod middleware;
-
-use crate::infra::app::run_server;
-use crate::infra::config::CONFIG;
-use crate::infra::logger::init_logging;
+use server::infra::app::run_server;
+use server::infra::cli::{self, Command};
+use server::infra::config::{self, AppConfig};
+use server::infra::logger::init_logging;
 
 #[used]
 #[unsafe(no_mangle)]
@@ -20,27 +16,42 @@ pub static RUSTZEN_ADMIN_MARKER: &str = concat!(
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // load env
     dotenvy::dotenv().ok();
+    let config = config::load()?;
+
+    init_process_timezone(config);
 
-    init_process_timezone();
+    let command = match Command::parse(std::env::args().skip(1)) {
+        Ok(Command::Help) => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
 
-    let runtime = tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .build()?;
+    let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build()?;
 
     runtime.block_on(async {
         // init log
-        let _logging = init_logging()?;
+        let _logging = init_logging(config)?;
+
+        if command != Command::Serve {
+            return cli::run(config, command).await;
+        }
 
         // create server
-        run_server().await
+        run_server(config).await
     })?;
 
     Ok(())
 }
 
-fn init_process_timezone() {
+fn init_process_timezone(config: &AppConfig) {
     // SAFETY: this runs in sync main before the Tokio runtime and worker threads are created.
     unsafe {
-        std::env::set_var("TZ", CONFIG.timezone.trim());
+        std::env::set_var("TZ", config.server.timezone.trim());
     }
 }
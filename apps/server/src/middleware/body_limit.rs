@@ -0,0 +1,51 @@
+use crate::common::error::{AppError, ServiceError};
+
+use axum::{
+    http::{StatusCode, header::CONTENT_TYPE},
+    response::{IntoResponse, Response},
+};
+
+/// Rewrites plain-text 413 rejections from `DefaultBodyLimit` into the JSON error envelope.
+pub async fn payload_too_large_response(response: Response) -> Response {
+    if response.status() != StatusCode::PAYLOAD_TOO_LARGE || is_json(&response) {
+        return response;
+    }
+
+    AppError::from(ServiceError::PayloadTooLarge).into_response()
+}
+
+fn is_json(response: &Response) -> bool {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::payload_too_large_response;
+    use axum::{
+        body::Body,
+        http::{StatusCode, header::CONTENT_TYPE},
+        response::{IntoResponse, Response},
+    };
+
+    #[tokio::test]
+    async fn plain_413_is_rewritten_as_json() {
+        let response = (StatusCode::PAYLOAD_TOO_LARGE, "length limit exceeded").into_response();
+        let response = payload_too_large_response(response).await;
+
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+    }
+
+    #[tokio::test]
+    async fn other_responses_pass_through() {
+        let response = Response::new(Body::from("ok"));
+        let response = payload_too_large_response(response).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(CONTENT_TYPE).is_none());
+    }
+}
@@ -0,0 +1,172 @@
+//! CSRF protection for cookie-session requests.
+//!
+//! Only requests a browser could be tricked into sending are checked: unsafe methods
+//! without an `Authorization: Bearer` header while cookie sessions are on. For those:
+//! - a browser `Origin` must be this host or an explicitly allowed CORS origin, which
+//!   stops cross-site login as well as cross-site writes;
+//! - requests carrying the session cookie must echo the CSRF cookie in `X-CSRF-Token`
+//!   (double submit), and that token must have been issued for the same session cookie
+//!   (see `infra::session`).
+
+use crate::{
+    common::error::{AppError, ServiceError},
+    infra::{
+        config::AppConfig,
+        session::{CSRF_COOKIE, CSRF_HEADER, verify_csrf_token},
+    },
+};
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, Method, header},
+    middleware::Next,
+    response::Response,
+};
+use rustzen_core::auth::cookie_value;
+
+/// Why a request failed the CSRF check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CsrfRejection {
+    CrossOrigin,
+    MissingToken,
+    InvalidToken,
+}
+
+pub async fn csrf_middleware(
+    State(config): State<&'static AppConfig>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    if !config.auth.session_cookie {
+        return Ok(next.run(request).await);
+    }
+    let allowed_origins: Vec<&str> =
+        config.cors_origins().into_iter().filter(|origin| *origin != "*").collect();
+    match check_request(
+        config,
+        request.method(),
+        request.headers(),
+        &config.auth.session_cookie_name,
+        &allowed_origins,
+    ) {
+        Ok(()) => Ok(next.run(request).await),
+        Err(rejection) => {
+            tracing::warn!(
+                method = %request.method(),
+                path = %request.uri().path(),
+                ?rejection,
+                "Rejected request failing CSRF checks"
+            );
+            Err(ServiceError::InvalidCsrfToken.into())
+        }
+    }
+}
+
+fn check_request(
+    config: &AppConfig,
+    method: &Method,
+    headers: &HeaderMap,
+    session_cookie: &str,
+    allowed_origins: &[&str],
+) -> Result<(), CsrfRejection> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("Bearer "));
+    if method.is_safe() || bearer {
+        return Ok(());
+    }
+
+    if let Some(origin) = headers.get(header::ORIGIN).and_then(|value| value.to_str().ok()) {
+        let host = headers.get(header::HOST).and_then(|value| value.to_str().ok());
+        let same_host = origin.split_once("://").map(|(_, rest)| rest) == host;
+        if !same_host && !allowed_origins.contains(&origin) {
+            return Err(CsrfRejection::CrossOrigin);
+        }
+    }
+
+    let Some(session) = cookie_value(headers, session_cookie) else {
+        return Ok(());
+    };
+    let header_token = headers.get(CSRF_HEADER).and_then(|value| value.to_str().ok());
+    let cookie_token = cookie_value(headers, CSRF_COOKIE);
+    match (header_token, cookie_token) {
+        (Some(header_token), Some(cookie_token)) => {
+            if header_token == cookie_token && verify_csrf_token(config, header_token, session) {
+                Ok(())
+            } else {
+                Err(CsrfRejection::InvalidToken)
+            }
+        }
+        _ => Err(CsrfRejection::MissingToken),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CsrfRejection, check_request};
+    use crate::infra::{config::test_config, session::issue_csrf_token};
+
+    use axum::http::{HeaderMap, HeaderValue, Method, header};
+
+    fn headers(pairs: &[(header::HeaderName, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::HOST, HeaderValue::from_static("admin.example.com"));
+        for (name, value) in pairs {
+            headers.insert(name.clone(), HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    fn check(method: Method, headers: &HeaderMap) -> Result<(), CsrfRejection> {
+        check_request(test_config(), &method, headers, "sid", &["https://ops.example.com"])
+    }
+
+    #[test]
+    fn bearer_and_safe_requests_are_exempt() {
+        let cross_site = [(header::ORIGIN, "https://evil.example"), (header::COOKIE, "sid=abc")];
+        assert_eq!(check(Method::GET, &headers(&cross_site)), Ok(()));
+
+        let bearer = [
+            (header::ORIGIN, "https://evil.example"),
+            (header::COOKIE, "sid=abc"),
+            (header::AUTHORIZATION, "Bearer token"),
+        ];
+        assert_eq!(check(Method::POST, &headers(&bearer)), Ok(()));
+    }
+
+    #[test]
+    fn cross_origin_writes_are_rejected_even_without_a_session() {
+        let evil = [(header::ORIGIN, "https://evil.example")];
+        assert_eq!(check(Method::POST, &headers(&evil)), Err(CsrfRejection::CrossOrigin));
+
+        let same_host = [(header::ORIGIN, "https://admin.example.com")];
+        assert_eq!(check(Method::POST, &headers(&same_host)), Ok(()));
+        let allowed = [(header::ORIGIN, "https://ops.example.com")];
+        assert_eq!(check(Method::POST, &headers(&allowed)), Ok(()));
+        assert_eq!(check(Method::POST, &headers(&[])), Ok(()));
+    }
+
+    #[test]
+    fn session_cookie_writes_need_a_matching_token_for_the_same_session() {
+        let token = issue_csrf_token(test_config(), "abc");
+        let cookies = format!("sid=abc; rustzen_csrf={}", token);
+
+        let missing = [(header::COOKIE, cookies.as_str())];
+        assert_eq!(check(Method::DELETE, &headers(&missing)), Err(CsrfRejection::MissingToken));
+
+        let other = issue_csrf_token(test_config(), "abc");
+        let mismatched =
+            [(header::COOKIE, cookies.as_str()), ("x-csrf-token".parse().unwrap(), &other)];
+        assert_eq!(check(Method::PUT, &headers(&mismatched)), Err(CsrfRejection::InvalidToken));
+
+        let valid = [(header::COOKIE, cookies.as_str()), ("x-csrf-token".parse().unwrap(), &token)];
+        assert_eq!(check(Method::PUT, &headers(&valid)), Ok(()));
+
+        let planted = issue_csrf_token(test_config(), "attacker");
+        let cookies = format!("sid=abc; rustzen_csrf={}", planted);
+        let planted =
+            [(header::COOKIE, cookies.as_str()), ("x-csrf-token".parse().unwrap(), &planted)];
+        assert_eq!(check(Method::PUT, &headers(&planted)), Err(CsrfRejection::InvalidToken));
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{
+    common::error::{AppError, ServerErrorInfo, ServiceError},
+    infra::{
+        config::AppConfig,
+        error_report::{self, RequestContext},
+    },
+};
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{HeaderValue, header::HeaderName},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use futures::FutureExt;
+use rustzen_core::auth::CurrentUser;
+use std::panic::AssertUnwindSafe;
+
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest caller-supplied request ID that is kept; longer ones are replaced.
+const REQUEST_ID_MAX_LEN: usize = 128;
+
+/// Tags the request with an ID, turns handler panics into `500` responses and reports
+/// `5xx` responses and panics through `infra::error_report`.
+///
+/// An incoming `X-Request-Id` is reused, so IDs from a proxy line up; the ID is echoed in
+/// the response either way.
+pub async fn error_report_middleware(
+    State(config): State<&'static AppConfig>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let request_id = request
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| !id.is_empty() && id.len() <= REQUEST_ID_MAX_LEN)
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string());
+    let context = RequestContext {
+        request_id,
+        method: request.method().to_string(),
+        route: request.extensions().get::<MatchedPath>().map(|path| path.as_str().to_string()),
+        user_id: request.extensions().get::<CurrentUser>().map(|user| user.user_id),
+    };
+
+    let result =
+        error_report::scope(context.clone(), AssertUnwindSafe(next.run(request)).catch_unwind())
+            .await;
+    let mut response = match result {
+        Ok(response) => {
+            if response.status().is_server_error() {
+                let message = match response.extensions().get::<ServerErrorInfo>() {
+                    Some(info) => format!("{} (code {})", info.message, info.code),
+                    None => response.status().to_string(),
+                };
+                error_report::capture_error(config, &context, &message);
+            }
+            response
+        }
+        // The panic hook already logged and reported it.
+        Err(_) => AppError::from(ServiceError::HandlerPanicked).into_response(),
+    };
+    if let Ok(value) = HeaderValue::from_str(&context.request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::error_report_middleware;
+    use crate::infra::config::test_config;
+
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode},
+        middleware,
+        routing::get,
+    };
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    async fn boom() -> &'static str {
+        panic!("handler exploded")
+    }
+
+    #[tokio::test]
+    async fn panics_become_500s_and_request_ids_are_echoed() {
+        let app = Router::new()
+            .route("/ok", get(|| async { "ok" }))
+            .route("/boom", get(boom))
+            .route_layer(middleware::from_fn_with_state(test_config(), error_report_middleware));
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::get("/ok").header("x-request-id", "edge-42").body(Body::empty()).unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["x-request-id"], "edge-42");
+
+        let response =
+            app.oneshot(Request::get("/boom").body(Body::empty()).unwrap()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(response.headers()["x-request-id"].len(), 32);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["code"], 20004);
+    }
+}
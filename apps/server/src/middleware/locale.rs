@@ -0,0 +1,33 @@
+use crate::common::{
+    error::AppError,
+    i18n::{self, Locale},
+};
+
+use axum::{
+    extract::Request,
+    http::header::ACCEPT_LANGUAGE,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use rustzen_core::error::CoreError;
+
+/// Scopes each API request to the locale from its `Accept-Language` header.
+///
+/// The auth layer's bare `401`/`403` responses are rewritten here as localized JSON errors.
+pub async fn locale_middleware(request: Request, next: Next) -> Response {
+    let locale = request
+        .headers()
+        .get(ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .map(Locale::from_accept_language)
+        .unwrap_or_default();
+
+    i18n::scope(locale, async move {
+        let response = next.run(request).await;
+        match response.extensions().get::<CoreError>() {
+            Some(err) => AppError::from(*err).into_response(),
+            None => response,
+        }
+    })
+    .await
+}
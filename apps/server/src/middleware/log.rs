@@ -1,13 +1,22 @@
-use crate::features::manage::log::{service::LogService, types::LogWriteCommand};
+use crate::{
+    common::error::{AppError, ServiceError},
+    features::{
+        manage::log::{service::LogService, types::LogWriteCommand},
+        system::debug_capture::service::{DebugCaptures, capture_payload, is_capturable},
+    },
+    infra::config::AppConfig,
+};
 
 use axum::{
-    extract::{ConnectInfo, Request, State},
+    body::Body,
+    extract::{ConnectInfo, FromRequestParts, MatchedPath, RawPathParams, Request, State},
     http::Method,
-    http::StatusCode,
+    http::{StatusCode, header::CONTENT_TYPE},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
 use rustzen_core::auth::CurrentUser;
+use serde_json::Value;
 use sqlx::SqlitePool;
 use std::{net::SocketAddr, time::Instant};
 
@@ -18,12 +27,16 @@ fn should_log(method: &Method, path: &str) -> bool {
             | (&Method::GET, "/api/dashboard/metrics")
             | (&Method::GET, "/api/dashboard/stats")
             | (&Method::GET, "/api/dashboard/trends")
+            | (&Method::GET, "/api/dashboard/top")
     )
 }
 
 /// HTTP logging middleware.
+///
+/// While a debug capture matches the user or route, the sanitized JSON request body is
+/// stored in the log entry's `data`.
 pub async fn log_middleware(
-    State(pool): State<SqlitePool>,
+    State((pool, config)): State<(SqlitePool, &'static AppConfig)>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     request: Request,
     next: Next,
@@ -36,6 +49,22 @@ pub async fn log_middleware(
     let client_ip = addr.ip().to_string();
     tracing::debug!(method = %method, uri = %uri, client_ip = %client_ip, "Handling request");
     let current_user = request.extensions().get::<CurrentUser>().cloned();
+    let route = request.extensions().get::<MatchedPath>().map(|path| path.as_str().to_string());
+    let (mut parts, body) = request.into_parts();
+    let params = RawPathParams::from_request_parts(&mut parts, &()).await.ok();
+    let params: Vec<(&str, &str)> = params.iter().flatten().collect();
+    let (resource_type, resource_id) =
+        route.as_deref().map(|route| resource_from_route(route, &params)).unwrap_or_default();
+    let capture_id =
+        DebugCaptures::matching(current_user.as_ref().map(|u| u.user_id), route.as_deref());
+    let (body, data) = match capture_id {
+        Some(capture_id) => match capture_body(capture_id, &parts, body, config).await {
+            Ok(captured) => captured,
+            Err(response) => return Ok(response),
+        },
+        None => (body, None),
+    };
+    let request = Request::from_parts(parts, body);
     let response = next.run(request).await;
     let duration = start.elapsed();
 
@@ -45,7 +74,7 @@ pub async fn log_middleware(
     let method_for_log = method.clone();
 
     if should_log(&method, &path) {
-        if let Err(e) = LogService::record_operation(
+        LogService::log_operation(
             &pool,
                 build_request_log(
                     RequestLogContext {
@@ -57,27 +86,46 @@ pub async fn log_middleware(
                         duration,
                         ip_address: client_ip,
                         user_agent,
+                        route: route.clone(),
+                        resource_type,
+                        resource_id,
+                        data,
                     },
             ),
         )
-        .await
-        {
-            tracing::error!("Failed to log HTTP request: {:?}", e);
-        } else {
-            tracing::debug!(
-                method = %method,
-                uri = %uri,
-                status_code,
-                duration_ms = duration.as_millis(),
-                user_id = user_id.unwrap_or(0),
-                "HTTP request recorded"
-            );
-        }
+        .await;
+        tracing::debug!(
+            method = %method,
+            uri = %uri,
+            route = route.as_deref().unwrap_or_default(),
+            status_code,
+            duration_ms = duration.as_millis(),
+            user_id = user_id.unwrap_or(0),
+            "HTTP request recorded"
+        );
     }
 
     Ok(response)
 }
 
+/// Buffers a JSON body for a debug capture and hands back a copy for the handler.
+async fn capture_body(
+    capture_id: i64,
+    parts: &axum::http::request::Parts,
+    body: Body,
+    config: &AppConfig,
+) -> Result<(Body, Option<Value>), Response> {
+    let content_type = parts.headers.get(CONTENT_TYPE).and_then(|value| value.to_str().ok());
+    if !is_capturable(content_type) {
+        return Ok((body, Some(capture_payload(capture_id, content_type, &[]))));
+    }
+    let bytes = axum::body::to_bytes(body, config.server.request_body_limit)
+        .await
+        .map_err(|_| AppError::from(ServiceError::PayloadTooLarge).into_response())?;
+    let data = capture_payload(capture_id, content_type, &bytes);
+    Ok((Body::from(bytes), Some(data)))
+}
+
 fn request_user_agent(request: &Request) -> String {
     request
         .headers()
@@ -97,6 +145,29 @@ struct RequestLogContext {
     duration: std::time::Duration,
     ip_address: String,
     user_agent: String,
+    route: Option<String>,
+    resource_type: Option<String>,
+    resource_id: Option<String>,
+    data: Option<Value>,
+}
+
+/// The first path parameter as `(resource_type, resource_id)`, named by the segment before it.
+///
+/// `/api/system/users/{id}/roles` with `id=7` gives `("users", "7")`.
+fn resource_from_route(route: &str, params: &[(&str, &str)]) -> (Option<String>, Option<String>) {
+    let segments: Vec<&str> = route.split('/').collect();
+    let Some(index) = segments.iter().position(|segment| segment.starts_with('{')) else {
+        return (None, None);
+    };
+    let name = segments[index].trim_matches(|c| c == '{' || c == '}');
+    let resource_id =
+        params.iter().find(|(key, _)| *key == name).map(|(_, value)| value.to_string());
+    let resource_type = index
+        .checked_sub(1)
+        .map(|previous| segments[previous])
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string);
+    (resource_type, resource_id)
 }
 
 fn build_request_log(
@@ -113,17 +184,22 @@ fn build_request_log(
             context.uri,
             context.status_code
         ),
-        data: None,
+        data: context.data,
         status: status.to_string(),
         duration_ms: context.duration.as_millis() as i32,
         ip_address: context.ip_address,
         user_agent: context.user_agent,
+        route: context.route,
+        resource_type: context.resource_type,
+        resource_id: context.resource_id,
+        status_code: Some(i32::from(context.status_code)),
+        ..Default::default()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::should_log;
+    use super::{resource_from_route, should_log};
     use axum::http::Method;
 
     #[test]
@@ -132,6 +208,7 @@ mod tests {
         assert!(!should_log(&Method::GET, "/api/dashboard/metrics"));
         assert!(!should_log(&Method::GET, "/api/dashboard/stats"));
         assert!(!should_log(&Method::GET, "/api/dashboard/trends"));
+        assert!(!should_log(&Method::GET, "/api/dashboard/top"));
     }
 
     #[test]
@@ -139,4 +216,20 @@ mod tests {
         assert!(should_log(&Method::POST, "/api/dashboard/health"));
         assert!(should_log(&Method::GET, "/api/system/user"));
     }
+
+    #[test]
+    fn resource_comes_from_the_first_path_parameter() {
+        assert_eq!(
+            resource_from_route("/api/system/users/{id}/roles", &[("id", "7")]),
+            (Some("users".to_string()), Some("7".to_string()))
+        );
+        assert_eq!(
+            resource_from_route(
+                "/api/manage/tasks/{task_id}/runs/{run_id}",
+                &[("task_id", "3"), ("run_id", "9")]
+            ),
+            (Some("tasks".to_string()), Some("3".to_string()))
+        );
+        assert_eq!(resource_from_route("/api/system/users", &[]), (None, None));
+    }
 }
@@ -1 +1,8 @@
+pub mod body_limit;
+pub mod csrf;
+pub mod error_report;
+pub mod locale;
 pub mod log;
+pub mod request_metrics;
+pub mod security_headers;
+pub mod static_cache;
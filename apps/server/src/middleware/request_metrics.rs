@@ -0,0 +1,82 @@
+use crate::infra::{route_metrics::ROUTE_METRICS, slow_log::SLOW_LOG};
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::time::{Duration, Instant};
+
+/// Records the latency and status of API requests per route template, and logs and records
+/// those slower than `slow_threshold` (`RUSTZEN_SLOW_REQUEST_MS`, `None` when that is 0).
+///
+/// Requests that matched no route only reach the slow log, under their raw path.
+pub async fn request_metrics_middleware(
+    State(slow_threshold): State<Option<Duration>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let start = Instant::now();
+    let method = request.method().clone();
+    let matched = request.extensions().get::<MatchedPath>().map(|path| path.as_str().to_string());
+    let route = matched.clone().unwrap_or_else(|| request.uri().path().to_string());
+    let response = next.run(request).await;
+    let elapsed = start.elapsed();
+    let duration_ms = elapsed.as_millis() as u64;
+    let status_code = response.status().as_u16();
+
+    if let Some(route) = &matched {
+        ROUTE_METRICS.record(method.as_str(), route, status_code, duration_ms);
+    }
+    if let Some(threshold) = slow_threshold.filter(|threshold| elapsed >= *threshold) {
+        tracing::warn!(
+            method = %method,
+            route = %route,
+            status_code,
+            duration_ms,
+            threshold_ms = threshold.as_millis() as u64,
+            "Slow request"
+        );
+        SLOW_LOG.record_request(method.as_str(), &route, status_code, duration_ms);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::request_metrics_middleware;
+    use crate::infra::{route_metrics::ROUTE_METRICS, slow_log::SLOW_LOG};
+
+    use axum::{Router, body::Body, http::Request, middleware, routing::get};
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn requests_are_measured_and_slow_ones_recorded_by_route_template() {
+        let app = Router::new()
+            .route("/slow-probe/{id}", get(|| tokio::time::sleep(Duration::from_millis(30))))
+            .route("/fast-probe", get(|| async {}))
+            .layer(middleware::from_fn_with_state(
+                Some(Duration::from_millis(20)),
+                request_metrics_middleware,
+            ));
+
+        for uri in ["/slow-probe/7", "/fast-probe"] {
+            let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+            app.clone().oneshot(request).await.unwrap();
+        }
+
+        let snapshot = SLOW_LOG.snapshot();
+        let probes: Vec<_> =
+            snapshot.requests.iter().filter(|r| r.route.contains("-probe")).collect();
+        assert_eq!(probes.len(), 1);
+        assert_eq!(probes[0].route, "/slow-probe/{id}");
+        assert_eq!(probes[0].status_code, 200);
+        assert!(probes[0].duration_ms >= 20);
+
+        let routes = ROUTE_METRICS.summary();
+        let probes: Vec<_> = routes.iter().filter(|r| r.route.contains("-probe")).collect();
+        assert_eq!(probes.len(), 2);
+        assert!(probes.iter().all(|r| r.requests == 1 && r.errors == 0));
+    }
+}
@@ -0,0 +1,74 @@
+use crate::infra::config::AppConfig;
+
+use axum::http::{
+    HeaderName, HeaderValue,
+    header::{
+        CONTENT_SECURITY_POLICY, InvalidHeaderValue, REFERRER_POLICY, STRICT_TRANSPORT_SECURITY,
+        X_CONTENT_TYPE_OPTIONS, X_FRAME_OPTIONS,
+    },
+};
+use tower_http::set_header::SetResponseHeaderLayer;
+
+/// Security headers for API, upload and web UI responses.
+///
+/// Headers a handler already set are kept, so a route can loosen or tighten its own policy.
+/// CSP and HSTS come from `RUSTZEN_CONTENT_SECURITY_POLICY` / `RUSTZEN_HSTS_MAX_AGE_SECS`;
+/// CSP is left off while `RUSTZEN_WEB_DEV_PROXY` serves the dev frontend.
+pub fn security_header_layers(
+    config: &AppConfig,
+) -> Result<Vec<SetResponseHeaderLayer<HeaderValue>>, InvalidHeaderValue> {
+    // Vite's dev client relies on inline scripts and its own websocket origin.
+    let content_security_policy = match config.server.web_dev_proxy {
+        Some(_) => "",
+        None => config.server.content_security_policy.as_str(),
+    };
+    let headers = default_headers(content_security_policy, config.server.hsts_max_age_secs)?;
+    Ok(headers
+        .into_iter()
+        .map(|(name, value)| SetResponseHeaderLayer::if_not_present(name, value))
+        .collect())
+}
+
+fn default_headers(
+    content_security_policy: &str,
+    hsts_max_age_secs: u64,
+) -> Result<Vec<(HeaderName, HeaderValue)>, InvalidHeaderValue> {
+    let mut headers = vec![
+        (X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff")),
+        (X_FRAME_OPTIONS, HeaderValue::from_static("SAMEORIGIN")),
+        (REFERRER_POLICY, HeaderValue::from_static("strict-origin-when-cross-origin")),
+    ];
+    if !content_security_policy.trim().is_empty() {
+        headers.push((
+            CONTENT_SECURITY_POLICY,
+            HeaderValue::from_str(content_security_policy.trim())?,
+        ));
+    }
+    if hsts_max_age_secs > 0 {
+        let value = format!("max-age={}", hsts_max_age_secs);
+        headers.push((STRICT_TRANSPORT_SECURITY, HeaderValue::from_str(&value)?));
+    }
+    Ok(headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::default_headers;
+    use axum::http::header::{CONTENT_SECURITY_POLICY, STRICT_TRANSPORT_SECURITY};
+
+    #[test]
+    fn csp_and_hsts_are_optional() {
+        let headers = default_headers("default-src 'self'", 600).unwrap();
+        assert_eq!(headers.len(), 5);
+        assert!(
+            headers.iter().any(|(name, value)| {
+                name == STRICT_TRANSPORT_SECURITY && value == "max-age=600"
+            })
+        );
+
+        let headers = default_headers(" ", 0).unwrap();
+        assert_eq!(headers.len(), 3);
+        assert!(!headers.iter().any(|(name, _)| name == CONTENT_SECURITY_POLICY));
+        assert!(default_headers("default-src\n'self'", 0).is_err());
+    }
+}
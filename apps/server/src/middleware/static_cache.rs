@@ -0,0 +1,142 @@
+//! Caching headers for the web UI's static files.
+//!
+//! Vite emits content-hashed files under `/assets/`, so those are cached for a year.
+//! Everything else, `index.html` included, must be revalidated on every use; responses
+//! carry a weak `ETag` built from size, modification time and encoding, and a matching
+//! `If-None-Match` gets an empty `304`.
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{
+        HeaderMap, HeaderValue, Method, StatusCode,
+        header::{
+            CACHE_CONTROL, CONTENT_ENCODING, CONTENT_LENGTH, ETAG, IF_NONE_MATCH, LAST_MODIFIED,
+            VARY,
+        },
+    },
+    middleware::Next,
+    response::Response,
+};
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+const HASHED_ASSETS_PREFIX: &str = "/assets/";
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+const REVALIDATE: &str = "no-cache";
+
+pub async fn static_cache_middleware(request: Request, next: Next) -> Response {
+    if !matches!(*request.method(), Method::GET | Method::HEAD) {
+        return next.run(request).await;
+    }
+    let hashed = request.uri().path().starts_with(HASHED_ASSETS_PREFIX);
+    let if_none_match = request.headers().get(IF_NONE_MATCH).cloned();
+
+    let mut response = next.run(request).await;
+    if !matches!(response.status(), StatusCode::OK | StatusCode::NOT_MODIFIED) {
+        return response;
+    }
+    let cache_control = if hashed { IMMUTABLE } else { REVALIDATE };
+    response.headers_mut().insert(CACHE_CONTROL, HeaderValue::from_static(cache_control));
+
+    let Some(etag) = weak_etag(response.headers()) else {
+        return response;
+    };
+    let fresh = if_none_match
+        .as_ref()
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| etag_matches(value, &etag));
+    let etag = HeaderValue::from_str(&etag).expect("etag is ASCII");
+    response.headers_mut().insert(ETAG, etag);
+
+    if fresh { not_modified(response.headers()) } else { response }
+}
+
+/// `W/"<size>-<hash of Last-Modified and Content-Encoding>"`; `None` without both inputs.
+fn weak_etag(headers: &HeaderMap) -> Option<String> {
+    let length = headers.get(CONTENT_LENGTH)?.to_str().ok()?;
+    let modified = headers.get(LAST_MODIFIED)?.as_bytes();
+    let mut hasher = DefaultHasher::new();
+    modified.hash(&mut hasher);
+    headers.get(CONTENT_ENCODING).map(HeaderValue::as_bytes).hash(&mut hasher);
+    Some(format!("W/\"{}-{:x}\"", length, hasher.finish()))
+}
+
+/// Weak comparison against an `If-None-Match` list, as RFC 9110 requires for it.
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let etag = opaque(etag);
+    if_none_match.split(',').any(|candidate| candidate.trim() == "*" || opaque(candidate) == etag)
+}
+
+fn not_modified(headers: &HeaderMap) -> Response {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = StatusCode::NOT_MODIFIED;
+    for name in [CACHE_CONTROL, ETAG, LAST_MODIFIED, VARY] {
+        for value in headers.get_all(&name) {
+            response.headers_mut().append(name.clone(), value.clone());
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::static_cache_middleware;
+
+    use axum::{
+        Router,
+        body::Body,
+        http::{
+            Request, StatusCode,
+            header::{ACCEPT_ENCODING, CACHE_CONTROL, CONTENT_ENCODING, ETAG, IF_NONE_MATCH},
+        },
+        middleware,
+        response::Response,
+    };
+    use tower::ServiceExt;
+    use tower_http::services::{ServeDir, ServeFile};
+    use uuid::Uuid;
+
+    async fn get(dir: &std::path::Path, uri: &str, headers: &[(&str, &str)]) -> Response {
+        let serve = ServeDir::new(dir)
+            .precompressed_br()
+            .precompressed_gzip()
+            .fallback(ServeFile::new(dir.join("index.html")));
+        let app = Router::new()
+            .fallback_service(serve)
+            .layer(middleware::from_fn(static_cache_middleware));
+        let mut request = Request::builder().uri(uri);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        app.oneshot(request.body(Body::empty()).unwrap()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn index_revalidates_by_etag_and_hashed_assets_are_immutable() {
+        let dir = std::env::temp_dir().join(format!("rustzen-static-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("assets")).unwrap();
+        std::fs::write(dir.join("index.html"), "<html></html>").unwrap();
+        std::fs::write(dir.join("assets/app-3f2a.js"), "console.log(1)").unwrap();
+        std::fs::write(dir.join("assets/app-3f2a.js.gz"), "gzip bytes").unwrap();
+
+        let response = get(&dir, "/system/users", &[]).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CACHE_CONTROL], "no-cache");
+        let etag = response.headers()[ETAG].to_str().unwrap().to_string();
+        assert!(etag.starts_with("W/\""), "{}", etag);
+
+        let response = get(&dir, "/", &[(IF_NONE_MATCH.as_str(), &etag)]).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[ETAG], etag.as_str());
+
+        let plain = get(&dir, "/assets/app-3f2a.js", &[]).await;
+        assert_eq!(plain.headers()[CACHE_CONTROL], "public, max-age=31536000, immutable");
+        let gzip =
+            get(&dir, "/assets/app-3f2a.js", &[(ACCEPT_ENCODING.as_str(), "gzip, br")]).await;
+        assert_eq!(gzip.headers()[CONTENT_ENCODING], "gzip");
+        assert_ne!(gzip.headers()[ETAG], plain.headers()[ETAG]);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
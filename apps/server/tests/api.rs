@@ -0,0 +1,2333 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode, header},
+};
+use chrono::{Duration, SecondsFormat, Utc};
+use common::TestApp;
+use http_body_util::BodyExt;
+use serde_json::json;
+use server::{
+    common::tx,
+    features::{
+        auth::service::AuthService,
+        manage::log::{service::LogService, types::LogWriteCommand},
+        system::{feature_flag::service::FeatureFlags, user::service::UserService},
+    },
+    infra::{
+        auth_runtime::jwt_codec,
+        events,
+        geoip::GeoLocation,
+        password::{HashPolicy, PasswordAlgorithm},
+    },
+};
+
+#[tokio::test]
+async fn login_rejects_bad_credentials_and_protected_routes_need_a_token() {
+    let app = TestApp::spawn().await;
+    app.create_user("alice", "correct-password", &[]).await;
+
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/auth/login",
+            None,
+            Some(json!({ "username": "alice", "password": "wrong-password" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["code"], 10101);
+
+    let (status, body) = app.request(Method::GET, "/api/auth/me", None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["code"], 30000);
+    let request = Request::get("/api/auth/me")
+        .header(header::AUTHORIZATION, "Bearer not-a-token")
+        .header(header::ACCEPT_LANGUAGE, "zh-CN")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.send(request).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["message"], "令牌无效或已过期，请重新登录。");
+
+    let token = app.login("alice", "correct-password").await;
+    let (status, body) = app.get("/api/auth/me", &token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["username"], "alice");
+}
+
+#[tokio::test]
+async fn login_succeeds_when_the_operation_log_cannot_be_written() {
+    let app = TestApp::spawn().await;
+    app.create_user("dora", "dora-password", &[]).await;
+    sqlx::query(
+        "CREATE TRIGGER reject_logs BEFORE INSERT ON operation_logs
+         BEGIN SELECT RAISE(ABORT, 'log store unavailable'); END",
+    )
+    .execute(&app.pool)
+    .await
+    .expect("trigger");
+
+    let token = app.login("dora", "dora-password").await;
+    let (status, body) = app.get("/api/auth/me", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let logs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM operation_logs WHERE username = ?")
+        .bind("dora")
+        .fetch_one(&app.pool)
+        .await
+        .unwrap();
+    assert_eq!(logs, 0);
+}
+
+#[tokio::test]
+async fn logins_from_a_new_country_raise_an_unusual_location_alert() {
+    let app = TestApp::spawn().await;
+    let user_id = app.create_user("erin", "erin-password", &[]).await.get();
+    let admin = app.admin_token().await;
+    app.login("erin", "erin-password").await;
+    let last_login = || async {
+        sqlx::query_as::<_, (Option<String>, Option<String>, Option<String>)>(
+            "SELECT last_login_ip, last_login_country, last_login_city FROM users WHERE id = ?",
+        )
+        .bind(user_id)
+        .fetch_one(&app.pool)
+        .await
+        .unwrap()
+    };
+    let (ip, country, _) = last_login().await;
+    assert!(ip.is_some_and(|ip| ip.starts_with("127.")));
+    assert_eq!(country, None);
+
+    let at = |country: &str, city: &str| GeoLocation {
+        country: Some(country.to_string()),
+        city: Some(city.to_string()),
+        ..GeoLocation::default()
+    };
+    let pool = &app.pool;
+    let record = |ip: &'static str, location: GeoLocation| async move {
+        let mut tx = tx::begin(pool).await.unwrap();
+        let recorded =
+            AuthService::record_login_location(&mut tx, user_id, "erin", ip, location).await;
+        tx::commit(tx).await.unwrap();
+        if let Some(recorded) = recorded.unwrap() {
+            events::deliver(pool, recorded).await;
+        }
+    };
+    record("203.0.113.7", at("CN", "Beijing")).await;
+    record("203.0.113.8", at("CN", "Shanghai")).await;
+    record("198.51.100.4", at("US", "Seattle")).await;
+    record("10.1.2.3", GeoLocation::default()).await;
+    assert_eq!(
+        last_login().await,
+        (Some("10.1.2.3".to_string()), Some("US".to_string()), Some("Seattle".to_string()))
+    );
+
+    let (status, body) = app.get("/api/manage/logs?action=AUTH_UNUSUAL_LOCATION", &admin).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let alerts = body["data"].as_array().unwrap();
+    assert_eq!(alerts.len(), 1, "{}", body);
+    assert_eq!(alerts[0]["username"], "erin");
+    assert_eq!(alerts[0]["status"], "WARN");
+    assert_eq!(alerts[0]["ipAddress"], "198.51.100.4");
+    assert_eq!(alerts[0]["country"], "US");
+    assert_eq!(alerts[0]["city"], "Seattle");
+    assert_eq!(alerts[0]["data"]["previousCountry"], "CN");
+}
+
+#[tokio::test]
+async fn suspicious_logins_notify_the_owner_and_security_admins() {
+    let app = &TestApp::spawn().await;
+    let admin = app.admin_token().await;
+    app.create_user("grace", "grace-password", &[]).await;
+    let login_with_agent = |user_agent: &'static str, password: &'static str| {
+        let body = json!({ "username": "grace", "password": password });
+        app.send(
+            Request::builder()
+                .method(Method::POST)
+                .uri("/api/auth/login")
+                .header(header::CONTENT_TYPE, "application/json")
+                .header(header::USER_AGENT, user_agent)
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+    };
+    let alerts = |token: String| async move {
+        let (status, body) = app.get("/api/account/notifications?unread=true", &token).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        let rules = body["data"].as_array().unwrap().iter().map(|n| n["data"]["rule"].clone());
+        rules.collect::<Vec<_>>()
+    };
+
+    assert_eq!(login_with_agent("laptop", "grace-password").await.status(), StatusCode::OK);
+    assert_eq!(login_with_agent("laptop", "grace-password").await.status(), StatusCode::OK);
+    let grace = app.login("grace", "grace-password").await;
+    let rules = alerts(grace.clone()).await;
+    assert_eq!(rules, [json!("new_device")]);
+    assert_eq!(alerts(admin.clone()).await, [json!("new_device")]);
+
+    for _ in 0..5 {
+        let response = login_with_agent("laptop", "wrong-password").await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+    assert_eq!(login_with_agent("laptop", "grace-password").await.status(), StatusCode::OK);
+    let rules = alerts(grace.clone()).await;
+    assert_eq!(rules, [json!("failures_then_success"), json!("new_device")]);
+
+    let (status, body) = app.get("/api/manage/logs?action=AUTH_SUSPICIOUS_LOGIN", &admin).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"].as_array().unwrap().len(), 2, "{}", body);
+
+    let (status, body) = app
+        .request(
+            Method::PUT,
+            "/api/system/login-alerts/rules/new_device",
+            Some(&admin),
+            Some(json!({ "enabled": false })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"][0]["code"], "new_device");
+    assert_eq!(body["data"][0]["enabled"], false);
+    assert_eq!(login_with_agent("phone", "grace-password").await.status(), StatusCode::OK);
+    let (status, _) =
+        app.request(Method::POST, "/api/account/notifications/read", Some(&grace), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let rules = alerts(grace).await;
+    assert!(rules.is_empty());
+}
+
+#[tokio::test]
+async fn sign_in_devices_and_login_logs_show_readable_device_names() {
+    let app = TestApp::spawn().await;
+    let admin = app.admin_token().await;
+    app.create_user("heidi", "heidi-password", &[]).await;
+    let chrome = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+        (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36";
+    let body = json!({ "username": "heidi", "password": "heidi-password" });
+    let response = app
+        .send(
+            Request::builder()
+                .method(Method::POST)
+                .uri("/api/auth/login")
+                .header(header::CONTENT_TYPE, "application/json")
+                .header(header::USER_AGENT, chrome)
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let token: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    let token = token["data"]["token"].as_str().unwrap();
+
+    let (status, body) = app.get("/api/account/devices", token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"][0]["userAgent"], chrome);
+    assert_eq!(body["data"][0]["device"]["name"], "Chrome on Windows 10");
+    assert_eq!(body["data"][0]["device"]["deviceType"], "desktop");
+
+    let (status, body) = app.get("/api/manage/logs?username=heidi&action=AUTH_LOGIN", &admin).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"][0]["device"]["name"], "Chrome on Windows 10", "{}", body);
+}
+
+#[tokio::test]
+async fn repeated_failed_logins_ban_the_client_ip() {
+    let app = TestApp::spawn().await;
+    app.create_user("carol", "correct-password", &[]).await;
+    let attempt = |password: &'static str| {
+        app.response(
+            Method::POST,
+            "/api/auth/login",
+            None,
+            Some(json!({ "username": "carol", "password": password })),
+        )
+    };
+
+    for _ in 0..10 {
+        assert_eq!(attempt("wrong-password").await.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    let response = attempt("correct-password").await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(response.headers()[header::RETRY_AFTER], "60");
+
+    let banned: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM operation_logs WHERE action = 'AUTH_IP_BAN'")
+            .fetch_one(&app.pool)
+            .await
+            .unwrap();
+    assert_eq!(banned, 1);
+}
+
+#[tokio::test]
+async fn login_upgrades_legacy_bcrypt_hashes_to_argon2id() {
+    let app = TestApp::spawn().await;
+    let id = app.create_user("dave", "legacy-password", &[]).await;
+    let legacy = HashPolicy { algorithm: PasswordAlgorithm::Bcrypt, bcrypt_cost: 4 }
+        .hash_password("legacy-password")
+        .unwrap();
+    sqlx::query("UPDATE users SET password_hash = ? WHERE id = ?")
+        .bind(&legacy)
+        .bind(id)
+        .execute(&app.pool)
+        .await
+        .unwrap();
+
+    app.login("dave", "legacy-password").await;
+
+    let stored: String = sqlx::query_scalar("SELECT password_hash FROM users WHERE id = ?")
+        .bind(id)
+        .fetch_one(&app.pool)
+        .await
+        .unwrap();
+    assert!(stored.starts_with("$argon2id$"), "{}", stored);
+    app.login("dave", "legacy-password").await;
+}
+
+#[tokio::test]
+async fn routes_are_gated_by_role_capabilities() {
+    let app = TestApp::spawn().await;
+    app.create_user("nobody", "nobody-password", &[]).await;
+    app.create_user("auditor", "auditor-password", &["viewer"]).await;
+    let nobody = app.login("nobody", "nobody-password").await;
+    let auditor = app.login("auditor", "auditor-password").await;
+
+    let (status, body) = app.get("/api/system/users", &nobody).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], 30001);
+
+    let (status, _) = app.get("/api/system/users", &auditor).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app
+        .request(
+            Method::POST,
+            "/api/system/roles",
+            Some(&auditor),
+            Some(json!({ "name": "Ops", "code": "ops", "status": 1, "menuIds": [] })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn admin_can_create_update_list_and_delete_users() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/system/users",
+            Some(&token),
+            Some(json!({
+                "username": "bob",
+                "email": "bob@example.com",
+                "password": "bob-password",
+                "realName": "Bob",
+            })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let id = body["data"].as_i64().expect("new user id");
+
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/system/users",
+            Some(&token),
+            Some(json!({ "username": "bob", "email": "bob2@example.com", "password": "x" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], 10201);
+
+    let (status, _) = app
+        .request(
+            Method::PUT,
+            &format!("/api/system/users/{}", id),
+            Some(&token),
+            Some(json!({ "email": "bob@example.org", "realName": "Robert", "roleIds": [] })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = app.get("/api/system/users?username=bob", &token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["data"][0]["realName"], "Robert");
+    // A new email waits for its owner to confirm it.
+    assert_eq!(body["data"][0]["email"], "bob@example.com");
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/auth/confirm-email",
+            None,
+            Some(json!({ "token": "not-a-token" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+
+    let (status, _) =
+        app.request(Method::DELETE, &format!("/api/system/users/{}", id), Some(&token), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, body) = app.get("/api/system/users?username=bob", &token).await;
+    assert_eq!(body["total"], 0);
+}
+
+#[tokio::test]
+async fn status_fields_are_checked_against_their_dictionary_type() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let menu = |code: &str, status: i16| {
+        json!({
+            "parentId": 0,
+            "name": code,
+            "code": code,
+            "menuType": 2,
+            "sortOrder": 1,
+            "status": status,
+        })
+    };
+
+    let (status, body) = app
+        .request(Method::POST, "/api/system/menus", Some(&token), Some(menu("reports", 3)))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    assert_eq!(body["code"], 10015);
+    assert_eq!(body["data"], json!([{ "field": "status", "message": "must be one of 1, 2" }]));
+
+    // Once `menu_status` has entries, only its enabled values are accepted.
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/manage/dicts",
+            Some(&token),
+            Some(json!({ "dictType": "menu_status", "label": "Visible", "value": "1" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (status, body) = app
+        .request(Method::POST, "/api/system/menus", Some(&token), Some(menu("reports", 2)))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    assert_eq!(body["data"][0]["message"], "must be one of 1");
+    let (status, body) = app
+        .request(Method::POST, "/api/system/menus", Some(&token), Some(menu("reports", 1)))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/manage/dicts",
+            Some(&token),
+            Some(json!({ "dictType": "color", "label": "Red", "value": "r", "status": 0 })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    assert_eq!(body["data"][0]["field"], "status");
+}
+
+#[tokio::test]
+async fn dictionary_types_are_reordered_as_a_whole() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let mut ids = Vec::new();
+    for label in ["Low", "Medium", "High"] {
+        let (status, body) = app
+            .request(
+                Method::POST,
+                "/api/manage/dicts",
+                Some(&token),
+                Some(json!({ "dictType": "priority", "label": label, "value": label })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        ids.push(body["data"].as_i64().unwrap());
+    }
+    let reorder = |ids: Vec<i64>| json!({ "ids": ids });
+
+    let (status, body) = app
+        .request(
+            Method::PUT,
+            "/api/manage/dicts/type/priority/reorder",
+            Some(&token),
+            Some(reorder(vec![ids[2], ids[0], ids[1]])),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (_, body) = app.get("/api/manage/dicts/type/priority", &token).await;
+    let labels: Vec<_> =
+        body["data"].as_array().unwrap().iter().map(|o| o["label"].clone()).collect();
+    assert_eq!(labels, [json!("High"), json!("Low"), json!("Medium")]);
+
+    let (status, body) = app
+        .request(
+            Method::PUT,
+            "/api/manage/dicts/type/priority/reorder",
+            Some(&token),
+            Some(reorder(vec![ids[2], ids[0]])),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    assert_eq!(body["data"][0]["field"], "ids");
+
+    let (status, _) = app
+        .request(
+            Method::PUT,
+            "/api/manage/dicts/type/missing/reorder",
+            Some(&token),
+            Some(reorder(vec![ids[0]])),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+/// GETs `uri` with an `Accept-Language` header and returns the JSON body.
+async fn get_in(app: &TestApp, uri: &str, token: &str, language: &str) -> serde_json::Value {
+    let request = Request::get(uri)
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::ACCEPT_LANGUAGE, language)
+        .body(Body::empty())
+        .unwrap();
+    let response = app.send(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+#[tokio::test]
+async fn dictionary_labels_and_menu_names_follow_the_request_language() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let mut ids = Vec::new();
+    for (label, value) in [("Active", "1"), ("Blocked", "2")] {
+        let item = json!({ "dictType": "account_state", "label": label, "value": value });
+        let (_, body) =
+            app.request(Method::POST, "/api/manage/dicts", Some(&token), Some(item)).await;
+        ids.push(body["data"].as_i64().unwrap());
+    }
+    let uri = format!("/api/manage/dicts/{}/translations", ids[0]);
+    let translations = json!({ "translations": [
+        { "locale": "ZH-cn", "text": " 启用 " },
+        { "locale": "fr", "text": "Actif" },
+    ] });
+    let (status, body) = app.request(Method::PUT, &uri, Some(&token), Some(translations)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    assert_eq!(body["data"][0]["field"], "translations[1].locale");
+    let translations = json!({ "translations": [{ "locale": "ZH-cn", "text": " 启用 " }] });
+    let (status, body) = app.request(Method::PUT, &uri, Some(&token), Some(translations)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"], json!([{ "locale": "zh-CN", "text": "启用" }]));
+    let (status, _) = app
+        .request(
+            Method::PUT,
+            "/api/manage/dicts/999999/translations",
+            Some(&token),
+            Some(json!({ "translations": [] })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let labels = |body: serde_json::Value| -> Vec<String> {
+        let items = body["data"].as_array().unwrap().iter();
+        items.map(|item| item["label"].as_str().unwrap().to_string()).collect()
+    };
+    let by_type = "/api/manage/dicts/type/account_state";
+    assert_eq!(labels(get_in(&app, by_type, &token, "zh-CN,zh;q=0.9").await), ["Blocked", "启用"]);
+    assert_eq!(labels(get_in(&app, by_type, &token, "en-US").await), ["Active", "Blocked"]);
+    let options = "/api/manage/dicts/options?dictType=account_state&q=启用";
+    assert_eq!(labels(get_in(&app, options, &token, "zh").await), ["启用"]);
+    // The management list always shows the base label.
+    let list = get_in(&app, "/api/manage/dicts?dictType=account_state", &token, "zh").await;
+    assert_eq!(list["data"][0]["label"], "Active");
+
+    let (menu_id, code, name): (i64, String, String) = sqlx::query_as(
+        "SELECT id, code, name FROM menus
+         WHERE menu_type = 1 AND status = 1 AND deleted_at IS NULL LIMIT 1",
+    )
+    .fetch_one(&app.pool)
+    .await
+    .unwrap();
+    let uri = format!("/api/system/menus/{}/translations", menu_id);
+    let translations = json!({ "translations": [{ "locale": "zh-CN", "text": "系统目录" }] });
+    let (status, body) = app.request(Method::PUT, &uri, Some(&token), Some(translations)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (_, body) = app.get(&uri, &token).await;
+    assert_eq!(body["data"][0]["text"], "系统目录");
+    let menu_name = |body: serde_json::Value| {
+        let menus = body["data"]["menus"].as_array().unwrap();
+        menus.iter().find(|menu| menu["code"] == code.as_str()).unwrap()["name"].clone()
+    };
+    assert_eq!(menu_name(get_in(&app, "/api/auth/me", &token, "zh-CN").await), "系统目录");
+    assert_eq!(menu_name(get_in(&app, "/api/auth/me", &token, "en").await), name.as_str());
+}
+
+#[tokio::test]
+async fn options_endpoints_share_status_exclude_and_include_filters() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let mut ids = Vec::new();
+    for (name, status) in [("Opt Alpha", 1), ("Opt Beta", 2), ("Opt Gamma", 1)] {
+        let id: i64 = sqlx::query_scalar(
+            "INSERT INTO roles (name, code, status) VALUES (?, ?, ?) RETURNING id",
+        )
+        .bind(name)
+        .bind(name.to_lowercase().replace(' ', "_"))
+        .bind(status)
+        .fetch_one(&app.pool)
+        .await
+        .expect("insert role");
+        ids.push(id);
+    }
+    let labels = |uri: String| {
+        let (app, token) = (&app, &token);
+        async move {
+            let (status, body) = app.get(&uri, token).await;
+            assert_eq!(status, StatusCode::OK, "{}", body);
+            let items = body["data"].as_array().unwrap().iter();
+            items.map(|item| item["label"].as_str().unwrap().to_string()).collect::<Vec<_>>()
+        }
+    };
+    let roles = "/api/system/roles/options?q=opt";
+    assert_eq!(labels(roles.to_string()).await, ["Opt Alpha", "Opt Gamma"]);
+    assert_eq!(labels(format!("{}&status=all", roles)).await.len(), 3);
+    assert_eq!(labels(format!("{}&excludeIds={}", roles, ids[0])).await, ["Opt Gamma"]);
+    // A disabled, non-matching selection still comes back, first, and survives the limit.
+    let preselected =
+        format!("/api/system/roles/options?q=gamma&includeIds={},{}&limit=1", ids[1], ids[0]);
+    assert_eq!(labels(preselected.clone()).await, ["Opt Alpha"]);
+    let preselected = preselected.replace("&limit=1", "");
+    assert_eq!(labels(preselected).await, ["Opt Alpha", "Opt Beta", "Opt Gamma"]);
+    let (status, _) = app.get("/api/system/roles/options?excludeIds=x", &token).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let user = app.create_user("opt_user", "opt-password", &[]).await;
+    let users = format!("/api/system/users/options?q=opt_&excludeIds={}", user);
+    assert!(labels(users).await.is_empty());
+}
+
+#[tokio::test]
+async fn each_dictionary_type_keeps_at_most_one_default() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let mut ids = Vec::new();
+    for (label, is_default) in [("Email", true), ("Sms", true), ("Push", false)] {
+        let item = json!({ "dictType": "channel", "label": label, "value": label, "isDefault": is_default });
+        let (status, body) =
+            app.request(Method::POST, "/api/manage/dicts", Some(&token), Some(item)).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        ids.push(body["data"].as_i64().unwrap());
+    }
+    let defaults = || async {
+        let (_, body) = app.get("/api/manage/dicts?dictType=channel", &token).await;
+        body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|item| item["isDefault"] == true)
+            .map(|item| item["label"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(defaults().await, ["Sms"]);
+
+    let update =
+        json!({ "dictType": "channel", "label": "Push", "value": "Push", "isDefault": true });
+    let (status, body) = app
+        .request(Method::PUT, &format!("/api/manage/dicts/{}", ids[2]), Some(&token), Some(update))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(defaults().await, ["Push"]);
+
+    // Editing without the flag keeps it; moving to another type drops it.
+    let update = json!({ "dictType": "channel", "label": "Push", "value": "push" });
+    app.request(Method::PUT, &format!("/api/manage/dicts/{}", ids[2]), Some(&token), Some(update))
+        .await;
+    assert_eq!(defaults().await, ["Push"]);
+    let update = json!({ "dictType": "channel_legacy", "label": "Push", "value": "push" });
+    app.request(Method::PUT, &format!("/api/manage/dicts/{}", ids[2]), Some(&token), Some(update))
+        .await;
+    assert!(defaults().await.is_empty());
+}
+
+#[tokio::test]
+async fn menu_display_flags_reach_the_login_info() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let menu = json!({
+        "parentId": 0,
+        "name": "Reports",
+        "code": "reports",
+        "menuType": 2,
+        "sortOrder": 1,
+        "status": 1,
+        "visible": false,
+        "keepAlive": true,
+    });
+    let (status, body) =
+        app.request(Method::POST, "/api/system/menus", Some(&token), Some(menu.clone())).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let menu_id = body["data"].as_i64().expect("new menu id");
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/system/roles",
+            Some(&token),
+            Some(json!({ "name": "Reporter", "code": "reporter", "status": 1, "menuIds": [menu_id] })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    app.create_user("erin", "erin-password", &["reporter"]).await;
+
+    let erin = app.login("erin", "erin-password").await;
+    let (status, body) = app.get("/api/auth/me", &erin).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(
+        body["data"]["menus"],
+        json!([{
+            "code": "reports",
+            "name": "Reports",
+            "menuType": 2,
+            "linkUrl": null,
+            "visible": false,
+            "keepAlive": true,
+        }])
+    );
+
+    // Updates that leave the flags out keep them.
+    let mut update = menu;
+    update["name"] = json!("Monthly Reports");
+    update.as_object_mut().unwrap().remove("visible");
+    update["keepAlive"] = json!(false);
+    let uri = format!("/api/system/menus/{}", menu_id);
+    let (status, body) = app.request(Method::PUT, &uri, Some(&token), Some(update)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (_, body) = app.get("/api/system/menus?code=reports", &token).await;
+    let listed = &body["data"][0];
+    assert_eq!(
+        (listed["visible"].as_bool(), listed["keepAlive"].as_bool()),
+        (Some(false), Some(false))
+    );
+}
+
+#[tokio::test]
+async fn iframe_menus_need_a_url_and_reach_the_login_info() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let mut menu = json!({
+        "parentId": 0,
+        "name": "Grafana",
+        "code": "ops:grafana",
+        "menuType": 5,
+        "sortOrder": 1,
+        "status": 1,
+    });
+    for (link_url, message) in [
+        (json!(null), "is required for link and iframe menus"),
+        (json!("ftp://grafana.internal"), "must be an http or https URL"),
+    ] {
+        menu["linkUrl"] = link_url;
+        let (status, body) =
+            app.request(Method::POST, "/api/system/menus", Some(&token), Some(menu.clone())).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+        assert_eq!(body["code"], 10015);
+        assert_eq!(body["data"], json!([{ "field": "linkUrl", "message": message }]));
+    }
+
+    menu["linkUrl"] = json!("https://grafana.internal/d/api");
+    let (status, body) =
+        app.request(Method::POST, "/api/system/menus", Some(&token), Some(menu)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let menu_id = body["data"].as_i64().expect("new menu id");
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/system/roles",
+            Some(&token),
+            Some(json!({ "name": "Operator", "code": "operator", "status": 1, "menuIds": [menu_id] })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    app.create_user("olga", "olga-password", &["operator"]).await;
+
+    let olga = app.login("olga", "olga-password").await;
+    let (status, body) = app.get("/api/auth/me", &olga).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let menus = &body["data"]["menus"];
+    assert_eq!(menus[0]["menuType"], 5);
+    assert_eq!(menus[0]["linkUrl"], "https://grafana.internal/d/api");
+}
+
+#[tokio::test]
+async fn menu_tree_loads_one_level_at_a_time() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let (status, body) = app.get("/api/system/menus/children", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let top = body["data"].as_array().unwrap();
+    assert!(top.iter().all(|menu| menu["parentId"] == 0), "{}", body);
+    let parent = top.iter().find(|menu| menu["hasChildren"] == true).expect("expandable menu");
+
+    let uri = format!("/api/system/menus/children?parentId={}", parent["id"]);
+    let (status, body) = app.get(&uri, &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let children = body["data"].as_array().unwrap();
+    assert!(!children.is_empty());
+    assert!(children.iter().all(|menu| menu["parentId"] == parent["id"]), "{}", body);
+    let (total,): (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM menus WHERE parent_id = ? AND deleted_at IS NULL")
+            .bind(parent["id"].as_i64())
+            .fetch_one(&app.pool)
+            .await
+            .unwrap();
+    assert_eq!(children.len() as i64, total);
+    let leaf = children.iter().find(|menu| menu["hasChildren"] == false).expect("leaf");
+    let (_, body) =
+        app.get(&format!("/api/system/menus/children?parent_id={}", leaf["id"]), &token).await;
+    assert_eq!(body["data"], json!([]));
+}
+
+#[tokio::test]
+async fn permission_registry_matches_routes_and_menus() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+
+    let (status, body) = app.get("/api/system/permissions/registry", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["issueCount"], 0, "{}", body);
+    let entries = body["data"]["entries"].as_array().expect("registry entries");
+    let menu_list = entries.iter().find(|entry| entry["code"] == "system:menu:list").unwrap();
+    assert_eq!(menu_list["declared"], true);
+    assert_eq!(menu_list["routed"], true);
+    assert_eq!(menu_list["menuStatus"], 1);
+    assert!(menu_list["issue"].is_null());
+}
+
+#[tokio::test]
+async fn capability_checks_report_the_granting_code() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let viewer_id = app.create_user("vera", "vera-password", &["viewer"]).await;
+    let vera = app.login("vera", "vera-password").await;
+
+    let (status, body) = app.get("/api/auth/me/can?perm=system:user:list", &vera).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(
+        (&body["data"]["allowed"], &body["data"]["grantedBy"], &body["data"]["declared"]),
+        (&json!(true), &json!("system:user:list"), &json!(true))
+    );
+
+    let uri = format!("/api/system/users/{}/can?perm=system:user:delete", viewer_id.0);
+    let (status, body) = app.get(&uri, &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(
+        (&body["data"]["allowed"], &body["data"]["grantedBy"]),
+        (&json!(false), &json!(null))
+    );
+    app.create_user("nora", "nora-password", &[]).await;
+    let nora = app.login("nora", "nora-password").await;
+    let (status, _) = app.get(&uri, &nora).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // Wildcard holders pass even for a misspelled code, which the registry flags.
+    let (_, body) = app.get("/api/auth/me/can?perm=system:usr:delete", &token).await;
+    assert_eq!(
+        (&body["data"]["allowed"], &body["data"]["grantedBy"], &body["data"]["declared"]),
+        (&json!(true), &json!("*"), &json!(false))
+    );
+    let (status, body) = app.get("/api/auth/me/can?perm=%20", &token).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    assert_eq!(body["data"], json!([{ "field": "perm", "message": "is required" }]));
+}
+
+#[tokio::test]
+async fn effective_access_lists_granting_roles_and_the_menu_tree() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let id = app.create_user("olga", "olga-password", &["viewer", "admin"]).await;
+
+    let (status, body) =
+        app.get(&format!("/api/system/users/{}/effective-access", id.0), &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let access = &body["data"];
+    assert_eq!(
+        (&access["username"], &access["active"], &access["isSuper"]),
+        (&json!("olga"), &json!(true), &json!(false))
+    );
+    let permissions = access["permissions"].as_array().unwrap();
+    let user_list = permissions.iter().find(|p| p["code"] == "system:user:list").unwrap();
+    assert_eq!(user_list["roles"], json!(["admin", "viewer"]));
+    let user_delete = permissions.iter().find(|p| p["code"] == "system:user:delete").unwrap();
+    assert_eq!(user_delete["roles"], json!(["admin"]));
+    let menus = access["menus"].as_array().unwrap();
+    assert!(menus.iter().any(|m| m["code"] == "system:user:list"), "{}", body);
+    assert!(menus.iter().all(|m| m["menuType"] != 3), "{}", body);
+
+    let owner_id: i64 = sqlx::query_scalar("SELECT id FROM users WHERE username = 'it_admin'")
+        .fetch_one(&app.pool)
+        .await
+        .unwrap();
+    let (_, body) =
+        app.get(&format!("/api/system/users/{}/effective-access", owner_id), &token).await;
+    assert_eq!(body["data"]["isSuper"], true);
+    assert_eq!(body["data"]["permissions"], json!([{ "code": "*", "roles": ["owner"] }]));
+
+    let (status, _) = app.get("/api/system/users/999999999/effective-access", &token).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn personal_fields_are_masked_without_the_privacy_capability() {
+    let app = TestApp::spawn().await;
+    let owner = app.admin_token().await;
+    app.create_user("pia", "pia-password", &["viewer"]).await;
+    let viewer = app.login("pia", "pia-password").await;
+    let command = LogWriteCommand {
+        username: "pia".to_string(),
+        action: "PRIVACY_PROBE".to_string(),
+        status: "SUCCESS".to_string(),
+        ip_address: "203.0.113.7".to_string(),
+        ..Default::default()
+    };
+    LogService::log_operation(&app.pool, command).await;
+
+    let users = "/api/system/users?username=pia";
+    let logs = "/api/manage/logs?action=PRIVACY_PROBE";
+    let (status, body) = app.get(users, &viewer).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"][0]["email"], "p***@example.com");
+    let (status, body) = app.get(logs, &viewer).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"][0]["ipAddress"], "203.0.*.*");
+
+    let (_, body) = app.get(users, &owner).await;
+    assert_eq!(body["data"][0]["email"], "pia@example.com");
+    let (_, body) = app.get(logs, &owner).await;
+    assert_eq!(body["data"][0]["ipAddress"], "203.0.113.7");
+}
+
+#[tokio::test]
+async fn api_and_web_responses_carry_security_headers() {
+    let app = TestApp::spawn().await;
+
+    for uri in ["/api/summary", "/api/auth/me", "/dashboard/index"] {
+        let response = app.response(Method::GET, uri, None, None).await;
+        let headers = response.headers();
+        assert_eq!(headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff", "{}", uri);
+        assert_eq!(headers[header::X_FRAME_OPTIONS], "SAMEORIGIN", "{}", uri);
+        assert_eq!(headers[header::REFERRER_POLICY], "strict-origin-when-cross-origin", "{}", uri);
+        assert!(headers.contains_key(header::CONTENT_SECURITY_POLICY), "{}", uri);
+        assert!(
+            headers[header::STRICT_TRANSPORT_SECURITY].to_str().unwrap().starts_with("max-age=")
+        );
+    }
+}
+
+#[tokio::test]
+async fn user_activity_merges_logins_operations_and_role_changes() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let id = app.create_user("erin", "erin-password", &["viewer"]).await;
+    let erin = app.login("erin", "erin-password").await;
+    app.get("/api/auth/me", &erin).await;
+    let url = format!("/api/system/users/{}/activity", id);
+
+    // Login events are written by an event subscriber, so give it a moment.
+    let mut kinds = Vec::new();
+    for _ in 0..50 {
+        let (status, body) = app.get(&url, &token).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        kinds = body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["kind"].as_str().unwrap().to_string())
+            .collect();
+        if kinds.iter().any(|kind| kind == "login") {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    assert!(kinds.contains(&"operation".to_string()), "{:?}", kinds);
+    assert_eq!(kinds.last().map(String::as_str), Some("role_change"), "{:?}", kinds);
+
+    let (_, body) = app.get(&format!("{}?kind=role_change", url), &token).await;
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["data"][0]["action"], "assigned");
+    assert!(body["data"][0]["roleName"].is_string());
+    let (_, body) = app.get(&format!("{}?kind=login&pageSize=1", url), &token).await;
+    assert_eq!(body["data"][0]["action"], "AUTH_LOGIN");
+    let (_, body) = app.get(&format!("{}?from=2000-01-01&to=2000-12-31", url), &token).await;
+    assert_eq!(body["total"], 0);
+
+    let (status, body) = app.get(&format!("{}?kind=purchase", url), &token).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    assert_eq!(body["data"][0]["field"], "kind");
+    let (status, _) = app.get(&url, &erin).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn personal_data_is_exported_then_anonymized() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let id = app.create_user("frank", "frank-password", &["viewer"]).await;
+    let frank = app.login("frank", "frank-password").await;
+    app.get("/api/auth/me", &frank).await;
+    sqlx::query(
+        "INSERT INTO email_change_requests (user_id, new_email, token_hash, expires_at)
+         VALUES (?, 'frank@new.example', 'hash', datetime('now', '+1 day'))",
+    )
+    .bind(id)
+    .execute(&app.pool)
+    .await
+    .expect("email change request");
+    let export_url = format!("/api/system/users/{}/export", id);
+
+    let mut body = serde_json::Value::Null;
+    for _ in 0..50 {
+        let (status, export) = app.get(&export_url, &token).await;
+        assert_eq!(status, StatusCode::OK, "{}", export);
+        body = export;
+        if !body["data"]["operationLogs"].as_array().unwrap().is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    let data = &body["data"];
+    assert_eq!(data["profile"]["username"], "frank");
+    assert_eq!(data["profile"]["email"], "frank@example.com");
+    assert!(data["profile"].get("passwordHash").is_none());
+    assert_eq!(data["roles"].as_array().unwrap().len(), 1);
+    assert_eq!(data["roleHistory"][0]["action"], "assigned");
+    assert_eq!(data["emailChanges"][0]["newEmail"], "frank@new.example");
+    assert!(data["emailChanges"][0]["confirmedAt"].is_null());
+    assert_eq!(data["operationLogs"][0]["username"], "frank");
+
+    let anonymize_url = format!("/api/system/users/{}/anonymize", id);
+    let (status, body) = app.request(Method::POST, &anonymize_url, Some(&token), None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let (_, body) = app.get(&export_url, &token).await;
+    let data = &body["data"];
+    let username = data["profile"]["username"].as_str().unwrap();
+    assert!(username.starts_with("anon_"), "{}", username);
+    assert!(data["profile"]["email"].is_null());
+    assert!(data["profile"]["anonymizedAt"].is_string());
+    assert_eq!(data["profile"]["status"], 2);
+    assert!(data["roles"].as_array().unwrap().is_empty());
+    assert_eq!(data["roleHistory"].as_array().unwrap().len(), 2);
+    assert!(data["emailChanges"].as_array().unwrap().is_empty());
+    for log in data["operationLogs"].as_array().unwrap() {
+        assert_eq!(log["username"], username);
+        assert_eq!(log["ipAddress"], "");
+    }
+
+    let (status, body) = app.get("/api/system/users?pageSize=100", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (status, _) = app
+        .request(
+            Method::POST,
+            "/api/auth/login",
+            None,
+            Some(json!({ "username": "frank", "password": "frank-password" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = app.request(Method::POST, &anonymize_url, Some(&token), None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn users_accept_the_current_policy_versions() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let publish = |kind: &str, title: &str| {
+        let body = json!({ "kind": kind, "title": title, "content": format!("{} text", title) });
+        app.request(Method::POST, "/api/system/policies", Some(&token), Some(body))
+    };
+    let (status, body) = publish("terms", "Terms v1").await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let terms_v1 = body["data"].as_i64().unwrap();
+    let (_, body) = publish("privacy", "Privacy v1").await;
+    let privacy_v1 = body["data"].as_i64().unwrap();
+    let (status, body) = publish("cookies", "Cookies").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["data"][0]["field"], "kind");
+
+    app.create_user("gina", "gina-password", &[]).await;
+    let gina = app.login("gina", "gina-password").await;
+    let (_, body) = app.get("/api/auth/me", &gina).await;
+    assert_eq!(body["data"]["pendingPolicies"].as_array().unwrap().len(), 2, "{}", body);
+
+    let accept = |ids: Vec<i64>| {
+        let body = json!({ "documentIds": ids });
+        app.request(Method::POST, "/api/auth/me/consent", Some(&gina), Some(body))
+    };
+    let (status, body) = accept(vec![terms_v1]).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"][0]["id"], privacy_v1);
+
+    let (_, body) = publish("terms", "Terms v2").await;
+    let terms_v2 = body["data"].as_i64().unwrap();
+    let (status, body) = accept(vec![terms_v1, privacy_v1]).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    assert_eq!(body["data"][0]["field"], "documentIds");
+    let (_, body) = app.get("/api/auth/me", &gina).await;
+    let pending = body["data"]["pendingPolicies"].as_array().unwrap();
+    assert_eq!(pending.len(), 2);
+    assert!(pending.iter().any(|p| p["id"] == terms_v2 && p["version"] == 2));
+
+    let (status, body) = accept(vec![terms_v2, privacy_v1]).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert!(body["data"].as_array().unwrap().is_empty());
+
+    let (_, body) = app.get("/api/system/policies?kind=terms", &token).await;
+    assert_eq!(body["total"], 2);
+    assert_eq!(body["data"][0]["current"], true);
+    assert_eq!(body["data"][1]["current"], false);
+    assert_eq!(body["data"][1]["consentCount"], 1);
+    let (_, body) = app.get(&format!("/api/system/policies/{}/consents", privacy_v1), &token).await;
+    assert_eq!(body["data"][0]["username"], "gina");
+    assert!(body["data"][0]["ipAddress"].as_str().is_some_and(|ip| ip.starts_with("127.0.0.")));
+    app.create_user("vic", "vic-password", &["viewer"]).await;
+    let viewer = app.login("vic", "vic-password").await;
+    let (_, body) =
+        app.get(&format!("/api/system/policies/{}/consents", privacy_v1), &viewer).await;
+    assert_eq!(body["data"][0]["ipAddress"], "127.0.*.*", "{}", body);
+    let (status, _) = app.get("/api/system/policies", &gina).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn self_registration_is_off_unless_configured() {
+    let app = TestApp::spawn().await;
+    let body =
+        json!({ "username": "newbie", "email": "newbie@example.com", "password": "pw-12345" });
+    let (status, body) = app.request(Method::POST, "/api/auth/register", None, Some(body)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN, "{}", body);
+    assert_eq!(body["code"], 10019);
+
+    app.create_user("hank", "hank-password", &[]).await;
+    let hank = app.login("hank", "hank-password").await;
+    let (status, _) = app.get("/api/system/registrations", &hank).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn request_logs_record_route_templates_and_resource_ids() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let id = app.create_user("carol", "carol-password", &[]).await;
+
+    for _ in 0..2 {
+        let (status, _) = app.get(&format!("/api/system/users/{}/role-history", id), &token).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    let (status, _) =
+        app.request(Method::DELETE, "/api/system/users/999999", Some(&token), None).await;
+    assert!(status.is_client_error(), "{}", status);
+
+    let route = "/api/system/users/{id}/role-history";
+    let (status, body) = app.get(&format!("/api/manage/logs?route={}", route), &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["total"], 2);
+    let log = &body["data"][0];
+    assert_eq!(log["resourceType"], "users");
+    assert_eq!(log["resourceId"], id.to_string());
+    assert_eq!(log["statusCode"], 200);
+
+    let (status, body) = app.get("/api/manage/logs/routes?hours=1", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let stats = body["data"].as_array().unwrap();
+    let find = |method: &str, route: &str| {
+        stats.iter().find(|row| row["method"] == method && row["route"] == route).cloned()
+    };
+    let history = find("GET", route).expect("role history stats");
+    assert_eq!((history["requests"].as_i64(), history["errors"].as_i64()), (Some(2), Some(0)));
+    let delete = find("DELETE", "/api/system/users/{id}").expect("user delete stats");
+    assert_eq!((delete["requests"].as_i64(), delete["errors"].as_i64()), (Some(1), Some(1)));
+}
+
+#[tokio::test]
+async fn timestamps_are_utc_and_exports_use_the_user_timezone() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let set_timezone = |timezone: Option<&str>| {
+        let body = json!({ "timezone": timezone });
+        app.request(Method::PUT, "/api/account/timezone", Some(&token), Some(body))
+    };
+    let (status, body) = set_timezone(Some("Mars/Olympus")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    assert_eq!(body["data"][0]["field"], "timezone");
+    let (status, body) = set_timezone(Some("Asia/Shanghai")).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (_, body) = app.get("/api/auth/me", &token).await;
+    assert_eq!(body["data"]["timezone"], "Asia/Shanghai");
+
+    // 23:50 UTC is already the next day in Shanghai.
+    let login_at = (Utc::now() - Duration::days(2)).date_naive().and_hms_opt(23, 50, 0).unwrap();
+    sqlx::query(
+        "INSERT INTO operation_logs
+             (user_id, username, action, description, status, duration_ms, ip_address,
+              user_agent, created_at)
+         VALUES (1, 'admin', 'AUTH_LOGIN', 'timezone check', 'SUCCESS', 3, '127.0.0.1',
+                 'test', ?)",
+    )
+    .bind(login_at)
+    .execute(&app.pool)
+    .await
+    .expect("insert log");
+
+    let (status, body) = app.get("/api/manage/logs?description=timezone%20check", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let created_at = login_at.and_utc().to_rfc3339_opts(SecondsFormat::Secs, true);
+    assert_eq!(body["data"][0]["createdAt"], created_at.as_str());
+
+    let response = app
+        .response(
+            Method::GET,
+            "/api/manage/logs/export?description=timezone%20check",
+            Some(&token),
+            None,
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let csv = response.into_body().collect().await.expect("body").to_bytes();
+    let local = login_at.and_utc().with_timezone(&chrono_tz::Asia::Shanghai);
+    let local = local.format("%Y-%m-%d %H:%M:%S+08:00").to_string();
+    assert!(String::from_utf8_lossy(&csv).contains(&local), "{}", String::from_utf8_lossy(&csv));
+
+    let (status, body) = app.get("/api/dashboard/trends?days=7", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let next_day = (login_at.date() + Duration::days(1)).format("%Y-%m-%d").to_string();
+    let days = body["data"]["dailyLogins"].as_array().unwrap();
+    assert!(days.iter().any(|day| day["date"] == next_day.as_str()), "{}", body);
+
+    let (status, body) = set_timezone(None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert!(body["data"]["timezone"].is_null());
+}
+
+#[tokio::test]
+async fn exports_need_their_own_capability_and_are_audited() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    app.create_user("exp_one", "exp-password", &["viewer"]).await;
+    app.create_user("exp_two", "exp-password", &[]).await;
+    let viewer = app.login("exp_one", "exp-password").await;
+    let download = |uri: &'static str, token: &str| {
+        let token = token.to_string();
+        let app = &app;
+        async move {
+            let response = app.response(Method::GET, uri, Some(&token), None).await;
+            let status = response.status();
+            let body = response.into_body().collect().await.expect("body").to_bytes();
+            (status, String::from_utf8_lossy(&body).into_owned())
+        }
+    };
+
+    let (status, csv) = download("/api/system/users/export?username=exp_", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", csv);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 3, "{}", csv);
+    assert!(lines[0].starts_with("id,username,email,phone"), "{}", csv);
+    assert!(csv.contains(",exp_one,exp_one@example.com,,,1,Viewer,"), "{}", csv);
+    let (status, csv) = download("/api/manage/dicts/export?dictType=user_status", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", csv);
+    assert!(csv.lines().count() > 1, "{}", csv);
+
+    for uri in ["/api/system/users/export", "/api/manage/dicts/export", "/api/manage/logs/export"] {
+        let (status, _) = download(uri, &viewer).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{}", uri);
+    }
+
+    let (status, body) = app.get("/api/manage/logs?action=DATA_EXPORT", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let exports = body["data"].as_array().unwrap();
+    assert_eq!(exports.len(), 2, "{}", body);
+    let users = exports.iter().find(|log| log["data"]["resource"] == "users").unwrap();
+    assert_eq!(users["username"], "it_admin");
+    assert_eq!(users["data"]["filters"], "username=exp_");
+    assert_eq!(users["data"]["rows"], 2);
+    assert_eq!(users["description"], "Exported 2 users");
+}
+
+#[tokio::test]
+async fn slow_log_reports_thresholds_and_recent_entries() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+
+    let (status, body) = app.get("/api/manage/logs/slow", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let slow = &body["data"];
+    assert_eq!(slow["requestThresholdMs"], 1000);
+    assert_eq!(slow["queryThresholdMs"], 200);
+    assert!(slow["slowRequestsTotal"].is_u64());
+    assert!(slow["requests"].is_array() && slow["queries"].is_array());
+
+    let (status, body) = app.get("/api/dashboard/metrics", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert!(body["data"]["slowQueries"].is_u64());
+    assert!(body["data"]["dbPool"]["primary"]["maxConnections"].as_u64() >= Some(1));
+    assert!(body["data"]["dbPool"]["replica"].is_null());
+
+    app.create_user("dave", "dave-password", &[]).await;
+    let user_token = app.login("dave", "dave-password").await;
+    let (status, _) = app.get("/api/manage/logs/slow", &user_token).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn feature_flags_are_managed_over_http_and_checked_in_process() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    assert!(!FeatureFlags::is_enabled("it.dark_launch"), "unknown flags are off");
+
+    let flag = json!({ "key": "it.dark_launch", "description": "Integration test flag" });
+    let (status, body) = app
+        .request(Method::POST, "/api/system/feature-flags", Some(&token), Some(flag.clone()))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let id = body["data"].as_i64().expect("flag id");
+    let (status, body) =
+        app.request(Method::POST, "/api/system/feature-flags", Some(&token), Some(flag)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    let bad = json!({ "key": "Dark Launch" });
+    let (_, body) =
+        app.request(Method::POST, "/api/system/feature-flags", Some(&token), Some(bad)).await;
+    assert_eq!(body["code"], 10015, "{}", body);
+    assert!(!FeatureFlags::is_enabled("it.dark_launch"));
+
+    let uri = format!("/api/system/feature-flags/{}", id);
+    let update = json!({ "description": "Now live", "enabled": true });
+    let (status, body) = app.request(Method::PUT, &uri, Some(&token), Some(update)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert!(FeatureFlags::is_enabled("it.dark_launch"));
+
+    let (status, body) = app.get("/api/system/feature-flags?key=dark", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["total"], 1, "{}", body);
+    assert_eq!(body["data"][0]["effective"], true);
+    assert!(body["data"][0]["envOverride"].is_null());
+
+    let (status, body) = app.request(Method::DELETE, &uri, Some(&token), None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert!(!FeatureFlags::is_enabled("it.dark_launch"));
+}
+
+#[tokio::test]
+async fn tags_label_users_and_roles_and_filter_the_user_list() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let ann = app.create_user("tag_ann", "tag-password", &[]).await;
+    let bob = app.create_user("tag_bob", "tag-password", &[]).await;
+    let create = |name: &str| json!({ "name": name, "color": "#1A2B3C" });
+    let mut ids = Vec::new();
+    for name in ["contractor", "pilot-group"] {
+        let (status, body) =
+            app.request(Method::POST, "/api/system/tags", Some(&token), Some(create(name))).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        ids.push(body["data"].as_i64().expect("tag id"));
+    }
+    let (status, body) = app
+        .request(Method::POST, "/api/system/tags", Some(&token), Some(create("contractor")))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    let bad = json!({ "name": "Pilot Group", "color": "blue" });
+    let (_, body) = app.request(Method::POST, "/api/system/tags", Some(&token), Some(bad)).await;
+    assert_eq!(body["code"], 10015, "{}", body);
+
+    let assign = |tag_ids: Vec<i64>| json!({ "tagIds": tag_ids });
+    let uri = format!("/api/system/users/{}/tags", ann);
+    let (status, body) =
+        app.request(Method::PUT, &uri, Some(&token), Some(assign(ids.clone()))).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let uri = format!("/api/system/users/{}/tags", bob);
+    app.request(Method::PUT, &uri, Some(&token), Some(assign(vec![ids[0]]))).await;
+    let (status, body) =
+        app.request(Method::PUT, &uri, Some(&token), Some(assign(vec![999_999]))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    let (status, body) = app.get(&uri, &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"], json!([{ "id": ids[0], "name": "contractor", "color": "#1a2b3c" }]));
+
+    let (_, body) = app.get("/api/system/users?username=tag_&tags=contractor", &token).await;
+    assert_eq!(body["total"], 2, "{}", body);
+    let (_, body) =
+        app.get("/api/system/users?username=tag_&tags=contractor,pilot-group", &token).await;
+    assert_eq!(body["total"], 1, "{}", body);
+    assert_eq!(body["data"][0]["username"], "tag_ann");
+
+    let viewer: i64 = sqlx::query_scalar("SELECT id FROM roles WHERE code = 'viewer'")
+        .fetch_one(&app.pool)
+        .await
+        .unwrap();
+    let uri = format!("/api/system/roles/{}/tags", viewer);
+    let (status, body) =
+        app.request(Method::PUT, &uri, Some(&token), Some(assign(vec![ids[1]]))).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (_, body) = app.get(&uri, &token).await;
+    assert_eq!(body["data"][0]["name"], "pilot-group", "{}", body);
+
+    let (status, body) = app.get("/api/system/tags?name=pilot", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["total"], 1, "{}", body);
+    assert_eq!(body["data"][0]["userCount"], 1);
+    assert_eq!(body["data"][0]["roleCount"], 1);
+
+    let uri = format!("/api/system/tags/{}", ids[0]);
+    let (status, body) = app.request(Method::DELETE, &uri, Some(&token), None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (_, body) = app.get(&format!("/api/system/users/{}/tags", bob), &token).await;
+    assert_eq!(body["data"], json!([]));
+}
+
+#[tokio::test]
+async fn profile_fields_validate_user_profiles_and_filter_the_list() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let fields = [
+        json!({ "key": "employee_id", "label": "Employee ID", "fieldType": "text", "required": true }),
+        json!({ "key": "region", "label": "Region", "fieldType": "select",
+                "options": ["north", "south"], "sortOrder": 1 }),
+        json!({ "key": "remote", "label": "Remote", "fieldType": "boolean", "sortOrder": 2 }),
+    ];
+    let mut ids = Vec::new();
+    for field in fields {
+        let (status, body) = app
+            .request(Method::POST, "/api/system/profile-fields", Some(&token), Some(field))
+            .await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        ids.push(body["data"].as_i64().expect("field id"));
+    }
+    let bad = json!({ "key": "Cost Center", "label": "", "fieldType": "list" });
+    let (_, body) =
+        app.request(Method::POST, "/api/system/profile-fields", Some(&token), Some(bad)).await;
+    assert_eq!(body["code"], 10015, "{}", body);
+    let (status, body) = app.get("/api/system/profile-fields", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let keys: Vec<&str> =
+        body["data"].as_array().unwrap().iter().map(|f| f["key"].as_str().unwrap()).collect();
+    assert_eq!(keys, ["employee_id", "region", "remote"]);
+
+    let user = |username: &str, profile: serde_json::Value| {
+        json!({ "username": username, "email": format!("{username}@example.com"),
+                "password": "profile-password", "roleIds": [], "profile": profile })
+    };
+    let invalid = user("pf_bad", json!({ "region": "east", "remote": "yes", "team": "core" }));
+    let (_, body) =
+        app.request(Method::POST, "/api/system/users", Some(&token), Some(invalid)).await;
+    assert_eq!(body["code"], 10015, "{}", body);
+    let invalid_fields: Vec<&str> =
+        body["data"].as_array().unwrap().iter().map(|e| e["field"].as_str().unwrap()).collect();
+    assert_eq!(
+        invalid_fields,
+        ["profile.region", "profile.remote", "profile.team", "profile.employee_id"]
+    );
+
+    let mut user_ids = Vec::new();
+    for (name, region, remote) in [("pf_ann", "north", true), ("pf_bob", "north", false)] {
+        let profile = json!({ "employee_id": name, "region": region, "remote": remote });
+        let (status, body) = app
+            .request(Method::POST, "/api/system/users", Some(&token), Some(user(name, profile)))
+            .await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        user_ids.push(body["data"].as_i64().expect("user id"));
+    }
+    let (_, body) =
+        app.get("/api/system/users?username=pf_&profile=region:north,remote:true", &token).await;
+    assert_eq!(body["total"], 1, "{}", body);
+    assert_eq!(body["data"][0]["username"], "pf_ann");
+    assert_eq!(body["data"][0]["profile"]["region"], "north");
+    let (_, body) = app.get("/api/system/users?profile=region", &token).await;
+    assert_eq!(body["code"], 10015, "{}", body);
+
+    let update = json!({ "email": "pf_bob@example.com", "realName": "Bob", "roleIds": [],
+                         "profile": { "employee_id": "E-2", "region": "south" } });
+    let uri = format!("/api/system/users/{}", user_ids[1]);
+    let (status, body) = app.request(Method::PUT, &uri, Some(&token), Some(update)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (_, body) = app.get("/api/system/users?username=pf_&profile=region:south", &token).await;
+    assert_eq!(body["total"], 1, "{}", body);
+    assert_eq!(body["data"][0]["profile"], json!({ "employee_id": "E-2", "region": "south" }));
+
+    let uri = format!("/api/system/profile-fields/{}", ids[1]);
+    let (status, body) = app.request(Method::DELETE, &uri, Some(&token), None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (_, body) = app.get("/api/system/users?username=pf_ann", &token).await;
+    assert_eq!(body["data"][0]["profile"], json!({ "employee_id": "pf_ann", "remote": true }));
+}
+
+#[tokio::test]
+async fn saved_filters_are_private_presets_of_the_list_queries() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    app.create_user("sf_ann", "filter-password", &[]).await;
+    app.create_user("sf_bob", "filter-password", &["viewer"]).await;
+    let viewer = app.login("sf_bob", "filter-password").await;
+
+    let save = |name: &str, query: serde_json::Value| json!({ "resource": "users", "name": name, "query": query });
+    let first = save("Filter users", json!({ "username": "sf_", "status": "all", "current": 3 }));
+    let (status, body) =
+        app.request(Method::POST, "/api/system/filters", Some(&token), Some(first)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["query"], json!({ "username": "sf_", "status": "all" }));
+    let id = body["data"]["id"].as_i64().unwrap();
+    let again = save(
+        " Filter users ",
+        json!({ "username": "sf_", "sortBy": "username", "sortOrder": "asc" }),
+    );
+    let (_, body) =
+        app.request(Method::POST, "/api/system/filters", Some(&token), Some(again)).await;
+    assert_eq!(body["data"]["id"], id, "{}", body);
+    assert_eq!(body["data"]["query"]["sortBy"], "username");
+
+    let bad = save("Bad sort", json!({ "sortBy": "password_hash" }));
+    let (status, body) =
+        app.request(Method::POST, "/api/system/filters", Some(&token), Some(bad)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    let bad = json!({ "resource": "roles", "name": "Roles" });
+    let (_, body) = app.request(Method::POST, "/api/system/filters", Some(&token), Some(bad)).await;
+    assert_eq!(body["code"], 10015, "{}", body);
+
+    let (status, body) =
+        app.get(&format!("/api/system/filters/{id}/apply?pageSize=1"), &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["total"], 2, "{}", body);
+    assert_eq!(body["data"][0]["username"], "sf_ann");
+    assert_eq!(body["data"].as_array().unwrap().len(), 1);
+    let (status, _) = app.get(&format!("/api/system/filters/{id}/apply"), &viewer).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let mine = save("Mine", json!({ "username": "sf_ann" }));
+    let (_, body) =
+        app.request(Method::POST, "/api/system/filters", Some(&viewer), Some(mine)).await;
+    let viewer_filter = body["data"]["id"].as_i64().expect("filter id");
+    let (_, body) = app.get(&format!("/api/system/filters/{viewer_filter}/apply"), &viewer).await;
+    assert_eq!(body["data"][0]["email"], "s***@example.com", "{}", body);
+    let logs = json!({ "resource": "logs", "name": "Logins", "query": { "action": "AUTH_LOGIN" } });
+    let (status, body) =
+        app.request(Method::POST, "/api/system/filters", Some(&token), Some(logs)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let logs_id = body["data"]["id"].as_i64().unwrap();
+    let (_, body) = app.get(&format!("/api/system/filters/{logs_id}/apply"), &token).await;
+    assert!(body["total"].as_i64().unwrap() >= 1, "{}", body);
+    assert_eq!(body["data"][0]["action"], "AUTH_LOGIN");
+
+    let (_, body) = app.get("/api/system/filters?resource=users", &token).await;
+    assert_eq!(body["data"].as_array().unwrap().len(), 1, "{}", body);
+    let (_, body) = app.get("/api/system/filters", &token).await;
+    assert_eq!(body["data"].as_array().unwrap().len(), 2, "{}", body);
+
+    let uri = format!("/api/system/filters/{id}");
+    let (status, _) = app.request(Method::DELETE, &uri, Some(&viewer), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = app.request(Method::DELETE, &uri, Some(&token), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app.request(Method::DELETE, &uri, Some(&token), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn export_templates_pick_the_columns_of_csv_exports() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    app.create_user("tpl_one", "tpl-password", &["viewer"]).await;
+    let viewer = app.login("tpl_one", "tpl-password").await;
+    let download = |uri: String| {
+        let token = token.clone();
+        let app = &app;
+        async move {
+            let response = app.response(Method::GET, &uri, Some(&token), None).await;
+            let status = response.status();
+            let body = response.into_body().collect().await.expect("body").to_bytes();
+            (status, String::from_utf8_lossy(&body).into_owned())
+        }
+    };
+
+    let (status, body) = app.get("/api/system/export-templates/columns", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let users = body["data"].as_array().unwrap().iter().find(|r| r["resource"] == "users");
+    assert_eq!(users.unwrap()["columns"][1], "username", "{}", body);
+
+    let contacts = json!({
+        "resource": "users",
+        "name": " Contacts ",
+        "columns": ["email", "username", "email"],
+    });
+    let (status, body) = app
+        .request(Method::POST, "/api/system/export-templates", Some(&token), Some(contacts))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let contacts_id = body["data"].as_i64().unwrap();
+    let bad = json!({ "resource": "users", "name": "Secrets", "columns": ["password_hash"] });
+    let (_, body) =
+        app.request(Method::POST, "/api/system/export-templates", Some(&token), Some(bad)).await;
+    assert_eq!(body["code"], 10015, "{}", body);
+    let duplicate = json!({ "resource": "users", "name": "Contacts", "columns": ["id"] });
+    let (status, _) = app
+        .request(Method::POST, "/api/system/export-templates", Some(&token), Some(duplicate))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, csv) =
+        download(format!("/api/system/users/export?username=tpl_&template={contacts_id}")).await;
+    assert_eq!(status, StatusCode::OK, "{}", csv);
+    assert_eq!(csv, "username,email\ntpl_one,tpl_one@example.com\n");
+    let (_, csv) = download("/api/system/users/export?username=tpl_".to_string()).await;
+    assert!(csv.starts_with("id,username,email,phone"), "{}", csv);
+
+    let ids = json!({ "name": "Ids", "columns": ["id", "status"], "isDefault": true });
+    let uri = format!("/api/system/export-templates/{contacts_id}");
+    let (status, body) = app.request(Method::PUT, &uri, Some(&token), Some(ids)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (_, csv) = download("/api/system/users/export?username=tpl_".to_string()).await;
+    assert!(csv.starts_with("id,status\n"), "{}", csv);
+    let (_, csv) = download("/api/manage/dicts/export?dictType=user_status".to_string()).await;
+    assert!(csv.starts_with("id,dict_type,label"), "{}", csv);
+
+    let (_, body) = app.get("/api/system/export-templates?resource=users", &token).await;
+    assert_eq!(body["data"][0]["name"], "Ids", "{}", body);
+    assert_eq!(body["data"][0]["columns"], json!(["id", "status"]));
+    assert_eq!(body["data"][0]["isDefault"], true);
+    let (status, body) = app.get("/api/system/export-templates", &viewer).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (status, _) = app
+        .request(Method::PUT, &uri, Some(&viewer), Some(json!({ "name": "X", "columns": ["id"] })))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = app.request(Method::DELETE, &uri, Some(&token), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, csv) = download("/api/system/users/export?username=tpl_".to_string()).await;
+    assert!(csv.starts_with("id,username,email,phone"), "{}", csv);
+}
+
+#[tokio::test]
+async fn dashboard_streams_snapshots_and_reports_host_and_endpoint_metrics() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+
+    let (status, body) = app.get("/api/dashboard/stream?interval=1", &token).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    let (status, _) = app.get("/api/dashboard/stream?days=14", &token).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let response =
+        app.response(Method::GET, "/api/dashboard/stream?interval=5", Some(&token), None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "text/event-stream");
+    let frame =
+        tokio::time::timeout(std::time::Duration::from_secs(5), response.into_body().frame())
+            .await
+            .expect("a snapshot right away")
+            .expect("open stream")
+            .expect("frame");
+    let text = String::from_utf8(frame.into_data().expect("data frame").to_vec()).unwrap();
+    let data = text.strip_prefix("event: snapshot\ndata: ").expect("snapshot event");
+    let snapshot: serde_json::Value = serde_json::from_str(data.trim()).unwrap();
+    assert!(snapshot["stats"]["totalUsers"].as_i64().unwrap() >= 1, "{}", snapshot);
+    assert!(snapshot["health"]["cpuTotal"].is_number(), "{}", snapshot);
+    assert!(snapshot["metrics"]["dbPool"]["primary"].is_object(), "{}", snapshot);
+
+    let (status, body) = app.get("/api/dashboard/system-metrics?minutes=15", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["intervalSecs"], 30, "{}", body);
+    assert!(body["data"]["history"].is_array(), "{}", body);
+    let (status, _) = app.get("/api/dashboard/system-metrics?minutes=90", &token).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = app.get("/api/system/export-templates/columns", &token).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, body) = app.get("/api/dashboard/endpoints?limit=50", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["windowMinutes"], 60);
+    let failing = body["data"]["mostErrors"].as_array().unwrap();
+    let failing = failing.iter().find(|r| r["route"] == "/api/dashboard/system-metrics");
+    let failing = failing.expect("the rejected system metrics query");
+    assert_eq!(failing["method"], "GET");
+    assert!(failing["errors"].as_u64().unwrap() >= 1, "{}", failing);
+    let slowest = body["data"]["slowest"].as_array().unwrap();
+    let columns = slowest.iter().find(|r| r["route"] == "/api/system/export-templates/columns");
+    assert_eq!(columns.expect("columns route")["histogram"].as_array().unwrap().len(), 11);
+}
+
+#[tokio::test]
+async fn temporary_roles_stop_granting_and_are_removed_with_a_notice() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let id = app.create_user("temp_carl", "carl-password", &["viewer"]).await;
+    let viewer: i64 = sqlx::query_scalar("SELECT id FROM roles WHERE code = 'viewer'")
+        .fetch_one(&app.pool)
+        .await
+        .unwrap();
+    let expiry = format!("/api/system/users/{}/roles/{}/expiry", id, viewer);
+    let set_expiry = |expires_at: serde_json::Value| {
+        app.request(Method::PUT, &expiry, Some(&token), Some(json!({ "expiresAt": expires_at })))
+    };
+
+    let past = (Utc::now() - Duration::hours(1)).to_rfc3339();
+    let (status, body) = set_expiry(json!(past)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    let in_30_days = (Utc::now() + Duration::days(30)).to_rfc3339();
+    let (status, body) = set_expiry(json!(in_30_days)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let roles = format!("/api/system/users/{}/roles", id);
+    let (_, body) = app.get(&roles, &token).await;
+    assert_eq!(body["data"][0]["roleCode"], "viewer", "{}", body);
+    assert!(body["data"][0]["expiresAt"].is_string(), "{}", body);
+
+    // Saving the user with the same roles keeps the expiry.
+    let (status, body) = app
+        .request(
+            Method::PUT,
+            &format!("/api/system/users/{}", id),
+            Some(&token),
+            Some(json!({ "email": "temp_carl@example.com", "realName": "Carl", "roleIds": [viewer] })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (_, body) = app.get(&roles, &token).await;
+    assert!(body["data"][0]["expiresAt"].is_string(), "{}", body);
+
+    let carl = app.login("temp_carl", "carl-password").await;
+    let (status, _) = app.get("/api/system/users", &carl).await;
+    assert_eq!(status, StatusCode::OK);
+
+    sqlx::query("UPDATE user_roles SET expires_at = '2000-01-01 00:00:00' WHERE user_id = ?")
+        .bind(id)
+        .execute(&app.pool)
+        .await
+        .unwrap();
+    let (_, body) = app.get(&roles, &token).await;
+    assert_eq!(body["data"], json!([]), "{}", body);
+    let (_, body) = app.get("/api/system/users?username=temp_carl", &token).await;
+    assert_eq!(body["data"][0]["roles"], json!([]), "{}", body);
+    let (status, _) = set_expiry(json!(in_30_days)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    assert_eq!(UserService::expire_roles(&app.pool).await.unwrap(), 1);
+    assert_eq!(UserService::expire_roles(&app.pool).await.unwrap(), 0);
+    let (status, _) = app.get("/api/system/users", &carl).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (_, body) = app.get("/api/account/notifications", &carl).await;
+    assert_eq!(body["data"][0]["kind"], "role_expired", "{}", body);
+    assert_eq!(body["data"][0]["data"]["roleId"], viewer);
+    let (_, body) = app.get(&format!("/api/system/users/{}/role-history", id), &token).await;
+    assert_eq!(body["data"][0]["action"], "removed", "{}", body);
+    assert!(body["data"][0]["operatorId"].is_null(), "{}", body);
+}
+
+#[tokio::test]
+async fn restricted_roles_only_grant_from_allowed_networks() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    app.create_user("office_olga", "olga-password", &["viewer"]).await;
+    let role_id = |code: &'static str| {
+        sqlx::query_scalar::<_, i64>("SELECT id FROM roles WHERE code = ?")
+            .bind(code)
+            .fetch_one(&app.pool)
+    };
+    let access = format!("/api/system/roles/{}/access", role_id("viewer").await.unwrap());
+    let set_access =
+        |body: serde_json::Value| app.request(Method::PUT, &access, Some(&token), Some(body));
+
+    let (status, body) = set_access(json!({
+        "allowedCidrs": ["10.0.0.0/33"],
+        "allowedDays": [0],
+        "startTime": "09:00"
+    }))
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    let owner_access = format!("/api/system/roles/{}/access", role_id("owner").await.unwrap());
+    let (status, _) = app
+        .request(
+            Method::PUT,
+            &owner_access,
+            Some(&token),
+            Some(json!({ "allowedCidrs": ["10.0.0.0/8"] })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Test clients connect from 127.0.x.y.
+    let olga = app.login("office_olga", "olga-password").await;
+    let (status, body) = set_access(json!({ "allowedCidrs": ["10.0.0.0/8", "10.1.2.3"] })).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["allowedCidrs"], json!(["10.0.0.0/8", "10.1.2.3/32"]), "{}", body);
+    let (status, _) = app.get("/api/system/users", &olga).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = app.get("/api/auth/me", &olga).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = set_access(json!({ "allowedCidrs": ["127.0.0.0/8"] })).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (status, _) = app.get("/api/system/users", &olga).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = set_access(json!({})).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (_, body) = app.get(&access, &token).await;
+    assert_eq!(body["data"]["allowedCidrs"], json!([]), "{}", body);
+    assert!(body["data"]["updatedAt"].is_null(), "{}", body);
+}
+
+#[tokio::test]
+async fn workflows_move_through_role_inboxes_step_by_step() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let mut role_ids = Vec::new();
+    for code in ["manager", "it_ops"] {
+        let role = json!({ "name": code, "code": code, "status": 1, "menuIds": [] });
+        let (status, body) =
+            app.request(Method::POST, "/api/system/roles", Some(&token), Some(role)).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        let id: i64 = sqlx::query_scalar("SELECT id FROM roles WHERE code = ?")
+            .bind(code)
+            .fetch_one(&app.pool)
+            .await
+            .unwrap();
+        role_ids.push(id);
+    }
+    app.create_user("mia", "mia-password", &["manager"]).await;
+    app.create_user("ian", "ian-password", &["it_ops"]).await;
+    let mia = app.login("mia", "mia-password").await;
+    let ian = app.login("ian", "ian-password").await;
+
+    let mut definition = json!({
+        "code": "user.onboarding",
+        "name": "User onboarding",
+        "steps": [
+            { "name": "Manager review", "approverRoleId": role_ids[0] },
+            { "name": "IT setup", "approverRoleId": 999_999 },
+        ],
+    });
+    let (status, body) = app
+        .request(Method::POST, "/api/workflow/definitions", Some(&token), Some(definition.clone()))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["data"][0]["field"], "steps[1].approverRoleId", "{}", body);
+    definition["steps"][1]["approverRoleId"] = json!(role_ids[1]);
+    let (status, body) = app
+        .request(Method::POST, "/api/workflow/definitions", Some(&token), Some(definition))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let start = json!({
+        "definitionCode": "user.onboarding",
+        "title": "Onboard new.hire",
+        "data": { "username": "new.hire" },
+    });
+    let (status, _) =
+        app.request(Method::POST, "/api/workflow/instances", Some(&mia), Some(start.clone())).await;
+    assert_eq!(status, StatusCode::FORBIDDEN, "starting needs workflow:instance:start");
+    let (status, body) = app
+        .request(Method::POST, "/api/workflow/instances", Some(&token), Some(start.clone()))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let instance_id = body["data"].as_i64().unwrap();
+
+    let (_, body) = app.get("/api/workflow/tasks/mine", &ian).await;
+    assert_eq!(body["total"], 0, "{}", body);
+    let (_, body) = app.get("/api/workflow/tasks/mine", &mia).await;
+    assert_eq!(body["total"], 1, "{}", body);
+    assert_eq!(body["data"][0]["title"], "Onboard new.hire");
+    let first_task = body["data"][0]["id"].as_i64().unwrap();
+
+    let approve = format!("/api/workflow/tasks/{}/approve", first_task);
+    let (status, _) = app.request(Method::POST, &approve, Some(&ian), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND, "only the step's role may decide it");
+    let (status, body) = app.request(Method::POST, &approve, Some(&mia), None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let (_, body) = app.get("/api/workflow/tasks/mine", &ian).await;
+    assert_eq!(body["data"][0]["stepName"], "IT setup", "{}", body);
+    let reject = format!("/api/workflow/tasks/{}/reject", body["data"][0]["id"]);
+    let comment = json!({ "comment": "No laptop budget" });
+    let (status, body) = app.request(Method::POST, &reject, Some(&ian), Some(comment)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let (status, body) = app.get(&format!("/api/workflow/instances/{}", instance_id), &mia).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["status"], "rejected");
+    assert_eq!(body["data"]["data"]["username"], "new.hire");
+    let tasks: Vec<_> = body["data"]["tasks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|task| (task["status"].as_str().unwrap(), task["decidedByName"].as_str().unwrap()))
+        .collect();
+    assert_eq!(tasks, vec![("approved", "mia"), ("rejected", "ian")]);
+    assert_eq!(body["data"]["tasks"][1]["comment"], "No laptop budget");
+
+    // Cancelling withdraws the open task from the inbox.
+    let (_, body) =
+        app.request(Method::POST, "/api/workflow/instances", Some(&token), Some(start)).await;
+    let cancel = format!("/api/workflow/instances/{}/cancel", body["data"]);
+    let (status, _) = app.request(Method::POST, &cancel, Some(&mia), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND, "only the starter may cancel");
+    let (status, body) = app.request(Method::POST, &cancel, Some(&token), None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (_, body) = app.get("/api/workflow/tasks/mine", &mia).await;
+    assert_eq!(body["total"], 0, "{}", body);
+    let (_, body) = app.get("/api/workflow/instances/mine?status=cancelled", &token).await;
+    assert_eq!(body["total"], 1, "{}", body);
+}
+
+#[tokio::test]
+async fn sensitive_actions_need_a_recent_sign_in() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let id = app.create_user("leaving", "leaving-password", &[]).await;
+
+    // The same session, signed in an hour ago: a token without `kid` is checked against
+    // the HS256 secret.
+    let mut claims = jwt_codec(app.config).decode(&token).unwrap();
+    claims.auth_time = Some(claims.iat - 3600);
+    let stale = jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &claims,
+        &jsonwebtoken::EncodingKey::from_secret(app.config.jwt.jwt_secret.as_bytes()),
+    )
+    .unwrap();
+    let delete_uri = format!("/api/system/users/{}", id);
+
+    let (status, body) = app.get("/api/system/users?username=leaving", &stale).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (status, body) = app.request(Method::DELETE, &delete_uri, Some(&stale), None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN, "{}", body);
+    assert_eq!(body["code"], 10106);
+    assert_eq!(body["data"]["maxAgeMinutes"], 10);
+
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/auth/reauth",
+            Some(&stale),
+            Some(json!({ "password": "wrong-password" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/auth/reauth",
+            Some(&stale),
+            Some(json!({ "password": "admin-password" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let fresh = body["data"]["token"].as_str().unwrap().to_string();
+
+    let (status, body) = app.request(Method::DELETE, &delete_uri, Some(&fresh), None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+}
+
+#[tokio::test]
+async fn purges_need_a_single_use_confirmation_for_their_exact_target() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    app.create_user("second", "second-password", &["owner"]).await;
+    let second = app.login("second", "second-password").await;
+    let doomed = app.create_user("doomed", "doomed-password", &[]).await;
+    let spared = app.create_user("spared", "spared-password", &[]).await;
+    for id in [doomed, spared] {
+        let uri = format!("/api/system/users/{}", id);
+        let (status, _) = app.request(Method::DELETE, &uri, Some(&token), None).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    let purge = format!("/api/system/users/{}/purge", doomed);
+
+    let (status, body) = app.request(Method::DELETE, &purge, Some(&token), None).await;
+    assert_eq!(status, StatusCode::PRECONDITION_REQUIRED, "{}", body);
+    assert_eq!(body["code"], 10022);
+    assert_eq!(body["data"]["action"], "user.purge");
+
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/system/confirmations",
+            Some(&token),
+            Some(json!({ "action": "user.purge", "params": { "userId": doomed } })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert!(body["data"]["summary"].as_str().unwrap().contains(&doomed.to_string()), "{}", body);
+    let confirmation = body["data"]["token"].as_str().unwrap().to_string();
+
+    // Bound to the target and to the issuer.
+    let other_target = format!("/api/system/users/{}/purge", spared);
+    let (status, _) = app.confirmed(Method::DELETE, &other_target, &token, &confirmation).await;
+    assert_eq!(status, StatusCode::PRECONDITION_REQUIRED);
+    let (status, _) = app.confirmed(Method::DELETE, &purge, &second, &confirmation).await;
+    assert_eq!(status, StatusCode::PRECONDITION_REQUIRED);
+
+    let (status, body) = app.confirmed(Method::DELETE, &purge, &token, &confirmation).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (status, _) = app.confirmed(Method::DELETE, &other_target, &token, &confirmation).await;
+    assert_eq!(status, StatusCode::PRECONDITION_REQUIRED, "a token works once");
+
+    let logs = "/api/manage/logs?olderThanDays=7";
+    let confirmation = app
+        .confirmation(&token, json!({ "action": "log.purge", "params": { "olderThanDays": 30 } }))
+        .await;
+    let (status, _) = app.confirmed(Method::DELETE, logs, &token, &confirmation).await;
+    assert_eq!(status, StatusCode::PRECONDITION_REQUIRED);
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/system/confirmations",
+            Some(&token),
+            Some(json!({ "action": "log.purge", "params": { "olderThanDays": 0 } })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    let confirmation = app
+        .confirmation(&token, json!({ "action": "log.purge", "params": { "olderThanDays": 7 } }))
+        .await;
+    let (status, body) = app.confirmed(Method::DELETE, logs, &token, &confirmation).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+}
+
+#[tokio::test]
+async fn restoring_records_whose_names_were_reused_offers_free_renames() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let casey = app.create_user("casey", "casey-password", &[]).await;
+    // A deleted record already holds the first suggestion, so it is skipped.
+    let taken = app.create_user("casey_restored", "other-password", &[]).await;
+    for id in [casey, taken] {
+        let uri = format!("/api/system/users/{}", id);
+        let (status, body) = app.request(Method::DELETE, &uri, Some(&token), None).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+    }
+    app.create_user("casey", "casey-password", &[]).await;
+
+    let restore = format!("/api/system/users/{}/restore", casey);
+    let (status, body) = app.request(Method::PUT, &restore, Some(&token), None).await;
+    assert_eq!(status, StatusCode::CONFLICT, "{}", body);
+    assert_eq!(body["code"], 10205);
+    assert_eq!(body["data"][0]["field"], "username");
+    assert_eq!(body["data"][0]["suggestions"], json!(["casey_2", "casey_3", "casey_4"]));
+    assert_eq!(body["data"][1]["field"], "email");
+    assert_eq!(body["data"][1]["suggestions"][0], "casey+restored@example.com");
+
+    let (status, body) = app.get("/api/system/recycle-bin?kind=users", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["total"], 2, "{}", body);
+    let item = body["data"].as_array().unwrap().iter().find(|item| item["id"] == casey.get());
+    assert_eq!(item.unwrap()["conflicts"].as_array().unwrap().len(), 2);
+    let (status, _) = app.get("/api/system/recycle-bin", &token).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = app
+        .request(
+            Method::PUT,
+            &restore,
+            Some(&token),
+            Some(json!({ "username": "casey_2", "email": "casey+restored@example.com" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    app.login("casey_2", "casey-password").await;
+
+    let role = json!({ "name": "Auditor", "code": "auditor", "status": 1, "menuIds": [] });
+    let (status, body) =
+        app.request(Method::POST, "/api/system/roles", Some(&token), Some(role.clone())).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let role_id: i64 = sqlx::query_scalar("SELECT id FROM roles WHERE code = 'auditor'")
+        .fetch_one(&app.pool)
+        .await
+        .unwrap();
+    let uri = format!("/api/system/roles/{}", role_id);
+    let (status, body) = app.request(Method::DELETE, &uri, Some(&token), None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    app.request(Method::POST, "/api/system/roles", Some(&token), Some(role)).await;
+
+    let restore = format!("/api/system/roles/{}/restore", role_id);
+    let (status, body) = app.request(Method::PUT, &restore, Some(&token), None).await;
+    assert_eq!(status, StatusCode::CONFLICT, "{}", body);
+    assert_eq!(body["data"][0]["suggestions"][0], "Auditor (restored)");
+    assert_eq!(body["data"][1]["suggestions"][0], "auditor_restored");
+    let (status, body) = app
+        .request(
+            Method::PUT,
+            &restore,
+            Some(&token),
+            Some(json!({ "name": "Auditor (restored)", "code": "auditor_restored" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (_, body) = app.get("/api/system/recycle-bin?kind=roles", &token).await;
+    assert_eq!(body["total"], 0, "{}", body);
+}
+
+#[tokio::test]
+async fn purges_report_their_cascade_before_deleting_it() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let menu = |name: &str, parent_id: i64| {
+        json!({
+            "parentId": parent_id,
+            "name": name,
+            "code": name,
+            "menuType": 2,
+            "sortOrder": 1,
+            "status": 1,
+        })
+    };
+    let (status, body) = app
+        .request(Method::POST, "/api/system/menus", Some(&token), Some(menu("reports", 0)))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let parent = body["data"].as_i64().unwrap();
+    let (status, body) = app
+        .request(Method::POST, "/api/system/menus", Some(&token), Some(menu("daily", parent)))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let child = body["data"].as_i64().unwrap();
+    let role = json!({ "name": "Reporter", "code": "reporter", "status": 1, "menuIds": [parent] });
+    let (status, body) =
+        app.request(Method::POST, "/api/system/roles", Some(&token), Some(role)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let erin = app.create_user("erin", "erin-password", &["reporter"]).await;
+    let erin_token = app.login("erin", "erin-password").await;
+    app.get("/api/auth/me", &erin_token).await;
+
+    // Only deleted users can be purged.
+    let report_uri = format!("/api/system/users/{}/purge", erin);
+    let (status, _) = app.get(&report_uri, &token).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let uri = format!("/api/system/users/{}", erin);
+    let (status, body) = app.request(Method::DELETE, &uri, Some(&token), None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (status, body) = app.get(&report_uri, &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["name"], "erin");
+    assert_eq!(body["data"]["canPurge"], true);
+    let reference = |body: &serde_json::Value, table: &str| {
+        body["data"]["references"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|item| item["table"] == table)
+            .cloned()
+            .unwrap()
+    };
+    assert_eq!(reference(&body, "user_roles")["rows"], 1);
+    assert_eq!(reference(&body, "user_roles")["action"], "delete");
+    assert_eq!(reference(&body, "operation_logs")["action"], "keep");
+    let logs_before = reference(&body, "operation_logs")["rows"].as_i64().unwrap();
+    assert!(logs_before > 0, "{}", body);
+
+    let confirmation = app
+        .confirmation(&token, json!({ "action": "user.purge", "params": { "userId": erin } }))
+        .await;
+    let (status, body) = app.confirmed(Method::DELETE, &report_uri, &token, &confirmation).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(reference(&body, "user_roles")["rows"], 1);
+    let count = |sql: &'static str| {
+        let pool = app.pool.clone();
+        async move { sqlx::query_scalar::<_, i64>(sql).bind(erin).fetch_one(&pool).await.unwrap() }
+    };
+    assert_eq!(count("SELECT COUNT(*) FROM users WHERE id = ?").await, 0);
+    assert_eq!(count("SELECT COUNT(*) FROM user_roles WHERE user_id = ?").await, 0);
+    assert_eq!(count("SELECT COUNT(*) FROM operation_logs WHERE user_id = ?").await, logs_before);
+
+    // A role goes with its menu grants.
+    let role_id: i64 = sqlx::query_scalar("SELECT id FROM roles WHERE code = 'reporter'")
+        .fetch_one(&app.pool)
+        .await
+        .unwrap();
+    let uri = format!("/api/system/roles/{}", role_id);
+    let (status, body) = app.request(Method::DELETE, &uri, Some(&token), None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let purge = format!("/api/system/roles/{}/purge", role_id);
+    let (_, body) = app.get(&purge, &token).await;
+    assert_eq!(reference(&body, "role_menus")["rows"], 1);
+    let confirmation = app
+        .confirmation(&token, json!({ "action": "role.purge", "params": { "roleId": role_id } }))
+        .await;
+    let (status, body) = app.confirmed(Method::DELETE, &purge, &token, &confirmation).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let grants: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM role_menus WHERE role_id = ?")
+        .bind(role_id)
+        .fetch_one(&app.pool)
+        .await
+        .unwrap();
+    assert_eq!(grants, 0);
+
+    // A menu with children is blocked until they are purged first.
+    for id in [parent, child] {
+        let uri = format!("/api/system/menus/{}", id);
+        let (status, body) = app.request(Method::DELETE, &uri, Some(&token), None).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+    }
+    let purge = format!("/api/system/menus/{}/purge", parent);
+    let (_, body) = app.get(&purge, &token).await;
+    assert_eq!(body["data"]["canPurge"], false);
+    assert_eq!(reference(&body, "menus")["action"], "block");
+    let confirmation = app
+        .confirmation(&token, json!({ "action": "menu.purge", "params": { "menuId": parent } }))
+        .await;
+    let (status, body) = app.confirmed(Method::DELETE, &purge, &token, &confirmation).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    for id in [child, parent] {
+        let purge = format!("/api/system/menus/{}/purge", id);
+        let confirmation = app
+            .confirmation(&token, json!({ "action": "menu.purge", "params": { "menuId": id } }))
+            .await;
+        let (status, body) = app.confirmed(Method::DELETE, &purge, &token, &confirmation).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+    }
+
+    // A dictionary item goes with its translations.
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/manage/dicts",
+            Some(&token),
+            Some(json!({ "dictType": "color", "label": "Red", "value": "r" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let dict_id = body["data"].as_i64().unwrap();
+    let uri = format!("/api/manage/dicts/{}/translations", dict_id);
+    let translations = json!({ "translations": [{ "locale": "zh-CN", "text": "红" }] });
+    let (status, body) = app.request(Method::PUT, &uri, Some(&token), Some(translations)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let uri = format!("/api/manage/dicts/{}", dict_id);
+    app.request(Method::DELETE, &uri, Some(&token), None).await;
+    let purge = format!("/api/manage/dicts/{}/purge", dict_id);
+    let (_, body) = app.get(&purge, &token).await;
+    assert_eq!(reference(&body, "dict_i18n")["rows"], 1);
+    let confirmation = app
+        .confirmation(&token, json!({ "action": "dict.purge", "params": { "dictId": dict_id } }))
+        .await;
+    let (status, body) = app.confirmed(Method::DELETE, &purge, &token, &confirmation).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (status, _) = app.get(&purge, &token).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn roles_move_between_environments_by_code() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let menu = json!({ "parentId": 0, "name": "reports", "code": "reports", "menuType": 2, "sortOrder": 1, "status": 1 });
+    let (status, body) =
+        app.request(Method::POST, "/api/system/menus", Some(&token), Some(menu)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let menu_id = body["data"].as_i64().unwrap();
+    let role = json!({ "name": "Reporter", "code": "reporter", "status": 1, "menuIds": [menu_id] });
+    let (status, body) =
+        app.request(Method::POST, "/api/system/roles", Some(&token), Some(role)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let (status, body) = app.get("/api/system/roles/export", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let mut doc = body["data"].clone();
+    let roles = doc["roles"].as_array().unwrap();
+    assert!(roles.iter().all(|role| role["code"] != "admin"), "{}", doc);
+    let reporter = roles.iter().find(|role| role["code"] == "reporter").unwrap();
+    assert_eq!(reporter["menuCodes"], json!(["reports"]));
+
+    doc["roles"] = json!([
+        { "code": "reporter", "name": "Report reader", "status": 1, "menuCodes": ["reports"] },
+        { "code": "auditor", "name": "Auditor", "status": 1, "menuCodes": ["reports", "no:such:menu"] },
+    ]);
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/system/roles/import?dryRun=true",
+            Some(&token),
+            Some(doc.clone()),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(
+        (body["data"]["created"].as_i64(), body["data"]["updated"].as_i64()),
+        (Some(1), Some(1))
+    );
+    assert_eq!(body["data"]["applied"], false);
+    assert_eq!(body["data"]["roles"][0]["changedFields"], json!(["name"]));
+    assert_eq!(body["data"]["roles"][1]["action"], "create");
+    assert_eq!(body["data"]["roles"][1]["problems"], json!(["Unknown menu codes: no:such:menu"]));
+
+    // Anything the dry run flags blocks the whole import.
+    let (status, body) = app
+        .request(Method::POST, "/api/system/roles/import", Some(&token), Some(doc.clone()))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+
+    doc["roles"][1]["menuCodes"] = json!(["reports"]);
+    let (status, body) = app
+        .request(Method::POST, "/api/system/roles/import", Some(&token), Some(doc.clone()))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["applied"], true);
+    let (_, body) = app.get("/api/system/roles/export", &token).await;
+    let names: Vec<&str> = body["data"]["roles"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|role| role["menuCodes"] == json!(["reports"]))
+        .map(|role| role["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["Auditor", "Report reader"]);
+
+    let (status, body) = app
+        .request(Method::POST, "/api/system/roles/import?dryRun=true", Some(&token), Some(doc))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["unchanged"], 2);
+}
+
+#[tokio::test]
+async fn rbac_snapshots_diff_against_the_live_database() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let (status, body) = app.get("/api/system/rbac/snapshot", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let mut snapshot = body["data"].clone();
+    let (status, body) = app
+        .request(Method::POST, "/api/system/rbac/diff", Some(&token), Some(snapshot.clone()))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["inSync"], true, "{}", body);
+
+    // Another environment has a menu this one lacks and grants it to `viewer`.
+    snapshot["menus"].as_array_mut().unwrap().push(json!({
+        "code": "reports", "name": "Reports", "menuType": 2, "status": 1
+    }));
+    let viewer = snapshot["roles"]
+        .as_array_mut()
+        .unwrap()
+        .iter_mut()
+        .find(|role| role["code"] == "viewer")
+        .unwrap();
+    viewer["menuCodes"].as_array_mut().unwrap().push(json!("reports"));
+    let (status, body) =
+        app.request(Method::POST, "/api/system/rbac/diff", Some(&token), Some(snapshot)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let diff = &body["data"];
+    assert_eq!(diff["inSync"], false);
+    assert_eq!(diff["menus"]["missing"][0]["code"], "reports");
+    assert_eq!(diff["roles"]["changed"][0]["code"], "viewer");
+    assert_eq!(diff["roles"]["changed"][0]["missingMenus"], json!(["reports"]));
+
+    // A role export is a snapshot without menus.
+    let (_, body) = app.get("/api/system/roles/export", &token).await;
+    let (status, body) = app
+        .request(Method::POST, "/api/system/rbac/diff", Some(&token), Some(body["data"].clone()))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["menusCompared"], false);
+    let extra = body["data"]["roles"]["extra"].as_array().unwrap();
+    assert!(extra.contains(&json!("owner")), "{}", body);
+}
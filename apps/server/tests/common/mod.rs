@@ -0,0 +1,282 @@
+//! Shared harness for the HTTP integration tests.
+//!
+//! Each [`TestApp`] gets its own in-memory SQLite database with every migration applied
+//! and drives the same router `run_server` serves, through `tower::ServiceExt::oneshot`.
+//! Every test binary compiles this module, and not all of them use every helper.
+#![allow(dead_code)]
+
+use axum::{
+    Router,
+    body::Body,
+    extract::connect_info::MockConnectInfo,
+    http::{Method, Request, StatusCode, header},
+    response::Response,
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use server::{
+    common::ids::{RoleId, UserId},
+    features::{
+        manage::{deploy::service::DeployService, task::service::TaskService},
+        system::user::{service::UserService, types::CreateUserRequest},
+    },
+    infra::{
+        app::build_router,
+        config::{self, AppConfig},
+        db::run_migrations,
+        permission::PermissionService,
+    },
+};
+use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+use std::{
+    net::SocketAddr,
+    sync::{
+        Arc,
+        atomic::{AtomicI64, Ordering},
+    },
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+use tower::ServiceExt;
+
+/// Capability caches are process-wide and keyed by user id, so every app starts its
+/// user ids in a separate range to keep parallel tests from seeing each other's grants.
+/// The login throttle is process-wide and keyed by IP, so each app also gets its own
+/// client address derived from the same counter.
+static NEXT_USER_ID_BASE: AtomicI64 = AtomicI64::new(1_000);
+
+pub struct TestApp {
+    pub pool: SqlitePool,
+    pub config: &'static AppConfig,
+    router: Router,
+}
+
+impl TestApp {
+    pub async fn spawn() -> Self {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("in-memory sqlite pool");
+        run_migrations(&pool).await.expect("migrations");
+        let user_id_base = NEXT_USER_ID_BASE.fetch_add(1_000, Ordering::Relaxed);
+        sqlx::query("UPDATE sqlite_sequence SET seq = ? WHERE name = 'users'")
+            .bind(user_id_base)
+            .execute(&pool)
+            .await
+            .expect("user id range");
+
+        let config = config::load().expect("RUSTZEN_* configuration");
+        let task_service = Arc::new(TaskService::new(config, pool.clone()).expect("task service"));
+        let deploy_service = Arc::new(DeployService::new(config, pool.clone()));
+        let router = build_router(config, pool.clone(), task_service, deploy_service)
+            .expect("router")
+            .layer(MockConnectInfo(client_addr(user_id_base)));
+        PermissionService::sync_permissions(&pool).await.expect("permission sync");
+
+        Self { pool, config, router }
+    }
+
+    /// Sends one request and returns the status with the JSON body (`Null` when empty).
+    pub async fn request(
+        &self,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let response = self.response(method, uri, token, body).await;
+        let status = response.status();
+        let bytes = response.into_body().collect().await.expect("body").to_bytes();
+        let json = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+        (status, json)
+    }
+
+    /// Sends one request and returns the raw response, for tests that check headers.
+    pub async fn response(
+        &self,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<Value>,
+    ) -> Response {
+        let mut builder = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let request = match body {
+            Some(body) => builder
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => builder.body(Body::empty()),
+        }
+        .expect("request");
+
+        self.send(request).await
+    }
+
+    /// Sends a prebuilt request, for tests that need custom headers.
+    pub async fn send(&self, request: Request<Body>) -> Response {
+        self.router.clone().oneshot(request).await.expect("response")
+    }
+
+    pub async fn get(&self, uri: &str, token: &str) -> (StatusCode, Value) {
+        self.request(Method::GET, uri, Some(token), None).await
+    }
+
+    /// Creates an active user holding the roles with the given codes.
+    pub async fn create_user(&self, username: &str, password: &str, role_codes: &[&str]) -> UserId {
+        let mut role_ids = Vec::new();
+        for code in role_codes {
+            let id: RoleId = sqlx::query_scalar("SELECT id FROM roles WHERE code = ?")
+                .bind(code)
+                .fetch_one(&self.pool)
+                .await
+                .expect("role code");
+            role_ids.push(id);
+        }
+        let request = CreateUserRequest {
+            username: username.to_string(),
+            email: format!("{}@example.com", username),
+            password: password.to_string(),
+            real_name: None,
+            status: Some(1),
+            role_ids,
+            profile: None,
+        };
+        UserService::create_user(self.config, &self.pool, None, request).await.expect("create user")
+    }
+
+    /// Logs in through the HTTP endpoint and returns the bearer token.
+    pub async fn login(&self, username: &str, password: &str) -> String {
+        let (status, body) = self
+            .request(
+                Method::POST,
+                "/api/auth/login",
+                None,
+                Some(json!({ "username": username, "password": password })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "login failed: {}", body);
+        body["data"]["token"].as_str().expect("token").to_string()
+    }
+
+    /// Asks for a confirmation token for `target`, e.g. `{"action": "user.purge", ...}`.
+    pub async fn confirmation(&self, token: &str, target: Value) -> String {
+        let (status, body) = self
+            .request(Method::POST, "/api/system/confirmations", Some(token), Some(target))
+            .await;
+        assert_eq!(status, StatusCode::OK, "confirmation failed: {}", body);
+        body["data"]["token"].as_str().expect("confirmation token").to_string()
+    }
+
+    /// Sends a body-less request with `X-Confirmation-Token`, as purge endpoints need.
+    pub async fn confirmed(
+        &self,
+        method: Method,
+        uri: &str,
+        token: &str,
+        confirmation: &str,
+    ) -> (StatusCode, Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .header("x-confirmation-token", confirmation)
+            .body(Body::empty())
+            .expect("request");
+        let response = self.send(request).await;
+        let status = response.status();
+        let bytes = response.into_body().collect().await.expect("body").to_bytes();
+        (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+    }
+
+    /// Creates a user holding the built-in `owner` (wildcard) role and logs in as it.
+    pub async fn admin_token(&self) -> String {
+        self.create_user("it_admin", "admin-password", &["owner"]).await;
+        self.login("it_admin", "admin-password").await
+    }
+}
+
+fn client_addr(user_id_base: i64) -> SocketAddr {
+    let [.., hi, lo] = (user_id_base / 1_000).to_be_bytes();
+    SocketAddr::from(([127, 0, hi, lo], 40_000))
+}
+
+/// Fake SMTP relay: accepts sessions until the receiver is dropped, answers every command
+/// with success, and sends each session's transcript down the channel.
+pub async fn fake_relay() -> (u16, mpsc::UnboundedReceiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("relay listener");
+    let port = listener.local_addr().expect("relay address").port();
+    let (sender, receiver) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        loop {
+            let socket = tokio::select! {
+                biased;
+                _ = sender.closed() => break,
+                accepted = listener.accept() => accepted.expect("relay accept").0,
+            };
+            let (read, mut write) = socket.into_split();
+            let mut read = BufReader::new(read);
+            write.write_all(b"220 relay\r\n").await.unwrap();
+            let mut transcript = String::new();
+            let mut in_data = false;
+            loop {
+                let mut line = String::new();
+                if read.read_line(&mut line).await.unwrap() == 0 {
+                    break;
+                }
+                transcript.push_str(&line);
+                let reply: &[u8] = match line.as_str() {
+                    ".\r\n" if in_data => b"250 queued\r\n",
+                    _ if in_data => continue,
+                    "DATA\r\n" => b"354 go ahead\r\n",
+                    "QUIT\r\n" => b"221 bye\r\n",
+                    _ => b"250 ok\r\n",
+                };
+                in_data = line == "DATA\r\n";
+                write.write_all(reply).await.unwrap();
+                if line == "QUIT\r\n" {
+                    break;
+                }
+            }
+            if sender.send(transcript).is_err() {
+                break;
+            }
+        }
+    });
+    (port, receiver)
+}
+
+/// Decodes the base64 text part of a relayed message.
+pub fn mail_text(transcript: &str) -> String {
+    let (_, rest) = transcript.split_once("base64\r\n\r\n").expect("text part");
+    let encoded: String = rest.lines().take_while(|line| !line.starts_with("--")).collect();
+    String::from_utf8(STANDARD.decode(encoded).expect("base64 body")).unwrap()
+}
+
+/// Reads one HTTP/1.1 request from a fake upstream's socket and returns its head and body.
+pub async fn read_http_request(socket: &mut TcpStream) -> (String, String) {
+    let mut raw = Vec::new();
+    let mut buf = [0u8; 1024];
+    loop {
+        let n = socket.read(&mut buf).await.unwrap();
+        assert!(n > 0, "connection closed before the body arrived");
+        raw.extend_from_slice(&buf[..n]);
+        let text = String::from_utf8_lossy(&raw);
+        if let Some((head, body)) = text.split_once("\r\n\r\n") {
+            let length = head
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length: "))
+                .and_then(|value| value.parse::<usize>().ok())
+                .unwrap_or(0);
+            if body.len() >= length {
+                return (head.to_string(), body.to_string());
+            }
+        }
+    }
+}
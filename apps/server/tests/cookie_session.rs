@@ -0,0 +1,143 @@
+//! Cookie session mode is read from `RUSTZEN_*` once per process, so it runs in its own
+//! test binary with the mode switched on before the config loads.
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode, header},
+    response::Response,
+};
+use common::TestApp;
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+
+fn set_cookie<'a>(response: &'a Response, name: &str) -> Option<&'a str> {
+    response
+        .headers()
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .find(|value| value.starts_with(&format!("{}=", name)))
+}
+
+fn cookie_pair(set_cookie: &str) -> &str {
+    set_cookie.split(';').next().unwrap()
+}
+
+async fn json_body(response: Response) -> Value {
+    let bytes = response.into_body().collect().await.expect("body").to_bytes();
+    serde_json::from_slice(&bytes).expect("json body")
+}
+
+fn create_role(cookies: &str, csrf: Option<&str>, code: &str) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method(Method::POST)
+        .uri("/api/system/roles")
+        .header(header::COOKIE, cookies)
+        .header(header::CONTENT_TYPE, "application/json");
+    if let Some(csrf) = csrf {
+        builder = builder.header("x-csrf-token", csrf);
+    }
+    let body = json!({ "name": code, "code": code, "status": 1, "menuIds": [] });
+    builder.body(Body::from(body.to_string())).expect("request")
+}
+
+fn browser_login(origin: &str) -> Request<Body> {
+    let body = json!({ "username": "erin", "password": "erin-password" });
+    Request::builder()
+        .method(Method::POST)
+        .uri("/api/auth/login")
+        .header(header::HOST, "admin.example.com")
+        .header(header::ORIGIN, origin)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("request")
+}
+
+#[tokio::test]
+async fn cookie_sessions_reject_cross_site_logins_and_writes_without_csrf_tokens() {
+    // SAFETY: set before any other thread reads the environment or the config loads.
+    unsafe { std::env::set_var("RUSTZEN_SESSION_COOKIE", "true") };
+    let app = TestApp::spawn().await;
+    app.create_user("erin", "erin-password", &["owner"]).await;
+
+    let response = app
+        .response(
+            Method::POST,
+            "/api/auth/login",
+            None,
+            Some(json!({ "username": "erin", "password": "erin-password" })),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let session = set_cookie(&response, "rustzen_session").expect("session cookie").to_string();
+    assert!(session.contains("HttpOnly"), "{}", session);
+    let login_csrf = cookie_pair(set_cookie(&response, "rustzen_csrf").expect("csrf cookie"))
+        .to_string();
+    let body = json_body(response).await;
+    assert!(body["data"].get("token").is_none(), "{}", body);
+    assert!(body["data"]["userInfo"].is_object());
+    let session = cookie_pair(&session).to_string();
+    let bearer = session.split_once('=').unwrap().1.to_string();
+
+    let response = app.send(browser_login("https://evil.example")).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(set_cookie(&response, "rustzen_session").is_none());
+    assert_eq!(json_body(response).await["code"], 10104);
+    let response = app.send(browser_login("https://admin.example.com")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(set_cookie(&response, "rustzen_session").is_some());
+
+    let me = Request::builder()
+        .uri("/api/auth/me")
+        .header(header::COOKIE, &session)
+        .body(Body::empty())
+        .unwrap();
+    assert_eq!(app.send(me).await.status(), StatusCode::OK);
+
+    let response = app.send(create_role(&session, None, "no_csrf")).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(json_body(response).await["code"], 10104);
+
+    let (_, login_csrf_value) = login_csrf.split_once('=').unwrap();
+    let cookies = format!("{}; {}", session, login_csrf);
+    let response = app.send(create_role(&cookies, Some(login_csrf_value), "login_csrf")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // A token issued without (or for another) session does not pass, even echoed.
+    let response = app.response(Method::GET, "/api/auth/csrf", None, None).await;
+    let planted = json_body(response).await["data"].as_str().unwrap().to_string();
+    let cookies = format!("{}; rustzen_csrf={}", session, planted);
+    let response = app.send(create_role(&cookies, Some(&planted), "planted")).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let request = Request::builder()
+        .uri("/api/auth/csrf")
+        .header(header::COOKIE, &session)
+        .body(Body::empty())
+        .unwrap();
+    let response = app.send(request).await;
+    let csrf_cookie = cookie_pair(set_cookie(&response, "rustzen_csrf").unwrap()).to_string();
+    let csrf = json_body(response).await["data"].as_str().unwrap().to_string();
+    let cookies = format!("{}; {}", session, csrf_cookie);
+
+    let response = app.send(create_role(&cookies, Some("forged.00"), "forged")).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = app.send(create_role(&cookies, Some(&csrf), "with_csrf")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut cross_site = create_role(&cookies, Some(&csrf), "cross_site");
+    cross_site.headers_mut().insert(header::ORIGIN, "https://evil.example".parse().unwrap());
+    assert_eq!(app.send(cross_site).await.status(), StatusCode::FORBIDDEN);
+
+    let (status, _) = app
+        .request(
+            Method::POST,
+            "/api/system/roles",
+            Some(&bearer),
+            Some(json!({ "name": "bearer", "code": "bearer", "status": 1, "menuIds": [] })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+}
@@ -0,0 +1,101 @@
+//! Active debug captures are held process-wide, so these run in their own test binary
+//! where no other test's requests can fall under them.
+
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::TestApp;
+use serde_json::{Value, json};
+
+async fn logged_data(app: &TestApp, route: &str) -> Vec<Option<String>> {
+    sqlx::query_scalar(
+        "SELECT data FROM operation_logs WHERE route = ? AND action LIKE 'HTTP_%' ORDER BY id",
+    )
+    .bind(route)
+    .fetch_all(&app.pool)
+    .await
+    .expect("operation logs")
+}
+
+#[tokio::test]
+async fn captures_log_sanitized_bodies_for_their_route_until_deleted() {
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+
+    let (status, body) = app
+        .request(Method::POST, "/api/system/debug-captures", Some(&token), Some(json!({})))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/system/debug-captures",
+            Some(&token),
+            Some(json!({ "route": "/api/system/users", "minutes": 5000 })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/system/debug-captures",
+            Some(&token),
+            Some(json!({ "route": "/api/system/users", "minutes": 10, "reason": "ticket 42" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let capture_id = body["data"].as_i64().expect("capture id");
+
+    let new_user = |username: &str| {
+        json!({
+            "username": username,
+            "email": format!("{}@example.com", username),
+            "password": "capture-secret-password",
+            "roleIds": [],
+        })
+    };
+    let (status, body) = app
+        .request(Method::POST, "/api/system/users", Some(&token), Some(new_user("captured_one")))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/system/roles",
+            Some(&token),
+            Some(json!({ "name": "Uncaptured", "code": "uncaptured", "status": 1, "menuIds": [] })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let logged = logged_data(&app, "/api/system/users").await;
+    let data: Value =
+        serde_json::from_str(logged.last().cloned().flatten().as_deref().expect("captured data"))
+            .expect("captured JSON");
+    assert_eq!(data["debugCapture"]["captureId"], capture_id, "{}", data);
+    assert_eq!(data["debugCapture"]["requestBody"]["username"], "captured_one", "{}", data);
+    assert_eq!(data["debugCapture"]["requestBody"]["password"], "[REDACTED]", "{}", data);
+    assert!(!data.to_string().contains("capture-secret-password"));
+    assert_eq!(logged_data(&app, "/api/system/roles").await, vec![None]);
+
+    let (status, body) = app.get("/api/system/debug-captures?active=true", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"][0]["reason"], "ticket 42", "{}", body);
+    assert_eq!(body["data"][0]["active"], true, "{}", body);
+
+    let (status, body) = app
+        .request(
+            Method::DELETE,
+            &format!("/api/system/debug-captures/{}", capture_id),
+            Some(&token),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (status, body) = app
+        .request(Method::POST, "/api/system/users", Some(&token), Some(new_user("captured_two")))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(logged_data(&app, "/api/system/users").await.last(), Some(&None));
+}
@@ -0,0 +1,82 @@
+//! Dual control is read from `RUSTZEN_*` once per process, so it runs in its own test
+//! binary with approvals switched on before the config loads.
+
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::TestApp;
+use serde_json::{Value, json};
+
+fn approval_id(body: &Value) -> i64 {
+    assert_eq!(body["code"], 10016, "{}", body);
+    body["data"]["approvalId"].as_i64().expect("approval id")
+}
+
+#[tokio::test]
+async fn destructive_actions_wait_for_a_second_administrator() {
+    // SAFETY: set before any other thread reads the environment or the config loads.
+    unsafe { std::env::set_var("RUSTZEN_DUAL_CONTROL", "true") };
+    let app = TestApp::spawn().await;
+    let alice = app.admin_token().await;
+    app.create_user("bob", "bob-password", &["owner"]).await;
+    let bob = app.login("bob", "bob-password").await;
+    let doomed = app.create_user("doomed", "doomed-password", &[]).await;
+
+    // Soft delete is not gated; the permanent purge is.
+    let uri = format!("/api/system/users/{}", doomed);
+    let (status, body) = app.request(Method::DELETE, &uri, Some(&alice), None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let purge = format!("/api/system/users/{}/purge", doomed);
+    let purge_target = json!({ "action": "user.purge", "params": { "userId": doomed } });
+    let confirmation = app.confirmation(&alice, purge_target.clone()).await;
+    let (status, body) = app.confirmed(Method::DELETE, &purge, &alice, &confirmation).await;
+    assert_eq!(status, StatusCode::ACCEPTED, "{}", body);
+    let id = approval_id(&body);
+    let confirmation = app.confirmation(&alice, purge_target).await;
+    let (_, body) = app.confirmed(Method::DELETE, &purge, &alice, &confirmation).await;
+    assert_eq!(approval_id(&body), id, "a repeated request reuses the open approval");
+
+    let (status, body) = app.get("/api/system/approvals?status=pending", &bob).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["total"], 1, "{}", body);
+    assert_eq!(body["data"][0]["action"], "user.purge");
+    assert_eq!(body["data"][0]["params"]["userId"], doomed.get());
+
+    let approve = format!("/api/system/approvals/{}/approve", id);
+    let (status, body) = app.request(Method::POST, &approve, Some(&alice), None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    assert!(body["message"].as_str().unwrap().contains("second administrator"), "{}", body);
+
+    let (status, body) = app.request(Method::POST, &approve, Some(&bob), None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["status"], "approved", "{}", body);
+    assert_eq!(body["data"]["decidedByName"], "bob");
+    let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE id = ?")
+        .bind(doomed)
+        .fetch_one(&app.pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, 0);
+    let (status, _) = app.request(Method::POST, &approve, Some(&bob), None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "an approval runs once");
+
+    // Log purges validate their range up front and can be withdrawn by the requester.
+    let (status, body) =
+        app.request(Method::DELETE, "/api/manage/logs?olderThanDays=0", Some(&alice), None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    let confirmation = app
+        .confirmation(&alice, json!({ "action": "log.purge", "params": { "olderThanDays": 30 } }))
+        .await;
+    let (status, body) = app
+        .confirmed(Method::DELETE, "/api/manage/logs?olderThanDays=30", &alice, &confirmation)
+        .await;
+    assert_eq!(status, StatusCode::ACCEPTED, "{}", body);
+    let reject = format!("/api/system/approvals/{}/reject", approval_id(&body));
+    let (status, body) = app.request(Method::POST, &reject, Some(&alice), None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["status"], "rejected");
+    assert_eq!(body["data"]["action"], "log.purge");
+
+    let (_, body) = app.get("/api/system/approvals?status=pending", &bob).await;
+    assert_eq!(body["total"], 0, "{}", body);
+}
@@ -0,0 +1,63 @@
+//! Confirmation mails go through the SMTP relay set in `RUSTZEN_*`, which is read once per
+//! process, so these run in their own test binary against a fake relay.
+
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::{TestApp, fake_relay, mail_text};
+use serde_json::{Value, json};
+
+async fn update_profile(app: &TestApp, token: &str, email: &str) -> (StatusCode, Value) {
+    let body = json!({ "email": email, "realName": "Kate" });
+    app.request(Method::PUT, "/api/account/profile", Some(token), Some(body)).await
+}
+
+#[tokio::test]
+async fn email_changes_apply_only_after_the_new_address_confirms() {
+    let (port, mut relay) = fake_relay().await;
+    // SAFETY: set before any other thread reads the environment or the config loads.
+    unsafe {
+        std::env::set_var("RUSTZEN_EMAIL_CONFIRM_URL", "https://admin.example.com/confirm");
+        std::env::set_var("RUSTZEN_SMTP_HOST", "127.0.0.1");
+        std::env::set_var("RUSTZEN_SMTP_TLS", "none");
+        std::env::set_var("RUSTZEN_SMTP_PORT", port.to_string());
+        std::env::set_var("RUSTZEN_SMTP_FROM", "noreply@example.com");
+    }
+    let app = TestApp::spawn().await;
+    app.create_user("kate", "kate-password", &[]).await;
+    let kate = app.login("kate", "kate-password").await;
+
+    let (status, body) = update_profile(&app, &kate, "kate@example.org").await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["email"], "kate@example.com");
+    assert_eq!(body["data"]["pendingEmail"], "kate@example.org");
+    assert_eq!(body["data"]["realName"], "Kate");
+    let mail = relay.recv().await.expect("confirmation mail");
+    assert!(mail.contains("RCPT TO:<kate@example.org>"), "{}", mail);
+    let text = mail_text(&mail);
+    let (_, token) = text.split_once("?token=").expect("confirmation link");
+    let token = token.split_whitespace().next().unwrap().to_string();
+
+    let confirm = |token: &str| {
+        let body = json!({ "token": token });
+        app.request(Method::POST, "/api/auth/confirm-email", None, Some(body))
+    };
+    let (status, _) = confirm("not-a-token").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, body) = confirm(&token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let notice = relay.recv().await.expect("notice to the old address");
+    assert!(notice.contains("RCPT TO:<kate@example.com>"), "{}", notice);
+    assert!(mail_text(&notice).contains("kate@example.org"));
+    let (status, _) = confirm(&token).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (_, body) = app.get("/api/auth/me", &kate).await;
+    assert_eq!(body["data"]["email"], "kate@example.org");
+    assert!(body["data"]["pendingEmail"].is_null());
+
+    app.create_user("liam", "liam-password", &[]).await;
+    let liam = app.login("liam", "liam-password").await;
+    let (status, _) = update_profile(&app, &liam, "kate@example.org").await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
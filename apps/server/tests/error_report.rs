@@ -0,0 +1,77 @@
+//! The Sentry DSN is read from `RUSTZEN_*` once per process, so this runs in its own test
+//! binary against a fake store endpoint.
+
+mod common;
+
+use axum::http::StatusCode;
+use common::TestApp;
+use serde_json::Value;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+};
+
+/// Accepts one HTTP request, answers `200`, and returns its head and body.
+async fn fake_store() -> (u16, tokio::task::JoinHandle<(String, String)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("store listener");
+    let port = listener.local_addr().expect("store address").port();
+    let store = tokio::spawn(async move {
+        let (socket, _) = listener.accept().await.expect("store accept");
+        let (read, mut write) = socket.into_split();
+        let mut read = BufReader::new(read);
+        let mut head = String::new();
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            read.read_line(&mut line).await.unwrap();
+            if let Some((name, value)) = line.split_once(':')
+                && name.eq_ignore_ascii_case("content-length")
+            {
+                content_length = value.trim().parse().unwrap();
+            }
+            if line == "\r\n" {
+                break;
+            }
+            head.push_str(&line);
+        }
+        let mut body = vec![0; content_length];
+        read.read_exact(&mut body).await.unwrap();
+        write.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\n\r\n{}").await.unwrap();
+        (head, String::from_utf8(body).unwrap())
+    });
+    (port, store)
+}
+
+#[tokio::test]
+async fn database_failures_are_reported_with_request_context() {
+    let (port, store) = fake_store().await;
+    // SAFETY: set before any other thread reads the environment or the config loads.
+    unsafe {
+        std::env::set_var("RUSTZEN_OUTBOUND_ALLOW_NETWORKS", "127.0.0.1");
+        std::env::set_var("RUSTZEN_SENTRY_DSN", format!("http://publickey@127.0.0.1:{}/42", port));
+    }
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let user_id: i64 = sqlx::query_scalar("SELECT id FROM users WHERE username = 'it_admin'")
+        .fetch_one(&app.pool)
+        .await
+        .unwrap();
+    sqlx::query("DROP TABLE feature_flags").execute(&app.pool).await.unwrap();
+
+    let response = app
+        .response(axum::http::Method::GET, "/api/system/feature-flags", Some(&token), None)
+        .await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let request_id = response.headers()["x-request-id"].to_str().unwrap().to_string();
+
+    let (head, body) =
+        tokio::time::timeout(std::time::Duration::from_secs(10), store).await.unwrap().unwrap();
+    assert!(head.starts_with("POST /api/42/store/ "), "{}", head);
+    assert!(head.contains("sentry_key=publickey"), "{}", head);
+    let event: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(event["level"], "error");
+    assert_eq!(event["tags"]["request_id"], request_id);
+    assert_eq!(event["user"]["id"], user_id.to_string());
+    assert!(event["transaction"].as_str().unwrap().starts_with("/api/system/feature-flags"));
+    assert!(event["message"]["formatted"].as_str().unwrap().contains("code 20001"), "{}", body);
+}
@@ -0,0 +1,125 @@
+//! Export files live under the runtime root, which is read from `RUSTZEN_*` once per process,
+//! so these run in their own test binary with a temporary runtime root.
+
+mod common;
+
+use axum::http::{Method, StatusCode, header};
+use common::TestApp;
+use http_body_util::BodyExt;
+use serde_json::json;
+use server::features::system::export_job::service::ExportJobService;
+
+#[tokio::test]
+async fn export_jobs_write_files_behind_signed_links() {
+    let runtime_root = std::env::temp_dir().join(format!("rustzen-exports-{}", std::process::id()));
+    // SAFETY: set before any other thread reads the environment or the config loads.
+    unsafe {
+        std::env::set_var("RUSTZEN_RUNTIME_ROOT", &runtime_root);
+    }
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    app.create_user("job_one", "job-password", &[]).await;
+    app.create_user("job_two", "job-password", &["viewer"]).await;
+    let viewer = app.login("job_two", "job-password").await;
+
+    let request = json!({ "resource": "users", "filters": { "username": "job_" } });
+    let (status, job) =
+        app.request(Method::POST, "/api/system/exports", Some(&token), Some(request)).await;
+    assert_eq!(status, StatusCode::OK, "{}", job);
+    assert_eq!(job["data"]["status"], "pending", "{}", job);
+    assert_eq!(job["data"]["filters"]["username"], "job_");
+    let id = job["data"]["id"].as_i64().unwrap();
+
+    let template = json!({ "resource": "logs", "name": "Actions", "columns": ["ID", "action"] });
+    let (_, body) = app
+        .request(Method::POST, "/api/system/export-templates", Some(&token), Some(template))
+        .await;
+    let logs_template = body["data"].as_i64().expect("template id");
+    let (status, job) = app
+        .request(
+            Method::POST,
+            "/api/system/exports",
+            Some(&token),
+            Some(json!({ "resource": "logs", "templateId": logs_template })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", job);
+    assert_eq!(job["data"]["columns"], json!(["ID", "action"]));
+    let logs_job = job["data"]["id"].as_i64().unwrap();
+    let mismatched = json!({ "resource": "users", "templateId": logs_template });
+    let (status, body) =
+        app.request(Method::POST, "/api/system/exports", Some(&token), Some(mismatched)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+
+    let bad = json!({ "resource": "users", "filters": { "sortBy": "password_hash" } });
+    let (status, body) =
+        app.request(Method::POST, "/api/system/exports", Some(&token), Some(bad)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    let unsorted = json!({ "resource": "logs", "filters": { "sortBy": "action" } });
+    let (status, body) =
+        app.request(Method::POST, "/api/system/exports", Some(&token), Some(unsorted)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    assert_eq!(body["data"][0]["field"], "sortBy");
+    let (status, body) = app
+        .request(
+            Method::POST,
+            "/api/system/exports",
+            Some(&viewer),
+            Some(json!({ "resource": "users" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN, "{}", body);
+
+    let (status, body) = app.get(&format!("/api/system/exports/{id}/link"), &token).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+
+    assert_eq!(ExportJobService::process_pending(app.config, &app.pool).await.expect("process"), 2);
+    let (status, job) = app.get(&format!("/api/system/exports/{id}"), &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", job);
+    assert_eq!(job["data"]["status"], "completed", "{}", job);
+    assert_eq!(job["data"]["totalRows"], 2);
+    assert_eq!(job["data"]["rowsDone"], 2);
+    assert!(job["data"]["expiresAt"].is_string(), "{}", job);
+
+    let (status, link) = app.get(&format!("/api/system/exports/{id}/link"), &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", link);
+    let url = link["data"]["url"].as_str().unwrap().to_string();
+    assert!(url.starts_with("/api/files/exports/"), "{}", url);
+
+    let response = app.response(Method::GET, &url, None, None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "text/csv; charset=utf-8");
+    let disposition = response.headers()[header::CONTENT_DISPOSITION].to_str().unwrap();
+    assert!(disposition.starts_with("attachment; filename=user_"), "{}", disposition);
+    let bytes = response.into_body().collect().await.expect("body").to_bytes();
+    assert_eq!(bytes.len() as u64, job["data"]["sizeBytes"].as_u64().unwrap());
+    let csv = String::from_utf8_lossy(&bytes);
+    assert!(csv.starts_with("id,username,email,phone"), "{}", csv);
+    assert!(csv.contains(",job_one,") && csv.contains(",job_two,"), "{}", csv);
+
+    let (_, link) = app.get(&format!("/api/system/exports/{logs_job}/link"), &token).await;
+    let response =
+        app.response(Method::GET, link["data"]["url"].as_str().unwrap(), None, None).await;
+    let bytes = response.into_body().collect().await.expect("body").to_bytes();
+    let csv = String::from_utf8_lossy(&bytes);
+    assert!(csv.starts_with("ID,action\n"), "{}", csv);
+    assert!(csv.lines().skip(1).all(|line| line.split(',').count() == 2), "{}", csv);
+
+    let tampered = url.replace("expires=", "expires=1");
+    let (status, _) = app.request(Method::GET, &tampered, None, None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, list) = app.get("/api/system/exports", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", list);
+    assert_eq!(list["total"], 2, "{}", list);
+    let (status, _) = app.get(&format!("/api/system/exports/{id}"), &viewer).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, logs) = app.get("/api/manage/logs?action=DATA_EXPORT", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", logs);
+    let users =
+        logs["data"].as_array().unwrap().iter().find(|log| log["data"]["resource"] == "users");
+    assert_eq!(users.expect("users export")["data"]["rows"], 2, "{}", logs);
+
+    let _ = std::fs::remove_dir_all(runtime_root);
+}
@@ -0,0 +1,32 @@
+//! Key rotation swaps the process-wide JWT keyring, so it runs in its own test binary.
+
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::TestApp;
+
+#[tokio::test]
+async fn rotating_the_signing_key_keeps_existing_sessions_valid() {
+    let app = TestApp::spawn().await;
+    let old_token = app.admin_token().await;
+
+    let (status, body) =
+        app.request(Method::POST, "/api/system/jwt-keys/rotate", Some(&old_token), None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let kid = body["data"].as_str().expect("new kid").to_string();
+
+    let (status, _) = app.get("/api/auth/me", &old_token).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let new_token = app.login("it_admin", "admin-password").await;
+    let header = jsonwebtoken::decode_header(&new_token).expect("jwt header");
+    assert_eq!(header.kid.as_deref(), Some(kid.as_str()));
+
+    let (status, body) = app.get("/api/system/jwt-keys", &new_token).await;
+    assert_eq!(status, StatusCode::OK);
+    let keys = body["data"].as_array().expect("keys");
+    assert_eq!(keys.len(), 2);
+    assert_eq!(keys[0]["kid"], kid.as_str());
+    assert_eq!(keys[0]["current"], true);
+    assert_eq!(keys[1]["source"], "config");
+}
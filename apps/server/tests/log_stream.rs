@@ -0,0 +1,48 @@
+//! The live log stream reads the process-wide tracing subscriber, so it runs in its own
+//! test binary that installs one.
+
+mod common;
+
+use axum::http::{Method, StatusCode, header};
+use common::TestApp;
+use http_body_util::BodyExt;
+use server::infra::log_stream::LogStreamLayer;
+use std::time::Duration;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+#[tokio::test]
+async fn admins_receive_log_lines_at_or_above_the_requested_level() {
+    tracing_subscriber::registry().with(LogStreamLayer).init();
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+
+    let (status, body) = app.get("/api/system/logs/stream?level=loud", &token).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+
+    let response =
+        app.response(Method::GET, "/api/system/logs/stream?level=warn", Some(&token), None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "text/event-stream");
+    let mut body = response.into_body();
+
+    tracing::info!(target: "log_stream_test", "below the requested level");
+    tracing::warn!(target: "log_stream_test", user_id = 42, "disk almost full");
+
+    // Skip lines other code logged meanwhile; the INFO probe must not show up first.
+    let line = loop {
+        let frame = tokio::time::timeout(Duration::from_secs(5), body.frame())
+            .await
+            .expect("a log line within 5s")
+            .expect("open stream")
+            .expect("frame");
+        let text = String::from_utf8(frame.into_data().expect("data frame").to_vec()).unwrap();
+        let Some(data) = text.strip_prefix("event: log\ndata: ") else { continue };
+        let line: serde_json::Value = serde_json::from_str(data.trim()).unwrap();
+        if line["target"] == "log_stream_test" {
+            break line;
+        }
+    };
+    assert_eq!(line["level"], "WARN");
+    assert_eq!(line["message"], "disk almost full");
+    assert_eq!(line["fields"]["user_id"], "42");
+}
@@ -0,0 +1,239 @@
+//! Provider login needs `RUSTZEN_OAUTH_*`, which is read once per process, so these run in
+//! their own test binary, with DingTalk and Feishu connectors pointed at one fake API.
+//!
+//! The fake treats the redirect `code` as the person who scanned: code `alice` signs in as
+//! DingTalk union id `union-alice`, or Feishu open id `ou_alice` with `alice@example.com`.
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode, header},
+};
+use common::{TestApp, read_http_request};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use server::infra::oauth::{self, DingTalkConnector, FeishuConnector};
+use tokio::{io::AsyncWriteExt, net::TcpListener};
+
+/// Answers the DingTalk and Feishu API calls until the test ends.
+async fn fake_provider() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("provider listener");
+    let port = listener.local_addr().expect("provider address").port();
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.expect("provider accept");
+            let (head, body) = read_http_request(&mut socket).await;
+            let answer = answer(&head, &body).to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                answer.len(),
+                answer
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+    port
+}
+
+fn answer(head: &str, body: &str) -> Value {
+    let request_line = head.lines().next().unwrap_or_default();
+    let header = |name: &str| {
+        head.lines()
+            .find_map(|line| line.strip_prefix(name))
+            .map(|value| value.trim().to_string())
+            .unwrap_or_default()
+    };
+    let code = || {
+        let body: Value = serde_json::from_str(body).expect("json body");
+        body["code"].as_str().unwrap_or_default().to_string()
+    };
+    if request_line.starts_with("POST /v1.0/oauth2/userAccessToken") {
+        json!({ "accessToken": format!("dt-{}", code()), "expireIn": 7200 })
+    } else if request_line.starts_with("GET /v1.0/contact/users/me") {
+        let person = header("x-acs-dingtalk-access-token:").replace("dt-", "");
+        json!({ "unionId": format!("union-{}", person), "nick": person })
+    } else if request_line.starts_with("POST /open-apis/auth/v3/app_access_token/internal") {
+        json!({ "code": 0, "app_access_token": "feishu-app", "expire": 7200 })
+    } else if request_line.starts_with("POST /open-apis/authen/v1/oidc/access_token") {
+        assert_eq!(header("authorization:"), "Bearer feishu-app");
+        json!({ "code": 0, "data": { "access_token": format!("fs-{}", code()) } })
+    } else if request_line.starts_with("GET /open-apis/authen/v1/user_info") {
+        let person = header("authorization:").replace("Bearer fs-", "");
+        json!({ "code": 0, "data": {
+            "open_id": format!("ou_{}", person),
+            "name": person,
+            "email": format!("{}@example.com", person),
+        } })
+    } else {
+        json!({ "code": 404, "msg": request_line })
+    }
+}
+
+/// A started round trip: its state and the state cookie the starting browser was given.
+struct RoundTrip {
+    state: String,
+    cookie: String,
+}
+
+/// Starts a round trip at `uri`.
+async fn authorize(app: &TestApp, uri: &str, token: Option<&str>) -> RoundTrip {
+    let response = app.response(Method::GET, uri, token, None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let set_cookie = response.headers().get(header::SET_COOKIE).expect("state cookie");
+    let set_cookie = set_cookie.to_str().unwrap();
+    assert!(set_cookie.contains("HttpOnly"), "{}", set_cookie);
+    let cookie = set_cookie.split(';').next().unwrap().to_string();
+    let bytes = response.into_body().collect().await.expect("body").to_bytes();
+    let body: Value = serde_json::from_slice(&bytes).expect("json body");
+    let state = body["data"]["state"].as_str().expect("state").to_string();
+    RoundTrip { state, cookie }
+}
+
+/// Posts `state` and `code` to `uri` as a browser holding `cookie` would.
+async fn finish(
+    app: &TestApp,
+    uri: &str,
+    token: Option<&str>,
+    state: &str,
+    code: &str,
+    cookie: Option<&str>,
+) -> (StatusCode, Value) {
+    let mut builder = Request::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header(header::CONTENT_TYPE, "application/json");
+    if let Some(token) = token {
+        builder = builder.header(header::AUTHORIZATION, format!("Bearer {}", token));
+    }
+    if let Some(cookie) = cookie {
+        builder = builder.header(header::COOKIE, cookie);
+    }
+    let body = json!({ "state": state, "code": code }).to_string();
+    let response = app.send(builder.body(Body::from(body)).expect("request")).await;
+    let status = response.status();
+    let bytes = response.into_body().collect().await.expect("body").to_bytes();
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+async fn callback(app: &TestApp, trip: &RoundTrip, code: &str) -> (StatusCode, Value) {
+    let uri = "/api/auth/oauth/callback";
+    finish(app, uri, None, &trip.state, code, Some(&trip.cookie)).await
+}
+
+/// Links the DingTalk account `alice` to the user behind `token`.
+async fn link(app: &TestApp, token: &str, trip: &RoundTrip) -> (StatusCode, Value) {
+    let uri = "/api/account/identities";
+    finish(app, uri, Some(token), &trip.state, "alice", Some(&trip.cookie)).await
+}
+
+#[tokio::test]
+async fn provider_accounts_link_to_users_and_sign_them_in() {
+    let port = fake_provider().await;
+    let provider = format!("http://127.0.0.1:{}", port);
+    // SAFETY: set before any other thread reads the environment or the config loads.
+    unsafe {
+        std::env::set_var("RUSTZEN_OUTBOUND_ALLOW_NETWORKS", "127.0.0.1");
+        std::env::set_var("RUSTZEN_OAUTH_REDIRECT_URL", "https://admin.example.com/oauth");
+        std::env::set_var("RUSTZEN_OAUTH_AUTO_PROVISION", "feishu");
+    }
+    oauth::install_connectors(vec![
+        Box::new(
+            DingTalkConnector::new("ding123".to_string(), "ding-secret".to_string())
+                .with_url(&provider),
+        ),
+        Box::new(
+            FeishuConnector::new("cli_123".to_string(), "feishu-secret".to_string())
+                .with_url(&provider),
+        ),
+    ]);
+    let app = TestApp::spawn().await;
+    app.create_user("oauth_user", "oauth-password", &[]).await;
+    let token = app.login("oauth_user", "oauth-password").await;
+
+    let (status, body) = app.request(Method::GET, "/api/auth/oauth/providers", None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"], json!(["dingtalk", "feishu"]));
+    let (status, _) = app.request(Method::GET, "/api/auth/oauth/wecom/authorize", None, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (_, body) =
+        app.request(Method::GET, "/api/auth/oauth/dingtalk/authorize", None, None).await;
+    let url = body["data"]["url"].as_str().expect("authorize url");
+    assert!(url.starts_with("https://login.dingtalk.com/oauth2/auth?"), "{}", url);
+    assert!(url.contains("redirect_uri=https%3A%2F%2Fadmin.example.com%2Foauth"), "{}", url);
+
+    // Unlinked accounts are refused, and a state only works once.
+    let trip = authorize(&app, "/api/auth/oauth/dingtalk/authorize", None).await;
+    let (status, body) = callback(&app, &trip, "alice").await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED, "{}", body);
+    assert_eq!(body["code"], 10105);
+    assert_eq!(body["data"]["provider"], "dingtalk");
+    let (status, _) = callback(&app, &trip, "alice").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // A callback only works in the browser that started the round trip.
+    let trip = authorize(&app, "/api/auth/oauth/dingtalk/authorize", None).await;
+    let other = authorize(&app, "/api/auth/oauth/dingtalk/authorize", None).await;
+    let uri = "/api/auth/oauth/callback";
+    let (status, _) = finish(&app, uri, None, &trip.state, "alice", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = finish(&app, uri, None, &trip.state, "alice", Some(&other.cookie)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = callback(&app, &trip, "alice").await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Linking: a login state cannot link and a link state cannot log in.
+    let trip = authorize(&app, "/api/auth/oauth/dingtalk/authorize", None).await;
+    let (status, _) = link(&app, &token, &trip).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let link_uri = "/api/account/identities/dingtalk/authorize";
+    let trip = authorize(&app, link_uri, Some(&token)).await;
+    let (status, _) = callback(&app, &trip, "alice").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let trip = authorize(&app, link_uri, Some(&token)).await;
+    let (status, body) = link(&app, &token, &trip).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"][0]["provider"], "dingtalk");
+    assert_eq!(body["data"][0]["subject"], "union-alice");
+    assert_eq!(body["data"][0]["displayName"], "alice");
+
+    let trip = authorize(&app, "/api/auth/oauth/dingtalk/authorize", None).await;
+    let (status, body) = callback(&app, &trip, "alice").await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["userInfo"]["username"], "oauth_user");
+    let session = body["data"]["token"].as_str().expect("token");
+    let (status, _) = app.get("/api/auth/me", session).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Another user cannot claim the same provider account.
+    app.create_user("oauth_other", "other-password", &[]).await;
+    let other = app.login("oauth_other", "other-password").await;
+    let trip = authorize(&app, link_uri, Some(&other)).await;
+    let (status, body) = link(&app, &other, &trip).await;
+    assert_eq!(status, StatusCode::CONFLICT, "{}", body);
+    assert_eq!(body["code"], 10204);
+
+    // Feishu is set to auto-provision first-time accounts.
+    let trip = authorize(&app, "/api/auth/oauth/feishu/authorize", None).await;
+    let (status, body) = callback(&app, &trip, "bob").await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["userInfo"]["username"], "feishu_ou_bob");
+    assert_eq!(body["data"]["userInfo"]["email"], "bob@example.com");
+    let trip = authorize(&app, "/api/auth/oauth/feishu/authorize", None).await;
+    let (_, body) = callback(&app, &trip, "bob").await;
+    assert_eq!(body["data"]["userInfo"]["username"], "feishu_ou_bob");
+
+    // Unlinking turns provider login off again.
+    let (status, body) = app
+        .request(Method::POST, "/api/account/identities/dingtalk/unbind", Some(&token), None)
+        .await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"], json!([]));
+    let (status, _) = app
+        .request(Method::POST, "/api/account/identities/dingtalk/unbind", Some(&token), None)
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let trip = authorize(&app, "/api/auth/oauth/dingtalk/authorize", None).await;
+    let (status, _) = callback(&app, &trip, "alice").await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,67 @@
+//! Quotas are read from `RUSTZEN_*` once per process, so they run in their own test binary
+//! with limits set before the config loads.
+
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::TestApp;
+use serde_json::json;
+
+#[tokio::test]
+async fn create_paths_stop_at_the_configured_quota() {
+    // SAFETY: set before any other thread reads the environment or the config loads.
+    unsafe {
+        std::env::set_var("RUSTZEN_MAX_USERS", "4");
+        std::env::set_var("RUSTZEN_MAX_ROLES", "6");
+    }
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+
+    let (status, usage) = app.get("/api/system/usage", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", usage);
+    assert_eq!(usage["data"]["users"]["limit"], 4, "{}", usage);
+    assert!(usage["data"]["storageBytes"]["limit"].is_null(), "{}", usage);
+    let users = usage["data"]["users"]["used"].as_u64().expect("user count");
+    let roles = usage["data"]["roles"]["used"].as_u64().expect("role count");
+
+    let new_user = |n: u64| {
+        json!({
+            "username": format!("quota_user_{}", n),
+            "email": format!("quota_user_{}@example.com", n),
+            "password": "quota-password",
+            "roleIds": [],
+        })
+    };
+    for n in users..4 {
+        let (status, body) =
+            app.request(Method::POST, "/api/system/users", Some(&token), Some(new_user(n))).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+    }
+    let (status, body) =
+        app.request(Method::POST, "/api/system/users", Some(&token), Some(new_user(99))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN, "{}", body);
+    assert_eq!(body["code"], 10017, "{}", body);
+    assert_eq!(body["data"], json!({ "resource": "users", "limit": 4 }));
+
+    let new_role = |n: u64| {
+        json!({
+            "name": format!("Quota role {}", n),
+            "code": format!("quota_role_{}", n),
+            "status": 1,
+            "menuIds": [],
+        })
+    };
+    for n in roles..6 {
+        let (status, body) =
+            app.request(Method::POST, "/api/system/roles", Some(&token), Some(new_role(n))).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+    }
+    let (status, body) =
+        app.request(Method::POST, "/api/system/roles", Some(&token), Some(new_role(99))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN, "{}", body);
+    assert_eq!(body["data"]["resource"], "roles", "{}", body);
+
+    let (_, usage) = app.get("/api/system/usage", &token).await;
+    assert_eq!(usage["data"]["users"]["used"], 4, "{}", usage);
+    assert_eq!(usage["data"]["roles"]["used"], 6, "{}", usage);
+}
@@ -0,0 +1,107 @@
+//! Self-registration is switched on through `RUSTZEN_*`, which is read once per process,
+//! so these run in their own test binary with a fake SMTP relay for the verification mail.
+
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::{TestApp, fake_relay, mail_text};
+use serde_json::json;
+
+/// The `token=` value of the verification link in a relayed message.
+fn mailed_token(transcript: &str) -> String {
+    let text = mail_text(transcript);
+    let (_, token) = text.split_once("?token=").expect("verification link");
+    token.split_whitespace().next().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn registered_users_verify_their_email_and_wait_for_approval() {
+    let (port, mut relay) = fake_relay().await;
+    // SAFETY: set before any other thread reads the environment or the config loads.
+    unsafe {
+        std::env::set_var("RUSTZEN_REGISTRATION_ENABLED", "true");
+        std::env::set_var("RUSTZEN_REGISTRATION_VERIFY_URL", "https://admin.example.com/verify");
+        std::env::set_var("RUSTZEN_SMTP_HOST", "127.0.0.1");
+        std::env::set_var("RUSTZEN_SMTP_TLS", "none");
+        std::env::set_var("RUSTZEN_SMTP_PORT", port.to_string());
+        std::env::set_var("RUSTZEN_SMTP_FROM", "noreply@example.com");
+    }
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+    let register = |username: &str, password: &str| {
+        let body = json!({
+            "username": username,
+            "email": format!("{}@example.com", username),
+            "password": password,
+        });
+        app.request(Method::POST, "/api/auth/register", None, Some(body))
+    };
+
+    let (status, body) = register("x", "short").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+    assert_eq!(body["data"][0]["field"], "username");
+    assert_eq!(body["data"][1]["field"], "password");
+    let (status, body) = register("ivy", "ivy-password").await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let transcript = relay.recv().await.expect("relay");
+    assert!(transcript.contains("RCPT TO:<ivy@example.com>"), "{}", transcript);
+
+    let login = json!({ "username": "ivy", "password": "ivy-password" });
+    let (_, body) = app.request(Method::POST, "/api/auth/login", None, Some(login.clone())).await;
+    assert_eq!(body["code"], 10005, "{}", body);
+    let (status, body) = app.get("/api/system/registrations", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["total"], 1);
+    let ivy = body["data"][0]["id"].as_i64().unwrap();
+    assert!(body["data"][0]["emailVerifiedAt"].is_null());
+    let roles: Vec<String> = sqlx::query_scalar(
+        "SELECT r.code FROM user_roles ur JOIN roles r ON r.id = ur.role_id WHERE ur.user_id = ?",
+    )
+    .bind(ivy)
+    .fetch_all(&app.pool)
+    .await
+    .unwrap();
+    assert_eq!(roles, ["viewer"]);
+
+    let approve = format!("/api/system/registrations/{}/approve", ivy);
+    let (status, _) = app.request(Method::POST, &approve, Some(&token), None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let verify = |token: String| {
+        let body = json!({ "token": token });
+        app.request(Method::POST, "/api/auth/register/verify", None, Some(body))
+    };
+    let (status, _) = verify("not-a-token".to_string()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let mailed = mailed_token(&transcript);
+    let (status, body) = verify(mailed.clone()).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (status, _) = verify(mailed).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (_, body) = app.get("/api/system/registrations?verified=true", &token).await;
+    assert_eq!(body["total"], 1);
+
+    let (status, body) = app.request(Method::POST, &approve, Some(&token), None).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    app.login("ivy", "ivy-password").await;
+
+    // The relay is gone, so the mail fails, but the registration still lands in the queue.
+    drop(relay);
+    let (status, body) = register("jack", "jack-password").await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (_, body) = app.get("/api/system/registrations", &token).await;
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["data"][0]["email"], "jack@example.com");
+    let jack = body["data"][0]["id"].as_i64().unwrap();
+    app.create_user("vera", "vera-password", &["viewer"]).await;
+    let viewer = app.login("vera", "vera-password").await;
+    let (status, body) = app.get("/api/system/registrations", &viewer).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"][0]["email"], "j***@example.com");
+    let reject = format!("/api/system/registrations/{}/reject", jack);
+    let (status, _) = app.request(Method::POST, &reject, Some(&token), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app.request(Method::POST, &reject, Some(&token), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (_, body) = app.get("/api/system/registrations", &token).await;
+    assert_eq!(body["total"], 0);
+}
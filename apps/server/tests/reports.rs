@@ -0,0 +1,62 @@
+//! Report storage and mail settings are read from `RUSTZEN_*` once per process, so these
+//! run in their own test binary with a temporary runtime root and a fake SMTP relay.
+
+mod common;
+
+use axum::http::{Method, StatusCode, header};
+use common::{TestApp, fake_relay};
+use http_body_util::BodyExt;
+use server::features::system::report::{service::ReportService, types::ReportTrigger};
+
+#[tokio::test]
+async fn generated_reports_are_mailed_listed_and_downloadable() {
+    let (port, mut relay) = fake_relay().await;
+    let runtime_root = std::env::temp_dir().join(format!("rustzen-reports-{}", std::process::id()));
+    // SAFETY: set before any other thread reads the environment or the config loads.
+    unsafe {
+        std::env::set_var("RUSTZEN_RUNTIME_ROOT", &runtime_root);
+        std::env::set_var("RUSTZEN_SMTP_HOST", "127.0.0.1");
+        std::env::set_var("RUSTZEN_SMTP_TLS", "none");
+        std::env::set_var("RUSTZEN_SMTP_PORT", port.to_string());
+        std::env::set_var("RUSTZEN_SMTP_FROM", "reports@example.com");
+        std::env::set_var("RUSTZEN_REPORT_RECIPIENTS", "ops@example.com,cfo@example.com");
+    }
+    let app = TestApp::spawn().await;
+    let token = app.admin_token().await;
+
+    let ids = ReportService::generate(app.config, &app.pool, ReportTrigger::Manual)
+        .await
+        .expect("reports");
+    assert_eq!(ids.len(), 2);
+    let transcript = relay.recv().await.expect("relay");
+    assert!(transcript.contains("RCPT TO:<ops@example.com>\r\nRCPT TO:<cfo@example.com>\r\n"));
+    assert!(transcript.contains(".xlsx\""), "{}", transcript);
+    assert!(transcript.contains(".pdf\""), "{}", transcript);
+
+    let (status, list) = app.get("/api/system/reports?format=pdf", &token).await;
+    assert_eq!(status, StatusCode::OK, "{}", list);
+    assert_eq!(list["total"], 1, "{}", list);
+    let report = &list["data"][0];
+    assert_eq!(report["triggerType"], "manual");
+    assert_eq!(report["recipients"], serde_json::json!(["ops@example.com", "cfo@example.com"]));
+    assert!(report["emailedAt"].is_string(), "{}", report);
+    assert!(report.get("storedName").is_none());
+
+    let (status, body) = app.get("/api/system/reports?format=csv", &token).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+
+    let uri = format!("/api/system/reports/{}/download", report["id"]);
+    let response = app.response(Method::GET, &uri, Some(&token), None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/pdf");
+    let disposition = response.headers()[header::CONTENT_DISPOSITION].to_str().unwrap();
+    assert!(disposition.starts_with("attachment; filename=report_"), "{}", disposition);
+    let bytes = response.into_body().collect().await.expect("body").to_bytes();
+    assert!(bytes.starts_with(b"%PDF-1.4"));
+    assert_eq!(bytes.len() as u64, report["sizeBytes"].as_u64().unwrap());
+
+    let (status, _) = app.get("/api/system/reports/999999/download", &token).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let _ = std::fs::remove_dir_all(runtime_root);
+}
@@ -0,0 +1,47 @@
+//! Single sessions are read from `RUSTZEN_*` once per process, so they run in their own
+//! test binary with the setting switched on before the config loads.
+
+mod common;
+
+use axum::http::{Method, StatusCode};
+use common::TestApp;
+use http_body_util::BodyExt;
+use std::time::Duration;
+
+#[tokio::test]
+async fn a_new_login_revokes_the_previous_session_and_tells_its_device() {
+    // SAFETY: set before any other thread reads the environment or the config loads.
+    unsafe { std::env::set_var("RUSTZEN_SINGLE_SESSION", "true") };
+    let app = TestApp::spawn().await;
+    app.create_user("solo", "solo-password", &["viewer"]).await;
+    app.create_user("other", "other-password", &["viewer"]).await;
+    let laptop = app.login("solo", "solo-password").await;
+    let other = app.login("other", "other-password").await;
+
+    let response = app.response(Method::GET, "/api/auth/session/events", Some(&laptop), None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut events = response.into_body();
+
+    let phone = app.login("solo", "solo-password").await;
+    let text = loop {
+        let frame = tokio::time::timeout(Duration::from_secs(5), events.frame())
+            .await
+            .expect("an event within 5s")
+            .expect("open stream")
+            .expect("frame");
+        let text = String::from_utf8(frame.into_data().expect("data frame").to_vec()).unwrap();
+        if !text.starts_with(':') {
+            break text;
+        }
+    };
+    let data = text.strip_prefix("event: logged_in_elsewhere\ndata: ").expect(&text);
+    let event: serde_json::Value = serde_json::from_str(data.trim()).unwrap();
+    assert!(event["replacedAt"].is_string(), "{}", event);
+
+    let (status, _) = app.get("/api/auth/me", &laptop).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = app.get("/api/auth/me", &phone).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app.get("/api/auth/me", &other).await;
+    assert_eq!(status, StatusCode::OK);
+}
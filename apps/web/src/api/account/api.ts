@@ -16,4 +16,103 @@ export const accountAPI = {
             params: data,
         });
     },
+
+    sendVerificationCode: () => {
+        return apiRequest<void>({
+            url: "/api/account/verification-code",
+            method: "POST",
+        });
+    },
+
+    /** Texts a code to a phone number before binding it. */
+    sendBindPhoneCode: (phone: string) => {
+        return apiRequest<void>({
+            url: "/api/account/phone/code",
+            method: "POST",
+            params: { phone },
+        });
+    },
+
+    bindPhone: (data: Account.BindPhoneRequest) => {
+        return apiRequest<Auth.UserInfoResponse, Account.BindPhoneRequest>({
+            url: "/api/account/phone",
+            method: "PUT",
+            params: data,
+        });
+    },
+
+    unbindPhone: (verificationCode?: string) => {
+        return apiRequest<Auth.UserInfoResponse>({
+            url: "/api/account/phone/unbind",
+            method: "POST",
+            params: { verificationCode },
+        });
+    },
+
+    /** Sets the timezone for dashboard trends and CSV exports; null restores the default. */
+    updateTimezone: (timezone: string | null) => {
+        return apiRequest<Auth.UserInfoResponse>({
+            url: "/api/account/timezone",
+            method: "PUT",
+            params: { timezone },
+        });
+    },
+
+    identities: () => {
+        return apiRequest<Account.LinkedIdentity[]>({ url: "/api/account/identities" });
+    },
+
+    /** Starts linking a provider account; the redirect's code and state go to linkIdentity. */
+    authorizeIdentity: (provider: Auth.OAuthProvider) => {
+        return apiRequest<Auth.OAuthAuthorizeResponse>({
+            url: `/api/account/identities/${provider}/authorize`,
+        });
+    },
+
+    linkIdentity: (data: Auth.OAuthCallbackRequest) => {
+        return apiRequest<Account.LinkedIdentity[], Auth.OAuthCallbackRequest>({
+            url: "/api/account/identities",
+            method: "POST",
+            params: data,
+        });
+    },
+
+    unlinkIdentity: (provider: Auth.OAuthProvider) => {
+        return apiRequest<Account.LinkedIdentity[]>({
+            url: `/api/account/identities/${provider}/unbind`,
+            method: "POST",
+        });
+    },
+
+    /** Address and user agent pairs this account signed in from, most recent first. */
+    devices: () => {
+        return apiRequest<Account.LoginDevice[]>({ url: "/api/account/devices" });
+    },
+
+    notifications: async (params: Account.NotificationQuery) => {
+        const res = await apiRequest<Account.Notification[], Account.NotificationQuery>({
+            url: "/api/account/notifications",
+            params,
+            raw: true,
+        });
+        return {
+            data: res.data,
+            total: res.total ?? 0,
+            success: true,
+        };
+    },
+
+    readNotification: (id: number) => {
+        return apiRequest<void>({
+            url: `/api/account/notifications/${id}/read`,
+            method: "POST",
+        });
+    },
+
+    readAllNotifications: () => {
+        return apiRequest<number>({
+            url: "/api/account/notifications/read",
+            method: "POST",
+        });
+    },
 };
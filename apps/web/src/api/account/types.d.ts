@@ -8,5 +8,46 @@ declare namespace Account {
         currentPassword: string;
         newPassword: string;
         confirmPassword: string;
+        verificationCode?: string; // 绑定手机且启用短信时必填
+    }
+
+    interface BindPhoneRequest {
+        phone: string;
+        code: string; // 发送到新手机号的验证码
+        verificationCode?: string; // 更换已绑定手机时，发送到原手机号的验证码
+    }
+
+    interface LinkedIdentity {
+        provider: Auth.OAuthProvider;
+        subject: string;
+        displayName?: string;
+        createdAt: string;
+        lastLoginAt?: string;
+    }
+
+    interface Notification {
+        id: number;
+        /** 例如 login_alert */
+        kind: string;
+        title: string;
+        body: string;
+        data?: Record<string, unknown>;
+        readAt?: string;
+        createdAt: string;
+    }
+
+    interface NotificationQuery {
+        current?: number;
+        pageSize?: number;
+        /** 只看未读 */
+        unread?: boolean;
+    }
+
+    interface LoginDevice {
+        ipAddress: string;
+        userAgent: string;
+        device: Auth.DeviceInfo;
+        firstSeenAt: string;
+        lastSeenAt: string;
     }
 }
@@ -10,6 +10,17 @@ declare namespace Api {
         message: string;
         data: T;
         total?: number;
+        // Paging metadata, present on list endpoints
+        current?: number;
+        pageSize?: number;
+        totalPages?: number;
+        hasNext?: boolean;
+    }
+
+    // One entry of a field validation error's `data` (code 10015)
+    interface FieldError {
+        field: string;
+        message: string;
     }
 
     // Page result type
@@ -27,10 +38,28 @@ declare namespace Api {
         [key: string]: BaseItem;
     }
 
+    // Sort direction for list queries
+    type SortOrder = "asc" | "desc";
+
     // Option type
     interface OptionItem<T = string | number> {
         label: string;
         value: T;
         [key: string]: unknown;
     }
+
+    // Query shared by every /options endpoint; id lists are comma-separated option values
+    interface OptionsParams {
+        q?: string;
+        limit?: number;
+        status?: number | "all"; // 默认只返回启用项
+        excludeIds?: string; // 例如编辑中的记录
+        includeIds?: string; // 已选中的值，始终排在最前
+    }
+
+    // Per-locale text of a dictionary label or menu name; locale is "en" or "zh-CN"
+    interface Translation {
+        locale: string;
+        text: string;
+    }
 }
@@ -1,4 +1,4 @@
-import { apiRequest } from "@/api/request";
+import { apiRequest, apiStream } from "@/api/request";
 
 export const authAPI = {
     login: (data: Auth.LoginRequest) => {
@@ -9,6 +9,70 @@ export const authAPI = {
         });
     },
 
+    /** Texts a login code; unbound numbers get the same empty success. */
+    sendSmsLoginCode: (phone: string) => {
+        return apiRequest<void>({
+            url: "/api/auth/login/sms/code",
+            method: "POST",
+            params: { phone },
+        });
+    },
+
+    smsLogin: (data: Auth.SmsLoginRequest) => {
+        return apiRequest<Auth.LoginResponse, Auth.SmsLoginRequest>({
+            url: "/api/auth/login/sms",
+            method: "POST",
+            params: data,
+        });
+    },
+
+    /** Login providers switched on at the server. */
+    oauthProviders: () => {
+        return apiRequest<Auth.OAuthProvider[]>({ url: "/api/auth/oauth/providers" });
+    },
+
+    /** Starts scan-to-login; open the returned URL and keep the state. */
+    oauthAuthorize: (provider: Auth.OAuthProvider) => {
+        return apiRequest<Auth.OAuthAuthorizeResponse>({
+            url: `/api/auth/oauth/${provider}/authorize`,
+        });
+    },
+
+    /** Signs in with the code and state from the provider redirect. */
+    oauthLogin: (data: Auth.OAuthCallbackRequest) => {
+        return apiRequest<Auth.LoginResponse, Auth.OAuthCallbackRequest>({
+            url: "/api/auth/oauth/callback",
+            method: "POST",
+            params: data,
+        });
+    },
+
+    /** Self-registers a pending account; a verification mail is sent to the email. */
+    register: (data: Auth.RegisterRequest) => {
+        return apiRequest<void, Auth.RegisterRequest>({
+            url: "/api/auth/register",
+            method: "POST",
+            params: data,
+        });
+    },
+
+    verifyEmail: (token: string) => {
+        return apiRequest<void>({
+            url: "/api/auth/register/verify",
+            method: "POST",
+            params: { token },
+        });
+    },
+
+    /** Applies an email change with the token mailed to the new address. */
+    confirmEmail: (token: string) => {
+        return apiRequest<void>({
+            url: "/api/auth/confirm-email",
+            method: "POST",
+            params: { token },
+        });
+    },
+
     logout: () => {
         return apiRequest<void>({ url: "/api/auth/logout" });
     },
@@ -16,4 +80,45 @@ export const authAPI = {
     me: () => {
         return apiRequest<Auth.UserInfoResponse>({ url: "/api/auth/me" });
     },
+
+    /** Checks a capability against the current user's live grants. */
+    can: (perm: string) => {
+        return apiRequest<Auth.CapabilityCheck>({ url: "/api/auth/me/can", params: { perm } });
+    },
+
+    /** Accepts the current policy versions; returns the ones still pending. */
+    acceptPolicies: (documentIds: number[]) => {
+        return apiRequest<Policy.Pending[]>({
+            url: "/api/auth/me/consent",
+            method: "POST",
+            params: { documentIds },
+        });
+    },
+
+    /** Confirms the password or an SMS code again before deleting users or changing roles. */
+    reauth: (data: Auth.ReauthRequest) => {
+        return apiRequest<Auth.ReauthResponse, Auth.ReauthRequest>({
+            url: "/api/auth/reauth",
+            method: "POST",
+            params: data,
+        });
+    },
+
+    /** Issues a CSRF token and sets the readable CSRF cookie (cookie session mode). */
+    csrf: () => {
+        return apiRequest<string>({ url: "/api/auth/csrf" });
+    },
+
+    /** Waits until a newer login replaces this session (single-session mode), or signal aborts. */
+    sessionEvents: (onReplaced: (event: Auth.SessionReplaced) => void, signal: AbortSignal) => {
+        return apiStream({
+            url: "/api/auth/session/events",
+            signal,
+            onEvent: (event, data) => {
+                if (event === "logged_in_elsewhere") {
+                    onReplaced(JSON.parse(data) as Auth.SessionReplaced);
+                }
+            },
+        });
+    },
 };
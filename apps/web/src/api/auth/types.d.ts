@@ -4,8 +4,36 @@ declare namespace Auth {
         password: string;
     }
 
+    // 短信验证码登录，手机号为国际格式，如 +8613812345678
+    interface SmsLoginRequest {
+        phone: string;
+        code: string;
+    }
+
+    // 企业扫码登录：wecom（企业微信）、dingtalk（钉钉）、feishu（飞书）
+    type OAuthProvider = "wecom" | "dingtalk" | "feishu";
+
+    interface OAuthAuthorizeResponse {
+        provider: OAuthProvider;
+        url: string; // 扫码页，完成后携带 code 与 state 跳回 RUSTZEN_OAUTH_REDIRECT_URL
+        state: string;
+    }
+
+    interface OAuthCallbackRequest {
+        state: string;
+        code: string;
+    }
+
+    // 自助注册（需服务端开启 RUSTZEN_REGISTRATION_ENABLED）
+    interface RegisterRequest {
+        username: string;
+        email: string;
+        password: string;
+        realName?: string;
+    }
+
     interface LoginResponse {
-        token: string;
+        token?: string; // 会话 Cookie 模式下不返回，由 HttpOnly Cookie 携带
         userInfo: UserInfoResponse;
     }
 
@@ -13,10 +41,56 @@ declare namespace Auth {
         id: number;
         username: string;
         email?: string;
+        phone?: string; // 已脱敏
         realName?: string;
         avatarUrl?: string;
         permissions: string[];
         isSystem: boolean;
+        timezone?: string; // IANA 时区，未设置时使用服务端默认
+        menus?: MenuInfo[];
+        pendingPolicies: Policy.Pending[]; // 全部同意后为空
+        pendingEmail?: string; // 等待确认的新邮箱
     }
 
+    // A directory, page, link or iframe menu the user can open
+    interface MenuInfo {
+        code: string;
+        name: string;
+        menuType: number; // 1 directory, 2 page, 4 external link, 5 iframe
+        linkUrl?: string | null;
+        visible: boolean; // false: routable, but left out of the sidebar
+        keepAlive: boolean;
+    }
+
+    // Result of a "can I / can user X" capability check
+    interface CapabilityCheck {
+        userId: number;
+        perm: string;
+        allowed: boolean;
+        grantedBy?: string | null; // the exact code, a prefix wildcard, or "*"
+        declared: boolean; // false usually means a typo in `perm`
+    }
+
+    // Readable name of a stored user agent, e.g. "Chrome on Windows 10"
+    type DeviceType = "desktop" | "mobile" | "api-client";
+
+    interface DeviceInfo {
+        name: string;
+        deviceType: DeviceType;
+    }
+
+    // Either the password or a critical-action SMS code; the response replaces the token
+    interface ReauthRequest {
+        password?: string;
+        verificationCode?: string;
+    }
+
+    interface ReauthResponse {
+        token?: string; // left out in cookie session mode
+    }
+
+    // Sent to the old device when a newer login of the same account replaces its session
+    interface SessionReplaced {
+        replacedAt: string;
+    }
 }
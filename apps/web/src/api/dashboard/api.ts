@@ -1,4 +1,4 @@
-import { apiRequest } from "@/api/request";
+import { apiRequest, apiStream } from "@/api/request";
 
 export const dashboardAPI = {
     stats: () => {
@@ -9,14 +9,53 @@ export const dashboardAPI = {
             url: "/api/dashboard/health",
         });
     },
-    metrics: () => {
-        return apiRequest<Dashboard.SystemMetricsData>({
+    metrics: (params?: Dashboard.WindowParams) => {
+        return apiRequest<Dashboard.SystemMetricsData, Dashboard.WindowParams>({
             url: "/api/dashboard/metrics",
+            params,
         });
     },
-    trends: () => {
-        return apiRequest<Dashboard.UserActivityChart>({
+    /** 本实例最近一小时最慢与出错最多的接口 */
+    endpoints: (params?: Dashboard.EndpointsParams) => {
+        return apiRequest<Dashboard.Endpoints, Dashboard.EndpointsParams>({
+            url: "/api/dashboard/endpoints",
+            params,
+        });
+    },
+    /** 本实例的 CPU、内存、磁盘与负载历史，每 30 秒采样一次 */
+    systemMetrics: (params?: Dashboard.HostMetricsParams) => {
+        return apiRequest<Dashboard.HostMetrics, Dashboard.HostMetricsParams>({
+            url: "/api/dashboard/system-metrics",
+            params,
+        });
+    },
+    trends: (params?: Dashboard.WindowParams) => {
+        return apiRequest<Dashboard.UserActivityChart, Dashboard.WindowParams>({
             url: "/api/dashboard/trends",
+            params,
+        });
+    },
+    top: (params?: Dashboard.TopParams) => {
+        return apiRequest<Dashboard.Top, Dashboard.TopParams>({
+            url: "/api/dashboard/top",
+            params,
+        });
+    },
+    /** 定时推送统计、健康与性能指标快照，直到 signal 中止 */
+    stream: (
+        params: Dashboard.StreamParams,
+        onSnapshot: (snapshot: Dashboard.Snapshot) => void,
+        onError: (message: string) => void,
+        signal: AbortSignal,
+    ) => {
+        return apiStream({
+            url: "/api/dashboard/stream",
+            params,
+            signal,
+            onEvent: (event, data) => {
+                if (event === "snapshot") onSnapshot(JSON.parse(data) as Dashboard.Snapshot);
+                if (event === "error") onError(data);
+            },
         });
     },
 };
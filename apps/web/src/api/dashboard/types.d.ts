@@ -1,4 +1,101 @@
 declare namespace Dashboard {
+    // 统计时间窗口（天数 7/30/90，时区默认取用户偏好，其次服务端配置）
+    interface WindowParams {
+        days?: 7 | 30 | 90;
+        timezone?: string;
+    }
+
+    // Top 排行查询
+    interface TopParams {
+        days?: 7 | 30 | 90;
+        limit?: number;
+    }
+
+    // 接口耗时排行查询
+    interface EndpointsParams {
+        limit?: number; // 每个列表的条数（1-50，默认 10）
+    }
+
+    // 耗时直方图的一个区间
+    interface LatencyBucket {
+        leMs: number | null; // 区间上限（毫秒），最后一个区间为 null
+        count: number;
+    }
+
+    // 单个路由最近一小时的耗时与错误
+    interface RouteLatency {
+        method: string;
+        route: string; // 路由模板
+        requests: number;
+        errors: number; // 状态码 >= 400 的请求数
+        errorRate: number; // 0-1
+        avgMs: number;
+        maxMs: number;
+        p50Ms: number; // 分位数取所在区间的上限
+        p95Ms: number;
+        p99Ms: number;
+        histogram: LatencyBucket[];
+    }
+
+    // 最慢与出错最多的接口
+    interface Endpoints {
+        windowMinutes: number;
+        slowest: RouteLatency[]; // 按 p95 降序
+        mostErrors: RouteLatency[]; // 按错误率降序
+    }
+
+    // 主机指标查询
+    interface HostMetricsParams {
+        minutes?: number; // 历史分钟数（1-60，默认 60）
+    }
+
+    // 主机资源采样
+    interface HostSample {
+        sampledAt: string;
+        cpuPercent: number; // CPU 使用率（0-100）
+        memoryTotal: number;
+        memoryUsed: number;
+        swapTotal: number;
+        swapUsed: number;
+        diskTotal: number;
+        diskUsed: number;
+        loadOne: number; // 1/5/15 分钟平均负载，Windows 下为 0
+        loadFive: number;
+        loadFifteen: number;
+    }
+
+    // 主机指标历史
+    interface HostMetrics {
+        intervalSecs: number; // 采样间隔秒数
+        current: HostSample | null; // 启动后首次采样前为 null
+        history: HostSample[]; // 按时间升序
+    }
+
+    // 实时推送参数
+    interface StreamParams {
+        interval?: number; // 推送间隔秒数（5-300，默认 15）
+        days?: 7 | 30 | 90; // 性能指标时间窗口
+    }
+
+    // 实时推送的快照
+    interface Snapshot {
+        stats: Stats;
+        health: SystemHealth;
+        metrics: SystemMetricsData;
+    }
+
+    interface TopItem {
+        name: string;
+        count: number;
+    }
+
+    // 操作、用户与错误接口排行
+    interface Top {
+        topActions: TopItem[];
+        topUsers: TopItem[];
+        topErrorEndpoints: TopItem[];
+    }
+
     // 顶部统计卡片
     interface Stats {
         totalUsers: number; // 总用户数
@@ -26,6 +123,24 @@ declare namespace Dashboard {
         avgResponseTime: number;
         errorRate: number;
         totalRequests: number;
+        slowRequests: number; // since server start
+        slowQueries: number;
+        dbPool: DbPoolHealth;
+    }
+
+    // 数据库连接池状态
+    interface PoolHealth {
+        size: number;
+        idle: number;
+        inUse: number;
+        maxConnections: number;
+        closed: boolean;
+    }
+
+    interface DbPoolHealth {
+        primary: PoolHealth;
+        replica: PoolHealth | null; // 未配置只读副本时为 null
+        connectRetries: number; // 启动时的连接重试次数
     }
 
     // 用户活动统计
@@ -4,3 +4,4 @@ export { authAPI } from "./auth/api";
 export { dashboardAPI } from "./dashboard/api";
 export { manageAPI } from "./manage";
 export { systemAPI } from "./system";
+export { workflowAPI } from "./workflow/api";
@@ -1,4 +1,5 @@
-import { apiRequest } from "@/api/request";
+import { apiDownload, apiRequest } from "@/api/request";
+import { CONFIRMATION_HEADER } from "@/api/system/confirmation/api";
 
 /**
  * Dictionary management API service.
@@ -36,9 +37,10 @@ export const dictAPI = {
             method: "DELETE",
         });
     },
-    options: () => {
-        return apiRequest<Api.OptionItem<string>[]>({
+    options: (params?: Api.OptionsParams & { dictType?: string }) => {
+        return apiRequest<Api.OptionItem<string>[], Api.OptionsParams & { dictType?: string }>({
             url: "/api/manage/dicts/options",
+            params,
         });
     },
     status: (id: number, status: number) => {
@@ -53,4 +55,36 @@ export const dictAPI = {
             url: `/api/manage/dicts/type/${type}`,
         });
     },
+    reorder: (type: string, data: Dict.ReorderRequest) => {
+        return apiRequest<void, Dict.ReorderRequest>({
+            url: `/api/manage/dicts/type/${type}/reorder`,
+            method: "PUT",
+            params: data,
+        });
+    },
+    purgeReport: (id: number) => {
+        return apiRequest<Purge.Report>({ url: `/api/manage/dicts/${id}/purge` });
+    },
+    /** `confirmation` comes from `confirmationAPI.create` for this dictionary item's purge. */
+    purge: (id: number, confirmation: string) => {
+        return apiRequest<Purge.Report>({
+            url: `/api/manage/dicts/${id}/purge`,
+            method: "DELETE",
+            headers: { [CONFIRMATION_HEADER]: confirmation },
+        });
+    },
+    translations: (id: number) => {
+        return apiRequest<Api.Translation[]>({ url: `/api/manage/dicts/${id}/translations` });
+    },
+    /** Replaces every translation of the label; locales left out lose theirs. */
+    setTranslations: (id: number, translations: Api.Translation[]) => {
+        return apiRequest<Api.Translation[]>({
+            url: `/api/manage/dicts/${id}/translations`,
+            method: "PUT",
+            params: { translations },
+        });
+    },
+    export: (params?: Omit<Dict.QueryParams, "current" | "pageSize">) => {
+        return apiDownload({ url: "/api/manage/dicts/export", params });
+    },
 };
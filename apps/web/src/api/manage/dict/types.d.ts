@@ -9,6 +9,7 @@ declare namespace Dict {
         status: number;
         description: string;
         sortOrder: number;
+        isDefault: boolean; // 该类型的默认项，每个类型至多一个
         updatedAt: string;
     }
 
@@ -22,6 +23,8 @@ declare namespace Dict {
         status?: string;
         q?: string;
         limit?: number;
+        sortBy?: string;
+        sortOrder?: Api.SortOrder;
     }
 
     // 创建字典请求
@@ -32,6 +35,7 @@ declare namespace Dict {
         status?: number;
         description?: string;
         sortOrder?: number;
+        isDefault?: boolean;
     }
 
     // 更新字典请求
@@ -42,5 +46,11 @@ declare namespace Dict {
         status?: number;
         description?: string;
         sortOrder?: number;
+        isDefault?: boolean;
+    }
+
+    // 整体排序请求：按显示顺序列出该类型下的全部字典项 ID
+    interface ReorderRequest {
+        ids: number[];
     }
 }
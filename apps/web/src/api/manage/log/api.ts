@@ -1,4 +1,5 @@
 import { apiDownload, apiRequest } from "@/api/request";
+import { CONFIRMATION_HEADER } from "@/api/system/confirmation/api";
 
 /**
  * Log management API service.
@@ -16,7 +17,21 @@ export const logAPI = {
             success: true,
         };
     },
-    export: () => {
-        return apiDownload({ url: "/api/manage/logs/export" });
+    routeStats: (hours?: number) =>
+        apiRequest<Log.RouteStats[], { hours?: number }>({
+            url: "/api/manage/logs/routes",
+            params: { hours },
+        }),
+    slow: () => apiRequest<Log.SlowLog>({ url: "/api/manage/logs/slow" }),
+    /** `confirmation` comes from `confirmationAPI.create` for the same `olderThanDays`. */
+    purge: (olderThanDays: number, confirmation: string) =>
+        apiRequest<Log.PurgeResult, { olderThanDays: number }>({
+            url: "/api/manage/logs",
+            method: "DELETE",
+            params: { olderThanDays },
+            headers: { [CONFIRMATION_HEADER]: confirmation },
+        }),
+    export: (params?: Omit<Log.QueryParams, "current" | "pageSize">) => {
+        return apiDownload({ url: "/api/manage/logs/export", params });
     },
 };
@@ -10,11 +10,55 @@ declare namespace Log {
         data?: unknown;
         status: string;
         durationMs: number;
-        ipAddress: string;
+        ipAddress: string; // masked without system:privacy:view
         userAgent: string;
+        device?: Auth.DeviceInfo; // parsed from userAgent
+        route?: string; // matched route template, e.g. /api/system/users/{id}
+        resourceType?: string;
+        resourceId?: string;
+        statusCode?: number;
+        country?: string; // ISO code from the GeoIP database, login logs only
+        city?: string;
         createdAt: string;
     }
 
+    interface RouteStats {
+        method: string;
+        route: string;
+        requests: number;
+        errors: number;
+        avgDurationMs: number;
+        maxDurationMs: number;
+    }
+
+    interface SlowRequest {
+        method: string;
+        route: string;
+        statusCode: number;
+        durationMs: number;
+        createdAt: string;
+    }
+
+    interface SlowQuery {
+        summary: string;
+        durationMs: number;
+        createdAt: string;
+    }
+
+    // In-memory per server process; thresholds are 0 when a check is off.
+    interface SlowLog {
+        requestThresholdMs: number;
+        queryThresholdMs: number;
+        slowRequestsTotal: number;
+        slowQueriesTotal: number;
+        requests: SlowRequest[];
+        queries: SlowQuery[];
+    }
+
+    interface PurgeResult {
+        deleted: number;
+    }
+
     interface QueryParams {
         current?: number;
         pageSize?: number;
@@ -23,5 +67,9 @@ declare namespace Log {
         action?: string;
         description?: string;
         ipAddress?: string;
+        route?: string;
+        sortBy?: string;
+        sortOrder?: Api.SortOrder;
+        after?: number; // keyset cursor: last seen log id
     }
 }
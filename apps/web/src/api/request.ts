@@ -1,6 +1,12 @@
 import { appMessage } from "@/api/runtime";
 import { useAuthStore } from "@/store/useAuthStore";
 
+/** Sent with HTTP 202 when dual control filed the action for a second administrator. */
+const APPROVAL_PENDING_CODE = 10016;
+
+/** Sent with HTTP 403 when a sensitive action needs `authAPI.reauth` first. */
+export const REAUTH_REQUIRED_CODE = 10106;
+
 export function apiRequest<T, P = Api.BaseParams>(
     props: RequestOptions<P> & { raw: true },
 ): Promise<Api.ApiResponse<T>>;
@@ -17,6 +23,10 @@ export async function apiRequest<T, P = Api.BaseParams>(
     }
 
     const result = (await response.json()) as Api.ApiResponse<T>;
+    if (result.code === APPROVAL_PENDING_CODE) {
+        appMessage.info(result.message);
+        return Promise.reject(new Error(result.message));
+    }
     if (result.code !== 0) {
         appMessage.error(result.message || response.statusText || "Request failed");
         return Promise.reject(new Error(result.message || response.statusText));
@@ -49,9 +59,61 @@ export const apiDownload = async ({
     return downloadName;
 };
 
-const getAuthHeaders = (): Record<string, string> => {
+/**
+ * Reads a server-sent event stream and calls `onEvent` for each event until the server
+ * closes it or `signal` aborts the request.
+ */
+export const apiStream = async ({
+    onEvent,
+    ...options
+}: RequestOptions & { onEvent: (event: string, data: string) => void }): Promise<void> => {
+    const { url, config } = formatFetchConfig(options);
+    const response = await fetch(url, config);
+    if (!response.ok || !response.body) {
+        return handleError(response);
+    }
+    const reader = response.body.pipeThrough(new TextDecoderStream()).getReader();
+    let buffer = "";
+    for (;;) {
+        const { value, done } = await reader.read();
+        if (done) return;
+        buffer += value;
+        let end = buffer.indexOf("\n\n");
+        while (end >= 0) {
+            let event = "message";
+            const data: string[] = [];
+            for (const line of buffer.slice(0, end).split("\n")) {
+                if (line.startsWith("event:")) event = line.slice(6).trim();
+                if (line.startsWith("data:")) data.push(line.slice(5).trimStart());
+            }
+            if (data.length) onEvent(event, data.join("\n"));
+            buffer = buffer.slice(end + 2);
+            end = buffer.indexOf("\n\n");
+        }
+    }
+};
+
+const CSRF_COOKIE = "rustzen_csrf";
+
+/** Echoes the CSRF cookie, which cookie-session writes must send as `X-CSRF-Token`. */
+const readCsrfToken = () => {
+    const prefix = `${CSRF_COOKIE}=`;
+    const pair = document.cookie.split("; ").find((item) => item.startsWith(prefix));
+    return pair ? decodeURIComponent(pair.slice(prefix.length)) : undefined;
+};
+
+/** Bearer token, or the CSRF token in cookie session mode, for requests built by hand. */
+export const getAuthHeaders = (): Record<string, string> => {
+    const headers: Record<string, string> = {};
     const token = useAuthStore.getState().token;
-    return token ? { Authorization: `Bearer ${token}` } : {};
+    if (token) {
+        headers.Authorization = `Bearer ${token}`;
+    }
+    const csrfToken = readCsrfToken();
+    if (csrfToken) {
+        headers["X-CSRF-Token"] = csrfToken;
+    }
+    return headers;
 };
 
 export const apiUpload = async <T>(url: string, formData: FormData): Promise<T> => {
@@ -114,7 +176,8 @@ const handleError = async (error: unknown) => {
 
     const payload = await readErrorPayload(error);
     const requestUrl = error.url || "";
-    const message = payload?.message || error.statusText || "Request failed";
+    const message =
+        withFieldErrors(payload?.message, payload?.data) || error.statusText || "Request failed";
 
     if (error.status === 401) {
         if (requestUrl.includes("/api/auth/login")) {
@@ -142,14 +205,23 @@ const handleError = async (error: unknown) => {
     return Promise.reject(error);
 };
 
-const readErrorPayload = async (response: Response): Promise<{ message?: string } | null> => {
+type ErrorPayload = { message?: string; data?: unknown };
+
+const readErrorPayload = async (response: Response): Promise<ErrorPayload | null> => {
     try {
-        return (await response.clone().json()) as { message?: string };
+        return (await response.clone().json()) as ErrorPayload;
     } catch {
         return null;
     }
 };
 
+/** Appends `field: message` pairs from a field validation error (code 10015). */
+const withFieldErrors = (message: string | undefined, data: unknown): string | undefined => {
+    if (!message || !Array.isArray(data)) return message;
+    const fields = (data as Api.FieldError[]).map((item) => `${item.field}: ${item.message}`);
+    return fields.length ? `${message} ${fields.join("; ")}` : message;
+};
+
 const buildQueryString = <P>(params?: P): string => {
     if (!params) return "";
     const searchParams = new URLSearchParams();
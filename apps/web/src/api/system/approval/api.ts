@@ -0,0 +1,31 @@
+import { apiRequest } from "@/api/request";
+
+/**
+ * Dual-control approval API service.
+ */
+export const approvalAPI = {
+    list: async (params: Approval.QueryParams) => {
+        const res = await apiRequest<Approval.Item[], Approval.QueryParams>({
+            url: "/api/system/approvals",
+            params,
+            raw: true,
+        });
+        return {
+            data: res.data,
+            total: res.total ?? 0,
+            success: true,
+        };
+    },
+    approve: (id: number) => {
+        return apiRequest<Approval.Item>({
+            url: `/api/system/approvals/${id}/approve`,
+            method: "POST",
+        });
+    },
+    reject: (id: number) => {
+        return apiRequest<Approval.Item>({
+            url: `/api/system/approvals/${id}/reject`,
+            method: "POST",
+        });
+    },
+};
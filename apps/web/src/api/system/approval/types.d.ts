@@ -0,0 +1,32 @@
+// ==================== 双人审批 ====================
+declare namespace Approval {
+    // 审批状态；approved 表示已批准并执行
+    type Status = "pending" | "approved" | "rejected" | "expired" | "failed";
+
+    // 需要审批的操作
+    type Action = "user.purge" | "role.delete" | "log.purge";
+
+    interface Item {
+        id: number;
+        action: Action;
+        params: Record<string, unknown>;
+        summary: string;
+        status: Status;
+        requestedBy: number;
+        requestedByName: string;
+        decidedBy?: number;
+        decidedByName?: string;
+        error?: string;
+        createdAt: string;
+        expiresAt: string;
+        decidedAt?: string;
+    }
+
+    // 查询参数
+    interface QueryParams {
+        current?: number;
+        pageSize?: number;
+        status?: Status | "all";
+        action?: Action;
+    }
+}
@@ -0,0 +1,17 @@
+import { apiRequest } from "@/api/request";
+
+/** Header the purge endpoints read the confirmation token from. */
+export const CONFIRMATION_HEADER = "X-Confirmation-Token";
+
+/**
+ * Confirmation tokens for irreversible actions.
+ */
+export const confirmationAPI = {
+    create: (target: Confirmation.Target) => {
+        return apiRequest<Confirmation.Item, Confirmation.Target>({
+            url: "/api/system/confirmations",
+            method: "POST",
+            params: target,
+        });
+    },
+};
@@ -0,0 +1,18 @@
+// ==================== 操作确认 ====================
+declare namespace Confirmation {
+    // 需要确认令牌的不可恢复操作
+    type Target =
+        | { action: "user.purge"; params: { userId: number } }
+        | { action: "role.purge"; params: { roleId: number } }
+        | { action: "menu.purge"; params: { menuId: number } }
+        | { action: "dict.purge"; params: { dictId: number } }
+        | { action: "log.purge"; params: { olderThanDays: number } };
+
+    // 单次有效，两分钟后过期；放在 X-Confirmation-Token 请求头中
+    interface Item {
+        token: string;
+        action: Target["action"];
+        summary: string;
+        expiresAt: string;
+    }
+}
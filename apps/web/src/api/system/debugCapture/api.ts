@@ -0,0 +1,32 @@
+import { apiRequest } from "@/api/request";
+
+/**
+ * Debug capture API service.
+ */
+export const debugCaptureAPI = {
+    list: async (params: DebugCapture.QueryParams) => {
+        const res = await apiRequest<DebugCapture.Item[], DebugCapture.QueryParams>({
+            url: "/api/system/debug-captures",
+            params,
+            raw: true,
+        });
+        return {
+            data: res.data,
+            total: res.total ?? 0,
+            success: true,
+        };
+    },
+    create: (data: DebugCapture.CreateRequest) => {
+        return apiRequest<number, DebugCapture.CreateRequest>({
+            url: "/api/system/debug-captures",
+            method: "POST",
+            params: data,
+        });
+    },
+    delete: (id: number) => {
+        return apiRequest<void>({
+            url: `/api/system/debug-captures/${id}`,
+            method: "DELETE",
+        });
+    },
+};
@@ -0,0 +1,32 @@
+// ==================== 调试抓取 ====================
+declare namespace DebugCapture {
+    interface Item {
+        id: number;
+        userId?: number;
+        username?: string;
+        /** 路由模板，例如 /api/system/users/{id} */
+        route?: string;
+        reason?: string;
+        expiresAt: string;
+        /** 是否仍在抓取（未过期） */
+        active: boolean;
+        createdBy: number;
+        createdAt: string;
+    }
+
+    interface QueryParams {
+        current?: number;
+        pageSize?: number;
+        /** 仅返回未过期的抓取 */
+        active?: boolean;
+    }
+
+    interface CreateRequest {
+        /** 用户与路由至少填写一项，两者都填时需同时匹配 */
+        userId?: number;
+        route?: string;
+        /** 抓取时长（分钟），默认 30，最长 1440 */
+        minutes?: number;
+        reason?: string;
+    }
+}
@@ -0,0 +1,36 @@
+import { apiRequest } from "@/api/request";
+
+/**
+ * Background export job API service.
+ */
+export const exportJobAPI = {
+    list: async (params: ExportJob.QueryParams) => {
+        const res = await apiRequest<ExportJob.Item[], ExportJob.QueryParams>({
+            url: "/api/system/exports",
+            params,
+            raw: true,
+        });
+        return {
+            data: res.data,
+            total: res.total ?? 0,
+            success: true,
+        };
+    },
+    get: (id: number) => {
+        return apiRequest<ExportJob.Item>({
+            url: `/api/system/exports/${id}`,
+        });
+    },
+    create: (data: ExportJob.CreateRequest) => {
+        return apiRequest<ExportJob.Item, ExportJob.CreateRequest>({
+            url: "/api/system/exports",
+            method: "POST",
+            params: data,
+        });
+    },
+    link: (id: number) => {
+        return apiRequest<ExportJob.Link>({
+            url: `/api/system/exports/${id}/link`,
+        });
+    },
+};
@@ -0,0 +1,45 @@
+// ==================== 后台导出 ====================
+declare namespace ExportJob {
+    type Resource = "logs" | "users" | "dicts";
+    type Status = "pending" | "running" | "completed" | "failed" | "expired";
+
+    interface Item {
+        id: number;
+        resource: Resource;
+        /** 对应列表接口的查询参数 */
+        filters: Record<string, unknown>;
+        /** 导出模板选定的列，为空时导出全部列 */
+        columns?: string[];
+        status: Status;
+        /** 任务开始后才有 */
+        totalRows?: number;
+        rowsDone: number;
+        /** 下载文件名 */
+        fileName: string;
+        sizeBytes?: number;
+        errorMessage?: string;
+        createdAt: string;
+        startedAt?: string;
+        finishedAt?: string;
+        /** 文件删除时间 */
+        expiresAt?: string;
+    }
+
+    interface CreateRequest {
+        resource: Resource;
+        filters?: Record<string, unknown>;
+        /** 导出模板，缺省时使用默认模板 */
+        templateId?: number;
+    }
+
+    interface QueryParams {
+        current?: number;
+        pageSize?: number;
+    }
+
+    /** 免登录下载链接，有效期 15 分钟 */
+    interface Link {
+        url: string;
+        expiresAt: string;
+    }
+}
@@ -0,0 +1,38 @@
+import { apiRequest } from "@/api/request";
+
+/**
+ * Export template API service
+ */
+export const exportTemplateAPI = {
+    list: (params?: ExportTemplate.QueryParams) => {
+        return apiRequest<ExportTemplate.Item[], ExportTemplate.QueryParams>({
+            url: "/api/system/export-templates",
+            params,
+        });
+    },
+    columns: () => {
+        return apiRequest<ExportTemplate.Columns[]>({
+            url: "/api/system/export-templates/columns",
+        });
+    },
+    create: (data: ExportTemplate.CreateRequest) => {
+        return apiRequest<number, ExportTemplate.CreateRequest>({
+            url: "/api/system/export-templates",
+            method: "POST",
+            params: data,
+        });
+    },
+    update: (id: number, data: ExportTemplate.UpdateRequest) => {
+        return apiRequest<void, ExportTemplate.UpdateRequest>({
+            url: `/api/system/export-templates/${id}`,
+            method: "PUT",
+            params: data,
+        });
+    },
+    delete: (id: number) => {
+        return apiRequest<void>({
+            url: `/api/system/export-templates/${id}`,
+            method: "DELETE",
+        });
+    },
+};
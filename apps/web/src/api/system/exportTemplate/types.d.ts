@@ -0,0 +1,40 @@
+// ==================== 导出模板 ====================
+declare namespace ExportTemplate {
+    type Resource = "logs" | "users" | "dicts";
+
+    interface Item {
+        id: number;
+        resource: Resource;
+        name: string;
+        /** 导出的列，按完整导出的列顺序排列 */
+        columns: string[];
+        /** 未指定模板的导出使用默认模板 */
+        isDefault: boolean;
+        createdAt: string;
+        updatedAt: string;
+    }
+
+    /** 某类数据可选的导出列 */
+    interface Columns {
+        resource: Resource;
+        columns: string[];
+    }
+
+    interface CreateRequest {
+        resource: Resource;
+        name: string;
+        columns: string[];
+        isDefault?: boolean;
+    }
+
+    /** 模板的数据类型不可修改 */
+    interface UpdateRequest {
+        name: string;
+        columns: string[];
+        isDefault?: boolean;
+    }
+
+    interface QueryParams {
+        resource?: Resource;
+    }
+}
@@ -0,0 +1,39 @@
+import { apiRequest } from "@/api/request";
+
+/**
+ * Feature flag management API service.
+ */
+export const featureFlagAPI = {
+    list: async (params: FeatureFlag.QueryParams) => {
+        const res = await apiRequest<FeatureFlag.Item[], FeatureFlag.QueryParams>({
+            url: "/api/system/feature-flags",
+            params,
+            raw: true,
+        });
+        return {
+            data: res.data,
+            total: res.total ?? 0,
+            success: true,
+        };
+    },
+    create: (data: FeatureFlag.CreateRequest) => {
+        return apiRequest<number, FeatureFlag.CreateRequest>({
+            url: "/api/system/feature-flags",
+            method: "POST",
+            params: data,
+        });
+    },
+    update: (id: number, data: FeatureFlag.UpdateRequest) => {
+        return apiRequest<void, FeatureFlag.UpdateRequest>({
+            url: `/api/system/feature-flags/${id}`,
+            method: "PUT",
+            params: data,
+        });
+    },
+    delete: (id: number) => {
+        return apiRequest<void>({
+            url: `/api/system/feature-flags/${id}`,
+            method: "DELETE",
+        });
+    },
+};
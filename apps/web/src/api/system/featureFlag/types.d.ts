@@ -0,0 +1,33 @@
+// ==================== 功能开关 ====================
+declare namespace FeatureFlag {
+    interface Item {
+        id: number;
+        key: string;
+        description?: string;
+        /** 数据库中保存的值 */
+        enabled: boolean;
+        /** RUSTZEN_FEATURE_FLAGS 强制的值 */
+        envOverride?: boolean;
+        /** 实际生效的值 */
+        effective: boolean;
+        createdAt: string;
+        updatedAt: string;
+    }
+
+    interface QueryParams {
+        current?: number;
+        pageSize?: number;
+        key?: string;
+    }
+
+    interface CreateRequest {
+        key: string;
+        description?: string;
+        enabled?: boolean;
+    }
+
+    interface UpdateRequest {
+        description?: string;
+        enabled: boolean;
+    }
+}
@@ -1,9 +1,55 @@
+import { approvalAPI } from "./approval/api";
+import { confirmationAPI } from "./confirmation/api";
+import { debugCaptureAPI } from "./debugCapture/api";
+import { exportJobAPI } from "./exportJob/api";
+import { exportTemplateAPI } from "./exportTemplate/api";
+import { featureFlagAPI } from "./featureFlag/api";
+import { infoAPI } from "./info/api";
+import { jwtKeyAPI } from "./jwtKey/api";
+import { licenseAPI } from "./license/api";
+import { loginAlertAPI } from "./loginAlert/api";
 import { menuAPI } from "./menu/api";
+import { permissionAPI } from "./permission/api";
+import { policyAPI } from "./policy/api";
+import { profileFieldAPI } from "./profileField/api";
+import { rbacAPI } from "./rbac/api";
+import { recycleBinAPI } from "./recycleBin/api";
+import { registrationAPI } from "./registration/api";
+import { reportAPI } from "./report/api";
 import { roleAPI } from "./role/api";
+import { savedFilterAPI } from "./savedFilter/api";
+import { seedAPI } from "./seed/api";
+import { serverLogAPI } from "./serverLog/api";
+import { tagAPI } from "./tag/api";
+import { usageAPI } from "./usage/api";
 import { userAPI } from "./user/api";
+import { webhookAPI } from "./webhook/api";
 
 export const systemAPI = {
     user: userAPI,
     role: roleAPI,
     menu: menuAPI,
+    permission: permissionAPI,
+    rbac: rbacAPI,
+    seed: seedAPI,
+    info: infoAPI,
+    webhook: webhookAPI,
+    jwtKey: jwtKeyAPI,
+    approval: approvalAPI,
+    confirmation: confirmationAPI,
+    usage: usageAPI,
+    license: licenseAPI,
+    featureFlag: featureFlagAPI,
+    tag: tagAPI,
+    profileField: profileFieldAPI,
+    recycleBin: recycleBinAPI,
+    policy: policyAPI,
+    registration: registrationAPI,
+    report: reportAPI,
+    exportJob: exportJobAPI,
+    exportTemplate: exportTemplateAPI,
+    savedFilter: savedFilterAPI,
+    serverLog: serverLogAPI,
+    loginAlert: loginAlertAPI,
+    debugCapture: debugCaptureAPI,
 };
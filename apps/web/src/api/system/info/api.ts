@@ -0,0 +1,18 @@
+import { apiRequest } from "@/api/request";
+
+/**
+ * System info panel API service.
+ */
+export const infoAPI = {
+    get: () => {
+        return apiRequest<SystemInfo.Info>({
+            url: "/api/system/info",
+        });
+    },
+    /** 各表行数、占用与空闲空间，会扫描整个数据库 */
+    dbStats: () => {
+        return apiRequest<SystemInfo.DbStats>({
+            url: "/api/system/db/stats",
+        });
+    },
+};
@@ -0,0 +1,49 @@
+// ==================== 系统信息 ====================
+declare namespace SystemInfo {
+    interface Build {
+        version: string;
+        commit: string;
+        builtAt?: string;
+        rustVersion: string;
+    }
+
+    interface Database {
+        poolSize: number;
+        poolIdle: number;
+        maxConnections: number;
+        pageCount: number;
+        pageSize: number;
+        sizeBytes: number;
+    }
+
+    interface DbTable {
+        name: string;
+        rowCount: number;
+        tableBytes: number;
+        indexBytes: number;
+        unusedBytes: number; // 页内未使用的字节，VACUUM 后回收
+        unusedRatio: number; // 0-1
+    }
+
+    interface DbStats {
+        pageSize: number;
+        pageCount: number;
+        sizeBytes: number;
+        freePages: number; // 删除后空出、VACUUM 前仍占用文件的页
+        freeRatio: number; // 0-1
+        autoVacuum: "none" | "full" | "incremental";
+        tables: DbTable[]; // 按占用降序
+    }
+
+    interface Caches {
+        permissionUsers: number;
+        dashboardEntries: number;
+    }
+
+    interface Info {
+        build: Build;
+        uptimeSecs: number;
+        database: Database;
+        caches: Caches;
+    }
+}
@@ -0,0 +1,18 @@
+import { apiRequest } from "@/api/request";
+
+/**
+ * JWT signing key API service.
+ */
+export const jwtKeyAPI = {
+    list: () => {
+        return apiRequest<JwtKey.Item[]>({
+            url: "/api/system/jwt-keys",
+        });
+    },
+    rotate: () => {
+        return apiRequest<string>({
+            url: "/api/system/jwt-keys/rotate",
+            method: "POST",
+        });
+    },
+};
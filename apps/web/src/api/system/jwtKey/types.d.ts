@@ -0,0 +1,16 @@
+// ==================== JWT 签名密钥 ====================
+declare namespace JwtKey {
+    // 密钥来源：环境配置或界面轮换
+    type Source = "config" | "rotated";
+
+    // 当前生效的密钥（不含密钥内容）
+    interface Item {
+        kid: string;
+        algorithm: string;
+        source: Source;
+        // 是否用于签发新令牌
+        current: boolean;
+        createdAt?: string;
+        retiredAt?: string;
+    }
+}
@@ -0,0 +1,12 @@
+import { apiRequest } from "@/api/request";
+
+/**
+ * License and edition API service.
+ */
+export const licenseAPI = {
+    get: () => {
+        return apiRequest<License.Info>({
+            url: "/api/system/license",
+        });
+    },
+};
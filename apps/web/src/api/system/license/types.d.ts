@@ -0,0 +1,21 @@
+// ==================== 许可证 ====================
+declare namespace License {
+    /** community 表示未配置许可证 */
+    type Status = "community" | "valid" | "expired" | "invalid";
+
+    interface Feature {
+        code: string;
+        enabled: boolean;
+    }
+
+    interface Info {
+        status: Status;
+        edition: string;
+        licensee?: string;
+        issuedAt?: string;
+        expiresAt?: string;
+        features: Feature[];
+        /** 许可证被拒绝的原因 */
+        error?: string;
+    }
+}
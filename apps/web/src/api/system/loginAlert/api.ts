@@ -0,0 +1,17 @@
+import { apiRequest } from "@/api/request";
+
+/**
+ * Suspicious login rule settings API service.
+ */
+export const loginAlertAPI = {
+    rules: () => {
+        return apiRequest<LoginAlert.Rule[]>({ url: "/api/system/login-alerts/rules" });
+    },
+    updateRule: (code: LoginAlert.RuleCode, data: LoginAlert.UpdateRuleRequest) => {
+        return apiRequest<LoginAlert.Rule[], LoginAlert.UpdateRuleRequest>({
+            url: `/api/system/login-alerts/rules/${code}`,
+            method: "PUT",
+            params: data,
+        });
+    },
+};
@@ -0,0 +1,15 @@
+// ==================== 可疑登录告警 ====================
+declare namespace LoginAlert {
+    type RuleCode = "new_device" | "impossible_travel" | "failures_then_success";
+
+    interface Rule {
+        code: RuleCode;
+        description: string;
+        enabled: boolean;
+        updatedAt: string;
+    }
+
+    interface UpdateRuleRequest {
+        enabled: boolean;
+    }
+}
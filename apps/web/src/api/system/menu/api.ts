@@ -1,4 +1,5 @@
 import { apiRequest } from "@/api/request";
+import { CONFIRMATION_HEADER } from "@/api/system/confirmation/api";
 
 /**
  * Menu management API service.
@@ -17,6 +18,13 @@ export const menuAPI = {
             success: true,
         };
     },
+    /** Direct children of one menu (0 for the top level), for trees too large to load whole. */
+    children: (parentId = 0) => {
+        return apiRequest<Menu.ChildItem[]>({
+            url: "/api/system/menus/children",
+            params: { parentId },
+        });
+    },
     create: (data: Menu.CreateRequest) => {
         return apiRequest<number, Menu.CreateRequest>({
             url: "/api/system/menus",
@@ -37,12 +45,35 @@ export const menuAPI = {
             method: "DELETE",
         });
     },
-    options: async () => {
-        const res = await apiRequest<Menu.OptionItem[]>({
+    options: async (params?: Api.OptionsParams) => {
+        const res = await apiRequest<Menu.OptionItem[], Api.OptionsParams>({
             url: "/api/system/menus/options",
+            params,
         });
         return [{ label: "Root", value: 0, code: "" }, ...res];
     },
+    purgeReport: (id: number) => {
+        return apiRequest<Purge.Report>({ url: `/api/system/menus/${id}/purge` });
+    },
+    /** `confirmation` comes from `confirmationAPI.create` for this menu's purge. */
+    purge: (id: number, confirmation: string) => {
+        return apiRequest<Purge.Report>({
+            url: `/api/system/menus/${id}/purge`,
+            method: "DELETE",
+            headers: { [CONFIRMATION_HEADER]: confirmation },
+        });
+    },
+    translations: (id: number) => {
+        return apiRequest<Api.Translation[]>({ url: `/api/system/menus/${id}/translations` });
+    },
+    /** Replaces every translation of the name; locales left out lose theirs. */
+    setTranslations: (id: number, translations: Api.Translation[]) => {
+        return apiRequest<Api.Translation[]>({
+            url: `/api/system/menus/${id}/translations`,
+            method: "PUT",
+            params: { translations },
+        });
+    },
 };
 
 function buildMenuTree(list: Menu.Item[], parentId = 0): Menu.Item[] {
@@ -15,12 +15,20 @@ declare namespace Menu {
         menuType: number;
         sortOrder: number;
         status: Status;
+        visible: boolean;
+        keepAlive: boolean;
+        linkUrl?: string | null; // target of link (4) and iframe (5) menus
         isSystem: boolean;
         createdAt: string;
         updatedAt: string;
         children?: Item[] | null;
     }
 
+    // 懒加载树的一层，hasChildren 表示可继续展开
+    interface ChildItem extends Omit<Item, "children"> {
+        hasChildren: boolean;
+    }
+
     // 查询参数
     interface QueryParams {
         current?: number;
@@ -38,6 +46,9 @@ declare namespace Menu {
         menuType: number;
         sortOrder: number;
         status: number;
+        visible?: boolean;
+        keepAlive?: boolean;
+        linkUrl?: string;
     }
 
     interface UpdateRequest {
@@ -47,6 +58,9 @@ declare namespace Menu {
         menuType: number;
         sortOrder: number;
         status: number;
+        visible?: boolean;
+        keepAlive?: boolean;
+        linkUrl?: string;
     }
 
     interface OptionItem extends Api.OptionItem<number> {
@@ -0,0 +1,12 @@
+import { apiRequest } from "@/api/request";
+
+/**
+ * Capability registry API service.
+ */
+export const permissionAPI = {
+    registry: () => {
+        return apiRequest<Permission.Registry>({
+            url: "/api/system/permissions/registry",
+        });
+    },
+};
@@ -0,0 +1,20 @@
+// ==================== 权限码注册表 ====================
+declare namespace Permission {
+    // undeclared: route code missing from the registry; missingMenu: no enabled menu;
+    // unrouted: declared but unused; stale: synced menu that is neither
+    type RegistryIssue = "undeclared" | "missingMenu" | "unrouted" | "stale";
+
+    interface RegistryEntry {
+        code: string;
+        name?: string | null;
+        declared: boolean;
+        routed: boolean;
+        menuStatus?: number | null;
+        issue?: RegistryIssue | null;
+    }
+
+    interface Registry {
+        entries: RegistryEntry[];
+        issueCount: number;
+    }
+}
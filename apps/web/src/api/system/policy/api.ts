@@ -0,0 +1,38 @@
+import { apiRequest } from "@/api/request";
+
+/**
+ * Policy document and consent API service.
+ */
+export const policyAPI = {
+    list: async (params: Policy.QueryParams) => {
+        const res = await apiRequest<Policy.Item[], Policy.QueryParams>({
+            url: "/api/system/policies",
+            params,
+            raw: true,
+        });
+        return {
+            data: res.data,
+            total: res.total ?? 0,
+            success: true,
+        };
+    },
+    publish: (data: Policy.PublishRequest) => {
+        return apiRequest<number, Policy.PublishRequest>({
+            url: "/api/system/policies",
+            method: "POST",
+            params: data,
+        });
+    },
+    consents: async (id: number, params: Policy.ConsentParams) => {
+        const res = await apiRequest<Policy.Consent[], Policy.ConsentParams>({
+            url: `/api/system/policies/${id}/consents`,
+            params,
+            raw: true,
+        });
+        return {
+            data: res.data,
+            total: res.total ?? 0,
+            success: true,
+        };
+    },
+};
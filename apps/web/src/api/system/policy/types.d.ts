@@ -0,0 +1,53 @@
+// ==================== 政策文档与同意记录 ====================
+declare namespace Policy {
+    type Kind = "terms" | "privacy";
+
+    interface Item {
+        id: number;
+        kind: Kind;
+        /** 同类文档内递增的版本号 */
+        version: number;
+        title: string;
+        content: string;
+        publishedBy?: number;
+        publishedByName?: string;
+        publishedAt: string;
+        /** 是否为该类型的最新版本（用户需要同意的版本） */
+        current: boolean;
+        consentCount: number;
+    }
+
+    // 当前用户尚未同意的最新版本
+    interface Pending {
+        id: number;
+        kind: Kind;
+        version: number;
+        title: string;
+    }
+
+    interface Consent {
+        id: number;
+        userId: number;
+        username?: string;
+        ipAddress: string;
+        userAgent: string;
+        acceptedAt: string;
+    }
+
+    interface QueryParams {
+        current?: number;
+        pageSize?: number;
+        kind?: Kind;
+    }
+
+    interface ConsentParams {
+        current?: number;
+        pageSize?: number;
+    }
+
+    interface PublishRequest {
+        kind: Kind;
+        title: string;
+        content: string;
+    }
+}
@@ -0,0 +1,32 @@
+import { apiRequest } from "@/api/request";
+
+/**
+ * Custom user profile field definitions API service.
+ */
+export const profileFieldAPI = {
+    list: () => {
+        return apiRequest<ProfileField.Item[]>({
+            url: "/api/system/profile-fields",
+        });
+    },
+    create: (data: ProfileField.CreateRequest) => {
+        return apiRequest<number, ProfileField.CreateRequest>({
+            url: "/api/system/profile-fields",
+            method: "POST",
+            params: data,
+        });
+    },
+    update: (id: number, data: ProfileField.UpdateRequest) => {
+        return apiRequest<void, ProfileField.UpdateRequest>({
+            url: `/api/system/profile-fields/${id}`,
+            method: "PUT",
+            params: data,
+        });
+    },
+    delete: (id: number) => {
+        return apiRequest<void>({
+            url: `/api/system/profile-fields/${id}`,
+            method: "DELETE",
+        });
+    },
+};
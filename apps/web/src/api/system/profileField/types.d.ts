@@ -0,0 +1,35 @@
+// ==================== 自定义用户资料字段 ====================
+declare namespace ProfileField {
+    type FieldType = "text" | "number" | "boolean" | "date" | "select";
+
+    interface Item {
+        id: number;
+        /** 小写字母、数字和 _，如 employee_id；即用户 profile 中的键 */
+        key: string;
+        label: string;
+        fieldType: FieldType;
+        required: boolean;
+        /** select 字段的可选值 */
+        options: string[];
+        sortOrder: number;
+        createdAt: string;
+        updatedAt: string;
+    }
+
+    interface CreateRequest {
+        key: string;
+        label: string;
+        fieldType: FieldType;
+        required?: boolean;
+        options?: string[];
+        sortOrder?: number;
+    }
+
+    /** 键和类型创建后不可修改 */
+    interface UpdateRequest {
+        label: string;
+        required: boolean;
+        options?: string[];
+        sortOrder?: number;
+    }
+}
@@ -0,0 +1,18 @@
+import { apiRequest } from "@/api/request";
+
+/**
+ * RBAC snapshot and environment diff API service.
+ */
+export const rbacAPI = {
+    snapshot: () => {
+        return apiRequest<Rbac.Snapshot>({ url: "/api/system/rbac/snapshot" });
+    },
+    /** A role export from `roleAPI.export` also works; its menus are then not compared. */
+    diff: (snapshot: Rbac.Snapshot | Role.ExportDoc) => {
+        return apiRequest<Rbac.Diff, Rbac.Snapshot | Role.ExportDoc>({
+            url: "/api/system/rbac/diff",
+            method: "POST",
+            params: snapshot,
+        });
+    },
+};
@@ -0,0 +1,39 @@
+// ==================== RBAC 环境对比 ====================
+declare namespace Rbac {
+    // 菜单快照，父级按编码引用
+    interface MenuItem {
+        code: string;
+        name: string;
+        parentCode?: string | null;
+        menuType: number;
+        status: number;
+    }
+
+    // 某个环境的菜单与角色授权快照
+    interface Snapshot {
+        version: number;
+        exportedAt?: string;
+        menus?: MenuItem[] | null;
+        roles: Role.ExportItem[];
+    }
+
+    interface MenuChange {
+        code: string;
+        changedFields: string[]; // name / parentCode / menuType / status
+    }
+
+    interface RoleChange {
+        code: string;
+        changedFields: string[]; // name / description / status
+        missingMenus: string[]; // 快照中有、当前环境缺少的授权
+        extraMenus: string[]; // 当前环境多出的授权
+    }
+
+    // missing：仅快照中存在；extra：仅当前环境存在
+    interface Diff {
+        inSync: boolean;
+        menusCompared: boolean;
+        menus: { missing: MenuItem[]; extra: string[]; changed: MenuChange[] };
+        roles: { missing: string[]; extra: string[]; changed: RoleChange[] };
+    }
+}
@@ -0,0 +1,23 @@
+import { apiRequest } from "@/api/request";
+
+/** Sent with HTTP 409 when a restore needs renames; `data` is `RecycleBin.Conflict[]`. */
+export const RESTORE_CONFLICT_CODE = 10205;
+
+/**
+ * Recycle bin API service: deleted users and roles, with what blocks their restore.
+ * Restoring goes through `userAPI.restore` and `roleAPI.restore`.
+ */
+export const recycleBinAPI = {
+    list: async (params: RecycleBin.QueryParams) => {
+        const res = await apiRequest<RecycleBin.Item[], RecycleBin.QueryParams>({
+            url: "/api/system/recycle-bin",
+            params,
+            raw: true,
+        });
+        return {
+            data: res.data,
+            total: res.total ?? 0,
+            success: true,
+        };
+    },
+};
@@ -0,0 +1,68 @@
+// ==================== 回收站 ====================
+declare namespace RecycleBin {
+    type Kind = "users" | "roles";
+
+    // 恢复时与在用记录冲突的唯一字段，及可直接使用的新值
+    interface Conflict {
+        field: "username" | "email" | "phone" | "name" | "code";
+        value: string;
+        suggestions: string[]; // 手机号没有建议值，使用 clearPhone 恢复
+    }
+
+    interface Item {
+        kind: Kind;
+        id: number;
+        name: string; // 用户名或角色名
+        deletedAt: string;
+        conflicts: Conflict[]; // 为空时可直接恢复
+    }
+
+    // 查询参数
+    interface QueryParams {
+        current?: number;
+        pageSize?: number;
+        kind: Kind;
+    }
+
+    // 恢复用户时的重命名，均可省略
+    interface RestoreUserRequest {
+        username?: string;
+        email?: string;
+        clearPhone?: boolean;
+    }
+
+    // 恢复角色时的重命名，均可省略
+    interface RestoreRoleRequest {
+        name?: string;
+        code?: string;
+    }
+}
+
+// ==================== 彻底删除 ====================
+declare namespace Purge {
+    // delete：随记录一并删除；keep：保留（审计记录）；block：存在时无法删除
+    type Action = "delete" | "keep" | "block";
+
+    interface Reference {
+        table: string;
+        column: string;
+        label: string;
+        action: Action;
+        rows: number;
+    }
+
+    interface File {
+        kind: "avatar" | "export";
+        name: string;
+    }
+
+    // 预览与执行返回相同的级联报告
+    interface Report {
+        kind: "user" | "role" | "menu" | "dict";
+        id: number;
+        name: string;
+        references: Reference[];
+        files: File[];
+        canPurge: boolean;
+    }
+}
@@ -0,0 +1,31 @@
+import { apiRequest } from "@/api/request";
+
+/**
+ * Self-registration approval queue API service.
+ */
+export const registrationAPI = {
+    list: async (params: Registration.QueryParams) => {
+        const res = await apiRequest<Registration.Item[], Registration.QueryParams>({
+            url: "/api/system/registrations",
+            params,
+            raw: true,
+        });
+        return {
+            data: res.data,
+            total: res.total ?? 0,
+            success: true,
+        };
+    },
+    approve: (id: number) => {
+        return apiRequest<void>({
+            url: `/api/system/registrations/${id}/approve`,
+            method: "POST",
+        });
+    },
+    reject: (id: number) => {
+        return apiRequest<void>({
+            url: `/api/system/registrations/${id}/reject`,
+            method: "POST",
+        });
+    },
+};
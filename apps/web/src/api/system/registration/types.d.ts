@@ -0,0 +1,19 @@
+// ==================== 自助注册审核 ====================
+declare namespace Registration {
+    interface Item {
+        id: number;
+        username: string;
+        email?: string;
+        realName?: string;
+        /** 邮箱验证时间，未验证时为空；审核通过前必须已验证 */
+        emailVerifiedAt?: string;
+        createdAt: string;
+    }
+
+    interface QueryParams {
+        current?: number;
+        pageSize?: number;
+        /** true 仅显示已验证邮箱，false 仅显示未验证 */
+        verified?: boolean;
+    }
+}
@@ -0,0 +1,25 @@
+import { apiDownload, apiRequest } from "@/api/request";
+
+/**
+ * Generated report API service.
+ */
+export const reportAPI = {
+    list: async (params: Report.QueryParams) => {
+        const res = await apiRequest<Report.Item[], Report.QueryParams>({
+            url: "/api/system/reports",
+            params,
+            raw: true,
+        });
+        return {
+            data: res.data,
+            total: res.total ?? 0,
+            success: true,
+        };
+    },
+    download: (item: Report.Item) => {
+        return apiDownload({
+            url: `/api/system/reports/${item.id}/download`,
+            filename: item.fileName,
+        });
+    },
+};
@@ -0,0 +1,30 @@
+// ==================== 报表 ====================
+declare namespace Report {
+    type Format = "xlsx" | "pdf";
+
+    interface Item {
+        id: number;
+        title: string;
+        format: Format;
+        /** 下载文件名 */
+        fileName: string;
+        sizeBytes: number;
+        periodStart: string;
+        periodEnd: string;
+        /** scheduled 定时生成，manual 手动执行 */
+        triggerType: "scheduled" | "manual";
+        /** 邮件收件人 */
+        recipients: string[];
+        /** 邮件发送成功时间 */
+        emailedAt?: string;
+        /** 邮件发送失败原因 */
+        emailError?: string;
+        createdAt: string;
+    }
+
+    interface QueryParams {
+        current?: number;
+        pageSize?: number;
+        format?: Format;
+    }
+}
@@ -1,4 +1,5 @@
 import { apiRequest } from "@/api/request";
+import { CONFIRMATION_HEADER } from "@/api/system/confirmation/api";
 
 /**
  * Role management API service.
@@ -36,9 +37,78 @@ export const roleAPI = {
             method: "DELETE",
         });
     },
-    options: () => {
-        return apiRequest<Api.OptionItem<number>[]>({
+    /** Renames are only needed after a `RESTORE_CONFLICT_CODE` answer. */
+    restore: (id: number, data: RecycleBin.RestoreRoleRequest = {}) => {
+        return apiRequest<void, RecycleBin.RestoreRoleRequest>({
+            url: `/api/system/roles/${id}/restore`,
+            method: "PUT",
+            params: data,
+        });
+    },
+    purgeReport: (id: number) => {
+        return apiRequest<Purge.Report>({ url: `/api/system/roles/${id}/purge` });
+    },
+    /** `confirmation` comes from `confirmationAPI.create` for this role's purge. */
+    purge: (id: number, confirmation: string) => {
+        return apiRequest<Purge.Report>({
+            url: `/api/system/roles/${id}/purge`,
+            method: "DELETE",
+            headers: { [CONFIRMATION_HEADER]: confirmation },
+        });
+    },
+    export: () => {
+        return apiRequest<Role.ExportDoc>({ url: "/api/system/roles/export" });
+    },
+    /** With `dryRun` nothing changes; the answer shows what the import would do. */
+    import: (doc: Role.ExportDoc, dryRun = false) => {
+        return apiRequest<Role.ImportResult, Role.ExportDoc>({
+            url: `/api/system/roles/import${dryRun ? "?dryRun=true" : ""}`,
+            method: "POST",
+            params: doc,
+        });
+    },
+    options: (params?: Api.OptionsParams) => {
+        return apiRequest<Api.OptionItem<number>[], Api.OptionsParams>({
             url: "/api/system/roles/options",
+            params,
+        });
+    },
+    members: async (id: number, params: Role.MemberQueryParams) => {
+        const res = await apiRequest<Role.Member[], Role.MemberQueryParams>({
+            url: `/api/system/roles/${id}/users`,
+            params,
+            raw: true,
+        });
+        return {
+            data: res.data,
+            total: res.total ?? 0,
+            success: true,
+        };
+    },
+    updateMembers: (id: number, data: Role.UpdateMembersRequest) => {
+        return apiRequest<Role.MembersChange, Role.UpdateMembersRequest>({
+            url: `/api/system/roles/${id}/users`,
+            method: "POST",
+            params: data,
+        });
+    },
+    transferMembers: (id: number, data: Role.TransferMembersRequest) => {
+        return apiRequest<Role.MembersChange, Role.TransferMembersRequest>({
+            url: `/api/system/roles/${id}/users/transfer`,
+            method: "POST",
+            params: data,
+        });
+    },
+    access: (id: number) => {
+        return apiRequest<Role.Access>({
+            url: `/api/system/roles/${id}/access`,
+        });
+    },
+    updateAccess: (id: number, data: Role.UpdateAccessRequest) => {
+        return apiRequest<Role.Access, Role.UpdateAccessRequest>({
+            url: `/api/system/roles/${id}/access`,
+            method: "PUT",
+            params: data,
         });
     },
 };
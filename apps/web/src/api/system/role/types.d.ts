@@ -25,6 +25,8 @@ declare namespace Role {
         roleName?: string;
         roleCode?: string;
         status?: string; // "1" | "2" | "all"
+        sortBy?: string;
+        sortOrder?: Api.SortOrder;
     }
 
     // 创建角色请求 - 更新为与后端一致
@@ -44,4 +46,89 @@ declare namespace Role {
         status: number;
         menuIds: number[];
     }
+
+    // 角色成员
+    interface Member {
+        id: number;
+        username: string;
+        realName?: string;
+        status: number;
+        assignedAt: string;
+        expiresAt?: string; // 临时分配的到期时间
+    }
+
+    interface MemberQueryParams {
+        current?: number;
+        pageSize?: number;
+    }
+
+    // 添加/移除角色成员
+    interface UpdateMembersRequest {
+        addUserIds?: number[];
+        removeUserIds?: number[];
+    }
+
+    // 转移全部成员到目标角色
+    interface TransferMembersRequest {
+        targetRoleId: number;
+    }
+
+    interface MembersChange {
+        added: number;
+        removed: number;
+    }
+
+    // 角色的网络与时间限制，全部为空表示不限制
+    interface Access {
+        roleId: number;
+        allowedCidrs: string[]; // 允许的 CIDR 网段
+        allowedDays: number[]; // 允许的星期，1 = 周一 … 7 = 周日
+        startTime?: string; // HH:MM，按服务器时区
+        endTime?: string; // 早于 startTime 表示跨零点
+        updatedAt?: string;
+    }
+
+    interface UpdateAccessRequest {
+        allowedCidrs?: string[];
+        allowedDays?: number[];
+        startTime?: string;
+        endTime?: string;
+    }
+
+    // 角色导出文件，按编码而非 ID 在环境间迁移
+    interface ExportDoc {
+        version: number;
+        exportedAt?: string;
+        roles: ExportItem[];
+    }
+
+    interface ExportItem {
+        code: string;
+        name: string;
+        description?: string;
+        status: number;
+        menuCodes: string[]; // 菜单与权限编码
+    }
+
+    type ImportAction = "create" | "update" | "unchanged";
+
+    // 单个角色与当前环境的差异
+    interface ImportItem {
+        code: string;
+        name: string;
+        action: ImportAction;
+        changedFields: string[]; // name / description / status
+        addedMenus: string[];
+        removedMenus: string[];
+        problems: string[]; // 非空时整个导入被拒绝
+    }
+
+    interface ImportResult {
+        dryRun: boolean;
+        applied: boolean;
+        created: number;
+        updated: number;
+        unchanged: number;
+        roles: ImportItem[];
+    }
 }
@@ -0,0 +1,38 @@
+import { apiRequest } from "@/api/request";
+
+/**
+ * Saved list filter API service; filters belong to the signed-in user.
+ */
+export const savedFilterAPI = {
+    list: (params?: SavedFilter.QueryParams) => {
+        return apiRequest<SavedFilter.Item[], SavedFilter.QueryParams>({
+            url: "/api/system/filters",
+            params,
+        });
+    },
+    save: (data: SavedFilter.SaveRequest) => {
+        return apiRequest<SavedFilter.Item, SavedFilter.SaveRequest>({
+            url: "/api/system/filters",
+            method: "POST",
+            params: data,
+        });
+    },
+    apply: async <T = User.Item | Log.Item>(id: number, params: SavedFilter.ApplyParams) => {
+        const res = await apiRequest<T[], SavedFilter.ApplyParams>({
+            url: `/api/system/filters/${id}/apply`,
+            params,
+            raw: true,
+        });
+        return {
+            data: res.data,
+            total: res.total ?? 0,
+            success: true,
+        };
+    },
+    delete: (id: number) => {
+        return apiRequest<void>({
+            url: `/api/system/filters/${id}`,
+            method: "DELETE",
+        });
+    },
+};
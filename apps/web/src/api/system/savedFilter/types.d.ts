@@ -0,0 +1,30 @@
+// ==================== 保存的列表筛选 ====================
+declare namespace SavedFilter {
+    type Resource = "users" | "logs";
+
+    interface Item {
+        id: number;
+        resource: Resource;
+        name: string;
+        /** 列表接口的筛选与排序参数，不含分页 */
+        query: Record<string, unknown>;
+        createdAt: string;
+        updatedAt: string;
+    }
+
+    /** 同名筛选会被覆盖 */
+    interface SaveRequest {
+        resource: Resource;
+        name: string;
+        query: Record<string, unknown>;
+    }
+
+    interface QueryParams {
+        resource?: Resource;
+    }
+
+    interface ApplyParams {
+        current?: number;
+        pageSize?: number;
+    }
+}
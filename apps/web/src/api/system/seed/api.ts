@@ -0,0 +1,13 @@
+import { apiRequest } from "@/api/request";
+
+/**
+ * Demo data seed API service (development only).
+ */
+export const seedAPI = {
+    run: () => {
+        return apiRequest<Seed.Report>({
+            url: "/api/system/seed",
+            method: "POST",
+        });
+    },
+};
@@ -0,0 +1,8 @@
+// ==================== 演示数据 ====================
+declare namespace Seed {
+    // 初始化结果
+    interface Report {
+        usersCreated: number;
+        dictsCreated: number;
+    }
+}
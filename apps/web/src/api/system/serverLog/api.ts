@@ -0,0 +1,24 @@
+import { apiStream } from "@/api/request";
+
+/**
+ * Live server log API service.
+ */
+export const serverLogAPI = {
+    /** 持续推送日志，直到 signal 中止 */
+    stream: (
+        params: ServerLog.StreamParams,
+        onLine: (line: ServerLog.Line) => void,
+        onLagged: (skipped: number) => void,
+        signal: AbortSignal,
+    ) => {
+        return apiStream({
+            url: "/api/system/logs/stream",
+            params,
+            signal,
+            onEvent: (event, data) => {
+                if (event === "log") onLine(JSON.parse(data) as ServerLog.Line);
+                if (event === "lagged") onLagged(Number(data));
+            },
+        });
+    },
+};
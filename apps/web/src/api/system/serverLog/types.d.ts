@@ -0,0 +1,18 @@
+// ==================== 实时服务日志 ====================
+declare namespace ServerLog {
+    type Level = "trace" | "debug" | "info" | "warn" | "error";
+
+    interface Line {
+        time: string;
+        level: "TRACE" | "DEBUG" | "INFO" | "WARN" | "ERROR";
+        target: string;
+        message: string;
+        /** 其他结构化字段 */
+        fields: Record<string, string>;
+    }
+
+    interface StreamParams {
+        /** 最低级别，默认 info */
+        level?: Level;
+    }
+}
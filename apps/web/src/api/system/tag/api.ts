@@ -0,0 +1,63 @@
+import { apiRequest } from "@/api/request";
+
+/**
+ * Tag management API service, including the tags of users and roles.
+ */
+export const tagAPI = {
+    list: async (params: Tag.QueryParams) => {
+        const res = await apiRequest<Tag.Item[], Tag.QueryParams>({
+            url: "/api/system/tags",
+            params,
+            raw: true,
+        });
+        return {
+            data: res.data,
+            total: res.total ?? 0,
+            success: true,
+        };
+    },
+    create: (data: Tag.CreateRequest) => {
+        return apiRequest<number, Tag.CreateRequest>({
+            url: "/api/system/tags",
+            method: "POST",
+            params: data,
+        });
+    },
+    update: (id: number, data: Tag.UpdateRequest) => {
+        return apiRequest<void, Tag.UpdateRequest>({
+            url: `/api/system/tags/${id}`,
+            method: "PUT",
+            params: data,
+        });
+    },
+    delete: (id: number) => {
+        return apiRequest<void>({
+            url: `/api/system/tags/${id}`,
+            method: "DELETE",
+        });
+    },
+    userTags: (userId: number) => {
+        return apiRequest<Tag.Label[]>({
+            url: `/api/system/users/${userId}/tags`,
+        });
+    },
+    setUserTags: (userId: number, data: Tag.AssignRequest) => {
+        return apiRequest<void, Tag.AssignRequest>({
+            url: `/api/system/users/${userId}/tags`,
+            method: "PUT",
+            params: data,
+        });
+    },
+    roleTags: (roleId: number) => {
+        return apiRequest<Tag.Label[]>({
+            url: `/api/system/roles/${roleId}/tags`,
+        });
+    },
+    setRoleTags: (roleId: number, data: Tag.AssignRequest) => {
+        return apiRequest<void, Tag.AssignRequest>({
+            url: `/api/system/roles/${roleId}/tags`,
+            method: "PUT",
+            params: data,
+        });
+    },
+};
@@ -0,0 +1,43 @@
+// ==================== 标签 ====================
+declare namespace Tag {
+    interface Item {
+        id: number;
+        /** 小写字母、数字、- 和 _，如 pilot-group */
+        name: string;
+        /** #rrggbb */
+        color?: string;
+        description?: string;
+        /** 带此标签的用户数 */
+        userCount: number;
+        /** 带此标签的角色数 */
+        roleCount: number;
+        createdAt: string;
+        updatedAt: string;
+    }
+
+    /** 用户或角色上的标签 */
+    interface Label {
+        id: number;
+        name: string;
+        color?: string;
+    }
+
+    interface CreateRequest {
+        name: string;
+        color?: string;
+        description?: string;
+    }
+
+    type UpdateRequest = CreateRequest;
+
+    /** 整体替换标签 */
+    interface AssignRequest {
+        tagIds: number[];
+    }
+
+    interface QueryParams {
+        current?: number;
+        pageSize?: number;
+        name?: string;
+    }
+}
@@ -0,0 +1,12 @@
+import { apiRequest } from "@/api/request";
+
+/**
+ * Quota usage API service.
+ */
+export const usageAPI = {
+    get: () => {
+        return apiRequest<Usage.Summary>({
+            url: "/api/system/usage",
+        });
+    },
+};
@@ -0,0 +1,14 @@
+// ==================== 配额用量 ====================
+declare namespace Usage {
+    interface Quota {
+        used: number;
+        /** 未设置上限时为空 */
+        limit?: number;
+    }
+
+    interface Summary {
+        users: Quota;
+        roles: Quota;
+        storageBytes: Quota;
+    }
+}
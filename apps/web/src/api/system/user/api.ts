@@ -1,4 +1,5 @@
-import { apiRequest } from "@/api/request";
+import { apiDownload, apiRequest } from "@/api/request";
+import { CONFIRMATION_HEADER } from "@/api/system/confirmation/api";
 
 /**
  * User management API service.
@@ -36,6 +37,86 @@ export const userAPI = {
             method: "DELETE",
         });
     },
+    /** Renames are only needed after a `RESTORE_CONFLICT_CODE` answer. */
+    restore: (id: number, data: RecycleBin.RestoreUserRequest = {}) => {
+        return apiRequest<void, RecycleBin.RestoreUserRequest>({
+            url: `/api/system/users/${id}/restore`,
+            method: "PUT",
+            params: data,
+        });
+    },
+    purgeReport: (id: number) => {
+        return apiRequest<Purge.Report>({ url: `/api/system/users/${id}/purge` });
+    },
+    /** `confirmation` comes from `confirmationAPI.create` for this user's purge. */
+    purge: (id: number, confirmation: string) => {
+        return apiRequest<Purge.Report>({
+            url: `/api/system/users/${id}/purge`,
+            method: "DELETE",
+            headers: { [CONFIRMATION_HEADER]: confirmation },
+        });
+    },
+    export: (params?: Omit<User.QueryParams, "current" | "pageSize">) => {
+        return apiDownload({ url: "/api/system/users/export", params });
+    },
+    exportData: (id: number) => {
+        return apiRequest<User.DataExport>({
+            url: `/api/system/users/${id}/export`,
+        });
+    },
+    anonymize: (id: number) => {
+        return apiRequest<void>({
+            url: `/api/system/users/${id}/anonymize`,
+            method: "POST",
+        });
+    },
+    roleHistory: async (id: number, params: User.RoleHistoryParams) => {
+        const res = await apiRequest<User.RoleHistoryItem[], User.RoleHistoryParams>({
+            url: `/api/system/users/${id}/role-history`,
+            params,
+            raw: true,
+        });
+        return {
+            data: res.data,
+            total: res.total ?? 0,
+            success: true,
+        };
+    },
+    roles: (id: number) => {
+        return apiRequest<User.RoleAssignment[]>({
+            url: `/api/system/users/${id}/roles`,
+        });
+    },
+    roleExpiry: (id: number, roleId: number, expiresAt: string | null) => {
+        return apiRequest<void, User.RoleExpiryRequest>({
+            url: `/api/system/users/${id}/roles/${roleId}/expiry`,
+            method: "PUT",
+            params: { expiresAt },
+        });
+    },
+    activity: async (id: number, params: User.ActivityParams) => {
+        const res = await apiRequest<User.ActivityItem[], User.ActivityParams>({
+            url: `/api/system/users/${id}/activity`,
+            params,
+            raw: true,
+        });
+        return {
+            data: res.data,
+            total: res.total ?? 0,
+            success: true,
+        };
+    },
+    effectiveAccess: (id: number) => {
+        return apiRequest<User.EffectiveAccess>({
+            url: `/api/system/users/${id}/effective-access`,
+        });
+    },
+    can: (id: number, perm: string) => {
+        return apiRequest<Auth.CapabilityCheck>({
+            url: `/api/system/users/${id}/can`,
+            params: { perm },
+        });
+    },
     status: (id: number, status: number) => {
         return apiRequest<boolean>({
             url: `/api/system/users/${id}/status`,
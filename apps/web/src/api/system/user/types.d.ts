@@ -18,7 +18,8 @@ declare namespace User {
     interface Item {
         id: number;
         username: string;
-        email: string;
+        email?: string; // 匿名化后为空；无 system:privacy:view 时脱敏
+        phone?: string; // 无 system:privacy:view 时脱敏
         realName?: string;
         avatarUrl?: string;
         status: Status;
@@ -26,6 +27,8 @@ declare namespace User {
         createdAt: string;
         updatedAt: string;
         roles: Api.OptionItem<number>[];
+        /** 自定义资料字段值，键为字段 key */
+        profile: Record<string, string | number | boolean>;
     }
 
     // 查询参数
@@ -36,6 +39,12 @@ declare namespace User {
         realName?: string;
         email?: string;
         status?: string; // "1" | "2" | "3" | "4" | "all"
+        /** 逗号分隔的标签名，需全部匹配 */
+        tags?: string;
+        /** 逗号分隔的 key:value 资料字段值，需全部匹配，如 region:north */
+        profile?: string;
+        sortBy?: string;
+        sortOrder?: Api.SortOrder;
     }
 
     // 创建用户请求
@@ -46,6 +55,7 @@ declare namespace User {
         realName?: string;
         status?: number;
         roleIds: number[];
+        profile?: Record<string, string | number | boolean | null>;
     }
 
     // 更新用户请求
@@ -53,5 +63,105 @@ declare namespace User {
         email: string;
         realName: string;
         roleIds: number[];
+        /** 提供时整体替换资料字段值 */
+        profile?: Record<string, string | number | boolean | null>;
+    }
+
+    // 角色分配历史
+    interface RoleHistoryItem {
+        id: number;
+        roleId: number;
+        roleName?: string;
+        roleCode?: string;
+        action: "assigned" | "removed";
+        operatorId?: number;
+        operatorUsername?: string;
+        createdAt: string;
+    }
+
+    interface RoleHistoryParams {
+        current?: number;
+        pageSize?: number;
+    }
+
+    // 当前持有的角色；临时分配到期后不再授予权限
+    interface RoleAssignment {
+        roleId: number;
+        roleName: string;
+        roleCode: string;
+        assignedAt: string;
+        expiresAt?: string; // 为空表示永久
+    }
+
+    // 设置角色分配到期时间，null 改回永久
+    interface RoleExpiryRequest {
+        expiresAt: string | null;
+    }
+
+    // 用户活动时间线：登录、其他操作与角色变更按时间倒序合并
+    interface ActivityItem {
+        kind: "login" | "operation" | "role_change";
+        sourceId: number; // operation_logs 或 user_role_history 的 ID
+        action: string;
+        description?: string;
+        status?: string;
+        ipAddress?: string; // 无 system:privacy:view 时脱敏
+        roleId?: number;
+        roleName?: string;
+        operatorUsername?: string;
+        createdAt: string;
+    }
+
+    interface ActivityParams {
+        current?: number;
+        pageSize?: number;
+        kind?: ActivityItem["kind"];
+        from?: string; // YYYY-MM-DD（UTC）
+        to?: string; // YYYY-MM-DD（UTC，含当天）
+    }
+
+    // 个人数据导出：资料（含已删除账号）、角色、角色历史、邮箱变更申请与操作日志
+    interface DataExport {
+        exportedAt: string;
+        profile: Omit<Item, "roles"> & {
+            isSystem: boolean;
+            deletedAt?: string;
+            anonymizedAt?: string;
+        };
+        roles: Api.OptionItem<number>[];
+        roleHistory: RoleHistoryItem[];
+        emailChanges: EmailChangeItem[];
+        operationLogs: Log.Item[];
+    }
+
+    // 申请过的新邮箱，含未确认的
+    interface EmailChangeItem {
+        newEmail: string;
+        createdAt: string;
+        expiresAt: string;
+        confirmedAt?: string | null;
+    }
+
+    // 有效权限：合并后的权限及其来源角色，以及该用户可见的菜单树
+    interface EffectivePermission {
+        code: string;
+        roles: string[]; // 授予该权限的角色编码
+    }
+
+    interface AccessMenuNode {
+        code: string;
+        name: string;
+        menuType: number; // 1 directory, 2 page, 4 external link, 5 iframe
+        visible: boolean;
+        children: AccessMenuNode[];
+    }
+
+    interface EffectiveAccess {
+        userId: number;
+        username: string;
+        active: boolean; // 非正常状态的用户没有任何权限
+        isSuper: boolean; // 持有 *
+        permissions: EffectivePermission[];
+        menus: AccessMenuNode[];
     }
 }
@@ -0,0 +1,62 @@
+import { apiRequest } from "@/api/request";
+
+/**
+ * Webhook management API service.
+ */
+export const webhookAPI = {
+    list: async (params: Webhook.QueryParams) => {
+        const res = await apiRequest<Webhook.Item[], Webhook.QueryParams>({
+            url: "/api/system/webhooks",
+            params,
+            raw: true,
+        });
+        return {
+            data: res.data,
+            total: res.total ?? 0,
+            success: true,
+        };
+    },
+    create: (data: Webhook.CreateRequest) => {
+        return apiRequest<number, Webhook.CreateRequest>({
+            url: "/api/system/webhooks",
+            method: "POST",
+            params: data,
+        });
+    },
+    update: (id: number, data: Webhook.UpdateRequest) => {
+        return apiRequest<void, Webhook.UpdateRequest>({
+            url: `/api/system/webhooks/${id}`,
+            method: "PUT",
+            params: data,
+        });
+    },
+    delete: (id: number) => {
+        return apiRequest<void>({
+            url: `/api/system/webhooks/${id}`,
+            method: "DELETE",
+        });
+    },
+    events: () => {
+        return apiRequest<string[]>({
+            url: "/api/system/webhooks/events",
+        });
+    },
+    deliveries: async (id: number, params: Webhook.DeliveryQueryParams) => {
+        const res = await apiRequest<Webhook.Delivery[], Webhook.DeliveryQueryParams>({
+            url: `/api/system/webhooks/${id}/deliveries`,
+            params,
+            raw: true,
+        });
+        return {
+            data: res.data,
+            total: res.total ?? 0,
+            success: true,
+        };
+    },
+    retryDelivery: (id: number, deliveryId: number) => {
+        return apiRequest<void>({
+            url: `/api/system/webhooks/${id}/deliveries/${deliveryId}/retry`,
+            method: "POST",
+        });
+    },
+};
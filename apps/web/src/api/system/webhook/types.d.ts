@@ -0,0 +1,68 @@
+// ==================== Webhook 管理 ====================
+declare namespace Webhook {
+    // Webhook 基本信息（secret 只写不读）
+    interface Item {
+        id: number;
+        name: string;
+        url: string;
+        events: string[];
+        status: number;
+        description?: string;
+        createdAt: string;
+        updatedAt: string;
+    }
+
+    // 查询参数
+    interface QueryParams {
+        current?: number;
+        pageSize?: number;
+        name?: string;
+        status?: string;
+    }
+
+    // 创建请求
+    interface CreateRequest {
+        name: string;
+        url: string;
+        secret: string;
+        events: string[];
+        status?: number;
+        description?: string;
+    }
+
+    // 更新请求，secret 留空则保持不变
+    interface UpdateRequest {
+        name: string;
+        url: string;
+        secret?: string;
+        events: string[];
+        status?: number;
+        description?: string;
+    }
+
+    // 投递状态
+    type DeliveryStatus = "pending" | "succeeded" | "failed";
+
+    // 投递记录
+    interface Delivery {
+        id: number;
+        webhookId: number;
+        event: string;
+        payload: string;
+        status: DeliveryStatus;
+        attempts: number;
+        nextAttemptAt: string;
+        responseStatus?: number;
+        lastError?: string;
+        createdAt: string;
+        deliveredAt?: string;
+    }
+
+    // 投递记录查询参数
+    interface DeliveryQueryParams {
+        current?: number;
+        pageSize?: number;
+        status?: DeliveryStatus | "all";
+        event?: string;
+    }
+}
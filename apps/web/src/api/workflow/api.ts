@@ -0,0 +1,105 @@
+import { apiRequest } from "@/api/request";
+
+/**
+ * Workflow definitions, instances and the personal task inbox.
+ */
+export const workflowAPI = {
+    definitions: async (params: Workflow.DefinitionQueryParams) => {
+        const res = await apiRequest<Workflow.Definition[], Workflow.DefinitionQueryParams>({
+            url: "/api/workflow/definitions",
+            params,
+            raw: true,
+        });
+        return {
+            data: res.data,
+            total: res.total ?? 0,
+            success: true,
+        };
+    },
+    createDefinition: (data: Workflow.DefinitionRequest) => {
+        return apiRequest<number, Workflow.DefinitionRequest>({
+            url: "/api/workflow/definitions",
+            method: "POST",
+            params: data,
+        });
+    },
+    updateDefinition: (id: number, data: Workflow.DefinitionRequest) => {
+        return apiRequest<void, Workflow.DefinitionRequest>({
+            url: `/api/workflow/definitions/${id}`,
+            method: "PUT",
+            params: data,
+        });
+    },
+    deleteDefinition: (id: number) => {
+        return apiRequest<void>({
+            url: `/api/workflow/definitions/${id}`,
+            method: "DELETE",
+        });
+    },
+    start: (data: Workflow.StartRequest) => {
+        return apiRequest<number, Workflow.StartRequest>({
+            url: "/api/workflow/instances",
+            method: "POST",
+            params: data,
+        });
+    },
+    instances: async (params: Workflow.InstanceQueryParams) => {
+        const res = await apiRequest<Workflow.Instance[], Workflow.InstanceQueryParams>({
+            url: "/api/workflow/instances",
+            params,
+            raw: true,
+        });
+        return {
+            data: res.data,
+            total: res.total ?? 0,
+            success: true,
+        };
+    },
+    myInstances: async (params: Workflow.InstanceQueryParams) => {
+        const res = await apiRequest<Workflow.Instance[], Workflow.InstanceQueryParams>({
+            url: "/api/workflow/instances/mine",
+            params,
+            raw: true,
+        });
+        return {
+            data: res.data,
+            total: res.total ?? 0,
+            success: true,
+        };
+    },
+    instance: (id: number) => {
+        return apiRequest<Workflow.InstanceDetail>({ url: `/api/workflow/instances/${id}` });
+    },
+    cancel: (id: number) => {
+        return apiRequest<void>({
+            url: `/api/workflow/instances/${id}/cancel`,
+            method: "POST",
+        });
+    },
+    myTasks: async (params: Workflow.InboxQueryParams) => {
+        const res = await apiRequest<Workflow.InboxTask[], Workflow.InboxQueryParams>({
+            url: "/api/workflow/tasks/mine",
+            params,
+            raw: true,
+        });
+        return {
+            data: res.data,
+            total: res.total ?? 0,
+            success: true,
+        };
+    },
+    approve: (id: number, data?: Workflow.DecideRequest) => {
+        return apiRequest<void, Workflow.DecideRequest>({
+            url: `/api/workflow/tasks/${id}/approve`,
+            method: "POST",
+            params: data ?? {},
+        });
+    },
+    reject: (id: number, data?: Workflow.DecideRequest) => {
+        return apiRequest<void, Workflow.DecideRequest>({
+            url: `/api/workflow/tasks/${id}/reject`,
+            method: "POST",
+            params: data ?? {},
+        });
+    },
+};
@@ -0,0 +1,112 @@
+// ==================== 审批流程 ====================
+declare namespace Workflow {
+    // 审批步骤，由该角色的成员审批
+    interface Step {
+        name: string;
+        approverRoleId: number;
+    }
+
+    // 流程定义
+    interface Definition {
+        id: number;
+        code: string;
+        name: string;
+        description?: string;
+        steps: Step[];
+        status: number;
+        createdAt: string;
+        updatedAt: string;
+    }
+
+    interface DefinitionQueryParams {
+        current?: number;
+        pageSize?: number;
+        name?: string;
+        status?: string;
+    }
+
+    // 创建/更新请求；更新不影响进行中的实例
+    interface DefinitionRequest {
+        code: string;
+        name: string;
+        description?: string;
+        steps: Step[];
+        status?: number;
+    }
+
+    type InstanceStatus = "running" | "approved" | "rejected" | "cancelled";
+
+    type TaskStatus = "pending" | "approved" | "rejected" | "cancelled";
+
+    // 发起请求
+    interface StartRequest {
+        definitionCode: string;
+        title: string;
+        data?: Record<string, unknown>;
+    }
+
+    // 流程实例，steps 为发起时的步骤快照
+    interface Instance {
+        id: number;
+        definitionCode: string;
+        title: string;
+        data: Record<string, unknown>;
+        steps: Step[];
+        currentStep: number;
+        status: InstanceStatus;
+        startedBy: number;
+        startedByName: string;
+        createdAt: string;
+        updatedAt: string;
+        finishedAt?: string;
+    }
+
+    interface InstanceQueryParams {
+        current?: number;
+        pageSize?: number;
+        status?: InstanceStatus | "all";
+        definitionCode?: string;
+    }
+
+    // 审批记录
+    interface Task {
+        id: number;
+        instanceId: number;
+        stepIndex: number;
+        stepName: string;
+        approverRoleId: number;
+        status: TaskStatus;
+        decidedBy?: number;
+        decidedByName?: string;
+        comment?: string;
+        createdAt: string;
+        decidedAt?: string;
+    }
+
+    interface InstanceDetail extends Instance {
+        tasks: Task[];
+    }
+
+    // 我的待办
+    interface InboxTask {
+        id: number;
+        instanceId: number;
+        stepIndex: number;
+        stepName: string;
+        approverRoleId: number;
+        definitionCode: string;
+        title: string;
+        startedBy: number;
+        startedByName: string;
+        createdAt: string;
+    }
+
+    interface InboxQueryParams {
+        current?: number;
+        pageSize?: number;
+    }
+
+    interface DecideRequest {
+        comment?: string;
+    }
+}
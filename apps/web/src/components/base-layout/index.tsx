@@ -9,7 +9,13 @@ import { appMessage, authAPI } from "@/api";
 import { useAuthStore } from "@/store/useAuthStore";
 
 import { AppSearch } from "./app-search";
-import { getMenuData, getSearchRouteItems, type AppRoutePath } from "./routes";
+import {
+    getLinkMenuData,
+    getMenuData,
+    getSearchRouteItems,
+    type AppRouteItem,
+    type AppRoutePath,
+} from "./routes";
 
 interface BaseLayoutProps {
     children: ReactNode;
@@ -28,15 +34,34 @@ export const BaseLayout = ({ children, hidden = false }: BaseLayoutProps) => {
     const userInfo = useAuthStore((state) => state.userInfo);
     const clearAuth = useAuthStore((state) => state.clearAuth);
     const checkMenuPermissions = useAuthStore((state) => state.checkMenuPermissions);
+    const checkMenuVisible = useAuthStore((state) => state.checkMenuVisible);
     const menuPermissionSignature = useAuthStore(
         (state) => state.userInfo?.permissions?.join("|") || "",
     );
+    const menus = useAuthStore((state) => state.userInfo?.menus);
+    const hiddenMenuSignature = useAuthStore(
+        (state) =>
+            state.userInfo?.menus
+                ?.filter((menu) => !menu.visible)
+                .map((menu) => menu.code)
+                .join("|") || "",
+    );
     const router = useRouter();
     const currentPath = useLocation().pathname;
 
+    // Hidden menus stay routable and searchable; they only leave the sidebar.
     const menuData = useMemo(
-        () => getMenuData(checkMenuPermissions),
-        [checkMenuPermissions, menuPermissionSignature],
+        () => [
+            ...getMenuData((path) => checkMenuPermissions(path) && checkMenuVisible(path)),
+            ...getLinkMenuData(menus ?? []),
+        ],
+        [
+            checkMenuPermissions,
+            checkMenuVisible,
+            menuPermissionSignature,
+            hiddenMenuSignature,
+            menus,
+        ],
     );
 
     const searchRoutes = useMemo(
@@ -83,11 +108,26 @@ export const BaseLayout = ({ children, hidden = false }: BaseLayoutProps) => {
             layout="mix"
             contentStyle={layoutContentStyle}
             onMenuHeaderClick={() => void router.navigate({ to: "/" })}
-            menuItemRender={(item, dom) => (
-                <Link to={item.path || "/"} className="block">
-                    {dom}
-                </Link>
-            )}
+            menuItemRender={(item, dom) => {
+                const { externalUrl } = item as AppRouteItem;
+                if (externalUrl) {
+                    return (
+                        <a
+                            href={externalUrl}
+                            target="_blank"
+                            rel="noopener noreferrer"
+                            className="block"
+                        >
+                            {dom}
+                        </a>
+                    );
+                }
+                return (
+                    <Link to={item.path || "/"} className="block">
+                        {dom}
+                    </Link>
+                );
+            }}
             route={{
                 path: "/",
                 children: menuData,
@@ -5,6 +5,7 @@ import {
     DashboardOutlined,
     FileUnknownOutlined,
     HistoryOutlined,
+    LinkOutlined,
     MenuOutlined,
     SettingOutlined,
     StopOutlined,
@@ -26,12 +27,17 @@ export type AppRoutePath =
     | "/manage/task"
     | "/manage/deploy";
 
-type AppRouteGroupPath = "/system" | "/manage";
+type AppRouteGroupPath = "/system" | "/manage" | "/links";
+
+// Link (4) and iframe (5) menus come from the login info instead of the route tree.
+type LinkMenuPath = `/frame/${string}` | `/link/${string}`;
 
 export type AppRouteItem = {
     name: string;
     icon?: ReactNode;
-    path?: AppRoutePath | AppRouteGroupPath;
+    path?: AppRoutePath | AppRouteGroupPath | LinkMenuPath;
+    /** Opened in a new tab instead of routed */
+    externalUrl?: string;
     children?: AppRouteItem[];
     requiresPermission?: boolean;
 };
@@ -178,6 +184,25 @@ export const getMenuData = (checkMenuPermissions: (path: string) => boolean): Ap
     return getMenuList(layoutMenuRoutes);
 };
 
+export const getLinkMenuData = (menus: Auth.MenuInfo[]): AppRouteItem[] => {
+    const children = menus
+        .filter((menu) => menu.visible && menu.linkUrl && [4, 5].includes(menu.menuType))
+        .map<AppRouteItem>((menu) => ({
+            name: menu.name,
+            icon: <LinkOutlined />,
+            path:
+                menu.menuType === 5
+                    ? `/frame/${encodeURIComponent(menu.code)}`
+                    : `/link/${encodeURIComponent(menu.code)}`,
+            externalUrl: menu.menuType === 4 ? (menu.linkUrl ?? undefined) : undefined,
+            requiresPermission: false,
+        }));
+    if (children.length === 0) {
+        return [];
+    }
+    return [{ name: "Links", icon: <LinkOutlined />, path: "/links", children }];
+};
+
 export const getSearchRouteItems = (
     checkMenuPermissions: (path: string) => boolean,
 ): SearchRouteItem[] => {
@@ -2,6 +2,7 @@ import { UploadOutlined } from "@ant-design/icons";
 import { Upload, type UploadFile } from "antd";
 
 import { appMessage } from "@/api";
+import { getAuthHeaders } from "@/api/request";
 import { useAuthStore } from "@/store/useAuthStore";
 
 // const getBase64 = (img: UploadFile, callback: (url: string) => void) => {
@@ -27,7 +28,7 @@ const beforeUpload = async (file: UploadFile) => {
     return isJpgOrPng && isLimt;
 };
 export const UserAvatar = () => {
-    const { userInfo, token, updateAvatar } = useAuthStore();
+    const { userInfo, updateAvatar } = useAuthStore();
 
     return (
         <>
@@ -38,9 +39,7 @@ export const UserAvatar = () => {
                 showUploadList={false}
                 action="/api/account/avatar"
                 beforeUpload={beforeUpload}
-                headers={{
-                    Authorization: `Bearer ${token}`,
-                }}
+                headers={getAuthHeaders()}
                 onChange={(info) => {
                     if (info.file.status === "done") {
                         updateAvatar(info.file.response.data);
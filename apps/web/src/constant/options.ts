@@ -7,4 +7,6 @@ export const MENU_TYPE_OPTIONS = [
     { label: "Directory", value: 1 },
     { label: "Menu", value: 2 },
     { label: "Button", value: 3 },
+    { label: "External Link", value: 4 },
+    { label: "Iframe", value: 5 },
 ];
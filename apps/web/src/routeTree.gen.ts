@@ -21,6 +21,7 @@ import { Route as ManageTaskRouteImport } from './routes/manage/task'
 import { Route as ManageLogRouteImport } from './routes/manage/log'
 import { Route as ManageDictRouteImport } from './routes/manage/dict'
 import { Route as ManageDeployRouteImport } from './routes/manage/deploy'
+import { Route as FrameCodeRouteImport } from './routes/frame/$code'
 
 const ProfileRoute = ProfileRouteImport.update({
   id: '/profile',
@@ -82,6 +83,11 @@ const ManageDeployRoute = ManageDeployRouteImport.update({
   path: '/manage/deploy',
   getParentRoute: () => rootRouteImport,
 } as any)
+const FrameCodeRoute = FrameCodeRouteImport.update({
+  id: '/frame/$code',
+  path: '/frame/$code',
+  getParentRoute: () => rootRouteImport,
+} as any)
 
 export interface FileRoutesByFullPath {
   '/': typeof IndexRoute
@@ -89,6 +95,7 @@ export interface FileRoutesByFullPath {
   '/404': typeof R404Route
   '/login': typeof LoginRoute
   '/profile': typeof ProfileRoute
+  '/frame/$code': typeof FrameCodeRoute
   '/manage/deploy': typeof ManageDeployRoute
   '/manage/dict': typeof ManageDictRoute
   '/manage/log': typeof ManageLogRoute
@@ -103,6 +110,7 @@ export interface FileRoutesByTo {
   '/404': typeof R404Route
   '/login': typeof LoginRoute
   '/profile': typeof ProfileRoute
+  '/frame/$code': typeof FrameCodeRoute
   '/manage/deploy': typeof ManageDeployRoute
   '/manage/dict': typeof ManageDictRoute
   '/manage/log': typeof ManageLogRoute
@@ -118,6 +126,7 @@ export interface FileRoutesById {
   '/404': typeof R404Route
   '/login': typeof LoginRoute
   '/profile': typeof ProfileRoute
+  '/frame/$code': typeof FrameCodeRoute
   '/manage/deploy': typeof ManageDeployRoute
   '/manage/dict': typeof ManageDictRoute
   '/manage/log': typeof ManageLogRoute
@@ -134,6 +143,7 @@ export interface FileRouteTypes {
     | '/404'
     | '/login'
     | '/profile'
+    | '/frame/$code'
     | '/manage/deploy'
     | '/manage/dict'
     | '/manage/log'
@@ -148,6 +158,7 @@ export interface FileRouteTypes {
     | '/404'
     | '/login'
     | '/profile'
+    | '/frame/$code'
     | '/manage/deploy'
     | '/manage/dict'
     | '/manage/log'
@@ -162,6 +173,7 @@ export interface FileRouteTypes {
     | '/404'
     | '/login'
     | '/profile'
+    | '/frame/$code'
     | '/manage/deploy'
     | '/manage/dict'
     | '/manage/log'
@@ -177,6 +189,7 @@ export interface RootRouteChildren {
   R404Route: typeof R404Route
   LoginRoute: typeof LoginRoute
   ProfileRoute: typeof ProfileRoute
+  FrameCodeRoute: typeof FrameCodeRoute
   ManageDeployRoute: typeof ManageDeployRoute
   ManageDictRoute: typeof ManageDictRoute
   ManageLogRoute: typeof ManageLogRoute
@@ -272,6 +285,13 @@ declare module '@tanstack/react-router' {
       preLoaderRoute: typeof ManageDeployRouteImport
       parentRoute: typeof rootRouteImport
     }
+    '/frame/$code': {
+      id: '/frame/$code'
+      path: '/frame/$code'
+      fullPath: '/frame/$code'
+      preLoaderRoute: typeof FrameCodeRouteImport
+      parentRoute: typeof rootRouteImport
+    }
   }
 }
 
@@ -281,6 +301,7 @@ const rootRouteChildren: RootRouteChildren = {
   R404Route: R404Route,
   LoginRoute: LoginRoute,
   ProfileRoute: ProfileRoute,
+  FrameCodeRoute: FrameCodeRoute,
   ManageDeployRoute: ManageDeployRoute,
   ManageDictRoute: ManageDictRoute,
   ManageLogRoute: ManageLogRoute,
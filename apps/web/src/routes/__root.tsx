@@ -14,10 +14,10 @@ const permissionFreePaths = new Set(["/", "/profile", "/403", "/404"]);
 export const Route = createRootRoute({
     beforeLoad: (ctx: { location: { pathname: string } }) => {
         const curPath = ctx.location.pathname;
-        const { token, checkMenuPermissions } = useAuthStore.getState();
+        const { userInfo, checkMenuPermissions } = useAuthStore.getState();
 
-        // Redirect to login if no token
-        if (!token) {
+        // Redirect to login if not signed in (cookie sessions keep no token in JS)
+        if (!userInfo) {
             if (curPath !== "/login") {
                 throw redirect({ to: "/login" });
             }
@@ -33,10 +33,15 @@ export const Route = createRootRoute({
         if (permissionFreePaths.has(curPath)) {
             return null;
         }
+
+        // Iframe pages check the menu against the login info themselves
+        if (curPath.startsWith("/frame/")) {
+            return null;
+        }
         const isPermission = checkMenuPermissions(curPath);
 
         // Redirect to 403 if no permission
-        if (token && !isPermission) {
+        if (!isPermission) {
             throw redirect({ to: "/403" });
         }
     },
@@ -45,11 +50,11 @@ export const Route = createRootRoute({
 });
 
 function RootLayout() {
-    const { token, updateUserInfo } = useAuthStore();
+    const { userInfo: signedInUser, updateUserInfo } = useAuthStore();
     const { data: userInfo } = useQuery({
         queryKey: ["auth", "me"],
         queryFn: authAPI.me,
-        enabled: !!token,
+        enabled: !!signedInUser,
     });
 
     useEffect(() => {
@@ -61,7 +66,7 @@ function RootLayout() {
     return (
         <ConfigProvider>
             <App>
-                <BaseLayout hidden={!token}>
+                <BaseLayout hidden={!signedInUser}>
                     <Outlet />
                 </BaseLayout>
                 <MessageContent />
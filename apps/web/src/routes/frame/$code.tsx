@@ -0,0 +1,27 @@
+import { createFileRoute, Navigate } from "@tanstack/react-router";
+
+import { useAuthStore } from "@/store/useAuthStore";
+
+export const Route = createFileRoute("/frame/$code")({
+    component: FramePage,
+});
+
+// Embeds an iframe menu (type 5). Only menus returned in the login info can be opened.
+function FramePage() {
+    const { code } = Route.useParams();
+    const menu = useAuthStore((state) =>
+        state.userInfo?.menus?.find((item) => item.code === code && item.menuType === 5),
+    );
+
+    if (!menu?.linkUrl) {
+        return <Navigate to="/403" />;
+    }
+
+    return (
+        <iframe
+            title={menu.name}
+            src={menu.linkUrl}
+            className="h-full w-full rounded-lg border-0 bg-white"
+        />
+    );
+}
@@ -1,6 +1,7 @@
 import { DeleteOutlined, EditOutlined } from "@ant-design/icons";
 import {
     ModalForm,
+    ProFormSwitch,
     ProFormText,
     ProFormTextArea,
     ProTable,
@@ -75,6 +76,12 @@ const columns: ProColumns<Dict.Item>[] = [
         dataIndex: "value",
         ellipsis: true,
     },
+    {
+        title: "Default",
+        dataIndex: "isDefault",
+        width: 80,
+        render: (_dom, entity) => (entity.isDefault ? <Tag color="green">Default</Tag> : null),
+    },
     {
         title: "Description",
         dataIndex: "description",
@@ -190,6 +197,11 @@ const DictModalForm = ({
                 placeholder="Enter value (e.g., 1)"
                 rules={[{ required: true, message: "Please enter value" }]}
             />
+            <ProFormSwitch
+                name="isDefault"
+                label="Default"
+                tooltip="Replaces the current default of this dictionary type"
+            />
             <ProFormTextArea
                 name="description"
                 label="Description"
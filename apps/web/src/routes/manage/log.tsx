@@ -150,6 +150,22 @@ const columns: ProColumns<Log.Item>[] = [
         width: 120,
         render: (_, record) => record.ipAddress || "-",
     },
+    {
+        title: "Device",
+        dataIndex: "userAgent",
+        width: 160,
+        search: false,
+        ellipsis: true,
+        render: (_, record) => record.device?.name || record.userAgent || "-",
+    },
+    {
+        title: "Location",
+        dataIndex: "country",
+        width: 120,
+        search: false,
+        render: (_, record) =>
+            [record.city, record.country].filter(Boolean).join(", ") || "-",
+    },
     {
         title: "Duration",
         dataIndex: "durationMs",
@@ -1,8 +1,10 @@
 import { EditOutlined, StopOutlined } from "@ant-design/icons";
 import {
     ModalForm,
+    ProFormDependency,
     ProFormDigit,
     ProFormSelect,
+    ProFormSwitch,
     ProFormText,
     ProTable,
     type ActionType,
@@ -57,6 +59,8 @@ const menuTypeEnum: Record<number, { text: string; color: string }> = {
     1: { text: "Directory", color: "cyan" },
     2: { text: "Menu", color: "purple" },
     3: { text: "Button", color: "warning" },
+    4: { text: "External Link", color: "blue" },
+    5: { text: "Iframe", color: "geekblue" },
 };
 
 const columns: ProColumns<Menu.Item>[] = [
@@ -175,6 +179,8 @@ const MenuModalForm = ({
                     form.setFieldsValue({
                         ...initialValues,
                         parentId: initialValues?.parentId ?? 0,
+                        visible: initialValues?.visible ?? true,
+                        keepAlive: initialValues?.keepAlive ?? false,
                     });
                 } else {
                     form.resetFields();
@@ -194,7 +200,7 @@ const MenuModalForm = ({
                 name="parentId"
                 label="Parent Menu"
                 placeholder="Select parent menu"
-                request={systemAPI.menu.options}
+                request={() => systemAPI.menu.options()}
                 fieldProps={{
                     optionFilterProp: "label",
                 }}
@@ -218,6 +224,22 @@ const MenuModalForm = ({
                 options={MENU_TYPE_OPTIONS}
                 rules={[{ required: true, message: "Please select menu type" }]}
             />
+            <ProFormDependency name={["menuType"]}>
+                {({ menuType }) =>
+                    menuType === 4 || menuType === 5 ? (
+                        <ProFormText
+                            name="linkUrl"
+                            label="Link URL"
+                            placeholder="https://"
+                            tooltip="Links open in a new tab; iframes are embedded in the page"
+                            rules={[
+                                { required: true, message: "Please enter link URL" },
+                                { type: "url", message: "Please enter an http or https URL" },
+                            ]}
+                        />
+                    ) : null
+                }
+            </ProFormDependency>
             <ProFormSelect
                 name="status"
                 label="Status"
@@ -225,6 +247,16 @@ const MenuModalForm = ({
                 options={ENABLE_OPTIONS}
                 rules={[{ required: true, message: "Please select status" }]}
             />
+            <ProFormSwitch
+                name="visible"
+                label="Show in Menu"
+                tooltip="Hidden pages stay reachable by URL and search"
+            />
+            <ProFormSwitch
+                name="keepAlive"
+                label="Keep Alive"
+                tooltip="Keep the page mounted after navigating away"
+            />
             <ProFormDigit
                 name="sortOrder"
                 label="Sort Order"
@@ -188,7 +188,7 @@ const RoleModalForm = ({ children, record, mode = "create", onSuccess }: RoleMod
     const [form] = Form.useForm();
     const { data: menuOptions = [] } = useQuery({
         queryKey: ["system", "menus", "options"],
-        queryFn: systemAPI.menu.options,
+        queryFn: () => systemAPI.menu.options(),
     });
     const permissionOptions = React.useMemo<PermissionTransferItem[]>(
         () =>
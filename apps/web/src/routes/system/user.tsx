@@ -232,7 +232,7 @@ const UserModalForm = ({
     const [form] = Form.useForm();
     const { data: roleOptions } = useQuery({
         queryKey: ["system", "roles", "options"],
-        queryFn: systemAPI.role.options,
+        queryFn: () => systemAPI.role.options(),
     });
 
     return (
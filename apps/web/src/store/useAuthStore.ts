@@ -3,14 +3,16 @@ import { persist } from "zustand/middleware";
 
 interface AuthState {
     userInfo: Auth.UserInfoResponse | null;
+    /** Bearer token; always null in cookie session mode, where an HttpOnly cookie holds it. */
     token: string | null;
-    handleLogin: (token: string, userInfo: Auth.UserInfoResponse) => void;
-    updateToken: (params: string) => void;
+    handleLogin: (token: string | undefined, userInfo: Auth.UserInfoResponse) => void;
+    updateToken: (params: string | undefined) => void;
     updateAvatar: (avatarUrl: string) => void;
     updateUserInfo: (params: Auth.UserInfoResponse) => void;
     clearAuth: () => void;
     checkPermissions: (code: string) => boolean;
     checkMenuPermissions: (path: string) => boolean;
+    checkMenuVisible: (path: string) => boolean;
 }
 
 export const useAuthStore = create<AuthState>()(
@@ -19,10 +21,10 @@ export const useAuthStore = create<AuthState>()(
             userInfo: null,
             token: null,
             handleLogin: (token, userInfo) => {
-                set({ token, userInfo });
+                set({ token: token ?? null, userInfo });
             },
-            updateToken: (params: string) => {
-                set({ token: params });
+            updateToken: (params: string | undefined) => {
+                set({ token: params ?? null });
             },
             updateAvatar: (avatarUrl: string) => {
                 set({
@@ -62,6 +64,11 @@ export const useAuthStore = create<AuthState>()(
                 const code = formatPathCode(path);
                 return get().checkPermissions(code);
             },
+            checkMenuVisible: (path: string) => {
+                const code = formatPathCode(path);
+                const menu = get().userInfo?.menus?.find((item) => item.code === code);
+                return menu?.visible ?? true;
+            },
         }),
         {
             name: "auth-store",
@@ -1,11 +1,34 @@
 import tailwindcss from "@tailwindcss/vite";
 import { tanstackRouter } from "@tanstack/router-plugin/vite";
 import viteReact from "@vitejs/plugin-react";
+import { writeFileSync } from "node:fs";
+import { join } from "node:path";
+import { brotliCompressSync, gzipSync } from "node:zlib";
+import type { Plugin } from "vite";
 import { defineConfig } from "vite-plus";
 
 const WEB_DEV_PORT = 9800;
 const BACKEND_PORT = 9801;
 
+/** Writes `.br` and `.gz` siblings of text assets; the server picks one per Accept-Encoding. */
+function precompress(): Plugin {
+    return {
+        name: "rustzen-precompress",
+        apply: "build",
+        writeBundle(options, bundle) {
+            const outDir = options.dir ?? "dist";
+            for (const [fileName, output] of Object.entries(bundle)) {
+                if (!/\.(html|js|css|svg|json)$/.test(fileName)) continue;
+                const source = output.type === "chunk" ? output.code : output.source;
+                if (source.length < 1024) continue;
+                const path = join(outDir, fileName);
+                writeFileSync(`${path}.br`, brotliCompressSync(source));
+                writeFileSync(`${path}.gz`, gzipSync(source, { level: 9 }));
+            }
+        },
+    };
+}
+
 // https://vite.dev/config/
 export default defineConfig({
     lint: { options: { typeAware: true, typeCheck: true } },
@@ -17,6 +40,7 @@ export default defineConfig({
         tanstackRouter({ autoCodeSplitting: true }),
         viteReact({ jsxImportSource: "@emotion/react" }),
         tailwindcss(),
+        precompress(),
     ],
     resolve: {
         tsconfigPaths: true,
@@ -25,6 +49,8 @@ export default defineConfig({
         host: "127.0.0.1",
         port: WEB_DEV_PORT,
         open: false,
+        // Keeps HMR on Vite's own port when pages come through RUSTZEN_WEB_DEV_PROXY.
+        hmr: { clientPort: WEB_DEV_PORT },
         proxy: {
             "/api": {
                 target: `http://127.0.0.1:${BACKEND_PORT}`,
@@ -6,4 +6,18 @@ pub struct AuthClaims {
     pub username: String,
     pub exp: usize,
     pub iat: usize,
+    /// Login session the token belongs to, when the server tracks sessions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+    /// When the holder last proved their identity, as a Unix timestamp; older tokens
+    /// fall back to `iat`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_time: Option<usize>,
+}
+
+impl AuthClaims {
+    /// When the holder last proved their identity.
+    pub fn authenticated_at(&self) -> usize {
+        self.auth_time.unwrap_or(self.iat)
+    }
 }
@@ -1,41 +1,214 @@
-use chrono::{Duration, Utc};
-use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{
+    Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, decode_header, encode,
+    errors::{Error, ErrorKind},
+};
+use std::{
+    fmt,
+    sync::{Arc, RwLock},
+};
 
 use super::AuthClaims;
 
+/// `kid` of the single key behind [`JwtCodec::new`].
+pub const DEFAULT_KID: &str = "default";
+
+/// One signing/verification key, named by the `kid` header of the tokens it signs.
+#[derive(Clone)]
+pub struct JwtKey {
+    kid: String,
+    algorithm: Algorithm,
+    encoding: Option<EncodingKey>,
+    decoding: DecodingKey,
+    verify_until: Option<DateTime<Utc>>,
+}
+
+impl JwtKey {
+    /// HS256 key from a shared secret.
+    pub fn hmac(kid: impl Into<String>, secret: &[u8]) -> Self {
+        Self {
+            kid: kid.into(),
+            algorithm: Algorithm::HS256,
+            encoding: Some(EncodingKey::from_secret(secret)),
+            decoding: DecodingKey::from_secret(secret),
+            verify_until: None,
+        }
+    }
+
+    /// RS256 key from PEM files; without the private half it can only verify.
+    pub fn rsa_pem(
+        kid: impl Into<String>,
+        private_pem: Option<&[u8]>,
+        public_pem: &[u8],
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            kid: kid.into(),
+            algorithm: Algorithm::RS256,
+            encoding: private_pem.map(EncodingKey::from_rsa_pem).transpose()?,
+            decoding: DecodingKey::from_rsa_pem(public_pem)?,
+            verify_until: None,
+        })
+    }
+
+    /// Stops the key verifying at `until`, e.g. one token lifetime after it was retired,
+    /// however long the keyring holding it stays loaded.
+    pub fn verifying_until(mut self, until: DateTime<Utc>) -> Self {
+        self.verify_until = Some(until);
+        self
+    }
+
+    fn verifies_at(&self, now: DateTime<Utc>) -> bool {
+        self.verify_until.is_none_or(|until| now < until)
+    }
+
+    pub fn kid(&self) -> &str {
+        &self.kid
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+}
+
+impl fmt::Debug for JwtKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JwtKey")
+            .field("kid", &self.kid)
+            .field("algorithm", &self.algorithm)
+            .field("can_sign", &self.encoding.is_some())
+            .field("verify_until", &self.verify_until)
+            .finish()
+    }
+}
+
+/// The key that signs new tokens plus older keys that still verify.
+#[derive(Debug, Clone)]
+pub struct JwtKeyring {
+    current: JwtKey,
+    previous: Vec<JwtKey>,
+}
+
+impl JwtKeyring {
+    pub fn new(current: JwtKey) -> Self {
+        Self { current, previous: Vec::new() }
+    }
+
+    /// Adds a verify-only key; a `kid` already in the ring is ignored.
+    pub fn with_previous(mut self, key: JwtKey) -> Self {
+        if self.find(&key.kid).is_none() {
+            self.previous.push(key);
+        }
+        self
+    }
+
+    pub fn current(&self) -> &JwtKey {
+        &self.current
+    }
+
+    /// Every key, current first.
+    pub fn keys(&self) -> impl Iterator<Item = &JwtKey> {
+        std::iter::once(&self.current).chain(&self.previous)
+    }
+
+    fn find(&self, kid: &str) -> Option<&JwtKey> {
+        self.keys().find(|key| key.kid == kid)
+    }
+}
+
+/// Issues and checks access tokens against a shared, swappable [`JwtKeyring`].
+///
+/// Clones share the ring, so [`JwtCodec::set_keyring`] takes effect for every holder.
 #[derive(Debug, Clone)]
 pub struct JwtCodec {
-    secret: String,
+    keyring: Arc<RwLock<JwtKeyring>>,
     expiration_seconds: i64,
 }
 
 impl JwtCodec {
     pub fn new(secret: impl Into<String>, expiration_seconds: i64) -> Self {
-        Self { secret: secret.into(), expiration_seconds }
+        let key = JwtKey::hmac(DEFAULT_KID, secret.into().as_bytes());
+        Self::with_keyring(JwtKeyring::new(key), expiration_seconds)
+    }
+
+    pub fn with_keyring(keyring: JwtKeyring, expiration_seconds: i64) -> Self {
+        Self { keyring: Arc::new(RwLock::new(keyring)), expiration_seconds }
     }
 
-    pub fn encode(
+    pub fn expiration_seconds(&self) -> i64 {
+        self.expiration_seconds
+    }
+
+    pub fn keyring(&self) -> JwtKeyring {
+        self.keyring.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn set_keyring(&self, keyring: JwtKeyring) {
+        *self.keyring.write().unwrap_or_else(|e| e.into_inner()) = keyring;
+    }
+
+    /// Signs with the current key and names it in the `kid` header.
+    pub fn encode(&self, user_id: i64, username: &str) -> Result<String, Error> {
+        self.encode_session(user_id, username, None)
+    }
+
+    /// Like [`JwtCodec::encode`], with the login session id in the `sid` claim. Both record
+    /// now as the `auth_time`, so call them only once the holder has proved their identity.
+    pub fn encode_session(
         &self,
         user_id: i64,
         username: &str,
-    ) -> Result<String, jsonwebtoken::errors::Error> {
+        sid: Option<&str>,
+    ) -> Result<String, Error> {
         let now = Utc::now();
         let claims = AuthClaims {
             user_id,
             username: username.to_string(),
             exp: (now + Duration::seconds(self.expiration_seconds)).timestamp() as usize,
             iat: now.timestamp() as usize,
+            sid: sid.map(str::to_string),
+            auth_time: Some(now.timestamp() as usize),
         };
-        encode(&Header::default(), &claims, &EncodingKey::from_secret(self.secret.as_bytes()))
+        let keyring = self.keyring.read().unwrap_or_else(|e| e.into_inner());
+        let key = keyring.current();
+        let encoding = key.encoding.as_ref().ok_or(ErrorKind::InvalidKeyFormat)?;
+        let mut header = Header::new(key.algorithm);
+        header.kid = Some(key.kid.clone());
+        encode(&header, &claims, encoding)
     }
 
-    pub fn decode(&self, token: &str) -> Result<AuthClaims, jsonwebtoken::errors::Error> {
-        let validation = Validation::new(Algorithm::HS256);
-        let token = decode::<AuthClaims>(
-            token,
-            &DecodingKey::from_secret(self.secret.as_bytes()),
-            &validation,
-        )?;
-        Ok(token.claims)
+    /// Verifies with the key named by `kid`, unless that key is past its verify-until time.
+    ///
+    /// Tokens without a `kid` predate key rotation, so every HS256 key is tried for them.
+    pub fn decode(&self, token: &str) -> Result<AuthClaims, Error> {
+        let header = decode_header(token)?;
+        let keyring = self.keyring.read().unwrap_or_else(|e| e.into_inner());
+        let now = Utc::now();
+        match header.kid {
+            Some(kid) => {
+                let key = keyring
+                    .find(&kid)
+                    .filter(|key| key.verifies_at(now))
+                    .ok_or(ErrorKind::InvalidSignature)?;
+                decode_with(token, key)
+            }
+            None => {
+                let mut result = Err(ErrorKind::InvalidSignature.into());
+                let keys = keyring
+                    .keys()
+                    .filter(|key| key.algorithm == Algorithm::HS256 && key.verifies_at(now));
+                for key in keys {
+                    result = decode_with(token, key);
+                    if result.is_ok() {
+                        break;
+                    }
+                }
+                result
+            }
+        }
     }
 }
+
+fn decode_with(token: &str, key: &JwtKey) -> Result<AuthClaims, Error> {
+    let validation = Validation::new(key.algorithm);
+    Ok(decode::<AuthClaims>(token, &key.decoding, &validation)?.claims)
+}
@@ -1,18 +1,53 @@
 use async_trait::async_trait;
 use axum::{
-    extract::{Request, State},
-    http::header,
+    RequestExt,
+    extract::{ConnectInfo, MatchedPath, Request, State},
+    http::{HeaderMap, header},
     middleware::Next,
     response::Response,
 };
 
+use std::net::{IpAddr, SocketAddr};
+
 use crate::error::CoreError;
 
-use super::{AuthClaims, CurrentUser, JwtCodec};
+use super::{AuthClaims, CurrentUser, JwtCodec, PublicRoutes};
 
 #[async_trait]
 pub trait AuthContextLoader: Clone + Send + Sync + 'static {
     async fn load_current_user(&self, claims: &AuthClaims) -> Result<CurrentUser, CoreError>;
+
+    /// Narrows the loaded user to the grants usable by this request, e.g. for roles limited to
+    /// some networks or hours. `client_ip` is `None` when the server was not started with
+    /// connect info. Every grant applies by default.
+    fn scope_to_request(
+        &self,
+        current_user: CurrentUser,
+        _client_ip: Option<IpAddr>,
+    ) -> CurrentUser {
+        current_user
+    }
+
+    /// Cookie to read the token from when no `Authorization` header is sent.
+    fn session_cookie_name(&self) -> Option<&str> {
+        None
+    }
+
+    /// Routes served without a token; everything else behind the middleware needs one.
+    fn public_routes(&self) -> &PublicRoutes {
+        &PublicRoutes::NONE
+    }
+}
+
+/// How the request presented its access token; inserted next to [`CurrentUser`] and the
+/// token's [`AuthClaims`].
+///
+/// Browsers attach cookies on their own, so cookie-authenticated writes need CSRF checks
+/// that Bearer requests do not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenSource {
+    Bearer,
+    Cookie,
 }
 
 pub async fn auth_middleware<L>(
@@ -23,15 +58,50 @@ pub async fn auth_middleware<L>(
 where
     L: AuthContextLoader,
 {
-    let token = request
+    let path = match request.extensions().get::<MatchedPath>() {
+        Some(matched) => matched.as_str(),
+        None => request.uri().path(),
+    };
+    if loader.public_routes().matches(path) {
+        return Ok(next.run(request).await);
+    }
+
+    let bearer = request
         .headers()
         .get(header::AUTHORIZATION)
         .and_then(|value| value.to_str().ok())
-        .and_then(|value| value.strip_prefix("Bearer "))
-        .ok_or(CoreError::InvalidToken)?;
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let (token, source) = match bearer {
+        Some(token) => (token.to_string(), TokenSource::Bearer),
+        None => {
+            let name = loader.session_cookie_name().ok_or(CoreError::InvalidToken)?;
+            let token = cookie_value(request.headers(), name).ok_or(CoreError::InvalidToken)?;
+            (token.to_string(), TokenSource::Cookie)
+        }
+    };
 
-    let claims = codec.decode(token).map_err(|_| CoreError::InvalidToken)?;
+    let claims = codec.decode(&token).map_err(|_| CoreError::InvalidToken)?;
     let current_user = loader.load_current_user(&claims).await?;
+    let client_ip = request
+        .extract_parts::<ConnectInfo<SocketAddr>>()
+        .await
+        .ok()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let current_user = loader.scope_to_request(current_user, client_ip);
     request.extensions_mut().insert(current_user);
+    request.extensions_mut().insert(source);
+    request.extensions_mut().insert(claims);
     Ok(next.run(request).await)
 }
+
+/// Reads one cookie from the `Cookie` request headers.
+pub fn cookie_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
@@ -3,9 +3,11 @@ mod context;
 mod extractor;
 mod jwt;
 mod middleware;
+mod public;
 
 pub use claims::AuthClaims;
 pub use context::CurrentUser;
 pub use extractor::RequireUser;
-pub use jwt::JwtCodec;
-pub use middleware::{AuthContextLoader, auth_middleware};
+pub use jwt::{DEFAULT_KID, JwtCodec, JwtKey, JwtKeyring};
+pub use middleware::{AuthContextLoader, TokenSource, auth_middleware, cookie_value};
+pub use public::PublicRoutes;
@@ -0,0 +1,42 @@
+/// Routes `auth_middleware` lets through without a token.
+///
+/// Patterns are `/`-separated: `{name}` matches any one segment and a trailing `*` matches
+/// the rest of the path. They are checked against the matched route template when there is
+/// one, so `/api/auth/oauth/{provider}/authorize` covers that route exactly.
+#[derive(Debug, Clone, Copy)]
+pub struct PublicRoutes {
+    patterns: &'static [&'static str],
+}
+
+impl PublicRoutes {
+    pub const NONE: PublicRoutes = PublicRoutes::new(&[]);
+
+    pub const fn new(patterns: &'static [&'static str]) -> Self {
+        Self { patterns }
+    }
+
+    pub fn patterns(&self) -> &'static [&'static str] {
+        self.patterns
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        self.patterns.iter().any(|pattern| pattern_matches(pattern, path))
+    }
+}
+
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let mut path_segments = path.trim_end_matches('/').split('/');
+    for expected in pattern.trim_end_matches('/').split('/') {
+        if expected == "*" {
+            return true;
+        }
+        let Some(actual) = path_segments.next() else {
+            return false;
+        };
+        let placeholder = expected.starts_with('{') && expected.ends_with('}');
+        if !(actual == expected || placeholder && !actual.is_empty()) {
+            return false;
+        }
+    }
+    path_segments.next().is_none()
+}
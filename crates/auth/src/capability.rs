@@ -6,6 +6,116 @@ pub fn is_deploy_capability_code(code: &str) -> bool {
     code == "manage:deploy:*" || code.starts_with("manage:deploy:")
 }
 
+/// Every capability code a route may require.
+///
+/// Route codes missing here are reported at startup, so a typo in a
+/// `route_with_permission` call shows up in the logs instead of as a locked feature.
+/// Aliases such as `system_user::OPTIONS` are listed once under their target code.
+pub const REGISTRY: &[&str] = &[
+    dashboard::VIEW,
+    system_user::LIST,
+    system_user::CREATE,
+    system_user::UPDATE,
+    system_user::DELETE,
+    system_user::RESET_PASSWORD,
+    system_user::UPDATE_STATUS,
+    system_user::RESTORE,
+    system_user::PURGE,
+    system_user::ROLE_HISTORY,
+    system_user::ACTIVITY,
+    system_user::EXPORT,
+    system_user::EXPORT_DATA,
+    system_user::ANONYMIZE,
+    system_role::LIST,
+    system_role::CREATE,
+    system_role::UPDATE,
+    system_role::DELETE,
+    system_role::OPTIONS,
+    system_role::MEMBERS,
+    system_role::RESTORE,
+    system_role::PURGE,
+    system_role::EXPORT,
+    system_role::IMPORT,
+    system_rbac::SNAPSHOT,
+    system_rbac::DIFF,
+    system_menu::LIST,
+    system_menu::CREATE,
+    system_menu::UPDATE,
+    system_menu::DELETE,
+    system_menu::OPTIONS,
+    system_menu::PURGE,
+    system_webhook::LIST,
+    system_webhook::CREATE,
+    system_webhook::UPDATE,
+    system_webhook::DELETE,
+    system_webhook::DELIVERIES,
+    system_jwt::LIST,
+    system_jwt::ROTATE,
+    system_approval::LIST,
+    system_approval::APPROVE,
+    system_flag::LIST,
+    system_flag::CREATE,
+    system_flag::UPDATE,
+    system_flag::DELETE,
+    system_tag::LIST,
+    system_tag::CREATE,
+    system_tag::UPDATE,
+    system_tag::DELETE,
+    system_tag::ASSIGN,
+    system_profile_field::LIST,
+    system_profile_field::CREATE,
+    system_profile_field::UPDATE,
+    system_profile_field::DELETE,
+    system_login_alert::LIST,
+    system_login_alert::UPDATE,
+    system_debug_capture::LIST,
+    system_debug_capture::CREATE,
+    system_debug_capture::DELETE,
+    system_policy::LIST,
+    system_policy::PUBLISH,
+    system_registration::LIST,
+    system_registration::APPROVE,
+    system_info::VIEW,
+    system_usage::VIEW,
+    system_license::VIEW,
+    system_report::LIST,
+    system_report::DOWNLOAD,
+    system_export_template::LIST,
+    system_export_template::CREATE,
+    system_export_template::UPDATE,
+    system_export_template::DELETE,
+    system_log::STREAM,
+    system_seed::RUN,
+    system_privacy::VIEW,
+    manage_dict::LIST,
+    manage_dict::CREATE,
+    manage_dict::UPDATE,
+    manage_dict::DELETE,
+    manage_dict::OPTIONS,
+    manage_dict::EXPORT,
+    manage_dict::PURGE,
+    manage_log::LIST,
+    manage_log::EXPORT,
+    manage_log::PURGE,
+    manage_task::LIST,
+    manage_task::RUN,
+    manage_deploy::LIST,
+    manage_deploy::CREATE,
+    manage_deploy::UPDATE,
+    manage_deploy::DELETE,
+    manage_deploy::RUN,
+    workflow_definition::LIST,
+    workflow_definition::CREATE,
+    workflow_definition::UPDATE,
+    workflow_definition::DELETE,
+    workflow_instance::LIST,
+    workflow_instance::START,
+];
+
+pub fn is_registered_capability_code(code: &str) -> bool {
+    REGISTRY.contains(&code)
+}
+
 /// Dashboard capability boundary.
 pub mod dashboard {
     pub const VIEW: &str = "dashboard:view";
@@ -20,6 +130,15 @@ pub mod system_user {
     pub const OPTIONS: &str = "system:user:list";
     pub const RESET_PASSWORD: &str = "system:user:password";
     pub const UPDATE_STATUS: &str = "system:user:status";
+    pub const RESTORE: &str = "system:user:restore";
+    pub const PURGE: &str = "system:user:purge";
+    pub const ROLE_HISTORY: &str = "system:user:history";
+    pub const ACTIVITY: &str = "system:user:activity";
+    /// CSV of the user list.
+    pub const EXPORT: &str = "system:user:export";
+    /// One user's personal data as JSON.
+    pub const EXPORT_DATA: &str = "system:user:export-data";
+    pub const ANONYMIZE: &str = "system:user:anonymize";
 }
 
 /// Role management capability boundaries.
@@ -29,6 +148,17 @@ pub mod system_role {
     pub const UPDATE: &str = "system:role:update";
     pub const DELETE: &str = "system:role:delete";
     pub const OPTIONS: &str = "system:role:options";
+    pub const MEMBERS: &str = "system:role:members";
+    pub const RESTORE: &str = "system:role:restore";
+    pub const PURGE: &str = "system:role:purge";
+    pub const EXPORT: &str = "system:role:export";
+    pub const IMPORT: &str = "system:role:import";
+}
+
+/// Cross-environment RBAC comparison capability boundaries.
+pub mod system_rbac {
+    pub const SNAPSHOT: &str = "system:rbac:snapshot";
+    pub const DIFF: &str = "system:rbac:diff";
 }
 
 /// Menu management capability boundaries.
@@ -38,6 +168,124 @@ pub mod system_menu {
     pub const UPDATE: &str = "system:menu:update";
     pub const DELETE: &str = "system:menu:delete";
     pub const OPTIONS: &str = "system:menu:options";
+    pub const PURGE: &str = "system:menu:purge";
+}
+
+/// Outbound webhook capability boundaries.
+pub mod system_webhook {
+    pub const LIST: &str = "system:webhook:list";
+    pub const CREATE: &str = "system:webhook:create";
+    pub const UPDATE: &str = "system:webhook:update";
+    pub const DELETE: &str = "system:webhook:delete";
+    pub const DELIVERIES: &str = "system:webhook:deliveries";
+}
+
+/// JWT signing key capability boundaries.
+pub mod system_jwt {
+    pub const LIST: &str = "system:jwt:list";
+    pub const ROTATE: &str = "system:jwt:rotate";
+}
+
+/// Dual-control approval capability boundaries.
+pub mod system_approval {
+    pub const LIST: &str = "system:approval:list";
+    pub const APPROVE: &str = "system:approval:approve";
+}
+
+/// Feature flag capability boundary.
+pub mod system_flag {
+    pub const LIST: &str = "system:flag:list";
+    pub const CREATE: &str = "system:flag:create";
+    pub const UPDATE: &str = "system:flag:update";
+    pub const DELETE: &str = "system:flag:delete";
+}
+
+/// Tag capability boundaries. `ASSIGN` sets the tags of users and roles.
+pub mod system_tag {
+    pub const LIST: &str = "system:tag:list";
+    pub const CREATE: &str = "system:tag:create";
+    pub const UPDATE: &str = "system:tag:update";
+    pub const DELETE: &str = "system:tag:delete";
+    pub const ASSIGN: &str = "system:tag:assign";
+}
+
+/// Custom user profile field capability boundary.
+pub mod system_profile_field {
+    pub const LIST: &str = "system:profile-field:list";
+    pub const CREATE: &str = "system:profile-field:create";
+    pub const UPDATE: &str = "system:profile-field:update";
+    pub const DELETE: &str = "system:profile-field:delete";
+}
+
+/// Suspicious login rule capability boundary. Holders of `LIST` also receive the alerts.
+pub mod system_login_alert {
+    pub const LIST: &str = "system:login-alert:list";
+    pub const UPDATE: &str = "system:login-alert:update";
+}
+
+/// Debug capture capability boundaries. Captures record sanitized request bodies in the
+/// operation log for a user or route until they expire.
+pub mod system_debug_capture {
+    pub const LIST: &str = "system:debug-capture:list";
+    pub const CREATE: &str = "system:debug-capture:create";
+    pub const DELETE: &str = "system:debug-capture:delete";
+}
+
+/// Policy document and consent capability boundary.
+pub mod system_policy {
+    pub const LIST: &str = "system:policy:list";
+    pub const PUBLISH: &str = "system:policy:publish";
+}
+
+/// Self-registration approval queue capability boundary.
+pub mod system_registration {
+    pub const LIST: &str = "system:registration:list";
+    pub const APPROVE: &str = "system:registration:approve";
+}
+
+/// System info panel capability boundary.
+pub mod system_info {
+    pub const VIEW: &str = "system:info:view";
+}
+
+/// Quota usage capability boundary.
+pub mod system_usage {
+    pub const VIEW: &str = "system:usage:view";
+}
+
+/// License and edition capability boundary.
+pub mod system_license {
+    pub const VIEW: &str = "system:license:view";
+}
+
+/// Generated report capability boundaries.
+pub mod system_report {
+    pub const LIST: &str = "system:report:list";
+    pub const DOWNLOAD: &str = "system:report:download";
+}
+
+/// Export template capability boundary. Exporters may list templates to pick one.
+pub mod system_export_template {
+    pub const LIST: &str = "system:export-template:list";
+    pub const CREATE: &str = "system:export-template:create";
+    pub const UPDATE: &str = "system:export-template:update";
+    pub const DELETE: &str = "system:export-template:delete";
+}
+
+/// Live server log capability boundary.
+pub mod system_log {
+    pub const STREAM: &str = "system:log:stream";
+}
+
+/// Demo data seeding capability boundary.
+pub mod system_seed {
+    pub const RUN: &str = "system:seed:run";
+}
+
+/// Unmasked personal data capability boundary. Without it, emails, phone numbers and
+/// client IPs in lists and logs are partially masked.
+pub mod system_privacy {
+    pub const VIEW: &str = "system:privacy:view";
 }
 
 /// Dictionary management capability boundaries.
@@ -47,12 +295,15 @@ pub mod manage_dict {
     pub const UPDATE: &str = "manage:dict:update";
     pub const DELETE: &str = "manage:dict:delete";
     pub const OPTIONS: &str = "manage:dict:options";
+    pub const EXPORT: &str = "manage:dict:export";
+    pub const PURGE: &str = "manage:dict:purge";
 }
 
 /// Log management capability boundaries.
 pub mod manage_log {
     pub const LIST: &str = "manage:log:list";
     pub const EXPORT: &str = "manage:log:export";
+    pub const PURGE: &str = "manage:log:purge";
 }
 
 /// Scheduled task capability boundaries.
@@ -61,6 +312,20 @@ pub mod manage_task {
     pub const RUN: &str = "manage:task:run";
 }
 
+/// Workflow definition capability boundaries.
+pub mod workflow_definition {
+    pub const LIST: &str = "workflow:definition:list";
+    pub const CREATE: &str = "workflow:definition:create";
+    pub const UPDATE: &str = "workflow:definition:update";
+    pub const DELETE: &str = "workflow:definition:delete";
+}
+
+/// Workflow instance capability boundaries; the task inbox only needs role membership.
+pub mod workflow_instance {
+    pub const LIST: &str = "workflow:instance:list";
+    pub const START: &str = "workflow:instance:start";
+}
+
 /// Deploy version capability boundaries.
 pub mod manage_deploy {
     pub const LIST: &str = "manage:deploy:list";
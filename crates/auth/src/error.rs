@@ -3,7 +3,7 @@ use axum::{
     response::{IntoResponse, Response},
 };
 
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, Copy, thiserror::Error)]
 pub enum CoreError {
     #[error("Invalid or expired token")]
     InvalidToken,
@@ -13,12 +13,16 @@ pub enum CoreError {
     PermissionDenied,
 }
 
+/// Bare `401`/`403` carrying the error as an extension, so the application can render it
+/// in its own error format.
 impl IntoResponse for CoreError {
     fn into_response(self) -> Response {
         let status = match self {
             CoreError::InvalidToken | CoreError::MissingAuthContext => StatusCode::UNAUTHORIZED,
             CoreError::PermissionDenied => StatusCode::FORBIDDEN,
         };
-        status.into_response()
+        let mut response = status.into_response();
+        response.extensions_mut().insert(self);
+        response
     }
 }
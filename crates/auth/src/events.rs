@@ -0,0 +1,239 @@
+//! In-process domain event bus.
+//!
+//! Services publish a [`DomainEvent`] after their write commits; subscribers such as the
+//! audit logger and the webhook dispatcher react to it without the service knowing they
+//! exist. `C` is the context handed to every subscriber, e.g. the server's database pool.
+//! Events serialize to JSON so the server can keep them in its outbox until every
+//! subscriber has handled them.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Something that happened in the admin domain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DomainEvent {
+    UserCreated {
+        user_id: i64,
+        username: String,
+        email: String,
+        operator_id: Option<i64>,
+    },
+    UserUpdated {
+        user_id: i64,
+        username: String,
+        role_ids: Vec<i64>,
+        operator_id: i64,
+    },
+    UserDeleted {
+        user_id: i64,
+        username: String,
+        operator_id: i64,
+    },
+    RoleCreated {
+        role_id: i64,
+        name: String,
+        code: String,
+        operator_id: i64,
+    },
+    RoleUpdated {
+        role_id: i64,
+        name: String,
+        code: String,
+        operator_id: i64,
+    },
+    RoleDeleted {
+        role_id: i64,
+        operator_id: i64,
+    },
+    /// Users put into a role from the role side (membership edit or transfer).
+    RoleAssigned {
+        role_id: i64,
+        user_ids: Vec<i64>,
+        operator_id: i64,
+    },
+    LoginSucceeded {
+        user_id: i64,
+        username: String,
+        ip_address: String,
+        user_agent: String,
+        duration_ms: i32,
+    },
+    LoginFailed {
+        username: String,
+        reason: String,
+        ip_address: String,
+        user_agent: String,
+        duration_ms: i32,
+    },
+    /// A source IP hit the failed-login threshold and is refused for `ban_secs`.
+    LoginIpBanned {
+        ip_address: String,
+        failures: u32,
+        ban_secs: u64,
+    },
+    /// A user signed in from a different country than their previous login.
+    LoginLocationChanged {
+        user_id: i64,
+        username: String,
+        ip_address: String,
+        country: String,
+        city: Option<String>,
+        previous_country: String,
+    },
+    /// A login rule flagged a successful login, e.g. from a new device.
+    SuspiciousLogin {
+        user_id: i64,
+        username: String,
+        rule: String,
+        ip_address: String,
+        detail: String,
+    },
+    /// Someone downloaded data in bulk, such as a CSV of operation logs.
+    DataExported {
+        user_id: i64,
+        username: String,
+        /// What was exported, e.g. `logs` or `users`.
+        resource: String,
+        /// Query string of the export request, or the JSON filters of an export job.
+        filters: String,
+        rows: u64,
+        ip_address: String,
+    },
+    /// A workflow instance was approved, rejected or cancelled.
+    WorkflowFinished {
+        instance_id: i64,
+        definition_code: String,
+        status: String,
+        started_by: i64,
+        operator_id: i64,
+    },
+}
+
+impl DomainEvent {
+    /// Stable dotted name used in logs.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DomainEvent::UserCreated { .. } => "user.created",
+            DomainEvent::UserUpdated { .. } => "user.updated",
+            DomainEvent::UserDeleted { .. } => "user.deleted",
+            DomainEvent::RoleCreated { .. } => "role.created",
+            DomainEvent::RoleUpdated { .. } => "role.updated",
+            DomainEvent::RoleDeleted { .. } => "role.deleted",
+            DomainEvent::RoleAssigned { .. } => "role.assigned",
+            DomainEvent::LoginSucceeded { .. } => "login.succeeded",
+            DomainEvent::LoginFailed { .. } => "login.failed",
+            DomainEvent::LoginIpBanned { .. } => "login.ip_banned",
+            DomainEvent::LoginLocationChanged { .. } => "login.unusual_location",
+            DomainEvent::SuspiciousLogin { .. } => "login.suspicious",
+            DomainEvent::DataExported { .. } => "data.exported",
+            DomainEvent::WorkflowFinished { .. } => "workflow.finished",
+        }
+    }
+}
+
+/// Reacts to published events. A failing subscriber must not affect the publisher or the
+/// other subscribers; it returns the error so the event can be handed to it again.
+#[async_trait]
+pub trait EventSubscriber<C>: Send + Sync {
+    /// Stable name; the server's outbox stores it with each pending delivery.
+    fn name(&self) -> &'static str;
+
+    async fn handle(&self, ctx: &C, event: &DomainEvent) -> Result<(), String>;
+}
+
+/// Fixed list of subscribers, called in registration order on every publish.
+pub struct EventBus<C> {
+    subscribers: Vec<Arc<dyn EventSubscriber<C>>>,
+}
+
+impl<C> Default for EventBus<C> {
+    fn default() -> Self {
+        Self { subscribers: Vec::new() }
+    }
+}
+
+impl<C: Sync> EventBus<C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn subscribe(mut self, subscriber: Arc<dyn EventSubscriber<C>>) -> Self {
+        self.subscribers.push(subscriber);
+        self
+    }
+
+    /// Names of the subscribers, in registration order.
+    pub fn subscriber_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.subscribers.iter().map(|subscriber| subscriber.name())
+    }
+
+    /// Runs every subscriber before returning, so callers observe their effects. Errors are
+    /// logged and not retried.
+    pub async fn publish(&self, ctx: &C, event: DomainEvent) {
+        for subscriber in &self.subscribers {
+            tracing::debug!("Event {} -> {}", event.name(), subscriber.name());
+            if let Err(e) = subscriber.handle(ctx, &event).await {
+                tracing::error!(
+                    "Subscriber {} failed on {}: {}",
+                    subscriber.name(),
+                    event.name(),
+                    e
+                );
+            }
+        }
+    }
+
+    /// Hands `event` to the subscriber called `name` alone.
+    pub async fn deliver(&self, ctx: &C, name: &str, event: &DomainEvent) -> Result<(), String> {
+        let subscriber = self
+            .subscribers
+            .iter()
+            .find(|subscriber| subscriber.name() == name)
+            .ok_or_else(|| format!("no subscriber named {}", name))?;
+        tracing::debug!("Event {} -> {}", event.name(), name);
+        subscriber.handle(ctx, event).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DomainEvent, EventBus, EventSubscriber};
+    use async_trait::async_trait;
+    use std::sync::{Arc, Mutex};
+
+    struct Recorder(&'static str);
+
+    #[async_trait]
+    impl EventSubscriber<Mutex<Vec<String>>> for Recorder {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        async fn handle(
+            &self,
+            ctx: &Mutex<Vec<String>>,
+            event: &DomainEvent,
+        ) -> Result<(), String> {
+            ctx.lock().unwrap().push(format!("{}:{}", self.0, event.name()));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn publish_reaches_every_subscriber_in_order() {
+        let bus = EventBus::new()
+            .subscribe(Arc::new(Recorder("audit")))
+            .subscribe(Arc::new(Recorder("webhook")));
+        let seen = Mutex::new(Vec::new());
+
+        bus.publish(&seen, DomainEvent::RoleDeleted { role_id: 3, operator_id: 1 }).await;
+
+        assert_eq!(*seen.lock().unwrap(), vec!["audit:role.deleted", "webhook:role.deleted"]);
+
+        let event = DomainEvent::RoleDeleted { role_id: 4, operator_id: 1 };
+        bus.deliver(&seen, "webhook", &event).await.unwrap();
+        assert_eq!(seen.lock().unwrap().last().unwrap(), "webhook:role.deleted");
+        assert!(bus.deliver(&seen, "mailer", &event).await.is_err());
+    }
+}
@@ -1,4 +1,7 @@
 pub mod auth;
 pub mod capability;
 pub mod error;
+pub mod events;
+pub mod oauth;
 pub mod permission;
+pub mod sms;
@@ -0,0 +1,40 @@
+//! Scan-to-login through enterprise identity providers.
+//!
+//! A [`LoginConnector`] wraps one provider (WeChat Work, DingTalk, Feishu): it builds the
+//! page the browser opens to scan the QR code, and turns the `code` the provider hands back
+//! into an [`ExternalIdentity`]. The server links identities to its own users; connectors
+//! know nothing about local accounts.
+
+use async_trait::async_trait;
+
+/// The provider's account behind a completed login.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ExternalIdentity {
+    /// Stable id of the account at the provider, e.g. a WeChat Work `userid`.
+    pub subject: String,
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub mobile: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConnectorError {
+    #[error("identity provider unreachable: {0}")]
+    Unreachable(String),
+    #[error("identity provider rejected the login: {0}")]
+    Rejected(String),
+}
+
+/// One enterprise identity provider.
+#[async_trait]
+pub trait LoginConnector: Send + Sync {
+    /// Provider key used in routes and stored links, e.g. `wecom`.
+    fn provider(&self) -> &'static str;
+
+    /// Provider page that shows the QR code and redirects to `redirect_uri` with `code`
+    /// and `state` once the user confirms.
+    fn authorize_url(&self, redirect_uri: &str, state: &str) -> String;
+
+    /// Exchanges the `code` from the redirect for the account that signed in.
+    async fn exchange_code(&self, code: &str) -> Result<ExternalIdentity, ConnectorError>;
+}
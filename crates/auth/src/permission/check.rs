@@ -36,18 +36,30 @@ impl PermissionsCheck {
     }
 }
 
-fn has_permission(user: &CurrentUser, code: &str) -> bool {
-    if user.permissions.contains("*") || user.permissions.contains(code) {
-        return true;
+impl CurrentUser {
+    /// Whether the user holds a runtime capability code, directly or through a wildcard.
+    pub fn has_capability(&self, code: &str) -> bool {
+        self.is_super || has_permission(self, code)
     }
 
-    let parts: Vec<&str> = code.split(':').collect();
-    for index in (1..parts.len()).rev() {
-        let wildcard = format!("{}:*", parts[..index].join(":"));
-        if user.permissions.contains(wildcard.as_str()) {
-            return true;
+    /// The held code that grants `code`: the code itself, the nearest prefix wildcard, or `*`.
+    pub fn granting_capability(&self, code: &str) -> Option<String> {
+        if self.permissions.contains(code) {
+            return Some(code.to_string());
+        }
+
+        let parts: Vec<&str> = code.split(':').collect();
+        for index in (1..parts.len()).rev() {
+            let wildcard = format!("{}:*", parts[..index].join(":"));
+            if self.permissions.contains(wildcard.as_str()) {
+                return Some(wildcard);
+            }
         }
+
+        (self.is_super || self.permissions.contains("*")).then(|| "*".to_string())
     }
+}
 
-    false
+fn has_permission(user: &CurrentUser, code: &str) -> bool {
+    user.granting_capability(code).is_some()
 }
@@ -3,5 +3,8 @@ mod registry;
 mod route;
 
 pub use check::PermissionsCheck;
-pub use registry::{register_permission_codes, take_registered_permission_codes};
+pub use registry::{
+    RoutePermission, register_permission_codes, take_registered_permission_codes,
+    take_route_permissions,
+};
 pub use route::RouterExt;
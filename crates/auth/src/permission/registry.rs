@@ -1,10 +1,25 @@
-use std::sync::RwLock;
+use std::{cell::RefCell, sync::RwLock};
 
 use once_cell::sync::Lazy;
 use tracing::debug;
 
+use super::PermissionsCheck;
+
 static ROUTE_PERMISSION_CODES: Lazy<RwLock<Vec<String>>> = Lazy::new(|| RwLock::new(Vec::new()));
 
+thread_local! {
+    /// Routers are built on one thread, so each build sees only its own routes.
+    static ROUTE_PERMISSIONS: RefCell<Vec<RoutePermission>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A route guarded by `route_with_permission`, for the startup route map.
+#[derive(Debug, Clone)]
+pub struct RoutePermission {
+    /// Path template, prefixed by every enclosing `nest_routes`.
+    pub path: String,
+    pub check: PermissionsCheck,
+}
+
 pub fn register_permission_codes<I>(codes: I)
 where
     I: IntoIterator<Item = &'static str>,
@@ -21,3 +36,31 @@ pub fn take_registered_permission_codes() -> Vec<String> {
     debug!("Took {} registered capability codes", codes.len());
     codes
 }
+
+pub(crate) fn record_route_permission(path: &str, check: PermissionsCheck) {
+    let route = RoutePermission { path: path.to_string(), check };
+    ROUTE_PERMISSIONS.with_borrow_mut(|routes| routes.push(route));
+}
+
+/// Position to pass to [`prefix_route_permissions`] once a nested router is built.
+pub(crate) fn route_permissions_mark() -> usize {
+    ROUTE_PERMISSIONS.with_borrow(Vec::len)
+}
+
+/// Puts `prefix` in front of the routes recorded since `mark`.
+pub(crate) fn prefix_route_permissions(mark: usize, prefix: &str) {
+    let prefix = prefix.trim_end_matches('/');
+    ROUTE_PERMISSIONS.with_borrow_mut(|routes| {
+        for route in routes.iter_mut().skip(mark) {
+            route.path = match route.path.as_str() {
+                "/" => prefix.to_string(),
+                path => format!("{prefix}{path}"),
+            };
+        }
+    });
+}
+
+/// Routes guarded on this thread since the last call, in registration order.
+pub fn take_route_permissions() -> Vec<RoutePermission> {
+    ROUTE_PERMISSIONS.with_borrow_mut(std::mem::take)
+}
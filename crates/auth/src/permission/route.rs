@@ -3,7 +3,10 @@ use axum::{Router, extract::Request, middleware::Next, response::Response, routi
 use crate::{
     auth::CurrentUser,
     error::CoreError,
-    permission::{PermissionsCheck, register_permission_codes},
+    permission::{
+        PermissionsCheck, register_permission_codes,
+        registry::{prefix_route_permissions, record_route_permission, route_permissions_mark},
+    },
 };
 
 pub trait RouterExt<S> {
@@ -13,6 +16,9 @@ pub trait RouterExt<S> {
         method_router: MethodRouter<S>,
         permissions_check: PermissionsCheck,
     ) -> Self;
+
+    /// `Router::nest` for a router built by `build`, keeping the route map's paths whole.
+    fn nest_routes(self, path: &str, build: impl FnOnce() -> Self) -> Self;
 }
 
 impl<S> RouterExt<S> for Router<S>
@@ -28,6 +34,7 @@ where
         let codes = permissions_check.codes();
         tracing::debug!("route_with_permission called for path='{:?}', codes={:?}", path, codes);
         register_permission_codes(codes);
+        record_route_permission(path, permissions_check.clone());
         self.route(
             path,
             method_router.layer(axum::middleware::from_fn(move |request: Request, next: Next| {
@@ -36,6 +43,13 @@ where
             })),
         )
     }
+
+    fn nest_routes(self, path: &str, build: impl FnOnce() -> Self) -> Self {
+        let mark = route_permissions_mark();
+        let router = build();
+        prefix_route_permissions(mark, path);
+        self.nest(path, router)
+    }
 }
 
 async fn permission_middleware(
@@ -0,0 +1,99 @@
+//! SMS delivery of one-time codes.
+//!
+//! [`SmsProvider`] hides the gateway (Twilio, Aliyun) from the services that send codes,
+//! such as phone login and verification of critical actions; the server picks the
+//! implementation from its configuration. Phone numbers are handled in E.164 form
+//! (`+` and 8 to 15 digits) everywhere.
+
+use async_trait::async_trait;
+
+/// One-time code to deliver.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OtpMessage {
+    pub code: String,
+    /// Minutes until the code expires, for the text shown to the user.
+    pub ttl_minutes: u32,
+}
+
+impl OtpMessage {
+    /// Plain-text body for gateways that send free text.
+    pub fn text(&self) -> String {
+        format!(
+            "Your rustzen-admin verification code is {}. It expires in {} minutes.",
+            self.code, self.ttl_minutes
+        )
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SmsError {
+    #[error("SMS gateway unreachable: {0}")]
+    Unreachable(String),
+    #[error("SMS gateway rejected the message: {0}")]
+    Rejected(String),
+}
+
+/// A gateway that can deliver a one-time code to a phone number.
+#[async_trait]
+pub trait SmsProvider: Send + Sync {
+    /// Short name for logs, e.g. `twilio`.
+    fn name(&self) -> &'static str;
+
+    /// Sends `message` to `phone`, an E.164 number from [`normalize_phone`].
+    async fn send_otp(&self, phone: &str, message: &OtpMessage) -> Result<(), SmsError>;
+}
+
+/// E.164 form of `raw` with spaces, dashes, dots and parentheses removed; `None` when it
+/// is not `+` followed by 8 to 15 digits.
+pub fn normalize_phone(raw: &str) -> Option<String> {
+    let phone: String = raw.chars().filter(|c| !matches!(c, ' ' | '-' | '.' | '(' | ')')).collect();
+    let digits = phone.strip_prefix('+')?;
+    let valid = (8..=15).contains(&digits.len())
+        && digits.bytes().all(|b| b.is_ascii_digit())
+        && !digits.starts_with('0');
+    valid.then_some(phone)
+}
+
+/// `phone` with all but the first four and last four characters replaced by `*`.
+pub fn mask_phone(phone: &str) -> String {
+    let chars: Vec<char> = phone.chars().collect();
+    if chars.len() <= 8 {
+        let keep = chars.len().min(2);
+        let masked = chars.len() - keep;
+        return "*".repeat(masked) + &chars[masked..].iter().collect::<String>();
+    }
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, c)| if i < 4 || i >= chars.len() - 4 { *c } else { '*' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OtpMessage, mask_phone, normalize_phone};
+
+    #[test]
+    fn phones_are_normalized_to_e164() {
+        assert_eq!(normalize_phone("+86 138-1234-5678").as_deref(), Some("+8613812345678"));
+        assert_eq!(normalize_phone("+1 (415) 555.0100").as_deref(), Some("+14155550100"));
+        assert_eq!(normalize_phone("13812345678"), None);
+        assert_eq!(normalize_phone("+0123456789"), None);
+        assert_eq!(normalize_phone("+1234"), None);
+        assert_eq!(normalize_phone("+86138abc5678"), None);
+    }
+
+    #[test]
+    fn masked_phones_keep_the_prefix_and_last_four_digits() {
+        assert_eq!(mask_phone("+8613812345678"), "+861******5678");
+        assert_eq!(mask_phone("+14155550100"), "+141****0100");
+        assert_eq!(mask_phone("+1234"), "***34");
+    }
+
+    #[test]
+    fn otp_text_names_the_code_and_its_lifetime() {
+        let message = OtpMessage { code: "123456".to_string(), ttl_minutes: 5 };
+        assert!(message.text().contains("123456"));
+        assert!(message.text().contains("5 minutes"));
+    }
+}
@@ -1,19 +1,28 @@
-use std::{collections::HashSet, sync::Arc};
+use std::{
+    collections::HashSet,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
 
 use async_trait::async_trait;
 use axum::{
     Router,
     body::Body,
+    extract::connect_info::MockConnectInfo,
     http::{Request, StatusCode},
     middleware,
     response::IntoResponse,
     routing::get,
 };
 use rustzen_core::{
-    auth::{AuthClaims, AuthContextLoader, CurrentUser, JwtCodec, auth_middleware},
+    auth::{
+        AuthClaims, AuthContextLoader, CurrentUser, JwtCodec, JwtKey, JwtKeyring, PublicRoutes,
+        TokenSource, auth_middleware,
+    },
     error::CoreError,
     permission::{
         PermissionsCheck, RouterExt, register_permission_codes, take_registered_permission_codes,
+        take_route_permissions,
     },
 };
 use tower::util::ServiceExt;
@@ -27,8 +36,71 @@ async fn jwt_codec_round_trip() {
 
     assert_eq!(
         claims,
-        AuthClaims { user_id: 7, username: "alice".to_string(), exp: claims.exp, iat: claims.iat }
+        AuthClaims {
+            user_id: 7,
+            username: "alice".to_string(),
+            exp: claims.exp,
+            iat: claims.iat,
+            sid: None,
+            auth_time: Some(claims.iat),
+        }
     );
+
+    let token = codec.encode_session(7, "alice", Some("s1")).expect("token should encode");
+    assert_eq!(codec.decode(&token).expect("token should decode").sid.as_deref(), Some("s1"));
+}
+
+#[tokio::test]
+async fn jwt_rotation_keeps_previous_keys_verifying() {
+    let codec = JwtCodec::with_keyring(JwtKeyring::new(JwtKey::hmac("k1", b"first")), 3600);
+    let old_token = codec.encode(7, "alice").expect("token should encode");
+    let legacy_token = jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &codec.decode(&old_token).expect("token should decode"),
+        &jsonwebtoken::EncodingKey::from_secret(b"first"),
+    )
+    .expect("legacy token should encode");
+
+    codec.set_keyring(
+        JwtKeyring::new(JwtKey::hmac("k2", b"second")).with_previous(JwtKey::hmac("k1", b"first")),
+    );
+    let new_token = codec.encode(7, "alice").expect("token should encode");
+
+    assert_eq!(jsonwebtoken::decode_header(&new_token).unwrap().kid.as_deref(), Some("k2"));
+    assert!(codec.decode(&old_token).is_ok());
+    assert!(codec.decode(&legacy_token).is_ok());
+    assert!(codec.decode(&new_token).is_ok());
+
+    let retired = JwtKey::hmac("k1", b"first").verifying_until(chrono::Utc::now());
+    codec.set_keyring(JwtKeyring::new(JwtKey::hmac("k2", b"second")).with_previous(retired));
+    assert!(codec.decode(&old_token).is_err());
+    assert!(codec.decode(&legacy_token).is_err());
+    assert!(codec.decode(&new_token).is_ok());
+
+    codec.set_keyring(JwtKeyring::new(JwtKey::hmac("k2", b"second")));
+    assert!(codec.decode(&old_token).is_err());
+    assert!(codec.decode(&legacy_token).is_err());
+    assert!(codec.decode(&new_token).is_ok());
+}
+
+#[tokio::test]
+async fn jwt_rs256_keys_sign_and_verify_only_keys_cannot_sign() {
+    let private_pem = include_bytes!("fixtures/jwt_rs256_private.pem");
+    let public_pem = include_bytes!("fixtures/jwt_rs256_public.pem");
+    let signer = JwtKey::rsa_pem("rs1", Some(private_pem), public_pem).expect("rsa key");
+    let codec = JwtCodec::with_keyring(
+        JwtKeyring::new(signer).with_previous(JwtKey::hmac("k1", b"first")),
+        3600,
+    );
+
+    let token = codec.encode(3, "bob").expect("token should encode");
+    assert_eq!(jsonwebtoken::decode_header(&token).unwrap().alg, jsonwebtoken::Algorithm::RS256);
+    assert_eq!(codec.decode(&token).expect("token should decode").user_id, 3);
+
+    let verifier = JwtKey::rsa_pem("rs1", None, public_pem).expect("rsa key");
+    let verify_only = JwtCodec::with_keyring(JwtKeyring::new(verifier), 3600);
+    assert!(verify_only.decode(&token).is_ok());
+    assert!(verify_only.encode(3, "bob").is_err());
 }
 
 #[tokio::test]
@@ -84,6 +156,74 @@ fn registry_collects_and_clears_codes() {
     assert!(take_registered_permission_codes().is_empty());
 }
 
+#[test]
+fn nested_routes_record_their_full_paths_and_checks() {
+    let _ = take_route_permissions();
+    let users = || {
+        Router::<()>::new()
+            .route_with_permission(
+                "/",
+                get(|| async {}),
+                PermissionsCheck::Require("system:user:list"),
+            )
+            .route_with_permission(
+                "/{id}",
+                get(|| async {}),
+                PermissionsCheck::Any(vec!["system:user:update", "system:user:list"]),
+            )
+    };
+    let _router = Router::<()>::new()
+        .nest_routes("/system", || Router::new().nest_routes("/users", users))
+        .route_with_permission("/other", get(|| async {}), PermissionsCheck::Require("other"));
+
+    let routes: Vec<(String, Vec<&str>)> =
+        take_route_permissions().into_iter().map(|r| (r.path, r.check.codes())).collect();
+    assert_eq!(
+        routes,
+        [
+            ("/system/users".to_string(), vec!["system:user:list"]),
+            ("/system/users/{id}".to_string(), vec!["system:user:update", "system:user:list"]),
+            ("/other".to_string(), vec!["other"]),
+        ]
+    );
+    assert!(take_route_permissions().is_empty());
+}
+
+#[test]
+fn public_route_patterns_match_segments_placeholders_and_tails() {
+    let routes =
+        PublicRoutes::new(&["/api/auth/login", "/api/oauth/{provider}/authorize", "/docs/*"]);
+
+    assert!(routes.matches("/api/auth/login"));
+    assert!(routes.matches("/api/auth/login/"));
+    assert!(routes.matches("/api/oauth/wecom/authorize"));
+    assert!(routes.matches("/api/oauth/{provider}/authorize"));
+    assert!(routes.matches("/docs/guides/setup"));
+    assert!(!routes.matches("/api/auth/login/sms"));
+    assert!(!routes.matches("/api/auth"));
+    assert!(!routes.matches("/api/oauth//authorize"));
+    assert!(!PublicRoutes::NONE.matches("/api/auth/login"));
+}
+
+#[tokio::test]
+async fn auth_middleware_lets_public_routes_through_without_a_token() {
+    let codec = JwtCodec::new("secret", 3600);
+    let app = Router::new()
+        .route("/login", get(|| async { "public" }))
+        .route("/me", get(|user: CurrentUser| async move { user.username }))
+        .route_layer(middleware::from_fn_with_state((codec, PublicLoader), auth_middleware));
+    let status = |uri: &'static str| {
+        let app = app.clone();
+        async move {
+            let request = Request::builder().uri(uri).body(Body::empty()).expect("request");
+            app.oneshot(request).await.expect("response").status()
+        }
+    };
+
+    assert_eq!(status("/login").await, StatusCode::OK);
+    assert_eq!(status("/me").await, StatusCode::UNAUTHORIZED);
+}
+
 #[tokio::test]
 async fn route_permission_denies_missing_permission() {
     let app = Router::new()
@@ -125,6 +265,62 @@ async fn auth_middleware_inserts_loaded_user() {
     assert_eq!(response.status(), StatusCode::OK);
 }
 
+#[tokio::test]
+async fn auth_middleware_reads_the_session_cookie_only_when_the_loader_names_one() {
+    let codec = JwtCodec::new("secret", 3600);
+    let token = codec.encode(9, "alice").expect("token should encode");
+    let request = || {
+        Request::builder()
+            .uri("/me")
+            .header("cookie", format!("theme=dark; sid={token}"))
+            .body(Body::empty())
+            .expect("request")
+    };
+    let source = |source: axum::Extension<TokenSource>| async move { format!("{:?}", source.0) };
+
+    let app = Router::new()
+        .route("/me", get(source))
+        .route_layer(middleware::from_fn_with_state((codec.clone(), FixedLoader), auth_middleware));
+    let response = app.oneshot(request()).await.expect("response");
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let app = Router::new()
+        .route("/me", get(source))
+        .route_layer(middleware::from_fn_with_state((codec, CookieLoader), auth_middleware));
+    let response = app.oneshot(request()).await.expect("response");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), 1024).await.expect("body");
+    assert_eq!(&body[..], b"Cookie");
+}
+
+#[tokio::test]
+async fn auth_middleware_lets_the_loader_scope_grants_to_the_client_address() {
+    let codec = JwtCodec::new("secret", 3600);
+    let token = codec.encode(9, "alice").expect("token should encode");
+    let grants = |user: CurrentUser| async move { user.permissions.len().to_string() };
+    let call = |client: &str| {
+        let app = Router::new()
+            .route("/me", get(grants))
+            .route_layer(middleware::from_fn_with_state(
+                (codec.clone(), ScopedLoader),
+                auth_middleware,
+            ))
+            .layer(MockConnectInfo(client.parse::<SocketAddr>().expect("address")));
+        let request = Request::builder()
+            .uri("/me")
+            .header("authorization", format!("Bearer {token}"))
+            .body(Body::empty())
+            .expect("request");
+        async move {
+            let response = app.oneshot(request).await.expect("response");
+            axum::body::to_bytes(response.into_body(), 1024).await.expect("body")
+        }
+    };
+
+    assert_eq!(&call("10.0.0.7:5000").await[..], b"1");
+    assert_eq!(&call("192.0.2.1:5000").await[..], b"0");
+}
+
 async fn inject_user_without_permission(
     mut request: Request<Body>,
     next: middleware::Next,
@@ -147,3 +343,54 @@ impl AuthContextLoader for FixedLoader {
         ))
     }
 }
+
+#[derive(Clone)]
+struct CookieLoader;
+
+#[async_trait]
+impl AuthContextLoader for CookieLoader {
+    async fn load_current_user(&self, claims: &AuthClaims) -> Result<CurrentUser, CoreError> {
+        FixedLoader.load_current_user(claims).await
+    }
+
+    fn session_cookie_name(&self) -> Option<&str> {
+        Some("sid")
+    }
+}
+
+/// Keeps grants only for clients on `10.0.0.0/8`.
+#[derive(Clone)]
+struct ScopedLoader;
+
+#[async_trait]
+impl AuthContextLoader for ScopedLoader {
+    async fn load_current_user(&self, claims: &AuthClaims) -> Result<CurrentUser, CoreError> {
+        FixedLoader.load_current_user(claims).await
+    }
+
+    fn scope_to_request(
+        &self,
+        current_user: CurrentUser,
+        client_ip: Option<IpAddr>,
+    ) -> CurrentUser {
+        match client_ip {
+            Some(IpAddr::V4(ip)) if ip.octets()[0] == 10 => current_user,
+            _ => CurrentUser::new(current_user.user_id, current_user.username, Vec::new(), false),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct PublicLoader;
+
+#[async_trait]
+impl AuthContextLoader for PublicLoader {
+    async fn load_current_user(&self, claims: &AuthClaims) -> Result<CurrentUser, CoreError> {
+        FixedLoader.load_current_user(claims).await
+    }
+
+    fn public_routes(&self) -> &PublicRoutes {
+        const ROUTES: PublicRoutes = PublicRoutes::new(&["/login"]);
+        &ROUTES
+    }
+}
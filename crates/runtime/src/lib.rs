@@ -59,6 +59,16 @@ impl RuntimeLayout {
         self.data_dir().join("uploads")
     }
 
+    /// Generated report files.
+    pub fn reports_dir(&self) -> PathBuf {
+        self.data_dir().join("reports")
+    }
+
+    /// Files written by background export jobs.
+    pub fn exports_dir(&self) -> PathBuf {
+        self.data_dir().join("exports")
+    }
+
     /// Public avatar prefix for static file route.
     pub fn avatars_prefix(&self) -> String {
         format!("{}/avatars", self.files_prefix.trim_end_matches('/'))
@@ -5,5 +5,5 @@ pub mod sqlite;
 
 pub use sqlite::{
     DatabaseConnectionOptions, SqlitePool, connect_sqlite, connect_sqlite_with_options,
-    database_url_from_path, test_connection,
+    database_url_from_path, is_in_memory_url, test_connection,
 };
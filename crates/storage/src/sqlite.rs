@@ -1,6 +1,8 @@
 use std::time::Duration;
 use std::{io, path::{Path, PathBuf}};
 
+use log::LevelFilter;
+use sqlx::ConnectOptions;
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 
 pub use sqlx::SqlitePool;
@@ -16,6 +18,11 @@ pub struct DatabaseConnectionOptions {
     pub connect_timeout: Duration,
     /// Optional idle timeout. `None` disables idle reaping.
     pub idle_timeout: Option<Duration>,
+    /// Statements at least this slow are logged at `WARN` on the `sqlx::query` target.
+    /// `None` turns slow statement logging off.
+    pub slow_statement_threshold: Option<Duration>,
+    /// Open the database read-only and never create it, as for a replica.
+    pub read_only: bool,
 }
 
 impl Default for DatabaseConnectionOptions {
@@ -25,6 +32,8 @@ impl Default for DatabaseConnectionOptions {
             min_connections: 1,
             connect_timeout: Duration::from_secs(10),
             idle_timeout: Some(Duration::from_secs(600)),
+            slow_statement_threshold: Some(Duration::from_secs(1)),
+            read_only: false,
         }
     }
 }
@@ -39,21 +48,43 @@ pub fn database_url_from_path(path: &Path) -> String {
     format!("sqlite:///{}", absolute_path.display())
 }
 
+/// Returns true when the URL points at a private in-memory SQLite database.
+pub fn is_in_memory_url(database_url: &str) -> bool {
+    let database_url = database_url.trim();
+    database_url == ":memory:"
+        || database_url == "sqlite::memory:"
+        || database_url.contains("mode=memory")
+}
+
 /// Create an SQLite pool with explicit options.
+///
+/// In-memory databases live only as long as their connection, so they are pinned
+/// to a single connection that is never reaped.
 pub async fn connect_sqlite_with_options(
     database_url: &str,
     options: DatabaseConnectionOptions,
 ) -> Result<SqlitePool, sqlx::Error> {
     ensure_database_directory(database_url)?;
     let connect_options: SqliteConnectOptions = database_url.parse()?;
-    let connect_options = connect_options.create_if_missing(true);
-    SqlitePoolOptions::new()
-        .max_connections(options.max_connections)
-        .min_connections(options.min_connections)
-        .acquire_timeout(options.connect_timeout)
-        .idle_timeout(options.idle_timeout)
-        .connect_with(connect_options)
-        .await
+    let connect_options = match options.slow_statement_threshold {
+        Some(threshold) => connect_options.log_slow_statements(LevelFilter::Warn, threshold),
+        None => connect_options.log_slow_statements(LevelFilter::Off, Duration::MAX),
+    };
+    let connect_options = if options.read_only {
+        connect_options.read_only(true)
+    } else {
+        connect_options.create_if_missing(true)
+    };
+    let pool_options = SqlitePoolOptions::new().acquire_timeout(options.connect_timeout);
+    let pool_options = if is_in_memory_url(database_url) {
+        pool_options.max_connections(1).min_connections(1).idle_timeout(None).max_lifetime(None)
+    } else {
+        pool_options
+            .max_connections(options.max_connections)
+            .min_connections(options.min_connections)
+            .idle_timeout(options.idle_timeout)
+    };
+    pool_options.connect_with(connect_options).await
 }
 
 /// Create an SQLite pool with default options.
@@ -77,7 +108,7 @@ fn ensure_database_directory(database_url: &str) -> Result<(), sqlx::Error> {
         )));
     }
 
-    if db_path == ":memory:" {
+    if is_in_memory_url(db_path) {
         return Ok(());
     }
 
@@ -100,7 +131,7 @@ fn ensure_database_directory(database_url: &str) -> Result<(), sqlx::Error> {
 #[cfg(test)]
 mod tests {
     use super::{
-        database_url_from_path, ensure_database_directory,
+        database_url_from_path, ensure_database_directory, is_in_memory_url,
     };
     use std::fs;
     use std::path::Path;
@@ -146,4 +177,11 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn in_memory_urls_are_detected() {
+        assert!(is_in_memory_url(":memory:"));
+        assert!(is_in_memory_url("sqlite::memory:"));
+        assert!(is_in_memory_url("sqlite://eval?mode=memory&cache=shared"));
+        assert!(!is_in_memory_url("sqlite:////tmp/data.db"));
+    }
 }